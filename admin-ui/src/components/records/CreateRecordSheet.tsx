@@ -7,7 +7,13 @@ import { validateRecordData } from "@/components/records/validation";
 import { Button } from "@/components/ui/button";
 import { Checkbox } from "@/components/ui/checkbox";
 import { FileUpload, type FileUploadFile } from "@/components/ui/file-upload";
-import { Form, FormControl, FormField, FormLabel } from "@/components/ui/form";
+import {
+	Form,
+	FormControl,
+	FormDescription,
+	FormField,
+	FormLabel,
+} from "@/components/ui/form";
 import { Input } from "@/components/ui/input";
 import { JsonEditor } from "@/components/ui/json-editor";
 import {
@@ -316,6 +322,9 @@ export function CreateRecordSheet({
 						/>
 					)}
 				</FormControl>
+				{field.description && (
+					<FormDescription>{field.description}</FormDescription>
+				)}
 			</FormField>
 		);
 	};
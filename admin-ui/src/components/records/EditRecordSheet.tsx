@@ -10,6 +10,7 @@ import { FileUpload, type FileUploadFile } from "@/components/ui/file-upload";
 import {
 	Form,
 	FormControl,
+	FormDescription,
 	FormField,
 	FormLabel,
 	FormMessage,
@@ -368,6 +369,9 @@ export function EditRecordSheet({
 						/>
 					)}
 				</FormControl>
+				{field.description && (
+					<FormDescription>{field.description}</FormDescription>
+				)}
 				<FormMessage />
 			</FormField>
 		);
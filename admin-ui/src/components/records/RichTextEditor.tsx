@@ -79,6 +79,7 @@ import Paragraph from "@tiptap/extension-paragraph";
 import Text from "@tiptap/extension-text";
 import { Dropcursor, Gapcursor, UndoRedo } from "@tiptap/extensions";
 import { toast } from "@/components/ui/toast";
+import { csrfHeaders } from "@/lib/api";
 import { cn } from "@/lib/utils";
 
 const CODE_LANGUAGES = [
@@ -877,6 +878,7 @@ export const RichTextEditor: React.FC<RichTextEditorProps> = ({
 				method: "DELETE",
 				headers: {
 					"Content-Type": "application/json",
+					...csrfHeaders(),
 				},
 				credentials: "include",
 				body: JSON.stringify({ url: imageUrl }),
@@ -936,6 +938,7 @@ export const RichTextEditor: React.FC<RichTextEditorProps> = ({
 
 			const response = await fetch("/api/upload-image", {
 				method: "POST",
+				headers: csrfHeaders(),
 				body: formData,
 				credentials: "include",
 				signal: controller.signal,
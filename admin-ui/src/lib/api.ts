@@ -3,6 +3,7 @@ import type {
 	BroadcastMessageRequest,
 	BroadcastMessageResponse,
 	Collection,
+	CollectionListOptions,
 	CollectionPermission,
 	CollectionRecordCounts,
 	CollectionStats,
@@ -22,11 +23,14 @@ import type {
 	OwnedRecordsResponse,
 	OwnershipCheckResponse,
 	OwnershipStatsResponse,
+	PaginatedCollectionsResponse,
 	PaginatedRecordsResponse,
 	PaginatedUsersResponse,
 	PermissionResult,
 	QueryOptions,
 	Record,
+	RecordData,
+	RecordValidationResponse,
 	RecordWithCollection,
 	RegisterRequest,
 	ResetPasswordRequest,
@@ -53,25 +57,38 @@ const API_BASE_URL = import.meta.env.VITE_API_BASE_URL || "/api";
 export class CustomApiError extends Error {
 	public statusCode: number;
 	public validationErrors?: string[];
+	public code?: string;
 
 	constructor(
 		message: string,
 		statusCode: number,
 		validationErrors?: string[],
+		code?: string,
 	) {
 		super(message);
 		this.name = "CustomApiError";
 		this.statusCode = statusCode;
 		this.validationErrors = validationErrors;
+		this.code = code;
 	}
 }
 
+// Cookie-authenticated writes must echo the csrf_token cookie set at login
+export function csrfHeaders(): { [key: string]: string } {
+	const token = document.cookie
+		.split("; ")
+		.find((cookie) => cookie.startsWith("csrf_token="))
+		?.slice("csrf_token=".length);
+	return token ? { "X-CSRF-Token": token } : {};
+}
+
 async function apiRequest<T>(
 	endpoint: string,
 	options: RequestInit = {},
 	isRetry: boolean = false,
 ): Promise<T> {
 	const headers: { [key: string]: string } = {
+		...csrfHeaders(),
 		...(options.headers as { [key: string]: string }),
 	};
 
@@ -95,6 +112,7 @@ async function apiRequest<T>(
 					method: "POST",
 					headers: {
 						"Content-Type": "application/json",
+						...csrfHeaders(),
 					},
 					credentials: "include",
 				});
@@ -134,6 +152,7 @@ async function apiRequest<T>(
 			errorMessage,
 			response.status,
 			errorData.validation_errors,
+			errorData.code,
 		);
 	}
 
@@ -242,8 +261,30 @@ export const backupApi = {
 };
 
 export const collectionsApi = {
-	list: (): Promise<ApiResponse<Collection[]>> =>
-		apiRequest<ApiResponse<Collection[]>>("/collections"),
+	list: (
+		options?: CollectionListOptions,
+	): Promise<ApiResponse<PaginatedCollectionsResponse>> => {
+		const params = new URLSearchParams();
+		if (options?.search) params.append("search", options.search);
+		if (options?.sort) params.append("sort", options.sort);
+		if (options?.fields) params.append("fields", options.fields);
+		if (options?.limit) params.append("limit", options.limit.toString());
+		if (options?.offset) params.append("offset", options.offset.toString());
+
+		const queryString = params.toString();
+		return apiRequest<ApiResponse<PaginatedCollectionsResponse>>(
+			`/collections${queryString ? `?${queryString}` : ""}`,
+		);
+	},
+
+	listNames: async (): Promise<Pick<Collection, "name" | "display_name">[]> => {
+		const response = await apiRequest<
+			ApiResponse<
+				PaginatedCollectionsResponse<Pick<Collection, "name" | "display_name">>
+			>
+		>("/collections?fields=names&sort=name&limit=500");
+		return response.data.collections;
+	},
 
 	get: (name: string): Promise<ApiResponse<Collection>> =>
 		apiRequest<ApiResponse<Collection>>(`/collections/${name}`),
@@ -270,10 +311,18 @@ export const collectionsApi = {
 		return response.data;
 	},
 
-	delete: (name: string): Promise<void> =>
-		apiRequest<void>(`/collections/${name}`, {
-			method: "DELETE",
-		}),
+	// The delete modal already asks for confirmation, so both phases of the
+	// server-side delete run back to back
+	delete: async (name: string): Promise<void> => {
+		const pending = await apiRequest<ApiResponse<{ confirm_token: string }>>(
+			`/collections/${name}`,
+			{ method: "DELETE" },
+		);
+		await apiRequest<void>(
+			`/collections/${name}?confirm_token=${encodeURIComponent(pending.data.confirm_token)}`,
+			{ method: "DELETE" },
+		);
+	},
 
 	getSchema: (name: string): Promise<unknown> =>
 		apiRequest<unknown>(`/collections/${name}/schema`),
@@ -343,6 +392,21 @@ export const recordsApi = {
 		return response.data;
 	},
 
+	validate: async (
+		collectionName: string,
+		data: RecordData,
+		recordId?: number,
+	): Promise<RecordValidationResponse> => {
+		const response = await apiRequest<ApiResponse<RecordValidationResponse>>(
+			`/collections/${collectionName}/records/validate`,
+			{
+				method: "POST",
+				body: JSON.stringify({ data, record_id: recordId }),
+			},
+		);
+		return response.data;
+	},
+
 	create: async (
 		collectionName: string,
 		data: CreateRecordRequest,
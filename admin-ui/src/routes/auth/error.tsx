@@ -9,10 +9,25 @@ import { Alert, AlertDescription } from "@/components/ui/alert";
 import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 
+const OAUTH_ERROR_MESSAGES: Record<string, string> = {
+	access_denied: "Sign-in was cancelled or not allowed by the provider.",
+	provider_unavailable:
+		"The sign-in provider could not be reached. Please try again later.",
+	account_link_conflict:
+		"An account with this email already exists and could not be linked. Sign in with your password instead.",
+	email_unverified_at_provider:
+		"Your email address is not verified with the provider. Verify it there and try again.",
+};
+
 export default function AuthErrorComponent() {
 	const navigate = useNavigate();
-	const search = useSearch({ from: "/auth/error" }) as { message?: string };
-	const errorMessage = search.message || "Authentication failed";
+	const search = useSearch({ from: "/auth/error" }) as {
+		code?: string;
+		request_id?: string;
+	};
+	const errorMessage =
+		(search.code && OAUTH_ERROR_MESSAGES[search.code]) ||
+		"Authentication failed";
 
 	useEffect(() => {
 		const timer = setTimeout(() => {
@@ -44,11 +59,15 @@ export default function AuthErrorComponent() {
 					</CardHeader>
 					<CardContent className="space-y-4">
 						<Alert variant="destructive">
-							<AlertDescription>
-								{decodeURIComponent(errorMessage)}
-							</AlertDescription>
+							<AlertDescription>{errorMessage}</AlertDescription>
 						</Alert>
 
+						{search.request_id && (
+							<p className="text-center text-xs text-nocta-500 dark:text-nocta-500">
+								Request ID: <code>{search.request_id}</code>
+							</p>
+						)}
+
 						<div className="text-center space-y-4">
 							<p className="text-sm text-nocta-600 dark:text-nocta-400">
 								You will be redirected to the login page in 10 seconds.
@@ -41,6 +41,7 @@ export interface LoginRequest {
 export interface LoginResponse {
 	user: User;
 	expires_in: number;
+	csrf_token: string;
 }
 
 export interface RegisterRequest {
@@ -114,6 +115,8 @@ export interface FieldDefinition {
 		| "richtext";
 	required: boolean;
 	default_value?: unknown;
+	description?: string;
+	example?: unknown;
 	validation?: {
 		min_length?: number;
 		max_length?: number;
@@ -140,6 +143,19 @@ export interface Collection {
 	permissions?: CollectionPermissions;
 }
 
+export interface CollectionListOptions {
+	search?: string;
+	sort?: string;
+	fields?: "full" | "summary" | "names";
+	limit?: number;
+	offset?: number;
+}
+
+export interface PaginatedCollectionsResponse<T = Collection> {
+	collections: T[];
+	pagination: PaginationMeta;
+}
+
 export interface CreateCollectionRequest {
 	name: string;
 	description?: string;
@@ -172,6 +188,17 @@ export interface UpdateRecordRequest {
 	data: RecordData;
 }
 
+export interface FieldValidationError {
+	field: string;
+	code: string;
+	message: string;
+}
+
+export interface RecordValidationResponse {
+	valid: boolean;
+	errors: FieldValidationError[];
+}
+
 export type Record = ApiRecord;
 
 export interface RecordWithCollection extends ApiRecord {
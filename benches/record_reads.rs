@@ -0,0 +1,77 @@
+//! Reads one record by id 10,000 times, once with the id inlined into a
+//! `diesel::sql_query` (prepared on every call) and once through
+//! `PreparedSql` (prepared once per connection).
+//!
+//! Run with `cargo bench --bench record_reads`.
+
+use diesel::sql_types::{Nullable, Text};
+use diesel::{Connection, RunQueryDsl, SqliteConnection};
+use lunarbase::database::prepared::{PreparedSql, SqlBind};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const READS: usize = 10_000;
+const RECORDS: usize = 1_000;
+
+#[derive(Debug, diesel::QueryableByName)]
+struct Row {
+    #[diesel(sql_type = Text)]
+    id: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    title: Option<String>,
+}
+
+fn setup() -> SqliteConnection {
+    let mut conn = SqliteConnection::establish(":memory:").expect("in-memory database");
+    diesel::sql_query(
+        "CREATE TABLE records_posts (id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL, \
+         title TEXT, created_at DATETIME DEFAULT CURRENT_TIMESTAMP, \
+         updated_at DATETIME DEFAULT CURRENT_TIMESTAMP)",
+    )
+    .execute(&mut conn)
+    .expect("create table");
+    for i in 0..RECORDS {
+        PreparedSql::new("INSERT INTO records_posts (title) VALUES (?)")
+            .bind(SqlBind::Text(Some(format!("Post {}", i))))
+            .execute(&mut conn)
+            .expect("insert");
+    }
+    conn
+}
+
+fn run(name: &str, mut read: impl FnMut(&mut SqliteConnection, usize) -> Vec<Row>) -> Duration {
+    let mut conn = setup();
+    let start = Instant::now();
+    for i in 0..READS {
+        let rows = read(&mut conn, i % RECORDS + 1);
+        assert_eq!(rows.len(), 1);
+        black_box(&rows[0].id);
+        black_box(&rows[0].title);
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{:<12} {:>8.2?} total, {:>6.2?} per read",
+        name,
+        elapsed,
+        elapsed / READS as u32
+    );
+    elapsed
+}
+
+fn main() {
+    let inlined = run("sql_query", |conn, id| {
+        diesel::sql_query(format!("SELECT * FROM records_posts WHERE id = '{}'", id))
+            .load(conn)
+            .expect("select")
+    });
+    let prepared = run("prepared", |conn, id| {
+        PreparedSql::new("/* schema v1 */ SELECT * FROM records_posts WHERE id = ?")
+            .bind(SqlBind::Text(Some(id.to_string())))
+            .load(conn)
+            .expect("select")
+    });
+    println!(
+        "speedup      {:.2}x",
+        inlined.as_secs_f64() / prepared.as_secs_f64()
+    );
+}
@@ -1,7 +1,14 @@
 use std::env;
+use std::fs;
+use std::io::Write;
 use std::path::Path;
 use std::process::Command;
 
+/// Text assets are stored precompressed next to the originals so the server
+/// can hand them out without compressing the same bytes on every request.
+const PRECOMPRESSED_EXTENSIONS: [&str; 5] = ["html", "js", "css", "svg", "json"];
+const PRECOMPRESS_MIN_SIZE: usize = 1024;
+
 fn main() {
     let profile = env::var("PROFILE").unwrap_or_default();
     let force_build = env::var("LUNARBASE_BUILD_FRONTEND").is_ok();
@@ -51,6 +58,8 @@ fn main() {
             panic!("Frontend build completed but dist directory not found");
         }
 
+        precompress_dir(&dist_dir);
+
         println!("Frontend build completed successfully");
     } else {
         println!(
@@ -58,3 +67,52 @@ fn main() {
         );
     }
 }
+
+fn precompress_dir(dir: &Path) {
+    let entries = fs::read_dir(dir).expect("Failed to read frontend dist directory");
+    for entry in entries {
+        let path = entry.expect("Failed to read dist entry").path();
+        if path.is_dir() {
+            precompress_dir(&path);
+            continue;
+        }
+
+        let compressible = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| PRECOMPRESSED_EXTENSIONS.contains(&extension));
+        if !compressible {
+            continue;
+        }
+
+        let data = fs::read(&path).expect("Failed to read dist asset");
+        if data.len() < PRECOMPRESS_MIN_SIZE {
+            continue;
+        }
+
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        gzip.write_all(&data).expect("Failed to gzip dist asset");
+        let gzip = gzip.finish().expect("Failed to gzip dist asset");
+
+        let mut brotli = Vec::new();
+        brotli::BrotliCompress(
+            &mut data.as_slice(),
+            &mut brotli,
+            &brotli::enc::BrotliEncoderParams {
+                quality: 11,
+                ..Default::default()
+            },
+        )
+        .expect("Failed to brotli-compress dist asset");
+
+        // A variant that is not smaller would only cost bytes in the binary
+        for (extension, compressed) in [("gz", gzip), ("br", brotli)] {
+            if compressed.len() < data.len() {
+                let mut variant = path.clone().into_os_string();
+                variant.push(".");
+                variant.push(extension);
+                fs::write(variant, compressed).expect("Failed to write precompressed asset");
+            }
+        }
+    }
+}
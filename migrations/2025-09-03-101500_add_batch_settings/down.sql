@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'api' AND setting_key = 'batch_max_operations';
@@ -0,0 +1,2 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'batch_max_operations', '50', 'integer', 'Maximum number of operations accepted in a single batch request', '50', FALSE, FALSE);
@@ -0,0 +1,7 @@
+DROP TRIGGER IF EXISTS update_ingest_endpoints_updated_at;
+
+DROP INDEX IF EXISTS idx_ingest_failures_endpoint_id;
+DROP INDEX IF EXISTS idx_ingest_endpoints_token;
+
+DROP TABLE IF EXISTS ingest_failures;
+DROP TABLE IF EXISTS ingest_endpoints;
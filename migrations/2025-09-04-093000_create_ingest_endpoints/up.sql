@@ -0,0 +1,35 @@
+-- Operator-defined webhook ingestion endpoints
+CREATE TABLE ingest_endpoints (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    token VARCHAR(255) NOT NULL UNIQUE,
+    collection_name VARCHAR(255) NOT NULL,
+    field_mapping TEXT NOT NULL,
+    hmac_secret TEXT,
+    signature_header VARCHAR(255),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Dead-letter list of payloads that could not be ingested
+CREATE TABLE ingest_failures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    endpoint_id INTEGER NOT NULL,
+    payload TEXT NOT NULL,
+    error TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (endpoint_id) REFERENCES ingest_endpoints(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_ingest_endpoints_token ON ingest_endpoints(token);
+CREATE INDEX idx_ingest_failures_endpoint_id ON ingest_failures(endpoint_id);
+
+CREATE TRIGGER update_ingest_endpoints_updated_at
+    AFTER UPDATE ON ingest_endpoints
+    FOR EACH ROW
+    BEGIN
+        UPDATE ingest_endpoints SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+    END;
@@ -0,0 +1,5 @@
+DROP TRIGGER IF EXISTS update_collection_views_updated_at;
+
+DROP INDEX IF EXISTS idx_collection_views_collection_id;
+
+DROP TABLE IF EXISTS collection_views;
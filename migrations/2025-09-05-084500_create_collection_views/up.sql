@@ -0,0 +1,26 @@
+-- Saved filter/sort/field presets for a collection
+CREATE TABLE collection_views (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    collection_id INTEGER NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    filter TEXT,
+    sort TEXT,
+    fields TEXT,
+    visibility VARCHAR(20) NOT NULL DEFAULT 'private',
+    role_name VARCHAR(255),
+    created_by INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (collection_id) REFERENCES collections(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE(collection_id, name)
+);
+
+CREATE INDEX idx_collection_views_collection_id ON collection_views(collection_id);
+
+CREATE TRIGGER update_collection_views_updated_at
+    AFTER UPDATE ON collection_views
+    FOR EACH ROW
+    BEGIN
+        UPDATE collection_views SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+    END;
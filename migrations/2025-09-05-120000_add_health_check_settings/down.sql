@@ -0,0 +1,9 @@
+DELETE FROM system_settings WHERE category = 'api' AND setting_key IN (
+    'health_check_cache_seconds',
+    'health_check_timeout_ms',
+    'health_check_s3',
+    'health_check_email',
+    'health_check_oauth',
+    'health_check_backup'
+);
+DELETE FROM system_settings WHERE category = 'database' AND setting_key = 'backup_max_age_hours';
@@ -0,0 +1,8 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'health_check_cache_seconds', '30', 'integer', 'How long dependency checks in the admin health report are cached', '30', FALSE, FALSE),
+('api', 'health_check_timeout_ms', '3000', 'integer', 'Time limit for each dependency check in the admin health report', '3000', FALSE, FALSE),
+('api', 'health_check_s3', 'true', 'boolean', 'Check S3 bucket access in the admin health report', 'true', FALSE, FALSE),
+('api', 'health_check_email', 'true', 'boolean', 'Check email API reachability in the admin health report', 'true', FALSE, FALSE),
+('api', 'health_check_oauth', 'true', 'boolean', 'Check OAuth provider configuration in the admin health report', 'true', FALSE, FALSE),
+('api', 'health_check_backup', 'true', 'boolean', 'Check backup recency in the admin health report', 'true', FALSE, FALSE),
+('database', 'backup_max_age_hours', '48', 'integer', 'Report backups as degraded when the last successful backup is older than this many hours', '48', FALSE, FALSE);
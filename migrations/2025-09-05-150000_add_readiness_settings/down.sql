@@ -0,0 +1,4 @@
+DELETE FROM system_settings WHERE category = 'api' AND setting_key IN (
+    'maintenance_mode',
+    'shutdown_drain_seconds'
+);
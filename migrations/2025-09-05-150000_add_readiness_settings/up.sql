@@ -0,0 +1,3 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'maintenance_mode', 'false', 'boolean', 'Report the server as not ready so load balancers stop routing traffic to it', 'false', FALSE, FALSE),
+('api', 'shutdown_drain_seconds', '5', 'integer', 'How long the server reports itself as not ready before closing the listener on shutdown', '5', FALSE, FALSE);
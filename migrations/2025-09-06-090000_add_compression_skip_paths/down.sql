@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'web_server' AND setting_key = 'compression_skip_paths';
@@ -0,0 +1,2 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('web_server', 'compression_skip_paths', '["/api/admin/backup"]', 'json', 'Path prefixes whose responses are never compressed', '["/api/admin/backup"]', FALSE, TRUE);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_collection_schema_versions_collection_id;
+DROP TABLE IF EXISTS collection_schema_versions;
+ALTER TABLE collections DROP COLUMN schema_version;
@@ -0,0 +1,21 @@
+-- Every schema revision of a collection, written on each schema-changing update
+CREATE TABLE collection_schema_versions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    collection_id INTEGER NOT NULL,
+    version INTEGER NOT NULL,
+    schema_json TEXT NOT NULL,
+    created_by INTEGER,
+    migration_summary TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (collection_id) REFERENCES collections(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL,
+    UNIQUE(collection_id, version)
+);
+
+CREATE INDEX idx_collection_schema_versions_collection_id ON collection_schema_versions(collection_id);
+
+ALTER TABLE collections ADD COLUMN schema_version INTEGER NOT NULL DEFAULT 1;
+
+-- Existing collections start their history at version 1
+INSERT INTO collection_schema_versions (collection_id, version, schema_json, migration_summary, created_at)
+SELECT id, 1, schema_json, 'Initial schema', created_at FROM collections;
@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'users' AND setting_key = 'relation_visibility';
@@ -0,0 +1,2 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('users', 'relation_visibility', 'authenticated', 'string', 'Who can read the _users system collection and expand user relations: disabled, authenticated or public', 'authenticated', FALSE, FALSE);
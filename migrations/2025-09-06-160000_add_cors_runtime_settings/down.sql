@@ -0,0 +1,3 @@
+UPDATE system_settings SET requires_restart = TRUE WHERE category = 'api' AND setting_key = 'cors_allowed_origins';
+
+DELETE FROM system_settings WHERE category = 'api' AND setting_key = 'cors_allow_credentials';
@@ -0,0 +1,5 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'cors_allow_credentials', 'true', 'boolean', 'Send Access-Control-Allow-Credentials so browsers include cookies on cross-origin requests; cannot be combined with a "*" origin', 'true', FALSE, FALSE);
+
+-- CORS origins are now applied at runtime
+UPDATE system_settings SET requires_restart = FALSE WHERE category = 'api' AND setting_key = 'cors_allowed_origins';
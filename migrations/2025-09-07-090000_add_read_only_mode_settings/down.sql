@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'system' AND setting_key IN ('read_only', 'read_only_roles');
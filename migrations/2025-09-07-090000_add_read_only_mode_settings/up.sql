@@ -0,0 +1,3 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('system', 'read_only', 'false', 'boolean', 'Reject collection, record and ownership writes from every non-admin role', 'false', FALSE, FALSE),
+('system', 'read_only_roles', '[]', 'json', 'Roles whose collection, record and ownership writes are rejected while the rest keep working', '[]', FALSE, FALSE);
@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'database' AND setting_key IN ('record_cache_enabled', 'record_cache_max_entries', 'record_cache_ttl_seconds');
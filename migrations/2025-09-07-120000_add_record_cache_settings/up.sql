@@ -0,0 +1,4 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('database', 'record_cache_enabled', 'true', 'boolean', 'Cache single-record reads in memory; updates, deletes and ownership transfers invalidate entries', 'true', FALSE, FALSE),
+('database', 'record_cache_max_entries', '1000', 'integer', 'Maximum number of records kept in the record cache before the least recently used are evicted', '1000', FALSE, FALSE),
+('database', 'record_cache_ttl_seconds', '30', 'integer', 'Seconds a cached record is served before it is read from the database again', '30', FALSE, FALSE);
@@ -0,0 +1,2 @@
+DELETE FROM system_settings WHERE category = 'database' AND setting_key IN ('query_cache_enabled', 'query_cache_max_entries');
+ALTER TABLE collections DROP COLUMN query_cache_ttl_seconds;
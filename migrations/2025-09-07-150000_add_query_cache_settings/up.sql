@@ -0,0 +1,6 @@
+-- Seconds list and count results of a collection stay cached; 0 disables the query cache for it
+ALTER TABLE collections ADD COLUMN query_cache_ttl_seconds INTEGER NOT NULL DEFAULT 0;
+
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('database', 'query_cache_enabled', 'true', 'boolean', 'Cache list and count results for collections with a query cache TTL; any record change in the collection invalidates them', 'true', FALSE, FALSE),
+('database', 'query_cache_max_entries', '500', 'integer', 'Maximum number of cached query results before the least recently used are evicted', '500', FALSE, FALSE);
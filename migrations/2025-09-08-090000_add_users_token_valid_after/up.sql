@@ -0,0 +1,2 @@
+-- Tokens issued at or before this instant are rejected; set when an account is deactivated
+ALTER TABLE users ADD COLUMN token_valid_after TIMESTAMP;
@@ -0,0 +1,2 @@
+DELETE FROM system_settings WHERE category = 'auth' AND setting_key IN ('progressive_lockout_enabled', 'max_lockout_duration_minutes', 'lockout_exempt_admins', 'expired_lock_cleanup_interval_seconds');
+ALTER TABLE users DROP COLUMN lockout_count;
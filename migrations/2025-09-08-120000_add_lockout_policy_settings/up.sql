@@ -0,0 +1,8 @@
+-- Lockouts since the last successful login; drives the progressive lockout duration
+ALTER TABLE users ADD COLUMN lockout_count INTEGER NOT NULL DEFAULT 0;
+
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('auth', 'progressive_lockout_enabled', 'false', 'boolean', 'Double the lockout duration for each lockout since the last successful login', 'false', FALSE, FALSE),
+('auth', 'max_lockout_duration_minutes', '1440', 'integer', 'Upper bound of a progressive lockout in minutes', '1440', FALSE, FALSE),
+('auth', 'lockout_exempt_admins', '[]', 'json', 'Emails of admin accounts that are never locked; reaching the attempt limit raises an alert instead', '[]', FALSE, FALSE),
+('auth', 'expired_lock_cleanup_interval_seconds', '60', 'integer', 'How often expired lockouts are cleared; 0 disables the cleanup task', '60', FALSE, FALSE);
@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS update_collection_templates_updated_at;
+
+DROP TABLE IF EXISTS collection_templates;
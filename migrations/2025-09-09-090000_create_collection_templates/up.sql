@@ -0,0 +1,21 @@
+-- Custom collection templates saved by admins; built-in templates live in code
+CREATE TABLE collection_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    template_id VARCHAR(100) NOT NULL UNIQUE,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    schema_json TEXT NOT NULL,
+    permissions_json TEXT NOT NULL DEFAULT '[]',
+    sample_records_json TEXT NOT NULL DEFAULT '[]',
+    created_by INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE TRIGGER update_collection_templates_updated_at
+    AFTER UPDATE ON collection_templates
+    FOR EACH ROW
+    BEGIN
+        UPDATE collection_templates SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+    END;
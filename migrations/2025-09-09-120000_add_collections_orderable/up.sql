@@ -0,0 +1,2 @@
+-- Orderable collections keep a manual position in a sort_order column of their records table
+ALTER TABLE collections ADD COLUMN orderable BOOLEAN NOT NULL DEFAULT FALSE;
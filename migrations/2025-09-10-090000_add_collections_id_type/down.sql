@@ -0,0 +1,32 @@
+CREATE TABLE record_permissions_old (
+    id INTEGER PRIMARY KEY NOT NULL,
+    record_id INTEGER NOT NULL,
+    collection_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    can_read BOOLEAN NOT NULL DEFAULT FALSE,
+    can_update BOOLEAN NOT NULL DEFAULT FALSE,
+    can_delete BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (collection_id) REFERENCES collections (id) ON DELETE CASCADE,
+    UNIQUE(record_id, collection_id, user_id)
+);
+
+-- Permissions on UUID records cannot be represented and are dropped
+INSERT INTO record_permissions_old (id, record_id, collection_id, user_id, can_read, can_update, can_delete, created_at)
+SELECT id, CAST(record_id AS INTEGER), collection_id, user_id, can_read, can_update, can_delete, created_at
+FROM record_permissions
+WHERE CAST(CAST(record_id AS INTEGER) AS TEXT) = record_id;
+
+DROP TABLE record_permissions;
+ALTER TABLE record_permissions_old RENAME TO record_permissions;
+
+CREATE INDEX idx_record_permissions_record_collection_user ON record_permissions(record_id, collection_id, user_id);
+
+CREATE TRIGGER update_record_permissions_updated_at 
+    AFTER UPDATE ON record_permissions
+    BEGIN
+        UPDATE record_permissions SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+    END;
+
+ALTER TABLE collections DROP COLUMN id_type;
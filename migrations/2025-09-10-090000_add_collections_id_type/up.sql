@@ -0,0 +1,26 @@
+-- Collections choose integer or UUID record ids when they are created
+ALTER TABLE collections ADD COLUMN id_type TEXT NOT NULL DEFAULT 'integer';
+
+-- Record ids may be UUIDs, so record permissions keep them as text
+CREATE TABLE record_permissions_new (
+    id INTEGER PRIMARY KEY NOT NULL,
+    record_id TEXT NOT NULL,
+    collection_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    can_read BOOLEAN NOT NULL DEFAULT FALSE,
+    can_update BOOLEAN NOT NULL DEFAULT FALSE,
+    can_delete BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (collection_id) REFERENCES collections (id) ON DELETE CASCADE,
+    UNIQUE(record_id, collection_id, user_id)
+);
+
+INSERT INTO record_permissions_new (id, record_id, collection_id, user_id, can_read, can_update, can_delete, created_at)
+SELECT id, CAST(record_id AS TEXT), collection_id, user_id, can_read, can_update, can_delete, created_at
+FROM record_permissions;
+
+DROP TABLE record_permissions;
+ALTER TABLE record_permissions_new RENAME TO record_permissions;
+
+CREATE INDEX idx_record_permissions_record_collection_user ON record_permissions(record_id, collection_id, user_id);
@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'api' AND setting_key = 'search_collection_timeout_ms';
@@ -0,0 +1,2 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'search_collection_timeout_ms', '1000', 'integer', 'Time limit for searching each collection in a global search request', '1000', FALSE, FALSE);
@@ -0,0 +1,4 @@
+DELETE FROM system_settings WHERE category = 'api' AND setting_key IN (
+    'max_query_complexity',
+    'max_concurrent_queries_per_user'
+);
@@ -0,0 +1,3 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'max_query_complexity', '50', 'integer', 'Largest complexity accepted for a record query: each filter costs 1, each LIKE comparison 3 and each expanded relation 5', '50', FALSE, FALSE),
+('api', 'max_concurrent_queries_per_user', '3', 'integer', 'Maximum number of record list and count queries a user may have running at once', '3', FALSE, FALSE);
@@ -0,0 +1,6 @@
+DELETE FROM system_settings WHERE category = 'system' AND setting_key IN (
+    'default_permissions_admin',
+    'default_permissions_user',
+    'default_permissions_guest',
+    'default_permissions_custom'
+);
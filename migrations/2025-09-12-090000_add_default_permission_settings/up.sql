@@ -0,0 +1,5 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('system', 'default_permissions_admin', '{"can_create":true,"can_read":true,"can_update":true,"can_delete":true,"can_list":true}', 'json', 'Permissions the admin role receives on new collections; must stay full access', '{"can_create":true,"can_read":true,"can_update":true,"can_delete":true,"can_list":true}', FALSE, FALSE),
+('system', 'default_permissions_user', '{"can_create":true,"can_read":true,"can_update":true,"can_delete":false,"can_list":true}', 'json', 'Permissions the user role receives on new collections', '{"can_create":true,"can_read":true,"can_update":true,"can_delete":false,"can_list":true}', FALSE, FALSE),
+('system', 'default_permissions_guest', '{"can_create":false,"can_read":true,"can_update":false,"can_delete":false,"can_list":true}', 'json', 'Permissions the guest role receives on new collections', '{"can_create":false,"can_read":true,"can_update":false,"can_delete":false,"can_list":true}', FALSE, FALSE),
+('system', 'default_permissions_custom', '{"can_create":false,"can_read":true,"can_update":false,"can_delete":false,"can_list":true}', 'json', 'Permissions every custom role receives on new collections', '{"can_create":false,"can_read":true,"can_update":false,"can_delete":false,"can_list":true}', FALSE, FALSE);
@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'system' AND setting_key = 'permission_inheritance_enabled';
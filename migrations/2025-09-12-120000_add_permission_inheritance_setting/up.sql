@@ -0,0 +1,2 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('system', 'permission_inheritance_enabled', 'false', 'boolean', 'Roles without permissions on a collection inherit them from the nearest lower-priority role that has them', 'false', FALSE, FALSE);
@@ -0,0 +1,2 @@
+-- Collections that accept caller-supplied record ids, e.g. when migrating data in
+ALTER TABLE collections ADD COLUMN allow_explicit_ids BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('system', 'default_language', 'en', 'string', 'Language of error and validation messages for clients whose Accept-Language names no supported language (en, pl or de)', 'en', FALSE, FALSE);
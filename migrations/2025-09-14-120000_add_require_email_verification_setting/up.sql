@@ -0,0 +1,2 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('auth', 'require_email_verification', 'true', 'boolean', 'Reject logins and WebSocket connections from users who have not verified their email; when disabled, new registrations are verified automatically', 'true', FALSE, FALSE);
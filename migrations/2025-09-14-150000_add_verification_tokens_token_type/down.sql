@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_verification_tokens_user_type;
+ALTER TABLE verification_tokens DROP COLUMN token_type;
@@ -0,0 +1,2 @@
+ALTER TABLE verification_tokens ADD COLUMN token_type TEXT NOT NULL DEFAULT 'email_verification';
+CREATE INDEX idx_verification_tokens_user_type ON verification_tokens(user_id, token_type);
@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'auth' AND setting_key IN ('email_rate_limit_max_requests', 'email_rate_limit_window_minutes');
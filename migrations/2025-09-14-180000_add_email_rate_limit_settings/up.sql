@@ -0,0 +1,3 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('auth', 'email_rate_limit_max_requests', '3', 'integer', 'Verification or password reset emails one address or client IP may request per window', '3', FALSE, FALSE),
+('auth', 'email_rate_limit_window_minutes', '60', 'integer', 'Length in minutes of the window email requests are counted in', '60', FALSE, FALSE);
@@ -0,0 +1,4 @@
+DELETE FROM verification_tokens;
+DROP INDEX IF EXISTS idx_verification_tokens_token_hash;
+ALTER TABLE verification_tokens RENAME COLUMN token_hash TO token;
+CREATE INDEX idx_verification_tokens_token ON verification_tokens(token);
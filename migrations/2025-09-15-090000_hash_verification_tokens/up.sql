@@ -0,0 +1,5 @@
+-- Tokens issued so far were stored in plaintext; their links stop working
+DELETE FROM verification_tokens;
+DROP INDEX IF EXISTS idx_verification_tokens_token;
+ALTER TABLE verification_tokens RENAME COLUMN token TO token_hash;
+CREATE INDEX idx_verification_tokens_token_hash ON verification_tokens(token_hash);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_record_shares_record;
+DROP INDEX IF EXISTS idx_record_shares_created_by;
+
+DROP TABLE IF EXISTS record_shares;
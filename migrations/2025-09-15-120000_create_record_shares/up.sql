@@ -0,0 +1,18 @@
+-- Signed, expiring links that expose a single record to people without an account
+CREATE TABLE record_shares (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    collection_name VARCHAR(255) NOT NULL,
+    record_id VARCHAR(255) NOT NULL,
+    fields TEXT,
+    password_hash TEXT,
+    max_views INTEGER,
+    view_count INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP NOT NULL,
+    created_by INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_record_shares_created_by ON record_shares(created_by);
+CREATE INDEX idx_record_shares_record ON record_shares(collection_name, record_id);
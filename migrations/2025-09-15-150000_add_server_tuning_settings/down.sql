@@ -0,0 +1,7 @@
+DELETE FROM system_settings WHERE category = 'api' AND setting_key IN (
+    'http2_enabled',
+    'http2_cleartext',
+    'keep_alive_timeout_seconds',
+    'http2_max_concurrent_streams',
+    'max_header_size_kb'
+);
@@ -0,0 +1,6 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'http2_enabled', 'true', 'boolean', 'Offer HTTP/2 to TLS clients through ALPN', 'true', FALSE, TRUE),
+('api', 'http2_cleartext', 'false', 'boolean', 'Accept HTTP/2 without TLS (h2c prior knowledge) on plain listeners; enable only behind a trusted proxy', 'false', FALSE, TRUE),
+('api', 'keep_alive_timeout_seconds', '75', 'integer', 'How long an idle connection is kept open waiting for the next request', '75', FALSE, TRUE),
+('api', 'http2_max_concurrent_streams', '200', 'integer', 'Maximum number of parallel requests on one HTTP/2 connection', '200', FALSE, TRUE),
+('api', 'max_header_size_kb', '64', 'integer', 'Maximum total size of request headers in kilobytes', '64', FALSE, TRUE);
@@ -0,0 +1,2 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'tls_expiry_warning_days', '14', 'integer', 'Report TLS as degraded in the admin health report when the ACME certificate expires within this many days', '14', FALSE, FALSE);
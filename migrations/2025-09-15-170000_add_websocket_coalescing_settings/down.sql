@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'api' AND setting_key IN ('websocket_coalesce_threshold', 'websocket_coalesce_window_ms');
@@ -0,0 +1,3 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'websocket_coalesce_threshold', '100', 'integer', 'Record events per collection sent individually within one coalescing window before the rest are replaced by a single BulkChange message (0 disables coalescing)', '100', FALSE, FALSE),
+('api', 'websocket_coalesce_window_ms', '1000', 'integer', 'Length of the WebSocket event coalescing window in milliseconds', '1000', FALSE, FALSE);
@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'database' AND setting_key = 'permission_cache_ttl_seconds';
@@ -0,0 +1,2 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('database', 'permission_cache_ttl_seconds', '60', 'integer', 'Seconds cached roles and collection permissions are used before they are read from the database again; permission changes apply immediately (0 disables the cache)', '60', FALSE, FALSE);
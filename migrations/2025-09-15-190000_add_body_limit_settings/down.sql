@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'limits' AND setting_key IN ('auth_body_kb', 'record_json_mb', 'file_upload_mb');
@@ -0,0 +1,4 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('limits', 'auth_body_kb', '64', 'integer', 'Maximum request body size of the /auth endpoints in kilobytes', '64', FALSE, FALSE),
+('limits', 'record_json_mb', '10', 'integer', 'Maximum request body size of JSON API requests, and of the data part of multipart record requests, in megabytes', '10', FALSE, FALSE),
+('limits', 'file_upload_mb', '50', 'integer', 'Maximum size of each file uploaded with a record or through the image upload endpoint in megabytes', '50', FALSE, FALSE);
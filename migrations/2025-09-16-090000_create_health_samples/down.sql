@@ -0,0 +1,5 @@
+DELETE FROM system_settings WHERE category = 'api' AND setting_key = 'health_history_persist';
+
+DROP INDEX IF EXISTS idx_health_samples_sampled_at;
+
+DROP TABLE IF EXISTS health_samples;
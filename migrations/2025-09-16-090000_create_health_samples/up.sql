@@ -0,0 +1,13 @@
+-- Minute-by-minute health samples, one row per component, kept when
+-- api.health_history_persist is enabled so uptime survives restarts
+CREATE TABLE health_samples (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    sampled_at TIMESTAMP NOT NULL,
+    component VARCHAR(64) NOT NULL,
+    status VARCHAR(32) NOT NULL
+);
+
+CREATE INDEX idx_health_samples_sampled_at ON health_samples(sampled_at);
+
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'health_history_persist', 'false', 'boolean', 'Store health history samples in the database so uptime covers restarts and windows longer than 24 hours', 'false', FALSE, FALSE);
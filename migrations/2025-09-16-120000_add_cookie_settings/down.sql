@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'auth' AND setting_key IN ('cookie_same_site', 'cookie_domain');
@@ -0,0 +1,4 @@
+DELETE FROM system_settings WHERE category = 'auth' AND setting_key IN ('cookie_same_site', 'cookie_domain');
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('auth', 'cookie_same_site', 'Lax', 'string', 'SameSite attribute of the session cookies set for cross-origin requests: Lax, Strict or None. None, which an admin panel on another site needs, is always sent with Secure and so requires HTTPS. Same-origin requests keep Lax unless this is Strict', 'Lax', FALSE, FALSE),
+('auth', 'cookie_domain', '', 'string', 'Domain attribute of the session cookies set for cross-origin requests, e.g. example.com to share them with admin.example.com; empty keeps them on the API host', '', FALSE, FALSE);
@@ -0,0 +1,5 @@
+DELETE FROM system_settings WHERE category = 'storage' AND setting_key IN ('upload_scan_mode', 'upload_scan_clamav_address', 'upload_scan_command', 'upload_scan_timeout_seconds', 'upload_scan_fail_open');
+
+DROP INDEX IF EXISTS idx_quarantined_uploads_detected_at;
+
+DROP TABLE IF EXISTS quarantined_uploads;
@@ -0,0 +1,25 @@
+-- Audit trail of uploads the scanner rejected. Only metadata is kept, the
+-- file itself is never stored
+CREATE TABLE quarantined_uploads (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    detected_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    collection_name VARCHAR(255),
+    field_name VARCHAR(255) NOT NULL,
+    filename TEXT NOT NULL,
+    content_type VARCHAR(255) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    sha256 VARCHAR(64) NOT NULL,
+    scanner VARCHAR(32) NOT NULL,
+    signature TEXT NOT NULL,
+    user_id INTEGER REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_quarantined_uploads_detected_at ON quarantined_uploads(detected_at);
+
+DELETE FROM system_settings WHERE category = 'storage' AND setting_key IN ('upload_scan_mode', 'upload_scan_clamav_address', 'upload_scan_command', 'upload_scan_timeout_seconds', 'upload_scan_fail_open');
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('storage', 'upload_scan_mode', 'off', 'string', 'Scan uploaded files for malware before they are stored: off, clamav (a clamd daemon) or command (an external scanner)', 'off', FALSE, FALSE),
+('storage', 'upload_scan_clamav_address', '127.0.0.1:3310', 'string', 'host:port of the clamd daemon used when upload_scan_mode is clamav', '127.0.0.1:3310', FALSE, FALSE),
+('storage', 'upload_scan_command', '', 'string', 'Scanner used when upload_scan_mode is command, with its arguments separated by spaces. It gets the file on stdin and exits with 0 when it is clean and 1 when it is infected, printing the signature', '', FALSE, FALSE),
+('storage', 'upload_scan_timeout_seconds', '30', 'integer', 'How long a single file may take to scan before the scanner counts as unavailable', '30', FALSE, FALSE),
+('storage', 'upload_scan_fail_open', 'false', 'boolean', 'Accept uploads when the scanner is unavailable or times out instead of rejecting them', 'false', FALSE, FALSE);
@@ -0,0 +1,5 @@
+DELETE FROM system_settings WHERE category = 'auth' AND setting_key IN ('guest_sessions_enabled', 'guest_session_collections', 'guest_session_ttl_minutes', 'guest_session_max_per_ip', 'guest_session_window_minutes');
+
+DROP INDEX IF EXISTS idx_guest_session_records_record;
+DROP INDEX IF EXISTS idx_guest_session_records_session_id;
+DROP TABLE IF EXISTS guest_session_records;
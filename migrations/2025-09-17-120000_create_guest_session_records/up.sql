@@ -0,0 +1,20 @@
+-- Records created with a guest session token have no owner, so the session
+-- they came from is kept here instead
+CREATE TABLE guest_session_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    session_id VARCHAR(36) NOT NULL,
+    collection_name VARCHAR(255) NOT NULL,
+    record_id TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_guest_session_records_session_id ON guest_session_records(session_id);
+CREATE INDEX idx_guest_session_records_record ON guest_session_records(collection_name, record_id);
+
+DELETE FROM system_settings WHERE category = 'auth' AND setting_key IN ('guest_sessions_enabled', 'guest_session_collections', 'guest_session_ttl_minutes', 'guest_session_max_per_ip', 'guest_session_window_minutes');
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('auth', 'guest_sessions_enabled', 'false', 'boolean', 'Issue guest session tokens from /auth/guest-session, e.g. for public forms', 'false', FALSE, FALSE),
+('auth', 'guest_session_collections', '[]', 'json', 'Collections guest session tokens may create records in; the guest role also needs create permission on them', '[]', FALSE, FALSE),
+('auth', 'guest_session_ttl_minutes', '15', 'integer', 'How many minutes a guest session token stays valid', '15', FALSE, FALSE),
+('auth', 'guest_session_max_per_ip', '5', 'integer', 'Guest sessions one client IP may start per window', '5', FALSE, FALSE),
+('auth', 'guest_session_window_minutes', '60', 'integer', 'Length in minutes of the window guest sessions are counted in', '60', FALSE, FALSE);
@@ -0,0 +1,3 @@
+-- Draft/publish collections keep a status and an optional publish_at time in
+-- their records table; only published records are shown to readers
+ALTER TABLE collections ADD COLUMN workflow TEXT NOT NULL DEFAULT 'none';
@@ -0,0 +1,4 @@
+DELETE FROM system_settings WHERE category = 'database' AND setting_key = 'record_schedule_interval_seconds';
+
+ALTER TABLE collections DROP COLUMN schedule_checked_at;
+ALTER TABLE collections DROP COLUMN schedule_json;
@@ -0,0 +1,10 @@
+-- Date fields whose values publish, archive or delete records, e.g.
+-- {"publish_at_field": "go_live", "expire_at_field": "ends", "expire_action": "delete"}
+ALTER TABLE collections ADD COLUMN schedule_json TEXT;
+-- Scheduled transitions are applied up to this time; after downtime the
+-- scheduler catches up on everything that fell due since
+ALTER TABLE collections ADD COLUMN schedule_checked_at TIMESTAMP;
+
+DELETE FROM system_settings WHERE category = 'database' AND setting_key = 'record_schedule_interval_seconds';
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('database', 'record_schedule_interval_seconds', '60', 'integer', 'How often scheduled record publishing and expiry run; 0 disables the scheduler', '60', FALSE, FALSE);
@@ -0,0 +1,10 @@
+DELETE FROM system_settings WHERE category = 'system' AND setting_key = 'enable_workspaces';
+
+ALTER TABLE roles DROP COLUMN workspace_id;
+
+DROP INDEX IF EXISTS idx_collections_workspace_id;
+ALTER TABLE collections DROP COLUMN workspace_id;
+
+DROP INDEX IF EXISTS idx_workspace_members_user_id;
+DROP TABLE workspace_members;
+DROP TABLE workspaces;
@@ -0,0 +1,34 @@
+CREATE TABLE workspaces (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Everything that exists before workspaces are enabled lives in this one
+INSERT INTO workspaces (id, name, description) VALUES
+(1, 'default', 'Collections and users from before workspaces were enabled');
+
+CREATE TABLE workspace_members (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    workspace_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    role TEXT NOT NULL DEFAULT 'user',
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    UNIQUE (workspace_id, user_id)
+);
+
+CREATE INDEX idx_workspace_members_user_id ON workspace_members (user_id);
+
+ALTER TABLE collections ADD COLUMN workspace_id INTEGER NOT NULL DEFAULT 1;
+CREATE INDEX idx_collections_workspace_id ON collections (workspace_id);
+
+-- NULL for the built-in roles, which every workspace shares
+ALTER TABLE roles ADD COLUMN workspace_id INTEGER;
+
+DELETE FROM system_settings WHERE category = 'system' AND setting_key = 'enable_workspaces';
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('system', 'enable_workspaces', 'false', 'boolean', 'Scope collections, custom roles and user memberships to workspaces; users with the global admin role manage them as superadmins', 'false', FALSE, FALSE);
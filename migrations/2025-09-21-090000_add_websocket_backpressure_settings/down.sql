@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'api' AND setting_key IN ('websocket_outbound_buffer_size', 'websocket_slow_consumer_max_dropped');
@@ -0,0 +1,3 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'websocket_outbound_buffer_size', '256', 'integer', 'Messages queued per WebSocket connection before further messages to it are dropped', '256', FALSE, FALSE),
+('api', 'websocket_slow_consumer_max_dropped', '100', 'integer', 'Dropped messages after which a WebSocket connection is closed as a slow consumer (0 never closes it)', '100', FALSE, FALSE);
@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'auth' AND setting_key IN ('permission_denial_alert_threshold', 'permission_denial_alert_window_seconds', 'permission_denial_alert_email', 'permission_denial_alert_webhook_url');
@@ -0,0 +1,5 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('auth', 'permission_denial_alert_threshold', '0', 'integer', 'Permission denials of a single user within the alert window that trigger an alert (0 disables alerts)', '0', FALSE, FALSE),
+('auth', 'permission_denial_alert_window_seconds', '60', 'integer', 'Length of the permission denial alert window in seconds; a user is alerted on at most once per window', '60', FALSE, FALSE),
+('auth', 'permission_denial_alert_email', '', 'string', 'Address that receives permission denial alerts; empty sends no email', '', FALSE, FALSE),
+('auth', 'permission_denial_alert_webhook_url', '', 'string', 'URL that receives permission denial alerts as a JSON POST; empty calls no webhook', '', TRUE, FALSE);
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_permission_audit_entries_target_user_id;
+
+DROP TABLE IF EXISTS permission_audit_entries;
+
+DROP INDEX IF EXISTS idx_record_permissions_user_id;
+
+ALTER TABLE record_permissions DROP COLUMN granted_by;
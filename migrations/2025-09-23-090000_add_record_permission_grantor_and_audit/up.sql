@@ -0,0 +1,18 @@
+-- Who granted a record permission; NULL for grants made before this column
+ALTER TABLE record_permissions ADD COLUMN granted_by INTEGER;
+
+CREATE INDEX idx_record_permissions_user_id ON record_permissions(user_id);
+
+-- Audit trail of admin reviews and bulk revocations of a user's record
+-- permissions. target_user_id has no foreign key so entries outlive the user
+CREATE TABLE permission_audit_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    actor_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    target_user_id INTEGER NOT NULL,
+    action VARCHAR(32) NOT NULL,
+    collection_name VARCHAR(255),
+    affected_count BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_permission_audit_entries_target_user_id ON permission_audit_entries(target_user_id);
@@ -0,0 +1,3 @@
+DELETE FROM system_settings WHERE category = 'database' AND setting_key = 'record_count_reconcile_interval_seconds';
+
+DROP TABLE IF EXISTS collection_record_counts;
@@ -0,0 +1,13 @@
+-- Record counts kept up to date on every record insert and delete, so the
+-- stats endpoint does not count every records table. reconciled_at is when
+-- the count was last checked against the table itself
+CREATE TABLE collection_record_counts (
+    collection_id INTEGER PRIMARY KEY NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+    record_count BIGINT NOT NULL DEFAULT 0,
+    reconciled_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+DELETE FROM system_settings WHERE category = 'database' AND setting_key = 'record_count_reconcile_interval_seconds';
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('database', 'record_count_reconcile_interval_seconds', '3600', 'integer', 'How often the cached record counts of collections are recounted from their tables; 0 disables reconciliation', '3600', FALSE, FALSE);
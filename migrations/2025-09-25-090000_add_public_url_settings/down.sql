@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'urls' AND setting_key IN ('public_api_url', 'frontend_url');
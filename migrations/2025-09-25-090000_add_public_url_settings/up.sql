@@ -0,0 +1,6 @@
+-- Public URLs for deployments where the API and the frontend are served from
+-- different hosts. Empty values fall back to the server address
+DELETE FROM system_settings WHERE category = 'urls' AND setting_key IN ('public_api_url', 'frontend_url');
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('urls', 'public_api_url', '', 'string', 'Absolute URL the API is reachable at, used for email verification links and OAuth callbacks; derived from the server address when empty', '', FALSE, TRUE),
+('urls', 'frontend_url', '', 'string', 'Absolute URL of the frontend, used for password reset links and redirects after sign-in; derived from the server address when empty', '', FALSE, TRUE);
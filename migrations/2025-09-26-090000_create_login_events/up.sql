@@ -0,0 +1,11 @@
+-- One row per successful sign-in, feeding the active user and login method
+-- series of the user analytics endpoint
+CREATE TABLE login_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    method VARCHAR(16) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_login_events_created_at ON login_events(created_at);
+CREATE INDEX idx_login_events_user_id ON login_events(user_id);
@@ -0,0 +1,2 @@
+DELETE FROM system_settings WHERE category = 'database' AND setting_key IN ('record_deletion_alert_threshold', 'record_deletion_alert_email', 'record_deletion_alert_webhook_url');
+DROP TABLE IF EXISTS collection_activity;
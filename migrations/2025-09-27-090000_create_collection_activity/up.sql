@@ -0,0 +1,17 @@
+-- Record changes per collection and day, incremented alongside every record
+-- event, for the record analytics endpoint and deletion spike alerts
+CREATE TABLE collection_activity (
+    collection_id INTEGER NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    created_count BIGINT NOT NULL DEFAULT 0,
+    updated_count BIGINT NOT NULL DEFAULT 0,
+    deleted_count BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (collection_id, day)
+);
+
+CREATE INDEX idx_collection_activity_day ON collection_activity(day);
+
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('database', 'record_deletion_alert_threshold', '0', 'integer', 'Alert when a collection deletes more records in a day than this multiple of its trailing 7-day average (0 disables alerts)', '0', FALSE, FALSE),
+('database', 'record_deletion_alert_email', '', 'string', 'Address that receives record deletion alerts; empty sends no email', '', FALSE, FALSE),
+('database', 'record_deletion_alert_webhook_url', '', 'string', 'URL that receives record deletion alerts as a JSON POST; empty calls no webhook', '', TRUE, FALSE);
@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'api' AND setting_key IN ('websocket_max_message_kb', 'websocket_max_subscriptions', 'websocket_max_filter_length', 'websocket_max_violations', 'websocket_ban_seconds');
@@ -0,0 +1,6 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'websocket_max_message_kb', '64', 'integer', 'Largest message a WebSocket client may send in kilobytes; messages over four times this close the connection', '64', FALSE, FALSE),
+('api', 'websocket_max_subscriptions', '100', 'integer', 'Subscriptions a single WebSocket connection may hold', '100', FALSE, FALSE),
+('api', 'websocket_max_filter_length', '1024', 'integer', 'Combined length in characters of the filter names and values of a WebSocket subscription', '1024', FALSE, FALSE),
+('api', 'websocket_max_violations', '10', 'integer', 'WebSocket limit violations of one user or IP within a minute after which it is disconnected and banned (0 never bans)', '10', FALSE, FALSE),
+('api', 'websocket_ban_seconds', '300', 'integer', 'How long a user or IP banned for WebSocket limit violations may not reconnect', '300', FALSE, FALSE);
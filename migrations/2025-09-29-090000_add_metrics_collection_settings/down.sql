@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'system' AND setting_key IN ('metrics_cpu_sampler_enabled', 'metrics_cpu_sampler_interval_seconds', 'metrics_route_histograms_enabled', 'activity_tracking_enabled');
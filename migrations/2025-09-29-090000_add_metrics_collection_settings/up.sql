@@ -0,0 +1,5 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('system', 'metrics_cpu_sampler_enabled', 'true', 'boolean', 'Sample system CPU usage in the background for metrics and health checks', 'true', FALSE, FALSE),
+('system', 'metrics_cpu_sampler_interval_seconds', '1', 'integer', 'Seconds between CPU usage samples', '1', FALSE, FALSE),
+('system', 'metrics_route_histograms_enabled', 'true', 'boolean', 'Record request duration histograms; per-route histograms are only added or removed on restart', 'true', FALSE, FALSE),
+('system', 'activity_tracking_enabled', 'true', 'boolean', 'Keep the WebSocket activity log shown on the admin dashboard', 'true', FALSE, FALSE);
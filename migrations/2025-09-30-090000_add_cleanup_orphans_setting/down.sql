@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'database' AND setting_key = 'cleanup_orphans_on_start';
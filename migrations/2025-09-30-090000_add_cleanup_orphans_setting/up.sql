@@ -0,0 +1,2 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('database', 'cleanup_orphans_on_start', 'false', 'boolean', 'Drop temporary tables, indexes and triggers left behind by interrupted schema changes at startup; when off they are only reported by the health endpoint', 'false', FALSE, TRUE);
@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'api' AND setting_key IN ('default_version', 'v1_deprecation_date', 'v1_sunset_date');
@@ -0,0 +1,4 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'default_version', '1', 'integer', 'API version of requests without an X-Api-Version header; raise it once clients have moved to the newer response shapes', '1', FALSE, FALSE),
+('api', 'v1_deprecation_date', '', 'string', 'Date (YYYY-MM-DD) announced in the Deprecation header of API version 1 responses; empty while it is not deprecated', '', FALSE, FALSE),
+('api', 'v1_sunset_date', '', 'string', 'Date (YYYY-MM-DD) announced in the Sunset header of API version 1 responses, after which it may be removed; empty while no date is set', '', FALSE, FALSE);
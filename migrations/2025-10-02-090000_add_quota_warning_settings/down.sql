@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'system' AND setting_key IN ('quota_warning_percent', 'quota_warning_email');
@@ -0,0 +1,3 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('system', 'quota_warning_percent', '80', 'integer', 'Warn admins and clients once a quota reaches this percentage of its limit (0 disables warnings)', '80', FALSE, FALSE),
+('system', 'quota_warning_email', '', 'string', 'Address that receives one email per quota crossing its warning threshold; empty sends no email', '', FALSE, FALSE);
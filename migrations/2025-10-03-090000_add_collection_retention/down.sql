@@ -0,0 +1,4 @@
+DELETE FROM system_settings WHERE category = 'database' AND setting_key IN ('retention_interval_seconds', 'retention_batch_size');
+
+DROP TABLE collection_retention_runs;
+ALTER TABLE collections DROP COLUMN retention_json;
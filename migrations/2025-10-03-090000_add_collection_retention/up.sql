@@ -0,0 +1,24 @@
+-- Deletes or archives records once a date field is older than a number of
+-- days, e.g. {"field": "created_at", "max_age_days": 90, "action": "delete"}
+ALTER TABLE collections ADD COLUMN retention_json TEXT;
+
+-- One row per retention run of a collection, updated after every batch so an
+-- interrupted run still reports what it did
+CREATE TABLE collection_retention_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    collection_id INTEGER NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+    action TEXT NOT NULL,
+    cutoff TEXT NOT NULL,
+    processed BIGINT NOT NULL DEFAULT 0,
+    failed BIGINT NOT NULL DEFAULT 0,
+    -- Whether the run got through every record past the cutoff
+    complete BOOLEAN NOT NULL DEFAULT FALSE,
+    started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP
+);
+
+CREATE INDEX idx_collection_retention_runs_collection ON collection_retention_runs(collection_id, started_at);
+
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('database', 'retention_interval_seconds', '3600', 'integer', 'How often collection retention policies run; 0 disables them', '3600', FALSE, FALSE),
+('database', 'retention_batch_size', '500', 'integer', 'Records a retention run deletes or archives per batch before yielding to other work', '500', FALSE, FALSE);
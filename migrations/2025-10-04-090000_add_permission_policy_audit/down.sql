@@ -0,0 +1,20 @@
+CREATE TABLE permission_audit_entries_old (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    actor_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    target_user_id INTEGER NOT NULL,
+    action VARCHAR(32) NOT NULL,
+    collection_name VARCHAR(255),
+    affected_count BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Entries about roles cannot be represented and are dropped
+INSERT INTO permission_audit_entries_old (id, actor_id, target_user_id, action, collection_name, affected_count, created_at)
+SELECT id, actor_id, target_user_id, action, collection_name, affected_count, created_at
+FROM permission_audit_entries
+WHERE target_user_id IS NOT NULL;
+
+DROP TABLE permission_audit_entries;
+ALTER TABLE permission_audit_entries_old RENAME TO permission_audit_entries;
+
+CREATE INDEX idx_permission_audit_entries_target_user_id ON permission_audit_entries(target_user_id);
@@ -0,0 +1,21 @@
+-- Permission policy imports audit changes to roles, which have no target
+-- user, so target_user_id becomes optional and entries name the role instead
+CREATE TABLE permission_audit_entries_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    actor_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    target_user_id INTEGER,
+    action VARCHAR(32) NOT NULL,
+    collection_name VARCHAR(255),
+    role_name VARCHAR(50),
+    affected_count BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO permission_audit_entries_new (id, actor_id, target_user_id, action, collection_name, affected_count, created_at)
+SELECT id, actor_id, target_user_id, action, collection_name, affected_count, created_at
+FROM permission_audit_entries;
+
+DROP TABLE permission_audit_entries;
+ALTER TABLE permission_audit_entries_new RENAME TO permission_audit_entries;
+
+CREATE INDEX idx_permission_audit_entries_target_user_id ON permission_audit_entries(target_user_id);
@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'auth' AND setting_key IN ('password_hash_memory_kib', 'password_hash_iterations', 'password_hash_parallelism');
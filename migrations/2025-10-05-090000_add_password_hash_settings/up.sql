@@ -0,0 +1,4 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('auth', 'password_hash_memory_kib', '65536', 'integer', 'Argon2id memory cost in KiB for newly set passwords (8192-1048576)', '65536', FALSE, FALSE),
+('auth', 'password_hash_iterations', '4', 'integer', 'Argon2id iterations for newly set passwords (1-10)', '4', FALSE, FALSE),
+('auth', 'password_hash_parallelism', '2', 'integer', 'Argon2id lanes for newly set passwords (1-16)', '2', FALSE, FALSE);
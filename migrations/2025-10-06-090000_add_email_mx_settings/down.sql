@@ -0,0 +1 @@
+DELETE FROM system_settings WHERE category = 'auth' AND setting_key IN ('email_mx_check_enabled', 'email_mx_resolver_url');
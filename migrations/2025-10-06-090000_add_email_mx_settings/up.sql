@@ -0,0 +1,3 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('auth', 'email_mx_check_enabled', 'false', 'boolean', 'Reject new user email addresses whose domain has no MX (or A/AAAA) record', 'false', FALSE, FALSE),
+('auth', 'email_mx_resolver_url', 'https://cloudflare-dns.com/dns-query', 'string', 'DNS-over-HTTPS JSON endpoint used for the MX check', 'https://cloudflare-dns.com/dns-query', FALSE, FALSE);
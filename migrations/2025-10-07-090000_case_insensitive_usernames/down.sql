@@ -0,0 +1,3 @@
+DELETE FROM system_settings WHERE category = 'auth' AND setting_key = 'reserved_usernames';
+DROP INDEX IF EXISTS idx_users_username_nocase;
+DROP TABLE IF EXISTS username_renames;
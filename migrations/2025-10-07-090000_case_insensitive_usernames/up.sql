@@ -0,0 +1,59 @@
+-- Usernames that only differ in letter case keep the oldest account's name;
+-- the others are renamed so the unique index below can be built. Renamed
+-- accounts are kept here so admins can tell their owners.
+CREATE TABLE username_renames (
+    user_id INTEGER PRIMARY KEY NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    old_username VARCHAR(100) NOT NULL,
+    new_username VARCHAR(100) NOT NULL,
+    renamed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_users_username_lookup ON users(username COLLATE NOCASE);
+
+-- The new name is the old one cut short enough for a `_<id>` suffix to fit in
+-- 30 characters, or `_<id>x<n>` with the first n that is free. The part after
+-- the last underscore starts with the id, so no two renamed accounts get the
+-- same name, and there are fewer taken names than attempts.
+WITH RECURSIVE attempts(n) AS (
+    SELECT 0
+    UNION ALL
+    SELECT n + 1 FROM attempts WHERE n < (SELECT COUNT(*) FROM users)
+),
+duplicates AS (
+    SELECT id, username FROM users
+    WHERE EXISTS (
+        SELECT 1 FROM users AS older
+        WHERE older.username = users.username COLLATE NOCASE
+          AND older.id < users.id
+    )
+),
+suffixes AS (
+    SELECT duplicates.id AS user_id, duplicates.username AS old_username, attempts.n,
+        CASE WHEN attempts.n = 0 THEN '_' || duplicates.id
+             ELSE '_' || duplicates.id || 'x' || attempts.n END AS suffix
+    FROM duplicates CROSS JOIN attempts
+),
+free AS (
+    SELECT user_id, old_username, n,
+        substr(old_username, 1, 30 - length(suffix)) || suffix AS new_username
+    FROM suffixes
+    WHERE NOT EXISTS (
+        SELECT 1 FROM users
+        WHERE users.username = substr(old_username, 1, 30 - length(suffix)) || suffix COLLATE NOCASE
+    )
+)
+INSERT INTO username_renames (user_id, old_username, new_username)
+SELECT user_id, old_username, new_username FROM free
+WHERE n = (SELECT MIN(n) FROM free AS first_free WHERE first_free.user_id = free.user_id);
+
+UPDATE users
+SET username = (
+    SELECT new_username FROM username_renames WHERE username_renames.user_id = users.id
+)
+WHERE id IN (SELECT user_id FROM username_renames);
+
+DROP INDEX idx_users_username_lookup;
+CREATE UNIQUE INDEX idx_users_username_nocase ON users(username COLLATE NOCASE);
+
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('auth', 'reserved_usernames', '["admin","administrator","root","system","support"]', 'json', 'Usernames nobody can register or be given, compared ignoring case', '["admin","administrator","root","system","support"]', FALSE, FALSE);
@@ -0,0 +1,2 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('auth', 'login_identifier', 'email', 'string', 'What users sign in with: email, username, or either (email is tried first)', 'email', FALSE, FALSE);
@@ -0,0 +1,4 @@
+ALTER TABLE roles DROP COLUMN max_upload_bytes_per_day;
+ALTER TABLE roles DROP COLUMN max_records_created_per_day;
+
+DROP TABLE IF EXISTS user_usage;
@@ -0,0 +1,14 @@
+-- Records created and bytes uploaded per user, in hourly buckets so daily
+-- quotas can be checked over a rolling window
+CREATE TABLE user_usage (
+    user_id INTEGER NOT NULL,
+    bucket_start TIMESTAMP NOT NULL,
+    records_created BIGINT NOT NULL DEFAULT 0,
+    upload_bytes BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, bucket_start),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Optional daily quotas of the users holding the role
+ALTER TABLE roles ADD COLUMN max_records_created_per_day BIGINT;
+ALTER TABLE roles ADD COLUMN max_upload_bytes_per_day BIGINT;
@@ -0,0 +1,4 @@
+DELETE FROM system_settings WHERE category = 'api' AND setting_key IN (
+    'health_check_collections',
+    'health_check_collections_cache_seconds'
+);
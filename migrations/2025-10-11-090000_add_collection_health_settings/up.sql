@@ -0,0 +1,3 @@
+INSERT INTO system_settings (category, setting_key, setting_value, data_type, description, default_value, is_sensitive, requires_restart) VALUES
+('api', 'health_check_collections', 'true', 'boolean', 'Count collections with unhealthy records tables in the admin health report', 'true', FALSE, FALSE),
+('api', 'health_check_collections_cache_seconds', '300', 'integer', 'How long the count of unhealthy collections in the admin health report is cached; checking them reads every records table', '300', FALSE, FALSE);
@@ -0,0 +1,5 @@
+DELETE FROM ingest_failures;
+DELETE FROM ingest_endpoints;
+DROP INDEX IF EXISTS idx_ingest_endpoints_token_hash;
+ALTER TABLE ingest_endpoints RENAME COLUMN token_hash TO token;
+CREATE INDEX idx_ingest_endpoints_token ON ingest_endpoints(token);
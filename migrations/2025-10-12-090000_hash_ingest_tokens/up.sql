@@ -0,0 +1,7 @@
+-- Tokens of the endpoints created so far were stored in plaintext; those
+-- endpoints have to be created again
+DELETE FROM ingest_failures;
+DELETE FROM ingest_endpoints;
+DROP INDEX IF EXISTS idx_ingest_endpoints_token;
+ALTER TABLE ingest_endpoints RENAME COLUMN token TO token_hash;
+CREATE INDEX idx_ingest_endpoints_token_hash ON ingest_endpoints(token_hash);
@@ -0,0 +1,145 @@
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Name of the canonical form below, recorded in export manifests so a
+/// verifier can tell which rules produced the hashes.
+pub const CANONICALIZATION: &str = "lunarbase-canonical-json/1";
+
+/// Canonical JSON text of `value`, the same for equal values across runs:
+///
+/// - no whitespace between tokens
+/// - object keys sorted by Unicode code point, i.e. by their UTF-8 bytes
+/// - array elements in their original order
+/// - strings escape only `"`, `\` and control characters, the latter as
+///   `\b`, `\f`, `\n`, `\r`, `\t` or lowercase `\u00xx`; everything else,
+///   including `/` and non-ASCII characters, is written as is
+/// - integers in plain decimal
+/// - other numbers as the shortest decimal that reads back as the same
+///   64-bit float, never in exponent notation and without a fractional part
+///   when integral (`1.0` is `1`, `-0.0` is `0`, `1e21` is
+///   `1000000000000000000000`)
+/// - `true`, `false` and `null` as is
+pub fn to_canonical_string(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+/// Lowercase hex SHA-256 of the canonical JSON text of `value`.
+pub fn canonical_sha256(value: &Value) -> String {
+    Sha256::digest(to_canonical_string(value).as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(number) => match (number.as_i64(), number.as_u64(), number.as_f64()) {
+            (Some(i), _, _) => out.push_str(&i.to_string()),
+            (None, Some(u), _) => out.push_str(&u.to_string()),
+            (None, None, Some(f)) => out.push_str(&canonical_float(f)),
+            (None, None, None) => out.push_str(&number.to_string()),
+        },
+        Value::String(s) => write_string(s, out),
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+
+            out.push('{');
+            for (index, (key, item)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_string(key, out);
+                out.push(':');
+                write_canonical(item, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn canonical_float(f: f64) -> String {
+    if f == 0.0 {
+        // Covers -0.0
+        return "0".to_string();
+    }
+    // Display of f64 is the shortest round-trip form without an exponent
+    format!("{}", f)
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_keys_are_sorted_at_every_level() {
+        let value = json!({ "b": 1, "a": { "z": true, "é": null, "Z": [3, 1] } });
+        assert_eq!(
+            to_canonical_string(&value),
+            r#"{"a":{"Z":[3,1],"z":true,"é":null},"b":1}"#
+        );
+    }
+
+    #[test]
+    fn test_number_formatting() {
+        let value = json!([1, -7, 1.0, -0.0, 2.5, 0.1, 1e21, 1.5e-7, u64::MAX]);
+        assert_eq!(
+            to_canonical_string(&value),
+            "[1,-7,1,0,2.5,0.1,1000000000000000000000,0.00000015,18446744073709551615]"
+        );
+    }
+
+    #[test]
+    fn test_string_escaping() {
+        let value = json!("a\"b\\c/d\n\u{01}é😀");
+        assert_eq!(
+            to_canonical_string(&value),
+            "\"a\\\"b\\\\c/d\\n\\u0001é😀\""
+        );
+    }
+
+    #[test]
+    fn test_hash_ignores_key_order_and_float_spelling() {
+        let a: Value = serde_json::from_str(r#"{"price": 10.0, "name": "Lamp"}"#).unwrap();
+        let b: Value = serde_json::from_str(r#"{ "name":"Lamp", "price":10 }"#).unwrap();
+        assert_eq!(canonical_sha256(&a), canonical_sha256(&b));
+        assert_eq!(
+            canonical_sha256(&json!({})),
+            // SHA-256 of "{}"
+            "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+    }
+}
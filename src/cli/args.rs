@@ -12,4 +12,6 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     Serve(crate::cli::commands::serve::ServeArgs),
+    Permissions(crate::cli::commands::permissions::PermissionsArgs),
+    Migrate(crate::cli::commands::migrate::MigrateArgs),
 }
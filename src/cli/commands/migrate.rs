@@ -0,0 +1,39 @@
+use clap::Args;
+
+use crate::Config;
+use crate::database::create_pool;
+use crate::database::migrations::migration_status;
+use crate::middleware::setup_logging;
+use crate::server::apply_pending_migrations;
+
+#[derive(Args)]
+#[command(about = "Apply pending database migrations, after a backup when backups are configured")]
+pub struct MigrateArgs {
+    #[arg(long, help = "Only list the applied and pending migrations")]
+    pub status: bool,
+}
+
+pub async fn run_migrate(args: &MigrateArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::from_env()?;
+    let pool = create_pool(&config.database_url)?;
+
+    let status = if args.status {
+        migration_status(&mut pool.get()?)
+            .map_err(|e| format!("Failed to read migration status: {}", e))?
+    } else {
+        setup_logging();
+        apply_pending_migrations(&pool, &config, true).await?
+    };
+
+    println!(
+        "Applied: {} (current version: {})",
+        status.applied,
+        status.current_version.as_deref().unwrap_or("none")
+    );
+    println!("Pending: {}", status.pending.len());
+    for migration in &status.pending {
+        println!("  {}", migration);
+    }
+
+    Ok(())
+}
@@ -1,3 +1,7 @@
+pub mod migrate;
+pub mod permissions;
 pub mod serve;
 
+pub use migrate::*;
+pub use permissions::*;
 pub use serve::*;
@@ -0,0 +1,76 @@
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+use crate::Config;
+use crate::database::create_pool;
+use crate::models::PermissionPolicy;
+use crate::server::apply_pending_migrations;
+use crate::services::{ConfigurationManager, PermissionService};
+use crate::utils::LunarbaseError;
+
+#[derive(Args)]
+#[command(about = "Export or import roles and permissions as a JSON policy document")]
+pub struct PermissionsArgs {
+    #[command(subcommand)]
+    pub command: PermissionsCommand,
+}
+
+#[derive(Subcommand)]
+pub enum PermissionsCommand {
+    #[command(about = "Write the roles, role permissions and user overrides of the database")]
+    Export {
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "File to write the policy to (default: stdout)"
+        )]
+        file: Option<PathBuf>,
+    },
+    #[command(about = "Sync the database with a policy document and print the changes")]
+    Import {
+        #[arg(long, value_name = "PATH", help = "Policy document to import")]
+        file: PathBuf,
+
+        #[arg(long, help = "Only print the changes and errors without applying them")]
+        dry_run: bool,
+    },
+}
+
+/// Runs against `DATABASE_URL` directly, so CI can sync permissions without a
+/// running server. Fails when the document has errors, dry run or not.
+pub async fn run_permissions(args: &PermissionsArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::from_env()?;
+    let pool = create_pool(&config.database_url)?;
+    apply_pending_migrations(&pool, &config, true).await?;
+    let permission_service = PermissionService::new(pool.clone(), ConfigurationManager::new(pool));
+
+    match &args.command {
+        PermissionsCommand::Export { file } => {
+            let policy = permission_service.export_policy().await?;
+            let document = serde_json::to_string_pretty(&policy)?;
+            match file {
+                Some(path) => std::fs::write(path, document + "\n")?,
+                None => println!("{}", document),
+            }
+        }
+        PermissionsCommand::Import { file, dry_run } => {
+            let document = std::fs::read_to_string(file)?;
+            let policy: PermissionPolicy = serde_json::from_str(&document)
+                .map_err(|e| format!("{}: {}", file.display(), e))?;
+
+            let report = permission_service
+                .import_policy(&policy, *dry_run, None)
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+
+            if !report.errors.is_empty() {
+                return Err(LunarbaseError::ValidationError(
+                    report.errors.iter().map(ToString::to_string).collect(),
+                )
+                .into());
+            }
+        }
+    }
+
+    Ok(())
+}
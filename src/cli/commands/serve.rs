@@ -1,21 +1,127 @@
-use clap::Args;
+use clap::{Args, ValueEnum};
 use std::path::PathBuf;
 
+/// How ACME proves control of the certificate domains.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum AcmeChallenge {
+    /// Answered on the TLS listener itself
+    #[default]
+    #[value(name = "tls-alpn-01")]
+    TlsAlpn01,
+    /// Answered on the HTTP redirect listener, for hosts where only port 80 is reachable
+    #[value(name = "http-01")]
+    Http01,
+}
+
+impl AcmeChallenge {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AcmeChallenge::TlsAlpn01 => "tls-alpn-01",
+            AcmeChallenge::Http01 => "http-01",
+        }
+    }
+}
+
 #[derive(Args)]
 #[command(about = "Start the LunarBase server")]
 pub struct ServeArgs {
     #[arg(short = 'H', long, help = "Host to bind the server to")]
     pub host: Option<String>,
 
+    #[arg(
+        long = "bind",
+        value_name = "ADDR",
+        help = "Address to listen on, e.g. 0.0.0.0:3000 or [::]:3000 (can be specified multiple times)"
+    )]
+    pub bind: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Listen on a Unix domain socket, e.g. /run/lunarbase.sock"
+    )]
+    pub uds: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "MODE",
+        help = "Octal permissions of the Unix socket (default: 660)"
+    )]
+    pub uds_mode: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "UID[:GID]",
+        help = "Numeric owner and group of the Unix socket"
+    )]
+    pub uds_owner: Option<String>,
+
+    #[arg(
+        long,
+        help = "Disable HTTP/2 on the TLS listeners (overrides the api.http2_enabled setting)"
+    )]
+    pub no_http2: bool,
+
+    #[arg(
+        long,
+        help = "Accept HTTP/2 with prior knowledge (h2c) on plain listeners, for trusted reverse proxies"
+    )]
+    pub http2_cleartext: bool,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u32).range(1..=3600),
+        help = "Idle keep-alive timeout of client connections (overrides api.keep_alive_timeout_seconds)"
+    )]
+    pub keep_alive_timeout: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..=10_000),
+        help = "Maximum concurrent HTTP/2 streams per connection (overrides api.http2_max_concurrent_streams)"
+    )]
+    pub max_concurrent_streams: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "KB",
+        value_parser = clap::value_parser!(u32).range(8..=1024),
+        help = "Maximum size of the request headers (overrides api.max_header_size_kb)"
+    )]
+    pub max_header_size_kb: Option<u32>,
+
     #[arg(short, long, help = "Path to configuration file")]
     pub config: Option<PathBuf>,
 
-    #[arg(long, help = "Run API-only mode without frontend")]
+    #[arg(
+        long,
+        help = "Run API-only mode without the admin panel, Swagger UI and avatar proxy (or set LUNARBASE_API_ONLY=true)"
+    )]
     pub api_only: bool,
 
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        default_value_t = true,
+        default_missing_value = "true",
+        action = clap::ArgAction::Set,
+        help = "Apply pending database migrations at startup, after a backup when backups are configured; with false the server refuses to start while migrations are pending"
+    )]
+    pub auto_migrate: bool,
+
     #[arg(long, help = "Enable HTTP to HTTPS redirect server")]
     pub enable_redirect: bool,
 
+    #[arg(
+        long = "redirect-port",
+        value_name = "PORT",
+        help = "Port of the HTTP redirect server, which also answers ACME HTTP-01 challenges (default: 80)"
+    )]
+    pub redirect_port: Option<u16>,
+
     #[arg(
         long,
         help = "Enable ACME/Let's Encrypt automatic certificate management"
@@ -28,6 +134,13 @@ pub struct ServeArgs {
     )]
     pub acme_domain: Vec<String>,
 
+    #[arg(
+        long,
+        value_enum,
+        help = "ACME challenge type; http-01 starts the redirect server even without --enable-redirect (or set ACME_CHALLENGE)"
+    )]
+    pub acme_challenge: Option<AcmeChallenge>,
+
     #[arg(long, help = "Contact email for ACME registration")]
     pub acme_email: Option<String>,
 
@@ -55,7 +168,7 @@ impl ServeArgs {
     }
 
     pub fn redirect_port(&self) -> u16 {
-        80
+        self.redirect_port.unwrap_or(80)
     }
 
     pub fn redirect_target_port(&self) -> u16 {
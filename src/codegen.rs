@@ -0,0 +1,232 @@
+use crate::models::{CollectionResponse, FieldType};
+use std::fmt::Write;
+
+const FILTER_OPERATORS: [&str; 14] = [
+    "eq",
+    "ne",
+    "gt",
+    "gte",
+    "lt",
+    "lte",
+    "like",
+    "notlike",
+    "in",
+    "notin",
+    "isnull",
+    "isnotnull",
+    "bbox",
+    "near",
+];
+
+const SYSTEM_FIELDS: [&str; 3] = ["id", "created_at", "updated_at"];
+
+pub fn generate_typescript(collections: &[CollectionResponse]) -> String {
+    let mut collections: Vec<&CollectionResponse> = collections.iter().collect();
+    collections.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut output = String::new();
+
+    output.push_str("// This file is generated by LunarBase. Do not edit it manually.\n\n");
+
+    let operators = FILTER_OPERATORS
+        .iter()
+        .map(|op| format!("\"{}\"", op))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    let _ = writeln!(output, "export type FilterOperator = {};\n", operators);
+
+    output.push_str(
+        "export type Filter<F extends string> =\n  | `${F}:${Exclude<FilterOperator, \"isnull\" | \"isnotnull\">}:${string}`\n  | `${F}:isnull`\n  | `${F}:isnotnull`;\n\n",
+    );
+    output.push_str("export type Sort<F extends string> = F | `-${F}`;\n\n");
+    output.push_str(
+        "export function filter<F extends string>(...conditions: Filter<F>[]): string {\n  return conditions.join(\",\");\n}\n\n",
+    );
+    output.push_str(
+        "export function sort<F extends string>(...fields: Sort<F>[]): string {\n  return fields.join(\",\");\n}\n",
+    );
+
+    for collection in &collections {
+        let type_name = to_pascal_case(&collection.name);
+
+        let _ = writeln!(output, "\nexport interface {}Data {{", type_name);
+        for field in &collection.schema.fields {
+            let ts_type = field_type_to_typescript(&field.field_type);
+            if field.required {
+                let _ = writeln!(output, "  {}: {};", property_name(&field.name), ts_type);
+            } else {
+                let _ = writeln!(
+                    output,
+                    "  {}?: {} | null;",
+                    property_name(&field.name),
+                    ts_type
+                );
+            }
+        }
+        output.push_str("  owner_id?: number;\n");
+        output.push_str("  author_id?: number;\n");
+        output.push_str("}\n");
+
+        let _ = writeln!(output, "\nexport interface {}Record {{", type_name);
+        output.push_str("  id: string;\n");
+        output.push_str("  collection_id: string;\n");
+        let _ = writeln!(output, "  data: {}Data;", type_name);
+        output.push_str("  created_at: string;\n");
+        output.push_str("  updated_at: string;\n");
+        output.push_str("}\n");
+
+        let fields = SYSTEM_FIELDS
+            .iter()
+            .map(|name| name.to_string())
+            .chain(collection.schema.fields.iter().map(|f| f.name.clone()))
+            .map(|name| format!("\"{}\"", name))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        let _ = writeln!(output, "\nexport type {}Field = {};", type_name, fields);
+        let _ = writeln!(
+            output,
+            "export type {0}Filter = Filter<{0}Field>;",
+            type_name
+        );
+        let _ = writeln!(output, "export type {0}Sort = Sort<{0}Field>;", type_name);
+    }
+
+    output.push_str("\nexport interface Collections {\n");
+    for collection in &collections {
+        let _ = writeln!(
+            output,
+            "  {}: {}Record;",
+            property_name(&collection.name),
+            to_pascal_case(&collection.name)
+        );
+    }
+    output.push_str("}\n");
+
+    output
+}
+
+fn field_type_to_typescript(field_type: &FieldType) -> &'static str {
+    match field_type {
+        FieldType::Text
+        | FieldType::RichText
+        | FieldType::Email
+        | FieldType::Url
+        | FieldType::File
+        | FieldType::Relation
+        | FieldType::Slug => "string",
+        FieldType::Number => "number",
+        FieldType::Boolean => "boolean",
+        FieldType::Date | FieldType::Decimal => "string",
+        FieldType::Json => "unknown",
+        FieldType::GeoPoint => "{ lat: number; lng: number }",
+    }
+}
+
+pub(crate) fn to_pascal_case(name: &str) -> String {
+    name.split(['_', '-'])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn property_name(name: &str) -> String {
+    let is_identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    if is_identifier {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CollectionSchema, CollectionWorkflow, FieldDefinition, RecordIdType};
+
+    fn collection(name: &str, fields: Vec<(&str, FieldType, bool)>) -> CollectionResponse {
+        CollectionResponse {
+            id: 1,
+            name: name.to_string(),
+            display_name: None,
+            description: None,
+            schema: CollectionSchema {
+                fields: fields
+                    .into_iter()
+                    .map(|(name, field_type, required)| FieldDefinition {
+                        name: name.to_string(),
+                        field_type,
+                        required,
+                        default_value: None,
+                        validation: None,
+                        relation_target: None,
+                        description: None,
+                        example: None,
+                        indexed_paths: None,
+                    })
+                    .collect(),
+            },
+            schema_version: 1,
+            query_cache_ttl_seconds: 0,
+            orderable: false,
+            id_type: RecordIdType::Integer,
+            allow_explicit_ids: false,
+            workflow: CollectionWorkflow::None,
+            schedule: None,
+            retention: None,
+            workspace_id: 1,
+            is_system: false,
+            created_at: "2024-01-01 12:00:00".to_string(),
+            updated_at: "2024-01-01 12:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_generate_typescript_interfaces() {
+        let output = generate_typescript(&[collection(
+            "blog_posts",
+            vec![
+                ("title", FieldType::Text, true),
+                ("views", FieldType::Number, false),
+                ("published_on", FieldType::Date, false),
+                ("meta", FieldType::Json, false),
+                ("author", FieldType::Relation, true),
+            ],
+        )]);
+
+        assert!(output.contains("export interface BlogPostsData {"));
+        assert!(output.contains("  title: string;"));
+        assert!(output.contains("  views?: number | null;"));
+        assert!(output.contains("  published_on?: string | null;"));
+        assert!(output.contains("  meta?: unknown | null;"));
+        assert!(output.contains("  author: string;"));
+        assert!(output.contains("  owner_id?: number;"));
+        assert!(output.contains("export interface BlogPostsRecord {"));
+        assert!(output.contains(
+            "export type BlogPostsField = \"id\" | \"created_at\" | \"updated_at\" | \"title\" | \"views\" | \"published_on\" | \"meta\" | \"author\";"
+        ));
+        assert!(output.contains("  blog_posts: BlogPostsRecord;"));
+    }
+
+    #[test]
+    fn test_generate_typescript_is_deterministic() {
+        let first = collection("zebras", vec![("name", FieldType::Text, true)]);
+        let second = collection("apples", vec![("name", FieldType::Text, true)]);
+
+        let output = generate_typescript(&[first.clone(), second.clone()]);
+        assert_eq!(output, generate_typescript(&[second, first]));
+        assert!(output.find("ApplesData").unwrap() < output.find("ZebrasData").unwrap());
+    }
+}
@@ -1,6 +1,10 @@
-use crate::cli::commands::serve::ServeArgs;
+use crate::cli::commands::serve::{AcmeChallenge, ServeArgs};
+use crate::listeners::parse_bind_addresses;
 use crate::services::configuration_service::ConfigurationService;
+use clap::ValueEnum;
 use serde::Deserialize;
+use std::net::SocketAddr;
+use std::path::PathBuf;
 
 #[derive(Debug, Deserialize, Clone, Default)]
 pub struct Config {
@@ -17,8 +21,14 @@ pub struct Config {
     pub admin_email: Option<String>,
     pub admin_password: Option<String>,
     pub admin_username: Option<String>,
+    pub admin_force_password: bool,
     pub resend_api_key: Option<String>,
     pub email_from: Option<String>,
+    /// Absolute URL the API is reachable at: email verification links and
+    /// OAuth callbacks point here
+    pub public_api_url: String,
+    /// Absolute URL of the frontend: password reset links and redirects
+    /// after sign-in point here
     pub frontend_url: String,
     pub s3_bucket_name: Option<String>,
     pub s3_region: Option<String>,
@@ -30,6 +40,16 @@ pub struct Config {
     pub acme_email: Option<String>,
     pub acme_cache_dir: Option<String>,
     pub acme_production: Option<bool>,
+    #[serde(skip)]
+    pub acme_challenge: AcmeChallenge,
+    /// Serve only the API: no admin panel, Swagger UI or avatar proxy
+    pub api_only: bool,
+    /// TCP addresses to listen on; the server address when empty and no
+    /// Unix socket is configured
+    pub bind_addresses: Vec<String>,
+    pub unix_socket_path: Option<PathBuf>,
+    pub unix_socket_mode: Option<String>,
+    pub unix_socket_owner: Option<String>,
 }
 
 impl Config {
@@ -48,6 +68,14 @@ impl Config {
             ("127.0.0.1".to_string(), 3000)
         };
 
+        // Both URLs default to the server address until `urls.*` settings
+        // are loaded
+        let server_url = Self::build_server_url(
+            &server_host,
+            server_port,
+            server_port == 443, // Use HTTPS if port is 443
+        );
+
         let config = Config {
             database_url: std::env::var("DATABASE_URL").unwrap_or_else(|_| "db.sqlite".to_string()),
             server_host: server_host.clone(),
@@ -64,13 +92,13 @@ impl Config {
             admin_email: std::env::var("LUNARBASE_ADMIN_EMAIL").ok(),
             admin_password: std::env::var("LUNARBASE_ADMIN_PASSWORD").ok(),
             admin_username: std::env::var("LUNARBASE_ADMIN_USERNAME").ok(),
+            admin_force_password: std::env::var("LUNARBASE_ADMIN_FORCE_PASSWORD")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
             resend_api_key: None,
             email_from: None,
-            frontend_url: Self::build_frontend_url(
-                &server_host,
-                server_port,
-                server_port == 443, // Use HTTPS if port is 443
-            ),
+            public_api_url: server_url.clone(),
+            frontend_url: server_url,
             s3_bucket_name: None,
             s3_region: None,
             s3_access_key_id: None,
@@ -109,6 +137,34 @@ impl Config {
                     .ok()
                     .and_then(|v| v.parse().ok())
             },
+            acme_challenge: serve_args
+                .and_then(|args| args.acme_challenge)
+                .or_else(|| {
+                    std::env::var("ACME_CHALLENGE")
+                        .ok()
+                        .and_then(|v| AcmeChallenge::from_str(&v, true).ok())
+                })
+                .unwrap_or_default(),
+            api_only: serve_args.is_some_and(|args| args.api_only)
+                || std::env::var("LUNARBASE_API_ONLY")
+                    .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                    .unwrap_or(false),
+            bind_addresses: match serve_args {
+                Some(args) if !args.bind.is_empty() => args.bind.clone(),
+                _ => std::env::var("LUNARBASE_BIND")
+                    .ok()
+                    .map(|binds| binds.split(',').map(|s| s.trim().to_string()).collect())
+                    .unwrap_or_default(),
+            },
+            unix_socket_path: serve_args
+                .and_then(|args| args.uds.clone())
+                .or_else(|| std::env::var("LUNARBASE_UDS").ok().map(PathBuf::from)),
+            unix_socket_mode: serve_args
+                .and_then(|args| args.uds_mode.clone())
+                .or_else(|| std::env::var("LUNARBASE_UDS_MODE").ok()),
+            unix_socket_owner: serve_args
+                .and_then(|args| args.uds_owner.clone())
+                .or_else(|| std::env::var("LUNARBASE_UDS_OWNER").ok()),
         };
 
         Ok(config)
@@ -118,7 +174,19 @@ impl Config {
         format!("{}:{}", self.server_host, self.server_port)
     }
 
-    fn build_frontend_url(host: &str, port: u16, is_https: bool) -> String {
+    /// TCP addresses to listen on. Without `--bind` this is the server address,
+    /// unless only a Unix socket was asked for.
+    pub fn tcp_bind_addresses(&self) -> Result<Vec<SocketAddr>, String> {
+        if !self.bind_addresses.is_empty() {
+            return parse_bind_addresses(&self.bind_addresses);
+        }
+        if self.unix_socket_path.is_some() {
+            return Ok(Vec::new());
+        }
+        parse_bind_addresses(&[self.server_address()])
+    }
+
+    fn build_server_url(host: &str, port: u16, is_https: bool) -> String {
         let scheme = if is_https { "https" } else { "http" };
         if (is_https && port == 443) || (!is_https && port == 80) {
             format!("{}://{}", scheme, host)
@@ -127,6 +195,24 @@ impl Config {
         }
     }
 
+    /// Replaces the URLs derived from the server address with the configured
+    /// ones. Empty values keep the derived URL.
+    pub fn apply_public_urls(
+        &mut self,
+        public_api_url: &str,
+        frontend_url: &str,
+    ) -> Result<(), String> {
+        if !public_api_url.is_empty() {
+            self.public_api_url = normalize_absolute_url(public_api_url)
+                .map_err(|e| format!("urls.public_api_url {}", e))?;
+        }
+        if !frontend_url.is_empty() {
+            self.frontend_url = normalize_absolute_url(frontend_url)
+                .map_err(|e| format!("urls.frontend_url {}", e))?;
+        }
+        Ok(())
+    }
+
     pub fn has_admin_config(&self) -> bool {
         self.admin_email.is_some() && self.admin_password.is_some() && self.admin_username.is_some()
     }
@@ -226,6 +312,33 @@ impl Config {
             }
         }
 
+        let public_api_url = config_service
+            .get_setting_value("urls", "public_api_url")
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let frontend_url = config_service
+            .get_setting_value("urls", "frontend_url")
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        self.apply_public_urls(&public_api_url, &frontend_url)?;
+
         Ok(())
     }
 }
+
+/// Checks that `value` is an absolute http(s) URL and returns it without a
+/// trailing slash, so paths can be appended with `format!("{}/...")`.
+pub fn normalize_absolute_url(value: &str) -> Result<String, String> {
+    let url = reqwest::Url::parse(value).map_err(|_| "must be an absolute URL".to_string())?;
+    if !matches!(url.scheme(), "http" | "https") || !url.has_host() {
+        return Err("must be an absolute http(s) URL".to_string());
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err("must not contain a query or fragment".to_string());
+    }
+    Ok(value.trim_end_matches('/').to_string())
+}
@@ -0,0 +1,162 @@
+use diesel::migration::{Migration, MigrationVersion};
+use diesel::sqlite::{Sqlite, SqliteConnection};
+use diesel_migrations::MigrationHarness;
+use serde::Serialize;
+
+use crate::server::MIGRATIONS;
+
+type MigrationResult<T> = diesel::migration::Result<T>;
+
+/// Migrations applied to the database compared to those embedded in the binary.
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    /// Version of the newest applied migration
+    pub current_version: Option<String>,
+    pub applied: usize,
+    /// Embedded migrations not applied yet, oldest first
+    pub pending: Vec<String>,
+}
+
+impl MigrationStatus {
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Explains why the server will not start while migrations are pending.
+    pub fn pending_message(&self) -> String {
+        format!(
+            "{} database migration(s) pending: {}. Run `lunarbase migrate` or start with --auto-migrate",
+            self.pending.len(),
+            self.pending.join(", ")
+        )
+    }
+}
+
+pub fn migration_status(conn: &mut SqliteConnection) -> MigrationResult<MigrationStatus> {
+    let applied: Vec<MigrationVersion<'static>> = conn.applied_migrations()?;
+    let pending: Vec<Box<dyn Migration<Sqlite>>> = conn.pending_migrations(MIGRATIONS)?;
+
+    Ok(MigrationStatus {
+        current_version: applied.iter().max().map(ToString::to_string),
+        applied: applied.len(),
+        pending: pending
+            .iter()
+            .map(|migration| migration.name().to_string())
+            .collect(),
+    })
+}
+
+/// Applies the pending migrations and returns their versions.
+pub fn run_migrations(conn: &mut SqliteConnection) -> MigrationResult<Vec<String>> {
+    Ok(conn
+        .run_pending_migrations(MIGRATIONS)?
+        .iter()
+        .map(ToString::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::create_pool;
+
+    #[test]
+    fn test_status_lists_pending_migrations_until_applied() {
+        let path = std::env::temp_dir().join(format!(
+            "lunarbase_migrations_{}.sqlite",
+            uuid::Uuid::new_v4().simple()
+        ));
+        let pool = create_pool(path.to_str().unwrap()).expect("Failed to create pool");
+        let mut conn = pool.get().unwrap();
+
+        let status = migration_status(&mut conn).unwrap();
+        assert_eq!(status.applied, 0);
+        assert_eq!(status.current_version, None);
+        assert!(!status.is_up_to_date());
+        assert!(status.pending_message().contains(&status.pending[0]));
+
+        let applied = run_migrations(&mut conn).unwrap();
+        assert_eq!(applied.len(), status.pending.len());
+
+        let status = migration_status(&mut conn).unwrap();
+        assert!(status.is_up_to_date());
+        assert_eq!(status.applied, applied.len());
+        assert_eq!(status.current_version.as_ref(), applied.last());
+    }
+
+    #[test]
+    fn test_usernames_differing_in_case_are_renamed_apart() {
+        use diesel::prelude::*;
+
+        use crate::schema::{username_renames, users};
+
+        let path = std::env::temp_dir().join(format!(
+            "lunarbase_migrations_{}.sqlite",
+            uuid::Uuid::new_v4().simple()
+        ));
+        let pool = create_pool(path.to_str().unwrap()).expect("Failed to create pool");
+        let mut conn = pool.get().unwrap();
+
+        while !migration_status(&mut conn).unwrap().pending[0]
+            .ends_with("case_insensitive_usernames")
+        {
+            conn.run_next_migration(MIGRATIONS).unwrap();
+        }
+        // `Bob` cannot simply become `Bob_2`, which `bob_2` already has in
+        // another case, and a long name must stay within 30 characters.
+        for (id, username) in [
+            (1, "bob"),
+            (2, "Bob"),
+            (3, "BOB_2"),
+            (4, "abcdefghijabcdefghijabcdefghij"),
+            (5, "ABCDEFGHIJabcdefghijabcdefghij"),
+        ] {
+            diesel::insert_into(users::table)
+                .values((
+                    users::id.eq(id),
+                    users::email.eq(format!("user{}@example.com", id)),
+                    users::password_hash.eq("hash"),
+                    users::username.eq(username),
+                ))
+                .execute(&mut conn)
+                .unwrap();
+        }
+        run_migrations(&mut conn).unwrap();
+
+        let renames: Vec<(i32, String, String)> = username_renames::table
+            .order(username_renames::user_id)
+            .select((
+                username_renames::user_id,
+                username_renames::old_username,
+                username_renames::new_username,
+            ))
+            .load(&mut conn)
+            .unwrap();
+        assert_eq!(
+            renames,
+            vec![
+                (2, "Bob".to_string(), "Bob_2x1".to_string()),
+                (
+                    5,
+                    "ABCDEFGHIJabcdefghijabcdefghij".to_string(),
+                    "ABCDEFGHIJabcdefghijabcdefgh_5".to_string()
+                ),
+            ]
+        );
+        let usernames: Vec<String> = users::table
+            .order(users::id)
+            .select(users::username)
+            .load(&mut conn)
+            .unwrap();
+        assert_eq!(
+            usernames,
+            [
+                "bob",
+                "Bob_2x1",
+                "BOB_2",
+                "abcdefghijabcdefghijabcdefghij",
+                "ABCDEFGHIJabcdefghijabcdefgh_5"
+            ]
+        );
+    }
+}
@@ -1,10 +1,33 @@
+pub mod migrations;
+pub mod prepared;
+pub mod transaction;
+
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool, PoolError, PooledConnection};
+use diesel::sql_types::{Double, Nullable, Text};
 use diesel::sqlite::SqliteConnection;
 use std::env;
 
 pub type DatabasePool = Pool<ConnectionManager<SqliteConnection>>;
 
+/// Mean Earth radius used for great-circle distances.
+const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+diesel::define_sql_function! {
+    /// Great-circle distance in kilometres between two points given in degrees.
+    fn haversine_km(
+        lat1: Nullable<Double>,
+        lng1: Nullable<Double>,
+        lat2: Nullable<Double>,
+        lng2: Nullable<Double>,
+    ) -> Nullable<Double>;
+}
+
+diesel::define_sql_function! {
+    /// SQLite `lower()`, which folds ASCII letters only.
+    fn lower(value: Text) -> Text;
+}
+
 pub fn create_pool(database_url: &str) -> Result<DatabasePool, PoolError> {
     create_pool_with_size(database_url, 10)
 }
@@ -39,10 +62,28 @@ impl diesel::r2d2::CustomizeConnection<SqliteConnection, diesel::r2d2::Error>
             .execute(conn)
             .map_err(|e| diesel::r2d2::Error::QueryError(e))?;
 
+        // The bundled SQLite is built without its math functions, so `near`
+        // filters rely on this one
+        haversine_km_utils::register_impl(
+            conn,
+            |lat1: Option<f64>, lng1: Option<f64>, lat2: Option<f64>, lng2: Option<f64>| {
+                Some(haversine_distance_km(lat1?, lng1?, lat2?, lng2?))
+            },
+        )
+        .map_err(diesel::r2d2::Error::QueryError)?;
+
         Ok(())
     }
 }
 
+pub fn haversine_distance_km(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lng = (lng2 - lng1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lng / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+}
+
 pub fn get_connection(
     pool: &DatabasePool,
 ) -> Result<PooledConnection<ConnectionManager<SqliteConnection>>, PoolError> {
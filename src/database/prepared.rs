@@ -0,0 +1,142 @@
+use diesel::query_builder::{AstPass, Query, QueryFragment, QueryId};
+use diesel::sql_types::{BigInt, Bool, Double, Nullable, Text, Untyped};
+use diesel::sqlite::Sqlite;
+use diesel::{QueryResult, RunQueryDsl};
+
+/// A value bound to a `?` placeholder of a [`PreparedSql`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlBind {
+    Text(Option<String>),
+    Double(Option<f64>),
+    BigInt(Option<i64>),
+    Bool(Option<bool>),
+}
+
+/// Raw SQL with `?` placeholders that, unlike `diesel::sql_query`, diesel's
+/// per-connection statement cache keeps prepared: it is keyed by the SQL text,
+/// so the text must only vary with the statement's shape and never with the
+/// bound values. Every `?` in the text is a placeholder, so none may appear in
+/// literals or comments.
+#[derive(Debug, Clone)]
+pub struct PreparedSql {
+    sql: String,
+    binds: Vec<SqlBind>,
+}
+
+impl PreparedSql {
+    pub fn new(sql: impl Into<String>) -> Self {
+        Self {
+            sql: sql.into(),
+            binds: Vec::new(),
+        }
+    }
+
+    pub fn bind(mut self, value: SqlBind) -> Self {
+        self.binds.push(value);
+        self
+    }
+
+    pub fn binds(mut self, values: impl IntoIterator<Item = SqlBind>) -> Self {
+        self.binds.extend(values);
+        self
+    }
+
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+}
+
+impl QueryFragment<Sqlite> for PreparedSql {
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Sqlite>) -> QueryResult<()> {
+        let mut segments = self.sql.split('?');
+        out.push_sql(segments.next().unwrap_or_default());
+        let mut binds = self.binds.iter();
+        for segment in segments {
+            // Diesel writes the placeholder itself when pushing a bind
+            match binds.next() {
+                Some(SqlBind::Text(value)) => out.push_bind_param::<Nullable<Text>, _>(value)?,
+                Some(SqlBind::Double(value)) => {
+                    out.push_bind_param::<Nullable<Double>, _>(value)?
+                }
+                Some(SqlBind::BigInt(value)) => {
+                    out.push_bind_param::<Nullable<BigInt>, _>(value)?
+                }
+                Some(SqlBind::Bool(value)) => out.push_bind_param::<Nullable<Bool>, _>(value)?,
+                None => return Err(placeholder_mismatch()),
+            }
+            out.push_sql(segment);
+        }
+        match binds.next() {
+            Some(_) => Err(placeholder_mismatch()),
+            None => Ok(()),
+        }
+    }
+}
+
+fn placeholder_mismatch() -> diesel::result::Error {
+    diesel::result::Error::QueryBuilderError(
+        "number of bound values does not match the `?` placeholders".into(),
+    )
+}
+
+impl QueryId for PreparedSql {
+    type QueryId = ();
+
+    const HAS_STATIC_QUERY_ID: bool = false;
+}
+
+impl Query for PreparedSql {
+    type SqlType = Untyped;
+}
+
+impl<Conn> RunQueryDsl<Conn> for PreparedSql {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::{Connection, SqliteConnection};
+
+    #[derive(Debug, diesel::QueryableByName)]
+    struct Row {
+        #[diesel(sql_type = Nullable<Text>)]
+        title: Option<String>,
+        #[diesel(sql_type = Nullable<Double>)]
+        score: Option<f64>,
+    }
+
+    #[test]
+    fn test_binds_values_and_is_cacheable() {
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        diesel::sql_query("CREATE TABLE items (id INTEGER PRIMARY KEY, title TEXT, score REAL)")
+            .execute(&mut conn)
+            .unwrap();
+
+        let insert = |title: &str, score: Option<f64>| {
+            PreparedSql::new("INSERT INTO items (title, score) VALUES (?, ?)")
+                .bind(SqlBind::Text(Some(title.to_string())))
+                .bind(SqlBind::Double(score))
+        };
+        assert!(
+            insert("first", None)
+                .is_safe_to_cache_prepared(&Sqlite)
+                .unwrap()
+        );
+        insert("it's", Some(1.5)).execute(&mut conn).unwrap();
+        insert("second", None).execute(&mut conn).unwrap();
+
+        let rows: Vec<Row> = PreparedSql::new("SELECT title, score FROM items WHERE id = ?")
+            .bind(SqlBind::Text(Some("1".to_string())))
+            .load(&mut conn)
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].title.as_deref(), Some("it's"));
+        assert_eq!(rows[0].score, Some(1.5));
+
+        let missing_bind =
+            PreparedSql::new("SELECT title, score FROM items WHERE id = ?").load::<Row>(&mut conn);
+        assert!(matches!(
+            missing_bind,
+            Err(diesel::result::Error::QueryBuilderError(_))
+        ));
+    }
+}
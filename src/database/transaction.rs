@@ -0,0 +1,144 @@
+use diesel::r2d2::PoolError;
+use diesel::sqlite::SqliteConnection;
+use std::future::Future;
+use std::pin::Pin;
+
+use super::DatabasePool;
+
+type SideEffect<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// Work that cannot be rolled back, such as deleting files from S3, sending
+/// emails or emitting events. It is collected while a transaction runs and
+/// only started once that transaction has committed.
+#[derive(Default)]
+pub struct AfterCommit<'a> {
+    effects: Vec<SideEffect<'a>>,
+}
+
+impl<'a> AfterCommit<'a> {
+    pub fn defer(&mut self, effect: impl Future<Output = ()> + Send + 'a) {
+        self.effects.push(Box::pin(effect));
+    }
+
+    /// One after another, in the order they were deferred.
+    async fn run(self) {
+        for effect in self.effects {
+            effect.await;
+        }
+    }
+}
+
+/// Runs `work` in an immediate transaction on a pooled connection, then the
+/// side effects it deferred. When `work` or the commit fails, they are
+/// dropped without running, so e.g. no file is deleted for a record that was
+/// never updated. The connection goes back to the pool before the side
+/// effects start, so slow S3 or SMTP calls do not hold it.
+pub async fn transaction_then<'a, T, E>(
+    pool: &DatabasePool,
+    work: impl FnOnce(&mut SqliteConnection, &mut AfterCommit<'a>) -> Result<T, E>,
+) -> Result<T, E>
+where
+    E: From<diesel::result::Error> + From<PoolError>,
+{
+    let mut after_commit = AfterCommit::default();
+    let value = {
+        let mut conn = pool.get()?;
+        conn.immediate_transaction(|conn| work(conn, &mut after_commit))?
+    };
+
+    after_commit.run().await;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::create_pool_with_size;
+    use crate::utils::LunarbaseError;
+    use diesel::RunQueryDsl;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, diesel::QueryableByName)]
+    struct CountRow {
+        #[diesel(sql_type = diesel::sql_types::BigInt)]
+        count: i64,
+    }
+
+    /// A single connection, so the side effects can only read once the
+    /// transaction's connection is back in the pool.
+    fn pool_with_files_table() -> DatabasePool {
+        let pool = create_pool_with_size(":memory:", 1).unwrap();
+        diesel::sql_query("CREATE TABLE files (url TEXT NOT NULL)")
+            .execute(&mut pool.get().unwrap())
+            .unwrap();
+        pool
+    }
+
+    fn count_files(pool: &DatabasePool) -> i64 {
+        diesel::sql_query("SELECT COUNT(*) AS count FROM files")
+            .get_result::<CountRow>(&mut pool.get().unwrap())
+            .unwrap()
+            .count
+    }
+
+    #[tokio::test]
+    async fn test_side_effects_run_after_commit() {
+        let pool = pool_with_files_table();
+        let deleted = Arc::new(Mutex::new(Vec::new()));
+
+        let result = transaction_then::<_, LunarbaseError>(&pool, |conn, after_commit| {
+            diesel::sql_query("INSERT INTO files (url) VALUES ('new.png')").execute(conn)?;
+            let deleted = deleted.clone();
+            let pool = &pool;
+            after_commit.defer(async move {
+                // Sees the committed row on the same, now released, connection
+                deleted.lock().unwrap().push(("old.png", count_files(pool)));
+            });
+            Ok("updated")
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "updated");
+        assert_eq!(*deleted.lock().unwrap(), vec![("old.png", 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_write_rolls_back_and_skips_side_effects() {
+        let pool = pool_with_files_table();
+        let deleted = Arc::new(Mutex::new(Vec::new()));
+
+        let result = transaction_then::<(), LunarbaseError>(&pool, |conn, after_commit| {
+            diesel::sql_query("INSERT INTO files (url) VALUES ('new.png')").execute(conn)?;
+            let deleted = deleted.clone();
+            after_commit.defer(async move {
+                deleted.lock().unwrap().push("old.png");
+            });
+            // Fails the write after the file deletion was already deferred
+            diesel::sql_query("INSERT INTO files (url) VALUES (NULL)").execute(conn)?;
+            Ok(())
+        })
+        .await;
+
+        assert!(matches!(result, Err(LunarbaseError::DatabaseError)));
+        assert!(deleted.lock().unwrap().is_empty());
+        assert_eq!(count_files(&pool), 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_work_skips_side_effects() {
+        let pool = pool_with_files_table();
+        let deleted = Arc::new(Mutex::new(Vec::new()));
+
+        let result = transaction_then::<(), LunarbaseError>(&pool, |_conn, after_commit| {
+            let deleted = deleted.clone();
+            after_commit.defer(async move {
+                deleted.lock().unwrap().push("old.png");
+            });
+            Err(LunarbaseError::NotFound("Record not found".to_string()))
+        })
+        .await;
+
+        assert!(matches!(result, Err(LunarbaseError::NotFound(_))));
+        assert!(deleted.lock().unwrap().is_empty());
+    }
+}
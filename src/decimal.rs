@@ -0,0 +1,115 @@
+use serde_json::Value;
+
+/// Largest precision whose minor units always fit in an `i64`.
+pub const MAX_DECIMAL_PRECISION: u32 = 18;
+pub const DEFAULT_DECIMAL_PRECISION: u32 = 18;
+pub const DEFAULT_DECIMAL_SCALE: u32 = 2;
+
+/// Text form of a decimal written as a JSON string or number.
+pub fn decimal_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Parses `text` into minor units (`19.99` at scale 2 is `1999`), rejecting
+/// values with more than `scale` fractional digits or `precision` digits overall.
+pub fn parse_minor_units(text: &str, precision: u32, scale: u32) -> Result<i64, String> {
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+    let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if (integer.is_empty() && fraction.is_empty()) || !is_digits(integer) || !is_digits(fraction) {
+        return Err(format!("'{}' is not a decimal number", text));
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > scale as usize {
+        return Err(format!("'{}' has more than {} decimal places", text, scale));
+    }
+
+    let integer = integer.trim_start_matches('0');
+    if integer.len() + scale as usize > precision as usize {
+        return Err(format!(
+            "'{}' has more than {} digits before the decimal point",
+            text,
+            precision.saturating_sub(scale)
+        ));
+    }
+
+    let digits = format!("{}{:0<width$}", integer, fraction, width = scale as usize);
+    let magnitude = if digits.is_empty() {
+        0
+    } else {
+        digits
+            .parse::<i64>()
+            .map_err(|_| format!("'{}' is out of range", text))?
+    };
+
+    Ok(if negative { -magnitude } else { magnitude })
+}
+
+/// Formats minor units with exactly `scale` fractional digits.
+pub fn format_minor_units(minor_units: i64, scale: u32) -> String {
+    let sign = if minor_units < 0 { "-" } else { "" };
+    let digits = format!(
+        "{:0>width$}",
+        minor_units.unsigned_abs(),
+        width = scale as usize + 1
+    );
+    let (integer, fraction) = digits.split_at(digits.len() - scale as usize);
+
+    if fraction.is_empty() {
+        format!("{}{}", sign, integer)
+    } else {
+        format!("{}{}.{}", sign, integer, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_minor_units() {
+        assert_eq!(parse_minor_units("19.99", 18, 2), Ok(1999));
+        assert_eq!(parse_minor_units("-0.5", 18, 2), Ok(-50));
+        assert_eq!(parse_minor_units("7", 18, 2), Ok(700));
+        assert_eq!(parse_minor_units(".25", 18, 2), Ok(25));
+        assert_eq!(parse_minor_units("1.500", 18, 2), Ok(150));
+        assert_eq!(parse_minor_units("42", 3, 0), Ok(42));
+        assert_eq!(
+            parse_minor_units("9999999999999999.99", 18, 2),
+            Ok(999_999_999_999_999_999)
+        );
+
+        assert!(parse_minor_units("19.999", 18, 2).is_err());
+        assert!(parse_minor_units("1000", 5, 2).is_err());
+        assert!(parse_minor_units("1e3", 18, 2).is_err());
+        assert!(parse_minor_units("", 18, 2).is_err());
+        assert!(parse_minor_units("-", 18, 2).is_err());
+        assert!(parse_minor_units("1.2.3", 18, 2).is_err());
+    }
+
+    #[test]
+    fn test_format_minor_units() {
+        assert_eq!(format_minor_units(1999, 2), "19.99");
+        assert_eq!(format_minor_units(-50, 2), "-0.50");
+        assert_eq!(format_minor_units(5, 3), "0.005");
+        assert_eq!(format_minor_units(42, 0), "42");
+        assert_eq!(format_minor_units(i64::MIN, 2), "-92233720368547758.08");
+    }
+
+    #[test]
+    fn test_decimal_text() {
+        assert_eq!(decimal_text(&json!(" 19.99 ")), Some("19.99".to_string()));
+        assert_eq!(decimal_text(&json!(0.1)), Some("0.1".to_string()));
+        assert_eq!(decimal_text(&json!(true)), None);
+    }
+}
@@ -1,6 +1,20 @@
 use rust_embed::RustEmbed;
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Length of the `[hash]` placeholder in the Vite output file names.
+const CONTENT_HASH_LEN: usize = 8;
+
+pub const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+pub const REVALIDATE_CACHE_CONTROL: &str = "no-cache";
+
+/// Bytes of embedded admin files handed out so far. The files sit in the
+/// binary's read-only data and only become resident once they are read.
+static ADMIN_BYTES_READ: AtomicUsize = AtomicUsize::new(0);
+
+/// Precompressed variants looked up next to an asset, in order of preference.
+const PRECOMPRESSED_VARIANTS: [(&str, &str); 2] = [("br", ".br"), ("gzip", ".gz")];
 
 #[derive(RustEmbed)]
 #[folder = "admin-ui/dist/"]
@@ -15,6 +29,8 @@ use std::collections::HashMap;
 #[include = "*.woff2"]
 #[include = "*.ttf"]
 #[include = "*.eot"]
+#[include = "*.br"]
+#[include = "*.gz"]
 #[exclude = "*.map"]
 pub struct AdminAssets;
 
@@ -70,13 +86,23 @@ impl MimeTypeMap {
 }
 
 impl AdminAssets {
+    fn read(path: &str) -> Option<rust_embed::EmbeddedFile> {
+        let file = Self::get(path)?;
+        ADMIN_BYTES_READ.fetch_add(file.data.len(), Ordering::Relaxed);
+        Some(file)
+    }
+
+    pub fn bytes_read() -> usize {
+        ADMIN_BYTES_READ.load(Ordering::Relaxed)
+    }
+
     pub fn get_asset_with_fallback(path: &str) -> Option<Cow<'static, [u8]>> {
-        if let Some(asset) = Self::get(path) {
+        if let Some(asset) = Self::read(path) {
             return Some(asset.data);
         }
 
         if !path.starts_with("api/") && !path.contains('.') {
-            if let Some(index) = Self::get("admin/index.html") {
+            if let Some(index) = Self::read("admin/index.html") {
                 return Some(index.data);
             }
         }
@@ -95,13 +121,13 @@ impl AdminAssets {
     pub fn get_asset_with_mime(path: &str) -> Option<(Cow<'static, [u8]>, &'static str)> {
         let mime_map = MimeTypeMap::new();
 
-        if let Some(asset) = Self::get(path) {
+        if let Some(asset) = Self::read(path) {
             let mime_type = mime_map.get_mime_type(path);
             return Some((asset.data, mime_type));
         }
 
         if !path.starts_with("api/") && !path.contains('.') {
-            if let Some(index) = Self::get("admin/index.html") {
+            if let Some(index) = Self::read("admin/index.html") {
                 return Some((index.data, "text/html"));
             }
         }
@@ -110,6 +136,145 @@ impl AdminAssets {
     }
 }
 
+/// An embedded admin file picked for a request, with the headers it should be
+/// served with.
+#[derive(Debug)]
+pub struct ResolvedAsset {
+    pub data: Cow<'static, [u8]>,
+    pub mime_type: &'static str,
+    pub etag: String,
+    pub cache_control: &'static str,
+    /// Set when the bytes are a precompressed variant of the requested file
+    pub content_encoding: Option<&'static str>,
+    /// Set when precompressed variants exist, so the response varies by encoding
+    pub vary_encoding: bool,
+}
+
+impl ResolvedAsset {
+    pub fn matches_etag(&self, if_none_match: &str) -> bool {
+        etag_matches(if_none_match, &self.etag)
+    }
+}
+
+impl AdminAssets {
+    /// Looks up an embedded file, preferring a precompressed variant the
+    /// client accepts. Returns `None` when the file itself is not embedded.
+    pub fn resolve(path: &str, accept_encoding: Option<&str>) -> Option<ResolvedAsset> {
+        resolve_asset(path, accept_encoding, |path| {
+            Self::read(path).map(|file| (file.data, file.metadata.sha256_hash()))
+        })
+    }
+}
+
+fn resolve_asset<F>(path: &str, accept_encoding: Option<&str>, lookup: F) -> Option<ResolvedAsset>
+where
+    F: Fn(&str) -> Option<(Cow<'static, [u8]>, [u8; 32])>,
+{
+    let (data, hash) = lookup(path)?;
+    let mime_type = MimeTypeMap::new().get_mime_type(path);
+    let cache_control = if is_content_hashed(path) {
+        IMMUTABLE_CACHE_CONTROL
+    } else {
+        REVALIDATE_CACHE_CONTROL
+    };
+
+    let mut vary_encoding = false;
+    for (encoding, extension) in PRECOMPRESSED_VARIANTS {
+        let Some((variant, variant_hash)) = lookup(&format!("{}{}", path, extension)) else {
+            continue;
+        };
+        vary_encoding = true;
+        if accepts_encoding(accept_encoding, encoding) {
+            return Some(ResolvedAsset {
+                data: variant,
+                mime_type,
+                etag: etag_for(&variant_hash),
+                cache_control,
+                content_encoding: Some(encoding),
+                vary_encoding,
+            });
+        }
+    }
+
+    Some(ResolvedAsset {
+        data,
+        mime_type,
+        etag: etag_for(&hash),
+        cache_control,
+        content_encoding: None,
+        vary_encoding,
+    })
+}
+
+/// Whether the file name carries a Vite content hash (`assets/[name]-[hash].[ext]`),
+/// which makes its bytes safe to cache forever.
+pub fn is_content_hashed(path: &str) -> bool {
+    let Some(file_name) = path.strip_prefix("admin/assets/") else {
+        return false;
+    };
+    let Some((stem, _extension)) = file_name.rsplit_once('.') else {
+        return false;
+    };
+    let Some(split) = stem.len().checked_sub(CONTENT_HASH_LEN + 1) else {
+        return false;
+    };
+    if split == 0 || !stem.is_char_boundary(split) {
+        return false;
+    }
+
+    let hash = &stem[split + 1..];
+    // Plain words of the same length are not hashes; a real hash almost
+    // always has a digit or an uppercase letter in it.
+    stem.as_bytes()[split] == b'-'
+        && hash
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        && hash
+            .bytes()
+            .any(|b| b.is_ascii_digit() || b.is_ascii_uppercase())
+}
+
+/// Whether an `Accept-Encoding` header allows `encoding`, honouring `q=0`.
+fn accepts_encoding(accept_encoding: Option<&str>, encoding: &str) -> bool {
+    let Some(accept_encoding) = accept_encoding else {
+        return false;
+    };
+
+    let mut wildcard = false;
+    for entry in accept_encoding.split(',') {
+        let mut parts = entry.split(';');
+        let name = parts.next().unwrap_or_default().trim();
+        let quality = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+
+        if name.eq_ignore_ascii_case(encoding)
+            || (encoding == "gzip" && name.eq_ignore_ascii_case("x-gzip"))
+        {
+            return quality > 0.0;
+        }
+        if name == "*" {
+            wildcard = quality > 0.0;
+        }
+    }
+
+    wildcard
+}
+
+fn etag_for(hash: &[u8; 32]) -> String {
+    let hex: String = hash[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("\"{}\"", hex)
+}
+
+/// Compares an `If-None-Match` header against a strong ETag using the weak
+/// comparison the header calls for.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
+
 impl StaticAssets {
     pub fn get_logo() -> Option<Cow<'static, [u8]>> {
         Self::get("logo.png").map(|asset| asset.data)
@@ -148,6 +313,110 @@ mod tests {
         );
     }
 
+    fn representative_assets() -> HashMap<&'static str, &'static [u8]> {
+        HashMap::from([
+            ("admin/index.html", b"<!doctype html>".as_slice()),
+            ("admin/favicon.ico", b"icon".as_slice()),
+            (
+                "admin/assets/index-BXk3a9Zq.js",
+                b"console.log(1)".as_slice(),
+            ),
+            ("admin/assets/index-BXk3a9Zq.js.br", b"brotli".as_slice()),
+            ("admin/assets/index-BXk3a9Zq.js.gz", b"gzip".as_slice()),
+            ("admin/assets/index-C_4f-x0L.css", b"body{}".as_slice()),
+            ("admin/assets/index-C_4f-x0L.css.gz", b"gzip css".as_slice()),
+            ("admin/assets/logo-D2e8Rt1p.svg", b"<svg/>".as_slice()),
+        ])
+    }
+
+    fn resolve(path: &str, accept_encoding: Option<&str>) -> Option<ResolvedAsset> {
+        use sha2::{Digest, Sha256};
+
+        let assets = representative_assets();
+        resolve_asset(path, accept_encoding, |path| {
+            assets
+                .get(path)
+                .map(|data| (Cow::Borrowed(*data), Sha256::digest(data).into()))
+        })
+    }
+
+    #[test]
+    fn test_content_hash_detection() {
+        assert!(is_content_hashed("admin/assets/index-BXk3a9Zq.js"));
+        assert!(is_content_hashed("admin/assets/index-C_4f-x0L.css"));
+        assert!(is_content_hashed("admin/assets/tiptap-core-a1b2c3d4.js"));
+
+        assert!(!is_content_hashed("admin/index.html"));
+        assert!(!is_content_hashed("admin/favicon.ico"));
+        assert!(!is_content_hashed("admin/logo-BXk3a9Zq.svg"));
+        assert!(!is_content_hashed("admin/assets/icon-download.svg"));
+        assert!(!is_content_hashed("admin/assets/-BXk3a9Zq.js"));
+        assert!(!is_content_hashed("admin/assets/short-a1.js"));
+    }
+
+    #[test]
+    fn test_cache_policy_by_asset() {
+        let index = resolve("admin/index.html", None).unwrap();
+        assert_eq!(index.cache_control, REVALIDATE_CACHE_CONTROL);
+        assert_eq!(index.mime_type, "text/html; charset=utf-8");
+
+        let favicon = resolve("admin/favicon.ico", None).unwrap();
+        assert_eq!(favicon.cache_control, REVALIDATE_CACHE_CONTROL);
+
+        let script = resolve("admin/assets/index-BXk3a9Zq.js", None).unwrap();
+        assert_eq!(script.cache_control, IMMUTABLE_CACHE_CONTROL);
+        assert_eq!(script.mime_type, "application/javascript; charset=utf-8");
+
+        assert!(resolve("admin/assets/missing-BXk3a9Zq.js", None).is_none());
+    }
+
+    #[test]
+    fn test_precompressed_variant_selection() {
+        let path = "admin/assets/index-BXk3a9Zq.js";
+
+        let identity = resolve(path, None).unwrap();
+        assert_eq!(identity.content_encoding, None);
+        assert_eq!(identity.data.as_ref(), b"console.log(1)");
+        assert!(identity.vary_encoding);
+
+        let brotli = resolve(path, Some("gzip, deflate, br")).unwrap();
+        assert_eq!(brotli.content_encoding, Some("br"));
+        assert_eq!(brotli.data.as_ref(), b"brotli");
+        assert_eq!(brotli.mime_type, identity.mime_type);
+
+        let gzip = resolve(path, Some("br;q=0, gzip")).unwrap();
+        assert_eq!(gzip.content_encoding, Some("gzip"));
+        assert_eq!(gzip.data.as_ref(), b"gzip");
+
+        let wildcard = resolve(path, Some("*")).unwrap();
+        assert_eq!(wildcard.content_encoding, Some("br"));
+
+        let css = resolve("admin/assets/index-C_4f-x0L.css", Some("br, gzip")).unwrap();
+        assert_eq!(css.content_encoding, Some("gzip"));
+
+        let svg = resolve("admin/assets/logo-D2e8Rt1p.svg", Some("br, gzip")).unwrap();
+        assert_eq!(svg.content_encoding, None);
+        assert!(!svg.vary_encoding);
+    }
+
+    #[test]
+    fn test_etags_identify_each_representation() {
+        let path = "admin/assets/index-BXk3a9Zq.js";
+        let identity = resolve(path, None).unwrap();
+        let brotli = resolve(path, Some("br")).unwrap();
+        let gzip = resolve(path, Some("gzip")).unwrap();
+
+        assert!(identity.etag.starts_with('"') && identity.etag.ends_with('"'));
+        assert_ne!(identity.etag, brotli.etag);
+        assert_ne!(brotli.etag, gzip.etag);
+        assert_eq!(identity.etag, resolve(path, Some("identity")).unwrap().etag);
+
+        assert!(identity.matches_etag(&identity.etag));
+        assert!(identity.matches_etag(&format!("\"other\", W/{}", identity.etag)));
+        assert!(identity.matches_etag("*"));
+        assert!(!identity.matches_etag(&brotli.etag));
+    }
+
     #[test]
     fn test_asset_availability() {
         println!("Assets available: {}", AdminAssets::is_available());
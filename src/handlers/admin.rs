@@ -0,0 +1,242 @@
+use axum::{
+    Extension,
+    extract::{Query, State},
+    response::Json,
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::AppState;
+use crate::models::{
+    AdminOverview, AnalyticsGranularity, LockedAccount, RecordAnalytics, UserAnalytics,
+};
+use crate::services::tls_status::TlsStatusReport;
+use crate::utils::{ApiResponse, Claims, ErrorResponse, LunarbaseError};
+
+#[utoipa::path(
+    get,
+    path = "/admin/overview",
+    tag = "Monitoring",
+    responses(
+        (status = 200, description = "Instance-wide overview for the admin dashboard", body = ApiResponse<AdminOverview>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_admin_overview(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<AdminOverview>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let overview = state
+        .admin_service
+        .get_overview(
+            &state.collection_service,
+            &state.websocket_service,
+            &state.health_service,
+            state.s3_service.as_deref(),
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success(overview)))
+}
+
+/// Longest window `/admin/analytics/users` accepts.
+const MAX_ANALYTICS_WINDOW_DAYS: i64 = 365;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UserAnalyticsQuery {
+    /// Number of days ending today, written as `<days>d`
+    #[schema(example = "30d")]
+    pub window: Option<String>,
+    /// `day` or `week`
+    #[schema(example = "day")]
+    pub granularity: Option<String>,
+}
+
+/// Parses the `window` and `granularity` of an analytics query, defaulting
+/// to 30 daily buckets.
+fn parse_analytics_range(
+    window: Option<&str>,
+    granularity: Option<&str>,
+) -> Result<(i64, AnalyticsGranularity), LunarbaseError> {
+    let window_days = window
+        .unwrap_or("30d")
+        .strip_suffix('d')
+        .filter(|days| !days.is_empty() && days.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|days| days.parse::<i64>().ok())
+        .filter(|days| (1..=MAX_ANALYTICS_WINDOW_DAYS).contains(days))
+        .ok_or_else(|| {
+            LunarbaseError::ValidationError(vec![format!(
+                "window must be between 1d and {}d",
+                MAX_ANALYTICS_WINDOW_DAYS
+            )])
+        })?;
+
+    let granularity =
+        AnalyticsGranularity::parse(granularity.unwrap_or("day")).ok_or_else(|| {
+            LunarbaseError::ValidationError(vec!["granularity must be 'day' or 'week'".to_string()])
+        })?;
+    if window_days < granularity.days() {
+        return Err(LunarbaseError::ValidationError(vec![
+            "window must cover at least one week for weekly granularity".to_string(),
+        ]));
+    }
+
+    Ok((window_days, granularity))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/analytics/users",
+    tag = "Monitoring",
+    params(
+        ("window" = Option<String>, Query, description = "Days ending today as `<days>d`, 1d to 365d (default 30d)"),
+        ("granularity" = Option<String>, Query, description = "`day` or `week` (default day); weeks need a window of at least 7d")
+    ),
+    responses(
+        (status = 200, description = "New, verified and active user series; cached for two minutes", body = ApiResponse<UserAnalytics>),
+        (status = 400, description = "Invalid window or granularity", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Superadmin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_user_analytics(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<UserAnalyticsQuery>,
+) -> Result<Json<ApiResponse<UserAnalytics>>, LunarbaseError> {
+    // Users are shared by every workspace
+    if !claims.is_superadmin() {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let (window_days, granularity) =
+        parse_analytics_range(query.window.as_deref(), query.granularity.as_deref())?;
+
+    let analytics = state
+        .admin_service
+        .user_analytics(window_days, granularity)
+        .await?;
+
+    Ok(Json(ApiResponse::success(analytics)))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RecordAnalyticsQuery {
+    /// Limits the series to one collection; all collections when omitted
+    #[schema(example = "articles")]
+    pub collection: Option<String>,
+    /// Number of days ending today, written as `<days>d`
+    #[schema(example = "30d")]
+    pub window: Option<String>,
+    /// `day` or `week`
+    #[schema(example = "day")]
+    pub granularity: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/analytics/records",
+    tag = "Monitoring",
+    params(
+        ("collection" = Option<String>, Query, description = "Collection to report on; all collections when omitted"),
+        ("window" = Option<String>, Query, description = "Days ending today as `<days>d`, 1d to 365d (default 30d)"),
+        ("granularity" = Option<String>, Query, description = "`day` or `week` (default day); weeks need a window of at least 7d")
+    ),
+    responses(
+        (status = 200, description = "Created, updated and deleted record series", body = ApiResponse<RecordAnalytics>),
+        (status = 400, description = "Invalid window or granularity", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_record_analytics(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<RecordAnalyticsQuery>,
+) -> Result<Json<ApiResponse<RecordAnalytics>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let (window_days, granularity) =
+        parse_analytics_range(query.window.as_deref(), query.granularity.as_deref())?;
+
+    // Workspace admins only see the activity of their workspace's collections
+    let workspace_id = claims.workspace_id.filter(|_| !claims.is_superadmin());
+    let analytics = state
+        .record_activity_service
+        .analytics(
+            query.collection.as_deref(),
+            workspace_id,
+            window_days,
+            granularity,
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success(analytics)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/users/locked",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Currently locked accounts, soonest to expire first", body = ApiResponse<Vec<LockedAccount>>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_locked_accounts(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<LockedAccount>>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let locked = state.lockout_service.list_locked_accounts()?;
+
+    Ok(Json(ApiResponse::success(locked)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/tls/status",
+    tag = "Monitoring",
+    responses(
+        (status = 200, description = "ACME certificate and renewal state; `enabled` is false when TLS is not managed by LunarBase", body = ApiResponse<TlsStatusReport>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_tls_status(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<TlsStatusReport>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    Ok(Json(ApiResponse::success(state.tls_status.report())))
+}
@@ -1,25 +1,36 @@
 use axum::{
     Extension,
+    body::Bytes,
     extract::{FromRequest, Query, Request, State},
-    http::{HeaderMap, StatusCode},
+    http::{HeaderMap, HeaderValue, StatusCode},
     response::{Json, Redirect},
 };
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 use std::time::Duration;
+use tower_governor::key_extractor::{KeyExtractor, SmartIpKeyExtractor};
 use tracing::debug;
 use utoipa::ToSchema;
 
 use crate::{
     AppState,
+    database::lower,
     middleware::extract_user_claims,
     models::{
-        AuthResponse, LoginRequest, LogoutRequest, LogoutResponse, NewUser, RegisterRequest, User,
-        UserResponse,
+        AuthResponse, GuestSessionRequest, GuestSessionResponse, LoginIdentifier, LoginRequest,
+        LogoutRequest, LogoutResponse, NewUser, QuotaKind, QuotaWarning, RegisterRequest,
+        SwitchWorkspaceRequest, User, UserResponse, WorkspaceResponse,
     },
     schema::users,
-    services::configuration_manager::ConfigurationAccess,
-    utils::{ApiResponse, Claims, CookieService, ErrorResponse, LunarbaseError},
+    services::{LoginMethod, QUOTA_WARNING_HEADER, configuration_manager::ConfigurationAccess},
+    utils::{
+        ApiResponse, Claims, CookieService, ErrorResponse, GuestScope, JwtService, LunarbaseError,
+        OAuthErrorCode, OAuthFailure,
+        email::ensure_deliverable_email,
+        is_same_origin, normalize_email,
+        username::{ensure_username_available, username_from_display_name, with_random_suffix},
+    },
 };
 
 #[utoipa::path(
@@ -38,13 +49,15 @@ pub async fn register(
     State(app_state): State<AppState>,
     request: Request,
 ) -> Result<(StatusCode, HeaderMap, Json<ApiResponse<AuthResponse>>), LunarbaseError> {
-    let Json(payload): Json<RegisterRequest> = Json::from_request(request, &app_state)
+    let request_headers = request.headers().clone();
+    let Json(mut payload): Json<RegisterRequest> = Json::from_request(request, &app_state)
         .await
         .map_err(|_| LunarbaseError::ValidationError(vec!["Invalid JSON payload".to_string()]))?;
 
     payload
         .validate()
         .map_err(LunarbaseError::ValidationError)?;
+    ensure_deliverable_email(&app_state, &payload.email).await?;
 
     let mut conn = app_state
         .db_pool
@@ -65,67 +78,59 @@ pub async fn register(
         ]));
     }
 
-    let existing_username = users::table
-        .filter(users::username.eq(&payload.username))
-        .select(User::as_select())
-        .first::<User>(&mut conn)
-        .optional()
-        .map_err(|_| LunarbaseError::DatabaseError)?;
-
-    if existing_username.is_some() {
+    if let Err(error) =
+        ensure_username_available(&app_state, &mut conn, &payload.username, None).await
+    {
         tokio::time::sleep(Duration::from_millis(100)).await;
-        return Err(LunarbaseError::ValidationError(vec![
-            "Username already taken".to_string(),
-        ]));
+        return Err(error);
     }
 
-    let new_user = NewUser::new(
+    let password_hash = app_state.password_service.hash(&payload.password).await?;
+    let new_user = NewUser::with_password_hash(
         payload.email,
-        &payload.password,
+        password_hash,
         payload.username,
-        &app_state.password_pepper,
-    )
-    .map_err(|_| LunarbaseError::InternalError)?;
+        "user".to_string(),
+    );
 
     diesel::insert_into(users::table)
         .values(&new_user)
         .execute(&mut conn)
         .map_err(|_| LunarbaseError::DatabaseError)?;
 
-    let user: User = users::table
+    let mut user: User = users::table
         .filter(users::email.eq(&new_user.email))
         .select(User::as_select())
         .first(&mut conn)
         .map_err(|_| LunarbaseError::DatabaseError)?;
 
-    if let Err(e) = app_state
-        .email_service
-        .send_verification_email(user.id, &user.email, &user.username)
-        .await
-    {
-        tracing::warn!(
-            "Failed to send verification email to {}: {:?}",
-            user.email,
-            e
-        );
+    if app_state.get_require_email_verification().await {
+        if let Err(e) = app_state
+            .email_service
+            .send_verification_email(user.id, &user.email, &user.username)
+            .await
+        {
+            tracing::warn!(
+                "Failed to send verification email to {}: {:?}",
+                user.email,
+                e
+            );
+        }
+    } else {
+        diesel::update(users::table.find(user.id))
+            .set(users::is_verified.eq(true))
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        user.is_verified = true;
     }
 
-    let access_token = app_state
-        .auth_state
-        .jwt_service
-        .generate_access_token(user.id, &user.email, &user.role)
-        .await?;
+    let (access_token, refresh_token) = issue_tokens(&app_state, &user, None).await?;
 
-    let refresh_token = app_state
-        .auth_state
-        .jwt_service
-        .generate_refresh_token(user.id)
-        .await?;
-
-    let cookie_service = CookieService::new();
+    let cookie_service = CookieService::for_request(&app_state, &request_headers).await;
     let mut headers = HeaderMap::new();
     cookie_service.set_access_token_cookie(&mut headers, &access_token);
     cookie_service.set_refresh_token_cookie(&mut headers, &refresh_token);
+    let csrf_token = cookie_service.set_csrf_token_cookie(&mut headers);
 
     let auth_response = AuthResponse {
         user: user.to_response(),
@@ -136,6 +141,7 @@ pub async fn register(
             .jwt_service
             .access_token_duration_seconds()
             .await,
+        csrf_token,
     };
 
     Ok((
@@ -262,20 +268,29 @@ pub struct ResetPasswordRequest {
         (status = 200, description = "Verification email sent", body = ApiResponse<String>),
         (status = 404, description = "User not found", body = ErrorResponse),
         (status = 400, description = "User already verified", body = ErrorResponse),
+        (status = 429, description = "Too many emails requested for this address or from this client", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
 pub async fn resend_verification(
     State(app_state): State<AppState>,
-    Json(payload): Json<ResendVerificationRequest>,
+    request: Request,
 ) -> Result<Json<ApiResponse<String>>, LunarbaseError> {
+    let client_ip = SmartIpKeyExtractor.extract(&request).ok();
+    let Json(payload): Json<ResendVerificationRequest> = Json::from_request(request, &app_state)
+        .await
+        .map_err(|_| LunarbaseError::ValidationError(vec!["Invalid JSON payload".to_string()]))?;
+
+    enforce_email_rate_limit(&app_state, "verification", &payload.email, client_ip).await?;
+
     let mut conn = app_state
         .db_pool
         .get()
         .map_err(|_| LunarbaseError::DatabaseError)?;
 
+    let email = normalize_email(&payload.email).ok_or(LunarbaseError::UserNotFound)?;
     let user: User = users::table
-        .filter(users::email.eq(&payload.email))
+        .filter(users::email.eq(&email))
         .select(User::as_select())
         .first(&mut conn)
         .map_err(|_| LunarbaseError::UserNotFound)?;
@@ -294,6 +309,81 @@ pub async fn resend_verification(
     )))
 }
 
+/// Issues an access and refresh token pair scoped to `workspace_id`, or to the
+/// user's default workspace when none is requested.
+async fn issue_tokens(
+    app_state: &AppState,
+    user: &User,
+    workspace_id: Option<i32>,
+) -> Result<(String, String), LunarbaseError> {
+    let session = app_state
+        .workspace_service
+        .session_for(user, workspace_id)
+        .await?;
+
+    let access_token = app_state
+        .auth_state
+        .jwt_service
+        .generate_access_token(user.id, &user.email, &user.role, session.as_ref())
+        .await?;
+
+    let refresh_token = app_state
+        .auth_state
+        .jwt_service
+        .generate_refresh_token(user.id, session.map(|session| session.workspace_id))
+        .await?;
+
+    Ok((access_token, refresh_token))
+}
+
+/// Rejects an email request once its address or client IP made more than
+/// `auth.email_rate_limit_max_requests` of them within the configured window.
+/// Checked before the account lookup so the answer never reveals whether the
+/// address is registered.
+async fn enforce_email_rate_limit(
+    app_state: &AppState,
+    action: &'static str,
+    email: &str,
+    client_ip: Option<IpAddr>,
+) -> Result<(), LunarbaseError> {
+    let mut keys = vec![format!("{}:email:{}", action, email.trim().to_lowercase())];
+    if let Some(ip) = client_ip {
+        keys.push(format!("{}:ip:{}", action, ip));
+    }
+
+    let max_requests = app_state.get_email_rate_limit_max_requests().await.max(1) as usize;
+    let window =
+        Duration::from_secs(app_state.get_email_rate_limit_window_minutes().await as u64 * 60);
+    if app_state
+        .email_rate_limiter
+        .try_record(&keys, max_requests, window)
+    {
+        return Ok(());
+    }
+
+    tracing::warn!(
+        "Rate limited {} email request for {} from {:?}",
+        action,
+        email,
+        client_ip
+    );
+    let (name, help) = match action {
+        "verification" => (
+            "verification_email_rate_limited_total",
+            "Verification email requests rejected by the per-address or per-IP limit",
+        ),
+        _ => (
+            "password_reset_email_rate_limited_total",
+            "Password reset email requests rejected by the per-address or per-IP limit",
+        ),
+    };
+    let _ = app_state
+        .metrics_state
+        .increment_custom_metric(name, help)
+        .await;
+    Err(LunarbaseError::RateLimitExceeded)
+}
+
 #[utoipa::path(
     post,
     path = "/auth/forgot-password",
@@ -302,13 +392,21 @@ pub async fn resend_verification(
     responses(
         (status = 200, description = "Password reset email sent", body = ApiResponse<String>),
         (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 429, description = "Too many emails requested for this address or from this client", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
 pub async fn forgot_password(
     State(app_state): State<AppState>,
-    Json(payload): Json<ForgotPasswordRequest>,
+    request: Request,
 ) -> Result<Json<ApiResponse<String>>, LunarbaseError> {
+    let client_ip = SmartIpKeyExtractor.extract(&request).ok();
+    let Json(payload): Json<ForgotPasswordRequest> = Json::from_request(request, &app_state)
+        .await
+        .map_err(|_| LunarbaseError::ValidationError(vec!["Invalid JSON payload".to_string()]))?;
+
+    enforce_email_rate_limit(&app_state, "password_reset", &payload.email, client_ip).await?;
+
     let start_time = std::time::Instant::now();
     let base_delay = std::time::Duration::from_millis(500);
 
@@ -318,7 +416,7 @@ pub async fn forgot_password(
         .map_err(|_| LunarbaseError::DatabaseError)?;
 
     let user_result: Result<User, _> = users::table
-        .filter(users::email.eq(&payload.email))
+        .filter(users::email.eq(normalize_email(&payload.email).unwrap_or_default()))
         .select(User::as_select())
         .first(&mut conn);
 
@@ -373,10 +471,7 @@ pub async fn reset_password(
     Json(payload): Json<ResetPasswordRequest>,
 ) -> Result<Json<ApiResponse<String>>, LunarbaseError> {
     use crate::models::verification_token::TokenType;
-    use argon2::password_hash::SaltString;
-    use argon2::{Argon2, PasswordHasher};
     use diesel::prelude::*;
-    use rand::rngs::OsRng;
 
     if payload.new_password.len() < 8 {
         return Err(LunarbaseError::WeakPassword);
@@ -399,17 +494,10 @@ pub async fn reset_password(
         .first(&mut conn)
         .map_err(|_| LunarbaseError::UserNotFound)?;
 
-    let salt = SaltString::generate(&mut OsRng);
-    let peppered_password = format!("{}{}", payload.new_password, app_state.password_pepper);
-    let argon2 = Argon2::new(
-        argon2::Algorithm::Argon2id,
-        argon2::Version::V0x13,
-        argon2::Params::new(65536, 4, 2, None).unwrap(),
-    );
-    let password_hash = argon2
-        .hash_password(peppered_password.as_bytes(), &salt)
-        .map_err(|_| LunarbaseError::InternalError)?
-        .to_string();
+    let password_hash = app_state
+        .password_service
+        .hash(&payload.new_password)
+        .await?;
 
     diesel::update(users::table.filter(users::id.eq(user.id)))
         .set((
@@ -485,6 +573,52 @@ pub async fn oauth_authorize(
     Ok(Redirect::temporary(&auth_url))
 }
 
+/// Usernames tried for a new OAuth account before giving up.
+const OAUTH_USERNAME_ATTEMPTS: usize = 10;
+
+/// The caller's `X-Request-Id` when it looks like one, otherwise a new id.
+/// Shown to the user with an OAuth error so support can find the log entry.
+fn oauth_request_id(headers: &HeaderMap) -> String {
+    headers
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= 64
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string())
+}
+
+/// Logs the full failure and sends the user to the error page with only the
+/// error code and the request id.
+fn oauth_error_redirect(
+    app_state: &AppState,
+    provider: &str,
+    request_id: &str,
+    failure: OAuthFailure,
+) -> (HeaderMap, Redirect) {
+    tracing::warn!(
+        request_id,
+        provider,
+        code = failure.code.as_str(),
+        "OAuth sign-in failed: {}",
+        failure.detail
+    );
+    (
+        HeaderMap::new(),
+        Redirect::temporary(&format!(
+            "{}/admin/auth/error?code={}&request_id={}",
+            app_state.email_service.get_frontend_url(),
+            failure.code.as_str(),
+            urlencoding::encode(request_id)
+        )),
+    )
+}
+
 #[utoipa::path(
     get,
     path = "/auth/oauth/{provider}/callback",
@@ -493,8 +627,8 @@ pub async fn oauth_authorize(
         ("provider" = String, Path, description = "OAuth provider (google or github)", example = "google")
     ),
     responses(
-        (status = 302, description = "Redirect to frontend with success"),
-        (status = 400, description = "OAuth error or validation error", body = ErrorResponse),
+        (status = 307, description = "Redirect to the frontend: `/admin/auth/success`, or `/admin/auth/error?code=...&request_id=...` where code is access_denied, provider_unavailable, account_link_conflict or email_unverified_at_provider"),
+        (status = 400, description = "Unknown provider, or callback without code and state", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
@@ -502,16 +636,35 @@ pub async fn oauth_callback(
     State(app_state): State<AppState>,
     axum::extract::Path(provider): axum::extract::Path<String>,
     Query(query): Query<OAuthCallbackQuery>,
+    request_headers: HeaderMap,
 ) -> Result<(HeaderMap, Redirect), LunarbaseError> {
+    if !matches!(provider.as_str(), "google" | "github") {
+        return Err(LunarbaseError::ValidationError(vec![
+            "Unsupported OAuth provider".to_string(),
+        ]));
+    }
+
+    let request_id = oauth_request_id(&request_headers);
+    let fail = |failure: OAuthFailure| -> Result<(HeaderMap, Redirect), LunarbaseError> {
+        Ok(oauth_error_redirect(
+            &app_state,
+            &provider,
+            &request_id,
+            failure,
+        ))
+    };
+
     if let Some(error) = query.error {
-        let error_msg = query.error_description.unwrap_or(error);
-        return Ok((
-            HeaderMap::new(),
-            Redirect::temporary(&format!(
-                "{}/admin/auth/error?message={}",
-                app_state.email_service.get_frontend_url(),
-                urlencoding::encode(&error_msg)
-            )),
+        return fail(OAuthFailure::new(
+            OAuthErrorCode::from_provider_error(&error),
+            format!(
+                "provider returned {}: {}",
+                error,
+                query
+                    .error_description
+                    .as_deref()
+                    .unwrap_or("no description")
+            ),
         ));
     }
 
@@ -525,21 +678,32 @@ pub async fn oauth_callback(
         LunarbaseError::ValidationError(vec!["Missing state parameter".to_string()])
     })?;
 
-    let access_token = oauth_service
+    let access_token = match oauth_service
         .exchange_code_for_token(&provider, &code, &state)
         .await
-        .map_err(|e| {
-            LunarbaseError::ValidationError(vec![format!("Failed to exchange OAuth code: {}", e)])
-        })?;
+    {
+        Ok(access_token) => access_token,
+        Err(e) => {
+            return fail(OAuthFailure::new(
+                OAuthErrorCode::ProviderUnavailable,
+                format!("code exchange failed: {:?}", e),
+            ));
+        }
+    };
 
-    let oauth_user = oauth_service
-        .get_user_info(&provider, &access_token)
-        .await
-        .map_err(|_| {
-            LunarbaseError::ValidationError(vec![
-                "Failed to get user info from OAuth provider".to_string(),
-            ])
-        })?;
+    let mut oauth_user = match oauth_service.get_user_info(&provider, &access_token).await {
+        Ok(oauth_user) => oauth_user,
+        Err(failure) => return fail(failure),
+    };
+    oauth_user.email = match normalize_email(&oauth_user.email) {
+        Some(email) => email,
+        None => {
+            return fail(OAuthFailure::new(
+                OAuthErrorCode::EmailUnverifiedAtProvider,
+                format!("provider returned an invalid email {:?}", oauth_user.email),
+            ));
+        }
+    };
 
     let mut conn = app_state
         .db_pool
@@ -554,6 +718,19 @@ pub async fn oauth_callback(
         .map_err(|_| LunarbaseError::DatabaseError)?;
 
     let user = if let Some(mut user) = existing_user {
+        // Whoever registered this address never proved they own it; signing
+        // in through the provider must not take that account over, nor leave
+        // its password with someone else.
+        if !user.is_verified {
+            return fail(OAuthFailure::new(
+                OAuthErrorCode::AccountLinkConflict,
+                format!(
+                    "user {} with email {} exists but is not verified",
+                    user.id, oauth_user.email
+                ),
+            ));
+        }
+
         let update_user = crate::models::user::UpdateUser {
             email: None,
             password_hash: None,
@@ -575,26 +752,64 @@ pub async fn oauth_callback(
         user.last_login_at = Some(chrono::Utc::now().naive_utc());
         user
     } else {
-        let username = oauth_user
-            .name
-            .unwrap_or_else(|| format!("{}_{}", provider, &oauth_user.id[..8]));
+        let fallback = format!(
+            "{}_{}",
+            provider,
+            oauth_user.id.chars().take(8).collect::<String>()
+        );
+        let base_username = username_from_display_name(oauth_user.name.as_deref(), &fallback);
 
         let random_password = uuid::Uuid::new_v4().to_string();
+        let password_hash = app_state.password_service.hash(&random_password).await?;
+
+        // Another account may own the name, or take it between the check and
+        // the insert; either way try again with a random suffix.
+        let mut created = false;
+        for attempt in 0..OAUTH_USERNAME_ATTEMPTS {
+            let username = if attempt == 0 {
+                base_username.clone()
+            } else {
+                with_random_suffix(&base_username)
+            };
+            match ensure_username_available(&app_state, &mut conn, &username, None).await {
+                Ok(()) => {}
+                Err(LunarbaseError::ValidationError(_)) => continue,
+                Err(error) => return Err(error),
+            }
 
-        let new_user = NewUser::new_with_avatar(
-            oauth_user.email.clone(),
-            &random_password,
-            username,
-            "user".to_string(),
-            oauth_user.avatar_url.clone(),
-            &app_state.password_pepper,
-        )
-        .map_err(|e| LunarbaseError::ValidationError(vec![e]))?;
-
-        diesel::insert_into(users::table)
-            .values(&new_user)
-            .execute(&mut conn)
-            .map_err(|_| LunarbaseError::DatabaseError)?;
+            let new_user = NewUser {
+                avatar_url: oauth_user.avatar_url.clone(),
+                ..NewUser::with_password_hash(
+                    oauth_user.email.clone(),
+                    password_hash.clone(),
+                    username,
+                    "user".to_string(),
+                )
+            };
+            match diesel::insert_into(users::table)
+                .values(&new_user)
+                .execute(&mut conn)
+            {
+                Ok(_) => {
+                    created = true;
+                    break;
+                }
+                Err(diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                )) => continue,
+                Err(_) => return Err(LunarbaseError::DatabaseError),
+            }
+        }
+        if !created {
+            return fail(OAuthFailure::new(
+                OAuthErrorCode::AccountLinkConflict,
+                format!(
+                    "no free username for {} after {} attempts",
+                    oauth_user.email, OAUTH_USERNAME_ATTEMPTS
+                ),
+            ));
+        }
 
         let mut created_user: User = users::table
             .filter(users::email.eq(&oauth_user.email))
@@ -611,24 +826,18 @@ pub async fn oauth_callback(
         created_user
     };
 
-    let jwt_access_token = app_state
-        .auth_state
-        .jwt_service
-        .generate_access_token(user.id, &user.email, &user.role)
-        .await
-        .map_err(|_| LunarbaseError::InternalError)?;
-
-    let jwt_refresh_token = app_state
-        .auth_state
-        .jwt_service
-        .generate_refresh_token(user.id)
+    let (jwt_access_token, jwt_refresh_token) = issue_tokens(&app_state, &user, None)
         .await
         .map_err(|_| LunarbaseError::InternalError)?;
+    app_state
+        .admin_service
+        .record_login(user.id, LoginMethod::OAuth);
 
-    let cookie_service = CookieService::new();
+    let cookie_service = CookieService::for_request(&app_state, &request_headers).await;
     let mut headers = HeaderMap::new();
     cookie_service.set_access_token_cookie(&mut headers, &jwt_access_token);
     cookie_service.set_refresh_token_cookie(&mut headers, &jwt_refresh_token);
+    cookie_service.set_csrf_token_cookie(&mut headers);
 
     Ok((
         headers,
@@ -710,7 +919,8 @@ pub async fn oauth_status(
     request_body = LogoutRequest,
     responses(
         (status = 200, description = "Logout successful", body = ApiResponse<LogoutResponse>),
-        (status = 401, description = "Unauthorized", body = ErrorResponse)
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Cookie-authenticated request without a matching X-CSRF-Token header", body = ErrorResponse)
     ),
     security(
         ("bearer_auth" = [])
@@ -747,7 +957,7 @@ pub async fn logout(
             .map_err(|_| LunarbaseError::InternalError)?;
     }
 
-    let cookie_service = CookieService::new();
+    let cookie_service = CookieService::for_request(&app_state, request.headers()).await;
     let mut headers = HeaderMap::new();
     cookie_service.clear_all_tokens(&mut headers);
 
@@ -767,6 +977,7 @@ pub async fn logout(
         (status = 200, description = "Login successful - tokens provided via httpOnly cookies", body = ApiResponse<AuthResponse>),
         (status = 400, description = "Validation error", body = ErrorResponse),
         (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Account not verified or deactivated", body = ErrorResponse),
         (status = 423, description = "Account locked", body = ErrorResponse),
         (status = 429, description = "Rate limit exceeded", body = ErrorResponse)
     )
@@ -775,6 +986,7 @@ pub async fn login(
     State(app_state): State<AppState>,
     request: Request,
 ) -> Result<(HeaderMap, Json<ApiResponse<AuthResponse>>), LunarbaseError> {
+    let request_headers = request.headers().clone();
     let Json(payload): Json<LoginRequest> = Json::from_request(request, &app_state)
         .await
         .map_err(|_| LunarbaseError::ValidationError(vec!["Invalid JSON payload".to_string()]))?;
@@ -791,14 +1003,34 @@ pub async fn login(
     let base_delay = Duration::from_millis(100);
     let start_time = std::time::Instant::now();
 
-    let user = users::table
-        .filter(users::email.eq(&payload.email))
-        .select(User::as_select())
-        .first::<User>(&mut conn)
-        .optional()
-        .map_err(|_| LunarbaseError::DatabaseError)?;
+    let lookup =
+        LoginIdentifier::parse(&app_state.get_login_identifier().await).unwrap_or_default();
 
-    let user = match user {
+    // Malformed addresses cannot belong to anyone and take the unknown-user path
+    let by_email = match normalize_email(&payload.identifier) {
+        Some(email) if lookup.matches_email() => users::table
+            .filter(users::email.eq(email))
+            .select(User::as_select())
+            .first::<User>(&mut conn)
+            .optional()
+            .map_err(|_| LunarbaseError::DatabaseError)?,
+        _ => None,
+    };
+    // Looked up even when the email matched, so the time taken does not tell
+    // which of the two found the account. An email match wins over a
+    // username that looks like the same address.
+    let by_username = if lookup.matches_username() {
+        users::table
+            .filter(lower(users::username).eq(payload.identifier.trim().to_ascii_lowercase()))
+            .select(User::as_select())
+            .first::<User>(&mut conn)
+            .optional()
+            .map_err(|_| LunarbaseError::DatabaseError)?
+    } else {
+        None
+    };
+
+    let user = match by_email.or(by_username) {
         Some(user) => user,
         None => {
             let elapsed = start_time.elapsed();
@@ -817,7 +1049,7 @@ pub async fn login(
         return Err(LunarbaseError::AccountLocked);
     }
 
-    if !user.is_verified {
+    if !user.is_verified && app_state.get_require_email_verification().await {
         let elapsed = start_time.elapsed();
         if elapsed < base_delay {
             tokio::time::sleep(base_delay - elapsed).await;
@@ -825,31 +1057,21 @@ pub async fn login(
         return Err(LunarbaseError::AccountNotVerified);
     }
 
-    let password_valid = user
-        .verify_password(&payload.password, &app_state.password_pepper)
-        .map_err(|_| LunarbaseError::InternalError)?;
+    if !user.is_active {
+        let elapsed = start_time.elapsed();
+        if elapsed < base_delay {
+            tokio::time::sleep(base_delay - elapsed).await;
+        }
+        return Err(LunarbaseError::AccountDeactivated);
+    }
 
-    if !password_valid {
-        let max_login_attempts = app_state.auth_state.get_max_login_attempts().await;
-        let lockout_duration_minutes = app_state.auth_state.get_lockout_duration_minutes().await;
-
-        let new_attempts = user.failed_login_attempts + 1;
-        let locked_until = if new_attempts >= max_login_attempts {
-            Some(
-                chrono::Utc::now().naive_utc()
-                    + chrono::Duration::minutes(lockout_duration_minutes as i64),
-            )
-        } else {
-            None
-        };
+    let password_valid = app_state
+        .password_service
+        .verify(&payload.password, &user.password_hash)
+        .await?;
 
-        diesel::update(users::table.find(user.id))
-            .set((
-                users::failed_login_attempts.eq(new_attempts),
-                users::locked_until.eq(locked_until),
-            ))
-            .execute(&mut conn)
-            .map_err(|_| LunarbaseError::DatabaseError)?;
+    if !password_valid {
+        app_state.lockout_service.record_failed_login(&user).await?;
 
         let elapsed = start_time.elapsed();
         if elapsed < base_delay {
@@ -863,27 +1085,23 @@ pub async fn login(
         .set((
             users::failed_login_attempts.eq(0),
             users::locked_until.eq(None::<chrono::NaiveDateTime>),
+            users::lockout_count.eq(0),
             users::last_login_at.eq(Some(chrono::Utc::now().naive_utc())),
         ))
         .execute(&mut conn)
         .map_err(|_| LunarbaseError::DatabaseError)?;
 
-    let access_token = app_state
-        .auth_state
-        .jwt_service
-        .generate_access_token(user.id, &user.email, &user.role)
-        .await?;
-
-    let refresh_token = app_state
-        .auth_state
-        .jwt_service
-        .generate_refresh_token(user.id)
-        .await?;
+    let (access_token, refresh_token) =
+        issue_tokens(&app_state, &user, payload.workspace_id).await?;
+    app_state
+        .admin_service
+        .record_login(user.id, LoginMethod::Password);
 
-    let cookie_service = CookieService::new();
+    let cookie_service = CookieService::for_request(&app_state, &request_headers).await;
     let mut headers = HeaderMap::new();
     cookie_service.set_access_token_cookie(&mut headers, &access_token);
     cookie_service.set_refresh_token_cookie(&mut headers, &refresh_token);
+    let csrf_token = cookie_service.set_csrf_token_cookie(&mut headers);
 
     let elapsed = start_time.elapsed();
     if elapsed < base_delay {
@@ -899,6 +1117,7 @@ pub async fn login(
             .jwt_service
             .access_token_duration_seconds()
             .await,
+        csrf_token,
     };
 
     Ok((headers, Json(ApiResponse::success(auth_response))))
@@ -911,7 +1130,8 @@ pub async fn login(
     request_body = serde_json::Value,
     responses(
         (status = 200, description = "Token refreshed successfully - new tokens provided via httpOnly cookies", body = ApiResponse<AuthResponse>),
-        (status = 401, description = "Invalid refresh token", body = ErrorResponse)
+        (status = 401, description = "Invalid refresh token", body = ErrorResponse),
+        (status = 403, description = "Cookie-authenticated request without a matching X-CSRF-Token header", body = ErrorResponse)
     )
 )]
 pub async fn refresh_token(
@@ -924,7 +1144,7 @@ pub async fn refresh_token(
     let refresh_claims = app_state
         .auth_state
         .jwt_service
-        .validate_refresh_token(&refresh_token)?;
+        .validate_refresh_token_with_blacklist(&refresh_token)?;
 
     let user_id: i32 = refresh_claims
         .sub
@@ -946,22 +1166,17 @@ pub async fn refresh_token(
         return Err(LunarbaseError::TokenInvalid);
     }
 
-    let access_token = app_state
-        .auth_state
-        .jwt_service
-        .generate_access_token(user.id, &user.email, &user.role)
-        .await?;
-
-    let new_refresh_token = app_state
-        .auth_state
-        .jwt_service
-        .generate_refresh_token(user.id)
-        .await?;
+    // A membership removed since the last refresh ends the session
+    let (access_token, new_refresh_token) =
+        issue_tokens(&app_state, &user, refresh_claims.workspace_id)
+            .await
+            .map_err(|_| LunarbaseError::TokenInvalid)?;
 
-    let cookie_service = CookieService::new();
+    let cookie_service = CookieService::for_request(&app_state, request.headers()).await;
     let mut headers = HeaderMap::new();
     cookie_service.set_access_token_cookie(&mut headers, &access_token);
     cookie_service.set_refresh_token_cookie(&mut headers, &new_refresh_token);
+    let csrf_token = cookie_service.set_csrf_token_cookie(&mut headers);
 
     let auth_response = AuthResponse {
         user: user.to_response(),
@@ -972,6 +1187,7 @@ pub async fn refresh_token(
             .jwt_service
             .access_token_duration_seconds()
             .await,
+        csrf_token,
     };
 
     Ok((headers, Json(ApiResponse::success(auth_response))))
@@ -1014,6 +1230,374 @@ pub async fn me(
     Ok(Json(ApiResponse::success(user.to_response())))
 }
 
+#[utoipa::path(
+    post,
+    path = "/auth/workspace",
+    tag = "Authentication",
+    request_body = SwitchWorkspaceRequest,
+    responses(
+        (status = 200, description = "Switched workspace - new tokens provided via httpOnly cookies", body = ApiResponse<AuthResponse>),
+        (status = 400, description = "Workspaces are not enabled", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not a member of the workspace", body = ErrorResponse),
+        (status = 404, description = "Workspace not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn switch_workspace(
+    State(app_state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    request_headers: HeaderMap,
+    Json(payload): Json<SwitchWorkspaceRequest>,
+) -> Result<(HeaderMap, Json<ApiResponse<AuthResponse>>), LunarbaseError> {
+    if !app_state.get_enable_workspaces().await {
+        return Err(LunarbaseError::BadRequest(
+            "Workspaces are not enabled".to_string(),
+        ));
+    }
+
+    let user_id: i32 = claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+    let user = users::table
+        .find(user_id)
+        .select(User::as_select())
+        .first(&mut conn)
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    let (access_token, refresh_token) =
+        issue_tokens(&app_state, &user, Some(payload.workspace_id)).await?;
+
+    if let Some(old_refresh_token) = CookieService::extract_refresh_token(&request_headers) {
+        let _ = app_state
+            .auth_state
+            .jwt_service
+            .blacklist_refresh_token(&old_refresh_token, Some("Switched workspace".to_string()));
+    }
+
+    let cookie_service = CookieService::for_request(&app_state, &request_headers).await;
+    let mut headers = HeaderMap::new();
+    cookie_service.set_access_token_cookie(&mut headers, &access_token);
+    cookie_service.set_refresh_token_cookie(&mut headers, &refresh_token);
+    let csrf_token = cookie_service.set_csrf_token_cookie(&mut headers);
+
+    let auth_response = AuthResponse {
+        user: user.to_response(),
+        access_token: String::new(),
+        refresh_token: String::new(),
+        expires_in: app_state
+            .auth_state
+            .jwt_service
+            .access_token_duration_seconds()
+            .await,
+        csrf_token,
+    };
+
+    Ok((headers, Json(ApiResponse::success(auth_response))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/workspaces",
+    tag = "Authentication",
+    responses(
+        (status = 200, description = "Workspaces the current user can switch to", body = ApiResponse<Vec<WorkspaceResponse>>),
+        (status = 400, description = "Workspaces are not enabled", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_my_workspaces(
+    State(app_state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<WorkspaceResponse>>>, LunarbaseError> {
+    if !app_state.get_enable_workspaces().await {
+        return Err(LunarbaseError::BadRequest(
+            "Workspaces are not enabled".to_string(),
+        ));
+    }
+
+    let user_id: i32 = claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+    let user = users::table
+        .find(user_id)
+        .select(User::as_select())
+        .first(&mut conn)
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    let workspaces = app_state
+        .workspace_service
+        .list_user_workspaces(&user)
+        .await?;
+    Ok(Json(ApiResponse::success(workspaces)))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionCookieAttributes {
+    #[schema(example = "None")]
+    pub same_site: String,
+    #[schema(example = "example.com")]
+    pub domain: Option<String>,
+    pub secure: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionInfoResponse {
+    /// `authenticated`, `no_credentials` when no access token reached the
+    /// server, or `invalid_token` when one did but was rejected
+    #[schema(example = "no_credentials")]
+    pub status: String,
+    /// `cookie` or `header`
+    #[schema(example = "cookie")]
+    pub credential_source: Option<String>,
+    /// Error code of a rejected token, e.g. `token_expired`
+    #[schema(example = "token_expired")]
+    pub token_error: Option<String>,
+    /// A refresh cookie arrived, so `/auth/refresh` can restore the session
+    pub refresh_cookie_present: bool,
+    /// Whether the request came from the API's own origin
+    pub same_origin: bool,
+    /// Attributes the session cookies get on responses to requests like this one
+    pub cookie: SessionCookieAttributes,
+    pub user: Option<UserResponse>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/session-info",
+    tag = "Authentication",
+    responses(
+        (status = 200, description = "Whether credentials reached the server and whether they are valid", body = ApiResponse<SessionInfoResponse>,
+            example = json!({
+                "success": true,
+                "data": {
+                    "status": "no_credentials",
+                    "credential_source": null,
+                    "token_error": null,
+                    "refresh_cookie_present": false,
+                    "same_origin": false,
+                    "cookie": {
+                        "same_site": "Lax",
+                        "domain": null,
+                        "secure": true
+                    },
+                    "user": null
+                }
+            })
+        )
+    )
+)]
+pub async fn session_info(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<SessionInfoResponse>>, LunarbaseError> {
+    let (credential_source, token) =
+        if let Some(token) = CookieService::extract_access_token(&headers) {
+            (Some("cookie"), Some(token))
+        } else if let Some(auth_header) = headers
+            .get(axum::http::header::AUTHORIZATION)
+            .and_then(|header| header.to_str().ok())
+        {
+            (
+                Some("header"),
+                JwtService::extract_token_from_header(auth_header)
+                    .ok()
+                    .map(str::to_string),
+            )
+        } else {
+            (None, None)
+        };
+
+    let validated = match (credential_source, token) {
+        (None, _) => None,
+        (Some(_), None) => Some(Err(LunarbaseError::TokenInvalid)),
+        (Some(_), Some(token)) => Some(
+            app_state
+                .auth_state
+                .jwt_service
+                .validate_access_token_with_verification(&token)
+                .and_then(|claims| {
+                    let user_id: i32 = claims
+                        .sub
+                        .parse()
+                        .map_err(|_| LunarbaseError::TokenInvalid)?;
+                    let mut conn = app_state
+                        .db_pool
+                        .get()
+                        .map_err(|_| LunarbaseError::DatabaseError)?;
+                    users::table
+                        .find(user_id)
+                        .select(User::as_select())
+                        .first(&mut conn)
+                        .map_err(|_| LunarbaseError::TokenInvalid)
+                }),
+        ),
+    };
+
+    let (status, token_error, user) = match validated {
+        None => ("no_credentials", None, None),
+        Some(Ok(user)) => ("authenticated", None, Some(user.to_response())),
+        Some(Err(LunarbaseError::DatabaseError)) => return Err(LunarbaseError::DatabaseError),
+        Some(Err(e)) => ("invalid_token", Some(e.code().to_string()), None),
+    };
+
+    let cookie_service = CookieService::for_request(&app_state, &headers).await;
+    let cookie = cookie_service.config();
+
+    Ok(Json(ApiResponse::success(SessionInfoResponse {
+        status: status.to_string(),
+        credential_source: credential_source.map(str::to_string),
+        token_error,
+        refresh_cookie_present: CookieService::extract_refresh_token(&headers).is_some(),
+        same_origin: is_same_origin(&headers),
+        cookie: SessionCookieAttributes {
+            same_site: cookie.same_site.clone(),
+            domain: cookie.domain.clone(),
+            secure: cookie.secure,
+        },
+        user,
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/guest-session",
+    tag = "Authentication",
+    request_body(
+        content = GuestSessionRequest,
+        description = "Optional; restricts the token to one of the guest collections"
+    ),
+    responses(
+        (status = 200, description = "Short-lived token that may only create records in the guest collections", body = ApiResponse<GuestSessionResponse>),
+        (status = 400, description = "Invalid JSON payload", body = ErrorResponse),
+        (status = 403, description = "Guest sessions are disabled or the collection is not open to them", body = ErrorResponse),
+        (status = 429, description = "Too many guest sessions started from this client", body = ErrorResponse)
+    )
+)]
+pub async fn guest_session(
+    State(app_state): State<AppState>,
+    request: Request,
+) -> Result<(HeaderMap, Json<ApiResponse<GuestSessionResponse>>), LunarbaseError> {
+    let client_ip = SmartIpKeyExtractor.extract(&request).ok();
+    let invalid_payload =
+        || LunarbaseError::ValidationError(vec!["Invalid JSON payload".to_string()]);
+    let body = Bytes::from_request(request, &app_state)
+        .await
+        .map_err(|_| invalid_payload())?;
+    let payload: GuestSessionRequest = if body.is_empty() {
+        GuestSessionRequest::default()
+    } else {
+        serde_json::from_slice(&body).map_err(|_| invalid_payload())?
+    };
+
+    if !app_state.get_guest_sessions_enabled().await {
+        return Err(LunarbaseError::Forbidden(
+            "Guest sessions are disabled".to_string(),
+        ));
+    }
+
+    let mut collections = app_state.get_guest_session_collections().await;
+    if let Some(collection) = &payload.collection {
+        if !collections.contains(collection) {
+            return Err(LunarbaseError::Forbidden(format!(
+                "Collection '{}' is not open to guest sessions",
+                collection
+            )));
+        }
+        collections = vec![collection.clone()];
+    }
+    if collections.is_empty() {
+        return Err(LunarbaseError::Forbidden(
+            "No collection is open to guest sessions".to_string(),
+        ));
+    }
+
+    let quota_warning = enforce_guest_session_limit(&app_state, client_ip).await?;
+
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let lifetime =
+        chrono::Duration::minutes(app_state.get_guest_session_ttl_minutes().await.max(1) as i64);
+    let access_token = app_state.auth_state.jwt_service.generate_guest_token(
+        GuestScope {
+            session_id: session_id.clone(),
+            collection: payload.collection,
+        },
+        lifetime,
+    )?;
+
+    debug!("Started guest session {} for {:?}", session_id, client_ip);
+    let mut headers = HeaderMap::new();
+    if let Some(warning) = quota_warning
+        && let Ok(value) = HeaderValue::from_str(&warning.header_value())
+    {
+        headers.insert(QUOTA_WARNING_HEADER, value);
+    }
+    Ok((
+        headers,
+        Json(ApiResponse::success(GuestSessionResponse {
+            access_token,
+            expires_in: lifetime.num_seconds(),
+            session_id,
+            collections,
+        })),
+    ))
+}
+
+/// Rejects a guest session once its client IP started
+/// `auth.guest_session_max_per_ip` of them within the configured window, and
+/// returns the quota warning of accepted sessions past the warning threshold.
+/// Requests whose IP cannot be told share one count.
+async fn enforce_guest_session_limit(
+    app_state: &AppState,
+    client_ip: Option<IpAddr>,
+) -> Result<Option<QuotaWarning>, LunarbaseError> {
+    let subject = client_ip.map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
+    let key = format!("guest_session:ip:{}", subject);
+
+    let max_sessions = app_state.get_guest_session_max_per_ip().await.max(1) as usize;
+    let window =
+        Duration::from_secs(app_state.get_guest_session_window_minutes().await as u64 * 60);
+    if app_state
+        .guest_session_limiter
+        .try_record(std::slice::from_ref(&key), max_sessions, window)
+    {
+        let started = app_state.guest_session_limiter.recorded(&key, window);
+        return Ok(app_state
+            .quota_warning_service
+            .observe(QuotaKind::GuestSessions, &subject, started, max_sessions)
+            .await);
+    }
+
+    tracing::warn!("Rate limited guest session request from {:?}", client_ip);
+    let _ = app_state
+        .metrics_state
+        .increment_custom_metric(
+            "guest_session_rate_limited_total",
+            "Guest session requests rejected by the per-IP limit",
+        )
+        .await;
+    Err(LunarbaseError::RateLimitExceeded)
+}
+
 #[utoipa::path(
     post,
     path = "/auth/register-admin",
@@ -1030,13 +1614,15 @@ pub async fn register_admin(
     State(app_state): State<AppState>,
     request: Request,
 ) -> Result<(StatusCode, HeaderMap, Json<ApiResponse<AuthResponse>>), LunarbaseError> {
-    let Json(payload): Json<RegisterRequest> = Json::from_request(request, &app_state)
+    let request_headers = request.headers().clone();
+    let Json(mut payload): Json<RegisterRequest> = Json::from_request(request, &app_state)
         .await
         .map_err(|_| LunarbaseError::ValidationError(vec!["Invalid JSON payload".to_string()]))?;
 
     payload
         .validate()
         .map_err(LunarbaseError::ValidationError)?;
+    ensure_deliverable_email(&app_state, &payload.email).await?;
 
     let mut conn = app_state
         .db_pool
@@ -1070,28 +1656,20 @@ pub async fn register_admin(
         ]));
     }
 
-    let existing_username = users::table
-        .filter(users::username.eq(&payload.username))
-        .select(User::as_select())
-        .first(&mut conn)
-        .optional()
-        .map_err(|_| LunarbaseError::DatabaseError)?;
-
-    if existing_username.is_some() {
+    if let Err(error) =
+        ensure_username_available(&app_state, &mut conn, &payload.username, None).await
+    {
         tokio::time::sleep(Duration::from_millis(100)).await;
-        return Err(LunarbaseError::ValidationError(vec![
-            "Username already taken".to_string(),
-        ]));
+        return Err(error);
     }
 
-    let new_user = NewUser::new_with_role(
+    let password_hash = app_state.password_service.hash(&payload.password).await?;
+    let new_user = NewUser::with_password_hash(
         payload.email,
-        &payload.password,
+        password_hash,
         payload.username,
         "admin".to_string(),
-        &app_state.password_pepper,
-    )
-    .map_err(|_| LunarbaseError::InternalError)?;
+    );
 
     diesel::insert_into(users::table)
         .values(&new_user)
@@ -1104,22 +1682,13 @@ pub async fn register_admin(
         .first(&mut conn)
         .map_err(|_| LunarbaseError::DatabaseError)?;
 
-    let access_token = app_state
-        .auth_state
-        .jwt_service
-        .generate_access_token(user.id, &user.email, &user.role)
-        .await?;
-
-    let refresh_token = app_state
-        .auth_state
-        .jwt_service
-        .generate_refresh_token(user.id)
-        .await?;
+    let (access_token, refresh_token) = issue_tokens(&app_state, &user, None).await?;
 
-    let cookie_service = CookieService::new();
+    let cookie_service = CookieService::for_request(&app_state, &request_headers).await;
     let mut headers = HeaderMap::new();
     cookie_service.set_access_token_cookie(&mut headers, &access_token);
     cookie_service.set_refresh_token_cookie(&mut headers, &refresh_token);
+    let csrf_token = cookie_service.set_csrf_token_cookie(&mut headers);
 
     let auth_response = AuthResponse {
         user: user.to_response(),
@@ -1130,6 +1699,7 @@ pub async fn register_admin(
             .jwt_service
             .access_token_duration_seconds()
             .await,
+        csrf_token,
     };
 
     Ok((
@@ -41,11 +41,11 @@ pub async fn create_manual_backup(
             error!("Backup service is not configured or disabled");
             return Err((
                 StatusCode::SERVICE_UNAVAILABLE,
-                Json(ErrorResponse {
-                    success: false,
-                    error: "Backup service is not available".to_string(),
-                    details: Some("Backup service is not configured or disabled".to_string()),
-                }),
+                Json(ErrorResponse::with_details(
+                    "backup_unavailable",
+                    "Backup service is not available".to_string(),
+                    "Backup service is not configured or disabled".to_string(),
+                )),
             ));
         }
     };
@@ -75,68 +75,66 @@ pub async fn create_manual_backup(
             error!("Backup is disabled in configuration");
             Err((
                 StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    success: false,
-                    error: "Backup disabled".to_string(),
-                    details: Some(
-                        "Backup functionality is disabled in the system configuration".to_string(),
-                    ),
-                }),
+                Json(ErrorResponse::with_details(
+                    "backup_disabled",
+                    "Backup disabled".to_string(),
+                    "Backup functionality is disabled in the system configuration".to_string(),
+                )),
             ))
         }
         Err(BackupError::S3Error(e)) => {
             error!("S3 error during backup: {}", e);
             Err((
                 StatusCode::SERVICE_UNAVAILABLE,
-                Json(ErrorResponse {
-                    success: false,
-                    error: "S3 service error".to_string(),
-                    details: Some(format!("Failed to upload backup to S3: {}", e)),
-                }),
+                Json(ErrorResponse::with_details(
+                    "storage_error",
+                    "S3 service error".to_string(),
+                    format!("Failed to upload backup to S3: {}", e),
+                )),
             ))
         }
         Err(BackupError::DatabaseError(e)) => {
             error!("Database error during backup: {}", e);
             Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    success: false,
-                    error: "Database error".to_string(),
-                    details: Some(format!("Failed to create database backup: {}", e)),
-                }),
+                Json(ErrorResponse::with_details(
+                    "database_error",
+                    "Database error".to_string(),
+                    format!("Failed to create database backup: {}", e),
+                )),
             ))
         }
         Err(BackupError::IoError(e)) => {
             error!("IO error during backup: {}", e);
             Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    success: false,
-                    error: "IO error".to_string(),
-                    details: Some(format!("File system error during backup: {}", e)),
-                }),
+                Json(ErrorResponse::with_details(
+                    "io_error",
+                    "IO error".to_string(),
+                    format!("File system error during backup: {}", e),
+                )),
             ))
         }
         Err(BackupError::CompressionError(e)) => {
             error!("Compression error during backup: {}", e);
             Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    success: false,
-                    error: "Compression error".to_string(),
-                    details: Some(format!("Failed to compress backup: {}", e)),
-                }),
+                Json(ErrorResponse::with_details(
+                    "compression_error",
+                    "Compression error".to_string(),
+                    format!("Failed to compress backup: {}", e),
+                )),
             ))
         }
         Err(e) => {
             error!("Unexpected error during backup: {}", e);
             Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    success: false,
-                    error: "Internal server error".to_string(),
-                    details: Some(format!("Unexpected error: {}", e)),
-                }),
+                Json(ErrorResponse::with_details(
+                    "internal_error",
+                    "Internal server error".to_string(),
+                    format!("Unexpected error: {}", e),
+                )),
             ))
         }
     }
@@ -172,11 +170,11 @@ pub async fn get_backup_health(
         }
         None => Err((
             StatusCode::SERVICE_UNAVAILABLE,
-            Json(ErrorResponse {
-                success: false,
-                error: "Backup service unavailable".to_string(),
-                details: Some("Backup service is not configured or disabled".to_string()),
-            }),
+            Json(ErrorResponse::with_details(
+                "backup_unavailable",
+                "Backup service unavailable".to_string(),
+                "Backup service is not configured or disabled".to_string(),
+            )),
         )),
     }
 }
@@ -0,0 +1,136 @@
+use crate::{
+    AppState,
+    handlers::collections::reject_explicit_record_id,
+    models::{BatchMethod, BatchRequest, BatchResponse, Permission, User},
+    services::configuration_manager::ConfigurationAccess,
+    utils::{ApiResponse, Claims, ErrorResponse, LunarbaseError},
+};
+use axum::{Extension, extract::State, response::Json};
+
+#[utoipa::path(
+    post,
+    path = "/batch",
+    tag = "Records",
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "All operations executed successfully", body = ApiResponse<BatchResponse>),
+        (status = 400, description = "Invalid batch or validation error, nothing was applied", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions for at least one operation", body = ErrorResponse),
+        (status = 404, description = "Collection or record not found, nothing was applied", body = ErrorResponse),
+        (status = 429, description = "The creates would exceed the role's daily record creation quota, nothing was applied", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn execute_batch(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(mut request): Json<BatchRequest>,
+) -> Result<Json<ApiResponse<BatchResponse>>, LunarbaseError> {
+    use crate::schema::users;
+    use diesel::prelude::*;
+
+    if request.operations.is_empty() {
+        return Err(LunarbaseError::ValidationError(vec![
+            "Batch must contain at least one operation".to_string(),
+        ]));
+    }
+
+    let max_operations = state.get_batch_max_operations().await.max(1) as usize;
+    if request.operations.len() > max_operations {
+        return Err(LunarbaseError::ValidationError(vec![format!(
+            "Batch contains {} operations, the maximum is {}",
+            request.operations.len(),
+            max_operations
+        )]));
+    }
+
+    let errors: Vec<String> = request
+        .operations
+        .iter()
+        .enumerate()
+        .filter_map(|(index, operation)| operation.validate(index).err())
+        .collect();
+    if !errors.is_empty() {
+        return Err(LunarbaseError::ValidationError(errors));
+    }
+
+    let user_id: i32 = claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| LunarbaseError::InternalError)?;
+
+    let user = users::table
+        .filter(users::id.eq(user_id))
+        .select(User::as_select())
+        .first::<User>(&mut conn)
+        .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
+    let user = claims.scope_user(user);
+
+    for operation in request.operations.iter_mut() {
+        let collection = state
+            .collection_service
+            .get_collection(&operation.collection)
+            .await?;
+        if claims
+            .workspace_id
+            .is_some_and(|workspace_id| workspace_id != collection.workspace_id)
+        {
+            return Err(LunarbaseError::NotFound("Collection not found".to_string()));
+        }
+
+        let permission = match operation.method {
+            BatchMethod::Create => Permission::Create,
+            BatchMethod::Update => Permission::Update,
+            BatchMethod::Delete => Permission::Delete,
+        };
+
+        let has_permission = state
+            .permission_service
+            .check_collection_permission(&user, collection.id, permission)
+            .await?;
+
+        if !has_permission {
+            state
+                .permission_audit_service
+                .record_denial(&user, &collection, None, permission)
+                .await;
+            return Err(LunarbaseError::RecordPermissionDenied(permission));
+        }
+
+        if operation.method == BatchMethod::Create
+            && let Some(data) = operation.data.as_mut()
+        {
+            reject_explicit_record_id(&user, data)?;
+            state.ownership_service.set_record_ownership(&user, data)?;
+        }
+    }
+
+    let records_created = request
+        .operations
+        .iter()
+        .filter(|operation| operation.method == BatchMethod::Create)
+        .count() as i64;
+    state
+        .usage_service
+        .check(user_id, &user.role, records_created, 0)
+        .await?;
+
+    let results = state
+        .collection_service
+        .execute_batch(&request.operations, Some(user_id))
+        .await?;
+    state
+        .usage_service
+        .record(user_id, records_created, 0)
+        .await;
+
+    Ok(Json(ApiResponse::success(BatchResponse { results })))
+}
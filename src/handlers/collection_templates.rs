@@ -0,0 +1,187 @@
+use crate::{
+    AppState,
+    models::{
+        CollectionTemplate, CreateFromTemplateRequest, CreateFromTemplateResponse,
+        DEFAULT_WORKSPACE_ID, SaveAsTemplateRequest,
+    },
+    utils::{ApiResponse, Claims, ErrorResponse, LunarbaseError},
+};
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+
+#[utoipa::path(
+    get,
+    path = "/collections/templates",
+    tag = "Collections",
+    responses(
+        (status = 200, description = "Built-in and custom collection templates", body = ApiResponse<Vec<CollectionTemplate>>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_collection_templates(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<CollectionTemplate>>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let templates = state.collection_template_service.list_templates()?;
+    Ok(Json(ApiResponse::success(templates)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/collections/templates/{template_id}",
+    tag = "Collections",
+    params(
+        ("template_id" = String, Path, description = "Template id")
+    ),
+    responses(
+        (status = 200, description = "Template retrieved successfully", body = ApiResponse<CollectionTemplate>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Template not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_collection_template(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(template_id): Path<String>,
+) -> Result<Json<ApiResponse<CollectionTemplate>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let template = state
+        .collection_template_service
+        .get_template(&template_id)?;
+    Ok(Json(ApiResponse::success(template)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/collections/templates/{template_id}",
+    tag = "Collections",
+    params(
+        ("template_id" = String, Path, description = "Template id")
+    ),
+    responses(
+        (status = 200, description = "Template deleted successfully", body = ApiResponse<String>),
+        (status = 400, description = "Built-in templates cannot be deleted", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Template not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_collection_template(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(template_id): Path<String>,
+) -> Result<Json<ApiResponse<String>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    state
+        .collection_template_service
+        .delete_template(&template_id)?;
+
+    Ok(Json(ApiResponse::success(
+        "Template deleted successfully".to_string(),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/collections/from-template/{template_id}",
+    tag = "Collections",
+    params(
+        ("template_id" = String, Path, description = "Template id")
+    ),
+    request_body = CreateFromTemplateRequest,
+    responses(
+        (status = 201, description = "Collection created from template", body = ApiResponse<CreateFromTemplateResponse>),
+        (status = 400, description = "Invalid collection name or sample records", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Template not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_collection_from_template(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(template_id): Path<String>,
+    Json(request): Json<CreateFromTemplateRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<CreateFromTemplateResponse>>), LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let response = state
+        .collection_template_service
+        .create_from_template(
+            &template_id,
+            request,
+            claims.workspace_id.unwrap_or(DEFAULT_WORKSPACE_ID),
+            claims.sub.parse().ok(),
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(response))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/collections/{name}/save-as-template",
+    tag = "Collections",
+    params(
+        ("name" = String, Path, description = "Collection name")
+    ),
+    request_body = SaveAsTemplateRequest,
+    responses(
+        (status = 201, description = "Template saved successfully", body = ApiResponse<CollectionTemplate>),
+        (status = 400, description = "Invalid template id or sample records", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse),
+        (status = 409, description = "A template with this id already exists", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn save_collection_as_template(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(name): Path<String>,
+    Json(request): Json<SaveAsTemplateRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<CollectionTemplate>>), LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let template = state
+        .collection_template_service
+        .save_as_template(&name, request, claims.sub.parse().ok())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(template))))
+}
@@ -0,0 +1,204 @@
+use crate::{
+    AppState,
+    handlers::collections::claims_to_user,
+    models::{
+        CollectionViewResponse, CreateCollectionViewRequest, Permission,
+        UpdateCollectionViewRequest,
+    },
+    utils::{ApiResponse, Claims, ErrorResponse, LunarbaseError},
+};
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+
+#[utoipa::path(
+    post,
+    path = "/collections/{collection_name}/views",
+    tag = "Collections",
+    params(
+        ("collection_name" = String, Path, description = "Collection name")
+    ),
+    request_body = CreateCollectionViewRequest,
+    responses(
+        (status = 201, description = "View created successfully", body = ApiResponse<CollectionViewResponse>),
+        (status = 400, description = "Invalid view definition", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse),
+        (status = 409, description = "A view with this name already exists", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_collection_view(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(collection_name): Path<String>,
+    Json(request): Json<CreateCollectionViewRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<CollectionViewResponse>>), LunarbaseError> {
+    let user = claims_to_user(&claims, &state).await?;
+    let collection = state
+        .collection_service
+        .get_collection(&collection_name)
+        .await?;
+
+    let can_list = state
+        .permission_service
+        .check_collection_permission(&user, collection.id, Permission::List)
+        .await?;
+    if !can_list {
+        state
+            .permission_audit_service
+            .record_denial(&user, &collection, None, Permission::List)
+            .await;
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let view = state
+        .collection_view_service
+        .create_view(&collection_name, request, &user)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(view))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/collections/{collection_name}/views",
+    tag = "Collections",
+    params(
+        ("collection_name" = String, Path, description = "Collection name")
+    ),
+    responses(
+        (status = 200, description = "Views visible to the current user", body = ApiResponse<Vec<CollectionViewResponse>>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_collection_views(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(collection_name): Path<String>,
+) -> Result<Json<ApiResponse<Vec<CollectionViewResponse>>>, LunarbaseError> {
+    let user = claims_to_user(&claims, &state).await?;
+
+    let views = state
+        .collection_view_service
+        .list_views(&collection_name, &user)
+        .await?;
+
+    Ok(Json(ApiResponse::success(views)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/collections/{collection_name}/views/{view_name}",
+    tag = "Collections",
+    params(
+        ("collection_name" = String, Path, description = "Collection name"),
+        ("view_name" = String, Path, description = "View name")
+    ),
+    responses(
+        (status = 200, description = "View retrieved successfully", body = ApiResponse<CollectionViewResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Collection or view not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_collection_view(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((collection_name, view_name)): Path<(String, String)>,
+) -> Result<Json<ApiResponse<CollectionViewResponse>>, LunarbaseError> {
+    let user = claims_to_user(&claims, &state).await?;
+
+    let view = state
+        .collection_view_service
+        .get_view(&collection_name, &view_name, Some(&user))
+        .await?;
+
+    Ok(Json(ApiResponse::success(
+        view.to_response(&collection_name),
+    )))
+}
+
+#[utoipa::path(
+    put,
+    path = "/collections/{collection_name}/views/{view_name}",
+    tag = "Collections",
+    params(
+        ("collection_name" = String, Path, description = "Collection name"),
+        ("view_name" = String, Path, description = "View name")
+    ),
+    request_body = UpdateCollectionViewRequest,
+    responses(
+        (status = 200, description = "View updated successfully", body = ApiResponse<CollectionViewResponse>),
+        (status = 400, description = "Invalid view definition", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Only the creator or an admin can modify the view", body = ErrorResponse),
+        (status = 404, description = "Collection or view not found", body = ErrorResponse),
+        (status = 409, description = "A view with this name already exists", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_collection_view(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((collection_name, view_name)): Path<(String, String)>,
+    Json(request): Json<UpdateCollectionViewRequest>,
+) -> Result<Json<ApiResponse<CollectionViewResponse>>, LunarbaseError> {
+    let user = claims_to_user(&claims, &state).await?;
+
+    let view = state
+        .collection_view_service
+        .update_view(&collection_name, &view_name, request, &user)
+        .await?;
+
+    Ok(Json(ApiResponse::success(view)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/collections/{collection_name}/views/{view_name}",
+    tag = "Collections",
+    params(
+        ("collection_name" = String, Path, description = "Collection name"),
+        ("view_name" = String, Path, description = "View name")
+    ),
+    responses(
+        (status = 200, description = "View deleted successfully", body = ApiResponse<String>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Only the creator or an admin can delete the view", body = ErrorResponse),
+        (status = 404, description = "Collection or view not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_collection_view(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((collection_name, view_name)): Path<(String, String)>,
+) -> Result<Json<ApiResponse<String>>, LunarbaseError> {
+    let user = claims_to_user(&claims, &state).await?;
+
+    state
+        .collection_view_service
+        .delete_view(&collection_name, &view_name, &user)
+        .await?;
+
+    Ok(Json(ApiResponse::success(
+        "View deleted successfully".to_string(),
+    )))
+}
@@ -1,22 +1,39 @@
 use crate::{
     AppState,
+    middleware::{ApiVersion, BodyLimit, read_field_limited},
     models::{
-        CollectionResponse, CreateCollectionRequest, CreateRecordRequest, FileUpload,
-        RecordResponse, UpdateCollectionRequest, UpdateRecordRequest, User,
+        CollectionFields, CollectionHealthReport, CollectionIntegrityReport, CollectionListEntry,
+        CollectionRepairReport, CollectionResponse, CollectionSchema,
+        CollectionSchemaVersionResponse, CollectionWorkflow, CreateCollectionRequest,
+        CreateRecordRequest, DEFAULT_WORKSPACE_ID, FieldValidationError, FileUpload,
+        ManifestVerification, MoveRecordRequest, NewPermissionAuditEntry, NotificationContent,
+        NotificationKind, OrphanSweepReport, PendingCollectionDelete, PublishRecordRequest,
+        QuotaKind, QuotaWarning, RecordExport, RecordManifest, RecordReferences, RecordResponse,
+        RecordScheduleReport, RecordStatus, RecordValidationResponse, RetentionReport,
+        USERS_SYSTEM_COLLECTION, UnpublishRecordRequest, UpdateCollectionRequest,
+        UpdateRecordRequest, User, ValidateRecordRequest,
     },
-    utils::{ApiResponse, Claims, ErrorResponse, LunarbaseError},
+    query_engine::QueryEngine,
+    services::{
+        CachedQueryResult, CollectionService, DELETE_CONFIRMATION_TTL, QUOTA_WARNING_HEADER,
+        configuration_manager::ConfigurationAccess,
+    },
+    utils::{ApiResponse, Claims, ErrorResponse, GuestScope, LunarbaseError, Message},
 };
 use axum::{
     Extension,
-    extract::{Multipart, Path, Query, State},
-    http::StatusCode,
-    response::Json,
+    extract::{Multipart, Path, Query, RawQuery, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Json},
 };
 use base64::{Engine as _, engine::general_purpose};
-use diesel::RunQueryDsl;
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
-async fn claims_to_user(claims: &Claims, state: &AppState) -> Result<User, LunarbaseError> {
+pub(crate) async fn claims_to_user(
+    claims: &Claims,
+    state: &AppState,
+) -> Result<User, LunarbaseError> {
     use crate::schema::users;
     use diesel::prelude::*;
 
@@ -30,13 +47,210 @@ async fn claims_to_user(claims: &Claims, state: &AppState) -> Result<User, Lunar
         .get()
         .map_err(|_| LunarbaseError::InternalError)?;
 
-    users::table
+    let user = users::table
         .filter(users::id.eq(user_id))
         .select(User::as_select())
         .first(&mut conn)
-        .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))
+        .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
+    Ok(claims.scope_user(user))
+}
+
+/// Collections visible from the caller's workspace; every collection while
+/// workspaces are disabled.
+async fn workspace_collections(
+    state: &AppState,
+    claims: &Claims,
+) -> Result<Vec<CollectionResponse>, LunarbaseError> {
+    let mut collections = state.collection_service.list_collections().await?;
+    if let Some(workspace_id) = claims.workspace_id {
+        collections.retain(|collection| collection.workspace_id == workspace_id);
+    }
+    Ok(collections)
+}
+
+/// Applies `users.relation_visibility` to reads of the `_users` system collection.
+async fn ensure_users_collection_readable(
+    state: &AppState,
+    claims: Option<&Claims>,
+) -> Result<(), LunarbaseError> {
+    match state.get_users_relation_visibility().await.as_str() {
+        "public" => Ok(()),
+        "authenticated" if claims.is_some() => Ok(()),
+        "authenticated" => Err(LunarbaseError::TokenMissing),
+        _ => Err(LunarbaseError::NotFound("Collection not found".to_string())),
+    }
+}
+
+/// Only admins may choose the id of a new record; the collection must allow it too.
+pub(crate) fn reject_explicit_record_id(
+    user: &User,
+    data: &serde_json::Value,
+) -> Result<(), LunarbaseError> {
+    if user.role != "admin" && data.get("id").is_some_and(|id| !id.is_null()) {
+        return Err(LunarbaseError::ValidationError(vec![
+            "Field 'id' can only be set by admins".to_string(),
+        ]));
+    }
+    Ok(())
+}
+
+fn reject_system_collection_write(collection_name: &str) -> Result<(), LunarbaseError> {
+    if collection_name == USERS_SYSTEM_COLLECTION {
+        return Err(LunarbaseError::MethodNotAllowed(format!(
+            "{} is read-only",
+            USERS_SYSTEM_COLLECTION
+        )));
+    }
+    Ok(())
+}
+
+/// A record write sent as `multipart/form-data`. Problems with single parts
+/// are collected rather than returned one at a time, keyed by the form part
+/// or file field they belong to so the admin UI can show them in place.
+struct MultipartRecord {
+    data: serde_json::Value,
+    files: HashMap<String, FileUpload>,
+    errors: Vec<FieldValidationError>,
+}
+
+impl MultipartRecord {
+    async fn read(state: &AppState, multipart: &mut Multipart) -> Result<Self, LunarbaseError> {
+        let mut record = Self {
+            data: serde_json::Value::Object(serde_json::Map::new()),
+            files: HashMap::new(),
+            errors: Vec::new(),
+        };
+        let mut seen_parts = std::collections::HashSet::new();
+        let data_limit = BodyLimit::record_json(state).await;
+        let file_limit = BodyLimit::file_upload(state).await;
+
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(|_| LunarbaseError::BadRequest("Invalid multipart data".to_string()))?
+        {
+            let name = field.name().unwrap_or_default().to_string();
+            let field_name = name.strip_prefix("file_").unwrap_or_default().to_string();
+
+            if name != "data" && field_name.is_empty() {
+                // Unnamed parts would otherwise be dropped from the `fields` map
+                let part = if name.is_empty() { "(unnamed)" } else { &name };
+                record.part_error(part, "unknown_part", Message::new("multipart.unknown_part"));
+                continue;
+            }
+            if !seen_parts.insert(name.clone()) {
+                let key = if name == "data" { &name } else { &field_name };
+                record.errors.push(FieldValidationError {
+                    field: key.clone(),
+                    code: "duplicate_part".to_string(),
+                    message: Message::new("multipart.duplicate_part").arg("part", &name),
+                });
+                continue;
+            }
+
+            if name == "data" {
+                let data_bytes =
+                    read_field_limited(field, data_limit, "Failed to read data field").await?;
+                record.read_data(&name, data_bytes);
+                continue;
+            }
+
+            let filename = field.file_name().unwrap_or("unknown").to_string();
+            // The raw header, so a malformed one is reported instead of
+            // silently falling back to a generic type
+            let content_type = field
+                .headers()
+                .get(axum::http::header::CONTENT_TYPE)
+                .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+                .unwrap_or_else(|| "application/octet-stream".to_string());
+            let base64_encoded = field
+                .headers()
+                .get("content-transfer-encoding")
+                .is_some_and(|value| value.as_bytes().eq_ignore_ascii_case(b"base64"));
+
+            let file_bytes = read_field_limited(field, file_limit, "Failed to read file").await?;
+            let data = if base64_encoded {
+                // Line breaks are allowed in base64 bodies of MIME parts
+                let encoded: Vec<u8> = file_bytes
+                    .into_iter()
+                    .filter(|byte| !byte.is_ascii_whitespace())
+                    .collect();
+                String::from_utf8_lossy(&encoded).into_owned()
+            } else {
+                general_purpose::STANDARD.encode(&file_bytes)
+            };
+
+            record.files.insert(
+                field_name,
+                FileUpload {
+                    filename,
+                    content_type,
+                    data,
+                },
+            );
+        }
+
+        Ok(record)
+    }
+
+    fn read_data(&mut self, part: &str, bytes: Vec<u8>) {
+        let Ok(text) = String::from_utf8(bytes) else {
+            self.part_error(
+                part,
+                "invalid_encoding",
+                Message::new("multipart.invalid_encoding"),
+            );
+            return;
+        };
+        match serde_json::from_str::<serde_json::Value>(&text) {
+            Ok(data) if data.is_object() => self.data = data,
+            Ok(_) => self.errors.push(FieldValidationError {
+                field: part.to_string(),
+                code: "invalid_type".to_string(),
+                message: Message::new("validation.not_an_object"),
+            }),
+            Err(e) => self.part_error(
+                part,
+                "invalid_json",
+                Message::new("multipart.invalid_json").arg("reason", e),
+            ),
+        }
+    }
+
+    /// Decoded size of the uploaded files, as counted against usage quotas
+    fn upload_bytes(&self) -> i64 {
+        self.files
+            .values()
+            .map(|file| file.decoded_len() as i64)
+            .sum()
+    }
+
+    fn part_error(&mut self, part: &str, code: &str, message: Message) {
+        self.errors.push(FieldValidationError {
+            field: part.to_string(),
+            code: code.to_string(),
+            message: message.arg("part", part),
+        });
+    }
+
+    /// Adds the file problems found against `schema` and fails with every
+    /// collected error, so none of the files are stored.
+    fn check(
+        mut self,
+        collection_service: &CollectionService,
+        schema: &CollectionSchema,
+    ) -> Result<(serde_json::Value, Option<HashMap<String, FileUpload>>), LunarbaseError> {
+        self.errors
+            .extend(collection_service.check_record_files(schema, &self.files));
+        if !self.errors.is_empty() {
+            return Err(LunarbaseError::InvalidFields(self.errors));
+        }
+        let files = (!self.files.is_empty()).then_some(self.files);
+        Ok((self.data, files))
+    }
 }
 use std::collections::HashMap;
+use tokio::sync::OwnedSemaphorePermit;
 use utoipa::ToSchema;
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -51,6 +265,68 @@ pub struct ListRecordsQuery {
     pub filter: Option<String>,
     #[schema(example = "search term")]
     pub search: Option<String>,
+    #[schema(example = false)]
+    pub count: Option<bool>,
+    #[schema(example = "open_tickets")]
+    pub view: Option<String>,
+    #[schema(example = "title,status")]
+    pub fields: Option<String>,
+    #[schema(example = "author")]
+    pub expand: Option<String>,
+    /// `draft`, `published` or `archived`; only callers with update permission
+    /// see records that are not published
+    #[schema(example = "draft")]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GetRecordQuery {
+    #[schema(example = "author")]
+    pub expand: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DeleteCollectionQuery {
+    pub confirm_token: Option<String>,
+    pub force: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SweepOrphansQuery {
+    /// Only report the orphans without dropping them
+    #[serde(default)]
+    #[schema(example = true)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RecordReferencesQuery {
+    /// Referencing record ids listed per collection (1-100, default 10)
+    #[schema(example = 10)]
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateRecordQuery {
+    /// Regenerate slug fields from their source fields
+    #[serde(default)]
+    #[schema(example = false)]
+    pub regenerate_slug: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CountRecordsQuery {
+    #[schema(example = "name:eq:Product")]
+    pub filter: Option<String>,
+    #[schema(example = "search term")]
+    pub search: Option<String>,
+    #[schema(example = "draft")]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecordCountResponse {
+    pub count: i64,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -59,6 +335,62 @@ pub struct PaginatedRecordsResponse {
     pub pagination: PaginationMeta,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecordPage {
+    pub records: Vec<RecordResponse>,
+    pub pagination: PaginationMeta,
+}
+
+/// Records of a collection: a bare array in API version 1, a [`RecordPage`]
+/// from version 2 on.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum RecordList {
+    Records(Vec<RecordResponse>),
+    Page(RecordPage),
+}
+
+/// Query parameters `list_records` understands.
+const LIST_RECORDS_PARAMETERS: [&str; 10] = [
+    "limit", "offset", "sort", "filter", "search", "count", "view", "fields", "expand", "status",
+];
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CollectionStatsQuery {
+    /// Recount every records table instead of using the cached counts
+    #[serde(default)]
+    #[schema(example = false)]
+    pub fresh: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RecordScheduleQuery {
+    #[schema(example = 100, minimum = 1, maximum = 1000)]
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ListCollectionsQuery {
+    #[schema(example = 50, minimum = 1, maximum = 500)]
+    pub limit: Option<i64>,
+    #[schema(example = 0, minimum = 0)]
+    pub offset: Option<i64>,
+    /// Matched against name, display name and description
+    #[schema(example = "blog")]
+    pub search: Option<String>,
+    /// `name`, `display_name`, `created_at` or `updated_at`, optionally with `:asc` or `:desc`
+    #[schema(example = "name:asc")]
+    pub sort: Option<String>,
+    #[serde(default)]
+    pub fields: CollectionFields,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PaginatedCollectionsResponse {
+    pub collections: Vec<CollectionListEntry>,
+    pub pagination: PaginationMeta,
+}
+
 #[derive(Debug, Serialize, ToSchema, Clone)]
 pub struct RecordWithCollection {
     #[serde(flatten)]
@@ -66,12 +398,62 @@ pub struct RecordWithCollection {
     pub collection_name: String,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GlobalSearchQuery {
+    #[schema(example = "rust")]
+    pub q: String,
+    /// Comma-separated collection names; all collections when omitted
+    #[schema(example = "posts,docs")]
+    pub collections: Option<String>,
+    /// Matches returned per collection
+    #[schema(example = 5, minimum = 1, maximum = 20)]
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GlobalSearchGroup {
+    pub collection_name: String,
+    pub results: Vec<GlobalSearchHit>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GlobalSearchHit {
+    pub record_id: String,
+    /// Field the snippet was taken from
+    pub field: Option<String>,
+    pub snippet: Option<String>,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct PaginationMeta {
     pub current_page: i64,
     pub page_size: i64,
     pub total_count: i64,
     pub total_pages: i64,
+    /// Filter as the server parsed it, in canonical `field:operator:value` form
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "price:gte:10,status:in:draft,published")]
+    pub applied_filter: Option<String>,
+    /// Sort the results came back in, including fallbacks for unsupported fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "-created_at")]
+    pub applied_sort: Option<String>,
+    /// Limit after clamping to the allowed range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 20)]
+    pub applied_limit: Option<i64>,
+    /// Offset after clamping to the allowed range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 0)]
+    pub applied_offset: Option<i64>,
+    /// Saved view the filter and sort were taken from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "open_tickets")]
+    pub view: Option<String>,
+    /// Query parameters the endpoint does not support and ignored
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[schema(example = json!(["view"]))]
+    pub ignored_parameters: Vec<String>,
 }
 
 #[utoipa::path(
@@ -98,7 +480,14 @@ pub async fn create_collection(
         return Err(LunarbaseError::InsufficientPermissions);
     }
 
-    let collection = state.collection_service.create_collection(request).await?;
+    let collection = state
+        .collection_service
+        .create_collection_in_workspace(
+            request,
+            user.workspace_id.unwrap_or(DEFAULT_WORKSPACE_ID),
+            user.sub.parse().ok(),
+        )
+        .await?;
     Ok((StatusCode::CREATED, Json(ApiResponse::success(collection))))
 }
 
@@ -106,15 +495,53 @@ pub async fn create_collection(
     get,
     path = "/collections",
     tag = "Collections",
+    params(
+        ("limit" = Option<i64>, Query, description = "Collections per page (default 50, max 500)"),
+        ("offset" = Option<i64>, Query, description = "Collections to skip"),
+        ("search" = Option<String>, Query, description = "Search name, display name and description"),
+        ("sort" = Option<String>, Query, description = "name, display_name, created_at or updated_at, with optional :asc or :desc (default created_at:desc)"),
+        ("fields" = Option<CollectionFields>, Query, description = "full (default), summary without the schema, or names")
+    ),
     responses(
-        (status = 200, description = "Collections retrieved successfully", body = ApiResponse<Vec<CollectionResponse>>)
+        (status = 200, description = "Collections retrieved successfully", body = ApiResponse<PaginatedCollectionsResponse>),
+        (status = 400, description = "Invalid sort", body = ErrorResponse)
     )
 )]
 pub async fn list_collections(
     State(state): State<AppState>,
-) -> Result<Json<ApiResponse<Vec<CollectionResponse>>>, LunarbaseError> {
-    let collections = state.collection_service.list_collections().await?;
-    Ok(Json(ApiResponse::success(collections)))
+    claims: Option<Extension<Claims>>,
+    Query(query): Query<ListCollectionsQuery>,
+) -> Result<Json<ApiResponse<PaginatedCollectionsResponse>>, LunarbaseError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let (collections, total_count) = state
+        .collection_service
+        .list_collections_page(
+            claims.and_then(|Extension(claims)| claims.workspace_id),
+            query.search.as_deref(),
+            query.sort.as_deref(),
+            query.fields,
+            limit,
+            offset,
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success(PaginatedCollectionsResponse {
+        collections,
+        pagination: PaginationMeta {
+            current_page: (offset / limit) + 1,
+            page_size: limit,
+            total_count,
+            total_pages: (total_count + limit - 1) / limit,
+            applied_filter: None,
+            applied_sort: None,
+            applied_limit: Some(limit),
+            applied_offset: Some(offset),
+            view: None,
+            ignored_parameters: Vec::new(),
+        },
+    })))
 }
 
 #[utoipa::path(
@@ -166,7 +593,7 @@ pub async fn update_collection(
 
     let collection = state
         .collection_service
-        .update_collection(&name, request)
+        .update_collection(&name, request, user.sub.parse().ok())
         .await?;
     Ok(Json(ApiResponse::success(collection)))
 }
@@ -175,12 +602,16 @@ pub async fn update_collection(
     delete,
     path = "/collections/{name}",
     params(
-        ("name" = String, Path, description = "Collection name")
+        ("name" = String, Path, description = "Collection name"),
+        ("confirm_token" = Option<String>, Query, description = "Token from a previous DELETE; drops the collection"),
+        ("force" = Option<bool>, Query, description = "Admins only: drop the collection without a confirmation token")
     ),
     responses(
+        (status = 202, description = "Nothing deleted yet; repeat the DELETE with `confirm_token` to drop the collection", body = ApiResponse<PendingCollectionDelete>),
         (status = 204, description = "Collection deleted successfully"),
+        (status = 400, description = "Confirmation token is invalid, expired or for another collection", body = ErrorResponse),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Insufficient permissions"),
+        (status = 403, description = "Insufficient permissions, or `force` from a non-admin"),
         (status = 404, description = "Collection not found")
     ),
     security(
@@ -191,7 +622,8 @@ pub async fn delete_collection(
     State(state): State<AppState>,
     Extension(user): Extension<Claims>,
     Path(name): Path<String>,
-) -> Result<StatusCode, LunarbaseError> {
+    Query(query): Query<DeleteCollectionQuery>,
+) -> Result<axum::response::Response, LunarbaseError> {
     use crate::schema::users;
     use diesel::prelude::*;
 
@@ -207,6 +639,7 @@ pub async fn delete_collection(
         .select(crate::models::User::as_select())
         .first::<crate::models::User>(&mut conn)
         .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
+    let user_model = user.scope_user(user_model);
 
     let collection = state.collection_service.get_collection(&name).await?;
 
@@ -220,11 +653,97 @@ pub async fn delete_collection(
         .await?;
 
     if !has_permission {
+        state
+            .permission_audit_service
+            .record_denial(
+                &user_model,
+                &collection,
+                None,
+                crate::models::Permission::Delete,
+            )
+            .await;
         return Err(LunarbaseError::InsufficientPermissions);
     }
 
+    let (confirmed_by, action) = if query.force.unwrap_or(false) {
+        if user_model.role != "admin" {
+            return Err(LunarbaseError::Forbidden(
+                "Only admins may force a collection delete".to_string(),
+            ));
+        }
+        ("force", "collection_force_deleted")
+    } else if let Some(token) = &query.confirm_token {
+        if !state
+            .delete_confirmations
+            .confirm(token, collection.id, user_model.id)
+        {
+            return Err(LunarbaseError::BadRequest(
+                "Invalid or expired confirmation token".to_string(),
+            ));
+        }
+        ("confirmation token", "collection_deleted")
+    } else {
+        let impact = state
+            .collection_service
+            .collection_delete_impact(&name)
+            .await?;
+        let confirm_token = state
+            .delete_confirmations
+            .issue(collection.id, user_model.id);
+        state
+            .permission_service
+            .record_audit_entry(&NewPermissionAuditEntry {
+                actor_id: Some(user_model.id),
+                target_user_id: None,
+                action: "collection_delete_requested".to_string(),
+                collection_name: Some(name.clone()),
+                role_name: None,
+                affected_count: impact.record_count,
+            })
+            .await?;
+        tracing::warn!(
+            "User {} ({}) requested deletion of collection {} ({} records, {} files, referenced by {:?})",
+            user.sub,
+            user.email,
+            name,
+            impact.record_count,
+            impact.file_count,
+            impact.referencing_collections
+        );
+        let pending = PendingCollectionDelete {
+            collection_name: name,
+            confirm_token,
+            expires_in_seconds: DELETE_CONFIRMATION_TTL.as_secs(),
+            impact,
+        };
+        return Ok((StatusCode::ACCEPTED, Json(ApiResponse::success(pending))).into_response());
+    };
+
+    let record_count = state
+        .collection_service
+        .collection_delete_impact(&name)
+        .await?
+        .record_count;
     state.collection_service.delete_collection(&name).await?;
-    Ok(StatusCode::NO_CONTENT)
+    state
+        .permission_service
+        .record_audit_entry(&NewPermissionAuditEntry {
+            actor_id: Some(user_model.id),
+            target_user_id: None,
+            action: action.to_string(),
+            collection_name: Some(name.clone()),
+            role_name: None,
+            affected_count: record_count,
+        })
+        .await?;
+    tracing::warn!(
+        "User {} ({}) deleted collection {} by {}",
+        user.sub,
+        user.email,
+        name,
+        confirmed_by
+    );
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
 #[utoipa::path(
@@ -236,13 +755,17 @@ pub async fn delete_collection(
     ),
     request_body(
         content_type = "multipart/form-data",
-        description = "Record data and optional files"
+        description = "Record data as JSON in a `data` part and files as `file_<field>` parts"
     ),
     responses(
         (status = 201, description = "Record created successfully", body = ApiResponse<RecordResponse>),
-        (status = 400, description = "Validation error", body = ErrorResponse),
-        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
-        (status = 404, description = "Collection not found", body = ErrorResponse)
+        (status = 400, description = "Validation error; `fields` is keyed by record field, or by form part for a malformed `data` or unknown part", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions, or a guest session token used outside its collections", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse),
+        (status = 405, description = "Collection is read-only", body = ErrorResponse),
+        (status = 409, description = "A record with the given id already exists", body = ErrorResponse),
+        (status = 413, description = "A file or the data part is over its limits.* setting, or the files would exceed the role's daily upload quota", body = ErrorResponse),
+        (status = 429, description = "The role's daily record creation quota is used up", body = ErrorResponse)
     ),
     security(
         ("bearer_auth" = [])
@@ -254,56 +777,13 @@ pub async fn create_record(
     Path(collection_name): Path<String>,
     mut multipart: Multipart,
 ) -> Result<(StatusCode, Json<ApiResponse<RecordResponse>>), LunarbaseError> {
-    let mut data = serde_json::Value::Object(serde_json::Map::new());
-    let mut files: HashMap<String, FileUpload> = HashMap::new();
-
-    while let Some(field) = multipart
-        .next_field()
-        .await
-        .map_err(|_| LunarbaseError::BadRequest("Invalid multipart data".to_string()))?
-    {
-        let name = field.name().unwrap_or("").to_string();
-
-        if name == "data" {
-            let data_bytes = field
-                .bytes()
-                .await
-                .map_err(|_| LunarbaseError::BadRequest("Failed to read data field".to_string()))?;
-            let data_str = String::from_utf8(data_bytes.to_vec()).map_err(|_| {
-                LunarbaseError::BadRequest("Invalid UTF-8 in data field".to_string())
-            })?;
-            data = serde_json::from_str(&data_str).map_err(|_| {
-                LunarbaseError::BadRequest("Invalid JSON in data field".to_string())
-            })?;
-        } else if name.starts_with("file_") {
-            let field_name = name.strip_prefix("file_").unwrap_or(&name).to_string();
-            let filename = field.file_name().unwrap_or("unknown").to_string();
-            let content_type = field
-                .content_type()
-                .unwrap_or("application/octet-stream")
-                .to_string();
-
-            let file_bytes = field
-                .bytes()
-                .await
-                .map_err(|_| LunarbaseError::BadRequest("Failed to read file".to_string()))?;
-            let file_data = general_purpose::STANDARD.encode(&file_bytes);
+    reject_system_collection_write(&collection_name)?;
 
-            files.insert(
-                field_name,
-                FileUpload {
-                    filename,
-                    content_type,
-                    data: file_data,
-                },
-            );
-        }
+    if let Some(guest) = &claims.guest {
+        return create_guest_record(&state, guest, &collection_name, &mut multipart).await;
     }
 
-    let mut request = CreateRecordRequest {
-        data,
-        files: if files.is_empty() { None } else { Some(files) },
-    };
+    let multipart = MultipartRecord::read(&state, &mut multipart).await?;
 
     use crate::schema::users;
     use diesel::prelude::*;
@@ -323,6 +803,7 @@ pub async fn create_record(
         .select(crate::models::User::as_select())
         .first::<crate::models::User>(&mut conn)
         .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
+    let user = claims.scope_user(user);
 
     let collection = state
         .collection_service
@@ -335,9 +816,24 @@ pub async fn create_record(
         .await?;
 
     if !has_permission {
-        return Err(LunarbaseError::InsufficientPermissions);
+        state
+            .permission_audit_service
+            .record_denial(&user, &collection, None, crate::models::Permission::Create)
+            .await;
+        return Err(LunarbaseError::RecordPermissionDenied(
+            crate::models::Permission::Create,
+        ));
     }
 
+    let upload_bytes = multipart.upload_bytes();
+    state
+        .usage_service
+        .check(user_id, &user.role, 1, upload_bytes)
+        .await?;
+
+    let (data, files) = multipart.check(&state.collection_service, &collection.schema)?;
+    let mut request = CreateRecordRequest { data, files };
+    reject_explicit_record_id(&user, &request.data)?;
     state
         .ownership_service
         .set_record_ownership(&user, &mut request.data)?;
@@ -346,81 +842,1567 @@ pub async fn create_record(
         .collection_service
         .create_record_with_events(&collection_name, request, Some(user_id))
         .await?;
+    state.usage_service.record(user_id, 1, upload_bytes).await;
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(record))))
+}
+
+/// Creates a record with a guest session token. The collection has to be one
+/// of the guest collections the session may use and the guest role needs
+/// create permission on it. The record gets no owner; the session is recorded
+/// instead.
+async fn create_guest_record(
+    state: &AppState,
+    guest: &GuestScope,
+    collection_name: &str,
+    multipart: &mut Multipart,
+) -> Result<(StatusCode, Json<ApiResponse<RecordResponse>>), LunarbaseError> {
+    let in_scope = state.get_guest_sessions_enabled().await
+        && state
+            .get_guest_session_collections()
+            .await
+            .iter()
+            .any(|name| name == collection_name)
+        && guest
+            .collection
+            .as_deref()
+            .is_none_or(|name| name == collection_name);
+    if !in_scope {
+        return Err(LunarbaseError::RecordPermissionDenied(
+            crate::models::Permission::Create,
+        ));
+    }
+
+    let collection = state
+        .collection_service
+        .get_collection(collection_name)
+        .await?;
+    let can_create = state
+        .permission_service
+        .get_effective_role_collection_permission("guest", collection.id)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|permissions| permissions.permission.can_create);
+    if !can_create {
+        return Err(LunarbaseError::RecordPermissionDenied(
+            crate::models::Permission::Create,
+        ));
+    }
+
+    let multipart = MultipartRecord::read(state, multipart).await?;
+    let (mut data, files) = multipart.check(&state.collection_service, &collection.schema)?;
+    if data.get("id").is_some_and(|id| !id.is_null()) {
+        return Err(LunarbaseError::ValidationError(vec![
+            "Field 'id' can only be set by admins".to_string(),
+        ]));
+    }
+    if let Some(fields) = data.as_object_mut() {
+        fields.remove("owner_id");
+        fields.remove("author_id");
+    }
+
+    let record = state
+        .collection_service
+        .create_guest_record(
+            collection_name,
+            CreateRecordRequest { data, files },
+            &guest.session_id,
+        )
+        .await?;
     Ok((StatusCode::CREATED, Json(ApiResponse::success(record))))
 }
 
 #[utoipa::path(
-    get,
-    path = "/collections/{collection_name}/records",
+    post,
+    path = "/collections/{collection_name}/records/validate",
     tag = "Records",
     params(
-        ("collection_name" = String, Path, description = "Collection name"),
-        ("limit" = Option<i64>, Query, description = "Limit number of records"),
-        ("offset" = Option<i64>, Query, description = "Offset for pagination"),
-        ("sort" = Option<String>, Query, description = "Sort field"),
-        ("filter" = Option<String>, Query, description = "Filter expression"),
-        ("search" = Option<String>, Query, description = "Search term")
+        ("collection_name" = String, Path, description = "Collection name")
     ),
+    request_body = ValidateRecordRequest,
     responses(
-        (status = 200, description = "Records retrieved successfully", body = ApiResponse<Vec<RecordResponse>>),
-        (status = 404, description = "Collection not found", body = ErrorResponse)
+        (status = 200, description = "Every field error the payload would be rejected with; nothing is written", body = ApiResponse<RecordValidationResponse>),
+        (status = 400, description = "Invalid record id", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse),
+        (status = 405, description = "Collection is read-only", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
     )
 )]
-pub async fn list_records(
+pub async fn validate_record(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
     Path(collection_name): Path<String>,
-    Query(query): Query<ListRecordsQuery>,
-) -> Result<Json<ApiResponse<Vec<RecordResponse>>>, LunarbaseError> {
-    let records = state
+    Json(request): Json<ValidateRecordRequest>,
+) -> Result<Json<ApiResponse<RecordValidationResponse>>, LunarbaseError> {
+    reject_system_collection_write(&collection_name)?;
+
+    let user = claims_to_user(&claims, &state).await?;
+    let collection = state
         .collection_service
-        .list_records(
-            &collection_name,
-            query.sort,
-            query.filter,
-            query.search,
-            query.limit,
-            query.offset,
-        )
+        .get_collection(&collection_name)
+        .await?;
+
+    // The same permission as the real write, so private schemas cannot be probed
+    let permission = match &request.record_id {
+        Some(record_id) => {
+            collection
+                .id_type
+                .normalize(record_id)
+                .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
+            crate::models::Permission::Update
+        }
+        None => crate::models::Permission::Create,
+    };
+    let has_permission = state
+        .permission_service
+        .check_collection_permission(&user, collection.id, permission)
+        .await?;
+    if !has_permission {
+        state
+            .permission_audit_service
+            .record_denial(&user, &collection, request.record_id.as_deref(), permission)
+            .await;
+        return Err(LunarbaseError::RecordPermissionDenied(permission));
+    }
+
+    let errors = state
+        .collection_service
+        .validate_record(&collection_name, &request.data)
         .await?;
-    Ok(Json(ApiResponse::success(records)))
+    Ok(Json(ApiResponse::success(RecordValidationResponse {
+        valid: errors.is_empty(),
+        errors,
+    })))
 }
 
 #[utoipa::path(
     get,
-    path = "/records",
+    path = "/collections/{collection_name}/records/export",
     tag = "Records",
     params(
-        ("limit" = Option<i64>, Query, description = "Limit number of records (max 100)"),
-        ("offset" = Option<i64>, Query, description = "Offset for pagination"),
-        ("sort" = Option<String>, Query, description = "Sort field"),
-        ("filter" = Option<String>, Query, description = "Filter expression"),
-        ("search" = Option<String>, Query, description = "Search term")
+        ("collection_name" = String, Path, description = "Collection name")
     ),
     responses(
-        (status = 200, description = "Records retrieved successfully", body = ApiResponse<PaginatedRecordsResponse>),
+        (status = 200, description = "All records by ascending id with a manifest of their SHA-256 hashes", body = ApiResponse<RecordExport>),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 403, description = "Insufficient permissions", body = ErrorResponse)
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
     ),
     security(
         ("bearer_auth" = [])
     )
 )]
-pub async fn list_all_records(
+pub async fn export_records(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
-    Query(query): Query<ListRecordsQuery>,
-) -> Result<Json<ApiResponse<PaginatedRecordsResponse>>, LunarbaseError> {
-    use crate::schema::users;
-    use diesel::prelude::*;
+    Path(collection_name): Path<String>,
+) -> Result<Json<ApiResponse<RecordExport>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
 
-    let user_id: i32 = claims
-        .sub
-        .parse()
-        .map_err(|_| LunarbaseError::TokenInvalid)?;
+    let export = state
+        .collection_service
+        .export_records(&collection_name)
+        .await?;
 
-    let mut conn = state
-        .db_pool
-        .get()
+    if let Ok(admin_id) = claims.sub.parse::<i32>() {
+        state
+            .notification_service
+            .try_notify(
+                admin_id,
+                NotificationContent::new(
+                    NotificationKind::ExportFinished,
+                    format!("Export of {} finished", collection_name),
+                )
+                .with_body(format!(
+                    "{} record(s) exported",
+                    export.manifest.record_count
+                ))
+                .with_data(serde_json::json!({
+                    "collection": collection_name,
+                    "record_count": export.manifest.record_count,
+                    "digest": export.manifest.digest,
+                })),
+            )
+            .await;
+    }
+
+    Ok(Json(ApiResponse::success(export)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/collections/{collection_name}/records/verify",
+    tag = "Records",
+    params(
+        ("collection_name" = String, Path, description = "Collection name")
+    ),
+    request_body = RecordManifest,
+    responses(
+        (status = 200, description = "Records changed, missing or added since the manifest was exported", body = ApiResponse<ManifestVerification>),
+        (status = 400, description = "Manifest is for another collection or uses an unsupported hash or canonicalization", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn verify_records(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(collection_name): Path<String>,
+    Json(manifest): Json<RecordManifest>,
+) -> Result<Json<ApiResponse<ManifestVerification>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let verification = state
+        .collection_service
+        .verify_record_manifest(&collection_name, &manifest)
+        .await?;
+    Ok(Json(ApiResponse::success(verification)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/collections/{collection_name}/records",
+    tag = "Records",
+    params(
+        ("collection_name" = String, Path, description = "Collection name"),
+        ("limit" = Option<i64>, Query, description = "Limit number of records"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination"),
+        ("sort" = Option<String>, Query, description = "Comma-separated sort fields, '-' prefix for descending (e.g. 'priority,-created_at'); empty values sort last"),
+        ("filter" = Option<String>, Query, description = "Filter expression"),
+        ("search" = Option<String>, Query, description = "Search term"),
+        ("count" = Option<bool>, Query, description = "Return the total number of matching records in the X-Total-Count header"),
+        ("view" = Option<String>, Query, description = "Apply a saved view; explicit filter, sort and fields parameters override it"),
+        ("fields" = Option<String>, Query, description = "Comma-separated list of data fields to return"),
+        ("expand" = Option<String>, Query, description = "Comma-separated relation fields to replace with the referenced records"),
+        ("Cache-Control" = Option<String>, Header, description = "Send `no-cache` to bypass the query cache")
+    ),
+    responses(
+        (status = 200, description = "Records retrieved successfully; a bare array for X-Api-Version 1 and records with pagination metadata from version 2. X-Query-Cache reports hit or miss when the collection caches queries", body = ApiResponse<RecordList>),
+        (status = 400, description = "Invalid query or view references removed fields", body = ErrorResponse),
+        (status = 403, description = "Anonymous callers need the guest role's list permission", body = ErrorResponse),
+        (status = 404, description = "Collection or view not found", body = ErrorResponse)
+    )
+)]
+pub async fn list_records(
+    State(state): State<AppState>,
+    claims: Option<Extension<Claims>>,
+    api_version: Option<Extension<ApiVersion>>,
+    request_headers: HeaderMap,
+    RawQuery(raw_query): RawQuery,
+    Path(collection_name): Path<String>,
+    Query(mut query): Query<ListRecordsQuery>,
+) -> Result<(HeaderMap, Json<ApiResponse<RecordList>>), LunarbaseError> {
+    let mut headers = HeaderMap::new();
+    let claims = claims.map(|Extension(claims)| claims);
+    let version = api_version.map_or(ApiVersion::V1, |Extension(version)| version);
+    let ignored_parameters = unsupported_parameters(raw_query.as_deref(), &LIST_RECORDS_PARAMETERS);
+    let query_slot = acquire_query_slot(&state, claims.as_ref()).await?;
+    query_slot.insert_warning(&mut headers);
+
+    if collection_name == USERS_SYSTEM_COLLECTION {
+        let (mut headers, body) =
+            list_user_records(&state, claims.as_ref(), query, version, ignored_parameters).await?;
+        query_slot.insert_warning(&mut headers);
+        return Ok((headers, body));
+    }
+
+    let mut fields = parse_field_list(query.fields.as_deref());
+    let expand = parse_field_list(query.expand.as_deref());
+
+    if let Some(view_name) = query.view.as_deref() {
+        let user = match &claims {
+            Some(claims) => Some(claims_to_user(claims, &state).await?),
+            None => None,
+        };
+
+        let view = state
+            .collection_view_service
+            .resolve_view(&collection_name, view_name, user.as_ref())
+            .await?;
+
+        query.filter = query.filter.or(view.filter.clone());
+        query.sort = query.sort.or(view.sort.clone());
+        if fields.is_empty() {
+            fields = view.get_fields();
+        }
+    }
+
+    let collection = state
+        .collection_service
+        .get_collection(&collection_name)
+        .await?;
+
+    if !fields.is_empty() {
+        let unknown: Vec<&str> = fields
+            .iter()
+            .map(String::as_str)
+            .filter(|field| !collection.schema.fields.iter().any(|f| &f.name == field))
+            .filter(|field| !matches!(*field, "id" | "created_at" | "updated_at"))
+            .filter(|field| {
+                collection.workflow != CollectionWorkflow::DraftPublish
+                    || !matches!(*field, "status" | "publish_at")
+            })
+            .collect();
+        if !unknown.is_empty() {
+            return Err(LunarbaseError::ValidationError(vec![format!(
+                "Unknown fields: {}",
+                unknown.join(", ")
+            )]));
+        }
+    }
+
+    enforce_query_budget(
+        &state,
+        &collection.schema,
+        query.filter.as_deref(),
+        query.search.as_deref(),
+        expand.len(),
+    )
+    .await?;
+    let status_filter = workflow_status_filter(
+        &state,
+        claims.as_ref(),
+        &collection,
+        query.status.as_deref(),
+    )
+    .await?;
+    let owner_filter = owner_scope_filter(&state, claims.as_ref(), &collection).await?;
+    query.filter = and_filter(and_filter(query.filter, status_filter), owner_filter);
+
+    let applied_query =
+        QueryEngine::new(query.sort.clone(), query.filter.clone(), None, None, None)?
+            .with_manual_order(collection.orderable);
+    let (limit, offset) = (query.limit, query.offset);
+    let respond = |records: Vec<RecordResponse>, total_count: Option<i64>| match version {
+        ApiVersion::V1 => RecordList::Records(records),
+        ApiVersion::V2 => RecordList::Page(RecordPage {
+            records,
+            pagination: PaginationMeta {
+                applied_filter: applied_query.canonical_filter(),
+                applied_sort: Some(applied_query.canonical_sort()),
+                view: query.view.clone(),
+                ignored_parameters: ignored_parameters.clone(),
+                ..page_meta(total_count.unwrap_or_default(), limit, offset)
+            },
+        }),
+    };
+
+    let include_users = !expand.is_empty()
+        && ensure_users_collection_readable(&state, claims.as_ref())
+            .await
+            .is_ok();
+    // Version 2 pages always report the total
+    let count = query.count.unwrap_or(false) || version >= ApiVersion::V2;
+
+    let cache_ttl = state.collection_service.query_cache_ttl(&collection).await;
+    let cache_key = cache_ttl.map(|_| {
+        state.collection_service.query_cache_key(
+            "list",
+            &[
+                ("sort", query.sort.clone()),
+                ("filter", query.filter.clone()),
+                ("search", query.search.clone()),
+                ("limit", query.limit.map(|limit| limit.to_string())),
+                ("offset", query.offset.map(|offset| offset.to_string())),
+                ("count", Some(count.to_string())),
+                ("fields", Some(fields.join(","))),
+                ("expand", Some(expand.join(","))),
+                ("include_users", Some(include_users.to_string())),
+                // Which related records are expanded depends on the caller
+                (
+                    "reader",
+                    claims
+                        .as_ref()
+                        .filter(|_| !expand.is_empty())
+                        .map(|claims| claims.sub.clone()),
+                ),
+            ],
+            &permission_fingerprint(claims.as_ref()),
+        )
+    });
+
+    if let (Some(ttl), Some(key)) = (cache_ttl, cache_key.as_deref()) {
+        let cached = if requests_fresh_read(&request_headers) {
+            None
+        } else {
+            state
+                .collection_service
+                .query_cache
+                .get(&collection_name, key, ttl)
+        };
+
+        if let Some(CachedQueryResult::Records {
+            records,
+            total_count,
+        }) = cached
+        {
+            insert_total_count(&mut headers, total_count);
+            headers.insert(QUERY_CACHE_HEADER, HeaderValue::from_static("hit"));
+            return Ok((
+                headers,
+                Json(ApiResponse::success(respond(records, total_count))),
+            ));
+        }
+        headers.insert(QUERY_CACHE_HEADER, HeaderValue::from_static("miss"));
+    }
+
+    let total_count = if count {
+        Some(
+            state
+                .collection_service
+                .count_records(&collection_name, query.filter.clone(), query.search.clone())
+                .await?,
+        )
+    } else {
+        None
+    };
+    insert_total_count(&mut headers, total_count);
+
+    let mut records = state
+        .collection_service
+        .list_records(
+            &collection_name,
+            query.sort,
+            query.filter,
+            query.search,
+            query.limit,
+            query.offset,
+        )
+        .await?;
+
+    if !expand.is_empty() {
+        let reader = match &claims {
+            Some(claims) => Some(claims_to_user(claims, &state).await?),
+            None => None,
+        };
+        state
+            .collection_service
+            .expand_relations(
+                &collection_name,
+                &mut records,
+                &expand,
+                include_users,
+                reader.as_ref(),
+            )
+            .await?;
+    }
+
+    if !fields.is_empty() {
+        for record in records.iter_mut() {
+            if let Some(data) = record.data.as_object_mut() {
+                data.retain(|key, _| fields.contains(key));
+            }
+        }
+    }
+
+    if let Some(key) = cache_key {
+        state
+            .collection_service
+            .cache_query_result(
+                &collection_name,
+                key,
+                CachedQueryResult::Records {
+                    records: records.clone(),
+                    total_count,
+                },
+            )
+            .await;
+    }
+
+    Ok((
+        headers,
+        Json(ApiResponse::success(respond(records, total_count))),
+    ))
+}
+
+/// Pagination of a `total_count` long result read with `limit` and `offset`;
+/// without a limit everything is on one page.
+fn page_meta(total_count: i64, limit: Option<i64>, offset: Option<i64>) -> PaginationMeta {
+    let offset = offset.unwrap_or(0).max(0);
+    let page_size = limit
+        .filter(|limit| *limit > 0)
+        .unwrap_or(total_count.max(1));
+    PaginationMeta {
+        current_page: (offset / page_size) + 1,
+        page_size,
+        total_count,
+        total_pages: (total_count + page_size - 1) / page_size,
+        applied_filter: None,
+        applied_sort: None,
+        applied_limit: limit,
+        applied_offset: Some(offset),
+        view: None,
+        ignored_parameters: Vec::new(),
+    }
+}
+
+/// One of the caller's concurrent record query slots, held until dropped.
+#[derive(Default)]
+struct QuerySlot {
+    _permit: Option<OwnedSemaphorePermit>,
+    /// Set while the caller's queries are past the quota warning threshold
+    warning: Option<QuotaWarning>,
+}
+
+impl QuerySlot {
+    fn insert_warning(&self, headers: &mut HeaderMap) {
+        if let Some(warning) = &self.warning
+            && let Ok(value) = HeaderValue::from_str(&warning.header_value())
+        {
+            headers.insert(QUOTA_WARNING_HEADER, value);
+        }
+    }
+}
+
+/// Takes one of the caller's concurrent record query slots. Anonymous
+/// requests are not limited.
+async fn acquire_query_slot(
+    state: &AppState,
+    claims: Option<&Claims>,
+) -> Result<QuerySlot, LunarbaseError> {
+    let Some(claims) = claims else {
+        return Ok(QuerySlot::default());
+    };
+
+    let max_in_flight = state.get_max_concurrent_queries_per_user().await.max(1) as usize;
+    match state.query_limiter.try_acquire(&claims.sub, max_in_flight) {
+        Some(permit) => {
+            let warning = state
+                .quota_warning_service
+                .observe(
+                    QuotaKind::ConcurrentQueries,
+                    &claims.sub,
+                    state.query_limiter.in_flight(&claims.sub),
+                    max_in_flight,
+                )
+                .await;
+            Ok(QuerySlot {
+                _permit: Some(permit),
+                warning,
+            })
+        }
+        None => {
+            let _ = state
+                .metrics_state
+                .increment_custom_metric(
+                    "query_concurrency_rejections_total",
+                    "Record queries rejected because the user had too many in flight",
+                )
+                .await;
+            Err(LunarbaseError::RateLimitExceeded)
+        }
+    }
+}
+
+/// The status condition a read of a draft/publish collection must add to its
+/// filter. Callers who may update the collection see every record, or the
+/// `?status=` they asked for; everyone else only sees published records.
+async fn workflow_status_filter(
+    state: &AppState,
+    claims: Option<&Claims>,
+    collection: &CollectionResponse,
+    requested: Option<&str>,
+) -> Result<Option<String>, LunarbaseError> {
+    let requested = match requested {
+        Some(status) => Some(RecordStatus::parse(status).ok_or_else(|| {
+            LunarbaseError::ValidationError(vec![format!(
+                "Invalid status '{}'; expected draft, published or archived",
+                status
+            )])
+        })?),
+        None => None,
+    };
+
+    if collection.workflow != CollectionWorkflow::DraftPublish {
+        return match requested {
+            Some(_) => Err(LunarbaseError::ValidationError(vec![format!(
+                "Collection '{}' has no draft/publish workflow",
+                collection.name
+            )])),
+            None => Ok(None),
+        };
+    }
+
+    if can_see_unpublished(state, claims, collection).await? {
+        return Ok(requested.map(|status| format!("status:eq:{}", status.as_str())));
+    }
+
+    match requested {
+        Some(status) if status != RecordStatus::Published => Err(
+            LunarbaseError::RecordPermissionDenied(crate::models::Permission::Update),
+        ),
+        _ => Ok(Some(format!(
+            "status:eq:{}",
+            RecordStatus::Published.as_str()
+        ))),
+    }
+}
+
+/// The filter that limits users without list permission on the collection to
+/// the records they own. Anonymous callers list through the guest role and own
+/// nothing, so without its list permission they are rejected. Listing and
+/// counting both apply it, so their totals agree for the same caller and filter.
+async fn owner_scope_filter(
+    state: &AppState,
+    claims: Option<&Claims>,
+    collection: &CollectionResponse,
+) -> Result<Option<String>, LunarbaseError> {
+    let Some(claims) = claims else {
+        let can_list = state
+            .permission_service
+            .get_effective_role_collection_permission("guest", collection.id)
+            .await
+            .ok()
+            .flatten()
+            .is_some_and(|permissions| permissions.permission.can_list);
+        return if can_list {
+            Ok(None)
+        } else {
+            Err(LunarbaseError::RecordPermissionDenied(
+                crate::models::Permission::List,
+            ))
+        };
+    };
+    let user = claims_to_user(claims, state).await?;
+    let can_list = state
+        .permission_service
+        .check_collection_permission(&user, collection.id, crate::models::Permission::List)
+        .await?;
+
+    Ok((!can_list).then(|| format!("owner_id:eq:{}", user.id)))
+}
+
+/// Rejects reading a record the caller may not read, decided on the owner
+/// probe alone so a denied caller never loads the record body. Anonymous
+/// callers read through the guest role.
+async fn ensure_record_readable(
+    state: &AppState,
+    claims: Option<&Claims>,
+    collection: &CollectionResponse,
+    record_id: &str,
+) -> Result<(), LunarbaseError> {
+    let denied = Err(LunarbaseError::RecordPermissionDenied(
+        crate::models::Permission::Read,
+    ));
+    let Some(claims) = claims else {
+        let can_read = state
+            .permission_service
+            .get_effective_role_collection_permission("guest", collection.id)
+            .await
+            .ok()
+            .flatten()
+            .is_some_and(|permissions| permissions.permission.can_read);
+        return if can_read { Ok(()) } else { denied };
+    };
+
+    let user = claims_to_user(claims, state).await?;
+    if user.role == "admin" {
+        return Ok(());
+    }
+    let owner_id = state
+        .collection_service
+        .get_record_owner_id(&collection.name, record_id)
+        .await?;
+    let can_read = state
+        .permission_service
+        .check_record_permission_with_owner_id(
+            &user,
+            collection.id,
+            record_id,
+            crate::models::Permission::Read,
+            owner_id,
+        )
+        .await?;
+    if can_read {
+        return Ok(());
+    }
+
+    state
+        .permission_audit_service
+        .record_denial(
+            &user,
+            collection,
+            Some(record_id),
+            crate::models::Permission::Read,
+        )
+        .await;
+    denied
+}
+
+/// Drafts and archived records are only visible to callers who may update them.
+async fn can_see_unpublished(
+    state: &AppState,
+    claims: Option<&Claims>,
+    collection: &CollectionResponse,
+) -> Result<bool, LunarbaseError> {
+    let Some(claims) = claims else {
+        return Ok(false);
+    };
+    let user = claims_to_user(claims, state).await?;
+    state
+        .permission_service
+        .check_collection_permission(&user, collection.id, crate::models::Permission::Update)
+        .await
+}
+
+/// Hides a record of a draft/publish collection that is not published from
+/// callers who may not see unpublished records.
+async fn ensure_record_visible(
+    state: &AppState,
+    claims: Option<&Claims>,
+    collection: &CollectionResponse,
+    record: &RecordResponse,
+) -> Result<(), LunarbaseError> {
+    if collection.workflow != CollectionWorkflow::DraftPublish {
+        return Ok(());
+    }
+
+    let published = record
+        .data
+        .get("status")
+        .and_then(serde_json::Value::as_str)
+        == Some(RecordStatus::Published.as_str());
+    if published || can_see_unpublished(state, claims, collection).await? {
+        return Ok(());
+    }
+
+    Err(LunarbaseError::NotFound("Record not found".to_string()))
+}
+
+fn and_filter(filter: Option<String>, condition: Option<String>) -> Option<String> {
+    match (filter, condition) {
+        (Some(filter), Some(condition)) if !filter.trim().is_empty() => {
+            Some(format!("{},{}", filter, condition))
+        }
+        (filter, None) => filter,
+        (_, condition) => condition,
+    }
+}
+
+/// Rejects filters, searches and relation expansions whose combined
+/// complexity is over the `max_query_complexity` setting.
+/// Names of the parameters in `raw_query` missing from `supported`, sorted
+/// and without duplicates.
+fn unsupported_parameters(raw_query: Option<&str>, supported: &[&str]) -> Vec<String> {
+    let mut ignored: Vec<String> = raw_query
+        .unwrap_or_default()
+        .split('&')
+        .filter_map(|pair| {
+            let name = pair.split('=').next().unwrap_or_default();
+            urlencoding::decode(name).ok().map(|name| name.into_owned())
+        })
+        .filter(|name| !name.is_empty() && !supported.contains(&name.as_str()))
+        .collect();
+    ignored.sort();
+    ignored.dedup();
+    ignored
+}
+
+async fn enforce_query_budget(
+    state: &AppState,
+    schema: &CollectionSchema,
+    filter: Option<&str>,
+    search: Option<&str>,
+    expansions: usize,
+) -> Result<(), LunarbaseError> {
+    let complexity = QueryEngine::new(
+        None,
+        filter.map(str::to_string),
+        search.map(str::to_string),
+        None,
+        None,
+    )?
+    .complexity(schema, expansions);
+    let budget = state.get_max_query_complexity().await;
+
+    if complexity <= budget {
+        return Ok(());
+    }
+
+    let _ = state
+        .metrics_state
+        .increment_custom_metric(
+            "query_complexity_rejections_total",
+            "Record queries rejected for exceeding the query complexity budget",
+        )
+        .await;
+
+    Err(LunarbaseError::QueryTooComplex { complexity, budget })
+}
+
+/// Reports whether a list or count response came from the query cache (`hit`)
+/// or the database (`miss`). Absent when the collection has no query cache TTL.
+const QUERY_CACHE_HEADER: &str = "x-query-cache";
+
+/// Query cache results are shared between callers with the same role; relation
+/// expansion and ownership scoping are keyed separately by the callers.
+fn permission_fingerprint(claims: Option<&Claims>) -> String {
+    claims
+        .map(|claims| format!("role:{}", claims.role))
+        .unwrap_or_else(|| "anonymous".to_string())
+}
+
+fn insert_total_count(headers: &mut HeaderMap, total_count: Option<i64>) {
+    if let Some(total_count) = total_count
+        && let Ok(value) = HeaderValue::from_str(&total_count.to_string())
+    {
+        headers.insert("x-total-count", value);
+    }
+}
+
+/// Serves `list_records` for the `_users` system collection, which only
+/// supports search, sorting by id, username or created_at, and pagination.
+async fn list_user_records(
+    state: &AppState,
+    claims: Option<&Claims>,
+    query: ListRecordsQuery,
+    version: ApiVersion,
+    ignored_parameters: Vec<String>,
+) -> Result<(HeaderMap, Json<ApiResponse<RecordList>>), LunarbaseError> {
+    ensure_users_collection_readable(state, claims).await?;
+
+    if query.filter.is_some() || query.view.is_some() || query.expand.is_some() {
+        return Err(LunarbaseError::ValidationError(vec![format!(
+            "{} does not support filter, view or expand parameters",
+            USERS_SYSTEM_COLLECTION
+        )]));
+    }
+
+    let mut headers = HeaderMap::new();
+    let total_count = if query.count.unwrap_or(false) || version >= ApiVersion::V2 {
+        Some(
+            state
+                .collection_service
+                .count_user_records(query.search.clone())
+                .await?,
+        )
+    } else {
+        None
+    };
+    insert_total_count(&mut headers, total_count);
+
+    let (limit, offset) = (query.limit, query.offset);
+    let applied_sort = query.sort.clone();
+    let mut records = state
+        .collection_service
+        .list_user_records(query.sort, query.search, query.limit, query.offset)
+        .await?;
+
+    let fields = parse_field_list(query.fields.as_deref());
+    if !fields.is_empty() {
+        for record in records.iter_mut() {
+            if let Some(data) = record.data.as_object_mut() {
+                data.retain(|key, _| fields.contains(key));
+            }
+        }
+    }
+
+    let records = match version {
+        ApiVersion::V1 => RecordList::Records(records),
+        ApiVersion::V2 => RecordList::Page(RecordPage {
+            records,
+            pagination: PaginationMeta {
+                applied_sort,
+                ignored_parameters,
+                ..page_meta(total_count.unwrap_or_default(), limit, offset)
+            },
+        }),
+    };
+    Ok((headers, Json(ApiResponse::success(records))))
+}
+
+fn parse_field_list(fields: Option<&str>) -> Vec<String> {
+    fields
+        .map(|fields| {
+            fields
+                .split(',')
+                .map(str::trim)
+                .filter(|field| !field.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[utoipa::path(
+    get,
+    path = "/collections/{collection_name}/records/count",
+    tag = "Records",
+    params(
+        ("collection_name" = String, Path, description = "Collection name"),
+        ("filter" = Option<String>, Query, description = "Filter expression"),
+        ("search" = Option<String>, Query, description = "Search term"),
+        ("Cache-Control" = Option<String>, Header, description = "Send `no-cache` to bypass the query cache")
+    ),
+    responses(
+        (status = 200, description = "Record count retrieved successfully; X-Query-Cache reports hit or miss when the collection caches queries", body = ApiResponse<RecordCountResponse>),
+        (status = 400, description = "Invalid filter", body = ErrorResponse),
+        (status = 403, description = "Anonymous callers need the guest role's list permission", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
+    )
+)]
+pub async fn count_records(
+    State(state): State<AppState>,
+    claims: Option<Extension<Claims>>,
+    request_headers: HeaderMap,
+    Path(collection_name): Path<String>,
+    Query(mut query): Query<CountRecordsQuery>,
+) -> Result<(HeaderMap, Json<ApiResponse<RecordCountResponse>>), LunarbaseError> {
+    let claims = claims.map(|Extension(claims)| claims);
+    let query_slot = acquire_query_slot(&state, claims.as_ref()).await?;
+    let collection = state
+        .collection_service
+        .get_collection(&collection_name)
+        .await?;
+    enforce_query_budget(
+        &state,
+        &collection.schema,
+        query.filter.as_deref(),
+        query.search.as_deref(),
+        0,
+    )
+    .await?;
+    let status_filter = workflow_status_filter(
+        &state,
+        claims.as_ref(),
+        &collection,
+        query.status.as_deref(),
+    )
+    .await?;
+    // Users without list access still get the number of records they own.
+    let owner_filter = owner_scope_filter(&state, claims.as_ref(), &collection).await?;
+    query.filter = and_filter(and_filter(query.filter, status_filter), owner_filter);
+
+    let mut headers = HeaderMap::new();
+    query_slot.insert_warning(&mut headers);
+    let cache_ttl = state.collection_service.query_cache_ttl(&collection).await;
+    let cache_key = cache_ttl.map(|_| {
+        state.collection_service.query_cache_key(
+            "count",
+            &[
+                ("filter", query.filter.clone()),
+                ("search", query.search.clone()),
+            ],
+            &permission_fingerprint(claims.as_ref()),
+        )
+    });
+
+    if let (Some(ttl), Some(key)) = (cache_ttl, cache_key.as_deref()) {
+        let cached = if requests_fresh_read(&request_headers) {
+            None
+        } else {
+            state
+                .collection_service
+                .query_cache
+                .get(&collection_name, key, ttl)
+        };
+
+        if let Some(CachedQueryResult::Count(count)) = cached {
+            headers.insert(QUERY_CACHE_HEADER, HeaderValue::from_static("hit"));
+            return Ok((
+                headers,
+                Json(ApiResponse::success(RecordCountResponse { count })),
+            ));
+        }
+        headers.insert(QUERY_CACHE_HEADER, HeaderValue::from_static("miss"));
+    }
+
+    let count = state
+        .collection_service
+        .count_records(&collection_name, query.filter, query.search)
+        .await?;
+
+    if let Some(key) = cache_key {
+        state
+            .collection_service
+            .cache_query_result(&collection_name, key, CachedQueryResult::Count(count))
+            .await;
+    }
+
+    Ok((
+        headers,
+        Json(ApiResponse::success(RecordCountResponse { count })),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/records",
+    tag = "Records",
+    params(
+        ("limit" = Option<i64>, Query, description = "Limit number of records (max 100)"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination"),
+        ("sort" = Option<String>, Query, description = "created_at, updated_at, id or collection_name, '-' prefix for descending (default -created_at)"),
+        ("filter" = Option<String>, Query, description = "Filter expression"),
+        ("search" = Option<String>, Query, description = "Search term")
+    ),
+    responses(
+        (status = 200, description = "Records retrieved successfully", body = ApiResponse<PaginatedRecordsResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_all_records(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    RawQuery(raw_query): RawQuery,
+    Query(query): Query<ListRecordsQuery>,
+) -> Result<Json<ApiResponse<PaginatedRecordsResponse>>, LunarbaseError> {
+    use crate::schema::users;
+    use diesel::prelude::*;
+
+    let user_id: i32 = claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| LunarbaseError::InternalError)?;
+
+    let user = users::table
+        .filter(users::id.eq(user_id))
+        .select(crate::models::User::as_select())
+        .first::<crate::models::User>(&mut conn)
+        .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
+    let user = claims.scope_user(user);
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
+    let applied_filter =
+        QueryEngine::new(None, query.filter.clone(), None, None, None)?.canonical_filter();
+
+    let _query_slot = acquire_query_slot(&state, Some(&claims)).await?;
+    let collections = workspace_collections(&state, &claims).await?;
+
+    let mut all_records = Vec::new();
+
+    for collection in collections {
+        let has_permission = state
+            .permission_service
+            .check_collection_permission(&user, collection.id, crate::models::Permission::List)
+            .await
+            .unwrap_or(false);
+
+        if has_permission {
+            enforce_query_budget(
+                &state,
+                &collection.schema,
+                query.filter.as_deref(),
+                query.search.as_deref(),
+                0,
+            )
+            .await?;
+            let status_filter =
+                workflow_status_filter(&state, Some(&claims), &collection, None).await?;
+
+            let records = state
+                .collection_service
+                .list_records(
+                    &collection.name,
+                    None,
+                    and_filter(query.filter.clone(), status_filter),
+                    query.search.clone(),
+                    None,
+                    None,
+                )
+                .await
+                .unwrap_or_default();
+
+            for record in records {
+                all_records.push(RecordWithCollection {
+                    record,
+                    collection_name: collection.name.clone(),
+                });
+            }
+        }
+    }
+
+    let total_count = all_records.len() as i64;
+
+    // Anything but these fields falls back to the newest records first
+    let sort = query
+        .sort
+        .as_deref()
+        .map(|sort| match sort.strip_prefix('-') {
+            Some(field) => (field, true),
+            None => (sort, false),
+        });
+    let (sort_field, descending) = match sort {
+        Some((field @ ("created_at" | "updated_at" | "id" | "collection_name"), descending)) => {
+            (field, descending)
+        }
+        _ => ("created_at", true),
+    };
+    all_records.sort_by(|a, b| {
+        let ordering = match sort_field {
+            "updated_at" => a.record.updated_at.cmp(&b.record.updated_at),
+            "id" => a.record.id.cmp(&b.record.id),
+            "collection_name" => a.collection_name.cmp(&b.collection_name),
+            _ => a.record.created_at.cmp(&b.record.created_at),
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+
+    let start_index = offset as usize;
+    let end_index = (start_index + limit as usize).min(all_records.len());
+    let paginated_records = if start_index < all_records.len() {
+        all_records[start_index..end_index].to_vec()
+    } else {
+        Vec::new()
+    };
+
+    let current_page = (offset / limit) + 1;
+    let total_pages = (total_count + limit - 1) / limit;
+
+    let pagination_meta = PaginationMeta {
+        current_page,
+        page_size: limit,
+        total_count,
+        total_pages,
+        applied_filter,
+        applied_sort: Some(format!(
+            "{}{}",
+            if descending { "-" } else { "" },
+            sort_field
+        )),
+        applied_limit: Some(limit),
+        applied_offset: Some(offset),
+        view: None,
+        ignored_parameters: unsupported_parameters(
+            raw_query.as_deref(),
+            &["limit", "offset", "sort", "filter", "search"],
+        ),
+    };
+
+    let response = PaginatedRecordsResponse {
+        records: paginated_records,
+        pagination: pagination_meta,
+    };
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/search",
+    tag = "Records",
+    params(
+        ("q" = String, Query, description = "Search term"),
+        ("collections" = Option<String>, Query, description = "Comma-separated collection names to search"),
+        ("limit" = Option<i64>, Query, description = "Matches per collection (max 20)")
+    ),
+    responses(
+        (status = 200, description = "Matches grouped by collection", body = ApiResponse<Vec<GlobalSearchGroup>>),
+        (status = 400, description = "Empty search term", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn global_search(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<GlobalSearchQuery>,
+) -> Result<Json<ApiResponse<Vec<GlobalSearchGroup>>>, LunarbaseError> {
+    let term = query.q.trim().to_string();
+    if term.is_empty() {
+        return Err(LunarbaseError::BadRequest(
+            "Search term must not be empty".to_string(),
+        ));
+    }
+
+    let user = claims_to_user(&claims, &state).await?;
+    let limit = query.limit.unwrap_or(5).clamp(1, 20);
+    let timeout = std::time::Duration::from_millis(
+        state.get_search_collection_timeout_ms().await.max(1) as u64,
+    );
+    let requested = parse_field_list(query.collections.as_deref());
+
+    let mut collections = Vec::new();
+    let mut status_filters = Vec::new();
+    for collection in workspace_collections(&state, &claims).await? {
+        if !requested.is_empty() && !requested.contains(&collection.name) {
+            continue;
+        }
+        let can_list = state
+            .permission_service
+            .check_collection_permission(&user, collection.id, crate::models::Permission::List)
+            .await
+            .unwrap_or(false);
+        if can_list {
+            status_filters
+                .push(workflow_status_filter(&state, Some(&claims), &collection, None).await?);
+            collections.push(collection);
+        }
+    }
+
+    let searches = collections
+        .iter()
+        .zip(status_filters)
+        .map(|(collection, status_filter)| {
+            tokio::time::timeout(
+                timeout,
+                state.collection_service.list_records(
+                    &collection.name,
+                    None,
+                    status_filter,
+                    Some(term.clone()),
+                    Some(limit),
+                    None,
+                ),
+            )
+        });
+    let outcomes = futures_util::future::join_all(searches).await;
+
+    let groups = collections
+        .iter()
+        .zip(outcomes)
+        .filter_map(|(collection, outcome)| {
+            let records = outcome.ok()?.ok()?;
+            if records.is_empty() {
+                return None;
+            }
+
+            let results = records
+                .iter()
+                .map(|record| {
+                    let (field, snippet) = collection
+                        .schema
+                        .fields
+                        .iter()
+                        .find_map(|field| {
+                            let text = record.data.get(&field.name)?.as_str()?;
+                            let snippet = search_snippet(text, &term)?;
+                            Some((field.name.clone(), snippet))
+                        })
+                        .unzip();
+                    GlobalSearchHit {
+                        record_id: record.id.clone(),
+                        field,
+                        snippet,
+                    }
+                })
+                .collect();
+
+            Some(GlobalSearchGroup {
+                collection_name: collection.name.clone(),
+                results,
+            })
+        })
+        .collect();
+
+    Ok(Json(ApiResponse::success(groups)))
+}
+
+/// Characters of context kept on each side of a search match.
+const SNIPPET_CONTEXT_CHARS: usize = 40;
+
+/// Excerpt of `text` around the first case-insensitive occurrence of `term`,
+/// with ellipses where the text was cut.
+fn search_snippet(text: &str, term: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let lowered: Vec<char> = chars.iter().map(|c| c.to_ascii_lowercase()).collect();
+    let needle: Vec<char> = term.chars().map(|c| c.to_ascii_lowercase()).collect();
+
+    let position = lowered
+        .windows(needle.len())
+        .position(|window| window == needle.as_slice())?;
+
+    let start = position.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (position + needle.len() + SNIPPET_CONTEXT_CHARS).min(chars.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    snippet.extend(&chars[start..end]);
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+#[utoipa::path(
+    get,
+    path = "/collections/{collection_name}/records/{record_id}",
+    tag = "Records",
+    params(
+        ("collection_name" = String, Path, description = "Collection name"),
+        ("record_id" = String, Path, description = "Record ID"),
+        ("expand" = Option<String>, Query, description = "Comma-separated relation fields to replace with the referenced records"),
+        ("Cache-Control" = Option<String>, Header, description = "Send `no-cache` to bypass the record cache")
+    ),
+    responses(
+        (status = 200, description = "Record retrieved successfully", body = ApiResponse<RecordResponse>),
+        (status = 400, description = "Invalid record id", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Record not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_record(
+    State(state): State<AppState>,
+    claims: Option<Extension<Claims>>,
+    headers: HeaderMap,
+    Path((collection_name, record_id)): Path<(String, String)>,
+    Query(query): Query<GetRecordQuery>,
+) -> Result<Json<ApiResponse<RecordResponse>>, LunarbaseError> {
+    let claims = claims.map(|Extension(claims)| claims);
+
+    if collection_name == USERS_SYSTEM_COLLECTION {
+        ensure_users_collection_readable(&state, claims.as_ref()).await?;
+        let user_id = record_id
+            .parse()
+            .map_err(|_| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
+        let record = state.collection_service.get_user_record(user_id).await?;
+        return Ok(Json(ApiResponse::success(record)));
+    }
+
+    let record_id = state
+        .collection_service
+        .resolve_record_id(&collection_name, &record_id)
+        .await?;
+
+    let collection = state
+        .collection_service
+        .get_collection(&collection_name)
+        .await?;
+    ensure_record_readable(&state, claims.as_ref(), &collection, &record_id).await?;
+
+    let record = state
+        .collection_service
+        .get_record_cached(&collection_name, &record_id, requests_fresh_read(&headers))
+        .await?;
+    ensure_record_visible(&state, claims.as_ref(), &collection, &record).await?;
+
+    let expand = parse_field_list(query.expand.as_deref());
+    if expand.is_empty() {
+        return Ok(Json(ApiResponse::success(record)));
+    }
+
+    let include_users = ensure_users_collection_readable(&state, claims.as_ref())
+        .await
+        .is_ok();
+    let reader = match &claims {
+        Some(claims) => Some(claims_to_user(claims, &state).await?),
+        None => None,
+    };
+    let mut records = [record];
+    state
+        .collection_service
+        .expand_relations(
+            &collection_name,
+            &mut records,
+            &expand,
+            include_users,
+            reader.as_ref(),
+        )
+        .await?;
+    let [record] = records;
+    Ok(Json(ApiResponse::success(record)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/collections/{collection_name}/records/by/{field}/{value}",
+    tag = "Records",
+    params(
+        ("collection_name" = String, Path, description = "Collection name"),
+        ("field" = String, Path, description = "Slug field to match"),
+        ("value" = String, Path, description = "Slug value")
+    ),
+    responses(
+        (status = 200, description = "Record retrieved successfully", body = ApiResponse<RecordResponse>),
+        (status = 400, description = "Field is not a slug field", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Record not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_record_by_field(
+    State(state): State<AppState>,
+    claims: Option<Extension<Claims>>,
+    Path((collection_name, field, value)): Path<(String, String, String)>,
+) -> Result<Json<ApiResponse<RecordResponse>>, LunarbaseError> {
+    let claims = claims.map(|Extension(claims)| claims);
+    let record = state
+        .collection_service
+        .get_record_by_field(&collection_name, &field, &value)
+        .await?;
+    let collection = state
+        .collection_service
+        .get_collection(&collection_name)
+        .await?;
+    ensure_record_readable(&state, claims.as_ref(), &collection, &record.id).await?;
+    ensure_record_visible(&state, claims.as_ref(), &collection, &record).await?;
+    Ok(Json(ApiResponse::success(record)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/collections/{collection_name}/records/{record_id}/references",
+    tag = "Records",
+    params(
+        ("collection_name" = String, Path, description = "Collection name"),
+        ("record_id" = String, Path, description = "Record ID"),
+        ("limit" = Option<i64>, Query, description = "Referencing record ids listed per collection (1-100, default 10)")
+    ),
+    responses(
+        (status = 200, description = "Records of collections the caller can read whose relation fields point at this record", body = ApiResponse<RecordReferences>),
+        (status = 400, description = "Invalid record id", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Record not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_record_references(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((collection_name, record_id)): Path<(String, String)>,
+    Query(query): Query<RecordReferencesQuery>,
+) -> Result<Json<ApiResponse<RecordReferences>>, LunarbaseError> {
+    let user = claims_to_user(&claims, &state).await?;
+    let limit = query.limit.unwrap_or(10).clamp(1, 100);
+
+    let record_id = if collection_name == USERS_SYSTEM_COLLECTION {
+        ensure_users_collection_readable(&state, Some(&claims)).await?;
+        record_id
+    } else {
+        let record_id = state
+            .collection_service
+            .resolve_record_id(&collection_name, &record_id)
+            .await?;
+        let collection = state
+            .collection_service
+            .get_collection(&collection_name)
+            .await?;
+        ensure_record_readable(&state, Some(&claims), &collection, &record_id).await?;
+        record_id
+    };
+
+    let mut referenced_by = Vec::new();
+    for references in state
+        .collection_service
+        .find_record_references(&collection_name, &record_id, limit)
+        .await?
+    {
+        if claims.workspace_id.is_some_and(|workspace_id| {
+            state
+                .collection_service
+                .workspace_of(&references.collection_name)
+                != Some(workspace_id)
+        }) {
+            continue;
+        }
+        if user.role != "admin" {
+            let collection = state
+                .collection_service
+                .get_collection(&references.collection_name)
+                .await?;
+            let can_read = state
+                .permission_service
+                .check_collection_permission(&user, collection.id, crate::models::Permission::Read)
+                .await?;
+            if !can_read {
+                continue;
+            }
+        }
+        referenced_by.push(references);
+    }
+
+    Ok(Json(ApiResponse::success(RecordReferences {
+        collection_name,
+        record_id,
+        total_count: referenced_by
+            .iter()
+            .map(|references| references.count)
+            .sum(),
+        referenced_by,
+    })))
+}
+
+/// `Cache-Control: no-cache` (or `no-store`) skips the record and query caches for this read.
+fn requests_fresh_read(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(axum::http::header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|directive| {
+            let directive = directive.trim();
+            directive.eq_ignore_ascii_case("no-cache") || directive.eq_ignore_ascii_case("no-store")
+        })
+}
+
+#[utoipa::path(
+    put,
+    path = "/collections/{collection_name}/records/{record_id}",
+    tag = "Records",
+    params(
+        ("collection_name" = String, Path, description = "Collection name"),
+        ("record_id" = String, Path, description = "Record ID"),
+        ("regenerate_slug" = Option<bool>, Query, description = "Regenerate slug fields from their source fields")
+    ),
+    request_body(
+        content_type = "multipart/form-data",
+        description = "Record data as JSON in a `data` part and files as `file_<field>` parts"
+    ),
+    responses(
+        (status = 200, description = "Record updated successfully", body = ApiResponse<RecordResponse>),
+        (status = 400, description = "Validation error; `fields` is keyed by record field, or by form part for a malformed `data` or unknown part", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Record not found", body = ErrorResponse),
+        (status = 405, description = "Collection is read-only", body = ErrorResponse),
+        (status = 413, description = "A file or the data part is over its limits.* setting, or the files would exceed the role's daily upload quota", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_record(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((collection_name, record_id)): Path<(String, String)>,
+    Query(query): Query<UpdateRecordQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<ApiResponse<RecordResponse>>, LunarbaseError> {
+    reject_system_collection_write(&collection_name)?;
+
+    let multipart = MultipartRecord::read(&state, &mut multipart).await?;
+
+    use crate::schema::users;
+    use diesel::prelude::*;
+
+    let user_id: i32 = claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    let mut conn = state
+        .db_pool
+        .get()
         .map_err(|_| LunarbaseError::InternalError)?;
 
     let user = users::table
@@ -428,213 +2410,267 @@ pub async fn list_all_records(
         .select(crate::models::User::as_select())
         .first::<crate::models::User>(&mut conn)
         .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
+    let user = claims.scope_user(user);
 
-    let limit = query.limit.unwrap_or(20).min(100);
-    let offset = query.offset.unwrap_or(0);
-
-    let collections = state.collection_service.list_collections().await?;
-
-    let mut all_records = Vec::new();
-
-    for collection in collections {
-        let has_permission = state
-            .permission_service
-            .check_collection_permission(&user, collection.id, crate::models::Permission::List)
-            .await
-            .unwrap_or(false);
+    let collection = state
+        .collection_service
+        .get_collection(&collection_name)
+        .await?;
+    let record_id = collection
+        .id_type
+        .normalize(&record_id)
+        .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
 
-        if has_permission {
-            let records = state
-                .collection_service
-                .list_records(
-                    &collection.name,
-                    None,
-                    query.filter.clone(),
-                    query.search.clone(),
-                    None,
-                    None,
-                )
-                .await
-                .unwrap_or_default();
+    let has_permission = state
+        .permission_service
+        .check_collection_permission(&user, collection.id, crate::models::Permission::Update)
+        .await?;
 
-            for record in records {
-                all_records.push(RecordWithCollection {
-                    record,
-                    collection_name: collection.name.clone(),
-                });
-            }
-        }
+    if !has_permission {
+        state
+            .permission_audit_service
+            .record_denial(
+                &user,
+                &collection,
+                Some(&record_id),
+                crate::models::Permission::Update,
+            )
+            .await;
+        return Err(LunarbaseError::RecordPermissionDenied(
+            crate::models::Permission::Update,
+        ));
     }
 
-    let total_count = all_records.len() as i64;
-
-    if let Some(sort_str) = &query.sort {
-        if sort_str.starts_with('-') {
-            let field = &sort_str[1..];
-            match field {
-                "created_at" => {
-                    all_records.sort_by(|a, b| b.record.created_at.cmp(&a.record.created_at))
-                }
-                "updated_at" => {
-                    all_records.sort_by(|a, b| b.record.updated_at.cmp(&a.record.updated_at))
-                }
-                "id" => all_records.sort_by(|a, b| b.record.id.cmp(&a.record.id)),
-                "collection_name" => {
-                    all_records.sort_by(|a, b| b.collection_name.cmp(&a.collection_name))
-                }
-                _ => all_records.sort_by(|a, b| b.record.created_at.cmp(&a.record.created_at)),
-            }
-        } else {
-            match sort_str.as_str() {
-                "created_at" => {
-                    all_records.sort_by(|a, b| a.record.created_at.cmp(&b.record.created_at))
-                }
-                "updated_at" => {
-                    all_records.sort_by(|a, b| a.record.updated_at.cmp(&b.record.updated_at))
-                }
-                "id" => all_records.sort_by(|a, b| a.record.id.cmp(&b.record.id)),
-                "collection_name" => {
-                    all_records.sort_by(|a, b| a.collection_name.cmp(&b.collection_name))
-                }
-                _ => all_records.sort_by(|a, b| b.record.created_at.cmp(&a.record.created_at)),
-            }
-        }
-    } else {
-        all_records.sort_by(|a, b| b.record.created_at.cmp(&a.record.created_at));
-    }
+    let upload_bytes = multipart.upload_bytes();
+    state
+        .usage_service
+        .check(user_id, &user.role, 0, upload_bytes)
+        .await?;
 
-    let start_index = offset as usize;
-    let end_index = (start_index + limit as usize).min(all_records.len());
-    let paginated_records = if start_index < all_records.len() {
-        all_records[start_index..end_index].to_vec()
-    } else {
-        Vec::new()
+    let (data, files) = multipart.check(&state.collection_service, &collection.schema)?;
+    let request = UpdateRecordRequest {
+        data,
+        files,
+        regenerate_slug: query.regenerate_slug,
     };
 
-    let current_page = (offset / limit) + 1;
-    let total_pages = (total_count + limit - 1) / limit;
+    let record = state
+        .collection_service
+        .update_record_with_events(&collection_name, &record_id, request, Some(user_id))
+        .await?;
+    state.usage_service.record(user_id, 0, upload_bytes).await;
+    Ok(Json(ApiResponse::success(record)))
+}
 
-    let pagination_meta = PaginationMeta {
-        current_page,
-        page_size: limit,
-        total_count,
-        total_pages,
-    };
+#[utoipa::path(
+    post,
+    path = "/collections/{collection_name}/records/{record_id}/move",
+    tag = "Records",
+    params(
+        ("collection_name" = String, Path, description = "Collection name"),
+        ("record_id" = String, Path, description = "Record ID")
+    ),
+    request_body = MoveRecordRequest,
+    responses(
+        (status = 200, description = "Record moved; `data.sort_order` holds its new position", body = ApiResponse<RecordResponse>),
+        (status = 400, description = "Collection is not orderable or the target is invalid", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Record not found", body = ErrorResponse),
+        (status = 405, description = "Collection is read-only", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn move_record(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((collection_name, record_id)): Path<(String, String)>,
+    Json(request): Json<MoveRecordRequest>,
+) -> Result<Json<ApiResponse<RecordResponse>>, LunarbaseError> {
+    reject_system_collection_write(&collection_name)?;
 
-    let response = PaginatedRecordsResponse {
-        records: paginated_records,
-        pagination: pagination_meta,
-    };
+    let user = claims_to_user(&claims, &state).await?;
+    let collection = state
+        .collection_service
+        .get_collection(&collection_name)
+        .await?;
+    let record_id = collection
+        .id_type
+        .normalize(&record_id)
+        .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
 
-    Ok(Json(ApiResponse::success(response)))
+    let has_permission = state
+        .permission_service
+        .check_collection_permission(&user, collection.id, crate::models::Permission::Update)
+        .await?;
+    if !has_permission {
+        state
+            .permission_audit_service
+            .record_denial(
+                &user,
+                &collection,
+                Some(&record_id),
+                crate::models::Permission::Update,
+            )
+            .await;
+        return Err(LunarbaseError::RecordPermissionDenied(
+            crate::models::Permission::Update,
+        ));
+    }
+
+    let record = state
+        .collection_service
+        .move_record(&collection_name, &record_id, request, Some(user.id))
+        .await?;
+    Ok(Json(ApiResponse::success(record)))
 }
 
 #[utoipa::path(
-    get,
-    path = "/collections/{collection_name}/records/{record_id}",
+    post,
+    path = "/collections/{collection_name}/records/{record_id}/publish",
     tag = "Records",
     params(
         ("collection_name" = String, Path, description = "Collection name"),
-        ("record_id" = i32, Path, description = "Record ID")
+        ("record_id" = String, Path, description = "Record ID")
     ),
+    request_body(content = Option<PublishRecordRequest>, description = "Omit to publish right away"),
     responses(
-        (status = 200, description = "Record retrieved successfully", body = ApiResponse<RecordResponse>),
-        (status = 404, description = "Record not found", body = ErrorResponse)
+        (status = 200, description = "Record published, or scheduled when `publish_at` is in the future", body = ApiResponse<RecordResponse>),
+        (status = 400, description = "Collection has no draft/publish workflow", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Record not found", body = ErrorResponse),
+        (status = 405, description = "Collection is read-only", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
     )
 )]
-pub async fn get_record(
+pub async fn publish_record(
     State(state): State<AppState>,
-    Path((collection_name, record_id)): Path<(String, i32)>,
+    Extension(claims): Extension<Claims>,
+    Path((collection_name, record_id)): Path<(String, String)>,
+    request: Option<Json<PublishRecordRequest>>,
 ) -> Result<Json<ApiResponse<RecordResponse>>, LunarbaseError> {
+    let (user, record_id) =
+        authorize_status_change(&state, &claims, &collection_name, &record_id).await?;
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+
     let record = state
         .collection_service
-        .get_record(&collection_name, record_id)
+        .publish_record(&collection_name, &record_id, request, Some(user.id))
         .await?;
     Ok(Json(ApiResponse::success(record)))
 }
 
 #[utoipa::path(
-    put,
-    path = "/collections/{collection_name}/records/{record_id}",
+    post,
+    path = "/collections/{collection_name}/records/{record_id}/unpublish",
     tag = "Records",
     params(
         ("collection_name" = String, Path, description = "Collection name"),
-        ("record_id" = i32, Path, description = "Record ID")
-    ),
-    request_body(
-        content_type = "multipart/form-data",
-        description = "Record data and optional files"
+        ("record_id" = String, Path, description = "Record ID")
     ),
+    request_body(content = Option<UnpublishRecordRequest>, description = "Omit to go back to draft"),
     responses(
-        (status = 200, description = "Record updated successfully", body = ApiResponse<RecordResponse>),
-        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 200, description = "Record is a draft again, or archived", body = ApiResponse<RecordResponse>),
+        (status = 400, description = "Collection has no draft/publish workflow", body = ErrorResponse),
         (status = 403, description = "Insufficient permissions", body = ErrorResponse),
-        (status = 404, description = "Record not found", body = ErrorResponse)
+        (status = 404, description = "Record not found", body = ErrorResponse),
+        (status = 405, description = "Collection is read-only", body = ErrorResponse)
     ),
     security(
         ("bearer_auth" = [])
     )
 )]
-pub async fn update_record(
+pub async fn unpublish_record(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
-    Path((collection_name, record_id)): Path<(String, i32)>,
-    mut multipart: Multipart,
+    Path((collection_name, record_id)): Path<(String, String)>,
+    request: Option<Json<UnpublishRecordRequest>>,
 ) -> Result<Json<ApiResponse<RecordResponse>>, LunarbaseError> {
-    let mut data = serde_json::Value::Object(serde_json::Map::new());
-    let mut files: HashMap<String, FileUpload> = HashMap::new();
-
-    while let Some(field) = multipart
-        .next_field()
-        .await
-        .map_err(|_| LunarbaseError::BadRequest("Invalid multipart data".to_string()))?
-    {
-        let name = field.name().unwrap_or("").to_string();
+    let (user, record_id) =
+        authorize_status_change(&state, &claims, &collection_name, &record_id).await?;
+    let request = request.map(|Json(request)| request).unwrap_or_default();
 
-        if name == "data" {
-            let data_bytes = field
-                .bytes()
-                .await
-                .map_err(|_| LunarbaseError::BadRequest("Failed to read data field".to_string()))?;
-            let data_str = String::from_utf8(data_bytes.to_vec()).map_err(|_| {
-                LunarbaseError::BadRequest("Invalid UTF-8 in data field".to_string())
-            })?;
-            data = serde_json::from_str(&data_str).map_err(|_| {
-                LunarbaseError::BadRequest("Invalid JSON in data field".to_string())
-            })?;
-        } else if name.starts_with("file_") {
-            let field_name = name.strip_prefix("file_").unwrap_or(&name).to_string();
-            let filename = field.file_name().unwrap_or("unknown").to_string();
-            let content_type = field
-                .content_type()
-                .unwrap_or("application/octet-stream")
-                .to_string();
+    let record = state
+        .collection_service
+        .unpublish_record(&collection_name, &record_id, request, Some(user.id))
+        .await?;
+    Ok(Json(ApiResponse::success(record)))
+}
 
-            let file_bytes = field
-                .bytes()
-                .await
-                .map_err(|_| LunarbaseError::BadRequest("Failed to read file".to_string()))?;
-            let file_data = general_purpose::STANDARD.encode(&file_bytes);
+/// Publishing and unpublishing need update permission on the collection.
+/// Returns the caller and the normalized record id.
+async fn authorize_status_change(
+    state: &AppState,
+    claims: &Claims,
+    collection_name: &str,
+    record_id: &str,
+) -> Result<(User, String), LunarbaseError> {
+    reject_system_collection_write(collection_name)?;
+
+    let user = claims_to_user(claims, state).await?;
+    let collection = state
+        .collection_service
+        .get_collection(collection_name)
+        .await?;
+    let record_id = collection
+        .id_type
+        .normalize(record_id)
+        .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
 
-            files.insert(
-                field_name,
-                FileUpload {
-                    filename,
-                    content_type,
-                    data: file_data,
-                },
-            );
-        }
+    let has_permission = state
+        .permission_service
+        .check_collection_permission(&user, collection.id, crate::models::Permission::Update)
+        .await?;
+    if !has_permission {
+        state
+            .permission_audit_service
+            .record_denial(
+                &user,
+                &collection,
+                Some(&record_id),
+                crate::models::Permission::Update,
+            )
+            .await;
+        return Err(LunarbaseError::RecordPermissionDenied(
+            crate::models::Permission::Update,
+        ));
     }
 
-    let request = UpdateRecordRequest {
-        data,
-        files: if files.is_empty() { None } else { Some(files) },
-    };
+    Ok((user, record_id))
+}
 
+#[utoipa::path(
+    delete,
+    path = "/collections/{collection_name}/records/{record_id}",
+    tag = "Records",
+    params(
+        ("collection_name" = String, Path, description = "Collection name"),
+        ("record_id" = String, Path, description = "Record ID")
+    ),
+    responses(
+        (status = 204, description = "Record deleted successfully"),
+        (status = 400, description = "Invalid record id", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "Record not found", body = ErrorResponse),
+        (status = 405, description = "Collection is read-only", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_record(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((collection_name, record_id)): Path<(String, String)>,
+) -> Result<StatusCode, LunarbaseError> {
     use crate::schema::users;
     use diesel::prelude::*;
 
+    reject_system_collection_write(&collection_name)?;
+
     let user_id: i32 = claims
         .sub
         .parse()
@@ -650,110 +2686,237 @@ pub async fn update_record(
         .select(crate::models::User::as_select())
         .first::<crate::models::User>(&mut conn)
         .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
+    let user = claims.scope_user(user);
 
     let collection = state
         .collection_service
         .get_collection(&collection_name)
         .await?;
+    let record_id = collection
+        .id_type
+        .normalize(&record_id)
+        .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
 
     let has_permission = state
         .permission_service
-        .check_collection_permission(&user, collection.id, crate::models::Permission::Update)
+        .check_collection_permission(&user, collection.id, crate::models::Permission::Delete)
         .await?;
 
     if !has_permission {
-        return Err(LunarbaseError::InsufficientPermissions);
+        state
+            .permission_audit_service
+            .record_denial(
+                &user,
+                &collection,
+                Some(&record_id),
+                crate::models::Permission::Delete,
+            )
+            .await;
+        return Err(LunarbaseError::RecordPermissionDenied(
+            crate::models::Permission::Delete,
+        ));
     }
 
-    let record = state
+    state
+        .collection_service
+        .delete_record_with_events(&collection_name, &record_id, Some(user_id))
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/collections/{name}/schema",
+    tag = "Collections",
+    params(
+        ("name" = String, Path, description = "Collection name")
+    ),
+    responses(
+        (status = 200, description = "Collection schema retrieved successfully", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_collection_schema(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, LunarbaseError> {
+    let schema = if name == USERS_SYSTEM_COLLECTION {
+        // The schema itself is not sensitive, so it is only hidden when the collection is disabled
+        if state.get_users_relation_visibility().await == "disabled" {
+            return Err(LunarbaseError::NotFound("Collection not found".to_string()));
+        }
+        CollectionService::users_collection_schema()
+    } else {
+        state.collection_service.get_collection(&name).await?.schema
+    };
+    let schema_json = serde_json::to_value(schema).map_err(|_| LunarbaseError::InternalError)?;
+    Ok(Json(ApiResponse::success(schema_json)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/collections/{name}/schema/versions",
+    tag = "Collections",
+    params(
+        ("name" = String, Path, description = "Collection name")
+    ),
+    responses(
+        (status = 200, description = "Schema history, newest version first", body = ApiResponse<Vec<CollectionSchemaVersionResponse>>),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
+    )
+)]
+pub async fn list_collection_schema_versions(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<Vec<CollectionSchemaVersionResponse>>>, LunarbaseError> {
+    let versions = state.collection_service.list_schema_versions(&name).await?;
+    Ok(Json(ApiResponse::success(versions)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/collections/{name}/schema/versions/{version}",
+    tag = "Collections",
+    params(
+        ("name" = String, Path, description = "Collection name"),
+        ("version" = i32, Path, description = "Schema version number")
+    ),
+    responses(
+        (status = 200, description = "Schema version retrieved successfully", body = ApiResponse<CollectionSchemaVersionResponse>),
+        (status = 404, description = "Collection or schema version not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_collection_schema_version(
+    State(state): State<AppState>,
+    Path((name, version)): Path<(String, i32)>,
+) -> Result<Json<ApiResponse<CollectionSchemaVersionResponse>>, LunarbaseError> {
+    let schema_version = state
         .collection_service
-        .update_record_with_events(&collection_name, record_id, request, Some(user_id))
+        .get_schema_version(&name, version)
         .await?;
-    Ok(Json(ApiResponse::success(record)))
+    Ok(Json(ApiResponse::success(schema_version)))
 }
 
 #[utoipa::path(
-    delete,
-    path = "/collections/{collection_name}/records/{record_id}",
-    tag = "Records",
+    post,
+    path = "/collections/{name}/schema/versions/{version}/restore",
+    tag = "Collections",
     params(
-        ("collection_name" = String, Path, description = "Collection name"),
-        ("record_id" = i32, Path, description = "Record ID")
+        ("name" = String, Path, description = "Collection name"),
+        ("version" = i32, Path, description = "Schema version to restore")
     ),
     responses(
-        (status = 204, description = "Record deleted successfully"),
+        (status = 200, description = "Schema restored as a new version", body = ApiResponse<CollectionResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 403, description = "Insufficient permissions", body = ErrorResponse),
-        (status = 404, description = "Record not found", body = ErrorResponse)
+        (status = 404, description = "Collection or schema version not found", body = ErrorResponse)
     ),
     security(
         ("bearer_auth" = [])
     )
 )]
-pub async fn delete_record(
+pub async fn restore_collection_schema_version(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
-    Path((collection_name, record_id)): Path<(String, i32)>,
-) -> Result<StatusCode, LunarbaseError> {
-    use crate::schema::users;
-    use diesel::prelude::*;
-
-    let user_id: i32 = claims
-        .sub
-        .parse()
-        .map_err(|_| LunarbaseError::TokenInvalid)?;
-
-    let mut conn = state
-        .db_pool
-        .get()
-        .map_err(|_| LunarbaseError::InternalError)?;
-
-    let user = users::table
-        .filter(users::id.eq(user_id))
-        .select(crate::models::User::as_select())
-        .first::<crate::models::User>(&mut conn)
-        .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
-
-    let collection = state
-        .collection_service
-        .get_collection(&collection_name)
-        .await?;
-
-    let has_permission = state
-        .permission_service
-        .check_collection_permission(&user, collection.id, crate::models::Permission::Delete)
-        .await?;
-
-    if !has_permission {
+    Path((name, version)): Path<(String, i32)>,
+) -> Result<Json<ApiResponse<CollectionResponse>>, LunarbaseError> {
+    if claims.role != "admin" {
         return Err(LunarbaseError::InsufficientPermissions);
     }
 
-    state
+    let collection = state
         .collection_service
-        .delete_record_with_events(&collection_name, record_id, Some(user_id))
+        .restore_schema_version(&name, version, claims.sub.parse().ok())
         .await?;
-    Ok(StatusCode::NO_CONTENT)
+    Ok(Json(ApiResponse::success(collection)))
 }
 
 #[utoipa::path(
     get,
-    path = "/collections/{name}/schema",
+    path = "/collections/{name}/schema.json",
     tag = "Collections",
     params(
         ("name" = String, Path, description = "Collection name")
     ),
     responses(
-        (status = 200, description = "Collection schema retrieved successfully", body = ApiResponse<serde_json::Value>),
+        (status = 200, description = "Collection schema as a JSON Schema (draft 2020-12) document", body = serde_json::Value),
         (status = 404, description = "Collection not found", body = ErrorResponse)
     )
 )]
-pub async fn get_collection_schema(
+pub async fn get_collection_json_schema(
     State(state): State<AppState>,
     Path(name): Path<String>,
-) -> Result<Json<ApiResponse<serde_json::Value>>, LunarbaseError> {
+) -> Result<Json<serde_json::Value>, LunarbaseError> {
     let collection = state.collection_service.get_collection(&name).await?;
-    let schema_json =
-        serde_json::to_value(collection.schema).map_err(|_| LunarbaseError::InternalError)?;
-    Ok(Json(ApiResponse::success(schema_json)))
+    Ok(Json(crate::json_schema::collection_to_json_schema(
+        &collection,
+    )))
+}
+
+#[utoipa::path(
+    get,
+    path = "/collections/schemas.json",
+    tag = "Collections",
+    responses(
+        (status = 200, description = "JSON Schema documents for all collections keyed by collection name", body = serde_json::Value)
+    )
+)]
+pub async fn get_collections_json_schema(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, LunarbaseError> {
+    let collections = state.collection_service.list_collections().await?;
+    Ok(Json(crate::json_schema::collections_to_json_schema_bundle(
+        &collections,
+    )))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api-docs/collections.json",
+    tag = "Collections",
+    responses(
+        (status = 200, description = "The API document extended with typed record paths and schemas for every collection", body = serde_json::Value)
+    )
+)]
+pub async fn get_collections_openapi(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, LunarbaseError> {
+    let collections = state.collection_service.list_collections().await?;
+    let document = state.collections_openapi.get(&collections);
+    Ok(Json(document.as_ref().clone()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/codegen/typescript",
+    tag = "Collections",
+    responses(
+        (status = 200, description = "TypeScript definitions for all collection records", body = String, content_type = "application/typescript"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn generate_typescript_types(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let collections = workspace_collections(&state, &claims).await?;
+    let typescript = crate::codegen::generate_typescript(&collections);
+
+    Ok((
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "application/typescript; charset=utf-8",
+        )],
+        typescript,
+    ))
 }
 
 #[derive(Serialize, ToSchema)]
@@ -766,12 +2929,19 @@ pub struct CollectionStats {
     pub average_records_per_collection: f64,
     pub largest_collection: Option<String>,
     pub smallest_collection: Option<String>,
+    /// When each collection's record count was last recounted from its table
+    pub record_counts_reconciled_at: HashMap<String, NaiveDateTime>,
+    /// The least recently recounted collection's time, if any
+    pub oldest_reconciled_at: Option<NaiveDateTime>,
 }
 
 #[utoipa::path(
     get,
     path = "/collections/stats",
     tag = "Collections",
+    params(
+        ("fresh" = Option<bool>, Query, description = "Recount every records table instead of using the cached counts")
+    ),
     responses(
         (status = 200, description = "Collection statistics retrieved successfully", body = ApiResponse<CollectionStats>),
         (status = 403, description = "Insufficient permissions", body = ErrorResponse)
@@ -783,12 +2953,13 @@ pub struct CollectionStats {
 pub async fn get_collections_stats(
     State(state): State<AppState>,
     Extension(user): Extension<Claims>,
+    Query(query): Query<CollectionStatsQuery>,
 ) -> Result<Json<ApiResponse<CollectionStats>>, LunarbaseError> {
     if user.role != "admin" {
         return Err(LunarbaseError::InsufficientPermissions);
     }
 
-    let collections = state.collection_service.list_collections().await?;
+    let collections = workspace_collections(&state, &user).await?;
     let total_collections = collections.len() as i64;
 
     let (
@@ -798,7 +2969,11 @@ pub async fn get_collections_stats(
         average_records_per_collection,
         largest_collection,
         smallest_collection,
-    ) = state.collection_service.get_collections_stats().await?;
+        record_counts_reconciled_at,
+    ) = state
+        .collection_service
+        .get_collections_stats(user.workspace_id, query.fresh)
+        .await?;
 
     let mut collections_by_type = HashMap::new();
     for collection in &collections {
@@ -819,6 +2994,8 @@ pub async fn get_collections_stats(
         average_records_per_collection,
         largest_collection,
         smallest_collection,
+        oldest_reconciled_at: record_counts_reconciled_at.values().min().copied(),
+        record_counts_reconciled_at,
     };
 
     Ok(Json(ApiResponse::success(stats)))
@@ -851,36 +3028,22 @@ pub async fn get_collections_record_counts(
         .get_user_accessible_collections(&user_model)
         .await?;
 
-    let collections = state.collection_service.list_collections().await?;
-    let mut records_per_collection = HashMap::new();
-
-    for collection in &collections {
-        if accessible_collection_ids.contains(&collection.id) {
-            let table_name = format!("records_{}", collection.name);
-            let count_sql = format!("SELECT COUNT(*) as count FROM {}", table_name);
-
-            #[derive(diesel::QueryableByName)]
-            struct CountResult {
-                #[diesel(sql_type = diesel::sql_types::BigInt)]
-                count: i64,
-            }
-
-            let mut conn = state
-                .collection_service
-                .pool
-                .get()
-                .map_err(|_| LunarbaseError::InternalError)?;
-            let count_result: Result<CountResult, _> =
-                diesel::sql_query(&count_sql).get_result(&mut conn);
-
-            let record_count = match count_result {
-                Ok(result) => result.count,
-                Err(_) => 0,
-            };
+    let mut collections = workspace_collections(&state, &user).await?;
+    collections.retain(|collection| accessible_collection_ids.contains(&collection.id));
+    let record_counts = state
+        .collection_service
+        .record_counts(&collections, false)
+        .await?;
 
-            records_per_collection.insert(collection.name.clone(), record_count);
-        }
-    }
+    let records_per_collection = collections
+        .iter()
+        .map(|collection| {
+            let count = record_counts
+                .get(&collection.id)
+                .map_or(0, |count| count.record_count);
+            (collection.name.clone(), count)
+        })
+        .collect();
 
     let response = CollectionRecordCounts {
         records_per_collection,
@@ -888,3 +3051,254 @@ pub async fn get_collections_record_counts(
 
     Ok(Json(ApiResponse::success(response)))
 }
+
+#[utoipa::path(
+    post,
+    path = "/admin/collections/{name}/verify",
+    tag = "Collections",
+    params(
+        ("name" = String, Path, description = "Collection name")
+    ),
+    responses(
+        (status = 200, description = "Records table compared against the stored schema", body = ApiResponse<CollectionIntegrityReport>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn verify_collection(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<CollectionIntegrityReport>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let report = state.collection_service.verify_collection(&name).await?;
+    state
+        .permission_service
+        .record_audit_entry(&NewPermissionAuditEntry {
+            actor_id: claims.sub.parse().ok(),
+            target_user_id: None,
+            action: "verify_collection".to_string(),
+            collection_name: Some(name.clone()),
+            role_name: None,
+            affected_count: report.issues.len() as i64,
+        })
+        .await?;
+
+    tracing::info!(
+        "Admin {} ({}) verified collection '{}': {} issue(s) found",
+        claims.sub,
+        claims.email,
+        name,
+        report.issues.len()
+    );
+
+    Ok(Json(ApiResponse::success(report)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/collections/{name}/health",
+    tag = "Collections",
+    params(
+        ("name" = String, Path, description = "Collection name")
+    ),
+    responses(
+        (status = 200, description = "Component-style checks of the records table: structure, indexes, triggers, page integrity, record count against the cached count and last write", body = ApiResponse<CollectionHealthReport>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_collection_health(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<CollectionHealthReport>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let report = state
+        .collection_service
+        .collection_health(&name, true)
+        .await?;
+    Ok(Json(ApiResponse::success(report)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/collections/{name}/repair",
+    tag = "Collections",
+    params(
+        ("name" = String, Path, description = "Collection name")
+    ),
+    responses(
+        (status = 200, description = "Non-destructive fixes applied; remaining issues listed as unresolved", body = ApiResponse<CollectionRepairReport>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn repair_collection(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<CollectionRepairReport>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let report = state.collection_service.repair_collection(&name).await?;
+    state
+        .permission_service
+        .record_audit_entry(&NewPermissionAuditEntry {
+            actor_id: claims.sub.parse().ok(),
+            target_user_id: None,
+            action: "repair_collection".to_string(),
+            collection_name: Some(name.clone()),
+            role_name: None,
+            affected_count: report.applied.len() as i64,
+        })
+        .await?;
+
+    tracing::info!(
+        "Admin {} ({}) repaired collection '{}': applied {:?}, {} issue(s) unresolved",
+        claims.sub,
+        claims.email,
+        name,
+        report.applied,
+        report.unresolved.len()
+    );
+
+    Ok(Json(ApiResponse::success(report)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/collections/orphans/sweep",
+    tag = "Collections",
+    params(
+        ("dry_run" = Option<bool>, Query, description = "Only report the orphans without dropping them (default false)")
+    ),
+    responses(
+        (status = 200, description = "Tables, indexes and triggers left behind by interrupted schema changes, and which of them were dropped", body = ApiResponse<OrphanSweepReport>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Superadmin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn sweep_orphans(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<SweepOrphansQuery>,
+) -> Result<Json<ApiResponse<OrphanSweepReport>>, LunarbaseError> {
+    // The sweep covers the tables of every workspace
+    if !claims.is_superadmin() {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let report = state
+        .collection_service
+        .sweep_orphans(query.dry_run)
+        .await?;
+
+    tracing::info!(
+        "Admin {} ({}) swept orphaned schema objects{}: {} found, dropped {:?}",
+        claims.sub,
+        claims.email,
+        if query.dry_run { " (dry run)" } else { "" },
+        report.orphans.len(),
+        report.dropped
+    );
+
+    Ok(Json(ApiResponse::success(report)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/collections/{name}/schedule",
+    tag = "Collections",
+    params(
+        ("name" = String, Path, description = "Collection name"),
+        ("limit" = Option<i64>, Query, description = "Pending operations to list (default 100, max 1000)")
+    ),
+    responses(
+        (status = 200, description = "Scheduled publish and expiry operations not yet applied", body = ApiResponse<RecordScheduleReport>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_record_schedule(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(name): Path<String>,
+    Query(query): Query<RecordScheduleQuery>,
+) -> Result<Json<ApiResponse<RecordScheduleReport>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let report = state
+        .collection_service
+        .get_record_schedule(&name, limit)
+        .await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/collections/{name}/retention",
+    tag = "Collections",
+    params(
+        ("name" = String, Path, description = "Collection name"),
+        ("limit" = Option<i64>, Query, description = "Latest runs to list (default 20, max 50)")
+    ),
+    responses(
+        (status = 200, description = "Retention policy, records currently due and the latest runs", body = ApiResponse<RetentionReport>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_retention_report(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(name): Path<String>,
+    Query(query): Query<RecordScheduleQuery>,
+) -> Result<Json<ApiResponse<RetentionReport>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 50);
+    let report = state
+        .collection_service
+        .get_retention_report(&name, limit)
+        .await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
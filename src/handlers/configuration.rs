@@ -9,19 +9,25 @@ use utoipa::ToSchema;
 
 use crate::{
     AppState,
-    models::system_setting::{
-        SettingCategory, SettingDataType, SystemSettingRequest, SystemSettingResponse,
+    config::normalize_absolute_url,
+    middleware::{ApiVersion, parse_version_date, validate_cors_origins},
+    models::{
+        LoginIdentifier, NewPermissionAuditEntry, PermissionSet,
+        system_setting::{
+            SettingCategory, SettingDataType, SystemSettingRequest, SystemSettingResponse,
+        },
     },
-    services::ConfigurationService,
+    services::{ConfigurationService, configuration_manager::ConfigurationAccess},
     utils::auth_error::ApiResponse,
-    utils::{Claims, ErrorResponse, LunarbaseError},
+    utils::{Claims, ErrorResponse, Locale, LunarbaseError, validate_cookie_settings},
 };
 
 fn validate_category(category: &str) -> Result<(), LunarbaseError> {
     match category {
-        "database" | "auth" | "api" | "email" | "oauth" | "storage" | "security_headers" => Ok(()),
+        "database" | "auth" | "api" | "email" | "oauth" | "storage" | "security_headers"
+        | "urls" | "users" | "system" | "limits" => Ok(()),
         _ => Err(LunarbaseError::ValidationError(vec![format!(
-            "Invalid category '{}'. Valid categories are: database, auth, api, email, oauth, storage, security_headers",
+            "Invalid category '{}'. Valid categories are: database, auth, api, email, oauth, storage, security_headers, urls, users, system, limits",
             category
         )])),
     }
@@ -63,6 +69,466 @@ fn validate_setting_key(key: &str) -> Result<(), LunarbaseError> {
     Ok(())
 }
 
+/// Rejects values that would leave a runtime-applied setting in an unusable state.
+async fn validate_setting_value(
+    app_state: &AppState,
+    category: &str,
+    key: &str,
+    value: &str,
+) -> Result<(), LunarbaseError> {
+    match (category, key) {
+        ("api", "cors_allowed_origins") => {
+            let origins: Vec<String> = serde_json::from_str(value).map_err(|_| {
+                LunarbaseError::ValidationError(vec![
+                    "cors_allowed_origins must be a JSON array of origin strings".to_string(),
+                ])
+            })?;
+            validate_cors_origins(&origins, app_state.get_cors_allow_credentials().await)
+                .map(|_| ())
+                .map_err(LunarbaseError::ValidationError)
+        }
+        ("api", "cors_allow_credentials") => {
+            let allow_credentials = value.parse::<bool>().map_err(|_| {
+                LunarbaseError::ValidationError(vec![
+                    "cors_allow_credentials must be true or false".to_string(),
+                ])
+            })?;
+            if allow_credentials
+                && app_state
+                    .get_cors_allowed_origins()
+                    .await
+                    .iter()
+                    .any(|o| o.trim() == "*")
+            {
+                return Err(LunarbaseError::ValidationError(vec![
+                    "cors_allow_credentials cannot be enabled while cors_allowed_origins contains '*'; list the origins explicitly first".to_string(),
+                ]));
+            }
+            Ok(())
+        }
+        ("auth", "lockout_duration_minutes") => {
+            let minutes = parse_positive_minutes(key, value)?;
+            let max_minutes = app_state.get_max_lockout_duration_minutes().await;
+            if minutes > max_minutes {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "lockout_duration_minutes cannot exceed max_lockout_duration_minutes ({})",
+                    max_minutes
+                )]));
+            }
+            Ok(())
+        }
+        ("auth", "max_lockout_duration_minutes") => {
+            let minutes = parse_positive_minutes(key, value)?;
+            let base_minutes = app_state.get_lockout_duration_minutes().await;
+            if minutes < base_minutes {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "max_lockout_duration_minutes cannot be below lockout_duration_minutes ({})",
+                    base_minutes
+                )]));
+            }
+            Ok(())
+        }
+        ("auth", "password_hash_memory_kib") => match value.parse::<u32>() {
+            Ok(kibibytes) if (8192..=1_048_576).contains(&kibibytes) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "password_hash_memory_kib must be between 8192 and 1048576".to_string(),
+            ])),
+        },
+        ("auth", "password_hash_iterations") => match value.parse::<u32>() {
+            Ok(iterations) if (1..=10).contains(&iterations) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "password_hash_iterations must be between 1 and 10".to_string(),
+            ])),
+        },
+        ("auth", "password_hash_parallelism") => match value.parse::<u32>() {
+            Ok(lanes) if (1..=16).contains(&lanes) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "password_hash_parallelism must be between 1 and 16".to_string(),
+            ])),
+        },
+        ("auth", "cookie_same_site") => {
+            validate_cookie_settings(value, &app_state.get_cookie_domain().await)
+                .map_err(|error| LunarbaseError::ValidationError(vec![error]))
+        }
+        ("auth", "cookie_domain") => {
+            validate_cookie_settings(&app_state.get_cookie_same_site().await, value)
+                .map_err(|error| LunarbaseError::ValidationError(vec![error]))
+        }
+        ("api", "keep_alive_timeout_seconds") => match value.parse::<u32>() {
+            Ok(seconds) if (1..=3600).contains(&seconds) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "keep_alive_timeout_seconds must be between 1 and 3600".to_string(),
+            ])),
+        },
+        ("api", "http2_max_concurrent_streams") => match value.parse::<u32>() {
+            Ok(streams) if (1..=10_000).contains(&streams) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "http2_max_concurrent_streams must be between 1 and 10000".to_string(),
+            ])),
+        },
+        ("api", "max_header_size_kb") => match value.parse::<u32>() {
+            Ok(kilobytes) if (8..=1024).contains(&kilobytes) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "max_header_size_kb must be between 8 and 1024".to_string(),
+            ])),
+        },
+        ("api", "tls_expiry_warning_days") => match value.parse::<u32>() {
+            Ok(days) if (1..=90).contains(&days) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "tls_expiry_warning_days must be between 1 and 90".to_string(),
+            ])),
+        },
+        ("api", "websocket_coalesce_threshold") => match value.parse::<u32>() {
+            Ok(events) if events <= 100_000 => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "websocket_coalesce_threshold must be between 0 and 100000".to_string(),
+            ])),
+        },
+        ("api", "websocket_coalesce_window_ms") => match value.parse::<u32>() {
+            Ok(milliseconds) if (10..=60_000).contains(&milliseconds) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "websocket_coalesce_window_ms must be between 10 and 60000".to_string(),
+            ])),
+        },
+        ("auth", "permission_denial_alert_threshold") => match value.parse::<u32>() {
+            Ok(denials) if denials <= 100_000 => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "permission_denial_alert_threshold must be between 0 and 100000".to_string(),
+            ])),
+        },
+        ("auth", "permission_denial_alert_window_seconds") => match value.parse::<u32>() {
+            Ok(seconds) if (1..=86_400).contains(&seconds) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "permission_denial_alert_window_seconds must be between 1 and 86400".to_string(),
+            ])),
+        },
+        ("auth", "permission_denial_alert_webhook_url")
+            if !(value.is_empty()
+                || value.starts_with("https://")
+                || value.starts_with("http://")) =>
+        {
+            Err(LunarbaseError::ValidationError(vec![
+                "permission_denial_alert_webhook_url must be an http(s) URL".to_string(),
+            ]))
+        }
+        ("database", "retention_batch_size") => match value.parse::<u32>() {
+            Ok(size) if (1..=10_000).contains(&size) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "retention_batch_size must be between 1 and 10000".to_string(),
+            ])),
+        },
+        ("database", "record_deletion_alert_threshold") => match value.parse::<u32>() {
+            Ok(multiple) if multiple <= 1000 => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "record_deletion_alert_threshold must be between 0 and 1000".to_string(),
+            ])),
+        },
+        ("database", "record_deletion_alert_webhook_url")
+            if !(value.is_empty()
+                || value.starts_with("https://")
+                || value.starts_with("http://")) =>
+        {
+            Err(LunarbaseError::ValidationError(vec![
+                "record_deletion_alert_webhook_url must be an http(s) URL".to_string(),
+            ]))
+        }
+        ("urls", "public_api_url" | "frontend_url") if !value.is_empty() => {
+            normalize_absolute_url(value)
+                .map(|_| ())
+                .map_err(|e| LunarbaseError::ValidationError(vec![format!("{} {}", key, e)]))
+        }
+        ("api", "websocket_outbound_buffer_size") => match value.parse::<u32>() {
+            Ok(messages) if (16..=65_536).contains(&messages) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "websocket_outbound_buffer_size must be between 16 and 65536".to_string(),
+            ])),
+        },
+        ("api", "websocket_slow_consumer_max_dropped") => match value.parse::<u32>() {
+            Ok(messages) if messages <= 1_000_000 => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "websocket_slow_consumer_max_dropped must be between 0 and 1000000".to_string(),
+            ])),
+        },
+        ("api", "websocket_max_message_kb") => match value.parse::<u32>() {
+            Ok(kilobytes) if (1..=16_384).contains(&kilobytes) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "websocket_max_message_kb must be between 1 and 16384".to_string(),
+            ])),
+        },
+        ("api", "websocket_max_subscriptions") => match value.parse::<u32>() {
+            Ok(subscriptions) if (1..=10_000).contains(&subscriptions) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "websocket_max_subscriptions must be between 1 and 10000".to_string(),
+            ])),
+        },
+        ("api", "default_version") => match value.parse::<u32>() {
+            Ok(number) if ApiVersion::from_number(number).is_some() => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![format!(
+                "default_version must be between 1 and {}",
+                ApiVersion::LATEST.number()
+            )])),
+        },
+        ("api", key)
+            if (key.ends_with("_deprecation_date") || key.ends_with("_sunset_date"))
+                && !value.is_empty()
+                && parse_version_date(value).is_none() =>
+        {
+            Err(LunarbaseError::ValidationError(vec![format!(
+                "{} must be a date in YYYY-MM-DD format or empty",
+                key
+            )]))
+        }
+        ("api", "websocket_max_filter_length") => match value.parse::<u32>() {
+            Ok(characters) if (16..=65_536).contains(&characters) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "websocket_max_filter_length must be between 16 and 65536".to_string(),
+            ])),
+        },
+        ("api", "websocket_max_violations") => match value.parse::<u32>() {
+            Ok(violations) if violations <= 1000 => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "websocket_max_violations must be between 0 and 1000".to_string(),
+            ])),
+        },
+        ("api", "websocket_ban_seconds") => match value.parse::<u32>() {
+            Ok(seconds) if (1..=86_400).contains(&seconds) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "websocket_ban_seconds must be between 1 and 86400".to_string(),
+            ])),
+        },
+        ("system", "quota_warning_percent") => match value.parse::<u32>() {
+            Ok(percent) if percent < 100 => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "quota_warning_percent must be between 0 and 99".to_string(),
+            ])),
+        },
+        ("system", "metrics_cpu_sampler_interval_seconds") => match value.parse::<u32>() {
+            Ok(seconds) if (1..=3_600).contains(&seconds) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "metrics_cpu_sampler_interval_seconds must be between 1 and 3600".to_string(),
+            ])),
+        },
+        ("database", "permission_cache_ttl_seconds") => match value.parse::<u32>() {
+            Ok(seconds) if seconds <= 3600 => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "permission_cache_ttl_seconds must be between 0 and 3600".to_string(),
+            ])),
+        },
+        ("limits", "auth_body_kb") => match value.parse::<u32>() {
+            Ok(kilobytes) if (1..=10_240).contains(&kilobytes) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "auth_body_kb must be between 1 and 10240".to_string(),
+            ])),
+        },
+        ("limits", "record_json_mb") | ("limits", "file_upload_mb") => match value.parse::<u32>() {
+            Ok(megabytes) if (1..=4096).contains(&megabytes) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![format!(
+                "{} must be between 1 and 4096",
+                key
+            )])),
+        },
+        ("storage", "upload_scan_mode") => match value {
+            "off" | "clamav" | "command" => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "upload_scan_mode must be off, clamav or command".to_string(),
+            ])),
+        },
+        ("storage", "upload_scan_timeout_seconds") => match value.parse::<u32>() {
+            Ok(seconds) if (1..=600).contains(&seconds) => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "upload_scan_timeout_seconds must be between 1 and 600".to_string(),
+            ])),
+        },
+        ("auth", "expired_lock_cleanup_interval_seconds") => match value.parse::<u32>() {
+            Ok(seconds) if seconds <= 86_400 => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "expired_lock_cleanup_interval_seconds must be between 0 and 86400".to_string(),
+            ])),
+        },
+        ("auth", "email_rate_limit_max_requests") => match value.parse::<u32>() {
+            Ok(requests) if requests > 0 => Ok(()),
+            _ => Err(LunarbaseError::ValidationError(vec![
+                "email_rate_limit_max_requests must be a positive number".to_string(),
+            ])),
+        },
+        ("auth", "email_rate_limit_window_minutes") => {
+            parse_positive_minutes(key, value).map(|_| ())
+        }
+        ("auth", "login_identifier") => {
+            LoginIdentifier::parse(value).map(|_| ()).ok_or_else(|| {
+                LunarbaseError::ValidationError(vec![
+                    "login_identifier must be one of: email, username, either".to_string(),
+                ])
+            })
+        }
+        ("auth", "reserved_usernames") => serde_json::from_str::<Vec<String>>(value)
+            .map(|_| ())
+            .map_err(|_| {
+                LunarbaseError::ValidationError(vec![
+                    "reserved_usernames must be a JSON array of usernames".to_string(),
+                ])
+            }),
+        ("auth", "lockout_exempt_admins") => {
+            let emails: Vec<String> = serde_json::from_str(value).map_err(|_| {
+                LunarbaseError::ValidationError(vec![
+                    "lockout_exempt_admins must be a JSON array of admin emails".to_string(),
+                ])
+            })?;
+            validate_exempt_admins(app_state, &emails)
+        }
+        ("system", "default_language") => Locale::parse(value).map(|_| ()).ok_or_else(|| {
+            LunarbaseError::ValidationError(vec![
+                "default_language must be one of: en, pl, de".to_string(),
+            ])
+        }),
+        ("system", "read_only_roles") => serde_json::from_str::<Vec<String>>(value)
+            .map(|_| ())
+            .map_err(|_| {
+                LunarbaseError::ValidationError(vec![
+                    "read_only_roles must be a JSON array of role names".to_string(),
+                ])
+            }),
+        ("system", key) if key.starts_with("default_permissions_") => {
+            let permissions: PermissionSet = serde_json::from_str(value).map_err(|_| {
+                LunarbaseError::ValidationError(vec![format!(
+                    "{} must be an object with can_create, can_read, can_update, can_delete and can_list",
+                    key
+                )])
+            })?;
+            if key == "default_permissions_admin" && permissions != PermissionSet::FULL {
+                return Err(LunarbaseError::ValidationError(vec![
+                    "The admin role must keep full access to new collections".to_string(),
+                ]));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn parse_positive_minutes(key: &str, value: &str) -> Result<i32, LunarbaseError> {
+    match value.parse::<i32>() {
+        Ok(minutes) if minutes > 0 => Ok(minutes),
+        _ => Err(LunarbaseError::ValidationError(vec![format!(
+            "{} must be a positive number of minutes",
+            key
+        )])),
+    }
+}
+
+/// Only existing admin accounts may skip the lockout.
+fn validate_exempt_admins(app_state: &AppState, emails: &[String]) -> Result<(), LunarbaseError> {
+    use crate::schema::users;
+    use diesel::prelude::*;
+
+    if emails.is_empty() {
+        return Ok(());
+    }
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+    let admin_emails: Vec<String> = users::table
+        .filter(users::role.eq("admin"))
+        .select(users::email)
+        .load(&mut conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+    let unknown: Vec<String> = emails
+        .iter()
+        .filter(|email| {
+            !admin_emails
+                .iter()
+                .any(|admin| admin.eq_ignore_ascii_case(email))
+        })
+        .map(|email| format!("'{}' is not an admin account", email))
+        .collect();
+
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(LunarbaseError::ValidationError(unknown))
+    }
+}
+
+/// Adds changes to `system.read_only` and `system.read_only_roles` to the
+/// audit log: one entry for the global switch, one per role added to or
+/// removed from the read-only roles.
+async fn audit_read_only_change(
+    app_state: &AppState,
+    claims: &Claims,
+    category: &str,
+    key: &str,
+    previous: Option<&str>,
+    current: &str,
+) -> Result<(), LunarbaseError> {
+    if category != "system"
+        || !(key == "read_only" || key == "read_only_roles")
+        || previous == Some(current)
+    {
+        return Ok(());
+    }
+
+    tracing::warn!(
+        "Admin {} ({}) changed system.{} from {} to {}",
+        claims.sub,
+        claims.email,
+        key,
+        previous.unwrap_or("<unset>"),
+        current
+    );
+
+    let changes: Vec<(&str, Option<String>)> = if key == "read_only" {
+        let action = if current == "true" {
+            "enable_read_only"
+        } else {
+            "disable_read_only"
+        };
+        vec![(action, None)]
+    } else {
+        let roles = |value: Option<&str>| -> Vec<String> {
+            value
+                .and_then(|value| serde_json::from_str(value).ok())
+                .unwrap_or_default()
+        };
+        let (before, after) = (roles(previous), roles(Some(current)));
+        let added = after
+            .iter()
+            .filter(|role| !before.contains(role))
+            .map(|role| ("enable_read_only_role", Some(role.clone())));
+        let removed = before
+            .iter()
+            .filter(|role| !after.contains(role))
+            .map(|role| ("disable_read_only_role", Some(role.clone())));
+        added.chain(removed).collect()
+    };
+
+    for (action, role_name) in changes {
+        app_state
+            .permission_service
+            .record_audit_entry(&NewPermissionAuditEntry {
+                actor_id: claims.sub.parse().ok(),
+                target_user_id: None,
+                action: action.to_string(),
+                collection_name: None,
+                role_name,
+                affected_count: 1,
+            })
+            .await?;
+    }
+    Ok(())
+}
+
+/// Settings changes go through ConfigurationService, so the cached values (and
+/// anything watching them, such as CORS) are refreshed here.
+async fn refresh_configuration_cache(app_state: &AppState) {
+    if let Err(e) = app_state.configuration_manager.reload_cache().await {
+        tracing::warn!("Failed to reload configuration cache: {:?}", e);
+    }
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct ListSettingsQuery {
     #[schema(example = "database")]
@@ -232,6 +698,7 @@ pub async fn get_setting(
     request_body = SystemSettingRequest,
     responses(
         (status = 200, description = "Setting updated successfully", body = ApiResponse<SystemSettingResponse>),
+        (status = 400, description = "Invalid setting value", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 403, description = "Insufficient permissions", body = ErrorResponse),
         (status = 404, description = "Setting not found", body = ErrorResponse)
@@ -252,8 +719,19 @@ pub async fn update_setting(
 
     validate_category(&category_str)?;
     validate_setting_key(&setting_key)?;
+    validate_setting_value(
+        &app_state,
+        &category_str,
+        &setting_key,
+        &payload.setting_value,
+    )
+    .await?;
 
     let config_service = ConfigurationService::new(app_state.db_pool.clone());
+    let previous = config_service
+        .get_setting(&category_str, &setting_key)
+        .await?
+        .map(|setting| setting.setting_value);
     let updated_setting = config_service
         .update_setting(
             &category_str,
@@ -262,6 +740,16 @@ pub async fn update_setting(
             payload.updated_by,
         )
         .await?;
+    refresh_configuration_cache(&app_state).await;
+    audit_read_only_change(
+        &app_state,
+        &claims,
+        &category_str,
+        &setting_key,
+        previous.as_deref(),
+        &updated_setting.setting_value,
+    )
+    .await?;
 
     Ok(Json(ApiResponse::success(updated_setting)))
 }
@@ -294,6 +782,13 @@ pub async fn create_setting(
     validate_category(&payload.category)?;
     validate_data_type(&payload.data_type)?;
     validate_setting_key(&payload.setting_key)?;
+    validate_setting_value(
+        &app_state,
+        &payload.category,
+        &payload.setting_key,
+        &payload.setting_value,
+    )
+    .await?;
 
     let category = match payload.category.as_str() {
         "database" => SettingCategory::Database,
@@ -324,6 +819,7 @@ pub async fn create_setting(
             payload.requires_restart.unwrap_or(false),
         )
         .await?;
+    refresh_configuration_cache(&app_state).await;
 
     Ok((StatusCode::CREATED, Json(ApiResponse::success(new_setting))))
 }
@@ -362,6 +858,7 @@ pub async fn delete_setting(
     config_service
         .delete_setting(&category_str, &setting_key)
         .await?;
+    refresh_configuration_cache(&app_state).await;
 
     Ok(Json(ApiResponse::success(())))
 }
@@ -376,6 +873,7 @@ pub async fn delete_setting(
     ),
     responses(
         (status = 200, description = "Setting reset successfully", body = ApiResponse<SystemSettingResponse>),
+        (status = 400, description = "Default value conflicts with related settings", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 403, description = "Insufficient permissions", body = ErrorResponse),
         (status = 404, description = "Setting not found", body = ErrorResponse)
@@ -397,9 +895,31 @@ pub async fn reset_setting(
     validate_setting_key(&setting_key)?;
 
     let config_service = ConfigurationService::new(app_state.db_pool.clone());
+    let current = config_service
+        .get_setting(&category_str, &setting_key)
+        .await?;
+    if let Some(default_value) = current
+        .as_ref()
+        .and_then(|setting| setting.default_value.as_deref())
+    {
+        validate_setting_value(&app_state, &category_str, &setting_key, default_value).await?;
+    }
+
     let reset_setting = config_service
         .reset_setting_to_default(&category_str, &setting_key, None)
         .await?;
+    refresh_configuration_cache(&app_state).await;
+    audit_read_only_change(
+        &app_state,
+        &claims,
+        &category_str,
+        &setting_key,
+        current
+            .as_ref()
+            .map(|setting| setting.setting_value.as_str()),
+        &reset_setting.setting_value,
+    )
+    .await?;
 
     Ok(Json(ApiResponse::success(reset_setting)))
 }
@@ -1,19 +1,17 @@
 use axum::{
     extract::Path,
-    http::{StatusCode, header},
-    response::{Html, IntoResponse, Response},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
 };
-use std::borrow::Cow;
 use tracing::debug;
 
-use crate::embedded_assets::AdminAssets;
+use crate::embedded_assets::{AdminAssets, ResolvedAsset};
 
-pub async fn serve_embedded_admin_html() -> impl IntoResponse {
-    match AdminAssets::get_asset_with_mime("admin/index.html") {
-        Some((content, mime_type)) => {
-            let html = String::from_utf8_lossy(&content);
-            ([(header::CONTENT_TYPE, mime_type)], Html(html.to_string())).into_response()
-        }
+const ADMIN_INDEX: &str = "admin/index.html";
+
+pub async fn serve_embedded_admin_html(headers: HeaderMap) -> impl IntoResponse {
+    match resolve(ADMIN_INDEX, &headers) {
+        Some(asset) => asset_response(asset, &headers),
         None => {
             tracing::warn!("Embedded admin assets not found, this might be a development build");
             (
@@ -25,7 +23,7 @@ pub async fn serve_embedded_admin_html() -> impl IntoResponse {
     }
 }
 
-pub async fn serve_embedded_assets(Path(path): Path<String>) -> Response {
+pub async fn serve_embedded_assets(Path(path): Path<String>, headers: HeaderMap) -> Response {
     debug!("serve_embedded_assets called with path: {}", path);
     let normalized_path = if path.starts_with("admin/") {
         path
@@ -34,13 +32,13 @@ pub async fn serve_embedded_assets(Path(path): Path<String>) -> Response {
     };
     debug!("normalized_path: {}", normalized_path);
 
-    match AdminAssets::get_asset_with_mime(&normalized_path) {
-        Some((content, mime_type)) => {
+    match resolve(&normalized_path, &headers) {
+        Some(asset) => {
             debug!(
-                "Found asset for path: {}, mime_type: {}",
-                normalized_path, mime_type
+                "Found asset for path: {}, mime_type: {}, encoding: {:?}",
+                normalized_path, asset.mime_type, asset.content_encoding
             );
-            create_asset_response(content, mime_type)
+            asset_response(asset, &headers)
         }
         None => {
             debug!(
@@ -49,15 +47,10 @@ pub async fn serve_embedded_assets(Path(path): Path<String>) -> Response {
             );
             if !normalized_path.contains('.') || normalized_path.ends_with('/') {
                 debug!("SPA fallback for path: {}", normalized_path);
-                match AdminAssets::get_asset_with_mime("admin/index.html") {
-                    Some((content, _)) => {
+                match resolve(ADMIN_INDEX, &headers) {
+                    Some(asset) => {
                         debug!("Serving index.html for SPA route: {}", normalized_path);
-                        (
-                            StatusCode::OK,
-                            [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
-                            content.to_vec(),
-                        )
-                            .into_response()
+                        asset_response(asset, &headers)
                     }
                     None => {
                         (StatusCode::NOT_FOUND, "Admin interface not available").into_response()
@@ -70,15 +63,18 @@ pub async fn serve_embedded_assets(Path(path): Path<String>) -> Response {
     }
 }
 
-pub async fn serve_embedded_asset_by_path(Path(asset_path): Path<String>) -> impl IntoResponse {
+pub async fn serve_embedded_asset_by_path(
+    Path(asset_path): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
     let normalized_path = if asset_path.starts_with("admin/") {
         asset_path
     } else {
         format!("admin/{}", asset_path)
     };
 
-    match AdminAssets::get_asset_with_mime(&normalized_path) {
-        Some((content, mime_type)) => create_asset_response(content, mime_type),
+    match resolve(&normalized_path, &headers) {
+        Some(asset) => asset_response(asset, &headers),
         None => (
             StatusCode::NOT_FOUND,
             format!("Asset not found: {}", normalized_path),
@@ -87,23 +83,52 @@ pub async fn serve_embedded_asset_by_path(Path(asset_path): Path<String>) -> imp
     }
 }
 
-pub async fn handle_embedded_admin_routes() -> impl IntoResponse {
-    serve_embedded_admin_html().await
+pub async fn handle_embedded_admin_routes(headers: HeaderMap) -> impl IntoResponse {
+    serve_embedded_admin_html(headers).await
 }
 
-fn create_asset_response(content: Cow<'static, [u8]>, mime_type: &'static str) -> Response {
-    let mut response = Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, mime_type)
-        .header(header::CACHE_CONTROL, "public, max-age=31536000")
-        .body(axum::body::Body::from(content.into_owned()))
-        .unwrap();
+fn resolve(path: &str, headers: &HeaderMap) -> Option<ResolvedAsset> {
+    let accept_encoding = headers
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|value| value.to_str().ok());
+    AdminAssets::resolve(path, accept_encoding)
+}
 
-    response
-        .headers_mut()
-        .insert(header::X_CONTENT_TYPE_OPTIONS, "nosniff".parse().unwrap());
+/// Builds the response for an embedded file, answering `304 Not Modified` when
+/// the client already holds the same representation. A `Content-Encoding` on
+/// precompressed variants also keeps the compression layer from touching them.
+fn asset_response(asset: ResolvedAsset, request_headers: &HeaderMap) -> Response {
+    let not_modified = request_headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| asset.matches_etag(value));
+
+    let mut builder = Response::builder()
+        .header(header::CACHE_CONTROL, asset.cache_control)
+        .header(header::ETAG, &asset.etag)
+        .header(
+            header::X_CONTENT_TYPE_OPTIONS,
+            HeaderValue::from_static("nosniff"),
+        );
+    if asset.vary_encoding {
+        builder = builder.header(header::VARY, header::ACCEPT_ENCODING);
+    }
+
+    let response = if not_modified {
+        builder
+            .status(StatusCode::NOT_MODIFIED)
+            .body(axum::body::Body::empty())
+    } else {
+        if let Some(encoding) = asset.content_encoding {
+            builder = builder.header(header::CONTENT_ENCODING, encoding);
+        }
+        builder
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, asset.mime_type)
+            .body(axum::body::Body::from(asset.data.into_owned()))
+    };
 
-    response
+    response.unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
 }
 
 pub async fn embedded_assets_health() -> impl IntoResponse {
@@ -131,17 +156,50 @@ mod tests {
 
     #[tokio::test]
     async fn test_serve_embedded_admin_html() {
-        let response = serve_embedded_admin_html().await.into_response();
+        let response = serve_embedded_admin_html(HeaderMap::new())
+            .await
+            .into_response();
         assert!(response.status() == StatusCode::OK || response.status() == StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
     async fn test_serve_embedded_assets() {
         let path = "index.html".to_string();
-        let response = serve_embedded_assets(Path(path)).await.into_response();
+        let response = serve_embedded_assets(Path(path), HeaderMap::new())
+            .await
+            .into_response();
         assert!(response.status() == StatusCode::OK || response.status() == StatusCode::NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn test_asset_response_revalidates_with_etag() {
+        let asset = || ResolvedAsset {
+            data: b"console.log(1)".as_slice().into(),
+            mime_type: "application/javascript; charset=utf-8",
+            etag: "\"abc123\"".to_string(),
+            cache_control: crate::embedded_assets::IMMUTABLE_CACHE_CONTROL,
+            content_encoding: Some("br"),
+            vary_encoding: true,
+        };
+
+        let response = asset_response(asset(), &HeaderMap::new());
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::ETAG], "\"abc123\"");
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "br");
+        assert_eq!(response.headers()[header::VARY], "accept-encoding");
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=31536000, immutable"
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, "\"abc123\"".parse().unwrap());
+        let response = asset_response(asset(), &headers);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], "\"abc123\"");
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+    }
+
     #[tokio::test]
     async fn test_embedded_assets_health() {
         let response = embedded_assets_health().await.into_response();
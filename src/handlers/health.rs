@@ -1,11 +1,23 @@
 use crate::AppState;
-use axum::{extract::State, http::StatusCode, response::Json};
+use crate::database::migrations::migration_status;
+use crate::server::MIGRATIONS;
+use crate::services::health_recorder::HealthHistoryResponse;
+use crate::services::health_service::overall_status;
+use crate::services::{ComponentHealth, ConfigurationAccess};
+use crate::utils::{ErrorResponse, LunarbaseError};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json,
+};
 use diesel::prelude::*;
+use diesel_migrations::MigrationHarness;
 use serde_json::{Value, json};
+use std::collections::BTreeMap;
 use std::sync::OnceLock;
 use std::time::SystemTime;
 use sysinfo::System;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 static APP_START_TIME: OnceLock<SystemTime> = OnceLock::new();
 
@@ -19,6 +31,15 @@ pub struct HealthResponse {
     pub database: DatabaseHealth,
     pub memory: MemoryInfo,
     pub system: SystemInfo,
+    pub components: BTreeMap<String, ComponentHealth>,
+    /// Addresses the server accepts connections on
+    pub listeners: Vec<String>,
+}
+
+#[derive(serde::Deserialize, IntoParams)]
+pub struct HealthHistoryQuery {
+    /// Minutes, hours or days such as `30m`, `24h` or `7d`; 24 hours by default
+    pub window: Option<String>,
 }
 
 #[derive(serde::Serialize, ToSchema)]
@@ -74,7 +95,46 @@ pub struct SystemInfo {
                     "cpu_usage": 15.2,
                     "load_average": 0.8,
                     "disk_usage_percentage": 45.0
-                }
+                },
+                "components": {
+                    "backup": {
+                        "status": "degraded",
+                        "message": "Last successful backup at 2024-01-12T02:00:00+00:00 is older than 48 hours",
+                        "latency_ms": 120,
+                        "checked_at": "2024-01-15T10:30:00Z"
+                    },
+                    "collections": {
+                        "status": "degraded",
+                        "message": "1 of 5 collections unhealthy: articles",
+                        "latency_ms": 18,
+                        "checked_at": "2024-01-15T10:28:00Z"
+                    },
+                    "email": {
+                        "status": "healthy",
+                        "message": null,
+                        "latency_ms": 85,
+                        "checked_at": "2024-01-15T10:30:00Z"
+                    },
+                    "oauth": {
+                        "status": "not_configured",
+                        "message": null,
+                        "latency_ms": null,
+                        "checked_at": "2024-01-15T10:30:00Z"
+                    },
+                    "s3": {
+                        "status": "healthy",
+                        "message": "Bucket 'lunarbase' reachable",
+                        "latency_ms": 42,
+                        "checked_at": "2024-01-15T10:30:00Z"
+                    },
+                    "schema": {
+                        "status": "degraded",
+                        "message": "1 orphaned schema object(s) left by interrupted schema changes: records_articles_temp_1699999999",
+                        "latency_ms": null,
+                        "checked_at": "2024-01-15T10:30:00Z"
+                    }
+                },
+                "listeners": ["tcp://0.0.0.0:3000", "tcp://[::]:3000", "unix:/run/lunarbase.sock"]
             })
         ),
         (status = 503, description = "Service is unhealthy", body = Value)
@@ -91,6 +151,7 @@ pub async fn health_check(
     let database_health = check_database_health(&state).await;
     let memory_info = get_memory_info();
     let system_info = get_system_info(&state);
+    let components = state.health_service.check_components().await;
 
     let is_healthy = database_health.status == "healthy";
     let status_code = if is_healthy {
@@ -99,12 +160,13 @@ pub async fn health_check(
         StatusCode::SERVICE_UNAVAILABLE
     };
 
+    let status = overall_status(
+        is_healthy,
+        components.values().map(|health| health.status.as_str()),
+    );
+
     let response = HealthResponse {
-        status: if is_healthy {
-            "healthy".to_string()
-        } else {
-            "unhealthy".to_string()
-        },
+        status: status.to_string(),
         message: "LunarBase health check".to_string(),
         timestamp: chrono::Utc::now().to_rfc3339(),
         version: env!("CARGO_PKG_VERSION").to_string(),
@@ -112,6 +174,8 @@ pub async fn health_check(
         database: database_health,
         memory: memory_info,
         system: system_info,
+        components,
+        listeners: state.listeners.list(),
     };
 
     Ok((status_code, Json(serde_json::to_value(response).unwrap())))
@@ -122,13 +186,19 @@ pub async fn health_check(
     path = "/health",
     tag = "Health",
     responses(
-        (status = 200, description = "Service is healthy with system information", body = Value,
+        (status = 200, description = "Service is healthy with system information; degraded while database migrations are pending", body = Value,
             example = json!({
                 "status": "healthy",
                 "message": "LunarBase is running",
                 "timestamp": "2024-01-15T10:30:00Z",
                 "version": "0.1.0",
                 "uptime": 3600,
+                "migrations": {
+                    "status": "up_to_date",
+                    "current_version": "20251004090000",
+                    "applied": 65,
+                    "pending": []
+                },
                 "memory": {
                     "used_mb": 256.5,
                     "total_mb": 8192.0,
@@ -152,12 +222,31 @@ pub async fn public_health_check(
     let memory_info = get_memory_info();
     let system_info = get_system_info(&state);
 
+    let migrations = state
+        .db_pool
+        .get()
+        .ok()
+        .and_then(|mut conn| migration_status(&mut conn).ok());
+    let status = match &migrations {
+        Some(migrations) if migrations.is_up_to_date() => "healthy",
+        _ => "degraded",
+    };
+
     let response = json!({
-        "status": "healthy",
+        "status": status,
         "message": "LunarBase is running",
         "timestamp": chrono::Utc::now().to_rfc3339(),
         "version": env!("CARGO_PKG_VERSION"),
         "uptime": get_uptime_seconds(),
+        "migrations": match migrations {
+            Some(migrations) => json!({
+                "status": if migrations.is_up_to_date() { "up_to_date" } else { "pending" },
+                "current_version": migrations.current_version,
+                "applied": migrations.applied,
+                "pending": migrations.pending
+            }),
+            None => json!({ "status": "unknown" }),
+        },
         "memory": {
             "used_mb": memory_info.used_mb,
             "total_mb": memory_info.total_mb,
@@ -173,6 +262,67 @@ pub async fn public_health_check(
     Ok((StatusCode::OK, Json(response)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/health/history",
+    tag = "Health",
+    params(
+        HealthHistoryQuery
+    ),
+    responses(
+        (status = 200, description = "Minute-by-minute health samples with uptime per component", body = HealthHistoryResponse,
+            example = json!({
+                "window": "24h",
+                "from": "2024-01-14T10:30:00Z",
+                "to": "2024-01-15T10:30:00Z",
+                "sample_interval_seconds": 60,
+                "persisted": false,
+                "overall": {
+                    "status": "degraded",
+                    "since": "2024-01-15T03:12:00Z",
+                    "previous_status": "healthy",
+                    "uptime_percentage": 69.58,
+                    "healthy_samples": 1002,
+                    "counted_samples": 1440
+                },
+                "components": {
+                    "backup": {
+                        "status": "degraded",
+                        "since": "2024-01-15T03:12:00Z",
+                        "previous_status": "healthy",
+                        "uptime_percentage": 69.58,
+                        "healthy_samples": 1002,
+                        "counted_samples": 1440
+                    },
+                    "database": {
+                        "status": "healthy",
+                        "since": "2024-01-14T10:30:00Z",
+                        "previous_status": null,
+                        "uptime_percentage": 100.0,
+                        "healthy_samples": 1440,
+                        "counted_samples": 1440
+                    }
+                },
+                "samples": [
+                    {
+                        "sampled_at": "2024-01-15T10:30:00Z",
+                        "status": "degraded",
+                        "components": {"backup": "degraded", "database": "healthy"}
+                    }
+                ]
+            })
+        ),
+        (status = 400, description = "Invalid window", body = ErrorResponse)
+    )
+)]
+pub async fn health_history(
+    State(state): State<AppState>,
+    Query(query): Query<HealthHistoryQuery>,
+) -> Result<Json<HealthHistoryResponse>, LunarbaseError> {
+    let window = query.window.as_deref().unwrap_or("24h");
+    Ok(Json(state.health_recorder.history(window).await?))
+}
+
 #[utoipa::path(
     get,
     path = "/health/simple",
@@ -196,6 +346,112 @@ pub async fn simple_health_check() -> Result<(StatusCode, Json<Value>), StatusCo
     Ok((StatusCode::OK, Json(response)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "Health",
+    responses(
+        (status = 200, description = "Process is alive", body = Value,
+            example = json!({
+                "status": "alive",
+                "timestamp": "2024-01-15T10:30:00Z"
+            })
+        )
+    )
+)]
+pub async fn liveness_check() -> (StatusCode, Json<Value>) {
+    let response = json!({
+        "status": "alive",
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    });
+
+    (StatusCode::OK, Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "Health",
+    responses(
+        (status = 200, description = "Service can accept traffic", body = Value,
+            example = json!({
+                "status": "ready",
+                "timestamp": "2024-01-15T10:30:00Z",
+                "checks": {
+                    "database": "ok",
+                    "migrations": "ok",
+                    "maintenance": "ok",
+                    "shutdown": "ok"
+                }
+            })
+        ),
+        (status = 503, description = "Service should not receive traffic", body = Value,
+            example = json!({
+                "status": "not_ready",
+                "timestamp": "2024-01-15T10:30:00Z",
+                "checks": {
+                    "database": "ok",
+                    "migrations": "ok",
+                    "maintenance": "ok",
+                    "shutdown": "draining"
+                }
+            })
+        )
+    )
+)]
+pub async fn readiness_check(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    let shutdown = if state.readiness.is_draining() {
+        "draining"
+    } else {
+        "ok"
+    };
+
+    let (database, migrations) = match state.db_pool.get() {
+        Ok(mut conn) => {
+            let database = match diesel::sql_query("SELECT 1").execute(&mut conn) {
+                Ok(_) => "ok",
+                Err(_) => "unreachable",
+            };
+            let migrations = match conn.has_pending_migration(MIGRATIONS) {
+                Ok(false) => "ok",
+                Ok(true) => "pending",
+                Err(_) => "unknown",
+            };
+            (database, migrations)
+        }
+        Err(_) => ("unreachable", "unknown"),
+    };
+
+    let maintenance = if state.get_maintenance_mode().await {
+        "enabled"
+    } else {
+        "ok"
+    };
+
+    let is_ready = [shutdown, database, migrations, maintenance]
+        .iter()
+        .all(|check| *check == "ok");
+
+    let response = json!({
+        "status": if is_ready { "ready" } else { "not_ready" },
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "checks": {
+            "database": database,
+            "migrations": migrations,
+            "maintenance": maintenance,
+            "shutdown": shutdown
+        }
+    });
+
+    let status_code = if is_ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status_code, Json(response))
+}
+
 async fn check_database_health(state: &AppState) -> DatabaseHealth {
     match state.db_pool.get() {
         Ok(mut conn) => match diesel::sql_query("SELECT 1").execute(&mut conn) {
@@ -1,6 +1,8 @@
 use crate::{
     AppState,
-    utils::{ApiResponse, Claims, LunarbaseError},
+    middleware::{BodyLimit, read_field_limited},
+    services::UploadOrigin,
+    utils::{ApiResponse, Claims, ErrorResponse, LunarbaseError},
 };
 use axum::{
     Extension,
@@ -33,9 +35,9 @@ pub struct ImageUploadResponse {
     ),
     responses(
         (status = 201, description = "Image uploaded successfully", body = ApiResponse<ImageUploadResponse>),
-        (status = 400, description = "Invalid file or missing file", body = ApiResponse<String>),
+        (status = 400, description = "Invalid file or missing file, or `fields.file` with `malware_detected` or `scan_unavailable` when the upload scanner rejects it", body = ApiResponse<String>),
         (status = 401, description = "Unauthorized", body = ApiResponse<String>),
-        (status = 413, description = "File too large", body = ApiResponse<String>),
+        (status = 413, description = "File larger than limits.file_upload_mb, or over the role's daily upload quota", body = ErrorResponse),
         (status = 415, description = "Unsupported media type", body = ApiResponse<String>),
         (status = 500, description = "Internal server error", body = ApiResponse<String>)
     ),
@@ -45,7 +47,7 @@ pub struct ImageUploadResponse {
 )]
 pub async fn upload_image(
     State(state): State<AppState>,
-    Extension(_claims): Extension<Claims>,
+    Extension(claims): Extension<Claims>,
     mut multipart: Multipart,
 ) -> Result<(StatusCode, Json<ApiResponse<ImageUploadResponse>>), LunarbaseError> {
     let s3_service = state.s3_service.as_ref().ok_or_else(|| {
@@ -57,6 +59,7 @@ pub async fn upload_image(
     let mut file_data: Option<Vec<u8>> = None;
     let mut filename: Option<String> = None;
     let mut content_type: Option<String> = None;
+    let file_limit = BodyLimit::file_upload(&state).await;
 
     while let Some(field) = multipart
         .next_field()
@@ -69,12 +72,8 @@ pub async fn upload_image(
             filename = field.file_name().map(|s| s.to_string());
             content_type = field.content_type().map(|s| s.to_string());
 
-            let bytes = field
-                .bytes()
-                .await
-                .map_err(|_| LunarbaseError::BadRequest("Failed to read file data".to_string()))?;
-
-            file_data = Some(bytes.to_vec());
+            file_data =
+                Some(read_field_limited(field, file_limit, "Failed to read file data").await?);
             break;
         }
     }
@@ -88,19 +87,33 @@ pub async fn upload_image(
     let file_name = filename.unwrap_or_else(|| "image".to_string());
     let file_content_type = content_type.unwrap_or_else(|| "application/octet-stream".to_string());
 
-    const MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
-    if file_bytes.len() > MAX_FILE_SIZE {
-        return Err(LunarbaseError::BadRequest(
-            "File too large. Maximum size is 10MB.".to_string(),
-        ));
-    }
-
     if !file_content_type.starts_with("image/") {
         return Err(LunarbaseError::BadRequest(
             "Only image files are allowed.".to_string(),
         ));
     }
 
+    let user_id: Option<i32> = claims.sub.parse().ok();
+    let upload_bytes = file_bytes.len() as i64;
+    if let Some(user_id) = user_id {
+        state
+            .usage_service
+            .check(user_id, &claims.role, 0, upload_bytes)
+            .await?;
+    }
+
+    let origin = UploadOrigin {
+        collection_name: None,
+        field_name: "file",
+        user_id,
+    };
+    state
+        .collection_service
+        .upload_scanner
+        .check(origin, &file_name, &file_content_type, &file_bytes)
+        .await
+        .map_err(|rejection| LunarbaseError::InvalidFields(vec![rejection.field_error("file")]))?;
+
     let upload_result = s3_service
         .upload_file(
             file_bytes.clone(),
@@ -112,6 +125,9 @@ pub async fn upload_image(
             tracing::error!("Failed to upload image to S3: {}", e);
             LunarbaseError::InternalError
         })?;
+    if let Some(user_id) = user_id {
+        state.usage_service.record(user_id, 0, upload_bytes).await;
+    }
 
     let response = ImageUploadResponse {
         url: upload_result.file_url,
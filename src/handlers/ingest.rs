@@ -0,0 +1,182 @@
+use crate::{
+    AppState,
+    models::{
+        CreateIngestEndpointRequest, IngestEndpointResponse, IngestFailureResponse, RecordResponse,
+    },
+    utils::{ApiResponse, Claims, ErrorResponse, LunarbaseError},
+};
+use axum::{
+    Extension,
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ListIngestFailuresQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/ingest-endpoints",
+    tag = "Ingest",
+    request_body = CreateIngestEndpointRequest,
+    responses(
+        (status = 201, description = "Ingest endpoint created; its token is only returned here", body = ApiResponse<IngestEndpointResponse>),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_ingest_endpoint(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<CreateIngestEndpointRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<IngestEndpointResponse>>), LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let user_id: i32 = claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    let endpoint = state
+        .ingest_service
+        .create_endpoint(request, user_id)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(endpoint))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/ingest-endpoints",
+    tag = "Ingest",
+    responses(
+        (status = 200, description = "Ingest endpoints retrieved", body = ApiResponse<Vec<IngestEndpointResponse>>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_ingest_endpoints(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<IngestEndpointResponse>>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let endpoints = state.ingest_service.list_endpoints().await?;
+    Ok(Json(ApiResponse::success(endpoints)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/ingest-endpoints/{id}",
+    tag = "Ingest",
+    params(
+        ("id" = i32, Path, description = "Ingest endpoint ID")
+    ),
+    responses(
+        (status = 200, description = "Ingest endpoint deleted", body = ApiResponse<String>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Ingest endpoint not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_ingest_endpoint(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(endpoint_id): Path<i32>,
+) -> Result<Json<ApiResponse<String>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    state.ingest_service.delete_endpoint(endpoint_id).await?;
+    Ok(Json(ApiResponse::success(
+        "Ingest endpoint deleted successfully".to_string(),
+    )))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/ingest-endpoints/{id}/failures",
+    tag = "Ingest",
+    params(
+        ("id" = i32, Path, description = "Ingest endpoint ID"),
+        ListIngestFailuresQuery
+    ),
+    responses(
+        (status = 200, description = "Dead-letter entries retrieved, newest first", body = ApiResponse<Vec<IngestFailureResponse>>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Ingest endpoint not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_ingest_failures(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(endpoint_id): Path<i32>,
+    Query(query): Query<ListIngestFailuresQuery>,
+) -> Result<Json<ApiResponse<Vec<IngestFailureResponse>>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let failures = state
+        .ingest_service
+        .list_failures(endpoint_id, limit, offset)
+        .await?;
+
+    Ok(Json(ApiResponse::success(failures)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/ingest/{token}",
+    tag = "Ingest",
+    params(
+        ("token" = String, Path, description = "Ingest endpoint token")
+    ),
+    request_body(content = String, description = "Arbitrary JSON payload", content_type = "application/json"),
+    responses(
+        (status = 201, description = "Payload ingested as a record", body = ApiResponse<RecordResponse>),
+        (status = 400, description = "Payload is invalid or does not match the collection schema", body = ErrorResponse),
+        (status = 403, description = "Missing or invalid payload signature", body = ErrorResponse),
+        (status = 404, description = "Ingest endpoint not found", body = ErrorResponse)
+    )
+)]
+pub async fn ingest_payload(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<ApiResponse<RecordResponse>>), LunarbaseError> {
+    let record = state.ingest_service.ingest(&token, &headers, &body).await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(record))))
+}
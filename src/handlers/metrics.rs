@@ -1,4 +1,6 @@
 use crate::AppState;
+use crate::models::PermissionDenialSummary;
+use crate::services::ConfigurationAccess;
 use axum::{extract::State, http::StatusCode};
 use serde::Serialize;
 use utoipa::ToSchema;
@@ -16,6 +18,10 @@ pub async fn get_metrics(State(app_state): State<AppState>) -> Result<String, St
     app_state
         .metrics_state
         .update_database_connections(&app_state.db_pool);
+    app_state
+        .websocket_service
+        .update_queue_depth_metrics()
+        .await;
 
     let websocket_count = app_state.websocket_service.connection_count().await;
     app_state
@@ -30,6 +36,17 @@ pub async fn get_metrics(State(app_state): State<AppState>) -> Result<String, St
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
 }
 
+/// Which collectors are running, so dashboards can tell a disabled collector
+/// from one that measured zero.
+#[derive(Serialize, ToSchema)]
+pub struct MetricsCollectors {
+    pub cpu_sampler: bool,
+    pub cpu_sampler_interval_seconds: u32,
+    /// Request duration histograms; per-route ones follow this after a restart
+    pub route_histograms: bool,
+    pub activity_tracking: bool,
+}
+
 #[derive(Serialize, ToSchema)]
 pub struct MetricsSummary {
     pub http_requests_total: f64,
@@ -41,6 +58,15 @@ pub struct MetricsSummary {
     pub backup_failures_total: f64,
     pub backup_cleanup_operations_total: f64,
     pub backup_files_deleted_total: f64,
+    pub record_cache_hits_total: f64,
+    pub record_cache_misses_total: f64,
+    pub query_cache_hits_total: f64,
+    pub query_cache_misses_total: f64,
+    /// Addresses the server accepts connections on
+    pub listeners: Vec<String>,
+    /// Users refused most often since startup, by collection and action
+    pub top_permission_denials: Vec<PermissionDenialSummary>,
+    pub collectors: MetricsCollectors,
     pub timestamp: String,
 }
 
@@ -61,6 +87,25 @@ pub struct MetricsSummary {
                 "backup_failures_total": 2.0,
                 "backup_cleanup_operations_total": 15.0,
                 "backup_files_deleted_total": 128.0,
+                "record_cache_hits_total": 5120.0,
+                "record_cache_misses_total": 87.0,
+                "query_cache_hits_total": 940.0,
+                "query_cache_misses_total": 63.0,
+                "listeners": ["tcp://127.0.0.1:3000", "unix:/run/lunarbase.sock"],
+                "top_permission_denials": [{
+                    "user_id": 7,
+                    "collection": "invoices",
+                    "action": "delete",
+                    "count": 12,
+                    "sources": { "role": 12 },
+                    "last_denied_at": "2024-01-15T10:29:41Z"
+                }],
+                "collectors": {
+                    "cpu_sampler": true,
+                    "cpu_sampler_interval_seconds": 1,
+                    "route_histograms": true,
+                    "activity_tracking": false
+                },
                 "timestamp": "2024-01-15T10:30:00Z"
             })
         ),
@@ -105,6 +150,9 @@ pub async fn get_metrics_summary(
         .map(|c| c.get())
         .unwrap_or(0.0);
 
+    let record_cache_stats = app_state.collection_service.record_cache.stats();
+    let query_cache_stats = app_state.collection_service.query_cache.stats();
+
     let summary = MetricsSummary {
         http_requests_total: request_count,
         active_websocket_connections: active_connections,
@@ -115,6 +163,20 @@ pub async fn get_metrics_summary(
         backup_failures_total: backup_failures,
         backup_cleanup_operations_total: backup_cleanup_operations,
         backup_files_deleted_total: backup_files_deleted,
+        record_cache_hits_total: record_cache_stats.hits as f64,
+        record_cache_misses_total: record_cache_stats.misses as f64,
+        query_cache_hits_total: query_cache_stats.hits as f64,
+        query_cache_misses_total: query_cache_stats.misses as f64,
+        listeners: app_state.listeners.list(),
+        top_permission_denials: app_state.metrics_state.top_permission_denials(10),
+        collectors: MetricsCollectors {
+            cpu_sampler: app_state.get_metrics_cpu_sampler_enabled().await,
+            cpu_sampler_interval_seconds: app_state
+                .get_metrics_cpu_sampler_interval_seconds()
+                .await,
+            route_histograms: app_state.get_metrics_route_histograms_enabled().await,
+            activity_tracking: app_state.get_activity_tracking_enabled().await,
+        },
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
 
@@ -1,30 +1,46 @@
+pub mod admin;
 pub mod auth;
 pub mod avatar_proxy;
 pub mod backup;
+pub mod batch;
+pub mod collection_templates;
+pub mod collection_views;
 pub mod collections;
 pub mod configuration;
 pub mod embedded_admin;
 pub mod health;
 pub mod image_upload;
+pub mod ingest;
 pub mod metrics;
+pub mod notifications;
 pub mod ownership;
 pub mod permissions;
 pub mod record_permissions;
+pub mod record_shares;
 pub mod users;
 pub mod websocket;
+pub mod workspaces;
 
+pub use admin::*;
 pub use auth::*;
 pub use avatar_proxy::*;
 pub use backup::*;
+pub use batch::*;
+pub use collection_templates::*;
+pub use collection_views::*;
 pub use configuration::*;
 pub use embedded_admin::*;
 pub use health::*;
 pub use image_upload::*;
+pub use ingest::*;
 pub use metrics::*;
+pub use notifications::*;
 pub use ownership::*;
 pub use permissions::*;
 pub use record_permissions::*;
+pub use record_shares::*;
 pub use users::*;
 pub use websocket::*;
+pub use workspaces::*;
 
 pub use auth::{oauth_authorize, oauth_callback, verify_email_get};
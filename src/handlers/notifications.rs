@@ -0,0 +1,121 @@
+use crate::{
+    AppState,
+    models::{
+        ListNotificationsQuery, MarkNotificationsReadRequest, MarkNotificationsReadResponse,
+        NotificationListResponse, NotificationPush,
+    },
+    utils::{ApiResponse, Claims, ErrorResponse, LunarbaseError},
+};
+use axum::{
+    Extension,
+    extract::{Query, State},
+    response::{
+        Json,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures_util::stream::{self, Stream, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
+
+fn notification_user_id(claims: &Claims) -> Result<i32, LunarbaseError> {
+    claims.sub.parse().map_err(|_| LunarbaseError::TokenInvalid)
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/me/notifications",
+    tag = "Users",
+    params(
+        ("unread_only" = Option<bool>, Query, description = "Only return unread notifications"),
+        ("limit" = Option<i64>, Query, description = "Number of notifications, at most 100 (default 20)"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination")
+    ),
+    responses(
+        (status = 200, description = "The caller's notifications, newest first, with the unread count", body = ApiResponse<NotificationListResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_my_notifications(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<ListNotificationsQuery>,
+) -> Result<Json<ApiResponse<NotificationListResponse>>, LunarbaseError> {
+    let user_id = notification_user_id(&claims)?;
+    let notifications = state.notification_service.list(user_id, &query).await?;
+    Ok(Json(ApiResponse::success(notifications)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/notifications/read",
+    tag = "Users",
+    request_body = MarkNotificationsReadRequest,
+    responses(
+        (status = 200, description = "Notifications marked as read; the new unread count is also pushed to the caller's open connections", body = ApiResponse<MarkNotificationsReadResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn mark_my_notifications_read(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<MarkNotificationsReadRequest>,
+) -> Result<Json<ApiResponse<MarkNotificationsReadResponse>>, LunarbaseError> {
+    let user_id = notification_user_id(&claims)?;
+    let response = state
+        .notification_service
+        .mark_read(user_id, request.ids.as_deref())
+        .await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/me/notifications/stream",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Server-sent `notification` events carrying the same payload as the WebSocket `Notification` message, starting with the current unread count", body = String, content_type = "text/event-stream"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn stream_my_notifications(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, LunarbaseError> {
+    let user_id = notification_user_id(&claims)?;
+
+    // Subscribe before counting so nothing pushed in between is missed
+    let receiver = state.websocket_service.subscribe_notifications();
+    let unread_count = state.notification_service.unread_count(user_id).await?;
+
+    let pushes = stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok((recipient, push)) if recipient == user_id => return Some((push, receiver)),
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!(
+                        "Notification stream of user {} skipped {} pushes",
+                        user_id,
+                        skipped
+                    );
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    let events = stream::once(async move { NotificationPush::UnreadCount { unread_count } })
+        .chain(pushes)
+        .map(|push| Event::default().event("notification").json_data(push));
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
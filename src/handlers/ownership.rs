@@ -10,7 +10,7 @@ use utoipa::ToSchema;
 
 use crate::{
     AppState,
-    models::User,
+    models::{GlobalOwnershipStats, Permission, User},
     utils::{ApiResponse, Claims, LunarbaseError},
 };
 
@@ -28,11 +28,12 @@ async fn claims_to_user(claims: &Claims, state: &AppState) -> Result<User, Lunar
         .get()
         .map_err(|_| LunarbaseError::InternalError)?;
 
-    users::table
+    let user = users::table
         .filter(users::id.eq(user_id))
         .select(User::as_select())
         .first(&mut conn)
-        .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))
+        .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
+    Ok(claims.scope_user(user))
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -52,7 +53,7 @@ pub struct GetOwnedRecordsQuery {
     tag = "Ownership",
     params(
         ("collection_name" = String, Path, description = "Collection name"),
-        ("record_id" = i32, Path, description = "Record ID")
+        ("record_id" = String, Path, description = "Record ID")
     ),
     request_body = TransferOwnershipRequest,
     responses(
@@ -68,27 +69,38 @@ pub struct GetOwnedRecordsQuery {
 pub async fn transfer_record_ownership(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
-    Path((collection_name, record_id)): Path<(String, i32)>,
+    Path((collection_name, record_id)): Path<(String, String)>,
     Json(request): Json<TransferOwnershipRequest>,
 ) -> Result<Json<ApiResponse<Value>>, LunarbaseError> {
     let user = claims_to_user(&claims, &state).await?;
+    let record_id = state
+        .collection_service
+        .resolve_record_id(&collection_name, &record_id)
+        .await?;
 
     let record = state
         .collection_service
-        .get_record(&collection_name, record_id)
+        .get_record(&collection_name, &record_id)
         .await
         .map_err(|_| LunarbaseError::NotFound("Record not found".to_string()))?;
 
-    state
+    let transferred = state
         .ownership_service
         .transfer_ownership(
             &user,
             &record,
             request.new_owner_id,
             &collection_name,
-            record_id,
+            &record_id,
         )
-        .await?;
+        .await;
+    if let Err(LunarbaseError::InsufficientPermissions) = transferred {
+        state
+            .permission_audit_service
+            .record_ownership_denial(&user, &collection_name, Permission::Update)
+            .await;
+    }
+    transferred?;
 
     Ok(Json(ApiResponse::success(json!({
         "message": "Ownership transferred successfully",
@@ -134,7 +146,7 @@ pub async fn get_my_owned_records(
     for record_id in owned_record_ids {
         if let Ok(record) = state
             .collection_service
-            .get_record(&collection_name, record_id)
+            .get_record(&collection_name, &record_id)
             .await
         {
             owned_records.push(record);
@@ -201,7 +213,7 @@ pub async fn get_user_owned_records(
     for record_id in owned_record_ids {
         if let Ok(record) = state
             .collection_service
-            .get_record(&collection_name, record_id)
+            .get_record(&collection_name, &record_id)
             .await
         {
             owned_records.push(record);
@@ -223,7 +235,7 @@ pub async fn get_user_owned_records(
     tag = "Ownership",
     params(
         ("collection_name" = String, Path, description = "Collection name"),
-        ("record_id" = i32, Path, description = "Record ID")
+        ("record_id" = String, Path, description = "Record ID")
     ),
     responses(
         (status = 200, description = "Ownership status retrieved successfully", body = ApiResponse<Value>),
@@ -237,13 +249,17 @@ pub async fn get_user_owned_records(
 pub async fn check_record_ownership(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
-    Path((collection_name, record_id)): Path<(String, i32)>,
+    Path((collection_name, record_id)): Path<(String, String)>,
 ) -> Result<Json<ApiResponse<Value>>, LunarbaseError> {
     let user = claims_to_user(&claims, &state).await?;
+    let record_id = state
+        .collection_service
+        .resolve_record_id(&collection_name, &record_id)
+        .await?;
 
     let record = state
         .collection_service
-        .get_record(&collection_name, record_id)
+        .get_record(&collection_name, &record_id)
         .await
         .map_err(|_| LunarbaseError::NotFound("Record not found".to_string()))?;
 
@@ -337,7 +353,9 @@ pub async fn get_ownership_stats(
         .get()
         .map_err(|_| LunarbaseError::InternalError)?;
 
-    let table_name = format!("records_{}", collection_name);
+    let table_name = state
+        .collection_service
+        .get_records_table_name(&collection_name);
 
     let total_records_query = format!("SELECT COUNT(*) as count FROM {}", table_name);
 
@@ -384,3 +402,50 @@ pub async fn get_ownership_stats(
         "timestamp": chrono::Utc::now().to_rfc3339()
     }))))
 }
+
+const DEFAULT_ORPHAN_LIMIT: usize = 50;
+const MAX_ORPHAN_LIMIT: usize = 500;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GlobalOwnershipStatsQuery {
+    /// Maximum number of orphaned records to list (default 50, max 500)
+    pub orphan_limit: Option<usize>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/ownership/stats/global",
+    tag = "Ownership",
+    params(
+        ("orphan_limit" = Option<usize>, Query, description = "Maximum number of orphaned records to list (default 50, max 500)")
+    ),
+    responses(
+        (status = 200, description = "Global ownership statistics retrieved successfully", body = ApiResponse<GlobalOwnershipStats>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions - Admin only", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_global_ownership_stats(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<GlobalOwnershipStatsQuery>,
+) -> Result<Json<ApiResponse<GlobalOwnershipStats>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let orphan_limit = params
+        .orphan_limit
+        .unwrap_or(DEFAULT_ORPHAN_LIMIT)
+        .min(MAX_ORPHAN_LIMIT);
+
+    let stats = state
+        .ownership_service
+        .get_global_ownership_stats(orphan_limit)
+        .await?;
+
+    Ok(Json(ApiResponse::success(stats)))
+}
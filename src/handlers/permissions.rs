@@ -4,15 +4,20 @@ use axum::{
     extract::{Path, Query, State},
     response::Json,
 };
+use serde::Deserialize;
 use serde_json::{Value, json};
 use std::collections::HashMap;
+use utoipa::ToSchema;
 
 use crate::{
     AppState,
     models::{
-        CollectionPermission, CreateRoleRequest, Role, SetCollectionPermissionRequest,
+        AccessFilter, CollectionPermission, CollectionUserAccessPage, CreateRoleRequest,
+        DefaultCollectionPermissions, NotificationContent, NotificationKind, PermissionPolicy,
+        PolicyImportReport, Role, RoleCollectionPermission, SetCollectionPermissionRequest,
         SetUserCollectionPermissionRequest, UpdateRoleRequest, User, UserCollectionPermission,
     },
+    services::{ConfigurationService, configuration_manager::ConfigurationAccess},
     utils::{ApiResponse, Claims, LunarbaseError},
 };
 
@@ -30,11 +35,12 @@ async fn claims_to_user(claims: &Claims, state: &AppState) -> Result<User, Lunar
         .get()
         .map_err(|_| LunarbaseError::InternalError)?;
 
-    users::table
+    let user = users::table
         .filter(users::id.eq(user_id))
         .select(User::as_select())
         .first(&mut conn)
-        .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))
+        .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
+    Ok(claims.scope_user(user))
 }
 
 #[utoipa::path(
@@ -65,7 +71,12 @@ pub async fn create_role(
         .validate()
         .map_err(LunarbaseError::ValidationError)?;
 
-    let role = state.permission_service.create_role(&role_request).await?;
+    // Roles created by workspace admins are only usable in their workspace
+    let workspace_id = claims.workspace_id.filter(|_| !claims.is_superadmin());
+    let role = state
+        .permission_service
+        .create_role(&role_request, workspace_id)
+        .await?;
 
     Ok(Json(ApiResponse::success(role)))
 }
@@ -91,7 +102,10 @@ pub async fn list_roles(
         return Err(LunarbaseError::InsufficientPermissions);
     }
 
-    let roles = state.permission_service.list_roles().await?;
+    let mut roles = state.permission_service.list_roles().await?;
+    if let Some(workspace_id) = claims.workspace_id {
+        roles.retain(|role| role.workspace_id.is_none_or(|id| id == workspace_id));
+    }
 
     Ok(Json(ApiResponse::success(roles)))
 }
@@ -215,7 +229,7 @@ pub async fn update_role(
         ("collection_name" = String, Path, description = "Collection name")
     ),
     responses(
-        (status = 200, description = "Role collection permission retrieved successfully", body = ApiResponse<CollectionPermission>),
+        (status = 200, description = "Role collection permission retrieved successfully, possibly inherited from a lower-priority role", body = ApiResponse<RoleCollectionPermission>),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Insufficient permissions"),
         (status = 404, description = "Role or collection not found")
@@ -229,7 +243,7 @@ pub async fn get_role_collection_permission(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
     Path((role_name, collection_name)): Path<(String, String)>,
-) -> Result<Json<ApiResponse<CollectionPermission>>, LunarbaseError> {
+) -> Result<Json<ApiResponse<RoleCollectionPermission>>, LunarbaseError> {
     if claims.role != "admin" {
         return Err(LunarbaseError::InsufficientPermissions);
     }
@@ -242,7 +256,7 @@ pub async fn get_role_collection_permission(
 
     let permission = state
         .permission_service
-        .get_role_collection_permission(&role_name, collection.id)
+        .get_effective_role_collection_permission(&role_name, collection.id)
         .await?;
 
     if let Some(permission) = permission {
@@ -336,14 +350,19 @@ pub async fn get_collection_permissions(
     if let Some(role_name) = params.get("role_name") {
         let permission = state
             .permission_service
-            .get_role_collection_permission(role_name, collection.id)
+            .get_effective_role_collection_permission(role_name, collection.id)
             .await?;
 
-        if let Some(permission) = permission {
+        if let Some(RoleCollectionPermission {
+            permission,
+            inherited_from,
+        }) = permission
+        {
             Ok(Json(ApiResponse::success(json!({
                 "collection_id": collection.id,
                 "collection_name": collection.name,
                 "role_name": role_name,
+                "inherited_from": inherited_from,
                 "permissions": {
                     "can_create": permission.can_create,
                     "can_read": permission.can_read,
@@ -526,6 +545,13 @@ pub async fn get_user_accessible_collections(
             .get_collection_by_id(collection_id)
             .await
         {
+            if claims
+                .workspace_id
+                .is_some_and(|workspace_id| workspace_id != collection.workspace_id)
+            {
+                continue;
+            }
+
             let permissions = state
                 .permission_service
                 .get_user_collection_permissions(&user, collection_id)
@@ -614,3 +640,234 @@ pub async fn check_permission(
         "has_permission": has_permission
     }))))
 }
+
+#[utoipa::path(
+    get,
+    path = "/permissions/defaults",
+    tag = "Permissions",
+    responses(
+        (status = 200, description = "Permissions given to each role on new collections", body = ApiResponse<DefaultCollectionPermissions>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions - Admin only", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_default_permissions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<DefaultCollectionPermissions>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let defaults = state.get_default_collection_permissions().await;
+    Ok(Json(ApiResponse::success(defaults)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/permissions/defaults",
+    tag = "Permissions",
+    request_body = DefaultCollectionPermissions,
+    responses(
+        (status = 200, description = "Default permissions updated; applies to collections created from now on", body = ApiResponse<DefaultCollectionPermissions>),
+        (status = 400, description = "The admin role must keep full access", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions - Admin only", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_default_permissions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<DefaultCollectionPermissions>,
+) -> Result<Json<ApiResponse<DefaultCollectionPermissions>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    request
+        .validate()
+        .map_err(LunarbaseError::ValidationError)?;
+
+    let config_service = ConfigurationService::new(state.db_pool.clone());
+    for (key, permissions) in [
+        ("default_permissions_admin", request.admin),
+        ("default_permissions_user", request.user),
+        ("default_permissions_guest", request.guest),
+        ("default_permissions_custom", request.custom),
+    ] {
+        let value =
+            serde_json::to_string(&permissions).map_err(|_| LunarbaseError::InternalError)?;
+        config_service
+            .update_setting("system", key, &value, Some(claims.email.clone()))
+            .await?;
+    }
+
+    if let Err(e) = state.configuration_manager.reload_cache().await {
+        tracing::warn!("Failed to reload configuration cache: {:?}", e);
+    }
+
+    Ok(Json(ApiResponse::success(request)))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CollectionUsersQuery {
+    /// `read`, `write` or `any` (default)
+    #[serde(default)]
+    pub access: AccessFilter,
+    #[schema(example = 50, minimum = 1, maximum = 200)]
+    pub limit: Option<i64>,
+    #[schema(example = 0, minimum = 0)]
+    pub offset: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/permissions/collections/{name}/users",
+    tag = "Permissions",
+    params(
+        ("name" = String, Path, description = "Collection name"),
+        ("access" = Option<AccessFilter>, Query, description = "Only users with read, write or any access (default any)"),
+        ("limit" = Option<i64>, Query, description = "Users per page (default 50, max 200)"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination")
+    ),
+    responses(
+        (status = 200, description = "Users with effective access to the collection", body = ApiResponse<CollectionUserAccessPage>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions - Admin only", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_collection_users(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(collection_name): Path<String>,
+    Query(query): Query<CollectionUsersQuery>,
+) -> Result<Json<ApiResponse<CollectionUserAccessPage>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let collection = state
+        .collection_service
+        .get_collection(&collection_name)
+        .await
+        .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+
+    let page = state
+        .permission_service
+        .list_collection_users(
+            collection.id,
+            query.access,
+            query.limit.unwrap_or(50).clamp(1, 200),
+            query.offset.unwrap_or(0).max(0),
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success(page)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/permissions/export",
+    tag = "Permissions",
+    responses(
+        (status = 200, description = "Shared roles, role permissions and user overrides of every collection as a policy document", body = ApiResponse<PermissionPolicy>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions - Admin only", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn export_permissions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<PermissionPolicy>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let policy = state.permission_service.export_policy().await?;
+    Ok(Json(ApiResponse::success(policy)))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportPermissionsQuery {
+    /// Only report the changes and errors without applying them
+    #[serde(default)]
+    #[schema(example = true)]
+    pub dry_run: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/permissions/import",
+    tag = "Permissions",
+    params(
+        ("dry_run" = Option<bool>, Query, description = "Only report the changes and errors without applying them (default false)")
+    ),
+    request_body = PermissionPolicy,
+    responses(
+        (status = 200, description = "Changes the document makes; applied in one transaction unless dry_run", body = ApiResponse<PolicyImportReport>),
+        (status = 400, description = "The document has errors, each prefixed with its location", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions - Admin only", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn import_permissions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<ImportPermissionsQuery>,
+    Json(policy): Json<PermissionPolicy>,
+) -> Result<Json<ApiResponse<PolicyImportReport>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let admin_id: i32 = claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    let report = state
+        .permission_service
+        .import_policy(&policy, query.dry_run, Some(admin_id))
+        .await?;
+
+    tracing::info!(
+        "Admin {} ({}) imported a permission policy{}: {} change(s)",
+        claims.sub,
+        claims.email,
+        if query.dry_run { " (dry run)" } else { "" },
+        report.changes.len()
+    );
+
+    if !query.dry_run {
+        state
+            .notification_service
+            .try_notify(
+                admin_id,
+                NotificationContent::new(
+                    NotificationKind::ImportFinished,
+                    "Permission policy import finished",
+                )
+                .with_body(format!("{} change(s) applied", report.changes.len()))
+                .with_data(json!({ "changes": report.changes.len() })),
+            )
+            .await;
+    }
+
+    Ok(Json(ApiResponse::success(report)))
+}
@@ -1,14 +1,16 @@
 use crate::utils::ErrorResponse;
 use axum::{
     Extension,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::Json,
 };
+use serde::Deserialize;
 use serde_json::{Value, json};
+use utoipa::ToSchema;
 
 use crate::{
     AppState,
-    models::{RecordPermission, SetRecordPermissionRequest, User},
+    models::{RecordPermission, SetRecordPermissionRequest, User, UserRecordGrantPage},
     utils::{ApiResponse, Claims, LunarbaseError},
 };
 
@@ -26,11 +28,12 @@ async fn claims_to_user(claims: &Claims, state: &AppState) -> Result<User, Lunar
         .get()
         .map_err(|_| LunarbaseError::InternalError)?;
 
-    users::table
+    let user = users::table
         .filter(users::id.eq(user_id))
         .select(User::as_select())
         .first(&mut conn)
-        .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))
+        .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
+    Ok(claims.scope_user(user))
 }
 
 #[utoipa::path(
@@ -54,8 +57,8 @@ async fn claims_to_user(claims: &Claims, state: &AppState) -> Result<User, Lunar
 pub async fn set_record_permission(
     State(state): State<AppState>,
     Extension(admin_claims): Extension<Claims>,
-    Path((collection_name, record_id)): Path<(String, i32)>,
-    Json(permission_request): Json<SetRecordPermissionRequest>,
+    Path((collection_name, record_id)): Path<(String, String)>,
+    Json(mut permission_request): Json<SetRecordPermissionRequest>,
 ) -> Result<Json<ApiResponse<RecordPermission>>, LunarbaseError> {
     if admin_claims.role != "admin" {
         return Err(LunarbaseError::InsufficientPermissions);
@@ -66,16 +69,29 @@ pub async fn set_record_permission(
         .get_collection(&collection_name)
         .await
         .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+    let record_id = collection
+        .id_type
+        .normalize(&record_id)
+        .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
 
     let _record = state
         .collection_service
-        .get_record(&collection_name, record_id)
+        .get_record(&collection_name, &record_id)
         .await
         .map_err(|_| LunarbaseError::NotFound("Record not found".to_string()))?;
 
+    permission_request.record_id = collection
+        .id_type
+        .normalize(&permission_request.record_id)
+        .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
+
+    let admin_id: i32 = admin_claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
     let permission = state
         .permission_service
-        .set_record_permission(collection.id, &permission_request)
+        .set_record_permission(collection.id, &permission_request, admin_id)
         .await?;
 
     Ok(Json(ApiResponse::success(permission)))
@@ -101,7 +117,7 @@ pub async fn set_record_permission(
 pub async fn get_record_permissions(
     State(state): State<AppState>,
     Extension(requesting_claims): Extension<Claims>,
-    Path((collection_name, record_id, user_id)): Path<(String, i32, i32)>,
+    Path((collection_name, record_id, user_id)): Path<(String, String, i32)>,
 ) -> Result<Json<ApiResponse<Value>>, LunarbaseError> {
     let requesting_user_id: i32 = requesting_claims
         .sub
@@ -117,10 +133,14 @@ pub async fn get_record_permissions(
         .get_collection(&collection_name)
         .await
         .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+    let record_id = collection
+        .id_type
+        .normalize(&record_id)
+        .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
 
     let _record = state
         .collection_service
-        .get_record(&collection_name, record_id)
+        .get_record(&collection_name, &record_id)
         .await
         .map_err(|_| LunarbaseError::NotFound("Record not found".to_string()))?;
 
@@ -146,7 +166,7 @@ pub async fn get_record_permissions(
         .check_record_permission(
             &target_user,
             collection.id,
-            record_id,
+            &record_id,
             crate::models::Permission::Read,
         )
         .await?;
@@ -156,7 +176,7 @@ pub async fn get_record_permissions(
         .check_record_permission(
             &target_user,
             collection.id,
-            record_id,
+            &record_id,
             crate::models::Permission::Update,
         )
         .await?;
@@ -166,7 +186,7 @@ pub async fn get_record_permissions(
         .check_record_permission(
             &target_user,
             collection.id,
-            record_id,
+            &record_id,
             crate::models::Permission::Delete,
         )
         .await?;
@@ -190,7 +210,7 @@ pub async fn get_record_permissions(
     tag = "Record Permissions",
     params(
         ("collection_name" = String, Path, description = "Collection name"),
-        ("record_id" = i32, Path, description = "Record ID"),
+        ("record_id" = String, Path, description = "Record ID"),
         ("user_id" = i32, Path, description = "User ID")
     ),
     responses(
@@ -206,8 +226,8 @@ pub async fn get_record_permissions(
 pub async fn get_user_record_permissions(
     State(state): State<AppState>,
     Extension(admin_claims): Extension<Claims>,
-    Path((collection_name, record_id)): Path<(String, i32)>,
-    Json(permission_request): Json<SetRecordPermissionRequest>,
+    Path((collection_name, record_id)): Path<(String, String)>,
+    Json(mut permission_request): Json<SetRecordPermissionRequest>,
 ) -> Result<Json<ApiResponse<RecordPermission>>, LunarbaseError> {
     if admin_claims.role != "admin" {
         return Err(LunarbaseError::InsufficientPermissions);
@@ -218,16 +238,29 @@ pub async fn get_user_record_permissions(
         .get_collection(&collection_name)
         .await
         .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+    let record_id = collection
+        .id_type
+        .normalize(&record_id)
+        .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
 
     let _record = state
         .collection_service
-        .get_record(&collection_name, record_id)
+        .get_record(&collection_name, &record_id)
         .await
         .map_err(|_| LunarbaseError::NotFound("Record not found".to_string()))?;
 
+    permission_request.record_id = collection
+        .id_type
+        .normalize(&permission_request.record_id)
+        .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
+
+    let admin_id: i32 = admin_claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
     let permission = state
         .permission_service
-        .set_record_permission(collection.id, &permission_request)
+        .set_record_permission(collection.id, &permission_request, admin_id)
         .await?;
 
     Ok(Json(ApiResponse::success(permission)))
@@ -239,7 +272,7 @@ pub async fn get_user_record_permissions(
     tag = "Record Permissions",
     params(
         ("collection_name" = String, Path, description = "Collection name"),
-        ("record_id" = i32, Path, description = "Record ID"),
+        ("record_id" = String, Path, description = "Record ID"),
         ("user_id" = i32, Path, description = "User ID")
     ),
     responses(
@@ -255,7 +288,7 @@ pub async fn get_user_record_permissions(
 pub async fn remove_record_permission(
     State(state): State<AppState>,
     Extension(admin_claims): Extension<Claims>,
-    Path((collection_name, record_id, user_id)): Path<(String, i32, i32)>,
+    Path((collection_name, record_id, user_id)): Path<(String, String, i32)>,
 ) -> Result<Json<ApiResponse<Value>>, LunarbaseError> {
     if admin_claims.role != "admin" {
         return Err(LunarbaseError::InsufficientPermissions);
@@ -266,16 +299,20 @@ pub async fn remove_record_permission(
         .get_collection(&collection_name)
         .await
         .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+    let record_id = collection
+        .id_type
+        .normalize(&record_id)
+        .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
 
     let _record = state
         .collection_service
-        .get_record(&collection_name, record_id)
+        .get_record(&collection_name, &record_id)
         .await
         .map_err(|_| LunarbaseError::NotFound("Record not found".to_string()))?;
 
     state
         .permission_service
-        .remove_record_permission(collection.id, record_id, user_id)
+        .remove_record_permission(collection.id, &record_id, user_id)
         .await?;
 
     Ok(Json(ApiResponse::success(json!({
@@ -306,7 +343,7 @@ pub async fn remove_record_permission(
 pub async fn list_record_permissions(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
-    Path((collection_name, record_id)): Path<(String, i32)>,
+    Path((collection_name, record_id)): Path<(String, String)>,
 ) -> Result<Json<ApiResponse<Value>>, LunarbaseError> {
     if claims.role != "admin" {
         return Err(LunarbaseError::InsufficientPermissions);
@@ -317,16 +354,20 @@ pub async fn list_record_permissions(
         .get_collection(&collection_name)
         .await
         .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+    let record_id = collection
+        .id_type
+        .normalize(&record_id)
+        .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
 
     let _record = state
         .collection_service
-        .get_record(&collection_name, record_id)
+        .get_record(&collection_name, &record_id)
         .await
         .map_err(|_| LunarbaseError::NotFound("Record not found".to_string()))?;
 
     let permissions_list = state
         .permission_service
-        .list_record_permissions(collection.id, record_id)
+        .list_record_permissions(collection.id, &record_id)
         .await?;
 
     Ok(Json(ApiResponse::success(json!({
@@ -343,7 +384,7 @@ pub async fn list_record_permissions(
     tag = "Record Permissions",
     params(
         ("collection_name" = String, Path, description = "Collection name"),
-        ("record_id" = i32, Path, description = "Record ID")
+        ("record_id" = String, Path, description = "Record ID")
     ),
     responses(
         (status = 200, description = "Record ownership permissions checked successfully", body = ApiResponse<Value>),
@@ -357,18 +398,22 @@ pub async fn list_record_permissions(
 pub async fn check_record_ownership_permissions(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
-    Path((collection_name, record_id)): Path<(String, i32)>,
+    Path((collection_name, record_id)): Path<(String, String)>,
 ) -> Result<Json<ApiResponse<Value>>, LunarbaseError> {
     let user = claims_to_user(&claims, &state).await?;
-    let _collection = state
+    let collection = state
         .collection_service
         .get_collection(&collection_name)
         .await
         .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+    let record_id = collection
+        .id_type
+        .normalize(&record_id)
+        .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
 
     let record = state
         .collection_service
-        .get_record(&collection_name, record_id)
+        .get_record(&collection_name, &record_id)
         .await
         .map_err(|_| LunarbaseError::NotFound("Record not found".to_string()))?;
 
@@ -385,3 +430,128 @@ pub async fn check_record_ownership_permissions(
         "can_access_as_owner": is_owner
     }))))
 }
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UserRecordGrantsQuery {
+    #[schema(example = 50, minimum = 1, maximum = 200)]
+    pub limit: Option<i64>,
+    #[schema(example = 0, minimum = 0)]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RevokeUserRecordGrantsQuery {
+    /// Only revoke permissions on records of this collection
+    pub collection: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/permissions/users/{user_id}/records",
+    tag = "Record Permissions",
+    params(
+        ("user_id" = i32, Path, description = "User ID"),
+        ("limit" = Option<i64>, Query, description = "Permissions per page (default 50, max 200)"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination")
+    ),
+    responses(
+        (status = 200, description = "Explicit record permissions of the user across collections", body = ApiResponse<UserRecordGrantPage>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions - Admin only", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_user_record_permissions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<i32>,
+    Query(query): Query<UserRecordGrantsQuery>,
+) -> Result<Json<ApiResponse<UserRecordGrantPage>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+    let admin_id: i32 = claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    // Workspace admins only see permissions on their workspace's records
+    let workspace_id = claims.workspace_id.filter(|_| !claims.is_superadmin());
+    let page = state
+        .permission_service
+        .list_user_record_grants(
+            admin_id,
+            user_id,
+            workspace_id,
+            query.limit.unwrap_or(50).clamp(1, 200),
+            query.offset.unwrap_or(0).max(0),
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success(page)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/permissions/users/{user_id}/records",
+    tag = "Record Permissions",
+    params(
+        ("user_id" = i32, Path, description = "User ID"),
+        ("collection" = Option<String>, Query, description = "Only revoke permissions in this collection")
+    ),
+    responses(
+        (status = 200, description = "Record permissions revoked", body = ApiResponse<Value>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions - Admin only", body = ErrorResponse),
+        (status = 404, description = "User or collection not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn revoke_user_record_permissions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<i32>,
+    Query(query): Query<RevokeUserRecordGrantsQuery>,
+) -> Result<Json<ApiResponse<Value>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+    let admin_id: i32 = claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    // Workspace admins only revoke permissions on their workspace's records
+    let workspace_id = claims.workspace_id.filter(|_| !claims.is_superadmin());
+    let collection = match &query.collection {
+        Some(name) => Some(
+            state
+                .collection_service
+                .get_collection(name)
+                .await
+                .ok()
+                .filter(|collection| {
+                    workspace_id.is_none_or(|workspace_id| collection.workspace_id == workspace_id)
+                })
+                .ok_or_else(|| LunarbaseError::NotFound("Collection not found".to_string()))?,
+        ),
+        None => None,
+    };
+
+    let revoked = state
+        .permission_service
+        .revoke_user_record_grants(admin_id, user_id, workspace_id, collection.as_ref())
+        .await?;
+
+    Ok(Json(ApiResponse::success(json!({
+        "message": "Record permissions revoked",
+        "user_id": user_id,
+        "collection_name": query.collection,
+        "revoked": revoked
+    }))))
+}
@@ -0,0 +1,172 @@
+use crate::{
+    AppState,
+    handlers::collections::claims_to_user,
+    models::{
+        CreateRecordShareRequest, Permission, RecordShareResponse, SharedRecordResponse,
+        USERS_SYSTEM_COLLECTION,
+    },
+    utils::{ApiResponse, Claims, ErrorResponse, LunarbaseError},
+};
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+
+const SHARE_PASSWORD_HEADER: &str = "x-share-password";
+
+#[utoipa::path(
+    post,
+    path = "/collections/{collection_name}/records/{record_id}/share",
+    tag = "Records",
+    params(
+        ("collection_name" = String, Path, description = "Collection name"),
+        ("record_id" = String, Path, description = "Record ID")
+    ),
+    request_body = CreateRecordShareRequest,
+    responses(
+        (status = 201, description = "Share link created; the token is only returned here", body = ApiResponse<RecordShareResponse>),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions to read the record", body = ErrorResponse),
+        (status = 404, description = "Collection or record not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_record_share(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((collection_name, record_id)): Path<(String, String)>,
+    Json(request): Json<CreateRecordShareRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<RecordShareResponse>>), LunarbaseError> {
+    if collection_name == USERS_SYSTEM_COLLECTION {
+        return Err(LunarbaseError::BadRequest(
+            "User records cannot be shared".to_string(),
+        ));
+    }
+
+    let user = claims_to_user(&claims, &state).await?;
+    let collection = state
+        .collection_service
+        .get_collection(&collection_name)
+        .await?;
+    let record_id = state
+        .collection_service
+        .resolve_record_id(&collection_name, &record_id)
+        .await?;
+
+    let can_read = state
+        .permission_service
+        .check_record_permission(&user, collection.id, &record_id, Permission::Read)
+        .await?;
+    if !can_read {
+        state
+            .permission_audit_service
+            .record_denial(&user, &collection, Some(&record_id), Permission::Read)
+            .await;
+        return Err(LunarbaseError::RecordPermissionDenied(Permission::Read));
+    }
+
+    let share = state
+        .record_share_service
+        .create_share(&collection_name, &record_id, request, user.id)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(share))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/shares",
+    tag = "Records",
+    responses(
+        (status = 200, description = "Share links created by the caller, newest first", body = ApiResponse<Vec<RecordShareResponse>>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_record_shares(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<RecordShareResponse>>>, LunarbaseError> {
+    let user_id: i32 = claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    let shares = state.record_share_service.list_shares(user_id).await?;
+    Ok(Json(ApiResponse::success(shares)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/shares/{id}",
+    tag = "Records",
+    params(
+        ("id" = i32, Path, description = "Share link ID")
+    ),
+    responses(
+        (status = 200, description = "Share link revoked", body = ApiResponse<String>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Share link not found or created by someone else", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn revoke_record_share(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(share_id): Path<i32>,
+) -> Result<Json<ApiResponse<String>>, LunarbaseError> {
+    let user_id: i32 = claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    state
+        .record_share_service
+        .revoke_share(share_id, user_id, claims.role == "admin")
+        .await?;
+
+    Ok(Json(ApiResponse::success(
+        "Share link revoked successfully".to_string(),
+    )))
+}
+
+#[utoipa::path(
+    get,
+    path = "/share/{token}",
+    tag = "Records",
+    params(
+        ("token" = String, Path, description = "Share link token"),
+        ("X-Share-Password" = Option<String>, Header, description = "Password of a protected share link")
+    ),
+    responses(
+        (status = 200, description = "Read-only view of the shared record; counts as one view", body = ApiResponse<SharedRecordResponse>),
+        (status = 401, description = "Missing or wrong share link password", body = ErrorResponse),
+        (status = 404, description = "Share link not found", body = ErrorResponse),
+        (status = 410, description = "Share link expired or reached its view limit", body = ErrorResponse)
+    )
+)]
+pub async fn get_shared_record(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<SharedRecordResponse>>, LunarbaseError> {
+    let password = headers
+        .get(SHARE_PASSWORD_HEADER)
+        .and_then(|value| value.to_str().ok());
+
+    let shared = state
+        .record_share_service
+        .open_share(&token, password)
+        .await?;
+
+    Ok(Json(ApiResponse::success(shared)))
+}
@@ -1,23 +1,26 @@
-use argon2::password_hash::SaltString;
-use argon2::{Argon2, PasswordHasher};
 use axum::{
     Extension,
     extract::{Query, State},
     http::StatusCode,
     response::Json,
 };
+use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::prelude::*;
-use rand::{RngCore, rngs::OsRng};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use utoipa::ToSchema;
 
 use crate::{
     AppState,
-    models::{NewUser, Role, UpdateUser, User},
-    schema::{roles, users},
+    models::{
+        EmailNormalizationReport, NewPermissionAuditEntry, NewUser, NotificationContent,
+        NotificationKind, Role, TokenType, UpdateUser, User, UserUsageResponse,
+    },
+    schema::{login_events, permission_audit_entries, roles, users, verification_tokens},
     utils::auth_error::ApiResponse,
-    utils::{Claims, ErrorResponse, LunarbaseError},
+    utils::email::ensure_deliverable_email,
+    utils::username::{INVALID_USERNAME_MESSAGE, ensure_username_available},
+    utils::{Claims, ErrorResponse, LunarbaseError, normalize_email, normalize_username},
 };
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -209,7 +212,7 @@ pub async fn list_users(
         ("user_id" = i32, Path, description = "User ID")
     ),
     responses(
-        (status = 200, description = "User retrieved successfully"),
+        (status = 200, description = "User retrieved successfully; includes when the last verification email was sent"),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 403, description = "Insufficient permissions", body = ErrorResponse),
         (status = 404, description = "User not found", body = ErrorResponse)
@@ -238,9 +241,31 @@ pub async fn get_user(
         .first(&mut conn)
         .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
 
-    Ok(Json(ApiResponse::success(
-        serde_json::to_value(user.to_response()).unwrap(),
-    )))
+    let mut response = serde_json::to_value(user.to_response()).unwrap();
+    if let Some(fields) = response.as_object_mut() {
+        fields.insert(
+            "verification_email_sent_at".to_string(),
+            serde_json::to_value(last_verification_email_sent_at(&mut conn, user.id)?).unwrap(),
+        );
+    }
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Only the latest verification link is kept and it is removed once used, so
+/// this is `None` for users who verified or never had an email sent.
+fn last_verification_email_sent_at(
+    conn: &mut SqliteConnection,
+    user_id: i32,
+) -> Result<Option<DateTime<Utc>>, LunarbaseError> {
+    let created_at: Option<NaiveDateTime> = verification_tokens::table
+        .filter(verification_tokens::user_id.eq(user_id))
+        .filter(verification_tokens::token_type.eq(TokenType::EmailVerification.as_str()))
+        .select(diesel::dsl::max(verification_tokens::created_at))
+        .first(conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+    Ok(created_at.map(|dt| DateTime::from_naive_utc_and_offset(dt, Utc)))
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -256,13 +281,18 @@ pub struct CreateUserRequest {
 }
 
 impl CreateUserRequest {
-    pub fn validate(&self) -> Result<(), Vec<String>> {
+    /// Also replaces `email` and `username` with their normalized forms when
+    /// they are valid.
+    pub fn validate(&mut self) -> Result<(), Vec<String>> {
         let mut errors = Vec::new();
 
-        if self.email.is_empty() {
+        if self.email.trim().is_empty() {
             errors.push("Email is required".to_string());
-        } else if !self.email.contains('@') || self.email.len() > 255 {
-            errors.push("Invalid email format".to_string());
+        } else {
+            match normalize_email(&self.email) {
+                Some(email) => self.email = email,
+                None => errors.push("Invalid email format".to_string()),
+            }
         }
 
         if self.password.is_empty() {
@@ -276,16 +306,13 @@ impl CreateUserRequest {
             errors.push("Password must be at least 8 characters long and contain uppercase, lowercase, number and special character".to_string());
         }
 
-        if self.username.is_empty() {
+        if self.username.trim().is_empty() {
             errors.push("Username is required".to_string());
-        } else if self.username.len() < 3
-            || self.username.len() > 30
-            || !self
-                .username
-                .chars()
-                .all(|c| c.is_alphanumeric() || c == '_')
-        {
-            errors.push("Username must be 3-30 characters long and contain only letters, numbers, and underscores".to_string());
+        } else {
+            match normalize_username(&self.username) {
+                Some(username) => self.username = username,
+                None => errors.push(INVALID_USERNAME_MESSAGE.to_string()),
+            }
         }
 
         if self.role.is_empty() {
@@ -319,7 +346,7 @@ impl CreateUserRequest {
 pub async fn create_user(
     State(app_state): State<AppState>,
     Extension(claims): Extension<Claims>,
-    Json(payload): Json<CreateUserRequest>,
+    Json(mut payload): Json<CreateUserRequest>,
 ) -> Result<(StatusCode, Json<ApiResponse<Value>>), LunarbaseError> {
     if claims.role != "admin" {
         return Err(LunarbaseError::InsufficientPermissions);
@@ -328,6 +355,7 @@ pub async fn create_user(
     payload
         .validate()
         .map_err(LunarbaseError::ValidationError)?;
+    ensure_deliverable_email(&app_state, &payload.email).await?;
 
     let mut conn = app_state
         .db_pool
@@ -360,27 +388,11 @@ pub async fn create_user(
         ]));
     }
 
-    let existing_username = users::table
-        .filter(users::username.eq(&payload.username))
-        .select(User::as_select())
-        .first::<User>(&mut conn)
-        .optional()
-        .map_err(|_| LunarbaseError::DatabaseError)?;
-
-    if existing_username.is_some() {
-        return Err(LunarbaseError::ValidationError(vec![
-            "Username already taken".to_string(),
-        ]));
-    }
+    ensure_username_available(&app_state, &mut conn, &payload.username, None).await?;
 
-    let new_user = NewUser::new_with_role(
-        payload.email,
-        &payload.password,
-        payload.username,
-        payload.role,
-        &app_state.password_pepper,
-    )
-    .map_err(|_| LunarbaseError::InternalError)?;
+    let password_hash = app_state.password_service.hash(&payload.password).await?;
+    let new_user =
+        NewUser::with_password_hash(payload.email, password_hash, payload.username, payload.role);
 
     diesel::insert_into(users::table)
         .values(&new_user)
@@ -428,14 +440,19 @@ pub struct UpdateUserRequest {
 }
 
 impl UpdateUserRequest {
-    pub fn validate(&self) -> Result<(), Vec<String>> {
+    /// Also replaces `email` and `username` with their normalized forms when
+    /// they are valid.
+    pub fn validate(&mut self) -> Result<(), Vec<String>> {
         let mut errors = Vec::new();
 
-        if let Some(email) = &self.email {
-            if email.is_empty() {
+        if let Some(email) = &mut self.email {
+            if email.trim().is_empty() {
                 errors.push("Email cannot be empty".to_string());
-            } else if !email.contains('@') || email.len() > 255 {
-                errors.push("Invalid email format".to_string());
+            } else {
+                match normalize_email(email) {
+                    Some(normalized) => *email = normalized,
+                    None => errors.push("Invalid email format".to_string()),
+                }
             }
         }
 
@@ -452,14 +469,14 @@ impl UpdateUserRequest {
             }
         }
 
-        if let Some(username) = &self.username {
-            if username.is_empty() {
+        if let Some(username) = &mut self.username {
+            if username.trim().is_empty() {
                 errors.push("Username cannot be empty".to_string());
-            } else if username.len() < 3
-                || username.len() > 30
-                || !username.chars().all(|c| c.is_alphanumeric() || c == '_')
-            {
-                errors.push("Username must be 3-30 characters long and contain only letters, numbers, and underscores".to_string());
+            } else {
+                match normalize_username(username) {
+                    Some(normalized) => *username = normalized,
+                    None => errors.push(INVALID_USERNAME_MESSAGE.to_string()),
+                }
             }
         }
 
@@ -501,7 +518,7 @@ pub async fn update_user(
     State(app_state): State<AppState>,
     Extension(claims): Extension<Claims>,
     axum::extract::Path(user_id): axum::extract::Path<i32>,
-    Json(payload): Json<UpdateUserRequest>,
+    Json(mut payload): Json<UpdateUserRequest>,
 ) -> Result<Json<ApiResponse<Value>>, LunarbaseError> {
     if claims.role != "admin" {
         return Err(LunarbaseError::InsufficientPermissions);
@@ -539,6 +556,7 @@ pub async fn update_user(
 
     if let Some(new_email) = &payload.email {
         if new_email != &existing_user.email {
+            ensure_deliverable_email(&app_state, new_email).await?;
             let email_conflict = users::table
                 .filter(users::email.eq(new_email))
                 .filter(users::id.ne(user_id))
@@ -557,22 +575,16 @@ pub async fn update_user(
 
     if let Some(new_username) = &payload.username {
         if new_username != &existing_user.username {
-            let username_conflict = users::table
-                .filter(users::username.eq(new_username))
-                .filter(users::id.ne(user_id))
-                .select(User::as_select())
-                .first(&mut conn)
-                .optional()
-                .map_err(|_| LunarbaseError::DatabaseError)?;
-
-            if username_conflict.is_some() {
-                return Err(LunarbaseError::ValidationError(vec![
-                    "Username already taken".to_string(),
-                ]));
-            }
+            ensure_username_available(&app_state, &mut conn, new_username, Some(user_id)).await?;
         }
     }
 
+    let deactivating = existing_user.is_active && payload.is_active == Some(false);
+    let new_role = payload
+        .role
+        .clone()
+        .filter(|role| role != &existing_user.role);
+
     let mut update_data = UpdateUser {
         email: payload.email,
         password_hash: None,
@@ -587,24 +599,7 @@ pub async fn update_user(
     };
 
     if let Some(new_password) = &payload.password {
-        let mut salt_bytes = [0u8; 32];
-        OsRng.fill_bytes(&mut salt_bytes);
-
-        let salt =
-            SaltString::encode_b64(&salt_bytes).map_err(|_| LunarbaseError::InternalError)?;
-
-        let peppered_password = format!("{}{}", new_password, &app_state.password_pepper);
-
-        let argon2 = Argon2::new(
-            argon2::Algorithm::Argon2id,
-            argon2::Version::V0x13,
-            argon2::Params::new(65536, 4, 2, None).unwrap(),
-        );
-        let password_hash = argon2
-            .hash_password(peppered_password.as_bytes(), &salt)
-            .map_err(|_| LunarbaseError::InternalError)?
-            .to_string();
-
+        let password_hash = app_state.password_service.hash(new_password).await?;
         update_data.password_hash = Some(password_hash);
     }
 
@@ -613,6 +608,31 @@ pub async fn update_user(
         .execute(&mut conn)
         .map_err(|_| LunarbaseError::DatabaseError)?;
 
+    if deactivating {
+        app_state
+            .admin_service
+            .deactivate_user(user_id, &app_state.websocket_service)
+            .await?;
+    }
+
+    if let Some(new_role) = new_role {
+        app_state
+            .notification_service
+            .try_notify(
+                user_id,
+                NotificationContent::new(NotificationKind::RoleChanged, "Your role changed")
+                    .with_body(format!(
+                        "Your role changed from {} to {}",
+                        existing_user.role, new_role
+                    ))
+                    .with_data(serde_json::json!({
+                        "from": existing_user.role,
+                        "to": new_role,
+                    })),
+            )
+            .await;
+    }
+
     let updated_user: User = users::table
         .select(User::as_select())
         .find(user_id)
@@ -673,6 +693,10 @@ pub async fn delete_user(
         .first(&mut conn)
         .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
 
+    diesel::delete(login_events::table.filter(login_events::user_id.eq(user_id)))
+        .execute(&mut conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
     let deleted_count = diesel::delete(users::table.find(user_id))
         .execute(&mut conn)
         .map_err(|_| LunarbaseError::DatabaseError)?;
@@ -687,15 +711,24 @@ pub async fn delete_user(
     }))))
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UnlockUserQuery {
+    /// Also email the user a password reset link
+    #[schema(example = false)]
+    pub require_reset: Option<bool>,
+}
+
 #[utoipa::path(
     post,
     path = "/users/{user_id}/unlock",
     tag = "Users",
     params(
-        ("user_id" = i32, Path, description = "User ID")
+        ("user_id" = i32, Path, description = "User ID"),
+        ("require_reset" = Option<bool>, Query, description = "Send the user a password reset email after unlocking")
     ),
     responses(
-        (status = 200, description = "User unlocked successfully"),
+        (status = 200, description = "User unlocked successfully; includes the lock status and failed attempt counter"),
+        (status = 400, description = "User is neither locked nor has failed login attempts", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 403, description = "Insufficient permissions", body = ErrorResponse),
         (status = 404, description = "User not found", body = ErrorResponse)
@@ -708,6 +741,7 @@ pub async fn unlock_user(
     State(app_state): State<AppState>,
     Extension(claims): Extension<Claims>,
     axum::extract::Path(user_id): axum::extract::Path<i32>,
+    Query(query): Query<UnlockUserQuery>,
 ) -> Result<Json<ApiResponse<Value>>, LunarbaseError> {
     if claims.role != "admin" {
         return Err(LunarbaseError::InsufficientPermissions);
@@ -724,26 +758,188 @@ pub async fn unlock_user(
         .first(&mut conn)
         .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
 
-    if existing_user.locked_until.is_none() {
+    // A user who is not locked yet but close to the limit would be locked again
+    // by the next bad attempt, so clearing the counter alone is also allowed.
+    if existing_user.locked_until.is_none() && existing_user.failed_login_attempts == 0 {
         return Err(LunarbaseError::BadRequest("User is not locked".to_string()));
     }
 
+    // Progressive lockout starts over as well, the same as after a successful login
+    conn.transaction::<_, LunarbaseError, _>(|conn| {
+        diesel::update(users::table.find(user_id))
+            .set((
+                users::failed_login_attempts.eq(0),
+                users::locked_until.eq(None::<NaiveDateTime>),
+                users::lockout_count.eq(0),
+            ))
+            .execute(conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        diesel::insert_into(permission_audit_entries::table)
+            .values(&NewPermissionAuditEntry {
+                actor_id: claims.sub.parse().ok(),
+                target_user_id: Some(user_id),
+                action: "unlock_user".to_string(),
+                collection_name: None,
+                role_name: None,
+                affected_count: existing_user.failed_login_attempts as i64,
+            })
+            .execute(conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        Ok(())
+    })?;
+
+    // Email requests are rate limited by address and by client IP; only the
+    // address buckets belong to this user.
+    for action in ["verification", "password_reset"] {
+        app_state.email_rate_limiter.forget(&format!(
+            "{}:email:{}",
+            action,
+            existing_user.email.to_lowercase()
+        ));
+    }
+
+    let updated_user: User = users::table
+        .find(user_id)
+        .select(User::as_select())
+        .first(&mut conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+    tracing::warn!(
+        "Admin {} ({}) unlocked user {} ({}); cleared {} failed login attempt(s)",
+        claims.sub,
+        claims.email,
+        updated_user.id,
+        updated_user.email,
+        existing_user.failed_login_attempts
+    );
+
+    let password_reset_email_sent = if query.require_reset.unwrap_or(false) {
+        match app_state
+            .email_service
+            .send_password_reset_email(updated_user.id, &updated_user.email, &updated_user.username)
+            .await
+        {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to send password reset email to {} after unlock: {:?}",
+                    updated_user.email,
+                    e
+                );
+                false
+            }
+        }
+    } else {
+        false
+    };
+
+    let mut response = serde_json::to_value(updated_user.to_response()).unwrap();
+    if let Some(fields) = response.as_object_mut() {
+        fields.insert("is_locked".to_string(), updated_user.is_locked().into());
+        fields.insert(
+            "failed_login_attempts".to_string(),
+            updated_user.failed_login_attempts.into(),
+        );
+        fields.insert(
+            "password_reset_email_sent".to_string(),
+            password_reset_email_sent.into(),
+        );
+    }
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VerifyUserEmailQuery {
+    /// Also email the user that their account has been activated
+    #[schema(example = false)]
+    pub notify: Option<bool>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/users/{user_id}/verify-email",
+    tag = "Users",
+    params(
+        ("user_id" = i32, Path, description = "User ID"),
+        ("notify" = Option<bool>, Query, description = "Send the user an account activated email")
+    ),
+    responses(
+        (status = 200, description = "User verified successfully; includes whether the activation email was sent"),
+        (status = 400, description = "User is already verified", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn verify_user_email(
+    State(app_state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(user_id): axum::extract::Path<i32>,
+    Query(query): Query<VerifyUserEmailQuery>,
+) -> Result<Json<ApiResponse<Value>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+    let existing_user: User = users::table
+        .find(user_id)
+        .select(User::as_select())
+        .first(&mut conn)
+        .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
+
+    if existing_user.is_verified {
+        return Err(LunarbaseError::BadRequest(
+            "User is already verified".to_string(),
+        ));
+    }
+
     let update_data = UpdateUser {
         email: None,
         password_hash: None,
         username: None,
-        is_verified: None,
+        is_verified: Some(true),
         is_active: None,
         role: None,
-        failed_login_attempts: Some(0),
-        locked_until: Some(None),
+        failed_login_attempts: None,
+        locked_until: None,
         avatar_url: None,
         last_login_at: None,
     };
 
-    diesel::update(users::table.find(user_id))
-        .set(&update_data)
-        .execute(&mut conn)
+    let revoked_tokens = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::update(users::table.find(user_id))
+                .set(&update_data)
+                .execute(conn)?;
+
+            // Links already sent would otherwise keep working after the fact
+            let revoked_tokens = diesel::delete(verification_tokens::table)
+                .filter(verification_tokens::user_id.eq(user_id))
+                .filter(verification_tokens::token_type.eq(TokenType::EmailVerification.as_str()))
+                .execute(conn)?;
+
+            diesel::insert_into(permission_audit_entries::table)
+                .values(&NewPermissionAuditEntry {
+                    actor_id: claims.sub.parse().ok(),
+                    target_user_id: Some(user_id),
+                    action: "verify_user_email".to_string(),
+                    collection_name: None,
+                    role_name: None,
+                    affected_count: revoked_tokens as i64,
+                })
+                .execute(conn)?;
+            Ok(revoked_tokens)
+        })
         .map_err(|_| LunarbaseError::DatabaseError)?;
 
     let updated_user: User = users::table
@@ -752,7 +948,140 @@ pub async fn unlock_user(
         .first(&mut conn)
         .map_err(|_| LunarbaseError::DatabaseError)?;
 
-    Ok(Json(ApiResponse::success(
-        serde_json::to_value(updated_user.to_response()).unwrap(),
-    )))
+    tracing::warn!(
+        "Admin {} ({}) verified the email of user {} ({}); revoked {} verification token(s)",
+        claims.sub,
+        claims.email,
+        updated_user.id,
+        updated_user.email,
+        revoked_tokens
+    );
+
+    let activation_email_sent = if query.notify.unwrap_or(false) {
+        match app_state
+            .email_service
+            .send_account_activated_email(&updated_user.email, &updated_user.username)
+            .await
+        {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to send account activated email to {}: {:?}",
+                    updated_user.email,
+                    e
+                );
+                false
+            }
+        }
+    } else {
+        false
+    };
+
+    let mut response = serde_json::to_value(updated_user.to_response()).unwrap();
+    if let Some(fields) = response.as_object_mut() {
+        fields.insert(
+            "activation_email_sent".to_string(),
+            activation_email_sent.into(),
+        );
+    }
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct NormalizeEmailsQuery {
+    /// Only report the changes and conflicts without rewriting any email
+    #[serde(default)]
+    #[schema(example = true)]
+    pub dry_run: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/users/normalize-emails",
+    tag = "Users",
+    params(
+        ("dry_run" = Option<bool>, Query, description = "Only report the changes and conflicts (default false)")
+    ),
+    responses(
+        (status = 200, description = "Emails rewritten to their normalized form, and accounts left alone because their emails collide or are invalid", body = ApiResponse<EmailNormalizationReport>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn normalize_user_emails(
+    State(app_state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<NormalizeEmailsQuery>,
+) -> Result<Json<ApiResponse<EmailNormalizationReport>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let report = app_state
+        .admin_service
+        .normalize_user_emails(query.dry_run)
+        .await?;
+    Ok(Json(ApiResponse::success(report)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/usage",
+    tag = "Users",
+    params(
+        ("user_id" = i32, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Records created and bytes uploaded by the user, with the daily quotas of their role", body = ApiResponse<UserUsageResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Insufficient permissions", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_user_usage(
+    State(app_state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(user_id): axum::extract::Path<i32>,
+) -> Result<Json<ApiResponse<UserUsageResponse>>, LunarbaseError> {
+    if claims.role != "admin" {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let usage = app_state.usage_service.usage(user_id, None).await?;
+    Ok(Json(ApiResponse::success(usage)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/me/usage",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Records created and bytes uploaded by the caller, with the daily quotas of their current role", body = ApiResponse<UserUsageResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_my_usage(
+    State(app_state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<UserUsageResponse>>, LunarbaseError> {
+    let user_id: i32 = claims
+        .sub
+        .parse()
+        .map_err(|_| LunarbaseError::TokenInvalid)?;
+
+    let usage = app_state
+        .usage_service
+        .usage(user_id, Some(&claims.role))
+        .await?;
+    Ok(Json(ApiResponse::success(usage)))
 }
@@ -7,13 +7,14 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use tower_governor::key_extractor::{KeyExtractor, SmartIpKeyExtractor};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
     AppState,
     middleware::extract_user_claims,
-    services::WebSocketStats,
+    services::{ConfigurationAccess, WebSocketLimits, WebSocketStats},
     utils::{ApiResponse, LunarbaseError},
 };
 
@@ -27,9 +28,11 @@ pub struct WebSocketQuery {
     path = "/ws",
     tag = "WebSocket",
     responses(
-        (status = 101, description = "WebSocket connection established"),
+        (status = 101, description = "WebSocket connection established. Record events carry an `action` of Created, Updated, Deleted, OwnershipTransferred, Reordered, Published or Unpublished"),
         (status = 400, description = "Bad request - WebSocket upgrade failed", body = ErrorResponse),
-        (status = 401, description = "Unauthorized", body = ErrorResponse)
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Email not verified while auth.require_email_verification is enabled", body = ErrorResponse),
+        (status = 429, description = "User or IP temporarily banned after repeated WebSocket limit violations", body = ErrorResponse)
     ),
     security(
         ("bearer_auth" = [])
@@ -41,30 +44,64 @@ pub async fn websocket_handler(
     Query(params): Query<WebSocketQuery>,
     request: Request,
 ) -> Result<Response, LunarbaseError> {
-    let user_id = if let Some(token) = params.token {
+    let claims = if let Some(token) = params.token {
         let mut headers = request.headers().clone();
         headers.insert(
             "authorization",
             format!("Bearer {}", token).parse().unwrap(),
         );
 
-        match app_state
+        app_state
             .auth_state
             .jwt_service
-            .validate_access_token(&token)
-        {
-            Ok(claims) => Some(claims.sub.parse::<i32>().unwrap_or_default()),
-            Err(_) => None,
-        }
+            .validate_access_token_with_blacklist(&token)
+            .ok()
     } else {
-        match extract_user_claims(&request) {
-            Ok(claims) => Some(claims.sub.parse::<i32>().unwrap_or_default()),
-            Err(_) => None,
-        }
+        extract_user_claims(&request).ok()
     };
+    // Tokens from before workspaces were toggled connect anonymously
+    let workspaces_enabled = app_state.get_enable_workspaces().await;
+    let claims = claims.filter(|claims| claims.workspace_id.is_some() == workspaces_enabled);
+    let user_id = claims
+        .as_ref()
+        .map(|claims| claims.sub.parse::<i32>().unwrap_or_default());
+    let workspace = claims.and_then(|claims| claims.workspace_session());
+
+    if let Some(user_id) = user_id
+        && app_state.get_require_email_verification().await
+    {
+        use crate::schema::users;
+        use diesel::prelude::*;
+
+        let mut conn = app_state
+            .db_pool
+            .get()
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        let is_verified: bool = users::table
+            .find(user_id)
+            .select(users::is_verified)
+            .first(&mut conn)
+            .map_err(|_| LunarbaseError::TokenInvalid)?;
+        if !is_verified {
+            return Err(LunarbaseError::AccountNotVerified);
+        }
+    }
 
+    let client_ip = SmartIpKeyExtractor.extract(&request).ok();
+    if app_state.websocket_service.is_banned(user_id, client_ip) {
+        return Err(LunarbaseError::RateLimitExceeded);
+    }
+
+    let limits = WebSocketLimits::load(&app_state).await;
     let websocket_service = std::sync::Arc::new(app_state.websocket_service.clone());
-    Ok(ws.on_upgrade(move |socket| websocket_service.clone().handle_connection(socket, user_id)))
+    Ok(ws
+        .max_message_size(limits.protocol_max_message_bytes())
+        .max_frame_size(limits.protocol_max_message_bytes())
+        .on_upgrade(move |socket| {
+            websocket_service
+                .clone()
+                .handle_connection(socket, user_id, workspace, client_ip, limits)
+        }))
 }
 
 #[utoipa::path(
@@ -139,6 +176,13 @@ pub async fn websocket_status(
         connections: connection_count,
         subscriptions: subscription_count,
         status: "operational".to_string(),
+        limits: WebSocketLimitsStatus {
+            max_message_kb: app_state.get_websocket_max_message_kb().await,
+            max_subscriptions: app_state.get_websocket_max_subscriptions().await,
+            max_filter_length: app_state.get_websocket_max_filter_length().await,
+            max_violations: app_state.get_websocket_max_violations().await,
+            ban_seconds: app_state.get_websocket_ban_seconds().await,
+        },
     };
 
     Ok(Json(ApiResponse::success(status)))
@@ -401,6 +445,31 @@ pub struct WebSocketStatus {
     pub connections: usize,
     pub subscriptions: usize,
     pub status: String,
+    pub limits: WebSocketLimitsStatus,
+}
+
+/// Limits applied to new connections. Messages breaking them get an `Error`
+/// message; users, or IPs when anonymous, that break them `max_violations`
+/// times within a minute are disconnected with close code 1008 and refused
+/// for `ban_seconds`.
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct WebSocketLimitsStatus {
+    /// Largest inbound message in kilobytes (default 64); messages over four
+    /// times this close the connection
+    #[schema(example = 64)]
+    pub max_message_kb: u32,
+    /// Subscriptions per connection (default 100)
+    #[schema(example = 100)]
+    pub max_subscriptions: u32,
+    /// Combined characters of a subscription's filter names and values (default 1024)
+    #[schema(example = 1024)]
+    pub max_filter_length: u32,
+    /// Violations within a minute before a ban; 0 never bans (default 10)
+    #[schema(example = 10)]
+    pub max_violations: u32,
+    /// Length of a ban in seconds (default 300)
+    #[schema(example = 300)]
+    pub ban_seconds: u32,
 }
 
 #[derive(Debug, Serialize, utoipa::ToSchema)]
@@ -409,6 +478,9 @@ pub struct ConnectionDetails {
     pub user_id: Option<i32>,
     pub connected_at: String,
     pub subscriptions: Vec<SubscriptionInfo>,
+    pub outbound_queue_depth: usize,
+    /// Messages dropped so far because the connection could not keep up
+    pub dropped_messages: u32,
 }
 
 #[derive(Debug, Serialize, utoipa::ToSchema)]
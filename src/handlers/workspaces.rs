@@ -0,0 +1,213 @@
+use crate::{
+    AppState,
+    models::{
+        CollectionResponse, CreateWorkspaceRequest, MoveCollectionRequest,
+        SetWorkspaceMemberRequest, WorkspaceMemberResponse, WorkspaceResponse,
+    },
+    utils::{ApiResponse, Claims, ErrorResponse, LunarbaseError},
+};
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+
+#[utoipa::path(
+    post,
+    path = "/admin/workspaces",
+    tag = "Workspaces",
+    request_body = CreateWorkspaceRequest,
+    responses(
+        (status = 201, description = "Workspace created", body = ApiResponse<WorkspaceResponse>),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Superadmin access required", body = ErrorResponse),
+        (status = 409, description = "A workspace with this name already exists", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_workspace(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<CreateWorkspaceRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<WorkspaceResponse>>), LunarbaseError> {
+    if !claims.is_superadmin() {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let workspace = state.workspace_service.create_workspace(request).await?;
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(workspace))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/workspaces",
+    tag = "Workspaces",
+    responses(
+        (status = 200, description = "Workspaces retrieved", body = ApiResponse<Vec<WorkspaceResponse>>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Superadmin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_workspaces(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<WorkspaceResponse>>>, LunarbaseError> {
+    if !claims.is_superadmin() {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let workspaces = state.workspace_service.list_workspaces().await?;
+    Ok(Json(ApiResponse::success(workspaces)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/workspaces/{id}",
+    tag = "Workspaces",
+    params(
+        ("id" = i32, Path, description = "Workspace ID")
+    ),
+    responses(
+        (status = 200, description = "Workspace deleted with its custom roles and memberships", body = ApiResponse<String>),
+        (status = 400, description = "The default workspace cannot be deleted", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Superadmin access required", body = ErrorResponse),
+        (status = 404, description = "Workspace not found", body = ErrorResponse),
+        (status = 409, description = "Workspace still has collections", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_workspace(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(workspace_id): Path<i32>,
+) -> Result<Json<ApiResponse<String>>, LunarbaseError> {
+    if !claims.is_superadmin() {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    state
+        .workspace_service
+        .delete_workspace(workspace_id)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Workspace deleted successfully".to_string(),
+    )))
+}
+
+#[utoipa::path(
+    put,
+    path = "/admin/workspaces/{id}/members/{user_id}",
+    tag = "Workspaces",
+    params(
+        ("id" = i32, Path, description = "Workspace ID"),
+        ("user_id" = i32, Path, description = "User ID")
+    ),
+    request_body = SetWorkspaceMemberRequest,
+    responses(
+        (status = 200, description = "Membership created or its role changed", body = ApiResponse<WorkspaceMemberResponse>),
+        (status = 400, description = "Role does not exist in this workspace", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Superadmin access required", body = ErrorResponse),
+        (status = 404, description = "Workspace or user not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn set_workspace_member(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((workspace_id, user_id)): Path<(i32, i32)>,
+    Json(request): Json<SetWorkspaceMemberRequest>,
+) -> Result<Json<ApiResponse<WorkspaceMemberResponse>>, LunarbaseError> {
+    if !claims.is_superadmin() {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let member = state
+        .workspace_service
+        .set_member(workspace_id, user_id, &request.role)
+        .await?;
+    Ok(Json(ApiResponse::success(member)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/workspaces/{id}/members/{user_id}",
+    tag = "Workspaces",
+    params(
+        ("id" = i32, Path, description = "Workspace ID"),
+        ("user_id" = i32, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Membership removed", body = ApiResponse<String>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Superadmin access required", body = ErrorResponse),
+        (status = 404, description = "User is not a member of this workspace", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn remove_workspace_member(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((workspace_id, user_id)): Path<(i32, i32)>,
+) -> Result<Json<ApiResponse<String>>, LunarbaseError> {
+    if !claims.is_superadmin() {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    state
+        .workspace_service
+        .remove_member(workspace_id, user_id)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Member removed successfully".to_string(),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/workspaces/{id}/collections",
+    tag = "Workspaces",
+    params(
+        ("id" = i32, Path, description = "Workspace to move the collection to")
+    ),
+    request_body = MoveCollectionRequest,
+    responses(
+        (status = 200, description = "Collection moved with its records", body = ApiResponse<CollectionResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Superadmin access required, or a system collection", body = ErrorResponse),
+        (status = 404, description = "Workspace or collection not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn move_collection_to_workspace(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(workspace_id): Path<i32>,
+    Json(request): Json<MoveCollectionRequest>,
+) -> Result<Json<ApiResponse<CollectionResponse>>, LunarbaseError> {
+    if !claims.is_superadmin() {
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    let collection = state
+        .workspace_service
+        .move_collection(workspace_id, &request.collection)
+        .await?;
+    Ok(Json(ApiResponse::success(collection)))
+}
@@ -0,0 +1,430 @@
+use crate::models::{
+    CollectionResponse, CollectionSchema, FieldDefinition, FieldType, NumberFormat,
+};
+use serde_json::{Map, Value, json};
+
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+pub fn collection_to_json_schema(collection: &CollectionResponse) -> Value {
+    let mut document = schema_to_json_schema(&collection.schema);
+
+    if let Some(object) = document.as_object_mut() {
+        object.insert(
+            "$id".to_string(),
+            Value::String(format!("collections/{}/schema.json", collection.name)),
+        );
+        object.insert(
+            "title".to_string(),
+            Value::String(
+                collection
+                    .display_name
+                    .clone()
+                    .unwrap_or_else(|| collection.name.clone()),
+            ),
+        );
+        if let Some(description) = &collection.description {
+            object.insert(
+                "description".to_string(),
+                Value::String(description.clone()),
+            );
+        }
+        object.insert(
+            "x-lunarbase-schema-version".to_string(),
+            Value::from(collection.schema_version),
+        );
+    }
+
+    document
+}
+
+pub fn collections_to_json_schema_bundle(collections: &[CollectionResponse]) -> Value {
+    let mut bundle = Map::new();
+
+    for collection in collections {
+        bundle.insert(
+            collection.name.clone(),
+            collection_to_json_schema(collection),
+        );
+    }
+
+    Value::Object(bundle)
+}
+
+pub fn schema_to_json_schema(schema: &CollectionSchema) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+
+    for field in &schema.fields {
+        properties.insert(field.name.clone(), field_to_json_schema(field));
+        if field.required {
+            required.push(Value::String(field.name.clone()));
+        }
+    }
+
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+pub fn field_to_json_schema(field: &FieldDefinition) -> Value {
+    let mut property = Map::new();
+
+    match field.field_type {
+        FieldType::Text | FieldType::RichText | FieldType::File | FieldType::Relation => {
+            property.insert("type".to_string(), json!("string"));
+        }
+        FieldType::Number => {
+            let json_type = match field.number_format() {
+                Some(NumberFormat::Integer) => "integer",
+                _ => "number",
+            };
+            property.insert("type".to_string(), json!(json_type));
+        }
+        FieldType::Boolean => {
+            property.insert("type".to_string(), json!("boolean"));
+        }
+        FieldType::Date => {
+            property.insert("type".to_string(), json!("string"));
+            property.insert("format".to_string(), json!("date"));
+        }
+        FieldType::Email => {
+            property.insert("type".to_string(), json!("string"));
+            property.insert("format".to_string(), json!("email"));
+        }
+        FieldType::Url => {
+            property.insert("type".to_string(), json!("string"));
+            property.insert("format".to_string(), json!("uri"));
+        }
+        FieldType::Slug => {
+            property.insert("type".to_string(), json!("string"));
+            property.insert("pattern".to_string(), json!("^[a-z0-9]+(-[a-z0-9]+)*$"));
+        }
+        FieldType::Json => {}
+        FieldType::Decimal => {
+            let (_, scale) = field.decimal_precision_and_scale();
+            let fraction = if scale == 0 {
+                String::new()
+            } else {
+                format!("(\\.\\d{{1,{}}})?", scale)
+            };
+            property.insert("type".to_string(), json!("string"));
+            property.insert(
+                "pattern".to_string(),
+                json!(format!("^-?\\d+{}$", fraction)),
+            );
+        }
+        FieldType::GeoPoint => {
+            property.insert("type".to_string(), json!("object"));
+            property.insert(
+                "properties".to_string(),
+                json!({
+                    "lat": {"type": "number", "minimum": -90, "maximum": 90},
+                    "lng": {"type": "number", "minimum": -180, "maximum": 180},
+                }),
+            );
+            property.insert("required".to_string(), json!(["lat", "lng"]));
+        }
+    }
+
+    if let Some(validation) = &field.validation {
+        if is_string_type(&field.field_type) {
+            if let Some(min_length) = validation.min_length {
+                property.insert("minLength".to_string(), json!(min_length));
+            }
+            if let Some(max_length) = validation.max_length {
+                property.insert("maxLength".to_string(), json!(max_length));
+            }
+            if let Some(pattern) = &validation.pattern {
+                property.insert("pattern".to_string(), json!(pattern));
+            }
+            if let Some(enum_values) = &validation.enum_values {
+                property.insert("enum".to_string(), json!(enum_values));
+            }
+        }
+
+        if field.field_type == FieldType::Number {
+            if let Some(min_value) = validation.min_value {
+                property.insert("minimum".to_string(), json!(min_value));
+            }
+            if let Some(max_value) = validation.max_value {
+                property.insert("maximum".to_string(), json!(max_value));
+            }
+        }
+    }
+
+    if let Some(default_value) = &field.default_value {
+        property.insert("default".to_string(), default_value.clone());
+    }
+    if let Some(description) = &field.description {
+        property.insert("description".to_string(), json!(description));
+    }
+    if let Some(example) = &field.example {
+        property.insert("examples".to_string(), json!([example]));
+    }
+
+    Value::Object(property)
+}
+
+fn is_string_type(field_type: &FieldType) -> bool {
+    matches!(
+        field_type,
+        FieldType::Text
+            | FieldType::RichText
+            | FieldType::Email
+            | FieldType::Url
+            | FieldType::File
+            | FieldType::Relation
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CollectionWorkflow, RecordIdType, ValidationRules};
+
+    fn field(name: &str, field_type: FieldType, required: bool) -> FieldDefinition {
+        FieldDefinition {
+            name: name.to_string(),
+            field_type,
+            required,
+            default_value: None,
+            validation: None,
+            relation_target: None,
+            description: None,
+            example: None,
+            indexed_paths: None,
+        }
+    }
+
+    fn rules() -> ValidationRules {
+        ValidationRules {
+            min_length: None,
+            max_length: None,
+            min_value: None,
+            max_value: None,
+            pattern: None,
+            enum_values: None,
+            precision: None,
+            scale: None,
+            source_field: None,
+            number_format: None,
+        }
+    }
+
+    fn all_rules() -> ValidationRules {
+        ValidationRules {
+            min_length: Some(2),
+            max_length: Some(50),
+            min_value: Some(0.0),
+            max_value: Some(10.5),
+            pattern: Some("^[a-z]+$".to_string()),
+            enum_values: Some(vec!["a".to_string(), "b".to_string()]),
+            precision: None,
+            scale: None,
+            source_field: None,
+            number_format: None,
+        }
+    }
+
+    #[test]
+    fn test_field_type_mapping() {
+        let cases = [
+            (FieldType::Text, Some("string"), None),
+            (FieldType::RichText, Some("string"), None),
+            (FieldType::File, Some("string"), None),
+            (FieldType::Relation, Some("string"), None),
+            (FieldType::Number, Some("number"), None),
+            (FieldType::Boolean, Some("boolean"), None),
+            (FieldType::Date, Some("string"), Some("date")),
+            (FieldType::Email, Some("string"), Some("email")),
+            (FieldType::Url, Some("string"), Some("uri")),
+            (FieldType::Json, None, None),
+            (FieldType::GeoPoint, Some("object"), None),
+            (FieldType::Decimal, Some("string"), None),
+        ];
+
+        for (field_type, expected_type, expected_format) in cases {
+            let property = field_to_json_schema(&field("value", field_type.clone(), false));
+            assert_eq!(
+                property.get("type").and_then(Value::as_str),
+                expected_type,
+                "type for {:?}",
+                field_type
+            );
+            assert_eq!(
+                property.get("format").and_then(Value::as_str),
+                expected_format,
+                "format for {:?}",
+                field_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_string_rules_apply_to_string_types() {
+        for field_type in [
+            FieldType::Text,
+            FieldType::RichText,
+            FieldType::Email,
+            FieldType::Url,
+            FieldType::File,
+            FieldType::Relation,
+        ] {
+            let mut definition = field("value", field_type.clone(), true);
+            definition.validation = Some(all_rules());
+
+            let property = field_to_json_schema(&definition);
+            assert_eq!(property["minLength"], 2, "minLength for {:?}", field_type);
+            assert_eq!(property["maxLength"], 50, "maxLength for {:?}", field_type);
+            assert_eq!(property["pattern"], "^[a-z]+$");
+            assert_eq!(property["enum"], json!(["a", "b"]));
+            assert!(property.get("minimum").is_none());
+            assert!(property.get("maximum").is_none());
+        }
+    }
+
+    #[test]
+    fn test_numeric_rules_apply_to_numbers_only() {
+        let mut definition = field("price", FieldType::Number, true);
+        definition.validation = Some(all_rules());
+
+        let property = field_to_json_schema(&definition);
+        assert_eq!(property["minimum"], 0.0);
+        assert_eq!(property["maximum"], 10.5);
+        assert!(property.get("minLength").is_none());
+        assert!(property.get("pattern").is_none());
+        assert!(property.get("enum").is_none());
+
+        for field_type in [FieldType::Boolean, FieldType::Date, FieldType::Json] {
+            let mut definition = field("value", field_type.clone(), true);
+            definition.validation = Some(all_rules());
+
+            let property = field_to_json_schema(&definition);
+            for keyword in [
+                "minLength",
+                "maxLength",
+                "pattern",
+                "enum",
+                "minimum",
+                "maximum",
+            ] {
+                assert!(
+                    property.get(keyword).is_none(),
+                    "{} should not apply to {:?}",
+                    keyword,
+                    field_type
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_integer_number_format() {
+        let mut definition = field("quantity", FieldType::Number, true);
+        definition.validation = Some(ValidationRules {
+            number_format: Some(NumberFormat::Integer),
+            ..rules()
+        });
+        assert_eq!(field_to_json_schema(&definition)["type"], "integer");
+
+        definition.validation = Some(ValidationRules {
+            number_format: Some(NumberFormat::Float),
+            ..rules()
+        });
+        assert_eq!(field_to_json_schema(&definition)["type"], "number");
+    }
+
+    #[test]
+    fn test_partial_rules() {
+        let mut definition = field("title", FieldType::Text, true);
+        definition.validation = Some(ValidationRules {
+            max_length: Some(100),
+            ..rules()
+        });
+
+        let property = field_to_json_schema(&definition);
+        assert_eq!(property["maxLength"], 100);
+        assert!(property.get("minLength").is_none());
+        assert!(property.get("pattern").is_none());
+
+        let mut definition = field("score", FieldType::Number, false);
+        definition.validation = Some(ValidationRules {
+            min_value: Some(-1.0),
+            ..rules()
+        });
+
+        let property = field_to_json_schema(&definition);
+        assert_eq!(property["minimum"], -1.0);
+        assert!(property.get("maximum").is_none());
+    }
+
+    #[test]
+    fn test_default_value() {
+        let mut definition = field("published", FieldType::Boolean, false);
+        definition.default_value = Some(json!(false));
+
+        let property = field_to_json_schema(&definition);
+        assert_eq!(property["default"], false);
+    }
+
+    #[test]
+    fn test_description_and_example() {
+        let mut definition = field("flag_b", FieldType::Boolean, false);
+        assert!(
+            field_to_json_schema(&definition)
+                .get("description")
+                .is_none()
+        );
+
+        definition.description = Some("Hides the product from search".to_string());
+        definition.example = Some(json!(true));
+        let property = field_to_json_schema(&definition);
+        assert_eq!(property["description"], "Hides the product from search");
+        assert_eq!(property["examples"], json!([true]));
+    }
+
+    #[test]
+    fn test_collection_document() {
+        let collection = CollectionResponse {
+            id: 1,
+            name: "articles".to_string(),
+            display_name: Some("Articles".to_string()),
+            description: Some("Blog articles".to_string()),
+            schema: CollectionSchema {
+                fields: vec![
+                    field("title", FieldType::Text, true),
+                    field("body", FieldType::RichText, false),
+                    field("views", FieldType::Number, true),
+                ],
+            },
+            schema_version: 1,
+            query_cache_ttl_seconds: 0,
+            orderable: false,
+            id_type: RecordIdType::Integer,
+            allow_explicit_ids: false,
+            workflow: CollectionWorkflow::None,
+            schedule: None,
+            retention: None,
+            workspace_id: 1,
+            is_system: false,
+            created_at: "2024-01-01 12:00:00".to_string(),
+            updated_at: "2024-01-01 12:00:00".to_string(),
+        };
+
+        let document = collection_to_json_schema(&collection);
+        assert_eq!(document["$schema"], JSON_SCHEMA_DIALECT);
+        assert_eq!(document["$id"], "collections/articles/schema.json");
+        assert_eq!(document["title"], "Articles");
+        assert_eq!(document["description"], "Blog articles");
+        assert_eq!(document["x-lunarbase-schema-version"], 1);
+        assert_eq!(document["type"], "object");
+        assert_eq!(document["required"], json!(["title", "views"]));
+        assert_eq!(document["properties"]["body"]["type"], "string");
+
+        let bundle = collections_to_json_schema_bundle(&[collection]);
+        assert_eq!(bundle["articles"]["title"], "Articles");
+    }
+}
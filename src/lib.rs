@@ -1,16 +1,23 @@
 use utoipa::OpenApi;
 
+pub mod canonical_json;
 pub mod cli;
+pub mod codegen;
 pub mod config;
 pub mod database;
+pub mod decimal;
 pub mod embedded_assets;
 pub mod handlers;
+pub mod json_schema;
+pub mod listeners;
 pub mod middleware;
 pub mod models;
+pub mod openapi;
 pub mod query_engine;
 pub mod schema;
 pub mod server;
 pub mod services;
+pub mod slug;
 pub mod utils;
 
 #[derive(OpenApi)]
@@ -37,12 +44,18 @@ pub mod utils;
         handlers::auth::login,
         handlers::auth::refresh_token,
         handlers::auth::me,
+        handlers::auth::session_info,
+        handlers::auth::guest_session,
         handlers::auth::logout,
         handlers::auth::oauth_authorize,
         handlers::auth::oauth_callback,
         handlers::auth::oauth_status,
         handlers::auth::verify_email,
         handlers::auth::resend_verification,
+        handlers::auth::forgot_password,
+        handlers::auth::reset_password,
+        handlers::auth::switch_workspace,
+        handlers::auth::list_my_workspaces,
 
         handlers::collections::create_collection,
         handlers::collections::list_collections,
@@ -50,15 +63,49 @@ pub mod utils;
         handlers::collections::update_collection,
         handlers::collections::delete_collection,
         handlers::collections::get_collection_schema,
+        handlers::collections::list_collection_schema_versions,
+        handlers::collections::get_collection_schema_version,
+        handlers::collections::restore_collection_schema_version,
+        handlers::collections::get_collection_json_schema,
+        handlers::collections::get_collections_json_schema,
+        handlers::collections::get_collections_openapi,
+        handlers::collections::generate_typescript_types,
         handlers::collections::get_collections_stats,
         handlers::collections::get_collections_record_counts,
+        handlers::collections::verify_collection,
+        handlers::collections::get_collection_health,
+        handlers::collections::repair_collection,
+        handlers::collections::sweep_orphans,
+        handlers::collections::get_record_schedule,
+        handlers::collections::get_retention_report,
+        handlers::collection_templates::list_collection_templates,
+        handlers::collection_templates::get_collection_template,
+        handlers::collection_templates::delete_collection_template,
+        handlers::collection_templates::create_collection_from_template,
+        handlers::collection_templates::save_collection_as_template,
+        handlers::collection_views::create_collection_view,
+        handlers::collection_views::list_collection_views,
+        handlers::collection_views::get_collection_view,
+        handlers::collection_views::update_collection_view,
+        handlers::collection_views::delete_collection_view,
 
         handlers::collections::create_record,
+        handlers::collections::validate_record,
+        handlers::collections::export_records,
+        handlers::collections::verify_records,
         handlers::collections::list_records,
+        handlers::collections::count_records,
         handlers::collections::list_all_records,
+        handlers::collections::global_search,
         handlers::collections::get_record,
+        handlers::collections::get_record_by_field,
+        handlers::collections::get_record_references,
         handlers::collections::update_record,
+        handlers::collections::move_record,
+        handlers::collections::publish_record,
+        handlers::collections::unpublish_record,
         handlers::collections::delete_record,
+        handlers::batch::execute_batch,
 
         handlers::permissions::create_role,
         handlers::permissions::list_roles,
@@ -68,17 +115,25 @@ pub mod utils;
         handlers::permissions::get_collection_permissions,
         handlers::permissions::set_user_collection_permission,
         handlers::permissions::get_user_collection_permissions,
+        handlers::permissions::get_default_permissions,
+        handlers::permissions::list_collection_users,
+        handlers::permissions::update_default_permissions,
+        handlers::permissions::export_permissions,
+        handlers::permissions::import_permissions,
 
         handlers::record_permissions::set_record_permission,
         handlers::record_permissions::get_record_permissions,
         handlers::record_permissions::remove_record_permission,
         handlers::record_permissions::list_record_permissions,
+        handlers::record_permissions::list_user_record_permissions,
+        handlers::record_permissions::revoke_user_record_permissions,
 
         handlers::ownership::transfer_record_ownership,
         handlers::ownership::get_my_owned_records,
         handlers::ownership::get_user_owned_records,
         handlers::ownership::check_record_ownership,
         handlers::ownership::get_ownership_stats,
+        handlers::ownership::get_global_ownership_stats,
 
         handlers::websocket::websocket_handler,
         handlers::websocket::websocket_stats,
@@ -88,21 +143,36 @@ pub mod utils;
         handlers::websocket::broadcast_message,
         handlers::websocket::get_activity,
 
+        handlers::notifications::list_my_notifications,
+        handlers::notifications::mark_my_notifications_read,
+        handlers::notifications::stream_my_notifications,
         handlers::users::list_users,
         handlers::users::get_user,
         handlers::users::create_user,
         handlers::users::update_user,
         handlers::users::delete_user,
         handlers::users::unlock_user,
+        handlers::users::verify_user_email,
+        handlers::users::normalize_user_emails,
+        handlers::users::get_user_usage,
+        handlers::users::get_my_usage,
 
         handlers::avatar_proxy::proxy_avatar,
 
         handlers::health::health_check,
         handlers::health::public_health_check,
         handlers::health::simple_health_check,
+        handlers::health::liveness_check,
+        handlers::health::readiness_check,
+        handlers::health::health_history,
 
         handlers::metrics::get_metrics,
         handlers::metrics::get_metrics_summary,
+        handlers::admin::get_admin_overview,
+        handlers::admin::get_user_analytics,
+        handlers::admin::get_record_analytics,
+        handlers::admin::list_locked_accounts,
+        handlers::admin::get_tls_status,
 
         handlers::configuration::get_all_settings,
         handlers::configuration::get_settings_by_category,
@@ -117,6 +187,24 @@ pub mod utils;
 
         handlers::image_upload::upload_image,
         handlers::image_upload::delete_image,
+
+        handlers::ingest::create_ingest_endpoint,
+        handlers::ingest::list_ingest_endpoints,
+        handlers::ingest::delete_ingest_endpoint,
+        handlers::ingest::list_ingest_failures,
+        handlers::ingest::ingest_payload,
+
+        handlers::record_shares::create_record_share,
+        handlers::record_shares::list_record_shares,
+        handlers::record_shares::revoke_record_share,
+        handlers::record_shares::get_shared_record,
+
+        handlers::workspaces::create_workspace,
+        handlers::workspaces::list_workspaces,
+        handlers::workspaces::delete_workspace,
+        handlers::workspaces::set_workspace_member,
+        handlers::workspaces::remove_workspace_member,
+        handlers::workspaces::move_collection_to_workspace,
     ),
     components(
         schemas(
@@ -127,56 +215,182 @@ pub mod utils;
             utils::ApiResponse<models::collection::RecordResponse>,
             utils::ApiResponse<Vec<models::collection::RecordResponse>>,
             utils::ErrorResponse,
+            utils::FieldError,
 
             models::user::RegisterRequest,
             models::user::LoginRequest,
             models::user::UserResponse,
+            models::user::EmailOwner,
+            models::user::EmailChange,
+            models::user::EmailConflict,
+            models::user::EmailNormalizationReport,
+            utils::ApiResponse<models::user::EmailNormalizationReport>,
+            handlers::users::NormalizeEmailsQuery,
             models::user::AuthResponse,
             models::blacklisted_token::LogoutRequest,
             models::blacklisted_token::LogoutResponse,
             handlers::auth::OAuthCallbackQuery,
             handlers::auth::OAuthAuthorizationResponse,
             handlers::auth::OAuthStatusResponse,
+            handlers::auth::SessionInfoResponse,
+            handlers::auth::SessionCookieAttributes,
+            models::guest_session::GuestSessionRequest,
+            models::guest_session::GuestSessionResponse,
             handlers::auth::VerifyEmailRequest,
             handlers::auth::ResendVerificationRequest,
+            handlers::auth::ForgotPasswordRequest,
+            handlers::auth::ResetPasswordRequest,
 
             handlers::avatar_proxy::AvatarQuery,
 
             models::collection::CreateCollectionRequest,
             models::collection::UpdateCollectionRequest,
             models::collection::CollectionResponse,
+            models::collection::CollectionFields,
+            models::collection::CollectionSummary,
+            models::collection::CollectionName,
+            models::collection::CollectionListEntry,
             models::collection::CollectionSchema,
             models::collection::FieldDefinition,
             models::collection::FieldType,
+            models::collection::RecordIdType,
+            models::collection::CollectionWorkflow,
+            models::collection::RecordStatus,
+            models::collection::RecordSchedule,
+            models::collection::ExpireAction,
+            models::collection::ScheduledAction,
+            models::collection::ScheduledOperation,
+            models::collection::RecordScheduleReport,
+            utils::ApiResponse<models::collection::RecordScheduleReport>,
+            models::collection::RetentionPolicy,
+            models::collection::RetentionRunEntry,
+            models::collection::RetentionReport,
+            utils::ApiResponse<models::collection::RetentionReport>,
+            models::record_manifest::RecordHash,
+            models::record_manifest::RecordManifest,
+            models::record_manifest::RecordExport,
+            models::record_manifest::ManifestVerification,
+            utils::ApiResponse<models::record_manifest::RecordExport>,
+            utils::ApiResponse<models::record_manifest::ManifestVerification>,
+            models::collection::RecordReferences,
+            models::collection::CollectionReferences,
+            utils::ApiResponse<models::collection::RecordReferences>,
+            models::collection::CollectionDeleteImpact,
+            models::collection::PendingCollectionDelete,
+            utils::ApiResponse<models::collection::PendingCollectionDelete>,
             models::collection::ValidationRules,
+            models::collection::NumberFormat,
+            models::collection_template::TemplatePermission,
+            models::collection_template::CollectionTemplate,
+            models::collection_template::CreateFromTemplateRequest,
+            models::collection_template::CreateFromTemplateResponse,
+            models::collection_template::SaveAsTemplateRequest,
+            utils::ApiResponse<models::collection_template::CollectionTemplate>,
+            utils::ApiResponse<Vec<models::collection_template::CollectionTemplate>>,
+            utils::ApiResponse<models::collection_template::CreateFromTemplateResponse>,
+            models::collection_view::ViewVisibility,
+            models::collection_view::CreateCollectionViewRequest,
+            models::collection_view::UpdateCollectionViewRequest,
+            models::collection_view::CollectionViewResponse,
+            utils::ApiResponse<models::collection_view::CollectionViewResponse>,
+            utils::ApiResponse<Vec<models::collection_view::CollectionViewResponse>>,
+            models::collection_integrity::IntegrityIssueKind,
+            models::collection_integrity::IntegrityIssue,
+            models::collection_integrity::CollectionIntegrityReport,
+            models::collection_integrity::CollectionRepairReport,
+            models::collection_integrity::CollectionHealthReport,
+            models::collection_integrity::OrphanObjectKind,
+            models::collection_integrity::OrphanObject,
+            models::collection_integrity::OrphanSweepReport,
+            utils::ApiResponse<models::collection_integrity::CollectionIntegrityReport>,
+            utils::ApiResponse<models::collection_integrity::CollectionRepairReport>,
+            utils::ApiResponse<models::collection_integrity::CollectionHealthReport>,
+            utils::ApiResponse<models::collection_integrity::OrphanSweepReport>,
+            models::collection_schema_version::CollectionSchemaVersionResponse,
+            utils::ApiResponse<models::collection_schema_version::CollectionSchemaVersionResponse>,
+            utils::ApiResponse<Vec<models::collection_schema_version::CollectionSchemaVersionResponse>>,
 
             models::collection::CreateRecordRequest,
             models::collection::UpdateRecordRequest,
+            models::collection::MoveRecordRequest,
+            models::collection::PublishRecordRequest,
+            models::collection::UnpublishRecordRequest,
+            models::collection::ValidateRecordRequest,
+            models::collection::FieldValidationError,
+            models::collection::RecordValidationResponse,
+            utils::ApiResponse<models::collection::RecordValidationResponse>,
             models::collection::RecordResponse,
             models::collection::FileUpload,
             handlers::collections::PaginatedRecordsResponse,
+            handlers::collections::PaginatedCollectionsResponse,
             handlers::collections::RecordWithCollection,
             handlers::collections::PaginationMeta,
+            handlers::collections::RecordPage,
+            handlers::collections::RecordList,
+            utils::ApiResponse<handlers::collections::RecordList>,
+            handlers::collections::GlobalSearchQuery,
+            handlers::collections::GlobalSearchGroup,
+            handlers::collections::GlobalSearchHit,
+            handlers::collections::RecordCountResponse,
+            models::batch::BatchMethod,
+            models::batch::BatchOperation,
+            models::batch::BatchRequest,
+            models::batch::BatchOperationResult,
+            models::batch::BatchResponse,
 
             models::permissions::Role,
             models::permissions::CollectionPermission,
+            models::permissions::RoleCollectionPermission,
             models::permissions::UserCollectionPermission,
             models::permissions::RecordPermission,
             models::permissions::CreateRoleRequest,
             models::permissions::SetCollectionPermissionRequest,
+            models::permissions::PermissionSet,
+            models::permissions::DefaultCollectionPermissions,
+            models::permissions::AccessFilter,
+            models::permissions::PermissionSource,
+            models::permissions::EffectivePermission,
+            models::permissions::RecordGrantSummary,
+            models::permissions::CollectionUserAccess,
+            models::permissions::CollectionUserAccessPage,
+            models::permissions::DenialSource,
+            models::permissions::PermissionDenialSummary,
+            models::permissions::UserRecordGrant,
+            models::permissions::UserRecordGrantPage,
+            models::permission_policy::PermissionPolicy,
+            models::permission_policy::PolicyRole,
+            models::permission_policy::PolicyCollection,
+            models::permission_policy::PermissionOverride,
+            models::permission_policy::PolicyChange,
+            models::permission_policy::PolicyError,
+            models::permission_policy::PolicyImportReport,
+            handlers::permissions::ImportPermissionsQuery,
+            handlers::record_permissions::UserRecordGrantsQuery,
+            handlers::record_permissions::RevokeUserRecordGrantsQuery,
+            handlers::permissions::CollectionUsersQuery,
             models::permissions::SetUserCollectionPermissionRequest,
             models::permissions::SetRecordPermissionRequest,
 
             handlers::ownership::TransferOwnershipRequest,
             handlers::ownership::GetOwnedRecordsQuery,
+            handlers::ownership::GlobalOwnershipStatsQuery,
+            models::ownership_stats::GlobalOwnershipStats,
+            models::ownership_stats::CollectionOwnershipStats,
+            models::ownership_stats::UserOwnershipStats,
+            models::ownership_stats::OrphanedRecord,
+            models::ownership_stats::OrphanReason,
+            utils::ApiResponse<models::ownership_stats::GlobalOwnershipStats>,
 
             handlers::users::CreateUserRequest,
             handlers::users::UpdateUserRequest,
             handlers::users::PaginatedUsersResponse,
             handlers::users::ListUsersQuery,
+            handlers::users::UnlockUserQuery,
+            handlers::users::VerifyUserEmailQuery,
 
             services::WebSocketStats,
             handlers::websocket::WebSocketStatus,
+            handlers::websocket::WebSocketLimitsStatus,
             handlers::websocket::ConnectionDetails,
             handlers::websocket::SubscriptionInfo,
             handlers::websocket::BroadcastRequest,
@@ -188,8 +402,34 @@ pub mod utils;
             handlers::health::DatabaseHealth,
             handlers::health::MemoryInfo,
             handlers::health::SystemInfo,
+            services::ComponentHealth,
+            services::health_recorder::HealthHistoryResponse,
+            services::health_recorder::ComponentUptime,
+            services::health_recorder::HealthSample,
 
             handlers::metrics::MetricsSummary,
+            handlers::metrics::MetricsCollectors,
+            models::admin_overview::AdminOverview,
+            models::admin_overview::UserOverview,
+            models::admin_overview::CollectionOverview,
+            models::admin_overview::StorageUsage,
+            models::admin_overview::WebSocketOverview,
+            models::admin_overview::AnalyticsGranularity,
+            models::admin_overview::UserAnalyticsBucket,
+            models::admin_overview::UserAnalyticsTotals,
+            models::admin_overview::UserAnalytics,
+            models::admin_overview::RecordAnalyticsBucket,
+            models::admin_overview::RecordAnalyticsTotals,
+            models::admin_overview::RecordAnalytics,
+            models::user::LockedAccount,
+            utils::ApiResponse<Vec<models::user::LockedAccount>>,
+            utils::ApiResponse<models::admin_overview::AdminOverview>,
+            utils::ApiResponse<models::admin_overview::UserAnalytics>,
+            utils::ApiResponse<models::admin_overview::RecordAnalytics>,
+            services::tls_status::TlsStatusReport,
+            services::tls_status::CertificateInfo,
+            services::tls_status::RenewalAttempt,
+            utils::ApiResponse<services::tls_status::TlsStatusReport>,
 
             models::system_setting::SystemSettingResponse,
             models::system_setting::SystemSettingRequest,
@@ -209,6 +449,40 @@ pub mod utils;
             handlers::image_upload::DeleteImageRequest,
             utils::ApiResponse<handlers::image_upload::ImageUploadResponse>,
             utils::ApiResponse<String>,
+
+            models::ingest::CreateIngestEndpointRequest,
+            models::ingest::IngestEndpointResponse,
+            models::ingest::IngestFailureResponse,
+            handlers::ingest::ListIngestFailuresQuery,
+            utils::ApiResponse<models::ingest::IngestEndpointResponse>,
+            utils::ApiResponse<Vec<models::ingest::IngestEndpointResponse>>,
+            utils::ApiResponse<Vec<models::ingest::IngestFailureResponse>>,
+            models::record_share::CreateRecordShareRequest,
+            models::record_share::RecordShareResponse,
+            models::record_share::SharedRecordResponse,
+            utils::ApiResponse<models::record_share::RecordShareResponse>,
+            utils::ApiResponse<Vec<models::record_share::RecordShareResponse>>,
+            utils::ApiResponse<models::record_share::SharedRecordResponse>,
+            models::notification::NotificationResponse,
+            models::notification::ListNotificationsQuery,
+            models::notification::NotificationListResponse,
+            models::notification::MarkNotificationsReadRequest,
+            models::notification::MarkNotificationsReadResponse,
+            utils::ApiResponse<models::notification::NotificationListResponse>,
+            utils::ApiResponse<models::notification::MarkNotificationsReadResponse>,
+            models::user_usage::UsageCounts,
+            models::user_usage::UsageQuotas,
+            models::user_usage::UserUsageResponse,
+            utils::ApiResponse<models::user_usage::UserUsageResponse>,
+            models::workspace::CreateWorkspaceRequest,
+            models::workspace::WorkspaceResponse,
+            models::workspace::SetWorkspaceMemberRequest,
+            models::workspace::WorkspaceMemberResponse,
+            models::workspace::MoveCollectionRequest,
+            models::workspace::SwitchWorkspaceRequest,
+            utils::ApiResponse<models::workspace::WorkspaceResponse>,
+            utils::ApiResponse<Vec<models::workspace::WorkspaceResponse>>,
+            utils::ApiResponse<models::workspace::WorkspaceMemberResponse>,
         )
     ),
     modifiers(&SecurityAddon),
@@ -226,7 +500,9 @@ pub mod utils;
         (name = "Monitoring", description = "System monitoring and metrics"),
         (name = "Configuration", description = "System configuration management"),
         (name = "Backup", description = "Database backup management"),
-        (name = "Images", description = "Image upload and management")
+        (name = "Images", description = "Image upload and management"),
+        (name = "Ingest", description = "Webhook ingest endpoints and dead-letter inspection"),
+        (name = "Workspaces", description = "Workspaces scoping collections, roles and memberships")
     )
 )]
 pub struct ApiDoc;
@@ -252,8 +528,12 @@ impl utoipa::Modify for SecurityAddon {
 pub use config::Config;
 pub use database::DatabasePool;
 use services::{
-    AdminService, BackupService, CollectionService, ConfigurationAccess, ConfigurationManager,
-    EmailService, OwnershipService, PermissionService, S3Service, WebSocketService,
+    AdminService, BackupService, CollectionService, CollectionTemplateService,
+    CollectionViewService, ConfigurationAccess, ConfigurationManager, DeleteConfirmations,
+    EmailRateLimiter, EmailService, HealthRecorder, HealthService, IngestService, LockoutService,
+    NotificationService, OwnershipService, PasswordService, PermissionAuditService,
+    PermissionService, QueryLimiter, QuotaWarningService, ReadinessState, RecordActivityService,
+    RecordShareService, S3Service, TlsStatus, UsageService, WebSocketService, WorkspaceService,
     create_backup_service_from_config, create_s3_service_from_config,
 };
 use std::sync::Arc;
@@ -263,11 +543,35 @@ pub struct AppState {
     pub auth_state: middleware::AuthState,
     pub metrics_state: middleware::MetricsState,
     pub collection_service: CollectionService,
+    pub collection_view_service: CollectionViewService,
+    pub collection_template_service: CollectionTemplateService,
     pub permission_service: PermissionService,
+    pub permission_audit_service: PermissionAuditService,
     pub ownership_service: OwnershipService,
     pub admin_service: AdminService,
+    pub record_activity_service: RecordActivityService,
+    pub lockout_service: LockoutService,
+    pub password_service: PasswordService,
     pub websocket_service: WebSocketService,
+    pub notification_service: NotificationService,
+    pub usage_service: UsageService,
     pub email_service: EmailService,
+    pub health_service: HealthService,
+    pub health_recorder: HealthRecorder,
+    pub readiness: ReadinessState,
+    pub listeners: listeners::ActiveListeners,
+    pub tls_status: TlsStatus,
+    pub query_limiter: QueryLimiter,
+    pub quota_warning_service: QuotaWarningService,
+    pub email_rate_limiter: EmailRateLimiter,
+    /// Counts guest sessions per client IP; separate from the email limiter
+    /// because the two use different windows
+    pub guest_session_limiter: EmailRateLimiter,
+    pub collections_openapi: openapi::CollectionsOpenApiCache,
+    pub delete_confirmations: DeleteConfirmations,
+    pub ingest_service: IngestService,
+    pub record_share_service: RecordShareService,
+    pub workspace_service: WorkspaceService,
     pub oauth_service: utils::OAuthService,
     pub backup_service: Option<BackupService>,
     pub configuration_manager: ConfigurationManager,
@@ -282,46 +586,114 @@ impl AppState {
         password_pepper: String,
         config: &Config,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        let permission_service = PermissionService::new(db_pool.clone());
-        let ownership_service = OwnershipService::new(db_pool.clone());
         let admin_service = AdminService::new(db_pool.clone());
         let metrics_state = middleware::MetricsState::new()?;
-        metrics_state.start_cpu_sampler();
         let configuration_manager = ConfigurationManager::new(db_pool.clone());
         configuration_manager.initialize().await?;
+        let permission_service =
+            PermissionService::new(db_pool.clone(), configuration_manager.clone());
+        metrics_state.start_cpu_sampler(permission_service.clone());
 
         let websocket_service =
             Arc::new(WebSocketService::new(Arc::new(permission_service.clone())));
+        websocket_service
+            .metrics()
+            .register(&metrics_state.registry)?;
+        let notification_service =
+            NotificationService::new(db_pool.clone(), websocket_service.clone());
+        let email_service =
+            EmailService::new(config, db_pool.clone(), configuration_manager.clone());
+        let record_activity_service = RecordActivityService::new(
+            db_pool.clone(),
+            configuration_manager.clone(),
+            email_service.clone(),
+        );
+        let quota_warning_service = QuotaWarningService::new(
+            configuration_manager.clone(),
+            email_service.clone(),
+            websocket_service.clone(),
+        );
         let mut collection_service =
             CollectionService::new(db_pool.clone(), configuration_manager.clone())
                 .with_websocket_service(websocket_service.clone())
-                .with_permission_service(permission_service.clone());
+                .with_permission_service(permission_service.clone())
+                .with_record_activity_service(record_activity_service.clone());
+        let ownership_service = OwnershipService::new(db_pool.clone())
+            .with_websocket_service(websocket_service.clone())
+            .with_record_cache(collection_service.record_cache.clone())
+            .with_query_cache(collection_service.query_cache.clone());
 
         let s3_service_option = create_s3_service_from_config(config).await.ok().flatten();
         if let Some(ref s3_service) = s3_service_option {
             collection_service = collection_service.with_s3_service(s3_service.clone());
         }
+        collection_service.sweep_orphans_on_start().await;
 
         let oauth_config = utils::oauth_service::OAuthConfig::from_database(
             &configuration_manager,
-            &config.frontend_url,
+            &config.public_api_url,
         )
         .await?;
         let oauth_service = utils::OAuthService::new(oauth_config, configuration_manager.clone());
 
-        let email_service =
-            EmailService::new(config, db_pool.clone(), configuration_manager.clone());
+        let permission_audit_service = PermissionAuditService::new(
+            permission_service.clone(),
+            configuration_manager.clone(),
+            email_service.clone(),
+            metrics_state.clone(),
+        );
+
+        let collection_view_service =
+            CollectionViewService::new(db_pool.clone(), collection_service.clone());
+        let collection_template_service = CollectionTemplateService::new(
+            db_pool.clone(),
+            collection_service.clone(),
+            permission_service.clone(),
+        );
+        let ingest_service = IngestService::new(db_pool.clone(), collection_service.clone());
+        let password_service =
+            PasswordService::new(configuration_manager.clone(), password_pepper.clone());
+        let record_share_service = RecordShareService::new(
+            db_pool.clone(),
+            collection_service.clone(),
+            jwt_secret,
+            password_service.clone(),
+        );
+        let workspace_service = WorkspaceService::new(
+            db_pool.clone(),
+            configuration_manager.clone(),
+            collection_service.clone(),
+        );
 
         let backup_service = create_backup_service_from_config(
             db_pool.clone(),
             s3_service_option.as_ref().map(|s| Arc::new(s.clone())),
             Arc::new(configuration_manager.clone()),
             Some(Arc::new(metrics_state.clone())),
+            Some(notification_service.clone()),
         )
         .await
         .ok()
         .flatten();
 
+        let s3_service_option = s3_service_option.map(Arc::new);
+
+        let tls_status = TlsStatus::new();
+        let health_service = HealthService::new(
+            s3_service_option.clone(),
+            email_service.clone(),
+            oauth_service.clone(),
+            backup_service.clone(),
+            tls_status.clone(),
+            collection_service.clone(),
+            configuration_manager.clone(),
+        );
+        let health_recorder = HealthRecorder::new(
+            db_pool.clone(),
+            health_service.clone(),
+            configuration_manager.clone(),
+        );
+
         Ok(Self {
             db_pool: db_pool.clone(),
             auth_state: middleware::AuthState::new(
@@ -332,15 +704,37 @@ impl AppState {
             .await,
             metrics_state,
             collection_service,
+            collection_view_service,
+            collection_template_service,
             permission_service,
+            permission_audit_service,
             ownership_service,
             admin_service,
+            record_activity_service,
+            lockout_service: LockoutService::new(db_pool.clone(), configuration_manager.clone()),
+            password_service,
             websocket_service: (*websocket_service).clone(),
+            notification_service,
+            usage_service: UsageService::new(db_pool.clone()),
             email_service,
+            health_service,
+            health_recorder,
+            readiness: ReadinessState::new(),
+            listeners: listeners::ActiveListeners::new(),
+            tls_status,
+            query_limiter: QueryLimiter::new(),
+            quota_warning_service,
+            email_rate_limiter: EmailRateLimiter::new(),
+            guest_session_limiter: EmailRateLimiter::new(),
+            collections_openapi: openapi::CollectionsOpenApiCache::new(&ApiDoc::openapi()),
+            delete_confirmations: DeleteConfirmations::new(),
+            ingest_service,
+            record_share_service,
+            workspace_service,
             oauth_service,
             backup_service,
             configuration_manager,
-            s3_service: s3_service_option.map(Arc::new),
+            s3_service: s3_service_option,
             password_pepper,
         })
     }
@@ -353,11 +747,33 @@ impl Clone for AppState {
             auth_state: self.auth_state.clone(),
             metrics_state: self.metrics_state.clone(),
             collection_service: self.collection_service.clone(),
+            collection_view_service: self.collection_view_service.clone(),
+            collection_template_service: self.collection_template_service.clone(),
             permission_service: self.permission_service.clone(),
+            permission_audit_service: self.permission_audit_service.clone(),
             ownership_service: self.ownership_service.clone(),
             admin_service: self.admin_service.clone(),
+            record_activity_service: self.record_activity_service.clone(),
+            lockout_service: self.lockout_service.clone(),
+            password_service: self.password_service.clone(),
             websocket_service: self.websocket_service.clone(),
+            notification_service: self.notification_service.clone(),
+            usage_service: self.usage_service.clone(),
             email_service: self.email_service.clone(),
+            health_service: self.health_service.clone(),
+            health_recorder: self.health_recorder.clone(),
+            readiness: self.readiness.clone(),
+            listeners: self.listeners.clone(),
+            tls_status: self.tls_status.clone(),
+            query_limiter: self.query_limiter.clone(),
+            quota_warning_service: self.quota_warning_service.clone(),
+            email_rate_limiter: self.email_rate_limiter.clone(),
+            guest_session_limiter: self.guest_session_limiter.clone(),
+            collections_openapi: self.collections_openapi.clone(),
+            delete_confirmations: self.delete_confirmations.clone(),
+            ingest_service: self.ingest_service.clone(),
+            record_share_service: self.record_share_service.clone(),
+            workspace_service: self.workspace_service.clone(),
             oauth_service: self.oauth_service.clone(),
             backup_service: self.backup_service.clone(),
             configuration_manager: self.configuration_manager.clone(),
@@ -0,0 +1,590 @@
+use hyper_util::rt::{TokioExecutor, TokioTimer};
+use hyper_util::server::conn::auto::Builder;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll, ready};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Permissions of the Unix socket unless configured otherwise: the owner and
+/// its group (usually the reverse proxy) may connect.
+pub const DEFAULT_UNIX_SOCKET_MODE: u32 = 0o660;
+
+const LISTEN_BACKLOG: i32 = 1024;
+
+/// hyper refuses HTTP/1 read buffers smaller than this.
+const MIN_HTTP1_BUFFER_SIZE: usize = 8192;
+
+const HTTP2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenAddress {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl fmt::Display for ListenAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListenAddress::Tcp(address) => write!(f, "tcp://{}", address),
+            ListenAddress::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// Addresses the server currently accepts connections on, reported by the
+/// admin health and metrics endpoints.
+#[derive(Clone, Default)]
+pub struct ActiveListeners {
+    addresses: Arc<RwLock<Vec<ListenAddress>>>,
+}
+
+impl ActiveListeners {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self, addresses: Vec<ListenAddress>) {
+        *self.addresses.write().unwrap_or_else(|e| e.into_inner()) = addresses;
+    }
+
+    pub fn clear(&self) {
+        self.set(Vec::new());
+    }
+
+    pub fn list(&self) -> Vec<String> {
+        self.addresses
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+}
+
+/// Protocol and connection limits applied to every listener.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerTuning {
+    /// Offer HTTP/2 to TLS clients through ALPN.
+    pub http2: bool,
+    /// Accept HTTP/2 with prior knowledge (h2c) on plain listeners, for
+    /// trusted reverse proxies that speak it to the backend.
+    pub http2_cleartext: bool,
+    /// How long an idle connection may wait for its next request, and the
+    /// interval of HTTP/2 keep-alive pings.
+    pub keep_alive_timeout: Duration,
+    pub max_concurrent_streams: u32,
+    pub max_header_size: usize,
+}
+
+impl Default for ServerTuning {
+    fn default() -> Self {
+        Self {
+            http2: true,
+            http2_cleartext: false,
+            keep_alive_timeout: Duration::from_secs(75),
+            max_concurrent_streams: 200,
+            max_header_size: 64 * 1024,
+        }
+    }
+}
+
+impl ServerTuning {
+    /// ALPN protocols the TLS listeners advertise, most preferred first.
+    pub fn alpn_protocols(&self) -> Vec<Vec<u8>> {
+        let mut protocols = Vec::new();
+        if self.http2 {
+            protocols.push(b"h2".to_vec());
+        }
+        protocols.push(b"http/1.1".to_vec());
+        protocols
+    }
+
+    /// Whether connections on a listener may speak HTTP/2. TLS listeners
+    /// negotiate it; plain ones only accept it when h2c is enabled.
+    pub fn allows_http2(&self, tls: bool) -> bool {
+        self.http2 && (tls || self.http2_cleartext)
+    }
+
+    /// Applies the limits to a connection builder. Extended CONNECT is not
+    /// enabled, so browsers open WebSockets over a separate HTTP/1.1
+    /// connection even when the page itself uses HTTP/2.
+    pub fn configure(&self, builder: &mut Builder<TokioExecutor>) {
+        builder
+            .http1()
+            .timer(TokioTimer::new())
+            .header_read_timeout(self.keep_alive_timeout)
+            .max_buf_size(self.max_header_size.max(MIN_HTTP1_BUFFER_SIZE));
+        builder
+            .http2()
+            .timer(TokioTimer::new())
+            .keep_alive_interval(self.keep_alive_timeout)
+            .max_concurrent_streams(self.max_concurrent_streams)
+            .max_header_list_size(u32::try_from(self.max_header_size).unwrap_or(u32::MAX));
+    }
+}
+
+/// Parses `--bind` values, dropping duplicates. IPv6 addresses need brackets,
+/// as in `[::]:3000`.
+pub fn parse_bind_addresses(values: &[String]) -> Result<Vec<SocketAddr>, String> {
+    let mut addresses = Vec::new();
+    for value in values {
+        let address: SocketAddr = value.trim().parse().map_err(|_| {
+            format!(
+                "Invalid bind address '{}', expected host:port such as 0.0.0.0:3000 or [::]:3000",
+                value
+            )
+        })?;
+        if !addresses.contains(&address) {
+            addresses.push(address);
+        }
+    }
+    Ok(addresses)
+}
+
+/// Parses octal socket permissions such as `660` or `0o660`.
+pub fn parse_socket_mode(value: &str) -> Result<u32, String> {
+    let digits = value.trim();
+    let digits = digits.strip_prefix("0o").unwrap_or(digits);
+    u32::from_str_radix(digits, 8)
+        .ok()
+        .filter(|mode| *mode <= 0o777)
+        .ok_or_else(|| {
+            format!(
+                "Invalid socket mode '{}', expected octal permissions such as 660",
+                value
+            )
+        })
+}
+
+/// Parses a numeric `UID[:GID]` socket owner; either side may be left empty
+/// to keep it unchanged, as in `:33`.
+pub fn parse_socket_owner(value: &str) -> Result<(Option<u32>, Option<u32>), String> {
+    let invalid = || {
+        format!(
+            "Invalid socket owner '{}', expected numeric UID[:GID] such as 1000:33",
+            value
+        )
+    };
+    let parse_id = |id: &str| -> Result<Option<u32>, String> {
+        if id.is_empty() {
+            Ok(None)
+        } else {
+            id.parse().map(Some).map_err(|_| invalid())
+        }
+    };
+
+    let (uid, gid) = value.trim().split_once(':').unwrap_or((value.trim(), ""));
+    let owner = (parse_id(uid)?, parse_id(gid)?);
+    if owner == (None, None) {
+        return Err(invalid());
+    }
+    Ok(owner)
+}
+
+/// Binds the TCP listeners for `addresses`. IPv6 sockets are made IPv6-only
+/// when IPv4 addresses are bound as well, so `[::]:3000` and `0.0.0.0:3000`
+/// can be used together.
+pub fn bind_tcp_listeners(addresses: &[SocketAddr]) -> io::Result<Vec<std::net::TcpListener>> {
+    let has_ipv4 = addresses.iter().any(SocketAddr::is_ipv4);
+    addresses
+        .iter()
+        .map(|address| bind_tcp(*address, has_ipv4))
+        .collect()
+}
+
+fn bind_tcp(address: SocketAddr, v6_only: bool) -> io::Result<std::net::TcpListener> {
+    let socket = Socket::new(
+        Domain::for_address(address),
+        Type::STREAM,
+        Some(Protocol::TCP),
+    )?;
+    if address.is_ipv6() && v6_only {
+        socket.set_only_v6(true)?;
+    }
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket
+        .bind(&address.into())
+        .map_err(|e| io::Error::new(e.kind(), format!("Failed to bind {}: {}", address, e)))?;
+    socket.listen(LISTEN_BACKLOG)?;
+    Ok(socket.into())
+}
+
+/// The file of a bound Unix socket, removed again when dropped.
+#[derive(Debug)]
+pub struct UnixSocketFile {
+    path: PathBuf,
+}
+
+impl UnixSocketFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for UnixSocketFile {
+    fn drop(&mut self) {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                tracing::warn!(
+                    "Failed to remove Unix socket {}: {}",
+                    self.path.display(),
+                    e
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Binds a Unix socket at `path` and applies `mode` and the optional owner.
+/// A socket file left behind by a previous run is replaced, but a live socket
+/// or any other kind of file is not.
+#[cfg(unix)]
+pub fn bind_unix_socket(
+    path: &Path,
+    mode: u32,
+    owner: (Option<u32>, Option<u32>),
+) -> io::Result<(tokio::net::UnixListener, UnixSocketFile)> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("Another process is listening on {}", path.display()),
+                ));
+            }
+            std::fs::remove_file(path)?;
+        }
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", path.display()),
+            ));
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+
+    let listener = tokio::net::UnixListener::bind(path)?;
+    let file = UnixSocketFile {
+        path: path.to_path_buf(),
+    };
+
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    if owner != (None, None) {
+        std::os::unix::fs::chown(path, owner.0, owner.1)?;
+    }
+
+    Ok((listener, file))
+}
+
+/// Creates the server for a bound TCP listener with `tuning` applied. Plain
+/// listeners attach a [`CleartextAcceptor`], TLS listeners their TLS acceptor.
+pub fn tcp_server(
+    listener: std::net::TcpListener,
+    tuning: &ServerTuning,
+    handle: axum_server::Handle,
+) -> axum_server::Server {
+    let mut server = axum_server::from_tcp(listener).handle(handle);
+    tuning.configure(server.http_builder());
+    server
+}
+
+/// Accepts plain HTTP connections, refusing HTTP/2 prior knowledge unless
+/// h2c is enabled.
+#[derive(Debug, Clone, Copy)]
+pub struct CleartextAcceptor {
+    allow_h2c: bool,
+}
+
+impl CleartextAcceptor {
+    pub fn new(tuning: &ServerTuning) -> Self {
+        Self {
+            allow_h2c: tuning.allows_http2(false),
+        }
+    }
+}
+
+impl<I, S> axum_server::accept::Accept<I, S> for CleartextAcceptor
+where
+    I: AsyncRead + AsyncWrite + Unpin,
+{
+    type Stream = CleartextStream<I>;
+    type Service = S;
+    type Future = std::future::Ready<io::Result<(Self::Stream, Self::Service)>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        std::future::ready(Ok((CleartextStream::new(stream, self.allow_h2c), service)))
+    }
+}
+
+/// A plain connection that fails once the client has sent the complete
+/// HTTP/2 connection preface, unless h2c is allowed. hyper's protocol
+/// detection ignores `http1_only` when upgrades are enabled, and WebSockets
+/// need them.
+pub struct CleartextStream<I> {
+    inner: I,
+    preface_matched: Option<usize>,
+}
+
+impl<I> CleartextStream<I> {
+    pub fn new(inner: I, allow_h2c: bool) -> Self {
+        Self {
+            inner,
+            preface_matched: (!allow_h2c).then_some(0),
+        }
+    }
+}
+
+impl<I: AsyncRead + Unpin> AsyncRead for CleartextStream<I> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+        ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+
+        if let Some(matched) = self.preface_matched {
+            let read = &buf.filled()[filled..];
+            let expected = &HTTP2_PREFACE[matched..];
+            let compared = read.len().min(expected.len());
+            if read[..compared] != expected[..compared] {
+                self.preface_matched = None;
+            } else if matched + compared == HTTP2_PREFACE.len() {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "HTTP/2 prior knowledge (h2c) is disabled",
+                )));
+            } else {
+                self.preface_matched = Some(matched + compared);
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<I: AsyncWrite + Unpin> AsyncWrite for CleartextStream<I> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// Serves `app` on a Unix socket until `shutdown` resolves, then lets open
+/// connections finish their requests.
+#[cfg(unix)]
+pub async fn serve_unix_socket(
+    listener: tokio::net::UnixListener,
+    app: axum::Router,
+    tuning: &ServerTuning,
+    shutdown: impl std::future::Future<Output = ()>,
+) {
+    use hyper_util::rt::TokioIo;
+    use hyper_util::server::graceful::GracefulShutdown;
+    use hyper_util::service::TowerToHyperService;
+
+    let mut builder = Builder::new(TokioExecutor::new());
+    tuning.configure(&mut builder);
+    let allow_h2c = tuning.allows_http2(false);
+    let graceful = GracefulShutdown::new();
+    tokio::pin!(shutdown);
+
+    loop {
+        let stream = tokio::select! {
+            result = listener.accept() => match result {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    tracing::warn!("Failed to accept Unix socket connection: {}", e);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        let connection = builder
+            .serve_connection_with_upgrades(
+                TokioIo::new(CleartextStream::new(stream, allow_h2c)),
+                TowerToHyperService::new(app.clone()),
+            )
+            .into_owned();
+        tokio::spawn(graceful.watch(connection));
+    }
+
+    drop(listener);
+    graceful.shutdown().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bind_addresses() {
+        let addresses = parse_bind_addresses(&[
+            "0.0.0.0:3000".to_string(),
+            "[::]:3000".to_string(),
+            " 0.0.0.0:3000 ".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(
+            addresses,
+            vec![
+                "0.0.0.0:3000".parse::<SocketAddr>().unwrap(),
+                "[::]:3000".parse::<SocketAddr>().unwrap()
+            ]
+        );
+
+        assert!(parse_bind_addresses(&["localhost:3000".to_string()]).is_err());
+        assert!(parse_bind_addresses(&["::1:3000".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_socket_mode_and_owner() {
+        assert_eq!(parse_socket_mode("660"), Ok(0o660));
+        assert_eq!(parse_socket_mode("0o600"), Ok(0o600));
+        assert_eq!(parse_socket_mode("0770"), Ok(0o770));
+        assert!(parse_socket_mode("999").is_err());
+        assert!(parse_socket_mode("1777").is_err());
+
+        assert_eq!(parse_socket_owner("1000:33"), Ok((Some(1000), Some(33))));
+        assert_eq!(parse_socket_owner("1000"), Ok((Some(1000), None)));
+        assert_eq!(parse_socket_owner(":33"), Ok((None, Some(33))));
+        assert!(parse_socket_owner(":").is_err());
+        assert!(parse_socket_owner("www-data").is_err());
+    }
+
+    #[test]
+    fn test_alpn_protocols_follow_http2_setting() {
+        let tuning = ServerTuning::default();
+        assert_eq!(
+            tuning.alpn_protocols(),
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        );
+        assert!(tuning.allows_http2(true));
+        assert!(!tuning.allows_http2(false));
+
+        let tuning = ServerTuning {
+            http2: false,
+            http2_cleartext: true,
+            ..ServerTuning::default()
+        };
+        assert_eq!(tuning.alpn_protocols(), vec![b"http/1.1".to_vec()]);
+        assert!(!tuning.allows_http2(true));
+        assert!(!tuning.allows_http2(false));
+    }
+
+    #[tokio::test]
+    async fn test_cleartext_stream_refuses_http2_preface_unless_allowed() {
+        use tokio::io::AsyncReadExt;
+
+        async fn read_all(data: &'static [u8], allow_h2c: bool) -> io::Result<Vec<u8>> {
+            let mut stream = CleartextStream::new(data, allow_h2c);
+            let mut read = Vec::new();
+            let mut chunk = [0u8; 8];
+            loop {
+                match stream.read(&mut chunk).await? {
+                    0 => return Ok(read),
+                    n => read.extend_from_slice(&chunk[..n]),
+                }
+            }
+        }
+
+        let request = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n\x00\x00";
+        assert_eq!(
+            read_all(request, false).await.unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert_eq!(read_all(request, true).await.unwrap(), request.to_vec());
+
+        let request = b"PRI /items HTTP/1.1\r\nhost: localhost\r\n\r\n";
+        assert_eq!(read_all(request, false).await.unwrap(), request.to_vec());
+    }
+
+    #[test]
+    fn test_dual_stack_binding() {
+        let listeners =
+            bind_tcp_listeners(&["127.0.0.1:0".parse().unwrap(), "[::1]:0".parse().unwrap()]);
+        // Hosts without IPv6 cannot bind the second address
+        if let Ok(listeners) = listeners {
+            assert_eq!(listeners.len(), 2);
+            assert!(listeners[1].local_addr().unwrap().is_ipv6());
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket_permissions_and_cleanup() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("lunarbase-uds-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("lunarbase.sock");
+
+        let (listener, file) = bind_unix_socket(&path, 0o600, (None, None)).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // A live socket is not replaced
+        assert_eq!(
+            bind_unix_socket(&path, 0o600, (None, None))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::AddrInUse
+        );
+
+        drop(listener);
+        drop(file);
+        assert!(!path.exists());
+
+        // A stale socket file from a crashed run is replaced
+        let stale = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        drop(stale);
+        let (_listener, file) = bind_unix_socket(&path, 0o660, (None, None)).unwrap();
+        assert_eq!(file.path(), path.as_path());
+        drop(file);
+
+        std::fs::write(&path, b"not a socket").unwrap();
+        assert_eq!(
+            bind_unix_socket(&path, 0o660, (None, None))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::AlreadyExists
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
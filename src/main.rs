@@ -1,5 +1,5 @@
 use clap::Parser;
-use lunarbase::cli::{Cli, Commands};
+use lunarbase::cli::{Cli, Commands, run_migrate, run_permissions};
 use lunarbase::server::run_server;
 
 #[tokio::main]
@@ -10,6 +10,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Commands::Serve(serve_args) => {
             run_server(&serve_args).await?;
         }
+        Commands::Permissions(permissions_args) => {
+            run_permissions(&permissions_args).await?;
+        }
+        Commands::Migrate(migrate_args) => {
+            run_migrate(&migrate_args).await?;
+        }
     }
 
     Ok(())
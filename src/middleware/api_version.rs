@@ -0,0 +1,149 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use chrono::NaiveDate;
+
+use crate::middleware::AuthState;
+use crate::services::ConfigurationAccess;
+use crate::utils::LunarbaseError;
+
+/// Request header selecting the API version, echoed on every response.
+pub const API_VERSION_HEADER: &str = "x-api-version";
+
+/// Versions of the API whose responses differ. Handlers that changed take an
+/// `Option<Extension<ApiVersion>>` and answer in the shape of the requested
+/// version; without the extension (routes outside the versioning layer) they
+/// keep answering as version 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiVersion {
+    /// Original response shapes
+    V1,
+    /// Record lists return `{ records, pagination }` instead of a bare array
+    V2,
+}
+
+impl ApiVersion {
+    pub const ALL: [ApiVersion; 2] = [ApiVersion::V1, ApiVersion::V2];
+    pub const LATEST: ApiVersion = ApiVersion::V2;
+
+    pub fn number(self) -> u32 {
+        match self {
+            ApiVersion::V1 => 1,
+            ApiVersion::V2 => 2,
+        }
+    }
+
+    pub fn from_number(number: u32) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|version| version.number() == number)
+    }
+
+    /// Accepts `2` as well as `v2`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let number = value
+            .strip_prefix(['v', 'V'])
+            .unwrap_or(value)
+            .parse()
+            .ok()?;
+        Self::from_number(number)
+    }
+
+    fn supported() -> String {
+        Self::ALL
+            .iter()
+            .map(|version| version.number().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Parses an `api.v{n}_deprecation_date` or `api.v{n}_sunset_date` setting.
+pub fn parse_version_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok()
+}
+
+/// Resolves the version of the request from `X-Api-Version`, falling back to
+/// `api.default_version`, and stores it in the request extensions. Responses
+/// of versions with an `api.v{n}_deprecation_date` or `api.v{n}_sunset_date`
+/// carry `Deprecation` (RFC 9745) and `Sunset` (RFC 8594) headers.
+pub async fn api_version_middleware(
+    State(auth_state): State<AuthState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, LunarbaseError> {
+    let version = match request.headers().get(API_VERSION_HEADER) {
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(ApiVersion::parse)
+            .ok_or_else(|| {
+                LunarbaseError::BadRequest(format!(
+                    "Unsupported API version; X-Api-Version must be one of {}",
+                    ApiVersion::supported()
+                ))
+            })?,
+        None => ApiVersion::from_number(auth_state.get_api_default_version().await)
+            .unwrap_or(ApiVersion::V1),
+    };
+    request.extensions_mut().insert(version);
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(
+        HeaderName::from_static(API_VERSION_HEADER),
+        HeaderValue::from(version.number()),
+    );
+
+    let deprecation = auth_state.get_api_deprecation_date(version.number()).await;
+    if let Some(date) = parse_version_date(&deprecation) {
+        let timestamp = date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        if let Ok(value) = HeaderValue::from_str(&format!("@{}", timestamp.timestamp())) {
+            headers.insert(HeaderName::from_static("deprecation"), value);
+        }
+    }
+
+    let sunset = auth_state.get_api_sunset_date(version.number()).await;
+    if let Some(date) = parse_version_date(&sunset) {
+        let http_date = date
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc()
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+        if let Ok(value) = HeaderValue::from_str(&http_date) {
+            headers.insert(HeaderName::from_static("sunset"), value);
+        }
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_parsing() {
+        assert_eq!(ApiVersion::parse("1"), Some(ApiVersion::V1));
+        assert_eq!(ApiVersion::parse(" v2 "), Some(ApiVersion::V2));
+        assert_eq!(ApiVersion::parse("V2"), Some(ApiVersion::V2));
+        assert_eq!(ApiVersion::parse("3"), None);
+        assert_eq!(ApiVersion::parse("latest"), None);
+        assert_eq!(ApiVersion::LATEST, *ApiVersion::ALL.last().unwrap());
+    }
+
+    #[test]
+    fn test_version_dates() {
+        assert_eq!(
+            parse_version_date("2026-03-01"),
+            NaiveDate::from_ymd_opt(2026, 3, 1)
+        );
+        assert_eq!(parse_version_date(""), None);
+        assert_eq!(parse_version_date("March 2026"), None);
+    }
+}
@@ -1,6 +1,6 @@
 use axum::{
-    extract::{Request, State},
-    http::header::AUTHORIZATION,
+    extract::{MatchedPath, Request, State},
+    http::{Method, header::AUTHORIZATION},
     middleware::Next,
     response::Response,
 };
@@ -75,13 +75,44 @@ pub async fn auth_middleware(
 
     let claims = auth_state
         .jwt_service
-        .validate_access_token_with_verification(&token)?;
+        .validate_access_or_guest_token(&token)?;
+
+    if claims.guest.is_some() && !is_guest_request(&request) {
+        tracing::debug!(
+            "Rejected guest session token for {} {}",
+            request.method(),
+            request.uri().path()
+        );
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    if !matches_workspace_mode(&auth_state, &claims).await {
+        tracing::debug!("Rejected token issued before workspaces were toggled");
+        return Err(LunarbaseError::TokenInvalid);
+    }
 
     request.extensions_mut().insert(claims);
 
     Ok(next.run(request).await)
 }
 
+/// Tokens carry a workspace exactly while workspaces are enabled, so toggling
+/// the setting signs everyone out instead of leaving unscoped sessions behind.
+async fn matches_workspace_mode(auth_state: &AuthState, claims: &Claims) -> bool {
+    claims.guest.is_some()
+        || claims.workspace_id.is_some() == auth_state.get_enable_workspaces().await
+}
+
+/// Guest session tokens may only create records; the handler checks the
+/// collection against the session's scope.
+fn is_guest_request(request: &Request) -> bool {
+    request.method() == Method::POST
+        && request
+            .extensions()
+            .get::<MatchedPath>()
+            .is_some_and(|path| path.as_str().ends_with("/collections/{name}/records"))
+}
+
 pub async fn optional_auth_middleware(
     State(auth_state): State<AuthState>,
     mut request: Request,
@@ -105,6 +136,7 @@ pub async fn optional_auth_middleware(
         if let Ok(claims) = auth_state
             .jwt_service
             .validate_access_token_with_blacklist(&token)
+            && matches_workspace_mode(&auth_state, &claims).await
         {
             request.extensions_mut().insert(claims);
         }
@@ -0,0 +1,197 @@
+use axum::{
+    body::Body,
+    extract::{Request, State, multipart::Field},
+    http::{Method, header},
+    middleware::Next,
+    response::Response,
+};
+use http_body_util::{BodyExt, LengthLimitError, Limited};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::middleware::AuthState;
+use crate::services::ConfigurationAccess;
+use crate::utils::LunarbaseError;
+
+/// A body size limit and the `limits.*` setting it comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyLimit {
+    pub setting: &'static str,
+    amount: u32,
+    unit: SizeUnit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SizeUnit {
+    Kilobytes,
+    Megabytes,
+}
+
+impl BodyLimit {
+    /// Request bodies of the `/auth` endpoints
+    pub async fn auth<C: ConfigurationAccess>(config: &C) -> Self {
+        Self {
+            setting: "auth_body_kb",
+            amount: config.get_auth_body_limit_kb().await,
+            unit: SizeUnit::Kilobytes,
+        }
+    }
+
+    /// JSON request bodies and the `data` part of multipart record requests
+    pub async fn record_json<C: ConfigurationAccess>(config: &C) -> Self {
+        Self {
+            setting: "record_json_mb",
+            amount: config.get_record_json_limit_mb().await,
+            unit: SizeUnit::Megabytes,
+        }
+    }
+
+    /// Each uploaded file part
+    pub async fn file_upload<C: ConfigurationAccess>(config: &C) -> Self {
+        Self {
+            setting: "file_upload_mb",
+            amount: config.get_file_upload_limit_mb().await,
+            unit: SizeUnit::Megabytes,
+        }
+    }
+
+    pub fn bytes(&self) -> usize {
+        let unit = match self.unit {
+            SizeUnit::Kilobytes => 1024,
+            SizeUnit::Megabytes => 1024 * 1024,
+        };
+        (self.amount as usize).saturating_mul(unit)
+    }
+
+    pub fn exceeded(&self) -> LunarbaseError {
+        let unit = match self.unit {
+            SizeUnit::Kilobytes => "KB",
+            SizeUnit::Megabytes => "MB",
+        };
+        LunarbaseError::PayloadTooLarge {
+            setting: self.setting,
+            limit: format!("{} {}", self.amount, unit),
+        }
+    }
+}
+
+enum RequestBody {
+    Auth,
+    Json,
+    /// Multipart uploads are limited per part by the handlers
+    Multipart,
+}
+
+fn request_body(method: &Method, path: &str) -> RequestBody {
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    match (method, segments.as_slice()) {
+        (_, ["auth", ..]) => RequestBody::Auth,
+        (&Method::POST, ["collections", _, "records"])
+        | (&Method::PUT, ["collections", _, "records", _])
+        | (&Method::POST, ["upload-image"]) => RequestBody::Multipart,
+        _ => RequestBody::Json,
+    }
+}
+
+/// Caps request bodies at the `limits.*` setting of their route group. Bodies
+/// announcing a larger `Content-Length` are rejected before they are read;
+/// others are cut off once they pass the limit.
+pub async fn body_limit_middleware(
+    State(auth_state): State<AuthState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, LunarbaseError> {
+    let limit = match request_body(request.method(), request.uri().path()) {
+        RequestBody::Auth => BodyLimit::auth(&auth_state).await,
+        RequestBody::Json => BodyLimit::record_json(&auth_state).await,
+        RequestBody::Multipart => return Ok(next.run(request).await),
+    };
+
+    let content_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if content_length.is_some_and(|length| length > limit.bytes()) {
+        return Err(limit.exceeded());
+    }
+
+    // Handlers map extractor rejections to their own errors, so whether the
+    // body was cut off is tracked here rather than read from the response
+    let cut_off = Arc::new(AtomicBool::new(false));
+    let request = request.map(|body| {
+        let cut_off = cut_off.clone();
+        Body::new(Limited::new(body, limit.bytes()).map_err(move |err| {
+            if err.is::<LengthLimitError>() {
+                cut_off.store(true, Ordering::Relaxed);
+            }
+            err
+        }))
+    });
+    let response = next.run(request).await;
+
+    if cut_off.load(Ordering::Relaxed) {
+        return Err(limit.exceeded());
+    }
+    Ok(response)
+}
+
+/// Reads a multipart part chunk by chunk, failing as soon as it passes `limit`
+/// instead of buffering the whole part first.
+pub async fn read_field_limited(
+    mut field: Field<'_>,
+    limit: BodyLimit,
+    read_error: &str,
+) -> Result<Vec<u8>, LunarbaseError> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = field
+        .chunk()
+        .await
+        .map_err(|_| LunarbaseError::BadRequest(read_error.to_string()))?
+    {
+        if bytes.len() + chunk.len() > limit.bytes() {
+            return Err(limit.exceeded());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes_are_grouped_by_body_kind() {
+        assert!(matches!(
+            request_body(&Method::POST, "/api/auth/login"),
+            RequestBody::Auth
+        ));
+        assert!(matches!(
+            request_body(&Method::POST, "/auth/refresh"),
+            RequestBody::Auth
+        ));
+        assert!(matches!(
+            request_body(&Method::POST, "/collections/posts/records"),
+            RequestBody::Multipart
+        ));
+        assert!(matches!(
+            request_body(&Method::PUT, "/api/collections/posts/records/1"),
+            RequestBody::Multipart
+        ));
+        assert!(matches!(
+            request_body(&Method::POST, "/upload-image"),
+            RequestBody::Multipart
+        ));
+        assert!(matches!(
+            request_body(&Method::POST, "/collections/posts/records/validate"),
+            RequestBody::Json
+        ));
+        assert!(matches!(
+            request_body(&Method::PUT, "/collections/posts"),
+            RequestBody::Json
+        ));
+    }
+}
@@ -1,13 +1,42 @@
+use axum::{extract::Request, middleware::Next, response::Response};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tower_http::compression::predicate::{And, NotForContentType, Predicate, SizeAbove};
 use tower_http::compression::{CompressionLayer, CompressionLevel};
 use tracing::{debug, warn};
 
+pub type CompressionPredicate = And<
+    And<And<And<SizeAbove, NotForContentType>, NotForContentType>, NotForContentType>,
+    NotMarkedSkip,
+>;
+
+/// Response extension that keeps the compression layer away from a response.
+/// Handlers can return `Extension(SkipCompression)` for streaming or
+/// already-compressed bodies.
+#[derive(Debug, Clone, Copy)]
+pub struct SkipCompression;
+
+/// Compression predicate that honours the [`SkipCompression`] marker.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NotMarkedSkip;
+
+impl Predicate for NotMarkedSkip {
+    fn should_compress<B>(&self, response: &axum::http::Response<B>) -> bool
+    where
+        B: axum::body::HttpBody,
+    {
+        response.extensions().get::<SkipCompression>().is_none()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompressionConfig {
     pub enabled: bool,
     pub level: u8,
     pub min_size: usize,
     pub algorithms: CompressionAlgorithms,
+    /// Path prefixes whose responses are never compressed
+    pub skip_paths: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +53,7 @@ impl Default for CompressionConfig {
             level: 6,
             min_size: 1024,
             algorithms: CompressionAlgorithms::default(),
+            skip_paths: Vec::new(),
         }
     }
 }
@@ -53,6 +83,7 @@ impl CompressionConfig {
                 brotli: true,
                 deflate: true,
             },
+            skip_paths: Vec::new(),
         }
     }
 
@@ -66,6 +97,7 @@ impl CompressionConfig {
                 brotli: false,
                 deflate: false,
             },
+            skip_paths: Vec::new(),
         }
     }
 
@@ -86,10 +118,28 @@ impl CompressionConfig {
     }
 }
 
-pub fn create_compression_layer(config: &CompressionConfig) -> Result<CompressionLayer, String> {
+/// Mirrors tower-http's default predicate (no images, gRPC or event streams)
+/// but uses the configured minimum size and respects [`SkipCompression`].
+pub fn compression_predicate(config: &CompressionConfig) -> CompressionPredicate {
+    let min_size = u16::try_from(config.min_size).unwrap_or(u16::MAX);
+
+    SizeAbove::new(min_size)
+        .and(NotForContentType::GRPC)
+        .and(NotForContentType::IMAGES)
+        .and(NotForContentType::SSE)
+        .and(NotMarkedSkip)
+}
+
+pub fn create_compression_layer(
+    config: &CompressionConfig,
+) -> Result<CompressionLayer<CompressionPredicate>, String> {
     if !config.enabled {
         debug!("Compression is disabled in configuration");
-        return Ok(CompressionLayer::new().no_br().no_gzip().no_deflate());
+        return Ok(CompressionLayer::new()
+            .no_br()
+            .no_gzip()
+            .no_deflate()
+            .compress_when(compression_predicate(config)));
     }
 
     config.validate()?;
@@ -121,7 +171,34 @@ pub fn create_compression_layer(config: &CompressionConfig) -> Result<Compressio
         layer = layer.no_deflate();
     }
 
-    Ok(layer)
+    Ok(layer.compress_when(compression_predicate(config)))
+}
+
+/// Marks responses for configured path prefixes with [`SkipCompression`].
+/// Must sit inside the compression layer so the marker is seen on the way out.
+pub async fn compression_skip_middleware(
+    skip_paths: Arc<Vec<String>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let skip = is_skipped_path(request.uri().path(), &skip_paths);
+    let mut response = next.run(request).await;
+
+    if skip {
+        response.extensions_mut().insert(SkipCompression);
+    }
+
+    response
+}
+
+fn is_skipped_path(path: &str, skip_paths: &[String]) -> bool {
+    skip_paths.iter().any(|prefix| {
+        let prefix = prefix.trim_end_matches('/');
+        !prefix.is_empty()
+            && path
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })
 }
 
 pub struct CompressionMetrics {
@@ -153,6 +230,68 @@ impl CompressionMetrics {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use axum::{
+        Extension, Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+        response::sse::{Event, Sse},
+        routing::get,
+    };
+    use http_body_util::BodyExt;
+    use std::convert::Infallible;
+    use std::time::Duration;
+    use tokio_stream::StreamExt;
+    use tower::ServiceExt;
+
+    const DOWNLOAD_SIZE: usize = 4096;
+
+    fn compressed_router(skip_paths: Vec<String>) -> Router {
+        let config = CompressionConfig {
+            skip_paths,
+            ..CompressionConfig::production()
+        };
+        let skip_paths = Arc::new(config.skip_paths.clone());
+
+        Router::new()
+            .route(
+                "/api/events",
+                get(|| async {
+                    let stream = tokio_stream::iter(vec![Ok::<_, Infallible>(
+                        Event::default().data("hello"),
+                    )])
+                    .chain(tokio_stream::pending());
+                    Sse::new(stream)
+                }),
+            )
+            .route("/api/files/report", get(download))
+            .route("/api/reports", get(download))
+            .route(
+                "/api/marked",
+                get(|| async { (Extension(SkipCompression), download().await) }),
+            )
+            .layer(axum::middleware::from_fn(move |req, next| {
+                compression_skip_middleware(skip_paths.clone(), req, next)
+            }))
+            .layer(create_compression_layer(&config).unwrap())
+    }
+
+    async fn download() -> impl axum::response::IntoResponse {
+        (
+            [
+                (header::CONTENT_TYPE, "application/octet-stream"),
+                (header::CONTENT_LENGTH, "4096"),
+            ],
+            vec![b'a'; DOWNLOAD_SIZE],
+        )
+    }
+
+    fn gzip_request(uri: &str) -> Request<Body> {
+        Request::builder()
+            .uri(uri)
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .body(Body::empty())
+            .unwrap()
+    }
 
     #[test]
     fn test_compression_config_validation() {
@@ -203,4 +342,63 @@ mod tests {
         assert!(dev_config.enabled);
         assert!(!dev_config.algorithms.brotli);
     }
+
+    #[test]
+    fn test_skip_path_matching() {
+        let skip_paths = vec!["/api/admin/backup".to_string(), "/api/files/".to_string()];
+
+        assert!(is_skipped_path("/api/admin/backup", &skip_paths));
+        assert!(is_skipped_path("/api/admin/backup/download", &skip_paths));
+        assert!(is_skipped_path("/api/files/report.csv", &skip_paths));
+        assert!(!is_skipped_path("/api/admin/backups", &skip_paths));
+        assert!(!is_skipped_path("/api/collections", &skip_paths));
+        assert!(!is_skipped_path("/api/anything", &["/".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn test_sse_response_flushes_immediately() {
+        let response = compressed_router(Vec::new())
+            .oneshot(gzip_request("/api/events"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+
+        let mut body = response.into_body();
+        let frame = tokio::time::timeout(Duration::from_secs(1), body.frame())
+            .await
+            .expect("first event was buffered instead of flushed")
+            .unwrap()
+            .unwrap();
+        let data = frame.into_data().unwrap();
+        assert_eq!(&data[..], b"data: hello\n\n");
+    }
+
+    #[tokio::test]
+    async fn test_skipped_download_keeps_content_length() {
+        let router = compressed_router(vec!["/api/files".to_string()]);
+
+        let skipped = router
+            .clone()
+            .oneshot(gzip_request("/api/files/report"))
+            .await
+            .unwrap();
+        assert!(skipped.headers().get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(skipped.headers()[header::CONTENT_LENGTH], "4096");
+        let body = skipped.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body.len(), DOWNLOAD_SIZE);
+
+        let marked = router
+            .clone()
+            .oneshot(gzip_request("/api/marked"))
+            .await
+            .unwrap();
+        assert!(marked.headers().get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(marked.headers()[header::CONTENT_LENGTH], "4096");
+
+        let compressed = router.oneshot(gzip_request("/api/reports")).await.unwrap();
+        assert_eq!(compressed.headers()[header::CONTENT_ENCODING], "gzip");
+        assert!(compressed.headers().get(header::CONTENT_LENGTH).is_none());
+    }
 }
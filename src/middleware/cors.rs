@@ -0,0 +1,387 @@
+use axum::http::{HeaderName, HeaderValue, Method, header, request::Parts};
+use std::sync::{Arc, RwLock};
+use tower_http::cors::{AllowCredentials, AllowOrigin, CorsLayer};
+use tracing::{debug, warn};
+
+use crate::middleware::API_VERSION_HEADER;
+use crate::services::QUOTA_WARNING_HEADER;
+use crate::services::configuration_manager::ConfigurationAccess;
+use crate::utils::CSRF_HEADER;
+
+/// Origins of the OAuth providers' avatar hosts, allowed on top of
+/// `api.cors_allowed_origins` so profile pictures keep loading.
+pub const OAUTH_PROVIDER_ORIGINS: [&str; 2] = [
+    "https://lh3.googleusercontent.com",
+    "https://avatars.githubusercontent.com",
+];
+
+/// One entry of `api.cors_allowed_origins`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OriginPattern {
+    /// `*`, any origin; only allowed while credentials are disabled
+    Any,
+    /// Normalized `scheme://host[:port]`
+    Exact(String),
+    /// `https://*.example.com`, matches any subdomain depth but not the apex
+    WildcardSubdomain {
+        scheme: String,
+        suffix: String,
+        port: Option<u16>,
+    },
+}
+
+impl OriginPattern {
+    pub fn matches(&self, origin: &str) -> bool {
+        match self {
+            OriginPattern::Any => true,
+            OriginPattern::Exact(allowed) => {
+                parse_origin(origin, false).is_ok_and(|parsed| parsed.to_string() == *allowed)
+            }
+            OriginPattern::WildcardSubdomain {
+                scheme,
+                suffix,
+                port,
+            } => parse_origin(origin, false).is_ok_and(|parsed| {
+                parsed.scheme == *scheme
+                    && parsed.port == *port
+                    && parsed
+                        .host
+                        .strip_suffix(suffix.as_str())
+                        .is_some_and(|label| label.len() > 1 && label.ends_with('.'))
+            }),
+        }
+    }
+}
+
+struct ParsedOrigin {
+    scheme: String,
+    host: String,
+    port: Option<u16>,
+}
+
+impl std::fmt::Display for ParsedOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}://{}", self.scheme, self.host)?;
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_origin(value: &str, allow_wildcard: bool) -> Result<ParsedOrigin, String> {
+    let (scheme, authority) = value
+        .split_once("://")
+        .ok_or_else(|| format!("'{}' is not an absolute origin (scheme://host)", value))?;
+
+    let scheme = scheme.to_ascii_lowercase();
+    if scheme != "http" && scheme != "https" {
+        return Err(format!("'{}' must use the http or https scheme", value));
+    }
+
+    if authority.is_empty() || authority.contains(['/', '?', '#', '@']) {
+        return Err(format!(
+            "'{}' must be an origin without path, query, fragment or credentials",
+            value
+        ));
+    }
+
+    let (host, port) = match authority.strip_prefix('[') {
+        Some(bracketed) => {
+            let (address, rest) = bracketed
+                .split_once(']')
+                .ok_or_else(|| format!("'{}' has an invalid host", value))?;
+            match rest {
+                "" => (format!("[{}]", address), None),
+                _ => (
+                    format!("[{}]", address),
+                    Some(parse_port(value, rest.strip_prefix(':').unwrap_or(rest))?),
+                ),
+            }
+        }
+        None => match authority.split_once(':') {
+            Some((host, port)) => (host.to_string(), Some(parse_port(value, port)?)),
+            None => (authority.to_string(), None),
+        },
+    };
+
+    let host = host.to_ascii_lowercase();
+    let labels = host.strip_prefix("*.").filter(|_| allow_wildcard);
+    let checked = labels.unwrap_or(&host);
+    let valid_host = if checked.starts_with('[') {
+        checked.ends_with(']') && checked.len() > 2
+    } else {
+        !checked.is_empty()
+            && checked.split('.').all(|label| {
+                !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            })
+    };
+    if !valid_host {
+        let hint = if checked.contains('*') {
+            " ('*' is only supported as the leftmost label, e.g. https://*.example.com)"
+        } else {
+            ""
+        };
+        return Err(format!("'{}' has an invalid host{}", value, hint));
+    }
+
+    let default_port = if scheme == "https" { 443 } else { 80 };
+    let port = port.filter(|port| *port != default_port);
+
+    Ok(ParsedOrigin { scheme, host, port })
+}
+
+fn parse_port(value: &str, port: &str) -> Result<u16, String> {
+    port.parse()
+        .map_err(|_| format!("'{}' has an invalid port", value))
+}
+
+/// Parses and normalizes one configured origin.
+pub fn parse_origin_pattern(value: &str) -> Result<OriginPattern, String> {
+    let value = value.trim();
+    if value == "*" {
+        return Ok(OriginPattern::Any);
+    }
+
+    let parsed = parse_origin(value, true)?;
+    Ok(match parsed.host.strip_prefix("*.") {
+        Some(suffix) => OriginPattern::WildcardSubdomain {
+            scheme: parsed.scheme,
+            suffix: suffix.to_string(),
+            port: parsed.port,
+        },
+        None => OriginPattern::Exact(parsed.to_string()),
+    })
+}
+
+/// Validates a full origin list the way it would be applied, collecting every problem.
+pub fn validate_cors_origins(
+    origins: &[String],
+    allow_credentials: bool,
+) -> Result<Vec<OriginPattern>, Vec<String>> {
+    let mut patterns = Vec::with_capacity(origins.len());
+    let mut errors = Vec::new();
+
+    for origin in origins {
+        match parse_origin_pattern(origin) {
+            Ok(OriginPattern::Any) if allow_credentials => errors.push(
+                "'*' cannot be used while api.cors_allow_credentials is true; list the origins explicitly or disable credentials first".to_string(),
+            ),
+            Ok(pattern) => patterns.push(pattern),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(patterns)
+    } else {
+        Err(errors)
+    }
+}
+
+#[derive(Debug, Default)]
+struct CorsRules {
+    origins: Vec<OriginPattern>,
+    allow_credentials: bool,
+}
+
+/// CORS rules shared with the layer, rebuilt whenever the configuration cache reloads.
+#[derive(Clone, Default)]
+pub struct CorsPolicy {
+    rules: Arc<RwLock<CorsRules>>,
+}
+
+impl CorsPolicy {
+    pub async fn from_config<C: ConfigurationAccess>(config: &C) -> Self {
+        let policy = Self::default();
+        policy.reload(config).await;
+        policy
+    }
+
+    pub fn new(origins: Vec<OriginPattern>, allow_credentials: bool) -> Self {
+        Self {
+            rules: Arc::new(RwLock::new(CorsRules {
+                origins,
+                allow_credentials,
+            })),
+        }
+    }
+
+    /// Re-reads the settings; entries that fail validation are skipped rather than
+    /// taking down CORS for every other origin.
+    pub async fn reload<C: ConfigurationAccess>(&self, config: &C) {
+        let allow_credentials = config.get_cors_allow_credentials().await;
+        let mut origins: Vec<OriginPattern> = OAUTH_PROVIDER_ORIGINS
+            .iter()
+            .map(|origin| OriginPattern::Exact(origin.to_string()))
+            .collect();
+
+        for origin in config.get_cors_allowed_origins().await {
+            match parse_origin_pattern(&origin) {
+                Ok(OriginPattern::Any) if allow_credentials => {
+                    warn!("Ignoring CORS origin '*' because credentials are allowed");
+                }
+                Ok(pattern) => origins.push(pattern),
+                Err(error) => warn!("Ignoring invalid CORS origin: {}", error),
+            }
+        }
+
+        debug!("Applied {} CORS origin rules", origins.len());
+        let mut rules = self.rules.write().unwrap_or_else(|e| e.into_inner());
+        *rules = CorsRules {
+            origins,
+            allow_credentials,
+        };
+    }
+
+    /// Keeps the policy in sync with configuration reloads until the manager is dropped.
+    pub fn watch<C: ConfigurationAccess + Send + 'static>(&self, config: C) {
+        let policy = self.clone();
+        let mut changes = config.config_manager().subscribe();
+
+        tokio::spawn(async move {
+            while changes.changed().await.is_ok() {
+                policy.reload(&config).await;
+            }
+        });
+    }
+
+    pub fn allows_origin(&self, origin: &HeaderValue) -> bool {
+        let Ok(origin) = origin.to_str() else {
+            return false;
+        };
+
+        let rules = self.rules.read().unwrap_or_else(|e| e.into_inner());
+        rules.origins.iter().any(|pattern| pattern.matches(origin))
+    }
+
+    pub fn allow_credentials(&self) -> bool {
+        self.rules
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .allow_credentials
+    }
+
+    pub fn layer(&self) -> CorsLayer {
+        let origins = self.clone();
+        let credentials = self.clone();
+
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::predicate(
+                move |origin: &HeaderValue, _: &Parts| origins.allows_origin(origin),
+            ))
+            .allow_credentials(AllowCredentials::predicate(
+                move |_: &HeaderValue, _: &Parts| credentials.allow_credentials(),
+            ))
+            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+            .allow_headers([
+                header::CONTENT_TYPE,
+                header::AUTHORIZATION,
+                header::CACHE_CONTROL,
+                header::COOKIE,
+                header::REFERRER_POLICY,
+                HeaderName::from_static(API_VERSION_HEADER),
+                HeaderName::from_static(CSRF_HEADER),
+            ])
+            .expose_headers([
+                header::CONTENT_SECURITY_POLICY,
+                HeaderName::from_static("x-total-count"),
+                HeaderName::from_static(API_VERSION_HEADER),
+                HeaderName::from_static("deprecation"),
+                HeaderName::from_static("sunset"),
+                HeaderName::from_static(QUOTA_WARNING_HEADER),
+            ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn origins(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_exact_origins_are_normalized() {
+        assert_eq!(
+            parse_origin_pattern("HTTPS://App.Example.com:443").unwrap(),
+            OriginPattern::Exact("https://app.example.com".to_string())
+        );
+        assert_eq!(
+            parse_origin_pattern("http://localhost:5173").unwrap(),
+            OriginPattern::Exact("http://localhost:5173".to_string())
+        );
+        assert_eq!(
+            parse_origin_pattern("http://[::1]:3000").unwrap(),
+            OriginPattern::Exact("http://[::1]:3000".to_string())
+        );
+    }
+
+    #[test]
+    fn test_non_origins_are_rejected() {
+        for value in [
+            "example.com",
+            "ftp://example.com",
+            "https://example.com/app",
+            "https://example.com?x=1",
+            "https://user@example.com",
+            "https://example.com:99999",
+            "https://app.*.example.com",
+            "https://*",
+            "https://",
+        ] {
+            assert!(
+                parse_origin_pattern(value).is_err(),
+                "{} should fail",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_wildcard_subdomain_matching() {
+        let pattern = parse_origin_pattern("https://*.example.com").unwrap();
+
+        assert!(pattern.matches("https://app.example.com"));
+        assert!(pattern.matches("https://a.b.example.com"));
+        assert!(pattern.matches("https://APP.example.com:443"));
+        assert!(!pattern.matches("https://example.com"));
+        assert!(!pattern.matches("https://evilexample.com"));
+        assert!(!pattern.matches("http://app.example.com"));
+        assert!(!pattern.matches("https://app.example.com:8443"));
+        assert!(!pattern.matches("null"));
+    }
+
+    #[test]
+    fn test_any_origin_requires_credentials_disabled() {
+        let errors = validate_cors_origins(&origins(&["*"]), true).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("cors_allow_credentials"));
+
+        let patterns = validate_cors_origins(&origins(&["*"]), false).unwrap();
+        assert_eq!(patterns, vec![OriginPattern::Any]);
+    }
+
+    #[test]
+    fn test_validation_reports_every_invalid_entry() {
+        let errors = validate_cors_origins(
+            &origins(&["https://ok.example.com", "nope", "https://x.com/path"]),
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn test_policy_uses_current_rules() {
+        let policy = CorsPolicy::new(
+            vec![parse_origin_pattern("https://*.example.com").unwrap()],
+            true,
+        );
+
+        assert!(policy.allows_origin(&HeaderValue::from_static("https://app.example.com")));
+        assert!(!policy.allows_origin(&HeaderValue::from_static("https://other.com")));
+        assert!(policy.allow_credentials());
+    }
+}
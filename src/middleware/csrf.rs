@@ -0,0 +1,60 @@
+use axum::{extract::Request, http::Method, middleware::Next, response::Response};
+use subtle::ConstantTimeEq;
+
+use crate::utils::{CSRF_HEADER, CookieService, LunarbaseError};
+
+fn is_state_changing(method: &Method) -> bool {
+    !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Whether the browser attached the auth cookies. An `Authorization` header
+/// does not exempt the request: the auth middleware prefers the cookie, so a
+/// cross-site page could otherwise send any header and ride on the cookie.
+fn is_cookie_authenticated(request: &Request) -> bool {
+    CookieService::extract_access_token(request.headers()).is_some()
+        || CookieService::extract_refresh_token(request.headers()).is_some()
+}
+
+/// Refreshing issues the `csrf_token` cookie and logging out clears the
+/// session, so sessions that started before the cookie existed can reach
+/// them without one.
+fn is_session_bootstrap(request: &Request) -> bool {
+    let path = request.uri().path();
+    matches!(
+        path.strip_prefix("/api").unwrap_or(path),
+        "/auth/refresh" | "/auth/logout"
+    )
+}
+
+/// Double-submit CSRF check: state-changing cookie-authenticated requests
+/// must echo the `csrf_token` cookie in the `X-CSRF-Token` header, which a
+/// cross-site page can neither read nor set.
+pub async fn csrf_middleware(request: Request, next: Next) -> Result<Response, LunarbaseError> {
+    if is_state_changing(request.method()) && is_cookie_authenticated(&request) {
+        let cookie = CookieService::extract_csrf_token(request.headers());
+        if cookie.is_none() && is_session_bootstrap(&request) {
+            return Ok(next.run(request).await);
+        }
+        let header = request
+            .headers()
+            .get(CSRF_HEADER)
+            .and_then(|value| value.to_str().ok());
+
+        let matches = match (cookie.as_deref(), header) {
+            (Some(cookie), Some(header)) if !cookie.is_empty() => {
+                bool::from(cookie.as_bytes().ct_eq(header.as_bytes()))
+            }
+            _ => false,
+        };
+        if !matches {
+            tracing::debug!(
+                "Rejected {} {}: missing or mismatched CSRF token",
+                request.method(),
+                request.uri().path()
+            );
+            return Err(LunarbaseError::CsrfTokenInvalid);
+        }
+    }
+
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,29 @@
+use axum::{
+    extract::{Request, State},
+    http::header::ACCEPT_LANGUAGE,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::middleware::AuthState;
+use crate::services::ConfigurationAccess;
+use crate::utils::Locale;
+use crate::utils::i18n::with_locale;
+
+/// Renders error and validation messages of the request in the language its
+/// `Accept-Language` prefers, falling back to `system.default_language`.
+/// Error codes are never translated.
+pub async fn locale_middleware(
+    State(auth_state): State<AuthState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let default = Locale::parse(&auth_state.get_default_language().await).unwrap_or_default();
+    let accept_language = request
+        .headers()
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok());
+    let locale = Locale::negotiate(accept_language, default);
+
+    with_locale(locale, next.run(request)).await
+}
@@ -1,10 +1,15 @@
 use crate::AppState;
+use crate::models::{DenialSource, Permission, PermissionDenialSummary, RetentionRun};
+use crate::services::ConfigurationAccess;
 use axum::{extract::State, http::Request, middleware, response::Response};
 use axum_prometheus::PrometheusMetricLayer;
-use prometheus::{Counter, Encoder, Gauge, Histogram, HistogramOpts, Registry, TextEncoder};
+use chrono::{DateTime, Utc};
+use prometheus::{
+    Counter, CounterVec, Encoder, Gauge, Histogram, HistogramOpts, Opts, Registry, TextEncoder,
+};
 use std::collections::HashMap;
-use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use sysinfo::System;
 use tokio::sync::RwLock;
@@ -24,6 +29,17 @@ pub struct MetricsState {
     pub custom_metrics: Arc<RwLock<HashMap<String, Counter>>>,
     pub cpu_cache_hundredths: Arc<AtomicU64>,
     pub cpu_usage_gauge: Gauge,
+    pub permission_denials_total: CounterVec,
+    pub retention_records_total: CounterVec,
+    permission_denials: Arc<Mutex<HashMap<DenialKey, DenialCounts>>>,
+}
+
+/// (user id, collection, action)
+type DenialKey = (i32, String, &'static str);
+
+struct DenialCounts {
+    sources: HashMap<DenialSource, u64>,
+    last_denied_at: DateTime<Utc>,
 }
 
 impl MetricsState {
@@ -80,6 +96,22 @@ impl MetricsState {
             "Total number of HTTP requests with compression applied",
         )?;
 
+        let permission_denials_total = CounterVec::new(
+            Opts::new(
+                "permission_denials_total",
+                "Total number of refused permission checks",
+            ),
+            &["action", "source"],
+        )?;
+
+        let retention_records_total = CounterVec::new(
+            Opts::new(
+                "retention_records_total",
+                "Total number of records deleted or archived by retention policies",
+            ),
+            &["action", "outcome"],
+        )?;
+
         if !cfg!(test) {
             registry.register(Box::new(request_counter.clone()))?;
             registry.register(Box::new(request_duration.clone()))?;
@@ -91,6 +123,8 @@ impl MetricsState {
             registry.register(Box::new(tls_connections.clone()))?;
             registry.register(Box::new(cpu_usage_gauge.clone()))?;
             registry.register(Box::new(compression_requests_total.clone()))?;
+            registry.register(Box::new(permission_denials_total.clone()))?;
+            registry.register(Box::new(retention_records_total.clone()))?;
         }
 
         Ok(MetricsState {
@@ -107,16 +141,34 @@ impl MetricsState {
             custom_metrics: Arc::new(RwLock::new(HashMap::new())),
             cpu_cache_hundredths: Arc::new(AtomicU64::new(0)),
             cpu_usage_gauge,
+            permission_denials_total,
+            retention_records_total,
+            permission_denials: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
-    pub fn start_cpu_sampler(&self) {
+    /// Samples CPU usage every `system.metrics_cpu_sampler_interval_seconds`.
+    /// While `system.metrics_cpu_sampler_enabled` is off the sampler reports
+    /// zero and waits for the next configuration reload instead of polling.
+    pub fn start_cpu_sampler<C: ConfigurationAccess + Send + 'static>(&self, config: C) {
         let cache = self.cpu_cache_hundredths.clone();
         let gauge = self.cpu_usage_gauge.clone();
+        let mut changes = config.config_manager().subscribe();
 
         tokio::spawn(async move {
-            let mut sys = System::new_all();
+            let mut system: Option<System> = None;
             loop {
+                if !config.get_metrics_cpu_sampler_enabled().await {
+                    system = None;
+                    cache.store(0, Ordering::Relaxed);
+                    gauge.set(0.0);
+                    if changes.changed().await.is_err() {
+                        return;
+                    }
+                    continue;
+                }
+
+                let sys = system.get_or_insert_with(System::new_all);
                 sys.refresh_cpu_all();
                 tokio::time::sleep(Duration::from_millis(100)).await;
                 sys.refresh_cpu_all();
@@ -133,7 +185,8 @@ impl MetricsState {
                 cache.store(hundredths, Ordering::Relaxed);
                 gauge.set(value_percent);
 
-                tokio::time::sleep(Duration::from_secs(1)).await;
+                let interval = config.get_metrics_cpu_sampler_interval_seconds().await;
+                tokio::time::sleep(Duration::from_secs(interval as u64)).await;
             }
         });
     }
@@ -178,6 +231,73 @@ impl MetricsState {
     pub fn record_compression(&self) {
         self.compression_requests_total.inc();
     }
+
+    pub fn record_permission_denial(
+        &self,
+        user_id: i32,
+        collection: &str,
+        action: Permission,
+        source: DenialSource,
+    ) {
+        self.permission_denials_total
+            .with_label_values(&[action.as_str(), source.as_str()])
+            .inc();
+
+        let mut denials = self
+            .permission_denials
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let counts = denials
+            .entry((user_id, collection.to_string(), action.as_str()))
+            .or_insert_with(|| DenialCounts {
+                sources: HashMap::new(),
+                last_denied_at: Utc::now(),
+            });
+        *counts.sources.entry(source).or_insert(0) += 1;
+        counts.last_denied_at = Utc::now();
+    }
+
+    pub fn record_retention_run(&self, run: &RetentionRun) {
+        self.retention_records_total
+            .with_label_values(&[run.action.as_str(), "processed"])
+            .inc_by(run.processed as f64);
+        self.retention_records_total
+            .with_label_values(&[run.action.as_str(), "failed"])
+            .inc_by(run.failed as f64);
+    }
+
+    /// The `limit` (user, collection, action) combinations denied most often.
+    pub fn top_permission_denials(&self, limit: usize) -> Vec<PermissionDenialSummary> {
+        let denials = self
+            .permission_denials
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let mut summaries: Vec<PermissionDenialSummary> = denials
+            .iter()
+            .map(
+                |((user_id, collection, action), counts)| PermissionDenialSummary {
+                    user_id: *user_id,
+                    collection: collection.clone(),
+                    action: action.to_string(),
+                    count: counts.sources.values().sum(),
+                    sources: counts
+                        .sources
+                        .iter()
+                        .map(|(source, count)| (source.as_str().to_string(), *count))
+                        .collect(),
+                    last_denied_at: counts.last_denied_at.to_rfc3339(),
+                },
+            )
+            .collect();
+
+        summaries.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| b.last_denied_at.cmp(&a.last_denied_at))
+        });
+        summaries.truncate(limit);
+        summaries
+    }
 }
 
 pub fn setup_metrics_layer() -> PrometheusMetricLayer<'static> {
@@ -214,14 +334,16 @@ pub async fn metrics_middleware(
     let duration_micros = duration.as_micros() as f64;
     let duration_seconds = duration.as_secs_f64();
 
-    app_state
-        .metrics_state
-        .request_duration
-        .observe(duration_seconds);
-    app_state
-        .metrics_state
-        .request_duration_microseconds
-        .observe(duration_micros);
+    if app_state.get_metrics_route_histograms_enabled().await {
+        app_state
+            .metrics_state
+            .request_duration
+            .observe(duration_seconds);
+        app_state
+            .metrics_state
+            .request_duration_microseconds
+            .observe(duration_micros);
+    }
 
     if duration_micros > 100_000.0 {
         app_state.metrics_state.slow_requests_counter.inc();
@@ -1,6 +1,6 @@
 use crate::AppState;
 use crate::services::configuration_manager::ConfigurationAccess;
-use axum::{Router, extract::DefaultBodyLimit, middleware};
+use axum::{Router, middleware};
 use tower_governor::key_extractor::SmartIpKeyExtractor;
 use tower_governor::{GovernorLayer, governor::GovernorConfigBuilder};
 use tower_http::cors::CorsLayer;
@@ -8,15 +8,29 @@ use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
 use tracing::{Level, debug};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+pub mod api_version;
 pub mod auth;
+pub mod body_limit;
 pub mod compression;
+pub mod cors;
+pub mod csrf;
+pub mod locale;
 pub mod metrics;
+pub mod read_only;
 pub mod security_headers;
+pub mod workspace;
 
+pub use api_version::*;
 pub use auth::*;
+pub use body_limit::*;
 pub use compression::*;
+pub use cors::*;
+pub use csrf::*;
+pub use locale::*;
 pub use metrics::*;
+pub use read_only::*;
 pub use security_headers::*;
+pub use workspace::*;
 
 pub fn setup_logging() {
     let default_filter = if cfg!(debug_assertions) {
@@ -40,35 +54,12 @@ pub fn setup_logging() {
         .init();
 }
 
+/// Builds the CORS layer from `api.cors_*` settings; later configuration changes
+/// apply to the running layer without a restart.
 pub async fn setup_cors(app_state: &AppState) -> CorsLayer {
-    let mut cors_origins = app_state.auth_state.get_cors_allowed_origins().await;
-
-    cors_origins.extend(vec![
-        "https://lh3.googleusercontent.com".to_string(),
-        "https://avatars.githubusercontent.com".to_string(),
-    ]);
-
-    let parsed_origins: Vec<_> = cors_origins
-        .iter()
-        .filter_map(|origin| origin.parse().ok())
-        .collect();
-
-    CorsLayer::new()
-        .allow_origin(parsed_origins)
-        .allow_methods([
-            axum::http::Method::GET,
-            axum::http::Method::POST,
-            axum::http::Method::PUT,
-            axum::http::Method::DELETE,
-        ])
-        .allow_headers([
-            axum::http::header::CONTENT_TYPE,
-            axum::http::header::AUTHORIZATION,
-            axum::http::header::COOKIE,
-            axum::http::header::REFERRER_POLICY,
-        ])
-        .allow_credentials(true)
-        .expose_headers([axum::http::header::CONTENT_SECURITY_POLICY])
+    let policy = CorsPolicy::from_config(&app_state.auth_state).await;
+    policy.watch(app_state.auth_state.clone());
+    policy.layer()
 }
 
 pub async fn add_middleware(app: Router, app_state: AppState) -> Router {
@@ -98,7 +89,12 @@ pub async fn add_middleware(app: Router, app_state: AppState) -> Router {
         let compression_config = build_compression_config_from_db(&app_state).await;
         if let Ok(compression_layer) = create_compression_layer(&compression_config) {
             debug!("Adding compression layer from database config");
-            router = router.layer(compression_layer);
+            let skip_paths = std::sync::Arc::new(compression_config.skip_paths.clone());
+            router = router
+                .layer(middleware::from_fn(move |req, next| {
+                    compression_skip_middleware(skip_paths.clone(), req, next)
+                }))
+                .layer(compression_layer);
         }
     }
 
@@ -114,16 +110,18 @@ pub async fn add_middleware(app: Router, app_state: AppState) -> Router {
     router = router
         .layer(governor_layer)
         .layer(cors_layer)
-        .layer(trace_layer)
-        .layer(DefaultBodyLimit::max(50 * 1024 * 1024));
+        .layer(trace_layer);
 
     if !cfg!(test) {
-        router = router
-            .layer(setup_metrics_layer())
-            .layer(middleware::from_fn_with_state(
-                app_state.clone(),
-                metrics_middleware,
-            ));
+        // The per-route layer cannot be swapped on a running router, so
+        // toggling it takes a restart
+        if app_state.get_metrics_route_histograms_enabled().await {
+            router = router.layer(setup_metrics_layer());
+        }
+        router = router.layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            metrics_middleware,
+        ));
     }
 
     router
@@ -139,6 +137,7 @@ async fn build_compression_config_from_db(app_state: &AppState) -> CompressionCo
             brotli: app_state.get_compression_brotli().await,
             deflate: app_state.get_compression_deflate().await,
         },
+        skip_paths: app_state.get_compression_skip_paths().await,
     }
 }
 
@@ -62,7 +62,7 @@ pub async fn check_collection_permission(
 
 pub async fn check_record_permission(
     State(state): State<AppState>,
-    Path((collection_name, record_id)): Path<(String, i32)>,
+    Path((collection_name, record_id)): Path<(String, String)>,
     req: Request<Body>,
     next: Next,
 ) -> Result<Response, LunarbaseError> {
@@ -76,6 +76,10 @@ pub async fn check_record_permission(
         .get_collection(&collection_name)
         .await
         .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+    let record_id = collection
+        .id_type
+        .normalize(&record_id)
+        .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
 
     let required_permission = match req.method().as_str() {
         "GET" => Permission::Read,
@@ -86,7 +90,7 @@ pub async fn check_record_permission(
 
     let has_permission = state
         .permission_service
-        .check_record_permission(user, collection.id, record_id, required_permission)
+        .check_record_permission(user, collection.id, &record_id, required_permission)
         .await
         .map_err(|_| LunarbaseError::InternalError)?;
 
@@ -150,7 +154,7 @@ impl<'a> PermissionChecker<'a> {
     pub async fn can_access_record(
         &self,
         collection_name: &str,
-        record_id: i32,
+        record_id: &str,
         permission: Permission,
     ) -> Result<bool, LunarbaseError> {
         let collection = self
@@ -0,0 +1,89 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::middleware::AuthState;
+use crate::services::ConfigurationAccess;
+use crate::utils::{Claims, LunarbaseError};
+
+/// Route prefixes whose writes are paused by `system.read_only` / `system.read_only_roles`.
+/// Auth, user and admin endpoints stay writable so people can still sign in.
+const READ_ONLY_PREFIXES: [&str; 3] = ["/collections", "/ownership", "/batch"];
+
+fn is_write(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    )
+}
+
+fn is_read_only_path(path: &str) -> bool {
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    READ_ONLY_PREFIXES.iter().any(|prefix| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })
+}
+
+/// Whether writes from this role are currently paused. Admins always bypass.
+pub async fn role_is_read_only<C: ConfigurationAccess>(config: &C, role: &str) -> bool {
+    if role == "admin" {
+        return false;
+    }
+
+    config.get_read_only_mode().await
+        || config
+            .get_read_only_roles()
+            .await
+            .iter()
+            .any(|read_only_role| read_only_role == role)
+}
+
+/// Must run after `auth_middleware` so the caller's role is known.
+pub async fn read_only_middleware(
+    State(auth_state): State<AuthState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, LunarbaseError> {
+    if is_write(request.method())
+        && is_read_only_path(request.uri().path())
+        && let Some(claims) = request.extensions().get::<Claims>()
+        && role_is_read_only(&auth_state, &claims.role).await
+    {
+        tracing::debug!(
+            "Rejected {} {} from user {} ({}): read-only mode",
+            request.method(),
+            request.uri().path(),
+            claims.sub,
+            claims.role
+        );
+        return Err(LunarbaseError::ReadOnlyMode);
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_collection_and_ownership_writes_are_covered() {
+        assert!(is_read_only_path("/collections/posts/records"));
+        assert!(is_read_only_path("/api/collections"));
+        assert!(is_read_only_path(
+            "/ownership/collections/posts/records/1/transfer"
+        ));
+        assert!(is_read_only_path("/batch"));
+        assert!(!is_read_only_path("/auth/login"));
+        assert!(!is_read_only_path("/collectionsx"));
+        assert!(!is_read_only_path("/admin/configuration/system/read_only"));
+
+        assert!(is_write(&Method::PATCH));
+        assert!(!is_write(&Method::GET));
+        assert!(!is_write(&Method::OPTIONS));
+    }
+}
@@ -0,0 +1,148 @@
+use axum::{
+    extract::{MatchedPath, RawPathParams, Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::AppState;
+use crate::utils::{Claims, LunarbaseError};
+
+/// Route templates, matched by prefix, whose handlers keep to the caller's
+/// workspace. Every other route manages the whole instance, so with workspaces
+/// enabled only superadmins reach it.
+const WORKSPACE_PREFIXES: [&str; 17] = [
+    "/auth",
+    "/users/me",
+    "/collections",
+    "/admin/collections/{name}",
+    "/admin/analytics/records",
+    "/admin/codegen",
+    "/records",
+    "/search",
+    "/batch",
+    "/shares",
+    "/permissions/roles",
+    "/permissions/collections/{name}",
+    "/permissions/users/{user_id}/collections",
+    "/permissions/users/{user_id}/records",
+    "/ownership/collections/{name}",
+    "/upload-image",
+    "/delete-image",
+];
+
+/// Whether workspace admins may reach the route `path`, preferably its
+/// matched template so that path parameters cannot pose as static segments.
+fn is_workspace_path(method: &Method, path: &str) -> bool {
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    let has_prefix = |prefix: &str| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    };
+
+    WORKSPACE_PREFIXES.iter().any(|prefix| has_prefix(prefix))
+        // Templates are shared, so only superadmins may delete them
+        && !(*method == Method::DELETE && has_prefix("/collections/templates"))
+}
+
+/// Confines workspace-scoped tokens to their workspace: collections and custom
+/// roles of other workspaces answer 404, shared roles are read-only for
+/// workspace admins and instance-wide endpoints need a superadmin. Must run
+/// after `auth_middleware` or `optional_auth_middleware`.
+pub async fn workspace_middleware(
+    State(app_state): State<AppState>,
+    params: RawPathParams,
+    request: Request,
+    next: Next,
+) -> Result<Response, LunarbaseError> {
+    let Some(claims) = request.extensions().get::<Claims>() else {
+        return Ok(next.run(request).await);
+    };
+    let Some(workspace_id) = claims.workspace_id else {
+        return Ok(next.run(request).await);
+    };
+
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or(request.uri().path(), MatchedPath::as_str);
+    if !claims.superadmin && !is_workspace_path(request.method(), path) {
+        tracing::debug!(
+            "Rejected {} {} from workspace {} user {}: superadmin only",
+            request.method(),
+            path,
+            workspace_id,
+            claims.sub
+        );
+        return Err(LunarbaseError::InsufficientPermissions);
+    }
+
+    for (key, value) in &params {
+        match key {
+            "name" | "collection_name"
+                if app_state
+                    .collection_service
+                    .workspace_of(value)
+                    .is_some_and(|id| id != workspace_id) =>
+            {
+                return Err(LunarbaseError::NotFound("Collection not found".to_string()));
+            }
+            "role_name" => {
+                let Ok(role) = app_state.permission_service.get_role_by_name(value).await else {
+                    continue;
+                };
+                match role.workspace_id {
+                    Some(id) if id != workspace_id => {
+                        return Err(LunarbaseError::NotFound(format!(
+                            "Role '{}' not found",
+                            value
+                        )));
+                    }
+                    None if !claims.superadmin
+                        && matches!(*request.method(), Method::PUT | Method::DELETE) =>
+                    {
+                        return Err(LunarbaseError::InsufficientPermissions);
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_workspace_paths_skip_the_superadmin_check() {
+        assert!(is_workspace_path(
+            &Method::GET,
+            "/api/collections/{name}/records"
+        ));
+        assert!(is_workspace_path(
+            &Method::POST,
+            "/admin/collections/{name}/verify"
+        ));
+        assert!(is_workspace_path(
+            &Method::GET,
+            "/collections/templates/{template_id}"
+        ));
+        assert!(!is_workspace_path(
+            &Method::DELETE,
+            "/collections/templates/{template_id}"
+        ));
+        assert!(!is_workspace_path(
+            &Method::POST,
+            "/admin/collections/orphans/sweep"
+        ));
+        assert!(!is_workspace_path(&Method::GET, "/api/users"));
+        assert!(!is_workspace_path(&Method::GET, "/users/{user_id}/usage"));
+        assert!(!is_workspace_path(&Method::GET, "/admin/analytics/users"));
+        assert!(!is_workspace_path(&Method::GET, "/recordsets"));
+        assert!(!is_workspace_path(&Method::GET, "/api/ws/stats"));
+    }
+}
@@ -0,0 +1,297 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::handlers::websocket::ActivityEntry;
+use crate::services::ComponentHealth;
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct UserOverview {
+    #[schema(example = 42)]
+    pub total: i64,
+    #[schema(example = json!({"admin": 1, "user": 41}))]
+    pub by_role: HashMap<String, i64>,
+    #[schema(example = 40)]
+    pub active: i64,
+    #[schema(example = 2)]
+    pub inactive: i64,
+    #[schema(example = 35)]
+    pub verified: i64,
+    #[schema(example = 7)]
+    pub unverified: i64,
+    /// Accounts whose lockout after failed logins has not expired yet
+    #[schema(example = 1)]
+    pub locked: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CollectionOverview {
+    #[schema(example = 5)]
+    pub total_collections: i64,
+    #[schema(example = 1200)]
+    pub total_records: i64,
+    #[schema(example = json!({"articles": 1000, "comments": 200}))]
+    pub records_per_collection: HashMap<String, i64>,
+    #[schema(example = "articles")]
+    pub largest_collection: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StorageUsage {
+    /// `database` or `s3`
+    #[schema(example = "database")]
+    pub backend: String,
+    #[schema(example = 4194304)]
+    pub bytes: Option<u64>,
+    /// Number of stored objects; only reported for object storage
+    #[schema(example = 128)]
+    pub objects: Option<u64>,
+    /// Set when usage could not be read
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebSocketOverview {
+    #[schema(example = 12)]
+    pub connections: usize,
+    #[schema(example = 9)]
+    pub authenticated_connections: usize,
+    #[schema(example = 30)]
+    pub subscriptions: usize,
+}
+
+/// Everything the admin dashboard shows at a glance, gathered in one request.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdminOverview {
+    pub users: UserOverview,
+    pub collections: CollectionOverview,
+    pub storage: Vec<StorageUsage>,
+    pub websocket: WebSocketOverview,
+    pub backup: Option<ComponentHealth>,
+    /// Emails are sent inline, so this reports delivery health rather than a queue
+    pub email: Option<ComponentHealth>,
+    /// Ten most recent entries of the activity log, newest first
+    pub recent_activity: Vec<ActivityEntry>,
+    #[schema(example = "2024-01-15T10:30:00Z")]
+    pub generated_at: String,
+}
+
+/// Bucket size of the user analytics series; weeks start on Monday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsGranularity {
+    Day,
+    Week,
+}
+
+impl AnalyticsGranularity {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "day" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            _ => None,
+        }
+    }
+
+    pub fn days(&self) -> i64 {
+        match self {
+            Self::Day => 1,
+            Self::Week => 7,
+        }
+    }
+
+    /// Start of the first bucket of the `window_days` days ending on `today`.
+    /// With weekly buckets this is the Monday on or before the window start.
+    pub fn first_bucket(&self, today: NaiveDate, window_days: i64) -> NaiveDate {
+        let window_start = today - chrono::Duration::days(window_days - 1);
+        match self {
+            Self::Day => window_start,
+            Self::Week => {
+                window_start
+                    - chrono::Duration::days(window_start.weekday().num_days_from_monday() as i64)
+            }
+        }
+    }
+
+    /// SQLite expression for the start date of the bucket holding `column`.
+    pub fn bucket_sql(&self, column: &str) -> String {
+        match self {
+            Self::Day => format!("date({})", column),
+            // SQLite moves to the next Sunday (or stays on one), so this
+            // lands on the Monday starting the week
+            Self::Week => format!("date({}, 'weekday 0', '-6 days')", column),
+        }
+    }
+
+    /// Start dates of every bucket from `first` up to and including `today`.
+    pub fn bucket_starts(&self, first: NaiveDate, today: NaiveDate) -> Vec<NaiveDate> {
+        let mut starts = Vec::new();
+        let mut start = first;
+        while start <= today {
+            starts.push(start);
+            start += chrono::Duration::days(self.days());
+        }
+        starts
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserAnalyticsBucket {
+    /// First day of the bucket (UTC)
+    #[schema(example = "2025-09-01")]
+    pub start: String,
+    #[schema(example = 12)]
+    pub new_users: i64,
+    /// Users registered in this bucket who have verified their email since
+    #[schema(example = 9)]
+    pub verified_users: i64,
+    /// Distinct users who signed in during the bucket
+    #[schema(example = 30)]
+    pub active_users: i64,
+    #[schema(example = 41)]
+    pub password_logins: i64,
+    #[schema(example = 17)]
+    pub oauth_logins: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct UserAnalyticsTotals {
+    #[schema(example = 120)]
+    pub new_users: i64,
+    #[schema(example = 97)]
+    pub verified_users: i64,
+    /// Distinct users who signed in anywhere in the window
+    #[schema(example = 210)]
+    pub active_users: i64,
+    /// Distinct users who signed in with a password
+    #[schema(example = 150)]
+    pub password_users: i64,
+    /// Distinct users who signed in through an OAuth provider
+    #[schema(example = 70)]
+    pub oauth_users: i64,
+}
+
+/// User growth and sign-in activity over a window, bucketed for charting.
+/// Every bucket of the window is present, oldest first, including empty ones.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserAnalytics {
+    #[schema(example = 30)]
+    pub window_days: i64,
+    pub granularity: AnalyticsGranularity,
+    /// Start of the first bucket; with weekly buckets this can precede the window
+    #[schema(example = "2025-08-27")]
+    pub since: String,
+    pub series: Vec<UserAnalyticsBucket>,
+    pub totals: UserAnalyticsTotals,
+    #[schema(example = "2025-09-25T10:30:00Z")]
+    pub generated_at: String,
+}
+
+/// Kind of record change counted in `collection_activity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordActivityKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecordAnalyticsBucket {
+    /// First day of the bucket (UTC)
+    #[schema(example = "2025-09-01")]
+    pub start: String,
+    #[schema(example = 120)]
+    pub created: i64,
+    /// Updates, including publishing, reordering and ownership transfers
+    #[schema(example = 340)]
+    pub updated: i64,
+    #[schema(example = 8)]
+    pub deleted: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct RecordAnalyticsTotals {
+    #[schema(example = 1200)]
+    pub created: i64,
+    #[schema(example = 3400)]
+    pub updated: i64,
+    #[schema(example = 80)]
+    pub deleted: i64,
+}
+
+/// Record changes over a window, bucketed for charting. Every bucket of the
+/// window is present, oldest first, including empty ones.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecordAnalytics {
+    /// Collection the series is limited to; all collections when absent
+    #[schema(example = "articles")]
+    pub collection: Option<String>,
+    #[schema(example = 30)]
+    pub window_days: i64,
+    pub granularity: AnalyticsGranularity,
+    /// Start of the first bucket; with weekly buckets this can precede the window
+    #[schema(example = "2025-08-27")]
+    pub since: String,
+    pub series: Vec<RecordAnalyticsBucket>,
+    pub totals: RecordAnalyticsTotals,
+    #[schema(example = "2025-09-25T10:30:00Z")]
+    pub generated_at: String,
+}
+
+/// Sent by email and to the alert webhook when a collection's deletions of a
+/// day exceed the configured multiple of its trailing daily average.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordDeletionAlert {
+    pub collection: String,
+    pub day: String,
+    pub deletions: i64,
+    pub trailing_average: f64,
+    pub threshold: u32,
+    pub triggered_at: String,
+}
+
+/// Limits that warn before they start rejecting requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaKind {
+    /// Record queries one user has in flight, `database.max_concurrent_queries_per_user`
+    ConcurrentQueries,
+    /// Guest sessions one client IP started, `auth.guest_session_max_per_ip`
+    GuestSessions,
+}
+
+impl QuotaKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QuotaKind::ConcurrentQueries => "concurrent_queries",
+            QuotaKind::GuestSessions => "guest_sessions",
+        }
+    }
+}
+
+/// Usage of a quota at or above `system.quota_warning_percent` of its limit,
+/// e.g. `{"quota": "guest_sessions", "subject": "203.0.113.7", "used": 4, "limit": 5, ...}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuotaWarning {
+    pub quota: QuotaKind,
+    /// User id or client IP the quota is counted for
+    pub subject: String,
+    pub used: usize,
+    pub limit: usize,
+    pub threshold_percent: u32,
+    pub triggered_at: String,
+}
+
+impl QuotaWarning {
+    /// Value of the `X-Lunarbase-Warning` response header.
+    pub fn header_value(&self) -> String {
+        format!(
+            "{}; used={}; limit={}; threshold={}%",
+            self.quota.as_str(),
+            self.used,
+            self.limit,
+            self.threshold_percent
+        )
+    }
+}
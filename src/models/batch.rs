@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use super::{RecordResponse, deserialize_optional_record_id};
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchMethod {
+    Create,
+    Update,
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchOperation {
+    pub method: BatchMethod,
+    #[schema(example = "orders")]
+    pub collection: String,
+    #[serde(default, deserialize_with = "deserialize_optional_record_id")]
+    #[schema(value_type = Option<String>, example = "1")]
+    pub record_id: Option<String>,
+    #[schema(example = json!({"product": "Widget", "quantity": 2}))]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchRequest {
+    pub operations: Vec<BatchOperation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchOperationResult {
+    #[schema(example = 0)]
+    pub index: usize,
+    pub method: BatchMethod,
+    #[schema(example = "orders")]
+    pub collection: String,
+    #[schema(example = "1")]
+    pub record_id: String,
+    pub record: Option<RecordResponse>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchResponse {
+    pub results: Vec<BatchOperationResult>,
+}
+
+impl BatchOperation {
+    pub fn validate(&self, index: usize) -> Result<(), String> {
+        match self.method {
+            BatchMethod::Create => {
+                if !self.data.as_ref().is_some_and(Value::is_object) {
+                    return Err(format!(
+                        "Operation {}: create requires a data object",
+                        index
+                    ));
+                }
+            }
+            BatchMethod::Update => {
+                if self.record_id.is_none() {
+                    return Err(format!("Operation {}: update requires a record_id", index));
+                }
+                if !self.data.as_ref().is_some_and(Value::is_object) {
+                    return Err(format!(
+                        "Operation {}: update requires a data object",
+                        index
+                    ));
+                }
+            }
+            BatchMethod::Delete => {
+                if self.record_id.is_none() {
+                    return Err(format!("Operation {}: delete requires a record_id", index));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
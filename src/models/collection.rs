@@ -1,10 +1,16 @@
-use crate::schema::collections;
-use chrono::NaiveDateTime;
+use crate::decimal::{DEFAULT_DECIMAL_PRECISION, DEFAULT_DECIMAL_SCALE};
+use crate::models::SetCollectionPermissionRequest;
+use crate::schema::{collection_record_counts, collection_retention_runs, collections};
+use crate::utils::Message;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use utoipa::ToSchema;
 
+/// Read-only virtual collection exposing a sanitized projection of registered users.
+pub const USERS_SYSTEM_COLLECTION: &str = "_users";
+
 #[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, ToSchema)]
 #[diesel(table_name = collections)]
 pub struct Collection {
@@ -22,6 +28,364 @@ pub struct Collection {
     pub is_system: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    #[schema(example = 1)]
+    pub schema_version: i32,
+    #[schema(example = 0)]
+    pub query_cache_ttl_seconds: i32,
+    #[schema(example = false)]
+    pub orderable: bool,
+    #[schema(example = "integer")]
+    pub id_type: String,
+    #[schema(example = false)]
+    pub allow_explicit_ids: bool,
+    #[schema(example = "none")]
+    pub workflow: String,
+    #[serde(skip_serializing)]
+    pub schedule_json: Option<String>,
+    /// Scheduled transitions have been applied up to this time
+    pub schedule_checked_at: Option<NaiveDateTime>,
+    #[schema(example = 1)]
+    pub workspace_id: i32,
+    #[serde(skip_serializing)]
+    pub retention_json: Option<String>,
+}
+
+/// Record count of a collection, adjusted on every insert and delete and
+/// recounted from the records table at `reconciled_at`.
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = collection_record_counts)]
+pub struct CollectionRecordCount {
+    pub collection_id: i32,
+    pub record_count: i64,
+    pub reconciled_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// How the records of a collection are identified; fixed at creation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordIdType {
+    /// Auto-incrementing integers
+    #[default]
+    Integer,
+    /// Time-ordered UUIDv7 strings
+    Uuid,
+}
+
+impl RecordIdType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecordIdType::Integer => "integer",
+            RecordIdType::Uuid => "uuid",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "uuid" => RecordIdType::Uuid,
+            _ => RecordIdType::Integer,
+        }
+    }
+
+    /// Canonical form of a record id given in a URL or request body, or `None`
+    /// when it cannot identify a record of this kind of collection.
+    pub fn normalize(&self, record_id: &str) -> Option<String> {
+        match self {
+            RecordIdType::Integer => record_id.parse::<i64>().ok().map(|id| id.to_string()),
+            RecordIdType::Uuid => uuid::Uuid::parse_str(record_id)
+                .ok()
+                .map(|id| id.hyphenated().to_string()),
+        }
+    }
+}
+
+/// Whether records go through an editorial workflow before readers see them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionWorkflow {
+    /// Every record is visible to readers
+    #[default]
+    None,
+    /// Records carry a `status` and readers only see published ones
+    DraftPublish,
+}
+
+impl CollectionWorkflow {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CollectionWorkflow::None => "none",
+            CollectionWorkflow::DraftPublish => "draft_publish",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "draft_publish" => CollectionWorkflow::DraftPublish,
+            _ => CollectionWorkflow::None,
+        }
+    }
+}
+
+/// Date fields of a collection whose values trigger record transitions. The
+/// scheduler publishes drafts once `publish_at_field` has passed and applies
+/// `expire_action` once `expire_at_field` has passed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RecordSchedule {
+    /// Needs the `draft_publish` workflow
+    #[schema(example = "go_live")]
+    pub publish_at_field: Option<String>,
+    #[schema(example = "ends")]
+    pub expire_at_field: Option<String>,
+    #[serde(default)]
+    pub expire_action: ExpireAction,
+}
+
+impl RecordSchedule {
+    pub fn is_empty(&self) -> bool {
+        self.publish_at_field.is_none() && self.expire_at_field.is_none()
+    }
+
+    /// The designated fields, publish field first.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.publish_at_field
+            .iter()
+            .chain(self.expire_at_field.iter())
+            .map(String::as_str)
+    }
+}
+
+/// What happens to a record once its `expire_at_field` has passed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpireAction {
+    /// Set `status` to `archived`; needs the `draft_publish` workflow
+    #[default]
+    Archive,
+    Delete,
+}
+
+/// Deletes or archives records once their `field` is older than
+/// `max_age_days`. Enforced in batches by the retention task, through the
+/// same paths as manual deletes, so events, file cleanup and hooks still run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RetentionPolicy {
+    /// `created_at`, `updated_at` or a date field of the collection
+    #[schema(example = "created_at")]
+    pub field: String,
+    /// 0 turns retention off when updating a collection
+    #[schema(example = 90)]
+    pub max_age_days: u32,
+    pub action: ExpireAction,
+}
+
+/// One run of a collection's retention policy.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = collection_retention_runs)]
+pub struct RetentionRun {
+    pub id: i32,
+    pub collection_id: i32,
+    pub action: String,
+    pub cutoff: String,
+    pub processed: i64,
+    pub failed: i64,
+    pub complete: bool,
+    pub started_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RetentionRunEntry {
+    #[schema(example = "delete")]
+    pub action: String,
+    /// Records with the policy field before this time were due
+    #[schema(example = "2025-07-05 09:00:00")]
+    pub cutoff: String,
+    #[schema(example = 500)]
+    pub processed: i64,
+    #[schema(example = 0)]
+    pub failed: i64,
+    /// Whether every due record was handled; otherwise the next run continues
+    pub complete: bool,
+    #[schema(example = "2025-10-03 09:00:00")]
+    pub started_at: String,
+    /// Absent while the run is in progress or when it was interrupted
+    #[schema(example = "2025-10-03 09:00:04")]
+    pub finished_at: Option<String>,
+}
+
+impl From<RetentionRun> for RetentionRunEntry {
+    fn from(run: RetentionRun) -> Self {
+        let timestamp = |value: NaiveDateTime| value.format("%Y-%m-%d %H:%M:%S").to_string();
+        Self {
+            action: run.action,
+            cutoff: run.cutoff,
+            processed: run.processed,
+            failed: run.failed,
+            complete: run.complete,
+            started_at: timestamp(run.started_at),
+            finished_at: run.finished_at.map(timestamp),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RetentionReport {
+    #[schema(example = "logs")]
+    pub collection_name: String,
+    pub retention: Option<RetentionPolicy>,
+    /// Records currently past the cutoff
+    #[schema(example = 1200)]
+    pub due: i64,
+    /// Most recent first
+    pub runs: Vec<RetentionRunEntry>,
+}
+
+/// A transition the scheduler has yet to apply to a record.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ScheduledOperation {
+    #[schema(example = "42")]
+    pub record_id: String,
+    pub action: ScheduledAction,
+    /// Schedule field holding the due time, or the workflow's `publish_at`
+    #[schema(example = "go_live")]
+    pub field: String,
+    #[schema(example = "2025-10-01")]
+    pub due_at: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduledAction {
+    Publish,
+    Archive,
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecordScheduleReport {
+    #[schema(example = "events")]
+    pub collection_name: String,
+    pub schedule: Option<RecordSchedule>,
+    /// Transitions due up to this time have been applied
+    #[schema(example = "2025-09-19 08:00:00")]
+    pub checked_at: Option<String>,
+    /// Soonest first; operations already due are applied on the next run
+    pub pending: Vec<ScheduledOperation>,
+}
+
+/// Records of other collections whose relation fields point at one record.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecordReferences {
+    #[schema(example = "categories")]
+    pub collection_name: String,
+    #[schema(example = "7")]
+    pub record_id: String,
+    /// Referencing records across every collection
+    #[schema(example = 3)]
+    pub total_count: i64,
+    pub referenced_by: Vec<CollectionReferences>,
+}
+
+/// The records of one collection that reference a record.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CollectionReferences {
+    #[schema(example = "products")]
+    pub collection_name: String,
+    /// Relation fields targeting the referenced collection
+    #[schema(example = json!(["category"]))]
+    pub fields: Vec<String>,
+    #[schema(example = 3)]
+    pub count: i64,
+    /// The first referencing record ids, in id order
+    #[schema(example = json!(["12", "15", "31"]))]
+    pub record_ids: Vec<String>,
+}
+
+/// What deleting a collection would destroy.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CollectionDeleteImpact {
+    #[schema(example = 120)]
+    pub record_count: i64,
+    /// Uploaded files referenced by the collection's file fields
+    #[schema(example = 14)]
+    pub file_count: i64,
+    /// Other collections with relation fields targeting this one
+    #[schema(example = json!(["reviews"]))]
+    pub referencing_collections: Vec<String>,
+}
+
+/// First phase of a collection delete: nothing has been dropped yet.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PendingCollectionDelete {
+    #[schema(example = "products")]
+    pub collection_name: String,
+    /// Send back as `confirm_token` on a second DELETE to drop the collection
+    #[schema(example = "3f1c0e9a6b2d4e58a7c9d0b1e2f3a4b5")]
+    pub confirm_token: String,
+    #[schema(example = 300)]
+    pub expires_in_seconds: u64,
+    pub impact: CollectionDeleteImpact,
+}
+
+/// `status` of a record in a draft/publish collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordStatus {
+    Draft,
+    Published,
+    Archived,
+}
+
+impl RecordStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecordStatus::Draft => "draft",
+            RecordStatus::Published => "published",
+            RecordStatus::Archived => "archived",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "draft" => Some(RecordStatus::Draft),
+            "published" => Some(RecordStatus::Published),
+            "archived" => Some(RecordStatus::Archived),
+            _ => None,
+        }
+    }
+}
+
+/// Record ids in request bodies may be written as numbers or strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawRecordId {
+    Text(String),
+    Number(i64),
+}
+
+impl From<RawRecordId> for String {
+    fn from(id: RawRecordId) -> Self {
+        match id {
+            RawRecordId::Text(id) => id,
+            RawRecordId::Number(id) => id.to_string(),
+        }
+    }
+}
+
+pub(crate) fn deserialize_record_id<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    RawRecordId::deserialize(deserializer).map(String::from)
+}
+
+pub(crate) fn deserialize_optional_record_id<'de, D>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<RawRecordId>::deserialize(deserializer).map(|id| id.map(String::from))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -33,6 +397,30 @@ pub struct CreateCollectionRequest {
     #[schema(example = "Collection for storing product data")]
     pub description: Option<String>,
     pub schema: CollectionSchema,
+    /// Keep a manual record order in a `sort_order` column; lists default to it
+    #[serde(default)]
+    #[schema(example = false)]
+    pub orderable: bool,
+    /// `uuid` gives records UUIDv7 ids instead of sequential integers
+    #[serde(default)]
+    pub id_type: RecordIdType,
+    /// Let admins create records with an `id` of their own, e.g. when migrating data
+    #[serde(default)]
+    #[schema(example = false)]
+    pub allow_explicit_ids: bool,
+    /// `draft_publish` keeps new records as drafts until they are published
+    #[serde(default)]
+    pub workflow: CollectionWorkflow,
+    /// Date fields that publish or expire records when they pass
+    #[serde(default)]
+    pub schedule: Option<RecordSchedule>,
+    /// Deletes or archives records once they are older than a number of days
+    #[serde(default)]
+    pub retention: Option<RetentionPolicy>,
+    /// Initial role permissions replacing the configured defaults; roles left
+    /// out get no access and the admin role always keeps full access
+    #[serde(default)]
+    pub permissions: Option<Vec<SetCollectionPermissionRequest>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -44,6 +432,29 @@ pub struct UpdateCollectionRequest {
     #[schema(example = "Updated description for product collection")]
     pub description: Option<String>,
     pub schema: Option<CollectionSchema>,
+    /// Seconds list and count results stay cached; 0 disables query caching
+    #[serde(default)]
+    #[schema(example = 5, minimum = 0)]
+    pub query_cache_ttl_seconds: Option<i32>,
+    /// Turning this off keeps the `sort_order` values but stops ordering by them
+    #[serde(default)]
+    #[schema(example = true)]
+    pub orderable: Option<bool>,
+    /// Only accepted when it matches the current id type
+    #[serde(default)]
+    pub id_type: Option<RecordIdType>,
+    #[serde(default)]
+    #[schema(example = true)]
+    pub allow_explicit_ids: Option<bool>,
+    /// Turning the workflow off keeps the record statuses but shows every record
+    #[serde(default)]
+    pub workflow: Option<CollectionWorkflow>,
+    /// A schedule without fields turns scheduling off
+    #[serde(default)]
+    pub schedule: Option<RecordSchedule>,
+    /// A policy with `max_age_days` 0 turns retention off
+    #[serde(default)]
+    pub retention: Option<RetentionPolicy>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -57,6 +468,25 @@ pub struct CollectionResponse {
     #[schema(example = "Collection for storing product data")]
     pub description: Option<String>,
     pub schema: CollectionSchema,
+    /// Current entry in the collection's schema history
+    #[schema(example = 3)]
+    pub schema_version: i32,
+    /// Seconds list and count results stay cached; 0 means the query cache is off
+    #[schema(example = 0)]
+    pub query_cache_ttl_seconds: i32,
+    /// Records carry a `sort_order` and are listed by it unless another sort is given
+    #[schema(example = false)]
+    pub orderable: bool,
+    pub id_type: RecordIdType,
+    /// Admins may give new records an `id` of their own
+    #[schema(example = false)]
+    pub allow_explicit_ids: bool,
+    /// With `draft_publish`, records carry a `status` and readers only see published ones
+    pub workflow: CollectionWorkflow,
+    pub schedule: Option<RecordSchedule>,
+    pub retention: Option<RetentionPolicy>,
+    #[schema(example = 1)]
+    pub workspace_id: i32,
     #[schema(example = false)]
     pub is_system: bool,
     #[schema(example = "2024-01-01 12:00:00")]
@@ -65,6 +495,79 @@ pub struct CollectionResponse {
     pub updated_at: String,
 }
 
+/// How much of each collection `GET /collections` returns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CollectionFields {
+    #[default]
+    Full,
+    /// Everything except the schema
+    Summary,
+    /// Only `name` and `display_name`, e.g. for dropdowns
+    Names,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CollectionSummary {
+    pub id: i32,
+    pub name: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub schema_version: i32,
+    pub orderable: bool,
+    pub id_type: RecordIdType,
+    pub allow_explicit_ids: bool,
+    pub workflow: CollectionWorkflow,
+    pub is_system: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CollectionName {
+    pub name: String,
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum CollectionListEntry {
+    Full(CollectionResponse),
+    Summary(CollectionSummary),
+    Name(CollectionName),
+}
+
+impl CollectionListEntry {
+    pub fn from_collection(
+        collection: Collection,
+        fields: CollectionFields,
+    ) -> Result<Self, serde_json::Error> {
+        let timestamp = |value: NaiveDateTime| value.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        Ok(match fields {
+            CollectionFields::Full => Self::Full(CollectionResponse::from_collection(collection)?),
+            CollectionFields::Summary => Self::Summary(CollectionSummary {
+                id_type: collection.record_id_type(),
+                workflow: collection.workflow(),
+                id: collection.id,
+                name: collection.name,
+                display_name: collection.display_name,
+                description: collection.description,
+                schema_version: collection.schema_version,
+                orderable: collection.orderable,
+                allow_explicit_ids: collection.allow_explicit_ids,
+                is_system: collection.is_system,
+                created_at: timestamp(collection.created_at),
+                updated_at: timestamp(collection.updated_at),
+            }),
+            CollectionFields::Names => Self::Name(CollectionName {
+                name: collection.name,
+                display_name: collection.display_name,
+            }),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateRecordRequest {
     #[schema(example = json!({"name": "Product 1", "price": 99.99}))]
@@ -83,11 +586,81 @@ pub struct FileUpload {
     pub data: String,
 }
 
+impl FileUpload {
+    /// Size of the file once `data` is base64-decoded, without decoding it
+    pub fn decoded_len(&self) -> usize {
+        let encoded = self.data.trim_end_matches('=').len();
+        encoded / 4 * 3 + encoded % 4 * 3 / 4
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UpdateRecordRequest {
     #[schema(example = json!({"name": "Updated Product", "price": 149.99}))]
     pub data: Value,
     pub files: Option<std::collections::HashMap<String, FileUpload>>,
+    /// Regenerate slug fields from their source fields instead of keeping them
+    #[serde(default)]
+    pub regenerate_slug: bool,
+}
+
+/// Payload checked by the record validation dry run. With `record_id` it is
+/// checked as an update of that record, otherwise as a new record.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ValidateRecordRequest {
+    #[schema(example = json!({"name": "Product 1", "price": 99.99}))]
+    pub data: Value,
+    #[serde(default, deserialize_with = "deserialize_optional_record_id")]
+    #[schema(value_type = Option<String>, example = "42")]
+    pub record_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FieldValidationError {
+    #[schema(example = "title")]
+    pub field: String,
+    /// Machine-readable reason, e.g. `required`, `too_long` or `invalid_type`
+    #[schema(example = "required")]
+    pub code: String,
+    /// Rendered in the language picked from the request's `Accept-Language`
+    #[schema(value_type = String, example = "Field 'title' is required")]
+    pub message: Message,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecordValidationResponse {
+    pub valid: bool,
+    pub errors: Vec<FieldValidationError>,
+}
+
+/// Moves a record of an orderable collection next to another record; give
+/// exactly one of `before` or `after`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MoveRecordRequest {
+    /// Place the record immediately before this record id
+    #[serde(default, deserialize_with = "deserialize_optional_record_id")]
+    #[schema(value_type = Option<String>, example = "4")]
+    pub before: Option<String>,
+    /// Place the record immediately after this record id
+    #[serde(default, deserialize_with = "deserialize_optional_record_id")]
+    #[schema(value_type = Option<String>, example = json!(null))]
+    pub after: Option<String>,
+}
+
+/// Publishes a draft/publish record now, or at `publish_at` when that is in
+/// the future.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct PublishRecordRequest {
+    #[schema(example = "2025-10-01T08:00:00Z")]
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+/// Takes a record back to `draft`, or to `archived` with `archive`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UnpublishRecordRequest {
+    #[serde(default)]
+    #[schema(example = false)]
+    pub archive: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -118,6 +691,22 @@ pub struct FieldDefinition {
     pub required: bool,
     pub default_value: Option<Value>,
     pub validation: Option<ValidationRules>,
+    /// Collection a `relation` field points at; `_users` targets registered users
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "_users")]
+    pub relation_target: Option<String>,
+    /// What the field holds, shown in the admin UI and the generated API docs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "Shown on the storefront when set")]
+    pub description: Option<String>,
+    /// Sample value for the generated API docs; never used as a default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example: Option<Value>,
+    /// Paths inside a `json` field backed by an indexed generated column, so
+    /// filters on them (`metadata.source:eq:import`) can use the index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = json!(["source", "address.city"]))]
+    pub indexed_paths: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
@@ -133,6 +722,12 @@ pub enum FieldType {
     File,
     Relation,
     RichText,
+    /// Stored as `{name}_lat` and `{name}_lng` REAL columns, exposed as `{lat, lng}`
+    GeoPoint,
+    /// Exact fixed-point number stored as integer minor units, exposed as a string
+    Decimal,
+    /// Unique URL slug generated from `validation.source_field` on create
+    Slug,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -149,17 +744,149 @@ pub struct ValidationRules {
     pub pattern: Option<String>,
     #[schema(example = json!(["option1", "option2"]))]
     pub enum_values: Option<Vec<String>>,
+    /// Total digits of a `decimal` field, 1-18 (default 18)
+    #[schema(example = 10)]
+    pub precision: Option<u32>,
+    /// Digits after the decimal point of a `decimal` field (default 2)
+    #[schema(example = 2)]
+    pub scale: Option<u32>,
+    /// Text field a `slug` field is generated from
+    #[schema(example = "title")]
+    pub source_field: Option<String>,
+    /// Whether a `number` field holds whole numbers or floats. Records then
+    /// always return that JSON number type; unset keeps returning values
+    /// without a fraction as integers.
+    pub number_format: Option<NumberFormat>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberFormat {
+    Integer,
+    Float,
+}
+
+impl FieldDefinition {
+    /// Whether `other` differs at most in its description and example, which
+    /// only document the field and never touch the records table.
+    pub fn same_structure(&self, other: &FieldDefinition) -> bool {
+        let structure = |field: &FieldDefinition| {
+            serde_json::to_value(FieldDefinition {
+                description: None,
+                example: None,
+                ..field.clone()
+            })
+            .ok()
+        };
+        structure(self) == structure(other)
+    }
+
+    /// Columns backing this field in the records table.
+    pub fn storage_columns(&self) -> Vec<String> {
+        match self.field_type {
+            FieldType::GeoPoint => {
+                let (lat, lng) = geo_point_columns(&self.name);
+                vec![lat, lng]
+            }
+            _ => vec![self.name.clone()],
+        }
+    }
+
+    /// Field a `slug` field is generated from.
+    pub fn slug_source_field(&self) -> Option<&str> {
+        self.validation
+            .as_ref()
+            .and_then(|rules| rules.source_field.as_deref())
+    }
+
+    pub fn number_format(&self) -> Option<NumberFormat> {
+        self.validation
+            .as_ref()
+            .and_then(|rules| rules.number_format)
+    }
+
+    /// Precision and scale of a `decimal` field, with defaults applied.
+    pub fn decimal_precision_and_scale(&self) -> (u32, u32) {
+        let rules = self.validation.as_ref();
+        (
+            rules
+                .and_then(|rules| rules.precision)
+                .unwrap_or(DEFAULT_DECIMAL_PRECISION),
+            rules
+                .and_then(|rules| rules.scale)
+                .unwrap_or(DEFAULT_DECIMAL_SCALE),
+        )
+    }
+}
+
+/// Latitude and longitude column names of a `geopoint` field.
+pub fn geo_point_columns(field_name: &str) -> (String, String) {
+    (format!("{}_lat", field_name), format!("{}_lng", field_name))
+}
+
+/// Whether `path` addresses a value inside a `json` field: dot-separated keys
+/// of letters, numbers and underscores, where all-digit keys index arrays.
+pub fn is_valid_json_path(path: &str) -> bool {
+    !path.is_empty()
+        && path.len() <= 100
+        && path.split('.').all(|key| {
+            !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+/// SQLite JSON path for a path accepted by [`is_valid_json_path`], e.g.
+/// `tags.0.name` becomes `$.tags[0].name`.
+pub fn sqlite_json_path(path: &str) -> String {
+    path.split('.').fold("$".to_string(), |mut json_path, key| {
+        if key.chars().all(|c| c.is_ascii_digit()) {
+            json_path.push_str(&format!("[{}]", key));
+        } else {
+            json_path.push_str(&format!(".{}", key));
+        }
+        json_path
+    })
+}
+
+/// Generated column holding `path` of a `json` field listed in its `indexed_paths`.
+pub fn json_path_column(field_name: &str, path: &str) -> String {
+    format!("{}__{}", field_name, path.replace('.', "__"))
 }
 
 impl Collection {
     pub fn get_schema(&self) -> Result<CollectionSchema, serde_json::Error> {
         serde_json::from_str(&self.schema_json)
     }
+
+    pub fn record_id_type(&self) -> RecordIdType {
+        RecordIdType::from_db(&self.id_type)
+    }
+
+    pub fn workflow(&self) -> CollectionWorkflow {
+        CollectionWorkflow::from_db(&self.workflow)
+    }
+
+    pub fn schedule(&self) -> Result<Option<RecordSchedule>, serde_json::Error> {
+        self.schedule_json
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()
+    }
+
+    pub fn retention(&self) -> Result<Option<RetentionPolicy>, serde_json::Error> {
+        self.retention_json
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()
+    }
 }
 
 impl CollectionResponse {
     pub fn from_collection(collection: Collection) -> Result<Self, serde_json::Error> {
         let schema: CollectionSchema = serde_json::from_str(&collection.schema_json)?;
+        let id_type = collection.record_id_type();
+        let workflow = collection.workflow();
+        let schedule = collection.schedule()?;
+        let retention = collection.retention()?;
 
         Ok(CollectionResponse {
             id: collection.id,
@@ -167,6 +894,15 @@ impl CollectionResponse {
             display_name: collection.display_name,
             description: collection.description,
             schema,
+            schema_version: collection.schema_version,
+            query_cache_ttl_seconds: collection.query_cache_ttl_seconds,
+            orderable: collection.orderable,
+            id_type,
+            allow_explicit_ids: collection.allow_explicit_ids,
+            workflow,
+            schedule,
+            retention,
+            workspace_id: collection.workspace_id,
             is_system: collection.is_system,
             created_at: collection
                 .created_at
@@ -188,6 +924,13 @@ pub struct NewCollection {
     pub description: Option<String>,
     pub schema_json: String,
     pub is_system: bool,
+    pub orderable: bool,
+    pub id_type: String,
+    pub allow_explicit_ids: bool,
+    pub workflow: String,
+    pub schedule_json: Option<String>,
+    pub workspace_id: i32,
+    pub retention_json: Option<String>,
 }
 
 #[derive(Debug, AsChangeset)]
@@ -197,4 +940,12 @@ pub struct UpdateCollection {
     pub display_name: Option<String>,
     pub description: Option<String>,
     pub schema_json: Option<String>,
+    pub schema_version: Option<i32>,
+    pub query_cache_ttl_seconds: Option<i32>,
+    pub orderable: Option<bool>,
+    pub allow_explicit_ids: Option<bool>,
+    pub workflow: Option<String>,
+    pub schedule_json: Option<Option<String>>,
+    pub schedule_checked_at: Option<Option<NaiveDateTime>>,
+    pub retention_json: Option<Option<String>>,
 }
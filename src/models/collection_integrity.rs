@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use crate::services::ComponentHealth;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityIssueKind {
+    MissingTable,
+    MissingColumn,
+    ExtraColumn,
+    TypeMismatch,
+    MissingIndex,
+    MissingTrigger,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IntegrityIssue {
+    pub kind: IntegrityIssueKind,
+    /// Column, index or trigger the issue refers to
+    #[schema(example = "title")]
+    pub target: String,
+    #[schema(example = "TEXT")]
+    pub expected: Option<String>,
+    #[schema(example = "INTEGER")]
+    pub actual: Option<String>,
+    /// Whether `repair` can fix this without touching existing data
+    pub auto_fixable: bool,
+    #[schema(example = "Column 'title' is defined in the schema but missing from the table")]
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CollectionIntegrityReport {
+    #[schema(example = "articles")]
+    pub collection_name: String,
+    #[schema(example = "records_articles")]
+    pub table_name: String,
+    pub healthy: bool,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+/// Health of one collection's records table, checked by component like the
+/// admin health report.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CollectionHealthReport {
+    #[schema(example = "articles")]
+    pub collection_name: String,
+    #[schema(example = "records_articles")]
+    pub table_name: String,
+    /// Worst status among `checks`
+    #[schema(example = "degraded")]
+    pub status: String,
+    /// `table`, `columns`, `indexes`, `triggers`, `integrity`, `record_count`
+    /// and `last_write`; only `table` when the records table is missing
+    pub checks: BTreeMap<String, ComponentHealth>,
+    #[schema(example = 1250)]
+    pub record_count: Option<i64>,
+    /// Count kept up to date by record writes, which `record_count` should match
+    #[schema(example = 1250)]
+    pub cached_record_count: Option<i64>,
+    #[schema(example = "2024-01-15 10:29:58")]
+    pub last_write_at: Option<String>,
+    #[schema(example = "2024-01-15T10:30:00Z")]
+    pub checked_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CollectionRepairReport {
+    #[schema(example = "articles")]
+    pub collection_name: String,
+    #[schema(example = json!(["Added column 'title'", "Created index 'idx_records_articles_created_at'"]))]
+    pub applied: Vec<String>,
+    /// Issues left for manual intervention
+    pub unresolved: Vec<IntegrityIssue>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanObjectKind {
+    Table,
+    Index,
+    Trigger,
+}
+
+/// Table, index or trigger left behind by an interrupted schema change.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrphanObject {
+    pub kind: OrphanObjectKind,
+    #[schema(example = "records_articles_temp_1699999999")]
+    pub name: String,
+    /// Table the object belongs to; the object itself for tables
+    #[schema(example = "records_articles_temp_1699999999")]
+    pub table_name: String,
+    #[schema(example = "Temporary table of an unfinished schema change")]
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrphanSweepReport {
+    /// Whether the orphans were only reported
+    pub dry_run: bool,
+    pub orphans: Vec<OrphanObject>,
+    /// Orphans dropped by this sweep; empty on dry runs
+    #[schema(example = json!(["records_articles_temp_1699999999"]))]
+    pub dropped: Vec<String>,
+    #[schema(example = "2025-09-29T09:00:00Z")]
+    pub swept_at: String,
+}
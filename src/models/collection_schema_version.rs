@@ -0,0 +1,57 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::CollectionSchema;
+use crate::schema::collection_schema_versions;
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = collection_schema_versions)]
+pub struct CollectionSchemaVersion {
+    pub id: i32,
+    pub collection_id: i32,
+    pub version: i32,
+    pub schema_json: String,
+    pub created_by: Option<i32>,
+    pub migration_summary: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = collection_schema_versions)]
+pub struct NewCollectionSchemaVersion {
+    pub collection_id: i32,
+    pub version: i32,
+    pub schema_json: String,
+    pub created_by: Option<i32>,
+    pub migration_summary: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CollectionSchemaVersionResponse {
+    #[schema(example = 2)]
+    pub version: i32,
+    pub schema: CollectionSchema,
+    /// User who made the change; `None` for migrated history or deleted users
+    #[schema(example = 1)]
+    pub created_by: Option<i32>,
+    #[schema(example = "Added field 'summary'; changed field 'title'")]
+    pub migration_summary: Option<String>,
+    #[schema(example = "2024-01-01 12:00:00")]
+    pub created_at: String,
+}
+
+impl CollectionSchemaVersionResponse {
+    pub fn from_version(version: CollectionSchemaVersion) -> Result<Self, serde_json::Error> {
+        let schema: CollectionSchema = serde_json::from_str(&version.schema_json)?;
+
+        Ok(CollectionSchemaVersionResponse {
+            version: version.version,
+            schema,
+            created_by: version.created_by,
+            migration_summary: version.migration_summary,
+            created_at: version.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        })
+    }
+}
@@ -0,0 +1,120 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use super::{CollectionResponse, CollectionSchema};
+use crate::schema::collection_templates;
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = collection_templates)]
+pub struct StoredCollectionTemplate {
+    pub id: i32,
+    pub template_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub schema_json: String,
+    pub permissions_json: String,
+    pub sample_records_json: String,
+    pub created_by: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = collection_templates)]
+pub struct NewCollectionTemplate {
+    pub template_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub schema_json: String,
+    pub permissions_json: String,
+    pub sample_records_json: String,
+    pub created_by: Option<i32>,
+}
+
+/// Role permissions granted to a collection created from a template.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TemplatePermission {
+    #[schema(example = "user")]
+    pub role_name: String,
+    pub can_create: bool,
+    pub can_read: bool,
+    pub can_update: bool,
+    pub can_delete: bool,
+    pub can_list: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CollectionTemplate {
+    #[schema(example = "blog_posts")]
+    pub id: String,
+    #[schema(example = "Blog posts")]
+    pub name: String,
+    #[schema(example = "Articles with a slug, rich text body, tags and publishing state")]
+    pub description: Option<String>,
+    /// Built-in templates ship with LunarBase and cannot be deleted
+    pub built_in: bool,
+    pub schema: CollectionSchema,
+    pub permissions: Vec<TemplatePermission>,
+    #[schema(example = json!([{"title": "Hello world", "slug": "hello-world"}]))]
+    pub sample_records: Vec<Value>,
+    #[schema(example = "2024-01-01 12:00:00")]
+    pub created_at: Option<String>,
+}
+
+impl StoredCollectionTemplate {
+    pub fn to_template(&self) -> Result<CollectionTemplate, serde_json::Error> {
+        Ok(CollectionTemplate {
+            id: self.template_id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            built_in: false,
+            schema: serde_json::from_str(&self.schema_json)?,
+            permissions: serde_json::from_str(&self.permissions_json)?,
+            sample_records: serde_json::from_str(&self.sample_records_json)?,
+            created_at: Some(self.created_at.format("%Y-%m-%d %H:%M:%S").to_string()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateFromTemplateRequest {
+    #[schema(example = "posts", min_length = 1, max_length = 50)]
+    pub name: String,
+    /// Defaults to the template name
+    #[schema(example = "Posts")]
+    pub display_name: Option<String>,
+    /// Defaults to the template description
+    pub description: Option<String>,
+    /// Also insert the template's sample records
+    #[serde(default)]
+    pub include_sample_records: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateFromTemplateResponse {
+    pub collection: CollectionResponse,
+    #[schema(example = "blog_posts")]
+    pub template_id: String,
+    /// Roles that received the template's permissions
+    #[schema(example = json!(["user", "guest"]))]
+    pub permissions_applied: Vec<String>,
+    #[schema(example = 2)]
+    pub sample_records_created: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SaveAsTemplateRequest {
+    /// Lowercase letters, digits, `_` and `-`
+    #[schema(example = "support_tickets", min_length = 1, max_length = 100)]
+    pub template_id: String,
+    /// Defaults to the collection's display name
+    #[schema(example = "Support tickets")]
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Records inserted when the template is used with `include_sample_records`
+    #[serde(default)]
+    pub sample_records: Vec<Value>,
+}
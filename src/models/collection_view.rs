@@ -0,0 +1,158 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::User;
+use crate::schema::collection_views;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewVisibility {
+    Private,
+    Role,
+    Public,
+}
+
+impl ViewVisibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ViewVisibility::Private => "private",
+            ViewVisibility::Role => "role",
+            ViewVisibility::Public => "public",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "private" => Some(ViewVisibility::Private),
+            "role" => Some(ViewVisibility::Role),
+            "public" => Some(ViewVisibility::Public),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = collection_views)]
+pub struct CollectionView {
+    pub id: i32,
+    pub collection_id: i32,
+    pub name: String,
+    pub filter: Option<String>,
+    pub sort: Option<String>,
+    pub fields: Option<String>,
+    pub visibility: String,
+    pub role_name: Option<String>,
+    pub created_by: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = collection_views)]
+pub struct NewCollectionView {
+    pub collection_id: i32,
+    pub name: String,
+    pub filter: Option<String>,
+    pub sort: Option<String>,
+    pub fields: Option<String>,
+    pub visibility: String,
+    pub role_name: Option<String>,
+    pub created_by: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateCollectionViewRequest {
+    #[schema(example = "open_high_priority")]
+    pub name: String,
+    #[schema(example = "status:eq:open,priority:gte:3")]
+    pub filter: Option<String>,
+    #[schema(example = "-created_at")]
+    pub sort: Option<String>,
+    #[schema(example = json!(["title", "status", "priority"]))]
+    pub fields: Option<Vec<String>>,
+    pub visibility: Option<ViewVisibility>,
+    /// Role allowed to use the view when visibility is `role`
+    #[schema(example = "editor")]
+    pub role_name: Option<String>,
+}
+
+/// Omitted fields are left unchanged; an empty filter, sort or field list clears the stored value.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateCollectionViewRequest {
+    pub name: Option<String>,
+    pub filter: Option<String>,
+    pub sort: Option<String>,
+    pub fields: Option<Vec<String>>,
+    pub visibility: Option<ViewVisibility>,
+    pub role_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CollectionViewResponse {
+    #[schema(example = 1)]
+    pub id: i32,
+    #[schema(example = "tickets")]
+    pub collection_name: String,
+    #[schema(example = "open_high_priority")]
+    pub name: String,
+    pub filter: Option<String>,
+    pub sort: Option<String>,
+    pub fields: Vec<String>,
+    pub visibility: ViewVisibility,
+    pub role_name: Option<String>,
+    pub created_by: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl CollectionView {
+    pub fn get_visibility(&self) -> ViewVisibility {
+        ViewVisibility::parse(&self.visibility).unwrap_or(ViewVisibility::Private)
+    }
+
+    pub fn get_fields(&self) -> Vec<String> {
+        self.fields
+            .as_deref()
+            .and_then(|fields| serde_json::from_str(fields).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn is_visible_to(&self, user: Option<&User>) -> bool {
+        if self.get_visibility() == ViewVisibility::Public {
+            return true;
+        }
+
+        let Some(user) = user else {
+            return false;
+        };
+
+        if user.role == "admin" || user.id == self.created_by {
+            return true;
+        }
+
+        self.get_visibility() == ViewVisibility::Role
+            && self.role_name.as_deref() == Some(user.role.as_str())
+    }
+
+    pub fn can_be_modified_by(&self, user: &User) -> bool {
+        user.role == "admin" || user.id == self.created_by
+    }
+
+    pub fn to_response(&self, collection_name: &str) -> CollectionViewResponse {
+        CollectionViewResponse {
+            id: self.id,
+            collection_name: collection_name.to_string(),
+            name: self.name.clone(),
+            filter: self.filter.clone(),
+            sort: self.sort.clone(),
+            fields: self.get_fields(),
+            visibility: self.get_visibility(),
+            role_name: self.role_name.clone(),
+            created_by: self.created_by,
+            created_at: self.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            updated_at: self.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
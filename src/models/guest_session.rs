@@ -0,0 +1,46 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::schema::guest_session_records;
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct GuestSessionRequest {
+    /// Restricts the token to one of the guest collections
+    #[schema(example = "feedback")]
+    pub collection: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GuestSessionResponse {
+    /// Send as `Authorization: Bearer <token>` to create records
+    #[schema(example = "")]
+    pub access_token: String,
+    #[schema(example = 900)]
+    pub expires_in: i64,
+    #[schema(example = "0b6f1f64-3c84-4b8e-9d0f-6d2f3a1c9e27")]
+    pub session_id: String,
+    /// Collections the token may create records in
+    #[schema(example = json!(["feedback"]))]
+    pub collections: Vec<String>,
+}
+
+/// A record created with a guest session token.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = guest_session_records)]
+pub struct GuestSessionRecord {
+    pub id: i32,
+    pub session_id: String,
+    pub collection_name: String,
+    pub record_id: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = guest_session_records)]
+pub struct NewGuestSessionRecord {
+    pub session_id: String,
+    pub collection_name: String,
+    pub record_id: String,
+}
@@ -0,0 +1,140 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use crate::schema::{ingest_endpoints, ingest_failures};
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = ingest_endpoints)]
+pub struct IngestEndpoint {
+    pub id: i32,
+    pub name: String,
+    pub token_hash: String,
+    pub collection_name: String,
+    pub field_mapping: String,
+    pub hmac_secret: Option<String>,
+    pub signature_header: Option<String>,
+    pub is_active: bool,
+    pub created_by: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = ingest_endpoints)]
+pub struct NewIngestEndpoint {
+    pub name: String,
+    pub token_hash: String,
+    pub collection_name: String,
+    pub field_mapping: String,
+    pub hmac_secret: Option<String>,
+    pub signature_header: Option<String>,
+    pub is_active: bool,
+    pub created_by: i32,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = ingest_failures)]
+pub struct IngestFailure {
+    pub id: i32,
+    pub endpoint_id: i32,
+    pub payload: String,
+    pub error: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = ingest_failures)]
+pub struct NewIngestFailure {
+    pub endpoint_id: i32,
+    pub payload: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateIngestEndpointRequest {
+    #[schema(example = "Stripe payments")]
+    pub name: String,
+    #[schema(example = "payments")]
+    pub collection_name: String,
+    /// Maps collection field names to payload paths such as `$.data.object.amount`
+    #[schema(example = json!({"amount": "$.data.object.amount", "customer": "$.data.object.customer"}))]
+    pub field_mapping: BTreeMap<String, String>,
+    /// Shared secret used to verify an HMAC-SHA256 signature of the raw request
+    /// body. It is kept to check signatures but never returned
+    pub hmac_secret: Option<String>,
+    #[schema(example = "X-Hub-Signature-256")]
+    pub signature_header: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IngestEndpointResponse {
+    #[schema(example = 1)]
+    pub id: i32,
+    #[schema(example = "Stripe payments")]
+    pub name: String,
+    /// Only returned when the endpoint is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "/api/ingest/3f0c9b2e...")]
+    pub url: Option<String>,
+    #[schema(example = "payments")]
+    pub collection_name: String,
+    pub field_mapping: BTreeMap<String, String>,
+    pub signature_verification: bool,
+    pub signature_header: Option<String>,
+    pub is_active: bool,
+    pub created_by: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IngestFailureResponse {
+    #[schema(example = 1)]
+    pub id: i32,
+    #[schema(example = 1)]
+    pub endpoint_id: i32,
+    pub payload: String,
+    #[schema(example = "Validation error: Field 'amount' must be a number")]
+    pub error: String,
+    pub created_at: String,
+}
+
+impl IngestEndpoint {
+    pub fn get_field_mapping(&self) -> Result<BTreeMap<String, String>, serde_json::Error> {
+        serde_json::from_str(&self.field_mapping)
+    }
+
+    pub fn to_response(&self) -> IngestEndpointResponse {
+        IngestEndpointResponse {
+            id: self.id,
+            name: self.name.clone(),
+            token: None,
+            url: None,
+            collection_name: self.collection_name.clone(),
+            field_mapping: self.get_field_mapping().unwrap_or_default(),
+            signature_verification: self.hmac_secret.is_some(),
+            signature_header: self.signature_header.clone(),
+            is_active: self.is_active,
+            created_by: self.created_by,
+            created_at: self.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            updated_at: self.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+impl From<IngestFailure> for IngestFailureResponse {
+    fn from(failure: IngestFailure) -> Self {
+        Self {
+            id: failure.id,
+            endpoint_id: failure.endpoint_id,
+            payload: failure.payload,
+            error: failure.error,
+            created_at: failure.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
@@ -1,15 +1,47 @@
+pub mod admin_overview;
+pub mod batch;
 pub mod blacklisted_token;
 pub mod collection;
+pub mod collection_integrity;
+pub mod collection_schema_version;
+pub mod collection_template;
+pub mod collection_view;
+pub mod guest_session;
+pub mod ingest;
+pub mod notification;
+pub mod ownership_stats;
+pub mod permission_policy;
 pub mod permissions;
+pub mod quarantined_upload;
+pub mod record_manifest;
+pub mod record_share;
 pub mod system_setting;
 pub mod user;
+pub mod user_usage;
 pub mod verification_token;
 pub mod websocket;
+pub mod workspace;
 
+pub use admin_overview::*;
+pub use batch::*;
 pub use blacklisted_token::*;
 pub use collection::*;
+pub use collection_integrity::*;
+pub use collection_schema_version::*;
+pub use collection_template::*;
+pub use collection_view::*;
+pub use guest_session::*;
+pub use ingest::*;
+pub use notification::*;
+pub use ownership_stats::*;
+pub use permission_policy::*;
 pub use permissions::*;
+pub use quarantined_upload::*;
+pub use record_manifest::*;
+pub use record_share::*;
 pub use system_setting::*;
 pub use user::*;
+pub use user_usage::*;
 pub use verification_token::*;
 pub use websocket::*;
+pub use workspace::*;
@@ -0,0 +1,167 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::schema::notifications;
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = notifications)]
+pub struct Notification {
+    pub id: i32,
+    pub user_id: i32,
+    pub kind: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub data: Option<String>,
+    pub read_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = notifications)]
+pub struct NewNotification {
+    pub user_id: i32,
+    pub kind: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub data: Option<String>,
+}
+
+/// What a notification is about, so clients can pick an icon or a link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    ImportFinished,
+    ExportFinished,
+    RoleChanged,
+    BackupCompleted,
+    BackupFailed,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ImportFinished => "import_finished",
+            Self::ExportFinished => "export_finished",
+            Self::RoleChanged => "role_changed",
+            Self::BackupCompleted => "backup_completed",
+            Self::BackupFailed => "backup_failed",
+        }
+    }
+}
+
+/// A notification before it is addressed to anyone.
+#[derive(Debug, Clone)]
+pub struct NotificationContent {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: Option<String>,
+    pub data: Option<Value>,
+}
+
+impl NotificationContent {
+    pub fn new(kind: NotificationKind, title: impl Into<String>) -> Self {
+        Self {
+            kind,
+            title: title.into(),
+            body: None,
+            data: None,
+        }
+    }
+
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Details a client can act on, e.g. the collection an export came from
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationResponse {
+    #[schema(example = 1)]
+    pub id: i32,
+    #[schema(example = "role_changed")]
+    pub kind: String,
+    #[schema(example = "Your role changed")]
+    pub title: String,
+    #[schema(example = "You are now an admin")]
+    pub body: Option<String>,
+    #[schema(example = json!({"from": "user", "to": "admin"}))]
+    pub data: Option<Value>,
+    pub read: bool,
+    #[schema(example = "2024-01-01 12:05:00")]
+    pub read_at: Option<String>,
+    #[schema(example = "2024-01-01 12:00:00")]
+    pub created_at: String,
+}
+
+impl Notification {
+    pub fn to_response(&self) -> NotificationResponse {
+        NotificationResponse {
+            id: self.id,
+            kind: self.kind.clone(),
+            title: self.title.clone(),
+            body: self.body.clone(),
+            data: self
+                .data
+                .as_deref()
+                .and_then(|data| serde_json::from_str(data).ok()),
+            read: self.read_at.is_some(),
+            read_at: self
+                .read_at
+                .map(|read_at| read_at.format("%Y-%m-%d %H:%M:%S").to_string()),
+            created_at: self.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct ListNotificationsQuery {
+    /// Only return notifications that have not been read
+    pub unread_only: Option<bool>,
+    /// At most 100, 20 by default
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct NotificationListResponse {
+    /// Newest first
+    pub notifications: Vec<NotificationResponse>,
+    /// Number of notifications matching the query
+    pub total: i64,
+    pub unread_count: i64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct MarkNotificationsReadRequest {
+    /// Notifications to mark as read; all of them when omitted
+    #[schema(example = json!([1, 2]))]
+    pub ids: Option<Vec<i32>>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MarkNotificationsReadResponse {
+    /// Notifications that were unread before the request
+    pub marked: usize,
+    pub unread_count: i64,
+}
+
+/// Pushed to every WebSocket connection and event stream the user has open, e.g.
+/// `{"event": "UnreadCount", "unread_count": 0}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event")]
+pub enum NotificationPush {
+    Created {
+        notification: NotificationResponse,
+        unread_count: i64,
+    },
+    /// Sent when notifications are marked as read
+    UnreadCount { unread_count: i64 },
+}
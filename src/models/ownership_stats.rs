@@ -0,0 +1,80 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CollectionOwnershipStats {
+    #[schema(example = "articles")]
+    pub collection_name: String,
+    /// Column the owner is read from (`owner_id`, falling back to `author_id`);
+    /// `None` when the collection has no ownership field
+    #[schema(example = "owner_id")]
+    pub ownership_field: Option<String>,
+    #[schema(example = 120)]
+    pub total_records: i64,
+    #[schema(example = 100)]
+    pub owned_records: i64,
+    #[schema(example = 20)]
+    pub unowned_records: i64,
+    /// Records whose owner was deleted or deactivated
+    #[schema(example = 3)]
+    pub orphaned_records: i64,
+    #[schema(example = 16.67)]
+    pub unowned_percentage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserOwnershipStats {
+    #[schema(example = 7)]
+    pub user_id: i32,
+    /// `None` when the user no longer exists
+    #[schema(example = "john_doe")]
+    pub username: Option<String>,
+    #[schema(example = true)]
+    pub is_active: bool,
+    #[schema(example = 42)]
+    pub total_records: i64,
+    #[schema(example = json!({"articles": 40, "comments": 2}))]
+    pub records_per_collection: HashMap<String, i64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanReason {
+    OwnerDeleted,
+    OwnerInactive,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OrphanedRecord {
+    #[schema(example = "articles")]
+    pub collection_name: String,
+    #[schema(example = "15")]
+    pub record_id: String,
+    #[schema(example = 9)]
+    pub owner_id: i32,
+    pub reason: OrphanReason,
+    /// Endpoint that reassigns the record to an active user
+    #[schema(example = "/api/ownership/collections/articles/records/15/transfer")]
+    pub transfer_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GlobalOwnershipStats {
+    #[schema(example = 1200)]
+    pub total_records: i64,
+    #[schema(example = 1100)]
+    pub owned_records: i64,
+    #[schema(example = 100)]
+    pub unowned_records: i64,
+    #[schema(example = 12)]
+    pub orphaned_records_total: i64,
+    /// Sorted by the share of unowned records, highest first
+    pub collections: Vec<CollectionOwnershipStats>,
+    /// Sorted by the number of owned records, highest first
+    pub users: Vec<UserOwnershipStats>,
+    /// Capped at the requested `orphan_limit`
+    pub orphaned_records: Vec<OrphanedRecord>,
+    #[schema(example = "2024-01-15T10:30:00Z")]
+    pub timestamp: String,
+}
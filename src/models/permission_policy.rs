@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use utoipa::ToSchema;
+
+use crate::models::{CollectionPermission, PermissionSet, Role, UserCollectionPermission};
+
+/// Version of the [`PermissionPolicy`] document format.
+pub const PERMISSION_POLICY_VERSION: u32 = 1;
+
+/// The roles, role permissions and user overrides of the instance as one
+/// document, so permissions can be kept in version control and synced.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PermissionPolicy {
+    #[schema(example = 1)]
+    pub version: u32,
+    /// Roles shared by every workspace; workspace roles are not part of the policy
+    #[serde(default)]
+    pub roles: Vec<PolicyRole>,
+    /// Importing replaces every role permission and user override of the
+    /// listed collections; collections left out are not touched
+    #[serde(default)]
+    pub collections: Vec<PolicyCollection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PolicyRole {
+    #[schema(example = "editor")]
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[schema(example = 50)]
+    pub priority: i32,
+}
+
+impl From<&Role> for PolicyRole {
+    fn from(role: &Role) -> Self {
+        Self {
+            name: role.name.clone(),
+            description: role.description.clone(),
+            priority: role.priority,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PolicyCollection {
+    #[schema(example = "articles")]
+    pub name: String,
+    /// Permissions of each role, by role name
+    #[serde(default)]
+    pub roles: BTreeMap<String, PermissionSet>,
+    /// Overrides of each user, by email
+    #[serde(default)]
+    pub users: BTreeMap<String, PermissionOverride>,
+}
+
+/// A user's override of their role's permissions; unset actions fall back to
+/// the role.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PermissionOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub can_create: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub can_read: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub can_update: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub can_delete: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub can_list: Option<bool>,
+}
+
+impl From<&CollectionPermission> for PermissionSet {
+    fn from(permission: &CollectionPermission) -> Self {
+        Self {
+            can_create: permission.can_create,
+            can_read: permission.can_read,
+            can_update: permission.can_update,
+            can_delete: permission.can_delete,
+            can_list: permission.can_list,
+        }
+    }
+}
+
+impl From<&UserCollectionPermission> for PermissionOverride {
+    fn from(permission: &UserCollectionPermission) -> Self {
+        Self {
+            can_create: permission.can_create,
+            can_read: permission.can_read,
+            can_update: permission.can_update,
+            can_delete: permission.can_delete,
+            can_list: permission.can_list,
+        }
+    }
+}
+
+/// One change an import makes. `location` points at the part of the document
+/// that causes it.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PolicyChange {
+    CreateRole {
+        location: String,
+        role: PolicyRole,
+    },
+    UpdateRole {
+        location: String,
+        before: PolicyRole,
+        after: PolicyRole,
+    },
+    SetRolePermissions {
+        location: String,
+        collection: String,
+        role: String,
+        before: Option<PermissionSet>,
+        after: PermissionSet,
+    },
+    RemoveRolePermissions {
+        location: String,
+        collection: String,
+        role: String,
+        before: PermissionSet,
+    },
+    SetUserOverride {
+        location: String,
+        collection: String,
+        user: String,
+        before: Option<PermissionOverride>,
+        after: PermissionOverride,
+    },
+    RemoveUserOverride {
+        location: String,
+        collection: String,
+        user: String,
+        before: PermissionOverride,
+    },
+}
+
+impl PolicyChange {
+    /// Action recorded in the permission audit trail.
+    pub fn audit_action(&self) -> &'static str {
+        match self {
+            PolicyChange::CreateRole { .. } => "policy_create_role",
+            PolicyChange::UpdateRole { .. } => "policy_update_role",
+            PolicyChange::SetRolePermissions { .. } => "policy_set_role_permissions",
+            PolicyChange::RemoveRolePermissions { .. } => "policy_remove_role_permissions",
+            PolicyChange::SetUserOverride { .. } => "policy_set_user_override",
+            PolicyChange::RemoveUserOverride { .. } => "policy_remove_user_override",
+        }
+    }
+}
+
+/// A part of the document that cannot be imported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct PolicyError {
+    #[schema(example = "collections[2].roles.editor")]
+    pub location: String,
+    #[schema(example = "Unknown role 'editor'")]
+    pub message: String,
+}
+
+impl PolicyError {
+    pub fn new(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PolicyImportReport {
+    pub dry_run: bool,
+    /// Whether the changes were made; never on dry runs or with errors
+    pub applied: bool,
+    pub changes: Vec<PolicyChange>,
+    pub errors: Vec<PolicyError>,
+}
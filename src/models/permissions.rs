@@ -1,10 +1,13 @@
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use utoipa::ToSchema;
 
+use crate::models::UsageQuota;
 use crate::schema::{
-    collection_permissions, record_permissions, roles, user_collection_permissions,
+    collection_permissions, permission_audit_entries, record_permissions, roles,
+    user_collection_permissions,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable, ToSchema)]
@@ -16,6 +19,12 @@ pub struct Role {
     pub priority: i32,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    /// `None` for roles shared by every workspace
+    pub workspace_id: Option<i32>,
+    /// Records a member may create in any 24 hours; `None` for no limit
+    pub max_records_created_per_day: Option<i64>,
+    /// File bytes a member may upload in any 24 hours; `None` for no limit
+    pub max_upload_bytes_per_day: Option<i64>,
 }
 
 #[derive(Debug, Insertable)]
@@ -24,6 +33,9 @@ pub struct NewRole {
     pub name: String,
     pub description: Option<String>,
     pub priority: i32,
+    pub workspace_id: Option<i32>,
+    pub max_records_created_per_day: Option<i64>,
+    pub max_upload_bytes_per_day: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, Identifiable, ToSchema)]
@@ -41,6 +53,16 @@ pub struct CollectionPermission {
     pub updated_at: NaiveDateTime,
 }
 
+/// A role's permissions on a collection. `inherited_from` names the
+/// lower-priority role they come from when the role has none of its own and
+/// permission inheritance is enabled.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RoleCollectionPermission {
+    #[serde(flatten)]
+    pub permission: CollectionPermission,
+    pub inherited_from: Option<String>,
+}
+
 #[derive(Debug, Insertable)]
 #[diesel(table_name = collection_permissions)]
 pub struct NewCollectionPermission {
@@ -84,24 +106,64 @@ pub struct NewUserCollectionPermission {
 #[diesel(table_name = record_permissions)]
 pub struct RecordPermission {
     pub id: i32,
-    pub record_id: i32,
+    pub record_id: String,
     pub collection_id: i32,
     pub user_id: i32,
     pub can_read: bool,
     pub can_update: bool,
     pub can_delete: bool,
     pub created_at: NaiveDateTime,
+    /// Admin who last set the permission, `None` for grants older than this field
+    pub granted_by: Option<i32>,
 }
 
 #[derive(Debug, Insertable)]
 #[diesel(table_name = record_permissions)]
 pub struct NewRecordPermission {
-    pub record_id: i32,
+    pub record_id: String,
     pub collection_id: i32,
     pub user_id: i32,
     pub can_read: bool,
     pub can_update: bool,
     pub can_delete: bool,
+    pub granted_by: Option<i32>,
+}
+
+/// One explicit record permission of a user, with the collection it belongs to.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserRecordGrant {
+    pub collection_id: i32,
+    pub collection_name: String,
+    pub record_id: String,
+    pub can_read: bool,
+    pub can_update: bool,
+    pub can_delete: bool,
+    pub granted_by: Option<i32>,
+    pub granted_by_email: Option<String>,
+    pub granted_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserRecordGrantPage {
+    pub grants: Vec<UserRecordGrant>,
+    pub total_count: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// A review or bulk revocation of a user's record permissions by an admin, a
+/// change made by a permission policy import, or another admin action on a
+/// user, collection or role.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = permission_audit_entries)]
+pub struct NewPermissionAuditEntry {
+    pub actor_id: Option<i32>,
+    /// `None` for changes to a role
+    pub target_user_id: Option<i32>,
+    pub action: String,
+    pub collection_name: Option<String>,
+    pub role_name: Option<String>,
+    pub affected_count: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -109,6 +171,12 @@ pub struct CreateRoleRequest {
     pub name: String,
     pub description: Option<String>,
     pub priority: i32,
+    /// Records a member may create in any 24 hours; omitted or 0 for no limit
+    #[serde(default)]
+    pub max_records_created_per_day: Option<i64>,
+    /// File bytes a member may upload in any 24 hours; omitted or 0 for no limit
+    #[serde(default)]
+    pub max_upload_bytes_per_day: Option<i64>,
 }
 
 impl CreateRoleRequest {
@@ -133,6 +201,12 @@ impl CreateRoleRequest {
             errors.push("Role priority must be between 0 and 100".to_string());
         }
 
+        validate_usage_quotas(
+            self.max_records_created_per_day,
+            self.max_upload_bytes_per_day,
+            &mut errors,
+        );
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -146,6 +220,10 @@ pub struct UpdateRoleRequest {
     pub name: Option<String>,
     pub description: Option<String>,
     pub priority: Option<i32>,
+    /// 0 removes the limit
+    pub max_records_created_per_day: Option<i64>,
+    /// 0 removes the limit
+    pub max_upload_bytes_per_day: Option<i64>,
 }
 
 impl UpdateRoleRequest {
@@ -176,6 +254,12 @@ impl UpdateRoleRequest {
             }
         }
 
+        validate_usage_quotas(
+            self.max_records_created_per_day,
+            self.max_upload_bytes_per_day,
+            &mut errors,
+        );
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -184,7 +268,27 @@ impl UpdateRoleRequest {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+fn validate_usage_quotas(
+    records: Option<i64>,
+    upload_bytes: Option<i64>,
+    errors: &mut Vec<String>,
+) {
+    for (quota, limit) in [
+        (UsageQuota::RecordsCreated, records),
+        (UsageQuota::UploadBytes, upload_bytes),
+    ] {
+        if limit.is_some_and(|limit| limit < 0) {
+            errors.push(format!("{} cannot be negative", quota.setting()));
+        }
+    }
+}
+
+/// A quota as stored on a role: 0 means no limit, like `None`.
+pub fn stored_usage_quota(limit: Option<i64>) -> Option<i64> {
+    limit.filter(|limit| *limit > 0)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SetCollectionPermissionRequest {
     pub role_name: String,
     pub can_create: bool,
@@ -194,6 +298,196 @@ pub struct SetCollectionPermissionRequest {
     pub can_list: bool,
 }
 
+/// Collection permissions granted to one role, without the role name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PermissionSet {
+    pub can_create: bool,
+    pub can_read: bool,
+    pub can_update: bool,
+    pub can_delete: bool,
+    pub can_list: bool,
+}
+
+impl PermissionSet {
+    pub const FULL: Self = Self {
+        can_create: true,
+        can_read: true,
+        can_update: true,
+        can_delete: true,
+        can_list: true,
+    };
+
+    pub const NONE: Self = Self {
+        can_create: false,
+        can_read: false,
+        can_update: false,
+        can_delete: false,
+        can_list: false,
+    };
+
+    pub const READ_ONLY: Self = Self {
+        can_create: false,
+        can_read: true,
+        can_update: false,
+        can_delete: false,
+        can_list: true,
+    };
+
+    pub fn for_role(&self, role_name: &str) -> SetCollectionPermissionRequest {
+        SetCollectionPermissionRequest {
+            role_name: role_name.to_string(),
+            can_create: self.can_create,
+            can_read: self.can_read,
+            can_update: self.can_update,
+            can_delete: self.can_delete,
+            can_list: self.can_list,
+        }
+    }
+}
+
+impl From<&SetCollectionPermissionRequest> for PermissionSet {
+    fn from(request: &SetCollectionPermissionRequest) -> Self {
+        Self {
+            can_create: request.can_create,
+            can_read: request.can_read,
+            can_update: request.can_update,
+            can_delete: request.can_delete,
+            can_list: request.can_list,
+        }
+    }
+}
+
+/// Permissions each role receives on a newly created collection, stored in
+/// the `system.default_permissions_*` settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DefaultCollectionPermissions {
+    pub admin: PermissionSet,
+    pub user: PermissionSet,
+    pub guest: PermissionSet,
+    /// Applied to every custom role
+    pub custom: PermissionSet,
+}
+
+impl Default for DefaultCollectionPermissions {
+    fn default() -> Self {
+        Self {
+            admin: PermissionSet::FULL,
+            user: PermissionSet {
+                can_delete: false,
+                ..PermissionSet::FULL
+            },
+            guest: PermissionSet::READ_ONLY,
+            custom: PermissionSet::READ_ONLY,
+        }
+    }
+}
+
+impl DefaultCollectionPermissions {
+    pub fn for_role(&self, role_name: &str) -> PermissionSet {
+        match role_name {
+            "admin" => self.admin,
+            "user" => self.user,
+            "guest" => self.guest,
+            _ => self.custom,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        if self.admin != PermissionSet::FULL {
+            return Err(vec![
+                "The admin role must keep full access to new collections".to_string(),
+            ]);
+        }
+        Ok(())
+    }
+}
+
+/// Which kind of access `GET /permissions/collections/{name}/users` lists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AccessFilter {
+    /// Read or list the collection, or read at least one record
+    Read,
+    /// Create, update or delete in the collection or on at least one record
+    Write,
+    #[default]
+    Any,
+}
+
+/// Where an effective collection permission comes from; a user override
+/// wins over the role (its own or an inherited one), and admins have every
+/// permission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionSource {
+    Admin,
+    Role,
+    InheritedRole,
+    UserOverride,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub struct EffectivePermission {
+    pub granted: bool,
+    pub source: PermissionSource,
+}
+
+impl EffectivePermission {
+    pub fn resolve(
+        is_admin: bool,
+        role: Option<bool>,
+        role_inherited: bool,
+        user_override: Option<bool>,
+    ) -> Self {
+        let (granted, source) = match (is_admin, user_override, role) {
+            (true, _, _) => (true, PermissionSource::Admin),
+            (false, Some(granted), _) => (granted, PermissionSource::UserOverride),
+            (false, None, Some(granted)) if role_inherited => {
+                (granted, PermissionSource::InheritedRole)
+            }
+            (false, None, Some(granted)) => (granted, PermissionSource::Role),
+            (false, None, None) => (false, PermissionSource::None),
+        };
+        Self { granted, source }
+    }
+}
+
+/// Record-level permissions a user holds in one collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub struct RecordGrantSummary {
+    /// Records with a user-specific permission
+    pub records: i64,
+    /// At least one record grants read
+    pub can_read: bool,
+    pub can_update: bool,
+    pub can_delete: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CollectionUserAccess {
+    pub user_id: i32,
+    pub username: String,
+    pub email: String,
+    pub role: String,
+    /// Role whose collection permissions apply in place of `role`'s own
+    pub inherited_from: Option<String>,
+    pub can_create: EffectivePermission,
+    pub can_read: EffectivePermission,
+    pub can_update: EffectivePermission,
+    pub can_delete: EffectivePermission,
+    pub can_list: EffectivePermission,
+    pub record_permissions: RecordGrantSummary,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CollectionUserAccessPage {
+    pub users: Vec<CollectionUserAccess>,
+    pub total_count: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SetUserCollectionPermissionRequest {
     pub can_create: Option<bool>,
@@ -205,7 +499,9 @@ pub struct SetUserCollectionPermissionRequest {
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SetRecordPermissionRequest {
-    pub record_id: i32,
+    #[serde(deserialize_with = "super::deserialize_record_id")]
+    #[schema(value_type = String, example = "1")]
+    pub record_id: String,
     pub user_id: i32,
     pub can_read: bool,
     pub can_update: bool,
@@ -288,3 +584,50 @@ impl std::fmt::Display for Permission {
         write!(f, "{}", self.as_str())
     }
 }
+
+/// The check that refused a permission, so misconfigured roles can be told
+/// apart from users probing records they were never given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DenialSource {
+    Role,
+    UserOverride,
+    RecordPermission,
+    Ownership,
+}
+
+impl DenialSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DenialSource::Role => "role",
+            DenialSource::UserOverride => "user_override",
+            DenialSource::RecordPermission => "record_permission",
+            DenialSource::Ownership => "ownership",
+        }
+    }
+}
+
+/// Denials of one action on one collection to one user since startup.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PermissionDenialSummary {
+    pub user_id: i32,
+    pub collection: String,
+    pub action: String,
+    pub count: u64,
+    /// Denials by the check that refused them
+    pub sources: HashMap<String, u64>,
+    pub last_denied_at: String,
+}
+
+/// Sent by email and to the alert webhook when a user crosses the denial threshold.
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionDenialAlert {
+    pub user_id: i32,
+    pub email: String,
+    pub denials: usize,
+    pub window_seconds: u32,
+    pub collection: String,
+    pub action: String,
+    pub source: DenialSource,
+    pub triggered_at: String,
+}
@@ -0,0 +1,37 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::schema::quarantined_uploads;
+
+/// An upload the scanner rejected, kept as an audit entry.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = quarantined_uploads)]
+pub struct QuarantinedUpload {
+    pub id: i32,
+    pub detected_at: NaiveDateTime,
+    /// `None` for uploads that do not belong to a record, e.g. images
+    pub collection_name: Option<String>,
+    pub field_name: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    /// `clamav` or `command`
+    pub scanner: String,
+    pub signature: String,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = quarantined_uploads)]
+pub struct NewQuarantinedUpload {
+    pub collection_name: Option<String>,
+    pub field_name: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub scanner: String,
+    pub signature: String,
+    pub user_id: Option<i32>,
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::RecordResponse;
+
+/// Hash of one exported record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RecordHash {
+    #[schema(example = "42")]
+    pub id: String,
+    /// SHA-256 of the canonical JSON of the record as exported
+    #[schema(example = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08")]
+    pub sha256: String,
+}
+
+/// Proof of what an export contained. `digest` is the SHA-256 of the
+/// canonical JSON of `records`, so it changes with any record hash, id or
+/// their order.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RecordManifest {
+    #[schema(example = "articles")]
+    pub collection: String,
+    #[schema(example = "sha256")]
+    pub algorithm: String,
+    #[schema(example = "lunarbase-canonical-json/1")]
+    pub canonicalization: String,
+    #[schema(example = "2025-10-05T09:00:00Z")]
+    pub exported_at: String,
+    pub record_count: usize,
+    /// In export order, by ascending id
+    pub records: Vec<RecordHash>,
+    #[schema(example = "5feceb66ffc86f38d952786c6d696c79c2dbc239dd4e91b46729d73a27fb57e9")]
+    pub digest: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecordExport {
+    pub records: Vec<RecordResponse>,
+    pub manifest: RecordManifest,
+}
+
+/// Differences between a manifest and the collection as it is now.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ManifestVerification {
+    #[schema(example = "articles")]
+    pub collection: String,
+    /// Whether the manifest's digest matches its own record hashes
+    pub manifest_intact: bool,
+    /// Whether the collection would export with the same digest today
+    pub digest_matches: bool,
+    pub unchanged: usize,
+    /// Records whose content differs from the export
+    #[schema(example = json!(["7"]))]
+    pub changed: Vec<String>,
+    /// Exported records that no longer exist
+    #[schema(example = json!(["12"]))]
+    pub missing: Vec<String>,
+    /// Records created since the export
+    #[schema(example = json!(["31"]))]
+    pub added: Vec<String>,
+    #[schema(example = "2025-10-06T09:00:00Z")]
+    pub verified_at: String,
+}
@@ -0,0 +1,112 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::RecordResponse;
+use crate::schema::record_shares;
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = record_shares)]
+pub struct RecordShare {
+    pub id: i32,
+    pub token_hash: String,
+    pub collection_name: String,
+    pub record_id: String,
+    pub fields: Option<String>,
+    pub password_hash: Option<String>,
+    pub max_views: Option<i32>,
+    pub view_count: i32,
+    pub expires_at: NaiveDateTime,
+    pub created_by: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = record_shares)]
+pub struct NewRecordShare {
+    pub token_hash: String,
+    pub collection_name: String,
+    pub record_id: String,
+    pub fields: Option<String>,
+    pub password_hash: Option<String>,
+    pub max_views: Option<i32>,
+    pub expires_at: NaiveDateTime,
+    pub created_by: i32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CreateRecordShareRequest {
+    /// Hours until the link expires, 24 by default and at most 720
+    #[schema(example = 72)]
+    pub expires_in_hours: Option<i64>,
+    /// Visitors must send it in the `X-Share-Password` header
+    pub password: Option<String>,
+    /// Number of times the link can be opened
+    #[schema(example = 5)]
+    pub max_views: Option<i32>,
+    /// Data fields visible through the link; all of them when omitted
+    #[schema(example = json!(["title", "summary"]))]
+    pub fields: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RecordShareResponse {
+    #[schema(example = 1)]
+    pub id: i32,
+    /// Only returned when the share is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "/api/share/3f0c9b2e...")]
+    pub url: Option<String>,
+    #[schema(example = "articles")]
+    pub collection_name: String,
+    #[schema(example = "1")]
+    pub record_id: String,
+    pub fields: Option<Vec<String>>,
+    pub password_protected: bool,
+    pub max_views: Option<i32>,
+    pub view_count: i32,
+    #[schema(example = "2024-01-04 12:00:00")]
+    pub expires_at: String,
+    pub created_by: i32,
+    #[schema(example = "2024-01-01 12:00:00")]
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SharedRecordResponse {
+    #[schema(example = "articles")]
+    pub collection_name: String,
+    pub record: RecordResponse,
+    #[schema(example = "2024-01-04 12:00:00")]
+    pub expires_at: String,
+    /// Views left after this one, or null when the link has no view limit
+    pub views_remaining: Option<i32>,
+}
+
+impl RecordShare {
+    pub fn get_fields(&self) -> Option<Vec<String>> {
+        self.fields
+            .as_deref()
+            .and_then(|fields| serde_json::from_str(fields).ok())
+    }
+
+    pub fn to_response(&self) -> RecordShareResponse {
+        RecordShareResponse {
+            id: self.id,
+            token: None,
+            url: None,
+            collection_name: self.collection_name.clone(),
+            record_id: self.record_id.clone(),
+            fields: self.get_fields(),
+            password_protected: self.password_hash.is_some(),
+            max_views: self.max_views,
+            view_count: self.view_count,
+            expires_at: self.expires_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            created_by: self.created_by,
+            created_at: self.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
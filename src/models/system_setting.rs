@@ -152,6 +152,8 @@ pub enum SettingCategory {
     Storage,
     #[serde(rename = "security_headers")]
     SecurityHeaders,
+    #[serde(rename = "urls")]
+    Urls,
 }
 
 impl ToString for SettingCategory {
@@ -164,6 +166,7 @@ impl ToString for SettingCategory {
             SettingCategory::OAuth => "oauth".to_string(),
             SettingCategory::Storage => "storage".to_string(),
             SettingCategory::SecurityHeaders => "security_headers".to_string(),
+            SettingCategory::Urls => "urls".to_string(),
         }
     }
 }
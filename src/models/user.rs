@@ -1,12 +1,13 @@
-use argon2::password_hash::SaltString;
-use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
 use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::prelude::*;
-use rand::{RngCore, rngs::OsRng};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::schema::users;
+use crate::services::PasswordHashParams;
+use crate::services::password_service::{hash_password, verify_password};
+use crate::utils::normalize_email;
+use crate::utils::username::{INVALID_USERNAME_MESSAGE, normalize_username};
 
 #[derive(Debug, Queryable, Selectable, Identifiable, AsChangeset, Serialize, ToSchema)]
 #[diesel(table_name = users)]
@@ -36,6 +37,8 @@ pub struct User {
     pub avatar_url: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    #[serde(skip_serializing)]
+    pub lockout_count: i32,
 }
 
 #[derive(Debug, AsChangeset)]
@@ -76,10 +79,18 @@ pub struct RegisterRequest {
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct LoginRequest {
+    /// Email address or username, whichever `auth.login_identifier` allows.
+    /// Older clients may send it as `email`
+    #[serde(alias = "email")]
     #[schema(example = "user@example.com")]
-    pub email: String,
+    pub identifier: String,
     #[schema(example = "SecurePassword123!")]
     pub password: String,
+    /// Workspace to sign in to while workspaces are enabled; defaults to the
+    /// user's first workspace
+    #[serde(default)]
+    #[schema(example = 2)]
+    pub workspace_id: Option<i32>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -103,6 +114,64 @@ pub struct UserResponse {
     pub created_at: DateTime<Utc>,
 }
 
+/// An account whose lockout has not expired yet.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LockedAccount {
+    #[schema(example = 7)]
+    pub user_id: i32,
+    #[schema(example = "user@example.com")]
+    pub email: String,
+    #[schema(example = "john_doe")]
+    pub username: String,
+    #[schema(example = "user")]
+    pub role: String,
+    pub locked_until: DateTime<Utc>,
+    #[schema(example = 840)]
+    pub remaining_seconds: i64,
+    #[schema(example = 5)]
+    pub failed_login_attempts: i32,
+    /// Lockouts since the last successful login
+    #[schema(example = 2)]
+    pub lockout_count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EmailOwner {
+    #[schema(example = 7)]
+    pub user_id: i32,
+    #[schema(example = "John@Example.COM")]
+    pub email: String,
+}
+
+/// A stored email rewritten to its normalized form.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EmailChange {
+    #[schema(example = 7)]
+    pub user_id: i32,
+    #[schema(example = " John@Example.COM")]
+    pub from: String,
+    #[schema(example = "John@example.com")]
+    pub to: String,
+}
+
+/// Accounts whose emails normalize to the same address. None of them is
+/// changed; an admin has to merge or rename them.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EmailConflict {
+    #[schema(example = "John@example.com")]
+    pub normalized: String,
+    pub users: Vec<EmailOwner>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EmailNormalizationReport {
+    pub dry_run: bool,
+    pub normalized: Vec<EmailChange>,
+    pub conflicts: Vec<EmailConflict>,
+    /// Stored emails that are not valid addresses, left as they are
+    pub invalid: Vec<EmailOwner>,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct AuthResponse {
     pub user: UserResponse,
@@ -112,6 +181,10 @@ pub struct AuthResponse {
     pub refresh_token: String,
     #[schema(example = 3600)]
     pub expires_in: i64,
+    /// Echo in the `X-CSRF-Token` header of cookie-authenticated writes;
+    /// also set in the script-readable `csrf_token` cookie.
+    #[schema(example = "9f86d081884c4d7a9a1b7e5b1c2d3e4f")]
+    pub csrf_token: String,
 }
 
 impl User {
@@ -123,18 +196,13 @@ impl User {
         }
     }
 
+    /// Blocking; request handlers go through `PasswordService::verify`.
     pub fn verify_password(
         &self,
         password: &str,
         pepper: &str,
     ) -> Result<bool, argon2::password_hash::Error> {
-        let parsed_hash = PasswordHash::new(&self.password_hash)?;
-
-        let peppered_password = format!("{}{}", password, pepper);
-
-        Ok(Argon2::default()
-            .verify_password(peppered_password.as_bytes(), &parsed_hash)
-            .is_ok())
+        verify_password(password, pepper, &self.password_hash)
     }
 
     pub fn to_response(&self) -> UserResponse {
@@ -162,111 +230,25 @@ impl User {
 }
 
 impl NewUser {
-    pub fn new(
+    /// A user whose password was hashed beforehand, normally by `PasswordService`.
+    pub fn with_password_hash(
         email: String,
-        password: &str,
-        username: String,
-        pepper: &str,
-    ) -> Result<Self, String> {
-        let mut salt_bytes = [0u8; 32];
-        OsRng.fill_bytes(&mut salt_bytes);
-
-        let salt = SaltString::encode_b64(&salt_bytes)
-            .map_err(|e| format!("Salt generation failed: {}", e))?;
-
-        let peppered_password = format!("{}{}", password, pepper);
-
-        let argon2 = Argon2::new(
-            argon2::Algorithm::Argon2id,
-            argon2::Version::V0x13,
-            argon2::Params::new(65536, 4, 2, None).unwrap(),
-        );
-        let password_hash = argon2
-            .hash_password(peppered_password.as_bytes(), &salt)
-            .map_err(|e| format!("Password hashing failed: {}", e))?
-            .to_string();
-
-        Ok(NewUser {
-            email,
-            password_hash,
-            username,
-            role: "user".to_string(),
-            is_verified: false,
-            avatar_url: None,
-        })
-    }
-
-    pub fn new_with_role(
-        email: String,
-        password: &str,
+        password_hash: String,
         username: String,
         role: String,
-        pepper: &str,
-    ) -> Result<Self, String> {
-        let mut salt_bytes = [0u8; 32];
-        OsRng.fill_bytes(&mut salt_bytes);
-
-        let salt = SaltString::encode_b64(&salt_bytes)
-            .map_err(|e| format!("Salt generation failed: {}", e))?;
-
-        let peppered_password = format!("{}{}", password, pepper);
-
-        let argon2 = Argon2::new(
-            argon2::Algorithm::Argon2id,
-            argon2::Version::V0x13,
-            argon2::Params::new(65536, 4, 2, None).unwrap(),
-        );
-        let password_hash = argon2
-            .hash_password(peppered_password.as_bytes(), &salt)
-            .map_err(|e| format!("Password hashing failed: {}", e))?
-            .to_string();
-
-        Ok(NewUser {
+    ) -> Self {
+        NewUser {
             email,
             password_hash,
             username,
             role,
             is_verified: false,
             avatar_url: None,
-        })
-    }
-
-    pub fn new_with_avatar(
-        email: String,
-        password: &str,
-        username: String,
-        role: String,
-        avatar_url: Option<String>,
-        pepper: &str,
-    ) -> Result<Self, String> {
-        let mut salt_bytes = [0u8; 32];
-        OsRng.fill_bytes(&mut salt_bytes);
-
-        let salt = SaltString::encode_b64(&salt_bytes)
-            .map_err(|e| format!("Salt generation failed: {}", e))?;
-
-        let peppered_password = format!("{}{}", password, pepper);
-
-        let argon2 = Argon2::new(
-            argon2::Algorithm::Argon2id,
-            argon2::Version::V0x13,
-            argon2::Params::new(65536, 4, 2, None).unwrap(),
-        );
-        let password_hash = argon2
-            .hash_password(peppered_password.as_bytes(), &salt)
-            .map_err(|e| format!("Password hashing failed: {}", e))?
-            .to_string();
-
-        Ok(NewUser {
-            email,
-            password_hash,
-            username,
-            role,
-            is_verified: false,
-            avatar_url,
-        })
+        }
     }
 
+    /// Hashes on the calling thread with the default parameters; meant for
+    /// startup and tests, not request handlers.
     pub fn new_verified(
         email: String,
         password: &str,
@@ -275,43 +257,28 @@ impl NewUser {
         is_verified: bool,
         pepper: &str,
     ) -> Result<Self, String> {
-        let mut salt_bytes = [0u8; 32];
-        OsRng.fill_bytes(&mut salt_bytes);
-
-        let salt = SaltString::encode_b64(&salt_bytes)
-            .map_err(|e| format!("Salt generation failed: {}", e))?;
-
-        let peppered_password = format!("{}{}", password, pepper);
-
-        let argon2 = Argon2::new(
-            argon2::Algorithm::Argon2id,
-            argon2::Version::V0x13,
-            argon2::Params::new(65536, 4, 2, None).unwrap(),
-        );
-        let password_hash = argon2
-            .hash_password(peppered_password.as_bytes(), &salt)
-            .map_err(|e| format!("Password hashing failed: {}", e))?
-            .to_string();
+        let password_hash = hash_password(password, pepper, &PasswordHashParams::default())?;
 
         Ok(NewUser {
-            email,
-            password_hash,
-            username,
-            role,
             is_verified,
-            avatar_url: None,
+            ..NewUser::with_password_hash(email, password_hash, username, role)
         })
     }
 }
 
 impl RegisterRequest {
-    pub fn validate(&self) -> Result<(), Vec<String>> {
+    /// Also replaces `email` and `username` with their normalized forms when
+    /// they are valid.
+    pub fn validate(&mut self) -> Result<(), Vec<String>> {
         let mut errors = Vec::new();
 
-        if self.email.is_empty() {
+        if self.email.trim().is_empty() {
             errors.push("Email is required".to_string());
-        } else if !self.is_valid_email() {
-            errors.push("Invalid email format".to_string());
+        } else {
+            match normalize_email(&self.email) {
+                Some(email) => self.email = email,
+                None => errors.push("Invalid email format".to_string()),
+            }
         }
 
         if self.password.is_empty() {
@@ -320,10 +287,13 @@ impl RegisterRequest {
             errors.push("Password must be at least 8 characters long and contain uppercase, lowercase, number and special character".to_string());
         }
 
-        if self.username.is_empty() {
+        if self.username.trim().is_empty() {
             errors.push("Username is required".to_string());
-        } else if !self.is_valid_username() {
-            errors.push("Username must be 3-30 characters long and contain only letters, numbers, and underscores".to_string());
+        } else {
+            match normalize_username(&self.username) {
+                Some(username) => self.username = username,
+                None => errors.push(INVALID_USERNAME_MESSAGE.to_string()),
+            }
         }
 
         if errors.is_empty() {
@@ -333,11 +303,6 @@ impl RegisterRequest {
         }
     }
 
-    fn is_valid_email(&self) -> bool {
-        // TODO: Basic email validation (in production, use proper email validation crate)
-        self.email.contains('@') && self.email.len() <= 255
-    }
-
     fn is_strong_password(&self) -> bool {
         self.password.len() >= 8
             && self.password.chars().any(|c| c.is_uppercase())
@@ -345,14 +310,34 @@ impl RegisterRequest {
             && self.password.chars().any(|c| c.is_numeric())
             && self.password.chars().any(|c| c.is_ascii_punctuation())
     }
+}
 
-    fn is_valid_username(&self) -> bool {
-        self.username.len() >= 3
-            && self.username.len() <= 30
-            && self
-                .username
-                .chars()
-                .all(|c| c.is_alphanumeric() || c == '_')
+/// What a login identifier is matched against, per `auth.login_identifier`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoginIdentifier {
+    #[default]
+    Email,
+    Username,
+    /// Email first, then username
+    Either,
+}
+
+impl LoginIdentifier {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "email" => Some(LoginIdentifier::Email),
+            "username" => Some(LoginIdentifier::Username),
+            "either" => Some(LoginIdentifier::Either),
+            _ => None,
+        }
+    }
+
+    pub fn matches_email(self) -> bool {
+        matches!(self, LoginIdentifier::Email | LoginIdentifier::Either)
+    }
+
+    pub fn matches_username(self) -> bool {
+        matches!(self, LoginIdentifier::Username | LoginIdentifier::Either)
     }
 }
 
@@ -360,8 +345,8 @@ impl LoginRequest {
     pub fn validate(&self) -> Result<(), Vec<String>> {
         let mut errors = Vec::new();
 
-        if self.email.is_empty() {
-            errors.push("Email is required".to_string());
+        if self.identifier.trim().is_empty() {
+            errors.push("Email or username is required".to_string());
         }
 
         if self.password.is_empty() {
@@ -0,0 +1,63 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::schema::user_usage;
+
+/// What one user created and uploaded within the hour starting at `bucket_start`.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = user_usage)]
+pub struct NewUserUsage {
+    pub user_id: i32,
+    pub bucket_start: NaiveDateTime,
+    pub records_created: i64,
+    pub upload_bytes: i64,
+}
+
+/// A per-role limit on what one user may do in any 24 hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageQuota {
+    RecordsCreated,
+    UploadBytes,
+}
+
+impl UsageQuota {
+    /// Name of the role field that sets the quota
+    pub fn setting(&self) -> &'static str {
+        match self {
+            Self::RecordsCreated => "max_records_created_per_day",
+            Self::UploadBytes => "max_upload_bytes_per_day",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct UsageCounts {
+    #[schema(example = 42)]
+    pub records_created: i64,
+    /// Decoded size of the files uploaded with records and images
+    #[schema(example = 1048576)]
+    pub upload_bytes: i64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, ToSchema)]
+pub struct UsageQuotas {
+    /// `None` when the role sets no limit
+    #[schema(example = 1000)]
+    pub max_records_created_per_day: Option<i64>,
+    #[schema(example = 104857600)]
+    pub max_upload_bytes_per_day: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserUsageResponse {
+    #[schema(example = 1)]
+    pub user_id: i32,
+    #[schema(example = "user")]
+    pub role: String,
+    /// What the quotas are checked against, counted by the hour
+    pub last_24_hours: UsageCounts,
+    pub last_30_days: UsageCounts,
+    pub quotas: UsageQuotas,
+}
@@ -10,14 +10,17 @@ use crate::schema::verification_tokens;
 pub struct VerificationToken {
     #[schema(example = 1)]
     pub id: i32,
-    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
-    pub token: String,
+    /// Hex SHA-256 of the token sent by email; the token itself is never stored
+    #[schema(example = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08")]
+    pub token_hash: String,
     #[schema(example = 1)]
     pub user_id: i32,
     #[schema(example = "user@example.com")]
     pub email: String,
     pub expires_at: NaiveDateTime,
     pub created_at: NaiveDateTime,
+    #[schema(example = "email_verification")]
+    pub token_type: String,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -46,37 +49,41 @@ impl TokenType {
 #[derive(Debug, Clone, Serialize, Deserialize, Insertable, ToSchema)]
 #[diesel(table_name = verification_tokens)]
 pub struct NewVerificationToken {
-    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
-    pub token: String,
+    /// Hex SHA-256 of the token sent by email; the token itself is never stored
+    #[schema(example = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08")]
+    pub token_hash: String,
     #[schema(example = 1)]
     pub user_id: i32,
     #[schema(example = "user@example.com")]
     pub email: String,
     pub expires_at: NaiveDateTime,
+    pub token_type: String,
 }
 
 impl NewVerificationToken {
-    pub fn new(token: String, user_id: i32, email: String, expires_at: NaiveDateTime) -> Self {
-        Self {
-            token,
+    pub fn new(token_hash: String, user_id: i32, email: String, expires_at: NaiveDateTime) -> Self {
+        Self::new_with_type(
+            token_hash,
             user_id,
             email,
             expires_at,
-        }
+            TokenType::EmailVerification,
+        )
     }
 
     pub fn new_with_type(
-        token: String,
+        token_hash: String,
         user_id: i32,
         email: String,
         expires_at: NaiveDateTime,
-        _token_type: TokenType,
+        token_type: TokenType,
     ) -> Self {
         Self {
-            token,
+            token_hash,
             user_id,
             email,
             expires_at,
+            token_type: token_type.as_str().to_string(),
         }
     }
 }
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::models::{NotificationPush, QuotaWarning, WorkspaceSession};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "data")]
 pub enum WebSocketMessage {
@@ -12,7 +15,28 @@ pub enum WebSocketMessage {
     SubscriptionConfirmed(SubscriptionConfirmed),
     SubscriptionError(SubscriptionError),
     Event(EventMessage),
+    CollectionEvent(CollectionEventMessage),
+    BulkChange(BulkChangeMessage),
+    SystemNotice(SystemNoticeMessage),
+    /// Sent to every connection of the user it is addressed to, without a
+    /// subscription
+    Notification(NotificationPush),
+    Error(WebSocketError),
     Pong,
+    /// Sent as a close frame rather than a text message
+    Close(CloseNotice),
+}
+
+/// Channel name to subscribe to for [`CollectionEvent`]s instead of record events.
+pub const COLLECTIONS_CHANNEL: &str = "_collections";
+
+/// Channel name admins subscribe to for [`SystemNotice`]s.
+pub const SYSTEM_CHANNEL: &str = "_system";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloseNotice {
+    pub code: u16,
+    pub reason: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +65,16 @@ pub struct SubscriptionError {
     pub error: String,
 }
 
+/// Sent when a client message breaks one of the connection limits, e.g.
+/// `{"code": "message_too_large", "error": "...", "limit": 65536, "subscription_id": null}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSocketError {
+    pub code: String,
+    pub error: String,
+    pub limit: usize,
+    pub subscription_id: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SubscriptionType {
     Collection,
@@ -55,6 +89,66 @@ pub struct EventMessage {
     pub event: RecordEvent,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionEventMessage {
+    pub subscription_id: String,
+    pub event: CollectionEvent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemNoticeMessage {
+    pub subscription_id: String,
+    pub notice: SystemNotice,
+}
+
+/// Sent on the `_system` channel, e.g.
+/// `{"notice": "QuotaWarning", "quota": "concurrent_queries", "subject": "7", ...}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "notice")]
+pub enum SystemNotice {
+    QuotaWarning(QuotaWarning),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkChangeMessage {
+    pub subscription_id: String,
+    pub change: BulkChange,
+}
+
+/// Replaces a burst of record events on one collection, e.g.
+/// `{"collection": "posts", "created": 1200, "updated": 0, "deleted": 3, "sample_ids": ["41", "42"]}`.
+/// Clients should refetch instead of patching their state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BulkChange {
+    pub collection: String,
+    pub created: u32,
+    pub updated: u32,
+    pub deleted: u32,
+    /// Some of the changed record ids
+    pub sample_ids: Vec<String>,
+}
+
+/// Sent on the `_collections` channel to users who can list the collection, e.g.
+/// `{"action": "SchemaUpdated", "collection": "posts", "version": 3, "renamed_from": null}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action")]
+pub enum CollectionEvent {
+    Created {
+        collection: String,
+        version: i32,
+    },
+    /// Sent for every collection update; `version` only changes when the
+    /// schema did, so clients can skip refetching otherwise.
+    SchemaUpdated {
+        collection: String,
+        version: i32,
+        renamed_from: Option<String>,
+    },
+    Deleted {
+        collection: String,
+    },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "action")]
 pub enum RecordEvent {
@@ -71,6 +165,34 @@ pub enum RecordEvent {
         record_id: String,
         old_record: Option<serde_json::Value>,
     },
+    /// Sent when a record's `owner_id` changes, e.g.
+    /// `{"action": "OwnershipTransferred", "record_id": "7", "old_owner_id": 1, "new_owner_id": 2}`.
+    /// `old_owner_id` is `null` when the record had no owner.
+    OwnershipTransferred {
+        record_id: String,
+        old_owner_id: Option<i32>,
+        new_owner_id: i32,
+    },
+    /// Sent when a record of an orderable collection is moved, e.g.
+    /// `{"action": "Reordered", "record_id": "7", "sort_order": 1536.0, "renormalized": false}`.
+    /// `renormalized` means every record got a new `sort_order`, so clients should refetch.
+    Reordered {
+        record_id: String,
+        sort_order: f64,
+        renormalized: bool,
+    },
+    /// Sent when a record of a draft/publish collection becomes published,
+    /// either on request or once its scheduled `publish_at` has passed.
+    Published {
+        record_id: String,
+        record: serde_json::Value,
+    },
+    /// Sent when a published record goes back to `draft` or is `archived`.
+    Unpublished {
+        record_id: String,
+        record: serde_json::Value,
+        status: String,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -78,6 +200,9 @@ pub struct ClientConnection {
     pub user_id: Option<i32>,
     pub connection_id: Uuid,
     pub subscriptions: HashMap<String, SubscriptionData>,
+    /// Workspace of the token the connection was opened with; collections of
+    /// other workspaces are hidden from it
+    pub workspace: Option<WorkspaceSession>,
 }
 
 #[derive(Debug, Clone)]
@@ -95,12 +220,20 @@ pub struct PendingEvent {
     pub user_id: Option<i32>,
 }
 
+#[derive(Debug, Clone)]
+pub struct PendingBulkChange {
+    pub change: BulkChange,
+    /// Every changed record id, used to match record subscriptions
+    pub record_ids: Arc<HashSet<String>>,
+}
+
 impl ClientConnection {
     pub fn new(user_id: Option<i32>) -> Self {
         Self {
             user_id,
             connection_id: Uuid::new_v4(),
             subscriptions: HashMap::new(),
+            workspace: None,
         }
     }
 
@@ -151,6 +284,22 @@ impl SubscriptionData {
                 | RecordEvent::Deleted {
                     record_id: event_record_id,
                     ..
+                }
+                | RecordEvent::OwnershipTransferred {
+                    record_id: event_record_id,
+                    ..
+                }
+                | RecordEvent::Reordered {
+                    record_id: event_record_id,
+                    ..
+                }
+                | RecordEvent::Published {
+                    record_id: event_record_id,
+                    ..
+                }
+                | RecordEvent::Unpublished {
+                    record_id: event_record_id,
+                    ..
                 } => record_id == event_record_id,
             },
             SubscriptionType::Query {
@@ -159,11 +308,28 @@ impl SubscriptionData {
         }
     }
 
+    /// Record subscriptions match when their record changed; query
+    /// subscriptions always match since the records are not included.
+    pub fn matches_bulk_change(&self, bulk: &PendingBulkChange) -> bool {
+        if self.collection_name != bulk.change.collection {
+            return false;
+        }
+
+        match &self.subscription_type {
+            SubscriptionType::Collection | SubscriptionType::Query { .. } => true,
+            SubscriptionType::Record { record_id } => bulk.record_ids.contains(record_id),
+        }
+    }
+
     fn matches_filters(&self, event: &RecordEvent, filters: &HashMap<String, String>) -> bool {
         let record_data = match event {
             RecordEvent::Created { record, .. } => Some(record),
-            RecordEvent::Updated { record, .. } => Some(record),
+            RecordEvent::Updated { record, .. }
+            | RecordEvent::Published { record, .. }
+            | RecordEvent::Unpublished { record, .. } => Some(record),
             RecordEvent::Deleted { old_record, .. } => old_record.as_ref(),
+            // Carries no record data, so query subscriptions cannot evaluate it
+            RecordEvent::OwnershipTransferred { .. } | RecordEvent::Reordered { .. } => None,
         };
 
         let record_data = match record_data {
@@ -0,0 +1,154 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::schema::{workspace_members, workspaces};
+
+/// Holds every collection and user from before workspaces were enabled.
+pub const DEFAULT_WORKSPACE_ID: i32 = 1;
+
+/// Records tables of the default workspace keep their unprefixed name, so a
+/// single-workspace install uses the same tables with or without workspaces.
+pub fn records_table_name(workspace_id: i32, collection_name: &str) -> String {
+    if workspace_id == DEFAULT_WORKSPACE_ID {
+        format!("records_{}", collection_name)
+    } else {
+        format!("ws{}_records_{}", workspace_id, collection_name)
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = workspaces)]
+pub struct Workspace {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = workspaces)]
+pub struct NewWorkspace {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = workspace_members)]
+pub struct WorkspaceMember {
+    pub id: i32,
+    pub workspace_id: i32,
+    pub user_id: i32,
+    pub role: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = workspace_members)]
+pub struct NewWorkspaceMember {
+    pub workspace_id: i32,
+    pub user_id: i32,
+    pub role: String,
+}
+
+/// The workspace an access token is scoped to and the user's role there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceSession {
+    pub workspace_id: i32,
+    pub role: String,
+    pub superadmin: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWorkspaceRequest {
+    #[schema(example = "acme")]
+    pub name: String,
+    #[schema(example = "Collections of the Acme storefront")]
+    pub description: Option<String>,
+}
+
+impl CreateWorkspaceRequest {
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.name.is_empty() || self.name.len() > 50 {
+            errors.push("Workspace name must be between 1 and 50 characters".to_string());
+        }
+        if !self
+            .name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            errors.push(
+                "Workspace name may only contain lowercase letters, digits, '-' and '_'"
+                    .to_string(),
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WorkspaceResponse {
+    #[schema(example = 2)]
+    pub id: i32,
+    #[schema(example = "acme")]
+    pub name: String,
+    pub description: Option<String>,
+    #[schema(example = 4)]
+    pub collection_count: i64,
+    /// Users with an explicit membership; users without any membership
+    /// belong to the default workspace
+    #[schema(example = 12)]
+    pub member_count: i64,
+    pub created_at: NaiveDateTime,
+}
+
+impl WorkspaceResponse {
+    pub fn new(workspace: Workspace, collection_count: i64, member_count: i64) -> Self {
+        Self {
+            id: workspace.id,
+            name: workspace.name,
+            description: workspace.description,
+            collection_count,
+            member_count,
+            created_at: workspace.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetWorkspaceMemberRequest {
+    /// A built-in role or a custom role of this workspace
+    #[schema(example = "editor")]
+    pub role: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WorkspaceMemberResponse {
+    #[schema(example = 2)]
+    pub workspace_id: i32,
+    #[schema(example = 7)]
+    pub user_id: i32,
+    #[schema(example = "editor")]
+    pub role: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MoveCollectionRequest {
+    #[schema(example = "products")]
+    pub collection: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SwitchWorkspaceRequest {
+    #[schema(example = 2)]
+    pub workspace_id: i32,
+}
@@ -0,0 +1,643 @@
+use crate::codegen::to_pascal_case;
+use crate::json_schema::schema_to_json_schema;
+use crate::models::CollectionResponse;
+use serde_json::{Map, Value, json};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+const ERROR_RESPONSE_REF: &str = "#/components/schemas/ErrorResponse";
+
+const TYPE_SUFFIXES: [&str; 4] = ["Data", "Record", "RecordResponse", "RecordListResponse"];
+
+/// API paths that are only mounted next to the admin panel.
+const FRONTEND_ONLY_PATHS: [&str; 1] = ["/avatar-proxy"];
+
+/// Drops the paths API-only mode does not serve, so the document matches the
+/// route tree.
+pub fn without_frontend_paths(mut document: utoipa::openapi::OpenApi) -> utoipa::openapi::OpenApi {
+    document
+        .paths
+        .paths
+        .retain(|path, _| !FRONTEND_ONLY_PATHS.contains(&path.as_str()));
+    document
+}
+
+/// Merges typed path items and component schemas for every collection into
+/// the static API document. The generic `/collections/{collection_name}/...`
+/// paths are kept for clients that work with collections dynamically.
+pub fn collections_openapi(base: &Value, collections: &[CollectionResponse]) -> Value {
+    let mut document = base.clone();
+    let mut collections: Vec<&CollectionResponse> = collections.iter().collect();
+    collections.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut paths = Map::new();
+    let mut schemas = Map::new();
+    let mut used_names: HashSet<String> = document
+        .pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .map(|existing| existing.keys().cloned().collect())
+        .unwrap_or_default();
+
+    for collection in collections {
+        let type_name = unique_type_name(&collection.name, &mut used_names);
+        let data_ref = schema_ref(&format!("{}Data", type_name));
+        let record_ref = schema_ref(&format!("{}Record", type_name));
+
+        schemas.insert(format!("{}Data", type_name), data_schema(collection));
+        schemas.insert(
+            format!("{}Record", type_name),
+            json!({
+                "type": "object",
+                "properties": {
+                    "id": {"type": "string"},
+                    "collection_id": {"type": "string"},
+                    "data": {"$ref": data_ref},
+                    "created_at": {"type": "string"},
+                    "updated_at": {"type": "string"},
+                },
+                "required": ["id", "collection_id", "data", "created_at", "updated_at"],
+            }),
+        );
+        schemas.insert(
+            format!("{}RecordResponse", type_name),
+            envelope(json!({"$ref": record_ref})),
+        );
+        schemas.insert(
+            format!("{}RecordListResponse", type_name),
+            envelope(json!({"type": "array", "items": {"$ref": record_ref}})),
+        );
+
+        let (records_path, record_path) = collection_paths(collection, &type_name);
+        paths.insert(
+            format!("/collections/{}/records", collection.name),
+            records_path,
+        );
+        paths.insert(
+            format!("/collections/{}/records/{{record_id}}", collection.name),
+            record_path,
+        );
+    }
+
+    if let Some(object) = document.as_object_mut() {
+        let existing_paths = object
+            .entry("paths")
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Some(existing_paths) = existing_paths.as_object_mut() {
+            existing_paths.extend(paths);
+        }
+
+        let components = object
+            .entry("components")
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Some(components) = components.as_object_mut() {
+            let existing_schemas = components
+                .entry("schemas")
+                .or_insert_with(|| Value::Object(Map::new()));
+            if let Some(existing_schemas) = existing_schemas.as_object_mut() {
+                existing_schemas.extend(schemas);
+            }
+        }
+    }
+
+    document
+}
+
+/// Collection names may differ only in underscores or case, which would map
+/// them to the same type name, so later ones get a numeric suffix.
+fn unique_type_name(collection_name: &str, used_names: &mut HashSet<String>) -> String {
+    let base = to_pascal_case(collection_name);
+    let mut candidate = base.clone();
+    let mut suffix = 2;
+
+    while TYPE_SUFFIXES
+        .iter()
+        .any(|kind| used_names.contains(&format!("{}{}", candidate, kind)))
+    {
+        candidate = format!("{}{}", base, suffix);
+        suffix += 1;
+    }
+
+    for kind in TYPE_SUFFIXES {
+        used_names.insert(format!("{}{}", candidate, kind));
+    }
+    candidate
+}
+
+fn data_schema(collection: &CollectionResponse) -> Value {
+    let mut schema = schema_to_json_schema(&collection.schema);
+
+    if let Some(object) = schema.as_object_mut() {
+        // Component schemas inherit the document's dialect
+        object.remove("$schema");
+        if let Some(description) = &collection.description {
+            object.insert("description".to_string(), json!(description));
+        }
+        if let Some(properties) = object.get_mut("properties").and_then(Value::as_object_mut) {
+            properties
+                .entry("owner_id")
+                .or_insert_with(|| json!({"type": "integer"}));
+            properties
+                .entry("author_id")
+                .or_insert_with(|| json!({"type": "integer"}));
+        }
+    }
+
+    schema
+}
+
+fn envelope(data: Value) -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "success": {"type": "boolean"},
+            "data": data,
+            "error": {},
+            "timestamp": {"type": "string"},
+        },
+        "required": ["success", "timestamp"],
+    })
+}
+
+fn collection_paths(collection: &CollectionResponse, type_name: &str) -> (Value, Value) {
+    let name = &collection.name;
+    let tags = json!(["Records"]);
+    let record_response = json!({"$ref": schema_ref(&format!("{}RecordResponse", type_name))});
+    let list_response = json!({"$ref": schema_ref(&format!("{}RecordListResponse", type_name))});
+    let multipart_body = json!({
+        "description": "Record data and optional files",
+        "required": true,
+        "content": {
+            "multipart/form-data": {
+                "schema": {
+                    "type": "object",
+                    "properties": {
+                        "data": {"$ref": schema_ref(&format!("{}Data", type_name))},
+                    },
+                    "required": ["data"],
+                },
+                "encoding": {
+                    "data": {"contentType": "application/json"},
+                },
+            },
+        },
+    });
+    let record_id = json!({
+        "name": "record_id",
+        "in": "path",
+        "required": true,
+        "description": "Record ID",
+        "schema": {"type": "string"},
+    });
+    let security = json!([{"bearer_auth": []}]);
+
+    let records_path = json!({
+        "get": {
+            "tags": tags,
+            "operationId": format!("list_{}_records", name),
+            "summary": format!("List {} records", name),
+            "parameters": [
+                query_parameter("limit", "integer", "Limit number of records"),
+                query_parameter("offset", "integer", "Offset for pagination"),
+                query_parameter("sort", "string", "Sort field"),
+                query_parameter("filter", "string", "Filter expression"),
+                query_parameter("search", "string", "Search term"),
+            ],
+            "responses": {
+                "200": json_response("Records retrieved successfully", list_response),
+                "400": error_response("Invalid query"),
+                "404": error_response("Collection not found"),
+            },
+        },
+        "post": {
+            "tags": tags,
+            "operationId": format!("create_{}_record", name),
+            "summary": format!("Create a {} record", name),
+            "requestBody": multipart_body,
+            "responses": {
+                "201": json_response("Record created successfully", record_response.clone()),
+                "400": error_response("Validation error"),
+                "403": error_response("Insufficient permissions"),
+                "405": error_response("Collection is read-only"),
+            },
+            "security": security,
+        },
+    });
+
+    let record_path = json!({
+        "get": {
+            "tags": tags,
+            "operationId": format!("get_{}_record", name),
+            "summary": format!("Get a {} record", name),
+            "parameters": [record_id],
+            "responses": {
+                "200": json_response("Record retrieved successfully", record_response.clone()),
+                "403": error_response("Insufficient permissions"),
+                "404": error_response("Record not found"),
+            },
+        },
+        "put": {
+            "tags": tags,
+            "operationId": format!("update_{}_record", name),
+            "summary": format!("Update a {} record", name),
+            "parameters": [record_id],
+            "requestBody": multipart_body,
+            "responses": {
+                "200": json_response("Record updated successfully", record_response),
+                "400": error_response("Validation error"),
+                "403": error_response("Insufficient permissions"),
+                "404": error_response("Record not found"),
+                "405": error_response("Collection is read-only"),
+            },
+            "security": security,
+        },
+        "delete": {
+            "tags": tags,
+            "operationId": format!("delete_{}_record", name),
+            "summary": format!("Delete a {} record", name),
+            "parameters": [record_id],
+            "responses": {
+                "204": {"description": "Record deleted successfully"},
+                "403": error_response("Insufficient permissions"),
+                "404": error_response("Record not found"),
+                "405": error_response("Collection is read-only"),
+            },
+            "security": security,
+        },
+    });
+
+    (records_path, record_path)
+}
+
+fn schema_ref(name: &str) -> String {
+    format!("#/components/schemas/{}", name)
+}
+
+fn query_parameter(name: &str, schema_type: &str, description: &str) -> Value {
+    json!({
+        "name": name,
+        "in": "query",
+        "required": false,
+        "description": description,
+        "schema": {"type": schema_type},
+    })
+}
+
+fn json_response(description: &str, schema: Value) -> Value {
+    json!({
+        "description": description,
+        "content": {"application/json": {"schema": schema}},
+    })
+}
+
+fn error_response(description: &str) -> Value {
+    json_response(description, json!({"$ref": ERROR_RESPONSE_REF}))
+}
+
+/// Caches the merged document until a collection is added, removed or changed,
+/// so the document is only rebuilt on the first request after a change.
+#[derive(Clone)]
+pub struct CollectionsOpenApiCache {
+    base: Arc<Value>,
+    cached: Arc<Mutex<Option<CachedDocument>>>,
+}
+
+struct CachedDocument {
+    fingerprint: Vec<String>,
+    document: Arc<Value>,
+}
+
+impl CollectionsOpenApiCache {
+    pub fn new(base: &utoipa::openapi::OpenApi) -> Self {
+        Self {
+            base: Arc::new(serde_json::to_value(base).unwrap_or_default()),
+            cached: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn get(&self, collections: &[CollectionResponse]) -> Arc<Value> {
+        let mut fingerprint: Vec<String> = collections
+            .iter()
+            .map(|collection| {
+                format!(
+                    "{}:{}:{}:{}",
+                    collection.id,
+                    collection.name,
+                    collection.schema_version,
+                    collection.updated_at
+                )
+            })
+            .collect();
+        fingerprint.sort();
+
+        let mut cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(entry) = cached.as_ref()
+            && entry.fingerprint == fingerprint
+        {
+            return entry.document.clone();
+        }
+
+        let document = Arc::new(collections_openapi(&self.base, collections));
+        *cached = Some(CachedDocument {
+            fingerprint,
+            document: document.clone(),
+        });
+        document
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        CollectionSchema, CollectionWorkflow, FieldDefinition, FieldType, RecordIdType,
+    };
+    use utoipa::OpenApi;
+
+    fn collection(name: &str, fields: Vec<(&str, FieldType, bool)>) -> CollectionResponse {
+        CollectionResponse {
+            id: 1,
+            name: name.to_string(),
+            display_name: None,
+            description: None,
+            schema: CollectionSchema {
+                fields: fields
+                    .into_iter()
+                    .map(|(name, field_type, required)| FieldDefinition {
+                        name: name.to_string(),
+                        field_type,
+                        required,
+                        default_value: None,
+                        validation: None,
+                        relation_target: None,
+                        description: None,
+                        example: None,
+                        indexed_paths: None,
+                    })
+                    .collect(),
+            },
+            schema_version: 1,
+            query_cache_ttl_seconds: 0,
+            orderable: false,
+            id_type: RecordIdType::Integer,
+            allow_explicit_ids: false,
+            workflow: CollectionWorkflow::None,
+            schedule: None,
+            retention: None,
+            workspace_id: 1,
+            is_system: false,
+            created_at: "2024-01-01 12:00:00".to_string(),
+            updated_at: "2024-01-01 12:00:00".to_string(),
+        }
+    }
+
+    fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object {
+                    match (key.as_str(), value) {
+                        ("$ref", Value::String(reference)) => refs.push(reference),
+                        _ => collect_refs(value, refs),
+                    }
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|item| collect_refs(item, refs)),
+            _ => {}
+        }
+    }
+
+    fn merged_document() -> Value {
+        let base = serde_json::to_value(crate::ApiDoc::openapi()).unwrap();
+        collections_openapi(
+            &base,
+            &[
+                collection(
+                    "blog_posts",
+                    vec![
+                        ("title", FieldType::Text, true),
+                        ("views", FieldType::Number, false),
+                        ("location", FieldType::GeoPoint, false),
+                    ],
+                ),
+                collection("blog_posts_", vec![("name", FieldType::Text, true)]),
+            ],
+        )
+    }
+
+    const HTTP_METHODS: [&str; 8] = [
+        "get", "put", "post", "delete", "options", "head", "patch", "trace",
+    ];
+
+    /// Checks the rules of the OpenAPI 3.1 schema that generated paths and
+    /// components can break.
+    fn assert_valid_openapi_3_1(document: &Value) {
+        assert_eq!(document["openapi"], "3.1.0");
+        assert!(document["info"]["title"].is_string());
+        assert!(document["info"]["version"].is_string());
+
+        let mut refs = Vec::new();
+        collect_refs(document, &mut refs);
+        assert!(!refs.is_empty());
+        for reference in refs {
+            assert!(
+                reference
+                    .strip_prefix('#')
+                    .is_some_and(|pointer| document.pointer(pointer).is_some()),
+                "unresolved reference {}",
+                reference
+            );
+        }
+
+        for name in document["components"]["schemas"]
+            .as_object()
+            .unwrap()
+            .keys()
+        {
+            assert!(
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_')),
+                "invalid component name {}",
+                name
+            );
+        }
+
+        let mut operation_ids = HashSet::new();
+        for (path, item) in document["paths"].as_object().unwrap() {
+            assert!(path.starts_with('/'), "path {} must start with /", path);
+            let templated: HashSet<&str> = path
+                .split('/')
+                .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+                .collect();
+
+            for (method, operation) in item.as_object().unwrap() {
+                if !HTTP_METHODS.contains(&method.as_str()) {
+                    continue;
+                }
+
+                if let Some(operation_id) = operation.get("operationId") {
+                    let operation_id = operation_id.as_str().unwrap();
+                    assert!(
+                        operation_ids.insert(operation_id),
+                        "duplicate operationId {}",
+                        operation_id
+                    );
+                }
+
+                let responses = operation["responses"].as_object().unwrap();
+                assert!(
+                    !responses.is_empty(),
+                    "{} {} has no responses",
+                    method,
+                    path
+                );
+                for (status, response) in responses {
+                    assert!(
+                        status == "default"
+                            || (status.len() == 3 && status.chars().all(|c| c.is_ascii_digit())),
+                        "invalid status {} in {} {}",
+                        status,
+                        method,
+                        path
+                    );
+                    assert!(
+                        response.get("$ref").is_some() || response["description"].is_string(),
+                        "response {} of {} {} needs a description",
+                        status,
+                        method,
+                        path
+                    );
+                }
+
+                let parameters = operation
+                    .get("parameters")
+                    .and_then(Value::as_array)
+                    .cloned()
+                    .unwrap_or_default();
+                let mut declared = HashSet::new();
+                for parameter in &parameters {
+                    let name = parameter["name"].as_str().unwrap();
+                    let location = parameter["in"].as_str().unwrap();
+                    assert!(
+                        ["query", "header", "path", "cookie"].contains(&location),
+                        "invalid location of {} in {} {}",
+                        name,
+                        method,
+                        path
+                    );
+                    assert!(
+                        declared.insert((name, location)),
+                        "duplicate parameter {} in {} {}",
+                        name,
+                        method,
+                        path
+                    );
+                    if location == "path" {
+                        assert_eq!(parameter["required"], true);
+                        assert!(templated.contains(name), "{} is not in {}", name, path);
+                    }
+                }
+                for name in &templated {
+                    assert!(
+                        declared.contains(&(*name, "path")),
+                        "{} {} does not declare {}",
+                        method,
+                        path,
+                        name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_api_only_document_omits_frontend_paths() {
+        let full = crate::ApiDoc::openapi();
+        assert!(full.paths.paths.contains_key("/avatar-proxy"));
+
+        let api_only = without_frontend_paths(full.clone());
+        assert!(!api_only.paths.paths.contains_key("/avatar-proxy"));
+        assert_eq!(api_only.paths.paths.len(), full.paths.paths.len() - 1);
+    }
+
+    #[test]
+    fn test_merged_document_is_valid_openapi_3_1() {
+        let document = merged_document();
+        assert_valid_openapi_3_1(&document);
+        assert!(
+            document["paths"]
+                .get("/collections/blog_posts/records/{record_id}")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_collection_types_are_generated_from_schema() {
+        let document = merged_document();
+        let schemas = &document["components"]["schemas"];
+
+        assert!(schemas.get("ErrorResponse").is_some());
+        assert_eq!(schemas["BlogPostsData"]["required"], json!(["title"]));
+        assert_eq!(
+            schemas["BlogPostsData"]["properties"]["views"]["type"],
+            "number"
+        );
+        assert!(schemas["BlogPostsData"].get("$schema").is_none());
+        assert_eq!(
+            schemas["BlogPostsRecord"]["properties"]["data"]["$ref"],
+            "#/components/schemas/BlogPostsData"
+        );
+        // Both names map to BlogPosts, so the later one gets a suffix
+        assert_eq!(schemas["BlogPosts2Data"]["required"], json!(["name"]));
+
+        let create = &document["paths"]["/collections/blog_posts/records"]["post"];
+        assert_eq!(
+            create["requestBody"]["content"]["multipart/form-data"]["schema"]["properties"]["data"]
+                ["$ref"],
+            "#/components/schemas/BlogPostsData"
+        );
+        assert_eq!(
+            create["responses"]["201"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/BlogPostsRecordResponse"
+        );
+
+        // The generic paths stay available
+        assert!(
+            document["paths"]
+                .get("/collections/{collection_name}/records")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_cache_regenerates_only_when_collections_change() {
+        let cache = CollectionsOpenApiCache::new(&crate::ApiDoc::openapi());
+        let mut collections = vec![collection(
+            "articles",
+            vec![("title", FieldType::Text, true)],
+        )];
+
+        let first = cache.get(&collections);
+        assert!(Arc::ptr_eq(&first, &cache.get(&collections)));
+
+        collections[0].schema_version = 2;
+        collections[0].schema.fields.push(FieldDefinition {
+            name: "body".to_string(),
+            field_type: FieldType::RichText,
+            required: false,
+            default_value: None,
+            validation: None,
+            relation_target: None,
+            description: None,
+            example: None,
+            indexed_paths: None,
+        });
+        let second = cache.get(&collections);
+        assert!(!Arc::ptr_eq(&first, &second));
+        assert!(
+            second["components"]["schemas"]["ArticlesData"]["properties"]
+                .get("body")
+                .is_some()
+        );
+    }
+}
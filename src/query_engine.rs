@@ -1,7 +1,18 @@
-use crate::models::CollectionSchema;
+use crate::decimal::parse_minor_units;
+use crate::models::{
+    CollectionSchema, FieldDefinition, FieldType, NumberFormat, geo_point_columns,
+    is_valid_json_path, json_path_column, sqlite_json_path,
+};
 use crate::utils::LunarbaseError;
 use serde::{Deserialize, Serialize};
 
+/// Query complexity of a comparison filter or `in` list item.
+pub const PREDICATE_COST: u32 = 1;
+/// Query complexity of a LIKE comparison, which scans every row.
+pub const LIKE_COST: u32 = 3;
+/// Query complexity of expanding a relation, one extra lookup per page.
+pub const EXPANSION_COST: u32 = 5;
+
 #[derive(Debug, Clone)]
 pub struct QueryEngine {
     pub sort: Vec<SortField>,
@@ -9,6 +20,10 @@ pub struct QueryEngine {
     pub search: Option<String>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    /// Default to `sort_order` and allow sorting by it (orderable collections)
+    pub manual_order: bool,
+    /// Allow filtering and sorting by `status` and `publish_at` (workflow collections)
+    pub workflow: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +43,9 @@ pub struct FilterCondition {
     pub field: String,
     pub operator: FilterOperator,
     pub value: FilterValue,
+    /// Value as written, for decimal fields that must not go through `f64`
+    #[serde(default)]
+    pub raw_value: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +62,35 @@ pub enum FilterOperator {
     NotIn,
     IsNull,
     IsNotNull,
+    /// `field:bbox:minLat,minLng,maxLat,maxLng` on a geopoint field
+    BoundingBox,
+    /// `field:near:lat,lng,radius_km` on a geopoint field
+    Near,
+    /// `field.path:contains:value` where the json value is an array
+    Contains,
+}
+
+impl FilterOperator {
+    /// Operator as written in filter expressions.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilterOperator::Eq => "eq",
+            FilterOperator::Ne => "ne",
+            FilterOperator::Gt => "gt",
+            FilterOperator::Gte => "gte",
+            FilterOperator::Lt => "lt",
+            FilterOperator::Lte => "lte",
+            FilterOperator::Like => "like",
+            FilterOperator::NotLike => "notlike",
+            FilterOperator::In => "in",
+            FilterOperator::NotIn => "notin",
+            FilterOperator::IsNull => "isnull",
+            FilterOperator::IsNotNull => "isnotnull",
+            FilterOperator::BoundingBox => "bbox",
+            FilterOperator::Near => "near",
+            FilterOperator::Contains => "contains",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,6 +99,7 @@ pub enum FilterValue {
     Number(f64),
     Boolean(bool),
     Array(Vec<String>),
+    Coordinates(Vec<f64>),
     Null,
 }
 
@@ -69,6 +117,8 @@ impl QueryEngine {
             search,
             limit,
             offset,
+            manual_order: false,
+            workflow: false,
         };
 
         if let Some(sort_str) = sort {
@@ -82,6 +132,69 @@ impl QueryEngine {
         Ok(query_engine)
     }
 
+    /// The filters as parsed, written back as `field:operator:value`
+    /// expressions with lowercase operators and trimmed list items.
+    pub fn canonical_filter(&self) -> Option<String> {
+        if self.filters.is_empty() {
+            return None;
+        }
+
+        let expressions: Vec<String> = self
+            .filters
+            .iter()
+            .map(|filter| {
+                let value = match &filter.value {
+                    FilterValue::Null => {
+                        return format!("{}:{}", filter.field, filter.operator.as_str());
+                    }
+                    FilterValue::String(value) => value.clone(),
+                    FilterValue::Number(n) => n.to_string(),
+                    FilterValue::Boolean(b) => b.to_string(),
+                    FilterValue::Array(values) => values.join(","),
+                    FilterValue::Coordinates(coordinates) => coordinates
+                        .iter()
+                        .map(f64::to_string)
+                        .collect::<Vec<_>>()
+                        .join(","),
+                };
+                format!("{}:{}:{}", filter.field, filter.operator.as_str(), value)
+            })
+            .collect();
+        Some(expressions.join(","))
+    }
+
+    /// The sort fields as parsed, `-` marking descending ones, or the default
+    /// order `build_order_by_clause` falls back to without any.
+    pub fn canonical_sort(&self) -> String {
+        if self.sort.is_empty() {
+            return if self.manual_order {
+                "sort_order,id".to_string()
+            } else {
+                "-created_at".to_string()
+            };
+        }
+
+        let fields: Vec<String> = self
+            .sort
+            .iter()
+            .map(|sort_field| match sort_field.direction {
+                SortDirection::Asc => sort_field.field.clone(),
+                SortDirection::Desc => format!("-{}", sort_field.field),
+            })
+            .collect();
+        fields.join(",")
+    }
+
+    pub fn with_manual_order(mut self, manual_order: bool) -> Self {
+        self.manual_order = manual_order;
+        self
+    }
+
+    pub fn with_workflow(mut self, workflow: bool) -> Self {
+        self.workflow = workflow;
+        self
+    }
+
     fn parse_sort(sort_str: &str) -> Result<Vec<SortField>, LunarbaseError> {
         let mut sort_fields = Vec::new();
 
@@ -116,7 +229,7 @@ impl QueryEngine {
     fn parse_filters(filter_str: &str) -> Result<Vec<FilterCondition>, LunarbaseError> {
         let mut filters = Vec::new();
 
-        for filter_part in filter_str.split(',') {
+        for filter_part in Self::split_filter_expressions(filter_str) {
             let filter_part = filter_part.trim();
             if filter_part.is_empty() {
                 continue;
@@ -153,12 +266,38 @@ impl QueryEngine {
                 field: field.to_string(),
                 operator,
                 value,
+                raw_value: value_str,
             });
         }
 
         Ok(filters)
     }
 
+    /// Splits on commas, except inside the values of list operators
+    /// (`in`, `notin`, `bbox`, `near`), whose items are comma separated too.
+    fn split_filter_expressions(filter_str: &str) -> Vec<String> {
+        let mut expressions: Vec<String> = Vec::new();
+
+        for part in filter_str.split(',') {
+            if let Some(previous) = expressions.last_mut() {
+                let takes_list = previous.split(':').nth(1).is_some_and(|op| {
+                    matches!(
+                        op.trim().to_lowercase().as_str(),
+                        "in" | "notin" | "bbox" | "near"
+                    )
+                });
+                if takes_list && !part.contains(':') {
+                    previous.push(',');
+                    previous.push_str(part);
+                    continue;
+                }
+            }
+            expressions.push(part.to_string());
+        }
+
+        expressions
+    }
+
     fn parse_operator(op_str: &str) -> Result<FilterOperator, LunarbaseError> {
         match op_str.to_lowercase().as_str() {
             "eq" => Ok(FilterOperator::Eq),
@@ -173,6 +312,9 @@ impl QueryEngine {
             "notin" => Ok(FilterOperator::NotIn),
             "isnull" => Ok(FilterOperator::IsNull),
             "isnotnull" => Ok(FilterOperator::IsNotNull),
+            "bbox" => Ok(FilterOperator::BoundingBox),
+            "near" => Ok(FilterOperator::Near),
+            "contains" => Ok(FilterOperator::Contains),
             _ => Err(LunarbaseError::ValidationError(vec![format!(
                 "Unsupported filter operator: {}",
                 op_str
@@ -194,6 +336,33 @@ impl QueryEngine {
                     .collect();
                 Ok(FilterValue::Array(values))
             }
+            FilterOperator::BoundingBox => {
+                let coordinates = Self::parse_coordinates(value_str, 4, "bbox")?;
+                let (min_lat, min_lng, max_lat, max_lng) = (
+                    coordinates[0],
+                    coordinates[1],
+                    coordinates[2],
+                    coordinates[3],
+                );
+                Self::validate_coordinate(min_lat, min_lng)?;
+                Self::validate_coordinate(max_lat, max_lng)?;
+                if min_lat > max_lat {
+                    return Err(LunarbaseError::ValidationError(vec![
+                        "bbox minLat must not be greater than maxLat".to_string(),
+                    ]));
+                }
+                Ok(FilterValue::Coordinates(coordinates))
+            }
+            FilterOperator::Near => {
+                let coordinates = Self::parse_coordinates(value_str, 3, "near")?;
+                Self::validate_coordinate(coordinates[0], coordinates[1])?;
+                if coordinates[2] <= 0.0 {
+                    return Err(LunarbaseError::ValidationError(vec![
+                        "near radius_km must be greater than 0".to_string(),
+                    ]));
+                }
+                Ok(FilterValue::Coordinates(coordinates))
+            }
             _ => {
                 if value_str.is_empty() {
                     return Ok(FilterValue::Null);
@@ -212,6 +381,43 @@ impl QueryEngine {
         }
     }
 
+    fn parse_coordinates(
+        value_str: &str,
+        count: usize,
+        operator: &str,
+    ) -> Result<Vec<f64>, LunarbaseError> {
+        let format = if operator == "bbox" {
+            "minLat,minLng,maxLat,maxLng"
+        } else {
+            "lat,lng,radius_km"
+        };
+        let invalid = || {
+            LunarbaseError::ValidationError(vec![format!("{} filter expects {}", operator, format)])
+        };
+
+        let coordinates = value_str
+            .split(',')
+            .map(|part| part.trim().parse::<f64>().ok().filter(|n| n.is_finite()))
+            .collect::<Option<Vec<f64>>>()
+            .ok_or_else(invalid)?;
+
+        if coordinates.len() != count {
+            return Err(invalid());
+        }
+
+        Ok(coordinates)
+    }
+
+    fn validate_coordinate(lat: f64, lng: f64) -> Result<(), LunarbaseError> {
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+            return Err(LunarbaseError::ValidationError(vec![format!(
+                "Invalid coordinate {},{}: latitude must be between -90 and 90 and longitude between -180 and 180",
+                lat, lng
+            )]));
+        }
+        Ok(())
+    }
+
     fn is_valid_field_name(field: &str) -> bool {
         !field.is_empty()
             && field.len() <= 100
@@ -225,18 +431,26 @@ impl QueryEngine {
         schema: &CollectionSchema,
     ) -> Result<String, LunarbaseError> {
         if self.sort.is_empty() {
+            if self.manual_order {
+                return Ok("ORDER BY \"sort_order\" ASC, \"id\" ASC".to_string());
+            }
             return Ok("ORDER BY \"created_at\" DESC".to_string());
         }
 
         let mut order_parts = Vec::new();
+        let mut sorted_by_id = false;
 
-        for sort_field in &self.sort {
-            if !self.is_valid_sort_field(&sort_field.field, schema) {
+        for (index, sort_field) in self.sort.iter().enumerate() {
+            if self.sort[..index]
+                .iter()
+                .any(|earlier| earlier.field == sort_field.field)
+            {
                 return Err(LunarbaseError::ValidationError(vec![format!(
-                    "Field '{}' does not exist or cannot be sorted",
+                    "Field '{}' appears more than once in sort",
                     sort_field.field
                 )]));
             }
+            let nullable = self.sort_field_nullable(&sort_field.field, schema)?;
 
             let direction = match sort_field.direction {
                 SortDirection::Asc => "ASC",
@@ -244,7 +458,20 @@ impl QueryEngine {
             };
 
             let escaped_field = self.escape_field_name(&sort_field.field);
+            // SQLite puts NULLs first in ascending order and has no NULLS LAST
+            if nullable {
+                order_parts.push(format!(
+                    "CASE WHEN {} IS NULL THEN 1 ELSE 0 END",
+                    escaped_field
+                ));
+            }
             order_parts.push(format!("{} {}", escaped_field, direction));
+            sorted_by_id |= sort_field.field == "id";
+        }
+
+        // Rows with equal sort keys keep a stable order across pages
+        if !sorted_by_id {
+            order_parts.push("\"id\" ASC".to_string());
         }
 
         Ok(format!("ORDER BY {}", order_parts.join(", ")))
@@ -262,29 +489,11 @@ impl QueryEngine {
                 let mut search_conditions = Vec::new();
                 let search_pattern = format!("%{}%", search_term.trim());
 
-                search_conditions.push("\"title\" LIKE ?".to_string());
-                parameters.push(search_pattern.clone());
-
-                if schema.fields.iter().any(|f| f.name == "content") {
-                    search_conditions.push("\"content\" LIKE ?".to_string());
+                for column in self.search_columns(schema) {
+                    search_conditions.push(format!("{} LIKE ?", column));
                     parameters.push(search_pattern.clone());
                 }
 
-                for field in &schema.fields {
-                    if field.name != "title" && field.name != "content" {
-                        match field.field_type {
-                            crate::models::FieldType::Text
-                            | crate::models::FieldType::Email
-                            | crate::models::FieldType::Url => {
-                                let escaped_field = self.escape_field_name(&field.name);
-                                search_conditions.push(format!("{} LIKE ?", escaped_field));
-                                parameters.push(search_pattern.clone());
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-
                 if !search_conditions.is_empty() {
                     where_parts.push(format!("({})", search_conditions.join(" OR ")));
                 }
@@ -292,14 +501,53 @@ impl QueryEngine {
         }
 
         for filter in &self.filters {
-            if !self.is_valid_filter_field(&filter.field, schema) {
+            let json_target = self.json_filter_target(filter, schema)?;
+            if json_target.is_none() && !self.is_valid_filter_field(&filter.field, schema) {
                 return Err(LunarbaseError::ValidationError(vec![format!(
                     "Field '{}' does not exist or cannot be filtered",
                     filter.field
                 )]));
             }
 
-            let (condition_sql, mut condition_params) = self.build_filter_condition(filter)?;
+            let is_geo_field = schema
+                .fields
+                .iter()
+                .any(|f| f.name == filter.field && f.field_type == FieldType::GeoPoint);
+            let is_geo_operator = matches!(
+                filter.operator,
+                FilterOperator::BoundingBox | FilterOperator::Near
+            );
+            if is_geo_field && !is_geo_operator {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Field '{}' is a geopoint and only supports the bbox and near operators",
+                    filter.field
+                )]));
+            }
+            if is_geo_operator && !is_geo_field {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Field '{}' is not a geopoint; bbox and near require a geopoint field",
+                    filter.field
+                )]));
+            }
+
+            let is_integer_field = schema.fields.iter().any(|f| {
+                f.name == filter.field && f.number_format() == Some(NumberFormat::Integer)
+            });
+            if is_integer_field {
+                self.ensure_whole_number_filter(filter)?;
+            }
+
+            let decimal_field = schema
+                .fields
+                .iter()
+                .find(|f| f.name == filter.field && f.field_type == FieldType::Decimal);
+            let (condition_sql, mut condition_params) = match (json_target, decimal_field) {
+                (Some((field, path)), _) => self.build_json_condition(filter, field, path)?,
+                (None, Some(field)) => {
+                    self.build_filter_condition(&self.decimal_filter(filter, field)?)?
+                }
+                (None, None) => self.build_filter_condition(filter)?,
+            };
             where_parts.push(condition_sql);
             parameters.append(&mut condition_params);
         }
@@ -316,33 +564,60 @@ impl QueryEngine {
         &self,
         filter: &FilterCondition,
     ) -> Result<(String, Vec<String>), LunarbaseError> {
-        let escaped_field = self.escape_field_name(&filter.field);
+        self.build_condition(filter, &self.escape_field_name(&filter.field), |_| "?")
+    }
 
+    /// Condition comparing `column`, an escaped column or expression, against
+    /// the filter value. `placeholder` gives the SQL each bound value goes in.
+    fn build_condition(
+        &self,
+        filter: &FilterCondition,
+        escaped_field: &str,
+        placeholder: fn(&str) -> &'static str,
+    ) -> Result<(String, Vec<String>), LunarbaseError> {
         match &filter.operator {
-            FilterOperator::Eq => Ok((
-                format!("{} = ?", escaped_field),
-                vec![self.filter_value_to_string(&filter.value)],
-            )),
-            FilterOperator::Ne => Ok((
-                format!("{} != ?", escaped_field),
-                vec![self.filter_value_to_string(&filter.value)],
-            )),
-            FilterOperator::Gt => Ok((
-                format!("{} > ?", escaped_field),
-                vec![self.filter_value_to_string(&filter.value)],
-            )),
-            FilterOperator::Gte => Ok((
-                format!("{} >= ?", escaped_field),
-                vec![self.filter_value_to_string(&filter.value)],
-            )),
-            FilterOperator::Lt => Ok((
-                format!("{} < ?", escaped_field),
-                vec![self.filter_value_to_string(&filter.value)],
-            )),
-            FilterOperator::Lte => Ok((
-                format!("{} <= ?", escaped_field),
-                vec![self.filter_value_to_string(&filter.value)],
-            )),
+            FilterOperator::Eq => {
+                let value = self.filter_value_to_string(&filter.value);
+                Ok((
+                    format!("{} = {}", escaped_field, placeholder(&value)),
+                    vec![value],
+                ))
+            }
+            FilterOperator::Ne => {
+                let value = self.filter_value_to_string(&filter.value);
+                Ok((
+                    format!("{} != {}", escaped_field, placeholder(&value)),
+                    vec![value],
+                ))
+            }
+            FilterOperator::Gt => {
+                let value = self.filter_value_to_string(&filter.value);
+                Ok((
+                    format!("{} > {}", escaped_field, placeholder(&value)),
+                    vec![value],
+                ))
+            }
+            FilterOperator::Gte => {
+                let value = self.filter_value_to_string(&filter.value);
+                Ok((
+                    format!("{} >= {}", escaped_field, placeholder(&value)),
+                    vec![value],
+                ))
+            }
+            FilterOperator::Lt => {
+                let value = self.filter_value_to_string(&filter.value);
+                Ok((
+                    format!("{} < {}", escaped_field, placeholder(&value)),
+                    vec![value],
+                ))
+            }
+            FilterOperator::Lte => {
+                let value = self.filter_value_to_string(&filter.value);
+                Ok((
+                    format!("{} <= {}", escaped_field, placeholder(&value)),
+                    vec![value],
+                ))
+            }
             FilterOperator::Like => {
                 let value_str = self.filter_value_to_string(&filter.value);
                 let like_value = if value_str.starts_with('%') || value_str.ends_with('%') {
@@ -365,7 +640,11 @@ impl QueryEngine {
             FilterOperator::IsNotNull => Ok((format!("{} IS NOT NULL", escaped_field), vec![])),
             FilterOperator::In => {
                 if let FilterValue::Array(values) = &filter.value {
-                    let placeholders = vec!["?"; values.len()].join(", ");
+                    let placeholders = values
+                        .iter()
+                        .map(|v| placeholder(v))
+                        .collect::<Vec<_>>()
+                        .join(", ");
                     let params = values.iter().map(|v| v.clone()).collect();
                     Ok((format!("{} IN ({})", escaped_field, placeholders), params))
                 } else {
@@ -376,7 +655,11 @@ impl QueryEngine {
             }
             FilterOperator::NotIn => {
                 if let FilterValue::Array(values) = &filter.value {
-                    let placeholders = vec!["?"; values.len()].join(", ");
+                    let placeholders = values
+                        .iter()
+                        .map(|v| placeholder(v))
+                        .collect::<Vec<_>>()
+                        .join(", ");
                     let params = values.iter().map(|v| v.clone()).collect();
                     Ok((
                         format!("{} NOT IN ({})", escaped_field, placeholders),
@@ -388,6 +671,204 @@ impl QueryEngine {
                     ]))
                 }
             }
+            FilterOperator::BoundingBox => {
+                let (lat_column, lng_column) = geo_point_columns(&filter.field);
+                let (lat_column, lng_column) = (
+                    self.escape_field_name(&lat_column),
+                    self.escape_field_name(&lng_column),
+                );
+                let [min_lat, min_lng, max_lat, max_lng] = self.coordinates(filter, "bbox")?;
+
+                // A box whose west edge is east of its east edge crosses the antimeridian
+                let lng_condition = if min_lng <= max_lng {
+                    format!("{} BETWEEN ? AND ?", lng_column)
+                } else {
+                    format!("({0} >= ? OR {0} <= ?)", lng_column)
+                };
+                Ok((
+                    format!("({} BETWEEN ? AND ? AND {})", lat_column, lng_condition),
+                    [min_lat, max_lat, min_lng, max_lng]
+                        .iter()
+                        .map(f64::to_string)
+                        .collect(),
+                ))
+            }
+            FilterOperator::Contains => Err(LunarbaseError::ValidationError(vec![format!(
+                "Field '{}' is not a json field; contains requires a json field or path",
+                filter.field
+            )])),
+            FilterOperator::Near => {
+                let (lat_column, lng_column) = geo_point_columns(&filter.field);
+                let [lat, lng, radius_km] = self.coordinates(filter, "near")?;
+
+                // `haversine_km` is registered on every pooled connection
+                Ok((
+                    format!(
+                        "haversine_km({}, {}, CAST(? AS REAL), CAST(? AS REAL)) <= CAST(? AS REAL)",
+                        self.escape_field_name(&lat_column),
+                        self.escape_field_name(&lng_column)
+                    ),
+                    vec![lat.to_string(), lng.to_string(), radius_km.to_string()],
+                ))
+            }
+        }
+    }
+
+    /// The json field and path inside it that `filter` applies to: `None` for
+    /// filters on plain columns, an error when the root is not a json field
+    /// or the path is malformed.
+    fn json_filter_target<'a>(
+        &self,
+        filter: &'a FilterCondition,
+        schema: &'a CollectionSchema,
+    ) -> Result<Option<(&'a FieldDefinition, Option<&'a str>)>, LunarbaseError> {
+        let (root, path) = match filter.field.split_once('.') {
+            Some((root, path)) => (root, Some(path)),
+            None if matches!(filter.operator, FilterOperator::Contains) => {
+                (filter.field.as_str(), None)
+            }
+            None => return Ok(None),
+        };
+
+        let field = schema
+            .fields
+            .iter()
+            .find(|f| f.name == root && f.field_type == FieldType::Json)
+            .ok_or_else(|| {
+                LunarbaseError::ValidationError(vec![format!(
+                    "Field '{}' is not a json field; '{}' needs a json field to look into",
+                    root, filter.field
+                )])
+            })?;
+        if let Some(path) = path
+            && !is_valid_json_path(path)
+        {
+            return Err(LunarbaseError::ValidationError(vec![format!(
+                "Invalid json path '{}': use dot-separated keys of letters, numbers and underscores",
+                filter.field
+            )]));
+        }
+
+        Ok(Some((field, path)))
+    }
+
+    /// Condition on a value inside a `json` field, read from the generated
+    /// column when the path is indexed and with `json_extract` otherwise.
+    fn build_json_condition(
+        &self,
+        filter: &FilterCondition,
+        field: &FieldDefinition,
+        path: Option<&str>,
+    ) -> Result<(String, Vec<String>), LunarbaseError> {
+        let json_path = path.map_or_else(|| "$".to_string(), sqlite_json_path);
+        let escaped_field = self.escape_field_name(&field.name);
+
+        if matches!(filter.operator, FilterOperator::Contains) {
+            if matches!(filter.value, FilterValue::Null) {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "contains filter on '{}' requires a value",
+                    filter.field
+                )]));
+            }
+            let value = self.filter_value_to_string(&filter.value);
+            return Ok((
+                format!(
+                    "EXISTS (SELECT 1 FROM json_each({}, '{}') WHERE value = {})",
+                    escaped_field,
+                    json_path,
+                    json_placeholder(&value)
+                ),
+                vec![value],
+            ));
+        }
+
+        let column = match path {
+            Some(path) if field.indexed_paths.iter().flatten().any(|p| p == path) => {
+                self.escape_field_name(&json_path_column(&field.name, path))
+            }
+            _ => format!("json_extract({}, '{}')", escaped_field, json_path),
+        };
+        self.build_condition(filter, &column, json_placeholder)
+    }
+
+    /// Rewrites the values of a filter on a decimal field as minor units, so they
+    /// compare numerically against the stored integers.
+    fn decimal_filter(
+        &self,
+        filter: &FilterCondition,
+        field: &FieldDefinition,
+    ) -> Result<FilterCondition, LunarbaseError> {
+        let (precision, scale) = field.decimal_precision_and_scale();
+        let to_minor_units = |text: &str| {
+            parse_minor_units(text.trim(), precision, scale)
+                .map(|minor_units| minor_units.to_string())
+                .map_err(|e| {
+                    LunarbaseError::ValidationError(vec![format!(
+                        "Invalid filter value for decimal field '{}': {}",
+                        filter.field, e
+                    )])
+                })
+        };
+
+        let value = match (&filter.operator, &filter.value) {
+            (FilterOperator::IsNull | FilterOperator::IsNotNull, _) => FilterValue::Null,
+            (FilterOperator::Like | FilterOperator::NotLike, _) => {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Field '{}' is a decimal and does not support like filters",
+                    filter.field
+                )]));
+            }
+            (_, FilterValue::Array(values)) => FilterValue::Array(
+                values
+                    .iter()
+                    .map(|value| to_minor_units(value))
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => FilterValue::String(to_minor_units(&filter.raw_value)?),
+        };
+
+        Ok(FilterCondition {
+            value,
+            ..filter.clone()
+        })
+    }
+
+    /// Integer fields never hold fractions, so filtering them by one is a
+    /// mistake in the query rather than a filter that matches nothing.
+    fn ensure_whole_number_filter(&self, filter: &FilterCondition) -> Result<(), LunarbaseError> {
+        let fractional = match &filter.value {
+            FilterValue::Number(n) => n.fract() != 0.0,
+            FilterValue::Array(values) => values
+                .iter()
+                .filter_map(|value| value.trim().parse::<f64>().ok())
+                .any(|n| n.fract() != 0.0),
+            _ => false,
+        };
+        if fractional {
+            return Err(LunarbaseError::ValidationError(vec![format!(
+                "Field '{}' is an integer field and can only be filtered by whole numbers",
+                filter.field
+            )]));
+        }
+        Ok(())
+    }
+
+    fn coordinates<const N: usize>(
+        &self,
+        filter: &FilterCondition,
+        operator: &str,
+    ) -> Result<[f64; N], LunarbaseError> {
+        match &filter.value {
+            FilterValue::Coordinates(values) => values.as_slice().try_into().map_err(|_| {
+                LunarbaseError::ValidationError(vec![format!(
+                    "{} operator requires {} coordinates",
+                    operator, N
+                )])
+            }),
+            _ => Err(LunarbaseError::ValidationError(vec![format!(
+                "{} operator requires coordinates",
+                operator
+            )])),
         }
     }
 
@@ -403,7 +884,7 @@ impl QueryEngine {
                 }
             }
             FilterValue::Null => "NULL".to_string(),
-            FilterValue::Array(_) => "".to_string(),
+            FilterValue::Array(_) | FilterValue::Coordinates(_) => "".to_string(),
         }
     }
 
@@ -411,16 +892,107 @@ impl QueryEngine {
         format!("\"{}\"", field.replace("\"", "\"\""))
     }
 
-    fn is_valid_sort_field(&self, field: &str, schema: &CollectionSchema) -> bool {
+    /// Escaped columns compared against the search term: `title`, `content` when
+    /// the schema has it, then every other text, email and url field.
+    fn search_columns(&self, schema: &CollectionSchema) -> Vec<String> {
+        let mut columns = vec!["\"title\"".to_string()];
+
+        if schema.fields.iter().any(|f| f.name == "content") {
+            columns.push("\"content\"".to_string());
+        }
+
+        for field in &schema.fields {
+            if field.name != "title"
+                && field.name != "content"
+                && matches!(
+                    field.field_type,
+                    FieldType::Text | FieldType::Email | FieldType::Url
+                )
+            {
+                columns.push(self.escape_field_name(&field.name));
+            }
+        }
+
+        columns
+    }
+
+    /// Estimated cost of running this query with `expansions` expanded
+    /// relations, compared against the `max_query_complexity` setting.
+    pub fn complexity(&self, schema: &CollectionSchema, expansions: usize) -> u32 {
+        let filters: u32 = self
+            .filters
+            .iter()
+            .map(|filter| match (&filter.operator, &filter.value) {
+                (FilterOperator::Like | FilterOperator::NotLike | FilterOperator::Contains, _) => {
+                    LIKE_COST
+                }
+                (FilterOperator::In | FilterOperator::NotIn, FilterValue::Array(items)) => {
+                    items.len().max(1) as u32 * PREDICATE_COST
+                }
+                _ => PREDICATE_COST,
+            })
+            .sum();
+
+        let search = match self.search.as_deref().map(str::trim) {
+            Some(term) if !term.is_empty() => self.search_columns(schema).len() as u32 * LIKE_COST,
+            _ => 0,
+        };
+
+        filters + search + expansions as u32 * EXPANSION_COST
+    }
+
+    /// Field names used by the sort and filter expressions, in that order.
+    pub fn referenced_fields(&self) -> Vec<&str> {
+        self.sort
+            .iter()
+            .map(|sort_field| sort_field.field.as_str())
+            .chain(self.filters.iter().map(|filter| {
+                // `metadata.source` filters inside the `metadata` field
+                filter.field.split('.').next().unwrap_or(&filter.field)
+            }))
+            .collect()
+    }
+
+    /// Whether sorting by `field` has to account for NULLs, or a validation
+    /// error when records cannot be sorted by it.
+    fn sort_field_nullable(
+        &self,
+        field: &str,
+        schema: &CollectionSchema,
+    ) -> Result<bool, LunarbaseError> {
         if matches!(field, "id" | "created_at" | "updated_at") {
-            return true;
+            return Ok(false);
+        }
+        if (self.manual_order && field == "sort_order")
+            || (self.workflow && matches!(field, "status" | "publish_at"))
+        {
+            return Ok(true);
         }
 
-        schema.fields.iter().any(|f| f.name == field)
+        let unsortable = match schema.fields.iter().find(|f| f.name == field) {
+            Some(f) => match f.field_type {
+                FieldType::Json => "json",
+                FieldType::RichText => "richtext",
+                FieldType::GeoPoint => "geopoint",
+                _ => return Ok(true),
+            },
+            None => {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Field '{}' does not exist or cannot be sorted",
+                    field
+                )]));
+            }
+        };
+        Err(LunarbaseError::ValidationError(vec![format!(
+            "Field '{}' cannot be sorted: {} fields have no meaningful order",
+            field, unsortable
+        )]))
     }
 
     fn is_valid_filter_field(&self, field: &str, schema: &CollectionSchema) -> bool {
-        if matches!(field, "id" | "created_at" | "updated_at") {
+        if matches!(field, "id" | "owner_id" | "created_at" | "updated_at")
+            || (self.workflow && matches!(field, "status" | "publish_at"))
+        {
             return true;
         }
 
@@ -457,12 +1029,41 @@ impl QueryEngine {
 
         Ok((sql, parameters))
     }
+
+    pub fn build_count_query(
+        &self,
+        table_name: &str,
+        schema: &CollectionSchema,
+    ) -> Result<(String, Vec<String>), LunarbaseError> {
+        let escaped_table_name = self.escape_field_name(table_name);
+        let mut sql = format!("SELECT COUNT(*) AS count FROM {}", escaped_table_name);
+        let mut parameters = Vec::new();
+
+        let (where_clause, where_params) = self.build_where_clause(schema)?;
+        if !where_clause.is_empty() {
+            sql.push(' ');
+            sql.push_str(&where_clause);
+            parameters.extend(where_params);
+        }
+
+        Ok((sql, parameters))
+    }
+}
+
+/// `json_extract` keeps JSON numbers and booleans numeric, so numeric filter
+/// values are compared as numbers rather than text.
+fn json_placeholder(value: &str) -> &'static str {
+    if value.parse::<f64>().is_ok() {
+        "CAST(? AS REAL)"
+    } else {
+        "?"
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{CollectionSchema, FieldDefinition, FieldType};
+    use crate::models::{CollectionSchema, FieldDefinition, FieldType, ValidationRules};
 
     fn create_test_schema() -> CollectionSchema {
         CollectionSchema {
@@ -473,6 +1074,10 @@ mod tests {
                     required: true,
                     default_value: None,
                     validation: None,
+                    relation_target: None,
+                    description: None,
+                    example: None,
+                    indexed_paths: None,
                 },
                 FieldDefinition {
                     name: "age".to_string(),
@@ -480,6 +1085,10 @@ mod tests {
                     required: false,
                     default_value: None,
                     validation: None,
+                    relation_target: None,
+                    description: None,
+                    example: None,
+                    indexed_paths: None,
                 },
                 FieldDefinition {
                     name: "active".to_string(),
@@ -487,6 +1096,10 @@ mod tests {
                     required: false,
                     default_value: None,
                     validation: None,
+                    relation_target: None,
+                    description: None,
+                    example: None,
+                    indexed_paths: None,
                 },
             ],
         }
@@ -560,4 +1173,400 @@ mod tests {
         assert!(sql.contains("OFFSET 5"));
         assert_eq!(params.len(), 2);
     }
+
+    #[test]
+    fn test_manual_order_defaults_to_sort_order() {
+        let schema = create_test_schema();
+
+        let query_engine = QueryEngine::new(None, None, None, None, None)
+            .unwrap()
+            .with_manual_order(true);
+        assert_eq!(
+            query_engine.build_order_by_clause(&schema).unwrap(),
+            "ORDER BY \"sort_order\" ASC, \"id\" ASC"
+        );
+
+        let explicit =
+            QueryEngine::new(Some("-sort_order".to_string()), None, None, None, None).unwrap();
+        assert!(explicit.build_order_by_clause(&schema).is_err());
+        assert!(
+            explicit
+                .with_manual_order(true)
+                .build_order_by_clause(&schema)
+                .unwrap()
+                .contains("DESC")
+        );
+    }
+
+    #[test]
+    fn test_multi_field_sort_puts_nulls_last() {
+        let mut schema = create_test_schema();
+        for (name, field_type) in [("metadata", FieldType::Json), ("body", FieldType::RichText)] {
+            schema.fields.push(FieldDefinition {
+                name: name.to_string(),
+                field_type,
+                required: false,
+                default_value: None,
+                validation: None,
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
+            });
+        }
+
+        let query_engine = QueryEngine::new(
+            Some("-age,name,created_at".to_string()),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            query_engine.build_order_by_clause(&schema).unwrap(),
+            "ORDER BY CASE WHEN \"age\" IS NULL THEN 1 ELSE 0 END, \"age\" DESC, \
+             CASE WHEN \"name\" IS NULL THEN 1 ELSE 0 END, \"name\" ASC, \
+             \"created_at\" ASC, \"id\" ASC"
+        );
+
+        let by_id = QueryEngine::new(Some("-id".to_string()), None, None, None, None).unwrap();
+        assert_eq!(
+            by_id.build_order_by_clause(&schema).unwrap(),
+            "ORDER BY \"id\" DESC"
+        );
+
+        for invalid in ["metadata", "-body", "age,-age", "missing"] {
+            let query_engine =
+                QueryEngine::new(Some(invalid.to_string()), None, None, None, None).unwrap();
+            assert!(
+                query_engine.build_order_by_clause(&schema).is_err(),
+                "sorting by {} should be rejected",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_workflow_columns_are_filterable() {
+        let schema = create_test_schema();
+
+        let query_engine = QueryEngine::new(
+            Some("publish_at".to_string()),
+            Some("status:eq:published".to_string()),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(query_engine.build_where_clause(&schema).is_err());
+        assert!(query_engine.build_order_by_clause(&schema).is_err());
+
+        let query_engine = query_engine.with_workflow(true);
+        let (where_clause, params) = query_engine.build_where_clause(&schema).unwrap();
+        assert_eq!(where_clause, "WHERE \"status\" = ?");
+        assert_eq!(params, vec!["published".to_string()]);
+        assert!(
+            query_engine
+                .build_order_by_clause(&schema)
+                .unwrap()
+                .contains("\"publish_at\" ASC")
+        );
+    }
+
+    #[test]
+    fn test_geo_filters() {
+        let mut schema = create_test_schema();
+        schema.fields.push(FieldDefinition {
+            name: "location".to_string(),
+            field_type: FieldType::GeoPoint,
+            required: false,
+            default_value: None,
+            validation: None,
+            relation_target: None,
+            description: None,
+            example: None,
+            indexed_paths: None,
+        });
+
+        let query_engine = QueryEngine::new(
+            None,
+            Some("location:bbox:-20,170,-10,-170,name:in:a,b".to_string()),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(query_engine.filters.len(), 2);
+        let (where_clause, params) = query_engine.build_where_clause(&schema).unwrap();
+        assert!(where_clause.contains("(\"location_lng\" >= ? OR \"location_lng\" <= ?)"));
+        assert_eq!(params, vec!["-20", "-10", "170", "-170", "a", "b"]);
+
+        let near = QueryEngine::new(
+            None,
+            Some("location:near:52.2,21,5".to_string()),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let (where_clause, params) = near.build_where_clause(&schema).unwrap();
+        assert!(where_clause.contains("haversine_km(\"location_lat\", \"location_lng\""));
+        assert_eq!(params, vec!["52.2", "21", "5"]);
+
+        for invalid in [
+            "location:bbox:1,2,3",
+            "location:bbox:10,0,5,1",
+            "location:near:91,0,5",
+            "location:near:0,0,0",
+        ] {
+            assert!(QueryEngine::new(None, Some(invalid.to_string()), None, None, None).is_err());
+        }
+
+        for mismatched in ["location:eq:1", "age:near:0,0,5"] {
+            let query_engine =
+                QueryEngine::new(None, Some(mismatched.to_string()), None, None, None).unwrap();
+            assert!(query_engine.build_where_clause(&schema).is_err());
+        }
+
+        let sorted =
+            QueryEngine::new(Some("location".to_string()), None, None, None, None).unwrap();
+        assert!(sorted.build_order_by_clause(&schema).is_err());
+    }
+
+    #[test]
+    fn test_decimal_filters_use_minor_units() {
+        let mut schema = create_test_schema();
+        schema.fields.push(FieldDefinition {
+            name: "price".to_string(),
+            field_type: FieldType::Decimal,
+            required: false,
+            default_value: None,
+            validation: None,
+            relation_target: None,
+            description: None,
+            example: None,
+            indexed_paths: None,
+        });
+
+        let query_engine = QueryEngine::new(
+            None,
+            Some("price:gt:9.5,price:in:0.1,19.99".to_string()),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let (where_clause, params) = query_engine.build_where_clause(&schema).unwrap();
+        assert!(where_clause.contains("\"price\" > ?"));
+        assert_eq!(params, vec!["950", "10", "1999"]);
+
+        for invalid in ["price:eq:1.999", "price:like:19"] {
+            let query_engine =
+                QueryEngine::new(None, Some(invalid.to_string()), None, None, None).unwrap();
+            assert!(query_engine.build_where_clause(&schema).is_err());
+        }
+    }
+
+    #[test]
+    fn test_integer_fields_reject_fractional_filters() {
+        let mut schema = create_test_schema();
+        schema.fields.push(FieldDefinition {
+            name: "quantity".to_string(),
+            field_type: FieldType::Number,
+            required: false,
+            default_value: None,
+            validation: Some(ValidationRules {
+                min_length: None,
+                max_length: None,
+                min_value: None,
+                max_value: None,
+                pattern: None,
+                enum_values: None,
+                precision: None,
+                scale: None,
+                source_field: None,
+                number_format: Some(NumberFormat::Integer),
+            }),
+            relation_target: None,
+            description: None,
+            example: None,
+            indexed_paths: None,
+        });
+
+        for valid in ["quantity:eq:10", "quantity:in:1,2", "age:eq:10.5"] {
+            let query_engine =
+                QueryEngine::new(None, Some(valid.to_string()), None, None, None).unwrap();
+            assert!(
+                query_engine.build_where_clause(&schema).is_ok(),
+                "{}",
+                valid
+            );
+        }
+
+        for invalid in ["quantity:eq:10.5", "quantity:gte:0.5", "quantity:in:1,2.5"] {
+            let query_engine =
+                QueryEngine::new(None, Some(invalid.to_string()), None, None, None).unwrap();
+            assert!(
+                query_engine.build_where_clause(&schema).is_err(),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_canonical_filter_and_sort() {
+        let query_engine = QueryEngine::new(
+            Some(" name ,-age,".to_string()),
+            Some(
+                "age:GTE:18.0,name:in: a , b,active:isnull:ignored,location:near:1,2,5".to_string(),
+            ),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(query_engine.canonical_sort(), "name,-age");
+        assert_eq!(
+            query_engine.canonical_filter().as_deref(),
+            Some("age:gte:18,name:in:a,b,active:isnull,location:near:1,2,5")
+        );
+
+        let empty = QueryEngine::new(None, Some(" ".to_string()), None, None, None).unwrap();
+        assert_eq!(empty.canonical_filter(), None);
+        assert_eq!(empty.canonical_sort(), "-created_at");
+        assert_eq!(
+            empty.with_manual_order(true).canonical_sort(),
+            "sort_order,id"
+        );
+    }
+
+    #[test]
+    fn test_build_count_query() {
+        let query_engine = QueryEngine::new(
+            Some("name".to_string()),
+            Some("active:eq:true".to_string()),
+            None,
+            Some(10),
+            Some(5),
+        )
+        .unwrap();
+
+        let schema = create_test_schema();
+        let (sql, params) = query_engine
+            .build_count_query("records_test", &schema)
+            .unwrap();
+
+        assert!(sql.starts_with("SELECT COUNT(*) AS count FROM"));
+        assert!(sql.contains("WHERE"));
+        assert!(!sql.contains("ORDER BY"));
+        assert!(!sql.contains("LIMIT"));
+        assert_eq!(params.len(), 1);
+    }
+
+    #[test]
+    fn test_json_path_filters() {
+        let mut schema = create_test_schema();
+        schema.fields.push(FieldDefinition {
+            name: "metadata".to_string(),
+            field_type: FieldType::Json,
+            required: false,
+            default_value: None,
+            validation: None,
+            relation_target: None,
+            description: None,
+            example: None,
+            indexed_paths: Some(vec!["source".to_string()]),
+        });
+
+        let query_engine = QueryEngine::new(
+            None,
+            Some(
+                "metadata.source:eq:import,metadata.stats.0.score:gte:2.5,metadata.tags:contains:urgent"
+                    .to_string(),
+            ),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let (where_clause, params) = query_engine.build_where_clause(&schema).unwrap();
+        assert_eq!(
+            where_clause,
+            "WHERE \"metadata__source\" = ? \
+             AND json_extract(\"metadata\", '$.stats[0].score') >= CAST(? AS REAL) \
+             AND EXISTS (SELECT 1 FROM json_each(\"metadata\", '$.tags') WHERE value = ?)"
+        );
+        assert_eq!(params, vec!["import", "2.5", "urgent"]);
+        assert_eq!(
+            query_engine.referenced_fields(),
+            vec!["metadata", "metadata", "metadata"]
+        );
+
+        for invalid in [
+            "metadata..source:eq:import",
+            "metadata.source.:eq:import",
+            "metadata.sour-ce:eq:import",
+            "name.first:eq:John",
+            "missing.key:eq:1",
+            "name:contains:John",
+            "metadata.tags:contains:",
+        ] {
+            let rejected = QueryEngine::new(None, Some(invalid.to_string()), None, None, None)
+                .and_then(|query_engine| query_engine.build_where_clause(&schema));
+            assert!(rejected.is_err(), "{} should be rejected", invalid);
+        }
+    }
+
+    #[test]
+    fn test_referenced_fields() {
+        let query_engine = QueryEngine::new(
+            Some("-age,name".to_string()),
+            Some("active:eq:true".to_string()),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            query_engine.referenced_fields(),
+            vec!["age", "name", "active"]
+        );
+    }
+
+    #[test]
+    fn test_complexity() {
+        let schema = create_test_schema();
+
+        let simple = QueryEngine::new(
+            None,
+            Some("age:gt:18,active:eq:true".to_string()),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(simple.complexity(&schema, 0), 2 * PREDICATE_COST);
+        assert_eq!(
+            simple.complexity(&schema, 2),
+            2 * PREDICATE_COST + 2 * EXPANSION_COST
+        );
+
+        let heavy = QueryEngine::new(
+            None,
+            Some("name:like:a,name:notlike:b,age:in:1,2,3".to_string()),
+            Some("term".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+        // title and name are searched
+        assert_eq!(
+            heavy.complexity(&schema, 0),
+            2 * LIKE_COST + 3 * PREDICATE_COST + 2 * LIKE_COST
+        );
+    }
 }
@@ -12,6 +12,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    collection_activity (collection_id, day) {
+        collection_id -> Integer,
+        day -> Date,
+        created_count -> BigInt,
+        updated_count -> BigInt,
+        deleted_count -> BigInt,
+    }
+}
+
 diesel::table! {
     collection_permissions (id) {
         id -> Integer,
@@ -27,6 +37,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    collection_record_counts (collection_id) {
+        collection_id -> Integer,
+        record_count -> BigInt,
+        reconciled_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     collection_records (id) {
         id -> Integer,
@@ -37,6 +56,63 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    collection_retention_runs (id) {
+        id -> Integer,
+        collection_id -> Integer,
+        action -> Text,
+        cutoff -> Text,
+        processed -> BigInt,
+        failed -> BigInt,
+        complete -> Bool,
+        started_at -> Timestamp,
+        finished_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    collection_schema_versions (id) {
+        id -> Integer,
+        collection_id -> Integer,
+        version -> Integer,
+        schema_json -> Text,
+        created_by -> Nullable<Integer>,
+        migration_summary -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    collection_templates (id) {
+        id -> Integer,
+        template_id -> Text,
+        name -> Text,
+        description -> Nullable<Text>,
+        schema_json -> Text,
+        permissions_json -> Text,
+        sample_records_json -> Text,
+        created_by -> Nullable<Integer>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    collection_views (id) {
+        id -> Integer,
+        collection_id -> Integer,
+        name -> Text,
+        filter -> Nullable<Text>,
+        sort -> Nullable<Text>,
+        fields -> Nullable<Text>,
+        visibility -> Text,
+        role_name -> Nullable<Text>,
+        created_by -> Integer,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     collections (id) {
         id -> Integer,
@@ -47,19 +123,142 @@ diesel::table! {
         is_system -> Bool,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        schema_version -> Integer,
+        query_cache_ttl_seconds -> Integer,
+        orderable -> Bool,
+        id_type -> Text,
+        allow_explicit_ids -> Bool,
+        workflow -> Text,
+        schedule_json -> Nullable<Text>,
+        schedule_checked_at -> Nullable<Timestamp>,
+        workspace_id -> Integer,
+        retention_json -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    guest_session_records (id) {
+        id -> Integer,
+        session_id -> Text,
+        collection_name -> Text,
+        record_id -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    health_samples (id) {
+        id -> Integer,
+        sampled_at -> Timestamp,
+        component -> Text,
+        status -> Text,
+    }
+}
+
+diesel::table! {
+    ingest_endpoints (id) {
+        id -> Integer,
+        name -> Text,
+        token_hash -> Text,
+        collection_name -> Text,
+        field_mapping -> Text,
+        hmac_secret -> Nullable<Text>,
+        signature_header -> Nullable<Text>,
+        is_active -> Bool,
+        created_by -> Integer,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    ingest_failures (id) {
+        id -> Integer,
+        endpoint_id -> Integer,
+        payload -> Text,
+        error -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    record_shares (id) {
+        id -> Integer,
+        token_hash -> Text,
+        collection_name -> Text,
+        record_id -> Text,
+        fields -> Nullable<Text>,
+        password_hash -> Nullable<Text>,
+        max_views -> Nullable<Integer>,
+        view_count -> Integer,
+        expires_at -> Timestamp,
+        created_by -> Integer,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    login_events (id) {
+        id -> Integer,
+        user_id -> Integer,
+        method -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    notifications (id) {
+        id -> Integer,
+        user_id -> Integer,
+        kind -> Text,
+        title -> Text,
+        body -> Nullable<Text>,
+        data -> Nullable<Text>,
+        read_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    permission_audit_entries (id) {
+        id -> Integer,
+        actor_id -> Nullable<Integer>,
+        target_user_id -> Nullable<Integer>,
+        action -> Text,
+        collection_name -> Nullable<Text>,
+        role_name -> Nullable<Text>,
+        affected_count -> BigInt,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    quarantined_uploads (id) {
+        id -> Integer,
+        detected_at -> Timestamp,
+        collection_name -> Nullable<Text>,
+        field_name -> Text,
+        filename -> Text,
+        content_type -> Text,
+        size_bytes -> BigInt,
+        sha256 -> Text,
+        scanner -> Text,
+        signature -> Text,
+        user_id -> Nullable<Integer>,
     }
 }
 
 diesel::table! {
     record_permissions (id) {
         id -> Integer,
-        record_id -> Integer,
+        record_id -> Text,
         collection_id -> Integer,
         user_id -> Integer,
         can_read -> Bool,
         can_update -> Bool,
         can_delete -> Bool,
         created_at -> Timestamp,
+        granted_by -> Nullable<Integer>,
     }
 }
 
@@ -71,6 +270,9 @@ diesel::table! {
         priority -> Integer,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        workspace_id -> Nullable<Integer>,
+        max_records_created_per_day -> Nullable<BigInt>,
+        max_upload_bytes_per_day -> Nullable<BigInt>,
     }
 }
 
@@ -106,6 +308,24 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    user_usage (user_id, bucket_start) {
+        user_id -> Integer,
+        bucket_start -> Timestamp,
+        records_created -> BigInt,
+        upload_bytes -> BigInt,
+    }
+}
+
+diesel::table! {
+    username_renames (user_id) {
+        user_id -> Integer,
+        old_username -> Text,
+        new_username -> Text,
+        renamed_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     users (id) {
         id -> Integer,
@@ -121,39 +341,100 @@ diesel::table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         avatar_url -> Nullable<Text>,
+        token_valid_after -> Nullable<Timestamp>,
+        lockout_count -> Integer,
     }
 }
 
 diesel::table! {
     verification_tokens (id) {
         id -> Integer,
-        token -> Text,
+        token_hash -> Text,
         user_id -> Integer,
         email -> Text,
         expires_at -> Timestamp,
         created_at -> Timestamp,
+        token_type -> Text,
+    }
+}
+
+diesel::table! {
+    workspace_members (id) {
+        id -> Integer,
+        workspace_id -> Integer,
+        user_id -> Integer,
+        role -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    workspaces (id) {
+        id -> Integer,
+        name -> Text,
+        description -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
 diesel::joinable!(blacklisted_tokens -> users (user_id));
+diesel::joinable!(collection_activity -> collections (collection_id));
 diesel::joinable!(collection_permissions -> collections (collection_id));
 diesel::joinable!(collection_permissions -> roles (role_id));
+diesel::joinable!(collection_record_counts -> collections (collection_id));
 diesel::joinable!(collection_records -> collections (collection_id));
+diesel::joinable!(collection_retention_runs -> collections (collection_id));
+diesel::joinable!(collection_schema_versions -> collections (collection_id));
+diesel::joinable!(collection_schema_versions -> users (created_by));
+diesel::joinable!(collection_templates -> users (created_by));
+diesel::joinable!(collection_views -> collections (collection_id));
+diesel::joinable!(collection_views -> users (created_by));
+diesel::joinable!(ingest_endpoints -> users (created_by));
+diesel::joinable!(ingest_failures -> ingest_endpoints (endpoint_id));
+diesel::joinable!(login_events -> users (user_id));
+diesel::joinable!(notifications -> users (user_id));
+diesel::joinable!(permission_audit_entries -> users (actor_id));
+diesel::joinable!(quarantined_uploads -> users (user_id));
 diesel::joinable!(record_permissions -> collections (collection_id));
 diesel::joinable!(record_permissions -> users (user_id));
+diesel::joinable!(record_shares -> users (created_by));
 diesel::joinable!(user_collection_permissions -> collections (collection_id));
 diesel::joinable!(user_collection_permissions -> users (user_id));
+diesel::joinable!(user_usage -> users (user_id));
+diesel::joinable!(username_renames -> users (user_id));
 diesel::joinable!(verification_tokens -> users (user_id));
+diesel::joinable!(workspace_members -> users (user_id));
+diesel::joinable!(workspace_members -> workspaces (workspace_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     blacklisted_tokens,
+    collection_activity,
     collection_permissions,
+    collection_record_counts,
     collection_records,
+    collection_retention_runs,
+    collection_schema_versions,
+    collection_templates,
+    collection_views,
     collections,
+    guest_session_records,
+    health_samples,
+    ingest_endpoints,
+    ingest_failures,
+    login_events,
+    notifications,
+    permission_audit_entries,
+    quarantined_uploads,
     record_permissions,
+    record_shares,
     roles,
     system_settings,
     user_collection_permissions,
+    user_usage,
+    username_renames,
     users,
     verification_tokens,
+    workspace_members,
+    workspaces,
 );
@@ -1,13 +1,18 @@
 use axum::{
-    Router, middleware,
+    Extension, Router,
+    extract::{ConnectInfo, DefaultBodyLimit},
+    middleware,
     routing::{delete, get, post, put},
 };
 use axum::{
     body::Body,
     http::{Request, Response},
 };
-use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
-use rustls_acme::{AcmeConfig, axum::AxumAcceptor, caches::DirCache};
+use diesel_migrations::{EmbeddedMigrations, embed_migrations};
+use rustls_acme::{
+    AcmeConfig, EventError, EventOk, UseChallenge, axum::AxumAcceptor, caches::DirCache,
+    tower::TowerHttp01ChallengeService,
+};
 use std::convert::Infallible;
 use std::future::Future;
 use std::net::SocketAddr;
@@ -15,17 +20,33 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::task::{Context, Poll};
+use std::time::Duration;
 use tokio::signal;
+use tokio::sync::watch;
+use tokio::task::{JoinError, JoinSet};
 use tokio_stream::StreamExt;
 use tower::Service;
 use tower::layer::Layer;
 use tracing::{info, warn};
 use utoipa::OpenApi;
-use utoipa_swagger_ui::SwaggerUi;
+use utoipa_swagger_ui::{Config as SwaggerConfig, SwaggerUi, Url};
 
+use crate::cli::commands::serve::AcmeChallenge;
 use crate::cli::commands::serve::ServeArgs;
-use crate::database::{create_pool, create_pool_with_size};
-use crate::services::{ConfigurationAccess, ConfigurationManager};
+use crate::database::migrations::{MigrationStatus, migration_status, run_migrations};
+use crate::database::{DatabasePool, create_pool, create_pool_with_size};
+use crate::listeners::{
+    CleartextAcceptor, ListenAddress, ServerTuning, bind_tcp_listeners, tcp_server,
+};
+#[cfg(unix)]
+use crate::listeners::{
+    DEFAULT_UNIX_SOCKET_MODE, UnixSocketFile, bind_unix_socket, parse_socket_mode,
+    parse_socket_owner, serve_unix_socket,
+};
+use crate::services::{
+    ConfigurationAccess, ConfigurationManager, ConfigurationService, ReadinessState, TlsStatus,
+    TlsStatusCache, create_pre_migration_backup, create_s3_service_from_config,
+};
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations/");
 
@@ -137,56 +158,122 @@ impl<S> Layer<S> for ConnectionTrackingLayer {
 }
 
 use crate::handlers::{
+    admin::{
+        get_admin_overview, get_record_analytics, get_tls_status, get_user_analytics,
+        list_locked_accounts,
+    },
     avatar_proxy::proxy_avatar,
     backup::{create_manual_backup, get_backup_health},
+    batch::execute_batch,
+    collection_templates::{
+        create_collection_from_template, delete_collection_template, get_collection_template,
+        list_collection_templates, save_collection_as_template,
+    },
+    collection_views::{
+        create_collection_view, delete_collection_view, get_collection_view, list_collection_views,
+        update_collection_view,
+    },
     collections::{
-        create_collection, create_record, delete_collection, delete_record, get_collection,
-        get_collection_schema, get_collections_record_counts, get_collections_stats, get_record,
-        list_all_records, list_collections, list_records, update_collection, update_record,
+        count_records, create_collection, create_record, delete_collection, delete_record,
+        export_records, generate_typescript_types, get_collection, get_collection_health,
+        get_collection_json_schema, get_collection_schema, get_collection_schema_version,
+        get_collections_json_schema, get_collections_openapi, get_collections_record_counts,
+        get_collections_stats, get_record, get_record_by_field, get_record_references,
+        get_record_schedule, get_retention_report, global_search, list_all_records,
+        list_collection_schema_versions, list_collections, list_records, move_record,
+        publish_record, repair_collection, restore_collection_schema_version, sweep_orphans,
+        unpublish_record, update_collection, update_record, validate_record, verify_collection,
+        verify_records,
     },
     configuration::{
         create_setting, delete_setting, get_all_settings, get_setting, get_settings_by_category,
         reset_setting, update_setting,
     },
     embedded_admin::{serve_embedded_admin_html, serve_embedded_assets},
-    forgot_password,
-    health::{health_check, public_health_check, simple_health_check},
+    forgot_password, guest_session,
+    health::{
+        health_check, health_history, liveness_check, public_health_check, readiness_check,
+        simple_health_check,
+    },
     image_upload::{delete_image, upload_image},
-    login, logout, me,
+    ingest::{
+        create_ingest_endpoint, delete_ingest_endpoint, ingest_payload, list_ingest_endpoints,
+        list_ingest_failures,
+    },
+    list_my_workspaces, login, logout, me,
     metrics::{get_metrics, get_metrics_summary},
+    notifications::{list_my_notifications, mark_my_notifications_read, stream_my_notifications},
     oauth_authorize, oauth_callback, oauth_status,
     ownership::{
-        check_record_ownership, get_my_owned_records, get_ownership_stats, get_user_owned_records,
-        transfer_record_ownership,
+        check_record_ownership, get_global_ownership_stats, get_my_owned_records,
+        get_ownership_stats, get_user_owned_records, transfer_record_ownership,
     },
     permissions::{
-        create_role, delete_role, get_collection_permissions, get_role,
-        get_role_collection_permission, get_user_accessible_collections,
-        get_user_collection_permissions, list_roles, set_collection_permission,
-        set_user_collection_permission, update_role,
+        create_role, delete_role, export_permissions, get_collection_permissions,
+        get_default_permissions, get_role, get_role_collection_permission,
+        get_user_accessible_collections, get_user_collection_permissions, import_permissions,
+        list_collection_users, list_roles, set_collection_permission,
+        set_user_collection_permission, update_default_permissions, update_role,
     },
     record_permissions::{
-        get_record_permissions, list_record_permissions, remove_record_permission,
-        set_record_permission,
+        get_record_permissions, list_record_permissions, list_user_record_permissions,
+        remove_record_permission, revoke_user_record_permissions, set_record_permission,
+    },
+    record_shares::{
+        create_record_share, get_shared_record, list_record_shares, revoke_record_share,
+    },
+    refresh_token, register, register_admin, resend_verification, reset_password, session_info,
+    switch_workspace,
+    users::{
+        create_user, delete_user, get_my_usage, get_user, get_user_usage, list_users,
+        normalize_user_emails, unlock_user, update_user, verify_user_email,
     },
-    refresh_token, register, register_admin, resend_verification, reset_password,
-    users::{create_user, delete_user, get_user, list_users, unlock_user, update_user},
     verify_email, verify_email_get,
     websocket::{
         broadcast_message, disconnect_connection, get_activity, get_connections, websocket_handler,
         websocket_stats, websocket_status,
     },
+    workspaces::{
+        create_workspace, delete_workspace, list_workspaces, move_collection_to_workspace,
+        remove_workspace_member, set_workspace_member,
+    },
 };
-use crate::middleware::{add_middleware, auth_middleware, setup_logging};
+use crate::middleware::{
+    add_middleware, api_version_middleware, auth_middleware, body_limit_middleware,
+    csrf_middleware, locale_middleware, optional_auth_middleware, read_only_middleware,
+    setup_logging, workspace_middleware,
+};
+use crate::openapi::{CollectionsOpenApiCache, without_frontend_paths};
+use crate::utils::validate_cookie_settings;
 use crate::{ApiDoc, AppState, Config};
 
 async fn create_redirect_server(
     redirect_port: u16,
     target_port: u16,
     target_host: &str,
+    acme_challenges: Option<TowerHttp01ChallengeService>,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let app = redirect_router(target_port, acme_challenges);
+
+    let addr = format!("{}:{}", target_host, redirect_port).parse::<SocketAddr>()?;
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+
+    info!("HTTP redirect server listening on {}", addr);
+
+    axum::serve(listener, app.into_make_service())
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    Ok(())
+}
+
+/// Redirects every request to HTTPS, except ACME HTTP-01 challenges when
+/// that challenge type is in use.
+fn redirect_router(
+    target_port: u16,
+    acme_challenges: Option<TowerHttp01ChallengeService>,
+) -> Router {
     use axum::{
-        Router,
         extract::Request,
         http::{StatusCode, header::HOST},
         response::Redirect,
@@ -231,16 +318,69 @@ async fn create_redirect_server(
         any(move |request| redirect_to_https(request, target_port)),
     );
 
-    let addr = format!("{}:{}", target_host, redirect_port).parse::<SocketAddr>()?;
-    let listener = tokio::net::TcpListener::bind(addr).await?;
+    match acme_challenges {
+        Some(challenges) => app.route_service("/.well-known/acme-challenge/{token}", challenges),
+        None => app,
+    }
+}
 
-    info!("HTTP redirect server listening on {}", addr);
+/// Brings the database schema up to date with the binary. Pending migrations
+/// are applied when `apply` is set, after a backup when backups are
+/// configured; otherwise the pending migrations are listed in the error.
+pub async fn apply_pending_migrations(
+    pool: &DatabasePool,
+    config: &Config,
+    apply: bool,
+) -> Result<MigrationStatus, Box<dyn std::error::Error>> {
+    let status = migration_status(&mut pool.get()?)
+        .map_err(|e| format!("Failed to read migration status: {}", e))?;
+    if status.is_up_to_date() {
+        return Ok(status);
+    }
+    if !apply {
+        return Err(status.pending_message().into());
+    }
 
-    axum::serve(listener, app.into_make_service())
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    // A new database has nothing to lose
+    if status.applied > 0 {
+        let config_manager = ConfigurationManager::new(pool.clone());
+        config_manager
+            .initialize()
+            .await
+            .map_err(|e| format!("Failed to read the backup settings before migrating: {}", e))?;
+        let mut config = config.clone();
+        config
+            .load_dynamic_settings(&ConfigurationService::new(pool.clone()))
+            .await?;
+        let s3_service = create_s3_service_from_config(&config)
+            .await
+            .ok()
+            .flatten()
+            .map(Arc::new);
 
-    Ok(())
+        match create_pre_migration_backup(pool.clone(), s3_service, Arc::new(config_manager))
+            .await
+            .map_err(|e| format!("Pre-migration backup failed, not migrating: {}", e))?
+        {
+            Some(backup) => info!(
+                "Backed up the database before migrating: {}",
+                backup.backup_id
+            ),
+            None => info!("Backups are not configured, migrating without a backup"),
+        }
+    }
+
+    let applied =
+        run_migrations(&mut pool.get()?).map_err(|e| format!("Failed to run migrations: {}", e))?;
+    info!(
+        "Applied {} pending migration(s): {}",
+        applied.len(),
+        applied.join(", ")
+    );
+
+    let status = migration_status(&mut pool.get()?)
+        .map_err(|e| format!("Failed to read migration status: {}", e))?;
+    Ok(status)
 }
 
 pub async fn run_server(serve_args: &ServeArgs) -> Result<(), Box<dyn std::error::Error>> {
@@ -253,17 +393,12 @@ pub async fn run_server(serve_args: &ServeArgs) -> Result<(), Box<dyn std::error
 
     let config = Config::from_env_with_args(Some(serve_args))?;
     info!("Configuration loaded successfully");
-    info!("Server will bind to: {}", serve_args.server_address());
 
     let initial_pool = create_pool(&config.database_url)?;
     info!("Initial database pool created successfully");
 
-    {
-        let mut conn = initial_pool.get()?;
-        conn.run_pending_migrations(MIGRATIONS)
-            .map_err(|e| format!("Failed to run migrations: {}", e))?;
-        info!("Database migrations completed successfully");
-    }
+    apply_pending_migrations(&initial_pool, &config, serve_args.auto_migrate).await?;
+    info!("Database migrations completed successfully");
 
     let config_manager = ConfigurationManager::new(initial_pool.clone());
     config_manager.initialize().await?;
@@ -304,6 +439,35 @@ pub async fn run_server(serve_args: &ServeArgs) -> Result<(), Box<dyn std::error
     )
     .await?;
 
+    match app_state.admin_service.normalize_user_emails(false).await {
+        Ok(report) => {
+            if !report.normalized.is_empty() {
+                info!(
+                    "Normalized {} stored user email(s)",
+                    report.normalized.len()
+                );
+            }
+            for conflict in &report.conflicts {
+                warn!(
+                    "Users {:?} have emails that all normalize to {}; left unchanged, rename or merge them and run POST /api/admin/users/normalize-emails",
+                    conflict
+                        .users
+                        .iter()
+                        .map(|user| user.user_id)
+                        .collect::<Vec<_>>(),
+                    conflict.normalized
+                );
+            }
+            for user in &report.invalid {
+                warn!(
+                    "User {} has an invalid email address: {}",
+                    user.user_id, user.email
+                );
+            }
+        }
+        Err(e) => warn!("Failed to normalize stored user emails: {}", e),
+    }
+
     if let Err(e) = app_state
         .admin_service
         .ensure_admin_exists(&config, &app_state.password_pepper)
@@ -312,112 +476,309 @@ pub async fn run_server(serve_args: &ServeArgs) -> Result<(), Box<dyn std::error
         warn!("Failed to create admin from environment variables: {}", e);
     }
 
+    if app_state.get_require_email_verification().await && !app_state.email_service.is_configured()
+    {
+        warn!(
+            "Email verification is required but no email transport is configured: new users cannot verify their accounts and will not be able to log in. Set email.resend_api_key or set auth.require_email_verification to false."
+        );
+    }
+
+    let cookie_same_site = app_state.get_cookie_same_site().await;
+    validate_cookie_settings(&cookie_same_site, &app_state.get_cookie_domain().await)?;
+    if cookie_same_site == "None" && !app_state.get_cors_allow_credentials().await {
+        warn!(
+            "auth.cookie_same_site is None but api.cors_allow_credentials is false: browsers will not send the session cookies with cross-origin API requests. Enable credentials and list the admin panel origin in api.cors_allowed_origins."
+        );
+    }
+
+    app_state.lockout_service.start_expired_lock_cleanup();
+    app_state.health_recorder.start();
+    app_state.collection_service.start_record_scheduler();
+    app_state
+        .collection_service
+        .start_retention_scheduler(app_state.metrics_state.clone());
+    app_state.collection_service.start_record_count_reconciler();
+
     let metrics_state_clone = app_state.metrics_state.clone();
+    let readiness = app_state.readiness.clone();
+    let active_listeners = app_state.listeners.clone();
+    let tls_status = app_state.tls_status.clone();
+    let drain_period = Duration::from_secs(app_state.get_shutdown_drain_seconds().await as u64);
+    let tuning = load_server_tuning(&app_state, serve_args).await;
+    info!(
+        "Connection settings: keep-alive timeout {}s, {} concurrent HTTP/2 streams, {} KB headers",
+        tuning.keep_alive_timeout.as_secs(),
+        tuning.max_concurrent_streams,
+        tuning.max_header_size / 1024
+    );
 
-    let app = create_router(app_state, serve_args).await;
+    let tcp_addresses = config.tcp_bind_addresses()?;
+    let tcp_listeners = bind_tcp_listeners(&tcp_addresses)?;
+    #[cfg(unix)]
+    let unix_socket = match &config.unix_socket_path {
+        Some(path) => Some(bind_configured_unix_socket(&config, path)?),
+        None => None,
+    };
+    #[cfg(not(unix))]
+    if config.unix_socket_path.is_some() {
+        return Err("Unix domain sockets are only supported on Unix platforms".into());
+    }
+
+    if config.api_only {
+        info!("API-only mode: admin panel, Swagger UI and avatar proxy are disabled");
+    }
+    let app = create_router(app_state, config.api_only).await;
+
+    let acme_enabled = config.acme_enabled.unwrap_or(false);
+    let scheme = if acme_enabled { "https" } else { "http" };
+    let mut addresses = Vec::new();
+    for address in &tcp_addresses {
+        addresses.push(ListenAddress::Tcp(*address));
+        let base_url = format!("{}://{}", scheme, address);
+        info!("API: {}/api", base_url);
+        if !config.api_only {
+            info!("API Docs: {}/docs", base_url);
+            info!("Admin panel: {}/admin", base_url);
+        }
+    }
 
-    let addr = serve_args.server_address().parse::<SocketAddr>()?;
-    info!("Server will listen on {}", addr);
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let mut servers: JoinSet<Result<(), String>> = JoinSet::new();
+    let mut tcp_handles = Vec::new();
+    let mut acme_task = None;
 
-    if config.acme_enabled.unwrap_or(false) {
+    if acme_enabled {
         info!("ACME enabled - starting HTTPS server with automatic certificate management");
 
         let connection_tracker = ConnectionTracker::new(metrics_state_clone);
         let tracking_layer = ConnectionTrackingLayer::new(connection_tracker);
-        let tracked_app = app.layer(tracking_layer);
+        let tracked_app = app.clone().layer(tracking_layer);
 
-        let (acceptor, acme_handle) = create_acme_config(&config).await?;
+        let acme = create_acme_config(&config, &tuning, tls_status).await?;
+        let acceptor = acme.acceptor;
+        acme_task = Some(acme.task);
 
-        let https_url = format!("https://{}:{}", serve_args.host(), serve_args.port());
-        info!("API: {}/api", https_url);
-        info!("API Docs: {}/docs", https_url);
-        if !serve_args.api_only {
-            info!("Admin panel: {}/admin", https_url);
-        }
-
-        if serve_args.enable_redirect {
+        // HTTP-01 challenges are answered on the redirect server, so it runs
+        // whenever that challenge type is used.
+        if serve_args.enable_redirect || acme.http01_challenges.is_some() {
             let redirect_target_port = serve_args.redirect_target_port();
             let redirect_host = serve_args.host().to_string();
             let redirect_port = serve_args.redirect_port();
+            let acme_challenges = acme.http01_challenges;
 
             info!("Starting HTTP redirect server on port {}", redirect_port);
 
-            let redirect_handle = tokio::spawn(async move {
-                if let Err(e) =
-                    create_redirect_server(redirect_port, redirect_target_port, &redirect_host)
-                        .await
+            tokio::spawn(async move {
+                if let Err(e) = create_redirect_server(
+                    redirect_port,
+                    redirect_target_port,
+                    &redirect_host,
+                    acme_challenges,
+                )
+                .await
                 {
                     tracing::error!("HTTP redirect server error: {}", e);
                 }
             });
+        }
+
+        if tuning.http2 {
+            info!("HTTP/2 enabled via ALPN");
+        }
 
-            let https_handle = tokio::spawn(async move {
-                if let Err(e) = axum_server::bind(addr)
-                    .acceptor(acceptor)
-                    .serve(tracked_app.into_make_service_with_connect_info::<SocketAddr>())
+        for (address, listener) in tcp_addresses.iter().copied().zip(tcp_listeners) {
+            let handle = axum_server::Handle::new();
+            tcp_handles.push(handle.clone());
+            let server = tcp_server(listener, &tuning, handle).acceptor(acceptor.clone());
+            let app = tracked_app.clone();
+            servers.spawn(async move {
+                server
+                    .serve(app.into_make_service_with_connect_info::<SocketAddr>())
                     .await
-                {
-                    tracing::error!("HTTPS server error: {}", e);
-                }
+                    .map_err(|e| format!("HTTPS listener {} failed: {}", address, e))
             });
-
-            tokio::select! {
-                _ = redirect_handle => {},
-                _ = https_handle => {},
-                _ = acme_handle => {},
-                _ = shutdown_signal() => {
-                    info!("Shutdown signal received, stopping servers...");
-                }
-            }
-        } else {
-            tokio::select! {
-                result = axum_server::bind(addr)
-                    .acceptor(acceptor)
-                    .serve(tracked_app.into_make_service_with_connect_info::<SocketAddr>()) => {
-                    if let Err(e) = result {
-                        tracing::error!("HTTPS server error: {}", e);
-                    }
-                },
-                _ = acme_handle => {},
-                _ = shutdown_signal() => {
-                    info!("Shutdown signal received, stopping servers...");
-                }
-            }
         }
     } else {
-        info!("TLS disabled - starting HTTP server (HTTP/1.1 only)");
+        if tuning.allows_http2(false) {
+            info!("TLS disabled - starting HTTP server (HTTP/1.1 and h2c)");
+        } else {
+            info!("TLS disabled - starting HTTP server (HTTP/1.1 only)");
+        }
 
-        let http_url = format!("http://{}:{}", serve_args.host(), serve_args.port());
-        info!("API: {}/api", http_url);
-        info!("API Docs: {}/docs", http_url);
-        if !serve_args.api_only {
-            info!("Admin panel: {}/admin", http_url);
+        for (address, listener) in tcp_addresses.iter().copied().zip(tcp_listeners) {
+            let handle = axum_server::Handle::new();
+            tcp_handles.push(handle.clone());
+            let server =
+                tcp_server(listener, &tuning, handle).acceptor(CleartextAcceptor::new(&tuning));
+            let app = app.clone();
+            servers.spawn(async move {
+                server
+                    .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+                    .await
+                    .map_err(|e| format!("HTTP listener {} failed: {}", address, e))
+            });
         }
+    }
 
-        let listener = tokio::net::TcpListener::bind(addr).await?;
+    // The socket is always plain HTTP: it is meant for a reverse proxy on the same host.
+    #[cfg(unix)]
+    let unix_socket_file = unix_socket.map(|(listener, file)| {
+        addresses.push(ListenAddress::Unix(file.path().to_path_buf()));
+        info!("API: unix:{} (HTTP)", file.path().display());
+
+        // Unix peers have no IP address, so rate limiting relies on the
+        // forwarded headers the proxy sets and shares one key without them.
+        let app = app.clone().layer(Extension(ConnectInfo(UNIX_SOCKET_PEER)));
+        let shutdown = wait_for_shutdown(shutdown_rx.clone());
+        let tuning = tuning.clone();
+        servers.spawn(async move {
+            serve_unix_socket(listener, app, &tuning, shutdown).await;
+            Ok(())
+        });
+        file
+    });
 
-        axum::serve(
-            listener,
-            app.into_make_service_with_connect_info::<SocketAddr>(),
-        )
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    active_listeners.set(addresses);
+    info!("Server started successfully");
+
+    let acme_stopped = async {
+        match acme_task {
+            Some(task) => {
+                let _ = task.await;
+            }
+            None => std::future::pending().await,
+        }
+    };
+
+    let mut failure = None;
+    tokio::select! {
+        _ = drain_on_shutdown(readiness.clone(), drain_period) => {
+            info!("Shutdown signal received, stopping listeners...");
+        }
+        Some(result) = servers.join_next() => {
+            failure = listener_failure(result);
+            readiness.mark_draining();
+        }
+        _ = acme_stopped => {
+            warn!("ACME certificate manager stopped, shutting down");
+            readiness.mark_draining();
+        }
     }
 
-    info!("Server started successfully");
+    let _ = shutdown_tx.send(true);
+    for handle in &tcp_handles {
+        handle.graceful_shutdown(None);
+    }
+    while let Some(result) = servers.join_next().await {
+        if let Some(e) = listener_failure(result) {
+            failure.get_or_insert(e);
+        }
+    }
+
+    active_listeners.clear();
+    #[cfg(unix)]
+    drop(unix_socket_file);
+
     info!("Server shutdown complete");
-    Ok(())
+    match failure {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
+
+/// Address rate limiting sees for requests that arrive over the Unix socket.
+#[cfg(unix)]
+const UNIX_SOCKET_PEER: SocketAddr = SocketAddr::V4(std::net::SocketAddrV4::new(
+    std::net::Ipv4Addr::LOCALHOST,
+    0,
+));
+
+#[cfg(unix)]
+fn bind_configured_unix_socket(
+    config: &Config,
+    path: &std::path::Path,
+) -> Result<(tokio::net::UnixListener, UnixSocketFile), Box<dyn std::error::Error>> {
+    let mode = config
+        .unix_socket_mode
+        .as_deref()
+        .map(parse_socket_mode)
+        .transpose()?
+        .unwrap_or(DEFAULT_UNIX_SOCKET_MODE);
+    let owner = config
+        .unix_socket_owner
+        .as_deref()
+        .map(parse_socket_owner)
+        .transpose()?
+        .unwrap_or((None, None));
+
+    bind_unix_socket(path, mode, owner)
+        .map_err(|e| format!("Failed to bind Unix socket {}: {}", path.display(), e).into())
+}
+
+async fn wait_for_shutdown(mut shutdown: watch::Receiver<bool>) {
+    let _ = shutdown.wait_for(|stop| *stop).await;
+}
+
+fn listener_failure(result: Result<Result<(), String>, JoinError>) -> Option<String> {
+    let error = match result {
+        Ok(Ok(())) => return None,
+        Ok(Err(e)) => e,
+        Err(e) => format!("Listener task failed: {}", e),
+    };
+    tracing::error!("{}", error);
+    Some(error)
+}
+
+/// Reads the connection settings, letting `serve` options override them.
+async fn load_server_tuning(
+    settings: &impl ConfigurationAccess,
+    serve_args: &ServeArgs,
+) -> ServerTuning {
+    let keep_alive_seconds = match serve_args.keep_alive_timeout {
+        Some(seconds) => seconds,
+        None => settings.get_keep_alive_timeout_seconds().await,
+    };
+    let max_concurrent_streams = match serve_args.max_concurrent_streams {
+        Some(streams) => streams,
+        None => settings.get_http2_max_concurrent_streams().await,
+    };
+    let max_header_size_kb = match serve_args.max_header_size_kb {
+        Some(kilobytes) => kilobytes,
+        None => settings.get_max_header_size_kb().await,
+    };
+
+    ServerTuning {
+        http2: !serve_args.no_http2 && settings.get_http2_enabled().await,
+        http2_cleartext: serve_args.http2_cleartext || settings.get_http2_cleartext().await,
+        keep_alive_timeout: Duration::from_secs(keep_alive_seconds as u64),
+        max_concurrent_streams,
+        max_header_size: max_header_size_kb as usize * 1024,
+    }
+}
+
+struct AcmeSetup {
+    acceptor: AxumAcceptor,
+    /// Set when domains are validated with HTTP-01 challenges
+    http01_challenges: Option<TowerHttp01ChallengeService>,
+    task: tokio::task::JoinHandle<()>,
 }
 
 async fn create_acme_config(
     config: &Config,
-) -> Result<(AxumAcceptor, tokio::task::JoinHandle<()>), Box<dyn std::error::Error>> {
+    tuning: &ServerTuning,
+    tls_status: TlsStatus,
+) -> Result<AcmeSetup, Box<dyn std::error::Error>> {
     let domains = config.acme_domains.clone();
     if domains.is_empty() {
         return Err("At least one domain must be specified for ACME".into());
     }
 
-    info!("Setting up ACME for domains: {:?}", domains);
+    info!(
+        "Setting up ACME for domains: {:?} ({} challenge)",
+        domains,
+        config.acme_challenge.as_str()
+    );
+    tls_status.enable(domains.clone(), config.acme_challenge.as_str());
 
     let cache_dir = config
         .acme_cache_dir
@@ -425,8 +786,16 @@ async fn create_acme_config(
         .map(|dir| dir.clone())
         .unwrap_or_else(|| "./acme_cache".to_string());
 
+    let challenge_type = match config.acme_challenge {
+        AcmeChallenge::TlsAlpn01 => UseChallenge::TlsAlpn01,
+        AcmeChallenge::Http01 => UseChallenge::Http01,
+    };
     let mut acme_config = AcmeConfig::new(domains)
-        .cache(DirCache::new(cache_dir))
+        .cache(TlsStatusCache::new(
+            DirCache::new(cache_dir),
+            tls_status.clone(),
+        ))
+        .challenge_type(challenge_type)
         .directory_lets_encrypt(config.acme_production.unwrap_or(false));
 
     if let Some(email) = &config.acme_email {
@@ -436,30 +805,77 @@ async fn create_acme_config(
     }
 
     let mut state = acme_config.state();
-    let acceptor = state.axum_acceptor(state.default_rustls_config());
-
-    let handle = tokio::spawn(async move {
-        loop {
-            match state.next().await {
-                Some(Ok(ok)) => info!("ACME event: {:?}", ok),
-                Some(Err(err)) => warn!("ACME error: {:?}", err),
-                None => break,
+    let mut rustls_config = (*state.default_rustls_config()).clone();
+    rustls_config.alpn_protocols = tuning.alpn_protocols();
+    let acceptor = state.axum_acceptor(Arc::new(rustls_config));
+    let http01_challenges = (config.acme_challenge == AcmeChallenge::Http01)
+        .then(|| state.http01_challenge_tower_service());
+
+    let task = tokio::spawn(async move {
+        while let Some(event) = state.next().await {
+            match event {
+                Ok(EventOk::DeployedNewCert) => {
+                    info!("ACME certificate issued and deployed");
+                    tls_status.record_renewal_success();
+                }
+                Ok(ok) => info!("ACME event: {:?}", ok),
+                // The next order only starts once the state is polled again,
+                // so waiting here is what backs off the retries.
+                Err(EventError::Order(err)) => {
+                    let (failures, delay) = tls_status.record_renewal_failure(&err.to_string());
+                    tracing::error!(
+                        "ACME certificate order failed ({} in a row), retrying in {}s: {}",
+                        failures,
+                        delay.as_secs(),
+                        err
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(err) => warn!("ACME error: {:?}", err),
             }
         }
     });
 
     info!("ACME configuration created successfully");
-    Ok((acceptor, handle))
+    Ok(AcmeSetup {
+        acceptor,
+        http01_challenges,
+        task,
+    })
 }
 
-async fn create_router(app_state: AppState, serve_args: &ServeArgs) -> Router {
+async fn create_router(app_state: AppState, api_only: bool) -> Router {
+    let app = build_routes(app_state.clone(), api_only);
+
+    // Probes are merged after the middleware stack so they bypass auth, rate limiting and compression.
+    let probe_routes = Router::new()
+        .route("/api/health/live", get(liveness_check))
+        .route("/api/health/ready", get(readiness_check))
+        .with_state(app_state.clone());
+
+    add_middleware(app, app_state).await.merge(probe_routes)
+}
+
+/// Builds the route tree without the middleware stack. In API-only mode the
+/// admin panel, Swagger UI and avatar proxy are not mounted at all, so their
+/// paths answer 404 and the embedded admin files are never read.
+pub fn build_routes(mut app_state: AppState, api_only: bool) -> Router {
+    if api_only {
+        app_state.collections_openapi =
+            CollectionsOpenApiCache::new(&without_frontend_paths(ApiDoc::openapi()));
+    }
+
     let public_routes = Router::new()
         .route("/health", get(public_health_check))
         .route("/health/simple", get(simple_health_check))
+        .route("/health/history", get(health_history))
         .route("/auth/register", post(register))
         .route("/auth/register-admin", post(register_admin))
         .route("/auth/login", post(login))
-        .route("/auth/refresh", post(refresh_token))
+        .route(
+            "/auth/refresh",
+            post(refresh_token).layer(middleware::from_fn(csrf_middleware)),
+        )
         .route("/auth/verify-email", post(verify_email))
         .route("/verify-email", get(verify_email_get))
         .route("/auth/resend-verification", post(resend_verification))
@@ -468,38 +884,230 @@ async fn create_router(app_state: AppState, serve_args: &ServeArgs) -> Router {
         .route("/auth/oauth/{provider}", get(oauth_authorize))
         .route("/auth/oauth/{provider}/callback", get(oauth_callback))
         .route("/auth/oauth/status", get(oauth_status))
-        .route("/avatar-proxy", get(proxy_avatar))
+        .route("/auth/session-info", get(session_info))
+        .route("/auth/guest-session", post(guest_session))
         .route("/metrics", get(get_metrics))
         .route("/metrics/summary", get(get_metrics_summary))
-        .route("/collections", get(list_collections))
+        .route(
+            "/collections",
+            get(list_collections).layer(middleware::from_fn_with_state(
+                app_state.auth_state.clone(),
+                optional_auth_middleware,
+            )),
+        )
         .route("/collections/{name}", get(get_collection))
+        .route(
+            "/collections/schemas.json",
+            get(get_collections_json_schema),
+        )
+        .route("/api-docs/collections.json", get(get_collections_openapi))
         .route("/collections/{name}/schema", get(get_collection_schema))
-        .route("/collections/{name}/records", get(list_records))
-        .route("/collections/{name}/records/{id}", get(get_record))
+        .route(
+            "/collections/{name}/schema/versions",
+            get(list_collection_schema_versions),
+        )
+        .route(
+            "/collections/{name}/schema/versions/{version}",
+            get(get_collection_schema_version),
+        )
+        .route(
+            "/collections/{name}/schema.json",
+            get(get_collection_json_schema),
+        )
+        .route(
+            "/collections/{name}/records",
+            get(list_records)
+                .layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    workspace_middleware,
+                ))
+                .layer(middleware::from_fn_with_state(
+                    app_state.auth_state.clone(),
+                    optional_auth_middleware,
+                )),
+        )
+        .route(
+            "/collections/{name}/records/count",
+            get(count_records)
+                .layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    workspace_middleware,
+                ))
+                .layer(middleware::from_fn_with_state(
+                    app_state.auth_state.clone(),
+                    optional_auth_middleware,
+                )),
+        )
+        .route(
+            "/collections/{name}/records/{id}",
+            get(get_record)
+                .layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    workspace_middleware,
+                ))
+                .layer(middleware::from_fn_with_state(
+                    app_state.auth_state.clone(),
+                    optional_auth_middleware,
+                )),
+        )
+        .route(
+            "/collections/{name}/records/by/{field}/{value}",
+            get(get_record_by_field)
+                .layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    workspace_middleware,
+                ))
+                .layer(middleware::from_fn_with_state(
+                    app_state.auth_state.clone(),
+                    optional_auth_middleware,
+                )),
+        )
         .route("/ws", get(websocket_handler))
-        .route("/ws/status", get(websocket_status));
+        .route("/ws/status", get(websocket_status))
+        .route("/ingest/{token}", post(ingest_payload))
+        .route("/share/{token}", get(get_shared_record));
+    let public_routes = if api_only {
+        public_routes
+    } else {
+        public_routes.route("/avatar-proxy", get(proxy_avatar))
+    };
 
     let protected_routes = Router::new()
         .route("/auth/me", get(me))
         .route("/auth/logout", post(logout))
+        .route("/auth/workspace", post(switch_workspace))
+        .route("/auth/workspaces", get(list_my_workspaces))
+        .route("/users/me/notifications", get(list_my_notifications))
+        .route("/users/me/usage", get(get_my_usage))
+        .route(
+            "/users/me/notifications/read",
+            post(mark_my_notifications_read),
+        )
+        .route(
+            "/users/me/notifications/stream",
+            get(stream_my_notifications),
+        )
+        .route(
+            "/admin/workspaces",
+            post(create_workspace).get(list_workspaces),
+        )
+        .route("/admin/workspaces/{id}", delete(delete_workspace))
+        .route(
+            "/admin/workspaces/{id}/members/{user_id}",
+            put(set_workspace_member).delete(remove_workspace_member),
+        )
+        .route(
+            "/admin/workspaces/{id}/collections",
+            post(move_collection_to_workspace),
+        )
         .route("/admin/health", get(health_check))
+        .route("/admin/overview", get(get_admin_overview))
+        .route("/admin/analytics/users", get(get_user_analytics))
+        .route("/admin/analytics/records", get(get_record_analytics))
+        .route("/admin/users/locked", get(list_locked_accounts))
+        .route("/admin/tls/status", get(get_tls_status))
         .route("/collections", post(create_collection))
         .route("/collections/{name}", put(update_collection))
         .route("/collections/{name}", delete(delete_collection))
         .route("/collections/stats", get(get_collections_stats))
+        .route("/collections/templates", get(list_collection_templates))
+        .route(
+            "/collections/templates/{template_id}",
+            get(get_collection_template).delete(delete_collection_template),
+        )
+        .route(
+            "/collections/from-template/{template_id}",
+            post(create_collection_from_template),
+        )
+        .route(
+            "/collections/{name}/save-as-template",
+            post(save_collection_as_template),
+        )
+        .route(
+            "/collections/{name}/schema/versions/{version}/restore",
+            post(restore_collection_schema_version),
+        )
+        .route("/admin/collections/{name}/verify", post(verify_collection))
+        .route("/admin/collections/{name}/repair", post(repair_collection))
+        .route("/admin/collections/orphans/sweep", post(sweep_orphans))
+        .route(
+            "/admin/collections/{name}/schedule",
+            get(get_record_schedule),
+        )
+        .route(
+            "/admin/collections/{name}/retention",
+            get(get_retention_report),
+        )
+        .route("/admin/codegen/typescript", get(generate_typescript_types))
         .route(
             "/collections/record-counts",
             get(get_collections_record_counts),
         )
         .route("/records", get(list_all_records))
+        .route("/search", get(global_search))
+        .route("/batch", post(execute_batch))
+        .route(
+            "/admin/ingest-endpoints",
+            post(create_ingest_endpoint).get(list_ingest_endpoints),
+        )
+        .route(
+            "/admin/ingest-endpoints/{id}",
+            delete(delete_ingest_endpoint),
+        )
+        .route(
+            "/admin/ingest-endpoints/{id}/failures",
+            get(list_ingest_failures),
+        )
+        .route(
+            "/collections/{name}/views",
+            post(create_collection_view).get(list_collection_views),
+        )
+        .route(
+            "/collections/{name}/views/{view_name}",
+            get(get_collection_view)
+                .put(update_collection_view)
+                .delete(delete_collection_view),
+        )
         .route("/collections/{name}/records", post(create_record))
+        .route(
+            "/collections/{name}/records/validate",
+            post(validate_record),
+        )
+        .route("/collections/{name}/records/export", get(export_records))
+        .route("/collections/{name}/records/verify", post(verify_records))
+        .route("/collections/{name}/health", get(get_collection_health))
         .route("/collections/{name}/records/{id}", put(update_record))
+        .route("/collections/{name}/records/{id}/move", post(move_record))
+        .route(
+            "/collections/{name}/records/{id}/references",
+            get(get_record_references),
+        )
+        .route(
+            "/collections/{name}/records/{id}/publish",
+            post(publish_record),
+        )
+        .route(
+            "/collections/{name}/records/{id}/unpublish",
+            post(unpublish_record),
+        )
+        .route(
+            "/collections/{name}/records/{id}/share",
+            post(create_record_share),
+        )
+        .route("/shares", get(list_record_shares))
+        .route("/shares/{id}", delete(revoke_record_share))
         .route("/collections/{name}/records/{id}", delete(delete_record))
         .route("/permissions/roles", post(create_role))
         .route("/permissions/roles", get(list_roles))
         .route("/permissions/roles/{role_name}", get(get_role))
         .route("/permissions/roles/{role_name}", put(update_role))
         .route("/permissions/roles/{role_name}", delete(delete_role))
+        .route(
+            "/permissions/defaults",
+            get(get_default_permissions).put(update_default_permissions),
+        )
+        .route("/permissions/export", get(export_permissions))
+        .route("/permissions/import", post(import_permissions))
         .route(
             "/permissions/roles/{role_name}/collections/{collection_name}",
             get(get_role_collection_permission),
@@ -512,6 +1120,10 @@ async fn create_router(app_state: AppState, serve_args: &ServeArgs) -> Router {
             "/permissions/collections/{name}",
             get(get_collection_permissions),
         )
+        .route(
+            "/permissions/collections/{name}/users",
+            get(list_collection_users),
+        )
         .route(
             "/permissions/users/{user_id}/collections/{name}",
             post(set_user_collection_permission),
@@ -524,6 +1136,10 @@ async fn create_router(app_state: AppState, serve_args: &ServeArgs) -> Router {
             "/permissions/users/{user_id}/collections",
             get(get_user_accessible_collections),
         )
+        .route(
+            "/permissions/users/{user_id}/records",
+            get(list_user_record_permissions).delete(revoke_user_record_permissions),
+        )
         .route(
             "/permissions/collections/{name}/records/{record_id}",
             post(set_record_permission),
@@ -560,12 +1176,19 @@ async fn create_router(app_state: AppState, serve_args: &ServeArgs) -> Router {
             "/ownership/collections/{name}/stats",
             get(get_ownership_stats),
         )
+        .route("/ownership/stats/global", get(get_global_ownership_stats))
         .route("/users", get(list_users))
         .route("/users", post(create_user))
         .route("/users/{user_id}", get(get_user))
         .route("/users/{user_id}", put(update_user))
         .route("/users/{user_id}", delete(delete_user))
         .route("/users/{user_id}/unlock", post(unlock_user))
+        .route("/users/{user_id}/usage", get(get_user_usage))
+        .route(
+            "/admin/users/{user_id}/verify-email",
+            post(verify_user_email),
+        )
+        .route("/admin/users/normalize-emails", post(normalize_user_emails))
         .route("/ws/stats", get(websocket_stats))
         .route("/ws/connections", get(get_connections))
         .route(
@@ -600,30 +1223,77 @@ async fn create_router(app_state: AppState, serve_args: &ServeArgs) -> Router {
         .route("/admin/backup/health", get(get_backup_health))
         .route("/upload-image", post(upload_image))
         .route("/delete-image", delete(delete_image))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            workspace_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.auth_state.clone(),
+            read_only_middleware,
+        ))
         .layer(middleware::from_fn_with_state(
             app_state.auth_state.clone(),
             auth_middleware,
-        ));
+        ))
+        .layer(middleware::from_fn(csrf_middleware));
 
-    let api_routes = Router::new().merge(public_routes).merge(protected_routes);
-
-    let swagger_router = SwaggerUi::new("/docs").url("/docs/openapi.json", ApiDoc::openapi());
+    let api_routes = Router::new()
+        .merge(public_routes)
+        .merge(protected_routes)
+        .layer(middleware::from_fn_with_state(
+            app_state.auth_state.clone(),
+            body_limit_middleware,
+        ))
+        .layer(DefaultBodyLimit::disable())
+        .layer(middleware::from_fn_with_state(
+            app_state.auth_state.clone(),
+            api_version_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.auth_state.clone(),
+            locale_middleware,
+        ));
 
-    let mut app = Router::new().nest("/api", api_routes).merge(swagger_router);
+    let mut app = Router::new().nest("/api", api_routes);
+
+    if !api_only {
+        // The collections document is served by the API router because it
+        // changes with the collections, so it is only listed here for the selector.
+        let swagger_router = SwaggerUi::new("/docs")
+            .url("/docs/openapi.json", ApiDoc::openapi())
+            .config(SwaggerConfig::new([
+                Url::new("LunarBase API", "/docs/openapi.json"),
+                Url::new(
+                    "LunarBase API with collections",
+                    "/api/api-docs/collections.json",
+                ),
+            ]));
 
-    if !serve_args.api_only {
         app = app
+            .merge(swagger_router)
             .route("/admin", get(serve_embedded_admin_html))
             .route("/admin/", get(serve_embedded_admin_html))
             .route("/admin/{*path}", get(serve_embedded_assets));
     }
 
-    let app = app
-        .route("/metrics", get(get_metrics))
+    app.route("/metrics", get(get_metrics))
         .route("/metrics/summary", get(get_metrics_summary))
-        .with_state(app_state.clone());
+        .with_state(app_state)
+}
 
-    add_middleware(app, app_state).await
+/// Waits for a shutdown signal, then fails readiness probes for `drain_period`
+/// so load balancers stop routing here before the listener closes.
+async fn drain_on_shutdown(readiness: ReadinessState, drain_period: Duration) {
+    shutdown_signal().await;
+
+    readiness.mark_draining();
+    if !drain_period.is_zero() {
+        info!(
+            "Draining for {}s before closing the listeners...",
+            drain_period.as_secs()
+        );
+        tokio::time::sleep(drain_period).await;
+    }
 }
 
 async fn shutdown_signal() {
@@ -1,93 +1,216 @@
+use chrono::NaiveDate;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
-use tracing::{debug, warn};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
 
 use crate::Config;
-use crate::models::{NewUser, User};
-use crate::schema::users;
-use crate::utils::LunarbaseError;
+use crate::database::prepared::{PreparedSql, SqlBind};
+use crate::models::{
+    AdminOverview, AnalyticsGranularity, CollectionOverview, EmailChange, EmailConflict,
+    EmailNormalizationReport, EmailOwner, NewUser, StorageUsage, User, UserAnalytics,
+    UserAnalyticsBucket, UserAnalyticsTotals, UserOverview, WebSocketOverview,
+};
+use crate::schema::{login_events, users};
+use crate::services::password_service::hash_password;
+use crate::services::{
+    CollectionService, HealthService, PasswordHashParams, S3Service, WebSocketService,
+};
+use crate::utils::{LunarbaseError, normalize_email};
 
 type DbPool = Pool<ConnectionManager<SqliteConnection>>;
 
+/// How long a booting replica waits for another one holding the bootstrap lock.
+const BOOTSTRAP_BUSY_TIMEOUT_MS: u32 = 10_000;
+
+/// WebSocket close code sent to connections of a user who was just deactivated.
+pub const ACCOUNT_DEACTIVATED_CLOSE_CODE: u16 = 4003;
+
+/// How long a computed `user_analytics` result is served; dashboards poll it.
+const USER_ANALYTICS_CACHE_TTL: Duration = Duration::from_secs(120);
+
+/// How a user signed in, as recorded in `login_events`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginMethod {
+    Password,
+    OAuth,
+}
+
+impl LoginMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoginMethod::Password => "password",
+            LoginMethod::OAuth => "oauth",
+        }
+    }
+}
+
+/// Which path the startup admin bootstrap took.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminBootstrapOutcome {
+    NotConfigured,
+    Created,
+    PasswordRotated,
+    AlreadyExists,
+    OtherAdminExists,
+}
+
+type UserAnalyticsCache = HashMap<(i64, AnalyticsGranularity), (Instant, UserAnalytics)>;
+
 #[derive(Clone)]
 pub struct AdminService {
     pub pool: DbPool,
+    analytics_cache: Arc<Mutex<UserAnalyticsCache>>,
 }
 
 impl AdminService {
     pub fn new(pool: DbPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            analytics_cache: Arc::default(),
+        }
     }
 
+    /// Creates the admin described by `LUNARBASE_ADMIN_*` when no admin exists yet,
+    /// or rotates its password when `LUNARBASE_ADMIN_FORCE_PASSWORD` is set.
+    /// Safe to run on every startup and from several replicas at once.
     pub async fn ensure_admin_exists(
         &self,
         config: &Config,
         pepper: &str,
-    ) -> Result<(), LunarbaseError> {
-        if !config.has_admin_config() {
-            debug!("No admin configuration provided via environment variables");
-            return Ok(());
-        }
-
-        let admin_email = config.admin_email.as_ref().unwrap();
-        let admin_password = config.admin_password.as_ref().unwrap();
-        let admin_username = config.admin_username.as_ref().unwrap();
+    ) -> Result<AdminBootstrapOutcome, LunarbaseError> {
+        let (Some(admin_email), Some(admin_password), Some(admin_username)) = (
+            config.admin_email.as_ref(),
+            config.admin_password.as_ref(),
+            config.admin_username.as_ref(),
+        ) else {
+            info!("Admin bootstrap skipped: LUNARBASE_ADMIN_* variables are not set");
+            return Ok(AdminBootstrapOutcome::NotConfigured);
+        };
+        let admin_email = &normalize_email(admin_email).ok_or_else(|| {
+            LunarbaseError::ValidationError(vec![
+                "LUNARBASE_ADMIN_EMAIL is not a valid email address".to_string(),
+            ])
+        })?;
 
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
-        let existing_admin = users::table
-            .filter(users::email.eq(admin_email))
-            .select(User::as_select())
-            .first(&mut conn)
-            .optional()
-            .map_err(|_| LunarbaseError::DatabaseError)?;
+        diesel::sql_query(format!(
+            "PRAGMA busy_timeout = {}",
+            BOOTSTRAP_BUSY_TIMEOUT_MS
+        ))
+        .execute(&mut conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
 
-        if existing_admin.is_some() {
-            debug!("Admin user already exists: {}", admin_email);
-            return Ok(());
-        }
+        // The immediate transaction takes SQLite's write lock up front, so a second
+        // replica booting at the same time waits here and then sees the new admin.
+        let outcome = conn.immediate_transaction(|conn| {
+            let existing_user = users::table
+                .filter(users::email.eq(admin_email))
+                .select(User::as_select())
+                .first(conn)
+                .optional()?;
 
-        let any_admin = users::table
-            .filter(users::role.eq("admin"))
-            .select(User::as_select())
-            .first(&mut conn)
-            .optional()
-            .map_err(|_| LunarbaseError::DatabaseError)?;
+            if let Some(user) = existing_user {
+                if user.role != "admin" {
+                    return Ok(AdminBootstrapOutcome::OtherAdminExists);
+                }
 
-        if any_admin.is_some() {
-            warn!(
-                "Admin already exists but with different email. Skipping admin creation from environment variables."
-            );
-            return Ok(());
-        }
+                if !config.admin_force_password {
+                    return Ok(AdminBootstrapOutcome::AlreadyExists);
+                }
 
-        let new_admin = NewUser::new_with_role(
-            admin_email.clone(),
-            admin_password,
-            admin_username.clone(),
-            "admin".to_string(),
-            pepper,
-        )
-        .map_err(|e| {
-            warn!("Failed to create admin user: {}", e);
-            LunarbaseError::InternalError
-        })?;
+                let password_hash =
+                    hash_password(admin_password, pepper, &PasswordHashParams::default())
+                        .map_err(|_| diesel::result::Error::RollbackTransaction)?;
 
-        diesel::insert_into(users::table)
-            .values(&new_admin)
-            .execute(&mut conn)
-            .map_err(|_| LunarbaseError::DatabaseError)?;
+                diesel::update(users::table.find(user.id))
+                    .set((
+                        users::password_hash.eq(&password_hash),
+                        users::failed_login_attempts.eq(0),
+                        users::locked_until.eq::<Option<chrono::NaiveDateTime>>(None),
+                        users::updated_at.eq(chrono::Utc::now().naive_utc()),
+                    ))
+                    .execute(conn)?;
 
-        diesel::update(users::table.filter(users::email.eq(admin_email)))
-            .set(users::is_verified.eq(true))
-            .execute(&mut conn)
-            .map_err(|_| LunarbaseError::DatabaseError)?;
+                return Ok(AdminBootstrapOutcome::PasswordRotated);
+            }
 
-        debug!(
-            "Admin user created successfully: {} ({})",
-            admin_email, admin_username
-        );
-        Ok(())
+            let admin_count = users::table
+                .filter(users::role.eq("admin"))
+                .count()
+                .get_result::<i64>(conn)?;
+
+            if admin_count > 0 {
+                return Ok(AdminBootstrapOutcome::OtherAdminExists);
+            }
+
+            let new_admin = NewUser::new_verified(
+                admin_email.clone(),
+                admin_password,
+                admin_username.clone(),
+                "admin".to_string(),
+                true,
+                pepper,
+            )
+            .map_err(|_| diesel::result::Error::RollbackTransaction)?;
+
+            diesel::insert_into(users::table)
+                .values(&new_admin)
+                .execute(conn)?;
+
+            diesel::update(users::table.filter(users::email.eq(admin_email)))
+                .set(users::is_active.eq(true))
+                .execute(conn)?;
+
+            Ok(AdminBootstrapOutcome::Created)
+        });
+
+        let outcome = match outcome {
+            Ok(outcome) => outcome,
+            // Another process inserted the same admin between our check and insert.
+            Err(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            )) => AdminBootstrapOutcome::AlreadyExists,
+            Err(diesel::result::Error::RollbackTransaction) => {
+                warn!("Admin bootstrap failed: could not hash the configured admin password");
+                return Err(LunarbaseError::InternalError);
+            }
+            Err(_) => return Err(LunarbaseError::DatabaseError),
+        };
+
+        match outcome {
+            AdminBootstrapOutcome::Created => {
+                info!(
+                    "Admin bootstrap: created admin user {} ({})",
+                    admin_email, admin_username
+                );
+            }
+            AdminBootstrapOutcome::PasswordRotated => {
+                info!(
+                    "Admin bootstrap: rotated password for admin user {} (LUNARBASE_ADMIN_FORCE_PASSWORD=true)",
+                    admin_email
+                );
+            }
+            AdminBootstrapOutcome::AlreadyExists => {
+                info!(
+                    "Admin bootstrap: admin user {} already exists, nothing to do",
+                    admin_email
+                );
+            }
+            AdminBootstrapOutcome::OtherAdminExists => {
+                warn!(
+                    "Admin bootstrap: skipped creating {} because an admin already exists or the email belongs to a non-admin user",
+                    admin_email
+                );
+            }
+            AdminBootstrapOutcome::NotConfigured => {}
+        }
+
+        Ok(outcome)
     }
 
     pub async fn get_admin(&self) -> Result<Option<User>, LunarbaseError> {
@@ -107,4 +230,813 @@ impl AdminService {
         let admin = self.get_admin().await?;
         Ok(admin.is_some())
     }
+
+    /// Rewrites stored user emails to their normalized form, which is what
+    /// sign-up and login compare against. Emails that would collide with
+    /// another account's are left alone and reported instead.
+    pub async fn normalize_user_emails(
+        &self,
+        dry_run: bool,
+    ) -> Result<EmailNormalizationReport, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        conn.immediate_transaction(|conn| {
+            let stored: Vec<(i32, String)> = users::table
+                .select((users::id, users::email))
+                .order(users::id.asc())
+                .load(conn)?;
+
+            let mut invalid = Vec::new();
+            let mut by_normalized: BTreeMap<String, Vec<EmailOwner>> = BTreeMap::new();
+            for (user_id, email) in stored {
+                match normalize_email(&email) {
+                    Some(normalized) => by_normalized
+                        .entry(normalized)
+                        .or_default()
+                        .push(EmailOwner { user_id, email }),
+                    None => invalid.push(EmailOwner { user_id, email }),
+                }
+            }
+
+            let mut normalized = Vec::new();
+            let mut conflicts = Vec::new();
+            for (to, mut owners) in by_normalized {
+                if owners.len() > 1 {
+                    conflicts.push(EmailConflict {
+                        normalized: to,
+                        users: owners,
+                    });
+                    continue;
+                }
+                let owner = owners.remove(0);
+                if owner.email != to {
+                    normalized.push(EmailChange {
+                        user_id: owner.user_id,
+                        from: owner.email,
+                        to,
+                    });
+                }
+            }
+
+            if !dry_run {
+                for change in &normalized {
+                    diesel::update(users::table.find(change.user_id))
+                        .set(users::email.eq(&change.to))
+                        .execute(conn)?;
+                }
+            }
+
+            Ok(EmailNormalizationReport {
+                dry_run,
+                normalized,
+                conflicts,
+                invalid,
+            })
+        })
+        .map_err(|_: diesel::result::Error| LunarbaseError::DatabaseError)
+    }
+
+    /// Deactivates `user_id` and cuts off every session it still has: outstanding
+    /// access and refresh tokens stop validating, pending email verification tokens
+    /// are dropped and its live WebSocket connections are closed. Returns the number
+    /// of connections closed.
+    pub async fn deactivate_user(
+        &self,
+        user_id: i32,
+        websocket_service: &WebSocketService,
+    ) -> Result<usize, LunarbaseError> {
+        use crate::schema::verification_tokens;
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let updated = diesel::update(users::table.find(user_id))
+                .set((
+                    users::is_active.eq(false),
+                    users::token_valid_after.eq(Some(chrono::Utc::now().naive_utc())),
+                ))
+                .execute(conn)?;
+            if updated == 0 {
+                return Err(diesel::result::Error::NotFound);
+            }
+
+            diesel::delete(
+                verification_tokens::table.filter(verification_tokens::user_id.eq(user_id)),
+            )
+            .execute(conn)?;
+            Ok(())
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => {
+                LunarbaseError::NotFound("User not found".to_string())
+            }
+            _ => LunarbaseError::DatabaseError,
+        })?;
+
+        let closed = websocket_service
+            .disconnect_user(
+                user_id,
+                ACCOUNT_DEACTIVATED_CLOSE_CODE,
+                "account_deactivated",
+            )
+            .await;
+
+        info!(
+            "Deactivated user {} and closed {} WebSocket connection(s)",
+            user_id, closed
+        );
+
+        Ok(closed)
+    }
+
+    /// Gathers the admin dashboard overview. The sub-queries run concurrently,
+    /// the database ones on the blocking pool; only a failure of the user or
+    /// collection statistics fails the whole call.
+    pub async fn get_overview(
+        &self,
+        collection_service: &CollectionService,
+        websocket_service: &WebSocketService,
+        health_service: &HealthService,
+        s3_service: Option<&S3Service>,
+    ) -> Result<AdminOverview, LunarbaseError> {
+        let admin = self.clone();
+        let user_overview = run_blocking(move || admin.get_user_overview());
+        let admin = self.clone();
+        let database_storage = run_blocking(move || admin.get_database_storage());
+        let (runtime, collections) = (
+            tokio::runtime::Handle::current(),
+            collection_service.clone(),
+        );
+        let collection_stats =
+            run_blocking(move || runtime.block_on(collections.get_collections_stats(None, false)));
+
+        let (
+            users,
+            collection_stats,
+            database_storage,
+            s3_storage,
+            websocket,
+            components,
+            activity,
+        ) = tokio::join!(
+            user_overview,
+            collection_stats,
+            database_storage,
+            get_s3_storage(s3_service),
+            websocket_service.get_stats(),
+            health_service.check_components(),
+            websocket_service.get_activity_log(10, 0),
+        );
+
+        let (total_records, records_per_collection, _, _, largest_collection, _, _) =
+            collection_stats??;
+        let mut components = components;
+
+        Ok(AdminOverview {
+            users: users??,
+            collections: CollectionOverview {
+                total_collections: records_per_collection.len() as i64,
+                total_records,
+                records_per_collection,
+                largest_collection,
+            },
+            storage: [Some(database_storage?), s3_storage]
+                .into_iter()
+                .flatten()
+                .collect(),
+            websocket: WebSocketOverview {
+                connections: websocket.total_connections,
+                authenticated_connections: websocket.authenticated_connections,
+                subscriptions: websocket.total_subscriptions,
+            },
+            backup: components.remove("backup"),
+            email: components.remove("email"),
+            recent_activity: activity.activities,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+
+    pub fn get_user_overview(&self) -> Result<UserOverview, LunarbaseError> {
+        use diesel::dsl::count_star;
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let by_role: Vec<(String, i64)> = users::table
+            .group_by(users::role)
+            .select((users::role, count_star()))
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let active: i64 = users::table
+            .filter(users::is_active.eq(true))
+            .count()
+            .get_result(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        let verified: i64 = users::table
+            .filter(users::is_verified.eq(true))
+            .count()
+            .get_result(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        let locked: i64 = users::table
+            .filter(users::locked_until.gt(chrono::Utc::now().naive_utc()))
+            .count()
+            .get_result(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let total = by_role.iter().map(|(_, count)| count).sum();
+
+        Ok(UserOverview {
+            total,
+            by_role: by_role.into_iter().collect(),
+            active,
+            inactive: total - active,
+            verified,
+            unverified: total - verified,
+            locked,
+        })
+    }
+
+    /// Records a successful sign-in for the analytics series. Failures are
+    /// logged rather than failing the sign-in.
+    pub fn record_login(&self, user_id: i32, method: LoginMethod) {
+        let result = self
+            .pool
+            .get()
+            .map_err(|e| e.to_string())
+            .and_then(|mut conn| {
+                diesel::insert_into(login_events::table)
+                    .values((
+                        login_events::user_id.eq(user_id),
+                        login_events::method.eq(method.as_str()),
+                        login_events::created_at.eq(chrono::Utc::now().naive_utc()),
+                    ))
+                    .execute(&mut conn)
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            warn!("Failed to record login of user {}: {}", user_id, e);
+        }
+    }
+
+    /// User growth, verification and sign-in series over the last
+    /// `window_days` days. Results are cached per window and granularity for
+    /// `USER_ANALYTICS_CACHE_TTL`.
+    pub async fn user_analytics(
+        &self,
+        window_days: i64,
+        granularity: AnalyticsGranularity,
+    ) -> Result<UserAnalytics, LunarbaseError> {
+        let key = (window_days, granularity);
+        if let Some((computed_at, analytics)) = self
+            .analytics_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+            && computed_at.elapsed() < USER_ANALYTICS_CACHE_TTL
+        {
+            return Ok(analytics.clone());
+        }
+
+        let analytics =
+            self.compute_user_analytics(window_days, granularity, chrono::Utc::now().date_naive())?;
+        self.analytics_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, (Instant::now(), analytics.clone()));
+        Ok(analytics)
+    }
+
+    fn compute_user_analytics(
+        &self,
+        window_days: i64,
+        granularity: AnalyticsGranularity,
+        today: NaiveDate,
+    ) -> Result<UserAnalytics, LunarbaseError> {
+        use diesel::sql_types::{BigInt, Text};
+
+        #[derive(QueryableByName)]
+        struct SignupRow {
+            #[diesel(sql_type = Text)]
+            bucket: String,
+            #[diesel(sql_type = BigInt)]
+            new_users: i64,
+            #[diesel(sql_type = BigInt)]
+            verified_users: i64,
+        }
+
+        #[derive(QueryableByName)]
+        struct LoginRow {
+            #[diesel(sql_type = Text)]
+            bucket: String,
+            #[diesel(sql_type = BigInt)]
+            active_users: i64,
+            #[diesel(sql_type = BigInt)]
+            password_logins: i64,
+            #[diesel(sql_type = BigInt)]
+            oauth_logins: i64,
+        }
+
+        #[derive(QueryableByName)]
+        struct LoginTotalsRow {
+            #[diesel(sql_type = BigInt)]
+            active_users: i64,
+            #[diesel(sql_type = BigInt)]
+            password_users: i64,
+            #[diesel(sql_type = BigInt)]
+            oauth_users: i64,
+        }
+
+        let since = granularity.first_bucket(today, window_days);
+        let bucket = granularity.bucket_sql("created_at");
+        let since_timestamp = since.format("%Y-%m-%d 00:00:00").to_string();
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let signups: HashMap<String, SignupRow> = PreparedSql::new(format!(
+            "SELECT {0} AS bucket, COUNT(*) AS new_users, \
+             COALESCE(SUM(is_verified), 0) AS verified_users \
+             FROM users WHERE created_at >= ? GROUP BY {0}",
+            bucket
+        ))
+        .bind(SqlBind::Text(Some(since_timestamp.clone())))
+        .load::<SignupRow>(&mut conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?
+        .into_iter()
+        .map(|row| (row.bucket.clone(), row))
+        .collect();
+
+        let logins: HashMap<String, LoginRow> = PreparedSql::new(format!(
+            "SELECT {0} AS bucket, COUNT(DISTINCT user_id) AS active_users, \
+             COALESCE(SUM(method = 'password'), 0) AS password_logins, \
+             COALESCE(SUM(method = 'oauth'), 0) AS oauth_logins \
+             FROM login_events WHERE created_at >= ? GROUP BY {0}",
+            bucket
+        ))
+        .bind(SqlBind::Text(Some(since_timestamp.clone())))
+        .load::<LoginRow>(&mut conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?
+        .into_iter()
+        .map(|row| (row.bucket.clone(), row))
+        .collect();
+
+        let login_totals = PreparedSql::new(
+            "SELECT COUNT(DISTINCT user_id) AS active_users, \
+             COUNT(DISTINCT CASE WHEN method = 'password' THEN user_id END) AS password_users, \
+             COUNT(DISTINCT CASE WHEN method = 'oauth' THEN user_id END) AS oauth_users \
+             FROM login_events WHERE created_at >= ?",
+        )
+        .bind(SqlBind::Text(Some(since_timestamp)))
+        .load::<LoginTotalsRow>(&mut conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?
+        .into_iter()
+        .next()
+        .ok_or(LunarbaseError::DatabaseError)?;
+
+        let series: Vec<UserAnalyticsBucket> = granularity
+            .bucket_starts(since, today)
+            .into_iter()
+            .map(|start| {
+                let key = start.format("%Y-%m-%d").to_string();
+                let signup = signups.get(&key);
+                let login = logins.get(&key);
+                UserAnalyticsBucket {
+                    new_users: signup.map_or(0, |row| row.new_users),
+                    verified_users: signup.map_or(0, |row| row.verified_users),
+                    active_users: login.map_or(0, |row| row.active_users),
+                    password_logins: login.map_or(0, |row| row.password_logins),
+                    oauth_logins: login.map_or(0, |row| row.oauth_logins),
+                    start: key,
+                }
+            })
+            .collect();
+
+        Ok(UserAnalytics {
+            window_days,
+            granularity,
+            since: since.format("%Y-%m-%d").to_string(),
+            totals: UserAnalyticsTotals {
+                new_users: series.iter().map(|bucket| bucket.new_users).sum(),
+                verified_users: series.iter().map(|bucket| bucket.verified_users).sum(),
+                active_users: login_totals.active_users,
+                password_users: login_totals.password_users,
+                oauth_users: login_totals.oauth_users,
+            },
+            series,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+
+    fn get_database_storage(&self) -> StorageUsage {
+        #[derive(QueryableByName)]
+        struct DatabaseSize {
+            #[diesel(sql_type = diesel::sql_types::BigInt)]
+            bytes: i64,
+        }
+
+        let size = self
+            .pool
+            .get()
+            .map_err(|e| e.to_string())
+            .and_then(|mut conn| {
+                diesel::sql_query(
+                    "SELECT page_count * page_size AS bytes \
+                     FROM pragma_page_count(), pragma_page_size()",
+                )
+                .get_result::<DatabaseSize>(&mut conn)
+                .map_err(|e| e.to_string())
+            });
+
+        match size {
+            Ok(size) => StorageUsage {
+                backend: "database".to_string(),
+                bytes: Some(size.bytes.max(0) as u64),
+                objects: None,
+                error: None,
+            },
+            Err(e) => StorageUsage {
+                backend: "database".to_string(),
+                bytes: None,
+                objects: None,
+                error: Some(e),
+            },
+        }
+    }
+}
+
+/// Runs blocking database work on the blocking pool, so the overview's
+/// queries neither wait for each other nor hold up the async workers.
+async fn run_blocking<T: Send + 'static>(
+    work: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, LunarbaseError> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|_| LunarbaseError::InternalError)
+}
+
+/// Sums every object in the bucket, following the listing across pages.
+async fn get_s3_storage(s3_service: Option<&S3Service>) -> Option<StorageUsage> {
+    let s3_service = s3_service?;
+
+    Some(match s3_service.list_objects("").await {
+        Ok(objects) => StorageUsage {
+            backend: "s3".to_string(),
+            bytes: Some(objects.iter().map(|object| object.size).sum()),
+            objects: Some(objects.len() as u64),
+            error: None,
+        },
+        Err(e) => StorageUsage {
+            backend: "s3".to_string(),
+            bytes: None,
+            objects: None,
+            error: Some(e.to_string()),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::create_pool;
+    use crate::server::MIGRATIONS;
+    use chrono::Datelike;
+    use diesel_migrations::MigrationHarness;
+
+    const PEPPER: &str = "test_pepper";
+
+    fn test_pool() -> DbPool {
+        let path = std::env::temp_dir().join(format!(
+            "lunarbase_admin_{}.sqlite",
+            uuid::Uuid::new_v4().simple()
+        ));
+        let pool = create_pool(path.to_str().unwrap()).expect("Failed to create pool");
+        pool.get()
+            .unwrap()
+            .run_pending_migrations(MIGRATIONS)
+            .expect("Failed to run migrations");
+        pool
+    }
+
+    fn admin_config(password: &str, force_password: bool) -> Config {
+        Config {
+            admin_email: Some("root@example.com".to_string()),
+            admin_password: Some(password.to_string()),
+            admin_username: Some("root".to_string()),
+            admin_force_password: force_password,
+            ..Config::default()
+        }
+    }
+
+    fn load_admin(pool: &DbPool) -> User {
+        users::table
+            .filter(users::email.eq("root@example.com"))
+            .select(User::as_select())
+            .first(&mut pool.get().unwrap())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_without_config_does_nothing() {
+        let service = AdminService::new(test_pool());
+
+        let outcome = service
+            .ensure_admin_exists(&Config::default(), PEPPER)
+            .await
+            .unwrap();
+
+        assert_eq!(outcome, AdminBootstrapOutcome::NotConfigured);
+        assert!(!service.has_admin().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_creates_verified_active_admin_once() {
+        let pool = test_pool();
+        let service = AdminService::new(pool.clone());
+        let config = admin_config("FirstPassword123!", false);
+
+        let first = service.ensure_admin_exists(&config, PEPPER).await.unwrap();
+        let second = service.ensure_admin_exists(&config, PEPPER).await.unwrap();
+
+        assert_eq!(first, AdminBootstrapOutcome::Created);
+        assert_eq!(second, AdminBootstrapOutcome::AlreadyExists);
+
+        let admin = load_admin(&pool);
+        assert_eq!(admin.role, "admin");
+        assert!(admin.is_verified);
+        assert!(admin.is_active);
+        assert!(admin.verify_password("FirstPassword123!", PEPPER).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_rotates_password_only_when_forced() {
+        let pool = test_pool();
+        let service = AdminService::new(pool.clone());
+
+        service
+            .ensure_admin_exists(&admin_config("FirstPassword123!", false), PEPPER)
+            .await
+            .unwrap();
+
+        let unforced = service
+            .ensure_admin_exists(&admin_config("SecondPassword123!", false), PEPPER)
+            .await
+            .unwrap();
+        assert_eq!(unforced, AdminBootstrapOutcome::AlreadyExists);
+        assert!(
+            load_admin(&pool)
+                .verify_password("FirstPassword123!", PEPPER)
+                .unwrap()
+        );
+
+        let forced = service
+            .ensure_admin_exists(&admin_config("SecondPassword123!", true), PEPPER)
+            .await
+            .unwrap();
+        assert_eq!(forced, AdminBootstrapOutcome::PasswordRotated);
+        assert!(
+            load_admin(&pool)
+                .verify_password("SecondPassword123!", PEPPER)
+                .unwrap()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_bootstrap_creates_single_admin() {
+        let pool = test_pool();
+        let config = admin_config("FirstPassword123!", false);
+
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let service = AdminService::new(pool.clone());
+                let config = config.clone();
+                tokio::spawn(async move { service.ensure_admin_exists(&config, PEPPER).await })
+            })
+            .collect();
+
+        let mut outcomes = Vec::new();
+        for handle in handles {
+            outcomes.push(handle.await.unwrap().unwrap());
+        }
+
+        let created = outcomes
+            .iter()
+            .filter(|outcome| **outcome == AdminBootstrapOutcome::Created)
+            .count();
+        assert_eq!(created, 1);
+        assert!(outcomes.contains(&AdminBootstrapOutcome::AlreadyExists));
+
+        let admin_count: i64 = users::table
+            .filter(users::role.eq("admin"))
+            .count()
+            .get_result(&mut pool.get().unwrap())
+            .unwrap();
+        assert_eq!(admin_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_user_overview_counts_roles_and_statuses() {
+        let pool = test_pool();
+        let service = AdminService::new(pool.clone());
+        service
+            .ensure_admin_exists(&admin_config("FirstPassword123!", false), PEPPER)
+            .await
+            .unwrap();
+
+        for (email, verified) in [("a@example.com", true), ("b@example.com", false)] {
+            diesel::insert_into(users::table)
+                .values(&NewUser {
+                    email: email.to_string(),
+                    password_hash: "hash".to_string(),
+                    username: email.split('@').next().unwrap().to_string(),
+                    role: "user".to_string(),
+                    is_verified: verified,
+                    avatar_url: None,
+                })
+                .execute(&mut pool.get().unwrap())
+                .unwrap();
+        }
+        diesel::update(users::table.filter(users::email.eq("b@example.com")))
+            .set((
+                users::is_active.eq(false),
+                users::locked_until.eq(Some(
+                    chrono::Utc::now().naive_utc() + chrono::Duration::hours(1),
+                )),
+            ))
+            .execute(&mut pool.get().unwrap())
+            .unwrap();
+
+        let overview = service.get_user_overview().unwrap();
+
+        assert_eq!(overview.total, 3);
+        assert_eq!(overview.by_role.get("admin"), Some(&1));
+        assert_eq!(overview.by_role.get("user"), Some(&2));
+        assert_eq!((overview.active, overview.inactive), (2, 1));
+        assert_eq!((overview.verified, overview.unverified), (2, 1));
+        assert_eq!(overview.locked, 1);
+    }
+
+    #[tokio::test]
+    async fn test_user_analytics_buckets_signups_and_logins() {
+        let pool = test_pool();
+        let service = AdminService::new(pool.clone());
+        let today = NaiveDate::from_ymd_opt(2025, 9, 24).unwrap();
+        let at = |date: &str| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+        };
+
+        let mut ids = Vec::new();
+        for (email, verified, created) in [
+            ("a@example.com", true, "2025-09-23"),
+            ("b@example.com", false, "2025-09-23"),
+            ("c@example.com", true, "2025-09-10"),
+        ] {
+            diesel::insert_into(users::table)
+                .values(&NewUser {
+                    email: email.to_string(),
+                    password_hash: "hash".to_string(),
+                    username: email.split('@').next().unwrap().to_string(),
+                    role: "user".to_string(),
+                    is_verified: verified,
+                    avatar_url: None,
+                })
+                .execute(&mut pool.get().unwrap())
+                .unwrap();
+            let id: i32 = users::table
+                .filter(users::email.eq(email))
+                .select(users::id)
+                .first(&mut pool.get().unwrap())
+                .unwrap();
+            diesel::update(users::table.find(id))
+                .set(users::created_at.eq(at(created)))
+                .execute(&mut pool.get().unwrap())
+                .unwrap();
+            ids.push(id);
+        }
+
+        for (user_id, method, date) in [
+            (ids[0], LoginMethod::Password, "2025-09-24"),
+            (ids[0], LoginMethod::Password, "2025-09-24"),
+            (ids[1], LoginMethod::OAuth, "2025-09-24"),
+            (ids[2], LoginMethod::Password, "2025-09-10"),
+        ] {
+            diesel::insert_into(login_events::table)
+                .values((
+                    login_events::user_id.eq(user_id),
+                    login_events::method.eq(method.as_str()),
+                    login_events::created_at.eq(at(date)),
+                ))
+                .execute(&mut pool.get().unwrap())
+                .unwrap();
+        }
+
+        let daily = service
+            .compute_user_analytics(7, AnalyticsGranularity::Day, today)
+            .unwrap();
+        assert_eq!(daily.since, "2025-09-18");
+        assert_eq!(daily.series.len(), 7);
+        let signup_day = &daily.series[5];
+        assert_eq!(signup_day.start, "2025-09-23");
+        assert_eq!((signup_day.new_users, signup_day.verified_users), (2, 1));
+        let login_day = &daily.series[6];
+        assert_eq!(login_day.active_users, 2);
+        assert_eq!((login_day.password_logins, login_day.oauth_logins), (2, 1));
+        assert_eq!(daily.totals.new_users, 2);
+        assert_eq!(
+            (
+                daily.totals.active_users,
+                daily.totals.password_users,
+                daily.totals.oauth_users
+            ),
+            (2, 1, 1)
+        );
+
+        let weekly = service
+            .compute_user_analytics(30, AnalyticsGranularity::Week, today)
+            .unwrap();
+        assert_eq!(weekly.since, "2025-08-25");
+        assert!(weekly.series.iter().all(|bucket| {
+            NaiveDate::parse_from_str(&bucket.start, "%Y-%m-%d")
+                .unwrap()
+                .weekday()
+                == chrono::Weekday::Mon
+        }));
+        assert_eq!(weekly.series.last().unwrap().start, "2025-09-22");
+        assert_eq!(weekly.totals.new_users, 3);
+        assert_eq!(weekly.totals.active_users, 3);
+    }
+
+    #[tokio::test]
+    async fn test_database_storage_reports_file_size() {
+        let service = AdminService::new(test_pool());
+
+        let storage = service.get_database_storage();
+
+        assert_eq!(storage.backend, "database");
+        assert!(storage.error.is_none());
+        assert!(storage.bytes.unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_normalize_user_emails_reports_conflicts_and_invalid_emails() {
+        let pool = test_pool();
+        let service = AdminService::new(pool.clone());
+        for (index, email) in [
+            " Ann@Example.COM",
+            "bob@example.com",
+            "Carl@EXAMPLE.com",
+            "Carl@example.com",
+            "not-an-email",
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            diesel::insert_into(users::table)
+                .values(NewUser::with_password_hash(
+                    email.to_string(),
+                    "unused".to_string(),
+                    format!("user_{}", index),
+                    "user".to_string(),
+                ))
+                .execute(&mut pool.get().unwrap())
+                .unwrap();
+        }
+        let stored_emails = || -> Vec<String> {
+            users::table
+                .select(users::email)
+                .order(users::id.asc())
+                .load(&mut pool.get().unwrap())
+                .unwrap()
+        };
+
+        let preview = service.normalize_user_emails(true).await.unwrap();
+        assert!(preview.dry_run);
+        assert_eq!(preview.normalized.len(), 1);
+        assert_eq!(preview.normalized[0].from, " Ann@Example.COM");
+        assert_eq!(preview.normalized[0].to, "Ann@example.com");
+        assert_eq!(preview.conflicts.len(), 1);
+        assert_eq!(preview.conflicts[0].normalized, "Carl@example.com");
+        assert_eq!(preview.conflicts[0].users.len(), 2);
+        assert_eq!(preview.invalid.len(), 1);
+        assert_eq!(preview.invalid[0].email, "not-an-email");
+        assert_eq!(stored_emails()[0], " Ann@Example.COM");
+
+        let report = service.normalize_user_emails(false).await.unwrap();
+        assert_eq!(report.normalized.len(), 1);
+        assert_eq!(
+            stored_emails(),
+            vec![
+                "Ann@example.com",
+                "bob@example.com",
+                "Carl@EXAMPLE.com",
+                "Carl@example.com",
+                "not-an-email"
+            ]
+        );
+
+        let again = service.normalize_user_emails(false).await.unwrap();
+        assert!(again.normalized.is_empty());
+        assert_eq!(again.conflicts.len(), 1);
+    }
 }
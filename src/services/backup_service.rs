@@ -4,14 +4,16 @@ use flate2::write::GzEncoder;
 use std::io::Write;
 use std::sync::Arc;
 use tokio::fs;
+use tokio::sync::RwLock;
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::{debug, error, warn};
 use uuid::Uuid;
 
 use crate::database::DatabasePool;
 use crate::middleware::MetricsState;
-use crate::services::S3Service;
+use crate::models::{NotificationContent, NotificationKind};
 use crate::services::configuration_manager::{ConfigurationAccess, ConfigurationManager};
+use crate::services::{NotificationService, S3Service};
 
 #[derive(Clone)]
 pub struct BackupService {
@@ -20,6 +22,10 @@ pub struct BackupService {
     scheduler: Arc<JobScheduler>,
     config_manager: Arc<ConfigurationManager>,
     metrics_state: Option<Arc<MetricsState>>,
+    /// Tells admins how scheduled and manual backups went
+    notification_service: Option<NotificationService>,
+    last_success: Arc<RwLock<Option<DateTime<Utc>>>>,
+    started_at: DateTime<Utc>,
 }
 
 #[derive(Debug)]
@@ -45,6 +51,8 @@ pub enum BackupError {
     BackupDisabled,
     #[error("Compression error: {0}")]
     CompressionError(String),
+    #[error("Backup could not be uploaded")]
+    UploadFailed,
 }
 
 impl ConfigurationAccess for BackupService {
@@ -59,6 +67,7 @@ impl BackupService {
         s3_service: Option<Arc<S3Service>>,
         config_manager: Arc<ConfigurationManager>,
         metrics_state: Option<Arc<MetricsState>>,
+        notification_service: Option<NotificationService>,
     ) -> Result<Self, BackupError> {
         let scheduler = JobScheduler::new()
             .await
@@ -70,6 +79,9 @@ impl BackupService {
             scheduler: Arc::new(scheduler),
             config_manager,
             metrics_state,
+            notification_service,
+            last_success: Arc::new(RwLock::new(None)),
+            started_at: Utc::now(),
         };
 
         if let Some(ref metrics) = service.metrics_state {
@@ -153,7 +165,8 @@ impl BackupService {
             let service = service_clone.clone();
             Box::pin(async move {
                 debug!("Starting scheduled backup...");
-                match service.create_backup().await {
+                let outcome = service.create_backup().await;
+                match &outcome {
                     Ok(result) => {
                         debug!(
                             "Scheduled backup completed successfully. ID: {}, Size: {} bytes",
@@ -167,6 +180,7 @@ impl BackupService {
                         error!("Scheduled backup failed: {}", e);
                     }
                 }
+                service.notify_admins("Scheduled", &outcome).await;
             })
         })
         .map_err(|e| {
@@ -289,6 +303,10 @@ impl BackupService {
             warn!("Failed to remove temporary backup file: {}", e);
         }
 
+        if s3_url.is_some() {
+            *self.last_success.write().await = Some(timestamp);
+        }
+
         if s3_url.is_some() {
             self.cleanup_old_backups(file_size).await;
         }
@@ -439,7 +457,9 @@ impl BackupService {
 
     pub async fn manual_backup(&self) -> Result<BackupResult, BackupError> {
         debug!("Manual backup requested");
-        let result = self.create_backup().await?;
+        let outcome = self.create_backup().await;
+        self.notify_admins("Manual", &outcome).await;
+        let result = outcome?;
 
         debug!("Running backup cleanup after manual backup...");
         self.cleanup_old_backups(result.file_size).await;
@@ -447,6 +467,39 @@ impl BackupService {
         Ok(result)
     }
 
+    /// A backup that was not uploaded counts as failed. Disabled backups are
+    /// not worth a notification.
+    async fn notify_admins(&self, trigger: &str, outcome: &Result<BackupResult, BackupError>) {
+        let Some(notification_service) = &self.notification_service else {
+            return;
+        };
+
+        let content = match outcome {
+            Ok(result) if result.s3_url.is_some() => NotificationContent::new(
+                NotificationKind::BackupCompleted,
+                format!("{} backup completed", trigger),
+            )
+            .with_data(serde_json::json!({
+                "backup_id": result.backup_id,
+                "file_size": result.file_size,
+                "s3_url": result.s3_url,
+            })),
+            Ok(result) => NotificationContent::new(
+                NotificationKind::BackupFailed,
+                format!("{} backup failed", trigger),
+            )
+            .with_body(BackupError::UploadFailed.to_string())
+            .with_data(serde_json::json!({ "backup_id": result.backup_id })),
+            Err(BackupError::BackupDisabled) => return,
+            Err(e) => NotificationContent::new(
+                NotificationKind::BackupFailed,
+                format!("{} backup failed", trigger),
+            )
+            .with_body(e.to_string()),
+        };
+        notification_service.notify_admins(content).await;
+    }
+
     pub async fn manual_cleanup(&self) {
         debug!("Manual cleanup requested");
         self.cleanup_old_backups(0).await;
@@ -475,6 +528,58 @@ impl BackupService {
         }
     }
 
+    /// Time of the most recent successful backup. Falls back to the newest
+    /// object in the backup bucket when nothing has been recorded since startup.
+    pub async fn last_successful_backup(&self) -> Option<DateTime<Utc>> {
+        if let Some(last_success) = *self.last_success.read().await {
+            return Some(last_success);
+        }
+
+        let s3_service = self.s3_service.as_ref()?;
+        let backup_prefix = format!("backups/{}", self.get_backup_prefix().await);
+        let latest = s3_service
+            .list_objects(&backup_prefix)
+            .await
+            .ok()?
+            .into_iter()
+            .map(|object| object.last_modified)
+            .max();
+
+        if latest.is_some() {
+            *self.last_success.write().await = latest;
+        }
+
+        latest
+    }
+
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    /// A service that only backs up on request.
+    async fn unscheduled(
+        db_pool: DatabasePool,
+        s3_service: Option<Arc<S3Service>>,
+        config_manager: Arc<ConfigurationManager>,
+        metrics_state: Option<Arc<MetricsState>>,
+        notification_service: Option<NotificationService>,
+    ) -> Result<Self, BackupError> {
+        Ok(Self {
+            db_pool,
+            s3_service,
+            scheduler: Arc::new(
+                JobScheduler::new()
+                    .await
+                    .map_err(|e| BackupError::SchedulerError(e.to_string()))?,
+            ),
+            config_manager,
+            metrics_state,
+            notification_service,
+            last_success: Arc::new(RwLock::new(None)),
+            started_at: Utc::now(),
+        })
+    }
+
     pub async fn stop(&self) -> Result<(), BackupError> {
         debug!("Backup service stopped");
         Ok(())
@@ -486,18 +591,16 @@ pub async fn create_backup_service_from_config(
     s3_service: Option<Arc<S3Service>>,
     config_manager: Arc<ConfigurationManager>,
     metrics_state: Option<Arc<MetricsState>>,
+    notification_service: Option<NotificationService>,
 ) -> Result<Option<BackupService>, BackupError> {
-    let temp_service = BackupService {
-        db_pool: db_pool.clone(),
-        s3_service: s3_service.clone(),
-        scheduler: Arc::new(
-            JobScheduler::new()
-                .await
-                .map_err(|e| BackupError::SchedulerError(e.to_string()))?,
-        ),
-        config_manager: config_manager.clone(),
-        metrics_state: metrics_state.clone(),
-    };
+    let temp_service = BackupService::unscheduled(
+        db_pool.clone(),
+        s3_service.clone(),
+        config_manager.clone(),
+        metrics_state.clone(),
+        None,
+    )
+    .await?;
 
     let backup_enabled = temp_service.get_backup_enabled().await;
     if !backup_enabled {
@@ -521,6 +624,43 @@ pub async fn create_backup_service_from_config(
         return Ok(None);
     }
 
-    let service = BackupService::new(db_pool, s3_service, config_manager, metrics_state).await?;
+    let service = BackupService::new(
+        db_pool,
+        s3_service,
+        config_manager,
+        metrics_state,
+        notification_service,
+    )
+    .await?;
     Ok(Some(service))
 }
+
+/// Backs the database up once before migrations change it, without
+/// scheduling further backups. `None` when backups are disabled or S3 is not
+/// configured; a backup that could not be uploaded is an error.
+pub async fn create_pre_migration_backup(
+    db_pool: DatabasePool,
+    s3_service: Option<Arc<S3Service>>,
+    config_manager: Arc<ConfigurationManager>,
+) -> Result<Option<BackupResult>, BackupError> {
+    if s3_service.is_none() {
+        return Ok(None);
+    }
+
+    let service =
+        BackupService::unscheduled(db_pool, s3_service, config_manager, None, None).await?;
+    let s3_enabled = service
+        .config_manager
+        .get_bool("storage", "s3_enabled")
+        .await
+        .unwrap_or(false);
+    if !service.get_backup_enabled().await || !s3_enabled {
+        return Ok(None);
+    }
+
+    let result = service.create_backup().await?;
+    if result.s3_url.is_none() {
+        return Err(BackupError::UploadFailed);
+    }
+    Ok(Some(result))
+}
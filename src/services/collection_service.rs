@@ -1,38 +1,116 @@
+use crate::canonical_json::{CANONICALIZATION, canonical_sha256};
+use crate::database::prepared::{PreparedSql, SqlBind};
+use crate::database::transaction::transaction_then;
+use crate::decimal::{MAX_DECIMAL_PRECISION, decimal_text, format_minor_units, parse_minor_units};
+use crate::middleware::MetricsState;
 use crate::models::{
-    Collection, CollectionResponse, CollectionSchema, CreateCollectionRequest, CreateRecordRequest,
-    FieldDefinition, FieldType, FileUpload, NewCollection, RecordResponse, Role,
-    SetCollectionPermissionRequest, UpdateCollection, UpdateCollectionRequest, UpdateRecordRequest,
+    BatchMethod, BatchOperation, BatchOperationResult, Collection, CollectionDeleteImpact,
+    CollectionEvent, CollectionFields, CollectionHealthReport, CollectionIntegrityReport,
+    CollectionListEntry, CollectionRecordCount, CollectionReferences, CollectionRepairReport,
+    CollectionResponse, CollectionSchema, CollectionSchemaVersion, CollectionSchemaVersionResponse,
+    CollectionWorkflow, CreateCollectionRequest, CreateRecordRequest, DEFAULT_WORKSPACE_ID,
+    ExpireAction, FieldDefinition, FieldType, FieldValidationError, FileUpload, IntegrityIssue,
+    IntegrityIssueKind, ManifestVerification, MoveRecordRequest, NewCollection,
+    NewCollectionSchemaVersion, NewGuestSessionRecord, NumberFormat, OrphanObject,
+    OrphanObjectKind, OrphanSweepReport, Permission, PermissionSet, PublishRecordRequest,
+    RecordActivityKind, RecordExport, RecordHash, RecordIdType, RecordManifest, RecordResponse,
+    RecordSchedule, RecordScheduleReport, RecordStatus, RetentionPolicy, RetentionReport,
+    RetentionRun, RetentionRunEntry, Role, ScheduledAction, ScheduledOperation,
+    SetCollectionPermissionRequest, USERS_SYSTEM_COLLECTION, UnpublishRecordRequest,
+    UpdateCollection, UpdateCollectionRequest, UpdateRecordRequest, User, geo_point_columns,
+    is_valid_json_path, json_path_column, records_table_name, sqlite_json_path,
 };
 use crate::query_engine::QueryEngine;
-use crate::schema::{collections, roles};
-use crate::services::S3Service;
-use crate::services::{ConfigurationManager, PermissionService};
-use crate::utils::LunarbaseError;
+use crate::schema::{
+    collection_activity, collection_record_counts, collection_retention_runs,
+    collection_schema_versions, collections, guest_session_records, ingest_endpoints,
+    record_shares, roles,
+};
+use crate::services::health_service::{
+    COMPONENT_DEGRADED, COMPONENT_DISABLED, COMPONENT_HEALTHY, COMPONENT_UNHEALTHY, worst_status,
+};
+use crate::services::{
+    CachedQueryResult, ConfigurationAccess, ConfigurationManager, PermissionService, QueryCache,
+    RecordActivityService, RecordCache,
+};
+use crate::services::{ComponentHealth, S3Service, UploadOrigin, UploadScanner};
+use crate::slug::{slugify, unique_slug};
+use crate::utils::{LunarbaseError, Message, normalize_email};
 use base64::Engine;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use serde_json::{Map, Value};
+use std::sync::{Arc, RwLock};
 use tracing::debug;
 
 type DbPool = Pool<ConnectionManager<SqliteConnection>>;
 
+const MAX_QUERY_CACHE_TTL_SECONDS: i32 = 3600;
+
+/// Records-table column holding the manual position in orderable collections.
+const SORT_ORDER_COLUMN: &str = "sort_order";
+/// Records-table columns managed by the draft/publish workflow.
+const STATUS_COLUMN: &str = "status";
+const PUBLISH_AT_COLUMN: &str = "publish_at";
+/// How often the record scheduler re-reads its interval while it is disabled.
+const DISABLED_SCHEDULER_POLL_SECONDS: u64 = 60;
+/// Batches of `database.retention_batch_size` records one retention run
+/// handles per collection; the next run continues with the rest.
+const RETENTION_BATCHES_PER_RUN: usize = 20;
+/// Retention runs kept per collection for the admin report.
+const RETENTION_RUNS_KEPT: i64 = 50;
+/// Upper bound of `max_age_days`, about a hundred years.
+const MAX_RETENTION_DAYS: u32 = 36_500;
+/// Temporary tables younger than this may belong to a schema change still
+/// running, so sweeps on demand leave them alone.
+const ORPHAN_MIN_AGE_SECONDS: i64 = 300;
+/// Spacing between neighbours after a renormalization, and the step used to
+/// place a record past the first or last one.
+const SORT_ORDER_GAP: f64 = 1024.0;
+/// Once two neighbours are closer than this, midpoints lose precision and the
+/// whole collection is renumbered.
+const MIN_SORT_ORDER_GAP: f64 = 1e-6;
+
+/// Hash algorithm of record export manifests.
+const MANIFEST_ALGORITHM: &str = "sha256";
+
 #[derive(Clone)]
 pub struct CollectionService {
     pub pool: DbPool,
     pub websocket_service: Option<std::sync::Arc<crate::services::WebSocketService>>,
     pub permission_service: Option<PermissionService>,
     pub s3_service: Option<S3Service>,
+    pub record_activity: Option<RecordActivityService>,
+    pub upload_scanner: UploadScanner,
     pub config_manager: ConfigurationManager,
+    pub record_cache: RecordCache,
+    pub query_cache: QueryCache,
+    /// Records table of each collection looked up so far, by collection name.
+    records_tables: Arc<RwLock<std::collections::HashMap<String, String>>>,
+    last_orphan_sweep: Arc<RwLock<Option<OrphanSweepReport>>>,
+}
+
+impl ConfigurationAccess for CollectionService {
+    fn config_manager(&self) -> &ConfigurationManager {
+        &self.config_manager
+    }
 }
 
 impl CollectionService {
     pub fn new(pool: DbPool, config_manager: ConfigurationManager) -> Self {
+        let upload_scanner = UploadScanner::new(pool.clone(), config_manager.clone());
         Self {
             pool,
             websocket_service: None,
             permission_service: None,
             s3_service: None,
+            record_activity: None,
+            upload_scanner,
             config_manager,
+            record_cache: RecordCache::new(),
+            query_cache: QueryCache::new(),
+            records_tables: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            last_orphan_sweep: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -54,12 +132,39 @@ impl CollectionService {
         self
     }
 
+    pub fn with_record_activity_service(mut self, record_activity: RecordActivityService) -> Self {
+        self.record_activity = Some(record_activity);
+        self
+    }
+
     async fn emit_record_event(
         &self,
         collection_name: &str,
         event: crate::models::RecordEvent,
         user_id: Option<i32>,
     ) {
+        self.query_cache.invalidate_collection(collection_name);
+        match &event {
+            crate::models::RecordEvent::Updated { record_id, .. }
+            | crate::models::RecordEvent::Deleted { record_id, .. }
+            | crate::models::RecordEvent::OwnershipTransferred { record_id, .. }
+            | crate::models::RecordEvent::Reordered { record_id, .. }
+            | crate::models::RecordEvent::Published { record_id, .. }
+            | crate::models::RecordEvent::Unpublished { record_id, .. } => {
+                self.record_cache.invalidate(collection_name, record_id);
+            }
+            crate::models::RecordEvent::Created { .. } => {}
+        }
+
+        if let Some(record_activity) = &self.record_activity {
+            let kind = match &event {
+                crate::models::RecordEvent::Created { .. } => RecordActivityKind::Created,
+                crate::models::RecordEvent::Deleted { .. } => RecordActivityKind::Deleted,
+                _ => RecordActivityKind::Updated,
+            };
+            record_activity.record(collection_name, kind).await;
+        }
+
         if let Some(ws_service) = &self.websocket_service {
             let pending_event = crate::models::PendingEvent {
                 collection_name: collection_name.to_string(),
@@ -73,8 +178,53 @@ impl CollectionService {
         }
     }
 
-    fn get_records_table_name(&self, collection_name: &str) -> String {
-        format!("records_{}", collection_name)
+    async fn emit_collection_event(&self, collection_id: i32, event: CollectionEvent) {
+        if let Some(ws_service) = &self.websocket_service {
+            ws_service
+                .broadcast_collection_event(collection_id, event)
+                .await;
+        }
+    }
+
+    /// Tables outside the default workspace carry a workspace prefix, so the
+    /// name is looked up once per collection and kept until it is renamed,
+    /// moved or deleted.
+    pub fn get_records_table_name(&self, collection_name: &str) -> String {
+        if let Some(table_name) = self.records_tables.read().unwrap().get(collection_name) {
+            return table_name.clone();
+        }
+
+        let Some(workspace_id) = self
+            .pool
+            .get()
+            .ok()
+            .and_then(|mut conn| collection_workspace_id(&mut conn, collection_name))
+        else {
+            return records_table_name(DEFAULT_WORKSPACE_ID, collection_name);
+        };
+
+        let table_name = records_table_name(workspace_id, collection_name);
+        self.records_tables
+            .write()
+            .unwrap()
+            .insert(collection_name.to_string(), table_name.clone());
+        table_name
+    }
+
+    /// Workspace of a collection, or `None` for system collections, which
+    /// every workspace shares, and for unknown names.
+    pub fn workspace_of(&self, collection_name: &str) -> Option<i32> {
+        let mut conn = self.pool.get().ok()?;
+        collections::table
+            .filter(collections::name.eq(collection_name))
+            .filter(collections::is_system.eq(false))
+            .select(collections::workspace_id)
+            .first(&mut conn)
+            .ok()
+    }
+
+    fn forget_records_table(&self, collection_name: &str) {
+        self.records_tables.write().unwrap().remove(collection_name);
     }
 
     fn map_field_type_to_sql(&self, field_type: &FieldType) -> &'static str {
@@ -84,21 +234,82 @@ impl CollectionService {
             FieldType::Boolean => "BOOLEAN",
             FieldType::Date => "TIMESTAMP",
             FieldType::Email => "TEXT",
-            FieldType::Url => "TEXT",
+            FieldType::Url | FieldType::Slug => "TEXT",
             FieldType::Json | FieldType::RichText => "TEXT",
             FieldType::File => "TEXT",
             FieldType::Relation => "TEXT",
+            FieldType::GeoPoint => "REAL",
+            FieldType::Decimal => "INTEGER",
+        }
+    }
+
+    /// Definitions of the latitude and longitude columns backing a `geopoint`
+    /// field. With `fill_required`, required columns default to 0 so they can
+    /// be added to a populated table.
+    fn geo_point_column_defs(&self, field: &FieldDefinition, fill_required: bool) -> Vec<String> {
+        let not_null = if field.required { " NOT NULL" } else { "" };
+        let default = field.default_value.as_ref().and_then(geo_point_coordinates);
+        let (lat_column, lng_column) = geo_point_columns(&field.name);
+
+        [
+            (lat_column, default.map(|(lat, _)| lat)),
+            (lng_column, default.map(|(_, lng)| lng)),
+        ]
+        .into_iter()
+        .map(|(column, default)| {
+            let default_clause = match default {
+                Some(value) => format!(" DEFAULT {}", value),
+                None if field.required && fill_required => " DEFAULT 0".to_string(),
+                None => String::new(),
+            };
+            format!("{} REAL{}{}", column, not_null, default_clause)
+        })
+        .collect()
+    }
+
+    /// Virtual generated column extracting `path` of the json field `field_name`.
+    fn json_path_column_def(&self, field_name: &str, path: &str) -> String {
+        format!(
+            "{} GENERATED ALWAYS AS (json_extract({}, '{}')) VIRTUAL",
+            json_path_column(field_name, path),
+            field_name,
+            sqlite_json_path(path)
+        )
+    }
+
+    /// Indexes the generated columns of every indexed json path in `schema`.
+    fn create_json_path_indexes(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+        schema: &CollectionSchema,
+    ) -> Result<(), LunarbaseError> {
+        for field in &schema.fields {
+            for path in field.indexed_paths.iter().flatten() {
+                let column = json_path_column(&field.name, path);
+                diesel::sql_query(format!(
+                    "CREATE INDEX IF NOT EXISTS idx_{}_{} ON {} ({})",
+                    table_name, column, table_name, column
+                ))
+                .execute(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to index json path column {}: {:?}", column, e);
+                    LunarbaseError::InternalError
+                })?;
+            }
         }
+        Ok(())
     }
 
     fn generate_create_table_sql(
         &self,
         collection_name: &str,
         schema: &CollectionSchema,
+        id_type: RecordIdType,
     ) -> String {
         let table_name = self.get_records_table_name(collection_name);
         let mut sql = format!("CREATE TABLE {} (\n", table_name);
-        sql.push_str("    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,\n");
+        sql.push_str(&format!("    {},\n", primary_key_column_def(id_type)));
 
         for field in &schema.fields {
             if field.name.to_lowercase() == "id"
@@ -110,6 +321,13 @@ impl CollectionService {
                 continue;
             }
 
+            if field.field_type == FieldType::GeoPoint {
+                for column_def in self.geo_point_column_defs(field, false) {
+                    sql.push_str(&format!("    {},\n", column_def));
+                }
+                continue;
+            }
+
             let field_type = self.map_field_type_to_sql(&field.field_type);
             let not_null = if field.required { " NOT NULL" } else { "" };
 
@@ -146,6 +364,12 @@ impl CollectionService {
                 "    {} {}{}{},\n",
                 field.name, field_type, not_null, default_clause
             ));
+            for path in field.indexed_paths.iter().flatten() {
+                sql.push_str(&format!(
+                    "    {},\n",
+                    self.json_path_column_def(&field.name, path)
+                ));
+            }
         }
 
         sql.push_str("    author_id INTEGER,\n");
@@ -162,12 +386,13 @@ impl CollectionService {
         conn: &mut SqliteConnection,
         collection_name: &str,
         schema: &CollectionSchema,
+        id_type: RecordIdType,
     ) -> Result<(), LunarbaseError> {
         tracing::debug!(
             "Generating CREATE TABLE SQL for collection: {}",
             collection_name
         );
-        let create_sql = self.generate_create_table_sql(collection_name, schema);
+        let create_sql = self.generate_create_table_sql(collection_name, schema, id_type);
         tracing::debug!("Generated SQL: {}", create_sql);
 
         tracing::debug!("Executing CREATE TABLE statement");
@@ -188,6 +413,7 @@ impl CollectionService {
             LunarbaseError::InternalError
         })?;
         tracing::debug!("Index created successfully");
+        self.create_json_path_indexes(conn, &table_name, schema)?;
 
         let trigger_sql = format!(
             "CREATE TRIGGER update_{}_updated_at 
@@ -238,6 +464,7 @@ impl CollectionService {
         collection_name: &str,
         old_schema: &CollectionSchema,
         new_schema: &CollectionSchema,
+        id_type: RecordIdType,
     ) -> Result<(), LunarbaseError> {
         let table_name = self.get_records_table_name(collection_name);
 
@@ -265,79 +492,143 @@ impl CollectionService {
                 "Dropping columns {:?} from table {}, using table recreation strategy",
                 fields_to_drop, table_name
             );
-            return self.recreate_table_with_schema(conn, collection_name, new_schema);
+            return self.recreate_table_with_schema(conn, collection_name, new_schema, id_type);
+        }
+
+        // Generated columns of json paths no longer indexed are dropped the same way
+        let drops_indexed_path = old_fields.values().any(|old_field| {
+            old_field.indexed_paths.iter().flatten().any(|path| {
+                !new_fields.get(&old_field.name).is_some_and(|new_field| {
+                    new_field.field_type == FieldType::Json
+                        && new_field.indexed_paths.iter().flatten().any(|p| p == path)
+                })
+            })
+        });
+        if drops_indexed_path {
+            debug!(
+                "Dropping json path columns from table {}, using table recreation strategy",
+                table_name
+            );
+            return self.recreate_table_with_schema(conn, collection_name, new_schema, id_type);
         }
 
         for field in &new_schema.fields {
             if !old_fields.contains_key(&field.name) && field.name.to_lowercase() != "id" {
-                let field_type = self.map_field_type_to_sql(&field.field_type);
-                let not_null = if field.required { " NOT NULL" } else { "" };
-
-                let default_clause = if let Some(default_value) = &field.default_value {
-                    match field.field_type {
-                        FieldType::Text | FieldType::Email | FieldType::Url => {
-                            if let Some(s) = default_value.as_str() {
-                                format!(" DEFAULT '{}'", s.replace("'", "''"))
-                            } else {
-                                String::new()
-                            }
-                        }
-                        FieldType::Number => {
-                            if let Some(n) = default_value.as_f64() {
-                                format!(" DEFAULT {}", n)
-                            } else {
-                                String::new()
-                            }
-                        }
-                        FieldType::Boolean => {
-                            if let Some(b) = default_value.as_bool() {
-                                format!(" DEFAULT {}", if b { "1" } else { "0" })
-                            } else {
-                                String::new()
-                            }
-                        }
-                        _ => String::new(),
-                    }
-                } else if field.required {
-                    match field.field_type {
-                        FieldType::Text
-                        | FieldType::Email
-                        | FieldType::Url
-                        | FieldType::File
-                        | FieldType::Relation => " DEFAULT ''".to_string(),
-                        FieldType::Number => " DEFAULT 0".to_string(),
-                        FieldType::Boolean => " DEFAULT 0".to_string(),
-                        FieldType::Json | FieldType::RichText => " DEFAULT '{}'".to_string(),
-                        FieldType::Date => " DEFAULT CURRENT_TIMESTAMP".to_string(),
-                    }
-                } else {
-                    String::new()
-                };
-
-                let add_column_sql = format!(
-                    "ALTER TABLE {} ADD COLUMN {} {}{}{}",
-                    table_name, field.name, field_type, not_null, default_clause
-                );
+                for add_column_sql in self.generate_add_column_sql(&table_name, field) {
+                    tracing::debug!("Adding column with SQL: {}", add_column_sql);
+                    diesel::sql_query(&add_column_sql)
+                        .execute(conn)
+                        .map_err(|e| {
+                            tracing::error!("Failed to add column {}: {:?}", field.name, e);
+                            LunarbaseError::InternalError
+                        })?;
+                }
+            }
 
-                tracing::debug!("Adding column with SQL: {}", add_column_sql);
-                diesel::sql_query(&add_column_sql)
-                    .execute(conn)
-                    .map_err(|e| {
-                        tracing::error!("Failed to add column {}: {:?}", field.name, e);
-                        LunarbaseError::InternalError
-                    })?;
+            let indexed_before = old_fields
+                .get(&field.name)
+                .and_then(|old_field| old_field.indexed_paths.as_ref());
+            for path in field.indexed_paths.iter().flatten() {
+                if indexed_before.is_some_and(|before| before.contains(path)) {
+                    continue;
+                }
+                diesel::sql_query(format!(
+                    "ALTER TABLE {} ADD COLUMN {}",
+                    table_name,
+                    self.json_path_column_def(&field.name, path)
+                ))
+                .execute(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to add json path column: {:?}", e);
+                    LunarbaseError::InternalError
+                })?;
             }
         }
+        self.create_json_path_indexes(conn, &table_name, new_schema)?;
 
         tracing::debug!("Schema update completed for table: {}", table_name);
         Ok(())
     }
 
+    /// One statement per column backing `field`, in `storage_columns` order.
+    /// Required columns get a type-appropriate default since SQLite rejects
+    /// adding a NOT NULL column without one to a populated table.
+    fn generate_add_column_sql(&self, table_name: &str, field: &FieldDefinition) -> Vec<String> {
+        if field.field_type == FieldType::GeoPoint {
+            return self
+                .geo_point_column_defs(field, true)
+                .into_iter()
+                .map(|column_def| format!("ALTER TABLE {} ADD COLUMN {}", table_name, column_def))
+                .collect();
+        }
+
+        let field_type = self.map_field_type_to_sql(&field.field_type);
+        let not_null = if field.required { " NOT NULL" } else { "" };
+
+        let default_clause = if let Some(default_value) = &field.default_value {
+            match field.field_type {
+                FieldType::Text | FieldType::Email | FieldType::Url => {
+                    if let Some(s) = default_value.as_str() {
+                        format!(" DEFAULT '{}'", s.replace("'", "''"))
+                    } else {
+                        String::new()
+                    }
+                }
+                FieldType::Number => {
+                    if let Some(n) = default_value.as_f64() {
+                        format!(" DEFAULT {}", n)
+                    } else {
+                        String::new()
+                    }
+                }
+                FieldType::Boolean => {
+                    if let Some(b) = default_value.as_bool() {
+                        format!(" DEFAULT {}", if b { "1" } else { "0" })
+                    } else {
+                        String::new()
+                    }
+                }
+                FieldType::Decimal => {
+                    let (precision, scale) = field.decimal_precision_and_scale();
+                    match decimal_text(default_value)
+                        .and_then(|text| parse_minor_units(&text, precision, scale).ok())
+                    {
+                        Some(minor_units) => format!(" DEFAULT {}", minor_units),
+                        None => String::new(),
+                    }
+                }
+                _ => String::new(),
+            }
+        } else if field.required {
+            match field.field_type {
+                FieldType::Text
+                | FieldType::Email
+                | FieldType::Url
+                | FieldType::File
+                | FieldType::Relation
+                | FieldType::Slug => " DEFAULT ''".to_string(),
+                FieldType::Number | FieldType::Decimal => " DEFAULT 0".to_string(),
+                FieldType::Boolean => " DEFAULT 0".to_string(),
+                FieldType::Json | FieldType::RichText => " DEFAULT '{}'".to_string(),
+                FieldType::Date => " DEFAULT CURRENT_TIMESTAMP".to_string(),
+                FieldType::GeoPoint => String::new(),
+            }
+        } else {
+            String::new()
+        };
+
+        vec![format!(
+            "ALTER TABLE {} ADD COLUMN {} {}{}{}",
+            table_name, field.name, field_type, not_null, default_clause
+        )]
+    }
+
     fn recreate_table_with_schema(
         &self,
         conn: &mut SqliteConnection,
         collection_name: &str,
         new_schema: &CollectionSchema,
+        id_type: RecordIdType,
     ) -> Result<(), LunarbaseError> {
         let table_name = self.get_records_table_name(collection_name);
         let temp_table_name = format!("{}_temp_{}", table_name, chrono::Utc::now().timestamp());
@@ -345,7 +636,7 @@ impl CollectionService {
         debug!("Starting table recreation for {}", table_name);
 
         let create_temp_sql =
-            self.generate_create_table_sql_with_name(&temp_table_name, new_schema);
+            self.generate_create_table_sql_with_name(&temp_table_name, new_schema, id_type);
         tracing::debug!("Creating temporary table with SQL: {}", create_temp_sql);
         diesel::sql_query(&create_temp_sql)
             .execute(conn)
@@ -357,6 +648,29 @@ impl CollectionService {
         let common_columns = self.get_common_columns(collection_name, new_schema, conn)?;
         let columns_list = common_columns.join(", ");
 
+        // An orderable collection's position column is not part of its schema
+        let keeps_sort_order = common_columns.iter().any(|c| c == SORT_ORDER_COLUMN)
+            && !new_schema
+                .fields
+                .iter()
+                .any(|f| f.name == SORT_ORDER_COLUMN);
+        if keeps_sort_order {
+            diesel::sql_query(format!(
+                "ALTER TABLE {} ADD COLUMN {} REAL",
+                temp_table_name, SORT_ORDER_COLUMN
+            ))
+            .execute(conn)
+            .map_err(|e| {
+                tracing::error!("Failed to add sort_order to temporary table: {:?}", e);
+                LunarbaseError::InternalError
+            })?;
+        }
+        // Neither are the workflow's status columns
+        let keeps_workflow = common_columns.iter().any(|c| c == STATUS_COLUMN);
+        if keeps_workflow {
+            self.add_workflow_columns(conn, &temp_table_name)?;
+        }
+
         let copy_data_sql = format!(
             "INSERT INTO {} ({}) SELECT {} FROM {}",
             temp_table_name, columns_list, columns_list, table_name
@@ -382,6 +696,13 @@ impl CollectionService {
         })?;
 
         self.create_table_indexes_and_triggers(conn, collection_name)?;
+        self.create_json_path_indexes(conn, &table_name, new_schema)?;
+        if keeps_sort_order {
+            self.create_sort_order_index(conn, &table_name)?;
+        }
+        if keeps_workflow {
+            self.create_status_index(conn, &table_name)?;
+        }
 
         debug!("Table recreation completed successfully for {}", table_name);
         Ok(())
@@ -391,9 +712,10 @@ impl CollectionService {
         &self,
         table_name: &str,
         schema: &CollectionSchema,
+        id_type: RecordIdType,
     ) -> String {
         let mut sql = format!("CREATE TABLE {} (\n", table_name);
-        sql.push_str("    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,\n");
+        sql.push_str(&format!("    {},\n", primary_key_column_def(id_type)));
 
         for field in &schema.fields {
             if field.name.to_lowercase() == "id"
@@ -405,6 +727,13 @@ impl CollectionService {
                 continue;
             }
 
+            if field.field_type == FieldType::GeoPoint {
+                for column_def in self.geo_point_column_defs(field, false) {
+                    sql.push_str(&format!("    {},\n", column_def));
+                }
+                continue;
+            }
+
             let field_type = self.map_field_type_to_sql(&field.field_type);
             let not_null = if field.required { " NOT NULL" } else { "" };
 
@@ -441,6 +770,12 @@ impl CollectionService {
                 "    {} {}{}{},\n",
                 field.name, field_type, not_null, default_clause
             ));
+            for path in field.indexed_paths.iter().flatten() {
+                sql.push_str(&format!(
+                    "    {},\n",
+                    self.json_path_column_def(&field.name, path)
+                ));
+            }
         }
 
         sql.push_str("    author_id INTEGER,\n");
@@ -490,9 +825,32 @@ impl CollectionService {
             }
         }
 
+        if existing_column_names.contains(SORT_ORDER_COLUMN)
+            && !new_schema
+                .fields
+                .iter()
+                .any(|f| f.name == SORT_ORDER_COLUMN)
+        {
+            common_columns.push(SORT_ORDER_COLUMN.to_string());
+        }
+
+        if existing_column_names.contains(STATUS_COLUMN)
+            && !new_schema.fields.iter().any(|f| f.name == STATUS_COLUMN)
+        {
+            common_columns.push(STATUS_COLUMN.to_string());
+            if existing_column_names.contains(PUBLISH_AT_COLUMN) {
+                common_columns.push(PUBLISH_AT_COLUMN.to_string());
+            }
+        }
+
         for field in &new_schema.fields {
-            if field.name.to_lowercase() != "id" && existing_column_names.contains(&field.name) {
-                common_columns.push(field.name.clone());
+            if field.name.to_lowercase() == "id" {
+                continue;
+            }
+            for column in field.storage_columns() {
+                if existing_column_names.contains(&column) {
+                    common_columns.push(column);
+                }
             }
         }
 
@@ -534,166 +892,475 @@ impl CollectionService {
         Ok(())
     }
 
-    async fn create_default_permissions(&self, collection_id: i32) -> Result<(), LunarbaseError> {
-        if let Some(permission_service) = &self.permission_service {
-            let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
-
-            let roles = roles::table
-                .load::<Role>(&mut conn)
-                .map_err(|_| LunarbaseError::InternalError)?;
+    fn table_has_column(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+        column_name: &str,
+    ) -> Result<bool, LunarbaseError> {
+        #[derive(Debug, diesel::QueryableByName)]
+        struct CountResult {
+            #[diesel(sql_type = diesel::sql_types::BigInt)]
+            count: i64,
+        }
 
-            for role in roles {
-                let permissions = match role.name.as_str() {
-                    "admin" => SetCollectionPermissionRequest {
-                        role_name: role.name.clone(),
-                        can_create: true,
-                        can_read: true,
-                        can_update: true,
-                        can_delete: true,
-                        can_list: true,
-                    },
-                    "user" => SetCollectionPermissionRequest {
-                        role_name: role.name.clone(),
-                        can_create: true,
-                        can_read: true,
-                        can_update: true,
-                        can_delete: false,
-                        can_list: true,
-                    },
-                    "guest" => SetCollectionPermissionRequest {
-                        role_name: role.name.clone(),
-                        can_create: false,
-                        can_read: true,
-                        can_update: false,
-                        can_delete: false,
-                        can_list: true,
-                    },
-                    _ => SetCollectionPermissionRequest {
-                        role_name: role.name.clone(),
-                        can_create: false,
-                        can_read: true,
-                        can_update: false,
-                        can_delete: false,
-                        can_list: true,
-                    },
-                };
+        let result: CountResult = diesel::sql_query(format!(
+            "SELECT COUNT(*) AS count FROM pragma_table_info('{}') WHERE name = ?",
+            table_name
+        ))
+        .bind::<diesel::sql_types::Text, _>(column_name)
+        .get_result(conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
 
-                if let Err(e) = permission_service
-                    .set_collection_permission(collection_id, role.id, &permissions)
-                    .await
-                {
-                    tracing::warn!(
-                        "Failed to create default permission for role {}: {:?}",
-                        role.name,
-                        e
-                    );
-                }
-            }
-        }
-        Ok(())
+        Ok(result.count > 0)
     }
 
-    fn value_to_sql_string(&self, value: &Value, field_type: &FieldType) -> String {
-        match field_type {
-            FieldType::Text
-            | FieldType::Email
-            | FieldType::Url
-            | FieldType::File
-            | FieldType::Relation => {
-                if let Some(s) = value.as_str() {
-                    format!("'{}'", s.replace("'", "''"))
-                } else {
-                    "NULL".to_string()
-                }
-            }
-            FieldType::Json | FieldType::RichText => match serde_json::to_string(value) {
-                Ok(json_str) => format!("'{}'", json_str.replace("'", "''")),
-                Err(_) => "NULL".to_string(),
-            },
-            FieldType::Number => {
-                if let Some(n) = value.as_f64() {
-                    n.to_string()
-                } else {
-                    "NULL".to_string()
-                }
-            }
-            FieldType::Boolean => {
-                if let Some(b) = value.as_bool() {
-                    if b { "TRUE" } else { "FALSE" }.to_string()
-                } else {
-                    "NULL".to_string()
-                }
-            }
-            FieldType::Date => {
-                if let Some(s) = value.as_str() {
-                    format!("'{}'", s.replace("'", "''"))
-                } else {
-                    "NULL".to_string()
-                }
-            }
+    /// Adds the `sort_order` column and its index to a records table. Existing
+    /// records are numbered in creation order, matching where new records go.
+    fn enable_manual_ordering(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+    ) -> Result<(), LunarbaseError> {
+        let table_name = self.get_records_table_name(collection_name);
+
+        if !self.table_has_column(conn, &table_name, SORT_ORDER_COLUMN)? {
+            let add_column_sql = format!(
+                "ALTER TABLE {} ADD COLUMN {} REAL",
+                table_name, SORT_ORDER_COLUMN
+            );
+            diesel::sql_query(&add_column_sql)
+                .execute(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to add sort_order column: {:?}", e);
+                    LunarbaseError::InternalError
+                })?;
+            self.renormalize_sort_order(conn, &table_name, "created_at ASC, id ASC")?;
         }
+
+        self.create_sort_order_index(conn, &table_name)
     }
 
-    fn query_record_by_sql(
+    /// Adds the `status` and `publish_at` columns and the status index to a
+    /// records table. Existing records stay published, so switching the
+    /// workflow on does not hide anything.
+    fn enable_workflow(
         &self,
         conn: &mut SqliteConnection,
-        sql: &str,
         collection_name: &str,
-    ) -> Result<RecordResponse, LunarbaseError> {
-        use diesel::sql_types::*;
-
-        let collection = collections::table
-            .filter(collections::name.eq(collection_name))
-            .first::<Collection>(conn)
-            .map_err(|_| LunarbaseError::InternalError)?;
-
-        let schema = collection
-            .get_schema()
-            .map_err(|_| LunarbaseError::InternalError)?;
+    ) -> Result<(), LunarbaseError> {
+        let table_name = self.get_records_table_name(collection_name);
+        self.add_workflow_columns(conn, &table_name)?;
+        self.create_status_index(conn, &table_name)
+    }
 
-        #[derive(Debug, diesel::QueryableByName)]
-        struct DynamicRow {
-            #[diesel(sql_type = Integer)]
-            id: i32,
-            #[diesel(sql_type = Text)]
-            created_at: String,
-            #[diesel(sql_type = Text)]
-            updated_at: String,
+    fn add_workflow_columns(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+    ) -> Result<(), LunarbaseError> {
+        let columns = [
+            (STATUS_COLUMN, "TEXT NOT NULL DEFAULT 'published'"),
+            (PUBLISH_AT_COLUMN, "TIMESTAMP"),
+        ];
+        for (column, definition) in columns {
+            if self.table_has_column(conn, table_name, column)? {
+                continue;
+            }
+            diesel::sql_query(format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table_name, column, definition
+            ))
+            .execute(conn)
+            .map_err(|e| {
+                tracing::error!("Failed to add {} column: {:?}", column, e);
+                LunarbaseError::InternalError
+            })?;
         }
+        Ok(())
+    }
 
-        let base_result: Vec<DynamicRow> = diesel::sql_query(sql)
-            .load(conn)
-            .map_err(|_| LunarbaseError::NotFound("Record not found".to_string()))?;
+    fn create_status_index(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+    ) -> Result<(), LunarbaseError> {
+        let index_sql = format!(
+            "CREATE INDEX IF NOT EXISTS idx_{}_status ON {} ({}, {})",
+            table_name, table_name, STATUS_COLUMN, PUBLISH_AT_COLUMN
+        );
+        diesel::sql_query(&index_sql).execute(conn).map_err(|e| {
+            tracing::error!("Failed to create status index: {:?}", e);
+            LunarbaseError::InternalError
+        })?;
+        Ok(())
+    }
 
-        if base_result.is_empty() {
-            return Err(LunarbaseError::NotFound("Record not found".to_string()));
+    /// Indexes the designated schedule fields so the scheduler's range scans
+    /// do not read the whole table.
+    fn create_schedule_indexes(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+        schedule: &RecordSchedule,
+    ) -> Result<(), LunarbaseError> {
+        let table_name = self.get_records_table_name(collection_name);
+        for field in schedule.fields() {
+            let index_sql = format!(
+                "CREATE INDEX IF NOT EXISTS idx_{}_{}_schedule ON {} ({})",
+                table_name, field, table_name, field
+            );
+            diesel::sql_query(&index_sql).execute(conn).map_err(|e| {
+                tracing::error!("Failed to create schedule index: {:?}", e);
+                LunarbaseError::InternalError
+            })?;
         }
+        Ok(())
+    }
 
-        let base_row = &base_result[0];
-
-        let mut data = Map::new();
+    /// Indexes the retention field so finding due records does not read the
+    /// whole table. `created_at` is indexed with every records table.
+    fn create_retention_index(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+        policy: &RetentionPolicy,
+    ) -> Result<(), LunarbaseError> {
+        if policy.field == "created_at" {
+            return Ok(());
+        }
         let table_name = self.get_records_table_name(collection_name);
+        let index_sql = format!(
+            "CREATE INDEX IF NOT EXISTS idx_{}_{}_retention ON {} ({})",
+            table_name, policy.field, table_name, policy.field
+        );
+        diesel::sql_query(&index_sql).execute(conn).map_err(|e| {
+            tracing::error!("Failed to create retention index: {:?}", e);
+            LunarbaseError::InternalError
+        })?;
+        Ok(())
+    }
 
-        for field in &schema.fields {
-            let field_value = match field.field_type {
-                FieldType::Text
+    fn create_sort_order_index(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+    ) -> Result<(), LunarbaseError> {
+        let index_sql = format!(
+            "CREATE INDEX IF NOT EXISTS idx_{}_sort_order ON {} ({})",
+            table_name, table_name, SORT_ORDER_COLUMN
+        );
+        diesel::sql_query(&index_sql).execute(conn).map_err(|e| {
+            tracing::error!("Failed to create sort_order index: {:?}", e);
+            LunarbaseError::InternalError
+        })?;
+        Ok(())
+    }
+
+    /// Renumbers every record `SORT_ORDER_GAP` apart, keeping the given order.
+    fn renormalize_sort_order(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+        order_by: &str,
+    ) -> Result<(), LunarbaseError> {
+        #[derive(Debug, diesel::QueryableByName)]
+        struct IdRow {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            id: String,
+        }
+
+        let rows: Vec<IdRow> = diesel::sql_query(format!(
+            "SELECT id FROM {} ORDER BY {}",
+            table_name, order_by
+        ))
+        .load(conn)
+        .map_err(|_| LunarbaseError::InternalError)?;
+
+        for (position, row) in rows.iter().enumerate() {
+            diesel::sql_query(format!(
+                "UPDATE {} SET {} = {} WHERE id = {}",
+                table_name,
+                SORT_ORDER_COLUMN,
+                (position + 1) as f64 * SORT_ORDER_GAP,
+                sql_record_id(&row.id)
+            ))
+            .execute(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+        }
+
+        debug!(
+            "Renormalized sort_order of {} records in {}",
+            rows.len(),
+            table_name
+        );
+        Ok(())
+    }
+
+    fn read_sort_order(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+        record_id: &str,
+    ) -> Result<Option<f64>, LunarbaseError> {
+        #[derive(Debug, diesel::QueryableByName)]
+        struct SortOrderRow {
+            #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Double>)]
+            sort_order: Option<f64>,
+        }
+
+        let rows: Vec<SortOrderRow> = PreparedSql::new(format!(
+            "SELECT {} AS sort_order FROM {} WHERE id = ?",
+            SORT_ORDER_COLUMN, table_name
+        ))
+        .bind(SqlBind::Text(Some(record_id.to_string())))
+        .load(conn)
+        .map_err(|_| LunarbaseError::InternalError)?;
+
+        Ok(rows.first().map(|row| row.sort_order.unwrap_or(0.0)))
+    }
+
+    /// The `status` and `publish_at` of a record in a workflow collection.
+    fn read_workflow_state(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+        record_id: &str,
+    ) -> Result<Option<(String, Option<String>)>, LunarbaseError> {
+        #[derive(Debug, diesel::QueryableByName)]
+        struct WorkflowRow {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            status: String,
+            #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+            publish_at: Option<String>,
+        }
+
+        let rows: Vec<WorkflowRow> = PreparedSql::new(format!(
+            "SELECT {} AS status, {} AS publish_at FROM {} WHERE id = ?",
+            STATUS_COLUMN, PUBLISH_AT_COLUMN, table_name
+        ))
+        .bind(SqlBind::Text(Some(record_id.to_string())))
+        .load(conn)
+        .map_err(|_| LunarbaseError::InternalError)?;
+
+        Ok(rows
+            .into_iter()
+            .next()
+            .map(|row| (row.status, row.publish_at)))
+    }
+
+    /// Nearest `sort_order` strictly before (or after) `anchor`, ignoring the
+    /// record being moved.
+    fn neighbour_sort_order(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+        anchor: f64,
+        before: bool,
+        moving_id: &str,
+    ) -> Result<Option<f64>, LunarbaseError> {
+        #[derive(Debug, diesel::QueryableByName)]
+        struct NeighbourRow {
+            #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Double>)]
+            sort_order: Option<f64>,
+        }
+
+        let (aggregate, operator) = if before { ("MAX", "<") } else { ("MIN", ">") };
+        let row: NeighbourRow = diesel::sql_query(format!(
+            "SELECT {}({}) AS sort_order FROM {} WHERE {} {} {} AND id != {}",
+            aggregate,
+            SORT_ORDER_COLUMN,
+            table_name,
+            SORT_ORDER_COLUMN,
+            operator,
+            anchor,
+            sql_record_id(moving_id)
+        ))
+        .get_result(conn)
+        .map_err(|_| LunarbaseError::InternalError)?;
+
+        Ok(row.sort_order)
+    }
+
+    /// Grants every role its permissions on a new collection: the explicit set
+    /// from the request when given (roles left out get none), otherwise the
+    /// defaults configured in the `system.default_permissions_*` settings.
+    async fn create_default_permissions(
+        &self,
+        collection_id: i32,
+        explicit: Option<&[SetCollectionPermissionRequest]>,
+    ) -> Result<(), LunarbaseError> {
+        if let Some(permission_service) = &self.permission_service {
+            let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+            let roles = roles::table
+                .load::<Role>(&mut conn)
+                .map_err(|_| LunarbaseError::InternalError)?;
+            let defaults = self.get_default_collection_permissions().await;
+
+            for role in roles {
+                let permission_set = match explicit {
+                    _ if role.name == "admin" => PermissionSet::FULL,
+                    Some(explicit) => explicit
+                        .iter()
+                        .find(|permissions| permissions.role_name == role.name)
+                        .map(PermissionSet::from)
+                        .unwrap_or(PermissionSet::NONE),
+                    None => defaults.for_role(&role.name),
+                };
+                let permissions = permission_set.for_role(&role.name);
+
+                if let Err(e) = permission_service
+                    .set_collection_permission(collection_id, role.id, &permissions)
+                    .await
+                {
+                    tracing::warn!(
+                        "Failed to create default permission for role {}: {:?}",
+                        role.name,
+                        e
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn value_to_sql_bind(&self, value: &Value, field_type: &FieldType) -> SqlBind {
+        match field_type {
+            FieldType::Text
+            | FieldType::Email
+            | FieldType::Url
+            | FieldType::File
+            | FieldType::Relation
+            | FieldType::Slug
+            | FieldType::Date => SqlBind::Text(value.as_str().map(str::to_string)),
+            FieldType::Json | FieldType::RichText => {
+                SqlBind::Text(serde_json::to_string(value).ok())
+            }
+            FieldType::Number => SqlBind::Double(value.as_f64()),
+            FieldType::Boolean => SqlBind::Bool(value.as_bool()),
+            // Span two columns or need the field's scale; written through `field_sql_binds`
+            FieldType::GeoPoint | FieldType::Decimal => SqlBind::Text(None),
+        }
+    }
+
+    /// Column and bound value pairs storing `value` for `field`.
+    fn field_sql_binds(&self, field: &FieldDefinition, value: &Value) -> Vec<(String, SqlBind)> {
+        if field.field_type == FieldType::Decimal {
+            let (precision, scale) = field.decimal_precision_and_scale();
+            let minor_units = decimal_text(value)
+                .and_then(|text| parse_minor_units(&text, precision, scale).ok());
+            return vec![(field.name.clone(), SqlBind::BigInt(minor_units))];
+        }
+
+        if field.field_type != FieldType::GeoPoint {
+            return vec![(
+                field.name.clone(),
+                self.value_to_sql_bind(value, &field.field_type),
+            )];
+        }
+
+        let (lat_column, lng_column) = geo_point_columns(&field.name);
+        let (lat, lng) = geo_point_coordinates(value).unzip();
+        vec![
+            (lat_column, SqlBind::Double(lat)),
+            (lng_column, SqlBind::Double(lng)),
+        ]
+    }
+
+    /// Reads the record matched by an arbitrary `sql` query selecting from the
+    /// records table of `collection_name`.
+    fn query_record_by_sql(
+        &self,
+        conn: &mut SqliteConnection,
+        sql: &str,
+        collection_name: &str,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        let collection = collections::table
+            .filter(collections::name.eq(collection_name))
+            .first::<Collection>(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+
+        let base_row = diesel::sql_query(sql)
+            .load::<RecordBaseRow>(conn)
+            .map_err(|_| LunarbaseError::NotFound("Record not found".to_string()))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| LunarbaseError::NotFound("Record not found".to_string()))?;
+
+        self.load_record_fields(conn, &collection, base_row)
+    }
+
+    /// Reads record `record_id` of `collection_name` with statements that stay
+    /// prepared on the connection, see [`record_statement`].
+    fn query_record_by_id(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+        record_id: &str,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        let collection = collections::table
+            .filter(collections::name.eq(collection_name))
+            .first::<Collection>(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+
+        let base_row = record_statement(
+            &collection,
+            format!(
+                "SELECT id, created_at, updated_at FROM {} WHERE id = ?",
+                self.get_records_table_name(collection_name)
+            ),
+        )
+        .bind(SqlBind::Text(Some(record_id.to_string())))
+        .load::<RecordBaseRow>(conn)
+        .map_err(|_| LunarbaseError::NotFound("Record not found".to_string()))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| LunarbaseError::NotFound("Record not found".to_string()))?;
+
+        self.load_record_fields(conn, &collection, base_row)
+    }
+
+    fn load_record_fields(
+        &self,
+        conn: &mut SqliteConnection,
+        collection: &Collection,
+        base_row: RecordBaseRow,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        use diesel::sql_types::*;
+
+        let schema = collection
+            .get_schema()
+            .map_err(|_| LunarbaseError::InternalError)?;
+
+        let mut data = Map::new();
+        let table_name = self.get_records_table_name(&collection.name);
+        let select_columns = |columns: String| {
+            record_statement(
+                collection,
+                format!("SELECT {} FROM {} WHERE id = ?", columns, table_name),
+            )
+            .bind(SqlBind::Text(Some(base_row.id.clone())))
+        };
+
+        for field in &schema.fields {
+            let field_value = match field.field_type {
+                FieldType::Text
                 | FieldType::Email
                 | FieldType::Url
                 | FieldType::File
-                | FieldType::Relation => {
+                | FieldType::Relation
+                | FieldType::Slug => {
                     #[derive(Debug, diesel::QueryableByName)]
                     struct StringField {
                         #[diesel(sql_type = Nullable<Text>)]
                         value: Option<String>,
                     }
 
-                    let query_with_alias = format!(
-                        "SELECT {} as value FROM {} WHERE id = {}",
-                        field.name, table_name, base_row.id
-                    );
-                    let result: Vec<StringField> = diesel::sql_query(&query_with_alias)
-                        .load(conn)
-                        .map_err(|_| LunarbaseError::InternalError)?;
+                    let result: Vec<StringField> =
+                        select_columns(format!("{} as value", field.name))
+                            .load(conn)
+                            .map_err(|_| LunarbaseError::InternalError)?;
 
                     if let Some(row) = result.first() {
                         row.value
@@ -711,11 +1378,7 @@ impl CollectionService {
                         value: Option<String>,
                     }
 
-                    let query_with_alias = format!(
-                        "SELECT {} as value FROM {} WHERE id = {}",
-                        field.name, table_name, base_row.id
-                    );
-                    let result: Vec<JsonField> = diesel::sql_query(&query_with_alias)
+                    let result: Vec<JsonField> = select_columns(format!("{} as value", field.name))
                         .load(conn)
                         .map_err(|_| LunarbaseError::InternalError)?;
 
@@ -739,27 +1402,15 @@ impl CollectionService {
                         value: Option<f64>,
                     }
 
-                    let query_with_alias = format!(
-                        "SELECT {} as value FROM {} WHERE id = {}",
-                        field.name, table_name, base_row.id
-                    );
-                    let result: Vec<NumberField> = diesel::sql_query(&query_with_alias)
-                        .load(conn)
-                        .map_err(|_| LunarbaseError::InternalError)?;
+                    let result: Vec<NumberField> =
+                        select_columns(format!("{} as value", field.name))
+                            .load(conn)
+                            .map_err(|_| LunarbaseError::InternalError)?;
 
                     if let Some(row) = result.first() {
-                        if let Some(n) = row.value {
-                            if n.fract() == 0.0 && n >= i64::MIN as f64 && n <= i64::MAX as f64 {
-                                Value::Number(serde_json::Number::from(n as i64))
-                            } else {
-                                Value::Number(
-                                    serde_json::Number::from_f64(n)
-                                        .unwrap_or(serde_json::Number::from(0)),
-                                )
-                            }
-                        } else {
-                            Value::Null
-                        }
+                        row.value
+                            .map(|n| number_to_json(n, field.number_format()))
+                            .unwrap_or(Value::Null)
                     } else {
                         Value::Null
                     }
@@ -771,11 +1422,7 @@ impl CollectionService {
                         value: Option<bool>,
                     }
 
-                    let query_with_alias = format!(
-                        "SELECT {} as value FROM {} WHERE id = {}",
-                        field.name, table_name, base_row.id
-                    );
-                    let result: Vec<BoolField> = diesel::sql_query(&query_with_alias)
+                    let result: Vec<BoolField> = select_columns(format!("{} as value", field.name))
                         .load(conn)
                         .map_err(|_| LunarbaseError::InternalError)?;
 
@@ -792,11 +1439,7 @@ impl CollectionService {
                         value: Option<String>,
                     }
 
-                    let query_with_alias = format!(
-                        "SELECT {} as value FROM {} WHERE id = {}",
-                        field.name, table_name, base_row.id
-                    );
-                    let result: Vec<DateField> = diesel::sql_query(&query_with_alias)
+                    let result: Vec<DateField> = select_columns(format!("{} as value", field.name))
                         .load(conn)
                         .map_err(|_| LunarbaseError::InternalError)?;
 
@@ -809,6 +1452,47 @@ impl CollectionService {
                         Value::Null
                     }
                 }
+                FieldType::GeoPoint => {
+                    #[derive(Debug, diesel::QueryableByName)]
+                    struct GeoPointField {
+                        #[diesel(sql_type = Nullable<Double>)]
+                        lat: Option<f64>,
+                        #[diesel(sql_type = Nullable<Double>)]
+                        lng: Option<f64>,
+                    }
+
+                    let (lat_column, lng_column) = geo_point_columns(&field.name);
+                    let result: Vec<GeoPointField> =
+                        select_columns(format!("{} as lat, {} as lng", lat_column, lng_column))
+                            .load(conn)
+                            .map_err(|_| LunarbaseError::InternalError)?;
+
+                    match result.first() {
+                        Some(GeoPointField {
+                            lat: Some(lat),
+                            lng: Some(lng),
+                        }) => serde_json::json!({ "lat": lat, "lng": lng }),
+                        _ => Value::Null,
+                    }
+                }
+                FieldType::Decimal => {
+                    #[derive(Debug, diesel::QueryableByName)]
+                    struct DecimalField {
+                        #[diesel(sql_type = Nullable<BigInt>)]
+                        value: Option<i64>,
+                    }
+
+                    let result: Vec<DecimalField> =
+                        select_columns(format!("{} as value", field.name))
+                            .load(conn)
+                            .map_err(|_| LunarbaseError::InternalError)?;
+
+                    let (_, scale) = field.decimal_precision_and_scale();
+                    match result.first().and_then(|row| row.value) {
+                        Some(minor_units) => Value::String(format_minor_units(minor_units, scale)),
+                        None => Value::Null,
+                    }
+                }
             };
 
             data.insert(field.name.clone(), field_value);
@@ -822,12 +1506,9 @@ impl CollectionService {
                 value: Option<i32>,
             }
 
-            let query_with_alias = format!(
-                "SELECT {} as value FROM {} WHERE id = {}",
-                field_name, table_name, base_row.id
-            );
-
-            if let Ok(result) = diesel::sql_query(&query_with_alias).load::<OwnershipField>(conn) {
+            if let Ok(result) =
+                select_columns(format!("{} as value", field_name)).load::<OwnershipField>(conn)
+            {
                 if let Some(row) = result.first() {
                     if let Some(value) = row.value {
                         data.insert(
@@ -839,8 +1520,26 @@ impl CollectionService {
             }
         }
 
+        if collection.orderable
+            && let Some(sort_order) = self.read_sort_order(conn, &table_name, &base_row.id)?
+            && let Some(number) = serde_json::Number::from_f64(sort_order)
+        {
+            data.insert(SORT_ORDER_COLUMN.to_string(), Value::Number(number));
+        }
+
+        if collection.workflow() == CollectionWorkflow::DraftPublish
+            && let Some((status, publish_at)) =
+                self.read_workflow_state(conn, &table_name, &base_row.id)?
+        {
+            data.insert(STATUS_COLUMN.to_string(), Value::String(status));
+            data.insert(
+                PUBLISH_AT_COLUMN.to_string(),
+                publish_at.map(Value::String).unwrap_or(Value::Null),
+            );
+        }
+
         Ok(RecordResponse {
-            id: base_row.id.to_string(),
+            id: base_row.id.clone(),
             collection_id: collection.id.to_string(),
             data: Value::Object(data),
             created_at: base_row.created_at.clone(),
@@ -848,45 +1547,390 @@ impl CollectionService {
         })
     }
 
-    pub async fn create_collection(
+    fn insert_record_row(
         &self,
-        request: CreateCollectionRequest,
-    ) -> Result<CollectionResponse, LunarbaseError> {
-        tracing::debug!("Starting create_collection for: {}", request.name);
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+        schema: &CollectionSchema,
+        data: &Value,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        let table_name = self.get_records_table_name(collection_name);
+        let data = &self.assign_slugs(conn, &table_name, schema, data, None)?;
+        let validated_data = self.validate_record_data(schema, data)?;
 
-        let mut conn = self.pool.get().map_err(|e| {
-            tracing::error!("Failed to get database connection: {:?}", e);
-            LunarbaseError::InternalError
-        })?;
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        let mut binds = Vec::new();
 
-        tracing::debug!("Got database connection successfully");
+        for field in &schema.fields {
+            if let Some(field_value) = validated_data.get(&field.name) {
+                for (column, bind) in self.field_sql_binds(field, field_value) {
+                    columns.push(column);
+                    values.push("?".to_string());
+                    binds.push(bind);
+                }
+            }
+        }
 
-        tracing::debug!("Validating collection name: {}", request.name);
-        self.validate_collection_name(&request.name)?;
-        tracing::debug!("Collection name validation passed");
+        let ownership_fields = ["owner_id", "author_id"];
+        for field_name in &ownership_fields {
+            if let Some(field_value) = data.get(field_name) {
+                if !columns.contains(&field_name.to_string()) {
+                    columns.push(field_name.to_string());
+                    values.push("?".to_string());
+                    binds.push(self.value_to_sql_bind(field_value, &FieldType::Number));
+                }
+            }
+        }
 
-        tracing::debug!("Checking if collection already exists");
-        let existing = collections::table
-            .filter(collections::name.eq(&request.name))
-            .first::<Collection>(&mut conn)
-            .optional()
-            .map_err(|e| {
-                tracing::error!("Failed to check existing collection: {:?}", e);
-                LunarbaseError::InternalError
-            })?;
+        let collection = collections::table
+            .filter(collections::name.eq(collection_name))
+            .first::<Collection>(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+        let id_type = RecordIdType::from_db(&collection.id_type);
+        let explicit_id = self.explicit_record_id(
+            conn,
+            &table_name,
+            id_type,
+            collection.allow_explicit_ids,
+            data,
+        )?;
+        // SQLite moves the AUTOINCREMENT sequence past explicit integer ids by itself
+        let new_id = explicit_id.or_else(|| match id_type {
+            RecordIdType::Integer => None,
+            RecordIdType::Uuid => Some(uuid::Uuid::now_v7().to_string()),
+        });
+        if let Some(id) = &new_id {
+            columns.push("id".to_string());
+            values.push("?".to_string());
+            binds.push(SqlBind::Text(Some(id.clone())));
+        }
+        if collection.orderable {
+            columns.push(SORT_ORDER_COLUMN.to_string());
+            values.push(format!(
+                "(SELECT COALESCE(MAX({}), 0) + {} FROM {})",
+                SORT_ORDER_COLUMN, SORT_ORDER_GAP, table_name
+            ));
+        }
+        // New records stay hidden until they are published
+        if collection.workflow() == CollectionWorkflow::DraftPublish {
+            columns.push(STATUS_COLUMN.to_string());
+            values.push("?".to_string());
+            binds.push(SqlBind::Text(Some(
+                RecordStatus::Draft.as_str().to_string(),
+            )));
+        }
 
-        if existing.is_some() {
-            tracing::debug!("Collection already exists");
+        let insert_sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table_name,
+            columns.join(", "),
+            values.join(", ")
+        );
+
+        record_statement(&collection, insert_sql)
+            .binds(binds)
+            .execute(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+        adjust_record_count(conn, collection_name, 1)?;
+
+        match &new_id {
+            Some(id) => self.query_record_by_id(conn, collection_name, id),
+            None => {
+                let select_sql = format!("SELECT * FROM {} ORDER BY id DESC LIMIT 1", table_name);
+                self.query_record_by_sql(conn, &select_sql, collection_name)
+            }
+        }
+    }
+
+    /// The `id` given in the data of a new record, in canonical form. Only
+    /// collections with `allow_explicit_ids` accept one, and it must be unused.
+    fn explicit_record_id(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+        id_type: RecordIdType,
+        allowed: bool,
+        data: &Value,
+    ) -> Result<Option<String>, LunarbaseError> {
+        let raw_id = match data.get("id") {
+            None | Some(Value::Null) => return Ok(None),
+            Some(Value::String(id)) => id.clone(),
+            Some(Value::Number(id)) => id.to_string(),
+            Some(other) => other.to_string(),
+        };
+
+        if !allowed {
             return Err(LunarbaseError::ValidationError(vec![
-                "Collection with this name already exists".to_string(),
+                "Field 'id' cannot be set on records of this collection".to_string(),
             ]));
         }
-        tracing::debug!("Collection does not exist, proceeding");
 
-        tracing::debug!("Validating schema");
+        let record_id = id_type.normalize(&raw_id).ok_or_else(|| {
+            LunarbaseError::ValidationError(vec![format!("Invalid record id '{}'", raw_id)])
+        })?;
+
+        #[derive(diesel::QueryableByName)]
+        struct CountResult {
+            #[diesel(sql_type = diesel::sql_types::BigInt)]
+            count: i64,
+        }
+
+        let existing = diesel::sql_query(format!(
+            "SELECT COUNT(*) as count FROM {} WHERE id = {}",
+            table_name,
+            sql_record_id(&record_id)
+        ))
+        .get_result::<CountResult>(conn)
+        .map_err(|_| LunarbaseError::InternalError)?;
+        if existing.count > 0 {
+            return Err(LunarbaseError::Conflict(format!(
+                "A record with id '{}' already exists",
+                record_id
+            )));
+        }
+
+        Ok(Some(record_id))
+    }
+
+    /// Fills the slug fields of a new record (`current` is `None`) or regenerates
+    /// those of `current`. The explicit value of a new record, or else its source
+    /// field, is slugified and suffixed with `-2`, `-3`... until it is unused.
+    fn assign_slugs(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+        schema: &CollectionSchema,
+        data: &Value,
+        current: Option<&RecordResponse>,
+    ) -> Result<Value, LunarbaseError> {
+        let mut data = data.clone();
+        let Some(map) = data.as_object_mut() else {
+            return Ok(data);
+        };
+
+        for field in schema
+            .fields
+            .iter()
+            .filter(|field| field.field_type == FieldType::Slug)
+        {
+            let explicit = map
+                .get(&field.name)
+                .and_then(Value::as_str)
+                .filter(|value| current.is_none() && !value.trim().is_empty());
+            let source = explicit.or_else(|| {
+                let source_field = field.slug_source_field()?;
+                map.get(source_field)
+                    .or_else(|| current.and_then(|record| record.data.get(source_field)))
+                    .and_then(Value::as_str)
+            });
+
+            let base = source.map(slugify).unwrap_or_default();
+            if base.is_empty() {
+                map.remove(&field.name);
+                continue;
+            }
+
+            let mut taken_sql = format!(
+                "SELECT {0} AS value FROM {1} WHERE ({0} = '{2}' OR {0} LIKE '{2}-%')",
+                field.name, table_name, base
+            );
+            if let Some(record) = current {
+                taken_sql.push_str(&format!(" AND id != {}", sql_record_id(&record.id)));
+            }
+
+            #[derive(Debug, diesel::QueryableByName)]
+            struct SlugRow {
+                #[diesel(sql_type = diesel::sql_types::Text)]
+                value: String,
+            }
+            let taken: Vec<String> = diesel::sql_query(&taken_sql)
+                .load::<SlugRow>(conn)
+                .map_err(|_| LunarbaseError::InternalError)?
+                .into_iter()
+                .map(|row| row.value)
+                .collect();
+
+            map.insert(
+                field.name.clone(),
+                Value::String(unique_slug(&base, &taken)),
+            );
+        }
+
+        Ok(data)
+    }
+
+    fn update_record_row(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+        schema: &CollectionSchema,
+        record_id: &str,
+        data: &Value,
+        regenerate_slugs: bool,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        let table_name = self.get_records_table_name(collection_name);
+        let has_slugs = schema
+            .fields
+            .iter()
+            .any(|field| field.field_type == FieldType::Slug);
+        let data = &if has_slugs {
+            let current = self.query_record_by_id(conn, collection_name, record_id)?;
+            if regenerate_slugs {
+                self.assign_slugs(conn, &table_name, schema, data, Some(&current))?
+            } else {
+                with_current_slugs(schema, data, &current)
+            }
+        } else {
+            data.clone()
+        };
+        let validated_data = self.validate_record_data(schema, data)?;
+
+        let mut set_clauses = Vec::new();
+        let mut binds = Vec::new();
+
+        for field in &schema.fields {
+            if let Some(field_value) = validated_data.get(&field.name) {
+                for (column, bind) in self.field_sql_binds(field, field_value) {
+                    set_clauses.push(format!("{} = ?", column));
+                    binds.push(bind);
+                }
+            }
+        }
+
+        let ownership_fields = ["owner_id", "author_id"];
+        for field_name in &ownership_fields {
+            if let Some(field_value) = data.get(field_name) {
+                let already_processed = schema.fields.iter().any(|f| f.name == *field_name);
+                if !already_processed {
+                    set_clauses.push(format!("{} = ?", field_name));
+                    binds.push(self.value_to_sql_bind(field_value, &FieldType::Number));
+                }
+            }
+        }
+
+        if set_clauses.is_empty() {
+            return Err(LunarbaseError::ValidationError(vec![
+                "No fields to update".to_string(),
+            ]));
+        }
+
+        let collection = collections::table
+            .filter(collections::name.eq(collection_name))
+            .first::<Collection>(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+        let update_sql = format!(
+            "UPDATE {} SET {}, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            table_name,
+            set_clauses.join(", ")
+        );
+        binds.push(SqlBind::Text(Some(record_id.to_string())));
+
+        let affected_rows = record_statement(&collection, update_sql)
+            .binds(binds)
+            .execute(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+
+        if affected_rows == 0 {
+            return Err(LunarbaseError::NotFound("Record not found".to_string()));
+        }
+
+        self.query_record_by_id(conn, collection_name, record_id)
+    }
+
+    fn delete_record_row(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+        record_id: &str,
+    ) -> Result<(), LunarbaseError> {
+        let table_name = self.get_records_table_name(collection_name);
+        let delete_sql = format!(
+            "DELETE FROM {} WHERE id = {}",
+            table_name,
+            sql_record_id(record_id)
+        );
+        let deleted_rows = diesel::sql_query(&delete_sql)
+            .execute(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+
+        if deleted_rows == 0 {
+            return Err(LunarbaseError::NotFound("Record not found".to_string()));
+        }
+        adjust_record_count(conn, collection_name, -1)?;
+
+        Ok(())
+    }
+
+    pub async fn create_collection(
+        &self,
+        request: CreateCollectionRequest,
+        actor_id: Option<i32>,
+    ) -> Result<CollectionResponse, LunarbaseError> {
+        self.create_collection_in_workspace(request, DEFAULT_WORKSPACE_ID, actor_id)
+            .await
+    }
+
+    pub async fn create_collection_in_workspace(
+        &self,
+        request: CreateCollectionRequest,
+        workspace_id: i32,
+        actor_id: Option<i32>,
+    ) -> Result<CollectionResponse, LunarbaseError> {
+        tracing::debug!("Starting create_collection for: {}", request.name);
+
+        let mut conn = self.pool.get().map_err(|e| {
+            tracing::error!("Failed to get database connection: {:?}", e);
+            LunarbaseError::InternalError
+        })?;
+
+        tracing::debug!("Got database connection successfully");
+
+        tracing::debug!("Validating collection name: {}", request.name);
+        self.validate_collection_name(&request.name)?;
+        tracing::debug!("Collection name validation passed");
+
+        tracing::debug!("Checking if collection already exists");
+        let existing = collections::table
+            .filter(collections::name.eq(&request.name))
+            .first::<Collection>(&mut conn)
+            .optional()
+            .map_err(|e| {
+                tracing::error!("Failed to check existing collection: {:?}", e);
+                LunarbaseError::InternalError
+            })?;
+
+        if existing.is_some() {
+            tracing::debug!("Collection already exists");
+            return Err(LunarbaseError::ValidationError(vec![
+                "Collection with this name already exists".to_string(),
+            ]));
+        }
+        tracing::debug!("Collection does not exist, proceeding");
+
+        tracing::debug!("Validating schema");
         self.validate_schema(&request.schema)?;
+        if request.orderable {
+            ensure_no_sort_order_field(&request.schema)?;
+        }
+        if request.workflow == CollectionWorkflow::DraftPublish {
+            ensure_no_workflow_fields(&request.schema)?;
+        }
+        let schedule = request.schedule.filter(|schedule| !schedule.is_empty());
+        if let Some(schedule) = &schedule {
+            validate_record_schedule(schedule, &request.schema, request.workflow)?;
+        }
+        let retention = request.retention.filter(|policy| policy.max_age_days > 0);
+        if let Some(policy) = &retention {
+            validate_retention_policy(policy, &request.schema, request.workflow)?;
+        }
+        self.validate_relation_targets(&mut conn, &request.name, &request.schema)?;
         tracing::debug!("Schema validation passed");
 
+        if let Some(permissions) = &request.permissions {
+            validate_initial_permissions(&mut conn, permissions)?;
+        }
+
         tracing::debug!("Serializing schema to JSON");
         let schema_json = serde_json::to_string(&request.schema).map_err(|e| {
             tracing::error!("Failed to serialize schema: {:?}", e);
@@ -900,6 +1944,21 @@ impl CollectionService {
             description: request.description,
             schema_json,
             is_system: false,
+            orderable: request.orderable,
+            id_type: request.id_type.as_str().to_string(),
+            allow_explicit_ids: request.allow_explicit_ids,
+            workflow: request.workflow.as_str().to_string(),
+            schedule_json: schedule
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()
+                .map_err(|_| LunarbaseError::InternalError)?,
+            workspace_id,
+            retention_json: retention
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()
+                .map_err(|_| LunarbaseError::InternalError)?,
         };
 
         tracing::debug!("Inserting collection metadata");
@@ -913,7 +1972,19 @@ impl CollectionService {
         tracing::debug!("Collection metadata inserted successfully");
 
         tracing::debug!("Creating records table for collection: {}", request.name);
-        self.create_records_table(&mut conn, &request.name, &request.schema)?;
+        self.create_records_table(&mut conn, &request.name, &request.schema, request.id_type)?;
+        if request.orderable {
+            self.enable_manual_ordering(&mut conn, &request.name)?;
+        }
+        if request.workflow == CollectionWorkflow::DraftPublish {
+            self.enable_workflow(&mut conn, &request.name)?;
+        }
+        if let Some(schedule) = &schedule {
+            self.create_schedule_indexes(&mut conn, &request.name, schedule)?;
+        }
+        if let Some(policy) = &retention {
+            self.create_retention_index(&mut conn, &request.name, policy)?;
+        }
         tracing::debug!("Records table created successfully");
 
         tracing::debug!("Fetching created collection");
@@ -926,8 +1997,20 @@ impl CollectionService {
             })?;
         tracing::debug!("Collection fetched successfully");
 
+        self.record_schema_version(
+            &mut conn,
+            collection.id,
+            1,
+            &collection.schema_json,
+            actor_id,
+            Some("Initial schema".to_string()),
+        )?;
+
         tracing::debug!("Creating default permissions for collection");
-        if let Err(e) = self.create_default_permissions(collection.id).await {
+        if let Err(e) = self
+            .create_default_permissions(collection.id, request.permissions.as_deref())
+            .await
+        {
             tracing::warn!(
                 "Failed to create default permissions for collection {}: {:?}",
                 collection.name,
@@ -937,6 +2020,15 @@ impl CollectionService {
             tracing::debug!("Default permissions created successfully");
         }
 
+        self.emit_collection_event(
+            collection.id,
+            CollectionEvent::Created {
+                collection: collection.name.clone(),
+                version: collection.schema_version,
+            },
+        )
+        .await;
+
         tracing::debug!("Converting collection to response");
         let response = CollectionResponse::from_collection(collection).map_err(|e| {
             tracing::error!("Failed to convert collection to response: {:?}", e);
@@ -990,10 +2082,103 @@ impl CollectionService {
         Ok(responses)
     }
 
+    /// One page of non-system collections matching `search` (on name, display
+    /// name or description), sorted by `sort` as `field[:asc|desc]`, along with
+    /// the total number of matches.
+    pub async fn list_collections_page(
+        &self,
+        workspace_id: Option<i32>,
+        search: Option<&str>,
+        sort: Option<&str>,
+        fields: CollectionFields,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<CollectionListEntry>, i64), LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let filtered = || {
+            let mut query = collections::table
+                .filter(collections::is_system.eq(false))
+                .into_boxed();
+            if let Some(workspace_id) = workspace_id {
+                query = query.filter(collections::workspace_id.eq(workspace_id));
+            }
+            if let Some(search) = search.map(str::trim).filter(|search| !search.is_empty()) {
+                let pattern = format!(
+                    "%{}%",
+                    search
+                        .replace('\\', "\\\\")
+                        .replace('%', "\\%")
+                        .replace('_', "\\_")
+                );
+                query = query.filter(
+                    collections::name
+                        .like(pattern.clone())
+                        .escape('\\')
+                        .or(collections::display_name.like(pattern.clone()).escape('\\'))
+                        .or(collections::description.like(pattern).escape('\\')),
+                );
+            }
+            query
+        };
+
+        let total_count = filtered()
+            .count()
+            .get_result::<i64>(&mut conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+
+        let (sort_field, direction) = match sort {
+            Some(sort) => sort.split_once(':').unwrap_or((sort, "asc")),
+            None => ("created_at", "desc"),
+        };
+        let descending = match direction {
+            "asc" => false,
+            "desc" => true,
+            _ => {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Invalid sort direction '{}'; use asc or desc",
+                    direction
+                )]));
+            }
+        };
+        let query = match (sort_field, descending) {
+            ("name", false) => filtered().order(collections::name.asc()),
+            ("name", true) => filtered().order(collections::name.desc()),
+            ("display_name", false) => filtered().order(collections::display_name.asc()),
+            ("display_name", true) => filtered().order(collections::display_name.desc()),
+            ("created_at", false) => filtered().order(collections::created_at.asc()),
+            ("created_at", true) => filtered().order(collections::created_at.desc()),
+            ("updated_at", false) => filtered().order(collections::updated_at.asc()),
+            ("updated_at", true) => filtered().order(collections::updated_at.desc()),
+            _ => {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Cannot sort collections by '{}'; use name, display_name, created_at or updated_at",
+                    sort_field
+                )]));
+            }
+        };
+
+        let entries = query
+            .then_order_by(collections::id.asc())
+            .limit(limit)
+            .offset(offset)
+            .load::<Collection>(&mut conn)
+            .map_err(|_| LunarbaseError::InternalError)?
+            .into_iter()
+            .map(|collection| CollectionListEntry::from_collection(collection, fields))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| LunarbaseError::InternalError)?;
+
+        Ok((entries, total_count))
+    }
+
+    /// Updates collection metadata and, when a new schema is given, migrates the
+    /// records table and appends an entry to the collection's schema history.
     pub async fn update_collection(
         &self,
         name: &str,
         request: UpdateCollectionRequest,
+        actor_id: Option<i32>,
     ) -> Result<CollectionResponse, LunarbaseError> {
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
@@ -1008,9 +2193,28 @@ impl CollectionService {
             ));
         }
 
+        if let Some(id_type) = request.id_type
+            && id_type != collection.record_id_type()
+        {
+            return Err(LunarbaseError::ValidationError(vec![
+                "id_type cannot be changed after a collection is created".to_string(),
+            ]));
+        }
+
+        if let Some(ttl) = request.query_cache_ttl_seconds
+            && !(0..=MAX_QUERY_CACHE_TTL_SECONDS).contains(&ttl)
+        {
+            return Err(LunarbaseError::ValidationError(vec![format!(
+                "query_cache_ttl_seconds must be between 0 and {}",
+                MAX_QUERY_CACHE_TTL_SECONDS
+            )]));
+        }
+
         if let Some(ref new_name) = request.name {
             if new_name != &collection.name {
-                if !new_name.chars().all(|c| c.is_alphanumeric() || c == '_') || new_name.is_empty()
+                if !new_name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                    || new_name.is_empty()
+                    || new_name.starts_with('_')
                 {
                     return Err(LunarbaseError::BadRequest(
                         "Collection name must contain only alphanumeric characters and underscores"
@@ -1030,794 +2234,4097 @@ impl CollectionService {
                     ));
                 }
 
-                let old_table_name = self.get_records_table_name(&collection.name);
-                let new_table_name = self.get_records_table_name(new_name);
-
-                let rename_sql = format!(
-                    "ALTER TABLE {} RENAME TO {}",
-                    old_table_name, new_table_name
-                );
-                diesel::sql_query(&rename_sql)
-                    .execute(&mut conn)
-                    .map_err(|_| LunarbaseError::InternalError)?;
+                drop(conn);
+                self.rename_collection(&collection, new_name).await?;
+                conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
             }
         }
+        // Everything below works on the collection under its new name
+        let current_name = request
+            .name
+            .clone()
+            .unwrap_or_else(|| collection.name.clone());
 
         let mut update = UpdateCollection {
             name: request.name,
             display_name: request.display_name,
             description: request.description,
             schema_json: None,
+            schema_version: None,
+            query_cache_ttl_seconds: request.query_cache_ttl_seconds,
+            orderable: request.orderable,
+            allow_explicit_ids: request.allow_explicit_ids,
+            workflow: request
+                .workflow
+                .map(|workflow| workflow.as_str().to_string()),
+            schedule_json: None,
+            schedule_checked_at: None,
+            retention_json: None,
+        };
+        let mut migration_summary = None;
+        let orderable = request.orderable.unwrap_or(collection.orderable);
+        let workflow = request.workflow.unwrap_or(collection.workflow());
+
+        let schedule = match request.schedule {
+            Some(schedule) => {
+                update.schedule_json = Some(if schedule.is_empty() {
+                    None
+                } else {
+                    Some(
+                        serde_json::to_string(&schedule)
+                            .map_err(|_| LunarbaseError::InternalError)?,
+                    )
+                });
+                // A new schedule catches up on everything already due
+                update.schedule_checked_at = Some(None);
+                (!schedule.is_empty()).then_some(schedule)
+            }
+            None => collection
+                .schedule()
+                .map_err(|_| LunarbaseError::InternalError)?,
+        };
+        if let Some(schedule) = &schedule {
+            let current_schema;
+            let schema = match &request.schema {
+                Some(schema) => schema,
+                None => {
+                    current_schema = collection
+                        .get_schema()
+                        .map_err(|_| LunarbaseError::InternalError)?;
+                    &current_schema
+                }
+            };
+            validate_record_schedule(schedule, schema, workflow)?;
+        }
+
+        let retention = match request.retention {
+            Some(policy) => {
+                update.retention_json = Some(if policy.max_age_days == 0 {
+                    None
+                } else {
+                    Some(
+                        serde_json::to_string(&policy)
+                            .map_err(|_| LunarbaseError::InternalError)?,
+                    )
+                });
+                (policy.max_age_days > 0).then_some(policy)
+            }
+            None => collection
+                .retention()
+                .map_err(|_| LunarbaseError::InternalError)?,
         };
+        if let Some(policy) = &retention {
+            let current_schema;
+            let schema = match &request.schema {
+                Some(schema) => schema,
+                None => {
+                    current_schema = collection
+                        .get_schema()
+                        .map_err(|_| LunarbaseError::InternalError)?;
+                    &current_schema
+                }
+            };
+            validate_retention_policy(policy, schema, workflow)?;
+        }
 
         if let Some(schema) = request.schema {
             self.validate_schema(&schema)?;
+            self.validate_relation_targets(&mut conn, &current_name, &schema)?;
+            if orderable {
+                ensure_no_sort_order_field(&schema)?;
+            }
+            if workflow == CollectionWorkflow::DraftPublish {
+                ensure_no_workflow_fields(&schema)?;
+            }
 
             let current_schema = collection
                 .get_schema()
                 .map_err(|_| LunarbaseError::InternalError)?;
-
-            self.update_records_table_schema(
+            ensure_decimal_scales_unchanged(&current_schema, &schema)?;
+            self.ensure_integer_fields_hold_integers(
                 &mut conn,
-                &collection.name,
+                &current_name,
                 &current_schema,
                 &schema,
             )?;
 
-            update.schema_json =
-                Some(serde_json::to_string(&schema).map_err(|_| LunarbaseError::InternalError)?);
+            let changes = summarize_schema_changes(&current_schema, &schema);
+            if !changes.is_empty() {
+                // Descriptions and examples only live in the stored schema
+                if !is_documentation_only_change(&current_schema, &schema) {
+                    self.update_records_table_schema(
+                        &mut conn,
+                        &current_name,
+                        &current_schema,
+                        &schema,
+                        collection.record_id_type(),
+                    )?;
+                }
+
+                update.schema_json = Some(
+                    serde_json::to_string(&schema).map_err(|_| LunarbaseError::InternalError)?,
+                );
+                update.schema_version = Some(collection.schema_version + 1);
+                migration_summary = Some(changes.join("; "));
+            }
         }
 
-        diesel::update(collections::table)
-            .filter(collections::id.eq(collection.id))
-            .set(&update)
-            .execute(&mut conn)
-            .map_err(|_| LunarbaseError::InternalError)?;
+        if orderable && !collection.orderable {
+            if update.schema_json.is_none() {
+                ensure_no_sort_order_field(
+                    &collection
+                        .get_schema()
+                        .map_err(|_| LunarbaseError::InternalError)?,
+                )?;
+            }
+            self.enable_manual_ordering(&mut conn, &current_name)?;
+        }
+
+        if workflow == CollectionWorkflow::DraftPublish
+            && collection.workflow() != CollectionWorkflow::DraftPublish
+        {
+            if update.schema_json.is_none() {
+                ensure_no_workflow_fields(
+                    &collection
+                        .get_schema()
+                        .map_err(|_| LunarbaseError::InternalError)?,
+                )?;
+            }
+            self.enable_workflow(&mut conn, &current_name)?;
+        }
+
+        // Also restores the indexes after the records table was recreated
+        if let Some(schedule) = &schedule {
+            self.create_schedule_indexes(&mut conn, &current_name, schedule)?;
+        }
+        if let Some(policy) = &retention {
+            self.create_retention_index(&mut conn, &current_name, policy)?;
+        }
+
+        // The stored schema and its history row are written together, so a
+        // version is never missing from the history
+        conn.transaction::<_, LunarbaseError, _>(|conn| {
+            diesel::update(collections::table)
+                .filter(collections::id.eq(collection.id))
+                .set(&update)
+                .execute(conn)
+                .map_err(|_| LunarbaseError::InternalError)?;
+
+            if let (Some(schema_json), Some(version)) = (&update.schema_json, update.schema_version)
+            {
+                self.record_schema_version(
+                    conn,
+                    collection.id,
+                    version,
+                    schema_json,
+                    actor_id,
+                    migration_summary,
+                )?;
+            }
+            Ok(())
+        })?;
+        self.record_cache.invalidate_collection(&collection.name);
+        self.query_cache.invalidate_collection(&collection.name);
 
         let updated_collection = collections::table
             .filter(collections::id.eq(collection.id))
             .first::<Collection>(&mut conn)
             .map_err(|_| LunarbaseError::InternalError)?;
 
+        self.emit_collection_event(
+            collection.id,
+            CollectionEvent::SchemaUpdated {
+                collection: updated_collection.name.clone(),
+                version: updated_collection.schema_version,
+                renamed_from: (updated_collection.name != collection.name)
+                    .then(|| collection.name.clone()),
+            },
+        )
+        .await;
+
         CollectionResponse::from_collection(updated_collection)
             .map_err(|_| LunarbaseError::InternalError)
     }
 
-    pub async fn delete_collection(&self, name: &str) -> Result<(), LunarbaseError> {
-        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+    /// Renames a collection and everything named after it in one
+    /// transaction: the records table with its indexes and triggers, relation
+    /// fields pointing at it and rows of tables that refer to collections by
+    /// name. Permission audit entries and quarantined uploads keep the name
+    /// they were recorded under.
+    async fn rename_collection(
+        &self,
+        collection: &Collection,
+        new_name: &str,
+    ) -> Result<(), LunarbaseError> {
+        let old_name = collection.name.as_str();
+        let old_table_name = self.get_records_table_name(old_name);
+        let new_table_name = records_table_name(collection.workspace_id, new_name);
+
+        transaction_then(&self.pool, |conn, after_commit| {
+            let rename_sql = format!(
+                "ALTER TABLE {} RENAME TO {}",
+                old_table_name, new_table_name
+            );
+            diesel::sql_query(&rename_sql).execute(conn).map_err(|e| {
+                tracing::error!("Failed to rename records table: {:?}", e);
+                LunarbaseError::InternalError
+            })?;
+            self.rename_table_objects(conn, &old_table_name, &new_table_name)?;
 
-        let collection = collections::table
-            .filter(collections::name.eq(name))
-            .first::<Collection>(&mut conn)
-            .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+            diesel::update(collections::table.filter(collections::id.eq(collection.id)))
+                .set(collections::name.eq(new_name))
+                .execute(conn)
+                .map_err(|_| LunarbaseError::InternalError)?;
+            let referencing = self.retarget_relations(conn, old_name, new_name)?;
 
-        if collection.is_system {
-            return Err(LunarbaseError::Forbidden(
-                "Cannot delete system collections".to_string(),
-            ));
-        }
+            diesel::update(
+                guest_session_records::table
+                    .filter(guest_session_records::collection_name.eq(old_name)),
+            )
+            .set(guest_session_records::collection_name.eq(new_name))
+            .execute(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+            diesel::update(
+                ingest_endpoints::table.filter(ingest_endpoints::collection_name.eq(old_name)),
+            )
+            .set(ingest_endpoints::collection_name.eq(new_name))
+            .execute(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+            diesel::update(
+                record_shares::table.filter(record_shares::collection_name.eq(old_name)),
+            )
+            .set(record_shares::collection_name.eq(new_name))
+            .execute(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
 
-        if let Some(permission_service) = &self.permission_service {
-            if let Err(e) = permission_service
-                .delete_collection_permissions(collection.id)
-                .await
-            {
-                tracing::warn!(
-                    "Failed to delete permissions for collection {}: {:?}",
-                    name,
-                    e
-                );
-            } else {
-                debug!("Successfully deleted permissions for collection: {}", name);
-            }
-        }
+            after_commit.defer(async move {
+                self.forget_records_table(old_name);
+                self.record_cache.invalidate_collection(old_name);
+                self.query_cache.invalidate_collection(old_name);
+                // Expanded results of referencing collections name the old target
+                for name in &referencing {
+                    self.query_cache.invalidate_collection(name);
+                }
+                if let Some(permission_service) = &self.permission_service {
+                    permission_service.forget_permissions();
+                }
+            });
+            Ok(())
+        })
+        .await
+    }
 
-        let schema = collection
-            .get_schema()
-            .map_err(|_| LunarbaseError::InternalError)?;
-        let file_deletion_errors = self.delete_collection_files(name, &schema).await;
-        if !file_deletion_errors.is_empty() {
-            tracing::warn!(
-                "Some files could not be deleted for collection {}: {:?}",
-                name,
-                file_deletion_errors
-            );
+    /// Gives the indexes and triggers of a renamed records table the names
+    /// they would have been created with under the new table name.
+    fn rename_table_objects(
+        &self,
+        conn: &mut SqliteConnection,
+        old_table_name: &str,
+        new_table_name: &str,
+    ) -> Result<(), LunarbaseError> {
+        #[derive(Debug, diesel::QueryableByName)]
+        struct TableObject {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            #[diesel(column_name = type_)]
+            object_type: String,
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            name: String,
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            sql: String,
         }
 
-        self.drop_records_table(&mut conn, name)?;
-
-        diesel::delete(collections::table.filter(collections::id.eq(collection.id)))
-            .execute(&mut conn)
-            .map_err(|_| LunarbaseError::InternalError)?;
+        // SQLite already points them at the new table, only their names are stale
+        let objects: Vec<TableObject> = diesel::sql_query(
+            "SELECT type AS type_, name, sql FROM sqlite_master \
+             WHERE type IN ('index', 'trigger') AND tbl_name = ? AND sql IS NOT NULL",
+        )
+        .bind::<diesel::sql_types::Text, _>(new_table_name)
+        .load(conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let index_prefix = format!("idx_{}_", old_table_name);
+        let trigger_name = format!("update_{}_updated_at", old_table_name);
+        for object in objects {
+            let (keyword, new_object_name) = match object.object_type.as_str() {
+                "index" => match object.name.strip_prefix(&index_prefix) {
+                    Some(suffix) => ("INDEX", format!("idx_{}_{}", new_table_name, suffix)),
+                    None => continue,
+                },
+                "trigger" if object.name == trigger_name => {
+                    ("TRIGGER", format!("update_{}_updated_at", new_table_name))
+                }
+                _ => continue,
+            };
 
+            let create_sql = object.sql.replacen(&object.name, &new_object_name, 1);
+            for statement in [format!("DROP {} {}", keyword, object.name), create_sql] {
+                diesel::sql_query(&statement).execute(conn).map_err(|e| {
+                    tracing::error!("Failed to rename {} '{}': {:?}", keyword, object.name, e);
+                    LunarbaseError::InternalError
+                })?;
+            }
+        }
         Ok(())
     }
 
-    pub async fn create_record(
+    /// Points relation fields targeting `old_name` at `new_name` and returns
+    /// the collections whose schema changed.
+    fn retarget_relations(
         &self,
-        collection_name: &str,
-        request: CreateRecordRequest,
-    ) -> Result<RecordResponse, LunarbaseError> {
-        self.create_record_with_events(collection_name, request, None)
-            .await
+        conn: &mut SqliteConnection,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<Vec<String>, LunarbaseError> {
+        let schemas: Vec<(i32, String, String)> = collections::table
+            .select((collections::id, collections::name, collections::schema_json))
+            .load(conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let mut referencing = Vec::new();
+        for (id, name, schema_json) in schemas {
+            let mut schema: CollectionSchema =
+                serde_json::from_str(&schema_json).map_err(|_| LunarbaseError::InternalError)?;
+            let mut retargeted = false;
+            for field in &mut schema.fields {
+                if field.relation_target.as_deref() == Some(old_name) {
+                    field.relation_target = Some(new_name.to_string());
+                    retargeted = true;
+                }
+            }
+            if !retargeted {
+                continue;
+            }
+
+            diesel::update(collections::table.filter(collections::id.eq(id)))
+                .set(collections::schema_json.eq(
+                    serde_json::to_string(&schema).map_err(|_| LunarbaseError::InternalError)?,
+                ))
+                .execute(conn)
+                .map_err(|_| LunarbaseError::InternalError)?;
+            referencing.push(name);
+        }
+        Ok(referencing)
     }
 
-    pub async fn create_record_with_events(
+    /// Records, uploaded files and referencing collections that
+    /// `delete_collection` would take with it. The record count is read
+    /// from the table rather than the cached count.
+    pub async fn collection_delete_impact(
         &self,
-        collection_name: &str,
-        request: CreateRecordRequest,
-        user_id: Option<i32>,
-    ) -> Result<RecordResponse, LunarbaseError> {
+        name: &str,
+    ) -> Result<CollectionDeleteImpact, LunarbaseError> {
+        let collection = self.get_collection(name).await?;
+        let referencing_collections = self
+            .list_collections()
+            .await?
+            .into_iter()
+            .filter(|other| {
+                other.name != name
+                    && other.schema.fields.iter().any(|field| {
+                        field.field_type == FieldType::Relation
+                            && field.relation_target.as_deref() == Some(name)
+                    })
+            })
+            .map(|other| other.name)
+            .collect();
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let record_count = self
+            .reconcile_record_count(&mut conn, collection.id, name)?
+            .record_count;
+        let file_count = self
+            .load_record_files(&mut conn, name, &collection.schema)?
+            .len() as i64;
+
+        Ok(CollectionDeleteImpact {
+            record_count,
+            file_count,
+            referencing_collections,
+        })
+    }
+
+    pub async fn delete_collection(&self, name: &str) -> Result<(), LunarbaseError> {
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
         let collection = collections::table
-            .filter(collections::name.eq(collection_name))
+            .filter(collections::name.eq(name))
             .first::<Collection>(&mut conn)
             .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
 
+        if collection.is_system {
+            return Err(LunarbaseError::Forbidden(
+                "Cannot delete system collections".to_string(),
+            ));
+        }
+
+        // Resolved while the collection's permissions still exist
+        let event_recipients = match &self.websocket_service {
+            Some(ws_service) => ws_service.collection_event_recipients(collection.id).await,
+            None => Vec::new(),
+        };
+        drop(conn);
+
         let schema = collection
             .get_schema()
             .map_err(|_| LunarbaseError::InternalError)?;
 
-        let mut data = request.data.clone();
-        if let Some(files) = &request.files {
-            let file_urls = self.process_file_uploads(&schema, files).await?;
+        transaction_then(&self.pool, |conn, after_commit| {
+            let record_files = self.load_record_files(conn, name, &schema)?;
 
-            if let Value::Object(ref mut map) = data {
-                for (field_name, url) in file_urls {
-                    map.insert(field_name, Value::String(url));
-                }
+            if let Some(permission_service) = &self.permission_service {
+                permission_service.delete_collection_permissions(conn, collection.id)?;
+                after_commit.defer(async move { permission_service.forget_permissions() });
             }
-        }
-
-        let validated_data = self.validate_record_data(&schema, &data)?;
 
-        let table_name = self.get_records_table_name(collection_name);
-        let mut columns = Vec::new();
-        let mut values = Vec::new();
+            self.drop_records_table(conn, name)?;
+            diesel::delete(
+                collection_record_counts::table
+                    .filter(collection_record_counts::collection_id.eq(collection.id)),
+            )
+            .execute(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+            diesel::delete(
+                collection_activity::table
+                    .filter(collection_activity::collection_id.eq(collection.id)),
+            )
+            .execute(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+            diesel::delete(
+                collection_retention_runs::table
+                    .filter(collection_retention_runs::collection_id.eq(collection.id)),
+            )
+            .execute(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+            diesel::delete(collections::table.filter(collections::id.eq(collection.id)))
+                .execute(conn)
+                .map_err(|_| LunarbaseError::InternalError)?;
 
-        for field in &schema.fields {
-            if let Some(field_value) = validated_data.get(&field.name) {
-                columns.push(field.name.clone());
-                let sql_value = self.value_to_sql_string(field_value, &field.field_type);
-                values.push(sql_value);
-            }
-        }
+            after_commit.defer(async move {
+                self.forget_records_table(name);
+                self.record_cache.invalidate_collection(name);
+                self.query_cache.invalidate_collection(name);
+
+                if let Some(ws_service) = &self.websocket_service {
+                    ws_service
+                        .send_collection_event(
+                            &event_recipients,
+                            CollectionEvent::Deleted {
+                                collection: name.to_string(),
+                            },
+                        )
+                        .await;
+                }
+            });
 
-        let ownership_fields = ["owner_id", "author_id"];
-        for field_name in &ownership_fields {
-            if let Some(field_value) = request.data.get(field_name) {
-                if !columns.contains(&field_name.to_string()) {
-                    columns.push(field_name.to_string());
-                    let sql_value = self.value_to_sql_string(field_value, &FieldType::Number);
-                    values.push(sql_value);
+            let schema = &schema;
+            after_commit.defer(async move {
+                let mut file_deletion_errors = Vec::new();
+                for files in &record_files {
+                    file_deletion_errors.extend(self.delete_record_files(schema, files).await);
                 }
-            }
-        }
+                if !file_deletion_errors.is_empty() {
+                    tracing::warn!(
+                        "Some files could not be deleted for collection {}: {:?}",
+                        name,
+                        file_deletion_errors
+                    );
+                }
+            });
 
-        let insert_sql = format!(
-            "INSERT INTO {} ({}) VALUES ({})",
-            table_name,
-            columns.join(", "),
-            values.join(", ")
-        );
+            Ok(())
+        })
+        .await
+    }
 
-        diesel::sql_query(&insert_sql)
-            .execute(&mut conn)
-            .map_err(|_| LunarbaseError::InternalError)?;
+    /// Moves a collection and its records table to another workspace. Its
+    /// permissions for shared roles keep applying there.
+    pub async fn move_collection_to_workspace(
+        &self,
+        name: &str,
+        workspace_id: i32,
+    ) -> Result<CollectionResponse, LunarbaseError> {
+        let collection = transaction_then(&self.pool, |conn, after_commit| {
+            let collection = collections::table
+                .filter(collections::name.eq(name))
+                .first::<Collection>(conn)
+                .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+
+            if collection.is_system {
+                return Err(LunarbaseError::Forbidden(
+                    "Cannot move system collections".to_string(),
+                ));
+            }
 
-        let select_sql = format!("SELECT * FROM {} ORDER BY id DESC LIMIT 1", table_name);
-        let record_response = self.query_record_by_sql(&mut conn, &select_sql, collection_name)?;
+            if collection.workspace_id != workspace_id {
+                let rename_sql = format!(
+                    "ALTER TABLE {} RENAME TO {}",
+                    records_table_name(collection.workspace_id, name),
+                    records_table_name(workspace_id, name)
+                );
+                diesel::sql_query(&rename_sql)
+                    .execute(conn)
+                    .map_err(|_| LunarbaseError::InternalError)?;
+                diesel::update(collections::table.filter(collections::id.eq(collection.id)))
+                    .set((
+                        collections::workspace_id.eq(workspace_id),
+                        collections::updated_at.eq(chrono::Utc::now().naive_utc()),
+                    ))
+                    .execute(conn)
+                    .map_err(|_| LunarbaseError::InternalError)?;
 
-        let event = crate::models::RecordEvent::Created {
-            record_id: record_response.id.to_string(),
-            record: serde_json::to_value(&record_response.data).unwrap_or_default(),
-        };
-        self.emit_record_event(collection_name, event, user_id)
-            .await;
+                after_commit.defer(async move {
+                    self.forget_records_table(name);
+                    self.record_cache.invalidate_collection(name);
+                    self.query_cache.invalidate_collection(name);
+                });
+            }
 
-        Ok(record_response)
+            collections::table
+                .filter(collections::id.eq(collection.id))
+                .first::<Collection>(conn)
+                .map_err(|_| LunarbaseError::InternalError)
+        })
+        .await?;
+
+        CollectionResponse::from_collection(collection).map_err(|_| LunarbaseError::InternalError)
     }
 
-    pub async fn get_record(
+    pub async fn list_schema_versions(
         &self,
-        collection_name: &str,
-        record_id: i32,
-    ) -> Result<RecordResponse, LunarbaseError> {
+        name: &str,
+    ) -> Result<Vec<CollectionSchemaVersionResponse>, LunarbaseError> {
+        let collection = self.get_collection(name).await?;
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
-        collections::table
-            .filter(collections::name.eq(collection_name))
-            .first::<Collection>(&mut conn)
-            .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+        let versions = collection_schema_versions::table
+            .filter(collection_schema_versions::collection_id.eq(collection.id))
+            .order(collection_schema_versions::version.desc())
+            .select(CollectionSchemaVersion::as_select())
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        versions
+            .into_iter()
+            .map(CollectionSchemaVersionResponse::from_version)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| LunarbaseError::InternalError)
+    }
 
-        let table_name = self.get_records_table_name(collection_name);
-        let select_sql = format!("SELECT * FROM {} WHERE id = {}", table_name, record_id);
+    pub async fn get_schema_version(
+        &self,
+        name: &str,
+        version: i32,
+    ) -> Result<CollectionSchemaVersionResponse, LunarbaseError> {
+        let collection = self.get_collection(name).await?;
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
-        self.query_record_by_sql(&mut conn, &select_sql, collection_name)
+        let schema_version = collection_schema_versions::table
+            .filter(collection_schema_versions::collection_id.eq(collection.id))
+            .filter(collection_schema_versions::version.eq(version))
+            .select(CollectionSchemaVersion::as_select())
+            .first(&mut conn)
+            .optional()
+            .map_err(|_| LunarbaseError::DatabaseError)?
+            .ok_or_else(|| LunarbaseError::NotFound("Schema version not found".to_string()))?;
+
+        CollectionSchemaVersionResponse::from_version(schema_version)
+            .map_err(|_| LunarbaseError::InternalError)
     }
 
-    pub async fn list_records(
+    /// Re-applies an old schema through [`Self::update_collection`], so the records
+    /// table is migrated and the restore itself becomes a new version.
+    pub async fn restore_schema_version(
         &self,
-        collection_name: &str,
-        sort: Option<String>,
-        filter: Option<String>,
-        search: Option<String>,
-        limit: Option<i64>,
-        offset: Option<i64>,
-    ) -> Result<Vec<RecordResponse>, LunarbaseError> {
-        tracing::debug!(
-            "list_records called with collection_name={}, sort={:?}, filter={:?}, limit={:?}, offset={:?}",
-            collection_name,
-            sort,
-            filter,
-            limit,
-            offset
-        );
+        name: &str,
+        version: i32,
+        actor_id: Option<i32>,
+    ) -> Result<CollectionResponse, LunarbaseError> {
+        let schema_version = self.get_schema_version(name, version).await?;
+
+        let request = UpdateCollectionRequest {
+            name: None,
+            display_name: None,
+            description: None,
+            schema: Some(schema_version.schema),
+            query_cache_ttl_seconds: None,
+            orderable: None,
+            id_type: None,
+            allow_explicit_ids: None,
+            workflow: None,
+            schedule: None,
+            retention: None,
+        };
 
-        let mut conn = self.pool.get().map_err(|e| {
-            tracing::error!("Failed to get database connection: {:?}", e);
-            LunarbaseError::InternalError
-        })?;
+        self.update_collection(name, request, actor_id).await
+    }
 
-        let collection = collections::table
-            .filter(collections::name.eq(collection_name))
-            .first::<Collection>(&mut conn)
+    fn record_schema_version(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_id: i32,
+        version: i32,
+        schema_json: &str,
+        actor_id: Option<i32>,
+        migration_summary: Option<String>,
+    ) -> Result<(), LunarbaseError> {
+        let new_version = NewCollectionSchemaVersion {
+            collection_id,
+            version,
+            schema_json: schema_json.to_string(),
+            created_by: actor_id,
+            migration_summary,
+        };
+
+        diesel::insert_into(collection_schema_versions::table)
+            .values(&new_version)
+            .execute(conn)
             .map_err(|e| {
-                tracing::error!("Failed to find collection '{}': {:?}", collection_name, e);
-                LunarbaseError::NotFound("Collection not found".to_string())
+                tracing::error!(
+                    "Failed to record schema version {} for collection {}: {:?}",
+                    version,
+                    collection_id,
+                    e
+                );
+                LunarbaseError::DatabaseError
             })?;
 
-        let schema = collection.get_schema().map_err(|e| {
-            tracing::error!("Failed to parse collection schema: {:?}", e);
-            LunarbaseError::InternalError
-        })?;
-
-        let query_engine = QueryEngine::new(sort, filter, search, limit, offset).map_err(|e| {
-            tracing::error!("Failed to create QueryEngine: {:?}", e);
-            e
-        })?;
+        Ok(())
+    }
 
-        let table_name = self.get_records_table_name(collection_name);
-        let (sql, parameters) = query_engine.build_complete_query(&table_name, &schema)?;
+    /// Compares the records table against the stored schema without changing anything.
+    pub async fn verify_collection(
+        &self,
+        name: &str,
+    ) -> Result<CollectionIntegrityReport, LunarbaseError> {
+        let collection = self.get_collection(name).await?;
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
-        tracing::debug!(
-            "Executing SQL: {} with {} parameters",
-            sql,
-            parameters.len()
-        );
+        let issues = self.inspect_records_table(
+            &mut conn,
+            name,
+            &collection.schema,
+            collection.orderable,
+            collection.workflow,
+            collection.id_type,
+        )?;
+
+        Ok(CollectionIntegrityReport {
+            collection_name: name.to_string(),
+            table_name: self.get_records_table_name(name),
+            healthy: issues.is_empty(),
+            issues,
+        })
+    }
 
-        use diesel::sql_types::*;
+    /// Checks the records table of one collection: its structure, indexes and
+    /// triggers, whether its row count matches the cached count and when it
+    /// was last written. `integrity_check` adds `PRAGMA integrity_check` on
+    /// the table's pages, which reads all of them.
+    pub async fn collection_health(
+        &self,
+        name: &str,
+        integrity_check: bool,
+    ) -> Result<CollectionHealthReport, LunarbaseError> {
+        let collection = self.get_collection(name).await?;
+        let table_name = self.get_records_table_name(name);
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let mut report = CollectionHealthReport {
+            collection_name: name.to_string(),
+            table_name: table_name.clone(),
+            status: COMPONENT_HEALTHY.to_string(),
+            checks: std::collections::BTreeMap::new(),
+            record_count: None,
+            cached_record_count: None,
+            last_write_at: None,
+            checked_at: chrono::Utc::now().to_rfc3339(),
+        };
 
-        #[derive(Debug, diesel::QueryableByName)]
-        struct RecordRow {
-            #[diesel(sql_type = Integer)]
-            id: i32,
+        if !self.sqlite_object_exists(&mut conn, "table", &table_name)? {
+            report.checks.insert(
+                "table".to_string(),
+                ComponentHealth::new(
+                    COMPONENT_UNHEALTHY,
+                    Some(format!("Records table '{}' does not exist", table_name)),
+                    None,
+                ),
+            );
+            report.status = COMPONENT_UNHEALTHY.to_string();
+            return Ok(report);
         }
+        report.checks.insert(
+            "table".to_string(),
+            ComponentHealth::new(COMPONENT_HEALTHY, None, None),
+        );
 
-        let mut final_sql = sql;
-        for param in parameters.iter() {
-            let escaped_param = param.replace("'", "''");
-            final_sql = final_sql.replacen("?", &format!("'{}'", escaped_param), 1);
+        let issues = self.inspect_records_table(
+            &mut conn,
+            name,
+            &collection.schema,
+            collection.orderable,
+            collection.workflow,
+            collection.id_type,
+        )?;
+        for (check, kinds) in [
+            (
+                "columns",
+                &[
+                    IntegrityIssueKind::MissingColumn,
+                    IntegrityIssueKind::ExtraColumn,
+                    IntegrityIssueKind::TypeMismatch,
+                ][..],
+            ),
+            ("indexes", &[IntegrityIssueKind::MissingIndex][..]),
+            ("triggers", &[IntegrityIssueKind::MissingTrigger][..]),
+        ] {
+            let messages: Vec<&str> = issues
+                .iter()
+                .filter(|issue| kinds.contains(&issue.kind))
+                .map(|issue| issue.message.as_str())
+                .collect();
+            let health = if messages.is_empty() {
+                ComponentHealth::new(COMPONENT_HEALTHY, None, None)
+            } else {
+                ComponentHealth::new(COMPONENT_DEGRADED, Some(messages.join("; ")), None)
+            };
+            report.checks.insert(check.to_string(), health);
         }
 
-        tracing::debug!("Final SQL after parameter substitution: {}", final_sql);
-
-        let rows: Vec<RecordRow> = diesel::sql_query(&final_sql).load(&mut conn).map_err(|e| {
-            tracing::error!("Failed to execute SQL query '{}': {:?}", final_sql, e);
-            LunarbaseError::InternalError
-        })?;
-
-        let mut responses = Vec::new();
-        for row in rows {
-            let select_sql = format!("SELECT * FROM {} WHERE id = {}", table_name, row.id);
-            let response = self.query_record_by_sql(&mut conn, &select_sql, collection_name)?;
-            responses.push(response);
+        let integrity = if integrity_check {
+            let started = std::time::Instant::now();
+            let mut health = self.check_table_integrity(&mut conn, &table_name);
+            health.latency_ms = Some(started.elapsed().as_millis() as u64);
+            health
+        } else {
+            ComponentHealth::new(
+                COMPONENT_DISABLED,
+                Some("Only run for a single collection".to_string()),
+                None,
+            )
+        };
+        report.checks.insert("integrity".to_string(), integrity);
+
+        #[derive(diesel::QueryableByName)]
+        struct TableStats {
+            #[diesel(sql_type = diesel::sql_types::BigInt)]
+            record_count: i64,
+            #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+            last_write_at: Option<String>,
         }
 
-        Ok(responses)
+        // Fails on a table missing its timestamp columns, which the columns
+        // check already reports
+        let Ok(stats) = diesel::sql_query(format!(
+            "SELECT COUNT(*) AS record_count, \
+             MAX(COALESCE(updated_at, created_at)) AS last_write_at FROM {}",
+            table_name
+        ))
+        .get_result::<TableStats>(&mut conn) else {
+            for check in ["record_count", "last_write"] {
+                report.checks.insert(
+                    check.to_string(),
+                    ComponentHealth::new(
+                        COMPONENT_UNHEALTHY,
+                        Some(format!("Could not read table '{}'", table_name)),
+                        None,
+                    ),
+                );
+            }
+            report.status = COMPONENT_UNHEALTHY.to_string();
+            return Ok(report);
+        };
+        let cached_record_count = collection_record_counts::table
+            .filter(collection_record_counts::collection_id.eq(collection.id))
+            .select(collection_record_counts::record_count)
+            .first::<i64>(&mut conn)
+            .optional()
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let record_count = match cached_record_count {
+            Some(cached) if cached != stats.record_count => ComponentHealth::new(
+                COMPONENT_DEGRADED,
+                Some(format!(
+                    "Cached count {} differs from the {} rows in the table",
+                    cached, stats.record_count
+                )),
+                None,
+            ),
+            Some(_) => ComponentHealth::new(
+                COMPONENT_HEALTHY,
+                Some(format!("{} records", stats.record_count)),
+                None,
+            ),
+            None => ComponentHealth::new(
+                COMPONENT_HEALTHY,
+                Some(format!("{} records, not counted yet", stats.record_count)),
+                None,
+            ),
+        };
+        report
+            .checks
+            .insert("record_count".to_string(), record_count);
+        report.checks.insert(
+            "last_write".to_string(),
+            ComponentHealth::new(
+                COMPONENT_HEALTHY,
+                Some(match &stats.last_write_at {
+                    Some(at) => format!("Last record written at {}", at),
+                    None => "No records written yet".to_string(),
+                }),
+                None,
+            ),
+        );
+
+        report.status =
+            worst_status(report.checks.values().map(|health| health.status.as_str())).to_string();
+        report.record_count = Some(stats.record_count);
+        report.cached_record_count = cached_record_count;
+        report.last_write_at = stats.last_write_at;
+        Ok(report)
     }
 
-    pub async fn update_record(
+    /// `PRAGMA integrity_check` limited to `table_name` and its indexes.
+    fn check_table_integrity(
         &self,
-        collection_name: &str,
-        record_id: i32,
-        request: UpdateRecordRequest,
-    ) -> Result<RecordResponse, LunarbaseError> {
-        self.update_record_with_events(collection_name, record_id, request, None)
-            .await
+        conn: &mut SqliteConnection,
+        table_name: &str,
+    ) -> ComponentHealth {
+        #[derive(diesel::QueryableByName)]
+        struct IntegrityRow {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            integrity_check: String,
+        }
+
+        let rows = diesel::sql_query(format!("PRAGMA integrity_check('{}')", table_name))
+            .load::<IntegrityRow>(conn);
+        match rows {
+            Ok(rows) if rows.iter().all(|row| row.integrity_check == "ok") => {
+                ComponentHealth::new(COMPONENT_HEALTHY, None, None)
+            }
+            Ok(rows) => ComponentHealth::new(
+                COMPONENT_UNHEALTHY,
+                Some(
+                    rows.iter()
+                        .take(5)
+                        .map(|row| row.integrity_check.as_str())
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                ),
+                None,
+            ),
+            Err(e) => {
+                tracing::warn!("Integrity check of '{}' failed: {:?}", table_name, e);
+                ComponentHealth::new(
+                    COMPONENT_UNHEALTHY,
+                    Some("The integrity check could not be run".to_string()),
+                    None,
+                )
+            }
+        }
     }
 
-    pub async fn update_record_with_events(
+    /// Applies the non-destructive fixes found by [`Self::verify_collection`]:
+    /// missing tables, columns, indexes and triggers. Extra columns and type
+    /// mismatches are left for manual intervention.
+    pub async fn repair_collection(
         &self,
-        collection_name: &str,
-        record_id: i32,
-        request: UpdateRecordRequest,
-        user_id: Option<i32>,
-    ) -> Result<RecordResponse, LunarbaseError> {
+        name: &str,
+    ) -> Result<CollectionRepairReport, LunarbaseError> {
+        let collection = self.get_collection(name).await?;
+        let schema = collection.schema;
+        let orderable = collection.orderable;
+        let workflow = collection.workflow;
+        let id_type = collection.id_type;
+        let table_name = self.get_records_table_name(name);
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
-        let collection = collections::table
-            .filter(collections::name.eq(collection_name))
-            .first::<Collection>(&mut conn)
-            .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+        let issues =
+            self.inspect_records_table(&mut conn, name, &schema, orderable, workflow, id_type)?;
+        let mut applied = Vec::new();
+
+        for issue in issues.iter().filter(|issue| issue.auto_fixable) {
+            let result = match issue.kind {
+                IntegrityIssueKind::MissingTable => self
+                    .create_records_table(&mut conn, name, &schema, id_type)
+                    .map(|_| format!("Created table '{}'", table_name)),
+                IntegrityIssueKind::MissingColumn if issue.target == SORT_ORDER_COLUMN => self
+                    .enable_manual_ordering(&mut conn, name)
+                    .map(|_| format!("Added column '{}'", issue.target)),
+                IntegrityIssueKind::MissingColumn
+                    if issue.target == STATUS_COLUMN || issue.target == PUBLISH_AT_COLUMN =>
+                {
+                    self.enable_workflow(&mut conn, name)
+                        .map(|_| format!("Added column '{}'", issue.target))
+                }
+                IntegrityIssueKind::MissingColumn => {
+                    let add_column_sql = schema
+                        .fields
+                        .iter()
+                        .find_map(|field| {
+                            field
+                                .storage_columns()
+                                .into_iter()
+                                .zip(self.generate_add_column_sql(&table_name, field))
+                                .find(|(column, _)| *column == issue.target)
+                                .map(|(_, sql)| sql)
+                        })
+                        .unwrap_or_else(|| {
+                            format!(
+                                "ALTER TABLE {} ADD COLUMN {} INTEGER",
+                                table_name, issue.target
+                            )
+                        });
+                    self.execute_repair(&mut conn, &add_column_sql)
+                        .map(|_| format!("Added column '{}'", issue.target))
+                }
+                IntegrityIssueKind::MissingIndex => {
+                    let index_sql = format!(
+                        "CREATE INDEX IF NOT EXISTS {} ON {} (created_at)",
+                        issue.target, table_name
+                    );
+                    self.execute_repair(&mut conn, &index_sql)
+                        .map(|_| format!("Created index '{}'", issue.target))
+                }
+                IntegrityIssueKind::MissingTrigger => {
+                    let trigger_sql = format!(
+                        "CREATE TRIGGER IF NOT EXISTS {} 
+             AFTER UPDATE ON {}
+             BEGIN
+                 UPDATE {} SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+             END",
+                        issue.target, table_name, table_name
+                    );
+                    self.execute_repair(&mut conn, &trigger_sql)
+                        .map(|_| format!("Created trigger '{}'", issue.target))
+                }
+                IntegrityIssueKind::ExtraColumn | IntegrityIssueKind::TypeMismatch => continue,
+            };
 
-        let schema = collection
-            .get_schema()
-            .map_err(|_| LunarbaseError::InternalError)?;
+            match result {
+                Ok(description) => applied.push(description),
+                Err(_) => tracing::warn!(
+                    "Could not repair {:?} '{}' on collection '{}'",
+                    issue.kind,
+                    issue.target,
+                    name
+                ),
+            }
+        }
 
-        let table_name = self.get_records_table_name(collection_name);
-        let select_sql = format!("SELECT * FROM {} WHERE id = {}", table_name, record_id);
-        let old_record = self
-            .query_record_by_sql(&mut conn, &select_sql, collection_name)
-            .ok();
+        if !applied.is_empty() {
+            self.record_cache.invalidate_collection(name);
+            self.query_cache.invalidate_collection(name);
+        }
 
-        let mut data = request.data.clone();
-        if let Some(files) = &request.files {
-            if let Some(ref old_rec) = old_record {
-                if let Some(s3_service) = &self.s3_service {
-                    for (field_name, _) in files {
-                        if let Some(field_def) =
-                            schema.fields.iter().find(|f| f.name == *field_name)
-                        {
-                            if field_def.field_type == FieldType::File {
-                                if let Some(old_url) = old_rec.data.get(field_name) {
-                                    if let Some(url_str) = old_url.as_str() {
-                                        if !url_str.is_empty() {
-                                            if let Err(e) = s3_service.delete_file(url_str).await {
-                                                tracing::warn!(
-                                                    "Failed to delete old file '{}' for field '{}': {}",
-                                                    url_str,
-                                                    field_name,
-                                                    e
-                                                );
-                                            } else {
-                                                debug!(
-                                                    "Deleted old file '{}' for field '{}'",
-                                                    url_str, field_name
-                                                );
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        let unresolved =
+            self.inspect_records_table(&mut conn, name, &schema, orderable, workflow, id_type)?;
+
+        Ok(CollectionRepairReport {
+            collection_name: name.to_string(),
+            applied,
+            unresolved,
+        })
+    }
 
-            let file_urls = self.process_file_uploads(&schema, files).await?;
+    fn execute_repair(&self, conn: &mut SqliteConnection, sql: &str) -> Result<(), LunarbaseError> {
+        tracing::debug!("Repairing records table with SQL: {}", sql);
+        diesel::sql_query(sql).execute(conn).map_err(|e| {
+            tracing::error!("Repair statement failed: {:?}", e);
+            LunarbaseError::InternalError
+        })?;
+        Ok(())
+    }
 
-            if let Value::Object(ref mut map) = data {
-                for (field_name, url) in file_urls {
-                    map.insert(field_name, Value::String(url));
-                }
-            }
+    fn inspect_records_table(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+        schema: &CollectionSchema,
+        orderable: bool,
+        workflow: CollectionWorkflow,
+        id_type: RecordIdType,
+    ) -> Result<Vec<IntegrityIssue>, LunarbaseError> {
+        let table_name = self.get_records_table_name(collection_name);
+        let mut issues = Vec::new();
+
+        if !self.sqlite_object_exists(conn, "table", &table_name)? {
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::MissingTable,
+                target: table_name.clone(),
+                expected: None,
+                actual: None,
+                auto_fixable: true,
+                message: format!("Records table '{}' does not exist", table_name),
+            });
+            return Ok(issues);
         }
 
-        let validated_data = self.validate_record_data(&schema, &data)?;
+        #[derive(Debug, diesel::QueryableByName)]
+        struct ColumnInfo {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            name: String,
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            #[diesel(column_name = type_)]
+            column_type: String,
+        }
 
-        let table_name = self.get_records_table_name(collection_name);
-        let mut set_clauses = Vec::new();
+        let pragma_sql = format!(
+            "SELECT name, type AS type_ FROM pragma_table_info('{}')",
+            table_name
+        );
+        let actual_columns: Vec<ColumnInfo> =
+            diesel::sql_query(&pragma_sql).load(conn).map_err(|e| {
+                tracing::error!("Failed to get table info: {:?}", e);
+                LunarbaseError::InternalError
+            })?;
 
+        let id_column_type = match id_type {
+            RecordIdType::Integer => "INTEGER",
+            RecordIdType::Uuid => "TEXT",
+        };
+        let mut expected_columns: Vec<(String, &'static str, bool)> =
+            vec![("id".to_string(), id_column_type, false)];
         for field in &schema.fields {
-            if let Some(field_value) = validated_data.get(&field.name) {
-                let sql_value = self.value_to_sql_string(field_value, &field.field_type);
-                set_clauses.push(format!("{} = {}", field.name, sql_value));
+            if field.name.to_lowercase() == "id"
+                || field.name == "author_id"
+                || field.name == "owner_id"
+                || field.name == "created_at"
+                || field.name == "updated_at"
+            {
+                continue;
+            }
+            for column in field.storage_columns() {
+                expected_columns.push((
+                    column,
+                    self.map_field_type_to_sql(&field.field_type),
+                    true,
+                ));
+            }
+        }
+        expected_columns.push(("author_id".to_string(), "INTEGER", true));
+        expected_columns.push(("owner_id".to_string(), "INTEGER", true));
+        if orderable {
+            expected_columns.push((SORT_ORDER_COLUMN.to_string(), "REAL", true));
+        }
+        if workflow == CollectionWorkflow::DraftPublish {
+            expected_columns.push((STATUS_COLUMN.to_string(), "TEXT", true));
+            expected_columns.push((PUBLISH_AT_COLUMN.to_string(), "TIMESTAMP", true));
+        }
+        // SQLite cannot add columns with a CURRENT_TIMESTAMP default to an existing table.
+        expected_columns.push(("created_at".to_string(), "TIMESTAMP", false));
+        expected_columns.push(("updated_at".to_string(), "TIMESTAMP", false));
+
+        for (column_name, expected_type, can_add) in &expected_columns {
+            match actual_columns
+                .iter()
+                .find(|column| column.name.eq_ignore_ascii_case(column_name))
+            {
+                None => issues.push(IntegrityIssue {
+                    kind: IntegrityIssueKind::MissingColumn,
+                    target: column_name.clone(),
+                    expected: Some(expected_type.to_string()),
+                    actual: None,
+                    auto_fixable: *can_add,
+                    message: format!(
+                        "Column '{}' is defined in the schema but missing from the table",
+                        column_name
+                    ),
+                }),
+                Some(column) if !column.column_type.eq_ignore_ascii_case(expected_type) => issues
+                    .push(IntegrityIssue {
+                        kind: IntegrityIssueKind::TypeMismatch,
+                        target: column_name.clone(),
+                        expected: Some(expected_type.to_string()),
+                        actual: Some(column.column_type.clone()),
+                        auto_fixable: false,
+                        message: format!(
+                            "Column '{}' has type '{}' but the schema expects '{}'",
+                            column_name, column.column_type, expected_type
+                        ),
+                    }),
+                Some(_) => {}
             }
         }
 
-        let ownership_fields = ["owner_id", "author_id"];
-        for field_name in &ownership_fields {
-            if let Some(field_value) = data.get(field_name) {
-                let already_processed = schema.fields.iter().any(|f| f.name == *field_name);
-                if !already_processed {
-                    let sql_value = self.value_to_sql_string(field_value, &FieldType::Number);
-                    set_clauses.push(format!("{} = {}", field_name, sql_value));
-                }
+        for column in &actual_columns {
+            // Turning `orderable` or the workflow off keeps their columns, so they may linger
+            let is_expected = expected_columns
+                .iter()
+                .any(|(name, _, _)| name.eq_ignore_ascii_case(&column.name))
+                || [SORT_ORDER_COLUMN, STATUS_COLUMN, PUBLISH_AT_COLUMN]
+                    .iter()
+                    .any(|managed| column.name.eq_ignore_ascii_case(managed));
+            if !is_expected {
+                issues.push(IntegrityIssue {
+                    kind: IntegrityIssueKind::ExtraColumn,
+                    target: column.name.clone(),
+                    expected: None,
+                    actual: Some(column.column_type.clone()),
+                    auto_fixable: false,
+                    message: format!(
+                        "Column '{}' exists in the table but not in the schema",
+                        column.name
+                    ),
+                });
             }
         }
 
-        if set_clauses.is_empty() {
-            return Err(LunarbaseError::ValidationError(vec![
-                "No fields to update".to_string(),
-            ]));
+        let index_name = format!("idx_{}_created_at", table_name);
+        if !self.sqlite_object_exists(conn, "index", &index_name)? {
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::MissingIndex,
+                target: index_name.clone(),
+                expected: None,
+                actual: None,
+                auto_fixable: true,
+                message: format!("Index '{}' is missing", index_name),
+            });
         }
 
-        let update_sql = format!(
-            "UPDATE {} SET {}, updated_at = CURRENT_TIMESTAMP WHERE id = {}",
-            table_name,
-            set_clauses.join(", "),
-            record_id
-        );
+        let trigger_name = format!("update_{}_updated_at", table_name);
+        if !self.sqlite_object_exists(conn, "trigger", &trigger_name)? {
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::MissingTrigger,
+                target: trigger_name.clone(),
+                expected: None,
+                actual: None,
+                auto_fixable: true,
+                message: format!("Trigger '{}' is missing", trigger_name),
+            });
+        }
 
-        let affected_rows = diesel::sql_query(&update_sql)
-            .execute(&mut conn)
-            .map_err(|_| LunarbaseError::InternalError)?;
+        Ok(issues)
+    }
 
-        if affected_rows == 0 {
-            return Err(LunarbaseError::NotFound("Record not found".to_string()));
+    fn sqlite_object_exists(
+        &self,
+        conn: &mut SqliteConnection,
+        object_type: &str,
+        name: &str,
+    ) -> Result<bool, LunarbaseError> {
+        #[derive(Debug, diesel::QueryableByName)]
+        struct CountResult {
+            #[diesel(sql_type = diesel::sql_types::BigInt)]
+            count: i64,
         }
 
-        let record_response = self.query_record_by_sql(&mut conn, &select_sql, collection_name)?;
-
-        let event = crate::models::RecordEvent::Updated {
-            record_id: record_response.id.to_string(),
-            record: serde_json::to_value(&record_response.data).unwrap_or_default(),
-            old_record: old_record.map(|r| serde_json::to_value(&r.data).unwrap_or_default()),
-        };
-        self.emit_record_event(collection_name, event, user_id)
-            .await;
+        let result: CountResult = diesel::sql_query(
+            "SELECT COUNT(*) AS count FROM sqlite_master WHERE type = ? AND name = ?",
+        )
+        .bind::<diesel::sql_types::Text, _>(object_type)
+        .bind::<diesel::sql_types::Text, _>(name)
+        .get_result(conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
 
-        Ok(record_response)
+        Ok(result.count > 0)
     }
 
-    pub async fn delete_record(
-        &self,
-        collection_name: &str,
-        record_id: i32,
-    ) -> Result<(), LunarbaseError> {
-        self.delete_record_with_events(collection_name, record_id, None)
-            .await
+    /// Sweeps orphans left by a crash during a schema change, dropping them
+    /// only when `database.cleanup_orphans_on_start` is enabled. Either way
+    /// they are logged and reported by the health endpoint.
+    pub async fn sweep_orphans_on_start(&self) {
+        let cleanup = self.get_cleanup_orphans_on_start().await;
+        // Nothing can be mid-change before the server starts, so every
+        // temporary table counts regardless of its age
+        match self.run_orphan_sweep(!cleanup, chrono::Utc::now().timestamp()) {
+            Ok(report) => {
+                for orphan in &report.orphans {
+                    tracing::warn!(
+                        "Orphaned {:?} '{}' on '{}': {}{}",
+                        orphan.kind,
+                        orphan.name,
+                        orphan.table_name,
+                        orphan.reason,
+                        if report.dropped.contains(&orphan.name) {
+                            " (dropped)"
+                        } else {
+                            ""
+                        }
+                    );
+                }
+            }
+            Err(e) => tracing::warn!("Failed to sweep orphaned schema objects: {}", e),
+        }
     }
 
-    async fn delete_collection_files(
-        &self,
-        collection_name: &str,
-        schema: &CollectionSchema,
-    ) -> Vec<String> {
-        let mut errors = Vec::new();
+    /// Finds tables, indexes and triggers left behind by interrupted schema
+    /// changes and drops them unless `dry_run`. Temporary tables created in
+    /// the last five minutes are skipped.
+    pub async fn sweep_orphans(&self, dry_run: bool) -> Result<OrphanSweepReport, LunarbaseError> {
+        self.run_orphan_sweep(
+            dry_run,
+            chrono::Utc::now().timestamp() - ORPHAN_MIN_AGE_SECONDS,
+        )
+    }
 
-        let has_file_fields = schema
-            .fields
-            .iter()
-            .any(|field| field.field_type == FieldType::File);
-        if !has_file_fields {
-            return errors;
-        }
+    /// Report of the most recent sweep, `None` before the first one.
+    pub fn last_orphan_sweep(&self) -> Option<OrphanSweepReport> {
+        self.last_orphan_sweep.read().unwrap().clone()
+    }
 
-        match self
-            .list_records(collection_name, None, None, None, None, None)
-            .await
-        {
-            Ok(records) => {
-                for record in records {
-                    let file_deletion_errors = self.delete_record_files(schema, &record.data).await;
-                    errors.extend(file_deletion_errors);
+    fn run_orphan_sweep(
+        &self,
+        dry_run: bool,
+        created_before: i64,
+    ) -> Result<OrphanSweepReport, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let orphans = self.find_orphans(&mut conn, created_before)?;
+
+        let mut dropped = Vec::new();
+        if !dry_run {
+            // Triggers and indexes before their tables, so each drop is explicit
+            let mut ordered: Vec<&OrphanObject> = orphans.iter().collect();
+            ordered.sort_by_key(|orphan| std::cmp::Reverse(orphan.kind));
+            for orphan in ordered {
+                let keyword = match orphan.kind {
+                    OrphanObjectKind::Table => "TABLE",
+                    OrphanObjectKind::Index => "INDEX",
+                    OrphanObjectKind::Trigger => "TRIGGER",
+                };
+                match diesel::sql_query(format!("DROP {} IF EXISTS {}", keyword, orphan.name))
+                    .execute(&mut conn)
+                {
+                    Ok(_) => dropped.push(orphan.name.clone()),
+                    Err(e) => tracing::error!(
+                        "Failed to drop orphaned {:?} '{}': {:?}",
+                        orphan.kind,
+                        orphan.name,
+                        e
+                    ),
                 }
             }
-            Err(e) => {
-                errors.push(format!(
-                    "Failed to retrieve records for collection {}: {:?}",
-                    collection_name, e
-                ));
-            }
         }
 
-        errors
+        let report = OrphanSweepReport {
+            dry_run,
+            orphans,
+            dropped,
+            swept_at: chrono::Utc::now().to_rfc3339(),
+        };
+        *self.last_orphan_sweep.write().unwrap() = Some(report.clone());
+        Ok(report)
     }
 
-    async fn delete_record_files(
+    /// Temporary records tables created before `created_before` (a Unix
+    /// timestamp) that no collection uses, and the indexes and triggers of
+    /// records tables that belong to no current collection.
+    fn find_orphans(
         &self,
-        schema: &CollectionSchema,
-        record_data: &serde_json::Value,
-    ) -> Vec<String> {
-        let mut errors = Vec::new();
-
-        let s3_service = match &self.s3_service {
-            Some(service) => service,
-            None => {
-                tracing::debug!("S3 service not available, skipping file deletion");
-                return errors;
-            }
-        };
+        conn: &mut SqliteConnection,
+        created_before: i64,
+    ) -> Result<Vec<OrphanObject>, LunarbaseError> {
+        #[derive(Debug, diesel::QueryableByName)]
+        struct SchemaObject {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            #[diesel(column_name = type_)]
+            object_type: String,
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            name: String,
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            tbl_name: String,
+        }
 
-        let file_fields: Vec<&FieldDefinition> = schema
-            .fields
+        let live_tables: std::collections::HashSet<String> = collections::table
+            .select(collections::name)
+            .load::<String>(conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?
             .iter()
-            .filter(|field| matches!(field.field_type, FieldType::File))
+            .map(|name| self.get_records_table_name(name))
             .collect();
 
-        if file_fields.is_empty() {
-            return errors;
-        }
-
-        for field in file_fields {
-            if let Some(file_url_value) = record_data.get(&field.name) {
-                if let Some(file_url) = file_url_value.as_str() {
-                    if !file_url.is_empty() {
-                        match s3_service.delete_file(file_url).await {
-                            Ok(_) => {
-                                debug!(
-                                    "Successfully deleted file '{}' for field '{}'",
-                                    file_url, field.name
-                                );
-                            }
-                            Err(e) => {
-                                let error_msg = format!(
-                                    "Failed to delete file '{}' for field '{}': {}",
-                                    file_url, field.name, e
-                                );
-                                tracing::error!("{}", error_msg);
-                                errors.push(error_msg);
-                            }
-                        }
-                    }
-                }
+        // Automatic indexes have no SQL and go away with their table
+        let objects: Vec<SchemaObject> = diesel::sql_query(
+            "SELECT type AS type_, name, tbl_name FROM sqlite_master \
+             WHERE type IN ('table', 'index', 'trigger') AND sql IS NOT NULL \
+             ORDER BY tbl_name, type, name",
+        )
+        .load(conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let mut orphans = Vec::new();
+        for object in objects {
+            if !is_records_table_name(&object.tbl_name) || live_tables.contains(&object.tbl_name) {
+                continue;
+            }
+            let temp_created_at = temp_table_created_at(&object.tbl_name);
+            if temp_created_at.is_some_and(|created_at| created_at >= created_before) {
+                continue;
             }
+
+            let (kind, reason) = match (object.object_type.as_str(), temp_created_at) {
+                ("table", Some(_)) => (
+                    OrphanObjectKind::Table,
+                    "Temporary table of an unfinished schema change".to_string(),
+                ),
+                // Tables of deleted collections may still hold data worth keeping
+                ("table", None) => continue,
+                ("index", _) => (
+                    OrphanObjectKind::Index,
+                    format!("Table '{}' belongs to no collection", object.tbl_name),
+                ),
+                ("trigger", _) => (
+                    OrphanObjectKind::Trigger,
+                    format!("Table '{}' belongs to no collection", object.tbl_name),
+                ),
+                _ => continue,
+            };
+            orphans.push(OrphanObject {
+                kind,
+                name: object.name,
+                table_name: object.tbl_name,
+                reason,
+            });
         }
 
-        errors
+        Ok(orphans)
     }
 
-    pub async fn delete_record_with_events(
+    pub async fn create_record(
         &self,
         collection_name: &str,
-        record_id: i32,
-        user_id: Option<i32>,
-    ) -> Result<(), LunarbaseError> {
-        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        request: CreateRecordRequest,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        self.create_record_with_events(collection_name, request, None)
+            .await
+    }
 
-        let collection = collections::table
-            .filter(collections::name.eq(collection_name))
-            .first::<Collection>(&mut conn)
-            .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+    pub async fn create_record_with_events(
+        &self,
+        collection_name: &str,
+        request: CreateRecordRequest,
+        user_id: Option<i32>,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        self.create_record_inner(collection_name, request, user_id, None)
+            .await
+    }
 
-        let schema = collection
-            .get_schema()
-            .map_err(|_| LunarbaseError::InternalError)?;
+    /// Creates a record without an owner for a guest session, which is
+    /// recorded in `guest_session_records` in the same transaction.
+    pub async fn create_guest_record(
+        &self,
+        collection_name: &str,
+        request: CreateRecordRequest,
+        session_id: &str,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        self.create_record_inner(collection_name, request, None, Some(session_id))
+            .await
+    }
 
-        let table_name = self.get_records_table_name(collection_name);
+    async fn create_record_inner(
+        &self,
+        collection_name: &str,
+        request: CreateRecordRequest,
+        user_id: Option<i32>,
+        guest_session: Option<&str>,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
-        let select_sql = format!("SELECT * FROM {} WHERE id = {}", table_name, record_id);
-        let old_record = self
-            .query_record_by_sql(&mut conn, &select_sql, collection_name)
-            .ok();
+        let collection = collections::table
+            .filter(collections::name.eq(collection_name))
+            .first::<Collection>(&mut conn)
+            .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
 
-        let delete_sql = format!("DELETE FROM {} WHERE id = {}", table_name, record_id);
-        let deleted_rows = diesel::sql_query(&delete_sql)
-            .execute(&mut conn)
+        let schema = collection
+            .get_schema()
             .map_err(|_| LunarbaseError::InternalError)?;
 
-        if deleted_rows == 0 {
-            return Err(LunarbaseError::NotFound("Record not found".to_string()));
-        }
+        let mut data = request.data.clone();
+        if let Some(files) = &request.files {
+            let file_urls = self
+                .process_file_uploads(collection_name, &schema, files, user_id)
+                .await?;
 
-        if let Some(ref record) = old_record {
-            let file_deletion_errors = self.delete_record_files(&schema, &record.data).await;
-            if !file_deletion_errors.is_empty() {
-                tracing::warn!(
-                    "Some files could not be deleted for record {} in collection {}: {:?}",
-                    record_id,
-                    collection_name,
-                    file_deletion_errors
-                );
+            if let Value::Object(ref mut map) = data {
+                for (field_name, url) in file_urls {
+                    map.insert(field_name, Value::String(url));
+                }
             }
         }
 
-        let event = crate::models::RecordEvent::Deleted {
-            record_id: record_id.to_string(),
-            old_record: old_record.map(|r| serde_json::to_value(&r.data).unwrap_or_default()),
+        // Slug collisions are checked and resolved under the same write lock as the insert
+        let record_response = conn.immediate_transaction(|conn| {
+            let record = self.insert_record_row(conn, collection_name, &schema, &data)?;
+            if let Some(session_id) = guest_session {
+                diesel::insert_into(guest_session_records::table)
+                    .values(&NewGuestSessionRecord {
+                        session_id: session_id.to_string(),
+                        collection_name: collection_name.to_string(),
+                        record_id: record.id.to_string(),
+                    })
+                    .execute(conn)
+                    .map_err(|_| LunarbaseError::DatabaseError)?;
+            }
+            Ok::<_, LunarbaseError>(record)
+        })?;
+
+        let event = crate::models::RecordEvent::Created {
+            record_id: record_response.id.to_string(),
+            record: serde_json::to_value(&record_response.data).unwrap_or_default(),
         };
         self.emit_record_event(collection_name, event, user_id)
             .await;
 
-        Ok(())
+        Ok(record_response)
     }
 
-    pub async fn get_collections_stats(
+    /// Validates a record id taken from a path or request body against the
+    /// collection's id type and returns it in canonical form.
+    pub async fn resolve_record_id(
         &self,
-    ) -> Result<
-        (
-            i64,
-            std::collections::HashMap<String, i64>,
-            std::collections::HashMap<String, i64>,
-            f64,
-            Option<String>,
-            Option<String>,
-        ),
-        LunarbaseError,
-    > {
+        collection_name: &str,
+        record_id: &str,
+    ) -> Result<String, LunarbaseError> {
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
-        let collections = self.list_collections().await?;
-        let total_collections = collections.len() as i64;
-
-        let mut total_records = 0i64;
-        let mut records_per_collection = std::collections::HashMap::new();
-        let mut field_types_distribution = std::collections::HashMap::new();
-
-        let mut max_records = 0i64;
-        let mut min_records = i64::MAX;
-        let mut largest_collection: Option<String> = None;
-        let mut smallest_collection: Option<String> = None;
-
-        for collection in &collections {
-            let table_name = self.get_records_table_name(&collection.name);
-            let count_sql = format!("SELECT COUNT(*) as count FROM {}", table_name);
-
-            #[derive(diesel::QueryableByName)]
-            struct CountResult {
-                #[diesel(sql_type = diesel::sql_types::BigInt)]
-                count: i64,
-            }
+        let id_type = collections::table
+            .filter(collections::name.eq(collection_name))
+            .select(collections::id_type)
+            .first::<String>(&mut conn)
+            .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
 
-            let count_result: Result<CountResult, _> =
-                diesel::sql_query(&count_sql).get_result(&mut conn);
+        RecordIdType::from_db(&id_type)
+            .normalize(record_id)
+            .ok_or_else(|| LunarbaseError::BadRequest("Invalid record id".to_string()))
+    }
 
-            let record_count = match count_result {
-                Ok(result) => result.count,
-                Err(_) => 0,
-            };
+    pub async fn get_record(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
-            total_records += record_count;
-            records_per_collection.insert(collection.name.clone(), record_count);
+        collections::table
+            .filter(collections::name.eq(collection_name))
+            .first::<Collection>(&mut conn)
+            .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
 
-            if record_count > max_records {
-                max_records = record_count;
-                largest_collection = Some(collection.name.clone());
-            }
-            if record_count < min_records && record_count >= 0 {
-                min_records = record_count;
-                smallest_collection = Some(collection.name.clone());
-            }
+        self.query_record_by_id(&mut conn, collection_name, record_id)
+    }
 
-            for field in &collection.schema.fields {
-                let field_type = format!("{:?}", field.field_type);
-                *field_types_distribution.entry(field_type).or_insert(0) += 1;
-            }
+    /// The `owner_id` of a record, read without loading any of its fields so
+    /// permissions can be decided before the record body is fetched.
+    pub async fn get_record_owner_id(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+    ) -> Result<Option<i32>, LunarbaseError> {
+        use diesel::sql_types::{Integer, Nullable};
+
+        #[derive(diesel::QueryableByName)]
+        struct OwnerRow {
+            #[diesel(sql_type = Nullable<Integer>)]
+            owner_id: Option<i32>,
         }
 
-        let average_records = if total_collections > 0 {
-            total_records as f64 / total_collections as f64
-        } else {
-            0.0
-        };
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let select_sql = format!(
+            "SELECT owner_id FROM {} WHERE id = {}",
+            self.get_records_table_name(collection_name),
+            sql_record_id(record_id)
+        );
 
-        if min_records == i64::MAX {
-            smallest_collection = collections.first().map(|c| c.name.clone());
+        diesel::sql_query(select_sql)
+            .get_result::<OwnerRow>(&mut conn)
+            .optional()
+            .map_err(|_| LunarbaseError::InternalError)?
+            .map(|row| row.owner_id)
+            .ok_or_else(|| LunarbaseError::NotFound("Record not found".to_string()))
+    }
+
+    /// Looks a record up by the value of one of its slug fields.
+    pub async fn get_record_by_field(
+        &self,
+        collection_name: &str,
+        field_name: &str,
+        value: &str,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        let collection = self.get_collection(collection_name).await?;
+        let is_slug = collection
+            .schema
+            .fields
+            .iter()
+            .any(|field| field.name == field_name && field.field_type == FieldType::Slug);
+        if !is_slug {
+            return Err(LunarbaseError::BadRequest(format!(
+                "Records can only be looked up by slug fields, and '{}' is not one",
+                field_name
+            )));
         }
 
-        Ok((
-            total_records,
-            records_per_collection,
-            field_types_distribution,
-            average_records,
-            largest_collection,
-            smallest_collection,
-        ))
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let select_sql = format!(
+            "SELECT * FROM {} WHERE {} = '{}' LIMIT 1",
+            self.get_records_table_name(collection_name),
+            field_name,
+            value.replace('\'', "''")
+        );
+        self.query_record_by_sql(&mut conn, &select_sql, collection_name)
     }
 
-    fn validate_collection_name(&self, name: &str) -> Result<(), LunarbaseError> {
-        if name.is_empty() {
-            return Err(LunarbaseError::ValidationError(vec![
-                "Collection name cannot be empty".to_string(),
-            ]));
+    /// `get_record` through the record cache. Pass `bypass_cache` for requests that
+    /// ask for a fresh read (`Cache-Control: no-cache`); the result still refreshes the cache.
+    pub async fn get_record_cached(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+        bypass_cache: bool,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        if !self.get_record_cache_enabled().await {
+            return self.get_record(collection_name, record_id).await;
         }
 
-        if name.len() > 50 {
-            return Err(LunarbaseError::ValidationError(vec![
-                "Collection name too long (max 50 characters)".to_string(),
-            ]));
+        let ttl = std::time::Duration::from_secs(self.get_record_cache_ttl_seconds().await as u64);
+        if !bypass_cache
+            && let Some(record) = self.record_cache.get(collection_name, record_id, ttl)
+        {
+            return Ok(record);
         }
 
-        if !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
-            return Err(LunarbaseError::ValidationError(vec![
-                "Collection name can only contain letters, numbers, and underscores".to_string(),
-            ]));
-        }
+        let record = self.get_record(collection_name, record_id).await?;
+        self.record_cache.insert(
+            collection_name,
+            record_id,
+            record.clone(),
+            self.get_record_cache_max_entries().await as usize,
+        );
+        Ok(record)
+    }
 
-        let reserved_names = ["users", "auth", "admin", "api", "system"];
-        if reserved_names.contains(&name) {
-            return Err(LunarbaseError::ValidationError(vec![
-                "Collection name is reserved".to_string(),
-            ]));
+    /// How long list and count results of `collection` may be served from the
+    /// query cache, or `None` when caching is off globally or for the collection.
+    pub async fn query_cache_ttl(
+        &self,
+        collection: &CollectionResponse,
+    ) -> Option<std::time::Duration> {
+        if collection.query_cache_ttl_seconds <= 0 || !self.get_query_cache_enabled().await {
+            return None;
         }
+        Some(std::time::Duration::from_secs(
+            collection.query_cache_ttl_seconds as u64,
+        ))
+    }
 
-        Ok(())
+    /// Normalized query cache key: the query kind, the parameters that shape the
+    /// result (order-independent, empty values dropped), the caller's permission
+    /// fingerprint and the current permissions version.
+    pub fn query_cache_key(
+        &self,
+        kind: &str,
+        params: &[(&str, Option<String>)],
+        permission_fingerprint: &str,
+    ) -> String {
+        let params: std::collections::BTreeMap<&str, &str> = params
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = value.as_deref()?.trim();
+                (!value.is_empty()).then_some((*name, value))
+            })
+            .collect();
+        let permissions_version = self
+            .permission_service
+            .as_ref()
+            .map(|service| service.permissions_version())
+            .unwrap_or(0);
+
+        serde_json::json!([kind, params, permission_fingerprint, permissions_version]).to_string()
     }
 
-    fn validate_schema(&self, schema: &CollectionSchema) -> Result<(), LunarbaseError> {
-        if schema.fields.is_empty() {
-            return Err(LunarbaseError::ValidationError(vec![
-                "Schema must have at least one field".to_string(),
-            ]));
+    pub async fn cache_query_result(
+        &self,
+        collection_name: &str,
+        query_key: String,
+        result: CachedQueryResult,
+    ) {
+        let max_entries = self.get_query_cache_max_entries().await as usize;
+        self.query_cache
+            .insert(collection_name, query_key, result, max_entries);
+    }
+
+    /// Schema of the read-only `_users` system collection. Only the public
+    /// profile fields are exposed; everything else on the user stays private.
+    pub fn users_collection_schema() -> CollectionSchema {
+        CollectionSchema {
+            fields: vec![
+                FieldDefinition {
+                    name: "username".to_string(),
+                    field_type: FieldType::Text,
+                    required: true,
+                    default_value: None,
+                    validation: None,
+                    relation_target: None,
+                    description: None,
+                    example: None,
+                    indexed_paths: None,
+                },
+                FieldDefinition {
+                    name: "avatar_url".to_string(),
+                    field_type: FieldType::Url,
+                    required: false,
+                    default_value: None,
+                    validation: None,
+                    relation_target: None,
+                    description: None,
+                    example: None,
+                    indexed_paths: None,
+                },
+            ],
         }
+    }
 
-        let mut field_names = std::collections::HashSet::new();
-        let reserved_field_names = ["created_at", "updated_at"];
+    pub async fn list_user_records(
+        &self,
+        sort: Option<String>,
+        search: Option<String>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<Vec<RecordResponse>, LunarbaseError> {
+        use crate::schema::users;
 
-        for field in &schema.fields {
-            if !field_names.insert(&field.name) {
-                return Err(LunarbaseError::ValidationError(vec![format!(
-                    "Duplicate field name: {}",
-                    field.name
-                )]));
-            }
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
-            if reserved_field_names.contains(&field.name.as_str()) {
+        let mut query = users::table
+            .select((
+                users::id,
+                users::username,
+                users::avatar_url,
+                users::created_at,
+                users::updated_at,
+            ))
+            .into_boxed();
+
+        if let Some(search) = search.filter(|search| !search.is_empty()) {
+            query = query.filter(users::username.like(format!("%{}%", search)));
+        }
+
+        query = match sort.as_deref().unwrap_or("id") {
+            "id" => query.order(users::id.asc()),
+            "-id" => query.order(users::id.desc()),
+            "username" => query.order(users::username.asc()),
+            "-username" => query.order(users::username.desc()),
+            "created_at" => query.order(users::created_at.asc()),
+            "-created_at" => query.order(users::created_at.desc()),
+            other => {
                 return Err(LunarbaseError::ValidationError(vec![format!(
-                    "Field name '{}' is reserved and cannot be used",
-                    field.name
+                    "Invalid sort '{}' for {}: use id, username or created_at",
+                    other, USERS_SYSTEM_COLLECTION
                 )]));
             }
+        };
 
-            if field.name.is_empty() || field.name.len() > 50 {
-                return Err(LunarbaseError::ValidationError(vec![
-                    "Field name must be 1-50 characters".to_string(),
-                ]));
-            }
+        let rows = query
+            .limit(limit.unwrap_or(50).clamp(1, 1000))
+            .offset(offset.unwrap_or(0).max(0))
+            .load::<UserProjectionRow>(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
 
-            if !field.name.chars().all(|c| c.is_alphanumeric() || c == '_') {
-                return Err(LunarbaseError::ValidationError(vec![
-                    "Field name can only contain letters, numbers, and underscores".to_string(),
-                ]));
-            }
+        Ok(rows.into_iter().map(user_projection_to_record).collect())
+    }
+
+    pub async fn count_user_records(&self, search: Option<String>) -> Result<i64, LunarbaseError> {
+        use crate::schema::users;
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let mut query = users::table.into_boxed();
+        if let Some(search) = search.filter(|search| !search.is_empty()) {
+            query = query.filter(users::username.like(format!("%{}%", search)));
         }
 
-        Ok(())
+        query
+            .count()
+            .get_result(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)
     }
 
-    async fn process_file_uploads(
+    pub async fn get_user_record(&self, user_id: i32) -> Result<RecordResponse, LunarbaseError> {
+        let mut rows = self.load_user_projections(&[user_id])?;
+        rows.pop()
+            .map(user_projection_to_record)
+            .ok_or_else(|| LunarbaseError::NotFound("Record not found".to_string()))
+    }
+
+    fn load_user_projections(&self, ids: &[i32]) -> Result<Vec<UserProjectionRow>, LunarbaseError> {
+        use crate::schema::users;
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        users::table
+            .filter(users::id.eq_any(ids))
+            .select((
+                users::id,
+                users::username,
+                users::avatar_url,
+                users::created_at,
+                users::updated_at,
+            ))
+            .load::<UserProjectionRow>(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)
+    }
+
+    /// Replaces the ids stored in the requested relation fields with the
+    /// referenced records. Targets that no longer exist or that `reader` may
+    /// not read keep their raw id, and `_users` targets are only expanded when
+    /// `include_users` is set. Without a reader the guest role decides.
+    pub async fn expand_relations(
         &self,
-        schema: &CollectionSchema,
-        files: &std::collections::HashMap<String, FileUpload>,
-    ) -> Result<std::collections::HashMap<String, String>, LunarbaseError> {
-        let s3_enabled = self
-            .config_manager
-            .get_bool("storage", "s3_enabled")
-            .await
-            .unwrap_or(false);
-        if !s3_enabled {
-            return Err(LunarbaseError::ValidationError(vec![
-                "File upload is disabled. S3 service is not enabled.".to_string(),
-            ]));
+        collection_name: &str,
+        records: &mut [RecordResponse],
+        expand: &[String],
+        include_users: bool,
+        reader: Option<&User>,
+    ) -> Result<(), LunarbaseError> {
+        if expand.is_empty() || records.is_empty() {
+            return Ok(());
         }
 
-        let mut file_urls = std::collections::HashMap::new();
-        let mut uploaded_files = Vec::new();
-
-        let s3_service = match &self.s3_service {
-            Some(service) => service,
-            None => {
-                return Err(LunarbaseError::ValidationError(vec![
-                    "File upload is not configured. S3 service is not available.".to_string(),
-                ]));
-            }
+        let schema = if collection_name == USERS_SYSTEM_COLLECTION {
+            Self::users_collection_schema()
+        } else {
+            self.get_collection(collection_name).await?.schema
         };
 
-        for (field_name, _file_upload) in files {
-            let field_def = schema.fields.iter().find(|f| &f.name == field_name);
-            match field_def {
-                Some(field) if matches!(field.field_type, FieldType::File) => {}
-                Some(_) => {
-                    return Err(LunarbaseError::ValidationError(vec![format!(
-                        "Field '{}' is not of type 'file'",
+        for field_name in expand {
+            let target = schema
+                .fields
+                .iter()
+                .find(|field| &field.name == field_name && field.field_type == FieldType::Relation)
+                .and_then(|field| field.relation_target.clone())
+                .ok_or_else(|| {
+                    LunarbaseError::ValidationError(vec![format!(
+                        "Field '{}' is not an expandable relation",
                         field_name
-                    )]));
-                }
-                None => {
+                    )])
+                })?;
+
+            let mut ids: Vec<String> = records
+                .iter()
+                .filter_map(|record| record.data.get(field_name).and_then(relation_id))
+                .collect();
+            ids.sort_unstable();
+            ids.dedup();
+
+            let mut expanded: std::collections::HashMap<String, Value> =
+                std::collections::HashMap::new();
+
+            if target == USERS_SYSTEM_COLLECTION {
+                if !include_users {
+                    continue;
+                }
+                let user_ids: Vec<i32> = ids.iter().filter_map(|id| id.parse().ok()).collect();
+                for row in self.load_user_projections(&user_ids)? {
+                    expanded.insert(row.0.to_string(), user_projection_to_value(&row));
+                }
+            } else {
+                let target_collection = self.get_collection(&target).await?;
+                for id in ids {
+                    if !self
+                        .can_read_related(reader, &target_collection, &id)
+                        .await?
+                    {
+                        continue;
+                    }
+                    let Ok(record) = self.get_record(&target, &id).await else {
+                        continue;
+                    };
+                    let value =
+                        serde_json::to_value(record).map_err(|_| LunarbaseError::InternalError)?;
+                    expanded.insert(id, value);
+                }
+            }
+
+            for record in records.iter_mut() {
+                let Some(data) = record.data.as_object_mut() else {
+                    continue;
+                };
+                let Some(id) = data.get(field_name).and_then(relation_id) else {
+                    continue;
+                };
+                if let Some(value) = expanded.get(&id) {
+                    data.insert(field_name.clone(), value.clone());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether `reader` may read a record reached through a relation: as its
+    /// owner, through a record grant or their role, or as a guest through the
+    /// guest role. Records that do not exist are not readable.
+    async fn can_read_related(
+        &self,
+        reader: Option<&User>,
+        collection: &CollectionResponse,
+        record_id: &str,
+    ) -> Result<bool, LunarbaseError> {
+        let Some(permission_service) = &self.permission_service else {
+            return Ok(true);
+        };
+        match reader {
+            Some(user) => {
+                let Ok(owner_id) = self.get_record_owner_id(&collection.name, record_id).await
+                else {
+                    return Ok(false);
+                };
+                permission_service
+                    .check_record_permission_with_owner_id(
+                        user,
+                        collection.id,
+                        record_id,
+                        Permission::Read,
+                        owner_id,
+                    )
+                    .await
+            }
+            None => Ok(permission_service
+                .get_effective_role_collection_permission("guest", collection.id)
+                .await
+                .ok()
+                .flatten()
+                .is_some_and(|permissions| permissions.permission.can_read)),
+        }
+    }
+
+    /// Records whose relation fields point at `record_id`, grouped by
+    /// collection with one query per referencing collection. Each group
+    /// carries its full count and up to `limit` record ids; collections with
+    /// no referencing records are left out.
+    pub async fn find_record_references(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+        limit: i64,
+    ) -> Result<Vec<CollectionReferences>, LunarbaseError> {
+        #[derive(Debug, diesel::QueryableByName)]
+        struct ReferenceRow {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            record_id: String,
+            #[diesel(sql_type = diesel::sql_types::BigInt)]
+            total: i64,
+        }
+
+        if collection_name == USERS_SYSTEM_COLLECTION {
+            let user_id = record_id
+                .parse()
+                .map_err(|_| LunarbaseError::BadRequest("Invalid record id".to_string()))?;
+            self.get_user_record(user_id).await?;
+        } else {
+            self.get_record_owner_id(collection_name, record_id).await?;
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let mut references = Vec::new();
+
+        for collection in self.list_collections().await? {
+            let fields: Vec<String> = collection
+                .schema
+                .fields
+                .iter()
+                .filter(|field| {
+                    field.field_type == FieldType::Relation
+                        && field.relation_target.as_deref() == Some(collection_name)
+                })
+                .map(|field| field.name.clone())
+                .collect();
+            if fields.is_empty() {
+                continue;
+            }
+
+            let condition = fields
+                .iter()
+                .map(|field| format!("{} = ?", field))
+                .collect::<Vec<_>>()
+                .join(" OR ");
+            let mut query = PreparedSql::new(format!(
+                "SELECT CAST(id AS TEXT) AS record_id, COUNT(*) OVER () AS total FROM {} WHERE {} ORDER BY id LIMIT ?",
+                self.get_records_table_name(&collection.name),
+                condition
+            ));
+            for _ in &fields {
+                query = query.bind(SqlBind::Text(Some(record_id.to_string())));
+            }
+            let rows: Vec<ReferenceRow> = query
+                .bind(SqlBind::BigInt(Some(limit)))
+                .load(&mut conn)
+                .map_err(|_| LunarbaseError::InternalError)?;
+
+            let Some(count) = rows.first().map(|row| row.total) else {
+                continue;
+            };
+            references.push(CollectionReferences {
+                collection_name: collection.name,
+                fields,
+                count,
+                record_ids: rows.into_iter().map(|row| row.record_id).collect(),
+            });
+        }
+
+        Ok(references)
+    }
+
+    pub async fn list_records(
+        &self,
+        collection_name: &str,
+        sort: Option<String>,
+        filter: Option<String>,
+        search: Option<String>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<Vec<RecordResponse>, LunarbaseError> {
+        tracing::debug!(
+            "list_records called with collection_name={}, sort={:?}, filter={:?}, limit={:?}, offset={:?}",
+            collection_name,
+            sort,
+            filter,
+            limit,
+            offset
+        );
+
+        let mut conn = self.pool.get().map_err(|e| {
+            tracing::error!("Failed to get database connection: {:?}", e);
+            LunarbaseError::InternalError
+        })?;
+
+        let collection = collections::table
+            .filter(collections::name.eq(collection_name))
+            .first::<Collection>(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to find collection '{}': {:?}", collection_name, e);
+                LunarbaseError::NotFound("Collection not found".to_string())
+            })?;
+
+        let schema = collection.get_schema().map_err(|e| {
+            tracing::error!("Failed to parse collection schema: {:?}", e);
+            LunarbaseError::InternalError
+        })?;
+
+        let query_engine = QueryEngine::new(sort, filter, search, limit, offset)
+            .map_err(|e| {
+                tracing::error!("Failed to create QueryEngine: {:?}", e);
+                e
+            })?
+            .with_manual_order(collection.orderable)
+            .with_workflow(collection.workflow() == CollectionWorkflow::DraftPublish);
+
+        let table_name = self.get_records_table_name(collection_name);
+        let (sql, parameters) = query_engine.build_complete_query(&table_name, &schema)?;
+
+        tracing::debug!(
+            "Executing SQL: {} with {} parameters",
+            sql,
+            parameters.len()
+        );
+
+        use diesel::sql_types::*;
+
+        #[derive(Debug, diesel::QueryableByName)]
+        struct RecordRow {
+            #[diesel(sql_type = Text)]
+            id: String,
+        }
+
+        let final_sql = self.bind_query_parameters(sql, &parameters);
+
+        tracing::debug!("Final SQL after parameter substitution: {}", final_sql);
+
+        let rows: Vec<RecordRow> = diesel::sql_query(&final_sql).load(&mut conn).map_err(|e| {
+            tracing::error!("Failed to execute SQL query '{}': {:?}", final_sql, e);
+            LunarbaseError::InternalError
+        })?;
+
+        let mut responses = Vec::new();
+        for row in rows {
+            let response = self.query_record_by_id(&mut conn, collection_name, &row.id)?;
+            responses.push(response);
+        }
+
+        Ok(responses)
+    }
+
+    pub async fn count_records(
+        &self,
+        collection_name: &str,
+        filter: Option<String>,
+        search: Option<String>,
+    ) -> Result<i64, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let collection = collections::table
+            .filter(collections::name.eq(collection_name))
+            .first::<Collection>(&mut conn)
+            .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+
+        let schema = collection
+            .get_schema()
+            .map_err(|_| LunarbaseError::InternalError)?;
+
+        let query_engine = QueryEngine::new(None, filter, search, None, None)?
+            .with_workflow(collection.workflow() == CollectionWorkflow::DraftPublish);
+
+        let table_name = self.get_records_table_name(collection_name);
+        let (sql, parameters) = query_engine.build_count_query(&table_name, &schema)?;
+
+        let final_sql = self.bind_query_parameters(sql, &parameters);
+
+        tracing::debug!("Executing count SQL: {}", final_sql);
+
+        use diesel::sql_types::BigInt;
+
+        #[derive(Debug, diesel::QueryableByName)]
+        struct CountRow {
+            #[diesel(sql_type = BigInt)]
+            count: i64,
+        }
+
+        let row: CountRow = diesel::sql_query(&final_sql)
+            .get_result(&mut conn)
+            .map_err(|e| {
+                tracing::error!("Failed to execute count query '{}': {:?}", final_sql, e);
+                LunarbaseError::InternalError
+            })?;
+
+        Ok(row.count)
+    }
+
+    fn bind_query_parameters(&self, sql: String, parameters: &[String]) -> String {
+        let mut final_sql = sql;
+        for param in parameters.iter() {
+            let escaped_param = param.replace("'", "''");
+            final_sql = final_sql.replacen("?", &format!("'{}'", escaped_param), 1);
+        }
+        final_sql
+    }
+
+    pub async fn update_record(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+        request: UpdateRecordRequest,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        self.update_record_with_events(collection_name, record_id, request, None)
+            .await
+    }
+
+    pub async fn update_record_with_events(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+        request: UpdateRecordRequest,
+        user_id: Option<i32>,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        let schema = self.get_collection(collection_name).await?.schema;
+
+        let mut data = request.data.clone();
+        let mut uploaded_files = Vec::new();
+        if let Some(files) = &request.files {
+            let file_urls = self
+                .process_file_uploads(collection_name, &schema, files, user_id)
+                .await?;
+
+            if let Value::Object(ref mut map) = data {
+                for (field_name, url) in file_urls {
+                    uploaded_files.push(url.clone());
+                    map.insert(field_name, Value::String(url));
+                }
+            }
+        }
+
+        let result = transaction_then(&self.pool, |conn, after_commit| {
+            let old_record = self
+                .query_record_by_id(conn, collection_name, record_id)
+                .ok();
+
+            let record_response = self.update_record_row(
+                conn,
+                collection_name,
+                &schema,
+                record_id,
+                &data,
+                request.regenerate_slug,
+            )?;
+
+            let event = crate::models::RecordEvent::Updated {
+                record_id: record_response.id.to_string(),
+                record: serde_json::to_value(&record_response.data).unwrap_or_default(),
+                old_record: old_record
+                    .as_ref()
+                    .map(|r| serde_json::to_value(&r.data).unwrap_or_default()),
+            };
+            after_commit.defer(self.emit_record_event(collection_name, event, user_id));
+
+            // The files that were replaced, once the record points at the new ones
+            let replaced_files: Map<String, Value> = match (&old_record, &request.files) {
+                (Some(old_record), Some(files)) => files
+                    .keys()
+                    .filter_map(|field_name| {
+                        let old_url = old_record.data.get(field_name)?;
+                        Some((field_name.clone(), old_url.clone()))
+                    })
+                    .collect(),
+                _ => Map::new(),
+            };
+            if !replaced_files.is_empty() {
+                let schema = &schema;
+                after_commit.defer(async move {
+                    let file_deletion_errors = self
+                        .delete_record_files(schema, &Value::Object(replaced_files))
+                        .await;
+                    if !file_deletion_errors.is_empty() {
+                        tracing::warn!(
+                            "Some replaced files could not be deleted for record {} in collection {}: {:?}",
+                            record_id,
+                            collection_name,
+                            file_deletion_errors
+                        );
+                    }
+                });
+            }
+
+            Ok(record_response)
+        })
+        .await;
+
+        // Nothing points at the new uploads when the update did not commit
+        if result.is_err()
+            && !uploaded_files.is_empty()
+            && let Some(s3_service) = &self.s3_service
+        {
+            s3_service.cleanup_files(uploaded_files).await;
+        }
+
+        result
+    }
+
+    /// Moves a record of an orderable collection right before or after another
+    /// record by giving it the midpoint of its new neighbours' positions. When
+    /// those are too close to split, the collection is renumbered first.
+    pub async fn move_record(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+        request: MoveRecordRequest,
+        user_id: Option<i32>,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        let (anchor_id, before) = match (request.before, request.after) {
+            (Some(anchor_id), None) => (anchor_id, true),
+            (None, Some(anchor_id)) => (anchor_id, false),
+            _ => {
+                return Err(LunarbaseError::ValidationError(vec![
+                    "Exactly one of 'before' or 'after' must be given".to_string(),
+                ]));
+            }
+        };
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let collection = collections::table
+            .filter(collections::name.eq(collection_name))
+            .first::<Collection>(&mut conn)
+            .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+        if !collection.orderable {
+            return Err(LunarbaseError::BadRequest(format!(
+                "Collection '{}' is not orderable",
+                collection_name
+            )));
+        }
+
+        let anchor_id = collection
+            .record_id_type()
+            .normalize(&anchor_id)
+            .ok_or_else(|| LunarbaseError::BadRequest("Invalid anchor record id".to_string()))?;
+        if anchor_id == record_id {
+            return Err(LunarbaseError::ValidationError(vec![
+                "A record cannot be moved relative to itself".to_string(),
+            ]));
+        }
+
+        let table_name = self.get_records_table_name(collection_name);
+
+        let (sort_order, renormalized) = conn.transaction::<_, LunarbaseError, _>(|conn| {
+            if self
+                .read_sort_order(conn, &table_name, record_id)?
+                .is_none()
+            {
+                return Err(LunarbaseError::NotFound("Record not found".to_string()));
+            }
+
+            let mut renormalized = false;
+            loop {
+                let anchor = self
+                    .read_sort_order(conn, &table_name, &anchor_id)?
+                    .ok_or_else(|| {
+                        LunarbaseError::NotFound(format!("Record {} not found", anchor_id))
+                    })?;
+
+                let sort_order = match self.neighbour_sort_order(
+                    conn,
+                    &table_name,
+                    anchor,
+                    before,
+                    record_id,
+                )? {
+                    Some(neighbour)
+                        if (anchor - neighbour).abs() < MIN_SORT_ORDER_GAP && !renormalized =>
+                    {
+                        self.renormalize_sort_order(conn, &table_name, "sort_order ASC, id ASC")?;
+                        renormalized = true;
+                        continue;
+                    }
+                    Some(neighbour) => (anchor + neighbour) / 2.0,
+                    None if before => anchor - SORT_ORDER_GAP,
+                    None => anchor + SORT_ORDER_GAP,
+                };
+
+                diesel::sql_query(format!(
+                    "UPDATE {} SET {} = {} WHERE id = {}",
+                    table_name, SORT_ORDER_COLUMN, sort_order, record_id
+                ))
+                .execute(conn)
+                .map_err(|_| LunarbaseError::InternalError)?;
+
+                return Ok((sort_order, renormalized));
+            }
+        })?;
+
+        if renormalized {
+            self.record_cache.invalidate_collection(collection_name);
+        }
+
+        let record = self.query_record_by_id(&mut conn, collection_name, record_id)?;
+
+        let event = crate::models::RecordEvent::Reordered {
+            record_id: record_id.to_string(),
+            sort_order,
+            renormalized,
+        };
+        self.emit_record_event(collection_name, event, user_id)
+            .await;
+
+        Ok(record)
+    }
+
+    /// Publishes a record of a draft/publish collection. With a future
+    /// `publish_at` it is kept as a draft and published by
+    /// [`Self::publish_due_records`] once that time has passed.
+    pub async fn publish_record(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+        request: PublishRecordRequest,
+        user_id: Option<i32>,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        let scheduled_at = request
+            .publish_at
+            .filter(|publish_at| *publish_at > chrono::Utc::now());
+        let status = match scheduled_at {
+            Some(_) => RecordStatus::Draft,
+            None => RecordStatus::Published,
+        };
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let (old_record, record) = self.set_record_status(
+            &mut conn,
+            collection_name,
+            record_id,
+            status,
+            scheduled_at.map(|publish_at| publish_at.naive_utc()),
+        )?;
+
+        let event = match scheduled_at {
+            Some(_) => crate::models::RecordEvent::Updated {
+                record_id: record.id.clone(),
+                record: record.data.clone(),
+                old_record: Some(old_record.data),
+            },
+            None => crate::models::RecordEvent::Published {
+                record_id: record.id.clone(),
+                record: record.data.clone(),
+            },
+        };
+        self.emit_record_event(collection_name, event, user_id)
+            .await;
+
+        Ok(record)
+    }
+
+    /// Takes a record of a draft/publish collection back to `draft`, or to
+    /// `archived`, and cancels a scheduled publish.
+    pub async fn unpublish_record(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+        request: UnpublishRecordRequest,
+        user_id: Option<i32>,
+    ) -> Result<RecordResponse, LunarbaseError> {
+        let status = if request.archive {
+            RecordStatus::Archived
+        } else {
+            RecordStatus::Draft
+        };
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let (_, record) =
+            self.set_record_status(&mut conn, collection_name, record_id, status, None)?;
+
+        let event = crate::models::RecordEvent::Unpublished {
+            record_id: record.id.clone(),
+            record: record.data.clone(),
+            status: status.as_str().to_string(),
+        };
+        self.emit_record_event(collection_name, event, user_id)
+            .await;
+
+        Ok(record)
+    }
+
+    /// Publishes every draft whose `publish_at` has passed, across all
+    /// draft/publish collections. Returns how many records were published.
+    pub async fn publish_due_records(&self) -> Result<usize, LunarbaseError> {
+        let collection_names: Vec<String> = {
+            let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+            collections::table
+                .filter(collections::workflow.eq(CollectionWorkflow::DraftPublish.as_str()))
+                .select(collections::name)
+                .load(&mut conn)
+                .map_err(|_| LunarbaseError::DatabaseError)?
+        };
+
+        #[derive(Debug, diesel::QueryableByName)]
+        struct DueRow {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            id: String,
+        }
+
+        let mut published = 0;
+        for collection_name in collection_names {
+            let due: Vec<DueRow> =
+                {
+                    let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+                    PreparedSql::new(format!(
+                    "SELECT CAST(id AS TEXT) AS id FROM {} WHERE {} = ? AND {} <= datetime('now')",
+                    self.get_records_table_name(&collection_name),
+                    STATUS_COLUMN,
+                    PUBLISH_AT_COLUMN
+                ))
+                .bind(SqlBind::Text(Some(RecordStatus::Draft.as_str().to_string())))
+                .load(&mut conn)
+                .map_err(|_| LunarbaseError::DatabaseError)?
+                };
+
+            for row in due {
+                match self
+                    .publish_record(
+                        &collection_name,
+                        &row.id,
+                        PublishRecordRequest::default(),
+                        None,
+                    )
+                    .await
+                {
+                    Ok(_) => published += 1,
+                    Err(e) => tracing::warn!(
+                        "Failed to publish scheduled record {}/{}: {}",
+                        collection_name,
+                        row.id,
+                        e
+                    ),
+                }
+            }
+        }
+
+        Ok(published)
+    }
+
+    /// Applies the schedule of every collection that has one, from where the
+    /// previous run stopped up to now. A collection that fails is retried on
+    /// the next run. Returns how many records were changed.
+    pub async fn run_record_schedules(&self) -> Result<usize, LunarbaseError> {
+        let scheduled: Vec<Collection> = {
+            let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+            collections::table
+                .filter(collections::schedule_json.is_not_null())
+                .load(&mut conn)
+                .map_err(|_| LunarbaseError::DatabaseError)?
+        };
+
+        let mut applied = 0;
+        for collection in scheduled {
+            let Some(schedule) = collection
+                .schedule()
+                .map_err(|_| LunarbaseError::InternalError)?
+            else {
+                continue;
+            };
+            match self.apply_record_schedule(&collection, &schedule).await {
+                Ok(count) => applied += count,
+                Err(e) => tracing::warn!(
+                    "Failed to run the record schedule of '{}': {}",
+                    collection.name,
+                    e
+                ),
+            }
+        }
+
+        Ok(applied)
+    }
+
+    /// Publishes, archives or deletes the records whose schedule fields fell
+    /// due since `schedule_checked_at`, then moves that mark to now. When a
+    /// transition fails the mark stays, so the next run retries it.
+    async fn apply_record_schedule(
+        &self,
+        collection: &Collection,
+        schedule: &RecordSchedule,
+    ) -> Result<usize, LunarbaseError> {
+        let now = chrono::Utc::now().naive_utc();
+        let until = now.format("%Y-%m-%d %H:%M:%S").to_string();
+        let since = collection
+            .schedule_checked_at
+            .map(|checked_at| checked_at.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+
+        let mut due = Vec::new();
+        for (field, action) in schedule_operations(schedule) {
+            due.extend(self.scheduled_operations(
+                collection,
+                field,
+                action,
+                &since,
+                Some(&until),
+                None,
+            )?);
+        }
+
+        let mut applied = 0;
+        let mut failed = false;
+        for operation in due {
+            let result = match operation.action {
+                ScheduledAction::Publish => self
+                    .publish_record(
+                        &collection.name,
+                        &operation.record_id,
+                        PublishRecordRequest::default(),
+                        None,
+                    )
+                    .await
+                    .map(|_| ()),
+                ScheduledAction::Archive => self
+                    .unpublish_record(
+                        &collection.name,
+                        &operation.record_id,
+                        UnpublishRecordRequest { archive: true },
+                        None,
+                    )
+                    .await
+                    .map(|_| ()),
+                ScheduledAction::Delete => {
+                    self.delete_record_with_events(&collection.name, &operation.record_id, None)
+                        .await
+                }
+            };
+            match result {
+                Ok(()) => applied += 1,
+                // Deleted since it was selected, e.g. by an earlier operation
+                Err(LunarbaseError::NotFound(_)) => {}
+                Err(e) => {
+                    failed = true;
+                    tracing::warn!(
+                        "Failed to {:?} scheduled record {}/{}: {}",
+                        operation.action,
+                        collection.name,
+                        operation.record_id,
+                        e
+                    );
+                }
+            }
+        }
+
+        if !failed {
+            let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+            diesel::update(collections::table)
+                .filter(collections::id.eq(collection.id))
+                .set(collections::schedule_checked_at.eq(Some(now)))
+                .execute(&mut conn)
+                .map_err(|_| LunarbaseError::DatabaseError)?;
+        }
+
+        Ok(applied)
+    }
+
+    /// Records whose `field` is after `since`, and at or before `until` when
+    /// given, that `action` would still change, soonest first.
+    fn scheduled_operations(
+        &self,
+        collection: &Collection,
+        field: &str,
+        action: ScheduledAction,
+        since: &str,
+        until: Option<&str>,
+        limit: Option<i64>,
+    ) -> Result<Vec<ScheduledOperation>, LunarbaseError> {
+        #[derive(Debug, diesel::QueryableByName)]
+        struct ScheduledRow {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            id: String,
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            due_at: String,
+        }
+
+        let mut sql = format!(
+            "SELECT CAST(id AS TEXT) AS id, CAST({field} AS TEXT) AS due_at FROM {} WHERE {field} > ?",
+            self.get_records_table_name(&collection.name)
+        );
+        let mut binds = vec![SqlBind::Text(Some(since.to_string()))];
+        if let Some(until) = until {
+            sql.push_str(&format!(" AND {} <= ?", field));
+            binds.push(SqlBind::Text(Some(until.to_string())));
+        }
+        match action {
+            ScheduledAction::Publish => {
+                sql.push_str(&format!(" AND {} = ?", STATUS_COLUMN));
+                binds.push(SqlBind::Text(Some(
+                    RecordStatus::Draft.as_str().to_string(),
+                )));
+            }
+            ScheduledAction::Archive => {
+                sql.push_str(&format!(" AND {} != ?", STATUS_COLUMN));
+                binds.push(SqlBind::Text(Some(
+                    RecordStatus::Archived.as_str().to_string(),
+                )));
+            }
+            ScheduledAction::Delete => {}
+        }
+        sql.push_str(&format!(" ORDER BY {} ASC, id ASC", field));
+        if let Some(limit) = limit {
+            sql.push_str(" LIMIT ?");
+            binds.push(SqlBind::BigInt(Some(limit)));
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let rows: Vec<ScheduledRow> = record_statement(collection, sql)
+            .binds(binds)
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ScheduledOperation {
+                record_id: row.id,
+                action,
+                field: field.to_string(),
+                due_at: row.due_at,
+            })
+            .collect())
+    }
+
+    /// The transitions still ahead for a collection: its schedule fields not
+    /// yet applied, and drafts waiting for their workflow `publish_at`.
+    pub async fn get_record_schedule(
+        &self,
+        collection_name: &str,
+        limit: i64,
+    ) -> Result<RecordScheduleReport, LunarbaseError> {
+        let collection = {
+            let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+            collections::table
+                .filter(collections::name.eq(collection_name))
+                .first::<Collection>(&mut conn)
+                .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?
+        };
+        let schedule = collection
+            .schedule()
+            .map_err(|_| LunarbaseError::InternalError)?;
+        let checked_at = collection
+            .schedule_checked_at
+            .map(|checked_at| checked_at.format("%Y-%m-%d %H:%M:%S").to_string());
+        let since = checked_at.clone().unwrap_or_default();
+
+        let mut pending = Vec::new();
+        if let Some(schedule) = &schedule {
+            for (field, action) in schedule_operations(schedule) {
+                pending.extend(self.scheduled_operations(
+                    &collection,
+                    field,
+                    action,
+                    &since,
+                    None,
+                    Some(limit),
+                )?);
+            }
+        }
+        if collection.workflow() == CollectionWorkflow::DraftPublish {
+            // Not tied to the mark: these stay pending until they are published
+            pending.extend(self.scheduled_operations(
+                &collection,
+                PUBLISH_AT_COLUMN,
+                ScheduledAction::Publish,
+                "",
+                None,
+                Some(limit),
+            )?);
+        }
+
+        pending.sort_by(|a, b| a.due_at.cmp(&b.due_at));
+        pending.truncate(limit.max(0) as usize);
+
+        Ok(RecordScheduleReport {
+            collection_name: collection.name,
+            schedule,
+            checked_at,
+            pending,
+        })
+    }
+
+    /// Runs [`Self::publish_due_records`] and [`Self::run_record_schedules`]
+    /// every `database.record_schedule_interval_seconds`. The first run is at
+    /// startup, so whatever fell due while the server was down is caught up.
+    pub fn start_record_scheduler(&self) {
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let interval = service.get_record_schedule_interval_seconds().await;
+                if interval == 0 {
+                    tokio::time::sleep(std::time::Duration::from_secs(
+                        DISABLED_SCHEDULER_POLL_SECONDS,
+                    ))
+                    .await;
+                    continue;
+                }
+
+                match service.publish_due_records().await {
+                    Ok(0) => {}
+                    Ok(published) => debug!("Published {} scheduled record(s)", published),
+                    Err(e) => tracing::warn!("Failed to publish scheduled records: {:?}", e),
+                }
+                match service.run_record_schedules().await {
+                    Ok(0) => {}
+                    Ok(applied) => debug!("Applied {} record schedule transition(s)", applied),
+                    Err(e) => tracing::warn!("Failed to run record schedules: {:?}", e),
+                }
+
+                tokio::time::sleep(std::time::Duration::from_secs(u64::from(interval))).await;
+            }
+        });
+    }
+
+    /// Applies the retention policy of every collection that has one. A
+    /// collection that fails is retried on the next run. Returns the runs.
+    pub async fn run_retention_policies(&self) -> Result<Vec<RetentionRun>, LunarbaseError> {
+        let retained: Vec<Collection> = {
+            let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+            collections::table
+                .filter(collections::retention_json.is_not_null())
+                .load(&mut conn)
+                .map_err(|_| LunarbaseError::DatabaseError)?
+        };
+
+        let mut runs = Vec::new();
+        for collection in retained {
+            let Some(policy) = collection
+                .retention()
+                .map_err(|_| LunarbaseError::InternalError)?
+            else {
+                continue;
+            };
+            match self.apply_retention_policy(&collection, &policy).await {
+                Ok(run) => runs.push(run),
+                Err(e) => tracing::warn!(
+                    "Failed to run the retention policy of '{}': {}",
+                    collection.name,
+                    e
+                ),
+            }
+        }
+
+        Ok(runs)
+    }
+
+    /// Deletes or archives the records past the policy's cutoff, oldest first,
+    /// in batches of `database.retention_batch_size`, saving the counts after
+    /// every batch. Due records are found by the cutoff alone, so whatever a
+    /// run leaves, because it hit `RETENTION_BATCHES_PER_RUN` or was
+    /// interrupted, is picked up by the next one.
+    async fn apply_retention_policy(
+        &self,
+        collection: &Collection,
+        policy: &RetentionPolicy,
+    ) -> Result<RetentionRun, LunarbaseError> {
+        let now = chrono::Utc::now().naive_utc();
+        let cutoff = (now - chrono::Duration::days(i64::from(policy.max_age_days)))
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        let batch_size = i64::from(self.get_retention_batch_size().await.clamp(1, 10_000));
+        let action = match policy.action {
+            ExpireAction::Archive => "archive",
+            ExpireAction::Delete => "delete",
+        };
+
+        let run_id = {
+            let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+            diesel::insert_into(collection_retention_runs::table)
+                .values((
+                    collection_retention_runs::collection_id.eq(collection.id),
+                    collection_retention_runs::action.eq(action),
+                    collection_retention_runs::cutoff.eq(&cutoff),
+                    collection_retention_runs::started_at.eq(now),
+                ))
+                .execute(&mut conn)
+                .map_err(|_| LunarbaseError::DatabaseError)?;
+            collection_retention_runs::table
+                .filter(collection_retention_runs::collection_id.eq(collection.id))
+                .order(collection_retention_runs::id.desc())
+                .select(collection_retention_runs::id)
+                .first::<i32>(&mut conn)
+                .map_err(|_| LunarbaseError::DatabaseError)?
+        };
+
+        let (mut processed, mut failed) = (0_i64, 0_i64);
+        let mut complete = false;
+        for _ in 0..RETENTION_BATCHES_PER_RUN {
+            // Records that failed are still due and sort before the rest
+            let due =
+                self.due_retention_records(collection, policy, &cutoff, batch_size, failed)?;
+            if due.is_empty() {
+                complete = true;
+                break;
+            }
+
+            for record_id in due {
+                let result = match policy.action {
+                    ExpireAction::Archive => self
+                        .unpublish_record(
+                            &collection.name,
+                            &record_id,
+                            UnpublishRecordRequest { archive: true },
+                            None,
+                        )
+                        .await
+                        .map(|_| ()),
+                    ExpireAction::Delete => {
+                        self.delete_record_with_events(&collection.name, &record_id, None)
+                            .await
+                    }
+                };
+                match result {
+                    Ok(()) => processed += 1,
+                    // Deleted since it was selected
+                    Err(LunarbaseError::NotFound(_)) => {}
+                    Err(e) => {
+                        failed += 1;
+                        tracing::warn!(
+                            "Failed to {} record {}/{} past its retention: {}",
+                            action,
+                            collection.name,
+                            record_id,
+                            e
+                        );
+                    }
+                }
+            }
+
+            let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+            diesel::update(collection_retention_runs::table.find(run_id))
+                .set((
+                    collection_retention_runs::processed.eq(processed),
+                    collection_retention_runs::failed.eq(failed),
+                ))
+                .execute(&mut conn)
+                .map_err(|_| LunarbaseError::DatabaseError)?;
+            drop(conn);
+            tokio::task::yield_now().await;
+        }
+        if !complete {
+            complete = self
+                .due_retention_records(collection, policy, &cutoff, 1, failed)?
+                .is_empty();
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        diesel::update(collection_retention_runs::table.find(run_id))
+            .set((
+                collection_retention_runs::complete.eq(complete && failed == 0),
+                collection_retention_runs::finished_at.eq(Some(chrono::Utc::now().naive_utc())),
+            ))
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let kept: Vec<i32> = collection_retention_runs::table
+            .filter(collection_retention_runs::collection_id.eq(collection.id))
+            .order(collection_retention_runs::id.desc())
+            .limit(RETENTION_RUNS_KEPT)
+            .select(collection_retention_runs::id)
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        diesel::delete(
+            collection_retention_runs::table
+                .filter(collection_retention_runs::collection_id.eq(collection.id))
+                .filter(collection_retention_runs::id.ne_all(kept)),
+        )
+        .execute(&mut conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        collection_retention_runs::table
+            .find(run_id)
+            .first::<RetentionRun>(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)
+    }
+
+    /// The `WHERE` clause matching the records of a policy due at `cutoff`.
+    fn retention_condition(policy: &RetentionPolicy, cutoff: &str) -> (String, Vec<SqlBind>) {
+        let mut condition = format!("{} < ?", policy.field);
+        let mut binds = vec![SqlBind::Text(Some(cutoff.to_string()))];
+        if policy.action == ExpireAction::Archive {
+            condition.push_str(&format!(" AND {} != ?", STATUS_COLUMN));
+            binds.push(SqlBind::Text(Some(
+                RecordStatus::Archived.as_str().to_string(),
+            )));
+        }
+        (condition, binds)
+    }
+
+    /// Ids of due records, oldest first.
+    fn due_retention_records(
+        &self,
+        collection: &Collection,
+        policy: &RetentionPolicy,
+        cutoff: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<String>, LunarbaseError> {
+        #[derive(Debug, diesel::QueryableByName)]
+        struct DueRow {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            id: String,
+        }
+
+        let (condition, mut binds) = Self::retention_condition(policy, cutoff);
+        let sql = format!(
+            "SELECT CAST(id AS TEXT) AS id FROM {} WHERE {} ORDER BY {} ASC, id ASC LIMIT ? OFFSET ?",
+            self.get_records_table_name(&collection.name),
+            condition,
+            policy.field
+        );
+        binds.push(SqlBind::BigInt(Some(limit)));
+        binds.push(SqlBind::BigInt(Some(offset)));
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let rows: Vec<DueRow> = record_statement(collection, sql)
+            .binds(binds)
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
+    /// Every record of the collection by ascending id with its hash.
+    fn hashed_records(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+    ) -> Result<Vec<(RecordResponse, RecordHash)>, LunarbaseError> {
+        use diesel::sql_types::Text;
+
+        #[derive(diesel::QueryableByName)]
+        struct IdRow {
+            #[diesel(sql_type = Text)]
+            id: String,
+        }
+
+        let ids: Vec<IdRow> = diesel::sql_query(format!(
+            "SELECT CAST(id AS TEXT) AS id FROM {} ORDER BY id",
+            self.get_records_table_name(collection_name)
+        ))
+        .load(conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        ids.into_iter()
+            .map(|row| {
+                let record = self.query_record_by_id(conn, collection_name, &row.id)?;
+                let value =
+                    serde_json::to_value(&record).map_err(|_| LunarbaseError::InternalError)?;
+                let hash = RecordHash {
+                    id: record.id.clone(),
+                    sha256: canonical_sha256(&value),
+                };
+                Ok((record, hash))
+            })
+            .collect()
+    }
+
+    /// Every record of the collection with a manifest of their hashes.
+    pub async fn export_records(
+        &self,
+        collection_name: &str,
+    ) -> Result<RecordExport, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        collections::table
+            .filter(collections::name.eq(collection_name))
+            .select(collections::id)
+            .first::<i32>(&mut conn)
+            .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+
+        let (records, hashes): (Vec<_>, Vec<_>) = self
+            .hashed_records(&mut conn, collection_name)?
+            .into_iter()
+            .unzip();
+
+        Ok(RecordExport {
+            records,
+            manifest: RecordManifest {
+                collection: collection_name.to_string(),
+                algorithm: MANIFEST_ALGORITHM.to_string(),
+                canonicalization: CANONICALIZATION.to_string(),
+                exported_at: chrono::Utc::now().to_rfc3339(),
+                record_count: hashes.len(),
+                digest: manifest_digest(&hashes),
+                records: hashes,
+            },
+        })
+    }
+
+    /// Compares `manifest` from an earlier export with the records as they
+    /// are now.
+    pub async fn verify_record_manifest(
+        &self,
+        collection_name: &str,
+        manifest: &RecordManifest,
+    ) -> Result<ManifestVerification, LunarbaseError> {
+        let mut errors = Vec::new();
+        if manifest.collection != collection_name {
+            errors.push(format!(
+                "Manifest is for collection '{}'",
+                manifest.collection
+            ));
+        }
+        if manifest.algorithm != MANIFEST_ALGORITHM {
+            errors.push(format!(
+                "Unsupported manifest algorithm '{}', expected '{}'",
+                manifest.algorithm, MANIFEST_ALGORITHM
+            ));
+        }
+        if manifest.canonicalization != CANONICALIZATION {
+            errors.push(format!(
+                "Unsupported canonicalization '{}', expected '{}'",
+                manifest.canonicalization, CANONICALIZATION
+            ));
+        }
+        if !errors.is_empty() {
+            return Err(LunarbaseError::ValidationError(errors));
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        collections::table
+            .filter(collections::name.eq(collection_name))
+            .select(collections::id)
+            .first::<i32>(&mut conn)
+            .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+
+        let current: Vec<RecordHash> = self
+            .hashed_records(&mut conn, collection_name)?
+            .into_iter()
+            .map(|(_, hash)| hash)
+            .collect();
+        let current_by_id: std::collections::HashMap<&str, &str> = current
+            .iter()
+            .map(|hash| (hash.id.as_str(), hash.sha256.as_str()))
+            .collect();
+
+        let mut unchanged = 0;
+        let mut changed = Vec::new();
+        let mut missing = Vec::new();
+        for exported in &manifest.records {
+            match current_by_id.get(exported.id.as_str()) {
+                Some(sha256) if *sha256 == exported.sha256 => unchanged += 1,
+                Some(_) => changed.push(exported.id.clone()),
+                None => missing.push(exported.id.clone()),
+            }
+        }
+        let exported_ids: std::collections::HashSet<&str> = manifest
+            .records
+            .iter()
+            .map(|hash| hash.id.as_str())
+            .collect();
+        let added = current
+            .iter()
+            .filter(|hash| !exported_ids.contains(hash.id.as_str()))
+            .map(|hash| hash.id.clone())
+            .collect();
+
+        Ok(ManifestVerification {
+            collection: collection_name.to_string(),
+            manifest_intact: manifest_digest(&manifest.records) == manifest.digest,
+            digest_matches: manifest_digest(&current) == manifest.digest,
+            unchanged,
+            changed,
+            missing,
+            added,
+            verified_at: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+
+    /// A collection's retention policy, how many records are due under it now
+    /// and its latest runs.
+    pub async fn get_retention_report(
+        &self,
+        collection_name: &str,
+        limit: i64,
+    ) -> Result<RetentionReport, LunarbaseError> {
+        #[derive(Debug, diesel::QueryableByName)]
+        struct CountRow {
+            #[diesel(sql_type = diesel::sql_types::BigInt)]
+            count: i64,
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let collection = collections::table
+            .filter(collections::name.eq(collection_name))
+            .first::<Collection>(&mut conn)
+            .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+        let retention = collection
+            .retention()
+            .map_err(|_| LunarbaseError::InternalError)?;
+
+        let due = match &retention {
+            Some(policy) => {
+                let cutoff = (chrono::Utc::now().naive_utc()
+                    - chrono::Duration::days(i64::from(policy.max_age_days)))
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string();
+                let (condition, binds) = Self::retention_condition(policy, &cutoff);
+                record_statement(
+                    &collection,
+                    format!(
+                        "SELECT COUNT(*) AS count FROM {} WHERE {}",
+                        self.get_records_table_name(&collection.name),
+                        condition
+                    ),
+                )
+                .binds(binds)
+                .load::<CountRow>(&mut conn)
+                .map_err(|_| LunarbaseError::DatabaseError)?
+                .first()
+                .map_or(0, |row| row.count)
+            }
+            None => 0,
+        };
+
+        let runs: Vec<RetentionRun> = collection_retention_runs::table
+            .filter(collection_retention_runs::collection_id.eq(collection.id))
+            .order(collection_retention_runs::id.desc())
+            .limit(limit)
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(RetentionReport {
+            collection_name: collection.name,
+            retention,
+            due,
+            runs: runs.into_iter().map(RetentionRunEntry::from).collect(),
+        })
+    }
+
+    /// Runs [`Self::run_retention_policies`] every
+    /// `database.retention_interval_seconds`, counting the records it handled
+    /// in `retention_records_total`. Records are deleted or archived one at a
+    /// time, so a run never holds the database for longer than a single
+    /// manual delete would.
+    pub fn start_retention_scheduler(&self, metrics: MetricsState) {
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let interval = service.get_retention_interval_seconds().await;
+                if interval == 0 {
+                    tokio::time::sleep(std::time::Duration::from_secs(
+                        DISABLED_SCHEDULER_POLL_SECONDS,
+                    ))
+                    .await;
+                    continue;
+                }
+
+                match service.run_retention_policies().await {
+                    Ok(runs) => {
+                        for run in runs {
+                            metrics.record_retention_run(&run);
+                            if run.processed > 0 || run.failed > 0 {
+                                debug!(
+                                    "Retention run ({}) on collection {}: {} record(s), {} failed",
+                                    run.action, run.collection_id, run.processed, run.failed
+                                );
+                            }
+                        }
+                    }
+                    Err(e) => tracing::warn!("Failed to run retention policies: {:?}", e),
+                }
+
+                tokio::time::sleep(std::time::Duration::from_secs(u64::from(interval))).await;
+            }
+        });
+    }
+
+    /// Sets `status` and `publish_at` of a record, returning it before and after.
+    fn set_record_status(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+        record_id: &str,
+        status: RecordStatus,
+        publish_at: Option<chrono::NaiveDateTime>,
+    ) -> Result<(RecordResponse, RecordResponse), LunarbaseError> {
+        let collection = collections::table
+            .filter(collections::name.eq(collection_name))
+            .first::<Collection>(conn)
+            .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+        if collection.workflow() != CollectionWorkflow::DraftPublish {
+            return Err(LunarbaseError::BadRequest(format!(
+                "Collection '{}' has no draft/publish workflow",
+                collection_name
+            )));
+        }
+
+        let old_record = self.query_record_by_id(conn, collection_name, record_id)?;
+
+        record_statement(
+            &collection,
+            format!(
+                "UPDATE {} SET {} = ?, {} = ? WHERE id = ?",
+                self.get_records_table_name(collection_name),
+                STATUS_COLUMN,
+                PUBLISH_AT_COLUMN
+            ),
+        )
+        .binds([
+            SqlBind::Text(Some(status.as_str().to_string())),
+            SqlBind::Text(
+                publish_at.map(|publish_at| publish_at.format("%Y-%m-%d %H:%M:%S").to_string()),
+            ),
+            SqlBind::Text(Some(record_id.to_string())),
+        ])
+        .execute(conn)
+        .map_err(|_| LunarbaseError::InternalError)?;
+
+        let record = self.query_record_by_id(conn, collection_name, record_id)?;
+        Ok((old_record, record))
+    }
+
+    pub async fn delete_record(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+    ) -> Result<(), LunarbaseError> {
+        self.delete_record_with_events(collection_name, record_id, None)
+            .await
+    }
+
+    /// The file fields of every record in the collection, one object per
+    /// record as [`Self::delete_record_files`] expects it.
+    fn load_record_files(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+        schema: &CollectionSchema,
+    ) -> Result<Vec<Value>, LunarbaseError> {
+        #[derive(diesel::QueryableByName)]
+        struct FileRow {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            url: String,
+        }
+
+        let table_name = self.get_records_table_name(collection_name);
+        let mut record_files = Vec::new();
+        for field in schema
+            .fields
+            .iter()
+            .filter(|field| field.field_type == FieldType::File)
+        {
+            let rows = diesel::sql_query(format!(
+                "SELECT {0} AS url FROM {1} WHERE {0} IS NOT NULL AND {0} != ''",
+                field.name, table_name
+            ))
+            .load::<FileRow>(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+            record_files.extend(
+                rows.into_iter()
+                    .map(|row| serde_json::json!({ field.name.clone(): row.url })),
+            );
+        }
+
+        Ok(record_files)
+    }
+
+    async fn delete_record_files(
+        &self,
+        schema: &CollectionSchema,
+        record_data: &serde_json::Value,
+    ) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        let s3_service = match &self.s3_service {
+            Some(service) => service,
+            None => {
+                tracing::debug!("S3 service not available, skipping file deletion");
+                return errors;
+            }
+        };
+
+        let file_fields: Vec<&FieldDefinition> = schema
+            .fields
+            .iter()
+            .filter(|field| matches!(field.field_type, FieldType::File))
+            .collect();
+
+        if file_fields.is_empty() {
+            return errors;
+        }
+
+        for field in file_fields {
+            if let Some(file_url_value) = record_data.get(&field.name) {
+                if let Some(file_url) = file_url_value.as_str() {
+                    if !file_url.is_empty() {
+                        match s3_service.delete_file(file_url).await {
+                            Ok(_) => {
+                                debug!(
+                                    "Successfully deleted file '{}' for field '{}'",
+                                    file_url, field.name
+                                );
+                            }
+                            Err(e) => {
+                                let error_msg = format!(
+                                    "Failed to delete file '{}' for field '{}': {}",
+                                    file_url, field.name, e
+                                );
+                                tracing::error!("{}", error_msg);
+                                errors.push(error_msg);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+
+    pub async fn delete_record_with_events(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+        user_id: Option<i32>,
+    ) -> Result<(), LunarbaseError> {
+        let schema = self.get_collection(collection_name).await?.schema;
+
+        transaction_then(&self.pool, |conn, after_commit| {
+            let old_record = self
+                .query_record_by_id(conn, collection_name, record_id)
+                .ok();
+
+            self.delete_record_row(conn, collection_name, record_id)?;
+
+            let event = crate::models::RecordEvent::Deleted {
+                record_id: record_id.to_string(),
+                old_record: old_record
+                    .as_ref()
+                    .map(|r| serde_json::to_value(&r.data).unwrap_or_default()),
+            };
+            after_commit.defer(self.emit_record_event(collection_name, event, user_id));
+
+            if let Some(record) = old_record {
+                let schema = &schema;
+                after_commit.defer(async move {
+                    let file_deletion_errors = self.delete_record_files(schema, &record.data).await;
+                    if !file_deletion_errors.is_empty() {
+                        tracing::warn!(
+                            "Some files could not be deleted for record {} in collection {}: {:?}",
+                            record_id,
+                            collection_name,
+                            file_deletion_errors
+                        );
+                    }
+                });
+            }
+
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn execute_batch(
+        &self,
+        operations: &[BatchOperation],
+        user_id: Option<i32>,
+    ) -> Result<Vec<BatchOperationResult>, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let mut schemas: std::collections::HashMap<String, CollectionSchema> =
+            std::collections::HashMap::new();
+        let mut id_types: std::collections::HashMap<String, RecordIdType> =
+            std::collections::HashMap::new();
+        for operation in operations {
+            if schemas.contains_key(&operation.collection) {
+                continue;
+            }
+
+            let collection = collections::table
+                .filter(collections::name.eq(&operation.collection))
+                .first::<Collection>(&mut conn)
+                .map_err(|_| {
+                    LunarbaseError::NotFound(format!(
+                        "Collection '{}' not found",
+                        operation.collection
+                    ))
+                })?;
+            let schema = collection
+                .get_schema()
+                .map_err(|_| LunarbaseError::InternalError)?;
+            schemas.insert(operation.collection.clone(), schema);
+            id_types.insert(operation.collection.clone(), collection.record_id_type());
+        }
+
+        let mut events = Vec::new();
+        let mut deleted_records = Vec::new();
+
+        let results = conn.transaction::<_, LunarbaseError, _>(|conn| {
+            let mut results = Vec::with_capacity(operations.len());
+
+            for (index, operation) in operations.iter().enumerate() {
+                let schema = &schemas[&operation.collection];
+                let collection_name = operation.collection.as_str();
+                let record_id = || {
+                    id_types[&operation.collection]
+                        .normalize(operation.record_id.as_deref().unwrap_or_default())
+                        .ok_or_else(|| {
+                            LunarbaseError::ValidationError(vec!["Invalid record_id".to_string()])
+                        })
+                };
+
+                let outcome = match operation.method {
+                    BatchMethod::Create => {
+                        let data = operation.data.clone().unwrap_or_default();
+                        self.insert_record_row(conn, collection_name, schema, &data)
+                            .map(|record| {
+                                events.push((
+                                    operation.collection.clone(),
+                                    crate::models::RecordEvent::Created {
+                                        record_id: record.id.clone(),
+                                        record: record.data.clone(),
+                                    },
+                                ));
+                                (record.id.clone(), Some(record))
+                            })
+                    }
+                    BatchMethod::Update => record_id().and_then(|record_id| {
+                        let data = operation.data.clone().unwrap_or_default();
+                        let old_record = self
+                            .query_record_by_id(conn, collection_name, &record_id)
+                            .ok();
+
+                        self.update_record_row(
+                            conn,
+                            collection_name,
+                            schema,
+                            &record_id,
+                            &data,
+                            false,
+                        )
+                        .map(|record| {
+                            events.push((
+                                operation.collection.clone(),
+                                crate::models::RecordEvent::Updated {
+                                    record_id: record.id.clone(),
+                                    record: record.data.clone(),
+                                    old_record: old_record.map(|r| r.data),
+                                },
+                            ));
+                            (record.id.clone(), Some(record))
+                        })
+                    }),
+                    BatchMethod::Delete => record_id().and_then(|record_id| {
+                        let old_record = self
+                            .query_record_by_id(conn, collection_name, &record_id)
+                            .ok();
+
+                        self.delete_record_row(conn, collection_name, &record_id)
+                            .map(|_| {
+                                events.push((
+                                    operation.collection.clone(),
+                                    crate::models::RecordEvent::Deleted {
+                                        record_id: record_id.clone(),
+                                        old_record: old_record.as_ref().map(|r| r.data.clone()),
+                                    },
+                                ));
+                                if let Some(record) = old_record {
+                                    deleted_records.push((operation.collection.clone(), record));
+                                }
+                                (record_id.clone(), None)
+                            })
+                    }),
+                };
+
+                let (record_id, record) = outcome.map_err(|e| {
+                    tracing::debug!("Batch operation {} failed, rolling back: {}", index, e);
+                    match e {
+                        LunarbaseError::ValidationError(errors) => LunarbaseError::ValidationError(
+                            errors
+                                .into_iter()
+                                .map(|error| format!("Operation {}: {}", index, error))
+                                .collect(),
+                        ),
+                        LunarbaseError::InvalidFields(errors) => LunarbaseError::InvalidFields(
+                            errors
+                                .into_iter()
+                                .map(|error| FieldValidationError {
+                                    field: format!("operations.{}.{}", index, error.field),
+                                    message: Message::new("validation.batch_operation")
+                                        .arg("index", index)
+                                        .nested("message", error.message),
+                                    ..error
+                                })
+                                .collect(),
+                        ),
+                        other => other,
+                    }
+                })?;
+
+                results.push(BatchOperationResult {
+                    index,
+                    method: operation.method.clone(),
+                    collection: operation.collection.clone(),
+                    record_id,
+                    record,
+                });
+            }
+
+            Ok(results)
+        })?;
+
+        for (collection_name, event) in events {
+            self.emit_record_event(&collection_name, event, user_id)
+                .await;
+        }
+
+        for (collection_name, record) in deleted_records {
+            let file_deletion_errors = self
+                .delete_record_files(&schemas[&collection_name], &record.data)
+                .await;
+            if !file_deletion_errors.is_empty() {
+                tracing::warn!(
+                    "Some files could not be deleted for record {} in collection {}: {:?}",
+                    record.id,
+                    collection_name,
+                    file_deletion_errors
+                );
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Statistics over every collection, or only those of `workspace_id`.
+    /// Record counts come from the cached counts unless `fresh` is set, in
+    /// which case every table is recounted first.
+    pub async fn get_collections_stats(
+        &self,
+        workspace_id: Option<i32>,
+        fresh: bool,
+    ) -> Result<
+        (
+            i64,
+            std::collections::HashMap<String, i64>,
+            std::collections::HashMap<String, i64>,
+            f64,
+            Option<String>,
+            Option<String>,
+            std::collections::HashMap<String, chrono::NaiveDateTime>,
+        ),
+        LunarbaseError,
+    > {
+        let mut collections = self.list_collections().await?;
+        if let Some(workspace_id) = workspace_id {
+            collections.retain(|collection| collection.workspace_id == workspace_id);
+        }
+        let total_collections = collections.len() as i64;
+        let record_counts = self.record_counts(&collections, fresh).await?;
+
+        let mut total_records = 0i64;
+        let mut records_per_collection = std::collections::HashMap::new();
+        let mut reconciled_at = std::collections::HashMap::new();
+        let mut field_types_distribution = std::collections::HashMap::new();
+
+        let mut max_records = 0i64;
+        let mut min_records = i64::MAX;
+        let mut largest_collection: Option<String> = None;
+        let mut smallest_collection: Option<String> = None;
+
+        for collection in &collections {
+            let count = record_counts.get(&collection.id);
+            let record_count = count.map_or(0, |count| count.record_count);
+            if let Some(count) = count {
+                reconciled_at.insert(collection.name.clone(), count.reconciled_at);
+            }
+
+            total_records += record_count;
+            records_per_collection.insert(collection.name.clone(), record_count);
+
+            if record_count > max_records {
+                max_records = record_count;
+                largest_collection = Some(collection.name.clone());
+            }
+            if record_count < min_records && record_count >= 0 {
+                min_records = record_count;
+                smallest_collection = Some(collection.name.clone());
+            }
+
+            for field in &collection.schema.fields {
+                let field_type = format!("{:?}", field.field_type);
+                *field_types_distribution.entry(field_type).or_insert(0) += 1;
+            }
+        }
+
+        let average_records = if total_collections > 0 {
+            total_records as f64 / total_collections as f64
+        } else {
+            0.0
+        };
+
+        if min_records == i64::MAX {
+            smallest_collection = collections.first().map(|c| c.name.clone());
+        }
+
+        Ok((
+            total_records,
+            records_per_collection,
+            field_types_distribution,
+            average_records,
+            largest_collection,
+            smallest_collection,
+            reconciled_at,
+        ))
+    }
+
+    /// Cached record counts of `collections` by collection id. Collections
+    /// that were never counted, or all of them when `fresh` is set, are
+    /// recounted from their tables. Collections whose table cannot be read
+    /// are left out.
+    pub async fn record_counts(
+        &self,
+        collections: &[CollectionResponse],
+        fresh: bool,
+    ) -> Result<std::collections::HashMap<i32, CollectionRecordCount>, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let ids: Vec<i32> = collections.iter().map(|collection| collection.id).collect();
+        let mut counts: std::collections::HashMap<i32, CollectionRecordCount> = if fresh {
+            std::collections::HashMap::new()
+        } else {
+            collection_record_counts::table
+                .filter(collection_record_counts::collection_id.eq_any(&ids))
+                .select(CollectionRecordCount::as_select())
+                .load(&mut conn)
+                .map_err(|_| LunarbaseError::InternalError)?
+                .into_iter()
+                .map(|count| (count.collection_id, count))
+                .collect()
+        };
+
+        for collection in collections {
+            if counts.contains_key(&collection.id) {
+                continue;
+            }
+            match self.reconcile_record_count(&mut conn, collection.id, &collection.name) {
+                Ok(count) => {
+                    counts.insert(collection.id, count);
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to count records of collection '{}': {:?}",
+                    collection.name,
+                    e
+                ),
+            }
+        }
+
+        Ok(counts)
+    }
+
+    /// Recounts the records of every collection, returning how many cached
+    /// counts had drifted from their table.
+    pub async fn reconcile_record_counts(&self) -> Result<usize, LunarbaseError> {
+        let collections = self.list_collections().await?;
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let cached: std::collections::HashMap<i32, i64> = collection_record_counts::table
+            .select((
+                collection_record_counts::collection_id,
+                collection_record_counts::record_count,
+            ))
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::InternalError)?
+            .into_iter()
+            .collect();
+
+        let mut drifted = 0;
+        for collection in &collections {
+            let count =
+                match self.reconcile_record_count(&mut conn, collection.id, &collection.name) {
+                    Ok(count) => count,
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to count records of collection '{}': {:?}",
+                            collection.name,
+                            e
+                        );
+                        continue;
+                    }
+                };
+            if let Some(previous) = cached.get(&collection.id)
+                && *previous != count.record_count
+            {
+                tracing::warn!(
+                    "Record count of collection '{}' had drifted: cached {}, actual {}",
+                    collection.name,
+                    previous,
+                    count.record_count
+                );
+                drifted += 1;
+            }
+        }
+
+        Ok(drifted)
+    }
+
+    /// Counts the records table and stores the result. The write lock is held
+    /// throughout so no insert or delete can slip in between.
+    fn reconcile_record_count(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_id: i32,
+        collection_name: &str,
+    ) -> Result<CollectionRecordCount, LunarbaseError> {
+        #[derive(diesel::QueryableByName)]
+        struct CountResult {
+            #[diesel(sql_type = diesel::sql_types::BigInt)]
+            count: i64,
+        }
+
+        let count_sql = format!(
+            "SELECT COUNT(*) as count FROM {}",
+            self.get_records_table_name(collection_name)
+        );
+
+        conn.immediate_transaction(|conn| {
+            let record_count = diesel::sql_query(&count_sql)
+                .get_result::<CountResult>(conn)?
+                .count;
+            let now = chrono::Utc::now().naive_utc();
+            let count = CollectionRecordCount {
+                collection_id,
+                record_count,
+                reconciled_at: now,
+                updated_at: now,
+            };
+
+            diesel::insert_into(collection_record_counts::table)
+                .values(&count)
+                .on_conflict(collection_record_counts::collection_id)
+                .do_update()
+                .set((
+                    collection_record_counts::record_count.eq(record_count),
+                    collection_record_counts::reconciled_at.eq(now),
+                    collection_record_counts::updated_at.eq(now),
+                ))
+                .execute(conn)?;
+            Ok::<_, diesel::result::Error>(count)
+        })
+        .map_err(|_| LunarbaseError::InternalError)
+    }
+
+    /// Runs [`Self::reconcile_record_counts`] every
+    /// `database.record_count_reconcile_interval_seconds`, starting at startup
+    /// so changes made while the server was down are picked up.
+    pub fn start_record_count_reconciler(&self) {
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let interval = service.get_record_count_reconcile_interval_seconds().await;
+                if interval == 0 {
+                    tokio::time::sleep(std::time::Duration::from_secs(
+                        DISABLED_SCHEDULER_POLL_SECONDS,
+                    ))
+                    .await;
+                    continue;
+                }
+
+                match service.reconcile_record_counts().await {
+                    Ok(0) => debug!("Record counts reconciled"),
+                    Ok(drifted) => {
+                        tracing::warn!("Corrected the record counts of {} collection(s)", drifted)
+                    }
+                    Err(e) => tracing::warn!("Failed to reconcile record counts: {:?}", e),
+                }
+
+                tokio::time::sleep(std::time::Duration::from_secs(u64::from(interval))).await;
+            }
+        });
+    }
+
+    fn validate_collection_name(&self, name: &str) -> Result<(), LunarbaseError> {
+        if name.is_empty() {
+            return Err(LunarbaseError::ValidationError(vec![
+                "Collection name cannot be empty".to_string(),
+            ]));
+        }
+
+        if name.len() > 50 {
+            return Err(LunarbaseError::ValidationError(vec![
+                "Collection name too long (max 50 characters)".to_string(),
+            ]));
+        }
+
+        if !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(LunarbaseError::ValidationError(vec![
+                "Collection name can only contain letters, numbers, and underscores".to_string(),
+            ]));
+        }
+
+        let reserved_names = ["users", "auth", "admin", "api", "system", "templates"];
+        if reserved_names.contains(&name) {
+            return Err(LunarbaseError::ValidationError(vec![
+                "Collection name is reserved".to_string(),
+            ]));
+        }
+
+        if name.starts_with('_') {
+            return Err(LunarbaseError::ValidationError(vec![
+                "Collection names starting with '_' are reserved for system collections"
+                    .to_string(),
+            ]));
+        }
+
+        Ok(())
+    }
+
+    fn validate_schema(&self, schema: &CollectionSchema) -> Result<(), LunarbaseError> {
+        if schema.fields.is_empty() {
+            return Err(LunarbaseError::ValidationError(vec![
+                "Schema must have at least one field".to_string(),
+            ]));
+        }
+
+        let mut field_names = std::collections::HashSet::new();
+        let reserved_field_names = ["created_at", "updated_at"];
+
+        for field in &schema.fields {
+            if !field_names.insert(&field.name) {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Duplicate field name: {}",
+                    field.name
+                )]));
+            }
+
+            if reserved_field_names.contains(&field.name.as_str()) {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Field name '{}' is reserved and cannot be used",
+                    field.name
+                )]));
+            }
+
+            if field.name.is_empty() || field.name.len() > 50 {
+                return Err(LunarbaseError::ValidationError(vec![
+                    "Field name must be 1-50 characters".to_string(),
+                ]));
+            }
+
+            if !field.name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Err(LunarbaseError::ValidationError(vec![
+                    "Field name can only contain letters, numbers, and underscores".to_string(),
+                ]));
+            }
+
+            if field.relation_target.is_some() && field.field_type != FieldType::Relation {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Field '{}' has a relation_target but is not a relation field",
+                    field.name
+                )]));
+            }
+
+            if let Some(format) = field.number_format() {
+                if field.field_type != FieldType::Number {
                     return Err(LunarbaseError::ValidationError(vec![format!(
-                        "Field '{}' does not exist in collection schema",
-                        field_name
+                        "Field '{}' has a number_format but is not a number field",
+                        field.name
+                    )]));
+                }
+                let fractional_default = field
+                    .default_value
+                    .as_ref()
+                    .and_then(Value::as_f64)
+                    .is_some_and(|n| n.fract() != 0.0);
+                if format == NumberFormat::Integer && fractional_default {
+                    return Err(LunarbaseError::ValidationError(vec![format!(
+                        "Integer field '{}' cannot default to a fractional number",
+                        field.name
+                    )]));
+                }
+            }
+
+            if field.field_type == FieldType::Decimal {
+                let (precision, scale) = field.decimal_precision_and_scale();
+                if precision == 0 || precision > MAX_DECIMAL_PRECISION || scale > precision {
+                    return Err(LunarbaseError::ValidationError(vec![format!(
+                        "Field '{}' must have a precision between 1 and {} and a scale no larger than its precision",
+                        field.name, MAX_DECIMAL_PRECISION
                     )]));
                 }
             }
         }
 
-        for (field_name, file_upload) in files {
-            let file_data =
-                match base64::engine::general_purpose::STANDARD.decode(&file_upload.data) {
-                    Ok(data) => data,
-                    Err(_) => {
-                        s3_service.cleanup_files(uploaded_files).await;
-                        return Err(LunarbaseError::ValidationError(vec![format!(
-                            "Invalid base64 data for file field '{}'",
-                            field_name
-                        )]));
-                    }
-                };
+        for field in &schema.fields {
+            if field.field_type != FieldType::GeoPoint {
+                continue;
+            }
+            let (lat_column, lng_column) = geo_point_columns(&field.name);
+            if let Some(other) = schema
+                .fields
+                .iter()
+                .find(|other| other.name == lat_column || other.name == lng_column)
+            {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Field name '{}' is used by the coordinates of geopoint field '{}'",
+                    other.name, field.name
+                )]));
+            }
+        }
+
+        for field in &schema.fields {
+            let Some(paths) = &field.indexed_paths else {
+                continue;
+            };
+            if field.field_type != FieldType::Json {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Field '{}' has indexed_paths but is not a json field",
+                    field.name
+                )]));
+            }
+            for (index, path) in paths.iter().enumerate() {
+                if !is_valid_json_path(path) {
+                    return Err(LunarbaseError::ValidationError(vec![format!(
+                        "Invalid indexed path '{}' of field '{}': use dot-separated keys of letters, numbers and underscores",
+                        path, field.name
+                    )]));
+                }
+                if paths[..index].contains(path) {
+                    return Err(LunarbaseError::ValidationError(vec![format!(
+                        "Indexed path '{}' is listed more than once for field '{}'",
+                        path, field.name
+                    )]));
+                }
+                let column = json_path_column(&field.name, path);
+                if schema.fields.iter().any(|other| other.name == column) {
+                    return Err(LunarbaseError::ValidationError(vec![format!(
+                        "Field name '{}' is used by indexed path '{}' of json field '{}'",
+                        column, path, field.name
+                    )]));
+                }
+            }
+        }
+
+        for field in &schema.fields {
+            let source_field = field.slug_source_field();
+            if field.field_type != FieldType::Slug {
+                if source_field.is_some() {
+                    return Err(LunarbaseError::ValidationError(vec![format!(
+                        "Field '{}' has a source_field but is not a slug field",
+                        field.name
+                    )]));
+                }
+                continue;
+            }
+
+            let is_text_source = source_field.is_some_and(|source| {
+                schema
+                    .fields
+                    .iter()
+                    .any(|other| other.name == source && other.field_type == FieldType::Text)
+            });
+            if !is_text_source {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Slug field '{}' needs a source_field naming a text field of the collection",
+                    field.name
+                )]));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Number fields switched to the integer format must not hold fractional
+    /// values already, which they could no longer return as written.
+    fn ensure_integer_fields_hold_integers(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+        old: &CollectionSchema,
+        new: &CollectionSchema,
+    ) -> Result<(), LunarbaseError> {
+        let table_name = self.get_records_table_name(collection_name);
+
+        for field in new
+            .fields
+            .iter()
+            .filter(|f| f.number_format() == Some(NumberFormat::Integer))
+        {
+            let existing = old.fields.iter().any(|f| {
+                f.name == field.name
+                    && f.field_type == FieldType::Number
+                    && f.number_format() != Some(NumberFormat::Integer)
+            });
+            if !existing {
+                continue;
+            }
+
+            #[derive(diesel::QueryableByName)]
+            struct CountResult {
+                #[diesel(sql_type = diesel::sql_types::BigInt)]
+                count: i64,
+            }
+
+            let fractional = diesel::sql_query(format!(
+                "SELECT COUNT(*) AS count FROM {0} WHERE \"{1}\" != CAST(\"{1}\" AS INTEGER)",
+                table_name, field.name
+            ))
+            .get_result::<CountResult>(conn)
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to check values of field '{}' in {}: {:?}",
+                    field.name,
+                    table_name,
+                    e
+                );
+                LunarbaseError::DatabaseError
+            })?
+            .count;
+
+            if fractional > 0 {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Field '{}' cannot become an integer field: {} records hold fractional values",
+                    field.name, fractional
+                )]));
+            }
+        }
+        Ok(())
+    }
+
+    /// Relation targets must name `_users`, an existing collection, or the
+    /// collection being defined (self-references).
+    fn validate_relation_targets(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+        schema: &CollectionSchema,
+    ) -> Result<(), LunarbaseError> {
+        for field in &schema.fields {
+            let Some(target) = field.relation_target.as_deref() else {
+                continue;
+            };
+
+            if target == USERS_SYSTEM_COLLECTION || target == collection_name {
+                continue;
+            }
+
+            let exists = collections::table
+                .filter(collections::name.eq(target))
+                .count()
+                .get_result::<i64>(conn)
+                .map_err(|_| LunarbaseError::DatabaseError)?
+                > 0;
+
+            if !exists {
+                return Err(LunarbaseError::ValidationError(vec![format!(
+                    "Field '{}' targets unknown collection '{}'",
+                    field.name, target
+                )]));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn process_file_uploads(
+        &self,
+        collection_name: &str,
+        schema: &CollectionSchema,
+        files: &std::collections::HashMap<String, FileUpload>,
+        user_id: Option<i32>,
+    ) -> Result<std::collections::HashMap<String, String>, LunarbaseError> {
+        let s3_enabled = self
+            .config_manager
+            .get_bool("storage", "s3_enabled")
+            .await
+            .unwrap_or(false);
+        let s3_service = match &self.s3_service {
+            Some(service) if s3_enabled => service,
+            _ => {
+                let mut field_names: Vec<&String> = files.keys().collect();
+                field_names.sort();
+                return Err(LunarbaseError::InvalidFields(
+                    field_names
+                        .into_iter()
+                        .map(|field_name| FieldValidationError {
+                            field: field_name.clone(),
+                            code: "uploads_disabled".to_string(),
+                            message: Message::new("validation.uploads_disabled")
+                                .arg("field", field_name),
+                        })
+                        .collect(),
+                ));
+            }
+        };
+
+        let errors = self.check_record_files(schema, files);
+        if !errors.is_empty() {
+            return Err(LunarbaseError::InvalidFields(errors));
+        }
+
+        let mut field_names: Vec<&String> = files.keys().collect();
+        field_names.sort();
+
+        // Every file is scanned before the first one is stored
+        let mut decoded_files = Vec::new();
+        let mut errors = Vec::new();
+        for field_name in field_names {
+            let file_upload = &files[field_name];
+            // Already checked by `check_record_files`
+            let file_data = base64::engine::general_purpose::STANDARD
+                .decode(&file_upload.data)
+                .unwrap_or_default();
+
+            let origin = UploadOrigin {
+                collection_name: Some(collection_name),
+                field_name,
+                user_id,
+            };
+            match self
+                .upload_scanner
+                .check(
+                    origin,
+                    &file_upload.filename,
+                    &file_upload.content_type,
+                    &file_data,
+                )
+                .await
+            {
+                Ok(()) => decoded_files.push((field_name, file_upload, file_data)),
+                Err(rejection) => errors.push(rejection.field_error(field_name)),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(LunarbaseError::InvalidFields(errors));
+        }
+
+        let mut file_urls = std::collections::HashMap::new();
+        let mut uploaded_files = Vec::new();
 
+        for (field_name, file_upload, file_data) in decoded_files {
             match s3_service
                 .upload_file(
                     file_data,
@@ -1830,259 +6337,789 @@ impl CollectionService {
                     uploaded_files.push(result.file_url.clone());
                     file_urls.insert(field_name.clone(), result.file_url);
                 }
-                Err(e) => {
-                    s3_service.cleanup_files(uploaded_files).await;
-                    tracing::error!("Failed to upload file for field '{}': {}", field_name, e);
-                    return Err(LunarbaseError::ValidationError(vec![format!(
-                        "Failed to upload file for field '{}'",
-                        field_name
-                    )]));
+                Err(e) => {
+                    s3_service.cleanup_files(uploaded_files).await;
+                    tracing::error!("Failed to upload file for field '{}': {}", field_name, e);
+                    return Err(LunarbaseError::InvalidFields(vec![FieldValidationError {
+                        field: field_name.clone(),
+                        code: "upload_failed".to_string(),
+                        message: Message::new("validation.upload_failed").arg("field", field_name),
+                    }]));
+                }
+            }
+        }
+
+        Ok(file_urls)
+    }
+
+    /// Every problem with `files` that can be found without storing them,
+    /// keyed by the file field each upload targets.
+    pub(crate) fn check_record_files(
+        &self,
+        schema: &CollectionSchema,
+        files: &std::collections::HashMap<String, FileUpload>,
+    ) -> Vec<FieldValidationError> {
+        let mut field_names: Vec<&String> = files.keys().collect();
+        field_names.sort();
+
+        let mut errors = Vec::new();
+        for field_name in field_names {
+            let file_upload = &files[field_name];
+            let mut error = |code: &str, message: Message| {
+                errors.push(FieldValidationError {
+                    field: field_name.clone(),
+                    code: code.to_string(),
+                    message: message.arg("field", field_name),
+                })
+            };
+
+            match schema.fields.iter().find(|f| &f.name == field_name) {
+                Some(field) if field.field_type == FieldType::File => {}
+                Some(_) => {
+                    error(
+                        "not_a_file_field",
+                        Message::new("validation.not_a_file_field"),
+                    );
+                    continue;
+                }
+                None => {
+                    error(
+                        "unknown_field",
+                        Message::new("validation.unknown_file_field"),
+                    );
+                    continue;
                 }
             }
-        }
 
-        Ok(file_urls)
+            if !is_media_type(&file_upload.content_type) {
+                error(
+                    "invalid_content_type",
+                    Message::new("validation.invalid_content_type")
+                        .arg("content_type", &file_upload.content_type),
+                );
+            }
+            if base64::engine::general_purpose::STANDARD
+                .decode(&file_upload.data)
+                .is_err()
+            {
+                error("invalid_base64", Message::new("validation.invalid_base64"));
+            }
+        }
+        errors
     }
 
-    fn validate_record_data(
+    pub(crate) fn validate_record_data(
         &self,
         schema: &CollectionSchema,
         data: &Value,
     ) -> Result<Value, LunarbaseError> {
-        let mut validated = Map::new();
+        self.check_record_data(schema, data, false)
+            .map_err(LunarbaseError::InvalidFields)
+    }
 
-        if let Some(data_obj) = data.as_object() {
-            for field in &schema.fields {
-                if field.name == "id" {
-                    continue;
-                }
+    /// Dry run of a record write: every field error `data` would be rejected
+    /// with. Slug fields are generated on write, so only given ones are checked.
+    pub async fn validate_record(
+        &self,
+        collection_name: &str,
+        data: &Value,
+    ) -> Result<Vec<FieldValidationError>, LunarbaseError> {
+        let collection = self.get_collection(collection_name).await?;
+        Ok(self
+            .check_record_data(&collection.schema, data, true)
+            .err()
+            .unwrap_or_default())
+    }
 
-                let field_value = data_obj.get(&field.name);
+    /// The validated field values of `data`, or every field error in it.
+    fn check_record_data(
+        &self,
+        schema: &CollectionSchema,
+        data: &Value,
+        skip_missing_slugs: bool,
+    ) -> Result<Value, Vec<FieldValidationError>> {
+        let Some(data_obj) = data.as_object() else {
+            return Err(vec![FieldValidationError {
+                field: String::new(),
+                code: "invalid_type".to_string(),
+                message: Message::new("validation.not_an_object"),
+            }]);
+        };
 
-                if field.required && (field_value.is_none() || field_value == Some(&Value::Null)) {
-                    return Err(LunarbaseError::ValidationError(vec![format!(
-                        "Field '{}' is required",
-                        field.name
-                    )]));
-                }
+        let mut validated = Map::new();
+        let mut errors = Vec::new();
 
-                let value_to_validate = if field_value.is_none() {
-                    if let Some(default) = &field.default_value {
-                        default
-                    } else {
-                        continue;
-                    }
-                } else {
-                    field_value.unwrap()
-                };
+        for field in &schema.fields {
+            if field.name == "id" {
+                continue;
+            }
 
-                let validated_value = self.validate_field_value(&field, value_to_validate)?;
-                validated.insert(field.name.clone(), validated_value);
+            let field_value = data_obj.get(&field.name);
+            let missing = field_value.is_none_or(Value::is_null);
+            if missing && skip_missing_slugs && field.field_type == FieldType::Slug {
+                continue;
+            }
+            if missing && field.required {
+                errors.push(field_error(
+                    field,
+                    "required",
+                    Message::new("validation.required"),
+                ));
+                continue;
+            }
+
+            let Some(value_to_validate) = field_value.or(field.default_value.as_ref()) else {
+                continue;
+            };
+
+            match self.validate_field_value(field, value_to_validate) {
+                Ok(value) => {
+                    validated.insert(field.name.clone(), value);
+                }
+                Err(error) => errors.push(error),
             }
-        } else {
-            return Err(LunarbaseError::ValidationError(vec![
-                "Record data must be a JSON object".to_string(),
-            ]));
         }
 
-        Ok(Value::Object(validated))
+        if errors.is_empty() {
+            Ok(Value::Object(validated))
+        } else {
+            Err(errors)
+        }
     }
 
     fn validate_field_value(
         &self,
         field: &FieldDefinition,
         value: &Value,
-    ) -> Result<Value, LunarbaseError> {
+    ) -> Result<Value, FieldValidationError> {
+        let invalid = |code: &str, message: Message| Err(field_error(field, code, message));
+
         match field.field_type {
             FieldType::Text => {
-                if let Some(s) = value.as_str() {
-                    if let Some(validation) = &field.validation {
-                        if let Some(min_len) = validation.min_length {
-                            if s.len() < min_len {
-                                return Err(LunarbaseError::ValidationError(vec![format!(
-                                    "Field '{}' is too short (minimum {} characters)",
-                                    field.name, min_len
-                                )]));
-                            }
-                        }
-                        if let Some(max_len) = validation.max_length {
-                            if s.len() > max_len {
-                                return Err(LunarbaseError::ValidationError(vec![format!(
-                                    "Field '{}' is too long (maximum {} characters)",
-                                    field.name, max_len
-                                )]));
-                            }
-                        }
-                        if let Some(pattern) = &validation.pattern {
-                            match regex::Regex::new(pattern) {
-                                Ok(regex) => {
-                                    if !regex.is_match(s) {
-                                        return Err(LunarbaseError::ValidationError(vec![
-                                            format!(
-                                                "Field '{}' does not match required pattern: {}",
-                                                field.name, pattern
-                                            ),
-                                        ]));
-                                    }
-                                }
-                                Err(_) => {
-                                    return Err(LunarbaseError::ValidationError(vec![format!(
-                                        "Invalid regex pattern for field '{}': {}",
-                                        field.name, pattern
-                                    )]));
-                                }
+                let Some(s) = value.as_str() else {
+                    return invalid("invalid_type", Message::new("validation.text_expected"));
+                };
+                if let Some(validation) = &field.validation {
+                    if let Some(min_len) = validation.min_length
+                        && s.len() < min_len
+                    {
+                        return invalid(
+                            "too_short",
+                            Message::new("validation.too_short").arg("min", min_len),
+                        );
+                    }
+                    if let Some(max_len) = validation.max_length
+                        && s.len() > max_len
+                    {
+                        return invalid(
+                            "too_long",
+                            Message::new("validation.too_long").arg("max", max_len),
+                        );
+                    }
+                    if let Some(pattern) = &validation.pattern {
+                        match regex::Regex::new(pattern) {
+                            Ok(regex) if !regex.is_match(s) => {
+                                return invalid(
+                                    "pattern_mismatch",
+                                    Message::new("validation.pattern_mismatch")
+                                        .arg("pattern", pattern),
+                                );
                             }
-                        }
-                        if let Some(enum_values) = &validation.enum_values {
-                            if !enum_values.contains(&s.to_string()) {
-                                return Err(LunarbaseError::ValidationError(vec![format!(
-                                    "Field '{}' must be one of: {:?}",
-                                    field.name, enum_values
-                                )]));
+                            Ok(_) => {}
+                            Err(_) => {
+                                return invalid(
+                                    "invalid_pattern",
+                                    Message::new("validation.invalid_pattern")
+                                        .arg("pattern", pattern),
+                                );
                             }
                         }
                     }
-                    Ok(value.clone())
-                } else {
-                    Err(LunarbaseError::ValidationError(vec![format!(
-                        "Field '{}' must be text",
-                        field.name
-                    )]))
+                    if let Some(enum_values) = &validation.enum_values
+                        && !enum_values.contains(&s.to_string())
+                    {
+                        return invalid(
+                            "invalid_choice",
+                            Message::new("validation.invalid_choice")
+                                .arg("choices", format!("{:?}", enum_values)),
+                        );
+                    }
                 }
+                Ok(value.clone())
             }
             FieldType::Number => {
-                if let Some(n) = value.as_f64() {
-                    if let Some(validation) = &field.validation {
-                        if let Some(min_val) = validation.min_value {
-                            if n < min_val {
-                                return Err(LunarbaseError::ValidationError(vec![format!(
-                                    "Field '{}' is too small (minimum {})",
-                                    field.name, min_val
-                                )]));
-                            }
-                        }
-                        if let Some(max_val) = validation.max_value {
-                            if n > max_val {
-                                return Err(LunarbaseError::ValidationError(vec![format!(
-                                    "Field '{}' is too large (maximum {})",
-                                    field.name, max_val
-                                )]));
-                            }
-                        }
+                let Some(n) = value.as_f64() else {
+                    return invalid("invalid_type", Message::new("validation.number_expected"));
+                };
+                if field.number_format() == Some(NumberFormat::Integer) && n.fract() != 0.0 {
+                    return invalid("invalid_type", Message::new("validation.integer_expected"));
+                }
+                if let Some(validation) = &field.validation {
+                    if let Some(min_val) = validation.min_value
+                        && n < min_val
+                    {
+                        return invalid(
+                            "too_small",
+                            Message::new("validation.too_small").arg("min", min_val),
+                        );
+                    }
+                    if let Some(max_val) = validation.max_value
+                        && n > max_val
+                    {
+                        return invalid(
+                            "too_large",
+                            Message::new("validation.too_large").arg("max", max_val),
+                        );
                     }
-                    Ok(value.clone())
-                } else {
-                    Err(LunarbaseError::ValidationError(vec![format!(
-                        "Field '{}' must be a number",
-                        field.name
-                    )]))
                 }
+                Ok(value.clone())
             }
             FieldType::Boolean => {
                 if value.is_boolean() {
                     Ok(value.clone())
                 } else {
-                    Err(LunarbaseError::ValidationError(vec![format!(
-                        "Field '{}' must be a boolean",
-                        field.name
-                    )]))
-                }
-            }
-            FieldType::Email => {
-                if let Some(s) = value.as_str() {
-                    if s.contains('@') && s.contains('.') {
-                        Ok(value.clone())
-                    } else {
-                        Err(LunarbaseError::ValidationError(vec![format!(
-                            "Field '{}' must be a valid email address",
-                            field.name
-                        )]))
-                    }
-                } else {
-                    Err(LunarbaseError::ValidationError(vec![format!(
-                        "Field '{}' must be text",
-                        field.name
-                    )]))
+                    invalid("invalid_type", Message::new("validation.boolean_expected"))
                 }
             }
+            FieldType::Email => match value.as_str().map(normalize_email) {
+                Some(Some(email)) => Ok(Value::String(email)),
+                Some(None) => invalid("invalid_format", Message::new("validation.invalid_email")),
+                None => invalid("invalid_type", Message::new("validation.text_expected")),
+            },
             FieldType::Json | FieldType::RichText => Ok(value.clone()),
-            FieldType::Date => {
-                if let Some(s) = value.as_str() {
-                    match chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
-                        Ok(_) => Ok(value.clone()),
-                        Err(_) => Err(LunarbaseError::ValidationError(vec![format!(
-                            "Field '{}' must be a valid date in YYYY-MM-DD format",
-                            field.name
-                        )])),
+            FieldType::Decimal => {
+                let (precision, scale) = field.decimal_precision_and_scale();
+                let Some(text) = decimal_text(value) else {
+                    return invalid("invalid_type", Message::new("validation.decimal_expected"));
+                };
+                let minor_units = match parse_minor_units(&text, precision, scale) {
+                    Ok(minor_units) => minor_units,
+                    Err(e) => {
+                        return invalid(
+                            "invalid_format",
+                            Message::new("validation.invalid_decimal").arg("reason", e),
+                        );
                     }
-                } else {
-                    Err(LunarbaseError::ValidationError(vec![format!(
-                        "Field '{}' must be a date string",
-                        field.name
-                    )]))
-                }
-            }
-            FieldType::Url => {
-                if let Some(s) = value.as_str() {
-                    if s.starts_with("http://") || s.starts_with("https://") {
-                        if s.contains('.') && s.len() > 10 {
-                            Ok(value.clone())
-                        } else {
-                            Err(LunarbaseError::ValidationError(vec![format!(
-                                "Field '{}' must be a valid URL",
-                                field.name
-                            )]))
-                        }
-                    } else {
-                        Err(LunarbaseError::ValidationError(vec![format!(
-                            "Field '{}' must be a valid URL starting with http:// or https://",
-                            field.name
-                        )]))
+                };
+
+                if let Some(validation) = &field.validation {
+                    let amount = minor_units as f64 / 10f64.powi(scale as i32);
+                    if let Some(min_val) = validation.min_value
+                        && amount < min_val
+                    {
+                        return invalid(
+                            "too_small",
+                            Message::new("validation.too_small").arg("min", min_val),
+                        );
                     }
-                } else {
-                    Err(LunarbaseError::ValidationError(vec![format!(
-                        "Field '{}' must be a URL string",
-                        field.name
-                    )]))
-                }
-            }
-            FieldType::File => {
-                if let Some(s) = value.as_str() {
-                    // TODO: For now, treat file as a path string - in future this could be enhanced
-                    if !s.is_empty() && s.len() <= 500 {
-                        Ok(value.clone())
-                    } else {
-                        Err(LunarbaseError::ValidationError(vec![format!(
-                            "Field '{}' must be a valid file path (max 500 characters)",
-                            field.name
-                        )]))
+                    if let Some(max_val) = validation.max_value
+                        && amount > max_val
+                    {
+                        return invalid(
+                            "too_large",
+                            Message::new("validation.too_large").arg("max", max_val),
+                        );
                     }
-                } else {
-                    Err(LunarbaseError::ValidationError(vec![format!(
-                        "Field '{}' must be a file path string",
-                        field.name
-                    )]))
                 }
+
+                Ok(Value::String(format_minor_units(minor_units, scale)))
             }
-            FieldType::Relation => {
-                if let Some(s) = value.as_str() {
-                    if !s.is_empty() && s.len() <= 50 {
-                        Ok(value.clone())
-                    } else {
-                        Err(LunarbaseError::ValidationError(vec![format!(
-                            "Field '{}' must be a valid relation ID (max 50 characters)",
-                            field.name
-                        )]))
-                    }
-                } else if let Some(_n) = value.as_i64() {
+            FieldType::GeoPoint => match geo_point_coordinates(value) {
+                Some((lat, _)) if !(-90.0..=90.0).contains(&lat) => invalid(
+                    "out_of_range",
+                    Message::new("validation.latitude_out_of_range"),
+                ),
+                Some((_, lng)) if !(-180.0..=180.0).contains(&lng) => invalid(
+                    "out_of_range",
+                    Message::new("validation.longitude_out_of_range"),
+                ),
+                Some((lat, lng)) => Ok(serde_json::json!({ "lat": lat, "lng": lng })),
+                None => invalid(
+                    "invalid_type",
+                    Message::new("validation.geo_point_expected"),
+                ),
+            },
+            FieldType::Date => match value.as_str() {
+                Some(s) if chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok() => {
                     Ok(value.clone())
-                } else {
-                    Err(LunarbaseError::ValidationError(vec![format!(
-                        "Field '{}' must be a relation ID (string or number)",
-                        field.name
-                    )]))
                 }
+                Some(_) => invalid("invalid_format", Message::new("validation.invalid_date")),
+                None => invalid("invalid_type", Message::new("validation.date_expected")),
+            },
+            FieldType::Url => match value.as_str() {
+                Some(s) if !s.starts_with("http://") && !s.starts_with("https://") => {
+                    invalid("invalid_format", Message::new("validation.url_scheme"))
+                }
+                Some(s) if s.contains('.') && s.len() > 10 => Ok(value.clone()),
+                Some(_) => invalid("invalid_format", Message::new("validation.invalid_url")),
+                None => invalid("invalid_type", Message::new("validation.url_expected")),
+            },
+            FieldType::Slug => match value.as_str() {
+                Some(s) if !s.is_empty() && slugify(s) == s => Ok(value.clone()),
+                _ => invalid("invalid_format", Message::new("validation.invalid_slug")),
+            },
+            FieldType::File => match value.as_str() {
+                // TODO: For now, treat file as a path string - in future this could be enhanced
+                Some(s) if !s.is_empty() && s.len() <= 500 => Ok(value.clone()),
+                Some(_) => invalid(
+                    "invalid_format",
+                    Message::new("validation.invalid_file_path"),
+                ),
+                None => invalid(
+                    "invalid_type",
+                    Message::new("validation.file_path_expected"),
+                ),
+            },
+            FieldType::Relation => match value.as_str() {
+                Some(s) if !s.is_empty() && s.len() <= 50 => Ok(value.clone()),
+                Some(_) => invalid(
+                    "invalid_format",
+                    Message::new("validation.invalid_relation"),
+                ),
+                None if value.as_i64().is_some() => Ok(value.clone()),
+                None => invalid("invalid_type", Message::new("validation.relation_expected")),
+            },
+        }
+    }
+}
+
+fn field_error(field: &FieldDefinition, code: &str, message: Message) -> FieldValidationError {
+    FieldValidationError {
+        field: field.name.clone(),
+        code: code.to_string(),
+        message: message.arg("field", &field.name),
+    }
+}
+
+/// `type/subtype` with optional `; name=value` parameters, as in a
+/// `Content-Type` header.
+fn is_media_type(value: &str) -> bool {
+    let is_token = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "!#$&^_.+-".contains(c))
+    };
+
+    let mut params = value.split(';');
+    let essence = params.next().unwrap_or_default().trim();
+    let Some((kind, subtype)) = essence.split_once('/') else {
+        return false;
+    };
+    is_token(kind)
+        && is_token(subtype)
+        && params.all(|param| {
+            param
+                .split_once('=')
+                .is_some_and(|(name, value)| is_token(name.trim()) && !value.trim().is_empty())
+        })
+}
+
+/// Reads `{lat, lng}` from a `geopoint` value.
+fn geo_point_coordinates(value: &Value) -> Option<(f64, f64)> {
+    Some((value.get("lat")?.as_f64()?, value.get("lng")?.as_f64()?))
+}
+
+/// Orderable collections keep their position in a `sort_order` column, so the
+/// schema cannot define a field with that name.
+fn ensure_no_sort_order_field(schema: &CollectionSchema) -> Result<(), LunarbaseError> {
+    if schema.fields.iter().any(|f| f.name == SORT_ORDER_COLUMN) {
+        return Err(LunarbaseError::ValidationError(vec![format!(
+            "Field name '{}' is reserved in orderable collections",
+            SORT_ORDER_COLUMN
+        )]));
+    }
+    Ok(())
+}
+
+/// Workflow collections keep record state in `status` and `publish_at`
+/// columns, so the schema cannot define fields with those names.
+fn ensure_no_workflow_fields(schema: &CollectionSchema) -> Result<(), LunarbaseError> {
+    for reserved in [STATUS_COLUMN, PUBLISH_AT_COLUMN] {
+        if schema.fields.iter().any(|f| f.name == reserved) {
+            return Err(LunarbaseError::ValidationError(vec![format!(
+                "Field name '{}' is reserved in collections with a draft/publish workflow",
+                reserved
+            )]));
+        }
+    }
+    Ok(())
+}
+
+/// Schedule fields must be date fields of the schema, and publishing or
+/// archiving on schedule needs the draft/publish workflow.
+fn validate_record_schedule(
+    schedule: &RecordSchedule,
+    schema: &CollectionSchema,
+    workflow: CollectionWorkflow,
+) -> Result<(), LunarbaseError> {
+    let mut errors = Vec::new();
+
+    for field_name in schedule.fields() {
+        let is_date = schema
+            .fields
+            .iter()
+            .any(|f| f.name == field_name && f.field_type == FieldType::Date);
+        if !is_date {
+            errors.push(format!(
+                "Schedule field '{}' must be a date field of the collection",
+                field_name
+            ));
+        }
+    }
+
+    if workflow != CollectionWorkflow::DraftPublish {
+        if schedule.publish_at_field.is_some() {
+            errors.push("publish_at_field needs the draft_publish workflow".to_string());
+        }
+        if schedule.expire_at_field.is_some() && schedule.expire_action == ExpireAction::Archive {
+            errors.push(
+                "expire_action 'archive' needs the draft_publish workflow; use 'delete'"
+                    .to_string(),
+            );
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(LunarbaseError::ValidationError(errors))
+    }
+}
+
+fn validate_retention_policy(
+    policy: &RetentionPolicy,
+    schema: &CollectionSchema,
+    workflow: CollectionWorkflow,
+) -> Result<(), LunarbaseError> {
+    let mut errors = Vec::new();
+
+    let is_date = matches!(policy.field.as_str(), "created_at" | "updated_at")
+        || schema
+            .fields
+            .iter()
+            .any(|f| f.name == policy.field && f.field_type == FieldType::Date);
+    if !is_date {
+        errors.push(format!(
+            "Retention field '{}' must be created_at, updated_at or a date field of the collection",
+            policy.field
+        ));
+    }
+    if policy.max_age_days > MAX_RETENTION_DAYS {
+        errors.push(format!(
+            "max_age_days must be between 1 and {}",
+            MAX_RETENTION_DAYS
+        ));
+    }
+    if policy.action == ExpireAction::Archive && workflow != CollectionWorkflow::DraftPublish {
+        errors.push(
+            "Retention action 'archive' needs the draft_publish workflow; use 'delete'".to_string(),
+        );
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(LunarbaseError::ValidationError(errors))
+    }
+}
+
+/// The fields a schedule watches and what falling due on each one does.
+fn schedule_operations(schedule: &RecordSchedule) -> Vec<(&str, ScheduledAction)> {
+    let mut operations = Vec::new();
+    if let Some(field) = &schedule.publish_at_field {
+        operations.push((field.as_str(), ScheduledAction::Publish));
+    }
+    if let Some(field) = &schedule.expire_at_field {
+        let action = match schedule.expire_action {
+            ExpireAction::Archive => ScheduledAction::Archive,
+            ExpireAction::Delete => ScheduledAction::Delete,
+        };
+        operations.push((field.as_str(), action));
+    }
+    operations
+}
+
+/// JSON number for a stored `number` field value. Integer fields always return
+/// integers and float fields always floats, so `10.0` reads back as written.
+fn number_to_json(n: f64, format: Option<NumberFormat>) -> Value {
+    let fits_integer = n >= i64::MIN as f64 && n <= i64::MAX as f64;
+    let as_integer = match format {
+        Some(NumberFormat::Integer) => fits_integer,
+        Some(NumberFormat::Float) => false,
+        None => fits_integer && n.fract() == 0.0,
+    };
+    if as_integer {
+        Value::Number(serde_json::Number::from(n.round() as i64))
+    } else {
+        Value::Number(serde_json::Number::from_f64(n).unwrap_or(serde_json::Number::from(0)))
+    }
+}
+
+/// Decimal values are stored as minor units of the field's scale, so changing
+/// the scale of an existing field would silently rescale every stored amount.
+fn ensure_decimal_scales_unchanged(
+    old: &CollectionSchema,
+    new: &CollectionSchema,
+) -> Result<(), LunarbaseError> {
+    for field in new
+        .fields
+        .iter()
+        .filter(|f| f.field_type == FieldType::Decimal)
+    {
+        let Some(previous) = old
+            .fields
+            .iter()
+            .find(|f| f.name == field.name && f.field_type == FieldType::Decimal)
+        else {
+            continue;
+        };
+
+        if previous.decimal_precision_and_scale().1 != field.decimal_precision_and_scale().1 {
+            return Err(LunarbaseError::ValidationError(vec![format!(
+                "The scale of decimal field '{}' cannot be changed",
+                field.name
+            )]));
+        }
+    }
+    Ok(())
+}
+
+/// Describes how `new` differs from `old` field by field; empty when they match.
+fn summarize_schema_changes(old: &CollectionSchema, new: &CollectionSchema) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    for field in &new.fields {
+        match old.fields.iter().find(|f| f.name == field.name) {
+            None => changes.push(format!("Added field '{}'", field.name)),
+            Some(previous) => {
+                let changed =
+                    serde_json::to_value(previous).ok() != serde_json::to_value(field).ok();
+                if changed && previous.same_structure(field) {
+                    changes.push(format!("Documented field '{}'", field.name));
+                } else if changed {
+                    changes.push(format!("Changed field '{}'", field.name));
+                }
+            }
+        }
+    }
+
+    for field in &old.fields {
+        if !new.fields.iter().any(|f| f.name == field.name) {
+            changes.push(format!("Removed field '{}'", field.name));
+        }
+    }
+
+    changes
+}
+
+/// Both schemas have the same fields, differing at most in descriptions and
+/// examples, so the records table can stay as it is.
+fn is_documentation_only_change(old: &CollectionSchema, new: &CollectionSchema) -> bool {
+    old.fields.len() == new.fields.len()
+        && new.fields.iter().all(|field| {
+            old.fields
+                .iter()
+                .any(|previous| previous.name == field.name && previous.same_structure(field))
+        })
+}
+
+type UserProjectionRow = (
+    i32,
+    String,
+    Option<String>,
+    chrono::NaiveDateTime,
+    chrono::NaiveDateTime,
+);
+
+fn user_projection_to_value(row: &UserProjectionRow) -> Value {
+    serde_json::json!({
+        "id": row.0.to_string(),
+        "username": row.1,
+        "avatar_url": row.2,
+    })
+}
+
+fn user_projection_to_record(row: UserProjectionRow) -> RecordResponse {
+    RecordResponse {
+        id: row.0.to_string(),
+        collection_id: USERS_SYSTEM_COLLECTION.to_string(),
+        data: serde_json::json!({
+            "username": row.1,
+            "avatar_url": row.2,
+        }),
+        created_at: row.3.format("%Y-%m-%d %H:%M:%S").to_string(),
+        updated_at: row.4.format("%Y-%m-%d %H:%M:%S").to_string(),
+    }
+}
+
+/// Relation fields are stored as text but may hold numeric ids.
+fn relation_id(value: &Value) -> Option<String> {
+    match value {
+        Value::Number(number) => number.as_i64().map(|id| id.to_string()),
+        Value::String(text) if !text.is_empty() => Some(text.clone()),
+        _ => None,
+    }
+}
+
+/// Columns every records table starts with.
+#[derive(Debug, diesel::QueryableByName)]
+struct RecordBaseRow {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    id: String,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    created_at: String,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    updated_at: String,
+}
+
+/// Whether `table_name` is named like the records table of a collection,
+/// in the default workspace (`records_*`) or another one (`ws<id>_records_*`).
+fn is_records_table_name(table_name: &str) -> bool {
+    if table_name.starts_with("records_") {
+        return true;
+    }
+    table_name
+        .strip_prefix("ws")
+        .and_then(|rest| rest.split_once("_records_"))
+        .is_some_and(|(workspace_id, _)| {
+            !workspace_id.is_empty() && workspace_id.chars().all(|c| c.is_ascii_digit())
+        })
+}
+
+/// Unix timestamp in the name of a temporary table created by
+/// `recreate_table_with_schema`, e.g. `records_posts_temp_1699999999`.
+fn temp_table_created_at(table_name: &str) -> Option<i64> {
+    let (_, timestamp) = table_name.rsplit_once("_temp_")?;
+    if timestamp.is_empty() || !timestamp.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    timestamp.parse().ok()
+}
+
+fn collection_workspace_id(conn: &mut SqliteConnection, collection_name: &str) -> Option<i32> {
+    collections::table
+        .filter(collections::name.eq(collection_name))
+        .select(collections::workspace_id)
+        .first(conn)
+        .ok()
+}
+
+/// Records table of `collection_name` for code without a [`CollectionService`].
+pub(crate) fn lookup_records_table_name(
+    conn: &mut SqliteConnection,
+    collection_name: &str,
+) -> String {
+    let workspace_id =
+        collection_workspace_id(conn, collection_name).unwrap_or(DEFAULT_WORKSPACE_ID);
+    records_table_name(workspace_id, collection_name)
+}
+
+/// A statement on the records table of `collection` that stays prepared on
+/// the connection. The schema version is part of the SQL text, so statements
+/// prepared before a schema change are never reused after it.
+fn record_statement(collection: &Collection, sql: String) -> PreparedSql {
+    PreparedSql::new(format!(
+        "/* schema v{} */ {}",
+        collection.schema_version, sql
+    ))
+}
+
+/// SQL literal for a record id. Ids are quoted so the same comparison works for
+/// integer and uuid primary keys.
+/// Digest over a manifest's record hashes in their listed order.
+fn manifest_digest(records: &[RecordHash]) -> String {
+    canonical_sha256(&serde_json::to_value(records).unwrap_or(Value::Null))
+}
+
+fn sql_record_id(record_id: &str) -> String {
+    format!("'{}'", record_id.replace('\'', "''"))
+}
+
+/// Keeps the cached record count in step with an insert or delete made on
+/// `conn`. Collections without a cached count are counted on their next read.
+fn adjust_record_count(
+    conn: &mut SqliteConnection,
+    collection_name: &str,
+    delta: i64,
+) -> Result<(), LunarbaseError> {
+    diesel::update(
+        collection_record_counts::table.filter(
+            collection_record_counts::collection_id.eq_any(
+                collections::table
+                    .filter(collections::name.eq(collection_name))
+                    .select(collections::id),
+            ),
+        ),
+    )
+    .set((
+        collection_record_counts::record_count.eq(collection_record_counts::record_count + delta),
+        collection_record_counts::updated_at.eq(diesel::dsl::now),
+    ))
+    .execute(conn)
+    .map_err(|_| LunarbaseError::InternalError)?;
+    Ok(())
+}
+
+fn primary_key_column_def(id_type: RecordIdType) -> &'static str {
+    match id_type {
+        RecordIdType::Integer => "id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL",
+        RecordIdType::Uuid => "id TEXT PRIMARY KEY NOT NULL",
+    }
+}
+
+/// Slugs are immutable once generated unless explicitly regenerated, so
+/// updates keep the values already stored for `current`.
+fn with_current_slugs(schema: &CollectionSchema, data: &Value, current: &RecordResponse) -> Value {
+    let mut data = data.clone();
+    if let Some(map) = data.as_object_mut() {
+        for field in &schema.fields {
+            if field.field_type != FieldType::Slug {
+                continue;
             }
+            match current.data.get(&field.name) {
+                Some(slug) => map.insert(field.name.clone(), slug.clone()),
+                None => map.remove(&field.name),
+            };
+        }
+    }
+    data
+}
+
+/// Checks the explicit permission set of a collection create request: every
+/// role must exist and appear once, and the admin role must keep full access.
+fn validate_initial_permissions(
+    conn: &mut SqliteConnection,
+    permissions: &[SetCollectionPermissionRequest],
+) -> Result<(), LunarbaseError> {
+    let role_names: Vec<String> = roles::table
+        .select(roles::name)
+        .load(conn)
+        .map_err(|_| LunarbaseError::InternalError)?;
+
+    let mut errors = Vec::new();
+    for (index, permission) in permissions.iter().enumerate() {
+        if !role_names.contains(&permission.role_name) {
+            errors.push(format!("Role '{}' does not exist", permission.role_name));
+        }
+        if permissions[..index]
+            .iter()
+            .any(|other| other.role_name == permission.role_name)
+        {
+            errors.push(format!(
+                "Role '{}' is listed more than once",
+                permission.role_name
+            ));
+        }
+        if permission.role_name == "admin" && PermissionSet::from(permission) != PermissionSet::FULL
+        {
+            errors.push("The admin role must keep full access to new collections".to_string());
         }
     }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(LunarbaseError::ValidationError(errors))
+    }
 }
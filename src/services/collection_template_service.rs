@@ -0,0 +1,512 @@
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use serde_json::{Value, json};
+
+use crate::models::{
+    CollectionTemplate, CollectionWorkflow, CreateCollectionRequest, CreateFromTemplateRequest,
+    CreateFromTemplateResponse, CreateRecordRequest, NewCollectionTemplate, RecordIdType,
+    SaveAsTemplateRequest, SetCollectionPermissionRequest, StoredCollectionTemplate,
+    TemplatePermission,
+};
+use crate::schema::collection_templates;
+use crate::services::{CollectionService, PermissionService};
+use crate::utils::LunarbaseError;
+
+type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+const MAX_TEMPLATE_ID_LENGTH: usize = 100;
+
+#[derive(Clone)]
+pub struct CollectionTemplateService {
+    pool: DbPool,
+    collection_service: CollectionService,
+    permission_service: PermissionService,
+}
+
+impl CollectionTemplateService {
+    pub fn new(
+        pool: DbPool,
+        collection_service: CollectionService,
+        permission_service: PermissionService,
+    ) -> Self {
+        Self {
+            pool,
+            collection_service,
+            permission_service,
+        }
+    }
+
+    /// Built-in templates first, then custom templates by name.
+    pub fn list_templates(&self) -> Result<Vec<CollectionTemplate>, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let stored: Vec<StoredCollectionTemplate> = collection_templates::table
+            .order(collection_templates::name.asc())
+            .select(StoredCollectionTemplate::as_select())
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let mut templates = builtin_templates();
+        for template in &stored {
+            templates.push(
+                template
+                    .to_template()
+                    .map_err(|_| LunarbaseError::InternalError)?,
+            );
+        }
+
+        Ok(templates)
+    }
+
+    pub fn get_template(&self, template_id: &str) -> Result<CollectionTemplate, LunarbaseError> {
+        if let Some(template) = builtin_templates()
+            .into_iter()
+            .find(|template| template.id == template_id)
+        {
+            return Ok(template);
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        collection_templates::table
+            .filter(collection_templates::template_id.eq(template_id))
+            .select(StoredCollectionTemplate::as_select())
+            .first(&mut conn)
+            .optional()
+            .map_err(|_| LunarbaseError::DatabaseError)?
+            .ok_or_else(|| LunarbaseError::NotFound("Template not found".to_string()))?
+            .to_template()
+            .map_err(|_| LunarbaseError::InternalError)
+    }
+
+    /// Creates a collection with the template's schema, then applies its role
+    /// permissions on top of the defaults. Roles that no longer exist, or
+    /// belong to another workspace, are skipped. Sample records are validated
+    /// before anything is created.
+    pub async fn create_from_template(
+        &self,
+        template_id: &str,
+        request: CreateFromTemplateRequest,
+        workspace_id: i32,
+        actor_id: Option<i32>,
+    ) -> Result<CreateFromTemplateResponse, LunarbaseError> {
+        let template = self.get_template(template_id)?;
+
+        if request.include_sample_records {
+            for record in &template.sample_records {
+                self.collection_service
+                    .validate_record_data(&template.schema, record)?;
+            }
+        }
+
+        let collection = self
+            .collection_service
+            .create_collection_in_workspace(
+                CreateCollectionRequest {
+                    name: request.name,
+                    display_name: request.display_name.or(Some(template.name.clone())),
+                    description: request.description.or(template.description.clone()),
+                    schema: template.schema.clone(),
+                    orderable: false,
+                    id_type: RecordIdType::default(),
+                    allow_explicit_ids: false,
+                    workflow: CollectionWorkflow::None,
+                    schedule: None,
+                    retention: None,
+                    permissions: None,
+                },
+                workspace_id,
+                actor_id,
+            )
+            .await?;
+
+        let mut permissions_applied = Vec::new();
+        for permission in &template.permissions {
+            let role = match self
+                .permission_service
+                .get_role_by_name(&permission.role_name)
+                .await
+            {
+                Ok(role) if role.workspace_id.is_none_or(|id| id == workspace_id) => role,
+                _ => {
+                    tracing::warn!(
+                        "Template '{}' grants permissions to missing role '{}'; skipping",
+                        template.id,
+                        permission.role_name
+                    );
+                    continue;
+                }
+            };
+
+            self.permission_service
+                .set_collection_permission(
+                    collection.id,
+                    role.id,
+                    &SetCollectionPermissionRequest {
+                        role_name: permission.role_name.clone(),
+                        can_create: permission.can_create,
+                        can_read: permission.can_read,
+                        can_update: permission.can_update,
+                        can_delete: permission.can_delete,
+                        can_list: permission.can_list,
+                    },
+                )
+                .await?;
+            permissions_applied.push(permission.role_name.clone());
+        }
+
+        let mut sample_records_created = 0;
+        if request.include_sample_records {
+            for record in template.sample_records {
+                self.collection_service
+                    .create_record_with_events(
+                        &collection.name,
+                        CreateRecordRequest {
+                            data: record,
+                            files: None,
+                        },
+                        actor_id,
+                    )
+                    .await?;
+                sample_records_created += 1;
+            }
+        }
+
+        Ok(CreateFromTemplateResponse {
+            collection,
+            template_id: template.id,
+            permissions_applied,
+            sample_records_created,
+        })
+    }
+
+    /// Stores an existing collection's schema and current role permissions as a
+    /// custom template.
+    pub async fn save_as_template(
+        &self,
+        collection_name: &str,
+        request: SaveAsTemplateRequest,
+        actor_id: Option<i32>,
+    ) -> Result<CollectionTemplate, LunarbaseError> {
+        let template_id = request.template_id.trim().to_string();
+        validate_template_id(&template_id)?;
+
+        if builtin_templates()
+            .iter()
+            .any(|template| template.id == template_id)
+        {
+            return Err(LunarbaseError::Conflict(format!(
+                "'{}' is a built-in template",
+                template_id
+            )));
+        }
+
+        let collection = self
+            .collection_service
+            .get_collection(collection_name)
+            .await?;
+
+        for record in &request.sample_records {
+            self.collection_service
+                .validate_record_data(&collection.schema, record)?;
+        }
+
+        let mut permissions = Vec::new();
+        for role in self.permission_service.list_roles().await? {
+            if let Some(permission) = self
+                .permission_service
+                .get_role_collection_permission(&role.name, collection.id)
+                .await?
+            {
+                permissions.push(TemplatePermission {
+                    role_name: role.name,
+                    can_create: permission.can_create,
+                    can_read: permission.can_read,
+                    can_update: permission.can_update,
+                    can_delete: permission.can_delete,
+                    can_list: permission.can_list,
+                });
+            }
+        }
+
+        let name = request
+            .name
+            .filter(|name| !name.trim().is_empty())
+            .or(collection.display_name.clone())
+            .unwrap_or_else(|| collection.name.clone());
+
+        let new_template = NewCollectionTemplate {
+            template_id: template_id.clone(),
+            name,
+            description: request.description.or(collection.description),
+            schema_json: serde_json::to_string(&collection.schema)
+                .map_err(|_| LunarbaseError::InternalError)?,
+            permissions_json: serde_json::to_string(&permissions)
+                .map_err(|_| LunarbaseError::InternalError)?,
+            sample_records_json: serde_json::to_string(&request.sample_records)
+                .map_err(|_| LunarbaseError::InternalError)?,
+            created_by: actor_id,
+        };
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        diesel::insert_into(collection_templates::table)
+            .values(&new_template)
+            .execute(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) => LunarbaseError::Conflict(format!(
+                    "A template with id '{}' already exists",
+                    template_id
+                )),
+                _ => LunarbaseError::DatabaseError,
+            })?;
+
+        self.get_template(&template_id)
+    }
+
+    pub fn delete_template(&self, template_id: &str) -> Result<(), LunarbaseError> {
+        if builtin_templates()
+            .iter()
+            .any(|template| template.id == template_id)
+        {
+            return Err(LunarbaseError::BadRequest(
+                "Built-in templates cannot be deleted".to_string(),
+            ));
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let deleted = diesel::delete(
+            collection_templates::table.filter(collection_templates::template_id.eq(template_id)),
+        )
+        .execute(&mut conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        if deleted == 0 {
+            return Err(LunarbaseError::NotFound("Template not found".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_template_id(template_id: &str) -> Result<(), LunarbaseError> {
+    if template_id.is_empty() || template_id.len() > MAX_TEMPLATE_ID_LENGTH {
+        return Err(LunarbaseError::ValidationError(vec![format!(
+            "Template id must be between 1 and {} characters",
+            MAX_TEMPLATE_ID_LENGTH
+        )]));
+    }
+
+    if !template_id
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    {
+        return Err(LunarbaseError::ValidationError(vec![
+            "Template id may only contain lowercase letters, digits, '_' and '-'".to_string(),
+        ]));
+    }
+
+    Ok(())
+}
+
+fn permission(
+    role_name: &str,
+    create: bool,
+    read: bool,
+    update: bool,
+    delete: bool,
+    list: bool,
+) -> Value {
+    json!({
+        "role_name": role_name,
+        "can_create": create,
+        "can_read": read,
+        "can_update": update,
+        "can_delete": delete,
+        "can_list": list,
+    })
+}
+
+fn builtin_template(
+    id: &str,
+    name: &str,
+    description: &str,
+    fields: Value,
+    permissions: Vec<Value>,
+    sample_records: Vec<Value>,
+) -> CollectionTemplate {
+    CollectionTemplate {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        built_in: true,
+        schema: serde_json::from_value(json!({ "fields": fields }))
+            .expect("built-in template schema is valid"),
+        permissions: serde_json::from_value(Value::Array(permissions))
+            .expect("built-in template permissions are valid"),
+        sample_records,
+        created_at: None,
+    }
+}
+
+fn builtin_templates() -> Vec<CollectionTemplate> {
+    vec![
+        builtin_template(
+            "blog_posts",
+            "Blog posts",
+            "Articles with a slug, rich text body, tags and publishing state",
+            json!([
+                {"name": "title", "field_type": "text", "required": true,
+                 "validation": {"min_length": 1, "max_length": 200}},
+                {"name": "slug", "field_type": "text", "required": true,
+                 "validation": {"pattern": "^[a-z0-9]+(?:-[a-z0-9]+)*$"}},
+                {"name": "excerpt", "field_type": "text", "required": false,
+                 "validation": {"max_length": 500}},
+                {"name": "content", "field_type": "richtext", "required": true},
+                {"name": "cover_image", "field_type": "file", "required": false},
+                {"name": "tags", "field_type": "json", "required": false, "default_value": []},
+                {"name": "status", "field_type": "text", "required": false, "default_value": "draft",
+                 "validation": {"enum_values": ["draft", "published", "archived"]}},
+                {"name": "published_at", "field_type": "date", "required": false},
+            ]),
+            vec![
+                permission("user", true, true, true, false, true),
+                permission("guest", false, true, false, false, true),
+            ],
+            vec![
+                json!({
+                    "title": "Hello world",
+                    "slug": "hello-world",
+                    "excerpt": "The first post on this blog.",
+                    "content": "<p>Welcome to your new blog.</p>",
+                    "tags": ["welcome"],
+                    "status": "published",
+                    "published_at": "2024-01-01",
+                }),
+                json!({
+                    "title": "Draft ideas",
+                    "slug": "draft-ideas",
+                    "content": "<p>Work in progress.</p>",
+                    "status": "draft",
+                }),
+            ],
+        ),
+        builtin_template(
+            "products",
+            "Products",
+            "Catalog items with pricing, stock and an active flag",
+            json!([
+                {"name": "name", "field_type": "text", "required": true,
+                 "validation": {"min_length": 1, "max_length": 200}},
+                {"name": "sku", "field_type": "text", "required": true,
+                 "validation": {"pattern": "^[A-Z0-9-]+$"}},
+                {"name": "description", "field_type": "richtext", "required": false},
+                {"name": "price", "field_type": "number", "required": true,
+                 "validation": {"min_value": 0.0}},
+                {"name": "stock", "field_type": "number", "required": false, "default_value": 0,
+                 "validation": {"min_value": 0.0}},
+                {"name": "image", "field_type": "file", "required": false},
+                {"name": "is_active", "field_type": "boolean", "required": false, "default_value": true},
+            ]),
+            vec![
+                permission("user", false, true, false, false, true),
+                permission("guest", false, true, false, false, true),
+            ],
+            vec![
+                json!({"name": "Coffee mug", "sku": "MUG-001", "price": 12.5, "stock": 40, "is_active": true}),
+                json!({"name": "T-shirt", "sku": "TEE-001", "price": 20.0, "stock": 0, "is_active": false}),
+            ],
+        ),
+        builtin_template(
+            "contacts",
+            "Contacts",
+            "People with contact details, company and free-form notes",
+            json!([
+                {"name": "first_name", "field_type": "text", "required": true,
+                 "validation": {"min_length": 1, "max_length": 100}},
+                {"name": "last_name", "field_type": "text", "required": true,
+                 "validation": {"min_length": 1, "max_length": 100}},
+                {"name": "email", "field_type": "email", "required": true},
+                {"name": "phone", "field_type": "text", "required": false,
+                 "validation": {"pattern": "^\\+?[0-9 ()-]{5,20}$"}},
+                {"name": "company", "field_type": "text", "required": false},
+                {"name": "website", "field_type": "url", "required": false},
+                {"name": "notes", "field_type": "richtext", "required": false},
+            ]),
+            vec![
+                permission("user", true, true, true, true, true),
+                permission("guest", false, false, false, false, false),
+            ],
+            vec![json!({
+                "first_name": "Ada",
+                "last_name": "Lovelace",
+                "email": "ada@example.com",
+                "company": "Analytical Engines",
+                "website": "https://example.com",
+            })],
+        ),
+        builtin_template(
+            "events",
+            "Events",
+            "Scheduled events with dates, location, capacity and a registration link",
+            json!([
+                {"name": "title", "field_type": "text", "required": true,
+                 "validation": {"min_length": 1, "max_length": 200}},
+                {"name": "description", "field_type": "richtext", "required": false},
+                {"name": "starts_on", "field_type": "date", "required": true},
+                {"name": "ends_on", "field_type": "date", "required": false},
+                {"name": "location", "field_type": "text", "required": false},
+                {"name": "capacity", "field_type": "number", "required": false,
+                 "validation": {"min_value": 1.0}},
+                {"name": "registration_url", "field_type": "url", "required": false},
+                {"name": "is_public", "field_type": "boolean", "required": false, "default_value": true},
+            ]),
+            vec![
+                permission("user", false, true, false, false, true),
+                permission("guest", false, true, false, false, true),
+            ],
+            vec![json!({
+                "title": "Launch meetup",
+                "starts_on": "2024-06-01",
+                "ends_on": "2024-06-01",
+                "location": "Online",
+                "capacity": 100,
+                "is_public": true,
+            })],
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_templates_have_unique_valid_ids() {
+        let templates = builtin_templates();
+        let mut ids: Vec<&str> = templates.iter().map(|t| t.id.as_str()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        assert_eq!(ids.len(), templates.len());
+        assert!(ids.iter().all(|id| validate_template_id(id).is_ok()));
+        assert!(
+            templates
+                .iter()
+                .all(|t| t.built_in && !t.schema.fields.is_empty())
+        );
+    }
+
+    #[test]
+    fn test_template_id_validation() {
+        assert!(validate_template_id("support_tickets-v2").is_ok());
+        assert!(validate_template_id("").is_err());
+        assert!(validate_template_id("Tickets").is_err());
+        assert!(validate_template_id("with space").is_err());
+        assert!(validate_template_id(&"a".repeat(MAX_TEMPLATE_ID_LENGTH + 1)).is_err());
+    }
+}
@@ -0,0 +1,418 @@
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+
+use crate::models::{
+    CollectionSchema, CollectionView, CollectionViewResponse, CreateCollectionViewRequest,
+    NewCollectionView, UpdateCollectionViewRequest, User, ViewVisibility,
+};
+use crate::query_engine::QueryEngine;
+use crate::schema::{collection_views, roles};
+use crate::services::CollectionService;
+use crate::utils::LunarbaseError;
+
+type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+const SYSTEM_FIELDS: [&str; 3] = ["id", "created_at", "updated_at"];
+
+#[derive(Clone)]
+pub struct CollectionViewService {
+    pub pool: DbPool,
+    collection_service: CollectionService,
+}
+
+impl CollectionViewService {
+    pub fn new(pool: DbPool, collection_service: CollectionService) -> Self {
+        Self {
+            pool,
+            collection_service,
+        }
+    }
+
+    pub async fn create_view(
+        &self,
+        collection_name: &str,
+        request: CreateCollectionViewRequest,
+        user: &User,
+    ) -> Result<CollectionViewResponse, LunarbaseError> {
+        let collection = self
+            .collection_service
+            .get_collection(collection_name)
+            .await?;
+
+        let name = request.name.trim().to_string();
+        let filter = non_empty(request.filter);
+        let sort = non_empty(request.sort);
+        let fields = request.fields.unwrap_or_default();
+        let visibility = request.visibility.unwrap_or(ViewVisibility::Private);
+        let role_name = non_empty(request.role_name);
+
+        validate_view_name(&name)?;
+        let unknown = unknown_view_fields(
+            filter.as_deref(),
+            sort.as_deref(),
+            &fields,
+            &collection.schema,
+        )?;
+        if !unknown.is_empty() {
+            return Err(LunarbaseError::ValidationError(vec![format!(
+                "Unknown fields in collection '{}': {}",
+                collection_name,
+                unknown.join(", ")
+            )]));
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let role_name = self.check_visibility(&mut conn, visibility, role_name, user)?;
+
+        let new_view = NewCollectionView {
+            collection_id: collection.id,
+            name: name.clone(),
+            filter,
+            sort,
+            fields: encode_fields(&fields)?,
+            visibility: visibility.as_str().to_string(),
+            role_name,
+            created_by: user.id,
+        };
+
+        diesel::insert_into(collection_views::table)
+            .values(&new_view)
+            .execute(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) => LunarbaseError::Conflict(format!(
+                    "A view named '{}' already exists for this collection",
+                    name
+                )),
+                _ => LunarbaseError::DatabaseError,
+            })?;
+
+        let view = self.find_view(&mut conn, collection.id, &name)?;
+        Ok(view.to_response(collection_name))
+    }
+
+    pub async fn list_views(
+        &self,
+        collection_name: &str,
+        user: &User,
+    ) -> Result<Vec<CollectionViewResponse>, LunarbaseError> {
+        let collection = self
+            .collection_service
+            .get_collection(collection_name)
+            .await?;
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let views = collection_views::table
+            .filter(collection_views::collection_id.eq(collection.id))
+            .order(collection_views::name.asc())
+            .select(CollectionView::as_select())
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(views
+            .iter()
+            .filter(|view| view.is_visible_to(Some(user)))
+            .map(|view| view.to_response(collection_name))
+            .collect())
+    }
+
+    pub async fn get_view(
+        &self,
+        collection_name: &str,
+        view_name: &str,
+        user: Option<&User>,
+    ) -> Result<CollectionView, LunarbaseError> {
+        let collection = self
+            .collection_service
+            .get_collection(collection_name)
+            .await?;
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let view = self.find_view(&mut conn, collection.id, view_name)?;
+
+        // Views the caller cannot see are reported as missing so their names don't leak.
+        if !view.is_visible_to(user) {
+            return Err(view_not_found(view_name));
+        }
+
+        Ok(view)
+    }
+
+    /// Loads a view for use in a record listing, checking that everything it
+    /// references still exists in the collection schema.
+    pub async fn resolve_view(
+        &self,
+        collection_name: &str,
+        view_name: &str,
+        user: Option<&User>,
+    ) -> Result<CollectionView, LunarbaseError> {
+        let view = self.get_view(collection_name, view_name, user).await?;
+        let collection = self
+            .collection_service
+            .get_collection(collection_name)
+            .await?;
+
+        let missing = unknown_view_fields(
+            view.filter.as_deref(),
+            view.sort.as_deref(),
+            &view.get_fields(),
+            &collection.schema,
+        )?;
+        if !missing.is_empty() {
+            tracing::warn!(
+                "View '{}' on collection '{}' references removed fields: {:?}",
+                view.name,
+                collection_name,
+                missing
+            );
+            return Err(LunarbaseError::BadRequest(format!(
+                "View '{}' references fields that no longer exist in collection '{}': {}",
+                view.name,
+                collection_name,
+                missing.join(", ")
+            )));
+        }
+
+        Ok(view)
+    }
+
+    pub async fn update_view(
+        &self,
+        collection_name: &str,
+        view_name: &str,
+        request: UpdateCollectionViewRequest,
+        user: &User,
+    ) -> Result<CollectionViewResponse, LunarbaseError> {
+        let view = self
+            .get_view(collection_name, view_name, Some(user))
+            .await?;
+        if !view.can_be_modified_by(user) {
+            return Err(LunarbaseError::InsufficientPermissions);
+        }
+
+        let collection = self
+            .collection_service
+            .get_collection(collection_name)
+            .await?;
+
+        let name = request
+            .name
+            .map(|name| name.trim().to_string())
+            .unwrap_or_else(|| view.name.clone());
+        let filter = match request.filter {
+            Some(filter) => non_empty(Some(filter)),
+            None => view.filter.clone(),
+        };
+        let sort = match request.sort {
+            Some(sort) => non_empty(Some(sort)),
+            None => view.sort.clone(),
+        };
+        let fields = request.fields.unwrap_or_else(|| view.get_fields());
+        let visibility = request.visibility.unwrap_or_else(|| view.get_visibility());
+        let role_name = match request.role_name {
+            Some(role_name) => non_empty(Some(role_name)),
+            None => view.role_name.clone(),
+        };
+
+        validate_view_name(&name)?;
+        let unknown = unknown_view_fields(
+            filter.as_deref(),
+            sort.as_deref(),
+            &fields,
+            &collection.schema,
+        )?;
+        if !unknown.is_empty() {
+            return Err(LunarbaseError::ValidationError(vec![format!(
+                "Unknown fields in collection '{}': {}",
+                collection_name,
+                unknown.join(", ")
+            )]));
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let role_name = self.check_visibility(&mut conn, visibility, role_name, user)?;
+
+        diesel::update(collection_views::table.find(view.id))
+            .set((
+                collection_views::name.eq(&name),
+                collection_views::filter.eq(&filter),
+                collection_views::sort.eq(&sort),
+                collection_views::fields.eq(encode_fields(&fields)?),
+                collection_views::visibility.eq(visibility.as_str()),
+                collection_views::role_name.eq(&role_name),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) => LunarbaseError::Conflict(format!(
+                    "A view named '{}' already exists for this collection",
+                    name
+                )),
+                _ => LunarbaseError::DatabaseError,
+            })?;
+
+        let view = self.find_view(&mut conn, collection.id, &name)?;
+        Ok(view.to_response(collection_name))
+    }
+
+    pub async fn delete_view(
+        &self,
+        collection_name: &str,
+        view_name: &str,
+        user: &User,
+    ) -> Result<(), LunarbaseError> {
+        let view = self
+            .get_view(collection_name, view_name, Some(user))
+            .await?;
+        if !view.can_be_modified_by(user) {
+            return Err(LunarbaseError::InsufficientPermissions);
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        diesel::delete(collection_views::table.find(view.id))
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(())
+    }
+
+    fn find_view(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_id: i32,
+        view_name: &str,
+    ) -> Result<CollectionView, LunarbaseError> {
+        collection_views::table
+            .filter(collection_views::collection_id.eq(collection_id))
+            .filter(collection_views::name.eq(view_name))
+            .select(CollectionView::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|_| LunarbaseError::DatabaseError)?
+            .ok_or_else(|| view_not_found(view_name))
+    }
+
+    /// Non-admins may share views with their own role but only admins can publish them.
+    fn check_visibility(
+        &self,
+        conn: &mut SqliteConnection,
+        visibility: ViewVisibility,
+        role_name: Option<String>,
+        user: &User,
+    ) -> Result<Option<String>, LunarbaseError> {
+        match visibility {
+            ViewVisibility::Private => Ok(None),
+            ViewVisibility::Public => {
+                if user.role != "admin" {
+                    return Err(LunarbaseError::InsufficientPermissions);
+                }
+                Ok(None)
+            }
+            ViewVisibility::Role => {
+                let role_name = role_name.ok_or_else(|| {
+                    LunarbaseError::ValidationError(vec![
+                        "role_name is required when visibility is 'role'".to_string(),
+                    ])
+                })?;
+
+                if user.role != "admin" && user.role != role_name {
+                    return Err(LunarbaseError::InsufficientPermissions);
+                }
+
+                let role_exists = roles::table
+                    .filter(roles::name.eq(&role_name))
+                    .count()
+                    .get_result::<i64>(conn)
+                    .map_err(|_| LunarbaseError::DatabaseError)?
+                    > 0;
+
+                if !role_exists {
+                    return Err(LunarbaseError::ValidationError(vec![format!(
+                        "Role '{}' does not exist",
+                        role_name
+                    )]));
+                }
+
+                Ok(Some(role_name))
+            }
+        }
+    }
+}
+
+/// Parses the filter and sort expressions and returns every field they
+/// reference, along with the selected fields, that is missing from `schema`.
+pub fn unknown_view_fields(
+    filter: Option<&str>,
+    sort: Option<&str>,
+    fields: &[String],
+    schema: &CollectionSchema,
+) -> Result<Vec<String>, LunarbaseError> {
+    let query_engine = QueryEngine::new(
+        sort.map(str::to_string),
+        filter.map(str::to_string),
+        None,
+        None,
+        None,
+    )?;
+
+    let mut unknown: Vec<String> = Vec::new();
+    let referenced = query_engine
+        .referenced_fields()
+        .into_iter()
+        .chain(fields.iter().map(String::as_str));
+
+    for field in referenced {
+        let exists =
+            SYSTEM_FIELDS.contains(&field) || schema.fields.iter().any(|f| f.name == field);
+        if !exists && !unknown.iter().any(|name| name == field) {
+            unknown.push(field.to_string());
+        }
+    }
+
+    Ok(unknown)
+}
+
+fn validate_view_name(name: &str) -> Result<(), LunarbaseError> {
+    let is_valid = !name.is_empty()
+        && name.len() <= 100
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+    if !is_valid {
+        return Err(LunarbaseError::ValidationError(vec![
+            "View name must be 1-100 characters of letters, numbers, underscores or hyphens"
+                .to_string(),
+        ]));
+    }
+
+    Ok(())
+}
+
+fn encode_fields(fields: &[String]) -> Result<Option<String>, LunarbaseError> {
+    if fields.is_empty() {
+        return Ok(None);
+    }
+
+    serde_json::to_string(fields)
+        .map(Some)
+        .map_err(|_| LunarbaseError::InternalError)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn view_not_found(view_name: &str) -> LunarbaseError {
+    LunarbaseError::NotFound(format!("View '{}' not found", view_name))
+}
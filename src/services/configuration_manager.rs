@@ -1,11 +1,13 @@
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, watch};
 use tracing::{debug, error, warn};
 
+use crate::models::DefaultCollectionPermissions;
 use crate::models::system_setting::SystemSetting;
 use crate::schema::system_settings;
 use crate::utils::LunarbaseError;
@@ -16,16 +18,30 @@ type DbPool = Pool<ConnectionManager<SqliteConnection>>;
 pub struct ConfigurationManager {
     pool: DbPool,
     cache: Arc<RwLock<HashMap<String, String>>>,
+    reloads: Arc<watch::Sender<u64>>,
 }
 
 impl ConfigurationManager {
     pub fn new(pool: DbPool) -> Self {
+        let (reloads, _) = watch::channel(0);
         Self {
             pool,
             cache: Arc::new(RwLock::new(HashMap::new())),
+            reloads: Arc::new(reloads),
         }
     }
 
+    /// Notified after every cache reload, so components that derive state from
+    /// settings (such as the CORS policy) can rebuild it without a restart.
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.reloads.subscribe()
+    }
+
+    /// Number of cache reloads so far.
+    pub fn generation(&self) -> u64 {
+        *self.reloads.borrow()
+    }
+
     pub async fn initialize(&self) -> Result<(), LunarbaseError> {
         debug!("Initializing configuration manager...");
         self.reload_cache().await?;
@@ -53,6 +69,9 @@ impl ConfigurationManager {
         }
 
         debug!("Loaded {} settings into cache", cache.len());
+        drop(cache);
+
+        self.reloads.send_modify(|generation| *generation += 1);
         Ok(())
     }
 
@@ -112,6 +131,22 @@ impl ConfigurationManager {
             .ok()
     }
 
+    pub async fn get_deserialized<T: DeserializeOwned>(
+        &self,
+        category: &str,
+        key: &str,
+    ) -> Option<T> {
+        serde_json::from_value(self.get_json(category, key).await?)
+            .map_err(|e| {
+                warn!(
+                    "Setting {}:{} does not have the expected shape: {}",
+                    category, key, e
+                );
+                e
+            })
+            .ok()
+    }
+
     pub async fn get_string_array(&self, category: &str, key: &str) -> Option<Vec<String>> {
         let json_value = self.get_json(category, key).await?;
         if let Value::Array(arr) = json_value {
@@ -240,129 +275,965 @@ pub trait ConfigurationAccess: Sync {
         }
     }
 
-    fn get_rate_limit_requests_per_minute(&self) -> impl std::future::Future<Output = i32> + Send {
+    fn get_password_hash_memory_kib(&self) -> impl std::future::Future<Output = u32> + Send {
         async {
             self.config_manager()
-                .get_i32_or_default("api", "rate_limit_requests_per_minute", 100)
+                .get_u32_or_default("auth", "password_hash_memory_kib", 65536)
                 .await
         }
     }
 
-    fn get_cors_allowed_origins(&self) -> impl std::future::Future<Output = Vec<String>> + Send {
+    fn get_password_hash_iterations(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("auth", "password_hash_iterations", 4)
+                .await
+        }
+    }
+
+    fn get_password_hash_parallelism(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("auth", "password_hash_parallelism", 2)
+                .await
+        }
+    }
+
+    fn get_email_mx_check_enabled(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("auth", "email_mx_check_enabled", false)
+                .await
+        }
+    }
+
+    fn get_email_mx_resolver_url(&self) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default(
+                    "auth",
+                    "email_mx_resolver_url",
+                    "https://cloudflare-dns.com/dns-query",
+                )
+                .await
+        }
+    }
+
+    fn get_login_identifier(&self) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default("auth", "login_identifier", "email")
+                .await
+        }
+    }
+
+    fn get_reserved_usernames(&self) -> impl std::future::Future<Output = Vec<String>> + Send {
         async {
             self.config_manager()
                 .get_string_array_or_default(
-                    "api",
-                    "cors_allowed_origins",
-                    vec![
-                        "http://localhost:3000".to_string(),
-                        "http://localhost:5173".to_string(),
-                    ],
+                    "auth",
+                    "reserved_usernames",
+                    ["admin", "administrator", "root", "system", "support"]
+                        .map(String::from)
+                        .to_vec(),
                 )
                 .await
         }
     }
 
-    fn get_connection_pool_size(&self) -> impl std::future::Future<Output = u32> + Send {
+    fn get_progressive_lockout_enabled(&self) -> impl std::future::Future<Output = bool> + Send {
         async {
             self.config_manager()
-                .get_u32_or_default("database", "connection_pool_size", 10)
+                .get_bool_or_default("auth", "progressive_lockout_enabled", false)
                 .await
         }
     }
 
-    fn get_backup_enabled(&self) -> impl std::future::Future<Output = bool> + Send {
+    fn get_max_lockout_duration_minutes(&self) -> impl std::future::Future<Output = i32> + Send {
         async {
             self.config_manager()
-                .get_bool_or_default("database", "backup_enabled", false)
+                .get_i32_or_default("auth", "max_lockout_duration_minutes", 1440)
                 .await
         }
     }
 
-    fn get_backup_retention_days(&self) -> impl std::future::Future<Output = u32> + Send {
+    fn get_lockout_exempt_admins(&self) -> impl std::future::Future<Output = Vec<String>> + Send {
         async {
             self.config_manager()
-                .get_u32_or_default("database", "backup_retention_days", 30)
+                .get_string_array_or_default("auth", "lockout_exempt_admins", vec![])
                 .await
         }
     }
 
-    fn get_backup_schedule(&self) -> impl std::future::Future<Output = String> + Send {
+    fn get_expired_lock_cleanup_interval_seconds(
+        &self,
+    ) -> impl std::future::Future<Output = u32> + Send {
         async {
             self.config_manager()
-                .get_string_or_default("database", "backup_schedule", "0 0 2 * * *")
+                .get_u32_or_default("auth", "expired_lock_cleanup_interval_seconds", 60)
                 .await
         }
     }
 
-    fn get_backup_compression(&self) -> impl std::future::Future<Output = bool> + Send {
+    fn get_record_schedule_interval_seconds(
+        &self,
+    ) -> impl std::future::Future<Output = u32> + Send {
         async {
             self.config_manager()
-                .get_bool_or_default("database", "backup_compression", true)
+                .get_u32_or_default("database", "record_schedule_interval_seconds", 60)
                 .await
         }
     }
 
-    fn get_backup_prefix(&self) -> impl std::future::Future<Output = String> + Send {
+    fn get_record_count_reconcile_interval_seconds(
+        &self,
+    ) -> impl std::future::Future<Output = u32> + Send {
         async {
             self.config_manager()
-                .get_string_or_default("database", "backup_prefix", "lunarbase-backup")
+                .get_u32_or_default("database", "record_count_reconcile_interval_seconds", 3600)
                 .await
         }
     }
 
-    fn get_backup_min_size_bytes(&self) -> impl std::future::Future<Output = u64> + Send {
+    fn get_cleanup_orphans_on_start(&self) -> impl std::future::Future<Output = bool> + Send {
         async {
             self.config_manager()
-                .get_u32_or_default("database", "backup_min_size_bytes", 1024)
-                .await as u64
+                .get_bool_or_default("database", "cleanup_orphans_on_start", false)
+                .await
         }
     }
 
-    fn get_compression_enabled(&self) -> impl std::future::Future<Output = bool> + Send {
+    fn get_retention_interval_seconds(&self) -> impl std::future::Future<Output = u32> + Send {
         async {
             self.config_manager()
-                .get_bool_or_default("web_server", "compression_enabled", true)
+                .get_u32_or_default("database", "retention_interval_seconds", 3600)
                 .await
         }
     }
 
-    fn get_compression_level(&self) -> impl std::future::Future<Output = u8> + Send {
+    fn get_retention_batch_size(&self) -> impl std::future::Future<Output = u32> + Send {
         async {
             self.config_manager()
-                .get_u32_or_default("web_server", "compression_level", 6)
-                .await as u8
+                .get_u32_or_default("database", "retention_batch_size", 500)
+                .await
         }
     }
 
-    fn get_compression_min_size(&self) -> impl std::future::Future<Output = usize> + Send {
+    fn get_record_deletion_alert_threshold(&self) -> impl std::future::Future<Output = u32> + Send {
         async {
             self.config_manager()
-                .get_u32_or_default("web_server", "compression_min_size", 1024)
-                .await as usize
+                .get_u32_or_default("database", "record_deletion_alert_threshold", 0)
+                .await
         }
     }
 
-    fn get_compression_gzip(&self) -> impl std::future::Future<Output = bool> + Send {
+    fn get_record_deletion_alert_email(&self) -> impl std::future::Future<Output = String> + Send {
         async {
             self.config_manager()
-                .get_bool_or_default("web_server", "compression_gzip", true)
+                .get_string_or_default("database", "record_deletion_alert_email", "")
                 .await
         }
     }
 
-    fn get_compression_brotli(&self) -> impl std::future::Future<Output = bool> + Send {
+    fn get_record_deletion_alert_webhook_url(
+        &self,
+    ) -> impl std::future::Future<Output = String> + Send {
         async {
             self.config_manager()
-                .get_bool_or_default("web_server", "compression_brotli", true)
+                .get_string_or_default("database", "record_deletion_alert_webhook_url", "")
                 .await
         }
     }
 
-    fn get_compression_deflate(&self) -> impl std::future::Future<Output = bool> + Send {
+    fn get_quota_warning_percent(&self) -> impl std::future::Future<Output = u32> + Send {
         async {
             self.config_manager()
-                .get_bool_or_default("web_server", "compression_deflate", true)
+                .get_u32_or_default("system", "quota_warning_percent", 80)
+                .await
+        }
+    }
+
+    fn get_quota_warning_email(&self) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default("system", "quota_warning_email", "")
+                .await
+        }
+    }
+
+    fn get_default_collection_permissions(
+        &self,
+    ) -> impl std::future::Future<Output = DefaultCollectionPermissions> + Send {
+        async {
+            let manager = self.config_manager();
+            let defaults = DefaultCollectionPermissions::default();
+            DefaultCollectionPermissions {
+                admin: manager
+                    .get_deserialized("system", "default_permissions_admin")
+                    .await
+                    .unwrap_or(defaults.admin),
+                user: manager
+                    .get_deserialized("system", "default_permissions_user")
+                    .await
+                    .unwrap_or(defaults.user),
+                guest: manager
+                    .get_deserialized("system", "default_permissions_guest")
+                    .await
+                    .unwrap_or(defaults.guest),
+                custom: manager
+                    .get_deserialized("system", "default_permissions_custom")
+                    .await
+                    .unwrap_or(defaults.custom),
+            }
+        }
+    }
+
+    fn get_permission_inheritance_enabled(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("system", "permission_inheritance_enabled", false)
+                .await
+        }
+    }
+
+    fn get_rate_limit_requests_per_minute(&self) -> impl std::future::Future<Output = i32> + Send {
+        async {
+            self.config_manager()
+                .get_i32_or_default("api", "rate_limit_requests_per_minute", 100)
+                .await
+        }
+    }
+
+    fn get_batch_max_operations(&self) -> impl std::future::Future<Output = i32> + Send {
+        async {
+            self.config_manager()
+                .get_i32_or_default("api", "batch_max_operations", 50)
+                .await
+        }
+    }
+
+    fn get_max_query_complexity(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "max_query_complexity", 50)
+                .await
+        }
+    }
+
+    fn get_max_concurrent_queries_per_user(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "max_concurrent_queries_per_user", 3)
+                .await
+        }
+    }
+
+    fn get_search_collection_timeout_ms(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "search_collection_timeout_ms", 1000)
+                .await
+        }
+    }
+
+    fn get_health_check_cache_seconds(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "health_check_cache_seconds", 30)
+                .await
+        }
+    }
+
+    fn get_health_check_timeout_ms(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "health_check_timeout_ms", 3000)
+                .await
+        }
+    }
+
+    fn get_health_check_s3(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("api", "health_check_s3", true)
+                .await
+        }
+    }
+
+    fn get_health_check_email(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("api", "health_check_email", true)
+                .await
+        }
+    }
+
+    fn get_health_check_oauth(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("api", "health_check_oauth", true)
+                .await
+        }
+    }
+
+    fn get_health_check_backup(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("api", "health_check_backup", true)
+                .await
+        }
+    }
+
+    fn get_health_check_collections(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("api", "health_check_collections", true)
+                .await
+        }
+    }
+
+    fn get_health_check_collections_cache_seconds(
+        &self,
+    ) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "health_check_collections_cache_seconds", 300)
+                .await
+        }
+    }
+
+    fn get_health_history_persist(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("api", "health_history_persist", false)
+                .await
+        }
+    }
+
+    fn get_record_cache_enabled(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("database", "record_cache_enabled", true)
+                .await
+        }
+    }
+
+    fn get_record_cache_max_entries(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("database", "record_cache_max_entries", 1000)
+                .await
+        }
+    }
+
+    fn get_record_cache_ttl_seconds(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("database", "record_cache_ttl_seconds", 30)
+                .await
+        }
+    }
+
+    fn get_query_cache_enabled(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("database", "query_cache_enabled", true)
+                .await
+        }
+    }
+
+    fn get_query_cache_max_entries(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("database", "query_cache_max_entries", 500)
+                .await
+        }
+    }
+
+    fn get_permission_cache_ttl_seconds(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("database", "permission_cache_ttl_seconds", 60)
+                .await
+        }
+    }
+
+    fn get_auth_body_limit_kb(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("limits", "auth_body_kb", 64)
+                .await
+        }
+    }
+
+    fn get_record_json_limit_mb(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("limits", "record_json_mb", 10)
+                .await
+        }
+    }
+
+    fn get_file_upload_limit_mb(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("limits", "file_upload_mb", 50)
+                .await
+        }
+    }
+
+    fn get_upload_scan_mode(&self) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default("storage", "upload_scan_mode", "off")
+                .await
+        }
+    }
+
+    fn get_upload_scan_clamav_address(&self) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default("storage", "upload_scan_clamav_address", "127.0.0.1:3310")
+                .await
+        }
+    }
+
+    fn get_upload_scan_command(&self) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default("storage", "upload_scan_command", "")
+                .await
+        }
+    }
+
+    fn get_upload_scan_timeout_seconds(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("storage", "upload_scan_timeout_seconds", 30)
+                .await
+        }
+    }
+
+    fn get_upload_scan_fail_open(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("storage", "upload_scan_fail_open", false)
+                .await
+        }
+    }
+
+    fn get_cookie_same_site(&self) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default("auth", "cookie_same_site", "Lax")
+                .await
+        }
+    }
+
+    fn get_cookie_domain(&self) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default("auth", "cookie_domain", "")
+                .await
+        }
+    }
+
+    fn get_permission_denial_alert_threshold(
+        &self,
+    ) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("auth", "permission_denial_alert_threshold", 0)
+                .await
+        }
+    }
+
+    fn get_permission_denial_alert_window_seconds(
+        &self,
+    ) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("auth", "permission_denial_alert_window_seconds", 60)
+                .await
+        }
+    }
+
+    fn get_permission_denial_alert_email(
+        &self,
+    ) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default("auth", "permission_denial_alert_email", "")
+                .await
+        }
+    }
+
+    fn get_permission_denial_alert_webhook_url(
+        &self,
+    ) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default("auth", "permission_denial_alert_webhook_url", "")
+                .await
+        }
+    }
+
+    fn get_enable_workspaces(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("system", "enable_workspaces", false)
+                .await
+        }
+    }
+
+    fn get_read_only_mode(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("system", "read_only", false)
+                .await
+        }
+    }
+
+    fn get_read_only_roles(&self) -> impl std::future::Future<Output = Vec<String>> + Send {
+        async {
+            self.config_manager()
+                .get_string_array_or_default("system", "read_only_roles", vec![])
+                .await
+        }
+    }
+
+    fn get_email_rate_limit_max_requests(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("auth", "email_rate_limit_max_requests", 3)
+                .await
+        }
+    }
+
+    fn get_email_rate_limit_window_minutes(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("auth", "email_rate_limit_window_minutes", 60)
+                .await
+        }
+    }
+
+    fn get_guest_sessions_enabled(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("auth", "guest_sessions_enabled", false)
+                .await
+        }
+    }
+
+    fn get_guest_session_collections(
+        &self,
+    ) -> impl std::future::Future<Output = Vec<String>> + Send {
+        async {
+            self.config_manager()
+                .get_string_array_or_default("auth", "guest_session_collections", vec![])
+                .await
+        }
+    }
+
+    fn get_guest_session_ttl_minutes(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("auth", "guest_session_ttl_minutes", 15)
+                .await
+        }
+    }
+
+    fn get_guest_session_max_per_ip(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("auth", "guest_session_max_per_ip", 5)
+                .await
+        }
+    }
+
+    fn get_guest_session_window_minutes(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("auth", "guest_session_window_minutes", 60)
+                .await
+        }
+    }
+
+    fn get_require_email_verification(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("auth", "require_email_verification", true)
+                .await
+        }
+    }
+
+    fn get_default_language(&self) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default("system", "default_language", "en")
+                .await
+        }
+    }
+
+    fn get_maintenance_mode(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("api", "maintenance_mode", false)
+                .await
+        }
+    }
+
+    fn get_shutdown_drain_seconds(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "shutdown_drain_seconds", 5)
+                .await
+        }
+    }
+
+    fn get_http2_enabled(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("api", "http2_enabled", true)
+                .await
+        }
+    }
+
+    fn get_http2_cleartext(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("api", "http2_cleartext", false)
+                .await
+        }
+    }
+
+    fn get_keep_alive_timeout_seconds(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "keep_alive_timeout_seconds", 75)
+                .await
+        }
+    }
+
+    fn get_http2_max_concurrent_streams(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "http2_max_concurrent_streams", 200)
+                .await
+        }
+    }
+
+    fn get_max_header_size_kb(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "max_header_size_kb", 64)
+                .await
+        }
+    }
+
+    fn get_tls_expiry_warning_days(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "tls_expiry_warning_days", 14)
+                .await
+        }
+    }
+
+    fn get_websocket_coalesce_threshold(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "websocket_coalesce_threshold", 100)
+                .await
+        }
+    }
+
+    fn get_websocket_coalesce_window_ms(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "websocket_coalesce_window_ms", 1000)
+                .await
+        }
+    }
+
+    fn get_websocket_outbound_buffer_size(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "websocket_outbound_buffer_size", 256)
+                .await
+        }
+    }
+
+    fn get_websocket_slow_consumer_max_dropped(
+        &self,
+    ) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "websocket_slow_consumer_max_dropped", 100)
+                .await
+        }
+    }
+
+    fn get_websocket_max_message_kb(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "websocket_max_message_kb", 64)
+                .await
+        }
+    }
+
+    fn get_websocket_max_subscriptions(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "websocket_max_subscriptions", 100)
+                .await
+        }
+    }
+
+    fn get_websocket_max_filter_length(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "websocket_max_filter_length", 1024)
+                .await
+        }
+    }
+
+    fn get_websocket_max_violations(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "websocket_max_violations", 10)
+                .await
+        }
+    }
+
+    fn get_websocket_ban_seconds(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "websocket_ban_seconds", 300)
+                .await
+        }
+    }
+
+    fn get_api_default_version(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("api", "default_version", 1)
+                .await
+        }
+    }
+
+    /// `api.v{version}_deprecation_date`, empty while the version is not deprecated.
+    fn get_api_deprecation_date(
+        &self,
+        version: u32,
+    ) -> impl std::future::Future<Output = String> + Send {
+        async move {
+            self.config_manager()
+                .get_string_or_default("api", &format!("v{}_deprecation_date", version), "")
+                .await
+        }
+    }
+
+    /// `api.v{version}_sunset_date`, empty while no removal date is set.
+    fn get_api_sunset_date(
+        &self,
+        version: u32,
+    ) -> impl std::future::Future<Output = String> + Send {
+        async move {
+            self.config_manager()
+                .get_string_or_default("api", &format!("v{}_sunset_date", version), "")
+                .await
+        }
+    }
+
+    fn get_metrics_cpu_sampler_enabled(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("system", "metrics_cpu_sampler_enabled", true)
+                .await
+        }
+    }
+
+    fn get_metrics_cpu_sampler_interval_seconds(
+        &self,
+    ) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("system", "metrics_cpu_sampler_interval_seconds", 1)
+                .await
+                .max(1)
+        }
+    }
+
+    fn get_metrics_route_histograms_enabled(
+        &self,
+    ) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("system", "metrics_route_histograms_enabled", true)
+                .await
+        }
+    }
+
+    fn get_activity_tracking_enabled(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("system", "activity_tracking_enabled", true)
+                .await
+        }
+    }
+
+    fn get_users_relation_visibility(&self) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default("users", "relation_visibility", "authenticated")
+                .await
+        }
+    }
+
+    fn get_cors_allow_credentials(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("api", "cors_allow_credentials", true)
+                .await
+        }
+    }
+
+    fn get_cors_allowed_origins(&self) -> impl std::future::Future<Output = Vec<String>> + Send {
+        async {
+            self.config_manager()
+                .get_string_array_or_default(
+                    "api",
+                    "cors_allowed_origins",
+                    vec![
+                        "http://localhost:3000".to_string(),
+                        "http://localhost:5173".to_string(),
+                    ],
+                )
+                .await
+        }
+    }
+
+    fn get_connection_pool_size(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("database", "connection_pool_size", 10)
+                .await
+        }
+    }
+
+    fn get_backup_enabled(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("database", "backup_enabled", false)
+                .await
+        }
+    }
+
+    fn get_backup_retention_days(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("database", "backup_retention_days", 30)
+                .await
+        }
+    }
+
+    fn get_backup_schedule(&self) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default("database", "backup_schedule", "0 0 2 * * *")
+                .await
+        }
+    }
+
+    fn get_backup_compression(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("database", "backup_compression", true)
+                .await
+        }
+    }
+
+    fn get_backup_prefix(&self) -> impl std::future::Future<Output = String> + Send {
+        async {
+            self.config_manager()
+                .get_string_or_default("database", "backup_prefix", "lunarbase-backup")
+                .await
+        }
+    }
+
+    fn get_backup_min_size_bytes(&self) -> impl std::future::Future<Output = u64> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("database", "backup_min_size_bytes", 1024)
+                .await as u64
+        }
+    }
+
+    fn get_backup_max_age_hours(&self) -> impl std::future::Future<Output = u32> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("database", "backup_max_age_hours", 48)
+                .await
+        }
+    }
+
+    fn get_compression_enabled(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("web_server", "compression_enabled", true)
+                .await
+        }
+    }
+
+    fn get_compression_level(&self) -> impl std::future::Future<Output = u8> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("web_server", "compression_level", 6)
+                .await as u8
+        }
+    }
+
+    fn get_compression_min_size(&self) -> impl std::future::Future<Output = usize> + Send {
+        async {
+            self.config_manager()
+                .get_u32_or_default("web_server", "compression_min_size", 1024)
+                .await as usize
+        }
+    }
+
+    fn get_compression_gzip(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("web_server", "compression_gzip", true)
+                .await
+        }
+    }
+
+    fn get_compression_brotli(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("web_server", "compression_brotli", true)
+                .await
+        }
+    }
+
+    fn get_compression_deflate(&self) -> impl std::future::Future<Output = bool> + Send {
+        async {
+            self.config_manager()
+                .get_bool_or_default("web_server", "compression_deflate", true)
+                .await
+        }
+    }
+
+    fn get_compression_skip_paths(&self) -> impl std::future::Future<Output = Vec<String>> + Send {
+        async {
+            self.config_manager()
+                .get_string_array_or_default(
+                    "web_server",
+                    "compression_skip_paths",
+                    vec!["/api/admin/backup".to_string()],
+                )
                 .await
         }
     }
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// How long a collection delete confirmation token stays valid.
+pub const DELETE_CONFIRMATION_TTL: Duration = Duration::from_secs(300);
+
+/// Confirmation tokens handed out by the first phase of a collection delete.
+/// A token is bound to the collection id and the user who requested it and
+/// can be redeemed once, until it expires.
+#[derive(Clone, Default)]
+pub struct DeleteConfirmations {
+    pending: Arc<Mutex<HashMap<String, PendingDelete>>>,
+}
+
+struct PendingDelete {
+    collection_id: i32,
+    user_id: i32,
+    expires_at: Instant,
+}
+
+impl DeleteConfirmations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn issue(&self, collection_id: i32, user_id: i32) -> String {
+        self.issue_at(collection_id, user_id, Instant::now())
+    }
+
+    /// Redeems `token`, returning `false` when it is unknown, expired, or was
+    /// issued for another collection or user.
+    pub fn confirm(&self, token: &str, collection_id: i32, user_id: i32) -> bool {
+        self.confirm_at(token, collection_id, user_id, Instant::now())
+    }
+
+    fn issue_at(&self, collection_id: i32, user_id: i32, now: Instant) -> String {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.retain(|_, delete| delete.expires_at > now);
+
+        let token = Uuid::new_v4().simple().to_string();
+        pending.insert(
+            token.clone(),
+            PendingDelete {
+                collection_id,
+                user_id,
+                expires_at: now + DELETE_CONFIRMATION_TTL,
+            },
+        );
+        token
+    }
+
+    fn confirm_at(&self, token: &str, collection_id: i32, user_id: i32, now: Instant) -> bool {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let matches = pending.get(token).is_some_and(|delete| {
+            delete.collection_id == collection_id
+                && delete.user_id == user_id
+                && delete.expires_at > now
+        });
+        if matches {
+            pending.remove(token);
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_are_single_use_and_bound_to_the_request() {
+        let confirmations = DeleteConfirmations::new();
+        let start = Instant::now();
+
+        let token = confirmations.issue_at(1, 10, start);
+        assert!(!confirmations.confirm_at(&token, 2, 10, start));
+        assert!(!confirmations.confirm_at(&token, 1, 11, start));
+        assert!(confirmations.confirm_at(&token, 1, 10, start));
+        assert!(!confirmations.confirm_at(&token, 1, 10, start));
+
+        let token = confirmations.issue_at(1, 10, start);
+        assert!(!confirmations.confirm_at(&token, 1, 10, start + DELETE_CONFIRMATION_TTL));
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Caps how many emails may be requested per key (an address or a client IP)
+/// within a sliding window. Keys only keep an entry while they have requests
+/// inside the window.
+#[derive(Clone, Default)]
+pub struct EmailRateLimiter {
+    requests: Arc<Mutex<HashMap<String, VecDeque<Instant>>>>,
+}
+
+impl EmailRateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a request for every key and returns `true`, or returns `false`
+    /// without recording anything when one of them already made `max_requests`
+    /// requests within `window`.
+    pub fn try_record(&self, keys: &[String], max_requests: usize, window: Duration) -> bool {
+        self.try_record_at(keys, max_requests, window, Instant::now())
+    }
+
+    fn try_record_at(
+        &self,
+        keys: &[String],
+        max_requests: usize,
+        window: Duration,
+        now: Instant,
+    ) -> bool {
+        let mut requests = self.requests.lock().unwrap_or_else(|e| e.into_inner());
+
+        requests.retain(|_, times| {
+            while times
+                .front()
+                .is_some_and(|time| now.duration_since(*time) >= window)
+            {
+                times.pop_front();
+            }
+            !times.is_empty()
+        });
+
+        let limited = keys.iter().any(|key| {
+            requests
+                .get(key)
+                .is_some_and(|times| times.len() >= max_requests)
+        });
+        if limited {
+            return false;
+        }
+
+        for key in keys {
+            requests.entry(key.clone()).or_default().push_back(now);
+        }
+        true
+    }
+
+    /// Drops the requests recorded for `key`, so it starts with a full budget.
+    pub fn forget(&self, key: &str) {
+        let mut requests = self.requests.lock().unwrap_or_else(|e| e.into_inner());
+        requests.remove(key);
+    }
+
+    /// Requests recorded for `key` within `window`.
+    pub fn recorded(&self, key: &str, window: Duration) -> usize {
+        let now = Instant::now();
+        let requests = self.requests.lock().unwrap_or_else(|e| e.into_inner());
+        requests.get(key).map_or(0, |times| {
+            times
+                .iter()
+                .filter(|time| now.duration_since(**time) < window)
+                .count()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_within_the_window_only() {
+        let limiter = EmailRateLimiter::new();
+        let window = Duration::from_secs(60);
+        let start = Instant::now();
+        let keys = vec!["email:a@test.com".to_string(), "ip:1.2.3.4".to_string()];
+
+        assert!(limiter.try_record_at(&keys, 2, window, start));
+        assert!(limiter.try_record_at(&keys, 2, window, start));
+        assert!(!limiter.try_record_at(&keys, 2, window, start));
+
+        // The shared IP is exhausted, so another address from it is refused too
+        let other = vec!["email:b@test.com".to_string(), "ip:1.2.3.4".to_string()];
+        assert!(!limiter.try_record_at(&other, 2, window, start));
+
+        assert!(limiter.try_record_at(&keys, 2, window, start + window));
+    }
+
+    #[test]
+    fn test_idle_keys_are_forgotten() {
+        let limiter = EmailRateLimiter::new();
+        let window = Duration::from_secs(60);
+        let start = Instant::now();
+
+        limiter.try_record_at(&["ip:1.2.3.4".to_string()], 3, window, start);
+        limiter.try_record_at(&["ip:5.6.7.8".to_string()], 3, window, start + window);
+        assert_eq!(limiter.requests.lock().unwrap().len(), 1);
+    }
+}
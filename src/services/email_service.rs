@@ -2,13 +2,17 @@ use chrono::{Duration, Utc};
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use resend_rs::{Resend, types::Attachment, types::CreateEmailBaseOptions};
+use sha2::{Digest, Sha256};
 use std::borrow::Cow;
 use tracing::{debug, error, warn};
 use uuid::Uuid;
 
 use crate::Config;
 use crate::embedded_assets::StaticAssets;
-use crate::models::{NewVerificationToken, TokenType, VerificationToken};
+use crate::models::{
+    NewVerificationToken, PermissionDenialAlert, QuotaWarning, RecordDeletionAlert, TokenType,
+    VerificationToken,
+};
 use crate::schema::verification_tokens;
 use crate::services::ConfigurationManager;
 use crate::utils::LunarbaseError;
@@ -19,6 +23,7 @@ type DbPool = Pool<ConnectionManager<SqliteConnection>>;
 pub struct EmailService {
     resend_client: Option<Resend>,
     from_email: String,
+    public_api_url: String,
     frontend_url: String,
     pool: DbPool,
     logo_bytes: Option<Cow<'static, [u8]>>,
@@ -48,13 +53,14 @@ impl EmailService {
         }
 
         debug!(
-            "EmailService: Configured with from_email: {}, frontend_url: {}",
-            from_email, config.frontend_url
+            "EmailService: Configured with from_email: {}, public_api_url: {}, frontend_url: {}",
+            from_email, config.public_api_url, config.frontend_url
         );
 
         Self {
             resend_client,
             from_email,
+            public_api_url: config.public_api_url.clone(),
             frontend_url: config.frontend_url.clone(),
             pool,
             logo_bytes,
@@ -95,16 +101,24 @@ impl EmailService {
         let token = Uuid::new_v4().to_string();
         let expires_at = (Utc::now() + duration).naive_utc();
 
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        // Only the latest link of each kind works, which also caps every
+        // user at one outstanding token per type
+        diesel::delete(verification_tokens::table)
+            .filter(verification_tokens::user_id.eq(user_id))
+            .filter(verification_tokens::token_type.eq(token_type.as_str()))
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+
         let new_token = NewVerificationToken::new_with_type(
-            token.clone(),
+            hash_token(&token),
             user_id,
             email,
             expires_at,
             token_type,
         );
 
-        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
-
         diesel::insert_into(verification_tokens::table)
             .values(&new_token)
             .execute(&mut conn)
@@ -124,38 +138,38 @@ impl EmailService {
             .await
     }
 
+    /// Consumes `token`: it is valid once, for `expected_type` only.
     pub async fn verify_token_with_type(
         &self,
         token: &str,
-        _expected_type: TokenType,
+        expected_type: TokenType,
     ) -> Result<i32, LunarbaseError> {
+        let invalid =
+            || LunarbaseError::ValidationError(vec!["Invalid verification token".to_string()]);
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
         let verification_token: VerificationToken = verification_tokens::table
-            .filter(verification_tokens::token.eq(token))
+            .filter(verification_tokens::token_hash.eq(hash_token(token)))
+            .filter(verification_tokens::token_type.eq(expected_type.as_str()))
             .first(&mut conn)
-            .map_err(|_| {
-                LunarbaseError::ValidationError(vec!["Invalid verification token".to_string()])
-            })?;
+            .map_err(|_| invalid())?;
+
+        // Whoever deletes the row uses the token, so concurrent requests with
+        // the same link cannot both succeed
+        let consumed = diesel::delete(verification_tokens::table.find(verification_token.id))
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+        if consumed == 0 {
+            return Err(invalid());
+        }
 
         if verification_token.expires_at <= Utc::now().naive_utc() {
-            diesel::delete(verification_tokens::table)
-                .filter(verification_tokens::token.eq(token))
-                .execute(&mut conn)
-                .ok();
             return Err(LunarbaseError::ValidationError(vec![
                 "Verification token has expired".to_string(),
             ]));
         }
 
-        let user_id = verification_token.user_id;
-
-        diesel::delete(verification_tokens::table)
-            .filter(verification_tokens::token.eq(token))
-            .execute(&mut conn)
-            .map_err(|_| LunarbaseError::InternalError)?;
-
-        Ok(user_id)
+        Ok(verification_token.user_id)
     }
 
     pub async fn send_verification_email(
@@ -189,7 +203,7 @@ impl EmailService {
             .generate_verification_token(user_id, email.to_string())
             .await?;
 
-        let verification_url = format!("{}/api/verify-email?token={}", self.frontend_url, token);
+        let verification_url = self.verification_url(&token);
 
         let subject = "Verify your email address";
         let html_content = self.create_verification_email_html(username, &verification_url);
@@ -246,7 +260,7 @@ impl EmailService {
             .generate_password_reset_token(user_id, email.to_string())
             .await?;
 
-        let reset_url = format!("{}/admin/reset-password?token={}", self.frontend_url, token);
+        let reset_url = self.password_reset_url(&token);
 
         let subject = "Reset your password";
         let html_content = self.create_password_reset_email_html(username, &reset_url);
@@ -272,6 +286,61 @@ impl EmailService {
         }
     }
 
+    pub async fn send_account_activated_email(
+        &self,
+        email: &str,
+        username: &str,
+    ) -> Result<(), LunarbaseError> {
+        debug!(
+            "EmailService: Attempting to send account activated email to {}",
+            email
+        );
+
+        let email_enabled = self
+            .config_manager
+            .get_bool("email", "email_enabled")
+            .await
+            .unwrap_or(false);
+
+        if !email_enabled {
+            debug!("Email service is disabled, skipping account activated email");
+            return Ok(());
+        }
+
+        let Some(ref resend_client) = self.resend_client else {
+            warn!("Resend client not configured, skipping account activated email");
+            return Ok(());
+        };
+
+        let login_url = format!("{}/admin/login", self.frontend_url);
+
+        let subject = "Your account has been activated";
+        let html_content = self.create_account_activated_email_html(username, &login_url);
+        let text_content = self.create_account_activated_email_text(username, &login_url);
+
+        let mut email_request = CreateEmailBaseOptions::new(&self.from_email, [email], subject)
+            .with_html(&html_content)
+            .with_text(&text_content);
+
+        if let Some(logo_attachment) = self.create_logo_attachment() {
+            email_request = email_request.with_attachment(logo_attachment);
+        }
+
+        match resend_client.emails.send(email_request).await {
+            Ok(_) => {
+                debug!("Account activated email sent successfully to: {}", email);
+                Ok(())
+            }
+            Err(e) => {
+                error!(
+                    "Failed to send account activated email to {}: {:?}",
+                    email, e
+                );
+                Err(LunarbaseError::InternalError)
+            }
+        }
+    }
+
     fn create_verification_email_html(&self, username: &str, verification_url: &str) -> String {
         format!(
             r#"
@@ -628,14 +697,387 @@ Need help? Contact your system administrator.
         )
     }
 
+    fn create_account_activated_email_html(&self, username: &str, login_url: &str) -> String {
+        format!(
+            r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Account Activated - LunarBase</title>
+    <style>
+        @media only screen and (max-width: 600px){{
+            .mobile-padding {{ padding: 10px !important; }}
+            .mobile-content-padding {{ padding: 20px 15px !important; }}
+            .mobile-header-padding {{ padding: 25px 15px !important; }}
+            .mobile-footer-padding {{ padding: 20px 15px !important; }}
+            .mobile-inner-padding {{ padding: 12px !important; }}
+            .mobile-font-size {{ font-size: 14px !important; }}
+            .mobile-title {{ font-size: 20px !important; }}
+            .mobile-brand {{ font-size: 26px !important; }}
+            .mobile-button {{ padding: 14px 24px !important; font-size: 15px !important; }}
+        }}
+    </style>
+</head>
+<body style="margin: 0; padding: 0; font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Arial, sans-serif; background-color: #f5f5f5;">
+    <table role="presentation" width="100%" cellpadding="0" cellspacing="0" style="background-color: #f5f5f5;" class="mobile-padding">
+        <tr>
+            <td align="center" style="padding: 20px;" class="mobile-padding">
+                <!-- Main Container -->
+                <table role="presentation" width="600" cellpadding="0" cellspacing="0" style="max-width: 600px; width: 100%; background-color: #ffffff; border-radius: 16px; box-shadow: 0 8px 24px rgba(0, 0, 0, 0.1), 0 4px 8px rgba(0, 0, 0, 0.05); overflow: hidden; border: 1px solid #e5e5e5;">
+                    
+                    <!-- Header -->
+                    <tr>
+                        <td style="background-color: #f8f8f8; padding: 40px 30px; text-align: center; border-bottom: 1px solid #e5e5e5;" class="mobile-header-padding">
+                            
+                            <!-- Logo Container -->
+                            <table role="presentation" width="100%" cellpadding="0" cellspacing="0">
+                                <tr>
+                                    <td align="center">
+                                        <!-- Logo -->
+                                        <div style="width: 64px; height: 64px; background-color: #1c1c1c; background: linear-gradient(#1c1c1c, #1c1c1c); border-radius: 16px; display: inline-block; line-height: 64px; text-align: center; margin-bottom: 16px; box-shadow: 0 4px 12px rgba(0, 0, 0, 0.08); border: 1px solid #d0d0d0;">
+                                            <img src="cid:lunarbase-logo" alt="LunarBase Logo" style="width: 64px; height: 64px; vertical-align: middle; border-radius: 4px;" />
+                                        </div>
+                                        
+                                        <!-- Brand Name -->
+                                        <div style="font-size: 32px; font-weight: 700; color: #1a1a1a; margin-bottom: 8px; letter-spacing: -0.6px;" class="mobile-brand">
+                                            LunarBase
+                                        </div>
+                                        
+                                        <!-- Tagline -->
+                                        <div style="font-size: 16px; color: #5a5a5a; font-weight: 500;">
+                                            Admin Panel
+                                        </div>
+                                    </td>
+                                </tr>
+                            </table>
+                        </td>
+                    </tr>
+                    
+                    <!-- Content -->
+                    <tr>
+                        <td style="padding: 40px 30px; background-color: #ffffff;" class="mobile-content-padding">
+                            
+                            <!-- Greeting -->
+                            <h1 style="font-size: 24px; font-weight: 600; color: #1a1a1a; margin: 0 0 16px 0; text-align: center;" class="mobile-title">
+                                Hi, {}!
+                            </h1>
+                            
+                            <!-- Message -->
+                            <p style="font-size: 16px; color: #3a3a3a; margin: 0 0 32px 0; text-align: center; line-height: 1.7;" class="mobile-font-size">
+                                An administrator has verified your email address, so your LunarBase account is now active. 
+                                You can sign in using the button below.
+                            </p>
+                            
+                            <!-- CTA Button -->
+                            <table role="presentation" width="100%" cellpadding="0" cellspacing="0">
+                                <tr>
+                                    <td align="center" style="padding: 0 0 32px 0;">
+                                        <a href="{}" style="display: inline-block; background-color: #1a1a1a; color: #ffffff; padding: 16px 32px; text-decoration: none; border-radius: 12px; font-weight: 600; font-size: 16px; letter-spacing: 0.5px; box-shadow: 0 4px 12px rgba(0, 0, 0, 0.15); border: 1px solid #2a2a2a; transition: all 0.2s ease;" class="mobile-button">
+                                            Sign In
+                                        </a>
+                                    </td>
+                                </tr>
+                            </table>
+                            
+                            <!-- Alternative Link -->
+                            <table role="presentation" width="100%" cellpadding="0" cellspacing="0" style="background-color: #f8f8f8; border: 1px solid #e5e5e5; border-radius: 8px; margin: 0 0 24px 0;">
+                                <tr>
+                                    <td style="padding: 20px;" class="mobile-inner-padding">
+                                        <p style="font-size: 14px; color: #5a5a5a; margin: 0 0 8px 0;" class="mobile-font-size">
+                                            If the button doesn't work, copy and paste this link:
+                                        </p>
+                                        <div style="font-size: 13px; color: #3a3a3a; word-break: break-all; font-family: 'Monaco', 'Menlo', 'Courier New', monospace; background-color: #ffffff; padding: 12px; border-radius: 6px; border: 1px solid #e5e5e5;">
+                                            {}
+                                        </div>
+                                    </td>
+                                </tr>
+                            </table>
+                            
+                            <!-- Security Notice -->
+                            <table role="presentation" width="100%" cellpadding="0" cellspacing="0" style="background-color: #f8f8f8; border-radius: 8px; border-left: 4px solid #9ca3af; margin-top: 24px; border: 1px solid #e5e5e5;">
+                                <tr>
+                                    <td style="padding: 16px;" class="mobile-inner-padding">
+                                        <p style="font-size: 13px; color: #5a5a5a; line-height: 1.5; margin: 0;" class="mobile-font-size">
+                                            If you didn't create an account with LunarBase, please contact your system administrator.
+                                        </p>
+                                    </td>
+                                </tr>
+                            </table>
+                        </td>
+                    </tr>
+                    
+                    <!-- Footer -->
+                    <tr>
+                        <td style="background-color: #f8f8f8; padding: 30px; text-align: center; border-top: 1px solid #e5e5e5;" class="mobile-footer-padding">
+                            <p style="font-size: 14px; color: #5a5a5a; margin: 0 0 8px 0;" class="mobile-font-size">
+                                This email was sent by LunarBase Admin System.
+                            </p>
+                            <p style="font-size: 14px; color: #5a5a5a; margin: 0 0 16px 0;" class="mobile-font-size">
+                                Need help? Contact your system administrator.
+                            </p>
+                            <p style="font-size: 12px; color: #888888; margin: 0;">
+                                © 2025 LunarBase.
+                            </p>
+                        </td>
+                    </tr>
+                    
+                </table>
+            </td>
+        </tr>
+    </table>
+</body>
+</html>
+            "#,
+            username, login_url, login_url
+        )
+    }
+
+    fn create_account_activated_email_text(&self, username: &str, login_url: &str) -> String {
+        format!(
+            r#"LunarBase
+
+Hi, {}!
+
+An administrator has verified your email address, so your LunarBase account is now active.
+You can sign in at the following link:
+
+{}
+
+SECURITY NOTE: If you didn't create an account with LunarBase, please contact your 
+system administrator.
+
+Best regards,
+The LunarBase Team
+
+---
+This email was sent by LunarBase Admin System.
+Need help? Contact your system administrator.
+
+© 2025 LunarBase."#,
+            username, login_url
+        )
+    }
+
+    pub async fn send_permission_denial_alert(
+        &self,
+        recipient: &str,
+        alert: &PermissionDenialAlert,
+    ) -> Result<(), LunarbaseError> {
+        let email_enabled = self
+            .config_manager
+            .get_bool("email", "email_enabled")
+            .await
+            .unwrap_or(false);
+
+        if !email_enabled {
+            debug!("Email service is disabled, skipping permission denial alert");
+            return Ok(());
+        }
+
+        let Some(ref resend_client) = self.resend_client else {
+            warn!("Resend client not configured, skipping permission denial alert");
+            return Ok(());
+        };
+
+        let subject = format!("Permission denial alert for user {}", alert.user_id);
+        let text_content = format!(
+            r#"LunarBase
+
+User {} ({}) was denied access {} times within {} seconds.
+
+Latest denial: {} on collection '{}', refused by {}.
+
+Repeated denials from one user usually mean either a misconfigured client or
+someone probing for data they cannot access. Check the permission denials in the
+metrics summary for details.
+
+---
+This email was sent by LunarBase Admin System."#,
+            alert.user_id,
+            alert.email,
+            alert.denials,
+            alert.window_seconds,
+            alert.action,
+            alert.collection,
+            alert.source.as_str()
+        );
+
+        let email_request =
+            CreateEmailBaseOptions::new(&self.from_email, [recipient], subject.as_str())
+                .with_text(&text_content);
+
+        match resend_client.emails.send(email_request).await {
+            Ok(_) => {
+                debug!("Permission denial alert sent to: {}", recipient);
+                Ok(())
+            }
+            Err(e) => {
+                error!(
+                    "Failed to send permission denial alert to {}: {:?}",
+                    recipient, e
+                );
+                Err(LunarbaseError::InternalError)
+            }
+        }
+    }
+
+    pub async fn send_record_deletion_alert(
+        &self,
+        recipient: &str,
+        alert: &RecordDeletionAlert,
+    ) -> Result<(), LunarbaseError> {
+        let email_enabled = self
+            .config_manager
+            .get_bool("email", "email_enabled")
+            .await
+            .unwrap_or(false);
+
+        if !email_enabled {
+            debug!("Email service is disabled, skipping record deletion alert");
+            return Ok(());
+        }
+
+        let Some(ref resend_client) = self.resend_client else {
+            warn!("Resend client not configured, skipping record deletion alert");
+            return Ok(());
+        };
+
+        let subject = format!(
+            "Record deletion alert for collection '{}'",
+            alert.collection
+        );
+        let text_content = format!(
+            r#"LunarBase
+
+Collection '{}' has had {} records deleted on {} (UTC), more than {} times its
+trailing daily average of {:.1}.
+
+A sudden burst of deletions usually means a misbehaving client or script.
+Check the record analytics in the admin panel and restore from a backup if
+the deletions were not intended.
+
+---
+This email was sent by LunarBase Admin System."#,
+            alert.collection, alert.deletions, alert.day, alert.threshold, alert.trailing_average
+        );
+
+        let email_request =
+            CreateEmailBaseOptions::new(&self.from_email, [recipient], subject.as_str())
+                .with_text(&text_content);
+
+        match resend_client.emails.send(email_request).await {
+            Ok(_) => {
+                debug!("Record deletion alert sent to: {}", recipient);
+                Ok(())
+            }
+            Err(e) => {
+                error!(
+                    "Failed to send record deletion alert to {}: {:?}",
+                    recipient, e
+                );
+                Err(LunarbaseError::InternalError)
+            }
+        }
+    }
+
+    pub async fn send_quota_warning(
+        &self,
+        recipient: &str,
+        warning: &QuotaWarning,
+    ) -> Result<(), LunarbaseError> {
+        let email_enabled = self
+            .config_manager
+            .get_bool("email", "email_enabled")
+            .await
+            .unwrap_or(false);
+
+        if !email_enabled {
+            debug!("Email service is disabled, skipping quota warning");
+            return Ok(());
+        }
+
+        let Some(ref resend_client) = self.resend_client else {
+            warn!("Resend client not configured, skipping quota warning");
+            return Ok(());
+        };
+
+        let subject = format!(
+            "Quota warning: {} of {}",
+            warning.quota.as_str(),
+            warning.subject
+        );
+        let text_content = format!(
+            r#"LunarBase
+
+The {} quota of {} is at {} of its limit of {}, past the warning threshold
+of {}%.
+
+Requests beyond the limit will be rejected. Raise the limit in the settings
+if this usage is expected. You will not be emailed again for this quota until
+its usage has dropped back below the threshold.
+
+---
+This email was sent by LunarBase Admin System."#,
+            warning.quota.as_str(),
+            warning.subject,
+            warning.used,
+            warning.limit,
+            warning.threshold_percent
+        );
+
+        let email_request =
+            CreateEmailBaseOptions::new(&self.from_email, [recipient], subject.as_str())
+                .with_text(&text_content);
+
+        match resend_client.emails.send(email_request).await {
+            Ok(_) => {
+                debug!("Quota warning sent to: {}", recipient);
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to send quota warning to {}: {:?}", recipient, e);
+                Err(LunarbaseError::InternalError)
+            }
+        }
+    }
+
     pub fn is_configured(&self) -> bool {
         self.resend_client.is_some()
     }
 
+    /// Confirms the email API is reachable and accepts the configured key
+    /// without sending anything. Send-only keys are reported as healthy.
+    pub async fn check_connectivity(&self) -> Result<(), String> {
+        let Some(ref resend_client) = self.resend_client else {
+            return Err("Email service is not configured".to_string());
+        };
+
+        match resend_client.domains.list().await {
+            Ok(_) => Ok(()),
+            Err(resend_rs::Error::Resend(e))
+                if e.kind() == resend_rs::types::ErrorKind::RestrictedApiKey =>
+            {
+                Ok(())
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
     pub fn get_frontend_url(&self) -> &str {
         &self.frontend_url
     }
 
+    /// Verification is handled by the API, which then redirects to the frontend.
+    pub fn verification_url(&self, token: &str) -> String {
+        format!("{}/api/verify-email?token={}", self.public_api_url, token)
+    }
+
+    pub fn password_reset_url(&self, token: &str) -> String {
+        format!("{}/admin/reset-password?token={}", self.frontend_url, token)
+    }
+
     fn create_logo_attachment(&self) -> Option<Attachment> {
         self.logo_bytes.as_ref().map(|logo_data| {
             Attachment::from_content(logo_data.to_vec())
@@ -645,3 +1087,12 @@ Need help? Contact your system administrator.
         })
     }
 }
+
+/// Tokens are stored as their SHA-256 so database access alone cannot be
+/// turned into account takeovers.
+fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
@@ -0,0 +1,314 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast;
+
+use crate::models::{BulkChange, PendingBulkChange, PendingEvent, RecordEvent};
+
+/// Record ids listed in a [`BulkChange`] so clients can tell what kind of
+/// records changed without receiving all of them.
+const BULK_CHANGE_SAMPLE_SIZE: usize = 10;
+
+#[derive(Debug, Clone)]
+pub enum BroadcastEvent {
+    Record(PendingEvent),
+    BulkChange(PendingBulkChange),
+}
+
+impl BroadcastEvent {
+    pub fn collection_name(&self) -> &str {
+        match self {
+            BroadcastEvent::Record(event) => &event.collection_name,
+            BroadcastEvent::BulkChange(bulk) => &bulk.change.collection,
+        }
+    }
+}
+
+struct CoalesceWindow {
+    started: Instant,
+    events: u32,
+    /// Events received after the threshold was crossed, sent as one message
+    /// when the window ends
+    bulk: Option<(BulkChange, HashSet<String>)>,
+}
+
+/// Publishes record events, collapsing bursts per collection. The first
+/// `threshold` events of a window are sent as they arrive; later ones in the
+/// same window are folded into a single [`BulkChange`] sent when the window
+/// ends, after every event sent before it. Sending happens under the lock so
+/// the two paths never reorder events of a collection.
+#[derive(Clone)]
+pub struct EventCoalescer {
+    sender: broadcast::Sender<BroadcastEvent>,
+    windows: Arc<Mutex<HashMap<String, CoalesceWindow>>>,
+}
+
+impl EventCoalescer {
+    pub fn new(sender: broadcast::Sender<BroadcastEvent>) -> Self {
+        Self {
+            sender,
+            windows: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Sends or coalesces `event`. A `threshold` of 0 disables coalescing.
+    /// Returns when the pending bulk change must be flushed if this event
+    /// started one.
+    pub fn publish(
+        &self,
+        event: PendingEvent,
+        threshold: u32,
+        window: Duration,
+        now: Instant,
+    ) -> Result<Option<Instant>, broadcast::error::SendError<BroadcastEvent>> {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        if threshold == 0 {
+            if let Some(state) = windows.remove(&event.collection_name) {
+                self.send_bulk_change(state)?;
+            }
+            return self
+                .sender
+                .send(BroadcastEvent::Record(event))
+                .map(|_| None);
+        }
+
+        let state = windows
+            .entry(event.collection_name.clone())
+            .or_insert_with(|| CoalesceWindow {
+                started: now,
+                events: 0,
+                bulk: None,
+            });
+        if state.bulk.is_none() && now.duration_since(state.started) >= window {
+            state.started = now;
+            state.events = 0;
+        }
+        state.events += 1;
+
+        if let Some((change, record_ids)) = state.bulk.as_mut() {
+            add_to_bulk_change(change, record_ids, &event.event);
+            return Ok(None);
+        }
+        if state.events <= threshold {
+            return self
+                .sender
+                .send(BroadcastEvent::Record(event))
+                .map(|_| None);
+        }
+
+        let mut change = BulkChange {
+            collection: event.collection_name.clone(),
+            created: 0,
+            updated: 0,
+            deleted: 0,
+            sample_ids: Vec::new(),
+        };
+        let mut record_ids = HashSet::new();
+        add_to_bulk_change(&mut change, &mut record_ids, &event.event);
+        state.bulk = Some((change, record_ids));
+        Ok(Some(state.started + window))
+    }
+
+    /// Sends the bulk change pending for `collection_name`, if any, and starts
+    /// a new window.
+    pub fn flush(
+        &self,
+        collection_name: &str,
+    ) -> Result<(), broadcast::error::SendError<BroadcastEvent>> {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        match windows.remove(collection_name) {
+            Some(state) => self.send_bulk_change(state),
+            None => Ok(()),
+        }
+    }
+
+    fn send_bulk_change(
+        &self,
+        state: CoalesceWindow,
+    ) -> Result<(), broadcast::error::SendError<BroadcastEvent>> {
+        let Some((change, record_ids)) = state.bulk else {
+            return Ok(());
+        };
+
+        self.sender
+            .send(BroadcastEvent::BulkChange(PendingBulkChange {
+                change,
+                record_ids: Arc::new(record_ids),
+            }))
+            .map(|_| ())
+    }
+}
+
+fn add_to_bulk_change(
+    change: &mut BulkChange,
+    record_ids: &mut HashSet<String>,
+    event: &RecordEvent,
+) {
+    let record_id = match event {
+        RecordEvent::Created { record_id, .. } => {
+            change.created += 1;
+            record_id
+        }
+        RecordEvent::Deleted { record_id, .. } => {
+            change.deleted += 1;
+            record_id
+        }
+        RecordEvent::Updated { record_id, .. }
+        | RecordEvent::OwnershipTransferred { record_id, .. }
+        | RecordEvent::Reordered { record_id, .. }
+        | RecordEvent::Published { record_id, .. }
+        | RecordEvent::Unpublished { record_id, .. } => {
+            change.updated += 1;
+            record_id
+        }
+    };
+
+    if record_ids.insert(record_id.clone()) && change.sample_ids.len() < BULK_CHANGE_SAMPLE_SIZE {
+        change.sample_ids.push(record_id.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    fn created(collection: &str, record_id: u32) -> PendingEvent {
+        PendingEvent {
+            collection_name: collection.to_string(),
+            event: RecordEvent::Created {
+                record_id: record_id.to_string(),
+                record: json!({ "id": record_id }),
+            },
+            user_id: None,
+        }
+    }
+
+    fn deleted(collection: &str, record_id: u32) -> PendingEvent {
+        PendingEvent {
+            collection_name: collection.to_string(),
+            event: RecordEvent::Deleted {
+                record_id: record_id.to_string(),
+                old_record: None,
+            },
+            user_id: None,
+        }
+    }
+
+    fn received(receiver: &mut broadcast::Receiver<BroadcastEvent>) -> Vec<String> {
+        let mut received = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            received.push(match event {
+                BroadcastEvent::Record(event) => match event.event {
+                    RecordEvent::Created { record_id, .. } => {
+                        format!("{}:created:{}", event.collection_name, record_id)
+                    }
+                    RecordEvent::Deleted { record_id, .. } => {
+                        format!("{}:deleted:{}", event.collection_name, record_id)
+                    }
+                    other => format!("{}:{:?}", event.collection_name, other),
+                },
+                BroadcastEvent::BulkChange(bulk) => format!(
+                    "{}:bulk:{}/{}/{}:{}",
+                    bulk.change.collection,
+                    bulk.change.created,
+                    bulk.change.updated,
+                    bulk.change.deleted,
+                    bulk.change.sample_ids.join(",")
+                ),
+            });
+        }
+        received
+    }
+
+    #[test]
+    fn test_events_over_threshold_are_coalesced_in_order() {
+        let (sender, mut receiver) = broadcast::channel(100);
+        let coalescer = EventCoalescer::new(sender);
+        let start = Instant::now();
+
+        for id in 1..=3 {
+            assert_eq!(
+                coalescer
+                    .publish(created("posts", id), 3, WINDOW, start)
+                    .unwrap(),
+                None
+            );
+        }
+        let flush_at = coalescer
+            .publish(created("posts", 4), 3, WINDOW, start)
+            .unwrap();
+        assert_eq!(flush_at, Some(start + WINDOW));
+        for id in 5..=15 {
+            assert_eq!(
+                coalescer
+                    .publish(created("posts", id), 3, WINDOW, start)
+                    .unwrap(),
+                None
+            );
+        }
+        coalescer
+            .publish(deleted("posts", 4), 3, WINDOW, start)
+            .unwrap();
+        // Other collections keep their own window
+        coalescer
+            .publish(created("tags", 1), 3, WINDOW, start)
+            .unwrap();
+
+        assert_eq!(
+            received(&mut receiver),
+            [
+                "posts:created:1",
+                "posts:created:2",
+                "posts:created:3",
+                "tags:created:1"
+            ]
+        );
+
+        coalescer.flush("posts").unwrap();
+        coalescer
+            .publish(created("posts", 16), 3, WINDOW, start + WINDOW)
+            .unwrap();
+        assert_eq!(
+            received(&mut receiver),
+            [
+                "posts:bulk:12/0/1:4,5,6,7,8,9,10,11,12,13",
+                "posts:created:16"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_window_resets_and_zero_threshold_disables_coalescing() {
+        let (sender, mut receiver) = broadcast::channel(100);
+        let coalescer = EventCoalescer::new(sender);
+        let start = Instant::now();
+
+        coalescer
+            .publish(created("posts", 1), 1, WINDOW, start)
+            .unwrap();
+        coalescer
+            .publish(created("posts", 2), 1, WINDOW, start + WINDOW)
+            .unwrap();
+        for id in 3..=5 {
+            coalescer
+                .publish(created("posts", id), 0, WINDOW, start)
+                .unwrap();
+        }
+        coalescer.flush("posts").unwrap();
+
+        assert_eq!(
+            received(&mut receiver),
+            [
+                "posts:created:1",
+                "posts:created:2",
+                "posts:created:3",
+                "posts:created:4",
+                "posts:created:5"
+            ]
+        );
+    }
+}
@@ -0,0 +1,448 @@
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+use utoipa::ToSchema;
+
+use crate::schema::health_samples;
+use crate::services::health_service::{
+    COMPONENT_DISABLED, COMPONENT_HEALTHY, COMPONENT_NOT_CONFIGURED, COMPONENT_UNHEALTHY,
+    overall_status,
+};
+use crate::services::{ConfigurationAccess, ConfigurationManager, HealthService};
+use crate::utils::LunarbaseError;
+
+type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+pub const SAMPLE_INTERVAL_SECONDS: i64 = 60;
+/// One day of samples is kept in memory; longer windows need persistence.
+const MEMORY_CAPACITY: usize = 24 * 60;
+/// Persisted samples older than this are pruned and no window may exceed it.
+const MAX_WINDOW_DAYS: i64 = 30;
+const DATABASE_COMPONENT: &str = "database";
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HealthSample {
+    pub sampled_at: DateTime<Utc>,
+    /// Composite status, computed like the admin health report
+    #[schema(example = "healthy")]
+    pub status: String,
+    #[schema(example = json!({"database": "healthy", "s3": "degraded"}))]
+    pub components: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ComponentUptime {
+    /// Status in the latest sample
+    #[schema(example = "degraded")]
+    pub status: String,
+    /// When the current status began; the oldest available sample if it has
+    /// not changed since then
+    pub since: DateTime<Utc>,
+    /// Status before the last transition, absent if none was seen
+    #[schema(example = "healthy")]
+    pub previous_status: Option<String>,
+    /// Share of counted samples in the window that were healthy; samples where
+    /// the component was disabled or not configured are not counted
+    #[schema(example = 99.31)]
+    pub uptime_percentage: Option<f64>,
+    pub healthy_samples: usize,
+    pub counted_samples: usize,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HealthHistoryResponse {
+    #[schema(example = "24h")]
+    pub window: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    #[schema(example = 60)]
+    pub sample_interval_seconds: i64,
+    /// Whether samples are also stored in the database
+    pub persisted: bool,
+    pub overall: Option<ComponentUptime>,
+    pub components: BTreeMap<String, ComponentUptime>,
+    pub samples: Vec<HealthSample>,
+}
+
+#[derive(Queryable)]
+struct StoredSample {
+    sampled_at: chrono::NaiveDateTime,
+    component: String,
+    status: String,
+}
+
+/// Samples the composite health status every minute into a bounded ring
+/// buffer, and into `health_samples` when `api.health_history_persist` is set.
+#[derive(Clone)]
+pub struct HealthRecorder {
+    pool: DbPool,
+    health_service: HealthService,
+    config_manager: ConfigurationManager,
+    samples: Arc<RwLock<VecDeque<HealthSample>>>,
+}
+
+impl ConfigurationAccess for HealthRecorder {
+    fn config_manager(&self) -> &ConfigurationManager {
+        &self.config_manager
+    }
+}
+
+impl HealthRecorder {
+    pub fn new(
+        pool: DbPool,
+        health_service: HealthService,
+        config_manager: ConfigurationManager,
+    ) -> Self {
+        Self {
+            pool,
+            health_service,
+            config_manager,
+            samples: Arc::new(RwLock::new(VecDeque::with_capacity(MEMORY_CAPACITY))),
+        }
+    }
+
+    /// Restores the last day of persisted samples, then records a sample every
+    /// `SAMPLE_INTERVAL_SECONDS`.
+    pub fn start(&self) {
+        let recorder = self.clone();
+
+        tokio::spawn(async move {
+            if recorder.get_health_history_persist().await {
+                recorder.restore_from_database().await;
+            }
+
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                SAMPLE_INTERVAL_SECONDS as u64,
+            ));
+            loop {
+                interval.tick().await;
+                recorder.record_sample().await;
+            }
+        });
+    }
+
+    pub async fn record_sample(&self) -> HealthSample {
+        let mut components: BTreeMap<String, String> = self
+            .health_service
+            .check_components()
+            .await
+            .into_iter()
+            .map(|(name, health)| (name, health.status))
+            .collect();
+        let database_healthy = self.check_database();
+        let status = overall_status(database_healthy, components.values().map(String::as_str));
+        components.insert(
+            DATABASE_COMPONENT.to_string(),
+            if database_healthy {
+                COMPONENT_HEALTHY
+            } else {
+                COMPONENT_UNHEALTHY
+            }
+            .to_string(),
+        );
+
+        let sample = HealthSample {
+            sampled_at: Utc::now(),
+            status: status.to_string(),
+            components,
+        };
+
+        {
+            let mut samples = self.samples.write().await;
+            if samples.len() == MEMORY_CAPACITY {
+                samples.pop_front();
+            }
+            samples.push_back(sample.clone());
+        }
+
+        if self.get_health_history_persist().await
+            && let Err(e) = self.persist(&sample)
+        {
+            warn!("Failed to persist health sample: {:?}", e);
+        }
+
+        sample
+    }
+
+    /// Samples and uptime for the last `window`, e.g. `30m`, `24h` or `7d`.
+    pub async fn history(&self, window: &str) -> Result<HealthHistoryResponse, LunarbaseError> {
+        let duration = parse_window(window)?;
+        let to = Utc::now();
+        let from = to - duration;
+        let persisted = self.get_health_history_persist().await;
+
+        // Transitions are looked up in everything that is loaded, not only the
+        // window, so a long-standing status still reports when it began
+        let loaded: Vec<HealthSample> = if persisted && duration > memory_span() {
+            self.load_from_database(from)?
+        } else {
+            self.samples.read().await.iter().cloned().collect()
+        };
+
+        Ok(summarize(window, from, to, persisted, loaded))
+    }
+
+    fn check_database(&self) -> bool {
+        match self.pool.get() {
+            Ok(mut conn) => diesel::sql_query("SELECT 1").execute(&mut conn).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    fn persist(&self, sample: &HealthSample) -> Result<(), LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::DatabaseError)?;
+        let sampled_at = sample.sampled_at.naive_utc();
+
+        let rows: Vec<_> = std::iter::once(("overall", sample.status.as_str()))
+            .chain(
+                sample
+                    .components
+                    .iter()
+                    .map(|(name, status)| (name.as_str(), status.as_str())),
+            )
+            .map(|(component, status)| {
+                (
+                    health_samples::sampled_at.eq(sampled_at),
+                    health_samples::component.eq(component),
+                    health_samples::status.eq(status),
+                )
+            })
+            .collect();
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::insert_into(health_samples::table)
+                .values(&rows)
+                .execute(conn)?;
+            diesel::delete(
+                health_samples::table.filter(
+                    health_samples::sampled_at
+                        .lt((Utc::now() - Duration::days(MAX_WINDOW_DAYS)).naive_utc()),
+                ),
+            )
+            .execute(conn)
+        })
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(())
+    }
+
+    fn load_from_database(&self, from: DateTime<Utc>) -> Result<Vec<HealthSample>, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let rows: Vec<StoredSample> = health_samples::table
+            .filter(health_samples::sampled_at.ge(from.naive_utc()))
+            .order((health_samples::sampled_at.asc(), health_samples::id.asc()))
+            .select((
+                health_samples::sampled_at,
+                health_samples::component,
+                health_samples::status,
+            ))
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let mut samples: Vec<HealthSample> = Vec::new();
+        for row in rows {
+            let sampled_at = DateTime::from_naive_utc_and_offset(row.sampled_at, Utc);
+            if samples
+                .last()
+                .is_none_or(|last| last.sampled_at != sampled_at)
+            {
+                samples.push(HealthSample {
+                    sampled_at,
+                    status: String::new(),
+                    components: BTreeMap::new(),
+                });
+            }
+            let sample = samples.last_mut().expect("sample was just pushed");
+            if row.component == "overall" {
+                sample.status = row.status;
+            } else {
+                sample.components.insert(row.component, row.status);
+            }
+        }
+
+        Ok(samples)
+    }
+
+    async fn restore_from_database(&self) {
+        match self.load_from_database(Utc::now() - memory_span()) {
+            Ok(restored) => {
+                debug!("Restored {} health samples", restored.len());
+                let mut samples = self.samples.write().await;
+                samples.clear();
+                samples.extend(restored.into_iter().rev().take(MEMORY_CAPACITY).rev());
+            }
+            Err(e) => warn!("Failed to restore health history: {:?}", e),
+        }
+    }
+}
+
+fn memory_span() -> Duration {
+    Duration::seconds(SAMPLE_INTERVAL_SECONDS * MEMORY_CAPACITY as i64)
+}
+
+fn parse_window(window: &str) -> Result<Duration, LunarbaseError> {
+    let invalid = || {
+        LunarbaseError::BadRequest(format!(
+            "Invalid window '{}'. Use minutes, hours or days such as 30m, 24h or 7d, up to {}d",
+            window, MAX_WINDOW_DAYS
+        ))
+    };
+
+    let split = window.len().checked_sub(1).ok_or_else(invalid)?;
+    let (amount, unit) = window.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let duration = match unit {
+        "m" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        _ => return Err(invalid()),
+    };
+
+    if amount <= 0 || duration > Duration::days(MAX_WINDOW_DAYS) {
+        return Err(invalid());
+    }
+    Ok(duration)
+}
+
+fn summarize(
+    window: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    persisted: bool,
+    loaded: Vec<HealthSample>,
+) -> HealthHistoryResponse {
+    let overall = uptime(
+        loaded.iter().map(|s| (s.sampled_at, s.status.as_str())),
+        from,
+    );
+
+    let names: std::collections::BTreeSet<&String> =
+        loaded.iter().flat_map(|s| s.components.keys()).collect();
+    let components = names
+        .into_iter()
+        .filter_map(|name| {
+            let statuses = loaded.iter().filter_map(|sample| {
+                sample
+                    .components
+                    .get(name)
+                    .map(|status| (sample.sampled_at, status.as_str()))
+            });
+            uptime(statuses, from).map(|uptime| (name.clone(), uptime))
+        })
+        .collect();
+
+    HealthHistoryResponse {
+        window: window.to_string(),
+        from,
+        to,
+        sample_interval_seconds: SAMPLE_INTERVAL_SECONDS,
+        persisted,
+        overall,
+        components,
+        samples: loaded
+            .into_iter()
+            .filter(|sample| sample.sampled_at >= from)
+            .collect(),
+    }
+}
+
+/// `statuses` must be in sampling order. Transitions are tracked over all of
+/// them, uptime only over those from `from` on.
+fn uptime<'a>(
+    statuses: impl Iterator<Item = (DateTime<Utc>, &'a str)>,
+    from: DateTime<Utc>,
+) -> Option<ComponentUptime> {
+    let mut current: Option<(&str, DateTime<Utc>)> = None;
+    let mut previous_status = None;
+    let mut healthy_samples = 0;
+    let mut counted_samples = 0;
+
+    for (sampled_at, status) in statuses {
+        match current {
+            Some((current_status, _)) if current_status == status => {}
+            Some((current_status, _)) => {
+                previous_status = Some(current_status.to_string());
+                current = Some((status, sampled_at));
+            }
+            None => current = Some((status, sampled_at)),
+        }
+
+        if sampled_at >= from && status != COMPONENT_DISABLED && status != COMPONENT_NOT_CONFIGURED
+        {
+            counted_samples += 1;
+            if status == COMPONENT_HEALTHY {
+                healthy_samples += 1;
+            }
+        }
+    }
+
+    let (status, since) = current?;
+    Some(ComponentUptime {
+        status: status.to_string(),
+        since,
+        previous_status,
+        uptime_percentage: (counted_samples > 0)
+            .then(|| (healthy_samples as f64 / counted_samples as f64 * 10_000.0).round() / 100.0),
+        healthy_samples,
+        counted_samples,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::health_service::COMPONENT_DEGRADED;
+
+    #[test]
+    fn test_windows_are_parsed_up_to_the_maximum() {
+        assert_eq!(parse_window("30m").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_window("24h").unwrap(), Duration::hours(24));
+        assert_eq!(parse_window("30d").unwrap(), Duration::days(30));
+
+        for window in ["", "h", "0h", "-1h", "24", "1w", "31d", "1.5h"] {
+            assert!(parse_window(window).is_err(), "{}", window);
+        }
+    }
+
+    #[test]
+    fn test_uptime_ignores_unconfigured_samples_and_tracks_the_last_transition() {
+        let start = Utc::now() - Duration::minutes(10);
+        let at = |minute: i64| start + Duration::minutes(minute);
+        let statuses = [
+            (at(0), COMPONENT_HEALTHY),
+            (at(1), COMPONENT_NOT_CONFIGURED),
+            (at(2), COMPONENT_HEALTHY),
+            (at(3), COMPONENT_HEALTHY),
+            (at(4), COMPONENT_DEGRADED),
+            (at(5), COMPONENT_DEGRADED),
+        ];
+
+        let uptime = uptime(statuses.into_iter(), at(1)).unwrap();
+        assert_eq!(uptime.status, COMPONENT_DEGRADED);
+        assert_eq!(uptime.since, at(4));
+        assert_eq!(uptime.previous_status.as_deref(), Some(COMPONENT_HEALTHY));
+        assert_eq!(uptime.counted_samples, 4);
+        assert_eq!(uptime.healthy_samples, 2);
+        assert_eq!(uptime.uptime_percentage, Some(50.0));
+    }
+
+    #[test]
+    fn test_status_before_the_window_still_dates_the_transition() {
+        let start = Utc::now() - Duration::hours(3);
+        let statuses = [
+            (start, COMPONENT_UNHEALTHY),
+            (start + Duration::hours(2), COMPONENT_UNHEALTHY),
+        ];
+
+        let uptime = uptime(statuses.into_iter(), start + Duration::hours(1)).unwrap();
+        assert_eq!(uptime.since, start);
+        assert_eq!(uptime.previous_status, None);
+        assert_eq!(uptime.uptime_percentage, Some(0.0));
+    }
+}
@@ -0,0 +1,440 @@
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use utoipa::ToSchema;
+
+use crate::services::{
+    BackupService, CollectionService, ConfigurationAccess, ConfigurationManager, EmailService,
+    S3Service, TlsStatus,
+};
+use crate::utils::OAuthService;
+
+pub const COMPONENT_HEALTHY: &str = "healthy";
+pub const COMPONENT_DEGRADED: &str = "degraded";
+pub const COMPONENT_UNHEALTHY: &str = "unhealthy";
+pub const COMPONENT_DISABLED: &str = "disabled";
+pub const COMPONENT_NOT_CONFIGURED: &str = "not_configured";
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ComponentHealth {
+    #[schema(example = "healthy")]
+    pub status: String,
+    #[schema(example = "Bucket reachable")]
+    pub message: Option<String>,
+    #[schema(example = 42)]
+    pub latency_ms: Option<u64>,
+    pub checked_at: String,
+}
+
+impl ComponentHealth {
+    pub(crate) fn new(status: &str, message: Option<String>, latency_ms: Option<u64>) -> Self {
+        Self {
+            status: status.to_string(),
+            message,
+            latency_ms,
+            checked_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Disabled and unconfigured components never degrade the overall status.
+    pub fn is_degraded(&self) -> bool {
+        self.status == COMPONENT_DEGRADED || self.status == COMPONENT_UNHEALTHY
+    }
+}
+
+/// Degraded dependencies are reported but only the database makes the service
+/// unhealthy.
+pub fn overall_status<'a>(
+    database_healthy: bool,
+    component_statuses: impl IntoIterator<Item = &'a str>,
+) -> &'static str {
+    if !database_healthy {
+        "unhealthy"
+    } else if component_statuses
+        .into_iter()
+        .any(|status| status == COMPONENT_DEGRADED || status == COMPONENT_UNHEALTHY)
+    {
+        "degraded"
+    } else {
+        "healthy"
+    }
+}
+
+/// The worst of `statuses`. Disabled and unconfigured checks count as
+/// healthy.
+pub fn worst_status<'a>(statuses: impl IntoIterator<Item = &'a str>) -> &'static str {
+    statuses
+        .into_iter()
+        .fold(COMPONENT_HEALTHY, |worst, status| match status {
+            COMPONENT_UNHEALTHY => COMPONENT_UNHEALTHY,
+            COMPONENT_DEGRADED if worst != COMPONENT_UNHEALTHY => COMPONENT_DEGRADED,
+            _ => worst,
+        })
+}
+
+/// Tracks whether the server should still receive traffic. Cleared when
+/// graceful shutdown starts so readiness probes fail before the listener closes.
+#[derive(Clone, Default)]
+pub struct ReadinessState {
+    draining: Arc<AtomicBool>,
+}
+
+impl ReadinessState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mark_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Clone)]
+pub struct HealthService {
+    s3_service: Option<Arc<S3Service>>,
+    email_service: EmailService,
+    oauth_service: OAuthService,
+    backup_service: Option<BackupService>,
+    tls_status: TlsStatus,
+    collection_service: CollectionService,
+    config_manager: ConfigurationManager,
+    cache: Arc<RwLock<HashMap<&'static str, (Instant, ComponentHealth)>>>,
+}
+
+impl ConfigurationAccess for HealthService {
+    fn config_manager(&self) -> &ConfigurationManager {
+        &self.config_manager
+    }
+}
+
+impl HealthService {
+    pub fn new(
+        s3_service: Option<Arc<S3Service>>,
+        email_service: EmailService,
+        oauth_service: OAuthService,
+        backup_service: Option<BackupService>,
+        tls_status: TlsStatus,
+        collection_service: CollectionService,
+        config_manager: ConfigurationManager,
+    ) -> Self {
+        Self {
+            s3_service,
+            email_service,
+            oauth_service,
+            backup_service,
+            tls_status,
+            collection_service,
+            config_manager,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Runs every dependency check concurrently, reusing results that are
+    /// younger than the configured cache interval.
+    pub async fn check_components(&self) -> BTreeMap<String, ComponentHealth> {
+        let (s3, email, oauth, backup, collections) = tokio::join!(
+            self.cached("s3", self.get_health_check_s3(), self.check_s3()),
+            self.cached("email", self.get_health_check_email(), self.check_email()),
+            self.cached("oauth", self.get_health_check_oauth(), self.check_oauth()),
+            self.cached(
+                "backup",
+                self.get_health_check_backup(),
+                self.check_backup()
+            ),
+            self.cached_for(
+                "collections",
+                self.get_health_check_collections(),
+                self.get_health_check_collections_cache_seconds(),
+                self.check_collections()
+            ),
+        );
+
+        BTreeMap::from([
+            ("s3".to_string(), s3),
+            ("email".to_string(), email),
+            ("oauth".to_string(), oauth),
+            ("backup".to_string(), backup),
+            ("collections".to_string(), collections),
+            ("tls".to_string(), self.check_tls().await),
+            ("schema".to_string(), self.check_schema()),
+        ])
+    }
+
+    async fn cached(
+        &self,
+        name: &'static str,
+        enabled: impl Future<Output = bool>,
+        check: impl Future<Output = ComponentHealth>,
+    ) -> ComponentHealth {
+        self.cached_for(name, enabled, self.get_health_check_cache_seconds(), check)
+            .await
+    }
+
+    async fn cached_for(
+        &self,
+        name: &'static str,
+        enabled: impl Future<Output = bool>,
+        cache_seconds: impl Future<Output = u32>,
+        check: impl Future<Output = ComponentHealth>,
+    ) -> ComponentHealth {
+        if !enabled.await {
+            return ComponentHealth::new(
+                COMPONENT_DISABLED,
+                Some("Check disabled in settings".to_string()),
+                None,
+            );
+        }
+
+        let cache_for = Duration::from_secs(cache_seconds.await as u64);
+        if let Some((checked_at, health)) = self.cache.read().await.get(name)
+            && checked_at.elapsed() < cache_for
+        {
+            return health.clone();
+        }
+
+        let timeout = Duration::from_millis(self.get_health_check_timeout_ms().await.max(1) as u64);
+        let started = Instant::now();
+        let health = match tokio::time::timeout(timeout, check).await {
+            Ok(mut health) => {
+                if health.latency_ms.is_none() && health.status != COMPONENT_NOT_CONFIGURED {
+                    health.latency_ms = Some(started.elapsed().as_millis() as u64);
+                }
+                health
+            }
+            Err(_) => ComponentHealth::new(
+                COMPONENT_UNHEALTHY,
+                Some(format!("Check timed out after {} ms", timeout.as_millis())),
+                Some(timeout.as_millis() as u64),
+            ),
+        };
+
+        self.cache
+            .write()
+            .await
+            .insert(name, (Instant::now(), health.clone()));
+
+        health
+    }
+
+    async fn check_s3(&self) -> ComponentHealth {
+        let Some(s3_service) = &self.s3_service else {
+            return ComponentHealth::new(COMPONENT_NOT_CONFIGURED, None, None);
+        };
+
+        match s3_service.health_check().await {
+            Ok(()) => ComponentHealth::new(
+                COMPONENT_HEALTHY,
+                Some(format!("Bucket '{}' reachable", s3_service.bucket_name())),
+                None,
+            ),
+            Err(e) => ComponentHealth::new(COMPONENT_UNHEALTHY, Some(e.to_string()), None),
+        }
+    }
+
+    async fn check_email(&self) -> ComponentHealth {
+        if !self.email_service.is_configured() {
+            return ComponentHealth::new(COMPONENT_NOT_CONFIGURED, None, None);
+        }
+
+        match self.email_service.check_connectivity().await {
+            Ok(()) => ComponentHealth::new(COMPONENT_HEALTHY, None, None),
+            Err(e) => ComponentHealth::new(COMPONENT_UNHEALTHY, Some(e), None),
+        }
+    }
+
+    async fn check_oauth(&self) -> ComponentHealth {
+        let results = self.oauth_service.check_providers().await;
+        if results.is_empty() {
+            return ComponentHealth::new(COMPONENT_NOT_CONFIGURED, None, None);
+        }
+
+        let failures: Vec<String> = results
+            .iter()
+            .filter_map(|(provider, result)| {
+                result
+                    .as_ref()
+                    .err()
+                    .map(|e| format!("{}: {}", provider, e))
+            })
+            .collect();
+
+        if failures.is_empty() {
+            let providers: Vec<&str> = results.iter().map(|(provider, _)| *provider).collect();
+            ComponentHealth::new(COMPONENT_HEALTHY, Some(providers.join(", ")), None)
+        } else {
+            ComponentHealth::new(COMPONENT_UNHEALTHY, Some(failures.join("; ")), None)
+        }
+    }
+
+    /// Local state only, so it is neither cached nor subject to the timeout.
+    async fn check_tls(&self) -> ComponentHealth {
+        let report = self.tls_status.report();
+        if !report.enabled {
+            return ComponentHealth::new(COMPONENT_NOT_CONFIGURED, None, None);
+        }
+
+        let renewal_note = match &report.last_renewal {
+            Some(attempt) if !attempt.succeeded => format!(
+                "; last renewal attempt failed: {}",
+                attempt.error.as_deref().unwrap_or("unknown error")
+            ),
+            _ => String::new(),
+        };
+        let Some(certificate) = &report.certificate else {
+            return ComponentHealth::new(
+                COMPONENT_DEGRADED,
+                Some(format!("No certificate issued yet{}", renewal_note)),
+                None,
+            );
+        };
+
+        let warning_period =
+            chrono::Duration::days(self.get_tls_expiry_warning_days().await as i64);
+        let remaining = certificate.not_after - Utc::now();
+        let (status, message) = if remaining <= chrono::Duration::zero() {
+            (
+                COMPONENT_UNHEALTHY,
+                format!(
+                    "Certificate expired at {}",
+                    certificate.not_after.to_rfc3339()
+                ),
+            )
+        } else if remaining < warning_period {
+            (
+                COMPONENT_DEGRADED,
+                format!(
+                    "Certificate expires in {} days at {}",
+                    remaining.num_days(),
+                    certificate.not_after.to_rfc3339()
+                ),
+            )
+        } else {
+            (
+                COMPONENT_HEALTHY,
+                format!(
+                    "Certificate valid until {}",
+                    certificate.not_after.to_rfc3339()
+                ),
+            )
+        };
+
+        ComponentHealth::new(status, Some(format!("{}{}", message, renewal_note)), None)
+    }
+
+    /// Counts the collections whose records table is not healthy. Skips the
+    /// integrity check, which is left to the per-collection endpoint.
+    async fn check_collections(&self) -> ComponentHealth {
+        let collections = match self.collection_service.list_collections().await {
+            Ok(collections) => collections,
+            Err(e) => return ComponentHealth::new(COMPONENT_UNHEALTHY, Some(e.to_string()), None),
+        };
+
+        let mut unhealthy = Vec::new();
+        let mut status = COMPONENT_HEALTHY;
+        for collection in &collections {
+            let report = self
+                .collection_service
+                .collection_health(&collection.name, false)
+                .await;
+            let collection_status = report
+                .as_ref()
+                .map_or(COMPONENT_UNHEALTHY, |report| report.status.as_str());
+            if collection_status != COMPONENT_HEALTHY {
+                unhealthy.push(collection.name.as_str());
+                status = worst_status([status, collection_status]);
+            }
+        }
+
+        let message = if unhealthy.is_empty() {
+            format!("All {} collections healthy", collections.len())
+        } else {
+            format!(
+                "{} of {} collections unhealthy: {}",
+                unhealthy.len(),
+                collections.len(),
+                unhealthy.join(", ")
+            )
+        };
+        ComponentHealth::new(status, Some(message), None)
+    }
+
+    /// Reports the orphans of the last sweep that are still in the database.
+    /// Local state only, like the TLS check.
+    fn check_schema(&self) -> ComponentHealth {
+        let Some(report) = self.collection_service.last_orphan_sweep() else {
+            return ComponentHealth::new(COMPONENT_NOT_CONFIGURED, None, None);
+        };
+
+        let remaining: Vec<&str> = report
+            .orphans
+            .iter()
+            .map(|orphan| orphan.name.as_str())
+            .filter(|name| !report.dropped.iter().any(|dropped| dropped == name))
+            .collect();
+        if remaining.is_empty() {
+            ComponentHealth::new(
+                COMPONENT_HEALTHY,
+                Some(format!(
+                    "No orphaned schema objects as of {}",
+                    report.swept_at
+                )),
+                None,
+            )
+        } else {
+            ComponentHealth::new(
+                COMPONENT_DEGRADED,
+                Some(format!(
+                    "{} orphaned schema object(s) left by interrupted schema changes: {}",
+                    remaining.len(),
+                    remaining.join(", ")
+                )),
+                None,
+            )
+        }
+    }
+
+    async fn check_backup(&self) -> ComponentHealth {
+        let Some(backup_service) = &self.backup_service else {
+            return ComponentHealth::new(COMPONENT_NOT_CONFIGURED, None, None);
+        };
+
+        let max_age = chrono::Duration::hours(self.get_backup_max_age_hours().await as i64);
+
+        match backup_service.last_successful_backup().await {
+            Some(last_backup) if Utc::now() - last_backup <= max_age => ComponentHealth::new(
+                COMPONENT_HEALTHY,
+                Some(format!("Last backup at {}", last_backup.to_rfc3339())),
+                None,
+            ),
+            Some(last_backup) => ComponentHealth::new(
+                COMPONENT_DEGRADED,
+                Some(format!(
+                    "Last successful backup at {} is older than {} hours",
+                    last_backup.to_rfc3339(),
+                    max_age.num_hours()
+                )),
+                None,
+            ),
+            // A freshly started server has not had a chance to run its schedule yet.
+            None if Utc::now() - backup_service.started_at() <= max_age => ComponentHealth::new(
+                COMPONENT_HEALTHY,
+                Some("No backup has run yet".to_string()),
+                None,
+            ),
+            None => ComponentHealth::new(
+                COMPONENT_DEGRADED,
+                Some("No successful backup found".to_string()),
+                None,
+            ),
+        }
+    }
+}
@@ -0,0 +1,489 @@
+use axum::http::HeaderMap;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use hmac::{Hmac, Mac};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::models::{
+    CreateIngestEndpointRequest, CreateRecordRequest, IngestEndpoint, IngestEndpointResponse,
+    IngestFailure, IngestFailureResponse, NewIngestEndpoint, NewIngestFailure, RecordResponse,
+};
+use crate::schema::{ingest_endpoints, ingest_failures};
+use crate::services::CollectionService;
+use crate::utils::LunarbaseError;
+
+type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+const DEFAULT_SIGNATURE_HEADER: &str = "X-Signature-256";
+const MAX_STORED_PAYLOAD_BYTES: usize = 64 * 1024;
+/// Dead-letter entries kept per endpoint; older ones are dropped as new ones
+/// are stored.
+const MAX_STORED_FAILURES: i64 = 1000;
+
+#[derive(Clone)]
+pub struct IngestService {
+    pub pool: DbPool,
+    collection_service: CollectionService,
+}
+
+impl IngestService {
+    pub fn new(pool: DbPool, collection_service: CollectionService) -> Self {
+        Self {
+            pool,
+            collection_service,
+        }
+    }
+
+    pub async fn create_endpoint(
+        &self,
+        request: CreateIngestEndpointRequest,
+        created_by: i32,
+    ) -> Result<IngestEndpointResponse, LunarbaseError> {
+        let mut errors = Vec::new();
+
+        if request.name.trim().is_empty() {
+            errors.push("Endpoint name cannot be empty".to_string());
+        }
+        if request.field_mapping.is_empty() {
+            errors.push("Field mapping must contain at least one field".to_string());
+        }
+        if request
+            .hmac_secret
+            .as_ref()
+            .is_some_and(|secret| secret.is_empty())
+        {
+            errors.push("HMAC secret cannot be empty".to_string());
+        }
+
+        let collection = self
+            .collection_service
+            .get_collection(&request.collection_name)
+            .await?;
+
+        for (field_name, expression) in &request.field_mapping {
+            // Endpoints are created by admins, so they may carry record ids over
+            let is_explicit_id = field_name == "id" && collection.allow_explicit_ids;
+            if !is_explicit_id
+                && !collection
+                    .schema
+                    .fields
+                    .iter()
+                    .any(|field| &field.name == field_name)
+            {
+                errors.push(format!(
+                    "Field '{}' does not exist in collection '{}'",
+                    field_name, request.collection_name
+                ));
+            }
+            if parse_path(expression).is_none() {
+                errors.push(format!(
+                    "Invalid payload path '{}' for field '{}'",
+                    expression, field_name
+                ));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(LunarbaseError::ValidationError(errors));
+        }
+
+        let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let field_mapping = serde_json::to_string(&request.field_mapping)
+            .map_err(|_| LunarbaseError::InternalError)?;
+
+        let new_endpoint = NewIngestEndpoint {
+            name: request.name.trim().to_string(),
+            token_hash: hash_token(&token),
+            collection_name: request.collection_name,
+            field_mapping,
+            hmac_secret: request.hmac_secret,
+            signature_header: request.signature_header,
+            is_active: true,
+            created_by,
+        };
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        diesel::insert_into(ingest_endpoints::table)
+            .values(&new_endpoint)
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let endpoint = ingest_endpoints::table
+            .filter(ingest_endpoints::token_hash.eq(&new_endpoint.token_hash))
+            .select(IngestEndpoint::as_select())
+            .first(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let mut response = endpoint.to_response();
+        response.url = Some(format!("/api/ingest/{}", token));
+        response.token = Some(token);
+        Ok(response)
+    }
+
+    pub async fn list_endpoints(&self) -> Result<Vec<IngestEndpointResponse>, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let endpoints = ingest_endpoints::table
+            .order(ingest_endpoints::id.asc())
+            .select(IngestEndpoint::as_select())
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(endpoints.iter().map(IngestEndpoint::to_response).collect())
+    }
+
+    pub async fn delete_endpoint(&self, endpoint_id: i32) -> Result<(), LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        diesel::delete(ingest_failures::table.filter(ingest_failures::endpoint_id.eq(endpoint_id)))
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let deleted = diesel::delete(ingest_endpoints::table.find(endpoint_id))
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        if deleted == 0 {
+            return Err(LunarbaseError::NotFound(
+                "Ingest endpoint not found".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub async fn list_failures(
+        &self,
+        endpoint_id: i32,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<IngestFailureResponse>, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        ingest_endpoints::table
+            .find(endpoint_id)
+            .select(IngestEndpoint::as_select())
+            .first(&mut conn)
+            .map_err(|_| LunarbaseError::NotFound("Ingest endpoint not found".to_string()))?;
+
+        let failures = ingest_failures::table
+            .filter(ingest_failures::endpoint_id.eq(endpoint_id))
+            .order(ingest_failures::id.desc())
+            .limit(limit)
+            .offset(offset)
+            .select(IngestFailure::as_select())
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(failures.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn ingest(
+        &self,
+        token: &str,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<RecordResponse, LunarbaseError> {
+        let endpoint = {
+            let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+            ingest_endpoints::table
+                .filter(ingest_endpoints::token_hash.eq(hash_token(token)))
+                .filter(ingest_endpoints::is_active.eq(true))
+                .select(IngestEndpoint::as_select())
+                .first(&mut conn)
+                .map_err(|_| LunarbaseError::NotFound("Ingest endpoint not found".to_string()))?
+        };
+
+        // Payloads that fail the signature check are not stored, so the
+        // dead-letter list only holds what the sender actually signed
+        Self::check_signature(&endpoint, headers, body)?;
+
+        match self.process_payload(&endpoint, body).await {
+            Ok(record) => {
+                debug!(
+                    "Ingested payload via endpoint {} into collection {}",
+                    endpoint.id, endpoint.collection_name
+                );
+                Ok(record)
+            }
+            Err(e) => {
+                self.record_failure(&endpoint, body, &e);
+                Err(e)
+            }
+        }
+    }
+
+    fn check_signature(
+        endpoint: &IngestEndpoint,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<(), LunarbaseError> {
+        if let Some(secret) = &endpoint.hmac_secret {
+            let header_name = endpoint
+                .signature_header
+                .as_deref()
+                .unwrap_or(DEFAULT_SIGNATURE_HEADER);
+            let signature = headers
+                .get(header_name)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| {
+                    LunarbaseError::Forbidden(format!("Missing signature header {}", header_name))
+                })?;
+
+            if !verify_signature(secret, signature, body) {
+                return Err(LunarbaseError::Forbidden(
+                    "Invalid payload signature".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn process_payload(
+        &self,
+        endpoint: &IngestEndpoint,
+        body: &[u8],
+    ) -> Result<RecordResponse, LunarbaseError> {
+        let payload: Value = serde_json::from_slice(body)
+            .map_err(|_| LunarbaseError::BadRequest("Payload is not valid JSON".to_string()))?;
+
+        let field_mapping = endpoint
+            .get_field_mapping()
+            .map_err(|_| LunarbaseError::InternalError)?;
+
+        let mut data = map_payload(&field_mapping, &payload);
+        if let Value::Object(ref mut map) = data {
+            map.insert("author_id".to_string(), Value::from(endpoint.created_by));
+            map.insert("owner_id".to_string(), Value::from(endpoint.created_by));
+        }
+
+        self.collection_service
+            .create_record_with_events(
+                &endpoint.collection_name,
+                CreateRecordRequest { data, files: None },
+                Some(endpoint.created_by),
+            )
+            .await
+    }
+
+    fn record_failure(&self, endpoint: &IngestEndpoint, body: &[u8], error: &LunarbaseError) {
+        let mut payload = String::from_utf8_lossy(body).to_string();
+        if payload.len() > MAX_STORED_PAYLOAD_BYTES {
+            let mut cut = MAX_STORED_PAYLOAD_BYTES;
+            while !payload.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            payload.truncate(cut);
+        }
+
+        let failure = NewIngestFailure {
+            endpoint_id: endpoint.id,
+            payload,
+            error: error.to_string(),
+        };
+
+        let result = self.pool.get().map(|mut conn| {
+            diesel::insert_into(ingest_failures::table)
+                .values(&failure)
+                .execute(&mut conn)?;
+
+            // Endpoints without a signature accept anything sent with the
+            // token, so keep only the newest entries of each
+            let newest_dropped = ingest_failures::table
+                .filter(ingest_failures::endpoint_id.eq(endpoint.id))
+                .order(ingest_failures::id.desc())
+                .offset(MAX_STORED_FAILURES)
+                .select(ingest_failures::id)
+                .first::<i32>(&mut conn)
+                .optional()?;
+            if let Some(newest_dropped) = newest_dropped {
+                diesel::delete(
+                    ingest_failures::table
+                        .filter(ingest_failures::endpoint_id.eq(endpoint.id))
+                        .filter(ingest_failures::id.le(newest_dropped)),
+                )
+                .execute(&mut conn)?;
+            }
+            Ok::<_, diesel::result::Error>(())
+        });
+
+        if !matches!(result, Ok(Ok(_))) {
+            warn!(
+                "Failed to store dead-letter entry for ingest endpoint {}",
+                endpoint.id
+            );
+        }
+    }
+}
+
+pub fn map_payload(field_mapping: &BTreeMap<String, String>, payload: &Value) -> Value {
+    let mut data = Map::new();
+
+    for (field_name, expression) in field_mapping {
+        if let Some(value) = resolve_path(payload, expression)
+            && !value.is_null()
+        {
+            data.insert(field_name.clone(), value.clone());
+        }
+    }
+
+    Value::Object(data)
+}
+
+pub fn resolve_path<'a>(payload: &'a Value, expression: &str) -> Option<&'a Value> {
+    let segments = parse_path(expression)?;
+    let mut current = payload;
+
+    for segment in segments {
+        current = match segment {
+            PathSegment::Key(key) => current.get(key)?,
+            PathSegment::Index(index) => current.get(index)?,
+        };
+    }
+
+    Some(current)
+}
+
+enum PathSegment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+fn parse_path(expression: &str) -> Option<Vec<PathSegment<'_>>> {
+    let expression = expression.trim();
+    let path = expression.strip_prefix('$').unwrap_or(expression);
+    let path = path.strip_prefix('.').unwrap_or(path);
+
+    let mut segments = Vec::new();
+    if path.is_empty() {
+        return Some(segments);
+    }
+
+    for part in path.split('.') {
+        let (key, mut rest) = match part.find('[') {
+            Some(position) => part.split_at(position),
+            None => (part, ""),
+        };
+
+        if key.is_empty() && rest.is_empty() {
+            return None;
+        }
+        if !key.is_empty() {
+            segments.push(PathSegment::Key(key));
+        }
+
+        while !rest.is_empty() {
+            let inner = rest.strip_prefix('[')?;
+            let end = inner.find(']')?;
+            segments.push(PathSegment::Index(inner[..end].parse().ok()?));
+            rest = &inner[end + 1..];
+        }
+    }
+
+    Some(segments)
+}
+
+fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn verify_signature(secret: &str, signature: &str, body: &[u8]) -> bool {
+    let signature = signature.trim();
+    let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+
+    let Some(expected) = decode_hex(signature) else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolve_path() {
+        let payload = json!({
+            "type": "charge.succeeded",
+            "data": {"object": {"amount": 1200, "items": [{"sku": "A1"}, {"sku": "B2"}]}}
+        });
+
+        assert_eq!(
+            resolve_path(&payload, "$.type"),
+            Some(&json!("charge.succeeded"))
+        );
+        assert_eq!(
+            resolve_path(&payload, "data.object.amount"),
+            Some(&json!(1200))
+        );
+        assert_eq!(
+            resolve_path(&payload, "$.data.object.items[1].sku"),
+            Some(&json!("B2"))
+        );
+        assert_eq!(resolve_path(&payload, "$.data.missing"), None);
+        assert_eq!(resolve_path(&payload, "$.data.object.items[5]"), None);
+        assert!(parse_path("$.data[x]").is_none());
+        assert!(parse_path("$.data..amount").is_none());
+    }
+
+    #[test]
+    fn test_map_payload_skips_missing_values() {
+        let mut mapping = BTreeMap::new();
+        mapping.insert("title".to_string(), "$.pull_request.title".to_string());
+        mapping.insert("merged".to_string(), "$.pull_request.merged".to_string());
+        mapping.insert("missing".to_string(), "$.nope".to_string());
+
+        let payload = json!({"pull_request": {"title": "Fix bug", "merged": true}});
+        let data = map_payload(&mapping, &payload);
+
+        assert_eq!(data, json!({"title": "Fix bug", "merged": true}));
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let body = b"{\"hello\":\"world\"}";
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(body);
+        let signature: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        assert!(verify_signature("secret", &signature, body));
+        assert!(verify_signature(
+            "secret",
+            &format!("sha256={}", signature),
+            body
+        ));
+        assert!(!verify_signature("other", &signature, body));
+        assert!(!verify_signature("secret", "not-hex", body));
+    }
+}
@@ -0,0 +1,229 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use tracing::{debug, info, warn};
+
+use crate::models::{LockedAccount, User};
+use crate::schema::users;
+use crate::services::{ConfigurationAccess, ConfigurationManager};
+use crate::utils::LunarbaseError;
+
+type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+/// How often the cleanup task re-reads its interval while it is disabled.
+const DISABLED_CLEANUP_POLL_SECONDS: u64 = 60;
+
+/// What a failed login did to the account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailedLoginOutcome {
+    Counted,
+    Locked {
+        until: NaiveDateTime,
+    },
+    /// An exempt admin reached the limit; an alert was raised instead of locking
+    Alerted,
+}
+
+/// Applies the account lockout policy driven by the `auth.*` settings.
+#[derive(Clone)]
+pub struct LockoutService {
+    pool: DbPool,
+    config_manager: ConfigurationManager,
+}
+
+impl ConfigurationAccess for LockoutService {
+    fn config_manager(&self) -> &ConfigurationManager {
+        &self.config_manager
+    }
+}
+
+impl LockoutService {
+    pub fn new(pool: DbPool, config_manager: ConfigurationManager) -> Self {
+        Self {
+            pool,
+            config_manager,
+        }
+    }
+
+    /// Lockout length in minutes. With progressive lockout every lockout since the
+    /// last successful login doubles the base duration, up to `max_minutes`.
+    pub fn lockout_duration_minutes(
+        base_minutes: i32,
+        max_minutes: i32,
+        progressive: bool,
+        previous_lockouts: i32,
+    ) -> i64 {
+        let base = i64::from(base_minutes.max(1));
+        if !progressive {
+            return base;
+        }
+
+        let doubled = base.saturating_mul(1i64 << previous_lockouts.clamp(0, 30));
+        doubled.min(i64::from(max_minutes).max(base))
+    }
+
+    pub async fn record_failed_login(
+        &self,
+        user: &User,
+    ) -> Result<FailedLoginOutcome, LunarbaseError> {
+        let max_attempts = self.get_max_login_attempts().await;
+        let attempts = user.failed_login_attempts + 1;
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::DatabaseError)?;
+
+        if attempts < max_attempts || self.is_exempt_admin(user).await {
+            diesel::update(users::table.find(user.id))
+                .set(users::failed_login_attempts.eq(attempts))
+                .execute(&mut conn)
+                .map_err(|_| LunarbaseError::DatabaseError)?;
+
+            if attempts < max_attempts {
+                return Ok(FailedLoginOutcome::Counted);
+            }
+
+            warn!(
+                "ALERT: exempt admin {} ({}) reached {} failed login attempts; account was not locked",
+                user.id, user.email, attempts
+            );
+            return Ok(FailedLoginOutcome::Alerted);
+        }
+
+        let minutes = Self::lockout_duration_minutes(
+            self.get_lockout_duration_minutes().await,
+            self.get_max_lockout_duration_minutes().await,
+            self.get_progressive_lockout_enabled().await,
+            user.lockout_count,
+        );
+        let until = Utc::now().naive_utc() + Duration::minutes(minutes);
+
+        diesel::update(users::table.find(user.id))
+            .set((
+                users::failed_login_attempts.eq(attempts),
+                users::locked_until.eq(Some(until)),
+                users::lockout_count.eq(user.lockout_count + 1),
+            ))
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        info!(
+            "Locked user {} for {} minute(s) after {} failed login attempts",
+            user.id, minutes, attempts
+        );
+
+        Ok(FailedLoginOutcome::Locked { until })
+    }
+
+    async fn is_exempt_admin(&self, user: &User) -> bool {
+        user.role == "admin"
+            && self
+                .get_lockout_exempt_admins()
+                .await
+                .iter()
+                .any(|email| email.eq_ignore_ascii_case(&user.email))
+    }
+
+    /// Clears every lockout that has expired and resets its attempt counter, so an
+    /// expired lock is gone from the database rather than only ignored by `is_locked()`.
+    pub fn clear_expired_locks(&self) -> Result<usize, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::DatabaseError)?;
+
+        diesel::update(users::table.filter(users::locked_until.le(Utc::now().naive_utc())))
+            .set((
+                users::locked_until.eq(None::<NaiveDateTime>),
+                users::failed_login_attempts.eq(0),
+            ))
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)
+    }
+
+    /// Runs `clear_expired_locks` every `auth.expired_lock_cleanup_interval_seconds`;
+    /// the interval is re-read on every run, so changing it needs no restart.
+    pub fn start_expired_lock_cleanup(&self) {
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let interval = service.get_expired_lock_cleanup_interval_seconds().await;
+                if interval == 0 {
+                    tokio::time::sleep(std::time::Duration::from_secs(
+                        DISABLED_CLEANUP_POLL_SECONDS,
+                    ))
+                    .await;
+                    continue;
+                }
+
+                match service.clear_expired_locks() {
+                    Ok(0) => {}
+                    Ok(cleared) => debug!("Cleared {} expired account lockout(s)", cleared),
+                    Err(e) => warn!("Failed to clear expired account lockouts: {:?}", e),
+                }
+
+                tokio::time::sleep(std::time::Duration::from_secs(u64::from(interval))).await;
+            }
+        });
+    }
+
+    /// Accounts that are locked right now, soonest to expire first.
+    pub fn list_locked_accounts(&self) -> Result<Vec<LockedAccount>, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::DatabaseError)?;
+        let now = Utc::now().naive_utc();
+
+        let locked: Vec<User> = users::table
+            .filter(users::locked_until.gt(now))
+            .order(users::locked_until.asc())
+            .select(User::as_select())
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(locked
+            .into_iter()
+            .filter_map(|user| {
+                let locked_until = user.locked_until?;
+                Some(LockedAccount {
+                    user_id: user.id,
+                    email: user.email,
+                    username: user.username,
+                    role: user.role,
+                    locked_until: DateTime::from_naive_utc_and_offset(locked_until, Utc),
+                    remaining_seconds: (locked_until - now).num_seconds(),
+                    failed_login_attempts: user.failed_login_attempts,
+                    lockout_count: user.lockout_count,
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lockout_duration_is_fixed_unless_progressive() {
+        assert_eq!(
+            LockoutService::lockout_duration_minutes(15, 1440, false, 3),
+            15
+        );
+        assert_eq!(
+            LockoutService::lockout_duration_minutes(0, 1440, false, 0),
+            1
+        );
+    }
+
+    #[test]
+    fn test_progressive_lockout_doubles_up_to_cap() {
+        let durations: Vec<i64> = (0..5)
+            .map(|previous| LockoutService::lockout_duration_minutes(15, 100, true, previous))
+            .collect();
+        assert_eq!(durations, vec![15, 30, 60, 100, 100]);
+
+        assert_eq!(
+            LockoutService::lockout_duration_minutes(15, 1440, true, i32::MAX),
+            1440
+        );
+        assert_eq!(
+            LockoutService::lockout_duration_minutes(30, 10, true, 2),
+            30
+        );
+    }
+}
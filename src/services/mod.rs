@@ -1,23 +1,76 @@
 pub mod admin_service;
 pub mod backup_service;
 pub mod collection_service;
+pub mod collection_template_service;
+pub mod collection_view_service;
 pub mod configuration_manager;
 pub mod configuration_service;
+pub mod delete_confirmations;
+pub mod email_rate_limiter;
 pub mod email_service;
+pub mod event_coalescer;
+pub mod health_recorder;
+pub mod health_service;
+pub mod ingest_service;
+pub mod lockout_service;
+pub mod notification_service;
 pub mod ownership_service;
+pub mod password_service;
+pub mod permission_audit_service;
+pub mod permission_cache;
 pub mod permission_service;
+pub mod query_cache;
+pub mod query_limiter;
+pub mod quota_warning_service;
+pub mod record_activity_service;
+pub mod record_cache;
+pub mod record_share_service;
 pub mod s3_service;
+pub mod tls_status;
+pub mod upload_scanner;
+pub mod usage_service;
+pub mod websocket_limits;
+pub mod websocket_metrics;
 pub mod websocket_service;
+pub mod workspace_service;
 
-pub use admin_service::AdminService;
+pub use admin_service::{
+    ACCOUNT_DEACTIVATED_CLOSE_CODE, AdminBootstrapOutcome, AdminService, LoginMethod,
+};
 pub use backup_service::{
     BackupError, BackupResult, BackupService, create_backup_service_from_config,
+    create_pre_migration_backup,
 };
 pub use collection_service::CollectionService;
+pub use collection_template_service::CollectionTemplateService;
+pub use collection_view_service::CollectionViewService;
 pub use configuration_manager::{ConfigurationAccess, ConfigurationManager};
 pub use configuration_service::ConfigurationService;
+pub use delete_confirmations::{DELETE_CONFIRMATION_TTL, DeleteConfirmations};
+pub use email_rate_limiter::EmailRateLimiter;
 pub use email_service::EmailService;
+pub use event_coalescer::{BroadcastEvent, EventCoalescer};
+pub use health_recorder::HealthRecorder;
+pub use health_service::{ComponentHealth, HealthService, ReadinessState};
+pub use ingest_service::IngestService;
+pub use lockout_service::{FailedLoginOutcome, LockoutService};
+pub use notification_service::NotificationService;
 pub use ownership_service::OwnershipService;
+pub use password_service::{PasswordHashParams, PasswordService};
+pub use permission_audit_service::PermissionAuditService;
+pub use permission_cache::PermissionCache;
 pub use permission_service::PermissionService;
+pub use query_cache::{CachedQueryResult, QueryCache, QueryCacheStats};
+pub use query_limiter::QueryLimiter;
+pub use quota_warning_service::{QUOTA_WARNING_HEADER, QuotaWarningService};
+pub use record_activity_service::RecordActivityService;
+pub use record_cache::{RecordCache, RecordCacheStats};
+pub use record_share_service::RecordShareService;
 pub use s3_service::{FileUploadResult, S3Service, S3ServiceError, create_s3_service_from_config};
+pub use tls_status::{TlsStatus, TlsStatusCache};
+pub use upload_scanner::{ScanRejection, UploadOrigin, UploadScanner};
+pub use usage_service::UsageService;
+pub use websocket_limits::{LIMIT_VIOLATIONS_CLOSE_CODE, WebSocketBans, WebSocketLimits};
+pub use websocket_metrics::WebSocketMetrics;
 pub use websocket_service::{WebSocketService, WebSocketStats};
+pub use workspace_service::WorkspaceService;
@@ -0,0 +1,214 @@
+use std::sync::Arc;
+
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use tracing::warn;
+
+use crate::models::{
+    ListNotificationsQuery, MarkNotificationsReadResponse, NewNotification, Notification,
+    NotificationContent, NotificationListResponse, NotificationPush,
+};
+use crate::schema::{notifications, users};
+use crate::services::WebSocketService;
+use crate::utils::LunarbaseError;
+
+type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 100;
+
+/// Stores notifications for one user and pushes them, along with the unread
+/// count, to whatever connections that user has open.
+#[derive(Clone)]
+pub struct NotificationService {
+    pool: DbPool,
+    websocket_service: Arc<WebSocketService>,
+}
+
+impl NotificationService {
+    pub fn new(pool: DbPool, websocket_service: Arc<WebSocketService>) -> Self {
+        Self {
+            pool,
+            websocket_service,
+        }
+    }
+
+    /// Stores `content` for `user_id` and pushes it live.
+    pub async fn notify(
+        &self,
+        user_id: i32,
+        content: NotificationContent,
+    ) -> Result<Notification, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let new_notification = NewNotification {
+            user_id,
+            kind: content.kind.as_str().to_string(),
+            title: content.title,
+            body: content.body,
+            data: content.data.map(|data| data.to_string()),
+        };
+        let notification = conn
+            .immediate_transaction(|conn| {
+                diesel::insert_into(notifications::table)
+                    .values(&new_notification)
+                    .execute(conn)?;
+                notifications::table
+                    .filter(notifications::user_id.eq(user_id))
+                    .order(notifications::id.desc())
+                    .select(Notification::as_select())
+                    .first::<Notification>(conn)
+            })
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        let unread_count = Self::count_unread(&mut conn, user_id)?;
+        drop(conn);
+
+        self.websocket_service
+            .notify_user(
+                user_id,
+                NotificationPush::Created {
+                    notification: notification.to_response(),
+                    unread_count,
+                },
+            )
+            .await;
+
+        Ok(notification)
+    }
+
+    /// Sends `content` to every active admin. Failures are logged rather than
+    /// returned, since the work being reported on has already happened.
+    pub async fn notify_admins(&self, content: NotificationContent) {
+        let admin_ids = self.pool.get().ok().and_then(|mut conn| {
+            users::table
+                .filter(users::role.eq("admin"))
+                .filter(users::is_active.eq(true))
+                .select(users::id)
+                .load::<i32>(&mut conn)
+                .ok()
+        });
+        let Some(admin_ids) = admin_ids else {
+            warn!(
+                "Could not look up admins to notify of {}",
+                content.kind.as_str()
+            );
+            return;
+        };
+
+        for admin_id in admin_ids {
+            if let Err(e) = self.notify(admin_id, content.clone()).await {
+                warn!("Failed to notify admin {}: {}", admin_id, e);
+            }
+        }
+    }
+
+    /// Like [`Self::notify`], for callers that should not fail when the
+    /// notification cannot be stored.
+    pub async fn try_notify(&self, user_id: i32, content: NotificationContent) {
+        let kind = content.kind;
+        if let Err(e) = self.notify(user_id, content).await {
+            warn!(
+                "Failed to notify user {} of {}: {}",
+                user_id,
+                kind.as_str(),
+                e
+            );
+        }
+    }
+
+    pub async fn list(
+        &self,
+        user_id: i32,
+        query: &ListNotificationsQuery,
+    ) -> Result<NotificationListResponse, LunarbaseError> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+        let offset = query.offset.unwrap_or(0).max(0);
+        let unread_only = query.unread_only.unwrap_or(false);
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let mut page = notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .into_boxed();
+        let mut total = notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .into_boxed();
+        if unread_only {
+            page = page.filter(notifications::read_at.is_null());
+            total = total.filter(notifications::read_at.is_null());
+        }
+
+        let page = page
+            .order((notifications::created_at.desc(), notifications::id.desc()))
+            .limit(limit)
+            .offset(offset)
+            .select(Notification::as_select())
+            .load::<Notification>(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        let total = total
+            .count()
+            .get_result::<i64>(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(NotificationListResponse {
+            notifications: page.iter().map(Notification::to_response).collect(),
+            total,
+            unread_count: Self::count_unread(&mut conn, user_id)?,
+        })
+    }
+
+    pub async fn unread_count(&self, user_id: i32) -> Result<i64, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::DatabaseError)?;
+        Self::count_unread(&mut conn, user_id)
+    }
+
+    /// Marks the given notifications of `user_id`, or all of them, as read and
+    /// pushes the new unread count when it changed. Ids of other users'
+    /// notifications are ignored.
+    pub async fn mark_read(
+        &self,
+        user_id: i32,
+        ids: Option<&[i32]>,
+    ) -> Result<MarkNotificationsReadResponse, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let unread = notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .filter(notifications::read_at.is_null());
+        let now = chrono::Utc::now().naive_utc();
+        let marked = match ids {
+            Some(ids) => diesel::update(unread.filter(notifications::id.eq_any(ids)))
+                .set(notifications::read_at.eq(now))
+                .execute(&mut conn),
+            None => diesel::update(unread)
+                .set(notifications::read_at.eq(now))
+                .execute(&mut conn),
+        }
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+        let unread_count = Self::count_unread(&mut conn, user_id)?;
+        drop(conn);
+
+        if marked > 0 {
+            self.websocket_service
+                .notify_user(user_id, NotificationPush::UnreadCount { unread_count })
+                .await;
+        }
+
+        Ok(MarkNotificationsReadResponse {
+            marked,
+            unread_count,
+        })
+    }
+
+    fn count_unread(conn: &mut SqliteConnection, user_id: i32) -> Result<i64, LunarbaseError> {
+        notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .filter(notifications::read_at.is_null())
+            .count()
+            .get_result(conn)
+            .map_err(|_| LunarbaseError::DatabaseError)
+    }
+}
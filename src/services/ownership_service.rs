@@ -1,9 +1,16 @@
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
 use tracing::debug;
 
-use crate::models::{Permission, RecordResponse, User};
+use crate::models::{
+    CollectionOwnershipStats, GlobalOwnershipStats, OrphanReason, OrphanedRecord, PendingEvent,
+    Permission, RecordEvent, RecordResponse, User, UserOwnershipStats,
+};
+use crate::services::collection_service::lookup_records_table_name;
+use crate::services::{QueryCache, RecordCache, WebSocketService};
 use crate::utils::LunarbaseError;
 
 type DbPool = Pool<ConnectionManager<SqliteConnection>>;
@@ -11,11 +18,69 @@ type DbPool = Pool<ConnectionManager<SqliteConnection>>;
 #[derive(Clone)]
 pub struct OwnershipService {
     pub pool: DbPool,
+    pub websocket_service: Option<Arc<WebSocketService>>,
+    pub record_cache: Option<RecordCache>,
+    pub query_cache: Option<QueryCache>,
 }
 
 impl OwnershipService {
     pub fn new(pool: DbPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            websocket_service: None,
+            record_cache: None,
+            query_cache: None,
+        }
+    }
+
+    pub fn with_websocket_service(mut self, websocket_service: Arc<WebSocketService>) -> Self {
+        self.websocket_service = Some(websocket_service);
+        self
+    }
+
+    pub fn with_record_cache(mut self, record_cache: RecordCache) -> Self {
+        self.record_cache = Some(record_cache);
+        self
+    }
+
+    pub fn with_query_cache(mut self, query_cache: QueryCache) -> Self {
+        self.query_cache = Some(query_cache);
+        self
+    }
+
+    /// Notifies subscribers that a record changed hands and drops it from the
+    /// record and query caches. Every transfer path, single or bulk, should report through
+    /// here once per record.
+    async fn emit_ownership_transferred(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+        old_owner_id: Option<i32>,
+        new_owner_id: i32,
+        actor_id: i32,
+    ) {
+        if let Some(record_cache) = &self.record_cache {
+            record_cache.invalidate(collection_name, record_id);
+        }
+        if let Some(query_cache) = &self.query_cache {
+            query_cache.invalidate_collection(collection_name);
+        }
+
+        if let Some(ws_service) = &self.websocket_service {
+            let pending_event = PendingEvent {
+                collection_name: collection_name.to_string(),
+                event: RecordEvent::OwnershipTransferred {
+                    record_id: record_id.to_string(),
+                    old_owner_id,
+                    new_owner_id,
+                },
+                user_id: Some(actor_id),
+            };
+
+            if let Err(e) = ws_service.broadcast_event(pending_event).await {
+                tracing::warn!("Failed to broadcast WebSocket event: {}", e);
+            }
+        }
     }
 
     pub fn set_record_ownership(
@@ -126,7 +191,7 @@ impl OwnershipService {
         record: &RecordResponse,
         new_owner_id: i32,
         collection_name: &str,
-        record_id: i32,
+        record_id: &str,
     ) -> Result<(), LunarbaseError> {
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
@@ -142,11 +207,13 @@ impl OwnershipService {
             .first(&mut conn)
             .map_err(|_| LunarbaseError::NotFound("New owner user not found".to_string()))?;
 
-        let table_name = format!("records_{}", collection_name);
+        let table_name = lookup_records_table_name(&mut conn, collection_name);
 
         let update_owner_id_sql = format!(
-            "UPDATE {} SET owner_id = {} WHERE id = {}",
-            table_name, new_owner_id, record_id
+            "UPDATE {} SET owner_id = {} WHERE id = '{}'",
+            table_name,
+            new_owner_id,
+            record_id.replace('\'', "''")
         );
 
         let owner_id_result = diesel::sql_query(&update_owner_id_sql).execute(&mut conn);
@@ -162,6 +229,20 @@ impl OwnershipService {
             collection_name, record_id, current_user.id, new_owner_id
         );
 
+        let old_owner_id = record.data.get("owner_id").and_then(|value| match value {
+            Value::Number(num) => num.as_i64().and_then(|id| i32::try_from(id).ok()),
+            Value::String(s) => s.parse::<i32>().ok(),
+            _ => None,
+        });
+        self.emit_ownership_transferred(
+            collection_name,
+            record_id,
+            old_owner_id,
+            new_owner_id,
+            current_user.id,
+        )
+        .await;
+
         Ok(())
     }
 
@@ -192,7 +273,7 @@ impl OwnershipService {
         collection_name: &str,
         limit: Option<i64>,
         offset: Option<i64>,
-    ) -> Result<Vec<i32>, LunarbaseError> {
+    ) -> Result<Vec<String>, LunarbaseError> {
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
         use crate::schema::collections;
@@ -201,14 +282,14 @@ impl OwnershipService {
             .first::<crate::models::Collection>(&mut conn)
             .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
 
-        let table_name = format!("records_{}", collection_name);
+        let table_name = lookup_records_table_name(&mut conn, collection_name);
         let limit_clause = limit.unwrap_or(100);
         let offset_clause = offset.unwrap_or(0);
 
         let mut owned_record_ids = Vec::new();
 
         let owner_id_query = format!(
-            "SELECT id FROM {} WHERE owner_id = {} LIMIT {} OFFSET {}",
+            "SELECT id FROM {} WHERE owner_id = {} ORDER BY id LIMIT {} OFFSET {}",
             table_name, user.id, limit_clause, offset_clause
         );
 
@@ -218,7 +299,7 @@ impl OwnershipService {
 
         if owned_record_ids.is_empty() {
             let author_id_query = format!(
-                "SELECT id FROM {} WHERE author_id = {} LIMIT {} OFFSET {}",
+                "SELECT id FROM {} WHERE author_id = {} ORDER BY id LIMIT {} OFFSET {}",
                 table_name, user.id, limit_clause, offset_clause
             );
 
@@ -229,7 +310,7 @@ impl OwnershipService {
 
         if owned_record_ids.is_empty() {
             let email_query = format!(
-                "SELECT id FROM {} WHERE email = '{}' LIMIT {} OFFSET {}",
+                "SELECT id FROM {} WHERE email = '{}' ORDER BY id LIMIT {} OFFSET {}",
                 table_name, user.email, limit_clause, offset_clause
             );
 
@@ -238,7 +319,6 @@ impl OwnershipService {
             }
         }
 
-        owned_record_ids.sort();
         owned_record_ids.dedup();
 
         debug!(
@@ -255,11 +335,11 @@ impl OwnershipService {
         &self,
         conn: &mut SqliteConnection,
         query: &str,
-    ) -> Result<Vec<i32>, LunarbaseError> {
+    ) -> Result<Vec<String>, LunarbaseError> {
         #[derive(QueryableByName)]
         struct RecordId {
-            #[diesel(sql_type = diesel::sql_types::Integer)]
-            id: i32,
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            id: String,
         }
 
         match diesel::sql_query(query).load::<RecordId>(conn) {
@@ -272,6 +352,245 @@ impl OwnershipService {
         }
     }
 
+    /// Ownership across every collection: one aggregate query per records table
+    /// plus, while the cap allows, a listing of records whose owner was deleted
+    /// or deactivated.
+    pub async fn get_global_ownership_stats(
+        &self,
+        orphan_limit: usize,
+    ) -> Result<GlobalOwnershipStats, LunarbaseError> {
+        use crate::schema::collections;
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let collection_names: Vec<String> = collections::table
+            .select(collections::name)
+            .order(collections::name.asc())
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+
+        let mut collection_stats = Vec::new();
+        let mut users: HashMap<i32, UserOwnershipStats> = HashMap::new();
+        let mut orphaned_records = Vec::new();
+
+        for collection_name in collection_names {
+            let table_name = lookup_records_table_name(&mut conn, &collection_name);
+            let ownership_field = self.find_ownership_field(&mut conn, &table_name)?;
+
+            let Some(field) = ownership_field.clone() else {
+                let total_records = self.count_table_records(&mut conn, &table_name)?;
+                collection_stats.push(CollectionOwnershipStats {
+                    collection_name,
+                    ownership_field: None,
+                    total_records,
+                    owned_records: 0,
+                    unowned_records: total_records,
+                    orphaned_records: 0,
+                    unowned_percentage: if total_records > 0 { 100.0 } else { 0.0 },
+                });
+                continue;
+            };
+
+            let owners = self.aggregate_owners(&mut conn, &table_name, &field)?;
+
+            let mut stats = CollectionOwnershipStats {
+                collection_name: collection_name.clone(),
+                ownership_field,
+                total_records: 0,
+                owned_records: 0,
+                unowned_records: 0,
+                orphaned_records: 0,
+                unowned_percentage: 0.0,
+            };
+
+            for owner in owners {
+                stats.total_records += owner.count;
+                let Some(owner_id) = owner.owner_id else {
+                    stats.unowned_records += owner.count;
+                    continue;
+                };
+
+                stats.owned_records += owner.count;
+                let is_active = owner.is_active.unwrap_or(false);
+                if !owner.user_exists || !is_active {
+                    stats.orphaned_records += owner.count;
+                }
+
+                let user = users.entry(owner_id).or_insert_with(|| UserOwnershipStats {
+                    user_id: owner_id,
+                    username: owner.username.clone(),
+                    is_active,
+                    total_records: 0,
+                    records_per_collection: HashMap::new(),
+                });
+                user.total_records += owner.count;
+                *user
+                    .records_per_collection
+                    .entry(collection_name.clone())
+                    .or_insert(0) += owner.count;
+            }
+
+            if stats.total_records > 0 {
+                stats.unowned_percentage =
+                    stats.unowned_records as f64 / stats.total_records as f64 * 100.0;
+            }
+
+            let remaining = orphan_limit.saturating_sub(orphaned_records.len());
+            if stats.orphaned_records > 0 && remaining > 0 {
+                orphaned_records.extend(self.list_orphaned_records(
+                    &mut conn,
+                    &collection_name,
+                    &field,
+                    remaining,
+                )?);
+            }
+
+            collection_stats.push(stats);
+        }
+
+        collection_stats.sort_by(|a, b| {
+            b.unowned_percentage
+                .total_cmp(&a.unowned_percentage)
+                .then_with(|| a.collection_name.cmp(&b.collection_name))
+        });
+
+        let mut users: Vec<UserOwnershipStats> = users.into_values().collect();
+        users.sort_by(|a, b| {
+            b.total_records
+                .cmp(&a.total_records)
+                .then_with(|| a.user_id.cmp(&b.user_id))
+        });
+
+        Ok(GlobalOwnershipStats {
+            total_records: collection_stats.iter().map(|c| c.total_records).sum(),
+            owned_records: collection_stats.iter().map(|c| c.owned_records).sum(),
+            unowned_records: collection_stats.iter().map(|c| c.unowned_records).sum(),
+            orphaned_records_total: collection_stats.iter().map(|c| c.orphaned_records).sum(),
+            collections: collection_stats,
+            users,
+            orphaned_records,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+
+    /// `owner_id` when the records table has it, otherwise `author_id`.
+    fn find_ownership_field(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+    ) -> Result<Option<String>, LunarbaseError> {
+        #[derive(QueryableByName)]
+        struct ColumnName {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            name: String,
+        }
+
+        let columns: Vec<String> = diesel::sql_query(format!(
+            "SELECT name FROM pragma_table_info('{}')",
+            table_name
+        ))
+        .load::<ColumnName>(conn)
+        .map_err(|_| LunarbaseError::InternalError)?
+        .into_iter()
+        .map(|column| column.name)
+        .collect();
+
+        Ok(["owner_id", "author_id"]
+            .into_iter()
+            .find(|field| columns.iter().any(|column| column == field))
+            .map(str::to_string))
+    }
+
+    fn count_table_records(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+    ) -> Result<i64, LunarbaseError> {
+        #[derive(QueryableByName)]
+        struct CountResult {
+            #[diesel(sql_type = diesel::sql_types::BigInt)]
+            count: i64,
+        }
+
+        diesel::sql_query(format!("SELECT COUNT(*) AS count FROM {}", table_name))
+            .get_result::<CountResult>(conn)
+            .map(|result| result.count)
+            .map_err(|_| LunarbaseError::InternalError)
+    }
+
+    fn aggregate_owners(
+        &self,
+        conn: &mut SqliteConnection,
+        table_name: &str,
+        field: &str,
+    ) -> Result<Vec<OwnerAggregate>, LunarbaseError> {
+        let query = format!(
+            "SELECT CAST(r.{field} AS INTEGER) AS owner_id, COUNT(*) AS count, \
+             MAX(u.username) AS username, MAX(u.is_active) AS is_active, \
+             COUNT(u.id) > 0 AS user_exists \
+             FROM {table} r LEFT JOIN users u ON u.id = r.{field} \
+             GROUP BY CAST(r.{field} AS INTEGER)",
+            field = field,
+            table = table_name
+        );
+
+        diesel::sql_query(query).load(conn).map_err(|e| {
+            tracing::error!("Failed to aggregate owners of {}: {:?}", table_name, e);
+            LunarbaseError::InternalError
+        })
+    }
+
+    fn list_orphaned_records(
+        &self,
+        conn: &mut SqliteConnection,
+        collection_name: &str,
+        field: &str,
+        limit: usize,
+    ) -> Result<Vec<OrphanedRecord>, LunarbaseError> {
+        #[derive(QueryableByName)]
+        struct OrphanRow {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            id: String,
+            #[diesel(sql_type = diesel::sql_types::Integer)]
+            owner_id: i32,
+            #[diesel(sql_type = diesel::sql_types::Bool)]
+            user_exists: bool,
+        }
+
+        let query = format!(
+            "SELECT r.id AS id, CAST(r.{field} AS INTEGER) AS owner_id, \
+             u.id IS NOT NULL AS user_exists \
+             FROM {table} r LEFT JOIN users u ON u.id = r.{field} \
+             WHERE r.{field} IS NOT NULL AND (u.id IS NULL OR u.is_active = 0) \
+             ORDER BY r.id LIMIT {limit}",
+            field = field,
+            table = lookup_records_table_name(conn, collection_name),
+            limit = limit
+        );
+
+        let rows = diesel::sql_query(query)
+            .load::<OrphanRow>(conn)
+            .map_err(|_| LunarbaseError::InternalError)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| OrphanedRecord {
+                collection_name: collection_name.to_string(),
+                owner_id: row.owner_id,
+                reason: if row.user_exists {
+                    OrphanReason::OwnerInactive
+                } else {
+                    OrphanReason::OwnerDeleted
+                },
+                transfer_url: format!(
+                    "/api/ownership/collections/{}/records/{}/transfer",
+                    collection_name, row.id
+                ),
+                record_id: row.id,
+            })
+            .collect())
+    }
+
     pub fn create_ownership_rule(
         &self,
         collection_name: &str,
@@ -291,6 +610,20 @@ impl OwnershipService {
     }
 }
 
+#[derive(QueryableByName)]
+struct OwnerAggregate {
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Integer>)]
+    owner_id: Option<i32>,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    count: i64,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    username: Option<String>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Bool>)]
+    is_active: Option<bool>,
+    #[diesel(sql_type = diesel::sql_types::Bool)]
+    user_exists: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct OwnershipPermissions {
     pub can_read: bool,
@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use argon2::password_hash::SaltString;
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use rand::{RngCore, rngs::OsRng};
+use tokio::sync::Semaphore;
+
+use crate::services::{ConfigurationAccess, ConfigurationManager};
+use crate::utils::LunarbaseError;
+
+/// Argon2id cost of new password hashes. Verification always uses the cost
+/// stored in the hash, so changing these only affects passwords set later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordHashParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for PasswordHashParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: 65536,
+            iterations: 4,
+            parallelism: 2,
+        }
+    }
+}
+
+/// Hashes `password` with `pepper` appended. Blocks for as long as the
+/// parameters make it take; call it off the async runtime.
+pub fn hash_password(
+    password: &str,
+    pepper: &str,
+    params: &PasswordHashParams,
+) -> Result<String, String> {
+    let mut salt_bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut salt_bytes);
+
+    let salt = SaltString::encode_b64(&salt_bytes)
+        .map_err(|e| format!("Salt generation failed: {}", e))?;
+
+    let argon2_params = argon2::Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        None,
+    )
+    .map_err(|e| format!("Invalid password hash parameters: {}", e))?;
+    let argon2 = Argon2::new(
+        argon2::Algorithm::Argon2id,
+        argon2::Version::V0x13,
+        argon2_params,
+    );
+
+    let peppered_password = format!("{}{}", password, pepper);
+    argon2
+        .hash_password(peppered_password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Password hashing failed: {}", e))
+}
+
+/// Checks `password` against a hash from [`hash_password`]. Blocking, like it.
+pub fn verify_password(
+    password: &str,
+    pepper: &str,
+    password_hash: &str,
+) -> Result<bool, argon2::password_hash::Error> {
+    let parsed_hash = PasswordHash::new(password_hash)?;
+
+    let peppered_password = format!("{}{}", password, pepper);
+
+    Ok(Argon2::default()
+        .verify_password(peppered_password.as_bytes(), &parsed_hash)
+        .is_ok())
+}
+
+/// Runs Argon2 on the blocking thread pool so a burst of logins cannot stall
+/// the runtime. At most one hash per CPU runs at a time; the rest wait their
+/// turn, which also caps the memory the hashes hold at once.
+#[derive(Clone)]
+pub struct PasswordService {
+    config_manager: ConfigurationManager,
+    pepper: Arc<str>,
+    workers: Arc<Semaphore>,
+}
+
+impl ConfigurationAccess for PasswordService {
+    fn config_manager(&self) -> &ConfigurationManager {
+        &self.config_manager
+    }
+}
+
+impl PasswordService {
+    pub fn new(config_manager: ConfigurationManager, pepper: String) -> Self {
+        let workers = std::thread::available_parallelism().map_or(2, |count| count.get());
+        Self {
+            config_manager,
+            pepper: pepper.into(),
+            workers: Arc::new(Semaphore::new(workers)),
+        }
+    }
+
+    /// Current `auth.password_hash_*` settings.
+    pub async fn params(&self) -> PasswordHashParams {
+        PasswordHashParams {
+            memory_kib: self.get_password_hash_memory_kib().await,
+            iterations: self.get_password_hash_iterations().await,
+            parallelism: self.get_password_hash_parallelism().await,
+        }
+    }
+
+    pub async fn hash(&self, password: &str) -> Result<String, LunarbaseError> {
+        let params = self.params().await;
+        let password = password.to_string();
+        let pepper = self.pepper.clone();
+
+        self.run(move || hash_password(&password, &pepper, &params))
+            .await?
+            .map_err(|_| LunarbaseError::InternalError)
+    }
+
+    pub async fn verify(
+        &self,
+        password: &str,
+        password_hash: &str,
+    ) -> Result<bool, LunarbaseError> {
+        let password = password.to_string();
+        let password_hash = password_hash.to_string();
+        let pepper = self.pepper.clone();
+
+        self.run(move || verify_password(&password, &pepper, &password_hash))
+            .await?
+            .map_err(|_| LunarbaseError::InternalError)
+    }
+
+    async fn run<T: Send + 'static>(
+        &self,
+        work: impl FnOnce() -> T + Send + 'static,
+    ) -> Result<T, LunarbaseError> {
+        let _permit = self
+            .workers
+            .acquire()
+            .await
+            .map_err(|_| LunarbaseError::InternalError)?;
+        tokio::task::spawn_blocking(work)
+            .await
+            .map_err(|_| LunarbaseError::InternalError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PEPPER: &str = "test_pepper";
+
+    #[test]
+    fn test_hash_uses_the_given_parameters() {
+        let params = PasswordHashParams {
+            memory_kib: 8192,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let hash = hash_password("Secret123!", PEPPER, &params).unwrap();
+
+        assert!(hash.starts_with("$argon2id$v=19$m=8192,t=1,p=1$"));
+        assert!(verify_password("Secret123!", PEPPER, &hash).unwrap());
+        assert!(!verify_password("Secret123!", "other_pepper", &hash).unwrap());
+        assert!(!verify_password("Wrong123!", PEPPER, &hash).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_parameters_are_rejected() {
+        let params = PasswordHashParams {
+            memory_kib: 1,
+            iterations: 1,
+            parallelism: 4,
+        };
+        assert!(hash_password("Secret123!", PEPPER, &params).is_err());
+    }
+}
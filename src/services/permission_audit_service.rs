@@ -0,0 +1,239 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use tracing::{debug, info, warn};
+
+use crate::middleware::MetricsState;
+use crate::models::{CollectionResponse, DenialSource, Permission, PermissionDenialAlert, User};
+use crate::services::{ConfigurationAccess, ConfigurationManager, EmailService, PermissionService};
+
+/// Records refused permission checks in [`MetricsState`] and alerts by email
+/// and/or webhook when a single user is refused too often within the alert
+/// window.
+#[derive(Clone)]
+pub struct PermissionAuditService {
+    permission_service: PermissionService,
+    config_manager: ConfigurationManager,
+    email_service: EmailService,
+    metrics_state: MetricsState,
+    http_client: reqwest::Client,
+    windows: Arc<Mutex<HashMap<i32, DenialWindow>>>,
+}
+
+impl ConfigurationAccess for PermissionAuditService {
+    fn config_manager(&self) -> &ConfigurationManager {
+        &self.config_manager
+    }
+}
+
+#[derive(Default)]
+struct DenialWindow {
+    denials: VecDeque<Instant>,
+    alerted_at: Option<Instant>,
+}
+
+impl DenialWindow {
+    /// Adds a denial at `now`. Returns the number of denials within `window`
+    /// when it reaches `threshold` and the user was not alerted on in the
+    /// last `window`.
+    fn observe(&mut self, now: Instant, window: Duration, threshold: usize) -> Option<usize> {
+        self.denials.push_back(now);
+        while self
+            .denials
+            .front()
+            .is_some_and(|denied| now.duration_since(*denied) > window)
+        {
+            self.denials.pop_front();
+        }
+
+        let quiet = self
+            .alerted_at
+            .is_none_or(|alerted| now.duration_since(alerted) >= window);
+        if self.denials.len() >= threshold && quiet {
+            self.alerted_at = Some(now);
+            Some(self.denials.len())
+        } else {
+            None
+        }
+    }
+}
+
+impl PermissionAuditService {
+    pub fn new(
+        permission_service: PermissionService,
+        config_manager: ConfigurationManager,
+        email_service: EmailService,
+        metrics_state: MetricsState,
+    ) -> Self {
+        Self {
+            permission_service,
+            config_manager,
+            email_service,
+            metrics_state,
+            http_client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            windows: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Records that `user` was refused `permission` on `collection`, or on one
+    /// of its records when `record_id` is given.
+    pub async fn record_denial(
+        &self,
+        user: &User,
+        collection: &CollectionResponse,
+        record_id: Option<&str>,
+        permission: Permission,
+    ) {
+        let source = self
+            .permission_service
+            .denial_source(user, collection.id, record_id, permission)
+            .await
+            .unwrap_or(DenialSource::Role);
+        self.record(user, &collection.name, permission, source)
+            .await;
+    }
+
+    /// Records that `user` was refused an action reserved for the record owner.
+    pub async fn record_ownership_denial(
+        &self,
+        user: &User,
+        collection_name: &str,
+        permission: Permission,
+    ) {
+        self.record(user, collection_name, permission, DenialSource::Ownership)
+            .await;
+    }
+
+    async fn record(
+        &self,
+        user: &User,
+        collection_name: &str,
+        permission: Permission,
+        source: DenialSource,
+    ) {
+        info!(
+            "Permission denied: user {} may not {} in collection '{}' ({})",
+            user.id,
+            permission,
+            collection_name,
+            source.as_str()
+        );
+        self.metrics_state
+            .record_permission_denial(user.id, collection_name, permission, source);
+
+        let threshold = self.get_permission_denial_alert_threshold().await;
+        if threshold == 0 {
+            return;
+        }
+        let window_seconds = self.get_permission_denial_alert_window_seconds().await;
+
+        let denials = {
+            let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+            windows.entry(user.id).or_default().observe(
+                Instant::now(),
+                Duration::from_secs(window_seconds as u64),
+                threshold as usize,
+            )
+        };
+        let Some(denials) = denials else {
+            return;
+        };
+
+        let alert = PermissionDenialAlert {
+            user_id: user.id,
+            email: user.email.clone(),
+            denials,
+            window_seconds,
+            collection: collection_name.to_string(),
+            action: permission.to_string(),
+            source,
+            triggered_at: Utc::now().to_rfc3339(),
+        };
+        let service = self.clone();
+        tokio::spawn(async move { service.send_alert(alert).await });
+    }
+
+    async fn send_alert(&self, alert: PermissionDenialAlert) {
+        warn!(
+            "User {} was denied {} times within {} seconds",
+            alert.user_id, alert.denials, alert.window_seconds
+        );
+
+        let recipient = self.get_permission_denial_alert_email().await;
+        if !recipient.is_empty()
+            && let Err(e) = self
+                .email_service
+                .send_permission_denial_alert(&recipient, &alert)
+                .await
+        {
+            warn!("Failed to email permission denial alert: {}", e);
+        }
+
+        let webhook_url = self.get_permission_denial_alert_webhook_url().await;
+        if webhook_url.is_empty() {
+            return;
+        }
+        match self
+            .http_client
+            .post(&webhook_url)
+            .json(&alert)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+        {
+            Ok(_) => debug!("Permission denial alert delivered to webhook"),
+            Err(e) => warn!("Permission denial alert webhook failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_alerts_once_per_window_when_threshold_is_reached() {
+        let mut window = DenialWindow::default();
+        let start = Instant::now();
+
+        assert_eq!(window.observe(start, WINDOW, 3), None);
+        assert_eq!(
+            window.observe(start + Duration::from_secs(1), WINDOW, 3),
+            None
+        );
+        assert_eq!(
+            window.observe(start + Duration::from_secs(2), WINDOW, 3),
+            Some(3)
+        );
+        assert_eq!(
+            window.observe(start + Duration::from_secs(3), WINDOW, 3),
+            None
+        );
+
+        // Still above the threshold once the window has passed
+        assert_eq!(
+            window.observe(start + Duration::from_secs(62), WINDOW, 3),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_denials_outside_the_window_are_forgotten() {
+        let mut window = DenialWindow::default();
+        let start = Instant::now();
+
+        window.observe(start, WINDOW, 2);
+        assert_eq!(
+            window.observe(start + Duration::from_secs(61), WINDOW, 2),
+            None
+        );
+        assert_eq!(window.denials.len(), 1);
+    }
+}
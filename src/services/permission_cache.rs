@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::models::{Role, RoleCollectionPermission, UserCollectionPermission};
+use crate::services::record_cache::LruState;
+
+/// Entries kept per kind of row before the least recently used are evicted.
+const MAX_ENTRIES: usize = 10_000;
+
+/// (role id, collection id or `None` for every collection, inheritance enabled)
+type RolePermissionsKey = (i32, Option<i32>, bool);
+
+/// (user id, collection id)
+type UserOverrideKey = (i32, i32);
+
+#[derive(Default)]
+struct PermissionCacheState {
+    roles: LruState<String, Option<Role>>,
+    role_permissions: LruState<RolePermissionsKey, Arc<HashMap<i32, RoleCollectionPermission>>>,
+    user_overrides: LruState<UserOverrideKey, Option<UserCollectionPermission>>,
+    generation: u64,
+}
+
+/// In-process cache of the role definitions, resolved role permissions and
+/// user overrides read by permission checks. Every role or permission change
+/// clears it, and values read from the database before a change are dropped
+/// instead of cached: callers take [`PermissionCache::generation`] before
+/// reading and pass it back when inserting.
+#[derive(Clone, Default)]
+pub struct PermissionCache {
+    state: Arc<Mutex<PermissionCacheState>>,
+}
+
+impl PermissionCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, PermissionCacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn insert_at(&self, generation: u64, insert: impl FnOnce(&mut PermissionCacheState)) {
+        let mut state = self.lock();
+        if state.generation == generation {
+            insert(&mut state);
+        }
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.lock().generation
+    }
+
+    /// `Some(None)` is a cached miss: no role has that name.
+    pub fn role(&self, name: &str, ttl: Duration) -> Option<Option<Role>> {
+        self.lock().roles.get(&name.to_string(), ttl)
+    }
+
+    pub fn insert_role(&self, name: String, role: Option<Role>, generation: u64) {
+        self.insert_at(generation, |state| {
+            state.roles.insert(name, role, MAX_ENTRIES)
+        });
+    }
+
+    pub fn role_permissions(
+        &self,
+        key: RolePermissionsKey,
+        ttl: Duration,
+    ) -> Option<Arc<HashMap<i32, RoleCollectionPermission>>> {
+        self.lock().role_permissions.get(&key, ttl)
+    }
+
+    pub fn insert_role_permissions(
+        &self,
+        key: RolePermissionsKey,
+        permissions: Arc<HashMap<i32, RoleCollectionPermission>>,
+        generation: u64,
+    ) {
+        self.insert_at(generation, |state| {
+            state.role_permissions.insert(key, permissions, MAX_ENTRIES)
+        });
+    }
+
+    pub fn user_override(
+        &self,
+        key: UserOverrideKey,
+        ttl: Duration,
+    ) -> Option<Option<UserCollectionPermission>> {
+        self.lock().user_overrides.get(&key, ttl)
+    }
+
+    pub fn insert_user_override(
+        &self,
+        key: UserOverrideKey,
+        permission: Option<UserCollectionPermission>,
+        generation: u64,
+    ) {
+        self.insert_at(generation, |state| {
+            state.user_overrides.insert(key, permission, MAX_ENTRIES)
+        });
+    }
+
+    pub fn invalidate(&self) {
+        let mut state = self.lock();
+        let generation = state.generation + 1;
+        *state = PermissionCacheState {
+            generation,
+            ..PermissionCacheState::default()
+        };
+    }
+
+    pub fn len(&self) -> usize {
+        let state = self.lock();
+        state.roles.len() + state.role_permissions.len() + state.user_overrides.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_invalidation_clears_entries_and_rejects_stale_reads() {
+        let cache = PermissionCache::new();
+
+        cache.insert_role("editor".to_string(), None, cache.generation());
+        cache.insert_user_override((1, 2), None, cache.generation());
+        assert!(matches!(cache.role("editor", TTL), Some(None)));
+        assert_eq!(cache.len(), 2);
+
+        // A read that started before the change must not repopulate the cache
+        let stale_generation = cache.generation();
+        cache.invalidate();
+        assert!(cache.is_empty());
+        cache.insert_role("editor".to_string(), None, stale_generation);
+        assert!(cache.role("editor", TTL).is_none());
+
+        cache.insert_role("editor".to_string(), None, cache.generation());
+        assert!(cache.role("editor", Duration::ZERO).is_none());
+        assert!(cache.is_empty());
+    }
+}
@@ -1,15 +1,25 @@
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
 use crate::models::{
-    CollectionPermission, NewCollectionPermission, NewRecordPermission, NewRole,
-    NewUserCollectionPermission, Permission, PermissionResult, RecordPermission, Role, User,
-    UserCollectionPermission,
+    AccessFilter, CollectionPermission, CollectionResponse, CollectionUserAccess,
+    CollectionUserAccessPage, CreateRoleRequest, DenialSource, EffectivePermission,
+    NewCollectionPermission, NewPermissionAuditEntry, NewRecordPermission, NewRole,
+    NewUserCollectionPermission, PERMISSION_POLICY_VERSION, Permission, PermissionOverride,
+    PermissionPolicy, PermissionResult, PermissionSet, PolicyChange, PolicyCollection, PolicyError,
+    PolicyImportReport, PolicyRole, RecordGrantSummary, RecordPermission, Role,
+    RoleCollectionPermission, User, UserCollectionPermission, UserRecordGrant, UserRecordGrantPage,
+    stored_usage_quota,
 };
 use crate::schema::{
-    collection_permissions, collections, record_permissions, roles, user_collection_permissions,
-    users,
+    collection_permissions, collections, permission_audit_entries, record_permissions, roles,
+    user_collection_permissions, users, workspace_members,
 };
+use crate::services::{ConfigurationAccess, ConfigurationManager, PermissionCache};
 use crate::utils::LunarbaseError;
 
 type DbPool = Pool<ConnectionManager<SqliteConnection>>;
@@ -17,16 +27,126 @@ type DbPool = Pool<ConnectionManager<SqliteConnection>>;
 #[derive(Clone)]
 pub struct PermissionService {
     pub pool: DbPool,
+    config_manager: ConfigurationManager,
+    version: Arc<AtomicU64>,
+    cache: PermissionCache,
+}
+
+impl ConfigurationAccess for PermissionService {
+    fn config_manager(&self) -> &ConfigurationManager {
+        &self.config_manager
+    }
 }
 
 impl PermissionService {
-    pub fn new(pool: DbPool) -> Self {
-        Self { pool }
+    pub fn new(pool: DbPool, config_manager: ConfigurationManager) -> Self {
+        Self {
+            pool,
+            config_manager,
+            version: Arc::new(AtomicU64::new(0)),
+            cache: PermissionCache::new(),
+        }
+    }
+
+    /// Increases on every role or permission change and every settings reload
+    /// (which may toggle inheritance); caches that depend on permission
+    /// checks include it in their keys.
+    pub fn permissions_version(&self) -> u64 {
+        self.version.load(Ordering::Relaxed) + self.config_manager.generation()
+    }
+
+    fn bump_version(&self) {
+        self.cache.invalidate();
+        self.version.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// `None` when `permission_cache_ttl_seconds` is 0 and the cache is bypassed.
+    async fn cache_ttl(&self) -> Option<Duration> {
+        let seconds = self.get_permission_cache_ttl_seconds().await;
+        (seconds > 0).then(|| Duration::from_secs(seconds as u64))
+    }
+
+    fn find_role(
+        &self,
+        conn: &mut SqliteConnection,
+        name: &str,
+        ttl: Option<Duration>,
+    ) -> Result<Option<Role>, LunarbaseError> {
+        if let Some(role) = ttl.and_then(|ttl| self.cache.role(name, ttl)) {
+            return Ok(role);
+        }
+
+        let generation = self.cache.generation();
+        let role = roles::table
+            .filter(roles::name.eq(name))
+            .first::<Role>(conn)
+            .optional()
+            .map_err(|_| LunarbaseError::InternalError)?;
+        if ttl.is_some() {
+            self.cache
+                .insert_role(name.to_string(), role.clone(), generation);
+        }
+        Ok(role)
+    }
+
+    fn role_permissions(
+        &self,
+        conn: &mut SqliteConnection,
+        role: &Role,
+        collection_id: Option<i32>,
+        inherit: bool,
+        ttl: Option<Duration>,
+    ) -> Result<Arc<HashMap<i32, RoleCollectionPermission>>, LunarbaseError> {
+        let key = (role.id, collection_id, inherit);
+        if let Some(permissions) = ttl.and_then(|ttl| self.cache.role_permissions(key, ttl)) {
+            return Ok(permissions);
+        }
+
+        let generation = self.cache.generation();
+        let permissions = Arc::new(resolve_role_permissions(
+            conn,
+            role,
+            collection_id,
+            inherit,
+        )?);
+        if ttl.is_some() {
+            self.cache
+                .insert_role_permissions(key, permissions.clone(), generation);
+        }
+        Ok(permissions)
+    }
+
+    fn user_override(
+        &self,
+        conn: &mut SqliteConnection,
+        user_id: i32,
+        collection_id: i32,
+        ttl: Option<Duration>,
+    ) -> Result<Option<UserCollectionPermission>, LunarbaseError> {
+        let key = (user_id, collection_id);
+        if let Some(permission) = ttl.and_then(|ttl| self.cache.user_override(key, ttl)) {
+            return Ok(permission);
+        }
+
+        let generation = self.cache.generation();
+        let permission = user_collection_permissions::table
+            .filter(user_collection_permissions::user_id.eq(user_id))
+            .filter(user_collection_permissions::collection_id.eq(collection_id))
+            .first::<UserCollectionPermission>(conn)
+            .optional()
+            .map_err(|_| LunarbaseError::InternalError)?;
+        if ttl.is_some() {
+            self.cache
+                .insert_user_override(key, permission.clone(), generation);
+        }
+        Ok(permission)
     }
 
+    /// Creates a role shared by every workspace, or only usable in `workspace_id`.
     pub async fn create_role(
         &self,
         role_request: &crate::models::CreateRoleRequest,
+        workspace_id: Option<i32>,
     ) -> Result<Role, LunarbaseError> {
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
@@ -47,12 +167,18 @@ impl PermissionService {
             name: role_request.name.clone(),
             description: role_request.description.clone(),
             priority: role_request.priority,
+            workspace_id,
+            max_records_created_per_day: stored_usage_quota(
+                role_request.max_records_created_per_day,
+            ),
+            max_upload_bytes_per_day: stored_usage_quota(role_request.max_upload_bytes_per_day),
         };
 
         diesel::insert_into(roles::table)
             .values(&new_role)
             .execute(&mut conn)
             .map_err(|_| LunarbaseError::InternalError)?;
+        self.bump_version();
 
         roles::table
             .order(roles::id.desc())
@@ -96,6 +222,28 @@ impl PermissionService {
         }
     }
 
+    /// The role's own permissions on the collection or, with inheritance
+    /// enabled, those it inherits.
+    pub async fn get_effective_role_collection_permission(
+        &self,
+        role_name: &str,
+        collection_id: i32,
+    ) -> Result<Option<RoleCollectionPermission>, LunarbaseError> {
+        let inherit = self.get_permission_inheritance_enabled().await;
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let role = roles::table
+            .filter(roles::name.eq(role_name))
+            .first::<Role>(&mut conn)
+            .optional()
+            .map_err(|_| LunarbaseError::InternalError)?
+            .ok_or_else(|| LunarbaseError::NotFound("Role not found".to_string()))?;
+
+        let mut resolved =
+            resolve_role_permissions(&mut conn, &role, Some(collection_id), inherit)?;
+        Ok(resolved.remove(&collection_id))
+    }
+
     pub async fn list_roles(&self) -> Result<Vec<Role>, LunarbaseError> {
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
@@ -123,11 +271,20 @@ impl PermissionService {
             ]));
         }
 
-        let users_with_role: Vec<i32> = users::table
+        let mut users_with_role: Vec<i32> = users::table
             .filter(users::role.eq(&role.name))
             .select(users::id)
             .load(&mut conn)
             .map_err(|_| LunarbaseError::InternalError)?;
+        users_with_role.extend(
+            workspace_members::table
+                .filter(workspace_members::role.eq(&role.name))
+                .select(workspace_members::user_id)
+                .load::<i32>(&mut conn)
+                .map_err(|_| LunarbaseError::InternalError)?,
+        );
+        users_with_role.sort_unstable();
+        users_with_role.dedup();
 
         if !users_with_role.is_empty() {
             return Err(LunarbaseError::ValidationError(vec![format!(
@@ -146,6 +303,7 @@ impl PermissionService {
         diesel::delete(roles::table.filter(roles::id.eq(role.id)))
             .execute(&mut conn)
             .map_err(|_| LunarbaseError::InternalError)?;
+        self.bump_version();
 
         Ok(())
     }
@@ -207,6 +365,20 @@ impl PermissionService {
                 .map_err(|_| LunarbaseError::InternalError)?;
         }
 
+        if let Some(limit) = update_request.max_records_created_per_day {
+            diesel::update(roles::table.find(role.id))
+                .set(roles::max_records_created_per_day.eq(stored_usage_quota(Some(limit))))
+                .execute(&mut conn)
+                .map_err(|_| LunarbaseError::InternalError)?;
+        }
+
+        if let Some(limit) = update_request.max_upload_bytes_per_day {
+            diesel::update(roles::table.find(role.id))
+                .set(roles::max_upload_bytes_per_day.eq(stored_usage_quota(Some(limit))))
+                .execute(&mut conn)
+                .map_err(|_| LunarbaseError::InternalError)?;
+        }
+
         if let Some(name) = &update_request.name {
             diesel::update(roles::table.find(role.id))
                 .set(roles::name.eq(name))
@@ -217,7 +389,13 @@ impl PermissionService {
                 .set(users::role.eq(name))
                 .execute(&mut conn)
                 .map_err(|_| LunarbaseError::InternalError)?;
+
+            diesel::update(workspace_members::table.filter(workspace_members::role.eq(&role.name)))
+                .set(workspace_members::role.eq(name))
+                .execute(&mut conn)
+                .map_err(|_| LunarbaseError::InternalError)?;
         }
+        self.bump_version();
 
         roles::table
             .find(role.id)
@@ -251,6 +429,7 @@ impl PermissionService {
                 ))
                 .execute(&mut conn)
                 .map_err(|_| LunarbaseError::InternalError)?;
+            self.bump_version();
 
             collection_permissions::table
                 .find(existing_permission.id)
@@ -271,6 +450,7 @@ impl PermissionService {
                 .values(&new_permission)
                 .execute(&mut conn)
                 .map_err(|_| LunarbaseError::InternalError)?;
+            self.bump_version();
 
             collection_permissions::table
                 .order(collection_permissions::id.desc())
@@ -305,6 +485,7 @@ impl PermissionService {
                 ))
                 .execute(&mut conn)
                 .map_err(|_| LunarbaseError::InternalError)?;
+            self.bump_version();
 
             user_collection_permissions::table
                 .find(existing_permission.id)
@@ -325,6 +506,7 @@ impl PermissionService {
                 .values(&new_permission)
                 .execute(&mut conn)
                 .map_err(|_| LunarbaseError::InternalError)?;
+            self.bump_version();
 
             user_collection_permissions::table
                 .order(user_collection_permissions::id.desc())
@@ -360,21 +542,17 @@ impl PermissionService {
             return Ok(PermissionResult::admin());
         }
 
-        let role = roles::table
-            .filter(roles::name.eq(&user.role))
-            .first::<Role>(&mut conn)
-            .optional()
-            .map_err(|_| LunarbaseError::InternalError)?;
+        let ttl = self.cache_ttl().await;
+        let role = self.find_role(&mut conn, &user.role, ttl)?;
 
         let mut final_permissions = PermissionResult::none();
 
         if let Some(role) = role {
-            let role_permissions = collection_permissions::table
-                .filter(collection_permissions::collection_id.eq(collection_id))
-                .filter(collection_permissions::role_id.eq(role.id))
-                .first::<CollectionPermission>(&mut conn)
-                .optional()
-                .map_err(|_| LunarbaseError::InternalError)?;
+            let inherit = self.get_permission_inheritance_enabled().await;
+            let role_permissions = self
+                .role_permissions(&mut conn, &role, Some(collection_id), inherit, ttl)?
+                .get(&collection_id)
+                .map(|resolved| resolved.permission.clone());
 
             if let Some(perm) = role_permissions {
                 final_permissions = PermissionResult::new(
@@ -387,12 +565,7 @@ impl PermissionService {
             }
         }
 
-        let user_permissions = user_collection_permissions::table
-            .filter(user_collection_permissions::user_id.eq(user.id))
-            .filter(user_collection_permissions::collection_id.eq(collection_id))
-            .first::<UserCollectionPermission>(&mut conn)
-            .optional()
-            .map_err(|_| LunarbaseError::InternalError)?;
+        let user_permissions = self.user_override(&mut conn, user.id, collection_id, ttl)?;
 
         if let Some(user_perm) = user_permissions {
             if let Some(can_create) = user_perm.can_create {
@@ -419,7 +592,7 @@ impl PermissionService {
         &self,
         user: &User,
         collection_id: i32,
-        record_id: i32,
+        record_id: &str,
         permission: Permission,
     ) -> Result<bool, LunarbaseError> {
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
@@ -449,6 +622,51 @@ impl PermissionService {
             .await
     }
 
+    /// Which check refused `permission` to `user`: a record permission of the
+    /// user on `record_id` decides first, then an explicit user override, then
+    /// the role.
+    pub async fn denial_source(
+        &self,
+        user: &User,
+        collection_id: i32,
+        record_id: Option<&str>,
+        permission: Permission,
+    ) -> Result<DenialSource, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        if let Some(record_id) = record_id {
+            let has_record_permission = record_permissions::table
+                .filter(record_permissions::record_id.eq(record_id))
+                .filter(record_permissions::collection_id.eq(collection_id))
+                .filter(record_permissions::user_id.eq(user.id))
+                .count()
+                .get_result::<i64>(&mut conn)
+                .map_err(|_| LunarbaseError::InternalError)?
+                > 0;
+            if has_record_permission {
+                return Ok(DenialSource::RecordPermission);
+            }
+        }
+
+        let ttl = self.cache_ttl().await;
+        let overridden = self
+            .user_override(&mut conn, user.id, collection_id, ttl)?
+            .and_then(|user_perm| match permission {
+                Permission::Create => user_perm.can_create,
+                Permission::Read => user_perm.can_read,
+                Permission::Update => user_perm.can_update,
+                Permission::Delete => user_perm.can_delete,
+                Permission::List => user_perm.can_list,
+            })
+            .is_some();
+
+        Ok(if overridden {
+            DenialSource::UserOverride
+        } else {
+            DenialSource::Role
+        })
+    }
+
     pub async fn get_user_accessible_collections(
         &self,
         user: &User,
@@ -463,28 +681,25 @@ impl PermissionService {
             return Ok(all_collections);
         }
 
-        let role = roles::table
-            .filter(roles::name.eq(&user.role))
-            .first::<Role>(&mut conn)
-            .optional()
-            .map_err(|_| LunarbaseError::InternalError)?;
+        let ttl = self.cache_ttl().await;
+        let role = self.find_role(&mut conn, &user.role, ttl)?;
 
         let mut accessible_collections = Vec::new();
 
         if let Some(role) = role {
-            let role_collections: Vec<i32> = collection_permissions::table
-                .filter(collection_permissions::role_id.eq(role.id))
-                .filter(
-                    collection_permissions::can_read
-                        .eq(true)
-                        .or(collection_permissions::can_list.eq(true))
-                        .or(collection_permissions::can_create.eq(true))
-                        .or(collection_permissions::can_update.eq(true))
-                        .or(collection_permissions::can_delete.eq(true)),
-                )
-                .select(collection_permissions::collection_id)
-                .load(&mut conn)
-                .map_err(|_| LunarbaseError::InternalError)?;
+            let inherit = self.get_permission_inheritance_enabled().await;
+            let role_permissions = self.role_permissions(&mut conn, &role, None, inherit, ttl)?;
+            let role_collections = role_permissions
+                .values()
+                .map(|resolved| &resolved.permission)
+                .filter(|perm| {
+                    perm.can_read
+                        || perm.can_list
+                        || perm.can_create
+                        || perm.can_update
+                        || perm.can_delete
+                })
+                .map(|perm| perm.collection_id);
 
             accessible_collections.extend(role_collections);
         }
@@ -511,44 +726,51 @@ impl PermissionService {
         Ok(accessible_collections)
     }
 
-    pub async fn delete_collection_permissions(
+    /// Deletes every permission on a collection inside the caller's
+    /// transaction; cached checks are dropped with [`Self::forget_permissions`]
+    /// once it has committed.
+    pub fn delete_collection_permissions(
         &self,
+        conn: &mut SqliteConnection,
         collection_id: i32,
     ) -> Result<(), LunarbaseError> {
-        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
-
         diesel::delete(
             collection_permissions::table
                 .filter(collection_permissions::collection_id.eq(collection_id)),
         )
-        .execute(&mut conn)
+        .execute(conn)
         .map_err(|_| LunarbaseError::InternalError)?;
 
         diesel::delete(
             user_collection_permissions::table
                 .filter(user_collection_permissions::collection_id.eq(collection_id)),
         )
-        .execute(&mut conn)
+        .execute(conn)
         .map_err(|_| LunarbaseError::InternalError)?;
 
         diesel::delete(
             record_permissions::table.filter(record_permissions::collection_id.eq(collection_id)),
         )
-        .execute(&mut conn)
+        .execute(conn)
         .map_err(|_| LunarbaseError::InternalError)?;
 
         Ok(())
     }
 
+    pub fn forget_permissions(&self) {
+        self.bump_version();
+    }
+
     pub async fn set_record_permission(
         &self,
         collection_id: i32,
         permission_request: &crate::models::SetRecordPermissionRequest,
+        granted_by: i32,
     ) -> Result<RecordPermission, LunarbaseError> {
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
         let existing = record_permissions::table
-            .filter(record_permissions::record_id.eq(permission_request.record_id))
+            .filter(record_permissions::record_id.eq(&permission_request.record_id))
             .filter(record_permissions::collection_id.eq(collection_id))
             .filter(record_permissions::user_id.eq(permission_request.user_id))
             .first::<RecordPermission>(&mut conn)
@@ -561,9 +783,11 @@ impl PermissionService {
                     record_permissions::can_read.eq(permission_request.can_read),
                     record_permissions::can_update.eq(permission_request.can_update),
                     record_permissions::can_delete.eq(permission_request.can_delete),
+                    record_permissions::granted_by.eq(Some(granted_by)),
                 ))
                 .execute(&mut conn)
                 .map_err(|_| LunarbaseError::InternalError)?;
+            self.bump_version();
 
             record_permissions::table
                 .find(existing_permission.id)
@@ -571,18 +795,20 @@ impl PermissionService {
                 .map_err(|_| LunarbaseError::InternalError)
         } else {
             let new_permission = NewRecordPermission {
-                record_id: permission_request.record_id,
+                record_id: permission_request.record_id.clone(),
                 collection_id,
                 user_id: permission_request.user_id,
                 can_read: permission_request.can_read,
                 can_update: permission_request.can_update,
                 can_delete: permission_request.can_delete,
+                granted_by: Some(granted_by),
             };
 
             diesel::insert_into(record_permissions::table)
                 .values(&new_permission)
                 .execute(&mut conn)
                 .map_err(|_| LunarbaseError::InternalError)?;
+            self.bump_version();
 
             record_permissions::table
                 .order(record_permissions::id.desc())
@@ -594,7 +820,7 @@ impl PermissionService {
     pub async fn remove_record_permission(
         &self,
         collection_id: i32,
-        record_id: i32,
+        record_id: &str,
         user_id: i32,
     ) -> Result<(), LunarbaseError> {
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
@@ -607,6 +833,7 @@ impl PermissionService {
         )
         .execute(&mut conn)
         .map_err(|_| LunarbaseError::InternalError)?;
+        self.bump_version();
 
         Ok(())
     }
@@ -614,7 +841,7 @@ impl PermissionService {
     pub async fn list_record_permissions(
         &self,
         collection_id: i32,
-        record_id: i32,
+        record_id: &str,
     ) -> Result<Vec<RecordPermission>, LunarbaseError> {
         let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
 
@@ -625,6 +852,150 @@ impl PermissionService {
             .map_err(|_| LunarbaseError::InternalError)
     }
 
+    /// Every explicit record permission of `user_id` across collections, only
+    /// those in collections of `workspace_id` when given, recorded in the
+    /// permission audit trail as a review by `actor_id`.
+    pub async fn list_user_record_grants(
+        &self,
+        actor_id: i32,
+        user_id: i32,
+        workspace_id: Option<i32>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<UserRecordGrantPage, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        find_user_id(&mut conn, user_id)?;
+
+        let user_grants = || {
+            let mut query = record_permissions::table
+                .inner_join(collections::table)
+                .filter(record_permissions::user_id.eq(user_id))
+                .into_boxed();
+            if let Some(workspace_id) = workspace_id {
+                query = query.filter(collections::workspace_id.eq(workspace_id));
+            }
+            query
+        };
+
+        let total_count = user_grants()
+            .count()
+            .get_result(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let rows: Vec<(RecordPermission, String)> = user_grants()
+            .order((collections::name.asc(), record_permissions::record_id.asc()))
+            .limit(limit)
+            .offset(offset)
+            .select((RecordPermission::as_select(), collections::name))
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let grantor_ids: Vec<i32> = rows
+            .iter()
+            .filter_map(|(permission, _)| permission.granted_by)
+            .collect();
+        let grantor_emails: HashMap<i32, String> = users::table
+            .filter(users::id.eq_any(grantor_ids))
+            .select((users::id, users::email))
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?
+            .into_iter()
+            .collect();
+
+        write_audit_entry(
+            &mut conn,
+            actor_id,
+            user_id,
+            "list_record_permissions",
+            None,
+            total_count,
+        )?;
+
+        let grants = rows
+            .into_iter()
+            .map(|(permission, collection_name)| UserRecordGrant {
+                collection_id: permission.collection_id,
+                collection_name,
+                record_id: permission.record_id,
+                can_read: permission.can_read,
+                can_update: permission.can_update,
+                can_delete: permission.can_delete,
+                granted_by_email: permission
+                    .granted_by
+                    .and_then(|id| grantor_emails.get(&id).cloned()),
+                granted_by: permission.granted_by,
+                granted_at: permission.created_at,
+            })
+            .collect();
+
+        Ok(UserRecordGrantPage {
+            grants,
+            total_count,
+            limit,
+            offset,
+        })
+    }
+
+    /// Removes the explicit record permissions of `user_id`, only those in
+    /// `collection` or in collections of `workspace_id` when given, and
+    /// returns how many were removed.
+    pub async fn revoke_user_record_grants(
+        &self,
+        actor_id: i32,
+        user_id: i32,
+        workspace_id: Option<i32>,
+        collection: Option<&CollectionResponse>,
+    ) -> Result<usize, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        find_user_id(&mut conn, user_id)?;
+
+        let revoked = conn.immediate_transaction(|conn| {
+            let mut query = diesel::delete(record_permissions::table)
+                .filter(record_permissions::user_id.eq(user_id))
+                .into_boxed();
+            if let Some(collection) = collection {
+                query = query.filter(record_permissions::collection_id.eq(collection.id));
+            }
+            if let Some(workspace_id) = workspace_id {
+                query = query.filter(
+                    record_permissions::collection_id.eq_any(
+                        collections::table
+                            .filter(collections::workspace_id.eq(workspace_id))
+                            .select(collections::id),
+                    ),
+                );
+            }
+            let revoked = query.execute(conn)?;
+
+            write_audit_entry(
+                conn,
+                actor_id,
+                user_id,
+                "revoke_record_permissions",
+                collection.map(|collection| collection.name.clone()),
+                revoked as i64,
+            )?;
+            Ok::<_, LunarbaseError>(revoked)
+        })?;
+        self.bump_version();
+
+        Ok(revoked)
+    }
+
+    /// Adds an admin action on a user, collection or role to the audit log.
+    pub async fn record_audit_entry(
+        &self,
+        entry: &NewPermissionAuditEntry,
+    ) -> Result<(), LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        diesel::insert_into(permission_audit_entries::table)
+            .values(entry)
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        Ok(())
+    }
+
     pub async fn check_record_ownership(
         &self,
         user: &User,
@@ -670,7 +1041,7 @@ impl PermissionService {
         &self,
         user: &User,
         collection_id: i32,
-        record_id: i32,
+        record_id: &str,
         permission: Permission,
         record: &crate::models::RecordResponse,
     ) -> Result<bool, LunarbaseError> {
@@ -694,4 +1065,748 @@ impl PermissionService {
         self.check_record_permission(user, collection_id, record_id, permission)
             .await
     }
+
+    /// Like `check_record_permission_with_ownership`, but decides ownership from
+    /// the record's `owner_id` column alone, so the record need not be loaded.
+    pub async fn check_record_permission_with_owner_id(
+        &self,
+        user: &User,
+        collection_id: i32,
+        record_id: &str,
+        permission: Permission,
+        owner_id: Option<i32>,
+    ) -> Result<bool, LunarbaseError> {
+        if user.role == "admin" {
+            return Ok(true);
+        }
+
+        if owner_id == Some(user.id)
+            && matches!(
+                permission,
+                Permission::Read | Permission::Update | Permission::Delete
+            )
+        {
+            return Ok(true);
+        }
+
+        self.check_record_permission(user, collection_id, record_id, permission)
+            .await
+    }
+
+    /// Every user with some effective access to the collection, through an
+    /// admin role, their role's permissions, a user override or at least one
+    /// record permission, ordered by user id.
+    pub async fn list_collection_users(
+        &self,
+        collection_id: i32,
+        access: AccessFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<CollectionUserAccessPage, LunarbaseError> {
+        use diesel::sql_types::{BigInt, Bool, Integer, Nullable, Text};
+
+        #[derive(QueryableByName)]
+        struct AccessRow {
+            #[diesel(sql_type = Integer)]
+            user_id: i32,
+            #[diesel(sql_type = Text)]
+            username: String,
+            #[diesel(sql_type = Text)]
+            email: String,
+            #[diesel(sql_type = Text)]
+            role: String,
+            #[diesel(sql_type = Nullable<Text>)]
+            inherited_from: Option<String>,
+            #[diesel(sql_type = Nullable<Bool>)]
+            role_create: Option<bool>,
+            #[diesel(sql_type = Nullable<Bool>)]
+            role_read: Option<bool>,
+            #[diesel(sql_type = Nullable<Bool>)]
+            role_update: Option<bool>,
+            #[diesel(sql_type = Nullable<Bool>)]
+            role_delete: Option<bool>,
+            #[diesel(sql_type = Nullable<Bool>)]
+            role_list: Option<bool>,
+            #[diesel(sql_type = Nullable<Bool>)]
+            user_create: Option<bool>,
+            #[diesel(sql_type = Nullable<Bool>)]
+            user_read: Option<bool>,
+            #[diesel(sql_type = Nullable<Bool>)]
+            user_update: Option<bool>,
+            #[diesel(sql_type = Nullable<Bool>)]
+            user_delete: Option<bool>,
+            #[diesel(sql_type = Nullable<Bool>)]
+            user_list: Option<bool>,
+            #[diesel(sql_type = BigInt)]
+            record_count: i64,
+            #[diesel(sql_type = Bool)]
+            record_read: bool,
+            #[diesel(sql_type = Bool)]
+            record_update: bool,
+            #[diesel(sql_type = Bool)]
+            record_delete: bool,
+        }
+
+        #[derive(QueryableByName)]
+        struct CountRow {
+            #[diesel(sql_type = BigInt)]
+            count: i64,
+        }
+
+        let inherit = self.get_permission_inheritance_enabled().await;
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        // Same precedence as `resolve_role_permissions`: the role's own row,
+        // then the highest lower-priority role, oldest first on ties
+        let inherited = if inherit {
+            format!(
+                "(SELECT icp.id FROM collection_permissions icp \
+                  JOIN roles ir ON ir.id = icp.role_id \
+                  WHERE icp.collection_id = {} AND ir.priority < r.priority \
+                  ORDER BY ir.priority DESC, ir.id LIMIT 1)",
+                collection_id
+            )
+        } else {
+            "NULL".to_string()
+        };
+
+        let from = format!(
+            "FROM users u \
+             LEFT JOIN roles r ON r.name = u.role \
+             LEFT JOIN collection_permissions cp ON cp.id = COALESCE( \
+                 (SELECT id FROM collection_permissions WHERE role_id = r.id AND collection_id = {0}), \
+                 {1}) \
+             LEFT JOIN roles src ON src.id = cp.role_id AND src.id <> r.id \
+             LEFT JOIN user_collection_permissions ucp ON ucp.user_id = u.id AND ucp.collection_id = {0} \
+             LEFT JOIN (SELECT user_id, COUNT(*) AS records, MAX(can_read) AS can_read, \
+                        MAX(can_update) AS can_update, MAX(can_delete) AS can_delete \
+                        FROM record_permissions WHERE collection_id = {0} GROUP BY user_id) rp \
+                 ON rp.user_id = u.id",
+            collection_id, inherited
+        );
+
+        let effective = |action: &str| {
+            format!(
+                "(u.role = 'admin' OR COALESCE(ucp.can_{0}, cp.can_{0}, 0))",
+                action
+            )
+        };
+        let read = format!(
+            "({} OR {} OR COALESCE(rp.can_read, 0))",
+            effective("read"),
+            effective("list")
+        );
+        let write = format!(
+            "({} OR {} OR {} OR COALESCE(rp.can_update, 0) OR COALESCE(rp.can_delete, 0))",
+            effective("create"),
+            effective("update"),
+            effective("delete")
+        );
+        let filter = match access {
+            AccessFilter::Read => read,
+            AccessFilter::Write => write,
+            AccessFilter::Any => format!("({} OR {} OR rp.records IS NOT NULL)", read, write),
+        };
+
+        let total_count = diesel::sql_query(format!(
+            "SELECT COUNT(*) AS count {} WHERE {}",
+            from, filter
+        ))
+        .get_result::<CountRow>(&mut conn)
+        .map_err(|_| LunarbaseError::InternalError)?
+        .count;
+
+        let rows: Vec<AccessRow> = diesel::sql_query(format!(
+            "SELECT u.id AS user_id, u.username, u.email, u.role, src.name AS inherited_from, \
+             cp.can_create AS role_create, cp.can_read AS role_read, cp.can_update AS role_update, \
+             cp.can_delete AS role_delete, cp.can_list AS role_list, \
+             ucp.can_create AS user_create, ucp.can_read AS user_read, ucp.can_update AS user_update, \
+             ucp.can_delete AS user_delete, ucp.can_list AS user_list, \
+             COALESCE(rp.records, 0) AS record_count, COALESCE(rp.can_read, 0) AS record_read, \
+             COALESCE(rp.can_update, 0) AS record_update, COALESCE(rp.can_delete, 0) AS record_delete \
+             {} WHERE {} ORDER BY u.id LIMIT {} OFFSET {}",
+            from, filter, limit, offset
+        ))
+        .load(&mut conn)
+        .map_err(|_| LunarbaseError::InternalError)?;
+
+        let users = rows
+            .into_iter()
+            .map(|row| {
+                let is_admin = row.role == "admin";
+                let inherited = row.inherited_from.is_some();
+                let resolve = |role: Option<bool>, user: Option<bool>| {
+                    EffectivePermission::resolve(is_admin, role, inherited, user)
+                };
+                CollectionUserAccess {
+                    can_create: resolve(row.role_create, row.user_create),
+                    can_read: resolve(row.role_read, row.user_read),
+                    can_update: resolve(row.role_update, row.user_update),
+                    can_delete: resolve(row.role_delete, row.user_delete),
+                    can_list: resolve(row.role_list, row.user_list),
+                    record_permissions: RecordGrantSummary {
+                        records: row.record_count,
+                        can_read: row.record_read,
+                        can_update: row.record_update,
+                        can_delete: row.record_delete,
+                    },
+                    user_id: row.user_id,
+                    username: row.username,
+                    email: row.email,
+                    role: row.role,
+                    inherited_from: row.inherited_from,
+                }
+            })
+            .collect();
+
+        Ok(CollectionUserAccessPage {
+            users,
+            total_count,
+            limit,
+            offset,
+        })
+    }
+
+    /// The shared roles, and the role permissions and user overrides of every
+    /// collection, as a policy document.
+    pub async fn export_policy(&self) -> Result<PermissionPolicy, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let roles: Vec<Role> = roles::table
+            .filter(roles::workspace_id.is_null())
+            .order((roles::priority.desc(), roles::name.asc()))
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let mut collections: BTreeMap<i32, PolicyCollection> = collections::table
+            .select((collections::id, collections::name))
+            .load::<(i32, String)>(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?
+            .into_iter()
+            .map(|(id, name)| {
+                (
+                    id,
+                    PolicyCollection {
+                        name,
+                        roles: BTreeMap::new(),
+                        users: BTreeMap::new(),
+                    },
+                )
+            })
+            .collect();
+
+        let role_permissions: Vec<(CollectionPermission, String)> = collection_permissions::table
+            .inner_join(roles::table)
+            .select((CollectionPermission::as_select(), roles::name))
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        for (permission, role_name) in role_permissions {
+            if let Some(collection) = collections.get_mut(&permission.collection_id) {
+                collection
+                    .roles
+                    .insert(role_name, PermissionSet::from(&permission));
+            }
+        }
+
+        let overrides: Vec<(UserCollectionPermission, String)> = user_collection_permissions::table
+            .inner_join(users::table)
+            .select((UserCollectionPermission::as_select(), users::email))
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        for (permission, email) in overrides {
+            if let Some(collection) = collections.get_mut(&permission.collection_id) {
+                collection
+                    .users
+                    .insert(email, PermissionOverride::from(&permission));
+            }
+        }
+
+        let mut collections: Vec<PolicyCollection> = collections.into_values().collect();
+        collections.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(PermissionPolicy {
+            version: PERMISSION_POLICY_VERSION,
+            roles: roles.iter().map(PolicyRole::from).collect(),
+            collections,
+        })
+    }
+
+    /// Compares `policy` with the current permissions and, unless `dry_run`,
+    /// makes the changes in one transaction with an audit entry each. A
+    /// document with errors changes nothing.
+    pub async fn import_policy(
+        &self,
+        policy: &PermissionPolicy,
+        dry_run: bool,
+        actor_id: Option<i32>,
+    ) -> Result<PolicyImportReport, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        if dry_run {
+            let (changes, errors) = plan_policy(&mut conn, policy)?;
+            return Ok(PolicyImportReport {
+                dry_run,
+                applied: false,
+                changes,
+                errors,
+            });
+        }
+
+        let changes = conn.immediate_transaction(|conn| {
+            let (changes, errors) = plan_policy(conn, policy)?;
+            if !errors.is_empty() {
+                return Err(LunarbaseError::ValidationError(
+                    errors.iter().map(ToString::to_string).collect(),
+                ));
+            }
+            for change in &changes {
+                apply_policy_change(conn, change, actor_id)?;
+            }
+            Ok(changes)
+        })?;
+        if !changes.is_empty() {
+            self.bump_version();
+        }
+
+        Ok(PolicyImportReport {
+            dry_run,
+            applied: true,
+            changes,
+            errors: Vec::new(),
+        })
+    }
+}
+
+/// The role's permissions per collection (only `collection_id` when given).
+/// With `inherit`, collections where the role has no row fall back to the
+/// nearest lower-priority role that has one; roles of equal priority never
+/// inherit from each other and lower roles sharing a priority are tried
+/// oldest first, so resolution is deterministic and cannot cycle.
+fn resolve_role_permissions(
+    conn: &mut SqliteConnection,
+    role: &Role,
+    collection_id: Option<i32>,
+    inherit: bool,
+) -> Result<HashMap<i32, RoleCollectionPermission>, LunarbaseError> {
+    let mut query = collection_permissions::table
+        .inner_join(roles::table)
+        .select((CollectionPermission::as_select(), roles::name))
+        .order((roles::priority.desc(), roles::id.asc()))
+        .into_boxed();
+
+    if let Some(collection_id) = collection_id {
+        query = query.filter(collection_permissions::collection_id.eq(collection_id));
+    }
+    query = if inherit {
+        query.filter(roles::id.eq(role.id).or(roles::priority.lt(role.priority)))
+    } else {
+        query.filter(roles::id.eq(role.id))
+    };
+
+    let rows: Vec<(CollectionPermission, String)> = query
+        .load(conn)
+        .map_err(|_| LunarbaseError::InternalError)?;
+
+    // The role's own row sorts first since every other candidate has a lower priority
+    let mut resolved = HashMap::new();
+    for (permission, source_role) in rows {
+        resolved
+            .entry(permission.collection_id)
+            .or_insert_with(|| RoleCollectionPermission {
+                inherited_from: (permission.role_id != role.id).then_some(source_role),
+                permission,
+            });
+    }
+
+    Ok(resolved)
+}
+
+fn find_user_id(conn: &mut SqliteConnection, user_id: i32) -> Result<i32, LunarbaseError> {
+    users::table
+        .find(user_id)
+        .select(users::id)
+        .first(conn)
+        .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))
+}
+
+fn write_audit_entry(
+    conn: &mut SqliteConnection,
+    actor_id: i32,
+    target_user_id: i32,
+    action: &str,
+    collection_name: Option<String>,
+    affected_count: i64,
+) -> Result<(), LunarbaseError> {
+    diesel::insert_into(permission_audit_entries::table)
+        .values(&NewPermissionAuditEntry {
+            actor_id: Some(actor_id),
+            target_user_id: Some(target_user_id),
+            action: action.to_string(),
+            collection_name,
+            role_name: None,
+            affected_count,
+        })
+        .execute(conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+    Ok(())
+}
+
+/// The changes importing `policy` makes, and the parts of it that cannot be
+/// imported. Role changes come first so the collection changes can use them.
+fn plan_policy(
+    conn: &mut SqliteConnection,
+    policy: &PermissionPolicy,
+) -> Result<(Vec<PolicyChange>, Vec<PolicyError>), LunarbaseError> {
+    let mut changes = Vec::new();
+    let mut errors = Vec::new();
+
+    if policy.version != PERMISSION_POLICY_VERSION {
+        errors.push(PolicyError::new(
+            "version",
+            format!(
+                "Unsupported policy version {}, expected {}",
+                policy.version, PERMISSION_POLICY_VERSION
+            ),
+        ));
+    }
+
+    let existing_roles: HashMap<String, Role> = roles::table
+        .load::<Role>(conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?
+        .into_iter()
+        .map(|role| (role.name.clone(), role))
+        .collect();
+
+    let mut declared_roles = HashSet::new();
+    for (index, role) in policy.roles.iter().enumerate() {
+        let location = format!("roles[{}]", index);
+        if !declared_roles.insert(role.name.as_str()) {
+            errors.push(PolicyError::new(
+                format!("{}.name", location),
+                format!("Role '{}' is declared more than once", role.name),
+            ));
+            continue;
+        }
+
+        let request = CreateRoleRequest {
+            name: role.name.clone(),
+            description: role.description.clone(),
+            priority: role.priority,
+            max_records_created_per_day: None,
+            max_upload_bytes_per_day: None,
+        };
+        if let Err(messages) = request.validate() {
+            errors.extend(
+                messages
+                    .into_iter()
+                    .map(|message| PolicyError::new(location.clone(), message)),
+            );
+            continue;
+        }
+
+        match existing_roles.get(&role.name) {
+            None => changes.push(PolicyChange::CreateRole {
+                location,
+                role: role.clone(),
+            }),
+            Some(existing) if existing.workspace_id.is_some() => errors.push(PolicyError::new(
+                format!("{}.name", location),
+                format!("Role '{}' belongs to a workspace", role.name),
+            )),
+            Some(existing) => {
+                let before = PolicyRole::from(existing);
+                if before != *role {
+                    changes.push(PolicyChange::UpdateRole {
+                        location,
+                        before,
+                        after: role.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut listed_collections = HashSet::new();
+    for (index, entry) in policy.collections.iter().enumerate() {
+        let location = format!("collections[{}]", index);
+        if !listed_collections.insert(entry.name.as_str()) {
+            errors.push(PolicyError::new(
+                format!("{}.name", location),
+                format!("Collection '{}' is listed more than once", entry.name),
+            ));
+            continue;
+        }
+
+        let collection_id = collections::table
+            .filter(collections::name.eq(&entry.name))
+            .select(collections::id)
+            .first::<i32>(conn)
+            .optional()
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        let Some(collection_id) = collection_id else {
+            errors.push(PolicyError::new(
+                format!("{}.name", location),
+                format!("Unknown collection '{}'", entry.name),
+            ));
+            continue;
+        };
+
+        let current_roles: BTreeMap<String, PermissionSet> = collection_permissions::table
+            .inner_join(roles::table)
+            .filter(collection_permissions::collection_id.eq(collection_id))
+            .select((CollectionPermission::as_select(), roles::name))
+            .load::<(CollectionPermission, String)>(conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?
+            .into_iter()
+            .map(|(permission, role_name)| (role_name, PermissionSet::from(&permission)))
+            .collect();
+
+        for (role_name, permissions) in &entry.roles {
+            let role_location = format!("{}.roles.{}", location, role_name);
+            if !declared_roles.contains(role_name.as_str())
+                && !existing_roles.contains_key(role_name)
+            {
+                errors.push(PolicyError::new(
+                    role_location,
+                    format!("Unknown role '{}'", role_name),
+                ));
+                continue;
+            }
+
+            let before = current_roles.get(role_name).copied();
+            if before != Some(*permissions) {
+                changes.push(PolicyChange::SetRolePermissions {
+                    location: role_location,
+                    collection: entry.name.clone(),
+                    role: role_name.clone(),
+                    before,
+                    after: *permissions,
+                });
+            }
+        }
+        for (role_name, permissions) in &current_roles {
+            if !entry.roles.contains_key(role_name) {
+                changes.push(PolicyChange::RemoveRolePermissions {
+                    location: format!("{}.roles", location),
+                    collection: entry.name.clone(),
+                    role: role_name.clone(),
+                    before: *permissions,
+                });
+            }
+        }
+
+        let current_overrides: BTreeMap<String, PermissionOverride> =
+            user_collection_permissions::table
+                .inner_join(users::table)
+                .filter(user_collection_permissions::collection_id.eq(collection_id))
+                .select((UserCollectionPermission::as_select(), users::email))
+                .load::<(UserCollectionPermission, String)>(conn)
+                .map_err(|_| LunarbaseError::DatabaseError)?
+                .into_iter()
+                .map(|(permission, email)| (email, PermissionOverride::from(&permission)))
+                .collect();
+
+        for (email, permission) in &entry.users {
+            let user_location = format!("{}.users.{}", location, email);
+            let before = current_overrides.get(email).copied();
+            if before.is_none() {
+                let exists = users::table
+                    .filter(users::email.eq(email))
+                    .select(users::id)
+                    .first::<i32>(conn)
+                    .optional()
+                    .map_err(|_| LunarbaseError::DatabaseError)?
+                    .is_some();
+                if !exists {
+                    errors.push(PolicyError::new(
+                        user_location,
+                        format!("Unknown user '{}'", email),
+                    ));
+                    continue;
+                }
+            }
+
+            if before != Some(*permission) {
+                changes.push(PolicyChange::SetUserOverride {
+                    location: user_location,
+                    collection: entry.name.clone(),
+                    user: email.clone(),
+                    before,
+                    after: *permission,
+                });
+            }
+        }
+        for (email, permission) in &current_overrides {
+            if !entry.users.contains_key(email) {
+                changes.push(PolicyChange::RemoveUserOverride {
+                    location: format!("{}.users", location),
+                    collection: entry.name.clone(),
+                    user: email.clone(),
+                    before: *permission,
+                });
+            }
+        }
+    }
+
+    Ok((changes, errors))
+}
+
+fn apply_policy_change(
+    conn: &mut SqliteConnection,
+    change: &PolicyChange,
+    actor_id: Option<i32>,
+) -> Result<(), LunarbaseError> {
+    let role_id = |conn: &mut SqliteConnection, name: &str| {
+        roles::table
+            .filter(roles::name.eq(name))
+            .select(roles::id)
+            .first::<i32>(conn)
+    };
+    let collection_id = |conn: &mut SqliteConnection, name: &str| {
+        collections::table
+            .filter(collections::name.eq(name))
+            .select(collections::id)
+            .first::<i32>(conn)
+    };
+    let user_id = |conn: &mut SqliteConnection, email: &str| {
+        users::table
+            .filter(users::email.eq(email))
+            .select(users::id)
+            .first::<i32>(conn)
+    };
+
+    let (target_user_id, collection_name, role_name) = match change {
+        PolicyChange::CreateRole { role, .. } => {
+            diesel::insert_into(roles::table)
+                .values(&NewRole {
+                    name: role.name.clone(),
+                    description: role.description.clone(),
+                    priority: role.priority,
+                    workspace_id: None,
+                    max_records_created_per_day: None,
+                    max_upload_bytes_per_day: None,
+                })
+                .execute(conn)?;
+            (None, None, Some(role.name.clone()))
+        }
+        PolicyChange::UpdateRole { after, .. } => {
+            diesel::update(roles::table.filter(roles::name.eq(&after.name)))
+                .set((
+                    roles::description.eq(&after.description),
+                    roles::priority.eq(after.priority),
+                ))
+                .execute(conn)?;
+            (None, None, Some(after.name.clone()))
+        }
+        PolicyChange::SetRolePermissions {
+            collection,
+            role,
+            after,
+            ..
+        } => {
+            let collection_id = collection_id(conn, collection)?;
+            let role_id = role_id(conn, role)?;
+            let updated = diesel::update(
+                collection_permissions::table
+                    .filter(collection_permissions::collection_id.eq(collection_id))
+                    .filter(collection_permissions::role_id.eq(role_id)),
+            )
+            .set((
+                collection_permissions::can_create.eq(after.can_create),
+                collection_permissions::can_read.eq(after.can_read),
+                collection_permissions::can_update.eq(after.can_update),
+                collection_permissions::can_delete.eq(after.can_delete),
+                collection_permissions::can_list.eq(after.can_list),
+            ))
+            .execute(conn)?;
+            if updated == 0 {
+                diesel::insert_into(collection_permissions::table)
+                    .values(&NewCollectionPermission {
+                        collection_id,
+                        role_id,
+                        can_create: after.can_create,
+                        can_read: after.can_read,
+                        can_update: after.can_update,
+                        can_delete: after.can_delete,
+                        can_list: after.can_list,
+                    })
+                    .execute(conn)?;
+            }
+            (None, Some(collection.clone()), Some(role.clone()))
+        }
+        PolicyChange::RemoveRolePermissions {
+            collection, role, ..
+        } => {
+            let collection_id = collection_id(conn, collection)?;
+            let role_id = role_id(conn, role)?;
+            diesel::delete(
+                collection_permissions::table
+                    .filter(collection_permissions::collection_id.eq(collection_id))
+                    .filter(collection_permissions::role_id.eq(role_id)),
+            )
+            .execute(conn)?;
+            (None, Some(collection.clone()), Some(role.clone()))
+        }
+        PolicyChange::SetUserOverride {
+            collection,
+            user,
+            after,
+            ..
+        } => {
+            let collection_id = collection_id(conn, collection)?;
+            let user_id = user_id(conn, user)?;
+            let updated = diesel::update(
+                user_collection_permissions::table
+                    .filter(user_collection_permissions::user_id.eq(user_id))
+                    .filter(user_collection_permissions::collection_id.eq(collection_id)),
+            )
+            .set((
+                user_collection_permissions::can_create.eq(after.can_create),
+                user_collection_permissions::can_read.eq(after.can_read),
+                user_collection_permissions::can_update.eq(after.can_update),
+                user_collection_permissions::can_delete.eq(after.can_delete),
+                user_collection_permissions::can_list.eq(after.can_list),
+            ))
+            .execute(conn)?;
+            if updated == 0 {
+                diesel::insert_into(user_collection_permissions::table)
+                    .values(&NewUserCollectionPermission {
+                        user_id,
+                        collection_id,
+                        can_create: after.can_create,
+                        can_read: after.can_read,
+                        can_update: after.can_update,
+                        can_delete: after.can_delete,
+                        can_list: after.can_list,
+                    })
+                    .execute(conn)?;
+            }
+            (Some(user_id), Some(collection.clone()), None)
+        }
+        PolicyChange::RemoveUserOverride {
+            collection, user, ..
+        } => {
+            let collection_id = collection_id(conn, collection)?;
+            let user_id = user_id(conn, user)?;
+            diesel::delete(
+                user_collection_permissions::table
+                    .filter(user_collection_permissions::user_id.eq(user_id))
+                    .filter(user_collection_permissions::collection_id.eq(collection_id)),
+            )
+            .execute(conn)?;
+            (Some(user_id), Some(collection.clone()), None)
+        }
+    };
+
+    diesel::insert_into(permission_audit_entries::table)
+        .values(&NewPermissionAuditEntry {
+            actor_id,
+            target_user_id,
+            action: change.audit_action().to_string(),
+            collection_name,
+            role_name,
+            affected_count: 1,
+        })
+        .execute(conn)?;
+    Ok(())
 }
@@ -0,0 +1,134 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::models::RecordResponse;
+use crate::services::record_cache::LruState;
+
+/// (collection, normalized query key)
+type QueryKey = (String, String);
+
+#[derive(Debug, Clone)]
+pub enum CachedQueryResult {
+    Records {
+        records: Vec<RecordResponse>,
+        total_count: Option<i64>,
+    },
+    Count(i64),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+/// In-process cache of list and count results. Keys are built by the caller from
+/// the normalized query parameters, the caller's permission fingerprint and the
+/// permissions version, so a permission change never serves a stale result.
+/// Any record event on a collection drops all of its entries.
+#[derive(Clone, Default)]
+pub struct QueryCache {
+    state: Arc<Mutex<LruState<QueryKey, CachedQueryResult>>>,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
+}
+
+impl QueryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruState<QueryKey, CachedQueryResult>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn get(
+        &self,
+        collection_name: &str,
+        query_key: &str,
+        ttl: Duration,
+    ) -> Option<CachedQueryResult> {
+        let result = self
+            .lock()
+            .get(&(collection_name.to_string(), query_key.to_string()), ttl);
+
+        let counter = if result.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+
+    pub fn insert(
+        &self,
+        collection_name: &str,
+        query_key: String,
+        result: CachedQueryResult,
+        max_entries: usize,
+    ) {
+        self.lock().insert(
+            (collection_name.to_string(), query_key),
+            result,
+            max_entries,
+        );
+    }
+
+    pub fn invalidate_collection(&self, collection_name: &str) {
+        self.lock()
+            .remove_where(|(collection, _)| collection == collection_name);
+    }
+
+    pub fn stats(&self) -> QueryCacheStats {
+        QueryCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.lock().len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_hits_are_scoped_to_query_key_and_collection() {
+        let cache = QueryCache::new();
+        cache.insert(
+            "orders",
+            "count|role=user".to_string(),
+            CachedQueryResult::Count(4),
+            10,
+        );
+
+        assert!(matches!(
+            cache.get("orders", "count|role=user", TTL),
+            Some(CachedQueryResult::Count(4))
+        ));
+        assert!(cache.get("orders", "count|role=admin", TTL).is_none());
+        assert!(cache.get("invoices", "count|role=user", TTL).is_none());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 1));
+    }
+
+    #[test]
+    fn test_collection_invalidation_and_expiry() {
+        let cache = QueryCache::new();
+        cache.insert("orders", "a".to_string(), CachedQueryResult::Count(1), 10);
+        cache.insert("orders", "b".to_string(), CachedQueryResult::Count(2), 10);
+        cache.insert("invoices", "a".to_string(), CachedQueryResult::Count(3), 10);
+
+        cache.invalidate_collection("orders");
+        assert_eq!(cache.stats().entries, 1);
+
+        assert!(cache.get("invoices", "a", Duration::ZERO).is_none());
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Limit the semaphore was created with, and the semaphore itself
+type UserPermits = (usize, Arc<Semaphore>);
+
+/// Caps how many record queries each user may have in flight at once.
+/// Users only keep an entry while one of their queries holds a permit.
+#[derive(Clone, Default)]
+pub struct QueryLimiter {
+    in_flight: Arc<Mutex<HashMap<String, UserPermits>>>,
+}
+
+impl QueryLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A permit held until the query finishes, or `None` when `user_key`
+    /// already has `max_in_flight` queries running.
+    pub fn try_acquire(
+        &self,
+        user_key: &str,
+        max_in_flight: usize,
+    ) -> Option<OwnedSemaphorePermit> {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+
+        // Permits hold the only other references, so idle users are dropped here
+        in_flight.retain(|_, (_, semaphore)| Arc::strong_count(semaphore) > 1);
+
+        let (limit, semaphore) = in_flight
+            .entry(user_key.to_string())
+            .or_insert_with(|| (max_in_flight, Arc::new(Semaphore::new(max_in_flight))));
+
+        // A changed setting applies from the next idle period of this user
+        if *limit != max_in_flight && Arc::strong_count(semaphore) == 1 {
+            *limit = max_in_flight;
+            *semaphore = Arc::new(Semaphore::new(max_in_flight));
+        }
+
+        semaphore.clone().try_acquire_owned().ok()
+    }
+
+    /// Queries `user_key` has in flight, out of the limit it was last acquired with.
+    pub fn in_flight(&self, user_key: &str) -> usize {
+        let in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        in_flight.get(user_key).map_or(0, |(limit, semaphore)| {
+            limit.saturating_sub(semaphore.available_permits())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_each_user_separately() {
+        let limiter = QueryLimiter::new();
+
+        let first = limiter.try_acquire("1", 2);
+        let second = limiter.try_acquire("1", 2);
+        assert!(first.is_some() && second.is_some());
+        assert!(limiter.try_acquire("1", 2).is_none());
+        assert!(limiter.try_acquire("2", 2).is_some());
+
+        assert_eq!(limiter.in_flight("1"), 2);
+
+        drop(first);
+        assert_eq!(limiter.in_flight("1"), 1);
+        assert!(limiter.try_acquire("1", 2).is_some());
+    }
+
+    #[test]
+    fn test_idle_users_are_forgotten() {
+        let limiter = QueryLimiter::new();
+
+        let permit = limiter.try_acquire("1", 3);
+        drop(permit);
+        let _permit = limiter.try_acquire("2", 3);
+        assert_eq!(limiter.in_flight.lock().unwrap().len(), 1);
+    }
+}
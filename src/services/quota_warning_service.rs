@@ -0,0 +1,159 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use tracing::warn;
+
+use crate::models::{QuotaKind, QuotaWarning, SystemNotice};
+use crate::services::{ConfigurationAccess, ConfigurationManager, EmailService, WebSocketService};
+
+/// Response header carrying [`QuotaWarning::header_value`] while a quota is
+/// past its warning threshold.
+pub const QUOTA_WARNING_HEADER: &str = "x-lunarbase-warning";
+
+/// (quota, subject)
+type QuotaKey = (QuotaKind, String);
+
+/// Warns before a quota starts rejecting requests. Every accepted request
+/// reports its usage; while usage is at or above `system.quota_warning_percent`
+/// of the limit the caller gets a warning for its response, and the crossing
+/// itself is announced once on the `_system` WebSocket channel and by email.
+#[derive(Clone)]
+pub struct QuotaWarningService {
+    config_manager: ConfigurationManager,
+    email_service: EmailService,
+    websocket_service: Arc<WebSocketService>,
+    /// Quotas whose last reported usage was past the threshold
+    crossed: Arc<Mutex<HashSet<QuotaKey>>>,
+}
+
+impl ConfigurationAccess for QuotaWarningService {
+    fn config_manager(&self) -> &ConfigurationManager {
+        &self.config_manager
+    }
+}
+
+/// Whether `used` is at least `percent` percent of `limit`; a percent of zero
+/// disables warnings.
+fn threshold_reached(used: usize, limit: usize, percent: u32) -> bool {
+    percent > 0 && limit > 0 && used.saturating_mul(100) >= limit.saturating_mul(percent as usize)
+}
+
+/// Updates the crossing state of `key` and returns whether it just crossed.
+/// Usage below the threshold forgets the key, so the next crossing notifies
+/// again.
+fn track_crossing(crossed: &mut HashSet<QuotaKey>, key: QuotaKey, reached: bool) -> bool {
+    if reached {
+        crossed.insert(key)
+    } else {
+        crossed.remove(&key);
+        false
+    }
+}
+
+impl QuotaWarningService {
+    pub fn new(
+        config_manager: ConfigurationManager,
+        email_service: EmailService,
+        websocket_service: Arc<WebSocketService>,
+    ) -> Self {
+        Self {
+            config_manager,
+            email_service,
+            websocket_service,
+            crossed: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Reports that `subject` now uses `used` of the `limit` of `quota`, and
+    /// returns the warning to attach to the response while it is past the
+    /// threshold.
+    pub async fn observe(
+        &self,
+        quota: QuotaKind,
+        subject: &str,
+        used: usize,
+        limit: usize,
+    ) -> Option<QuotaWarning> {
+        let percent = self.get_quota_warning_percent().await;
+        let reached = threshold_reached(used, limit, percent);
+        let crossed_now = track_crossing(
+            &mut self.crossed.lock().unwrap_or_else(|e| e.into_inner()),
+            (quota, subject.to_string()),
+            reached,
+        );
+        if !reached {
+            return None;
+        }
+
+        let warning = QuotaWarning {
+            quota,
+            subject: subject.to_string(),
+            used,
+            limit,
+            threshold_percent: percent,
+            triggered_at: Utc::now().to_rfc3339(),
+        };
+        if crossed_now {
+            let service = self.clone();
+            let notice = warning.clone();
+            tokio::spawn(async move { service.notify(notice).await });
+        }
+        Some(warning)
+    }
+
+    async fn notify(&self, warning: QuotaWarning) {
+        warn!(
+            "Quota {} of {} reached {} of its limit of {}",
+            warning.quota.as_str(),
+            warning.subject,
+            warning.used,
+            warning.limit
+        );
+
+        self.websocket_service
+            .broadcast_system_notice(SystemNotice::QuotaWarning(warning.clone()))
+            .await;
+
+        let recipient = self.get_quota_warning_email().await;
+        if !recipient.is_empty()
+            && let Err(e) = self
+                .email_service
+                .send_quota_warning(&recipient, &warning)
+                .await
+        {
+            warn!("Failed to email quota warning: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_reached() {
+        assert!(!threshold_reached(3, 5, 80));
+        assert!(threshold_reached(4, 5, 80));
+        assert!(threshold_reached(5, 5, 80));
+        assert!(!threshold_reached(5, 5, 0));
+        assert!(!threshold_reached(1, 0, 80));
+    }
+
+    #[test]
+    fn test_crossing_notifies_once_until_usage_drops() {
+        let mut crossed = HashSet::new();
+        let key = || (QuotaKind::GuestSessions, "203.0.113.7".to_string());
+
+        assert!(track_crossing(&mut crossed, key(), true));
+        assert!(!track_crossing(&mut crossed, key(), true));
+        assert!(track_crossing(
+            &mut crossed,
+            (QuotaKind::ConcurrentQueries, "203.0.113.7".to_string()),
+            true
+        ));
+
+        assert!(!track_crossing(&mut crossed, key(), false));
+        assert!(track_crossing(&mut crossed, key(), true));
+    }
+}
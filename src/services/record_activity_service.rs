@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use tracing::{debug, warn};
+
+use crate::database::prepared::{PreparedSql, SqlBind};
+use crate::models::{
+    AnalyticsGranularity, RecordActivityKind, RecordAnalytics, RecordAnalyticsBucket,
+    RecordAnalyticsTotals, RecordDeletionAlert,
+};
+use crate::schema::{collection_activity, collections};
+use crate::services::{ConfigurationAccess, ConfigurationManager, EmailService};
+use crate::utils::LunarbaseError;
+
+type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+/// Days before the current one whose average a day's deletions are compared to.
+const TRAILING_DAYS: i64 = 7;
+
+/// Counts record changes per collection and day in `collection_activity`,
+/// serves them as analytics series and alerts by email and/or webhook when a
+/// collection deletes far more records than usual.
+#[derive(Clone)]
+pub struct RecordActivityService {
+    pool: DbPool,
+    config_manager: ConfigurationManager,
+    email_service: EmailService,
+    http_client: reqwest::Client,
+    /// Day each collection was last alerted on, so a spike alerts once a day.
+    alerted: Arc<Mutex<HashMap<String, NaiveDate>>>,
+}
+
+impl ConfigurationAccess for RecordActivityService {
+    fn config_manager(&self) -> &ConfigurationManager {
+        &self.config_manager
+    }
+}
+
+/// Whether `deletions` exceed `threshold` times the trailing daily average.
+/// Averages below one count as one, so a collection that rarely deletes
+/// anything does not alert on its first few deletions.
+fn exceeds_trailing_average(deletions: i64, trailing_average: f64, threshold: u32) -> bool {
+    deletions as f64 > threshold as f64 * trailing_average.max(1.0)
+}
+
+impl RecordActivityService {
+    pub fn new(
+        pool: DbPool,
+        config_manager: ConfigurationManager,
+        email_service: EmailService,
+    ) -> Self {
+        Self {
+            pool,
+            config_manager,
+            email_service,
+            http_client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            alerted: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Counts one change of `kind` in `collection_name` for today. Failures
+    /// are logged rather than failing the change itself.
+    pub async fn record(&self, collection_name: &str, kind: RecordActivityKind) {
+        let today = Utc::now().date_naive();
+        if let Err(e) = self.increment(collection_name, kind, today) {
+            warn!(
+                "Failed to count record activity in collection '{}': {}",
+                collection_name, e
+            );
+            return;
+        }
+
+        if kind == RecordActivityKind::Deleted {
+            self.check_deletion_spike(collection_name, today).await;
+        }
+    }
+
+    fn increment(
+        &self,
+        collection_name: &str,
+        kind: RecordActivityKind,
+        day: NaiveDate,
+    ) -> Result<(), LunarbaseError> {
+        let (created, updated, deleted) = match kind {
+            RecordActivityKind::Created => (1, 0, 0),
+            RecordActivityKind::Updated => (0, 1, 0),
+            RecordActivityKind::Deleted => (0, 0, 1),
+        };
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        PreparedSql::new(
+            "INSERT INTO collection_activity \
+             (collection_id, day, created_count, updated_count, deleted_count) \
+             SELECT id, ?, ?, ?, ? FROM collections WHERE name = ? \
+             ON CONFLICT (collection_id, day) DO UPDATE SET \
+             created_count = created_count + excluded.created_count, \
+             updated_count = updated_count + excluded.updated_count, \
+             deleted_count = deleted_count + excluded.deleted_count",
+        )
+        .bind(SqlBind::Text(Some(day.format("%Y-%m-%d").to_string())))
+        .bind(SqlBind::BigInt(Some(created)))
+        .bind(SqlBind::BigInt(Some(updated)))
+        .bind(SqlBind::BigInt(Some(deleted)))
+        .bind(SqlBind::Text(Some(collection_name.to_string())))
+        .execute(&mut conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+        Ok(())
+    }
+
+    /// Deletions of `day` and the average daily deletions of the
+    /// `TRAILING_DAYS` days before it.
+    fn deletion_counts(
+        &self,
+        collection_name: &str,
+        day: NaiveDate,
+    ) -> Result<(i64, f64), LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let rows: Vec<(NaiveDate, i64)> = collection_activity::table
+            .inner_join(collections::table)
+            .filter(collections::name.eq(collection_name))
+            .filter(collection_activity::day.ge(day - chrono::Duration::days(TRAILING_DAYS)))
+            .filter(collection_activity::day.le(day))
+            .select((collection_activity::day, collection_activity::deleted_count))
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let deletions = rows
+            .iter()
+            .find(|(row_day, _)| *row_day == day)
+            .map_or(0, |(_, count)| *count);
+        let trailing: i64 = rows
+            .iter()
+            .filter(|(row_day, _)| *row_day < day)
+            .map(|(_, count)| count)
+            .sum();
+        Ok((deletions, trailing as f64 / TRAILING_DAYS as f64))
+    }
+
+    async fn check_deletion_spike(&self, collection_name: &str, day: NaiveDate) {
+        let threshold = self.get_record_deletion_alert_threshold().await;
+        if threshold == 0 {
+            return;
+        }
+        let alerted_today = |alerted: Option<&NaiveDate>| alerted == Some(&day);
+        if alerted_today(
+            self.alerted
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(collection_name),
+        ) {
+            return;
+        }
+
+        let (deletions, trailing_average) = match self.deletion_counts(collection_name, day) {
+            Ok(counts) => counts,
+            Err(e) => {
+                warn!(
+                    "Failed to read deletions of collection '{}': {}",
+                    collection_name, e
+                );
+                return;
+            }
+        };
+        if !exceeds_trailing_average(deletions, trailing_average, threshold) {
+            return;
+        }
+
+        // Another deletion may have alerted since the check above
+        let previous = self
+            .alerted
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(collection_name.to_string(), day);
+        if alerted_today(previous.as_ref()) {
+            return;
+        }
+
+        let alert = RecordDeletionAlert {
+            collection: collection_name.to_string(),
+            day: day.format("%Y-%m-%d").to_string(),
+            deletions,
+            trailing_average,
+            threshold,
+            triggered_at: Utc::now().to_rfc3339(),
+        };
+        let service = self.clone();
+        tokio::spawn(async move { service.send_alert(alert).await });
+    }
+
+    async fn send_alert(&self, alert: RecordDeletionAlert) {
+        warn!(
+            "Collection '{}' deleted {} records on {}, trailing average {:.1}",
+            alert.collection, alert.deletions, alert.day, alert.trailing_average
+        );
+
+        let recipient = self.get_record_deletion_alert_email().await;
+        if !recipient.is_empty()
+            && let Err(e) = self
+                .email_service
+                .send_record_deletion_alert(&recipient, &alert)
+                .await
+        {
+            warn!("Failed to email record deletion alert: {}", e);
+        }
+
+        let webhook_url = self.get_record_deletion_alert_webhook_url().await;
+        if webhook_url.is_empty() {
+            return;
+        }
+        match self
+            .http_client
+            .post(&webhook_url)
+            .json(&alert)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+        {
+            Ok(_) => debug!("Record deletion alert delivered to webhook"),
+            Err(e) => warn!("Record deletion alert webhook failed: {}", e),
+        }
+    }
+
+    /// Created, updated and deleted record counts over the last `window_days`
+    /// days, for one collection or all of them, only those of `workspace_id`
+    /// when given.
+    pub async fn analytics(
+        &self,
+        collection_name: Option<&str>,
+        workspace_id: Option<i32>,
+        window_days: i64,
+        granularity: AnalyticsGranularity,
+    ) -> Result<RecordAnalytics, LunarbaseError> {
+        self.compute_analytics(
+            collection_name,
+            workspace_id,
+            window_days,
+            granularity,
+            Utc::now().date_naive(),
+        )
+    }
+
+    fn compute_analytics(
+        &self,
+        collection_name: Option<&str>,
+        workspace_id: Option<i32>,
+        window_days: i64,
+        granularity: AnalyticsGranularity,
+        today: NaiveDate,
+    ) -> Result<RecordAnalytics, LunarbaseError> {
+        use diesel::sql_types::{BigInt, Text};
+
+        #[derive(QueryableByName)]
+        struct BucketRow {
+            #[diesel(sql_type = Text)]
+            bucket: String,
+            #[diesel(sql_type = BigInt)]
+            created: i64,
+            #[diesel(sql_type = BigInt)]
+            updated: i64,
+            #[diesel(sql_type = BigInt)]
+            deleted: i64,
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        if let Some(name) = collection_name {
+            let mut query = collections::table
+                .filter(collections::name.eq(name))
+                .into_boxed();
+            if let Some(workspace_id) = workspace_id {
+                query = query.filter(collections::workspace_id.eq(workspace_id));
+            }
+            let exists: i64 = query
+                .count()
+                .get_result(&mut conn)
+                .map_err(|_| LunarbaseError::DatabaseError)?;
+            if exists == 0 {
+                return Err(LunarbaseError::NotFound("Collection not found".to_string()));
+            }
+        }
+
+        let since = granularity.first_bucket(today, window_days);
+        let bucket = granularity.bucket_sql("a.day");
+        let mut collection_filter = String::new();
+        if collection_name.is_some() {
+            collection_filter.push_str(" AND c.name = ?");
+        }
+        if workspace_id.is_some() {
+            collection_filter.push_str(" AND c.workspace_id = ?");
+        }
+
+        let rows: HashMap<String, BucketRow> = PreparedSql::new(format!(
+            "SELECT {0} AS bucket, SUM(a.created_count) AS created, \
+             SUM(a.updated_count) AS updated, SUM(a.deleted_count) AS deleted \
+             FROM collection_activity a JOIN collections c ON c.id = a.collection_id \
+             WHERE a.day >= ?{1} GROUP BY {0}",
+            bucket, collection_filter
+        ))
+        .bind(SqlBind::Text(Some(since.format("%Y-%m-%d").to_string())))
+        .binds(collection_name.map(|name| SqlBind::Text(Some(name.to_string()))))
+        .binds(workspace_id.map(|id| SqlBind::BigInt(Some(i64::from(id)))))
+        .load::<BucketRow>(&mut conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?
+        .into_iter()
+        .map(|row| (row.bucket.clone(), row))
+        .collect();
+
+        let series: Vec<RecordAnalyticsBucket> = granularity
+            .bucket_starts(since, today)
+            .into_iter()
+            .map(|start| {
+                let key = start.format("%Y-%m-%d").to_string();
+                let row = rows.get(&key);
+                RecordAnalyticsBucket {
+                    created: row.map_or(0, |row| row.created),
+                    updated: row.map_or(0, |row| row.updated),
+                    deleted: row.map_or(0, |row| row.deleted),
+                    start: key,
+                }
+            })
+            .collect();
+
+        Ok(RecordAnalytics {
+            collection: collection_name.map(str::to_string),
+            window_days,
+            granularity,
+            since: since.format("%Y-%m-%d").to_string(),
+            totals: RecordAnalyticsTotals {
+                created: series.iter().map(|bucket| bucket.created).sum(),
+                updated: series.iter().map(|bucket| bucket.updated).sum(),
+                deleted: series.iter().map(|bucket| bucket.deleted).sum(),
+            },
+            series,
+            generated_at: Utc::now().to_rfc3339(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deletions_must_exceed_the_trailing_average_multiple() {
+        assert!(!exceeds_trailing_average(30, 10.0, 3));
+        assert!(exceeds_trailing_average(31, 10.0, 3));
+    }
+
+    #[test]
+    fn test_quiet_collections_compare_against_one_deletion_a_day() {
+        assert!(!exceeds_trailing_average(5, 0.0, 5));
+        assert!(exceeds_trailing_average(6, 0.0, 5));
+        assert!(!exceeds_trailing_average(5, 0.5, 5));
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::models::RecordResponse;
+
+type RecordKey = (String, String);
+
+struct CachedEntry<V> {
+    value: V,
+    stored_at: Instant,
+    last_used: u64,
+}
+
+/// TTL-aware LRU map shared by the record and query caches.
+pub(super) struct LruState<K, V> {
+    entries: HashMap<K, CachedEntry<V>>,
+    /// `last_used` tick -> key, oldest first, for LRU eviction
+    recency: BTreeMap<u64, K>,
+    tick: u64,
+}
+
+impl<K, V> Default for LruState<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> LruState<K, V> {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns a clone of the entry if it is younger than `ttl`; expired entries are dropped.
+    pub(super) fn get(&mut self, key: &K, ttl: Duration) -> Option<V> {
+        let fresh = self
+            .entries
+            .get(key)
+            .map(|entry| entry.stored_at.elapsed() < ttl)?;
+
+        if !fresh {
+            self.remove(key);
+            return None;
+        }
+
+        let tick = self.next_tick();
+        let entry = self.entries.get_mut(key)?;
+        let previous = std::mem::replace(&mut entry.last_used, tick);
+        let value = entry.value.clone();
+        self.recency.remove(&previous);
+        self.recency.insert(tick, key.clone());
+        Some(value)
+    }
+
+    pub(super) fn insert(&mut self, key: K, value: V, max_entries: usize) {
+        if max_entries == 0 {
+            return;
+        }
+
+        self.remove(&key);
+
+        while self.entries.len() >= max_entries {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+
+        let tick = self.next_tick();
+        self.recency.insert(tick, key.clone());
+        self.entries.insert(
+            key,
+            CachedEntry {
+                value,
+                stored_at: Instant::now(),
+                last_used: tick,
+            },
+        );
+    }
+
+    pub(super) fn remove(&mut self, key: &K) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+        }
+    }
+
+    pub(super) fn remove_where(&mut self, predicate: impl Fn(&K) -> bool) {
+        let keys: Vec<K> = self
+            .entries
+            .keys()
+            .filter(|key| predicate(key))
+            .cloned()
+            .collect();
+        for key in keys {
+            self.remove(&key);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecordCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+/// In-process LRU cache of single records keyed by (collection, record id).
+/// Only single-record reads go through it; list queries always hit the database.
+#[derive(Clone, Default)]
+pub struct RecordCache {
+    state: Arc<Mutex<LruState<RecordKey, RecordResponse>>>,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
+}
+
+impl RecordCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruState<RecordKey, RecordResponse>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns a clone of the cached record if it is younger than `ttl`, counting a hit or miss.
+    pub fn get(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+        ttl: Duration,
+    ) -> Option<RecordResponse> {
+        let record = self
+            .lock()
+            .get(&(collection_name.to_string(), record_id.to_string()), ttl);
+
+        let counter = if record.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        record
+    }
+
+    pub fn insert(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+        record: RecordResponse,
+        max_entries: usize,
+    ) {
+        self.lock().insert(
+            (collection_name.to_string(), record_id.to_string()),
+            record,
+            max_entries,
+        );
+    }
+
+    pub fn invalidate(&self, collection_name: &str, record_id: &str) {
+        self.lock()
+            .remove(&(collection_name.to_string(), record_id.to_string()));
+    }
+
+    /// Drops every cached record of a collection, e.g. after a schema change or delete.
+    pub fn invalidate_collection(&self, collection_name: &str) {
+        self.lock()
+            .remove_where(|(collection, _)| collection == collection_name);
+    }
+
+    pub fn stats(&self) -> RecordCacheStats {
+        RecordCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.lock().len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    fn record(id: i32) -> RecordResponse {
+        RecordResponse {
+            id: id.to_string(),
+            collection_id: "flags".to_string(),
+            data: json!({ "title": format!("record {}", id) }),
+            created_at: "2024-01-01 12:00:00".to_string(),
+            updated_at: "2024-01-01 12:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_hit_miss_and_invalidation() {
+        let cache = RecordCache::new();
+
+        assert!(cache.get("flags", "1", TTL).is_none());
+        cache.insert("flags", "1", record(1), 10);
+        assert_eq!(cache.get("flags", "1", TTL).unwrap().id, "1");
+
+        cache.invalidate("flags", "1");
+        assert!(cache.get("flags", "1", TTL).is_none());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 0));
+    }
+
+    #[test]
+    fn test_least_recently_used_entry_is_evicted() {
+        let cache = RecordCache::new();
+        cache.insert("flags", "1", record(1), 2);
+        cache.insert("flags", "2", record(2), 2);
+
+        // Touch 1 so 2 becomes the eviction candidate
+        assert!(cache.get("flags", "1", TTL).is_some());
+        cache.insert("flags", "3", record(3), 2);
+
+        assert!(cache.get("flags", "1", TTL).is_some());
+        assert!(cache.get("flags", "2", TTL).is_none());
+        assert!(cache.get("flags", "3", TTL).is_some());
+    }
+
+    #[test]
+    fn test_expired_entries_and_collection_invalidation() {
+        let cache = RecordCache::new();
+        cache.insert("flags", "1", record(1), 10);
+        cache.insert("settings", "1", record(1), 10);
+
+        assert!(cache.get("flags", "1", Duration::ZERO).is_none());
+        assert_eq!(cache.stats().entries, 1);
+
+        cache.invalidate_collection("settings");
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
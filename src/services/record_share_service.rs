@@ -0,0 +1,352 @@
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::models::{
+    CreateRecordShareRequest, NewRecordShare, RecordResponse, RecordShare, RecordShareResponse,
+    SharedRecordResponse,
+};
+use crate::schema::record_shares;
+use crate::services::{CollectionService, PasswordService};
+use crate::utils::LunarbaseError;
+
+type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+const DEFAULT_EXPIRY_HOURS: i64 = 24;
+const MAX_EXPIRY_HOURS: i64 = 720;
+const OWNERSHIP_FIELDS: [&str; 2] = ["owner_id", "author_id"];
+
+#[derive(Clone)]
+pub struct RecordShareService {
+    pub pool: DbPool,
+    collection_service: CollectionService,
+    signing_key: Vec<u8>,
+    password_service: PasswordService,
+}
+
+impl RecordShareService {
+    pub fn new(
+        pool: DbPool,
+        collection_service: CollectionService,
+        signing_key: &str,
+        password_service: PasswordService,
+    ) -> Self {
+        Self {
+            pool,
+            collection_service,
+            signing_key: signing_key.as_bytes().to_vec(),
+            password_service,
+        }
+    }
+
+    /// `record_id` must already be normalized and readable by `created_by`.
+    pub async fn create_share(
+        &self,
+        collection_name: &str,
+        record_id: &str,
+        request: CreateRecordShareRequest,
+        created_by: i32,
+    ) -> Result<RecordShareResponse, LunarbaseError> {
+        let collection = self
+            .collection_service
+            .get_collection(collection_name)
+            .await?;
+        self.collection_service
+            .get_record(collection_name, record_id)
+            .await?;
+
+        let mut errors = Vec::new();
+        let expires_in_hours = request.expires_in_hours.unwrap_or(DEFAULT_EXPIRY_HOURS);
+        if !(1..=MAX_EXPIRY_HOURS).contains(&expires_in_hours) {
+            errors.push(format!(
+                "expires_in_hours must be between 1 and {}",
+                MAX_EXPIRY_HOURS
+            ));
+        }
+        if request.max_views.is_some_and(|max_views| max_views < 1) {
+            errors.push("max_views must be at least 1".to_string());
+        }
+        if request
+            .password
+            .as_ref()
+            .is_some_and(|password| password.is_empty())
+        {
+            errors.push("Password cannot be empty".to_string());
+        }
+        if let Some(fields) = &request.fields {
+            if fields.is_empty() {
+                errors.push("Fields must list at least one field".to_string());
+            }
+            for field in fields {
+                if !collection.schema.fields.iter().any(|f| &f.name == field) {
+                    errors.push(format!(
+                        "Field '{}' does not exist in collection '{}'",
+                        field, collection_name
+                    ));
+                }
+            }
+        }
+        if !errors.is_empty() {
+            return Err(LunarbaseError::ValidationError(errors));
+        }
+
+        let expires_at = Utc::now() + Duration::hours(expires_in_hours);
+        let token = self.sign(&expires_at);
+        let password_hash = match &request.password {
+            Some(password) => Some(self.password_service.hash(password).await?),
+            None => None,
+        };
+        let fields = match &request.fields {
+            Some(fields) => {
+                Some(serde_json::to_string(fields).map_err(|_| LunarbaseError::InternalError)?)
+            }
+            None => None,
+        };
+
+        let new_share = NewRecordShare {
+            token_hash: hash_token(&token),
+            collection_name: collection_name.to_string(),
+            record_id: record_id.to_string(),
+            fields,
+            password_hash,
+            max_views: request.max_views,
+            expires_at: expires_at.naive_utc(),
+            created_by,
+        };
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        diesel::insert_into(record_shares::table)
+            .values(&new_share)
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let share = record_shares::table
+            .filter(record_shares::token_hash.eq(&new_share.token_hash))
+            .select(RecordShare::as_select())
+            .first(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        let mut response = share.to_response();
+        response.url = Some(format!("/api/share/{}", token));
+        response.token = Some(token);
+        Ok(response)
+    }
+
+    pub async fn list_shares(
+        &self,
+        created_by: i32,
+    ) -> Result<Vec<RecordShareResponse>, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let shares = record_shares::table
+            .filter(record_shares::created_by.eq(created_by))
+            .order(record_shares::id.desc())
+            .select(RecordShare::as_select())
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(shares.iter().map(RecordShare::to_response).collect())
+    }
+
+    /// Only the creator can revoke a share, unless `is_admin` is set.
+    pub async fn revoke_share(
+        &self,
+        share_id: i32,
+        user_id: i32,
+        is_admin: bool,
+    ) -> Result<(), LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let share = record_shares::table
+            .find(share_id)
+            .select(RecordShare::as_select())
+            .first(&mut conn)
+            .map_err(|_| LunarbaseError::NotFound("Share link not found".to_string()))?;
+
+        if share.created_by != user_id && !is_admin {
+            return Err(LunarbaseError::NotFound("Share link not found".to_string()));
+        }
+
+        diesel::delete(record_shares::table.find(share_id))
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(())
+    }
+
+    /// Counts a view and returns the shared projection of the record. Wrong
+    /// passwords do not use up views.
+    pub async fn open_share(
+        &self,
+        token: &str,
+        password: Option<&str>,
+    ) -> Result<SharedRecordResponse, LunarbaseError> {
+        let not_found = || LunarbaseError::NotFound("Share link not found".to_string());
+
+        // Forged tokens are rejected before touching the database, and the
+        // signed expiry answers stale links without a lookup
+        let signed_expiry = self.verify(token).ok_or_else(not_found)?;
+        if signed_expiry <= Utc::now().timestamp() {
+            return Err(LunarbaseError::ShareLinkExpired);
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let share = record_shares::table
+            .filter(record_shares::token_hash.eq(hash_token(token)))
+            .select(RecordShare::as_select())
+            .first(&mut conn)
+            .map_err(|_| not_found())?;
+
+        if share.expires_at <= Utc::now().naive_utc()
+            || share
+                .max_views
+                .is_some_and(|max_views| share.view_count >= max_views)
+        {
+            return Err(LunarbaseError::ShareLinkExpired);
+        }
+
+        if let Some(password_hash) = &share.password_hash {
+            let valid = match password {
+                Some(password) => self
+                    .password_service
+                    .verify(password, password_hash)
+                    .await
+                    .unwrap_or(false),
+                None => false,
+            };
+            if !valid {
+                return Err(LunarbaseError::SharePasswordInvalid);
+            }
+        }
+
+        // Conditional so concurrent visitors cannot exceed the view limit
+        let counted =
+            diesel::update(
+                record_shares::table
+                    .filter(record_shares::id.eq(share.id))
+                    .filter(record_shares::max_views.is_null().or(
+                        record_shares::view_count.lt(record_shares::max_views.assume_not_null()),
+                    )),
+            )
+            .set(record_shares::view_count.eq(record_shares::view_count + 1))
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+        if counted == 0 {
+            return Err(LunarbaseError::ShareLinkExpired);
+        }
+
+        let record = self
+            .collection_service
+            .get_record(&share.collection_name, &share.record_id)
+            .await
+            .map_err(|_| not_found())?;
+
+        Ok(SharedRecordResponse {
+            collection_name: share.collection_name.clone(),
+            record: project_record(record, share.get_fields().as_deref()),
+            expires_at: share.expires_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            views_remaining: share
+                .max_views
+                .map(|max_views| max_views - share.view_count - 1),
+        })
+    }
+
+    /// `{nonce}.{expiry}.{signature}`, where the signature covers the nonce
+    /// and the expiry as a unix timestamp.
+    fn sign(&self, expires_at: &DateTime<Utc>) -> String {
+        let payload = format!("{}.{}", Uuid::new_v4().simple(), expires_at.timestamp());
+        let signature = self.signature(&payload).finalize().into_bytes();
+        format!("{}.{}", payload, encode_hex(&signature))
+    }
+
+    /// The signed expiry of a token, or `None` when it was not issued here.
+    fn verify(&self, token: &str) -> Option<i64> {
+        let (payload, signature) = token.rsplit_once('.')?;
+        let (_, expiry) = payload.split_once('.')?;
+        let expected = decode_hex(signature)?;
+        self.signature(payload).verify_slice(&expected).ok()?;
+        expiry.parse().ok()
+    }
+
+    fn signature(&self, payload: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.signing_key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        mac
+    }
+}
+
+/// Ownership metadata never leaves through a share link, and when the share
+/// lists fields only those are kept.
+fn project_record(mut record: RecordResponse, fields: Option<&[String]>) -> RecordResponse {
+    if let Some(data) = record.data.as_object_mut() {
+        data.retain(|key, _| {
+            !OWNERSHIP_FIELDS.contains(&key.as_str())
+                && fields.is_none_or(|fields| fields.contains(key))
+        });
+    }
+    record
+}
+
+fn hash_token(token: &str) -> String {
+    encode_hex(&Sha256::digest(token.as_bytes()))
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(data: serde_json::Value) -> RecordResponse {
+        RecordResponse {
+            id: "1".to_string(),
+            collection_id: "articles".to_string(),
+            data,
+            created_at: "2024-01-01 12:00:00".to_string(),
+            updated_at: "2024-01-01 12:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_projection_masks_ownership_and_unlisted_fields() {
+        let data = json!({"title": "Hello", "draft_notes": "internal", "owner_id": 7});
+
+        let projected = project_record(record(data.clone()), None);
+        assert_eq!(
+            projected.data,
+            json!({"title": "Hello", "draft_notes": "internal"})
+        );
+
+        let projected = project_record(record(data), Some(&["title".to_string()]));
+        assert_eq!(projected.data, json!({"title": "Hello"}));
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let bytes = [0u8, 15, 16, 255];
+        assert_eq!(encode_hex(&bytes), "000f10ff");
+        assert_eq!(decode_hex("000f10ff"), Some(bytes.to_vec()));
+        assert_eq!(decode_hex("0f1"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+}
@@ -290,32 +290,46 @@ impl S3Service {
         Ok(())
     }
 
+    /// Every object under `prefix`, following continuation tokens past the
+    /// 1000 keys a single listing returns.
     pub async fn list_objects(&self, prefix: &str) -> Result<Vec<S3Object>, S3ServiceError> {
-        let response = self
-            .client
-            .list_objects_v2()
-            .bucket(&self.bucket_name)
-            .prefix(prefix)
-            .send()
-            .await
-            .map_err(|e| S3ServiceError::SdkError(e.to_string()))?;
-
         let mut objects = Vec::new();
-        for object in response.contents() {
-            if let (Some(key), Some(last_modified), Some(size)) =
-                (object.key(), object.last_modified(), object.size())
-            {
-                let chrono_datetime = DateTime::<Utc>::from_timestamp(
-                    last_modified.secs(),
-                    last_modified.subsec_nanos(),
-                )
-                .unwrap_or_else(|| Utc::now());
-
-                objects.push(S3Object {
-                    key: key.to_string(),
-                    last_modified: chrono_datetime,
-                    size: size as u64,
-                });
+        let mut continuation_token: Option<String> = None;
+
+        loop {
+            let response = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket_name)
+                .prefix(prefix)
+                .set_continuation_token(continuation_token.take())
+                .send()
+                .await
+                .map_err(|e| S3ServiceError::SdkError(e.to_string()))?;
+
+            for object in response.contents() {
+                if let (Some(key), Some(last_modified), Some(size)) =
+                    (object.key(), object.last_modified(), object.size())
+                {
+                    let chrono_datetime = DateTime::<Utc>::from_timestamp(
+                        last_modified.secs(),
+                        last_modified.subsec_nanos(),
+                    )
+                    .unwrap_or_else(|| Utc::now());
+
+                    objects.push(S3Object {
+                        key: key.to_string(),
+                        last_modified: chrono_datetime,
+                        size: size as u64,
+                    });
+                }
+            }
+
+            match response.next_continuation_token() {
+                Some(token) if response.is_truncated().unwrap_or(false) => {
+                    continuation_token = Some(token.to_string());
+                }
+                _ => break,
             }
         }
 
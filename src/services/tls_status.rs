@@ -0,0 +1,313 @@
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use rustls_acme::{AccountCache, CertCache};
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use utoipa::ToSchema;
+use x509_parser::pem::Pem;
+
+/// Delay before retrying a failed certificate order, doubled on each
+/// consecutive failure up to `RENEWAL_RETRY_MAX`. Let's Encrypt only allows a
+/// handful of failed validations per hour.
+const RENEWAL_RETRY_BASE: Duration = Duration::from_secs(60);
+const RENEWAL_RETRY_MAX: Duration = Duration::from_secs(6 * 60 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct CertificateInfo {
+    /// DNS names the certificate is valid for
+    #[schema(example = json!(["example.com", "www.example.com"]))]
+    pub domains: Vec<String>,
+    #[schema(example = "C=US, O=Let's Encrypt, CN=R11")]
+    pub issuer: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RenewalAttempt {
+    pub at: DateTime<Utc>,
+    pub succeeded: bool,
+    /// Error of a failed attempt
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct TlsStatusReport {
+    /// Whether ACME certificate management is enabled
+    pub enabled: bool,
+    /// Challenge used to prove domain ownership
+    #[schema(example = "http-01")]
+    pub challenge: Option<String>,
+    /// Domains certificates are requested for
+    pub domains: Vec<String>,
+    /// The certificate currently served, if one has been deployed
+    pub certificate: Option<CertificateInfo>,
+    #[schema(example = 42)]
+    pub days_until_expiry: Option<i64>,
+    /// The most recent certificate order
+    pub last_renewal: Option<RenewalAttempt>,
+    pub consecutive_failures: u32,
+    /// When the next order is attempted after a failure
+    pub next_retry_at: Option<DateTime<Utc>>,
+}
+
+/// Certificate and renewal state of the ACME-managed TLS listeners, shared
+/// between the ACME task, the health report and the admin API.
+#[derive(Clone, Default)]
+pub struct TlsStatus {
+    state: Arc<RwLock<TlsStatusReport>>,
+}
+
+impl TlsStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn enable(&self, domains: Vec<String>, challenge: &str) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.enabled = true;
+        state.domains = domains;
+        state.challenge = Some(challenge.to_string());
+    }
+
+    /// Records the certificate deployed from `pem`, the key and chain format
+    /// the ACME cache stores.
+    pub fn record_certificate(&self, pem: &[u8]) -> Result<CertificateInfo, String> {
+        let certificate = parse_certificate(pem)?;
+        self.state
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .certificate = Some(certificate.clone());
+        Ok(certificate)
+    }
+
+    pub fn record_renewal_success(&self) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.last_renewal = Some(RenewalAttempt {
+            at: Utc::now(),
+            succeeded: true,
+            error: None,
+        });
+        state.consecutive_failures = 0;
+        state.next_retry_at = None;
+    }
+
+    /// Records a failed order and returns how many orders failed in a row and
+    /// how long to wait before the next one.
+    pub fn record_renewal_failure(&self, error: &str) -> (u32, Duration) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.consecutive_failures += 1;
+        let delay = renewal_backoff(state.consecutive_failures);
+        let now = Utc::now();
+        state.last_renewal = Some(RenewalAttempt {
+            at: now,
+            succeeded: false,
+            error: Some(error.to_string()),
+        });
+        state.next_retry_at = chrono::Duration::from_std(delay).ok().map(|d| now + d);
+        (state.consecutive_failures, delay)
+    }
+
+    pub fn report(&self) -> TlsStatusReport {
+        let mut report = self.state.read().unwrap_or_else(|e| e.into_inner()).clone();
+        report.days_until_expiry = report
+            .certificate
+            .as_ref()
+            .map(|certificate| (certificate.not_after - Utc::now()).num_days());
+        report
+    }
+}
+
+/// Delay before the order following `failures` consecutive failures.
+pub fn renewal_backoff(failures: u32) -> Duration {
+    let doublings = failures.saturating_sub(1).min(16);
+    RENEWAL_RETRY_BASE
+        .saturating_mul(1 << doublings)
+        .min(RENEWAL_RETRY_MAX)
+}
+
+/// Reads the leaf certificate, the first `CERTIFICATE` block in `pem`.
+pub fn parse_certificate(pem: &[u8]) -> Result<CertificateInfo, String> {
+    let block = Pem::iter_from_buffer(pem)
+        .filter_map(Result::ok)
+        .find(|block| block.label == "CERTIFICATE")
+        .ok_or_else(|| "No certificate found in PEM data".to_string())?;
+    let certificate = block
+        .parse_x509()
+        .map_err(|e| format!("Invalid certificate: {}", e))?;
+
+    let mut domains: Vec<String> = match certificate.subject_alternative_name() {
+        Ok(Some(names)) => names
+            .value
+            .general_names
+            .iter()
+            .filter_map(|name| match name {
+                x509_parser::extensions::GeneralName::DNSName(dns) => Some(dns.to_string()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    if domains.is_empty() {
+        domains = certificate
+            .subject()
+            .iter_common_name()
+            .filter_map(|name| name.as_str().ok())
+            .map(str::to_string)
+            .collect();
+    }
+
+    let timestamp = |seconds: i64| {
+        Utc.timestamp_opt(seconds, 0)
+            .single()
+            .ok_or_else(|| "Certificate validity is out of range".to_string())
+    };
+    let validity = certificate.validity();
+
+    Ok(CertificateInfo {
+        domains,
+        issuer: certificate.issuer().to_string(),
+        not_before: timestamp(validity.not_before.timestamp())?,
+        not_after: timestamp(validity.not_after.timestamp())?,
+    })
+}
+
+/// ACME cache that reports every certificate it loads or stores to
+/// [`TlsStatus`], so the status shows the certificate actually deployed.
+pub struct TlsStatusCache<C> {
+    inner: C,
+    status: TlsStatus,
+}
+
+impl<C> TlsStatusCache<C> {
+    pub fn new(inner: C, status: TlsStatus) -> Self {
+        Self { inner, status }
+    }
+
+    fn observe(&self, pem: &[u8]) {
+        if let Err(e) = self.status.record_certificate(pem) {
+            tracing::warn!("Could not read the ACME certificate: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl<C: CertCache> CertCache for TlsStatusCache<C> {
+    type EC = C::EC;
+
+    async fn load_cert(
+        &self,
+        domains: &[String],
+        directory_url: &str,
+    ) -> Result<Option<Vec<u8>>, Self::EC> {
+        let pem = self.inner.load_cert(domains, directory_url).await?;
+        if let Some(pem) = &pem {
+            self.observe(pem);
+        }
+        Ok(pem)
+    }
+
+    async fn store_cert(
+        &self,
+        domains: &[String],
+        directory_url: &str,
+        cert: &[u8],
+    ) -> Result<(), Self::EC> {
+        self.observe(cert);
+        self.inner.store_cert(domains, directory_url, cert).await
+    }
+}
+
+#[async_trait]
+impl<C: AccountCache> AccountCache for TlsStatusCache<C> {
+    type EA = C::EA;
+
+    async fn load_account(
+        &self,
+        contact: &[String],
+        directory_url: &str,
+    ) -> Result<Option<Vec<u8>>, Self::EA> {
+        self.inner.load_account(contact, directory_url).await
+    }
+
+    async fn store_account(
+        &self,
+        contact: &[String],
+        directory_url: &str,
+        account: &[u8],
+    ) -> Result<(), Self::EA> {
+        self.inner
+            .store_account(contact, directory_url, account)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_CERTIFICATE: &str = "-----BEGIN CERTIFICATE-----
+MIIB4zCCAYigAwIBAgIUP6l3/Tj3XomPR5wBEaC3JbHOJvAwCgYIKoZIzj0EAwIw
+MjEUMBIGA1UEAwwLZXhhbXBsZS5jb20xGjAYBgNVBAoMEUx1bmFyQmFzZSBUZXN0
+IENBMB4XDTI2MTAxNTE2MDkwMFoXDTI3MDExMzE2MDkwMFowMjEUMBIGA1UEAwwL
+ZXhhbXBsZS5jb20xGjAYBgNVBAoMEUx1bmFyQmFzZSBUZXN0IENBMFkwEwYHKoZI
+zj0CAQYIKoZIzj0DAQcDQgAEAaARtACCX7t19AzSLJ4IShja273K0FB0GL8rIMaQ
+c/Aiqw6bmeQTr1d97bQ7PwcSuB6+Rkaw9b3WcjW2AqxHFqN8MHowHQYDVR0OBBYE
+FDGFGePb4FeYPjEJMu3R26eB41gdMB8GA1UdIwQYMBaAFDGFGePb4FeYPjEJMu3R
+26eB41gdMA8GA1UdEwEB/wQFMAMBAf8wJwYDVR0RBCAwHoILZXhhbXBsZS5jb22C
+D3d3dy5leGFtcGxlLmNvbTAKBggqhkjOPQQDAgNJADBGAiEA7bftb6eH1A1OAfn3
+TaD4S7B3awz7dWwn4esvI4WCFiICIQC8DB0jehkXJC6ypMMvObzmMGyJVu53s1Jf
+cbfvrcbB1A==
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_parse_certificate() {
+        let certificate = parse_certificate(TEST_CERTIFICATE.as_bytes()).unwrap();
+        assert_eq!(certificate.domains, vec!["example.com", "www.example.com"]);
+        assert_eq!(certificate.issuer, "CN=example.com, O=LunarBase Test CA");
+        assert_eq!(
+            certificate.not_after.to_rfc3339(),
+            "2027-01-13T16:09:00+00:00"
+        );
+        assert_eq!(
+            certificate.not_after - certificate.not_before,
+            chrono::Duration::days(90)
+        );
+
+        assert!(parse_certificate(b"not a certificate").is_err());
+    }
+
+    #[test]
+    fn test_renewal_failures_back_off_until_success() {
+        assert_eq!(renewal_backoff(1), Duration::from_secs(60));
+        assert_eq!(renewal_backoff(3), Duration::from_secs(240));
+        assert_eq!(renewal_backoff(40), RENEWAL_RETRY_MAX);
+
+        let status = TlsStatus::new();
+        status.enable(vec!["example.com".to_string()], "http-01");
+        status
+            .record_certificate(TEST_CERTIFICATE.as_bytes())
+            .unwrap();
+
+        assert_eq!(status.record_renewal_failure("rate limited").0, 1);
+        let (failures, delay) = status.record_renewal_failure("rate limited");
+        assert_eq!((failures, delay), (2, Duration::from_secs(120)));
+
+        let report = status.report();
+        assert!(report.enabled);
+        assert_eq!(report.consecutive_failures, 2);
+        assert!(report.next_retry_at.is_some());
+        let attempt = report.last_renewal.unwrap();
+        assert!(!attempt.succeeded);
+        assert_eq!(attempt.error.as_deref(), Some("rate limited"));
+        assert!(report.days_until_expiry.is_some());
+
+        status.record_renewal_success();
+        let report = status.report();
+        assert_eq!(report.consecutive_failures, 0);
+        assert!(report.next_retry_at.is_none());
+        assert!(report.last_renewal.unwrap().succeeded);
+    }
+}
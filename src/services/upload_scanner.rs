@@ -0,0 +1,451 @@
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use sha2::{Digest, Sha256};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::process::Command;
+
+use crate::models::{FieldValidationError, NewQuarantinedUpload};
+use crate::schema::quarantined_uploads;
+use crate::services::{ConfigurationAccess, ConfigurationManager};
+use crate::utils::{LunarbaseError, Message};
+
+type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+/// Size of the INSTREAM chunks sent to clamd, well below its default
+/// StreamMaxLength so a single chunk is never refused.
+const CLAMAV_CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanMode {
+    Off,
+    /// A clamd daemon at `host:port`
+    Clamav(String),
+    /// A scanner binary and its arguments, fed the file on stdin
+    Command(String),
+    /// A `storage.upload_scan_mode` this version does not know
+    Unknown(String),
+}
+
+impl ScanMode {
+    pub fn name(&self) -> &str {
+        match self {
+            ScanMode::Off => "off",
+            ScanMode::Clamav(_) => "clamav",
+            ScanMode::Command(_) => "command",
+            ScanMode::Unknown(mode) => mode,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanSettings {
+    pub mode: ScanMode,
+    pub timeout: Duration,
+    /// Whether uploads are accepted when the scanner cannot be used
+    pub fail_open: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ScanVerdict {
+    Clean,
+    /// The signature the scanner reported
+    Infected(String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScanError {
+    #[error("Scanner is not configured: {0}")]
+    NotConfigured(String),
+    #[error("Scanner is unavailable: {0}")]
+    Unavailable(#[from] std::io::Error),
+    #[error("Scanner did not finish within {0:?}")]
+    Timeout(Duration),
+    #[error("Unexpected scanner response: {0}")]
+    UnexpectedResponse(String),
+}
+
+/// Why an upload may not be stored.
+#[derive(Debug, PartialEq)]
+pub enum ScanRejection {
+    Infected(String),
+    /// The scanner failed and `storage.upload_scan_fail_open` is off
+    Unavailable,
+}
+
+impl ScanRejection {
+    /// The error reported for the field the file was uploaded to.
+    pub fn field_error(&self, field_name: &str) -> FieldValidationError {
+        let (code, message) = match self {
+            ScanRejection::Infected(signature) => (
+                "malware_detected",
+                Message::new("validation.malware_detected").arg("signature", signature),
+            ),
+            ScanRejection::Unavailable => (
+                "scan_unavailable",
+                Message::new("validation.scan_unavailable"),
+            ),
+        };
+        FieldValidationError {
+            field: field_name.to_string(),
+            code: code.to_string(),
+            message: message.arg("field", field_name),
+        }
+    }
+}
+
+/// Where an upload was headed, recorded with it when it is quarantined.
+#[derive(Debug, Clone, Copy)]
+pub struct UploadOrigin<'a> {
+    /// `None` for uploads outside of records, e.g. images
+    pub collection_name: Option<&'a str>,
+    pub field_name: &'a str,
+    pub user_id: Option<i32>,
+}
+
+/// Scans uploads with the scanner set up in the `storage.upload_scan_*`
+/// settings before they are stored, and keeps an audit entry of every file it
+/// rejects as infected.
+#[derive(Clone)]
+pub struct UploadScanner {
+    pool: DbPool,
+    config_manager: ConfigurationManager,
+}
+
+impl ConfigurationAccess for UploadScanner {
+    fn config_manager(&self) -> &ConfigurationManager {
+        &self.config_manager
+    }
+}
+
+impl UploadScanner {
+    pub fn new(pool: DbPool, config_manager: ConfigurationManager) -> Self {
+        Self {
+            pool,
+            config_manager,
+        }
+    }
+
+    pub async fn settings(&self) -> ScanSettings {
+        let mode = match self.get_upload_scan_mode().await.as_str() {
+            "off" => ScanMode::Off,
+            "clamav" => ScanMode::Clamav(self.get_upload_scan_clamav_address().await),
+            "command" => ScanMode::Command(self.get_upload_scan_command().await),
+            mode => ScanMode::Unknown(mode.to_string()),
+        };
+
+        ScanSettings {
+            mode,
+            timeout: Duration::from_secs(self.get_upload_scan_timeout_seconds().await.into()),
+            fail_open: self.get_upload_scan_fail_open().await,
+        }
+    }
+
+    /// `Ok` when the file may be stored. Infected files are quarantined
+    /// before they are rejected.
+    pub async fn check(
+        &self,
+        origin: UploadOrigin<'_>,
+        filename: &str,
+        content_type: &str,
+        data: &[u8],
+    ) -> Result<(), ScanRejection> {
+        let settings = self.settings().await;
+        if settings.mode == ScanMode::Off {
+            return Ok(());
+        }
+
+        match settings.scan(data).await {
+            Ok(ScanVerdict::Clean) => Ok(()),
+            Ok(ScanVerdict::Infected(signature)) => {
+                tracing::warn!(
+                    "Rejected upload '{}' for field '{}': {} found by {}",
+                    filename,
+                    origin.field_name,
+                    signature,
+                    settings.mode.name()
+                );
+                let quarantined = NewQuarantinedUpload {
+                    collection_name: origin.collection_name.map(str::to_string),
+                    field_name: origin.field_name.to_string(),
+                    filename: filename.to_string(),
+                    content_type: content_type.to_string(),
+                    size_bytes: data.len() as i64,
+                    sha256: Sha256::digest(data)
+                        .iter()
+                        .map(|byte| format!("{:02x}", byte))
+                        .collect(),
+                    scanner: settings.mode.name().to_string(),
+                    signature: signature.clone(),
+                    user_id: origin.user_id,
+                };
+                if let Err(e) = self.quarantine(&quarantined) {
+                    tracing::error!("Failed to record quarantined upload: {:?}", e);
+                }
+                Err(ScanRejection::Infected(signature))
+            }
+            Err(e) if settings.fail_open => {
+                tracing::warn!("Accepting upload '{}' without a scan: {}", filename, e);
+                Ok(())
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Rejecting upload '{}' that could not be scanned: {}",
+                    filename,
+                    e
+                );
+                Err(ScanRejection::Unavailable)
+            }
+        }
+    }
+
+    fn quarantine(&self, upload: &NewQuarantinedUpload) -> Result<(), LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::DatabaseError)?;
+        diesel::insert_into(quarantined_uploads::table)
+            .values(upload)
+            .execute(&mut conn)?;
+        Ok(())
+    }
+}
+
+impl ScanSettings {
+    pub async fn scan(&self, data: &[u8]) -> Result<ScanVerdict, ScanError> {
+        let scan = async {
+            match &self.mode {
+                ScanMode::Off => Ok(ScanVerdict::Clean),
+                ScanMode::Clamav(address) => scan_with_clamav(address, data).await,
+                ScanMode::Command(command) => scan_with_command(command, data).await,
+                ScanMode::Unknown(mode) => Err(ScanError::NotConfigured(format!(
+                    "unknown upload_scan_mode '{}'",
+                    mode
+                ))),
+            }
+        };
+
+        tokio::time::timeout(self.timeout, scan)
+            .await
+            .map_err(|_| ScanError::Timeout(self.timeout))?
+    }
+}
+
+/// Streams `data` to clamd with the INSTREAM command: length-prefixed chunks
+/// ended by an empty one.
+async fn scan_with_clamav(address: &str, data: &[u8]) -> Result<ScanVerdict, ScanError> {
+    let mut stream = TcpStream::connect(address).await?;
+    stream.write_all(b"zINSTREAM\0").await?;
+    for chunk in data.chunks(CLAMAV_CHUNK_SIZE) {
+        stream
+            .write_all(&(chunk.len() as u32).to_be_bytes())
+            .await?;
+        stream.write_all(chunk).await?;
+    }
+    stream.write_all(&0u32.to_be_bytes()).await?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    parse_clamav_response(String::from_utf8_lossy(&response).trim_end_matches('\0'))
+}
+
+/// clamd answers `stream: OK`, `stream: <signature> FOUND` or a message
+/// ending in `ERROR`.
+fn parse_clamav_response(response: &str) -> Result<ScanVerdict, ScanError> {
+    match response.trim().strip_prefix("stream:").map(str::trim) {
+        Some("OK") => Ok(ScanVerdict::Clean),
+        Some(result) if result.ends_with(" FOUND") => Ok(ScanVerdict::Infected(
+            result.trim_end_matches(" FOUND").trim().to_string(),
+        )),
+        _ => Err(ScanError::UnexpectedResponse(response.trim().to_string())),
+    }
+}
+
+/// Runs the scanner with `data` on stdin. Exit code 0 means clean and 1
+/// infected, with the signature on the first line of stdout, as with
+/// `clamdscan -`.
+async fn scan_with_command(command: &str, data: &[u8]) -> Result<ScanVerdict, ScanError> {
+    let mut parts = command.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| ScanError::NotConfigured("upload_scan_command is empty".to_string()))?;
+
+    let mut child = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        // Stops scanners that outlive the timeout
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let write = async move {
+        // Scanners may stop reading as soon as they find something
+        let _ = stdin.write_all(data).await;
+    };
+    let (_, output) = tokio::join!(write, child.wait_with_output());
+    let output = output?;
+
+    match output.status.code() {
+        Some(0) => Ok(ScanVerdict::Clean),
+        Some(1) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let signature = stdout
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .unwrap_or("Detected by upload_scan_command");
+            Ok(ScanVerdict::Infected(signature.to_string()))
+        }
+        _ => Err(ScanError::UnexpectedResponse(format!(
+            "{} exited with {}",
+            program, output.status
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    fn settings(mode: ScanMode) -> ScanSettings {
+        ScanSettings {
+            mode,
+            timeout: Duration::from_secs(1),
+            fail_open: false,
+        }
+    }
+
+    /// A clamd that reports every stream containing "EICAR" as infected and
+    /// answers after `delay`.
+    async fn stub_clamd(delay: Duration) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut command = [0u8; 10];
+                stream.read_exact(&mut command).await.unwrap();
+                assert_eq!(&command, b"zINSTREAM\0");
+
+                let mut data = Vec::new();
+                loop {
+                    let length = stream.read_u32().await.unwrap() as usize;
+                    if length == 0 {
+                        break;
+                    }
+                    let mut chunk = vec![0u8; length];
+                    stream.read_exact(&mut chunk).await.unwrap();
+                    data.extend(chunk);
+                }
+
+                tokio::time::sleep(delay).await;
+                let response: &[u8] = if data.windows(5).any(|window| window == b"EICAR") {
+                    b"stream: Eicar-Test-Signature FOUND\0"
+                } else {
+                    b"stream: OK\0"
+                };
+                let _ = stream.write_all(response).await;
+            }
+        });
+        address
+    }
+
+    #[test]
+    fn test_parse_clamav_response() {
+        assert_eq!(
+            parse_clamav_response("stream: OK").unwrap(),
+            ScanVerdict::Clean
+        );
+        assert_eq!(
+            parse_clamav_response("stream: Win.Test.EICAR_HDB-1 FOUND\n").unwrap(),
+            ScanVerdict::Infected("Win.Test.EICAR_HDB-1".to_string())
+        );
+        assert!(matches!(
+            parse_clamav_response("INSTREAM size limit exceeded. ERROR"),
+            Err(ScanError::UnexpectedResponse(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_clamav_scan_streams_the_whole_file() {
+        let address = stub_clamd(Duration::ZERO).await;
+        let settings = settings(ScanMode::Clamav(address));
+
+        let clean = vec![b'a'; CLAMAV_CHUNK_SIZE * 2 + 1];
+        assert_eq!(settings.scan(&clean).await.unwrap(), ScanVerdict::Clean);
+
+        // Only found when the last, partial chunk arrives
+        let mut infected = clean.clone();
+        infected.extend_from_slice(b"EICAR");
+        assert_eq!(
+            settings.scan(&infected).await.unwrap(),
+            ScanVerdict::Infected("Eicar-Test-Signature".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clamav_scan_times_out() {
+        let address = stub_clamd(Duration::from_secs(5)).await;
+        let result = settings(ScanMode::Clamav(address)).scan(b"data").await;
+        assert!(matches!(result, Err(ScanError::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_clamav_is_unavailable() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        drop(listener);
+
+        let result = settings(ScanMode::Clamav(address)).scan(b"data").await;
+        assert!(matches!(result, Err(ScanError::Unavailable(_))));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_scan_uses_the_exit_code() {
+        // Arguments are split on whitespace, so the script runs from a file
+        let path = std::env::temp_dir().join(format!("scan-{}.sh", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "if grep -q EICAR; then echo Stub.Signature; exit 1; fi",
+        )
+        .unwrap();
+        let settings = settings(ScanMode::Command(format!("sh {}", path.display())));
+
+        assert_eq!(settings.scan(b"clean").await.unwrap(), ScanVerdict::Clean);
+        assert_eq!(
+            settings.scan(b"X5O!EICAR").await.unwrap(),
+            ScanVerdict::Infected("Stub.Signature".to_string())
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_scan_failures_are_errors() {
+        let crashed = settings(ScanMode::Command("sh -c".to_string()));
+        assert!(matches!(
+            crashed.scan(b"data").await,
+            Err(ScanError::UnexpectedResponse(_))
+        ));
+
+        let missing = settings(ScanMode::Command("/nonexistent/scanner".to_string()));
+        assert!(matches!(
+            missing.scan(b"data").await,
+            Err(ScanError::Unavailable(_))
+        ));
+
+        let slow = settings(ScanMode::Command("sleep 5".to_string()));
+        assert!(matches!(
+            slow.scan(b"data").await,
+            Err(ScanError::Timeout(_))
+        ));
+
+        let empty = settings(ScanMode::Command(" ".to_string()));
+        assert!(matches!(
+            empty.scan(b"data").await,
+            Err(ScanError::NotConfigured(_))
+        ));
+    }
+}
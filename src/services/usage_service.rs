@@ -0,0 +1,270 @@
+use chrono::{DurationRound, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::upsert::excluded;
+use tracing::warn;
+
+use crate::models::{NewUserUsage, UsageCounts, UsageQuota, UsageQuotas, UserUsageResponse};
+use crate::schema::{roles, user_usage, users};
+use crate::utils::LunarbaseError;
+
+type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+/// Hourly buckets older than this are deleted as new ones are written.
+const RETENTION_DAYS: i64 = 30;
+
+/// Counts the records each user creates and the file bytes they upload in
+/// hourly buckets of `user_usage`, and enforces the daily quotas of their role
+/// over the last 24 of them.
+#[derive(Clone)]
+pub struct UsageService {
+    pool: DbPool,
+}
+
+/// Start of the hour `at` falls in.
+fn bucket_start(at: NaiveDateTime) -> NaiveDateTime {
+    at.duration_trunc(chrono::Duration::hours(1)).unwrap_or(at)
+}
+
+/// Start of the oldest bucket in the rolling window of `hours` that ends in
+/// the bucket of `now`.
+fn window_start(now: NaiveDateTime, hours: i64) -> NaiveDateTime {
+    bucket_start(now) - chrono::Duration::hours(hours - 1)
+}
+
+/// The first quota that `used` plus `adding` would exceed, with its limit.
+fn exceeded_quota(
+    quotas: &UsageQuotas,
+    used: UsageCounts,
+    adding: UsageCounts,
+) -> Option<(UsageQuota, i64)> {
+    let checks = [
+        (
+            UsageQuota::RecordsCreated,
+            quotas.max_records_created_per_day,
+            used.records_created,
+            adding.records_created,
+        ),
+        (
+            UsageQuota::UploadBytes,
+            quotas.max_upload_bytes_per_day,
+            used.upload_bytes,
+            adding.upload_bytes,
+        ),
+    ];
+    checks.into_iter().find_map(|(quota, limit, used, adding)| {
+        let limit = limit?;
+        (adding > 0 && used.saturating_add(adding) > limit).then_some((quota, limit))
+    })
+}
+
+impl UsageService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Adds to the usage of `user_id` in the current hour. Failures are
+    /// logged rather than failing the request that is being counted.
+    pub async fn record(&self, user_id: i32, records_created: i64, upload_bytes: i64) {
+        if records_created == 0 && upload_bytes == 0 {
+            return;
+        }
+        if let Err(e) = self.increment(user_id, records_created, upload_bytes) {
+            warn!("Failed to count usage of user {}: {}", user_id, e);
+        }
+    }
+
+    fn increment(
+        &self,
+        user_id: i32,
+        records_created: i64,
+        upload_bytes: i64,
+    ) -> Result<(), LunarbaseError> {
+        let now = Utc::now().naive_utc();
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        diesel::insert_into(user_usage::table)
+            .values(&NewUserUsage {
+                user_id,
+                bucket_start: bucket_start(now),
+                records_created,
+                upload_bytes,
+            })
+            .on_conflict((user_usage::user_id, user_usage::bucket_start))
+            .do_update()
+            .set((
+                user_usage::records_created
+                    .eq(user_usage::records_created + excluded(user_usage::records_created)),
+                user_usage::upload_bytes
+                    .eq(user_usage::upload_bytes + excluded(user_usage::upload_bytes)),
+            ))
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        diesel::delete(
+            user_usage::table
+                .filter(user_usage::user_id.eq(user_id))
+                .filter(user_usage::bucket_start.lt(window_start(now, RETENTION_DAYS * 24))),
+        )
+        .execute(&mut conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+        Ok(())
+    }
+
+    /// Rejects creating `records_created` records and uploading
+    /// `upload_bytes` when that would take `user_id` over a quota of `role`
+    /// for the last 24 hours: 429 for records, 413 for bytes.
+    pub async fn check(
+        &self,
+        user_id: i32,
+        role: &str,
+        records_created: i64,
+        upload_bytes: i64,
+    ) -> Result<(), LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let quotas = Self::role_quotas(&mut conn, role)?;
+        if quotas.max_records_created_per_day.is_none() && quotas.max_upload_bytes_per_day.is_none()
+        {
+            return Ok(());
+        }
+
+        let now = Utc::now().naive_utc();
+        let used = Self::usage_since(&mut conn, user_id, window_start(now, 24))?;
+        let adding = UsageCounts {
+            records_created,
+            upload_bytes,
+        };
+        match exceeded_quota(&quotas, used, adding) {
+            Some((quota, limit)) => Err(LunarbaseError::UsageQuotaExceeded {
+                quota,
+                role: role.to_string(),
+                limit,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Usage of `user_id` over the last day and month, with the quotas of
+    /// `role`, or of their own role when `None`.
+    pub async fn usage(
+        &self,
+        user_id: i32,
+        role: Option<&str>,
+    ) -> Result<UserUsageResponse, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let own_role = users::table
+            .find(user_id)
+            .select(users::role)
+            .first::<String>(&mut conn)
+            .optional()
+            .map_err(|_| LunarbaseError::DatabaseError)?
+            .ok_or_else(|| LunarbaseError::NotFound("User not found".to_string()))?;
+        let role = role.map_or(own_role, str::to_string);
+
+        let now = Utc::now().naive_utc();
+        Ok(UserUsageResponse {
+            user_id,
+            last_24_hours: Self::usage_since(&mut conn, user_id, window_start(now, 24))?,
+            last_30_days: Self::usage_since(
+                &mut conn,
+                user_id,
+                window_start(now, RETENTION_DAYS * 24),
+            )?,
+            quotas: Self::role_quotas(&mut conn, &role)?,
+            role,
+        })
+    }
+
+    fn role_quotas(conn: &mut SqliteConnection, role: &str) -> Result<UsageQuotas, LunarbaseError> {
+        let quotas = roles::table
+            .filter(roles::name.eq(role))
+            .select((
+                roles::max_records_created_per_day,
+                roles::max_upload_bytes_per_day,
+            ))
+            .first::<(Option<i64>, Option<i64>)>(conn)
+            .optional()
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(quotas
+            .map(
+                |(max_records_created_per_day, max_upload_bytes_per_day)| UsageQuotas {
+                    max_records_created_per_day,
+                    max_upload_bytes_per_day,
+                },
+            )
+            .unwrap_or_default())
+    }
+
+    fn usage_since(
+        conn: &mut SqliteConnection,
+        user_id: i32,
+        since: NaiveDateTime,
+    ) -> Result<UsageCounts, LunarbaseError> {
+        let buckets = user_usage::table
+            .filter(user_usage::user_id.eq(user_id))
+            .filter(user_usage::bucket_start.ge(since))
+            .select((user_usage::records_created, user_usage::upload_bytes))
+            .load::<(i64, i64)>(conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(buckets.into_iter().fold(
+            UsageCounts::default(),
+            |total, (records_created, upload_bytes)| UsageCounts {
+                records_created: total.records_created + records_created,
+                upload_bytes: total.upload_bytes + upload_bytes,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_the_daily_window_covers_the_current_hour_and_the_23_before_it() {
+        assert_eq!(
+            bucket_start(at("2025-10-10 14:37:12")),
+            at("2025-10-10 14:00:00")
+        );
+        assert_eq!(
+            window_start(at("2025-10-10 14:37:12"), 24),
+            at("2025-10-09 15:00:00")
+        );
+    }
+
+    #[test]
+    fn test_quotas_are_checked_only_for_what_is_being_added() {
+        let quotas = UsageQuotas {
+            max_records_created_per_day: Some(10),
+            max_upload_bytes_per_day: Some(1000),
+        };
+        let used = UsageCounts {
+            records_created: 10,
+            upload_bytes: 900,
+        };
+        let adding = |records_created, upload_bytes| UsageCounts {
+            records_created,
+            upload_bytes,
+        };
+
+        assert_eq!(
+            exceeded_quota(&quotas, used, adding(1, 0)),
+            Some((UsageQuota::RecordsCreated, 10))
+        );
+        assert_eq!(exceeded_quota(&quotas, used, adding(0, 100)), None);
+        assert_eq!(
+            exceeded_quota(&quotas, used, adding(0, 101)),
+            Some((UsageQuota::UploadBytes, 1000))
+        );
+        assert_eq!(
+            exceeded_quota(&UsageQuotas::default(), used, adding(5, 5000)),
+            None
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::models::{SubscriptionRequest, SubscriptionType, WebSocketError};
+use crate::services::ConfigurationAccess;
+
+/// Messages are read up to this multiple of the message size limit, so
+/// oversized ones within it get an error message. Anything larger fails at the
+/// protocol level and drops the connection without buffering the message.
+const PROTOCOL_MESSAGE_SIZE_FACTOR: usize = 4;
+
+/// Close code sent to connections that broke the limits too often.
+pub const LIMIT_VIOLATIONS_CLOSE_CODE: u16 = 1008;
+
+/// Sliding window in which limit violations of one user or IP are counted.
+const VIOLATION_WINDOW: Duration = Duration::from_secs(60);
+
+/// What a WebSocket client may send, read from the `api.websocket_*` settings
+/// when the connection is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebSocketLimits {
+    pub max_message_bytes: usize,
+    pub max_subscriptions: usize,
+    pub max_filter_length: usize,
+}
+
+/// A message that broke one of the [`WebSocketLimits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitViolation {
+    MessageTooLarge { size: usize, limit: usize },
+    TooManySubscriptions { limit: usize },
+    FilterTooLong { length: usize, limit: usize },
+}
+
+impl LimitViolation {
+    /// Error frame sent to the client, tied to the subscription it refused.
+    pub fn to_error(self, subscription_id: Option<String>) -> WebSocketError {
+        let (code, error, limit) = match self {
+            LimitViolation::MessageTooLarge { size, limit } => (
+                "message_too_large",
+                format!(
+                    "Message of {} bytes exceeds the limit of {} bytes",
+                    size, limit
+                ),
+                limit,
+            ),
+            LimitViolation::TooManySubscriptions { limit } => (
+                "too_many_subscriptions",
+                format!("Connections may hold at most {} subscriptions", limit),
+                limit,
+            ),
+            LimitViolation::FilterTooLong { length, limit } => (
+                "filter_too_long",
+                format!(
+                    "Filters of {} characters exceed the limit of {} characters",
+                    length, limit
+                ),
+                limit,
+            ),
+        };
+        WebSocketError {
+            code: code.to_string(),
+            error,
+            limit,
+            subscription_id,
+        }
+    }
+}
+
+impl WebSocketLimits {
+    pub async fn load(config: &impl ConfigurationAccess) -> Self {
+        Self {
+            max_message_bytes: config.get_websocket_max_message_kb().await as usize * 1024,
+            max_subscriptions: config.get_websocket_max_subscriptions().await as usize,
+            max_filter_length: config.get_websocket_max_filter_length().await as usize,
+        }
+    }
+
+    /// Largest message or frame the WebSocket protocol layer accepts.
+    pub fn protocol_max_message_bytes(&self) -> usize {
+        self.max_message_bytes
+            .saturating_mul(PROTOCOL_MESSAGE_SIZE_FACTOR)
+    }
+
+    pub fn check_message(&self, size: usize) -> Result<(), LimitViolation> {
+        if size > self.max_message_bytes {
+            return Err(LimitViolation::MessageTooLarge {
+                size,
+                limit: self.max_message_bytes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks a subscription request against a connection that already holds
+    /// `subscriptions`, of which `replaced` shares the request's id.
+    pub fn check_subscription(
+        &self,
+        request: &SubscriptionRequest,
+        subscriptions: usize,
+        replaced: bool,
+    ) -> Result<(), LimitViolation> {
+        if !replaced && subscriptions >= self.max_subscriptions {
+            return Err(LimitViolation::TooManySubscriptions {
+                limit: self.max_subscriptions,
+            });
+        }
+
+        let length = filter_length(request);
+        if length > self.max_filter_length {
+            return Err(LimitViolation::FilterTooLong {
+                length,
+                limit: self.max_filter_length,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Combined length of the filter names and values of a subscription.
+fn filter_length(request: &SubscriptionRequest) -> usize {
+    let query_filters = match &request.subscription_type {
+        SubscriptionType::Query { filters } => Some(filters),
+        _ => None,
+    };
+    request
+        .filters
+        .iter()
+        .chain(query_filters)
+        .flatten()
+        .map(|(field, value)| field.chars().count() + value.chars().count())
+        .sum()
+}
+
+/// Temporarily bans users, or clients by IP when anonymous, that keep
+/// breaking the [`WebSocketLimits`].
+#[derive(Clone, Default)]
+pub struct WebSocketBans {
+    violations: Arc<Mutex<HashMap<String, VecDeque<Instant>>>>,
+    banned_until: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl WebSocketBans {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bans are per user for authenticated connections and per IP otherwise.
+    /// Connections whose IP cannot be told share one key.
+    pub fn key(user_id: Option<i32>, client_ip: Option<IpAddr>) -> String {
+        match (user_id, client_ip) {
+            (Some(user_id), _) => format!("user:{}", user_id),
+            (None, Some(ip)) => format!("ip:{}", ip),
+            (None, None) => "ip:unknown".to_string(),
+        }
+    }
+
+    pub fn is_banned(&self, key: &str) -> bool {
+        self.is_banned_at(key, Instant::now())
+    }
+
+    fn is_banned_at(&self, key: &str, now: Instant) -> bool {
+        let mut banned_until = self.banned_until.lock().unwrap_or_else(|e| e.into_inner());
+        banned_until.retain(|_, until| *until > now);
+        banned_until.contains_key(key)
+    }
+
+    /// Records a violation of `key` and returns `true` when it reached
+    /// `max_violations` (0 never bans) within the violation window, banning
+    /// the key for `ban`.
+    pub fn record_violation(&self, key: &str, max_violations: u32, ban: Duration) -> bool {
+        self.record_violation_at(key, max_violations, ban, Instant::now())
+    }
+
+    fn record_violation_at(
+        &self,
+        key: &str,
+        max_violations: u32,
+        ban: Duration,
+        now: Instant,
+    ) -> bool {
+        if max_violations == 0 {
+            return false;
+        }
+
+        let mut violations = self.violations.lock().unwrap_or_else(|e| e.into_inner());
+        violations.retain(|_, times| {
+            while times
+                .front()
+                .is_some_and(|time| now.duration_since(*time) >= VIOLATION_WINDOW)
+            {
+                times.pop_front();
+            }
+            !times.is_empty()
+        });
+
+        let times = violations.entry(key.to_string()).or_default();
+        times.push_back(now);
+        if times.len() < max_violations as usize {
+            return false;
+        }
+
+        violations.remove(key);
+        self.banned_until
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.to_string(), now + ban);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: WebSocketLimits = WebSocketLimits {
+        max_message_bytes: 1024,
+        max_subscriptions: 2,
+        max_filter_length: 10,
+    };
+
+    fn subscription(filters: &[(&str, &str)]) -> SubscriptionRequest {
+        SubscriptionRequest {
+            subscription_id: "sub".to_string(),
+            collection_name: "posts".to_string(),
+            subscription_type: SubscriptionType::Collection,
+            filters: Some(
+                filters
+                    .iter()
+                    .map(|(field, value)| (field.to_string(), value.to_string()))
+                    .collect(),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_message_size_boundary() {
+        assert_eq!(LIMITS.check_message(1024), Ok(()));
+        assert_eq!(
+            LIMITS.check_message(1025),
+            Err(LimitViolation::MessageTooLarge {
+                size: 1025,
+                limit: 1024
+            })
+        );
+        assert_eq!(LIMITS.protocol_max_message_bytes(), 4096);
+    }
+
+    #[test]
+    fn test_subscription_count_boundary() {
+        let request = subscription(&[]);
+        assert_eq!(LIMITS.check_subscription(&request, 1, false), Ok(()));
+        assert_eq!(
+            LIMITS.check_subscription(&request, 2, false),
+            Err(LimitViolation::TooManySubscriptions { limit: 2 })
+        );
+        // Reusing a subscription id replaces it rather than adding one
+        assert_eq!(LIMITS.check_subscription(&request, 2, true), Ok(()));
+    }
+
+    #[test]
+    fn test_filter_length_boundary() {
+        assert_eq!(
+            LIMITS.check_subscription(&subscription(&[("title", "hello")]), 0, false),
+            Ok(())
+        );
+        assert_eq!(
+            LIMITS.check_subscription(&subscription(&[("title", "hello!")]), 0, false),
+            Err(LimitViolation::FilterTooLong {
+                length: 11,
+                limit: 10
+            })
+        );
+
+        let mut request = subscription(&[("a", "bcde")]);
+        request.subscription_type = SubscriptionType::Query {
+            filters: HashMap::from([("f".to_string(), "ghijk".to_string())]),
+        };
+        assert_eq!(
+            LIMITS.check_subscription(&request, 0, false),
+            Err(LimitViolation::FilterTooLong {
+                length: 11,
+                limit: 10
+            })
+        );
+    }
+
+    #[test]
+    fn test_repeated_violations_ban_until_the_ban_expires() {
+        let bans = WebSocketBans::new();
+        let ban = Duration::from_secs(300);
+        let start = Instant::now();
+
+        assert!(!bans.record_violation_at("user:1", 3, ban, start));
+        assert!(!bans.record_violation_at("user:1", 3, ban, start));
+        assert!(!bans.is_banned_at("user:1", start));
+        assert!(bans.record_violation_at("user:1", 3, ban, start));
+        assert!(bans.is_banned_at("user:1", start));
+        assert!(!bans.is_banned_at("user:2", start));
+
+        assert!(!bans.is_banned_at("user:1", start + ban));
+    }
+
+    #[test]
+    fn test_violations_outside_the_window_are_forgotten() {
+        let bans = WebSocketBans::new();
+        let ban = Duration::from_secs(300);
+        let start = Instant::now();
+
+        assert!(!bans.record_violation_at("ip:10.0.0.1", 2, ban, start));
+        assert!(!bans.record_violation_at("ip:10.0.0.1", 2, ban, start + VIOLATION_WINDOW));
+        assert!(!bans.record_violation_at("ip:10.0.0.1", 0, ban, start));
+    }
+}
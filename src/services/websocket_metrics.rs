@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use prometheus::core::Collector;
+use prometheus::{
+    Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGaugeVec, Opts, Registry,
+};
+
+/// Event throughput and back-pressure counters of the WebSocket service. The
+/// Prometheus collectors are the only storage, so `/metrics` and
+/// [`WebSocketStats`](crate::services::WebSocketStats) always agree.
+#[derive(Clone)]
+pub struct WebSocketMetrics {
+    events_broadcast: IntCounterVec,
+    messages_delivered: IntCounter,
+    messages_dropped: IntCounter,
+    slow_consumer_disconnects: IntCounter,
+    fan_out_latency: Histogram,
+    outbound_queue_depth: IntGaugeVec,
+}
+
+impl WebSocketMetrics {
+    pub fn new() -> Self {
+        Self {
+            events_broadcast: IntCounterVec::new(
+                Opts::new(
+                    "websocket_events_broadcast_total",
+                    "Record events published to WebSocket subscribers",
+                ),
+                &["collection"],
+            )
+            .expect("valid metric"),
+            messages_delivered: IntCounter::new(
+                "websocket_messages_delivered_total",
+                "Messages written to WebSocket clients",
+            )
+            .expect("valid metric"),
+            messages_dropped: IntCounter::new(
+                "websocket_messages_dropped_total",
+                "Messages dropped because a client's outbound queue was full",
+            )
+            .expect("valid metric"),
+            slow_consumer_disconnects: IntCounter::new(
+                "websocket_slow_consumer_disconnects_total",
+                "Connections closed for dropping too many messages",
+            )
+            .expect("valid metric"),
+            fan_out_latency: Histogram::with_opts(
+                HistogramOpts::new(
+                    "websocket_fan_out_latency_seconds",
+                    "Time to hand one event to every matching subscription",
+                )
+                .buckets(vec![
+                    0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0,
+                ]),
+            )
+            .expect("valid metric"),
+            outbound_queue_depth: IntGaugeVec::new(
+                Opts::new(
+                    "websocket_outbound_queue_depth",
+                    "Messages waiting in a connection's outbound queue",
+                ),
+                &["connection_id"],
+            )
+            .expect("valid metric"),
+        }
+    }
+
+    pub fn register(&self, registry: &Registry) -> prometheus::Result<()> {
+        registry.register(Box::new(self.events_broadcast.clone()))?;
+        registry.register(Box::new(self.messages_delivered.clone()))?;
+        registry.register(Box::new(self.messages_dropped.clone()))?;
+        registry.register(Box::new(self.slow_consumer_disconnects.clone()))?;
+        registry.register(Box::new(self.fan_out_latency.clone()))?;
+        registry.register(Box::new(self.outbound_queue_depth.clone()))?;
+        Ok(())
+    }
+
+    pub fn record_broadcast(&self, collection_name: &str) {
+        self.events_broadcast
+            .with_label_values(&[collection_name])
+            .inc();
+    }
+
+    pub fn record_delivered(&self) {
+        self.messages_delivered.inc();
+    }
+
+    pub fn record_dropped(&self) {
+        self.messages_dropped.inc();
+    }
+
+    pub fn record_slow_consumer_disconnect(&self) {
+        self.slow_consumer_disconnects.inc();
+    }
+
+    pub fn record_fan_out(&self, elapsed: Duration) {
+        self.fan_out_latency.observe(elapsed.as_secs_f64());
+    }
+
+    /// Replaces the per-connection queue depth gauges, dropping closed connections.
+    pub fn set_queue_depths(&self, depths: &HashMap<String, usize>) {
+        self.outbound_queue_depth.reset();
+        for (connection_id, depth) in depths {
+            self.outbound_queue_depth
+                .with_label_values(&[connection_id])
+                .set(*depth as i64);
+        }
+    }
+
+    pub fn events_broadcast_by_collection(&self) -> HashMap<String, u64> {
+        self.events_broadcast
+            .collect()
+            .iter()
+            .flat_map(|family| family.get_metric())
+            .filter_map(|metric| {
+                let collection = metric.get_label().first()?.value().to_string();
+                Some((collection, metric.get_counter().value() as u64))
+            })
+            .collect()
+    }
+
+    pub fn messages_delivered(&self) -> u64 {
+        self.messages_delivered.get()
+    }
+
+    pub fn messages_dropped(&self) -> u64 {
+        self.messages_dropped.get()
+    }
+
+    pub fn slow_consumer_disconnects(&self) -> u64 {
+        self.slow_consumer_disconnects.get()
+    }
+
+    /// Mean fan-out latency in milliseconds, 0 before the first event.
+    pub fn average_fan_out_ms(&self) -> f64 {
+        let count = self.fan_out_latency.get_sample_count();
+        if count == 0 {
+            return 0.0;
+        }
+        self.fan_out_latency.get_sample_sum() / count as f64 * 1000.0
+    }
+}
+
+impl Default for WebSocketMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_feed_stats_and_registry() {
+        let metrics = WebSocketMetrics::new();
+        let registry = Registry::new();
+        metrics.register(&registry).unwrap();
+
+        metrics.record_broadcast("posts");
+        metrics.record_broadcast("posts");
+        metrics.record_broadcast("tags");
+        metrics.record_delivered();
+        metrics.record_dropped();
+        metrics.record_fan_out(Duration::from_millis(2));
+        metrics.record_fan_out(Duration::from_millis(4));
+
+        let by_collection = metrics.events_broadcast_by_collection();
+        assert_eq!(by_collection["posts"], 2);
+        assert_eq!(by_collection["tags"], 1);
+        assert_eq!(metrics.messages_delivered(), 1);
+        assert_eq!(metrics.messages_dropped(), 1);
+        assert!((metrics.average_fan_out_ms() - 3.0).abs() < 0.01);
+
+        let names: Vec<String> = registry
+            .gather()
+            .iter()
+            .map(|family| family.name().to_string())
+            .collect();
+        assert!(names.contains(&"websocket_messages_dropped_total".to_string()));
+    }
+
+    #[test]
+    fn test_queue_depths_forget_closed_connections() {
+        let metrics = WebSocketMetrics::new();
+        metrics.set_queue_depths(&HashMap::from([("a".to_string(), 3), ("b".to_string(), 0)]));
+        metrics.set_queue_depths(&HashMap::from([("b".to_string(), 5)]));
+
+        let family = &metrics.outbound_queue_depth.collect()[0];
+        assert_eq!(family.get_metric().len(), 1);
+        assert_eq!(family.get_metric()[0].get_gauge().value(), 5.0);
+    }
+}
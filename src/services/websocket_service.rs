@@ -1,40 +1,120 @@
-use axum::extract::ws::{Message, WebSocket};
+use axum::extract::ws::{CloseFrame, Message, WebSocket};
 use chrono::{DateTime, Utc};
 use futures_util::{SinkExt, StreamExt};
 use serde_json::json;
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::sync::Arc;
-use tokio::sync::{RwLock, broadcast, mpsc};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{RwLock, broadcast, mpsc, watch};
 use tracing::{debug, error, warn};
 use uuid::Uuid;
 
 use crate::models::{
-    ClientConnection, EventMessage, PendingEvent, Permission, SubscriptionConfirmed,
-    SubscriptionData, SubscriptionError, SubscriptionRequest, UnsubscribeRequest, WebSocketMessage,
+    BulkChangeMessage, COLLECTIONS_CHANNEL, ClientConnection, CloseNotice, Collection,
+    CollectionEvent, CollectionEventMessage, EventMessage, NotificationPush, PendingEvent,
+    Permission, RecordEvent, SYSTEM_CHANNEL, SubscriptionConfirmed, SubscriptionData,
+    SubscriptionError, SubscriptionRequest, SubscriptionType, SystemNotice, SystemNoticeMessage,
+    UnsubscribeRequest, User, WebSocketMessage, WorkspaceSession,
+};
+use crate::services::websocket_limits::{
+    LIMIT_VIOLATIONS_CLOSE_CODE, LimitViolation, WebSocketBans, WebSocketLimits,
+};
+use crate::services::{
+    BroadcastEvent, ConfigurationAccess, EventCoalescer, PermissionService, WebSocketMetrics,
 };
-use crate::services::PermissionService;
 use crate::utils::LunarbaseError;
 
 pub type ConnectionId = Uuid;
 pub type SubscriptionId = String;
+type ConnectionEntry = (ConnectionSender, ClientConnection, DateTime<Utc>);
+
+/// Close code sent to connections that dropped too many messages.
+const SLOW_CONSUMER_CLOSE_CODE: u16 = 1008;
+
+/// Bounded outbound queue of one connection. Messages that do not fit are
+/// dropped, and after `max_dropped` of them (0 = never) the connection is
+/// closed as a slow consumer. Close notices skip the queue so they still get
+/// through when it is full.
+#[derive(Clone)]
+pub struct ConnectionSender {
+    queue: mpsc::Sender<WebSocketMessage>,
+    close: watch::Sender<Option<CloseNotice>>,
+    dropped: Arc<AtomicU32>,
+    max_dropped: u32,
+    metrics: WebSocketMetrics,
+}
+
+impl ConnectionSender {
+    fn new(
+        capacity: usize,
+        max_dropped: u32,
+        metrics: WebSocketMetrics,
+    ) -> (
+        Self,
+        mpsc::Receiver<WebSocketMessage>,
+        watch::Receiver<Option<CloseNotice>>,
+    ) {
+        let (queue, queue_rx) = mpsc::channel(capacity.max(1));
+        let (close, close_rx) = watch::channel(None);
+        let sender = Self {
+            queue,
+            close,
+            dropped: Arc::new(AtomicU32::new(0)),
+            max_dropped,
+            metrics,
+        };
+        (sender, queue_rx, close_rx)
+    }
+
+    pub fn send(&self, message: WebSocketMessage) -> Result<(), mpsc::error::TrySendError<()>> {
+        if let WebSocketMessage::Close(notice) = message {
+            self.close.send_replace(Some(notice));
+            return Ok(());
+        }
+
+        self.queue.try_send(message).map_err(|error| match error {
+            mpsc::error::TrySendError::Full(_) => {
+                self.record_dropped();
+                mpsc::error::TrySendError::Full(())
+            }
+            mpsc::error::TrySendError::Closed(_) => mpsc::error::TrySendError::Closed(()),
+        })
+    }
+
+    fn record_dropped(&self) {
+        self.metrics.record_dropped();
+        let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.max_dropped > 0 && dropped == self.max_dropped {
+            self.metrics.record_slow_consumer_disconnect();
+            self.close.send_replace(Some(CloseNotice {
+                code: SLOW_CONSUMER_CLOSE_CODE,
+                reason: "Slow consumer".to_string(),
+            }));
+        }
+    }
+
+    pub fn queue_depth(&self) -> usize {
+        self.queue.max_capacity() - self.queue.capacity()
+    }
+
+    pub fn dropped(&self) -> u32 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
 
 #[derive(Clone)]
 pub struct WebSocketService {
-    connections: Arc<
-        RwLock<
-            HashMap<
-                ConnectionId,
-                (
-                    mpsc::UnboundedSender<WebSocketMessage>,
-                    ClientConnection,
-                    DateTime<Utc>,
-                ),
-            >,
-        >,
-    >,
-    event_sender: broadcast::Sender<PendingEvent>,
+    connections: Arc<RwLock<HashMap<ConnectionId, ConnectionEntry>>>,
+    event_sender: broadcast::Sender<BroadcastEvent>,
+    coalescer: EventCoalescer,
     permission_service: Arc<PermissionService>,
     activity_log: Arc<RwLock<Vec<ActivityLogEntry>>>,
+    metrics: WebSocketMetrics,
+    bans: WebSocketBans,
+    /// Notifications by recipient, for event stream listeners
+    notifications: broadcast::Sender<(i32, NotificationPush)>,
 }
 
 #[derive(Debug, Clone)]
@@ -54,16 +134,39 @@ impl WebSocketService {
 
         Self {
             connections: Arc::new(RwLock::new(HashMap::new())),
+            coalescer: EventCoalescer::new(event_sender.clone()),
             event_sender,
             permission_service,
             activity_log: Arc::new(RwLock::new(Vec::new())),
+            metrics: WebSocketMetrics::new(),
+            bans: WebSocketBans::new(),
+            notifications: broadcast::channel(256).0,
         }
     }
 
-    pub async fn handle_connection(self: Arc<Self>, socket: WebSocket, user_id: Option<i32>) {
+    pub fn metrics(&self) -> &WebSocketMetrics {
+        &self.metrics
+    }
+
+    /// Whether the user, or the IP of an anonymous client, is banned for
+    /// breaking the connection limits too often.
+    pub fn is_banned(&self, user_id: Option<i32>, client_ip: Option<IpAddr>) -> bool {
+        self.bans.is_banned(&WebSocketBans::key(user_id, client_ip))
+    }
+
+    pub async fn handle_connection(
+        self: Arc<Self>,
+        socket: WebSocket,
+        user_id: Option<i32>,
+        workspace: Option<WorkspaceSession>,
+        client_ip: Option<IpAddr>,
+        limits: WebSocketLimits,
+    ) {
         let connection_id = Uuid::new_v4();
+        let ban_key = WebSocketBans::key(user_id, client_ip);
         let mut client_connection = ClientConnection::new(user_id);
         client_connection.connection_id = connection_id;
+        client_connection.workspace = workspace;
 
         debug!(
             "New WebSocket connection: {} (user: {:?})",
@@ -71,7 +174,16 @@ impl WebSocketService {
         );
 
         let (mut sender, mut receiver) = socket.split();
-        let (tx, mut rx) = mpsc::unbounded_channel::<WebSocketMessage>();
+        let (tx, mut rx, mut close_rx) = ConnectionSender::new(
+            self.permission_service
+                .get_websocket_outbound_buffer_size()
+                .await as usize,
+            self.permission_service
+                .get_websocket_slow_consumer_max_dropped()
+                .await,
+            self.metrics.clone(),
+        );
+        let mut closed = close_rx.clone();
 
         let connected_at = Utc::now();
 
@@ -91,9 +203,31 @@ impl WebSocketService {
         let mut event_receiver = self.event_sender.subscribe();
         let connections_clone = self.connections.clone();
         let permission_service = self.permission_service.clone();
+        let send_metrics = self.metrics.clone();
+        let event_metrics = self.metrics.clone();
+
+        let mut send_task = tokio::spawn(async move {
+            loop {
+                let message = tokio::select! {
+                    biased;
+                    Ok(()) = close_rx.changed() => {
+                        let notice = close_rx.borrow_and_update().clone();
+                        if let Some(notice) = notice {
+                            let _ = sender
+                                .send(Message::Close(Some(CloseFrame {
+                                    code: notice.code,
+                                    reason: notice.reason.into(),
+                                })))
+                                .await;
+                        }
+                        break;
+                    }
+                    message = rx.recv() => match message {
+                        Some(message) => message,
+                        None => break,
+                    },
+                };
 
-        let send_task = tokio::spawn(async move {
-            while let Some(message) = rx.recv().await {
                 let json_message = match serde_json::to_string(&message) {
                     Ok(json) => json,
                     Err(e) => {
@@ -110,81 +244,115 @@ impl WebSocketService {
                     debug!("Client disconnected during send");
                     break;
                 }
+                send_metrics.record_delivered();
             }
         });
 
         let event_task = tokio::spawn(async move {
             while let Ok(event) = event_receiver.recv().await {
+                let started = Instant::now();
                 let connections = connections_clone.read().await;
 
                 for (conn_id, (sender, client, _)) in connections.iter() {
                     for (sub_id, sub_data) in &client.subscriptions {
-                        if sub_data.matches_event(&event) {
-                            if let Some(sub_user_id) = sub_data.user_id {
-                                let mut conn = match permission_service.pool.get() {
-                                    Ok(conn) => conn,
-                                    Err(_) => continue,
-                                };
-
-                                use crate::models::User;
-                                use crate::schema::users;
-                                use diesel::prelude::*;
-                                let user = match users::table
-                                    .find(sub_user_id)
-                                    .select(User::as_select())
-                                    .first::<User>(&mut conn)
-                                {
-                                    Ok(user) => user,
-                                    Err(_) => continue,
-                                };
-
-                                use crate::models::Collection;
-                                use crate::schema::collections;
-                                let collection = match collections::table
-                                    .filter(collections::name.eq(&event.collection_name))
-                                    .first::<Collection>(&mut conn)
-                                {
-                                    Ok(collection) => collection,
-                                    Err(_) => continue,
-                                };
-
-                                let has_permission = permission_service
-                                    .check_collection_permission(
-                                        &user,
-                                        collection.id,
-                                        Permission::Read,
-                                    )
-                                    .await
-                                    .unwrap_or(false);
-
-                                if !has_permission {
-                                    debug!(
-                                        "User {} lacks permission for collection {}",
-                                        sub_user_id, event.collection_name
-                                    );
-                                    continue;
-                                }
+                        let message = match &event {
+                            BroadcastEvent::Record(event) if sub_data.matches_event(event) => {
+                                WebSocketMessage::Event(EventMessage {
+                                    subscription_id: sub_id.clone(),
+                                    collection_name: event.collection_name.clone(),
+                                    event: event.event.clone(),
+                                })
                             }
+                            BroadcastEvent::BulkChange(bulk)
+                                if sub_data.matches_bulk_change(bulk) =>
+                            {
+                                WebSocketMessage::BulkChange(BulkChangeMessage {
+                                    subscription_id: sub_id.clone(),
+                                    change: bulk.change.clone(),
+                                })
+                            }
+                            _ => continue,
+                        };
+
+                        if let Some(sub_user_id) = sub_data.user_id {
+                            let mut conn = match permission_service.pool.get() {
+                                Ok(conn) => conn,
+                                Err(_) => continue,
+                            };
 
-                            let event_message = EventMessage {
-                                subscription_id: sub_id.clone(),
-                                collection_name: event.collection_name.clone(),
-                                event: event.event.clone(),
+                            use crate::models::User;
+                            use crate::schema::users;
+                            use diesel::prelude::*;
+                            let mut user = match users::table
+                                .find(sub_user_id)
+                                .select(User::as_select())
+                                .first::<User>(&mut conn)
+                            {
+                                Ok(user) => user,
+                                Err(_) => continue,
                             };
 
-                            if sender.send(WebSocketMessage::Event(event_message)).is_err() {
-                                debug!("Failed to send event to connection {}", conn_id);
+                            use crate::models::Collection;
+                            use crate::schema::collections;
+                            let collection = match collections::table
+                                .filter(collections::name.eq(event.collection_name()))
+                                .first::<Collection>(&mut conn)
+                            {
+                                Ok(collection) => collection,
+                                Err(_) => continue,
+                            };
+
+                            if !scope_to_workspace(
+                                &mut user,
+                                client.workspace.as_ref(),
+                                &collection,
+                            ) {
+                                continue;
+                            }
+
+                            let has_permission = permission_service
+                                .check_collection_permission(&user, collection.id, Permission::Read)
+                                .await
+                                .unwrap_or(false);
+
+                            if !has_permission {
+                                debug!(
+                                    "User {} lacks permission for collection {}",
+                                    sub_user_id,
+                                    event.collection_name()
+                                );
+                                continue;
                             }
                         }
+
+                        if sender.send(message).is_err() {
+                            debug!("Failed to send event to connection {}", conn_id);
+                        }
                     }
                 }
+                event_metrics.record_fan_out(started.elapsed());
             }
         });
 
-        while let Some(msg) = receiver.next().await {
+        loop {
+            let msg = tokio::select! {
+                msg = receiver.next() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+                // Closed by the server, e.g. as a slow consumer
+                Ok(()) = closed.changed() => break,
+            };
             match msg {
                 Ok(Message::Text(text)) => {
-                    if let Err(e) = self.handle_client_message(connection_id, &text).await {
+                    if let Err(violation) = limits.check_message(text.len()) {
+                        self.reject(connection_id, &ban_key, violation, None).await;
+                        continue;
+                    }
+                    if let Err(e) = self
+                        .handle_client_message(connection_id, &text, &ban_key, &limits)
+                        .await
+                    {
                         warn!("Error handling client message: {}", e);
                     }
                 }
@@ -206,14 +374,39 @@ impl WebSocketService {
             }
         }
 
-        self.log_activity(connection_id, user_id, "disconnected".to_string(), None)
-            .await;
-
-        {
+        let removed = {
             let mut connections = self.connections.write().await;
-            connections.remove(&connection_id);
+            connections.remove(&connection_id)
+        };
+
+        let slow_consumer = removed.and_then(|(sender, _, _)| {
+            let dropped = sender.dropped();
+            (sender.max_dropped > 0 && dropped >= sender.max_dropped).then_some(dropped)
+        });
+        match slow_consumer {
+            Some(dropped) => {
+                warn!(
+                    "Closed slow WebSocket consumer {} after {} dropped messages",
+                    connection_id, dropped
+                );
+                self.log_activity(
+                    connection_id,
+                    user_id,
+                    "slow_consumer_disconnected".to_string(),
+                    Some(format!("Dropped messages: {}", dropped)),
+                )
+                .await;
+            }
+            None => {
+                self.log_activity(connection_id, user_id, "disconnected".to_string(), None)
+                    .await;
+            }
         }
 
+        // Let a pending close frame reach the client
+        if closed.borrow().is_some() {
+            let _ = tokio::time::timeout(Duration::from_secs(1), &mut send_task).await;
+        }
         send_task.abort();
         event_task.abort();
 
@@ -224,6 +417,8 @@ impl WebSocketService {
         &self,
         connection_id: ConnectionId,
         text: &str,
+        ban_key: &str,
+        limits: &WebSocketLimits,
     ) -> Result<(), LunarbaseError> {
         let message: WebSocketMessage = serde_json::from_str(text).map_err(|_| {
             LunarbaseError::ValidationError(vec!["Invalid JSON message".to_string()])
@@ -231,6 +426,21 @@ impl WebSocketService {
 
         match message {
             WebSocketMessage::Subscribe(req) => {
+                let checked = {
+                    let connections = self.connections.read().await;
+                    connections.get(&connection_id).map(|(_, client, _)| {
+                        limits.check_subscription(
+                            &req,
+                            client.subscriptions.len(),
+                            client.subscriptions.contains_key(&req.subscription_id),
+                        )
+                    })
+                };
+                if let Some(Err(violation)) = checked {
+                    self.reject(connection_id, ban_key, violation, Some(req.subscription_id))
+                        .await;
+                    return Ok(());
+                }
                 self.handle_subscribe(connection_id, req).await?;
             }
             WebSocketMessage::Unsubscribe(req) => {
@@ -250,6 +460,54 @@ impl WebSocketService {
         Ok(())
     }
 
+    /// Tells the client which limit it broke. Once its user, or IP when
+    /// anonymous, broke them too often the connection is closed and banned
+    /// from reconnecting for a while.
+    async fn reject(
+        &self,
+        connection_id: ConnectionId,
+        ban_key: &str,
+        violation: LimitViolation,
+        subscription_id: Option<String>,
+    ) {
+        let max_violations = self.permission_service.get_websocket_max_violations().await;
+        let ban_seconds = self.permission_service.get_websocket_ban_seconds().await;
+        let banned = self.bans.record_violation(
+            ban_key,
+            max_violations,
+            Duration::from_secs(ban_seconds as u64),
+        );
+
+        let user_id = {
+            let connections = self.connections.read().await;
+            let Some((sender, client, _)) = connections.get(&connection_id) else {
+                return;
+            };
+            let _ = sender.send(WebSocketMessage::Error(violation.to_error(subscription_id)));
+            if banned {
+                let _ = sender.send(WebSocketMessage::Close(CloseNotice {
+                    code: LIMIT_VIOLATIONS_CLOSE_CODE,
+                    reason: "limit_violations".to_string(),
+                }));
+            }
+            client.user_id
+        };
+
+        if banned {
+            warn!(
+                "Banned {} from WebSocket for {} seconds after repeated limit violations",
+                ban_key, ban_seconds
+            );
+            self.log_activity(
+                connection_id,
+                user_id,
+                "banned_for_limit_violations".to_string(),
+                Some(format!("Banned {} for {} seconds", ban_key, ban_seconds)),
+            )
+            .await;
+        }
+    }
+
     async fn handle_subscribe(
         &self,
         connection_id: ConnectionId,
@@ -258,7 +516,40 @@ impl WebSocketService {
         let mut connections = self.connections.write().await;
 
         if let Some((sender, client, _)) = connections.get_mut(&connection_id) {
-            if let Some(user_id) = client.user_id {
+            if req.collection_name == COLLECTIONS_CHANNEL {
+                // Events are permission-checked per collection when sent
+                let error = if client.user_id.is_none() {
+                    Some("Authentication required")
+                } else if !matches!(req.subscription_type, SubscriptionType::Collection) {
+                    Some("The _collections channel only supports Collection subscriptions")
+                } else {
+                    None
+                };
+                if let Some(error) = error {
+                    let error_msg = SubscriptionError {
+                        subscription_id: req.subscription_id.clone(),
+                        error: error.to_string(),
+                    };
+                    let _ = sender.send(WebSocketMessage::SubscriptionError(error_msg));
+                    return Err(LunarbaseError::Forbidden(error.to_string()));
+                }
+            } else if req.collection_name == SYSTEM_CHANNEL {
+                let error = if !client.user_id.is_some_and(|user_id| self.is_admin(user_id)) {
+                    Some("The _system channel is only available to admins")
+                } else if !matches!(req.subscription_type, SubscriptionType::Collection) {
+                    Some("The _system channel only supports Collection subscriptions")
+                } else {
+                    None
+                };
+                if let Some(error) = error {
+                    let error_msg = SubscriptionError {
+                        subscription_id: req.subscription_id.clone(),
+                        error: error.to_string(),
+                    };
+                    let _ = sender.send(WebSocketMessage::SubscriptionError(error_msg));
+                    return Err(LunarbaseError::Forbidden(error.to_string()));
+                }
+            } else if let Some(user_id) = client.user_id {
                 let mut conn = self
                     .permission_service
                     .pool
@@ -269,7 +560,7 @@ impl WebSocketService {
                 use crate::schema::{collections, users};
                 use diesel::prelude::*;
 
-                let user = users::table
+                let mut user = users::table
                     .find(user_id)
                     .select(User::as_select())
                     .first::<User>(&mut conn)
@@ -278,7 +569,11 @@ impl WebSocketService {
                 let collection = collections::table
                     .filter(collections::name.eq(&req.collection_name))
                     .first::<Collection>(&mut conn)
-                    .map_err(|_| LunarbaseError::NotFound("Collection not found".to_string()))?;
+                    .ok()
+                    .filter(|collection| {
+                        scope_to_workspace(&mut user, client.workspace.as_ref(), collection)
+                    })
+                    .ok_or_else(|| LunarbaseError::NotFound("Collection not found".to_string()))?;
 
                 let has_permission = self
                     .permission_service
@@ -343,15 +638,248 @@ impl WebSocketService {
             "Broadcasting event for collection: {}",
             event.collection_name
         );
+        self.metrics.record_broadcast(&event.collection_name);
 
-        if let Err(e) = self.event_sender.send(event) {
-            error!("Failed to broadcast event: {}", e);
-            return Err(LunarbaseError::InternalError);
+        if let RecordEvent::OwnershipTransferred {
+            record_id,
+            old_owner_id,
+            new_owner_id,
+        } = &event.event
+        {
+            // Not tied to a client connection, so it is logged under the nil id
+            self.log_activity(
+                Uuid::nil(),
+                event.user_id,
+                "ownership_transferred".to_string(),
+                Some(format!(
+                    "Record {}/{} owner {} -> {}",
+                    event.collection_name,
+                    record_id,
+                    old_owner_id.map_or_else(|| "none".to_string(), |id| id.to_string()),
+                    new_owner_id
+                )),
+            )
+            .await;
+        }
+
+        let collection_name = event.collection_name.clone();
+        let threshold = self
+            .permission_service
+            .get_websocket_coalesce_threshold()
+            .await;
+        let window = Duration::from_millis(
+            self.permission_service
+                .get_websocket_coalesce_window_ms()
+                .await as u64,
+        );
+
+        match self
+            .coalescer
+            .publish(event, threshold, window, Instant::now())
+        {
+            Ok(Some(flush_at)) => {
+                debug!(
+                    "Coalescing record events for collection {} into a bulk change",
+                    collection_name
+                );
+                let coalescer = self.coalescer.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep_until(flush_at.into()).await;
+                    if let Err(e) = coalescer.flush(&collection_name) {
+                        error!("Failed to broadcast bulk change: {}", e);
+                    }
+                });
+            }
+            Ok(None) => {}
+            Err(e) => {
+                error!("Failed to broadcast event: {}", e);
+                return Err(LunarbaseError::InternalError);
+            }
         }
 
         Ok(())
     }
 
+    /// Sends `event` to `_collections` subscribers who can list the collection.
+    pub async fn broadcast_collection_event(&self, collection_id: i32, event: CollectionEvent) {
+        let recipients = self.collection_event_recipients(collection_id).await;
+        self.send_collection_event(&recipients, event).await;
+    }
+
+    /// `_collections` subscriptions whose user can list the collection. Resolved
+    /// separately from sending so a deletion can be announced to the users who
+    /// could see the collection before its permissions were removed.
+    pub async fn collection_event_recipients(
+        &self,
+        collection_id: i32,
+    ) -> Vec<(ConnectionId, SubscriptionId)> {
+        type Subscriber = (ConnectionId, SubscriptionId, i32, Option<WorkspaceSession>);
+        let subscribers: Vec<Subscriber> = {
+            let connections = self.connections.read().await;
+            connections
+                .iter()
+                .filter_map(|(conn_id, (_, client, _))| Some((conn_id, client, client.user_id?)))
+                .flat_map(|(conn_id, client, user_id)| {
+                    client
+                        .subscriptions
+                        .iter()
+                        .filter(|(_, sub)| sub.collection_name == COLLECTIONS_CHANNEL)
+                        .map(move |(sub_id, _)| {
+                            (*conn_id, sub_id.clone(), user_id, client.workspace.clone())
+                        })
+                })
+                .collect()
+        };
+
+        let mut can_list: HashMap<(i32, Option<i32>), bool> = HashMap::new();
+        let mut recipients = Vec::new();
+        for (conn_id, sub_id, user_id, workspace) in subscribers {
+            let key = (user_id, workspace.as_ref().map(|ws| ws.workspace_id));
+            let allowed = match can_list.get(&key) {
+                Some(allowed) => *allowed,
+                None => {
+                    let allowed = self
+                        .user_can_list(user_id, workspace.as_ref(), collection_id)
+                        .await;
+                    can_list.insert(key, allowed);
+                    allowed
+                }
+            };
+            if allowed {
+                recipients.push((conn_id, sub_id));
+            }
+        }
+
+        recipients
+    }
+
+    pub async fn send_collection_event(
+        &self,
+        recipients: &[(ConnectionId, SubscriptionId)],
+        event: CollectionEvent,
+    ) {
+        let connections = self.connections.read().await;
+
+        for (conn_id, sub_id) in recipients {
+            if let Some((sender, _, _)) = connections.get(conn_id) {
+                let message = WebSocketMessage::CollectionEvent(CollectionEventMessage {
+                    subscription_id: sub_id.clone(),
+                    event: event.clone(),
+                });
+                if sender.send(message).is_err() {
+                    debug!("Failed to send collection event to connection {}", conn_id);
+                }
+            }
+        }
+    }
+
+    fn is_admin(&self, user_id: i32) -> bool {
+        use crate::schema::users;
+        use diesel::prelude::*;
+
+        let Ok(mut conn) = self.permission_service.pool.get() else {
+            return false;
+        };
+        users::table
+            .find(user_id)
+            .select(users::role)
+            .first::<String>(&mut conn)
+            .is_ok_and(|role| role == "admin")
+    }
+
+    /// Sends `notice` to every `_system` subscription, which only admins can
+    /// hold. Returns the number of subscriptions it was sent to.
+    pub async fn broadcast_system_notice(&self, notice: SystemNotice) -> usize {
+        let connections = self.connections.read().await;
+        let mut sent = 0;
+
+        for (conn_id, (sender, client, _)) in connections.iter() {
+            for (sub_id, _) in client
+                .subscriptions
+                .iter()
+                .filter(|(_, sub)| sub.collection_name == SYSTEM_CHANNEL)
+            {
+                let message = WebSocketMessage::SystemNotice(SystemNoticeMessage {
+                    subscription_id: sub_id.clone(),
+                    notice: notice.clone(),
+                });
+                if sender.send(message).is_ok() {
+                    sent += 1;
+                } else {
+                    debug!("Failed to send system notice to connection {}", conn_id);
+                }
+            }
+        }
+
+        sent
+    }
+
+    /// Sends `push` to every connection `user_id` has open and to their event
+    /// streams. Returns the number of WebSocket connections it was sent to.
+    pub async fn notify_user(&self, user_id: i32, push: NotificationPush) -> usize {
+        let connections = self.connections.read().await;
+        let mut sent = 0;
+
+        for (conn_id, (sender, _, _)) in connections
+            .iter()
+            .filter(|(_, (_, client, _))| client.user_id == Some(user_id))
+        {
+            if sender
+                .send(WebSocketMessage::Notification(push.clone()))
+                .is_ok()
+            {
+                sent += 1;
+            } else {
+                debug!("Failed to send notification to connection {}", conn_id);
+            }
+        }
+        drop(connections);
+
+        // No receivers just means no event stream is open
+        let _ = self.notifications.send((user_id, push));
+        sent
+    }
+
+    /// Notifications for every user as they are pushed; event streams filter
+    /// out their own user's.
+    pub fn subscribe_notifications(&self) -> broadcast::Receiver<(i32, NotificationPush)> {
+        self.notifications.subscribe()
+    }
+
+    async fn user_can_list(
+        &self,
+        user_id: i32,
+        workspace: Option<&WorkspaceSession>,
+        collection_id: i32,
+    ) -> bool {
+        use crate::models::{Collection, User};
+        use crate::schema::{collections, users};
+        use diesel::prelude::*;
+
+        let Ok(mut conn) = self.permission_service.pool.get() else {
+            return false;
+        };
+        let (Ok(mut user), Ok(collection)) = (
+            users::table
+                .find(user_id)
+                .select(User::as_select())
+                .first::<User>(&mut conn),
+            collections::table
+                .find(collection_id)
+                .first::<Collection>(&mut conn),
+        ) else {
+            return false;
+        };
+        drop(conn);
+
+        scope_to_workspace(&mut user, workspace, &collection)
+            && self
+                .permission_service
+                .check_collection_permission(&user, collection_id, Permission::List)
+                .await
+                .unwrap_or(false)
+    }
+
     pub async fn connection_count(&self) -> usize {
         let connections = self.connections.read().await;
         connections.len()
@@ -390,9 +918,21 @@ impl WebSocketService {
                 .map(|(_, client, _)| client.subscriptions.len())
                 .sum(),
             subscriptions_by_collection,
+            events_broadcast_by_collection: self.metrics.events_broadcast_by_collection(),
+            messages_delivered: self.metrics.messages_delivered(),
+            messages_dropped: self.metrics.messages_dropped(),
+            slow_consumer_disconnects: self.metrics.slow_consumer_disconnects(),
+            average_fan_out_latency_ms: self.metrics.average_fan_out_ms(),
+            outbound_queue_depth: queue_depths(&connections),
         }
     }
 
+    /// Refreshes the per-connection queue depth gauges before a Prometheus scrape.
+    pub async fn update_queue_depth_metrics(&self) {
+        let connections = self.connections.read().await;
+        self.metrics.set_queue_depths(&queue_depths(&connections));
+    }
+
     pub async fn get_connection_details(
         &self,
     ) -> Vec<crate::handlers::websocket::ConnectionDetails> {
@@ -401,7 +941,7 @@ impl WebSocketService {
         let connections = self.connections.read().await;
         let mut details = Vec::new();
 
-        for (conn_id, (_, client, connected_at)) in connections.iter() {
+        for (conn_id, (sender, client, connected_at)) in connections.iter() {
             let subscriptions = client
                 .subscriptions
                 .iter()
@@ -428,6 +968,8 @@ impl WebSocketService {
                 user_id: client.user_id,
                 connected_at: connected_at.to_rfc3339(),
                 subscriptions,
+                outbound_queue_depth: sender.queue_depth(),
+                dropped_messages: sender.dropped(),
             });
         }
 
@@ -464,6 +1006,37 @@ impl WebSocketService {
         }
     }
 
+    /// Closes every connection opened by `user_id` with the given close code and
+    /// returns how many were closed.
+    pub async fn disconnect_user(&self, user_id: i32, code: u16, reason: &str) -> usize {
+        let mut connections = self.connections.write().await;
+
+        let connection_ids: Vec<ConnectionId> = connections
+            .iter()
+            .filter(|(_, (_, client, _))| client.user_id == Some(user_id))
+            .map(|(conn_id, _)| *conn_id)
+            .collect();
+
+        for connection_id in &connection_ids {
+            if let Some((sender, _, _)) = connections.remove(connection_id) {
+                let _ = sender.send(WebSocketMessage::Close(CloseNotice {
+                    code,
+                    reason: reason.to_string(),
+                }));
+            }
+
+            self.log_activity(
+                *connection_id,
+                Some(user_id),
+                "force_disconnected".to_string(),
+                Some(reason.to_string()),
+            )
+            .await;
+        }
+
+        connection_ids.len()
+    }
+
     pub async fn broadcast_admin_message(
         &self,
         message: &str,
@@ -562,6 +1135,14 @@ impl WebSocketService {
         action: String,
         details: Option<String>,
     ) {
+        if !self
+            .permission_service
+            .get_activity_tracking_enabled()
+            .await
+        {
+            return;
+        }
+
         let mut activity_log = self.activity_log.write().await;
 
         let entry = ActivityLogEntry {
@@ -580,10 +1161,90 @@ impl WebSocketService {
     }
 }
 
+/// Whether a connection scoped to `workspace` may see `collection`; if so,
+/// `user` gets the role of that workspace for the permission check.
+fn scope_to_workspace(
+    user: &mut User,
+    workspace: Option<&WorkspaceSession>,
+    collection: &Collection,
+) -> bool {
+    let Some(workspace) = workspace else {
+        return true;
+    };
+    if !collection.is_system && collection.workspace_id != workspace.workspace_id {
+        return false;
+    }
+    user.role = workspace.role.clone();
+    true
+}
+
+fn queue_depths(connections: &HashMap<ConnectionId, ConnectionEntry>) -> HashMap<String, usize> {
+    connections
+        .iter()
+        .map(|(conn_id, (sender, _, _))| (conn_id.to_string(), sender.queue_depth()))
+        .collect()
+}
+
 #[derive(Debug, serde::Serialize, utoipa::ToSchema)]
 pub struct WebSocketStats {
     pub total_connections: usize,
     pub authenticated_connections: usize,
     pub total_subscriptions: usize,
     pub subscriptions_by_collection: HashMap<String, usize>,
+    /// Record events published per collection since startup
+    pub events_broadcast_by_collection: HashMap<String, u64>,
+    pub messages_delivered: u64,
+    /// Messages dropped because the client's outbound queue was full
+    pub messages_dropped: u64,
+    pub slow_consumer_disconnects: u64,
+    /// Mean time to hand one event to every matching subscription
+    pub average_fan_out_latency_ms: f64,
+    /// Messages waiting to be written, by connection id
+    pub outbound_queue_depth: HashMap<String, usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_queue_drops_messages_and_closes_slow_consumers() {
+        let metrics = WebSocketMetrics::new();
+        let (sender, mut queue, close) = ConnectionSender::new(1, 2, metrics.clone());
+
+        assert!(sender.send(WebSocketMessage::Pong).is_ok());
+        assert!(sender.send(WebSocketMessage::Pong).is_err());
+        assert_eq!(sender.queue_depth(), 1);
+        assert!(close.borrow().is_none());
+
+        assert!(sender.send(WebSocketMessage::Pong).is_err());
+        assert_eq!(sender.dropped(), 2);
+        assert_eq!(metrics.messages_dropped(), 2);
+        assert_eq!(metrics.slow_consumer_disconnects(), 1);
+        assert_eq!(
+            close.borrow().as_ref().map(|notice| notice.code),
+            Some(SLOW_CONSUMER_CLOSE_CODE)
+        );
+
+        assert!(queue.try_recv().is_ok());
+        assert_eq!(sender.queue_depth(), 0);
+    }
+
+    #[test]
+    fn test_close_notices_skip_a_full_queue() {
+        let (sender, _queue, close) = ConnectionSender::new(1, 0, WebSocketMetrics::new());
+        sender.send(WebSocketMessage::Pong).unwrap();
+
+        sender
+            .send(WebSocketMessage::Close(CloseNotice {
+                code: 4001,
+                reason: "Account deactivated".to_string(),
+            }))
+            .unwrap();
+        assert_eq!(
+            close.borrow().as_ref().map(|notice| notice.code),
+            Some(4001)
+        );
+        assert_eq!(sender.dropped(), 0);
+    }
 }
@@ -0,0 +1,346 @@
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+
+use crate::models::{
+    CollectionResponse, CreateWorkspaceRequest, DEFAULT_WORKSPACE_ID, NewWorkspace,
+    NewWorkspaceMember, Role, User, Workspace, WorkspaceMember, WorkspaceMemberResponse,
+    WorkspaceResponse, WorkspaceSession,
+};
+use crate::schema::{collections, roles, users, workspace_members, workspaces};
+use crate::services::{CollectionService, ConfigurationAccess, ConfigurationManager};
+use crate::utils::LunarbaseError;
+
+type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+#[derive(Clone)]
+pub struct WorkspaceService {
+    pub pool: DbPool,
+    config_manager: ConfigurationManager,
+    collection_service: CollectionService,
+}
+
+impl ConfigurationAccess for WorkspaceService {
+    fn config_manager(&self) -> &ConfigurationManager {
+        &self.config_manager
+    }
+}
+
+impl WorkspaceService {
+    pub fn new(
+        pool: DbPool,
+        config_manager: ConfigurationManager,
+        collection_service: CollectionService,
+    ) -> Self {
+        Self {
+            pool,
+            config_manager,
+            collection_service,
+        }
+    }
+
+    /// The workspace a new access token for `user` is scoped to, or `None`
+    /// while workspaces are disabled. Without `requested`, superadmins start
+    /// in the default workspace and everyone else in their first one.
+    pub async fn session_for(
+        &self,
+        user: &User,
+        requested: Option<i32>,
+    ) -> Result<Option<WorkspaceSession>, LunarbaseError> {
+        if !self.get_enable_workspaces().await {
+            return Ok(None);
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let memberships = load_memberships(&mut conn, user.id)?;
+        let superadmin = user.role == "admin";
+
+        let workspace_id = match requested {
+            Some(workspace_id) => workspace_id,
+            None if superadmin => DEFAULT_WORKSPACE_ID,
+            None => memberships
+                .first()
+                .map_or(DEFAULT_WORKSPACE_ID, |member| member.workspace_id),
+        };
+        find_workspace(&mut conn, workspace_id)?;
+
+        let role = if superadmin {
+            "admin".to_string()
+        } else {
+            member_role(&memberships, user, workspace_id).ok_or_else(|| {
+                LunarbaseError::Forbidden("Not a member of this workspace".to_string())
+            })?
+        };
+
+        Ok(Some(WorkspaceSession {
+            workspace_id,
+            role,
+            superadmin,
+        }))
+    }
+
+    pub async fn list_workspaces(&self) -> Result<Vec<WorkspaceResponse>, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let all = workspaces::table
+            .order(workspaces::id.asc())
+            .select(Workspace::as_select())
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        all.into_iter()
+            .map(|workspace| to_response(&mut conn, workspace))
+            .collect()
+    }
+
+    /// The workspaces `user` can switch to.
+    pub async fn list_user_workspaces(
+        &self,
+        user: &User,
+    ) -> Result<Vec<WorkspaceResponse>, LunarbaseError> {
+        if user.role == "admin" {
+            return self.list_workspaces().await;
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        let memberships = load_memberships(&mut conn, user.id)?;
+        let ids: Vec<i32> = if memberships.is_empty() {
+            vec![DEFAULT_WORKSPACE_ID]
+        } else {
+            memberships
+                .iter()
+                .map(|member| member.workspace_id)
+                .collect()
+        };
+
+        let joined = workspaces::table
+            .filter(workspaces::id.eq_any(ids))
+            .order(workspaces::id.asc())
+            .select(Workspace::as_select())
+            .load(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        joined
+            .into_iter()
+            .map(|workspace| to_response(&mut conn, workspace))
+            .collect()
+    }
+
+    pub async fn create_workspace(
+        &self,
+        request: CreateWorkspaceRequest,
+    ) -> Result<WorkspaceResponse, LunarbaseError> {
+        request
+            .validate()
+            .map_err(LunarbaseError::ValidationError)?;
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        diesel::insert_into(workspaces::table)
+            .values(&NewWorkspace {
+                name: request.name.clone(),
+                description: request.description,
+            })
+            .execute(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) => LunarbaseError::Conflict(format!(
+                    "A workspace named '{}' already exists",
+                    request.name
+                )),
+                _ => LunarbaseError::DatabaseError,
+            })?;
+
+        let workspace = workspaces::table
+            .filter(workspaces::name.eq(&request.name))
+            .select(Workspace::as_select())
+            .first(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        to_response(&mut conn, workspace)
+    }
+
+    /// Only empty workspaces can be deleted; their custom roles and
+    /// memberships go with them.
+    pub async fn delete_workspace(&self, workspace_id: i32) -> Result<(), LunarbaseError> {
+        if workspace_id == DEFAULT_WORKSPACE_ID {
+            return Err(LunarbaseError::BadRequest(
+                "The default workspace cannot be deleted".to_string(),
+            ));
+        }
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        find_workspace(&mut conn, workspace_id)?;
+
+        conn.immediate_transaction(|conn| {
+            let collection_count: i64 = collections::table
+                .filter(collections::workspace_id.eq(workspace_id))
+                .count()
+                .get_result(conn)?;
+            if collection_count > 0 {
+                return Err(LunarbaseError::Conflict(format!(
+                    "Workspace still has {} collection(s); move or delete them first",
+                    collection_count
+                )));
+            }
+
+            diesel::delete(
+                workspace_members::table.filter(workspace_members::workspace_id.eq(workspace_id)),
+            )
+            .execute(conn)?;
+            diesel::delete(roles::table.filter(roles::workspace_id.eq(workspace_id)))
+                .execute(conn)?;
+            diesel::delete(workspaces::table.find(workspace_id)).execute(conn)?;
+            Ok(())
+        })
+    }
+
+    /// Adds `user_id` to the workspace or changes their role there.
+    pub async fn set_member(
+        &self,
+        workspace_id: i32,
+        user_id: i32,
+        role: &str,
+    ) -> Result<WorkspaceMemberResponse, LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+        find_workspace(&mut conn, workspace_id)?;
+
+        users::table
+            .find(user_id)
+            .select(users::id)
+            .first::<i32>(&mut conn)
+            .map_err(|_| LunarbaseError::NotFound("User not found".to_string()))?;
+
+        let role_usable = roles::table
+            .filter(roles::name.eq(role))
+            .select(Role::as_select())
+            .first(&mut conn)
+            .optional()
+            .map_err(|_| LunarbaseError::DatabaseError)?
+            .is_some_and(|found| found.workspace_id.is_none_or(|id| id == workspace_id));
+        if !role_usable {
+            return Err(LunarbaseError::ValidationError(vec![format!(
+                "Role '{}' does not exist in this workspace",
+                role
+            )]));
+        }
+
+        diesel::insert_into(workspace_members::table)
+            .values(&NewWorkspaceMember {
+                workspace_id,
+                user_id,
+                role: role.to_string(),
+            })
+            .on_conflict((workspace_members::workspace_id, workspace_members::user_id))
+            .do_update()
+            .set(workspace_members::role.eq(role))
+            .execute(&mut conn)
+            .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        Ok(WorkspaceMemberResponse {
+            workspace_id,
+            user_id,
+            role: role.to_string(),
+        })
+    }
+
+    pub async fn remove_member(
+        &self,
+        workspace_id: i32,
+        user_id: i32,
+    ) -> Result<(), LunarbaseError> {
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let removed = diesel::delete(
+            workspace_members::table
+                .filter(workspace_members::workspace_id.eq(workspace_id))
+                .filter(workspace_members::user_id.eq(user_id)),
+        )
+        .execute(&mut conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+        if removed == 0 {
+            return Err(LunarbaseError::NotFound(
+                "User is not a member of this workspace".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn move_collection(
+        &self,
+        workspace_id: i32,
+        collection_name: &str,
+    ) -> Result<CollectionResponse, LunarbaseError> {
+        {
+            let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+            find_workspace(&mut conn, workspace_id)?;
+        }
+
+        self.collection_service
+            .move_collection_to_workspace(collection_name, workspace_id)
+            .await
+    }
+}
+
+fn find_workspace(
+    conn: &mut SqliteConnection,
+    workspace_id: i32,
+) -> Result<Workspace, LunarbaseError> {
+    workspaces::table
+        .find(workspace_id)
+        .select(Workspace::as_select())
+        .first(conn)
+        .map_err(|_| LunarbaseError::NotFound("Workspace not found".to_string()))
+}
+
+fn load_memberships(
+    conn: &mut SqliteConnection,
+    user_id: i32,
+) -> Result<Vec<WorkspaceMember>, LunarbaseError> {
+    workspace_members::table
+        .filter(workspace_members::user_id.eq(user_id))
+        .order(workspace_members::workspace_id.asc())
+        .select(WorkspaceMember::as_select())
+        .load(conn)
+        .map_err(|_| LunarbaseError::DatabaseError)
+}
+
+/// Users without any membership belong to the default workspace with their
+/// global role, so accounts from before workspaces keep working.
+fn member_role(memberships: &[WorkspaceMember], user: &User, workspace_id: i32) -> Option<String> {
+    match memberships
+        .iter()
+        .find(|member| member.workspace_id == workspace_id)
+    {
+        Some(member) => Some(member.role.clone()),
+        None if memberships.is_empty() && workspace_id == DEFAULT_WORKSPACE_ID => {
+            Some(user.role.clone())
+        }
+        None => None,
+    }
+}
+
+fn to_response(
+    conn: &mut SqliteConnection,
+    workspace: Workspace,
+) -> Result<WorkspaceResponse, LunarbaseError> {
+    let collection_count = collections::table
+        .filter(collections::workspace_id.eq(workspace.id))
+        .filter(collections::is_system.eq(false))
+        .count()
+        .get_result(conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+    let member_count = workspace_members::table
+        .filter(workspace_members::workspace_id.eq(workspace.id))
+        .count()
+        .get_result(conn)
+        .map_err(|_| LunarbaseError::DatabaseError)?;
+
+    Ok(WorkspaceResponse::new(
+        workspace,
+        collection_count,
+        member_count,
+    ))
+}
@@ -0,0 +1,94 @@
+/// Longest slug generated from a source value, before any `-N` suffix.
+pub const MAX_SLUG_LENGTH: usize = 80;
+
+/// Lowercase ASCII slug of `text`: accented Latin letters are transliterated,
+/// every other run of non-alphanumeric characters becomes a single dash.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+
+    for c in text.chars().flat_map(char::to_lowercase) {
+        let ascii = if c.is_ascii_alphanumeric() {
+            Some(c.to_string())
+        } else {
+            transliterate(c).map(str::to_string)
+        };
+
+        match ascii {
+            Some(part) => slug.push_str(&part),
+            None if !slug.is_empty() && !slug.ends_with('-') => slug.push('-'),
+            None => {}
+        }
+    }
+
+    if slug.len() > MAX_SLUG_LENGTH {
+        slug.truncate(MAX_SLUG_LENGTH);
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// `base`, or `base-2`, `base-3`... whichever is first not in `taken`.
+pub fn unique_slug(base: &str, taken: &[String]) -> String {
+    if !taken.iter().any(|slug| slug == base) {
+        return base.to_string();
+    }
+
+    (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_else(|| base.to_string())
+}
+
+fn transliterate(c: char) -> Option<&'static str> {
+    let ascii = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        '&' => "and",
+        _ => return None,
+    };
+    Some(ascii)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("  Leading and trailing  "), "leading-and-trailing");
+        assert_eq!(slugify("Zażółć gęślą jaźń"), "zazolc-gesla-jazn");
+        assert_eq!(slugify("Crème Brûlée & Straße"), "creme-brulee-and-strasse");
+        assert_eq!(slugify("Rust 2024: what's new?"), "rust-2024-what-s-new");
+        assert_eq!(slugify("日本"), "");
+        assert_eq!(slugify(&"a".repeat(200)).len(), MAX_SLUG_LENGTH);
+    }
+
+    #[test]
+    fn test_unique_slug() {
+        let taken = vec!["post".to_string(), "post-2".to_string()];
+        assert_eq!(unique_slug("post", &taken), "post-3");
+        assert_eq!(unique_slug("other", &taken), "other");
+        assert_eq!(unique_slug("post", &[]), "post");
+    }
+}
@@ -4,30 +4,62 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use serde_json::{Value, json};
+use std::collections::BTreeMap;
 use std::fmt;
 
+use crate::models::{FieldValidationError, Permission, UsageQuota};
+use crate::utils::i18n::current_locale;
+use crate::utils::{ErrorResponse, FieldError, Message};
+
 #[derive(Debug)]
 pub enum LunarbaseError {
     InvalidCredentials,
     AccountLocked,
     AccountNotVerified,
+    AccountDeactivated,
     UserAlreadyVerified,
     UserNotFound,
     TokenExpired,
     TokenInvalid,
     TokenMissing,
+    /// A cookie-authenticated write without a matching `X-CSRF-Token` header
+    CsrfTokenInvalid,
     InsufficientPermissions,
+    /// A record operation denied by the collection or record permissions
+    RecordPermissionDenied(Permission),
     RateLimitExceeded,
+    QueryTooComplex {
+        complexity: u32,
+        budget: u32,
+    },
+    /// A request body or multipart part over one of the `limits.*` settings
+    PayloadTooLarge {
+        setting: &'static str,
+        limit: String,
+    },
+    /// More records or upload bytes in the last 24 hours than the user's role allows
+    UsageQuotaExceeded {
+        quota: UsageQuota,
+        role: String,
+        limit: i64,
+    },
     ValidationError(Vec<String>),
+    /// Record data rejected field by field; answered with a `fields` map
+    InvalidFields(Vec<FieldValidationError>),
     BadRequest(String),
     Conflict(String),
     DatabaseError,
     InternalError,
     NotFound(String),
     Forbidden(String),
+    MethodNotAllowed(String),
+    ReadOnlyMode,
     PasswordResetTokenInvalid,
     PasswordResetTokenExpired,
     WeakPassword,
+    /// A record share link past its expiry or view limit
+    ShareLinkExpired,
+    SharePasswordInvalid,
 }
 
 impl fmt::Display for LunarbaseError {
@@ -36,140 +68,278 @@ impl fmt::Display for LunarbaseError {
             LunarbaseError::InvalidCredentials => write!(f, "Invalid credentials"),
             LunarbaseError::AccountLocked => write!(f, "Account temporarily locked"),
             LunarbaseError::AccountNotVerified => write!(f, "Account not verified"),
+            LunarbaseError::AccountDeactivated => write!(f, "Account deactivated"),
             LunarbaseError::UserAlreadyVerified => write!(f, "User already verified"),
             LunarbaseError::UserNotFound => write!(f, "User not found"),
             LunarbaseError::TokenExpired => write!(f, "Token expired"),
             LunarbaseError::TokenInvalid => write!(f, "Invalid token"),
             LunarbaseError::TokenMissing => write!(f, "Token missing"),
+            LunarbaseError::CsrfTokenInvalid => write!(f, "Invalid CSRF token"),
             LunarbaseError::PasswordResetTokenInvalid => write!(f, "Invalid password reset token"),
             LunarbaseError::PasswordResetTokenExpired => write!(f, "Password reset token expired"),
             LunarbaseError::WeakPassword => {
                 write!(f, "Password does not meet security requirements")
             }
             LunarbaseError::InsufficientPermissions => write!(f, "Insufficient permissions"),
+            LunarbaseError::RecordPermissionDenied(permission) => {
+                write!(f, "Insufficient permissions to {} records", permission)
+            }
             LunarbaseError::RateLimitExceeded => write!(f, "Rate limit exceeded"),
+            LunarbaseError::QueryTooComplex { complexity, budget } => {
+                write!(f, "{}", query_budget_message(*complexity, *budget))
+            }
+            LunarbaseError::PayloadTooLarge { setting, limit } => {
+                write!(f, "{}", payload_limit_message(setting, limit))
+            }
+            LunarbaseError::UsageQuotaExceeded { quota, role, limit } => {
+                write!(f, "{}", usage_quota_message(*quota, role, *limit))
+            }
             LunarbaseError::ValidationError(errors) => {
                 write!(f, "Validation error: {}", errors.join(", "))
             }
+            LunarbaseError::InvalidFields(errors) => {
+                let messages: Vec<String> = errors.iter().map(|e| e.message.to_string()).collect();
+                write!(f, "Validation error: {}", messages.join(", "))
+            }
             LunarbaseError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
             LunarbaseError::Conflict(msg) => write!(f, "Conflict: {}", msg),
             LunarbaseError::DatabaseError => write!(f, "Database error"),
             LunarbaseError::InternalError => write!(f, "Internal server error"),
             LunarbaseError::NotFound(msg) => write!(f, "Not found: {}", msg),
             LunarbaseError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
+            LunarbaseError::MethodNotAllowed(msg) => write!(f, "Method not allowed: {}", msg),
+            LunarbaseError::ReadOnlyMode => write!(f, "API is in read-only mode"),
+            LunarbaseError::ShareLinkExpired => write!(f, "Share link expired"),
+            LunarbaseError::SharePasswordInvalid => write!(f, "Invalid share link password"),
         }
     }
 }
 
 impl std::error::Error for LunarbaseError {}
 
-impl IntoResponse for LunarbaseError {
-    fn into_response(self) -> Response {
-        let (status, error_message, error_code) = match self {
-            LunarbaseError::InvalidCredentials => (
-                StatusCode::UNAUTHORIZED,
-                "Invalid email or password",
-                "INVALID_CREDENTIALS",
-            ),
-            LunarbaseError::AccountLocked => (
-                StatusCode::FORBIDDEN,
-                "Account temporarily locked due to multiple failed login attempts",
-                "ACCOUNT_LOCKED",
-            ),
-            LunarbaseError::AccountNotVerified => (
-                StatusCode::FORBIDDEN,
-                "Please verify your email address to continue",
-                "ACCOUNT_NOT_VERIFIED",
-            ),
-            LunarbaseError::UserAlreadyVerified => (
-                StatusCode::BAD_REQUEST,
-                "User is already verified",
-                "USER_ALREADY_VERIFIED",
-            ),
-            LunarbaseError::UserNotFound => {
-                (StatusCode::NOT_FOUND, "User not found", "USER_NOT_FOUND")
+impl From<diesel::result::Error> for LunarbaseError {
+    fn from(error: diesel::result::Error) -> Self {
+        match error {
+            // A missing row is the caller's 404, not a failing database
+            diesel::result::Error::NotFound => {
+                LunarbaseError::NotFound("Resource not found".to_string())
+            }
+            _ => LunarbaseError::DatabaseError,
+        }
+    }
+}
+
+impl From<diesel::r2d2::PoolError> for LunarbaseError {
+    fn from(_: diesel::r2d2::PoolError) -> Self {
+        LunarbaseError::InternalError
+    }
+}
+
+impl LunarbaseError {
+    /// Stable machine-readable code for clients to match on instead of the
+    /// human-readable messages, whose wording may change.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LunarbaseError::InvalidCredentials => "invalid_credentials",
+            LunarbaseError::AccountLocked => "account_locked",
+            LunarbaseError::AccountNotVerified => "account_not_verified",
+            LunarbaseError::AccountDeactivated => "account_deactivated",
+            LunarbaseError::UserAlreadyVerified => "user_already_verified",
+            LunarbaseError::UserNotFound => "user_not_found",
+            LunarbaseError::TokenExpired => "token_expired",
+            LunarbaseError::TokenInvalid => "token_invalid",
+            LunarbaseError::TokenMissing => "token_missing",
+            LunarbaseError::CsrfTokenInvalid => "csrf_token_invalid",
+            LunarbaseError::InsufficientPermissions => "permission_denied",
+            LunarbaseError::RecordPermissionDenied(permission) => match permission {
+                Permission::Create => "permission_denied.record_create",
+                Permission::Read => "permission_denied.record_read",
+                Permission::Update => "permission_denied.record_update",
+                Permission::Delete => "permission_denied.record_delete",
+                Permission::List => "permission_denied.record_list",
+            },
+            LunarbaseError::PasswordResetTokenInvalid => "password_reset_token_invalid",
+            LunarbaseError::PasswordResetTokenExpired => "password_reset_token_expired",
+            LunarbaseError::WeakPassword => "weak_password",
+            LunarbaseError::RateLimitExceeded => "rate_limit_exceeded",
+            LunarbaseError::QueryTooComplex { .. } => "query_too_complex",
+            LunarbaseError::PayloadTooLarge { .. } => "payload_too_large",
+            LunarbaseError::UsageQuotaExceeded { .. } => "usage_quota_exceeded",
+            LunarbaseError::ValidationError(_) | LunarbaseError::InvalidFields(_) => {
+                "validation_failed"
+            }
+            LunarbaseError::BadRequest(_) => "bad_request",
+            LunarbaseError::Conflict(_) => "conflict",
+            LunarbaseError::DatabaseError => "database_error",
+            LunarbaseError::InternalError => "internal_error",
+            // Messages name the missing resource first, e.g. "Record not found"
+            LunarbaseError::NotFound(message) => match message.split_whitespace().next() {
+                Some("Collection") => "collection_not_found",
+                Some("Record") => "record_not_found",
+                Some("User") => "user_not_found",
+                Some("Role") => "role_not_found",
+                Some("Share") => "share_link_not_found",
+                _ => "not_found",
+            },
+            LunarbaseError::Forbidden(_) => "forbidden",
+            LunarbaseError::MethodNotAllowed(_) => "method_not_allowed",
+            LunarbaseError::ReadOnlyMode => "read_only_mode",
+            LunarbaseError::ShareLinkExpired => "share_link_expired",
+            LunarbaseError::SharePasswordInvalid => "share_password_invalid",
+        }
+    }
+
+    /// Status code and the catalog key of the message shown to clients,
+    /// which never includes internal details.
+    fn status_and_message_key(&self) -> (StatusCode, &'static str) {
+        match self {
+            LunarbaseError::InvalidCredentials => {
+                (StatusCode::UNAUTHORIZED, "error.invalid_credentials")
+            }
+            LunarbaseError::AccountLocked => (StatusCode::FORBIDDEN, "error.account_locked"),
+            LunarbaseError::AccountNotVerified => {
+                (StatusCode::FORBIDDEN, "error.account_not_verified")
+            }
+            LunarbaseError::AccountDeactivated => {
+                (StatusCode::FORBIDDEN, "error.account_deactivated")
+            }
+            LunarbaseError::UserAlreadyVerified => {
+                (StatusCode::BAD_REQUEST, "error.user_already_verified")
+            }
+            LunarbaseError::UserNotFound => (StatusCode::NOT_FOUND, "error.user_not_found"),
+            LunarbaseError::TokenExpired => (StatusCode::UNAUTHORIZED, "error.token_expired"),
+            LunarbaseError::TokenInvalid => (StatusCode::UNAUTHORIZED, "error.token_invalid"),
+            LunarbaseError::TokenMissing => (StatusCode::UNAUTHORIZED, "error.token_missing"),
+            LunarbaseError::CsrfTokenInvalid => (StatusCode::FORBIDDEN, "error.csrf_token_invalid"),
+            LunarbaseError::InsufficientPermissions | LunarbaseError::RecordPermissionDenied(_) => {
+                (StatusCode::FORBIDDEN, "error.permission_denied")
             }
-            LunarbaseError::TokenExpired => (
-                StatusCode::UNAUTHORIZED,
-                "Token has expired",
-                "TOKEN_EXPIRED",
-            ),
-            LunarbaseError::TokenInvalid => (
-                StatusCode::UNAUTHORIZED,
-                "Invalid or malformed token",
-                "TOKEN_INVALID",
-            ),
-            LunarbaseError::TokenMissing => (
-                StatusCode::UNAUTHORIZED,
-                "Authorization token is missing",
-                "TOKEN_MISSING",
-            ),
-            LunarbaseError::InsufficientPermissions => (
-                StatusCode::FORBIDDEN,
-                "You don't have permission to access this resource",
-                "INSUFFICIENT_PERMISSIONS",
-            ),
             LunarbaseError::PasswordResetTokenInvalid => (
                 StatusCode::BAD_REQUEST,
-                "Invalid or expired password reset token",
-                "PASSWORD_RESET_TOKEN_INVALID",
+                "error.password_reset_token_invalid",
             ),
             LunarbaseError::PasswordResetTokenExpired => (
                 StatusCode::BAD_REQUEST,
-                "Password reset token has expired",
-                "PASSWORD_RESET_TOKEN_EXPIRED",
-            ),
-            LunarbaseError::WeakPassword => (
-                StatusCode::BAD_REQUEST,
-                "Password does not meet security requirements",
-                "WEAK_PASSWORD",
+                "error.password_reset_token_expired",
             ),
-            LunarbaseError::RateLimitExceeded => (
-                StatusCode::TOO_MANY_REQUESTS,
-                "Too many requests. Please try again later",
-                "RATE_LIMIT_EXCEEDED",
-            ),
-            LunarbaseError::ValidationError(_) => (
-                StatusCode::BAD_REQUEST,
-                "Validation failed",
-                "VALIDATION_ERROR",
-            ),
-            LunarbaseError::BadRequest(_) => {
-                (StatusCode::BAD_REQUEST, "Bad request", "BAD_REQUEST")
+            LunarbaseError::WeakPassword => (StatusCode::BAD_REQUEST, "error.weak_password"),
+            LunarbaseError::RateLimitExceeded => {
+                (StatusCode::TOO_MANY_REQUESTS, "error.rate_limit_exceeded")
             }
-            LunarbaseError::Conflict(_) => {
-                (StatusCode::CONFLICT, "Resource already exists", "CONFLICT")
+            LunarbaseError::QueryTooComplex { .. } => {
+                (StatusCode::BAD_REQUEST, "error.query_too_complex")
             }
-            LunarbaseError::DatabaseError => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "A database error occurred. Please try again later",
-                "DATABASE_ERROR",
-            ),
-            LunarbaseError::InternalError => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "An internal error occurred. Please try again later",
-                "INTERNAL_ERROR",
-            ),
-            LunarbaseError::NotFound(_) => {
-                (StatusCode::NOT_FOUND, "Resource not found", "NOT_FOUND")
+            LunarbaseError::PayloadTooLarge { .. } => {
+                (StatusCode::PAYLOAD_TOO_LARGE, "error.payload_too_large")
+            }
+            LunarbaseError::UsageQuotaExceeded { quota, .. } => match quota {
+                UsageQuota::RecordsCreated => {
+                    (StatusCode::TOO_MANY_REQUESTS, "error.usage_quota_exceeded")
+                }
+                UsageQuota::UploadBytes => {
+                    (StatusCode::PAYLOAD_TOO_LARGE, "error.usage_quota_exceeded")
+                }
+            },
+            LunarbaseError::ValidationError(_) | LunarbaseError::InvalidFields(_) => {
+                (StatusCode::BAD_REQUEST, "error.validation_failed")
             }
-            LunarbaseError::Forbidden(_) => {
-                (StatusCode::FORBIDDEN, "Access forbidden", "FORBIDDEN")
+            LunarbaseError::BadRequest(_) => (StatusCode::BAD_REQUEST, "error.bad_request"),
+            LunarbaseError::Conflict(_) => (StatusCode::CONFLICT, "error.conflict"),
+            LunarbaseError::DatabaseError => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "error.database_error")
             }
+            LunarbaseError::InternalError => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "error.internal_error")
+            }
+            LunarbaseError::NotFound(_) => (StatusCode::NOT_FOUND, "error.not_found"),
+            LunarbaseError::Forbidden(_) => (StatusCode::FORBIDDEN, "error.forbidden"),
+            LunarbaseError::MethodNotAllowed(_) => {
+                (StatusCode::METHOD_NOT_ALLOWED, "error.method_not_allowed")
+            }
+            LunarbaseError::ReadOnlyMode => {
+                (StatusCode::SERVICE_UNAVAILABLE, "error.read_only_mode")
+            }
+            LunarbaseError::ShareLinkExpired => (StatusCode::GONE, "error.share_link_expired"),
+            LunarbaseError::SharePasswordInvalid => {
+                (StatusCode::UNAUTHORIZED, "error.share_password_invalid")
+            }
+        }
+    }
+}
+
+impl IntoResponse for LunarbaseError {
+    fn into_response(self) -> Response {
+        let (status, message_key) = self.status_and_message_key();
+        let locale = current_locale();
+
+        // The budget and the exceeded limit are the whole point of these
+        // errors, so unlike the other variants their detailed message is
+        // returned to the client
+        let details = match &self {
+            LunarbaseError::QueryTooComplex { complexity, budget } => {
+                Some(query_budget_message(*complexity, *budget).render(locale))
+            }
+            LunarbaseError::PayloadTooLarge { setting, limit } => {
+                Some(payload_limit_message(setting, limit).render(locale))
+            }
+            LunarbaseError::UsageQuotaExceeded { quota, role, limit } => {
+                Some(usage_quota_message(*quota, role, *limit).render(locale))
+            }
+            _ => None,
         };
 
-        let body = Json(json!({
-            "error": {
-                "code": error_code,
-                "message": error_message,
-                "timestamp": chrono::Utc::now().to_rfc3339()
+        let fields = match &self {
+            LunarbaseError::InvalidFields(errors) => {
+                let mut fields: BTreeMap<String, Vec<FieldError>> = BTreeMap::new();
+                for error in errors.iter().filter(|error| !error.field.is_empty()) {
+                    fields
+                        .entry(error.field.clone())
+                        .or_default()
+                        .push(FieldError {
+                            code: error.code.clone(),
+                            message: error.message.clone(),
+                        });
+                }
+                Some(fields)
             }
-        }));
+            _ => None,
+        };
+
+        let body = ErrorResponse {
+            success: false,
+            code: self.code().to_string(),
+            error: Message::new(message_key).render(locale),
+            details,
+            fields,
+        };
 
-        (status, body).into_response()
+        (status, Json(body)).into_response()
     }
 }
 
+fn query_budget_message(complexity: u32, budget: u32) -> Message {
+    Message::new("error.query_too_complex_details")
+        .arg("complexity", complexity)
+        .arg("budget", budget)
+        .arg("predicate_cost", crate::query_engine::PREDICATE_COST)
+        .arg("like_cost", crate::query_engine::LIKE_COST)
+        .arg("expand_cost", crate::query_engine::EXPANSION_COST)
+}
+
+fn payload_limit_message(setting: &str, limit: &str) -> Message {
+    Message::new("error.payload_too_large_details")
+        .arg("setting", format!("limits.{}", setting))
+        .arg("limit", limit)
+}
+
+fn usage_quota_message(quota: UsageQuota, role: &str, limit: i64) -> Message {
+    let key = match quota {
+        UsageQuota::RecordsCreated => "error.records_quota_exceeded_details",
+        UsageQuota::UploadBytes => "error.upload_quota_exceeded_details",
+    };
+    Message::new(key).arg("role", role).arg("limit", limit)
+}
+
 #[derive(serde::Serialize, utoipa::ToSchema)]
 pub struct ApiResponse<T> {
     pub success: bool,
@@ -193,7 +363,7 @@ impl<T: serde::Serialize> ApiResponse<T> {
             success: false,
             data: None,
             error: Some(json!({
-                "code": format!("{:?}", error),
+                "code": error.code(),
                 "message": error.to_string()
             })),
             timestamp: chrono::Utc::now().to_rfc3339(),
@@ -1,6 +1,16 @@
-use axum::http::{HeaderMap, HeaderValue, header::SET_COOKIE};
+use axum::http::{
+    HeaderMap, HeaderValue,
+    header::{HOST, ORIGIN, SET_COOKIE},
+};
 use chrono::{Duration, Utc};
 use std::env;
+use uuid::Uuid;
+
+use crate::services::ConfigurationAccess;
+
+/// Readable by scripts so clients can echo it in [`CSRF_HEADER`].
+pub const CSRF_COOKIE: &str = "csrf_token";
+pub const CSRF_HEADER: &str = "x-csrf-token";
 
 #[derive(Debug, Clone)]
 pub struct CookieConfig {
@@ -26,6 +36,83 @@ impl Default for CookieConfig {
     }
 }
 
+impl CookieConfig {
+    /// The defaults with `auth.cookie_same_site` and `auth.cookie_domain`
+    /// applied. `SameSite=None` always comes with `Secure`, which browsers
+    /// require; same-origin requests keep the host-only `Lax` defaults, so the
+    /// settings only ever widen what cross-origin requests get.
+    pub async fn for_request<C: ConfigurationAccess>(config: &C, headers: &HeaderMap) -> Self {
+        let defaults = Self::default();
+        let same_site = config.get_cookie_same_site().await;
+
+        if is_same_origin(headers) {
+            return match same_site.as_str() {
+                "Strict" => Self {
+                    same_site,
+                    ..defaults
+                },
+                _ => defaults,
+            };
+        }
+
+        let domain = config.get_cookie_domain().await;
+        Self {
+            secure: defaults.secure || same_site == "None",
+            same_site,
+            domain: (!domain.is_empty()).then_some(domain),
+            ..defaults
+        }
+    }
+}
+
+/// Checks `auth.cookie_same_site` and `auth.cookie_domain`; used both at
+/// startup and when the settings are changed.
+pub fn validate_cookie_settings(same_site: &str, domain: &str) -> Result<(), String> {
+    if !matches!(same_site, "Lax" | "Strict" | "None") {
+        return Err(format!(
+            "auth.cookie_same_site must be Lax, Strict or None, not '{}'",
+            same_site
+        ));
+    }
+
+    let host = domain.strip_prefix('.').unwrap_or(domain);
+    let valid_domain = domain.is_empty()
+        || (!host.is_empty()
+            && host.split('.').all(|label| {
+                !label.is_empty()
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            }));
+    if !valid_domain {
+        return Err(format!(
+            "auth.cookie_domain must be a host name such as example.com, without scheme, port or path, not '{}'",
+            domain
+        ));
+    }
+
+    Ok(())
+}
+
+/// Requests without an `Origin` header fall back to `Sec-Fetch-Site`; without
+/// either there is nothing to relax for, so they count as same-origin.
+pub fn is_same_origin(headers: &HeaderMap) -> bool {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+    if let Some(origin) = header(ORIGIN.as_str()) {
+        let origin_host = origin
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(origin);
+        return header(HOST.as_str()).is_some_and(|host| host.eq_ignore_ascii_case(origin_host));
+    }
+
+    match header("sec-fetch-site") {
+        Some(site) => site == "same-origin" || site == "none",
+        None => true,
+    }
+}
+
 pub struct CookieService {
     config: CookieConfig,
 }
@@ -41,6 +128,16 @@ impl CookieService {
         Self { config }
     }
 
+    /// Cookies for a response to the request with `headers`, see
+    /// [`CookieConfig::for_request`].
+    pub async fn for_request<C: ConfigurationAccess>(config: &C, headers: &HeaderMap) -> Self {
+        Self::with_config(CookieConfig::for_request(config, headers).await)
+    }
+
+    pub fn config(&self) -> &CookieConfig {
+        &self.config
+    }
+
     pub fn set_access_token_cookie(&self, headers: &mut HeaderMap, token: &str) {
         let cookie_value = self.build_cookie("access_token", token, Duration::minutes(15), "/");
 
@@ -57,6 +154,22 @@ impl CookieService {
         }
     }
 
+    /// Issues a new double-submit CSRF token alongside the auth cookies and
+    /// returns it. Unlike the tokens it is not `HttpOnly`.
+    pub fn set_csrf_token_cookie(&self, headers: &mut HeaderMap) -> String {
+        let token = Uuid::new_v4().simple().to_string();
+        let readable = Self::with_config(CookieConfig {
+            http_only: false,
+            ..self.config.clone()
+        });
+        let cookie_value = readable.build_cookie(CSRF_COOKIE, &token, Duration::days(7), "/");
+
+        if let Ok(header_value) = HeaderValue::from_str(&cookie_value) {
+            headers.append(SET_COOKIE, header_value);
+        }
+        token
+    }
+
     pub fn clear_access_token_cookie(&self, headers: &mut HeaderMap) {
         let cookie_value = self.build_clear_cookie("access_token", "/");
 
@@ -73,9 +186,22 @@ impl CookieService {
         }
     }
 
+    pub fn clear_csrf_token_cookie(&self, headers: &mut HeaderMap) {
+        let readable = Self::with_config(CookieConfig {
+            http_only: false,
+            ..self.config.clone()
+        });
+        let cookie_value = readable.build_clear_cookie(CSRF_COOKIE, "/");
+
+        if let Ok(header_value) = HeaderValue::from_str(&cookie_value) {
+            headers.append(SET_COOKIE, header_value);
+        }
+    }
+
     pub fn clear_all_tokens(&self, headers: &mut HeaderMap) {
         self.clear_access_token_cookie(headers);
         self.clear_refresh_token_cookie(headers);
+        self.clear_csrf_token_cookie(headers);
     }
 
     fn build_cookie(&self, name: &str, value: &str, max_age: Duration, path: &str) -> String {
@@ -163,6 +289,10 @@ impl CookieService {
     pub fn extract_refresh_token(headers: &HeaderMap) -> Option<String> {
         Self::extract_token_from_cookies(headers, "refresh_token")
     }
+
+    pub fn extract_csrf_token(headers: &HeaderMap) -> Option<String> {
+        Self::extract_token_from_cookies(headers, CSRF_COOKIE)
+    }
 }
 
 #[cfg(test)]
@@ -198,6 +328,70 @@ mod tests {
         assert_eq!(token, Some("test123".to_string()));
     }
 
+    #[test]
+    fn test_csrf_cookie_is_readable_by_scripts() {
+        let service = CookieService::new();
+        let mut headers = HeaderMap::new();
+
+        let token = service.set_csrf_token_cookie(&mut headers);
+
+        let cookie_str = headers.get(SET_COOKIE).unwrap().to_str().unwrap();
+        assert!(cookie_str.starts_with(&format!("csrf_token={};", token)));
+        assert!(!cookie_str.contains("HttpOnly"));
+        assert_ne!(token, service.set_csrf_token_cookie(&mut headers));
+    }
+
+    #[test]
+    fn test_same_origin_detection() {
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.insert(*name, value.parse().unwrap());
+            }
+            headers
+        };
+
+        assert!(is_same_origin(&headers(&[])));
+        assert!(is_same_origin(&headers(&[
+            ("host", "api.example.com"),
+            ("origin", "https://api.example.com"),
+        ])));
+        assert!(!is_same_origin(&headers(&[
+            ("host", "api.example.com"),
+            ("origin", "https://admin.example.com"),
+        ])));
+        assert!(!is_same_origin(&headers(&[
+            ("host", "api.example.com:8443"),
+            ("origin", "https://api.example.com"),
+        ])));
+        assert!(!is_same_origin(&headers(&[(
+            "origin",
+            "https://admin.example.com"
+        )])));
+        assert!(is_same_origin(&headers(&[(
+            "sec-fetch-site",
+            "same-origin"
+        )])));
+        assert!(!is_same_origin(&headers(&[(
+            "sec-fetch-site",
+            "cross-site"
+        )])));
+    }
+
+    #[test]
+    fn test_cookie_settings_validation() {
+        assert!(validate_cookie_settings("Lax", "").is_ok());
+        assert!(validate_cookie_settings("None", "example.com").is_ok());
+        assert!(validate_cookie_settings("Strict", ".admin.example.com").is_ok());
+
+        assert!(validate_cookie_settings("none", "").is_err());
+        assert!(validate_cookie_settings("Lax", "https://example.com").is_err());
+        assert!(validate_cookie_settings("Lax", "example.com:443").is_err());
+        assert!(validate_cookie_settings("Lax", "example.com/admin").is_err());
+        assert!(validate_cookie_settings("Lax", "-example.com").is_err());
+        assert!(validate_cookie_settings("Lax", ".").is_err());
+    }
+
     #[test]
     fn test_clear_cookies() {
         let service = CookieService::new();
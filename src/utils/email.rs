@@ -0,0 +1,264 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::services::ConfigurationAccess;
+use crate::utils::LunarbaseError;
+
+/// Longest address that fits a SMTP path (RFC 5321, 4.5.3.1.3).
+pub const MAX_EMAIL_LENGTH: usize = 254;
+const MAX_LOCAL_PART_LENGTH: usize = 64;
+const MAX_DOMAIN_LABEL_LENGTH: usize = 63;
+
+const MX_LOOKUP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Canonical form of an email address: surrounding whitespace trimmed and the
+/// domain lowercased. The local part keeps its case, since RFC 5321 leaves it
+/// to the receiving server. `None` when the address is not RFC 5322
+/// addr-spec syntax (dot-atom or quoted local part, host name or address
+/// literal domain); non-ASCII letters are allowed as in RFC 6531.
+pub fn normalize_email(input: &str) -> Option<String> {
+    let email = input.trim();
+    let (local, domain) = email.rsplit_once('@')?;
+
+    if !is_valid_local_part(local) || !is_valid_domain(domain) {
+        return None;
+    }
+
+    let normalized = format!("{}@{}", local, domain.to_lowercase());
+    (normalized.len() <= MAX_EMAIL_LENGTH).then_some(normalized)
+}
+
+pub fn is_valid_email(input: &str) -> bool {
+    normalize_email(input).is_some()
+}
+
+fn is_atext(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c) || !c.is_ascii()
+}
+
+fn is_valid_local_part(local: &str) -> bool {
+    if local.is_empty() || local.len() > MAX_LOCAL_PART_LENGTH {
+        return false;
+    }
+
+    if let Some(quoted) = local
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    if !chars
+                        .next()
+                        .is_some_and(|escaped| escaped == ' ' || escaped.is_ascii_graphic())
+                    {
+                        return false;
+                    }
+                }
+                '"' => return false,
+                c if c.is_ascii_control() => return false,
+                _ => {}
+            }
+        }
+        return true;
+    }
+
+    local
+        .split('.')
+        .all(|atom| !atom.is_empty() && atom.chars().all(is_atext))
+}
+
+fn is_valid_domain(domain: &str) -> bool {
+    if let Some(literal) = domain
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        return match literal.strip_prefix("IPv6:") {
+            Some(v6) => v6.parse::<std::net::Ipv6Addr>().is_ok(),
+            None => literal.parse::<std::net::Ipv4Addr>().is_ok(),
+        };
+    }
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label.len() <= MAX_DOMAIN_LABEL_LENGTH
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+        // A numeric top-level label would make it an IP address
+        && !labels[labels.len() - 1].chars().all(|c| c.is_ascii_digit())
+}
+
+#[derive(Deserialize)]
+struct DnsJsonResponse {
+    #[serde(rename = "Status")]
+    status: u32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DnsJsonAnswer>,
+}
+
+#[derive(Deserialize)]
+struct DnsJsonAnswer {
+    #[serde(rename = "type")]
+    record_type: u16,
+    data: String,
+}
+
+const DNS_TYPE_A: u16 = 1;
+const DNS_TYPE_MX: u16 = 15;
+const DNS_TYPE_AAAA: u16 = 28;
+const DNS_NXDOMAIN: u32 = 3;
+
+async fn dns_query(
+    client: &reqwest::Client,
+    resolver_url: &str,
+    domain: &str,
+    record_type: &str,
+) -> Result<DnsJsonResponse, reqwest::Error> {
+    client
+        .get(resolver_url)
+        .query(&[("name", domain), ("type", record_type)])
+        .header("accept", "application/dns-json")
+        .timeout(MX_LOOKUP_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+}
+
+/// Whether `domain` can receive mail, asked of a DNS-over-HTTPS resolver that
+/// speaks the JSON API: it needs an MX record other than the null MX of
+/// RFC 7505, or failing any MX an A/AAAA record to fall back to. `None` when
+/// the resolver could not be asked.
+pub async fn domain_accepts_mail(resolver_url: &str, domain: &str) -> Option<bool> {
+    let client = reqwest::Client::new();
+
+    let mx = dns_query(&client, resolver_url, domain, "MX").await.ok()?;
+    if mx.status == DNS_NXDOMAIN {
+        return Some(false);
+    }
+    let exchanges: Vec<&DnsJsonAnswer> = mx
+        .answer
+        .iter()
+        .filter(|answer| answer.record_type == DNS_TYPE_MX)
+        .collect();
+    if !exchanges.is_empty() {
+        return Some(exchanges.iter().any(|answer| {
+            answer
+                .data
+                .split_whitespace()
+                .nth(1)
+                .is_some_and(|host| host != ".")
+        }));
+    }
+
+    for record_type in ["A", "AAAA"] {
+        let address = dns_query(&client, resolver_url, domain, record_type)
+            .await
+            .ok()?;
+        if address
+            .answer
+            .iter()
+            .any(|answer| matches!(answer.record_type, DNS_TYPE_A | DNS_TYPE_AAAA))
+        {
+            return Some(true);
+        }
+    }
+    Some(false)
+}
+
+/// With `auth.email_mx_check_enabled` set, rejects a normalized address whose
+/// domain cannot receive mail. A resolver that cannot be reached lets the
+/// address through, so an outage does not block sign-ups.
+pub async fn ensure_deliverable_email(
+    config: &impl ConfigurationAccess,
+    email: &str,
+) -> Result<(), LunarbaseError> {
+    if !config.get_email_mx_check_enabled().await {
+        return Ok(());
+    }
+    let Some((_, domain)) = email.rsplit_once('@') else {
+        return Ok(());
+    };
+    if domain.starts_with('[') {
+        return Ok(());
+    }
+
+    let resolver_url = config.get_email_mx_resolver_url().await;
+    match domain_accepts_mail(&resolver_url, domain).await {
+        Some(false) => Err(LunarbaseError::ValidationError(vec![format!(
+            "The domain {} does not accept email",
+            domain
+        )])),
+        Some(true) => Ok(()),
+        None => {
+            tracing::warn!(
+                "MX lookup for {} via {} failed; accepting the address",
+                domain,
+                resolver_url
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_trims_and_lowercases_only_the_domain() {
+        assert_eq!(
+            normalize_email("  User.Name+tag@Example.COM "),
+            Some("User.Name+tag@example.com".to_string())
+        );
+        assert_eq!(
+            normalize_email("\"john doe\"@Example.org"),
+            Some("\"john doe\"@example.org".to_string())
+        );
+        assert_eq!(
+            normalize_email("ÜSER@BÜCHER.DE"),
+            Some("ÜSER@bücher.de".to_string())
+        );
+        assert_eq!(
+            normalize_email("admin@[192.168.0.1]"),
+            Some("admin@[192.168.0.1]".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_syntax() {
+        for email in [
+            "",
+            "plainaddress",
+            "@example.com",
+            "user@",
+            "user@localhost",
+            "user@example..com",
+            "user@-example.com",
+            "user@example.123",
+            ".user@example.com",
+            "user.@example.com",
+            "us..er@example.com",
+            "us er@example.com",
+            "user@exa mple.com",
+            "\"unterminated@example.com",
+            "user@[300.1.1.1]",
+        ] {
+            assert!(!is_valid_email(email), "{}", email);
+        }
+
+        let long_local = format!("{}@example.com", "a".repeat(65));
+        assert!(!is_valid_email(&long_local));
+        let long_domain = format!("a@{}.com", "b".repeat(64));
+        assert!(!is_valid_email(&long_domain));
+        let too_long = format!("a@{}.com", ["c".repeat(60); 5].join("."));
+        assert!(!is_valid_email(&too_long));
+    }
+}
@@ -0,0 +1,991 @@
+use serde::{Serialize, Serializer};
+use std::future::Future;
+
+/// Languages with a bundled message catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Pl,
+    De,
+}
+
+impl Locale {
+    /// The locale of a language tag like `pl` or `de-AT`, ignoring the region.
+    pub fn parse(tag: &str) -> Option<Self> {
+        let language = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match language.as_str() {
+            "en" => Some(Locale::En),
+            "pl" => Some(Locale::Pl),
+            "de" => Some(Locale::De),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Pl => "pl",
+            Locale::De => "de",
+        }
+    }
+
+    /// The most preferred supported language of an `Accept-Language` header,
+    /// or `default` when the client accepts none of them.
+    pub fn negotiate(accept_language: Option<&str>, default: Locale) -> Locale {
+        let Some(header) = accept_language else {
+            return default;
+        };
+
+        let mut preferences: Vec<(f32, Locale)> = header
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let locale = Locale::parse(parts.next()?)?;
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                (quality > 0.0).then_some((quality, locale))
+            })
+            .collect();
+
+        // Stable, so equally weighted languages keep the client's order
+        preferences.sort_by(|a, b| b.0.total_cmp(&a.0));
+        preferences.first().map_or(default, |(_, locale)| *locale)
+    }
+}
+
+tokio::task_local! {
+    static REQUEST_LOCALE: Locale;
+}
+
+/// Runs `future` with `locale` as the language its responses are rendered in.
+pub async fn with_locale<F: Future>(locale: Locale, future: F) -> F::Output {
+    REQUEST_LOCALE.scope(locale, future).await
+}
+
+/// Language of the request being handled; English outside of a request.
+pub fn current_locale() -> Locale {
+    REQUEST_LOCALE
+        .try_with(|locale| *locale)
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Param {
+    Text(String),
+    Message(Box<Message>),
+}
+
+/// A catalog key with its parameters, rendered in the request's language
+/// only when it is sent to the client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    key: &'static str,
+    params: Vec<(&'static str, Param)>,
+}
+
+impl Message {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            params: Vec::new(),
+        }
+    }
+
+    pub fn arg(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.params.push((name, Param::Text(value.to_string())));
+        self
+    }
+
+    /// A parameter that is itself a message, rendered in the same language.
+    pub fn nested(mut self, name: &'static str, message: Message) -> Self {
+        self.params.push((name, Param::Message(Box::new(message))));
+        self
+    }
+
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+
+    pub fn render(&self, locale: Locale) -> String {
+        let template = translate(locale, self.key)
+            .or_else(|| translate(Locale::En, self.key))
+            .unwrap_or(self.key);
+
+        // One pass, so braces inside parameter values are left alone
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            rendered.push_str(&rest[..start]);
+            let placeholder = &rest[start..];
+            let param = placeholder.find('}').and_then(|end| {
+                let name = &placeholder[1..end];
+                let (_, param) = self.params.iter().find(|(param, _)| *param == name)?;
+                Some((end, param))
+            });
+            match param {
+                Some((end, Param::Text(text))) => {
+                    rendered.push_str(text);
+                    rest = &placeholder[end + 1..];
+                }
+                Some((end, Param::Message(message))) => {
+                    rendered.push_str(&message.render(locale));
+                    rest = &placeholder[end + 1..];
+                }
+                None => {
+                    rendered.push('{');
+                    rest = &placeholder[1..];
+                }
+            }
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+}
+
+impl std::fmt::Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(Locale::En))
+    }
+}
+
+impl Serialize for Message {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.render(current_locale()))
+    }
+}
+
+fn translate(locale: Locale, key: &str) -> Option<&'static str> {
+    let catalog = match locale {
+        Locale::En => EN,
+        Locale::Pl => PL,
+        Locale::De => DE,
+    };
+    catalog
+        .iter()
+        .find(|(entry, _)| *entry == key)
+        .map(|(_, text)| *text)
+}
+
+const EN: &[(&str, &str)] = &[
+    ("error.invalid_credentials", "Invalid email or password"),
+    (
+        "error.account_locked",
+        "Account temporarily locked due to multiple failed login attempts",
+    ),
+    (
+        "error.account_not_verified",
+        "Please verify your email address to continue",
+    ),
+    (
+        "error.account_deactivated",
+        "This account has been deactivated",
+    ),
+    ("error.user_already_verified", "User is already verified"),
+    ("error.user_not_found", "User not found"),
+    ("error.token_expired", "Token has expired"),
+    ("error.token_invalid", "Invalid or malformed token"),
+    ("error.token_missing", "Authorization token is missing"),
+    ("error.csrf_token_invalid", "Missing or invalid CSRF token"),
+    (
+        "error.permission_denied",
+        "You don't have permission to access this resource",
+    ),
+    (
+        "error.password_reset_token_invalid",
+        "Invalid or expired password reset token",
+    ),
+    (
+        "error.password_reset_token_expired",
+        "Password reset token has expired",
+    ),
+    (
+        "error.weak_password",
+        "Password does not meet security requirements",
+    ),
+    (
+        "error.rate_limit_exceeded",
+        "Too many requests. Please try again later",
+    ),
+    ("error.query_too_complex", "Query is too complex"),
+    (
+        "error.query_too_complex_details",
+        "Query complexity {complexity} exceeds the budget of {budget}. Each filter or `in` value costs {predicate_cost}, each LIKE comparison (like/notlike filters and every searched field) costs {like_cost}, and each expanded relation costs {expand_cost}",
+    ),
+    ("error.payload_too_large", "Request body is too large"),
+    (
+        "error.payload_too_large_details",
+        "The request body exceeds the {limit} allowed by {setting}",
+    ),
+    ("error.usage_quota_exceeded", "Usage quota exceeded"),
+    (
+        "error.records_quota_exceeded_details",
+        "Role {role} may create at most {limit} records in any 24 hours",
+    ),
+    (
+        "error.upload_quota_exceeded_details",
+        "Role {role} may upload at most {limit} bytes in any 24 hours",
+    ),
+    ("error.validation_failed", "Validation failed"),
+    ("error.bad_request", "Bad request"),
+    ("error.conflict", "Resource already exists"),
+    (
+        "error.database_error",
+        "A database error occurred. Please try again later",
+    ),
+    (
+        "error.internal_error",
+        "An internal error occurred. Please try again later",
+    ),
+    ("error.not_found", "Resource not found"),
+    ("error.forbidden", "Access forbidden"),
+    (
+        "error.method_not_allowed",
+        "This operation is not allowed on this resource",
+    ),
+    (
+        "error.read_only_mode",
+        "The API is temporarily read-only for your role; reading and signing in still work, writes are disabled until maintenance finishes",
+    ),
+    (
+        "error.share_link_expired",
+        "This share link has expired or reached its view limit",
+    ),
+    (
+        "error.share_password_invalid",
+        "A valid password is required to open this share link",
+    ),
+    (
+        "validation.not_an_object",
+        "Record data must be a JSON object",
+    ),
+    ("validation.required", "Field '{field}' is required"),
+    ("validation.text_expected", "Field '{field}' must be text"),
+    (
+        "validation.too_short",
+        "Field '{field}' is too short (minimum {min} characters)",
+    ),
+    (
+        "validation.too_long",
+        "Field '{field}' is too long (maximum {max} characters)",
+    ),
+    (
+        "validation.pattern_mismatch",
+        "Field '{field}' does not match required pattern: {pattern}",
+    ),
+    (
+        "validation.invalid_pattern",
+        "Invalid regex pattern for field '{field}': {pattern}",
+    ),
+    (
+        "validation.invalid_choice",
+        "Field '{field}' must be one of: {choices}",
+    ),
+    (
+        "validation.number_expected",
+        "Field '{field}' must be a number",
+    ),
+    (
+        "validation.integer_expected",
+        "Field '{field}' must be a whole number",
+    ),
+    (
+        "validation.too_small",
+        "Field '{field}' is too small (minimum {min})",
+    ),
+    (
+        "validation.too_large",
+        "Field '{field}' is too large (maximum {max})",
+    ),
+    (
+        "validation.boolean_expected",
+        "Field '{field}' must be a boolean",
+    ),
+    (
+        "validation.invalid_email",
+        "Field '{field}' must be a valid email address",
+    ),
+    (
+        "validation.decimal_expected",
+        "Field '{field}' must be a decimal string or number",
+    ),
+    ("validation.invalid_decimal", "Field '{field}': {reason}"),
+    (
+        "validation.latitude_out_of_range",
+        "Field '{field}' latitude must be between -90 and 90",
+    ),
+    (
+        "validation.longitude_out_of_range",
+        "Field '{field}' longitude must be between -180 and 180",
+    ),
+    (
+        "validation.geo_point_expected",
+        "Field '{field}' must be an object with numeric lat and lng",
+    ),
+    (
+        "validation.invalid_date",
+        "Field '{field}' must be a valid date in YYYY-MM-DD format",
+    ),
+    (
+        "validation.date_expected",
+        "Field '{field}' must be a date string",
+    ),
+    (
+        "validation.url_scheme",
+        "Field '{field}' must be a valid URL starting with http:// or https://",
+    ),
+    (
+        "validation.invalid_url",
+        "Field '{field}' must be a valid URL",
+    ),
+    (
+        "validation.url_expected",
+        "Field '{field}' must be a URL string",
+    ),
+    (
+        "validation.invalid_slug",
+        "Field '{field}' must be a slug of lowercase letters, digits and dashes",
+    ),
+    (
+        "validation.invalid_file_path",
+        "Field '{field}' must be a valid file path (max 500 characters)",
+    ),
+    (
+        "validation.file_path_expected",
+        "Field '{field}' must be a file path string",
+    ),
+    (
+        "validation.invalid_relation",
+        "Field '{field}' must be a valid relation ID (max 50 characters)",
+    ),
+    (
+        "validation.relation_expected",
+        "Field '{field}' must be a relation ID (string or number)",
+    ),
+    (
+        "multipart.unknown_part",
+        "Unexpected form part '{part}'; send the record as 'data' and files as 'file_<field>'",
+    ),
+    (
+        "multipart.duplicate_part",
+        "Form part '{part}' was sent more than once",
+    ),
+    (
+        "multipart.invalid_encoding",
+        "Form part '{part}' is not valid UTF-8",
+    ),
+    (
+        "multipart.invalid_json",
+        "Form part '{part}' is not valid JSON: {reason}",
+    ),
+    (
+        "validation.unknown_file_field",
+        "Field '{field}' does not exist in the collection schema",
+    ),
+    (
+        "validation.not_a_file_field",
+        "Field '{field}' is not a file field",
+    ),
+    (
+        "validation.invalid_content_type",
+        "Field '{field}' has an invalid content type '{content_type}'",
+    ),
+    (
+        "validation.invalid_base64",
+        "Field '{field}' does not contain valid base64 data",
+    ),
+    (
+        "validation.uploads_disabled",
+        "Cannot upload '{field}': file uploads are disabled because S3 storage is not enabled",
+    ),
+    (
+        "validation.upload_failed",
+        "Failed to upload the file for field '{field}'",
+    ),
+    (
+        "validation.malware_detected",
+        "The file for field '{field}' was rejected because the malware scanner found {signature}",
+    ),
+    (
+        "validation.scan_unavailable",
+        "The file for field '{field}' could not be scanned for malware, try again later",
+    ),
+    ("validation.batch_operation", "Operation {index}: {message}"),
+];
+
+const PL: &[(&str, &str)] = &[
+    (
+        "error.invalid_credentials",
+        "Nieprawidłowy e-mail lub hasło",
+    ),
+    (
+        "error.account_locked",
+        "Konto zostało tymczasowo zablokowane po wielu nieudanych próbach logowania",
+    ),
+    (
+        "error.account_not_verified",
+        "Potwierdź swój adres e-mail, aby kontynuować",
+    ),
+    (
+        "error.account_deactivated",
+        "To konto zostało dezaktywowane",
+    ),
+    (
+        "error.user_already_verified",
+        "Użytkownik jest już zweryfikowany",
+    ),
+    ("error.user_not_found", "Nie znaleziono użytkownika"),
+    ("error.token_expired", "Token wygasł"),
+    ("error.token_invalid", "Nieprawidłowy lub uszkodzony token"),
+    ("error.token_missing", "Brak tokenu autoryzacyjnego"),
+    (
+        "error.csrf_token_invalid",
+        "Brak lub nieprawidłowy token CSRF",
+    ),
+    (
+        "error.permission_denied",
+        "Nie masz uprawnień do tego zasobu",
+    ),
+    (
+        "error.password_reset_token_invalid",
+        "Nieprawidłowy lub wygasły token resetowania hasła",
+    ),
+    (
+        "error.password_reset_token_expired",
+        "Token resetowania hasła wygasł",
+    ),
+    (
+        "error.weak_password",
+        "Hasło nie spełnia wymagań bezpieczeństwa",
+    ),
+    (
+        "error.rate_limit_exceeded",
+        "Zbyt wiele żądań. Spróbuj ponownie później",
+    ),
+    ("error.query_too_complex", "Zapytanie jest zbyt złożone"),
+    (
+        "error.query_too_complex_details",
+        "Złożoność zapytania {complexity} przekracza limit {budget}. Każdy filtr lub wartość `in` kosztuje {predicate_cost}, każde porównanie LIKE (filtry like/notlike i każde przeszukiwane pole) kosztuje {like_cost}, a każda rozwinięta relacja kosztuje {expand_cost}",
+    ),
+    ("error.payload_too_large", "Treść żądania jest zbyt duża"),
+    (
+        "error.payload_too_large_details",
+        "Treść żądania przekracza limit {limit} ustawiony w {setting}",
+    ),
+    ("error.usage_quota_exceeded", "Przekroczono limit użycia"),
+    (
+        "error.records_quota_exceeded_details",
+        "Rola {role} może utworzyć co najwyżej {limit} rekordów w ciągu 24 godzin",
+    ),
+    (
+        "error.upload_quota_exceeded_details",
+        "Rola {role} może przesłać co najwyżej {limit} bajtów w ciągu 24 godzin",
+    ),
+    ("error.validation_failed", "Walidacja nie powiodła się"),
+    ("error.bad_request", "Nieprawidłowe żądanie"),
+    ("error.conflict", "Zasób już istnieje"),
+    (
+        "error.database_error",
+        "Wystąpił błąd bazy danych. Spróbuj ponownie później",
+    ),
+    (
+        "error.internal_error",
+        "Wystąpił błąd wewnętrzny. Spróbuj ponownie później",
+    ),
+    ("error.not_found", "Nie znaleziono zasobu"),
+    ("error.forbidden", "Dostęp zabroniony"),
+    (
+        "error.method_not_allowed",
+        "Ta operacja nie jest dozwolona dla tego zasobu",
+    ),
+    (
+        "error.read_only_mode",
+        "API jest tymczasowo tylko do odczytu dla Twojej roli; odczyt i logowanie nadal działają, zapisy są wyłączone do końca prac serwisowych",
+    ),
+    (
+        "error.share_link_expired",
+        "Ten link udostępniania wygasł lub osiągnął limit wyświetleń",
+    ),
+    (
+        "error.share_password_invalid",
+        "Do otwarcia tego linku udostępniania wymagane jest prawidłowe hasło",
+    ),
+    (
+        "validation.not_an_object",
+        "Dane rekordu muszą być obiektem JSON",
+    ),
+    ("validation.required", "Pole '{field}' jest wymagane"),
+    (
+        "validation.text_expected",
+        "Pole '{field}' musi być tekstem",
+    ),
+    (
+        "validation.too_short",
+        "Pole '{field}' jest za krótkie (minimum {min} znaków)",
+    ),
+    (
+        "validation.too_long",
+        "Pole '{field}' jest za długie (maksimum {max} znaków)",
+    ),
+    (
+        "validation.pattern_mismatch",
+        "Pole '{field}' nie pasuje do wymaganego wzorca: {pattern}",
+    ),
+    (
+        "validation.invalid_pattern",
+        "Nieprawidłowe wyrażenie regularne dla pola '{field}': {pattern}",
+    ),
+    (
+        "validation.invalid_choice",
+        "Pole '{field}' musi mieć jedną z wartości: {choices}",
+    ),
+    (
+        "validation.number_expected",
+        "Pole '{field}' musi być liczbą",
+    ),
+    (
+        "validation.integer_expected",
+        "Pole '{field}' musi być liczbą całkowitą",
+    ),
+    (
+        "validation.too_small",
+        "Wartość pola '{field}' jest za mała (minimum {min})",
+    ),
+    (
+        "validation.too_large",
+        "Wartość pola '{field}' jest za duża (maksimum {max})",
+    ),
+    (
+        "validation.boolean_expected",
+        "Pole '{field}' musi być wartością logiczną",
+    ),
+    (
+        "validation.invalid_email",
+        "Pole '{field}' musi być prawidłowym adresem e-mail",
+    ),
+    (
+        "validation.decimal_expected",
+        "Pole '{field}' musi być liczbą dziesiętną lub tekstem z liczbą",
+    ),
+    ("validation.invalid_decimal", "Pole '{field}': {reason}"),
+    (
+        "validation.latitude_out_of_range",
+        "Szerokość geograficzna w polu '{field}' musi mieścić się między -90 a 90",
+    ),
+    (
+        "validation.longitude_out_of_range",
+        "Długość geograficzna w polu '{field}' musi mieścić się między -180 a 180",
+    ),
+    (
+        "validation.geo_point_expected",
+        "Pole '{field}' musi być obiektem z liczbowymi lat i lng",
+    ),
+    (
+        "validation.invalid_date",
+        "Pole '{field}' musi być prawidłową datą w formacie RRRR-MM-DD",
+    ),
+    (
+        "validation.date_expected",
+        "Pole '{field}' musi być tekstem z datą",
+    ),
+    (
+        "validation.url_scheme",
+        "Pole '{field}' musi być prawidłowym adresem URL zaczynającym się od http:// lub https://",
+    ),
+    (
+        "validation.invalid_url",
+        "Pole '{field}' musi być prawidłowym adresem URL",
+    ),
+    (
+        "validation.url_expected",
+        "Pole '{field}' musi być tekstem z adresem URL",
+    ),
+    (
+        "validation.invalid_slug",
+        "Pole '{field}' musi być slugiem złożonym z małych liter, cyfr i myślników",
+    ),
+    (
+        "validation.invalid_file_path",
+        "Pole '{field}' musi być prawidłową ścieżką pliku (maksimum 500 znaków)",
+    ),
+    (
+        "validation.file_path_expected",
+        "Pole '{field}' musi być tekstem ze ścieżką pliku",
+    ),
+    (
+        "validation.invalid_relation",
+        "Pole '{field}' musi być prawidłowym identyfikatorem relacji (maksimum 50 znaków)",
+    ),
+    (
+        "validation.relation_expected",
+        "Pole '{field}' musi być identyfikatorem relacji (tekst lub liczba)",
+    ),
+    (
+        "multipart.unknown_part",
+        "Nieoczekiwana część formularza '{part}'; wyślij rekord jako 'data', a pliki jako 'file_<pole>'",
+    ),
+    (
+        "multipart.duplicate_part",
+        "Część formularza '{part}' została wysłana więcej niż raz",
+    ),
+    (
+        "multipart.invalid_encoding",
+        "Część formularza '{part}' nie jest poprawnym UTF-8",
+    ),
+    (
+        "multipart.invalid_json",
+        "Część formularza '{part}' nie jest poprawnym JSON-em: {reason}",
+    ),
+    (
+        "validation.unknown_file_field",
+        "Pole '{field}' nie istnieje w schemacie kolekcji",
+    ),
+    (
+        "validation.not_a_file_field",
+        "Pole '{field}' nie jest polem pliku",
+    ),
+    (
+        "validation.invalid_content_type",
+        "Pole '{field}' ma nieprawidłowy typ zawartości '{content_type}'",
+    ),
+    (
+        "validation.invalid_base64",
+        "Pole '{field}' nie zawiera poprawnych danych base64",
+    ),
+    (
+        "validation.uploads_disabled",
+        "Nie można przesłać '{field}': przesyłanie plików jest wyłączone, ponieważ magazyn S3 nie jest włączony",
+    ),
+    (
+        "validation.upload_failed",
+        "Nie udało się przesłać pliku dla pola '{field}'",
+    ),
+    (
+        "validation.malware_detected",
+        "Plik dla pola '{field}' został odrzucony, ponieważ skaner wykrył {signature}",
+    ),
+    (
+        "validation.scan_unavailable",
+        "Nie udało się przeskanować pliku dla pola '{field}' w poszukiwaniu złośliwego oprogramowania, spróbuj ponownie później",
+    ),
+    ("validation.batch_operation", "Operacja {index}: {message}"),
+];
+
+const DE: &[(&str, &str)] = &[
+    (
+        "error.invalid_credentials",
+        "Ungültige E-Mail-Adresse oder ungültiges Passwort",
+    ),
+    (
+        "error.account_locked",
+        "Konto nach mehreren fehlgeschlagenen Anmeldeversuchen vorübergehend gesperrt",
+    ),
+    (
+        "error.account_not_verified",
+        "Bitte bestätige deine E-Mail-Adresse, um fortzufahren",
+    ),
+    (
+        "error.account_deactivated",
+        "Dieses Konto wurde deaktiviert",
+    ),
+    (
+        "error.user_already_verified",
+        "Der Benutzer ist bereits verifiziert",
+    ),
+    ("error.user_not_found", "Benutzer nicht gefunden"),
+    ("error.token_expired", "Das Token ist abgelaufen"),
+    ("error.token_invalid", "Ungültiges oder fehlerhaftes Token"),
+    ("error.token_missing", "Das Autorisierungstoken fehlt"),
+    (
+        "error.csrf_token_invalid",
+        "CSRF-Token fehlt oder ist ungültig",
+    ),
+    (
+        "error.permission_denied",
+        "Du hast keine Berechtigung für diese Ressource",
+    ),
+    (
+        "error.password_reset_token_invalid",
+        "Ungültiges oder abgelaufenes Token zum Zurücksetzen des Passworts",
+    ),
+    (
+        "error.password_reset_token_expired",
+        "Das Token zum Zurücksetzen des Passworts ist abgelaufen",
+    ),
+    (
+        "error.weak_password",
+        "Das Passwort erfüllt die Sicherheitsanforderungen nicht",
+    ),
+    (
+        "error.rate_limit_exceeded",
+        "Zu viele Anfragen. Bitte versuche es später erneut",
+    ),
+    ("error.query_too_complex", "Die Abfrage ist zu komplex"),
+    (
+        "error.query_too_complex_details",
+        "Die Abfragekomplexität {complexity} überschreitet das Budget von {budget}. Jeder Filter oder `in`-Wert kostet {predicate_cost}, jeder LIKE-Vergleich (like/notlike-Filter und jedes durchsuchte Feld) kostet {like_cost} und jede erweiterte Relation kostet {expand_cost}",
+    ),
+    ("error.payload_too_large", "Der Anfrageinhalt ist zu groß"),
+    (
+        "error.payload_too_large_details",
+        "Der Anfrageinhalt überschreitet das in {setting} festgelegte Limit von {limit}",
+    ),
+    (
+        "error.usage_quota_exceeded",
+        "Nutzungskontingent überschritten",
+    ),
+    (
+        "error.records_quota_exceeded_details",
+        "Die Rolle {role} darf innerhalb von 24 Stunden höchstens {limit} Datensätze erstellen",
+    ),
+    (
+        "error.upload_quota_exceeded_details",
+        "Die Rolle {role} darf innerhalb von 24 Stunden höchstens {limit} Bytes hochladen",
+    ),
+    ("error.validation_failed", "Validierung fehlgeschlagen"),
+    ("error.bad_request", "Ungültige Anfrage"),
+    ("error.conflict", "Die Ressource existiert bereits"),
+    (
+        "error.database_error",
+        "Ein Datenbankfehler ist aufgetreten. Bitte versuche es später erneut",
+    ),
+    (
+        "error.internal_error",
+        "Ein interner Fehler ist aufgetreten. Bitte versuche es später erneut",
+    ),
+    ("error.not_found", "Ressource nicht gefunden"),
+    ("error.forbidden", "Zugriff verweigert"),
+    (
+        "error.method_not_allowed",
+        "Dieser Vorgang ist für diese Ressource nicht erlaubt",
+    ),
+    (
+        "error.read_only_mode",
+        "Die API ist für deine Rolle vorübergehend schreibgeschützt; Lesen und Anmelden funktionieren weiterhin, Schreibzugriffe sind bis zum Ende der Wartung deaktiviert",
+    ),
+    (
+        "error.share_link_expired",
+        "Dieser Freigabelink ist abgelaufen oder hat sein Aufruflimit erreicht",
+    ),
+    (
+        "error.share_password_invalid",
+        "Zum Öffnen dieses Freigabelinks ist ein gültiges Passwort erforderlich",
+    ),
+    (
+        "validation.not_an_object",
+        "Die Datensatzdaten müssen ein JSON-Objekt sein",
+    ),
+    ("validation.required", "Das Feld '{field}' ist erforderlich"),
+    (
+        "validation.text_expected",
+        "Das Feld '{field}' muss Text sein",
+    ),
+    (
+        "validation.too_short",
+        "Das Feld '{field}' ist zu kurz (mindestens {min} Zeichen)",
+    ),
+    (
+        "validation.too_long",
+        "Das Feld '{field}' ist zu lang (höchstens {max} Zeichen)",
+    ),
+    (
+        "validation.pattern_mismatch",
+        "Das Feld '{field}' entspricht nicht dem erforderlichen Muster: {pattern}",
+    ),
+    (
+        "validation.invalid_pattern",
+        "Ungültiger regulärer Ausdruck für das Feld '{field}': {pattern}",
+    ),
+    (
+        "validation.invalid_choice",
+        "Das Feld '{field}' muss einen dieser Werte haben: {choices}",
+    ),
+    (
+        "validation.number_expected",
+        "Das Feld '{field}' muss eine Zahl sein",
+    ),
+    (
+        "validation.integer_expected",
+        "Das Feld '{field}' muss eine ganze Zahl sein",
+    ),
+    (
+        "validation.too_small",
+        "Der Wert des Feldes '{field}' ist zu klein (mindestens {min})",
+    ),
+    (
+        "validation.too_large",
+        "Der Wert des Feldes '{field}' ist zu groß (höchstens {max})",
+    ),
+    (
+        "validation.boolean_expected",
+        "Das Feld '{field}' muss ein Wahrheitswert sein",
+    ),
+    (
+        "validation.invalid_email",
+        "Das Feld '{field}' muss eine gültige E-Mail-Adresse sein",
+    ),
+    (
+        "validation.decimal_expected",
+        "Das Feld '{field}' muss eine Dezimalzahl oder ein Dezimaltext sein",
+    ),
+    ("validation.invalid_decimal", "Das Feld '{field}': {reason}"),
+    (
+        "validation.latitude_out_of_range",
+        "Der Breitengrad im Feld '{field}' muss zwischen -90 und 90 liegen",
+    ),
+    (
+        "validation.longitude_out_of_range",
+        "Der Längengrad im Feld '{field}' muss zwischen -180 und 180 liegen",
+    ),
+    (
+        "validation.geo_point_expected",
+        "Das Feld '{field}' muss ein Objekt mit numerischen Werten lat und lng sein",
+    ),
+    (
+        "validation.invalid_date",
+        "Das Feld '{field}' muss ein gültiges Datum im Format JJJJ-MM-TT sein",
+    ),
+    (
+        "validation.date_expected",
+        "Das Feld '{field}' muss ein Datumstext sein",
+    ),
+    (
+        "validation.url_scheme",
+        "Das Feld '{field}' muss eine gültige URL sein, die mit http:// oder https:// beginnt",
+    ),
+    (
+        "validation.invalid_url",
+        "Das Feld '{field}' muss eine gültige URL sein",
+    ),
+    (
+        "validation.url_expected",
+        "Das Feld '{field}' muss ein URL-Text sein",
+    ),
+    (
+        "validation.invalid_slug",
+        "Das Feld '{field}' muss ein Slug aus Kleinbuchstaben, Ziffern und Bindestrichen sein",
+    ),
+    (
+        "validation.invalid_file_path",
+        "Das Feld '{field}' muss ein gültiger Dateipfad sein (höchstens 500 Zeichen)",
+    ),
+    (
+        "validation.file_path_expected",
+        "Das Feld '{field}' muss ein Dateipfad-Text sein",
+    ),
+    (
+        "validation.invalid_relation",
+        "Das Feld '{field}' muss eine gültige Relations-ID sein (höchstens 50 Zeichen)",
+    ),
+    (
+        "validation.relation_expected",
+        "Das Feld '{field}' muss eine Relations-ID sein (Text oder Zahl)",
+    ),
+    (
+        "multipart.unknown_part",
+        "Unerwarteter Formularteil '{part}'; senden Sie den Datensatz als 'data' und Dateien als 'file_<feld>'",
+    ),
+    (
+        "multipart.duplicate_part",
+        "Der Formularteil '{part}' wurde mehr als einmal gesendet",
+    ),
+    (
+        "multipart.invalid_encoding",
+        "Der Formularteil '{part}' ist kein gültiges UTF-8",
+    ),
+    (
+        "multipart.invalid_json",
+        "Der Formularteil '{part}' ist kein gültiges JSON: {reason}",
+    ),
+    (
+        "validation.unknown_file_field",
+        "Das Feld '{field}' existiert nicht im Sammlungsschema",
+    ),
+    (
+        "validation.not_a_file_field",
+        "Das Feld '{field}' ist kein Dateifeld",
+    ),
+    (
+        "validation.invalid_content_type",
+        "Das Feld '{field}' hat einen ungültigen Inhaltstyp '{content_type}'",
+    ),
+    (
+        "validation.invalid_base64",
+        "Das Feld '{field}' enthält keine gültigen Base64-Daten",
+    ),
+    (
+        "validation.uploads_disabled",
+        "'{field}' kann nicht hochgeladen werden: Datei-Uploads sind deaktiviert, weil der S3-Speicher nicht aktiviert ist",
+    ),
+    (
+        "validation.upload_failed",
+        "Die Datei für das Feld '{field}' konnte nicht hochgeladen werden",
+    ),
+    (
+        "validation.malware_detected",
+        "Die Datei für das Feld '{field}' wurde abgelehnt, weil der Virenscanner {signature} gefunden hat",
+    ),
+    (
+        "validation.scan_unavailable",
+        "Die Datei für das Feld '{field}' konnte nicht auf Schadsoftware geprüft werden, bitte später erneut versuchen",
+    ),
+    ("validation.batch_operation", "Vorgang {index}: {message}"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiates_the_most_preferred_supported_language() {
+        assert_eq!(Locale::negotiate(None, Locale::Pl), Locale::Pl);
+        assert_eq!(
+            Locale::negotiate(Some("de-AT,de;q=0.9,en;q=0.8"), Locale::En),
+            Locale::De
+        );
+        assert_eq!(
+            Locale::negotiate(Some("fr;q=1.0, en;q=0.5, pl;q=0.7"), Locale::En),
+            Locale::Pl
+        );
+        assert_eq!(Locale::negotiate(Some("pl;q=0"), Locale::De), Locale::De);
+        assert_eq!(Locale::negotiate(Some("*"), Locale::De), Locale::De);
+    }
+
+    #[test]
+    fn test_every_key_is_translated() {
+        for (key, _) in EN {
+            assert!(
+                translate(Locale::Pl, key).is_some(),
+                "pl is missing {}",
+                key
+            );
+            assert!(
+                translate(Locale::De, key).is_some(),
+                "de is missing {}",
+                key
+            );
+        }
+        assert_eq!(PL.len(), EN.len());
+        assert_eq!(DE.len(), EN.len());
+    }
+
+    #[test]
+    fn test_renders_params_and_nested_messages() {
+        let message = Message::new("validation.batch_operation")
+            .arg("index", 2)
+            .nested(
+                "message",
+                Message::new("validation.required").arg("field", "title"),
+            );
+
+        assert_eq!(
+            message.render(Locale::En),
+            "Operation 2: Field 'title' is required"
+        );
+        assert_eq!(
+            message.render(Locale::Pl),
+            "Operacja 2: Pole 'title' jest wymagane"
+        );
+    }
+}
@@ -5,6 +5,7 @@ use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, deco
 use serde::{Deserialize, Serialize};
 
 use super::LunarbaseError;
+use crate::models::{User, WorkspaceSession};
 use crate::schema::blacklisted_tokens;
 use crate::services::{ConfigurationAccess, ConfigurationManager};
 
@@ -16,6 +17,52 @@ pub struct Claims {
     pub exp: i64,
     pub iat: i64,
     pub jti: String,
+    /// Set on guest session tokens, which have no account behind them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guest: Option<GuestScope>,
+    /// Active workspace while `system.enable_workspaces` is on; `role` is
+    /// then the user's role in that workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<i32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub superadmin: bool,
+}
+
+impl Claims {
+    /// May manage workspaces and instance-wide settings. Without workspaces
+    /// that is every admin.
+    pub fn is_superadmin(&self) -> bool {
+        match self.workspace_id {
+            Some(_) => self.superadmin,
+            None => self.role == "admin",
+        }
+    }
+
+    pub fn workspace_session(&self) -> Option<WorkspaceSession> {
+        self.workspace_id.map(|workspace_id| WorkspaceSession {
+            workspace_id,
+            role: self.role.clone(),
+            superadmin: self.superadmin,
+        })
+    }
+
+    /// `user` with the role this token grants, which differs from the
+    /// account's role in workspaces where the user has a membership.
+    pub fn scope_user(&self, mut user: User) -> User {
+        if self.workspace_id.is_some() {
+            user.role = self.role.clone();
+        }
+        user
+    }
+}
+
+/// What a guest session token may do: create records in the guest
+/// collections, or only in `collection` when the session was started for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuestScope {
+    pub session_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -25,6 +72,8 @@ pub struct RefreshClaims {
     pub iat: i64,
     pub jti: String,
     pub token_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<i32>,
 }
 
 pub struct JwtService {
@@ -48,11 +97,14 @@ impl JwtService {
         }
     }
 
+    /// Within a workspace the token carries the user's role there instead of
+    /// their global `role`.
     pub async fn generate_access_token(
         &self,
         user_id: i32,
         email: &str,
         role: &str,
+        workspace: Option<&WorkspaceSession>,
     ) -> Result<String, LunarbaseError> {
         let now = Utc::now();
         let jwt_lifetime_hours = self.get_jwt_lifetime_hours().await;
@@ -61,17 +113,52 @@ impl JwtService {
         let claims = Claims {
             sub: user_id.to_string(),
             email: email.to_string(),
-            role: role.to_string(),
+            role: workspace
+                .map_or(role, |workspace| &workspace.role)
+                .to_string(),
             exp: exp.timestamp(),
             iat: now.timestamp(),
             jti: uuid::Uuid::new_v4().to_string(),
+            guest: None,
+            workspace_id: workspace.map(|workspace| workspace.workspace_id),
+            superadmin: workspace.is_some_and(|workspace| workspace.superadmin),
         };
 
         encode(&Header::default(), &claims, &self.encoding_key)
             .map_err(|_| LunarbaseError::InternalError)
     }
 
-    pub async fn generate_refresh_token(&self, user_id: i32) -> Result<String, LunarbaseError> {
+    /// Issues a token with the `guest` role for `scope`. Its subject is not a
+    /// user id, so every check that looks up the user rejects it.
+    pub fn generate_guest_token(
+        &self,
+        scope: GuestScope,
+        lifetime: Duration,
+    ) -> Result<String, LunarbaseError> {
+        let now = Utc::now();
+
+        let claims = Claims {
+            sub: "guest".to_string(),
+            email: String::new(),
+            role: "guest".to_string(),
+            exp: (now + lifetime).timestamp(),
+            iat: now.timestamp(),
+            jti: scope.session_id.clone(),
+            guest: Some(scope),
+            workspace_id: None,
+            superadmin: false,
+        };
+
+        encode(&Header::default(), &claims, &self.encoding_key)
+            .map_err(|_| LunarbaseError::InternalError)
+    }
+
+    /// Refreshing keeps the access token in `workspace_id`.
+    pub async fn generate_refresh_token(
+        &self,
+        user_id: i32,
+        workspace_id: Option<i32>,
+    ) -> Result<String, LunarbaseError> {
         let now = Utc::now();
         let jwt_lifetime_hours = self.get_jwt_lifetime_hours().await;
         let exp = now + Duration::hours((jwt_lifetime_hours * 7) as i64);
@@ -82,6 +169,7 @@ impl JwtService {
             iat: now.timestamp(),
             jti: uuid::Uuid::new_v4().to_string(),
             token_type: "refresh".to_string(),
+            workspace_id,
         };
 
         encode(&Header::default(), &claims, &self.encoding_key)
@@ -233,6 +321,8 @@ impl JwtService {
             return Err(LunarbaseError::TokenInvalid);
         }
 
+        self.ensure_token_not_revoked(&claims.sub, claims.iat)?;
+
         Ok(claims)
     }
 
@@ -241,7 +331,29 @@ impl JwtService {
         token: &str,
     ) -> Result<Claims, LunarbaseError> {
         let claims = self.validate_access_token_with_blacklist(token)?;
+        self.ensure_user_verified(&claims)?;
+        Ok(claims)
+    }
 
+    /// Like `validate_access_token_with_verification`, but also accepts guest
+    /// session tokens, which have no account to check. Callers must limit
+    /// what the returned claims may do when `guest` is set.
+    pub fn validate_access_or_guest_token(&self, token: &str) -> Result<Claims, LunarbaseError> {
+        let claims = self.validate_access_token(token)?;
+        if claims.guest.is_some() {
+            return Ok(claims);
+        }
+
+        if self.is_token_blacklisted(&claims.jti)? {
+            return Err(LunarbaseError::TokenInvalid);
+        }
+        self.ensure_token_not_revoked(&claims.sub, claims.iat)?;
+        self.ensure_user_verified(&claims)?;
+
+        Ok(claims)
+    }
+
+    fn ensure_user_verified(&self, claims: &Claims) -> Result<(), LunarbaseError> {
         let user_id: i32 = claims
             .sub
             .parse()
@@ -249,8 +361,7 @@ impl JwtService {
         if !self.is_user_verified(user_id)? {
             return Err(LunarbaseError::AccountNotVerified);
         }
-
-        Ok(claims)
+        Ok(())
     }
 
     pub fn is_user_verified(&self, user_id: i32) -> Result<bool, LunarbaseError> {
@@ -277,9 +388,46 @@ impl JwtService {
             return Err(LunarbaseError::TokenInvalid);
         }
 
+        self.ensure_token_not_revoked(&claims.sub, claims.iat)?;
+
         Ok(claims)
     }
 
+    /// Rejects tokens of deactivated users and every token issued at or before the
+    /// user's `token_valid_after` marker, so reactivating an account does not bring
+    /// back tokens that were outstanding when it was deactivated.
+    fn ensure_token_not_revoked(
+        &self,
+        subject: &str,
+        issued_at: i64,
+    ) -> Result<(), LunarbaseError> {
+        use crate::schema::users;
+
+        let user_id: i32 = subject.parse().map_err(|_| LunarbaseError::TokenInvalid)?;
+
+        let mut conn = self.pool.get().map_err(|_| LunarbaseError::InternalError)?;
+
+        let (is_active, token_valid_after): (bool, Option<NaiveDateTime>) = users::table
+            .filter(users::id.eq(user_id))
+            .select((users::is_active, users::token_valid_after))
+            .first(&mut conn)
+            .optional()
+            .map_err(|_| LunarbaseError::InternalError)?
+            .ok_or(LunarbaseError::TokenInvalid)?;
+
+        if !is_active {
+            return Err(LunarbaseError::AccountDeactivated);
+        }
+
+        if token_valid_after
+            .is_some_and(|valid_after| issued_at <= valid_after.and_utc().timestamp())
+        {
+            return Err(LunarbaseError::TokenInvalid);
+        }
+
+        Ok(())
+    }
+
     pub fn blacklist_refresh_token(
         &self,
         token: &str,
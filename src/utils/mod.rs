@@ -1,15 +1,24 @@
 use serde::Serialize;
+use std::collections::BTreeMap;
 use utoipa::ToSchema;
 
 pub mod auth_error;
 pub mod cookie_service;
+pub mod email;
+pub mod i18n;
 pub mod jwt_service;
 pub mod oauth_service;
+pub mod username;
 
 pub use auth_error::LunarbaseError;
-pub use cookie_service::CookieService;
-pub use jwt_service::{Claims, JwtService};
-pub use oauth_service::{OAuthConfig, OAuthService, OAuthUserInfo};
+pub use cookie_service::{
+    CSRF_COOKIE, CSRF_HEADER, CookieConfig, CookieService, is_same_origin, validate_cookie_settings,
+};
+pub use email::normalize_email;
+pub use i18n::{Locale, Message};
+pub use jwt_service::{Claims, GuestScope, JwtService};
+pub use oauth_service::{OAuthConfig, OAuthErrorCode, OAuthFailure, OAuthService, OAuthUserInfo};
+pub use username::normalize_username;
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ApiResponse<T> {
@@ -22,9 +31,25 @@ pub struct ApiResponse<T> {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ErrorResponse {
     pub success: bool,
+    /// Stable machine-readable code, e.g. `validation_failed` or `record_not_found`
+    #[schema(example = "record_not_found")]
+    pub code: String,
+    /// Human-readable summary; its wording may change
     pub error: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub details: Option<String>,
+    /// Field errors of a rejected record, keyed by field name, or by form part
+    /// name when a multipart request is malformed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<BTreeMap<String, Vec<FieldError>>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FieldError {
+    #[schema(example = "too_long")]
+    pub code: String,
+    #[schema(value_type = String)]
+    pub message: Message,
 }
 
 impl<T> ApiResponse<T> {
@@ -46,19 +71,20 @@ impl<T> ApiResponse<T> {
 }
 
 impl ErrorResponse {
-    pub fn new(error: String) -> Self {
+    pub fn new(code: &str, error: String) -> Self {
         Self {
             success: false,
+            code: code.to_string(),
             error,
             details: None,
+            fields: None,
         }
     }
 
-    pub fn with_details(error: String, details: String) -> Self {
+    pub fn with_details(code: &str, error: String, details: String) -> Self {
         Self {
-            success: false,
-            error,
             details: Some(details),
+            ..Self::new(code, error)
         }
     }
 }
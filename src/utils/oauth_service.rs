@@ -5,6 +5,7 @@ use oauth2::{
 use reqwest::Client as HttpClient;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use std::sync::{Arc, Mutex};
 
 use crate::services::ConfigurationManager;
@@ -60,6 +61,65 @@ pub struct GitHubEmail {
     pub visibility: Option<String>,
 }
 
+/// Why an OAuth sign-in failed, as far as the user is told. Whatever the
+/// provider said goes to the server log only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OAuthErrorCode {
+    AccessDenied,
+    ProviderUnavailable,
+    AccountLinkConflict,
+    EmailUnverifiedAtProvider,
+}
+
+impl OAuthErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OAuthErrorCode::AccessDenied => "access_denied",
+            OAuthErrorCode::ProviderUnavailable => "provider_unavailable",
+            OAuthErrorCode::AccountLinkConflict => "account_link_conflict",
+            OAuthErrorCode::EmailUnverifiedAtProvider => "email_unverified_at_provider",
+        }
+    }
+
+    /// Maps the `error` parameter of an OAuth error redirect (RFC 6749,
+    /// 4.1.2.1). Only a refusal is the user's doing; the rest is reported
+    /// as the provider failing.
+    pub fn from_provider_error(error: &str) -> Self {
+        match error {
+            "access_denied" | "consent_required" | "interaction_required" | "login_required" => {
+                OAuthErrorCode::AccessDenied
+            }
+            _ => OAuthErrorCode::ProviderUnavailable,
+        }
+    }
+}
+
+/// A failed OAuth step: the code shown to the user and the detail to log.
+#[derive(Debug)]
+pub struct OAuthFailure {
+    pub code: OAuthErrorCode,
+    pub detail: String,
+}
+
+impl OAuthFailure {
+    pub fn new(code: OAuthErrorCode, detail: impl Into<String>) -> Self {
+        Self {
+            code,
+            detail: detail.into(),
+        }
+    }
+
+    fn provider_unavailable(detail: impl fmt::Display) -> Self {
+        Self::new(OAuthErrorCode::ProviderUnavailable, detail.to_string())
+    }
+}
+
+impl fmt::Display for OAuthFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code.as_str(), self.detail)
+    }
+}
+
 #[derive(Clone)]
 pub struct OAuthService {
     config: OAuthConfig,
@@ -195,26 +255,96 @@ impl OAuthService {
         &self,
         provider: &str,
         access_token: &str,
-    ) -> Result<OAuthUserInfo, Box<dyn std::error::Error>> {
+    ) -> Result<OAuthUserInfo, OAuthFailure> {
         match provider {
             "google" => self.get_google_user_info(access_token).await,
             "github" => self.get_github_user_info(access_token).await,
-            _ => Err("Unsupported OAuth provider".into()),
+            _ => Err(OAuthFailure::provider_unavailable(
+                "Unsupported OAuth provider",
+            )),
         }
     }
 
-    async fn get_google_user_info(
+    /// Sanity-checks every configured provider: the endpoint and redirect URLs
+    /// must parse and the authorization endpoint must respond. Returns an empty
+    /// list when OAuth is disabled or no provider is configured.
+    pub async fn check_providers(&self) -> Vec<(&'static str, Result<(), String>)> {
+        let oauth_enabled = self
+            .config_manager
+            .get_bool("oauth", "oauth_enabled")
+            .await
+            .unwrap_or(false);
+
+        if !oauth_enabled {
+            return Vec::new();
+        }
+
+        let mut results = Vec::new();
+        for (provider, provider_config) in [
+            ("google", self.config.google.as_ref()),
+            ("github", self.config.github.as_ref()),
+        ] {
+            if let Some(provider_config) = provider_config {
+                results.push((
+                    provider,
+                    self.check_provider(provider, provider_config).await,
+                ));
+            }
+        }
+
+        results
+    }
+
+    async fn check_provider(
         &self,
-        access_token: &str,
-    ) -> Result<OAuthUserInfo, Box<dyn std::error::Error>> {
+        provider: &str,
+        provider_config: &OAuthProviderConfig,
+    ) -> Result<(), String> {
+        AuthUrl::new(provider_config.auth_url.clone())
+            .map_err(|e| format!("invalid authorization URL: {}", e))?;
+        TokenUrl::new(provider_config.token_url.clone())
+            .map_err(|e| format!("invalid token URL: {}", e))?;
+        RedirectUrl::new(format!(
+            "{}/api/auth/oauth/{}/callback",
+            self.config.redirect_base_url, provider
+        ))
+        .map_err(|e| format!("invalid redirect URL: {}", e))?;
+
         let response = self
             .http_client
-            .get("https://www.googleapis.com/oauth2/v2/userinfo")
-            .bearer_auth(access_token)
+            .get(&provider_config.auth_url)
+            .header("User-Agent", "lunarbase-oauth")
             .send()
+            .await
+            .map_err(|e| format!("authorization endpoint unreachable: {}", e))?;
+
+        if response.status().is_server_error() {
+            return Err(format!(
+                "authorization endpoint returned {}",
+                response.status()
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn get_google_user_info(
+        &self,
+        access_token: &str,
+    ) -> Result<OAuthUserInfo, OAuthFailure> {
+        let google_user: GoogleUserInfo = self
+            .fetch_json(
+                "https://www.googleapis.com/oauth2/v2/userinfo",
+                access_token,
+            )
             .await?;
 
-        let google_user: GoogleUserInfo = response.json().await?;
+        if google_user.verified_email == Some(false) {
+            return Err(OAuthFailure::new(
+                OAuthErrorCode::EmailUnverifiedAtProvider,
+                format!("Google account email {} is not verified", google_user.email),
+            ));
+        }
 
         Ok(OAuthUserInfo {
             id: google_user.id,
@@ -228,34 +358,40 @@ impl OAuthService {
     async fn get_github_user_info(
         &self,
         access_token: &str,
-    ) -> Result<OAuthUserInfo, Box<dyn std::error::Error>> {
-        let user_response = self
-            .http_client
-            .get("https://api.github.com/user")
-            .bearer_auth(access_token)
-            .header("User-Agent", "lunarbase-oauth")
-            .send()
+    ) -> Result<OAuthUserInfo, OAuthFailure> {
+        let github_user: GitHubUserInfo = self
+            .fetch_json("https://api.github.com/user", access_token)
             .await?;
 
-        let github_user: GitHubUserInfo = user_response.json().await?;
-
-        let email = if github_user.email.is_some() {
-            github_user.email.unwrap()
-        } else {
-            let emails_response = self
-                .http_client
-                .get("https://api.github.com/user/emails")
-                .bearer_auth(access_token)
-                .header("User-Agent", "lunarbase-oauth")
-                .send()
-                .await?;
-
-            let emails: Vec<GitHubEmail> = emails_response.json().await?;
-            emails
-                .into_iter()
-                .find(|e| e.primary && e.verified)
-                .map(|e| e.email)
-                .ok_or("No verified primary email found")?
+        // The profile only shows the public email, which is missing when the
+        // user keeps it private; the emails API lists every address with its
+        // verification state. GitHub only lets verified addresses be public,
+        // so the profile email will do if that API is unavailable.
+        let email = match self
+            .fetch_json::<Vec<GitHubEmail>>("https://api.github.com/user/emails", access_token)
+            .await
+        {
+            Ok(emails) => pick_github_email(&emails).ok_or_else(|| {
+                OAuthFailure::new(
+                    OAuthErrorCode::EmailUnverifiedAtProvider,
+                    format!(
+                        "GitHub user {} has no verified email among {} address(es)",
+                        github_user.login,
+                        emails.len()
+                    ),
+                )
+            })?,
+            Err(failure) => match github_user.email {
+                Some(email) => {
+                    tracing::warn!(
+                        "GitHub emails API unavailable for {}, using the public profile email: {}",
+                        github_user.login,
+                        failure
+                    );
+                    email
+                }
+                None => return Err(failure),
+            },
         };
 
         Ok(OAuthUserInfo {
@@ -266,12 +402,64 @@ impl OAuthService {
             provider: "github".to_string(),
         })
     }
+
+    async fn fetch_json<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        access_token: &str,
+    ) -> Result<T, OAuthFailure> {
+        let response = self
+            .http_client
+            .get(url)
+            .bearer_auth(access_token)
+            .header("User-Agent", "lunarbase-oauth")
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .map_err(|e| {
+                OAuthFailure::provider_unavailable(format!("GET {} failed: {}", url, e))
+            })?;
+
+        let status = response.status();
+        let body = response.text().await.map_err(|e| {
+            OAuthFailure::provider_unavailable(format!("Reading {} failed: {}", url, e))
+        })?;
+        if !status.is_success() {
+            let code = if status == reqwest::StatusCode::UNAUTHORIZED
+                || status == reqwest::StatusCode::FORBIDDEN
+            {
+                OAuthErrorCode::AccessDenied
+            } else {
+                OAuthErrorCode::ProviderUnavailable
+            };
+            return Err(OAuthFailure::new(
+                code,
+                format!("GET {} returned {}: {}", url, status, body),
+            ));
+        }
+
+        serde_json::from_str(&body).map_err(|e| {
+            OAuthFailure::provider_unavailable(format!(
+                "Unexpected response from {}: {}: {}",
+                url, e, body
+            ))
+        })
+    }
+}
+
+/// The primary address when it is verified, otherwise any verified one.
+fn pick_github_email(emails: &[GitHubEmail]) -> Option<String> {
+    emails
+        .iter()
+        .find(|e| e.primary && e.verified)
+        .or_else(|| emails.iter().find(|e| e.verified))
+        .map(|e| e.email.clone())
 }
 
 impl OAuthConfig {
     pub async fn from_database(
         config_manager: &ConfigurationManager,
-        frontend_url: &str,
+        public_api_url: &str,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         let google_client_id = config_manager
             .get_string("oauth", "google_client_id")
@@ -318,7 +506,8 @@ impl OAuthConfig {
             None
         };
 
-        let redirect_base_url = frontend_url.to_string();
+        // Providers call back into the API, not the frontend
+        let redirect_base_url = public_api_url.to_string();
 
         Ok(Self {
             google,
@@ -327,3 +516,59 @@ impl OAuthConfig {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn github_email(email: &str, primary: bool, verified: bool) -> GitHubEmail {
+        GitHubEmail {
+            email: email.to_string(),
+            primary,
+            verified,
+            visibility: None,
+        }
+    }
+
+    #[test]
+    fn test_provider_errors_map_to_user_facing_codes() {
+        assert_eq!(
+            OAuthErrorCode::from_provider_error("access_denied"),
+            OAuthErrorCode::AccessDenied
+        );
+        for error in [
+            "server_error",
+            "temporarily_unavailable",
+            "redirect_uri_mismatch",
+        ] {
+            assert_eq!(
+                OAuthErrorCode::from_provider_error(error),
+                OAuthErrorCode::ProviderUnavailable
+            );
+        }
+    }
+
+    #[test]
+    fn test_github_email_prefers_the_verified_primary() {
+        let emails = vec![
+            github_email("old@example.com", false, true),
+            github_email("me@example.com", true, true),
+        ];
+        assert_eq!(
+            pick_github_email(&emails).as_deref(),
+            Some("me@example.com")
+        );
+
+        let emails = vec![
+            github_email("new@example.com", true, false),
+            github_email("old@example.com", false, true),
+        ];
+        assert_eq!(
+            pick_github_email(&emails).as_deref(),
+            Some("old@example.com")
+        );
+
+        let emails = vec![github_email("new@example.com", true, false)];
+        assert_eq!(pick_github_email(&emails), None);
+    }
+}
@@ -0,0 +1,163 @@
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use rand::Rng;
+
+use crate::database::lower;
+use crate::schema::users;
+use crate::services::ConfigurationAccess;
+use crate::utils::LunarbaseError;
+
+pub const MIN_USERNAME_LENGTH: usize = 3;
+pub const MAX_USERNAME_LENGTH: usize = 30;
+
+pub const INVALID_USERNAME_MESSAGE: &str =
+    "Username must be 3-30 characters long and contain only letters, numbers, and underscores";
+
+/// `input` without surrounding whitespace, when what remains is 3-30 ASCII
+/// letters, digits or underscores. The case is kept for display; uniqueness
+/// and reserved names ignore it.
+pub fn normalize_username(input: &str) -> Option<String> {
+    let username = input.trim();
+    let valid = (MIN_USERNAME_LENGTH..=MAX_USERNAME_LENGTH).contains(&username.len())
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then(|| username.to_string())
+}
+
+pub fn is_reserved_username(username: &str, reserved: &[String]) -> bool {
+    reserved
+        .iter()
+        .any(|name| name.trim().eq_ignore_ascii_case(username))
+}
+
+/// Whether an account other than `except_user_id` has `username` in any
+/// letter case.
+pub fn username_taken(
+    conn: &mut SqliteConnection,
+    username: &str,
+    except_user_id: Option<i32>,
+) -> Result<bool, LunarbaseError> {
+    let mut query = users::table
+        .filter(lower(users::username).eq(username.to_ascii_lowercase()))
+        .select(users::id)
+        .into_boxed();
+    if let Some(user_id) = except_user_id {
+        query = query.filter(users::id.ne(user_id));
+    }
+
+    query
+        .first::<i32>(conn)
+        .optional()
+        .map(|user_id| user_id.is_some())
+        .map_err(|_| LunarbaseError::DatabaseError)
+}
+
+/// Rejects a normalized `username` that is on `auth.reserved_usernames` or
+/// already belongs to another account.
+pub async fn ensure_username_available(
+    config: &impl ConfigurationAccess,
+    conn: &mut SqliteConnection,
+    username: &str,
+    except_user_id: Option<i32>,
+) -> Result<(), LunarbaseError> {
+    if is_reserved_username(username, &config.get_reserved_usernames().await) {
+        return Err(LunarbaseError::ValidationError(vec![format!(
+            "Username {} is reserved",
+            username
+        )]));
+    }
+    if username_taken(conn, username, except_user_id)? {
+        return Err(LunarbaseError::ValidationError(vec![
+            "Username already taken".to_string(),
+        ]));
+    }
+    Ok(())
+}
+
+/// First username to try for an account created from an OAuth profile: the
+/// display name with unsupported characters turned into underscores, or
+/// `fallback` when too little of it is left.
+pub fn username_from_display_name(name: Option<&str>, fallback: &str) -> String {
+    let candidate = name
+        .map(|name| {
+            name.trim()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect::<String>()
+        })
+        .and_then(|name| fit_username(&name));
+
+    candidate
+        .or_else(|| fit_username(fallback))
+        .unwrap_or_else(|| with_random_suffix("user"))
+}
+
+/// `base` with a random four-digit suffix, shortened to stay within the
+/// length limit. Used to retry a username that is taken.
+pub fn with_random_suffix(base: &str) -> String {
+    let suffix = rand::thread_rng().gen_range(1000..10000);
+    let stem: String = base.chars().take(MAX_USERNAME_LENGTH - 5).collect();
+    format!("{}_{}", stem.trim_end_matches('_'), suffix)
+}
+
+fn fit_username(raw: &str) -> Option<String> {
+    let trimmed: String = raw
+        .trim_matches('_')
+        .chars()
+        .take(MAX_USERNAME_LENGTH)
+        .collect();
+    normalize_username(trimmed.trim_end_matches('_'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_trims_and_restricts_characters() {
+        assert_eq!(
+            normalize_username("  John_Doe "),
+            Some("John_Doe".to_string())
+        );
+        for username in [
+            "ab",
+            "john doe",
+            "jöhn",
+            "john-doe",
+            "john.doe",
+            "a".repeat(31).as_str(),
+        ] {
+            assert_eq!(normalize_username(username), None, "{}", username);
+        }
+
+        let reserved = vec!["admin".to_string(), " Support ".to_string()];
+        assert!(is_reserved_username("ADMIN", &reserved));
+        assert!(is_reserved_username("support", &reserved));
+        assert!(!is_reserved_username("admin2", &reserved));
+    }
+
+    #[test]
+    fn test_oauth_usernames_are_derived_from_the_display_name() {
+        assert_eq!(
+            username_from_display_name(Some("Jane Q. Public"), "github_1234"),
+            "Jane_Q__Public"
+        );
+        assert_eq!(
+            username_from_display_name(Some("李"), "github_1234"),
+            "github_1234"
+        );
+        assert_eq!(
+            username_from_display_name(None, "github_1234"),
+            "github_1234"
+        );
+        assert_eq!(
+            username_from_display_name(Some(&"x".repeat(40)), "github_1234").len(),
+            MAX_USERNAME_LENGTH
+        );
+
+        let retried = with_random_suffix(&"y".repeat(30));
+        assert_eq!(retried.len(), MAX_USERNAME_LENGTH);
+        assert!(normalize_username(&retried).is_some());
+    }
+}
@@ -0,0 +1,90 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use serde_json::Value;
+use tower::ServiceExt;
+
+use lunarbase::AppState;
+use lunarbase::database::create_pool;
+use lunarbase::embedded_assets::AdminAssets;
+use lunarbase::server::build_routes;
+
+mod common;
+
+async fn create_app_state() -> AppState {
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+
+    AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState")
+}
+
+async fn get_status(app: &Router, uri: &str) -> StatusCode {
+    app.clone()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+const FRONTEND_URIS: [&str; 7] = [
+    "/admin",
+    "/admin/",
+    "/admin/index.html",
+    "/admin/collections",
+    "/docs/",
+    "/docs/openapi.json",
+    "/api/avatar-proxy?url=https%3A%2F%2Fexample.com%2Favatar.png",
+];
+
+// A single test keeps the process-wide embedded asset counter free of
+// concurrent reads from other tests.
+#[tokio::test]
+async fn test_api_only_mode_unmounts_frontend_and_never_reads_admin_assets() {
+    let app_state = create_app_state().await;
+    let api_only = build_routes(app_state.clone(), true);
+    let bytes_before = AdminAssets::bytes_read();
+
+    for uri in FRONTEND_URIS {
+        assert_eq!(
+            get_status(&api_only, uri).await,
+            StatusCode::NOT_FOUND,
+            "{}",
+            uri
+        );
+    }
+    assert_eq!(get_status(&api_only, "/api/health").await, StatusCode::OK);
+
+    let response = api_only
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/api-docs/collections.json")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let document: Value = serde_json::from_slice(&body).unwrap();
+    assert!(document["paths"].get("/avatar-proxy").is_none());
+    assert!(document["paths"].get("/auth/login").is_some());
+
+    assert_eq!(AdminAssets::bytes_read(), bytes_before);
+
+    let full = build_routes(app_state, false);
+    for uri in FRONTEND_URIS {
+        assert_ne!(
+            get_status(&full, uri).await,
+            StatusCode::NOT_FOUND,
+            "{}",
+            uri
+        );
+    }
+    assert!(AdminAssets::bytes_read() > bytes_before);
+}
@@ -0,0 +1,572 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    middleware,
+    response::Response,
+    routing::{get, post, put},
+};
+use diesel::prelude::*;
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use lunarbase::AppState;
+use lunarbase::database::create_pool;
+use lunarbase::handlers::auth::*;
+use lunarbase::handlers::configuration::update_setting;
+use lunarbase::handlers::health::liveness_check;
+use lunarbase::middleware::auth_middleware;
+use lunarbase::models::{NewUser, TokenType, User};
+use lunarbase::schema::users;
+use lunarbase::server::build_routes;
+
+mod common;
+
+const TEST_PASSWORD: &str = "TestPassword123!";
+
+struct TestUser {
+    id: i32,
+    email: String,
+    token: String,
+}
+
+async fn create_test_app_state() -> AppState {
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState")
+}
+
+fn create_test_router(app_state: AppState) -> Router {
+    let public_routes = Router::new()
+        .route("/auth/register", post(register))
+        .route("/auth/login", post(login))
+        .route("/auth/forgot-password", post(forgot_password))
+        .route("/auth/reset-password", post(reset_password))
+        .route("/health/live", get(liveness_check));
+
+    let protected_routes = Router::new()
+        .route(
+            "/admin/configuration/{category}/{setting_key}",
+            put(update_setting),
+        )
+        .layer(middleware::from_fn_with_state(
+            app_state.auth_state.clone(),
+            auth_middleware,
+        ));
+
+    let api_routes = Router::new().merge(public_routes).merge(protected_routes);
+    Router::new().nest("/api", api_routes).with_state(app_state)
+}
+
+/// Inserts a verified user and returns its id.
+fn insert_user(
+    app_state: &AppState,
+    email: String,
+    username: String,
+    role: &str,
+    password: &str,
+) -> i32 {
+    let new_user = NewUser::new_verified(
+        email,
+        password,
+        username,
+        role.to_string(),
+        true,
+        "test_pepper",
+    )
+    .expect("Failed to create new user");
+
+    let mut conn = app_state
+        .db_pool
+        .get()
+        .expect("Failed to get database connection");
+    diesel::insert_into(users::table)
+        .values(&new_user)
+        .execute(&mut conn)
+        .expect("Failed to insert user");
+    users::table
+        .filter(users::email.eq(&new_user.email))
+        .select(User::as_select())
+        .first::<User>(&mut conn)
+        .expect("Failed to fetch inserted user")
+        .id
+}
+
+/// A verified user with `role` and `TEST_PASSWORD`, and an access token.
+async fn create_test_user(app_state: &AppState, role: &str) -> TestUser {
+    let username = format!("auth_{}", &uuid::Uuid::new_v4().simple().to_string()[0..8]);
+    let email = format!("{}@test.com", username);
+    let id = insert_user(app_state, email.clone(), username, role, TEST_PASSWORD);
+    let token = app_state
+        .auth_state
+        .jwt_service
+        .generate_access_token(id, &email, role, None)
+        .await
+        .expect("Failed to generate token");
+
+    TestUser { id, email, token }
+}
+
+async fn post_json(app: &Router, uri: &str, body: Value) -> Response {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .uri(uri)
+                .method("POST")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+async fn login_with(app: &Router, email: &str, password: &str) -> Response {
+    post_json(
+        app,
+        "/api/auth/login",
+        json!({ "email": email, "password": password }),
+    )
+    .await
+}
+
+async fn read_json(response: Response) -> Value {
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[derive(Clone, Default)]
+struct CapturedLogs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_email_tokens_are_hashed_at_rest_and_single_use() {
+    use lunarbase::models::VerificationToken;
+    use lunarbase::schema::verification_tokens;
+
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::TRACE)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let app_state = create_test_app_state().await;
+    let user = create_test_user(&app_state, "user").await;
+
+    let token = app_state
+        .email_service
+        .generate_password_reset_token(user.id, user.email.clone())
+        .await
+        .unwrap();
+
+    let stored: Vec<VerificationToken> = verification_tokens::table
+        .filter(verification_tokens::user_id.eq(user.id))
+        .select(VerificationToken::as_select())
+        .load(&mut app_state.db_pool.get().unwrap())
+        .unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].token_hash.len(), 64);
+    assert!(!format!("{:?}", stored).contains(&token));
+
+    let verify = || {
+        app_state
+            .email_service
+            .verify_token_with_type(&token, TokenType::PasswordReset)
+    };
+    assert_eq!(verify().await.unwrap(), user.id);
+    assert!(verify().await.is_err());
+
+    let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    assert!(!logs.is_empty());
+    assert!(!logs.contains(&token));
+}
+
+#[tokio::test]
+async fn test_password_reset_flow_replaces_the_old_password() {
+    let app_state = create_test_app_state().await;
+    let app = create_test_router(app_state.clone());
+    let user = create_test_user(&app_state, "user").await;
+
+    let response = post_json(
+        &app,
+        "/api/auth/forgot-password",
+        json!({ "email": user.email }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The token the reset email links to
+    let token = app_state
+        .email_service
+        .generate_password_reset_token(user.id, user.email.clone())
+        .await
+        .unwrap();
+
+    let reset = |token: String| {
+        post_json(
+            &app,
+            "/api/auth/reset-password",
+            json!({ "token": token, "new_password": "NewPassword456!" }),
+        )
+    };
+    let response = reset(token.clone()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = reset(token).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = login_with(&app, &user.email, TEST_PASSWORD).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = login_with(&app, &user.email, "NewPassword456!").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    use utoipa::OpenApi;
+    let openapi = lunarbase::ApiDoc::openapi();
+    assert!(openapi.paths.paths.contains_key("/auth/forgot-password"));
+    assert!(openapi.paths.paths.contains_key("/auth/reset-password"));
+    let schemas = openapi.components.unwrap().schemas;
+    assert!(schemas.contains_key("ForgotPasswordRequest"));
+    assert!(schemas.contains_key("ResetPasswordRequest"));
+}
+
+#[tokio::test]
+async fn test_cookie_authenticated_writes_require_a_csrf_token() {
+    let app_state = create_test_app_state().await;
+    let user = create_test_user(&app_state, "user").await;
+    let app = build_routes(app_state, true);
+
+    let response = login_with(&app, &user.email, TEST_PASSWORD).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let cookies: Vec<String> = response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|value| value.to_str().unwrap().to_string())
+        .collect();
+    let csrf_cookie = cookies
+        .iter()
+        .find(|cookie| cookie.starts_with("csrf_token="))
+        .expect("csrf_token cookie");
+    assert!(!csrf_cookie.contains("HttpOnly"));
+    let cookie_header = cookies
+        .iter()
+        .map(|cookie| cookie.split(';').next().unwrap())
+        .collect::<Vec<_>>()
+        .join("; ");
+    let csrf_token = read_json(response).await["data"]["csrf_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(csrf_cookie.starts_with(&format!("csrf_token={};", csrf_token)));
+
+    let send = |method: &'static str, uri: &'static str, csrf: Option<&str>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("cookie", cookie_header.clone());
+        if let Some(csrf) = csrf {
+            request = request.header("x-csrf-token", csrf);
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap())
+    };
+
+    for csrf in [None, Some("forged")] {
+        let response = send("POST", "/api/auth/refresh", csrf).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(read_json(response).await["code"], "csrf_token_invalid");
+
+        let response = send("POST", "/api/auth/logout", csrf).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    // A junk Authorization header does not exempt a request carrying the cookies
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/auth/refresh")
+                .method("POST")
+                .header("cookie", cookie_header.clone())
+                .header("authorization", "Bearer junk")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Reads and Bearer clients are not affected
+    let response = send("GET", "/api/auth/me", None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/auth/logout")
+                .method("POST")
+                .header("authorization", format!("Bearer {}", user.token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send("POST", "/api/auth/refresh", Some(&csrf_token))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Sessions from before the CSRF cookie can still refresh, which issues it,
+    // but nothing else without it
+    let legacy_cookies: Vec<String> = response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|value| {
+            value
+                .to_str()
+                .unwrap()
+                .split(';')
+                .next()
+                .unwrap()
+                .to_string()
+        })
+        .filter(|cookie| !cookie.starts_with("csrf_token="))
+        .collect();
+    let legacy_send = |uri: &'static str| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .method("POST")
+                .header("cookie", legacy_cookies.join("; "))
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+    let response = legacy_send("/api/auth/workspace").await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = legacy_send("/api/auth/refresh").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(
+        response
+            .headers()
+            .get_all("set-cookie")
+            .iter()
+            .any(|value| value.to_str().unwrap().starts_with("csrf_token="))
+    );
+}
+
+// The default current-thread runtime: a hash computed on it would hold up
+// every other request until it finished.
+#[tokio::test]
+async fn test_concurrent_logins_do_not_stall_other_requests() {
+    let app_state = create_test_app_state().await;
+    let app = create_test_router(app_state.clone());
+
+    let mut emails = Vec::new();
+    for _ in 0..8 {
+        emails.push(create_test_user(&app_state, "user").await.email);
+    }
+
+    let logins: Vec<_> = emails
+        .into_iter()
+        .map(|email| {
+            let app = app.clone();
+            tokio::spawn(async move { login_with(&app, &email, TEST_PASSWORD).await.status() })
+        })
+        .collect();
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+    let mut slowest = std::time::Duration::ZERO;
+    for _ in 0..10 {
+        let started = std::time::Instant::now();
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/health/live")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        slowest = slowest.max(started.elapsed());
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert!(
+        slowest < std::time::Duration::from_millis(500),
+        "liveness took {:?} while logins were hashing",
+        slowest
+    );
+
+    for login in logins {
+        assert_eq!(login.await.unwrap(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn test_registration_and_login_use_the_normalized_email() {
+    let app = create_test_router(create_test_app_state().await);
+
+    let username = format!("norm_{}", &uuid::Uuid::new_v4().simple().to_string()[0..8]);
+    let response = post_json(
+        &app,
+        "/api/auth/register",
+        json!({
+            "email": format!(" {}@Example.COM ", username),
+            "password": TEST_PASSWORD,
+            "username": username
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(
+        read_json(response).await["data"]["user"]["email"],
+        format!("{}@example.com", username)
+    );
+
+    // Found under any spelling of the domain; an unverified account is
+    // refused for that reason, not as unknown
+    let response = login_with(&app, &format!("{}@EXAMPLE.com ", username), TEST_PASSWORD).await;
+    assert_ne!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = post_json(
+        &app,
+        "/api/auth/register",
+        json!({
+            "email": format!("{}@localhost", username),
+            "password": TEST_PASSWORD,
+            "username": format!("{}_2", username)
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(read_json(response).await["code"], "validation_failed");
+}
+
+#[tokio::test]
+async fn test_usernames_are_unique_ignoring_case_and_reserved_names_are_refused() {
+    let app = create_test_router(create_test_app_state().await);
+    let register = |email: String, username: String| {
+        post_json(
+            &app,
+            "/api/auth/register",
+            json!({
+                "email": email,
+                "password": TEST_PASSWORD,
+                "username": username
+            }),
+        )
+    };
+
+    let suffix = &uuid::Uuid::new_v4().simple().to_string()[0..8];
+    let username = format!("Case_{}", suffix);
+    let response = register(
+        format!("case_{}@test.com", suffix),
+        format!("  {}  ", username),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(
+        read_json(response).await["data"]["user"]["username"],
+        username
+    );
+
+    for taken in [
+        username.to_lowercase(),
+        username.to_uppercase(),
+        "Admin".to_string(),
+    ] {
+        let response = register(format!("other_{}@test.com", suffix), taken.clone()).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", taken);
+    }
+}
+
+#[tokio::test]
+async fn test_login_identifier_looks_up_email_then_username() {
+    let app_state = create_test_app_state().await;
+    let app = create_test_router(app_state.clone());
+    let admin = create_test_user(&app_state, "admin").await;
+
+    let put_setting = |value: &'static str| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri("/api/admin/configuration/auth/login_identifier")
+                .method("PUT")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", admin.token))
+                .body(Body::from(json!({ "setting_value": value }).to_string()))
+                .unwrap(),
+        )
+    };
+    let login = |body: Value| post_json(&app, "/api/auth/login", body);
+
+    let suffix = &uuid::Uuid::new_v4().simple().to_string()[0..8];
+    let username = format!("Ident_{}", suffix);
+    let email = format!("ident_{}@test.com", suffix);
+    let user_id = insert_user(
+        &app_state,
+        email.clone(),
+        username.clone(),
+        "user",
+        TEST_PASSWORD,
+    );
+    // Created before usernames were restricted: its name is the other
+    // account's email address
+    insert_user(
+        &app_state,
+        format!("legacy_{}@test.com", suffix),
+        email.clone(),
+        "user",
+        "OtherPassword123!",
+    );
+
+    let response = put_setting("phone").await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = put_setting("either").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = login(json!({ "identifier": email, "password": TEST_PASSWORD })).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["data"]["user"]["id"], user_id);
+
+    // The email match wins, so the look-alike username cannot be reached
+    let response = login(json!({ "identifier": email, "password": "OtherPassword123!" })).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = login(json!({
+        "identifier": format!(" {} ", username.to_uppercase()),
+        "password": TEST_PASSWORD
+    }))
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["data"]["user"]["id"], user_id);
+
+    // Older clients still send the field as `email`
+    let response = login_with(&app, &username, TEST_PASSWORD).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = put_setting("email").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = login(json!({ "identifier": username, "password": TEST_PASSWORD })).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = login_with(&app, &email, TEST_PASSWORD).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
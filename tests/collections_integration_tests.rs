@@ -10,41 +10,178 @@ use tower::ServiceExt;
 use uuid;
 
 use axum::middleware;
+use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 use lunarbase::AppState;
 use lunarbase::database::create_pool;
+use lunarbase::handlers::admin::get_record_analytics;
 use lunarbase::handlers::auth::*;
+use lunarbase::handlers::batch::execute_batch;
+use lunarbase::handlers::collection_templates::{
+    create_collection_from_template, delete_collection_template, list_collection_templates,
+    save_collection_as_template,
+};
+use lunarbase::handlers::collection_views::{create_collection_view, list_collection_views};
 use lunarbase::handlers::collections::*;
-use lunarbase::middleware::auth_middleware;
+use lunarbase::handlers::ingest::{create_ingest_endpoint, ingest_payload, list_ingest_failures};
+use lunarbase::handlers::record_shares::{
+    create_record_share, get_shared_record, list_record_shares, revoke_record_share,
+};
+use lunarbase::handlers::users::get_my_usage;
+use lunarbase::middleware::{
+    api_version_middleware, auth_middleware, locale_middleware, optional_auth_middleware,
+    read_only_middleware,
+};
 use lunarbase::models::{CollectionSchema, FieldDefinition, FieldType, ValidationRules};
 
 mod common;
 
-async fn create_test_router() -> Router {
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations/");
+
+async fn create_test_app_state() -> AppState {
     let test_jwt_secret = "test_secret".to_string();
     let test_password_pepper = "test_pepper".to_string();
 
     let config = common::create_test_config().expect("Failed to load config");
     let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    db_pool
+        .get()
+        .expect("Failed to get database connection")
+        .run_pending_migrations(MIGRATIONS)
+        .expect("Failed to run migrations");
 
-    let app_state = AppState::new(db_pool, &test_jwt_secret, test_password_pepper, &config)
+    AppState::new(db_pool, &test_jwt_secret, test_password_pepper, &config)
         .await
-        .expect("Failed to create AppState");
+        .expect("Failed to create AppState")
+}
+
+async fn create_test_router() -> Router {
+    create_test_router_for(create_test_app_state().await)
+}
 
+fn create_test_router_for(app_state: AppState) -> Router {
     let public_routes = Router::new()
         .route("/collections", get(list_collections))
         .route("/collections/{name}", get(get_collection))
         .route("/collections/{name}/schema", get(get_collection_schema))
-        .route("/collections/{name}/records", get(list_records))
-        .route("/collections/{name}/records/{record_id}", get(get_record))
+        .route(
+            "/collections/{name}/schema/versions",
+            get(list_collection_schema_versions),
+        )
+        .route(
+            "/collections/{name}/schema/versions/{version}",
+            get(get_collection_schema_version),
+        )
+        .route(
+            "/collections/{name}/records",
+            get(list_records).layer(middleware::from_fn_with_state(
+                app_state.auth_state.clone(),
+                optional_auth_middleware,
+            )),
+        )
+        .route(
+            "/collections/{name}/records/count",
+            get(count_records).layer(middleware::from_fn_with_state(
+                app_state.auth_state.clone(),
+                optional_auth_middleware,
+            )),
+        )
+        .route(
+            "/collections/{name}/records/{record_id}",
+            get(get_record).layer(middleware::from_fn_with_state(
+                app_state.auth_state.clone(),
+                optional_auth_middleware,
+            )),
+        )
+        .route(
+            "/collections/{name}/records/by/{field}/{value}",
+            get(get_record_by_field).layer(middleware::from_fn_with_state(
+                app_state.auth_state.clone(),
+                optional_auth_middleware,
+            )),
+        )
         .route("/auth/register", post(register))
-        .route("/auth/login", post(login));
+        .route("/auth/login", post(login))
+        .route("/auth/guest-session", post(guest_session))
+        .route("/ingest/{token}", post(ingest_payload))
+        .route("/share/{token}", get(get_shared_record));
 
     let protected_routes = Router::new()
         .route("/collections", post(create_collection))
         .route("/collections/{name}", put(update_collection))
         .route("/collections/{name}", delete(delete_collection))
         .route("/collections/stats", get(get_collections_stats))
+        .route("/collections/templates", get(list_collection_templates))
+        .route(
+            "/collections/templates/{template_id}",
+            delete(delete_collection_template),
+        )
+        .route(
+            "/collections/from-template/{template_id}",
+            post(create_collection_from_template),
+        )
+        .route(
+            "/collections/{name}/save-as-template",
+            post(save_collection_as_template),
+        )
+        .route("/admin/analytics/records", get(get_record_analytics))
+        .route("/admin/collections/{name}/verify", post(verify_collection))
+        .route("/collections/{name}/health", get(get_collection_health))
+        .route("/admin/collections/{name}/repair", post(repair_collection))
+        .route("/admin/collections/orphans/sweep", post(sweep_orphans))
+        .route(
+            "/admin/collections/{name}/schedule",
+            get(get_record_schedule),
+        )
+        .route(
+            "/admin/collections/{name}/retention",
+            get(get_retention_report),
+        )
+        .route(
+            "/collections/{name}/schema/versions/{version}/restore",
+            post(restore_collection_schema_version),
+        )
         .route("/collections/{name}/records", post(create_record))
+        .route(
+            "/collections/{name}/records/validate",
+            post(validate_record),
+        )
+        .route("/collections/{name}/records/export", get(export_records))
+        .route("/collections/{name}/records/verify", post(verify_records))
+        .route(
+            "/collections/{name}/records/{record_id}/move",
+            post(move_record),
+        )
+        .route(
+            "/collections/{name}/records/{record_id}/publish",
+            post(publish_record),
+        )
+        .route(
+            "/collections/{name}/records/{record_id}/unpublish",
+            post(unpublish_record),
+        )
+        .route(
+            "/collections/{name}/records/{record_id}/share",
+            post(create_record_share),
+        )
+        .route(
+            "/collections/{name}/records/{record_id}/references",
+            get(get_record_references),
+        )
+        .route("/shares", get(list_record_shares))
+        .route("/shares/{id}", delete(revoke_record_share))
+        .route("/batch", post(execute_batch))
+        .route("/search", get(global_search))
+        .route("/records", get(list_all_records))
+        .route("/users/me/usage", get(get_my_usage))
+        .route(
+            "/collections/{name}/views",
+            post(create_collection_view).get(list_collection_views),
+        )
+        .route("/admin/ingest-endpoints", post(create_ingest_endpoint))
+        .route(
+            "/admin/ingest-endpoints/{id}/failures",
+            get(list_ingest_failures),
+        )
         .route(
             "/collections/{name}/records/{record_id}",
             put(update_record),
@@ -53,12 +190,26 @@ async fn create_test_router() -> Router {
             "/collections/{name}/records/{record_id}",
             delete(delete_record),
         )
+        .layer(middleware::from_fn_with_state(
+            app_state.auth_state.clone(),
+            read_only_middleware,
+        ))
         .layer(middleware::from_fn_with_state(
             app_state.auth_state.clone(),
             auth_middleware,
         ));
 
-    let api_routes = Router::new().merge(public_routes).merge(protected_routes);
+    let api_routes = Router::new()
+        .merge(public_routes)
+        .merge(protected_routes)
+        .layer(middleware::from_fn_with_state(
+            app_state.auth_state.clone(),
+            api_version_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.auth_state.clone(),
+            locale_middleware,
+        ));
 
     let router = Router::new().nest("/api", api_routes).with_state(app_state);
 
@@ -80,7 +231,15 @@ fn create_test_schema() -> CollectionSchema {
                     max_value: None,
                     pattern: None,
                     enum_values: None,
+                    precision: None,
+                    scale: None,
+                    source_field: None,
+                    number_format: None,
                 }),
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
             },
             FieldDefinition {
                 name: "content".to_string(),
@@ -94,7 +253,15 @@ fn create_test_schema() -> CollectionSchema {
                     max_value: None,
                     pattern: None,
                     enum_values: None,
+                    precision: None,
+                    scale: None,
+                    source_field: None,
+                    number_format: None,
                 }),
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
             },
             FieldDefinition {
                 name: "published".to_string(),
@@ -102,6 +269,10 @@ fn create_test_schema() -> CollectionSchema {
                 required: false,
                 default_value: Some(json!(false)),
                 validation: None,
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
             },
             FieldDefinition {
                 name: "views".to_string(),
@@ -115,7 +286,15 @@ fn create_test_schema() -> CollectionSchema {
                     max_value: Some(1000000.0),
                     pattern: None,
                     enum_values: None,
+                    precision: None,
+                    scale: None,
+                    source_field: None,
+                    number_format: None,
                 }),
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
             },
             FieldDefinition {
                 name: "email".to_string(),
@@ -123,6 +302,10 @@ fn create_test_schema() -> CollectionSchema {
                 required: false,
                 default_value: None,
                 validation: None,
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
             },
         ],
     }
@@ -200,6 +383,9 @@ fn create_token_for_user(user_id: i32, email: &str, role: &str) -> String {
         exp,
         iat: now,
         jti: uuid::Uuid::new_v4().to_string(),
+        guest: None,
+        workspace_id: None,
+        superadmin: false,
     };
 
     let jwt_secret = "test_secret".to_string();
@@ -535,17 +721,16 @@ async fn test_list_records_public() {
 }
 
 #[tokio::test]
-async fn test_get_collection_schema() {
-    let app1 = create_test_router().await;
-    let app2 = create_test_router().await;
-    let (_admin_id, token) = create_admin_token(&app1).await;
+async fn test_count_records_matches_list_count() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
 
     let schema = create_test_schema();
-    let unique_name = unique_collection_name("schema_test");
+    let unique_name = unique_collection_name("count_records");
     let collection_payload = json!({
         "name": unique_name,
-        "display_name": "Schema Test Unique Collection",
-        "description": "Test getting schema",
+        "display_name": "Count Records Unique Test",
+        "description": "Test counting records",
         "schema": schema
     });
 
@@ -557,60 +742,261 @@ async fn test_get_collection_schema() {
         .body(Body::from(collection_payload.to_string()))
         .unwrap();
 
-    let create_collection_response = app1.oneshot(create_collection_request).await.unwrap();
+    let create_collection_response = app
+        .clone()
+        .oneshot(create_collection_request)
+        .await
+        .unwrap();
     assert_eq!(create_collection_response.status(), StatusCode::CREATED);
 
-    let schema_request = Request::builder()
-        .uri(&format!("/api/collections/{}/schema", unique_name))
+    for (title, published) in [("First", true), ("Second", false), ("Third", true)] {
+        let record_data = json!({
+            "title": title,
+            "published": published
+        });
+
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary,
+            serde_json::to_string(&record_data).unwrap(),
+            boundary
+        );
+
+        let create_record_request = Request::builder()
+            .uri(&format!("/api/collections/{}/records", unique_name))
+            .method("POST")
+            .header(
+                "content-type",
+                format!("multipart/form-data; boundary={}", boundary),
+            )
+            .header("authorization", format!("Bearer {}", token))
+            .body(Body::from(body))
+            .unwrap();
+
+        let create_record_response = app.clone().oneshot(create_record_request).await.unwrap();
+        assert_eq!(create_record_response.status(), StatusCode::CREATED);
+    }
+
+    let count_request = Request::builder()
+        .uri(&format!(
+            "/api/collections/{}/records/count?filter=published:eq:true",
+            unique_name
+        ))
         .method("GET")
+        .header("authorization", format!("Bearer {}", token))
         .body(Body::empty())
         .unwrap();
 
-    let schema_response = app2.oneshot(schema_request).await.unwrap();
-    assert_eq!(schema_response.status(), StatusCode::OK);
+    let count_response = app.clone().oneshot(count_request).await.unwrap();
+    assert_eq!(count_response.status(), StatusCode::OK);
 
-    let body = schema_response
+    let body = count_response
         .into_body()
         .collect()
         .await
         .unwrap()
         .to_bytes();
-    let body_str = String::from_utf8(body.to_vec()).unwrap();
-    let json_response: Value = serde_json::from_str(&body_str).unwrap();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json_response["data"]["count"], 2);
 
-    assert!(json_response["data"]["fields"].is_array());
-    assert_eq!(json_response["data"]["fields"].as_array().unwrap().len(), 5);
+    let list_request = Request::builder()
+        .uri(&format!(
+            "/api/collections/{}/records?filter=published:eq:true&count=true&limit=1",
+            unique_name
+        ))
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let list_response = app.oneshot(list_request).await.unwrap();
+    assert_eq!(list_response.status(), StatusCode::OK);
+    assert_eq!(list_response.headers()["x-total-count"], "2");
 }
 
 #[tokio::test]
-async fn test_delete_record_with_files() {
-    let app = create_test_router().await;
+async fn test_count_and_list_count_agree_for_every_caller() {
+    use diesel::RunQueryDsl;
+    use lunarbase::models::SetCollectionPermissionRequest;
+
+    let app_state = create_test_app_state().await;
+    let app = create_test_router_for(app_state.clone());
     let (_admin_id, admin_token) = create_admin_token(&app).await;
-    let unique_name = unique_collection_name("delete_files_test");
+    let (user_id, user_token) = create_test_user(&app, "user").await;
+    let collection_name = unique_collection_name("owner_scoped_count");
 
-    let schema = CollectionSchema {
-        fields: vec![
-            FieldDefinition {
-                name: "title".to_string(),
-                field_type: FieldType::Text,
-                required: true,
-                default_value: None,
-                validation: None,
-            },
-            FieldDefinition {
-                name: "document".to_string(),
-                field_type: FieldType::File,
-                required: false,
-                default_value: None,
-                validation: None,
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/collections")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::from(
+                    json!({ "name": collection_name, "schema": create_test_schema() }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let mut ids = Vec::new();
+    for title in ["Mine", "Theirs", "Also theirs"] {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary,
+            json!({ "title": title, "published": true }),
+            boundary
+        );
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/collections/{}/records", collection_name))
+                    .method("POST")
+                    .header(
+                        "content-type",
+                        format!("multipart/form-data; boundary={}", boundary),
+                    )
+                    .header("authorization", format!("Bearer {}", admin_token))
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let record: Value = serde_json::from_slice(&body).unwrap();
+        ids.push(record["data"]["id"].as_str().unwrap().to_string());
+    }
+    diesel::sql_query(format!(
+        "UPDATE records_{} SET owner_id = {} WHERE id = {}",
+        collection_name, user_id, ids[0]
+    ))
+    .execute(&mut app_state.db_pool.get().unwrap())
+    .unwrap();
+
+    let collection = app_state
+        .collection_service
+        .get_collection(&collection_name)
+        .await
+        .unwrap();
+    let user_role = app_state
+        .permission_service
+        .get_role_by_name("user")
+        .await
+        .unwrap();
+    app_state
+        .permission_service
+        .set_collection_permission(
+            collection.id,
+            user_role.id,
+            &SetCollectionPermissionRequest {
+                role_name: "user".to_string(),
+                can_create: false,
+                can_read: true,
+                can_update: false,
+                can_delete: false,
+                can_list: false,
             },
-        ],
+        )
+        .await
+        .unwrap();
+
+    let get = |uri: String, token: Option<&str>| {
+        let mut request = Request::builder().uri(uri);
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {}", token));
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap())
     };
 
+    // Anonymous callers list through the guest role, users without list
+    // access only see their own records and admins see every record.
+    for (token, expected) in [
+        (None, 3),
+        (Some(user_token.as_str()), 1),
+        (Some(admin_token.as_str()), 3),
+    ] {
+        let response = get(
+            format!(
+                "/api/collections/{}/records/count?filter=published:eq:true",
+                collection_name
+            ),
+            token,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let count: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(count["data"]["count"], expected);
+
+        let response = get(
+            format!(
+                "/api/collections/{}/records?filter=published:eq:true&count=true",
+                collection_name
+            ),
+            token,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-total-count"], expected.to_string());
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let records: Value = serde_json::from_slice(&body).unwrap();
+        let records = records["data"].as_array().unwrap();
+        assert_eq!(records.len(), expected);
+        if expected == 1 {
+            assert_eq!(records[0]["data"]["title"], "Mine");
+        }
+    }
+
+    // Without the guest role's list permission anonymous callers get neither.
+    let guest_role = app_state
+        .permission_service
+        .get_role_by_name("guest")
+        .await
+        .unwrap();
+    app_state
+        .permission_service
+        .set_collection_permission(
+            collection.id,
+            guest_role.id,
+            &SetCollectionPermissionRequest {
+                role_name: "guest".to_string(),
+                can_create: false,
+                can_read: true,
+                can_update: false,
+                can_delete: false,
+                can_list: false,
+            },
+        )
+        .await
+        .unwrap();
+    for uri in [
+        format!("/api/collections/{}/records/count", collection_name),
+        format!("/api/collections/{}/records?count=true", collection_name),
+    ] {
+        let response = get(uri, None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
+
+#[tokio::test]
+async fn test_batch_operations_are_atomic() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+
+    let schema = create_test_schema();
+    let unique_name = unique_collection_name("batch_records");
     let collection_payload = json!({
         "name": unique_name,
-        "display_name": "Delete Files Test Collection",
-        "description": "Test collection for file deletion",
+        "display_name": "Batch Records Unique Test",
+        "description": "Test batch operations",
         "schema": schema
     });
 
@@ -618,7 +1004,7 @@ async fn test_delete_record_with_files() {
         .uri("/api/collections")
         .method("POST")
         .header("content-type", "application/json")
-        .header("authorization", format!("Bearer {}", admin_token))
+        .header("authorization", format!("Bearer {}", token))
         .body(Body::from(collection_payload.to_string()))
         .unwrap();
 
@@ -629,113 +1015,92 @@ async fn test_delete_record_with_files() {
         .unwrap();
     assert_eq!(create_collection_response.status(), StatusCode::CREATED);
 
-    let record_data = json!({
-        "title": "Test Record with File",
-        "document": "https://test-bucket.s3.amazonaws.com/files/test-document.pdf"
+    let failing_batch = json!({
+        "operations": [
+            { "method": "create", "collection": unique_name, "data": { "title": "Kept?" } },
+            { "method": "create", "collection": unique_name, "data": { "content": "Missing title" } }
+        ]
     });
 
-    let boundary = "boundary";
-    let body = format!(
-        "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
-        boundary,
-        serde_json::to_string(&record_data).unwrap(),
-        boundary
-    );
-
-    let create_record_request = Request::builder()
-        .uri(&format!("/api/collections/{}/records", unique_name))
+    let failing_request = Request::builder()
+        .uri("/api/batch")
         .method("POST")
-        .header(
-            "content-type",
-            format!("multipart/form-data; boundary={}", boundary),
-        )
-        .header("authorization", format!("Bearer {}", admin_token))
-        .body(Body::from(body))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(failing_batch.to_string()))
         .unwrap();
 
-    let create_record_response = app.clone().oneshot(create_record_request).await.unwrap();
-    assert_eq!(create_record_response.status(), StatusCode::CREATED);
+    let failing_response = app.clone().oneshot(failing_request).await.unwrap();
+    assert_eq!(failing_response.status(), StatusCode::BAD_REQUEST);
 
-    let body = create_record_response
+    let count_request = Request::builder()
+        .uri(&format!("/api/collections/{}/records/count", unique_name))
+        .method("GET")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let count_response = app.clone().oneshot(count_request).await.unwrap();
+    let body = count_response
         .into_body()
         .collect()
         .await
         .unwrap()
         .to_bytes();
-    let body_str = String::from_utf8(body.to_vec()).unwrap();
-    let json_response: Value = serde_json::from_str(&body_str).unwrap();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json_response["data"]["count"], 0);
 
-    let record_id: i32 = json_response["data"]["id"]
-        .as_str()
-        .unwrap()
-        .parse()
-        .unwrap();
+    let batch = json!({
+        "operations": [
+            { "method": "create", "collection": unique_name, "data": { "title": "First" } },
+            { "method": "create", "collection": unique_name, "data": { "title": "Second" } }
+        ]
+    });
 
-    let delete_record_request = Request::builder()
-        .uri(&format!(
-            "/api/collections/{}/records/{}",
-            unique_name, record_id
-        ))
-        .method("DELETE")
-        .header("authorization", format!("Bearer {}", admin_token))
-        .body(Body::empty())
+    let batch_request = Request::builder()
+        .uri("/api/batch")
+        .method("POST")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(batch.to_string()))
         .unwrap();
 
-    let delete_record_response = app.clone().oneshot(delete_record_request).await.unwrap();
-    assert_eq!(delete_record_response.status(), StatusCode::NO_CONTENT);
-
-    let get_record_request = Request::builder()
-        .uri(&format!(
-            "/api/collections/{}/records/{}",
-            unique_name, record_id
-        ))
-        .method("GET")
-        .body(Body::empty())
-        .unwrap();
+    let batch_response = app.oneshot(batch_request).await.unwrap();
+    assert_eq!(batch_response.status(), StatusCode::OK);
 
-    let get_record_response = app.clone().oneshot(get_record_request).await.unwrap();
-    assert_eq!(get_record_response.status(), StatusCode::NOT_FOUND);
+    let body = batch_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    let results = json_response["data"]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["record"]["data"]["title"], "First");
+    assert_eq!(results[1]["record"]["data"]["title"], "Second");
 }
 
 #[tokio::test]
-async fn test_delete_collection_with_files() {
+async fn test_ingest_endpoint_creates_records_and_records_failures() {
     let app = create_test_router().await;
-    let (_admin_id, admin_token) = create_admin_token(&app).await;
-    let unique_name = unique_collection_name("delete_collection_files_test");
+    let (_admin_id, token) = create_admin_token(&app).await;
 
-    let schema = CollectionSchema {
-        fields: vec![
-            FieldDefinition {
-                name: "title".to_string(),
-                field_type: FieldType::Text,
-                required: true,
-                default_value: None,
-                validation: None,
-            },
-            FieldDefinition {
-                name: "document".to_string(),
-                field_type: FieldType::File,
-                required: false,
-                default_value: None,
-                validation: None,
-            },
-        ],
-    };
+    let schema = create_test_schema();
+    let unique_name = unique_collection_name("ingest_records");
+    let collection_payload = json!({
+        "name": unique_name,
+        "display_name": "Ingest Records Unique Test",
+        "description": "Test webhook ingestion",
+        "schema": schema
+    });
 
     let create_collection_request = Request::builder()
         .uri("/api/collections")
         .method("POST")
         .header("content-type", "application/json")
-        .header("authorization", format!("Bearer {}", admin_token))
-        .body(Body::from(
-            serde_json::to_string(&json!({
-                "name": unique_name,
-                "display_name": "Test Collection with Files",
-                "description": "Test collection for file deletion",
-                "schema": schema
-            }))
-            .unwrap(),
-        ))
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(collection_payload.to_string()))
         .unwrap();
 
     let create_collection_response = app
@@ -745,80 +1110,6343 @@ async fn test_delete_collection_with_files() {
         .unwrap();
     assert_eq!(create_collection_response.status(), StatusCode::CREATED);
 
-    let record_data = json!({
-        "title": "Test Document",
-        "document": "https://test-bucket.s3.amazonaws.com/files/test-document.pdf"
+    let endpoint_payload = json!({
+        "name": "Issue tracker",
+        "collection_name": unique_name,
+        "field_mapping": {
+            "title": "$.issue.title",
+            "content": "$.issue.labels[0]"
+        }
     });
 
-    let boundary = "boundary";
-    let body = format!(
-        "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
-        boundary,
-        serde_json::to_string(&record_data).unwrap(),
-        boundary
-    );
+    let create_endpoint_request = Request::builder()
+        .uri("/api/admin/ingest-endpoints")
+        .method("POST")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(endpoint_payload.to_string()))
+        .unwrap();
 
-    let create_record_request = Request::builder()
-        .uri(&format!("/api/collections/{}/records", unique_name))
+    let create_endpoint_response = app.clone().oneshot(create_endpoint_request).await.unwrap();
+    assert_eq!(create_endpoint_response.status(), StatusCode::CREATED);
+
+    let body = create_endpoint_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    let endpoint_id = json_response["data"]["id"].as_i64().unwrap();
+    let ingest_token = json_response["data"]["token"].as_str().unwrap().to_string();
+
+    let ingest_request = Request::builder()
+        .uri(&format!("/api/ingest/{}", ingest_token))
         .method("POST")
-        .header(
-            "content-type",
-            format!("multipart/form-data; boundary={}", boundary),
-        )
-        .header("authorization", format!("Bearer {}", admin_token))
-        .body(Body::from(body))
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"issue": {"title": "Crash on start", "labels": ["bug", "p1"]}}).to_string(),
+        ))
         .unwrap();
 
-    let create_record_response = app.clone().oneshot(create_record_request).await.unwrap();
-    assert_eq!(create_record_response.status(), StatusCode::CREATED);
+    let ingest_response = app.clone().oneshot(ingest_request).await.unwrap();
+    assert_eq!(ingest_response.status(), StatusCode::CREATED);
 
-    let record_data2 = json!({
-        "title": "Test Document 2",
-        "document": "https://test-bucket.s3.amazonaws.com/files/test-document-2.pdf"
+    let body = ingest_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json_response["data"]["data"]["title"], "Crash on start");
+    assert_eq!(json_response["data"]["data"]["content"], "bug");
+
+    let invalid_request = Request::builder()
+        .uri(&format!("/api/ingest/{}", ingest_token))
+        .method("POST")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({"issue": {"labels": []}}).to_string()))
+        .unwrap();
+
+    let invalid_response = app.clone().oneshot(invalid_request).await.unwrap();
+    assert_eq!(invalid_response.status(), StatusCode::BAD_REQUEST);
+
+    let failures_request = Request::builder()
+        .uri(&format!(
+            "/api/admin/ingest-endpoints/{}/failures",
+            endpoint_id
+        ))
+        .method("GET")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let failures_response = app.oneshot(failures_request).await.unwrap();
+    assert_eq!(failures_response.status(), StatusCode::OK);
+
+    let body = failures_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    let failures = json_response["data"].as_array().unwrap();
+    assert_eq!(failures.len(), 1);
+    assert!(failures[0]["payload"].as_str().unwrap().contains("labels"));
+}
+
+#[tokio::test]
+async fn test_list_records_with_saved_view() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+
+    let schema = create_test_schema();
+    let unique_name = unique_collection_name("view_records");
+    let collection_payload = json!({
+        "name": unique_name,
+        "display_name": "View Records Unique Test",
+        "description": "Test saved views",
+        "schema": schema
     });
 
-    let boundary2 = "boundary2";
-    let body2 = format!(
-        "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
-        boundary2,
-        serde_json::to_string(&record_data2).unwrap(),
-        boundary2
-    );
+    let create_collection_request = Request::builder()
+        .uri("/api/collections")
+        .method("POST")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(collection_payload.to_string()))
+        .unwrap();
 
-    let create_record_request2 = Request::builder()
-        .uri(&format!("/api/collections/{}/records", unique_name))
+    let create_collection_response = app
+        .clone()
+        .oneshot(create_collection_request)
+        .await
+        .unwrap();
+    assert_eq!(create_collection_response.status(), StatusCode::CREATED);
+
+    let batch = json!({
+        "operations": [
+            { "method": "create", "collection": unique_name, "data": { "title": "Kept", "content": "keep" } },
+            { "method": "create", "collection": unique_name, "data": { "title": "Skipped", "content": "skip" } }
+        ]
+    });
+
+    let batch_request = Request::builder()
+        .uri("/api/batch")
         .method("POST")
-        .header(
-            "content-type",
-            format!("multipart/form-data; boundary={}", boundary2),
-        )
-        .header("authorization", format!("Bearer {}", admin_token))
-        .body(Body::from(body2))
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(batch.to_string()))
         .unwrap();
 
-    let create_record_response2 = app.clone().oneshot(create_record_request2).await.unwrap();
-    assert_eq!(create_record_response2.status(), StatusCode::CREATED);
+    let batch_response = app.clone().oneshot(batch_request).await.unwrap();
+    assert_eq!(batch_response.status(), StatusCode::OK);
 
-    let delete_collection_request = Request::builder()
-        .uri(&format!("/api/collections/{}", unique_name))
-        .method("DELETE")
-        .header("authorization", format!("Bearer {}", admin_token))
+    let view_payload = json!({
+        "name": "kept_titles",
+        "filter": "content:eq:keep",
+        "fields": ["title"]
+    });
+
+    let create_view_request = Request::builder()
+        .uri(&format!("/api/collections/{}/views", unique_name))
+        .method("POST")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(view_payload.to_string()))
+        .unwrap();
+
+    let create_view_response = app.clone().oneshot(create_view_request).await.unwrap();
+    assert_eq!(create_view_response.status(), StatusCode::CREATED);
+
+    let list_request = Request::builder()
+        .uri(&format!(
+            "/api/collections/{}/records?view=kept_titles",
+            unique_name
+        ))
+        .method("GET")
+        .header("authorization", format!("Bearer {}", token))
         .body(Body::empty())
         .unwrap();
 
-    let delete_collection_response = app
-        .clone()
-        .oneshot(delete_collection_request)
+    let list_response = app.clone().oneshot(list_request).await.unwrap();
+    assert_eq!(list_response.status(), StatusCode::OK);
+
+    let body = list_response
+        .into_body()
+        .collect()
         .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    let records = json_response["data"].as_array().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["data"], json!({"title": "Kept"}));
+
+    // Explicit query parameters take precedence over the stored view.
+    let override_request = Request::builder()
+        .uri(&format!(
+            "/api/collections/{}/records?view=kept_titles&filter=content:eq:skip",
+            unique_name
+        ))
+        .method("GET")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::empty())
         .unwrap();
-    assert_eq!(delete_collection_response.status(), StatusCode::NO_CONTENT);
 
-    let get_collection_request = Request::builder()
+    let override_response = app.clone().oneshot(override_request).await.unwrap();
+    let body = override_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json_response["data"][0]["data"]["title"], "Skipped");
+
+    // Private views are not available to anonymous callers.
+    let anonymous_request = Request::builder()
+        .uri(&format!(
+            "/api/collections/{}/records?view=kept_titles",
+            unique_name
+        ))
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let anonymous_response = app.clone().oneshot(anonymous_request).await.unwrap();
+    assert_eq!(anonymous_response.status(), StatusCode::NOT_FOUND);
+
+    let mut reduced_schema = create_test_schema();
+    reduced_schema
+        .fields
+        .retain(|field| field.name != "content");
+
+    let update_collection_request = Request::builder()
         .uri(&format!("/api/collections/{}", unique_name))
+        .method("PUT")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(json!({"schema": reduced_schema}).to_string()))
+        .unwrap();
+
+    let update_collection_response = app
+        .clone()
+        .oneshot(update_collection_request)
+        .await
+        .unwrap();
+    assert_eq!(update_collection_response.status(), StatusCode::OK);
+
+    let stale_request = Request::builder()
+        .uri(&format!(
+            "/api/collections/{}/records?view=kept_titles",
+            unique_name
+        ))
         .method("GET")
+        .header("authorization", format!("Bearer {}", token))
         .body(Body::empty())
         .unwrap();
 
-    let get_collection_response = app.clone().oneshot(get_collection_request).await.unwrap();
-    assert_eq!(get_collection_response.status(), StatusCode::NOT_FOUND);
+    let stale_response = app.oneshot(stale_request).await.unwrap();
+    assert_eq!(stale_response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_verify_and_repair_corrupted_records_table() {
+    use diesel::prelude::*;
+    use lunarbase::schema::permission_audit_entries;
+
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let (_user_id, user_token) = create_test_user(&app, "user").await;
+
+    let unique_name = unique_collection_name("integrity");
+    let collection_payload = json!({
+        "name": unique_name,
+        "display_name": "Integrity Test",
+        "schema": create_test_schema()
+    });
+
+    let create_collection_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/collections")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(collection_payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(create_collection_response.status(), StatusCode::CREATED);
+
+    let post_admin = |path: String, token: String| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(path)
+                .method("POST")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+    let verify_path = format!("/api/admin/collections/{}/verify", unique_name);
+    let repair_path = format!("/api/admin/collections/{}/repair", unique_name);
+
+    let healthy_response = post_admin(verify_path.clone(), token.clone())
+        .await
+        .unwrap();
+    assert_eq!(healthy_response.status(), StatusCode::OK);
+    let body = healthy_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json_response["data"]["healthy"], true);
+
+    let forbidden_response = post_admin(verify_path.clone(), user_token.clone())
+        .await
+        .unwrap();
+    assert_eq!(forbidden_response.status(), StatusCode::FORBIDDEN);
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let mut conn = db_pool.get().expect("Failed to get database connection");
+    let table_name = format!("records_{}", unique_name);
+    for statement in [
+        format!("DROP TRIGGER update_{}_updated_at", table_name),
+        format!("DROP INDEX idx_{}_created_at", table_name),
+        format!("ALTER TABLE {} DROP COLUMN content", table_name),
+        format!("ALTER TABLE {} ADD COLUMN legacy_notes TEXT", table_name),
+    ] {
+        diesel::sql_query(statement).execute(&mut conn).unwrap();
+    }
+
+    let verify_response = post_admin(verify_path.clone(), token.clone())
+        .await
+        .unwrap();
+    assert_eq!(verify_response.status(), StatusCode::OK);
+    let body = verify_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json_response["data"]["healthy"], false);
+    let mut issues: Vec<(String, String)> = json_response["data"]["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|issue| {
+            (
+                issue["kind"].as_str().unwrap().to_string(),
+                issue["target"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    issues.sort();
+    assert_eq!(
+        issues,
+        vec![
+            ("extra_column".to_string(), "legacy_notes".to_string()),
+            ("missing_column".to_string(), "content".to_string()),
+            (
+                "missing_index".to_string(),
+                format!("idx_{}_created_at", table_name)
+            ),
+            (
+                "missing_trigger".to_string(),
+                format!("update_{}_updated_at", table_name)
+            ),
+        ]
+    );
+
+    let repair_response = post_admin(repair_path, token.clone()).await.unwrap();
+    assert_eq!(repair_response.status(), StatusCode::OK);
+    let body = repair_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        json_response["data"]["applied"].as_array().unwrap().len(),
+        3
+    );
+    let unresolved = json_response["data"]["unresolved"].as_array().unwrap();
+    assert_eq!(unresolved.len(), 1);
+    assert_eq!(unresolved[0]["kind"], "extra_column");
+    assert_eq!(unresolved[0]["target"], "legacy_notes");
+    assert_eq!(unresolved[0]["auto_fixable"], false);
+
+    let reverify_response = post_admin(verify_path, token).await.unwrap();
+    let body = reverify_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json_response["data"]["issues"].as_array().unwrap().len(), 1);
+
+    // Both actions are in the audit log, the refused one is not
+    let actions: Vec<String> = permission_audit_entries::table
+        .filter(permission_audit_entries::collection_name.eq(&unique_name))
+        .order(permission_audit_entries::id.asc())
+        .select(permission_audit_entries::action)
+        .load(&mut conn)
+        .unwrap();
+    assert_eq!(
+        actions,
+        vec![
+            "verify_collection",
+            "verify_collection",
+            "repair_collection",
+            "verify_collection"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_record_list_shape_follows_the_requested_api_version() {
+    let app_state = create_test_app_state().await;
+    let app = create_test_router_for(app_state.clone());
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("versioned");
+
+    let send = |method: &'static str, uri: String, version: Option<&str>, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if let Some(version) = version {
+            request = request.header("x-api-version", version);
+        }
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        None,
+        Some(json!({ "name": collection_name, "schema": create_test_schema() })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        None,
+        Some(json!({
+            "operations": ["First", "Second", "Third"]
+                .iter()
+                .map(|title| json!({ "method": "create", "collection": collection_name, "data": { "title": title } }))
+                .collect::<Vec<_>>()
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let list_uri = format!(
+        "/api/collections/{}/records?limit=2&offset=2&filter=title:NE:Nothing&colour=blue",
+        collection_name
+    );
+
+    // Clients without a version header keep the bare array
+    let response = send("GET", list_uri.clone(), None, None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-api-version"], "1");
+    assert!(response.headers().get("deprecation").is_none());
+    assert_eq!(
+        read_json(response).await["data"].as_array().unwrap().len(),
+        1
+    );
+
+    let response = send("GET", list_uri.clone(), Some("v2"), None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-api-version"], "2");
+    let page = read_json(response).await["data"].clone();
+    assert_eq!(page["records"].as_array().unwrap().len(), 1);
+    let pagination = &page["pagination"];
+    assert_eq!(pagination["total_count"], 3);
+    assert_eq!(pagination["current_page"], 2);
+    assert_eq!(pagination["total_pages"], 2);
+    assert_eq!(pagination["applied_filter"], "title:ne:Nothing");
+    assert_eq!(pagination["applied_sort"], "-created_at");
+    assert_eq!(pagination["applied_limit"], 2);
+    assert_eq!(pagination["applied_offset"], 2);
+    assert_eq!(pagination["ignored_parameters"], json!(["colour"]));
+
+    let response = send("GET", list_uri.clone(), Some("7"), None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Deprecated versions announce their deprecation and sunset dates
+    let config = &app_state.configuration_manager;
+    config
+        .update_cache("api", "v1_deprecation_date", "2026-01-01")
+        .await;
+    config
+        .update_cache("api", "v1_sunset_date", "2026-07-01")
+        .await;
+
+    let response = send("GET", list_uri.clone(), Some("1"), None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["deprecation"], "@1767225600");
+    assert_eq!(
+        response.headers()["sunset"],
+        "Wed, 01 Jul 2026 00:00:00 GMT"
+    );
+
+    let response = send("GET", list_uri.clone(), Some("2"), None)
+        .await
+        .unwrap();
+    assert!(response.headers().get("deprecation").is_none());
+    assert!(response.headers().get("sunset").is_none());
+
+    // Raising the default moves header-less clients to the new shape
+    config.update_cache("api", "default_version", "2").await;
+    let response = send("GET", list_uri, None, None).await.unwrap();
+    assert!(read_json(response).await["data"]["records"].is_array());
+}
+
+#[tokio::test]
+async fn test_list_all_records_echoes_the_applied_query() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+
+    let get = |uri: &str| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .method("GET")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let response = get(
+        "/api/records?filter=title:EQ:First,views:gte:10.0&sort=title&limit=500&offset=-3&view=mine&expand=author",
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let pagination = read_json(response).await["data"]["pagination"].clone();
+    assert_eq!(pagination["applied_filter"], "title:eq:First,views:gte:10");
+    // Unsupported sort fields fall back to the newest records first
+    assert_eq!(pagination["applied_sort"], "-created_at");
+    assert_eq!(pagination["applied_limit"], 100);
+    assert_eq!(pagination["applied_offset"], 0);
+    assert_eq!(pagination["ignored_parameters"], json!(["expand", "view"]));
+
+    let response = get("/api/records?sort=-updated_at").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let pagination = read_json(response).await["data"]["pagination"].clone();
+    assert_eq!(pagination["applied_sort"], "-updated_at");
+    assert_eq!(pagination["applied_limit"], 20);
+    assert!(pagination.get("applied_filter").is_none());
+    assert!(pagination.get("ignored_parameters").is_none());
+
+    let response = get("/api/records?filter=title:between:1").await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_number_formats_round_trip_as_written() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("number_formats");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let create = |data: Value| {
+        send(
+            "POST",
+            "/api/batch".to_string(),
+            Some(json!({
+                "operations": [{ "method": "create", "collection": collection_name, "data": data }]
+            })),
+        )
+    };
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({
+            "name": collection_name,
+            "schema": { "fields": [
+                { "name": "title", "field_type": "text", "required": true },
+                { "name": "price", "field_type": "number", "required": false,
+                  "validation": { "number_format": "float" } },
+                { "name": "quantity", "field_type": "number", "required": false,
+                  "validation": { "number_format": "integer" } },
+                { "name": "score", "field_type": "number", "required": false }
+            ] }
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = create(json!({ "title": "Widget", "price": 10.0, "quantity": 3, "score": 4.0 }))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record_id = read_json(response).await["data"]["results"][0]["record"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let response = send(
+        "GET",
+        format!("/api/collections/{}/records/{}", collection_name, record_id),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let data = read_json(response).await["data"]["data"].clone();
+    assert!(
+        data["price"].is_f64(),
+        "price read back as {}",
+        data["price"]
+    );
+    assert_eq!(data["price"].as_f64(), Some(10.0));
+    assert_eq!(data["quantity"].as_i64(), Some(3));
+    // Fields without a declared format keep returning whole numbers as integers
+    assert_eq!(data["score"].as_i64(), Some(4));
+
+    let response = create(json!({ "title": "Half", "quantity": 2.5 }))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        "GET",
+        format!(
+            "/api/collections/{}/records?filter=quantity:eq:2.5",
+            collection_name
+        ),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        "GET",
+        format!(
+            "/api/collections/{}/records?filter=price:eq:10",
+            collection_name
+        ),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["data"][0]["id"], record_id);
+
+    // Fields holding fractions cannot be switched to the integer format
+    create(json!({ "title": "Fraction", "score": 4.5 }))
+        .await
+        .unwrap();
+    let response = send(
+        "PUT",
+        format!("/api/collections/{}", collection_name),
+        Some(json!({ "schema": { "fields": [
+            { "name": "title", "field_type": "text", "required": true },
+            { "name": "price", "field_type": "number", "required": false,
+              "validation": { "number_format": "float" } },
+            { "name": "quantity", "field_type": "number", "required": false,
+              "validation": { "number_format": "integer" } },
+            { "name": "score", "field_type": "number", "required": false,
+              "validation": { "number_format": "integer" } }
+        ] } })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_renamed_collection_keeps_working_under_its_new_name() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let (_user_id, user_token) = create_test_user(&app, "user").await;
+    let old_name = unique_collection_name("rename_from");
+    let new_name = unique_collection_name("rename_to");
+    let referencing_name = unique_collection_name("rename_ref");
+
+    let send = |method: &'static str, uri: String, token: &str, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    for body in [
+        json!({ "name": old_name, "schema": create_test_schema() }),
+        json!({
+            "name": referencing_name,
+            "schema": { "fields": [
+                { "name": "source", "field_type": "relation", "required": false, "relation_target": old_name }
+            ] }
+        }),
+    ] {
+        let response = send("POST", "/api/collections".to_string(), &token, Some(body))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        &token,
+        Some(json!({
+            "operations": [{ "method": "create", "collection": old_name, "data": { "title": "Before" } }]
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record_id = read_json(response).await["data"]["results"][0]["record"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let response = send(
+        "POST",
+        format!("/api/collections/{}/records/{}/share", old_name, record_id),
+        &token,
+        Some(json!({ "expires_in_hours": 1 })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let share_token = read_json(response).await["data"]["token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let response = send(
+        "PUT",
+        format!("/api/collections/{}", old_name),
+        &token,
+        Some(json!({ "name": new_name })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send(
+        "GET",
+        format!("/api/collections/{}/records", old_name),
+        &token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Index and trigger follow the table name, so the table verifies cleanly
+    let response = send(
+        "POST",
+        format!("/api/admin/collections/{}/verify", new_name),
+        &token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(read_json(response).await["data"]["healthy"], true);
+
+    // Record CRUD under the new name
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        &token,
+        Some(json!({
+            "operations": [
+                { "method": "create", "collection": new_name, "data": { "title": "After" } },
+                { "method": "update", "collection": new_name, "record_id": record_id, "data": { "title": "Updated" } }
+            ]
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let created_id = read_json(response).await["data"]["results"][0]["record"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let response = send(
+        "GET",
+        format!("/api/collections/{}/records/{}", new_name, record_id),
+        &token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        read_json(response).await["data"]["data"]["title"],
+        "Updated"
+    );
+
+    let response = send(
+        "DELETE",
+        format!("/api/collections/{}/records/{}", new_name, created_id),
+        &token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    // Role permissions are kept for the renamed collection
+    let response = send(
+        "GET",
+        format!("/api/collections/{}/records", new_name),
+        &user_token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Schema updates work on the renamed table
+    let mut schema = create_test_schema();
+    schema.fields.push(FieldDefinition {
+        name: "summary".to_string(),
+        field_type: FieldType::Text,
+        required: false,
+        default_value: None,
+        validation: None,
+        relation_target: None,
+        description: None,
+        example: None,
+        indexed_paths: None,
+    });
+    let response = send(
+        "PUT",
+        format!("/api/collections/{}", new_name),
+        &token,
+        Some(json!({ "schema": schema })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = send(
+        "POST",
+        format!("/api/admin/collections/{}/verify", new_name),
+        &token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(read_json(response).await["data"]["healthy"], true);
+
+    // Relations and shares follow the new name
+    let response = send(
+        "GET",
+        format!("/api/collections/{}", referencing_name),
+        &token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        read_json(response).await["data"]["schema"]["fields"][0]["relation_target"],
+        new_name
+    );
+
+    let response = send("GET", format!("/api/share/{}", share_token), &token, None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        read_json(response).await["data"]["collection_name"],
+        new_name
+    );
+}
+
+#[tokio::test]
+async fn test_sweep_orphans_reports_and_drops_leftover_temp_tables() {
+    use diesel::RunQueryDsl;
+
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let (_user_id, user_token) = create_test_user(&app, "user").await;
+
+    // One temporary table from a crash long ago and one from a schema change
+    // that may still be running
+    let stale_table = format!("records_{}_temp_1000", unique_collection_name("orphan"));
+    let stale_index = format!("idx_{}_created_at", stale_table);
+    let fresh_table = format!(
+        "records_{}_temp_{}",
+        unique_collection_name("orphan"),
+        chrono::Utc::now().timestamp()
+    );
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let mut conn = db_pool.get().expect("Failed to get database connection");
+    for statement in [
+        format!(
+            "CREATE TABLE {} (id INTEGER PRIMARY KEY, created_at TIMESTAMP)",
+            stale_table
+        ),
+        format!(
+            "CREATE INDEX {} ON {} (created_at)",
+            stale_index, stale_table
+        ),
+        format!(
+            "CREATE TABLE {} (id INTEGER PRIMARY KEY, created_at TIMESTAMP)",
+            fresh_table
+        ),
+    ] {
+        diesel::sql_query(statement).execute(&mut conn).unwrap();
+    }
+
+    let sweep = |query: &str, token: &str| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/admin/collections/orphans/sweep{}", query))
+                .method("POST")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let names = |values: &Value| -> Vec<String> {
+        values
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|value| {
+                value
+                    .as_str()
+                    .unwrap_or_else(|| value["name"].as_str().unwrap())
+            })
+            .map(str::to_string)
+            .collect()
+    };
+
+    let forbidden_response = sweep("", &user_token).await.unwrap();
+    assert_eq!(forbidden_response.status(), StatusCode::FORBIDDEN);
+
+    let dry_run_response = sweep("?dry_run=true", &token).await.unwrap();
+    assert_eq!(dry_run_response.status(), StatusCode::OK);
+    let report = read_json(dry_run_response).await;
+    assert_eq!(report["data"]["dry_run"], true);
+    let orphans = names(&report["data"]["orphans"]);
+    assert!(orphans.contains(&stale_table));
+    assert!(orphans.contains(&stale_index));
+    assert!(!orphans.contains(&fresh_table));
+    assert!(names(&report["data"]["dropped"]).is_empty());
+
+    let sweep_response = sweep("", &token).await.unwrap();
+    assert_eq!(sweep_response.status(), StatusCode::OK);
+    let report = read_json(sweep_response).await;
+    let dropped = names(&report["data"]["dropped"]);
+    assert!(dropped.contains(&stale_table));
+    assert!(dropped.contains(&stale_index));
+
+    #[derive(diesel::QueryableByName)]
+    struct TableName {
+        #[diesel(sql_type = diesel::sql_types::Text)]
+        name: String,
+    }
+    let tables: Vec<String> = diesel::sql_query(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name LIKE 'records_orphan_%'",
+    )
+    .load::<TableName>(&mut conn)
+    .unwrap()
+    .into_iter()
+    .map(|table| table.name)
+    .collect();
+    assert!(!tables.contains(&stale_table));
+    assert!(tables.contains(&fresh_table));
+
+    diesel::sql_query(format!("DROP TABLE {}", fresh_table))
+        .execute(&mut conn)
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_get_collection_schema() {
+    let app1 = create_test_router().await;
+    let app2 = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app1).await;
+
+    let schema = create_test_schema();
+    let unique_name = unique_collection_name("schema_test");
+    let collection_payload = json!({
+        "name": unique_name,
+        "display_name": "Schema Test Unique Collection",
+        "description": "Test getting schema",
+        "schema": schema
+    });
+
+    let create_collection_request = Request::builder()
+        .uri("/api/collections")
+        .method("POST")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(collection_payload.to_string()))
+        .unwrap();
+
+    let create_collection_response = app1.oneshot(create_collection_request).await.unwrap();
+    assert_eq!(create_collection_response.status(), StatusCode::CREATED);
+
+    let schema_request = Request::builder()
+        .uri(&format!("/api/collections/{}/schema", unique_name))
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let schema_response = app2.oneshot(schema_request).await.unwrap();
+    assert_eq!(schema_response.status(), StatusCode::OK);
+
+    let body = schema_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let body_str = String::from_utf8(body.to_vec()).unwrap();
+    let json_response: Value = serde_json::from_str(&body_str).unwrap();
+
+    assert!(json_response["data"]["fields"].is_array());
+    assert_eq!(json_response["data"]["fields"].as_array().unwrap().len(), 5);
+}
+
+#[tokio::test]
+async fn test_delete_record_with_files() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let unique_name = unique_collection_name("delete_files_test");
+
+    let schema = CollectionSchema {
+        fields: vec![
+            FieldDefinition {
+                name: "title".to_string(),
+                field_type: FieldType::Text,
+                required: true,
+                default_value: None,
+                validation: None,
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
+            },
+            FieldDefinition {
+                name: "document".to_string(),
+                field_type: FieldType::File,
+                required: false,
+                default_value: None,
+                validation: None,
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
+            },
+        ],
+    };
+
+    let collection_payload = json!({
+        "name": unique_name,
+        "display_name": "Delete Files Test Collection",
+        "description": "Test collection for file deletion",
+        "schema": schema
+    });
+
+    let create_collection_request = Request::builder()
+        .uri("/api/collections")
+        .method("POST")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", admin_token))
+        .body(Body::from(collection_payload.to_string()))
+        .unwrap();
+
+    let create_collection_response = app
+        .clone()
+        .oneshot(create_collection_request)
+        .await
+        .unwrap();
+    assert_eq!(create_collection_response.status(), StatusCode::CREATED);
+
+    let record_data = json!({
+        "title": "Test Record with File",
+        "document": "https://test-bucket.s3.amazonaws.com/files/test-document.pdf"
+    });
+
+    let boundary = "boundary";
+    let body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+        boundary,
+        serde_json::to_string(&record_data).unwrap(),
+        boundary
+    );
+
+    let create_record_request = Request::builder()
+        .uri(&format!("/api/collections/{}/records", unique_name))
+        .method("POST")
+        .header(
+            "content-type",
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .header("authorization", format!("Bearer {}", admin_token))
+        .body(Body::from(body))
+        .unwrap();
+
+    let create_record_response = app.clone().oneshot(create_record_request).await.unwrap();
+    assert_eq!(create_record_response.status(), StatusCode::CREATED);
+
+    let body = create_record_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let body_str = String::from_utf8(body.to_vec()).unwrap();
+    let json_response: Value = serde_json::from_str(&body_str).unwrap();
+
+    let record_id: i32 = json_response["data"]["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let delete_record_request = Request::builder()
+        .uri(&format!(
+            "/api/collections/{}/records/{}",
+            unique_name, record_id
+        ))
+        .method("DELETE")
+        .header("authorization", format!("Bearer {}", admin_token))
+        .body(Body::empty())
+        .unwrap();
+
+    let delete_record_response = app.clone().oneshot(delete_record_request).await.unwrap();
+    assert_eq!(delete_record_response.status(), StatusCode::NO_CONTENT);
+
+    let get_record_request = Request::builder()
+        .uri(&format!(
+            "/api/collections/{}/records/{}",
+            unique_name, record_id
+        ))
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let get_record_response = app.clone().oneshot(get_record_request).await.unwrap();
+    assert_eq!(get_record_response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_delete_collection_with_files() {
+    use diesel::prelude::*;
+    use lunarbase::schema::permission_audit_entries;
+
+    let app = create_test_router().await;
+    let (admin_id, admin_token) = create_admin_token(&app).await;
+    let unique_name = unique_collection_name("delete_collection_files_test");
+
+    let schema = CollectionSchema {
+        fields: vec![
+            FieldDefinition {
+                name: "title".to_string(),
+                field_type: FieldType::Text,
+                required: true,
+                default_value: None,
+                validation: None,
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
+            },
+            FieldDefinition {
+                name: "document".to_string(),
+                field_type: FieldType::File,
+                required: false,
+                default_value: None,
+                validation: None,
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
+            },
+        ],
+    };
+
+    let create_collection_request = Request::builder()
+        .uri("/api/collections")
+        .method("POST")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", admin_token))
+        .body(Body::from(
+            serde_json::to_string(&json!({
+                "name": unique_name,
+                "display_name": "Test Collection with Files",
+                "description": "Test collection for file deletion",
+                "schema": schema
+            }))
+            .unwrap(),
+        ))
+        .unwrap();
+
+    let create_collection_response = app
+        .clone()
+        .oneshot(create_collection_request)
+        .await
+        .unwrap();
+    assert_eq!(create_collection_response.status(), StatusCode::CREATED);
+
+    let record_data = json!({
+        "title": "Test Document",
+        "document": "https://test-bucket.s3.amazonaws.com/files/test-document.pdf"
+    });
+
+    let boundary = "boundary";
+    let body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+        boundary,
+        serde_json::to_string(&record_data).unwrap(),
+        boundary
+    );
+
+    let create_record_request = Request::builder()
+        .uri(&format!("/api/collections/{}/records", unique_name))
+        .method("POST")
+        .header(
+            "content-type",
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .header("authorization", format!("Bearer {}", admin_token))
+        .body(Body::from(body))
+        .unwrap();
+
+    let create_record_response = app.clone().oneshot(create_record_request).await.unwrap();
+    assert_eq!(create_record_response.status(), StatusCode::CREATED);
+
+    let record_data2 = json!({
+        "title": "Test Document 2",
+        "document": "https://test-bucket.s3.amazonaws.com/files/test-document-2.pdf"
+    });
+
+    let boundary2 = "boundary2";
+    let body2 = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+        boundary2,
+        serde_json::to_string(&record_data2).unwrap(),
+        boundary2
+    );
+
+    let create_record_request2 = Request::builder()
+        .uri(&format!("/api/collections/{}/records", unique_name))
+        .method("POST")
+        .header(
+            "content-type",
+            format!("multipart/form-data; boundary={}", boundary2),
+        )
+        .header("authorization", format!("Bearer {}", admin_token))
+        .body(Body::from(body2))
+        .unwrap();
+
+    let create_record_response2 = app.clone().oneshot(create_record_request2).await.unwrap();
+    assert_eq!(create_record_response2.status(), StatusCode::CREATED);
+
+    let delete_collection_request = |query: &str| {
+        Request::builder()
+            .uri(format!("/api/collections/{}{}", unique_name, query))
+            .method("DELETE")
+            .header("authorization", format!("Bearer {}", admin_token))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // The first DELETE only reports what would be lost
+    let pending_response = app
+        .clone()
+        .oneshot(delete_collection_request(""))
+        .await
+        .unwrap();
+    assert_eq!(pending_response.status(), StatusCode::ACCEPTED);
+    let body = pending_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let pending: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(pending["data"]["impact"]["record_count"], 2);
+    assert_eq!(pending["data"]["impact"]["file_count"], 2);
+    assert_eq!(
+        pending["data"]["impact"]["referencing_collections"],
+        json!([])
+    );
+    let confirm_token = pending["data"]["confirm_token"].as_str().unwrap();
+
+    let wrong_token_response = app
+        .clone()
+        .oneshot(delete_collection_request("?confirm_token=not-a-token"))
+        .await
+        .unwrap();
+    assert_eq!(wrong_token_response.status(), StatusCode::BAD_REQUEST);
+
+    let get_collection_request = Request::builder()
+        .uri(format!("/api/collections/{}", unique_name))
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+    let get_collection_response = app.clone().oneshot(get_collection_request).await.unwrap();
+    assert_eq!(get_collection_response.status(), StatusCode::OK);
+
+    let delete_collection_response = app
+        .clone()
+        .oneshot(delete_collection_request(&format!(
+            "?confirm_token={}",
+            confirm_token
+        )))
+        .await
+        .unwrap();
+    assert_eq!(delete_collection_response.status(), StatusCode::NO_CONTENT);
+
+    let get_collection_request = Request::builder()
+        .uri(&format!("/api/collections/{}", unique_name))
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let get_collection_response = app.clone().oneshot(get_collection_request).await.unwrap();
+    assert_eq!(get_collection_response.status(), StatusCode::NOT_FOUND);
+
+    // Both phases are in the audit log, the refused token is not
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let mut conn = db_pool.get().expect("Failed to get database connection");
+    let entries: Vec<(String, Option<i32>, i64)> = permission_audit_entries::table
+        .filter(permission_audit_entries::collection_name.eq(&unique_name))
+        .order(permission_audit_entries::id.asc())
+        .select((
+            permission_audit_entries::action,
+            permission_audit_entries::actor_id,
+            permission_audit_entries::affected_count,
+        ))
+        .load(&mut conn)
+        .unwrap();
+    assert_eq!(
+        entries,
+        vec![
+            ("collection_delete_requested".to_string(), Some(admin_id), 2),
+            ("collection_deleted".to_string(), Some(admin_id), 2),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_schema_versions_recorded_and_restored() {
+    let app = create_test_router().await;
+    let (admin_id, token) = create_admin_token(&app).await;
+
+    let unique_name = unique_collection_name("versioned");
+    let collection_payload = json!({
+        "name": unique_name,
+        "schema": create_test_schema()
+    });
+
+    let create_collection_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/collections")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(collection_payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(create_collection_response.status(), StatusCode::CREATED);
+
+    let mut updated_schema = create_test_schema();
+    updated_schema.fields.push(FieldDefinition {
+        name: "summary".to_string(),
+        field_type: FieldType::Text,
+        required: false,
+        default_value: None,
+        validation: None,
+        relation_target: None,
+        description: None,
+        example: None,
+        indexed_paths: None,
+    });
+
+    let update_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}", unique_name))
+                .method("PUT")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(json!({ "schema": updated_schema }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(update_response.status(), StatusCode::OK);
+    let body = update_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json_response["data"]["schema_version"], 2);
+
+    let versions_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/schema/versions", unique_name))
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(versions_response.status(), StatusCode::OK);
+    let body = versions_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    let versions = json_response["data"].as_array().unwrap();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0]["version"], 2);
+    assert_eq!(versions[0]["created_by"], admin_id);
+    assert_eq!(versions[0]["migration_summary"], "Added field 'summary'");
+    assert_eq!(versions[1]["version"], 1);
+
+    let version_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/collections/{}/schema/versions/1",
+                    unique_name
+                ))
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(version_response.status(), StatusCode::OK);
+    let body = version_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        json_response["data"]["schema"]["fields"]
+            .as_array()
+            .unwrap()
+            .len(),
+        5
+    );
+
+    let restore_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/collections/{}/schema/versions/1/restore",
+                    unique_name
+                ))
+                .method("POST")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(restore_response.status(), StatusCode::OK);
+    let body = restore_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json_response["data"]["schema_version"], 3);
+    assert_eq!(
+        json_response["data"]["schema"]["fields"]
+            .as_array()
+            .unwrap()
+            .len(),
+        5
+    );
+
+    let missing_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/collections/{}/schema/versions/99",
+                    unique_name
+                ))
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(missing_response.status(), StatusCode::NOT_FOUND);
+}
+
+static EXECUTED_SQL: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+fn record_executed_sql() -> Option<Box<dyn diesel::connection::Instrumentation>> {
+    Some(Box::new(
+        |event: diesel::connection::InstrumentationEvent<'_>| {
+            if let diesel::connection::InstrumentationEvent::StartQuery { query, .. } = event {
+                EXECUTED_SQL.lock().unwrap().push(query.to_string());
+            }
+        },
+    ))
+}
+
+/// ALTER TABLE statements run so far against the records table of `collection`.
+fn alter_table_statements(collection: &str) -> usize {
+    let table = format!("records_{}", collection);
+    EXECUTED_SQL
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|sql| sql.contains("ALTER TABLE") && sql.contains(&table))
+        .count()
+}
+
+#[tokio::test]
+async fn test_field_descriptions_update_without_migrating_the_table() {
+    // Only connections opened afterwards are instrumented, so this goes first
+    diesel::connection::set_default_instrumentation(record_executed_sql).unwrap();
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+
+    let unique_name = unique_collection_name("documented");
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/collections")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(
+                    json!({
+                        "name": unique_name,
+                        "description": "Posts on the company blog",
+                        "schema": create_test_schema()
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let update_schema = |schema: CollectionSchema| {
+        Request::builder()
+            .uri(format!("/api/collections/{}", unique_name))
+            .method("PUT")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {}", token))
+            .body(Body::from(json!({ "schema": schema }).to_string()))
+            .unwrap()
+    };
+
+    let mut documented_schema = create_test_schema();
+    documented_schema.fields[0].description = Some("Headline shown in listings".to_string());
+    documented_schema.fields[0].example = Some(json!("Release notes for 2.0"));
+    let response = app
+        .clone()
+        .oneshot(update_schema(documented_schema.clone()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json_response["data"]["schema_version"], 2);
+    assert_eq!(alter_table_statements(&unique_name), 0);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/schema", unique_name))
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        json_response["data"]["fields"][0]["description"],
+        "Headline shown in listings"
+    );
+    assert_eq!(
+        json_response["data"]["fields"][0]["example"],
+        "Release notes for 2.0"
+    );
+    assert!(
+        json_response["data"]["fields"][1]
+            .get("description")
+            .is_none()
+    );
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/schema/versions", unique_name))
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        json_response["data"][0]["migration_summary"],
+        "Documented field 'title'"
+    );
+
+    // A structural change still migrates the table and keeps the descriptions
+    documented_schema.fields.push(FieldDefinition {
+        name: "summary".to_string(),
+        field_type: FieldType::Text,
+        required: false,
+        default_value: None,
+        validation: None,
+        relation_target: None,
+        description: Some("Teaser below the headline".to_string()),
+        example: None,
+        indexed_paths: None,
+    });
+    let response = app
+        .clone()
+        .oneshot(update_schema(documented_schema))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(alter_table_statements(&unique_name), 1);
+    assert_eq!(
+        json_response["data"]["schema"]["fields"][0]["description"],
+        "Headline shown in listings"
+    );
+}
+
+#[tokio::test]
+async fn test_users_system_collection_is_read_only_and_expandable() {
+    let app = create_test_router().await;
+    let (admin_id, token) = create_admin_token(&app).await;
+
+    let get = |uri: String, token: Option<String>| {
+        let mut builder = Request::builder().uri(uri).method("GET");
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        app.clone().oneshot(builder.body(Body::empty()).unwrap())
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let schema_response = get("/api/collections/_users/schema".to_string(), None)
+        .await
+        .unwrap();
+    assert_eq!(schema_response.status(), StatusCode::OK);
+    let json_response = read_json(schema_response).await;
+    assert_eq!(json_response["data"]["fields"].as_array().unwrap().len(), 2);
+
+    let invalid_target_payload = json!({
+        "name": unique_collection_name("bad_target"),
+        "schema": {
+            "fields": [
+                {"name": "author", "field_type": "relation", "required": false, "relation_target": "missing_collection"}
+            ]
+        }
+    });
+    let invalid_target_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/collections")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(invalid_target_payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(invalid_target_response.status(), StatusCode::BAD_REQUEST);
+
+    let unique_name = unique_collection_name("posts");
+    let collection_payload = json!({
+        "name": unique_name,
+        "schema": {
+            "fields": [
+                {"name": "title", "field_type": "text", "required": true},
+                {"name": "author", "field_type": "relation", "required": false, "relation_target": "_users"}
+            ]
+        }
+    });
+    let create_collection_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/collections")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(collection_payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(create_collection_response.status(), StatusCode::CREATED);
+
+    let multipart = |uri: String, data: Value| {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary, data, boundary
+        );
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+
+    let create_record_response = multipart(
+        format!("/api/collections/{}/records", unique_name),
+        json!({"title": "Hello", "author": admin_id.to_string()}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(create_record_response.status(), StatusCode::CREATED);
+
+    let write_response = multipart(
+        "/api/collections/_users/records".to_string(),
+        json!({"username": "intruder"}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(write_response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    let expanded_response = get(
+        format!("/api/collections/{}/records?expand=author", unique_name),
+        Some(token.clone()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(expanded_response.status(), StatusCode::OK);
+    let json_response = read_json(expanded_response).await;
+    let author = &json_response["data"][0]["data"]["author"];
+    assert_eq!(author["id"], admin_id.to_string());
+    assert!(author["username"].is_string());
+    assert!(author.get("email").is_none());
+
+    let anonymous_response = get(
+        format!("/api/collections/{}/records?expand=author", unique_name),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(anonymous_response.status(), StatusCode::OK);
+    let json_response = read_json(anonymous_response).await;
+    assert_eq!(
+        json_response["data"][0]["data"]["author"],
+        admin_id.to_string()
+    );
+
+    let user_record_response = get(
+        format!("/api/collections/_users/records/{}", admin_id),
+        Some(token.clone()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(user_record_response.status(), StatusCode::OK);
+    let json_response = read_json(user_record_response).await;
+    let data = json_response["data"]["data"].as_object().unwrap();
+    let mut keys: Vec<&str> = data.keys().map(String::as_str).collect();
+    keys.sort();
+    assert_eq!(keys, vec!["avatar_url", "username"]);
+
+    let anonymous_user_response = get(
+        format!("/api/collections/_users/records/{}", admin_id),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(anonymous_user_response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_expanded_relations_respect_read_permissions_on_the_target() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let (_user_id, user_token) = create_test_user(&app, "user").await;
+    let secrets = unique_collection_name("secrets");
+    let posts = unique_collection_name("posts");
+
+    let create_collection = |payload: Value| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri("/api/collections")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+    };
+    let create_record = |collection_name: String, token: String, data: Value| {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary, data, boundary
+        );
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+    let list_expanded = |token: Option<&str>| {
+        let mut builder = Request::builder()
+            .uri(format!(
+                "/api/collections/{}/records?expand=secret&sort=title",
+                posts
+            ))
+            .method("GET");
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        app.clone().oneshot(builder.body(Body::empty()).unwrap())
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    // Users may only create secrets, so they can read their own and no others
+    let response = create_collection(json!({
+        "name": secrets,
+        "schema": { "fields": [{ "name": "title", "field_type": "text", "required": true }] },
+        "permissions": [{
+            "role_name": "user",
+            "can_create": true,
+            "can_read": false,
+            "can_update": false,
+            "can_delete": false,
+            "can_list": false
+        }]
+    }))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = create_collection(json!({
+        "name": posts,
+        "schema": { "fields": [
+            { "name": "title", "field_type": "text", "required": true },
+            { "name": "secret", "field_type": "relation", "required": false, "relation_target": secrets }
+        ] }
+    }))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let mut secret_ids = Vec::new();
+    for token in [&admin_token, &user_token] {
+        let response = create_record(secrets.clone(), token.clone(), json!({ "title": "secret" }))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let id = read_json(response).await["data"]["id"].clone();
+        secret_ids.push(id.as_str().unwrap().to_string());
+    }
+    for (title, secret_id) in ["a_admins", "b_users"].iter().zip(&secret_ids) {
+        let response = create_record(
+            posts.clone(),
+            admin_token.clone(),
+            json!({ "title": title, "secret": secret_id }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let expanded = |json: &Value| -> Vec<bool> {
+        json["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|post| post["data"]["secret"].is_object())
+            .collect()
+    };
+
+    let json = read_json(list_expanded(Some(&admin_token)).await.unwrap()).await;
+    assert_eq!(expanded(&json), vec![true, true]);
+
+    let json = read_json(list_expanded(Some(&user_token)).await.unwrap()).await;
+    assert_eq!(expanded(&json), vec![false, true]);
+    assert_eq!(json["data"][0]["data"]["secret"], secret_ids[0]);
+
+    let json = read_json(list_expanded(None).await.unwrap()).await;
+    assert_eq!(expanded(&json), vec![false, false]);
+}
+
+#[tokio::test]
+async fn test_single_record_reads_check_permissions_for_every_caller() {
+    use lunarbase::models::SetCollectionPermissionRequest;
+
+    let state = create_test_app_state().await;
+    let app = create_test_router_for(state.clone());
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let (_user_id, user_token) = create_test_user(&app, "user").await;
+    let collection_name = unique_collection_name("private_pages");
+
+    let get = |uri: String, token: Option<&str>| {
+        let mut builder = Request::builder().uri(uri).method("GET");
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        app.clone().oneshot(builder.body(Body::empty()).unwrap())
+    };
+
+    // An explicit empty permission list leaves every role but admin without access
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/collections")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::from(
+                    json!({
+                        "name": collection_name,
+                        "schema": { "fields": [
+                            { "name": "title", "field_type": "text", "required": true },
+                            {
+                                "name": "slug",
+                                "field_type": "slug",
+                                "required": true,
+                                "validation": { "source_field": "title" }
+                            }
+                        ] },
+                        "permissions": []
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let boundary = "boundary";
+    let body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+        boundary,
+        json!({ "title": "Private page" }),
+        boundary
+    );
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let record_id = serde_json::from_slice::<Value>(&body).unwrap()["data"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let uris = [
+        format!("/api/collections/{}/records/{}", collection_name, record_id),
+        format!(
+            "/api/collections/{}/records/by/slug/private-page",
+            collection_name
+        ),
+    ];
+    for uri in &uris {
+        for token in [None, Some(user_token.as_str())] {
+            let response = get(uri.clone(), token).await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{}", uri);
+        }
+        let response = get(uri.clone(), Some(&admin_token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+    }
+
+    // Anonymous callers read through the guest role
+    let collection = state
+        .collection_service
+        .get_collection(&collection_name)
+        .await
+        .unwrap();
+    let guest_role = state
+        .permission_service
+        .get_role_by_name("guest")
+        .await
+        .unwrap();
+    state
+        .permission_service
+        .set_collection_permission(
+            collection.id,
+            guest_role.id,
+            &SetCollectionPermissionRequest {
+                role_name: "guest".to_string(),
+                can_create: false,
+                can_read: true,
+                can_update: false,
+                can_delete: false,
+                can_list: false,
+            },
+        )
+        .await
+        .unwrap();
+    for uri in &uris {
+        let response = get(uri.clone(), None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_read_only_roles_block_writes_but_not_reads() {
+    use lunarbase::services::ConfigurationService;
+
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+
+    let collection_name = unique_collection_name("read_only");
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/collections")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::from(
+                    json!({
+                        "name": collection_name,
+                        "display_name": "Read Only",
+                        "schema": create_test_schema()
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let read_only_role = format!("ro_{}", &uuid::Uuid::new_v4().simple().to_string()[0..8]);
+    let (_user_id, read_only_token) = create_test_user(&app, &read_only_role).await;
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let config_service =
+        ConfigurationService::new(create_pool(&config.database_url).expect("pool"));
+    let previous_roles = config_service
+        .get_setting("system", "read_only_roles")
+        .await
+        .unwrap()
+        .expect("read_only_roles setting is seeded")
+        .setting_value;
+    config_service
+        .update_setting(
+            "system",
+            "read_only_roles",
+            &json!([read_only_role]).to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    // A fresh router loads the updated settings into its cache
+    let app = create_test_router().await;
+    let create_record = |token: String| {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary,
+            json!({ "title": "Migrating", "email": "ro@example.com" }),
+            boundary
+        );
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+
+    let response = create_record(read_only_token.clone()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["code"], "read_only_mode");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/batch")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", read_only_token))
+                .body(Body::from(json!({ "operations": [] }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("GET")
+                .header("authorization", format!("Bearer {}", read_only_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_ne!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let response = create_record(admin_token).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    config_service
+        .update_setting("system", "read_only_roles", &previous_roles, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_query_cache_serves_hits_until_records_change() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("query_cache");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/collections")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(
+                    json!({ "name": collection_name, "schema": create_test_schema() }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}", collection_name))
+                .method("PUT")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(
+                    json!({ "query_cache_ttl_seconds": 60 }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let create_record = |title: &'static str| {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary,
+            json!({ "title": title }),
+            boundary
+        );
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+    let count_records = |cache_control: Option<&'static str>| {
+        let mut request = Request::builder()
+            .uri(format!(
+                "/api/collections/{}/records/count",
+                collection_name
+            ))
+            .method("GET")
+            .header("authorization", format!("Bearer {}", token));
+        if let Some(cache_control) = cache_control {
+            request = request.header("cache-control", cache_control);
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap())
+    };
+    let read_count = |response: axum::response::Response| async move {
+        let cache_status = response.headers()["x-query-cache"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        (cache_status, json["data"]["count"].as_i64().unwrap())
+    };
+
+    assert_eq!(
+        create_record("First").await.unwrap().status(),
+        StatusCode::CREATED
+    );
+
+    let first = read_count(count_records(None).await.unwrap()).await;
+    assert_eq!(first, ("miss".to_string(), 1));
+    let second = read_count(count_records(None).await.unwrap()).await;
+    assert_eq!(second, ("hit".to_string(), 1));
+    let fresh = read_count(count_records(Some("no-cache")).await.unwrap()).await;
+    assert_eq!(fresh, ("miss".to_string(), 1));
+
+    assert_eq!(
+        create_record("Second").await.unwrap().status(),
+        StatusCode::CREATED
+    );
+
+    let after_create = read_count(count_records(None).await.unwrap()).await;
+    assert_eq!(after_create, ("miss".to_string(), 2));
+
+    let list_with_count = || {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/collections/{}/records?count=true",
+                    collection_name
+                ))
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+    let response = list_with_count().await.unwrap();
+    assert_eq!(response.headers()["x-query-cache"], "miss");
+    assert_eq!(response.headers()["x-total-count"], "2");
+    let response = list_with_count().await.unwrap();
+    assert_eq!(response.headers()["x-query-cache"], "hit");
+    assert_eq!(response.headers()["x-total-count"], "2");
+}
+
+#[tokio::test]
+async fn test_collection_templates_create_save_and_delete() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let (_user_id, user_token) = create_test_user(&app, "user").await;
+
+    let send = |method: &'static str, uri: String, token: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let response = send(
+        "GET",
+        "/api/collections/templates".to_string(),
+        user_token.clone(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send(
+        "GET",
+        "/api/collections/templates".to_string(),
+        admin_token.clone(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let templates = read_json(response).await;
+    let built_in: Vec<&str> = templates["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|template| template["built_in"] == true)
+        .map(|template| template["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        built_in,
+        vec!["blog_posts", "products", "contacts", "events"]
+    );
+
+    let collection_name = unique_collection_name("tpl_posts");
+    let response = send(
+        "POST",
+        "/api/collections/from-template/blog_posts".to_string(),
+        admin_token.clone(),
+        Some(json!({ "name": collection_name, "include_sample_records": true })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created = read_json(response).await;
+    assert_eq!(created["data"]["collection"]["display_name"], "Blog posts");
+    assert_eq!(created["data"]["sample_records_created"], 2);
+    assert_eq!(
+        created["data"]["permissions_applied"],
+        json!(["user", "guest"])
+    );
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let records = read_json(response).await;
+    assert_eq!(records["data"].as_array().unwrap().len(), 2);
+
+    let template_id = format!("tpl_{}", uuid::Uuid::new_v4().simple());
+    let response = send(
+        "POST",
+        format!("/api/collections/{}/save-as-template", collection_name),
+        admin_token.clone(),
+        Some(json!({
+            "template_id": template_id,
+            "sample_records": [{ "title": "Invalid slug", "slug": "Not A Slug", "content": "x" }]
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        "POST",
+        format!("/api/collections/{}/save-as-template", collection_name),
+        admin_token.clone(),
+        Some(json!({
+            "template_id": template_id,
+            "name": "Team posts",
+            "sample_records": [{ "title": "Kickoff", "slug": "kickoff", "content": "<p>Hi</p>" }]
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let saved = read_json(response).await;
+    assert_eq!(saved["data"]["built_in"], false);
+    assert_eq!(saved["data"]["name"], "Team posts");
+    let guest_permission = saved["data"]["permissions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|permission| permission["role_name"] == "guest")
+        .unwrap();
+    assert_eq!(guest_permission["can_read"], true);
+    assert_eq!(guest_permission["can_create"], false);
+
+    let response = send(
+        "POST",
+        format!("/api/collections/{}/save-as-template", collection_name),
+        admin_token.clone(),
+        Some(json!({ "template_id": template_id })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let copy_name = unique_collection_name("tpl_copy");
+    let response = send(
+        "POST",
+        format!("/api/collections/from-template/{}", template_id),
+        admin_token.clone(),
+        Some(json!({ "name": copy_name, "include_sample_records": true })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let copy = read_json(response).await;
+    assert_eq!(copy["data"]["collection"]["display_name"], "Team posts");
+    assert_eq!(copy["data"]["sample_records_created"], 1);
+
+    let response = send(
+        "DELETE",
+        "/api/collections/templates/blog_posts".to_string(),
+        admin_token.clone(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        "DELETE",
+        format!("/api/collections/templates/{}", template_id),
+        admin_token.clone(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send(
+        "POST",
+        format!("/api/collections/from-template/{}", template_id),
+        admin_token.clone(),
+        Some(json!({ "name": unique_collection_name("tpl_gone") })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let mut created_collections = vec![collection_name, copy_name];
+    for template_id in ["products", "contacts", "events"] {
+        let name = unique_collection_name(&format!("tpl_{}", template_id));
+        let response = send(
+            "POST",
+            format!("/api/collections/from-template/{}", template_id),
+            admin_token.clone(),
+            Some(json!({ "name": name, "include_sample_records": true })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED, "{}", template_id);
+        created_collections.push(name);
+    }
+
+    for name in created_collections {
+        let response = send(
+            "DELETE",
+            format!("/api/collections/{}?force=true", name),
+            admin_token.clone(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+}
+
+#[tokio::test]
+async fn test_orderable_collection_moves_records_and_renormalizes() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("ordered");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({
+            "name": collection_name,
+            "schema": create_test_schema(),
+            "orderable": true
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(read_json(response).await["data"]["orderable"], true);
+
+    let mut ids = Vec::new();
+    for title in ["A", "B", "C"] {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary,
+            json!({ "title": title }),
+            boundary
+        );
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/collections/{}/records", collection_name))
+                    .method("POST")
+                    .header(
+                        "content-type",
+                        format!("multipart/form-data; boundary={}", boundary),
+                    )
+                    .header("authorization", format!("Bearer {}", token))
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let record = read_json(response).await;
+        assert!(record["data"]["data"]["sort_order"].is_number());
+        ids.push(record["data"]["id"].as_str().unwrap().to_string());
+    }
+
+    let list_titles = || async {
+        let response = send(
+            "GET",
+            format!("/api/collections/{}/records", collection_name),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        read_json(response).await["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|record| record["data"]["title"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    let move_record = |id: &str, body: Value| {
+        send(
+            "POST",
+            format!("/api/collections/{}/records/{}/move", collection_name, id),
+            Some(body),
+        )
+    };
+
+    assert_eq!(list_titles().await, vec!["A", "B", "C"]);
+
+    let response = move_record(&ids[2], json!({ "before": ids[0].parse::<i32>().unwrap() }))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(list_titles().await, vec!["C", "A", "B"]);
+
+    let response = move_record(&ids[0], json!({ "after": ids[1].parse::<i32>().unwrap() }))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(list_titles().await, vec!["C", "B", "A"]);
+
+    let response = move_record(&ids[0], json!({ "before": 1, "after": 2 }))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Alternately squeezing A and B right after C halves the gap on every move
+    // until the positions are renumbered.
+    let anchor = ids[2].parse::<i32>().unwrap();
+    let mut sort_orders = Vec::new();
+    for step in 0..40 {
+        let id = if step % 2 == 0 { &ids[0] } else { &ids[1] };
+        let response = move_record(id, json!({ "after": anchor })).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        sort_orders.push(
+            read_json(response).await["data"]["data"]["sort_order"]
+                .as_f64()
+                .unwrap(),
+        );
+    }
+    assert!(sort_orders.contains(&1536.0));
+    assert_eq!(list_titles().await, vec!["C", "B", "A"]);
+
+    let sorted = send(
+        "GET",
+        format!(
+            "/api/collections/{}/records?sort=-sort_order",
+            collection_name
+        ),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(sorted.status(), StatusCode::OK);
+
+    let plain_name = unique_collection_name("unordered");
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({ "name": plain_name, "schema": create_test_schema() })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = send(
+        "POST",
+        format!("/api/collections/{}/records/1/move", plain_name),
+        Some(json!({ "before": 2 })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        "POST",
+        format!("/api/admin/collections/{}/verify", collection_name),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["data"]["healthy"], true);
+}
+
+#[tokio::test]
+async fn test_draft_publish_workflow_hides_unpublished_records_from_readers() {
+    use diesel::RunQueryDsl;
+
+    let app_state = create_test_app_state().await;
+    let app = create_test_router_for(app_state.clone());
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("articles");
+
+    let send = |method: &'static str, uri: String, token: Option<&str>, body: Option<Value>| {
+        let mut request = Request::builder().uri(uri).method(method);
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {}", token));
+        }
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let list_titles = |query: &'static str, token: Option<&str>| {
+        let uri = format!("/api/collections/{}/records{}", collection_name, query);
+        let response = send("GET", uri, token, None);
+        async move {
+            let response = response.await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let mut titles = read_json(response).await["data"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|record| record["data"]["title"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>();
+            titles.sort();
+            titles
+        }
+    };
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(&token),
+        Some(json!({
+            "name": collection_name,
+            "schema": create_test_schema(),
+            "workflow": "draft_publish"
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(
+        read_json(response).await["data"]["workflow"],
+        "draft_publish"
+    );
+
+    let mut ids = Vec::new();
+    for title in ["Draft", "Live", "Scheduled"] {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary,
+            json!({ "title": title }),
+            boundary
+        );
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/collections/{}/records", collection_name))
+                    .method("POST")
+                    .header(
+                        "content-type",
+                        format!("multipart/form-data; boundary={}", boundary),
+                    )
+                    .header("authorization", format!("Bearer {}", token))
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let record = read_json(response).await;
+        assert_eq!(record["data"]["data"]["status"], "draft");
+        ids.push(record["data"]["id"].as_str().unwrap().to_string());
+    }
+
+    let record_uri = |id: &str, action: &str| {
+        format!(
+            "/api/collections/{}/records/{}{}",
+            collection_name, id, action
+        )
+    };
+
+    let response = send("POST", record_uri(&ids[1], "/publish"), Some(&token), None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record = read_json(response).await;
+    assert_eq!(record["data"]["data"]["status"], "published");
+    assert!(record["data"]["data"]["publish_at"].is_null());
+
+    let response = send(
+        "POST",
+        record_uri(&ids[2], "/publish"),
+        Some(&token),
+        Some(json!({ "publish_at": "2999-01-01T00:00:00Z" })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record = read_json(response).await;
+    assert_eq!(record["data"]["data"]["status"], "draft");
+    assert_eq!(record["data"]["data"]["publish_at"], "2999-01-01 00:00:00");
+
+    // Readers only see the published record, editors see everything
+    assert_eq!(list_titles("", None).await, vec!["Live"]);
+    let token = token.as_str();
+    assert_eq!(
+        list_titles("", Some(token)).await,
+        vec!["Draft", "Live", "Scheduled"]
+    );
+    assert_eq!(
+        list_titles("?status=draft", Some(token)).await,
+        vec!["Draft", "Scheduled"]
+    );
+    assert_eq!(
+        list_titles("?filter=status:eq:published", Some(token)).await,
+        vec!["Live"]
+    );
+
+    let response = send(
+        "GET",
+        format!("/api/collections/{}/records?status=draft", collection_name),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send("GET", record_uri(&ids[0], ""), None, None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = send("GET", record_uri(&ids[1], ""), None, None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send(
+        "POST",
+        record_uri(&ids[1], "/unpublish"),
+        Some(token),
+        Some(json!({ "archive": true })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        read_json(response).await["data"]["data"]["status"],
+        "archived"
+    );
+    assert!(list_titles("", None).await.is_empty());
+    assert_eq!(
+        list_titles("?status=archived", Some(token)).await,
+        vec!["Live"]
+    );
+
+    // The background task publishes scheduled drafts once their time has passed
+    diesel::sql_query(format!(
+        "UPDATE records_{} SET publish_at = '2000-01-01 00:00:00' WHERE id = {}",
+        collection_name, ids[2]
+    ))
+    .execute(&mut app_state.db_pool.get().unwrap())
+    .unwrap();
+    let published = app_state
+        .collection_service
+        .publish_due_records()
+        .await
+        .unwrap();
+    assert!(published >= 1);
+    assert_eq!(list_titles("", None).await, vec!["Scheduled"]);
+
+    let plain_name = unique_collection_name("plain");
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(token),
+        Some(json!({ "name": plain_name, "schema": create_test_schema() })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = send(
+        "POST",
+        format!("/api/collections/{}/records/1/publish", plain_name),
+        Some(token),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        "POST",
+        format!("/api/admin/collections/{}/verify", collection_name),
+        Some(token),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["data"]["healthy"], true);
+}
+
+#[tokio::test]
+async fn test_geopoint_fields_store_and_filter_by_location() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("places");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let create_place = |data: Value| {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary, data, boundary
+        );
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+
+    let schema = json!({
+        "fields": [
+            { "name": "title", "field_type": "text", "required": true },
+            { "name": "location", "field_type": "geopoint", "required": true }
+        ]
+    });
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({ "name": collection_name, "schema": schema })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let places = [
+        ("Warsaw", 52.2297, 21.0122),
+        ("Berlin", 52.52, 13.405),
+        ("Suva", -18.1416, 178.4419),
+        ("Apia", -13.8333, -171.7667),
+    ];
+    for (title, lat, lng) in places {
+        let response =
+            create_place(json!({ "title": title, "location": { "lat": lat, "lng": lng } }))
+                .await
+                .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let record = read_json(response).await;
+        assert_eq!(
+            record["data"]["data"]["location"],
+            json!({ "lat": lat, "lng": lng })
+        );
+    }
+
+    for invalid in [
+        json!({ "lat": 95.0, "lng": 10.0 }),
+        json!({ "lat": 10.0, "lng": -181.0 }),
+        json!({ "lat": "north" }),
+    ] {
+        let response = create_place(json!({ "title": "Nowhere", "location": invalid }))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let list_titles = |filter: &'static str| {
+        let send = &send;
+        let read_json = &read_json;
+        let collection_name = &collection_name;
+        async move {
+            let response = send(
+                "GET",
+                format!(
+                    "/api/collections/{}/records?sort=title&filter={}",
+                    collection_name, filter
+                ),
+                None,
+            )
+            .await
+            .unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "filter {}", filter);
+            read_json(response).await["data"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|record| record["data"]["title"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(
+        list_titles("location:near:52.23,21.01,100").await,
+        vec!["Warsaw"]
+    );
+    assert_eq!(
+        list_titles("location:near:52.23,21.01,600").await,
+        vec!["Berlin", "Warsaw"]
+    );
+    assert_eq!(
+        list_titles("location:bbox:50,10,55,25,title:ne:Berlin").await,
+        vec!["Warsaw"]
+    );
+    assert_eq!(
+        list_titles("location:bbox:-20,170,-10,-170").await,
+        vec!["Apia", "Suva"]
+    );
+
+    for rejected in [
+        "filter=location:eq:1",
+        "filter=title:near:52,21,10",
+        "filter=location:bbox:55,10,50,25",
+        "filter=location:near:52,21",
+        "sort=location",
+    ] {
+        let response = send(
+            "GET",
+            format!("/api/collections/{}/records?{}", collection_name, rejected),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", rejected);
+    }
+
+    let response = send(
+        "PUT",
+        format!("/api/collections/{}", collection_name),
+        Some(json!({
+            "schema": {
+                "fields": [
+                    { "name": "title", "field_type": "text", "required": true },
+                    { "name": "location", "field_type": "geopoint", "required": true },
+                    { "name": "venue", "field_type": "geopoint", "required": true },
+                    { "name": "venue_lat", "field_type": "number", "required": false }
+                ]
+            }
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        "PUT",
+        format!("/api/collections/{}", collection_name),
+        Some(json!({
+            "schema": {
+                "fields": [
+                    { "name": "title", "field_type": "text", "required": true },
+                    { "name": "location", "field_type": "geopoint", "required": true },
+                    { "name": "venue", "field_type": "geopoint", "required": true }
+                ]
+            }
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send(
+        "PUT",
+        format!("/api/collections/{}", collection_name),
+        Some(json!({
+            "schema": {
+                "fields": [
+                    { "name": "title", "field_type": "text", "required": true },
+                    { "name": "venue", "field_type": "geopoint", "required": true }
+                ]
+            }
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert_eq!(
+        list_titles("venue:bbox:-1,-1,1,1").await,
+        vec!["Apia", "Berlin", "Suva", "Warsaw"]
+    );
+
+    let response = send(
+        "POST",
+        format!("/api/admin/collections/{}/verify", collection_name),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["data"]["healthy"], true);
+}
+
+#[tokio::test]
+async fn test_decimal_fields_are_exact_and_filter_numerically() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("prices");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let create_item = |data: Value| {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary, data, boundary
+        );
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+
+    let schema = |scale: u32| {
+        json!({
+            "fields": [
+                { "name": "title", "field_type": "text", "required": true },
+                {
+                    "name": "price",
+                    "field_type": "decimal",
+                    "required": true,
+                    "validation": { "precision": 10, "scale": scale }
+                }
+            ]
+        })
+    };
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({ "name": collection_name, "schema": schema(2) })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    for (title, price, stored) in [
+        ("Gum", json!(0.1), "0.10"),
+        ("Tea", json!("0.20"), "0.20"),
+        ("Book", json!("19.99"), "19.99"),
+        ("Lamp", json!(9.5), "9.50"),
+    ] {
+        let response = create_item(json!({ "title": title, "price": price }))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(read_json(response).await["data"]["data"]["price"], stored);
+    }
+
+    for invalid in [
+        json!("19.999"),
+        json!("123456789.00"),
+        json!("ten"),
+        json!(true),
+    ] {
+        let response = create_item(json!({ "title": "Invalid", "price": invalid }))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let list_titles = |query: &'static str| {
+        let send = &send;
+        let read_json = &read_json;
+        let collection_name = &collection_name;
+        async move {
+            let response = send(
+                "GET",
+                format!("/api/collections/{}/records?{}", collection_name, query),
+                None,
+            )
+            .await
+            .unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{}", query);
+            read_json(response).await["data"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|record| record["data"]["title"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(
+        list_titles("sort=price").await,
+        vec!["Gum", "Tea", "Lamp", "Book"]
+    );
+    // "19.99" > "9.5" is false as text, true as a number
+    assert_eq!(
+        list_titles("sort=price&filter=price:gt:9.5").await,
+        vec!["Book"]
+    );
+    assert_eq!(
+        list_titles("sort=price&filter=price:in:0.1,19.990").await,
+        vec!["Gum", "Book"]
+    );
+    assert_eq!(
+        list_titles("filter=price:eq:0.30").await,
+        Vec::<String>::new()
+    );
+
+    let response = send(
+        "GET",
+        format!(
+            "/api/collections/{}/records?filter=price:like:19",
+            collection_name
+        ),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        "PUT",
+        format!("/api/collections/{}", collection_name),
+        Some(json!({ "schema": schema(3) })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_uuid_collections_use_string_record_ids() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("tickets");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let write_record = |method: &'static str, uri: String, data: Value| {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary, data, boundary
+        );
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .method(method)
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+
+    let schema = json!({
+        "fields": [{ "name": "title", "field_type": "text", "required": true }]
+    });
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({ "name": collection_name, "schema": schema, "id_type": "uuid" })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(read_json(response).await["data"]["id_type"], "uuid");
+
+    let records_uri = format!("/api/collections/{}/records", collection_name);
+    let mut ids = Vec::new();
+    for title in ["First", "Second"] {
+        let response = write_record("POST", records_uri.clone(), json!({ "title": title }))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let record = read_json(response).await;
+        assert_eq!(record["data"]["data"]["title"], title);
+        ids.push(record["data"]["id"].as_str().unwrap().to_string());
+    }
+    let first = uuid::Uuid::parse_str(&ids[0]).unwrap();
+    assert_eq!(first.get_version_num(), 7);
+    assert_ne!(ids[0], ids[1]);
+
+    let response = send("GET", records_uri.clone(), None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut listed: Vec<String> = read_json(response).await["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|record| record["id"].as_str().unwrap().to_string())
+        .collect();
+    listed.sort();
+    let mut created = ids.clone();
+    created.sort();
+    assert_eq!(listed, created);
+
+    let response = send(
+        "GET",
+        format!("{}/{}", records_uri, ids[0].to_uppercase()),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["data"]["id"], ids[0]);
+
+    let response = send("GET", format!("{}/1", records_uri), None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = write_record(
+        "PUT",
+        format!("{}/{}", records_uri, ids[1]),
+        json!({ "title": "Renamed" }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record = read_json(response).await;
+    assert_eq!(record["data"]["id"], ids[1]);
+    assert_eq!(record["data"]["data"]["title"], "Renamed");
+
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        Some(json!({
+            "operations": [
+                { "method": "update", "collection": collection_name, "record_id": ids[0], "data": { "title": "Batched" } }
+            ]
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send("DELETE", format!("{}/{}", records_uri, ids[0]), None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = send("GET", format!("{}/{}", records_uri, ids[0]), None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = send(
+        "PUT",
+        format!("/api/collections/{}", collection_name),
+        Some(json!({ "id_type": "integer" })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        "POST",
+        format!("/api/admin/collections/{}/verify", collection_name),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["data"]["healthy"], true);
+
+    // Integer collections keep their numeric ids and reject anything else
+    let integer_name = unique_collection_name("counters");
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({ "name": integer_name, "schema": schema })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(read_json(response).await["data"]["id_type"], "integer");
+
+    let integer_uri = format!("/api/collections/{}/records", integer_name);
+    let response = write_record("POST", integer_uri.clone(), json!({ "title": "One" }))
+        .await
+        .unwrap();
+    let id = read_json(response).await["data"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(id.parse::<i64>().is_ok());
+
+    let response = send("GET", format!("{}/{}", integer_uri, id), None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = send("GET", format!("{}/{}", integer_uri, ids[1]), None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_slug_fields_are_unique_immutable_and_addressable() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("articles");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let write_record = |method: &'static str, uri: String, data: Value| {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary, data, boundary
+        );
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .method(method)
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({
+            "name": unique_collection_name("broken"),
+            "schema": { "fields": [{ "name": "slug", "field_type": "slug", "required": true }] }
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({
+            "name": collection_name,
+            "schema": {
+                "fields": [
+                    { "name": "title", "field_type": "text", "required": true },
+                    {
+                        "name": "slug",
+                        "field_type": "slug",
+                        "required": true,
+                        "validation": { "source_field": "title" }
+                    }
+                ]
+            }
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let records_uri = format!("/api/collections/{}/records", collection_name);
+    let mut ids = Vec::new();
+    for (data, slug) in [
+        (json!({ "title": "Zażółć gęślą jaźń" }), "zazolc-gesla-jazn"),
+        (
+            json!({ "title": "Zazolc Gesla Jazn!" }),
+            "zazolc-gesla-jazn-2",
+        ),
+        (
+            json!({ "title": "Third", "slug": "Zazolc gesla jazn" }),
+            "zazolc-gesla-jazn-3",
+        ),
+    ] {
+        let response = write_record("POST", records_uri.clone(), data)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let record = read_json(response).await;
+        assert_eq!(record["data"]["data"]["slug"], slug);
+        ids.push(record["data"]["id"].as_str().unwrap().to_string());
+    }
+
+    let response = write_record(
+        "PUT",
+        format!("{}/{}", records_uri, ids[1]),
+        json!({ "title": "Renamed", "slug": "hand-written" }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record = read_json(response).await;
+    assert_eq!(record["data"]["data"]["title"], "Renamed");
+    assert_eq!(record["data"]["data"]["slug"], "zazolc-gesla-jazn-2");
+
+    let response = write_record(
+        "PUT",
+        format!("{}/{}?regenerate_slug=true", records_uri, ids[1]),
+        json!({ "title": "Renamed again" }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        read_json(response).await["data"]["data"]["slug"],
+        "renamed-again"
+    );
+
+    let response = send(
+        "GET",
+        format!("{}/by/slug/zazolc-gesla-jazn-3", records_uri),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record = read_json(response).await;
+    assert_eq!(record["data"]["id"], ids[2]);
+    assert_eq!(record["data"]["data"]["title"], "Third");
+
+    let response = send("GET", format!("{}/by/slug/missing", records_uri), None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = send("GET", format!("{}/by/title/Third", records_uri), None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_global_search_groups_matches_by_accessible_collection() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let role = format!(
+        "searcher_{}",
+        &uuid::Uuid::new_v4().simple().to_string()[0..8]
+    );
+    let (_user_id, user_token) = create_test_user(&app, &role).await;
+    let term = format!("needle{}", &uuid::Uuid::new_v4().simple().to_string()[0..8]);
+
+    let send = |method: &'static str, uri: String, token: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let create_item = |collection: String, data: Value| {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary, data, boundary
+        );
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+
+    let posts = unique_collection_name("search_posts");
+    let docs = unique_collection_name("search_docs");
+    for name in [&posts, &docs] {
+        let response = send(
+            "POST",
+            "/api/collections".to_string(),
+            token.clone(),
+            Some(json!({ "name": name, "schema": create_test_schema() })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let long_prefix = "lorem ipsum ".repeat(10);
+    for (collection, data) in [
+        (
+            &posts,
+            json!({ "title": "Unrelated", "content": format!("{}{} tail", long_prefix, term) }),
+        ),
+        (
+            &posts,
+            json!({ "title": "Nothing to see", "content": "plain" }),
+        ),
+        (
+            &docs,
+            json!({ "title": format!("{} guide", term.to_uppercase()) }),
+        ),
+    ] {
+        let response = create_item(collection.clone(), data).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let response = send(
+        "GET",
+        format!("/api/search?q={}&collections={},{}", term, posts, docs),
+        token.clone(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let groups = read_json(response).await["data"]
+        .as_array()
+        .unwrap()
+        .clone();
+    assert_eq!(groups.len(), 2);
+
+    let post_group = groups
+        .iter()
+        .find(|g| g["collection_name"] == *posts)
+        .unwrap();
+    let post_hits = post_group["results"].as_array().unwrap();
+    assert_eq!(post_hits.len(), 1);
+    assert_eq!(post_hits[0]["field"], "content");
+    let snippet = post_hits[0]["snippet"].as_str().unwrap();
+    assert!(snippet.starts_with('…'));
+    assert!(snippet.contains(&term));
+    assert!(snippet.ends_with("tail"));
+
+    let doc_group = groups
+        .iter()
+        .find(|g| g["collection_name"] == *docs)
+        .unwrap();
+    assert_eq!(doc_group["results"][0]["field"], "title");
+
+    let response = send(
+        "GET",
+        format!("/api/search?q={}&collections={}", term, docs),
+        token.clone(),
+        None,
+    )
+    .await
+    .unwrap();
+    let groups = read_json(response).await["data"]
+        .as_array()
+        .unwrap()
+        .clone();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0]["collection_name"], *docs);
+
+    // Collections the caller cannot list are left out rather than rejected
+    let response = send("GET", format!("/api/search?q={}", term), user_token, None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["data"], json!([]));
+
+    let response = send("GET", "/api/search?q=%20".to_string(), token.clone(), None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_queries_over_complexity_budget_are_rejected() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("budget");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({ "name": collection_name, "schema": create_test_schema() })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let heavy_filter = (0..20)
+        .map(|i| format!("title:like:term{}", i))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    for path in ["records", "records/count"] {
+        let response = send(
+            "GET",
+            format!(
+                "/api/collections/{}/{}?filter={}",
+                collection_name, path, heavy_filter
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "query_too_complex");
+        assert!(
+            json["details"]
+                .as_str()
+                .unwrap()
+                .contains("Query complexity 60 exceeds the budget of 50")
+        );
+
+        let response = send(
+            "GET",
+            format!(
+                "/api/collections/{}/{}?filter=title:like:term&search=term",
+                collection_name, path
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn test_list_collections_paginates_searches_and_sorts() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let term = format!(
+        "listing{}",
+        &uuid::Uuid::new_v4().simple().to_string()[0..8]
+    );
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    // The third collection only matches through its description
+    for (name, description) in [
+        (format!("{}_b", term), None),
+        (format!("{}_a", term), None),
+        (
+            unique_collection_name("zz_described"),
+            Some(format!("Mentions {}", term.to_uppercase())),
+        ),
+    ] {
+        let response = send(
+            "POST",
+            "/api/collections".to_string(),
+            Some(json!({
+                "name": name,
+                "description": description,
+                "schema": create_test_schema()
+            })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let response = send(
+        "GET",
+        format!("/api/collections?search={}&sort=name:asc&limit=2", term),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let page = read_json(response).await["data"].clone();
+    assert_eq!(page["pagination"]["total_count"], 3);
+    assert_eq!(page["pagination"]["total_pages"], 2);
+    let collections = page["collections"].as_array().unwrap();
+    assert_eq!(collections.len(), 2);
+    assert_eq!(collections[0]["name"], format!("{}_a", term));
+    assert_eq!(collections[1]["name"], format!("{}_b", term));
+    assert!(collections[0]["schema"].is_object());
+
+    let response = send(
+        "GET",
+        format!(
+            "/api/collections?search={}&sort=name:asc&limit=2&offset=2&fields=summary",
+            term
+        ),
+        None,
+    )
+    .await
+    .unwrap();
+    let page = read_json(response).await["data"].clone();
+    assert_eq!(page["pagination"]["current_page"], 2);
+    let summary = &page["collections"][0];
+    assert!(
+        summary["name"]
+            .as_str()
+            .unwrap()
+            .starts_with("zz_described_")
+    );
+    assert!(summary.get("schema").is_none());
+    assert_eq!(summary["schema_version"], 1);
+
+    let response = send(
+        "GET",
+        format!(
+            "/api/collections?search={}&sort=name:desc&fields=names",
+            term
+        ),
+        None,
+    )
+    .await
+    .unwrap();
+    let page = read_json(response).await["data"].clone();
+    assert_eq!(
+        page["collections"][1],
+        json!({ "name": format!("{}_b", term), "display_name": null })
+    );
+    assert_eq!(page["collections"].as_array().unwrap().len(), 3);
+
+    let response = send("GET", "/api/collections?sort=schema_json".to_string(), None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_admins_can_create_records_with_explicit_ids() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let (_user_id, user_token) = create_test_user(&app, "user").await;
+
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let create_collection = |name: String, allow_explicit_ids: bool| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri("/api/collections")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::from(
+                    json!({
+                        "name": name,
+                        "schema": create_test_schema(),
+                        "allow_explicit_ids": allow_explicit_ids,
+                        "permissions": [{
+                            "role_name": "user",
+                            "can_create": true,
+                            "can_read": true,
+                            "can_update": false,
+                            "can_delete": false,
+                            "can_list": true
+                        }]
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+    };
+    let create_record = |collection_name: String, token: String, data: Value| {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary, data, boundary
+        );
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+
+    let migrated = unique_collection_name("migrated");
+    let response = create_collection(migrated.clone(), true).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(
+        read_json(response).await["data"]["allow_explicit_ids"],
+        true
+    );
+
+    let response = create_record(
+        migrated.clone(),
+        admin_token.clone(),
+        json!({ "id": 500, "title": "Imported" }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(read_json(response).await["data"]["id"], "500");
+
+    let response = create_record(
+        migrated.clone(),
+        admin_token.clone(),
+        json!({ "id": "500", "title": "Duplicate" }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Automatic ids continue after the highest explicit one
+    let response = create_record(
+        migrated.clone(),
+        admin_token.clone(),
+        json!({ "title": "Native" }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let native_id: i64 = read_json(response).await["data"]["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(native_id > 500);
+
+    let response = create_record(
+        migrated.clone(),
+        user_token.clone(),
+        json!({ "id": 900, "title": "Not allowed" }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let regular = unique_collection_name("regular_ids");
+    let response = create_collection(regular.clone(), false).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = create_record(
+        regular,
+        admin_token.clone(),
+        json!({ "id": 7, "title": "Not allowed" }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_validate_record_reports_every_field_error_without_writing() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let (_user_id, user_token) = create_test_user(&app, "user").await;
+    let collection_name = unique_collection_name("validated");
+
+    let send = |uri: String, token: &str, body: Value| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    // Users may update but not create records
+    let response = send(
+        "/api/collections".to_string(),
+        &admin_token,
+        json!({
+            "name": collection_name,
+            "schema": create_test_schema(),
+            "permissions": [{
+                "role_name": "user",
+                "can_create": false,
+                "can_read": true,
+                "can_update": true,
+                "can_delete": false,
+                "can_list": true
+            }]
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let validate_uri = format!("/api/collections/{}/records/validate", collection_name);
+    let response = send(
+        validate_uri.clone(),
+        &admin_token,
+        json!({ "data": { "content": 5, "views": -1, "published": true } }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let result = read_json(response).await;
+    assert_eq!(result["data"]["valid"], false);
+    let errors: Vec<(String, String)> = result["data"]["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| {
+            (
+                error["field"].as_str().unwrap().to_string(),
+                error["code"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            ("title".to_string(), "required".to_string()),
+            ("content".to_string(), "invalid_type".to_string()),
+            ("views".to_string(), "too_small".to_string()),
+        ]
+    );
+
+    let response = send(
+        validate_uri.clone(),
+        &admin_token,
+        json!({ "data": { "title": "Fine" } }),
+    )
+    .await
+    .unwrap();
+    let result = read_json(response).await;
+    assert_eq!(result["data"]["valid"], true);
+    assert_eq!(result["data"]["errors"], json!([]));
+
+    let response = send(
+        validate_uri.clone(),
+        &user_token,
+        json!({ "data": { "title": "Fine" } }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send(
+        validate_uri,
+        &user_token,
+        json!({ "data": { "title": "Fine" }, "record_id": 1 }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(read_json(response).await["data"], json!([]));
+}
+
+#[tokio::test]
+async fn test_error_responses_carry_stable_codes() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let (_user_id, user_token) = create_test_user(&app, "user").await;
+    let collection_name = unique_collection_name("coded_errors");
+
+    let send = |uri: String, token: &str, body: Value| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+    };
+    let create_record = |token: &str, data: Value| {
+        let body = format!(
+            "--boundary\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--boundary--\r\n",
+            data
+        );
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("POST")
+                .header("content-type", "multipart/form-data; boundary=boundary")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let response = send(
+        "/api/collections".to_string(),
+        &admin_token,
+        json!({
+            "name": collection_name,
+            "schema": create_test_schema(),
+            "permissions": [{
+                "role_name": "user",
+                "can_create": false,
+                "can_read": true,
+                "can_update": false,
+                "can_delete": false,
+                "can_list": true
+            }]
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = create_record(&admin_token, json!({ "content": 5 }))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let error = read_json(response).await;
+    assert_eq!(error["success"], false);
+    assert_eq!(error["code"], "validation_failed");
+    assert_eq!(error["error"], "Validation failed");
+    assert_eq!(error["fields"]["title"][0]["code"], "required");
+    assert_eq!(error["fields"]["content"][0]["code"], "invalid_type");
+
+    let response = create_record(&user_token, json!({ "title": "Denied" }))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let error = read_json(response).await;
+    assert_eq!(error["code"], "permission_denied.record_create");
+    assert!(error.get("fields").is_none());
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/collections/{}/records/999999",
+                    collection_name
+                ))
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(read_json(response).await["code"], "record_not_found");
+}
+
+#[tokio::test]
+async fn test_messages_follow_accept_language_but_codes_do_not() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("localized");
+
+    let send = |uri: String, language: &str, body: Value| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("accept-language", language)
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let response = send(
+        "/api/collections".to_string(),
+        "en",
+        json!({ "name": collection_name, "schema": create_test_schema() }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let validate_uri = format!("/api/collections/{}/records/validate", collection_name);
+    let response = send(
+        validate_uri.clone(),
+        "de-DE,de;q=0.9,en;q=0.8",
+        json!({ "data": {} }),
+    )
+    .await
+    .unwrap();
+    let result = read_json(response).await;
+    assert_eq!(result["data"]["errors"][0]["code"], "required");
+    assert_eq!(
+        result["data"]["errors"][0]["message"],
+        "Das Feld 'title' ist erforderlich"
+    );
+
+    let response = send(validate_uri.clone(), "fr", json!({ "data": {} }))
+        .await
+        .unwrap();
+    let result = read_json(response).await;
+    assert_eq!(
+        result["data"]["errors"][0]["message"],
+        "Field 'title' is required"
+    );
+
+    let response = send(
+        "/api/batch".to_string(),
+        "pl",
+        json!({ "operations": [{
+            "method": "create",
+            "collection": collection_name,
+            "data": { "title": "Ok" }
+        }, {
+            "method": "create",
+            "collection": collection_name,
+            "data": {}
+        }] }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let error = read_json(response).await;
+    assert_eq!(error["code"], "validation_failed");
+    assert_eq!(error["error"], "Walidacja nie powiodła się");
+    let field_error = &error["fields"]["operations.1.title"][0];
+    assert_eq!(field_error["code"], "required");
+    assert_eq!(
+        field_error["message"],
+        "Operacja 1: Pole 'title' jest wymagane"
+    );
+}
+
+#[tokio::test]
+async fn test_record_share_links_expose_a_single_record_read_only() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let (_owner_id, owner_token) = create_test_user(&app, "user").await;
+    let (_other_id, other_token) = create_test_user(&app, "user").await;
+    let collection_name = unique_collection_name("shared_records");
+
+    let send = |method: &str, uri: String, token: &str, body: Value| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .method(method)
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+    };
+    let open_share = |token: &str, password: Option<&str>| {
+        let mut request = Request::builder()
+            .uri(format!("/api/share/{}", token))
+            .method("GET");
+        if let Some(password) = password {
+            request = request.header("x-share-password", password);
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap())
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        &admin_token,
+        json!({
+            "name": collection_name,
+            "schema": create_test_schema(),
+            "permissions": [{
+                "role_name": "user",
+                "can_create": false,
+                "can_read": false,
+                "can_update": false,
+                "can_delete": false,
+                "can_list": false
+            }]
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = format!(
+        "--boundary\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--boundary--\r\n",
+        json!({ "title": "Quarterly report", "content": "Internal notes" })
+    );
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("POST")
+                .header("content-type", "multipart/form-data; boundary=boundary")
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let record_id = read_json(response).await["data"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let share_uri = format!(
+        "/api/collections/{}/records/{}/share",
+        collection_name, record_id
+    );
+
+    // Sharing requires read access to the record
+    let response = send("POST", share_uri.clone(), &owner_token, json!({}))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send(
+        "POST",
+        share_uri.clone(),
+        &admin_token,
+        json!({ "fields": ["missing"] }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        "POST",
+        share_uri,
+        &admin_token,
+        json!({
+            "expires_in_hours": 2,
+            "password": "open sesame",
+            "max_views": 2,
+            "fields": ["title"]
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let share = read_json(response).await;
+    let share_id = share["data"]["id"].as_i64().unwrap();
+    let share_token = share["data"]["token"].as_str().unwrap().to_string();
+    assert_eq!(share["data"]["url"], format!("/api/share/{}", share_token));
+    assert_eq!(share["data"]["password_protected"], true);
+
+    let response = open_share(&share_token, None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(read_json(response).await["code"], "share_password_invalid");
+
+    let response = open_share(&share_token, Some("wrong")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = open_share(&share_token, Some("open sesame")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let shared = read_json(response).await;
+    assert_eq!(shared["data"]["collection_name"], collection_name);
+    assert_eq!(shared["data"]["views_remaining"], 1);
+    let data = &shared["data"]["record"]["data"];
+    assert_eq!(data["title"], "Quarterly report");
+    assert!(data.get("content").is_none());
+    assert!(data.get("owner_id").is_none());
+
+    // The signature covers the whole token, so editing it breaks the link
+    let last = if share_token.ends_with('0') { '1' } else { '0' };
+    let tampered = format!("{}{}", &share_token[..share_token.len() - 1], last);
+    let response = open_share(&tampered, Some("open sesame")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = open_share(&share_token, Some("open sesame")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["data"]["views_remaining"], 0);
+
+    let response = open_share(&share_token, Some("open sesame")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::GONE);
+    assert_eq!(read_json(response).await["code"], "share_link_expired");
+
+    let response = send("GET", "/api/shares".to_string(), &admin_token, json!({}))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let shares = read_json(response).await;
+    let listed = shares["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|share| share["id"] == share_id)
+        .expect("share is listed for its creator");
+    assert_eq!(listed["view_count"], 2);
+    assert!(listed.get("token").is_none());
+
+    let response = send(
+        "DELETE",
+        format!("/api/shares/{}", share_id),
+        &other_token,
+        json!({}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = send(
+        "DELETE",
+        format!("/api/shares/{}", share_id),
+        &admin_token,
+        json!({}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = open_share(&share_token, Some("open sesame")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// An app with guest sessions enabled for `collections`, each of which the
+/// guest role may create records in.
+async fn create_guest_session_app(collections: &[&str], max_per_ip: u32) -> (Router, AppState) {
+    use lunarbase::models::SetCollectionPermissionRequest;
+
+    let app_state = create_test_app_state().await;
+    let app = create_test_router_for(app_state.clone());
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+
+    let guest_role = app_state
+        .permission_service
+        .get_role_by_name("guest")
+        .await
+        .unwrap();
+    for name in collections {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/collections")
+                    .method("POST")
+                    .header("content-type", "application/json")
+                    .header("authorization", format!("Bearer {}", admin_token))
+                    .body(Body::from(
+                        json!({
+                            "name": name,
+                            "display_name": "Feedback",
+                            "schema": create_test_schema()
+                        })
+                        .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let collection = app_state
+            .collection_service
+            .get_collection(name)
+            .await
+            .unwrap();
+        app_state
+            .permission_service
+            .set_collection_permission(
+                collection.id,
+                guest_role.id,
+                &SetCollectionPermissionRequest {
+                    role_name: "guest".to_string(),
+                    can_create: true,
+                    can_read: false,
+                    can_update: false,
+                    can_delete: false,
+                    can_list: false,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let config = &app_state.configuration_manager;
+    config
+        .update_cache("auth", "guest_sessions_enabled", "true")
+        .await;
+    config
+        .update_cache("auth", "guest_session_max_per_ip", &max_per_ip.to_string())
+        .await;
+    (app, app_state)
+}
+
+async fn start_guest_session(app: &Router, client_ip: &str, payload: Value) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/auth/guest-session")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("x-forwarded-for", client_ip)
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+async fn create_guest_record(
+    app: &Router,
+    token: &str,
+    collection_name: &str,
+    data: Value,
+) -> (StatusCode, Value) {
+    let boundary = "boundary";
+    let body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+        boundary, data, boundary
+    );
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_guest_sessions_only_create_ownerless_records_in_guest_collections() {
+    use diesel::prelude::*;
+    use lunarbase::models::GuestSessionRecord;
+    use lunarbase::schema::guest_session_records;
+
+    let feedback = unique_collection_name("guest_feedback");
+    let survey = unique_collection_name("guest_survey");
+    let closed = unique_collection_name("guest_closed");
+    let (app, app_state) = create_guest_session_app(&[&feedback, &survey, &closed], 10).await;
+
+    let (status, json) = start_guest_session(&app, "203.0.113.10", json!({})).await;
+    assert_eq!(
+        status,
+        StatusCode::FORBIDDEN,
+        "no collection is whitelisted"
+    );
+    assert_eq!(json["code"], "forbidden");
+
+    app_state
+        .configuration_manager
+        .update_cache(
+            "auth",
+            "guest_session_collections",
+            &json!([feedback, survey]).to_string(),
+        )
+        .await;
+
+    let (status, _) =
+        start_guest_session(&app, "203.0.113.10", json!({ "collection": closed })).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, json) =
+        start_guest_session(&app, "203.0.113.10", json!({ "collection": feedback })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["data"]["collections"], json!([feedback]));
+    assert_eq!(json["data"]["expires_in"], 15 * 60);
+    let token = json["data"]["access_token"].as_str().unwrap().to_string();
+    let session_id = json["data"]["session_id"].as_str().unwrap().to_string();
+
+    let (status, json) = create_guest_record(
+        &app,
+        &token,
+        &feedback,
+        json!({ "title": "Great form", "email": "guest@example.com", "owner_id": 1 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(json["data"]["data"]["title"], "Great form");
+    assert!(json["data"]["data"]["owner_id"].is_null());
+    let record_id = json["data"]["id"].as_str().unwrap().to_string();
+
+    let mut conn = app_state.db_pool.get().unwrap();
+    let recorded: Vec<GuestSessionRecord> = guest_session_records::table
+        .filter(guest_session_records::session_id.eq(&session_id))
+        .select(GuestSessionRecord::as_select())
+        .load(&mut conn)
+        .unwrap();
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(recorded[0].collection_name, feedback);
+    assert_eq!(recorded[0].record_id, record_id);
+
+    // Whitelisted, but the session was started for another collection
+    let (status, _) = create_guest_record(
+        &app,
+        &token,
+        &survey,
+        json!({ "title": "Elsewhere", "email": "guest@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, json) = start_guest_session(&app, "203.0.113.10", json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["data"]["collections"], json!([feedback, survey]));
+    let unrestricted = json["data"]["access_token"].as_str().unwrap().to_string();
+
+    let (status, _) = create_guest_record(
+        &app,
+        &unrestricted,
+        &survey,
+        json!({ "title": "Survey answer", "email": "guest@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, _) = create_guest_record(
+        &app,
+        &unrestricted,
+        &closed,
+        json!({ "title": "Not open", "email": "guest@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = create_guest_record(
+        &app,
+        &unrestricted,
+        &feedback,
+        json!({ "id": 999999, "title": "Chosen id", "email": "guest@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Only record creation accepts guest tokens
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records/count", feedback))
+                .method("GET")
+                .header("authorization", format!("Bearer {}", unrestricted))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Turning guest sessions off also retires the tokens already issued
+    app_state
+        .configuration_manager
+        .update_cache("auth", "guest_sessions_enabled", "false")
+        .await;
+    let (status, _) = start_guest_session(&app, "203.0.113.10", json!({})).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = create_guest_record(
+        &app,
+        &unrestricted,
+        &survey,
+        json!({ "title": "Too late", "email": "guest@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_guest_sessions_are_capped_per_client_ip() {
+    let feedback = unique_collection_name("guest_capped");
+    let (app, app_state) = create_guest_session_app(&[&feedback], 2).await;
+    app_state
+        .configuration_manager
+        .update_cache(
+            "auth",
+            "guest_session_collections",
+            &json!([feedback]).to_string(),
+        )
+        .await;
+
+    for _ in 0..2 {
+        let (status, _) = start_guest_session(&app, "198.51.100.20", json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, json) = start_guest_session(&app, "198.51.100.20", json!({})).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(json["code"], "rate_limit_exceeded");
+
+    let (status, _) = start_guest_session(&app, "198.51.100.21", json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_guest_sessions_warn_before_the_per_ip_cap() {
+    let feedback = unique_collection_name("guest_warned");
+    let (app, app_state) = create_guest_session_app(&[&feedback], 5).await;
+    let config = &app_state.configuration_manager;
+    config
+        .update_cache(
+            "auth",
+            "guest_session_collections",
+            &json!([feedback]).to_string(),
+        )
+        .await;
+    config
+        .update_cache("system", "quota_warning_percent", "80")
+        .await;
+
+    let mut warnings = Vec::new();
+    for _ in 0..5 {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/auth/guest-session")
+                    .method("POST")
+                    .header("content-type", "application/json")
+                    .header("x-forwarded-for", "198.51.100.30")
+                    .body(Body::from("{}"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        warnings.push(
+            response
+                .headers()
+                .get("x-lunarbase-warning")
+                .map(|value| value.to_str().unwrap().to_string()),
+        );
+    }
+
+    assert_eq!(warnings[..3], [None, None, None]);
+    assert_eq!(
+        warnings[3].as_deref(),
+        Some("guest_sessions; used=4; limit=5; threshold=80%")
+    );
+    assert_eq!(
+        warnings[4].as_deref(),
+        Some("guest_sessions; used=5; limit=5; threshold=80%")
+    );
+
+    config
+        .update_cache("system", "quota_warning_percent", "0")
+        .await;
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/auth/guest-session")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("x-forwarded-for", "198.51.100.31")
+                .body(Body::from("{}"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert!(response.headers().get("x-lunarbase-warning").is_none());
+}
+
+#[tokio::test]
+async fn test_record_schedule_publishes_and_expires_due_records() {
+    let app_state = create_test_app_state().await;
+    let app = create_test_router_for(app_state.clone());
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("events");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let create_event = |collection: &str, data: Value| {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary, data, boundary
+        );
+        let response = app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body))
+                .unwrap(),
+        );
+        async move {
+            let response = response.await.unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+            read_json(response).await["data"]["id"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        }
+    };
+
+    let schema = json!({
+        "fields": [
+            { "name": "title", "field_type": "text", "required": true },
+            { "name": "go_live", "field_type": "date", "required": false },
+            { "name": "ends", "field_type": "date", "required": false }
+        ]
+    });
+
+    // Schedule fields must be dates, and archiving needs the workflow
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({
+            "name": collection_name,
+            "schema": schema,
+            "workflow": "draft_publish",
+            "schedule": { "publish_at_field": "title" }
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({
+            "name": collection_name,
+            "schema": schema,
+            "schedule": { "expire_at_field": "ends" }
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({
+            "name": collection_name,
+            "schema": schema,
+            "workflow": "draft_publish",
+            "schedule": { "publish_at_field": "go_live", "expire_at_field": "ends" }
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(
+        read_json(response).await["data"]["schedule"],
+        json!({ "publish_at_field": "go_live", "expire_at_field": "ends", "expire_action": "archive" })
+    );
+
+    let running = create_event(
+        &collection_name,
+        json!({ "title": "Running", "go_live": "2000-01-01", "ends": "2999-01-01" }),
+    )
+    .await;
+    let over = create_event(
+        &collection_name,
+        json!({ "title": "Over", "go_live": "2000-01-01", "ends": "2000-01-02" }),
+    )
+    .await;
+    let upcoming = create_event(
+        &collection_name,
+        json!({ "title": "Upcoming", "go_live": "2999-01-01" }),
+    )
+    .await;
+
+    let schedule_uri = format!("/api/admin/collections/{}/schedule", collection_name);
+    let response = send("GET", schedule_uri.clone(), None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report = read_json(response).await["data"].clone();
+    assert!(report["checked_at"].is_null());
+    assert_eq!(report["pending"].as_array().unwrap().len(), 5);
+
+    // The first run catches up on everything that fell due before it
+    let applied = app_state
+        .collection_service
+        .run_record_schedules()
+        .await
+        .unwrap();
+    assert!(applied >= 3);
+
+    let status_of = |id: &str| {
+        let response = send(
+            "GET",
+            format!("/api/collections/{}/records/{}", collection_name, id),
+            None,
+        );
+        async move {
+            let response = response.await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            read_json(response).await["data"]["data"]["status"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        }
+    };
+    assert_eq!(status_of(&running).await, "published");
+    assert_eq!(status_of(&over).await, "archived");
+    assert_eq!(status_of(&upcoming).await, "draft");
+
+    let response = send("GET", schedule_uri, None).await.unwrap();
+    let report = read_json(response).await["data"].clone();
+    assert!(report["checked_at"].is_string());
+    let pending = report["pending"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|operation| {
+            (
+                operation["record_id"].as_str().unwrap().to_string(),
+                operation["action"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pending,
+        vec![
+            (upcoming.clone(), "publish".to_string()),
+            (running.clone(), "archive".to_string()),
+        ]
+    );
+
+    // Without the workflow, expired records can be deleted instead
+    let plain_name = unique_collection_name("notices");
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({ "name": plain_name, "schema": schema })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let expired = create_event(&plain_name, json!({ "title": "Old", "ends": "2000-01-01" })).await;
+    let current = create_event(&plain_name, json!({ "title": "New", "ends": "2999-01-01" })).await;
+    let response = send(
+        "PUT",
+        format!("/api/collections/{}", plain_name),
+        Some(json!({ "schedule": { "expire_at_field": "ends", "expire_action": "delete" } })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    app_state
+        .collection_service
+        .run_record_schedules()
+        .await
+        .unwrap();
+    let record_status = |id: &str| {
+        let response = send(
+            "GET",
+            format!("/api/collections/{}/records/{}", plain_name, id),
+            None,
+        );
+        async move { response.await.unwrap().status() }
+    };
+    assert_eq!(record_status(&expired).await, StatusCode::NOT_FOUND);
+    assert_eq!(record_status(&current).await, StatusCode::OK);
+
+    let response = send(
+        "GET",
+        format!("/api/admin/collections/{}/schedule", plain_name),
+        None,
+    )
+    .await
+    .unwrap();
+    let report = read_json(response).await["data"].clone();
+    assert_eq!(report["pending"][0]["record_id"], current.as_str());
+    assert_eq!(report["pending"][0]["action"], "delete");
+}
+
+#[tokio::test]
+async fn test_retention_policy_deletes_records_past_their_age_in_batches() {
+    let app_state = create_test_app_state().await;
+    let app = create_test_router_for(app_state.clone());
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("logs");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let schema = json!({
+        "fields": [
+            { "name": "message", "field_type": "text", "required": true },
+            { "name": "logged_at", "field_type": "date", "required": false }
+        ]
+    });
+
+    // Retention fields must be dates, and archiving needs the workflow
+    for retention in [
+        json!({ "field": "message", "max_age_days": 90, "action": "delete" }),
+        json!({ "field": "logged_at", "max_age_days": 90, "action": "archive" }),
+    ] {
+        let response = send(
+            "POST",
+            "/api/collections".to_string(),
+            Some(json!({ "name": collection_name, "schema": schema, "retention": retention })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let policy = json!({ "field": "logged_at", "max_age_days": 90, "action": "delete" });
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({ "name": collection_name, "schema": schema, "retention": policy })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(read_json(response).await["data"]["retention"], policy);
+
+    let mut operations = Vec::new();
+    for logged_at in ["2000-01-01", "2000-01-02", "2000-01-03", "2999-01-01"] {
+        operations.push(json!({
+            "method": "create",
+            "collection": collection_name,
+            "data": { "message": format!("Logged {}", logged_at), "logged_at": logged_at }
+        }));
+    }
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        Some(json!({ "operations": operations })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let retention_uri = format!("/api/admin/collections/{}/retention", collection_name);
+    let response = send("GET", retention_uri.clone(), None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report = read_json(response).await["data"].clone();
+    assert_eq!(report["due"], 3);
+    assert_eq!(report["runs"], json!([]));
+
+    // Two records per batch: the run still gets through all three due ones
+    app_state
+        .configuration_manager
+        .update_cache("database", "retention_batch_size", "2")
+        .await;
+    app_state
+        .collection_service
+        .run_retention_policies()
+        .await
+        .unwrap();
+
+    let response = send(
+        "GET",
+        format!("/api/collections/{}/records", collection_name),
+        None,
+    )
+    .await
+    .unwrap();
+    let records = read_json(response).await["data"].clone();
+    assert_eq!(records.as_array().unwrap().len(), 1);
+    assert_eq!(records[0]["data"]["logged_at"], "2999-01-01");
+
+    let response = send("GET", retention_uri.clone(), None).await.unwrap();
+    let report = read_json(response).await["data"].clone();
+    assert_eq!(report["due"], 0);
+    assert_eq!(report["runs"][0]["action"], "delete");
+    assert_eq!(report["runs"][0]["processed"], 3);
+    assert_eq!(report["runs"][0]["failed"], 0);
+    assert_eq!(report["runs"][0]["complete"], true);
+    assert!(report["runs"][0]["finished_at"].is_string());
+
+    // max_age_days 0 turns retention off
+    let response = send(
+        "PUT",
+        format!("/api/collections/{}", collection_name),
+        Some(
+            json!({ "retention": { "field": "logged_at", "max_age_days": 0, "action": "delete" } }),
+        ),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(read_json(response).await["data"]["retention"].is_null());
+}
+
+#[tokio::test]
+async fn test_collection_stats_use_maintained_record_counts() {
+    use diesel::RunQueryDsl;
+
+    let app_state = create_test_app_state().await;
+    let app = create_test_router_for(app_state.clone());
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("counted");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let record_count = |fresh: bool| {
+        let response = send(
+            "GET",
+            format!("/api/collections/stats?fresh={}", fresh),
+            None,
+        );
+        let collection_name = collection_name.clone();
+        async move {
+            let response = response.await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let stats = read_json(response).await["data"].clone();
+            assert!(stats["record_counts_reconciled_at"][&collection_name].is_string());
+            assert!(stats["oldest_reconciled_at"].is_string());
+            stats["records_per_collection"][&collection_name].clone()
+        }
+    };
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({ "name": collection_name, "schema": create_test_schema() })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(record_count(false).await, 0);
+
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        Some(json!({
+            "operations": [
+                { "method": "create", "collection": collection_name, "data": { "title": "First" } },
+                { "method": "create", "collection": collection_name, "data": { "title": "Second" } }
+            ]
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let results = read_json(response).await["data"]["results"].clone();
+    assert_eq!(record_count(false).await, 2);
+
+    let response = send(
+        "DELETE",
+        format!(
+            "/api/collections/{}/records/{}",
+            collection_name,
+            results[0]["record"]["id"].as_str().unwrap()
+        ),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(record_count(false).await, 1);
+
+    // Rows written behind the service's back only show up once reconciled
+    diesel::sql_query(format!(
+        "INSERT INTO records_{} (title) VALUES ('Imported')",
+        collection_name
+    ))
+    .execute(&mut app_state.db_pool.get().unwrap())
+    .unwrap();
+    assert_eq!(record_count(false).await, 1);
+    assert_eq!(record_count(true).await, 2);
+    assert_eq!(record_count(false).await, 2);
+}
+
+#[tokio::test]
+async fn test_record_analytics_count_changes_per_collection() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("tracked");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({ "name": collection_name, "schema": create_test_schema() })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        Some(json!({
+            "operations": [
+                { "method": "create", "collection": collection_name, "data": { "title": "First" } },
+                { "method": "create", "collection": collection_name, "data": { "title": "Second" } }
+            ]
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let results = read_json(response).await["data"]["results"].clone();
+    let record_id = |index: usize| results[index]["record"]["id"].as_str().unwrap().to_string();
+
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        Some(json!({
+            "operations": [{
+                "method": "update",
+                "collection": collection_name,
+                "record_id": record_id(0),
+                "data": { "title": "Renamed" }
+            }]
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send(
+        "DELETE",
+        format!(
+            "/api/collections/{}/records/{}",
+            collection_name,
+            record_id(1)
+        ),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = send(
+        "GET",
+        format!(
+            "/api/admin/analytics/records?collection={}&window=7d&granularity=day",
+            collection_name
+        ),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let analytics = read_json(response).await["data"].clone();
+    assert_eq!(analytics["series"].as_array().unwrap().len(), 7);
+    assert_eq!(analytics["series"][6]["created"], 2);
+    assert_eq!(analytics["series"][6]["updated"], 1);
+    assert_eq!(analytics["series"][6]["deleted"], 1);
+    assert_eq!(analytics["totals"]["created"], 2);
+
+    for query in [
+        "window=0d",
+        "window=30",
+        "window=366d",
+        "granularity=month",
+        "window=3d&granularity=week",
+    ] {
+        let response = send(
+            "GET",
+            format!("/api/admin/analytics/records?{}", query),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", query);
+    }
+
+    let response = send(
+        "GET",
+        "/api/admin/analytics/records?collection=missing_collection".to_string(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_multi_field_sort_puts_nulls_last_across_pages() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("scores");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({
+            "name": collection_name,
+            "schema": {
+                "fields": [
+                    { "name": "title", "field_type": "text", "required": true },
+                    { "name": "score", "field_type": "number", "required": false },
+                    { "name": "metadata", "field_type": "json", "required": false }
+                ]
+            }
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let operations = [
+        ("E", None),
+        ("B", Some(5)),
+        ("D", None),
+        ("C", Some(9)),
+        ("A", Some(5)),
+        ("F", Some(1)),
+    ]
+    .into_iter()
+    .map(|(title, score)| {
+        let mut data = json!({ "title": title });
+        if let Some(score) = score {
+            data["score"] = json!(score);
+        }
+        json!({ "method": "create", "collection": collection_name, "data": data })
+    })
+    .collect::<Vec<_>>();
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        Some(json!({ "operations": operations })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let page = |sort: &'static str, offset: i64| {
+        let response = send(
+            "GET",
+            format!(
+                "/api/collections/{}/records?sort={}&limit=2&offset={}",
+                collection_name, sort, offset
+            ),
+            None,
+        );
+        async move {
+            let response = response.await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            read_json(response).await["data"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|record| record["data"]["title"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    // Pages stitch together without gaps or repeats, ties broken by the
+    // second key and unscored records last in both directions
+    for (sort, expected) in [
+        ("-score,title", ["C", "A", "B", "F", "D", "E"]),
+        ("score,-title", ["F", "B", "A", "C", "E", "D"]),
+    ] {
+        let mut titles = Vec::new();
+        for offset in [0, 2, 4] {
+            titles.extend(page(sort, offset).await);
+        }
+        assert_eq!(titles, expected, "sort={}", sort);
+    }
+
+    for sort in ["metadata", "-score,score"] {
+        let response = send(
+            "GET",
+            format!("/api/collections/{}/records?sort={}", collection_name, sort),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "sort={}", sort);
+    }
+}
+
+#[tokio::test]
+async fn test_json_path_filters_and_indexed_paths() {
+    use diesel::RunQueryDsl;
+
+    let app_state = create_test_app_state().await;
+    let app = create_test_router_for(app_state.clone());
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let collection_name = unique_collection_name("imports");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let schema = |indexed_paths: Value| {
+        json!({
+            "fields": [
+                { "name": "title", "field_type": "text", "required": true },
+                {
+                    "name": "metadata",
+                    "field_type": "json",
+                    "required": false,
+                    "indexed_paths": indexed_paths
+                }
+            ]
+        })
+    };
+    let filter_titles = |filter: &'static str| {
+        let response = send(
+            "GET",
+            format!(
+                "/api/collections/{}/records?filter={}&sort=title",
+                collection_name, filter
+            ),
+            None,
+        );
+        async move {
+            let response = response.await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "filter={}", filter);
+            read_json(response).await["data"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|record| record["data"]["title"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+    let index_names = || {
+        #[derive(diesel::QueryableByName)]
+        struct IndexName {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            name: String,
+        }
+        diesel::sql_query(format!(
+            "SELECT name FROM pragma_index_list('records_{}') ORDER BY name",
+            collection_name
+        ))
+        .load::<IndexName>(&mut app_state.db_pool.get().unwrap())
+        .unwrap()
+        .into_iter()
+        .map(|index| index.name)
+        .collect::<Vec<_>>()
+    };
+
+    // Only json fields can index paths, and only well-formed ones
+    for (name, invalid_schema) in [
+        (
+            "text",
+            json!({ "fields": [{ "name": "title", "field_type": "text", "required": true, "indexed_paths": ["source"] }] }),
+        ),
+        ("path", schema(json!(["source..kind"]))),
+    ] {
+        let response = send(
+            "POST",
+            "/api/collections".to_string(),
+            Some(json!({ "name": collection_name, "schema": invalid_schema })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", name);
+    }
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({ "name": collection_name, "schema": schema(json!(["source"])) })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let source_index = format!("idx_records_{0}_metadata__source", collection_name);
+    assert!(index_names().contains(&source_index));
+
+    let operations = [
+        (
+            "A",
+            json!({ "source": "import", "priority": 3, "tags": ["urgent", "bulk"] }),
+        ),
+        (
+            "B",
+            json!({ "source": "manual", "priority": 1, "tags": ["urgent"] }),
+        ),
+        (
+            "C",
+            json!({ "source": "import", "priority": 1, "tags": [] }),
+        ),
+        ("D", json!({ "priority": 5 })),
+    ]
+    .into_iter()
+    .map(|(title, metadata)| {
+        json!({
+            "method": "create",
+            "collection": collection_name,
+            "data": { "title": title, "metadata": metadata }
+        })
+    })
+    .collect::<Vec<_>>();
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        Some(json!({ "operations": operations })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert_eq!(
+        filter_titles("metadata.source:eq:import").await,
+        vec!["A", "C"]
+    );
+    assert_eq!(
+        filter_titles("metadata.priority:gte:3").await,
+        vec!["A", "D"]
+    );
+    assert_eq!(
+        filter_titles("metadata.tags:contains:urgent").await,
+        vec!["A", "B"]
+    );
+    assert_eq!(filter_titles("metadata.source:isnull").await, vec!["D"]);
+    assert_eq!(
+        filter_titles("metadata.tags.0:eq:urgent,metadata.source:ne:manual").await,
+        vec!["A"]
+    );
+
+    for filter in [
+        "metadata..source:eq:import",
+        "title.source:eq:import",
+        "title:contains:A",
+    ] {
+        let response = send(
+            "GET",
+            format!(
+                "/api/collections/{}/records?filter={}",
+                collection_name, filter
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            response.status(),
+            StatusCode::BAD_REQUEST,
+            "filter={}",
+            filter
+        );
+    }
+
+    // Indexed paths can be added to and removed from an existing collection
+    let response = send(
+        "PUT",
+        format!("/api/collections/{}", collection_name),
+        Some(json!({ "schema": schema(json!(["source", "priority"])) })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let priority_index = format!("idx_records_{0}_metadata__priority", collection_name);
+    assert!(index_names().contains(&priority_index));
+    assert_eq!(
+        filter_titles("metadata.priority:gte:3").await,
+        vec!["A", "D"]
+    );
+
+    let response = send(
+        "PUT",
+        format!("/api/collections/{}", collection_name),
+        Some(json!({ "schema": schema(json!(["priority"])) })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let indexes = index_names();
+    assert!(indexes.contains(&priority_index));
+    assert!(!indexes.contains(&source_index));
+    assert_eq!(
+        filter_titles("metadata.source:eq:import").await,
+        vec!["A", "C"]
+    );
+}
+
+#[tokio::test]
+async fn test_record_references_list_referencing_records() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let categories = unique_collection_name("ref_categories");
+    let products = unique_collection_name("ref_products");
+    let reviews = unique_collection_name("ref_reviews");
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    for (name, fields) in [
+        (
+            &categories,
+            json!([{ "name": "title", "field_type": "text", "required": true }]),
+        ),
+        (
+            &products,
+            json!([
+                { "name": "title", "field_type": "text", "required": true },
+                { "name": "category", "field_type": "relation", "required": false, "relation_target": categories },
+                { "name": "alt_category", "field_type": "relation", "required": false, "relation_target": categories }
+            ]),
+        ),
+        (
+            &reviews,
+            json!([
+                { "name": "title", "field_type": "text", "required": true },
+                { "name": "category", "field_type": "relation", "required": false, "relation_target": categories }
+            ]),
+        ),
+    ] {
+        let response = send(
+            "POST",
+            "/api/collections".to_string(),
+            Some(json!({ "name": name, "schema": { "fields": fields } })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let create = |collection: &str, data: Value| json!({ "method": "create", "collection": collection, "data": data });
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        Some(json!({ "operations": [
+            create(&categories, json!({ "title": "Books" })),
+            create(&categories, json!({ "title": "Games" })),
+        ] })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let results = read_json(response).await["data"]["results"].clone();
+    let record_id = |result: &Value| match &result["record"]["id"] {
+        Value::String(id) => id.clone(),
+        id => id.to_string(),
+    };
+    let books = record_id(&results[0]);
+    let games = record_id(&results[1]);
+
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        Some(json!({ "operations": [
+            create(&products, json!({ "title": "Novel", "category": books })),
+            create(&products, json!({ "title": "Atlas", "alt_category": books })),
+            create(&products, json!({ "title": "Chess", "category": games })),
+            create(&products, json!({ "title": "Diary", "category": books })),
+            create(&reviews, json!({ "title": "Great shelf", "category": books })),
+        ] })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let results = read_json(response).await["data"]["results"].clone();
+    let product_ids: Vec<String> = [0, 1, 3].iter().map(|&i| record_id(&results[i])).collect();
+
+    let references = |record: &str, query: &str| {
+        send(
+            "GET",
+            format!(
+                "/api/collections/{}/records/{}/references{}",
+                categories, record, query
+            ),
+            None,
+        )
+    };
+
+    let response = references(&books, "").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let data = read_json(response).await["data"].clone();
+    assert_eq!(data["record_id"], json!(books));
+    assert_eq!(data["total_count"], 4);
+    let referenced_by = data["referenced_by"].as_array().unwrap();
+    assert_eq!(referenced_by.len(), 2);
+    let group = |name: &str| {
+        referenced_by
+            .iter()
+            .find(|group| group["collection_name"] == name)
+            .unwrap()
+            .clone()
+    };
+    let product_group = group(&products);
+    assert_eq!(product_group["count"], 3);
+    assert_eq!(product_group["fields"], json!(["category", "alt_category"]));
+    let mut listed: Vec<String> = product_group["record_ids"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|id| id.as_str().unwrap().to_string())
+        .collect();
+    listed.sort();
+    let mut expected = product_ids.clone();
+    expected.sort();
+    assert_eq!(listed, expected);
+    assert_eq!(group(&reviews)["count"], 1);
+
+    let response = references(&books, "?limit=1").await.unwrap();
+    let data = read_json(response).await["data"].clone();
+    assert_eq!(data["total_count"], 4);
+    for group in data["referenced_by"].as_array().unwrap() {
+        assert_eq!(group["record_ids"].as_array().unwrap().len(), 1);
+    }
+
+    let response = references(&games, "").await.unwrap();
+    let data = read_json(response).await["data"].clone();
+    assert_eq!(data["total_count"], 1);
+    assert_eq!(data["referenced_by"][0]["collection_name"], json!(products));
+
+    let response = references("999999", "").await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_export_manifest_reports_changed_missing_and_added_records() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let (_user_id, user_token) = create_test_user(&app, "user").await;
+    let collection_name = unique_collection_name("manifested");
+    let records_uri = format!("/api/collections/{}/records", collection_name);
+
+    let send = |method: &'static str, uri: String, token: &str, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        &token,
+        Some(json!({ "name": collection_name, "schema": create_test_schema() })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        &token,
+        Some(json!({
+            "operations": ["First", "Second", "Third"]
+                .iter()
+                .map(|title| json!({ "method": "create", "collection": collection_name, "data": { "title": title } }))
+                .collect::<Vec<_>>()
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let export_uri = format!("{}/export", records_uri);
+    let verify_uri = format!("{}/verify", records_uri);
+    let response = send("GET", export_uri.clone(), &user_token, None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send("GET", export_uri.clone(), &token, None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let export = read_json(response).await["data"].clone();
+    let manifest = export["manifest"].clone();
+    assert_eq!(manifest["algorithm"], "sha256");
+    assert_eq!(manifest["canonicalization"], "lunarbase-canonical-json/1");
+    assert_eq!(manifest["record_count"], 3);
+    let ids: Vec<String> = export["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|record| record["id"].as_str().unwrap().to_string())
+        .collect();
+    let hashed_ids: Vec<&str> = manifest["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hash| hash["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(hashed_ids, ids);
+
+    // Exporting again yields the same hashes
+    let response = send("GET", export_uri, &token, None).await.unwrap();
+    let again = read_json(response).await["data"]["manifest"].clone();
+    assert_eq!(again["records"], manifest["records"]);
+    assert_eq!(again["digest"], manifest["digest"]);
+
+    let response = send("POST", verify_uri.clone(), &token, Some(manifest.clone()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let result = read_json(response).await["data"].clone();
+    assert_eq!(result["manifest_intact"], true);
+    assert_eq!(result["digest_matches"], true);
+    assert_eq!(result["unchanged"], 3);
+    assert_eq!(result["changed"], json!([]));
+    assert_eq!(result["missing"], json!([]));
+    assert_eq!(result["added"], json!([]));
+
+    let response = send(
+        "DELETE",
+        format!("{}/{}", records_uri, ids[1]),
+        &token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = send(
+        "POST",
+        "/api/batch".to_string(),
+        &token,
+        Some(json!({
+            "operations": [
+                { "method": "update", "collection": collection_name, "record_id": ids[0], "data": { "title": "Edited" } },
+                { "method": "create", "collection": collection_name, "data": { "title": "Fourth" } }
+            ]
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send("POST", verify_uri.clone(), &token, Some(manifest.clone()))
+        .await
+        .unwrap();
+    let result = read_json(response).await["data"].clone();
+    assert_eq!(result["manifest_intact"], true);
+    assert_eq!(result["digest_matches"], false);
+    assert_eq!(result["unchanged"], 1);
+    assert_eq!(result["changed"], json!([ids[0]]));
+    assert_eq!(result["missing"], json!([ids[1]]));
+    assert_eq!(result["added"].as_array().unwrap().len(), 1);
+
+    let mut tampered = manifest.clone();
+    tampered["records"][2]["sha256"] = json!("0".repeat(64));
+    let response = send("POST", verify_uri.clone(), &token, Some(tampered))
+        .await
+        .unwrap();
+    let result = read_json(response).await["data"].clone();
+    assert_eq!(result["manifest_intact"], false);
+    assert_eq!(result["changed"], json!([ids[0], ids[2]]));
+
+    let mut foreign = manifest;
+    foreign["canonicalization"] = json!("other/1");
+    let response = send("POST", verify_uri, &token, Some(foreign))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_role_quota_limits_records_created_per_day() {
+    use lunarbase::models::{CreateRoleRequest, SetCollectionPermissionRequest};
+
+    let app_state = create_test_app_state().await;
+    let app = create_test_router_for(app_state.clone());
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let role = format!("quota_{}", &uuid::Uuid::new_v4().simple().to_string()[0..8]);
+    let (_user_id, user_token) = create_test_user(&app, &role).await;
+
+    let role = app_state
+        .permission_service
+        .create_role(
+            &CreateRoleRequest {
+                name: role,
+                description: None,
+                priority: 10,
+                max_records_created_per_day: Some(2),
+                max_upload_bytes_per_day: None,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(role.max_records_created_per_day, Some(2));
+
+    let name = unique_collection_name("quota_records");
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/collections")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::from(
+                    json!({ "name": name, "schema": create_test_schema() }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let collection = app_state
+        .collection_service
+        .get_collection(&name)
+        .await
+        .unwrap();
+    app_state
+        .permission_service
+        .set_collection_permission(
+            collection.id,
+            role.id,
+            &SetCollectionPermissionRequest {
+                role_name: role.name.clone(),
+                can_create: true,
+                can_read: true,
+                can_update: false,
+                can_delete: false,
+                can_list: true,
+            },
+        )
+        .await
+        .unwrap();
+
+    let create_item = |title: &str| {
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary,
+            json!({ "title": title }),
+            boundary
+        );
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", name))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", user_token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+
+    for title in ["First", "Second"] {
+        let response = create_item(title).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+    let response = create_item("Third").await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error["code"], "usage_quota_exceeded");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/users/me/usage")
+                .header("authorization", format!("Bearer {}", user_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let usage: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(usage["data"]["role"], role.name);
+    assert_eq!(usage["data"]["last_24_hours"]["records_created"], 2);
+    assert_eq!(usage["data"]["last_30_days"]["records_created"], 2);
+    assert_eq!(usage["data"]["quotas"]["max_records_created_per_day"], 2);
+    assert_eq!(
+        usage["data"]["quotas"]["max_upload_bytes_per_day"],
+        Value::Null
+    );
+}
+
+#[tokio::test]
+async fn test_collection_health_reports_a_missing_trigger() {
+    use diesel::prelude::*;
+
+    let app_state = create_test_app_state().await;
+    let app = create_test_router_for(app_state.clone());
+    let (_admin_id, token) = create_admin_token(&app).await;
+    let (_user_id, user_token) = create_test_user(&app, "user").await;
+
+    let name = unique_collection_name("health_check");
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/collections")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(
+                    json!({ "name": name, "schema": create_test_schema() }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let check_health = |token: String| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/health", name))
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+    let read_report = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()["data"].clone()
+    };
+
+    let response = check_health(user_token).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = check_health(token.clone()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report = read_report(response).await;
+    assert_eq!(report["status"], "healthy");
+    assert_eq!(report["checks"]["integrity"]["status"], "healthy");
+    assert_eq!(report["checks"]["triggers"]["status"], "healthy");
+    assert_eq!(report["record_count"], 0);
+    assert_eq!(report["last_write_at"], Value::Null);
+
+    let table_name = report["table_name"].as_str().unwrap().to_string();
+    let mut conn = app_state.db_pool.get().unwrap();
+    diesel::sql_query(format!("DROP TRIGGER update_{}_updated_at", table_name))
+        .execute(&mut conn)
+        .unwrap();
+
+    let report = read_report(check_health(token).await.unwrap()).await;
+    assert_eq!(report["status"], "degraded");
+    assert_eq!(report["checks"]["triggers"]["status"], "degraded");
+    assert_eq!(report["checks"]["indexes"]["status"], "healthy");
 }
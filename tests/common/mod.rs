@@ -1,21 +1,64 @@
+use diesel_migrations::MigrationHarness;
 use lunarbase::Config;
 use lunarbase::cli::commands::serve::ServeArgs;
+use lunarbase::database::create_pool;
+use lunarbase::server::MIGRATIONS;
+use std::sync::OnceLock;
 
 pub fn create_test_serve_args() -> ServeArgs {
     ServeArgs {
         host: Some("127.0.0.1".to_string()),
+        bind: vec![],
+        uds: None,
+        uds_mode: None,
+        uds_owner: None,
+        no_http2: false,
+        http2_cleartext: false,
+        keep_alive_timeout: None,
+        max_concurrent_streams: None,
+        max_header_size_kb: None,
         config: None,
         api_only: false,
+        auto_migrate: true,
         enable_redirect: false,
+        redirect_port: None,
         acme: false,
         acme_domain: vec![],
+        acme_challenge: None,
         acme_email: None,
         acme_cache_dir: "./test_acme_cache".to_string(),
         acme_production: false,
     }
 }
 
+/// A migrated database in the temp directory, shared by the tests of one test
+/// binary, unless `DATABASE_URL` names one.
+fn test_database_url() -> String {
+    static DATABASE_URL: OnceLock<String> = OnceLock::new();
+
+    DATABASE_URL
+        .get_or_init(|| {
+            let path = std::env::temp_dir().join(format!(
+                "lunarbase_test_{}.sqlite",
+                uuid::Uuid::new_v4().simple()
+            ));
+            let database_url = path.to_str().unwrap().to_string();
+            create_pool(&database_url)
+                .expect("Failed to create test database")
+                .get()
+                .unwrap()
+                .run_pending_migrations(MIGRATIONS)
+                .expect("Failed to run migrations");
+            database_url
+        })
+        .clone()
+}
+
 pub fn create_test_config() -> Result<Config, Box<dyn std::error::Error>> {
     let serve_args = create_test_serve_args();
-    Config::from_env_with_args(Some(&serve_args))
+    let mut config = Config::from_env_with_args(Some(&serve_args))?;
+    if std::env::var("DATABASE_URL").is_err() {
+        config.database_url = test_database_url();
+    }
+    Ok(config)
 }
@@ -15,7 +15,11 @@ use lunarbase::AppState;
 use lunarbase::database::create_pool;
 use lunarbase::handlers::auth::*;
 use lunarbase::handlers::configuration::*;
-use lunarbase::middleware::auth_middleware;
+use lunarbase::handlers::health::{liveness_check, readiness_check};
+use lunarbase::handlers::users::{get_user, unlock_user, verify_user_email};
+use lunarbase::middleware::{auth_middleware, setup_cors};
+use lunarbase::models::TokenType;
+use lunarbase::services::configuration_manager::ConfigurationAccess;
 
 mod common;
 
@@ -40,7 +44,9 @@ async fn create_test_router() -> Router {
 
     let public_routes = Router::new()
         .route("/auth/register", post(register))
-        .route("/auth/login", post(login));
+        .route("/auth/login", post(login))
+        .route("/auth/resend-verification", post(resend_verification))
+        .route("/auth/forgot-password", post(forgot_password));
 
     let protected_routes = Router::new()
         .route("/admin/configuration", get(get_all_settings))
@@ -65,6 +71,12 @@ async fn create_test_router() -> Router {
             "/admin/configuration/{category}/{setting_key}/reset",
             post(reset_setting),
         )
+        .route("/users/{user_id}", get(get_user))
+        .route("/users/{user_id}/unlock", post(unlock_user))
+        .route(
+            "/admin/users/{user_id}/verify-email",
+            post(verify_user_email),
+        )
         .layer(middleware::from_fn_with_state(
             app_state.auth_state.clone(),
             auth_middleware,
@@ -142,6 +154,9 @@ fn create_token_for_user(user_id: i32, email: &str, role: &str) -> String {
         exp,
         iat: now,
         jti: uuid::Uuid::new_v4().to_string(),
+        guest: None,
+        workspace_id: None,
+        superadmin: false,
     };
 
     let jwt_secret = "test_secret".to_string();
@@ -504,3 +519,962 @@ async fn test_create_setting_duplicate_key() {
 
     cleanup_test_setting("database", &unique_key).await;
 }
+
+#[tokio::test]
+async fn test_readiness_fails_while_draining() {
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    {
+        let mut conn = db_pool.get().expect("Failed to get database connection");
+        conn.run_pending_migrations(MIGRATIONS)
+            .expect("Failed to run migrations");
+    }
+
+    let app_state = AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState");
+    let readiness = app_state.readiness.clone();
+
+    let app = Router::new()
+        .route("/api/health/live", get(liveness_check))
+        .route("/api/health/ready", get(readiness_check))
+        .with_state(app_state);
+
+    let probe = |uri: &'static str| {
+        app.clone().oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+
+    let live_response = probe("/api/health/live").await.unwrap();
+    assert_eq!(live_response.status(), StatusCode::OK);
+
+    let ready_response = probe("/api/health/ready").await.unwrap();
+    assert_eq!(ready_response.status(), StatusCode::OK);
+
+    readiness.mark_draining();
+
+    let draining_response = probe("/api/health/ready").await.unwrap();
+    assert_eq!(draining_response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = draining_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["status"], "not_ready");
+    assert_eq!(json["checks"]["shutdown"], "draining");
+
+    let live_response = probe("/api/health/live").await.unwrap();
+    assert_eq!(live_response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_cors_origins_apply_at_runtime_and_are_validated() {
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    {
+        let mut conn = db_pool.get().expect("Failed to get database connection");
+        conn.run_pending_migrations(MIGRATIONS)
+            .expect("Failed to run migrations");
+    }
+
+    let app_state = AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState");
+    let original_origins = app_state.get_cors_allowed_origins().await;
+    let cors_layer = setup_cors(&app_state).await;
+
+    let app = Router::new()
+        .route(
+            "/api/admin/configuration/{category}/{setting_key}",
+            put(update_setting).layer(middleware::from_fn_with_state(
+                app_state.auth_state.clone(),
+                auth_middleware,
+            )),
+        )
+        .route("/api/health/live", get(liveness_check))
+        .layer(cors_layer)
+        .with_state(app_state);
+    let (_admin_id, token) = create_admin_token(&app).await;
+
+    let put_origins = |origins: Value| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri("/api/admin/configuration/api/cors_allowed_origins")
+                .method("PUT")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(
+                    json!({ "setting_value": origins.to_string() }).to_string(),
+                ))
+                .unwrap(),
+        )
+    };
+    let preflight = |origin: &'static str| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri("/api/health/live")
+                .method("OPTIONS")
+                .header("origin", origin)
+                .header("access-control-request-method", "GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+
+    let response = preflight("https://app.example.com").await.unwrap();
+    assert!(
+        response
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none()
+    );
+
+    let response = put_origins(json!(["https://*.example.com", "http://localhost:5173"]))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut allowed_origin = None;
+    for _ in 0..50 {
+        let response = preflight("https://app.example.com").await.unwrap();
+        allowed_origin = response
+            .headers()
+            .get("access-control-allow-origin")
+            .map(|value| value.to_str().unwrap().to_string());
+        if allowed_origin.is_some() {
+            assert_eq!(
+                response.headers()["access-control-allow-credentials"],
+                "true"
+            );
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(allowed_origin.as_deref(), Some("https://app.example.com"));
+
+    let response = preflight("https://example.com").await.unwrap();
+    assert!(
+        response
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none()
+    );
+
+    // The OAuth providers' avatar hosts stay allowed whatever is configured
+    let response = preflight("https://avatars.githubusercontent.com")
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers()["access-control-allow-origin"],
+        "https://avatars.githubusercontent.com"
+    );
+
+    let response = put_origins(json!(["*"])).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = put_origins(json!(["https://example.com/app"]))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = put_origins(json!(original_origins)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_lockout_policy_settings_and_locked_accounts() {
+    use diesel::prelude::*;
+    use lunarbase::models::User;
+    use lunarbase::schema::users;
+    use lunarbase::services::FailedLoginOutcome;
+
+    let app = create_test_router().await;
+    let (admin_id, token) = create_admin_token(&app).await;
+    let (user_id, _user_token) = create_test_user(&app, "user").await;
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let app_state = AppState::new(
+        db_pool.clone(),
+        "test_secret",
+        "test_pepper".to_string(),
+        &config,
+    )
+    .await
+    .expect("Failed to create AppState");
+
+    let load_user = |id: i32| -> User {
+        users::table
+            .find(id)
+            .select(User::as_select())
+            .first(&mut db_pool.get().unwrap())
+            .unwrap()
+    };
+
+    let put_setting = |key: &'static str, value: String| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/admin/configuration/auth/{}", key))
+                .method("PUT")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(json!({ "setting_value": value }).to_string()))
+                .unwrap(),
+        )
+    };
+
+    let user_email = load_user(user_id).email;
+    let admin_email = load_user(admin_id).email;
+
+    let response = put_setting("lockout_exempt_admins", json!([user_email]).to_string())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = put_setting("lockout_exempt_admins", json!([admin_email]).to_string())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = put_setting("max_lockout_duration_minutes", "1".to_string())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = put_setting("expired_lock_cleanup_interval_seconds", "-5".to_string())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let max_attempts = app_state.get_max_login_attempts().await;
+    for id in [user_id, admin_id] {
+        diesel::update(users::table.find(id))
+            .set(users::failed_login_attempts.eq(max_attempts - 1))
+            .execute(&mut db_pool.get().unwrap())
+            .unwrap();
+    }
+
+    app_state
+        .configuration_manager
+        .reload_cache()
+        .await
+        .unwrap();
+    let outcome = app_state
+        .lockout_service
+        .record_failed_login(&load_user(admin_id))
+        .await
+        .unwrap();
+    assert_eq!(outcome, FailedLoginOutcome::Alerted);
+    assert!(load_user(admin_id).locked_until.is_none());
+
+    let outcome = app_state
+        .lockout_service
+        .record_failed_login(&load_user(user_id))
+        .await
+        .unwrap();
+    assert!(matches!(outcome, FailedLoginOutcome::Locked { .. }));
+    assert_eq!(load_user(user_id).lockout_count, 1);
+
+    let locked = app_state.lockout_service.list_locked_accounts().unwrap();
+    let entry = locked
+        .iter()
+        .find(|account| account.user_id == user_id)
+        .expect("Locked user missing from list");
+    assert!(entry.remaining_seconds > 0);
+
+    diesel::update(users::table.find(user_id))
+        .set(users::locked_until.eq(Some(
+            chrono::Utc::now().naive_utc() - chrono::Duration::minutes(1),
+        )))
+        .execute(&mut db_pool.get().unwrap())
+        .unwrap();
+    assert!(app_state.lockout_service.clear_expired_locks().unwrap() >= 1);
+
+    let user = load_user(user_id);
+    assert!(user.locked_until.is_none());
+    assert_eq!(user.failed_login_attempts, 0);
+
+    let response = put_setting("lockout_exempt_admins", "[]".to_string())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_email_verification_requirement_is_configurable() {
+    let app = create_test_router().await;
+    let (_admin_id, token) = create_admin_token(&app).await;
+
+    let post_json = |uri: &'static str, body: Value| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .method("POST")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+    };
+    let put_setting = |value: &'static str| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri("/api/admin/configuration/auth/require_email_verification")
+                .method("PUT")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(json!({ "setting_value": value }).to_string()))
+                .unwrap(),
+        )
+    };
+    let register = |username: String| {
+        post_json(
+            "/api/auth/register",
+            json!({
+                "email": format!("{}@test.com", username),
+                "password": "TestPassword123!",
+                "username": username
+            }),
+        )
+    };
+    let login = |username: &str| {
+        post_json(
+            "/api/auth/login",
+            json!({
+                "email": format!("{}@test.com", username),
+                "password": "TestPassword123!"
+            }),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let unverified = format!("reg_{}", &uuid::Uuid::new_v4().simple().to_string()[0..8]);
+    let response = register(unverified.clone()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(
+        read_json(response).await["data"]["user"]["is_verified"],
+        false
+    );
+
+    let response = login(&unverified).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(read_json(response).await["code"], "account_not_verified");
+
+    let response = put_setting("false").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = login(&unverified).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let auto_verified = format!("reg_{}", &uuid::Uuid::new_v4().simple().to_string()[0..8]);
+    let response = register(auto_verified.clone()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(
+        read_json(response).await["data"]["user"]["is_verified"],
+        true
+    );
+
+    let response = put_setting("true").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = login(&auto_verified).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_email_requests_are_rate_limited_per_address() {
+    let app = create_test_router().await;
+    let (user_id, _token) = create_test_user(&app, "user").await;
+
+    let request_email = |uri: &'static str, email: String| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .method("POST")
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "email": email }).to_string()))
+                .unwrap(),
+        )
+    };
+
+    // Unknown addresses count too, so a 429 says nothing about the account
+    let unknown = format!("nobody_{}@test.com", uuid::Uuid::new_v4().simple());
+    for _ in 0..3 {
+        let response = request_email("/api/auth/forgot-password", unknown.clone())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = request_email("/api/auth/forgot-password", unknown.to_uppercase())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error["code"], "rate_limit_exceeded");
+
+    // Each endpoint has its own budget
+    let response = request_email("/api/auth/resend-verification", unknown.clone())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let app_state = AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState");
+    let email = format!("user_{}@test.com", user_id);
+
+    let first = app_state
+        .email_service
+        .generate_verification_token(user_id, email.clone())
+        .await
+        .unwrap();
+    let reset = app_state
+        .email_service
+        .generate_password_reset_token(user_id, email.clone())
+        .await
+        .unwrap();
+    let latest = app_state
+        .email_service
+        .generate_verification_token(user_id, email)
+        .await
+        .unwrap();
+
+    assert!(app_state.email_service.verify_token(&first).await.is_err());
+    assert_eq!(
+        app_state.email_service.verify_token(&latest).await.unwrap(),
+        user_id
+    );
+    assert!(app_state.email_service.verify_token(&reset).await.is_err());
+    assert_eq!(
+        app_state
+            .email_service
+            .verify_token_with_type(&reset, TokenType::PasswordReset)
+            .await
+            .unwrap(),
+        user_id
+    );
+}
+
+#[tokio::test]
+async fn test_admin_can_verify_user_email() {
+    use diesel::prelude::*;
+    use lunarbase::schema::{permission_audit_entries, users, verification_tokens};
+
+    let app = create_test_router().await;
+    let (admin_id, admin_token) = create_admin_token(&app).await;
+    let (user_id, user_token) = create_test_user(&app, "user").await;
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    diesel::update(users::table.find(user_id))
+        .set(users::is_verified.eq(false))
+        .execute(&mut db_pool.get().unwrap())
+        .unwrap();
+
+    let app_state = AppState::new(
+        db_pool.clone(),
+        "test_secret",
+        "test_pepper".to_string(),
+        &config,
+    )
+    .await
+    .expect("Failed to create AppState");
+    let link_token = app_state
+        .email_service
+        .generate_verification_token(user_id, format!("user_{}@test.com", user_id))
+        .await
+        .unwrap();
+
+    let send = |method: &str, uri: String, token: &str| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(uri)
+                .method(method)
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let verify_uri = format!("/api/admin/users/{}/verify-email?notify=true", user_id);
+
+    let response = send("GET", format!("/api/users/{}", user_id), &admin_token)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let user = read_json(response).await;
+    assert_eq!(user["data"]["is_verified"], false);
+    assert!(user["data"]["verification_email_sent_at"].is_string());
+
+    let response = send("POST", verify_uri.clone(), &user_token).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send("POST", verify_uri.clone(), &admin_token)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let verified = read_json(response).await;
+    assert_eq!(verified["data"]["is_verified"], true);
+    // Sending is skipped while email is disabled, which is not a failure
+    assert_eq!(verified["data"]["activation_email_sent"], true);
+
+    let remaining: i64 = verification_tokens::table
+        .filter(verification_tokens::user_id.eq(user_id))
+        .count()
+        .get_result(&mut db_pool.get().unwrap())
+        .unwrap();
+    assert_eq!(remaining, 0);
+    let audited: Vec<Option<i32>> = permission_audit_entries::table
+        .filter(permission_audit_entries::action.eq("verify_user_email"))
+        .filter(permission_audit_entries::target_user_id.eq(user_id))
+        .select(permission_audit_entries::actor_id)
+        .load(&mut db_pool.get().unwrap())
+        .unwrap();
+    assert_eq!(audited, vec![Some(admin_id)]);
+    assert!(
+        app_state
+            .email_service
+            .verify_token(&link_token)
+            .await
+            .is_err()
+    );
+
+    let response = send("GET", format!("/api/users/{}", user_id), &admin_token)
+        .await
+        .unwrap();
+    let user = read_json(response).await;
+    assert_eq!(user["data"]["is_verified"], true);
+    assert!(user["data"]["verification_email_sent_at"].is_null());
+
+    let response = send("POST", verify_uri, &admin_token).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_oversized_bodies_are_rejected_with_the_limit_named() {
+    use lunarbase::server::build_routes;
+
+    let migrated = create_test_router().await;
+    let (_, bearer_token) = create_test_user(&migrated, "user").await;
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let app_state = AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState");
+    let app = build_routes(app_state, true);
+
+    let payload_too_large = |response: axum::response::Response, setting: &'static str| async move {
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let error: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE, "{}", error);
+        assert_eq!(error["code"], "payload_too_large");
+        assert!(
+            error["details"].as_str().unwrap().contains(setting),
+            "{}",
+            error
+        );
+    };
+
+    // Over limits.auth_body_kb (64 KB), whether or not the size is announced
+    let login = json!({
+        "email": "someone@example.com",
+        "password": "x".repeat(65 * 1024),
+    })
+    .to_string();
+    for announce_length in [false, true] {
+        let mut request = Request::builder()
+            .uri("/api/auth/login")
+            .method("POST")
+            .header("content-type", "application/json");
+        if announce_length {
+            request = request.header("content-length", login.len());
+        }
+        let response = app
+            .clone()
+            .oneshot(request.body(Body::from(login.clone())).unwrap())
+            .await
+            .unwrap();
+        payload_too_large(response, "limits.auth_body_kb").await;
+    }
+
+    let upload = |file_size: usize| {
+        let boundary = "limit-boundary";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"data\"\r\n\r\n{{}}\r\n--{boundary}\r\nContent-Disposition: form-data; name=\"file_attachment\"; filename=\"big.bin\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+        )
+        .into_bytes();
+        body.resize(body.len() + file_size, b'a');
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+        app.clone().oneshot(
+            Request::builder()
+                .uri("/api/collections/limit_test_missing/records")
+                .method("POST")
+                .header("authorization", format!("Bearer {}", bearer_token))
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .body(Body::from(body))
+                .unwrap(),
+        )
+    };
+
+    // Files are limited per part by limits.file_upload_mb (50 MB), not by the
+    // request limits, so a 3 MB file is read and the missing collection reported
+    let response = upload(3 * 1024 * 1024).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = upload(51 * 1024 * 1024).await.unwrap();
+    payload_too_large(response, "limits.file_upload_mb").await;
+}
+
+#[tokio::test]
+async fn test_health_history_reports_uptime_per_component() {
+    use lunarbase::server::build_routes;
+
+    create_test_router().await;
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let app_state = AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState");
+    for _ in 0..2 {
+        app_state.health_recorder.record_sample().await;
+    }
+    let app = build_routes(app_state, true);
+
+    let get = |uri: &'static str| {
+        app.clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+    };
+
+    let response = get("/api/health/history?window=1h").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let history: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(history["window"], "1h");
+    assert_eq!(history["sample_interval_seconds"], 60);
+    assert_eq!(history["samples"].as_array().unwrap().len(), 2);
+    let database = &history["components"]["database"];
+    assert_eq!(database["status"], "healthy");
+    assert_eq!(database["uptime_percentage"], 100.0);
+    assert_eq!(database["counted_samples"], 2);
+    assert!(database["since"].is_string());
+    for component in ["s3", "email", "backup"] {
+        assert!(
+            history["components"].get(component).is_some(),
+            "{}",
+            history
+        );
+    }
+
+    let response = get("/api/health/history?window=1y").await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_session_cookies_for_same_site_and_cross_site_admin_panels() {
+    use diesel::prelude::*;
+    use lunarbase::schema::users;
+    use lunarbase::server::build_routes;
+
+    let migrated = create_test_router().await;
+    let (user_id, _) = create_test_user(&migrated, "admin").await;
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let email: String = users::table
+        .filter(users::id.eq(user_id))
+        .select(users::email)
+        .first(&mut db_pool.get().unwrap())
+        .unwrap();
+    let app_state = AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState");
+    // Only this AppState's settings cache, so other tests keep the defaults
+    app_state
+        .configuration_manager
+        .update_cache("auth", "cookie_same_site", "None")
+        .await;
+    app_state
+        .configuration_manager
+        .update_cache("auth", "cookie_domain", "example.com")
+        .await;
+    let app = build_routes(app_state, true);
+
+    let login = |origin: Option<&'static str>| {
+        let mut request = Request::builder()
+            .uri("/api/auth/login")
+            .method("POST")
+            .header("host", "api.example.com")
+            .header("content-type", "application/json");
+        if let Some(origin) = origin {
+            request = request.header("origin", origin);
+        }
+        app.clone().oneshot(
+            request
+                .body(Body::from(
+                    json!({ "email": email, "password": "TestPassword123!" }).to_string(),
+                ))
+                .unwrap(),
+        )
+    };
+    let set_cookies = |response: &axum::response::Response| -> Vec<String> {
+        response
+            .headers()
+            .get_all("set-cookie")
+            .iter()
+            .map(|value| value.to_str().unwrap().to_string())
+            .collect()
+    };
+    let session_info = |origin: &'static str, cookie: Option<String>| {
+        let mut request = Request::builder()
+            .uri("/api/auth/session-info")
+            .header("host", "api.example.com")
+            .header("origin", origin);
+        if let Some(cookie) = cookie {
+            request = request.header("cookie", cookie);
+        }
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(request.body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            serde_json::from_slice::<Value>(&body).unwrap()["data"].clone()
+        }
+    };
+
+    // Same-origin logins keep host-only Lax cookies whatever the settings say
+    for origin in [None, Some("https://api.example.com")] {
+        let response = login(origin).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        for cookie in set_cookies(&response) {
+            assert!(cookie.contains("SameSite=Lax"), "{}", cookie);
+            assert!(!cookie.contains("Domain="), "{}", cookie);
+        }
+    }
+
+    let response = login(Some("https://admin.example.net")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let cookies = set_cookies(&response);
+    assert_eq!(cookies.len(), 3);
+    for cookie in &cookies {
+        assert!(cookie.contains("SameSite=None"), "{}", cookie);
+        assert!(cookie.contains("; Secure"), "{}", cookie);
+        assert!(cookie.contains("Domain=example.com"), "{}", cookie);
+    }
+    let cookie_header = cookies
+        .iter()
+        .map(|cookie| cookie.split(';').next().unwrap())
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    let info = session_info("https://admin.example.net", None).await;
+    assert_eq!(info["status"], "no_credentials");
+    assert_eq!(info["same_origin"], false);
+    assert_eq!(info["refresh_cookie_present"], false);
+    assert_eq!(info["cookie"]["same_site"], "None");
+    assert_eq!(info["cookie"]["secure"], true);
+    assert_eq!(info["cookie"]["domain"], "example.com");
+
+    let info = session_info("https://admin.example.net", Some(cookie_header)).await;
+    assert_eq!(info["status"], "authenticated");
+    assert_eq!(info["credential_source"], "cookie");
+    assert_eq!(info["refresh_cookie_present"], true);
+    assert_eq!(info["user"]["email"], email.as_str());
+
+    let info = session_info(
+        "https://api.example.com",
+        Some("access_token=not-a-jwt".to_string()),
+    )
+    .await;
+    assert_eq!(info["status"], "invalid_token");
+    assert_eq!(info["token_error"], "token_invalid");
+    assert_eq!(info["same_origin"], true);
+    assert_eq!(info["cookie"]["same_site"], "Lax");
+    assert!(info["cookie"]["domain"].is_null());
+    assert!(info["user"].is_null());
+}
+
+#[tokio::test]
+async fn test_read_only_changes_are_written_to_the_audit_log() {
+    use diesel::prelude::*;
+    use lunarbase::schema::permission_audit_entries;
+
+    let app = create_test_router().await;
+    let (admin_id, token) = create_admin_token(&app).await;
+    let role = format!("ro_{}", &uuid::Uuid::new_v4().simple().to_string()[0..8]);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/admin/configuration/system/read_only_roles")
+                .method("PUT")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(
+                    json!({ "setting_value": json!([role]).to_string() }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/admin/configuration/system/read_only_roles/reset")
+                .method("POST")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let entries: Vec<(String, Option<String>)> = permission_audit_entries::table
+        .filter(permission_audit_entries::actor_id.eq(admin_id))
+        .order(permission_audit_entries::id.asc())
+        .select((
+            permission_audit_entries::action,
+            permission_audit_entries::role_name,
+        ))
+        .load(&mut db_pool.get().unwrap())
+        .unwrap();
+    assert_eq!(
+        entries,
+        vec![
+            ("enable_read_only_role".to_string(), Some(role.clone())),
+            ("disable_read_only_role".to_string(), Some(role)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_unlocking_a_user_clears_the_lockout_and_is_audited() {
+    use diesel::prelude::*;
+    use lunarbase::models::User;
+    use lunarbase::schema::{permission_audit_entries, users};
+
+    let app = create_test_router().await;
+    let (admin_id, token) = create_admin_token(&app).await;
+    let (user_id, _user_token) = create_test_user(&app, "user").await;
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let load_user = |id: i32| -> User {
+        users::table
+            .find(id)
+            .select(User::as_select())
+            .first(&mut db_pool.get().unwrap())
+            .unwrap()
+    };
+    let email = load_user(user_id).email;
+
+    diesel::update(users::table.find(user_id))
+        .set((
+            users::failed_login_attempts.eq(5),
+            users::locked_until.eq(Some(
+                chrono::Utc::now().naive_utc() + chrono::Duration::minutes(30),
+            )),
+            users::lockout_count.eq(2),
+        ))
+        .execute(&mut db_pool.get().unwrap())
+        .unwrap();
+
+    let forgot_password = || {
+        app.clone().oneshot(
+            Request::builder()
+                .uri("/api/auth/forgot-password")
+                .method("POST")
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "email": email }).to_string()))
+                .unwrap(),
+        )
+    };
+    for _ in 0..3 {
+        assert_eq!(forgot_password().await.unwrap().status(), StatusCode::OK);
+    }
+    assert_eq!(
+        forgot_password().await.unwrap().status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+
+    let unlock = |bearer: &str| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/users/{}/unlock", user_id))
+                .method("POST")
+                .header("authorization", format!("Bearer {}", bearer))
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+    let user_token = create_token_for_user(user_id, &email, "user");
+    assert_eq!(
+        unlock(&user_token).await.unwrap().status(),
+        StatusCode::FORBIDDEN
+    );
+
+    let response = unlock(&token).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json_response["data"]["is_locked"], false);
+    assert_eq!(json_response["data"]["failed_login_attempts"], 0);
+
+    let user = load_user(user_id);
+    assert!(user.locked_until.is_none());
+    assert_eq!(user.failed_login_attempts, 0);
+    assert_eq!(user.lockout_count, 0);
+
+    let entries: Vec<(Option<i32>, String, i64)> = permission_audit_entries::table
+        .filter(permission_audit_entries::target_user_id.eq(user_id))
+        .select((
+            permission_audit_entries::actor_id,
+            permission_audit_entries::action,
+            permission_audit_entries::affected_count,
+        ))
+        .load(&mut db_pool.get().unwrap())
+        .unwrap();
+    assert_eq!(
+        entries,
+        vec![(Some(admin_id), "unlock_user".to_string(), 5)]
+    );
+
+    // The address gets a fresh email budget
+    assert_eq!(forgot_password().await.unwrap().status(), StatusCode::OK);
+
+    assert_eq!(
+        unlock(&token).await.unwrap().status(),
+        StatusCode::BAD_REQUEST
+    );
+}
@@ -1,90 +1,92 @@
 use axum::{
     Router,
     body::Body,
-    http::{Request, StatusCode},
+    http::{Method, Request, StatusCode, Uri},
+    response::IntoResponse,
     routing::{delete, get, post, put},
 };
 use diesel::prelude::*;
+use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 use http_body_util::BodyExt;
 use jsonwebtoken::{EncodingKey, Header, encode};
 use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tower::ServiceExt;
 use uuid;
 
 use axum::middleware;
-use lunarbase::AppState;
 use lunarbase::database::create_pool;
 use lunarbase::handlers::auth::*;
 use lunarbase::handlers::collections::*;
 use lunarbase::middleware::auth_middleware;
 use lunarbase::models::{
-    CollectionSchema, FieldDefinition, FieldType, NewUser, User, ValidationRules,
+    CollectionSchema, FieldDefinition, FieldType, NewUser, QuarantinedUpload, User, ValidationRules,
 };
-use lunarbase::schema::users;
+use lunarbase::schema::{quarantined_uploads, users};
+use lunarbase::{AppState, Config};
 
 mod common;
 
-async fn create_test_router() -> Router {
-    let test_jwt_secret = "test_secret".to_string();
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations/");
 
+async fn create_test_router() -> Router {
     let config = common::create_test_config().expect("Failed to load config");
-    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
-    let test_password_pepper = "test_pepper".to_string();
-    let app_state = AppState::new(db_pool, &test_jwt_secret, test_password_pepper, &config)
-        .await
-        .expect("Failed to create AppState");
-
-    let public_routes = Router::new()
-        .route("/collections", get(list_collections))
-        .route("/collections/{name}", get(get_collection))
-        .route("/collections/{name}/schema", get(get_collection_schema))
-        .route("/collections/{name}/records", get(list_records))
-        .route("/collections/{name}/records/{record_id}", get(get_record))
-        .route("/auth/register", post(register))
-        .route("/auth/login", post(login));
-
-    let protected_routes = Router::new()
-        .route("/collections", post(create_collection))
-        .route("/collections/{name}", put(update_collection))
-        .route("/collections/{name}", delete(delete_collection))
-        .route("/collections/stats", get(get_collections_stats))
-        .route("/collections/{name}/records", post(create_record))
-        .route(
-            "/collections/{name}/records/{record_id}",
-            put(update_record),
-        )
-        .route(
-            "/collections/{name}/records/{record_id}",
-            delete(delete_record),
-        )
-        .layer(middleware::from_fn_with_state(
-            app_state.auth_state.clone(),
-            auth_middleware,
-        ));
-
-    Router::new()
-        .merge(public_routes)
-        .nest("/api", protected_routes)
-        .with_state(app_state)
+    create_test_router_for(create_test_app_state(&config).await)
 }
 
 async fn create_test_router_without_s3() -> Router {
-    let test_jwt_secret = "test_secret".to_string();
-
     let mut config = common::create_test_config().expect("Failed to load config");
     config.s3_bucket_name = None;
     config.s3_region = None;
     config.s3_access_key_id = None;
     config.s3_secret_access_key = None;
     config.s3_endpoint_url = None;
+    create_test_router_for(create_test_app_state(&config).await)
+}
+
+/// A router whose S3 storage is a [`FakeS3`] on a local port, with the
+/// `storage` settings overridden for this router only.
+async fn create_test_router_with_fake_s3(storage_settings: &[(&str, &str)]) -> (Router, FakeS3) {
+    let fake_s3 = FakeS3::start().await;
+
+    let mut config = common::create_test_config().expect("Failed to load config");
+    config.s3_bucket_name = Some(FakeS3::BUCKET.to_string());
+    config.s3_region = Some("us-east-1".to_string());
+    config.s3_access_key_id = Some("test".to_string());
+    config.s3_secret_access_key = Some("test".to_string());
+    config.s3_endpoint_url = Some(fake_s3.endpoint.clone());
+
+    let app_state = create_test_app_state(&config).await;
+    app_state
+        .configuration_manager
+        .update_cache("storage", "s3_enabled", "true")
+        .await;
+    for (key, value) in storage_settings {
+        app_state
+            .configuration_manager
+            .update_cache("storage", key, value)
+            .await;
+    }
+    (create_test_router_for(app_state), fake_s3)
+}
+
+async fn create_test_app_state(config: &Config) -> AppState {
+    let test_jwt_secret = "test_secret".to_string();
 
     let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    db_pool
+        .get()
+        .expect("Failed to get database connection")
+        .run_pending_migrations(MIGRATIONS)
+        .expect("Failed to run migrations");
     let test_password_pepper = "test_pepper".to_string();
-    let app_state = AppState::new(db_pool, &test_jwt_secret, test_password_pepper, &config)
+    AppState::new(db_pool, &test_jwt_secret, test_password_pepper, config)
         .await
-        .expect("Failed to create AppState");
+        .expect("Failed to create AppState")
+}
 
+fn create_test_router_for(app_state: AppState) -> Router {
     let public_routes = Router::new()
         .route("/collections", get(list_collections))
         .route("/collections/{name}", get(get_collection))
@@ -119,6 +121,59 @@ async fn create_test_router_without_s3() -> Router {
         .with_state(app_state)
 }
 
+/// Just enough of the S3 API for uploads and deletes, remembering every
+/// write it was sent as e.g. `DELETE uploads/<id>.png`.
+#[derive(Clone)]
+struct FakeS3 {
+    endpoint: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl FakeS3 {
+    const BUCKET: &'static str = "fake-bucket";
+
+    async fn start() -> Self {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let app = Router::new().fallback(move |method: Method, uri: Uri| {
+            let recorded = recorded.clone();
+            async move {
+                let key = uri
+                    .path()
+                    .trim_start_matches(&format!("/{}", FakeS3::BUCKET))
+                    .trim_start_matches('/')
+                    .to_string();
+                if method == Method::PUT || method == Method::DELETE {
+                    recorded.lock().unwrap().push(format!("{} {}", method, key));
+                }
+                match method {
+                    Method::DELETE => StatusCode::NO_CONTENT.into_response(),
+                    _ => ([("etag", "\"fake\"")], "").into_response(),
+                }
+            }
+        });
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        Self { endpoint, requests }
+    }
+
+    fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn key_of(&self, file_url: &Value) -> String {
+        let prefix = format!("{}/{}/", self.endpoint, FakeS3::BUCKET);
+        file_url
+            .as_str()
+            .and_then(|url| url.strip_prefix(&prefix))
+            .expect("File URL on the fake S3 endpoint")
+            .to_string()
+    }
+}
+
 fn unique_collection_name(prefix: &str) -> String {
     let uuid_suffix = uuid::Uuid::new_v4().to_string();
     let short_uuid = &uuid_suffix[0..8];
@@ -183,6 +238,9 @@ fn create_token_for_user(user_id: i32, email: &str, role: &str) -> String {
         exp,
         iat: now,
         jti: uuid::Uuid::new_v4().to_string(),
+        guest: None,
+        workspace_id: None,
+        superadmin: false,
     };
 
     let jwt_secret = "test_secret".to_string();
@@ -209,7 +267,15 @@ fn create_test_schema() -> CollectionSchema {
                     min_value: None,
                     max_value: None,
                     enum_values: None,
+                    precision: None,
+                    scale: None,
+                    source_field: None,
+                    number_format: None,
                 }),
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
             },
             FieldDefinition {
                 name: "avatar".to_string(),
@@ -217,6 +283,10 @@ fn create_test_schema() -> CollectionSchema {
                 required: false,
                 default_value: None,
                 validation: None,
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
             },
             FieldDefinition {
                 name: "documents".to_string(),
@@ -224,6 +294,10 @@ fn create_test_schema() -> CollectionSchema {
                 required: false,
                 default_value: None,
                 validation: None,
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
             },
         ],
     }
@@ -308,7 +382,7 @@ async fn test_file_upload_s3_disabled() {
 
     eprintln!("Response body: {}", response_text);
     assert_eq!(status, StatusCode::BAD_REQUEST);
-    assert!(response_text.contains("VALIDATION_ERROR"));
+    assert!(response_text.contains("\"code\":\"validation_failed\""));
 }
 
 #[tokio::test]
@@ -484,7 +558,7 @@ async fn test_file_field_validation() {
 
     assert_eq!(status, StatusCode::BAD_REQUEST);
 
-    assert!(response_text.contains("VALIDATION_ERROR"));
+    assert!(response_text.contains("\"code\":\"validation_failed\""));
 }
 
 #[tokio::test]
@@ -614,5 +688,695 @@ async fn test_invalid_base64_data() {
 
     let body = response.into_body().collect().await.unwrap().to_bytes();
     let response_text = String::from_utf8(body.to_vec()).unwrap();
-    assert!(response_text.contains("VALIDATION_ERROR"));
+    assert!(response_text.contains("\"code\":\"validation_failed\""));
+}
+
+async fn create_files_collection(app: &Router, admin_token: &str, prefix: &str) -> String {
+    let collection_name = unique_collection_name(prefix);
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/collections")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", admin_token))
+        .body(Body::from(
+            json!({ "name": collection_name, "schema": create_test_schema() }).to_string(),
+        ))
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    collection_name
+}
+
+/// Sends `parts` (headers, body) as a multipart record write.
+async fn send_multipart(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    admin_token: &str,
+    parts: &[(&str, &str)],
+) -> (StatusCode, Value) {
+    let boundary = "part_boundary";
+    let mut body = String::new();
+    for (headers, content) in parts {
+        body.push_str(&format!(
+            "--{}\r\n{}\r\n\r\n{}\r\n",
+            boundary, headers, content
+        ));
+    }
+    body.push_str(&format!("--{}--\r\n", boundary));
+
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(
+            "content-type",
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .header("authorization", format!("Bearer {}", admin_token))
+        .body(Body::from(body))
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_multipart_part_errors_are_keyed_by_part() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let collection_name = create_files_collection(&app, &admin_token, "part_errors").await;
+
+    let (status, body) = send_multipart(
+        &app,
+        "POST",
+        &format!("/api/collections/{}/records", collection_name),
+        &admin_token,
+        &[
+            (
+                "Content-Disposition: form-data; name=\"data\"",
+                r#"{"name": "Broken",}"#,
+            ),
+            (
+                "Content-Disposition: form-data; name=\"attachment\"",
+                "stray",
+            ),
+            (
+                "Content-Disposition: form-data; name=\"data\"",
+                r#"{"name": "Again"}"#,
+            ),
+        ],
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body,
+        json!({
+            "success": false,
+            "code": "validation_failed",
+            "error": "Validation failed",
+            "fields": {
+                "attachment": [{
+                    "code": "unknown_part",
+                    "message": "Unexpected form part 'attachment'; send the record as 'data' and files as 'file_<field>'"
+                }],
+                "data": [
+                    {
+                        "code": "invalid_json",
+                        "message": "Form part 'data' is not valid JSON: trailing comma at line 1 column 19"
+                    },
+                    {
+                        "code": "duplicate_part",
+                        "message": "Form part 'data' was sent more than once"
+                    }
+                ]
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_multipart_data_must_be_an_object() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let collection_name = create_files_collection(&app, &admin_token, "data_object").await;
+
+    let (status, body) = send_multipart(
+        &app,
+        "POST",
+        &format!("/api/collections/{}/records", collection_name),
+        &admin_token,
+        &[(
+            "Content-Disposition: form-data; name=\"data\"",
+            r#"{"name": "Created"}"#,
+        )],
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let record_id = body["data"]["id"].as_str().unwrap().to_string();
+
+    let (status, body) = send_multipart(
+        &app,
+        "PUT",
+        &format!("/api/collections/{}/records/{}", collection_name, record_id),
+        &admin_token,
+        &[
+            ("Content-Disposition: form-data; name=\"data\"", "[1, 2]"),
+            ("Content-Disposition: form-data; name=\"file_\"", "no field"),
+        ],
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body,
+        json!({
+            "success": false,
+            "code": "validation_failed",
+            "error": "Validation failed",
+            "fields": {
+                "data": [{
+                    "code": "invalid_type",
+                    "message": "Record data must be a JSON object"
+                }],
+                "file_": [{
+                    "code": "unknown_part",
+                    "message": "Unexpected form part 'file_'; send the record as 'data' and files as 'file_<field>'"
+                }]
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_multipart_file_errors_are_keyed_by_field() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let collection_name = create_files_collection(&app, &admin_token, "file_errors").await;
+
+    let (status, body) = send_multipart(
+        &app,
+        "POST",
+        &format!("/api/collections/{}/records", collection_name),
+        &admin_token,
+        &[
+            (
+                "Content-Disposition: form-data; name=\"data\"",
+                r#"{"name": "Files"}"#,
+            ),
+            (
+                "Content-Disposition: form-data; name=\"file_name\"; filename=\"name.txt\"\r\nContent-Type: text/plain",
+                "not a file field",
+            ),
+            (
+                "Content-Disposition: form-data; name=\"file_cover\"; filename=\"cover.png\"\r\nContent-Type: image/png",
+                "no such field",
+            ),
+            (
+                "Content-Disposition: form-data; name=\"file_avatar\"; filename=\"avatar.png\"\r\nContent-Type: image",
+                "png bytes",
+            ),
+            (
+                "Content-Disposition: form-data; name=\"file_documents\"; filename=\"doc.pdf\"\r\nContent-Type: application/pdf\r\nContent-Transfer-Encoding: base64",
+                "invalid_base64_data!!!",
+            ),
+        ],
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body,
+        json!({
+            "success": false,
+            "code": "validation_failed",
+            "error": "Validation failed",
+            "fields": {
+                "avatar": [{
+                    "code": "invalid_content_type",
+                    "message": "Field 'avatar' has an invalid content type 'image'"
+                }],
+                "cover": [{
+                    "code": "unknown_field",
+                    "message": "Field 'cover' does not exist in the collection schema"
+                }],
+                "documents": [{
+                    "code": "invalid_base64",
+                    "message": "Field 'documents' does not contain valid base64 data"
+                }],
+                "name": [{
+                    "code": "not_a_file_field",
+                    "message": "Field 'name' is not a file field"
+                }]
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_multipart_upload_errors_are_keyed_by_field() {
+    let app = create_test_router_without_s3().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let collection_name = create_files_collection(&app, &admin_token, "upload_errors").await;
+
+    let (status, body) = send_multipart(
+        &app,
+        "POST",
+        &format!("/api/collections/{}/records", collection_name),
+        &admin_token,
+        &[
+            (
+                "Content-Disposition: form-data; name=\"data\"",
+                r#"{"name": "Upload"}"#,
+            ),
+            (
+                "Content-Disposition: form-data; name=\"file_avatar\"; filename=\"avatar.png\"\r\nContent-Type: image/png\r\nContent-Transfer-Encoding: base64",
+                "iVBORw0KGgo=",
+            ),
+        ],
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body,
+        json!({
+            "success": false,
+            "code": "validation_failed",
+            "error": "Validation failed",
+            "fields": {
+                "avatar": [{
+                    "code": "uploads_disabled",
+                    "message": "Cannot upload 'avatar': file uploads are disabled because S3 storage is not enabled"
+                }]
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_multipart_schema_errors_are_keyed_by_field() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let collection_name = create_files_collection(&app, &admin_token, "schema_errors").await;
+
+    let (status, body) = send_multipart(
+        &app,
+        "POST",
+        &format!("/api/collections/{}/records", collection_name),
+        &admin_token,
+        &[(
+            "Content-Disposition: form-data; name=\"data\"",
+            r#"{"name": ""}"#,
+        )],
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body,
+        json!({
+            "success": false,
+            "code": "validation_failed",
+            "error": "Validation failed",
+            "fields": {
+                "name": [{
+                    "code": "too_short",
+                    "message": "Field 'name' is too short (minimum 1 characters)"
+                }]
+            }
+        })
+    );
+}
+
+/// Makes every `event` (UPDATE, DELETE) on `table` fail from inside SQLite,
+/// after the handler has already read the rows it is about to change.
+fn inject_write_failure(trigger: &str, event: &str, table: &str, when: &str) {
+    let config = common::create_test_config().expect("Failed to load config");
+    let mut conn = create_pool(&config.database_url).unwrap().get().unwrap();
+    diesel::sql_query(format!(
+        "CREATE TRIGGER {} BEFORE {} ON {} WHEN {} BEGIN SELECT RAISE(ABORT, 'injected failure'); END",
+        trigger, event, table, when
+    ))
+    .execute(&mut conn)
+    .unwrap();
+}
+
+fn remove_write_failure(trigger: &str) {
+    let config = common::create_test_config().expect("Failed to load config");
+    let mut conn = create_pool(&config.database_url).unwrap().get().unwrap();
+    diesel::sql_query(format!("DROP TRIGGER {}", trigger))
+        .execute(&mut conn)
+        .unwrap();
+}
+
+async fn create_record_with_avatar(
+    app: &Router,
+    admin_token: &str,
+    collection_name: &str,
+) -> (String, Value) {
+    let (status, body) = send_multipart(
+        app,
+        "POST",
+        &format!("/api/collections/{}/records", collection_name),
+        admin_token,
+        &[
+            (
+                "Content-Disposition: form-data; name=\"data\"",
+                r#"{"name": "With avatar"}"#,
+            ),
+            (
+                "Content-Disposition: form-data; name=\"file_avatar\"; filename=\"avatar.png\"\r\nContent-Type: image/png\r\nContent-Transfer-Encoding: base64",
+                "iVBORw0KGgo=",
+            ),
+        ],
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+
+    let record = &body["data"];
+    (
+        record["id"].as_str().unwrap().to_string(),
+        record["data"]["avatar"].clone(),
+    )
+}
+
+async fn get_avatar(app: &Router, collection_name: &str, record_id: &str) -> Option<Value> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!(
+            "/collections/{}/records/{}",
+            collection_name, record_id
+        ))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    if response.status() != StatusCode::OK {
+        return None;
+    }
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    Some(body["data"]["data"]["avatar"].clone())
+}
+
+#[tokio::test]
+async fn test_failed_record_update_keeps_the_old_file() {
+    let (app, fake_s3) = create_test_router_with_fake_s3(&[]).await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let collection_name = create_files_collection(&app, &admin_token, "update_rollback").await;
+    let (record_id, old_avatar) =
+        create_record_with_avatar(&app, &admin_token, &collection_name).await;
+    let old_key = fake_s3.key_of(&old_avatar);
+
+    let trigger = format!("fail_update_{}", collection_name);
+    inject_write_failure(
+        &trigger,
+        "UPDATE",
+        &format!("records_{}", collection_name),
+        "1",
+    );
+    let update_avatar = [
+        (
+            "Content-Disposition: form-data; name=\"data\"",
+            r#"{"name": "New avatar"}"#,
+        ),
+        (
+            "Content-Disposition: form-data; name=\"file_avatar\"; filename=\"new.png\"\r\nContent-Type: image/png\r\nContent-Transfer-Encoding: base64",
+            "iVBORw0KGgo=",
+        ),
+    ];
+    let (status, body) = send_multipart(
+        &app,
+        "PUT",
+        &format!("/api/collections/{}/records/{}", collection_name, record_id),
+        &admin_token,
+        &update_avatar,
+    )
+    .await;
+    remove_write_failure(&trigger);
+
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR, "{}", body);
+    assert_eq!(
+        get_avatar(&app, &collection_name, &record_id).await,
+        Some(old_avatar.clone())
+    );
+    // Only the upload nothing points at is cleaned up
+    let requests = fake_s3.requests();
+    let new_upload = requests[1].strip_prefix("PUT ").unwrap().to_string();
+    assert_eq!(
+        requests,
+        vec![
+            format!("PUT {}", old_key),
+            format!("PUT {}", new_upload),
+            format!("DELETE {}", new_upload),
+        ]
+    );
+
+    let (status, body) = send_multipart(
+        &app,
+        "PUT",
+        &format!("/api/collections/{}/records/{}", collection_name, record_id),
+        &admin_token,
+        &update_avatar,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let new_key = fake_s3.key_of(&body["data"]["data"]["avatar"]);
+    assert_eq!(
+        fake_s3.requests()[3..],
+        [format!("PUT {}", new_key), format!("DELETE {}", old_key)]
+    );
+}
+
+#[tokio::test]
+async fn test_failed_record_delete_keeps_its_files() {
+    let (app, fake_s3) = create_test_router_with_fake_s3(&[]).await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let collection_name = create_files_collection(&app, &admin_token, "delete_rollback").await;
+    let (record_id, avatar) = create_record_with_avatar(&app, &admin_token, &collection_name).await;
+    let key = fake_s3.key_of(&avatar);
+
+    let trigger = format!("fail_delete_{}", collection_name);
+    inject_write_failure(
+        &trigger,
+        "DELETE",
+        &format!("records_{}", collection_name),
+        "1",
+    );
+    let delete_record = || {
+        Request::builder()
+            .method("DELETE")
+            .uri(format!(
+                "/api/collections/{}/records/{}",
+                collection_name, record_id
+            ))
+            .header("authorization", format!("Bearer {}", admin_token))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.clone().oneshot(delete_record()).await.unwrap();
+    remove_write_failure(&trigger);
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+        get_avatar(&app, &collection_name, &record_id).await,
+        Some(avatar)
+    );
+    assert_eq!(fake_s3.requests(), vec![format!("PUT {}", key)]);
+
+    let response = app.clone().oneshot(delete_record()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(get_avatar(&app, &collection_name, &record_id).await, None);
+    assert_eq!(
+        fake_s3.requests(),
+        vec![format!("PUT {}", key), format!("DELETE {}", key)]
+    );
+}
+
+#[tokio::test]
+async fn test_failed_collection_delete_keeps_its_records_and_files() {
+    let (app, fake_s3) = create_test_router_with_fake_s3(&[]).await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let collection_name = create_files_collection(&app, &admin_token, "collection_rollback").await;
+    let (record_id, avatar) = create_record_with_avatar(&app, &admin_token, &collection_name).await;
+    let key = fake_s3.key_of(&avatar);
+
+    // Fails on the last step, after the records table was dropped
+    let trigger = format!("fail_drop_{}", collection_name);
+    inject_write_failure(
+        &trigger,
+        "DELETE",
+        "collections",
+        &format!("OLD.name = '{}'", collection_name),
+    );
+    let delete_collection = || {
+        Request::builder()
+            .method("DELETE")
+            .uri(format!("/api/collections/{}?force=true", collection_name))
+            .header("authorization", format!("Bearer {}", admin_token))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.clone().oneshot(delete_collection()).await.unwrap();
+    remove_write_failure(&trigger);
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+        get_avatar(&app, &collection_name, &record_id).await,
+        Some(avatar)
+    );
+    assert_eq!(fake_s3.requests(), vec![format!("PUT {}", key)]);
+
+    let response = app.clone().oneshot(delete_collection()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        fake_s3.requests(),
+        vec![format!("PUT {}", key), format!("DELETE {}", key)]
+    );
+}
+
+/// A scanner for `storage.upload_scan_command` that finds "EICAR" in any file.
+fn stub_scan_command() -> String {
+    let path = std::env::temp_dir().join(format!("scan-{}.sh", uuid::Uuid::new_v4()));
+    std::fs::write(
+        &path,
+        "if grep -q EICAR; then echo Stub.Signature; exit 1; fi",
+    )
+    .unwrap();
+    format!("sh {}", path.display())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_infected_upload_is_rejected_and_quarantined() {
+    let scan_command = stub_scan_command();
+    let (app, fake_s3) = create_test_router_with_fake_s3(&[
+        ("upload_scan_mode", "command"),
+        ("upload_scan_command", &scan_command),
+    ])
+    .await;
+    let (admin_id, admin_token) = create_admin_token(&app).await;
+    let collection_name = create_files_collection(&app, &admin_token, "scanned").await;
+    let uri = format!("/api/collections/{}/records", collection_name);
+
+    // base64 of "X5O!EICAR", with a clean file in the same request
+    let (status, body) = send_multipart(
+        &app,
+        "POST",
+        &uri,
+        &admin_token,
+        &[
+            (
+                "Content-Disposition: form-data; name=\"data\"",
+                r#"{"name": "Infected"}"#,
+            ),
+            (
+                "Content-Disposition: form-data; name=\"file_avatar\"; filename=\"avatar.png\"\r\nContent-Type: image/png\r\nContent-Transfer-Encoding: base64",
+                "iVBORw0KGgo=",
+            ),
+            (
+                "Content-Disposition: form-data; name=\"file_documents\"; filename=\"invoice.pdf\"\r\nContent-Type: application/pdf\r\nContent-Transfer-Encoding: base64",
+                "WDVPIUVJQ0FS",
+            ),
+        ],
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body,
+        json!({
+            "success": false,
+            "code": "validation_failed",
+            "error": "Validation failed",
+            "fields": {
+                "documents": [{
+                    "code": "malware_detected",
+                    "message": "The file for field 'documents' was rejected because the malware scanner found Stub.Signature"
+                }]
+            }
+        })
+    );
+    // Not even the clean file is stored
+    assert!(fake_s3.requests().is_empty());
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let mut conn = create_pool(&config.database_url).unwrap().get().unwrap();
+    let quarantined: Vec<QuarantinedUpload> = quarantined_uploads::table
+        .filter(quarantined_uploads::collection_name.eq(&collection_name))
+        .select(QuarantinedUpload::as_select())
+        .load(&mut conn)
+        .unwrap();
+    assert_eq!(quarantined.len(), 1);
+    let entry = &quarantined[0];
+    assert_eq!(entry.field_name, "documents");
+    assert_eq!(entry.filename, "invoice.pdf");
+    assert_eq!(entry.content_type, "application/pdf");
+    assert_eq!(entry.size_bytes, 9);
+    assert_eq!(
+        entry.sha256,
+        "7526107414f30132ae68f57435874a669e10133ddd8aa4491a5b26a991ec0c6e"
+    );
+    assert_eq!(entry.scanner, "command");
+    assert_eq!(entry.signature, "Stub.Signature");
+    assert_eq!(entry.user_id, Some(admin_id));
+
+    let (status, body) = send_multipart(
+        &app,
+        "POST",
+        &uri,
+        &admin_token,
+        &[
+            (
+                "Content-Disposition: form-data; name=\"data\"",
+                r#"{"name": "Clean"}"#,
+            ),
+            (
+                "Content-Disposition: form-data; name=\"file_avatar\"; filename=\"avatar.png\"\r\nContent-Type: image/png\r\nContent-Transfer-Encoding: base64",
+                "iVBORw0KGgo=",
+            ),
+        ],
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    assert_eq!(fake_s3.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_unavailable_scanner_fails_closed_unless_fail_open() {
+    // Nothing listens on a port that was just released
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let clamav_address = listener.local_addr().unwrap().to_string();
+    drop(listener);
+
+    for (fail_open, expected_status) in [
+        ("false", StatusCode::BAD_REQUEST),
+        ("true", StatusCode::CREATED),
+    ] {
+        let (app, fake_s3) = create_test_router_with_fake_s3(&[
+            ("upload_scan_mode", "clamav"),
+            ("upload_scan_clamav_address", &clamav_address),
+            ("upload_scan_fail_open", fail_open),
+        ])
+        .await;
+        let (_admin_id, admin_token) = create_admin_token(&app).await;
+        let collection_name = create_files_collection(&app, &admin_token, "unscanned").await;
+
+        let (status, body) = send_multipart(
+            &app,
+            "POST",
+            &format!("/api/collections/{}/records", collection_name),
+            &admin_token,
+            &[
+                (
+                    "Content-Disposition: form-data; name=\"data\"",
+                    r#"{"name": "Unscanned"}"#,
+                ),
+                (
+                    "Content-Disposition: form-data; name=\"file_avatar\"; filename=\"avatar.png\"\r\nContent-Type: image/png\r\nContent-Transfer-Encoding: base64",
+                    "iVBORw0KGgo=",
+                ),
+            ],
+        )
+        .await;
+
+        assert_eq!(status, expected_status, "{}", body);
+        if fail_open == "false" {
+            assert_eq!(
+                body["fields"],
+                json!({
+                    "avatar": [{
+                        "code": "scan_unavailable",
+                        "message": "The file for field 'avatar' could not be scanned for malware, try again later"
+                    }]
+                })
+            );
+            assert!(fake_s3.requests().is_empty());
+        } else {
+            assert_eq!(fake_s3.requests().len(), 1);
+        }
+    }
 }
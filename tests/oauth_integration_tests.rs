@@ -8,18 +8,22 @@ use diesel::RunQueryDsl;
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 use tower::ServiceExt;
 
-use lunarbase::AppState;
 use lunarbase::database::create_pool;
 use lunarbase::handlers::auth::*;
+use lunarbase::{AppState, Config};
 
 mod common;
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations/");
 
 async fn create_test_router() -> Router {
+    let config = common::create_test_config().expect("Failed to load config");
+    create_test_router_for(create_test_app_state(&config).await)
+}
+
+async fn create_test_app_state(config: &Config) -> AppState {
     let test_jwt_secret = "test_secret".to_string();
 
-    let config = common::create_test_config().expect("Failed to load config");
     let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
 
     {
@@ -49,10 +53,12 @@ async fn create_test_router() -> Router {
     }
 
     let test_password_pepper = "test_pepper".to_string();
-    let app_state = AppState::new(db_pool, &test_jwt_secret, test_password_pepper, &config)
+    AppState::new(db_pool, &test_jwt_secret, test_password_pepper, config)
         .await
-        .expect("Failed to create AppState");
+        .expect("Failed to create AppState")
+}
 
+fn create_test_router_for(app_state: AppState) -> Router {
     let oauth_routes = Router::new()
         .route("/auth/oauth/{provider}", get(oauth_authorize))
         .route("/auth/oauth/{provider}/callback", get(oauth_callback));
@@ -187,8 +193,26 @@ async fn test_oauth_callback_with_error_parameter() {
     let location = response.headers().get("location").unwrap();
     let location_str = location.to_str().unwrap();
 
-    assert!(location_str.contains("error"));
-    assert!(location_str.contains("User%20denied%20access"));
+    assert!(location_str.contains("/admin/auth/error?code=access_denied&request_id="));
+    assert!(!location_str.contains("denied%20access"));
+}
+
+#[tokio::test]
+async fn test_oauth_callback_error_keeps_the_request_id_and_hides_provider_detail() {
+    let app = create_test_router().await;
+
+    let request = Request::builder()
+        .uri("/api/auth/oauth/github/callback?error=application_suspended&error_description=Your%20application%20has%20been%20suspended.%20Contact%20support%40github.com")
+        .header("x-request-id", "req-42")
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+    let location = response.headers()["location"].to_str().unwrap();
+    assert!(location.ends_with("/admin/auth/error?code=provider_unavailable&request_id=req-42"));
 }
 
 #[tokio::test]
@@ -258,3 +282,109 @@ async fn test_oauth_scope_limitation() {
         google_location.contains("scope=https%3A%2F%2Fwww.googleapis.com%2Fauth%2Fuserinfo.email")
     );
 }
+
+async fn oauth_redirect_locations(app: &Router) -> (String, String) {
+    let location = |response: axum::response::Response| {
+        response.headers()["location"].to_str().unwrap().to_string()
+    };
+
+    let authorize = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/auth/oauth/github")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let denied = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/auth/oauth/github/callback?error=access_denied")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    (location(authorize), location(denied))
+}
+
+#[tokio::test]
+async fn test_public_urls_split_between_api_and_frontend_domains() {
+    let mut config = common::create_test_config().expect("Failed to load config");
+    config
+        .apply_public_urls("https://api.example.com/", "https://app.example.com")
+        .unwrap();
+    let app_state = create_test_app_state(&config).await;
+    let app = create_test_router_for(app_state.clone());
+
+    assert_eq!(
+        app_state.email_service.verification_url("token"),
+        "https://api.example.com/api/verify-email?token=token"
+    );
+    assert_eq!(
+        app_state.email_service.password_reset_url("token"),
+        "https://app.example.com/admin/reset-password?token=token"
+    );
+
+    let (authorize, denied) = oauth_redirect_locations(&app).await;
+    assert!(authorize.contains(&format!(
+        "redirect_uri={}",
+        urlencoding::encode("https://api.example.com/api/auth/oauth/github/callback")
+    )));
+    assert!(denied.starts_with("https://app.example.com/admin/auth/error"));
+}
+
+#[tokio::test]
+async fn test_public_urls_default_to_the_server_address() {
+    let mut config = common::create_test_config().expect("Failed to load config");
+    let server_url = format!("http://{}", config.server_address());
+    config.apply_public_urls("", "").unwrap();
+    assert_eq!(config.public_api_url, server_url);
+    assert_eq!(config.frontend_url, server_url);
+
+    let app_state = create_test_app_state(&config).await;
+    let app = create_test_router_for(app_state.clone());
+
+    assert_eq!(
+        app_state.email_service.verification_url("token"),
+        format!("{}/api/verify-email?token=token", server_url)
+    );
+    assert_eq!(
+        app_state.email_service.password_reset_url("token"),
+        format!("{}/admin/reset-password?token=token", server_url)
+    );
+
+    let (authorize, denied) = oauth_redirect_locations(&app).await;
+    assert!(authorize.contains(&format!(
+        "redirect_uri={}",
+        urlencoding::encode(&format!("{}/api/auth/oauth/github/callback", server_url))
+    )));
+    assert!(denied.starts_with(&format!("{}/admin/auth/error", server_url)));
+}
+
+#[tokio::test]
+async fn test_public_urls_must_be_absolute() {
+    let mut config = common::create_test_config().expect("Failed to load config");
+    let server_url = config.public_api_url.clone();
+
+    for (public_api_url, frontend_url) in [
+        ("/api", ""),
+        ("api.example.com", ""),
+        ("ftp://api.example.com", ""),
+        ("", "https://app.example.com/?next=admin"),
+    ] {
+        assert!(
+            config
+                .apply_public_urls(public_api_url, frontend_url)
+                .is_err(),
+            "{} / {} should be rejected",
+            public_api_url,
+            frontend_url
+        );
+    }
+    assert_eq!(config.public_api_url, server_url);
+}
@@ -15,12 +15,16 @@ use lunarbase::database::create_pool;
 use lunarbase::handlers::{
     auth::*, collections::*, ownership::*, permissions::*, record_permissions::*,
 };
-use lunarbase::middleware::auth_middleware;
+use lunarbase::middleware::{auth_middleware, optional_auth_middleware};
 use lunarbase::models::{CollectionSchema, FieldDefinition, FieldType, ValidationRules};
 
 mod common;
 
 async fn create_test_router() -> Router {
+    create_test_router_with_state().await.0
+}
+
+async fn create_test_router_with_state() -> (Router, AppState) {
     let test_jwt_secret = "test_permission_secret".to_string();
     let config = common::create_test_config().expect("Failed to load config");
     let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
@@ -36,7 +40,13 @@ async fn create_test_router() -> Router {
         .route("/collections/{name}", get(get_collection))
         .route("/collections/{name}/schema", get(get_collection_schema))
         .route("/collections/{name}/records", get(list_records))
-        .route("/collections/{name}/records/{id}", get(get_record));
+        .route(
+            "/collections/{name}/records/{id}",
+            get(get_record).layer(middleware::from_fn_with_state(
+                app_state.auth_state.clone(),
+                optional_auth_middleware,
+            )),
+        );
 
     let protected_routes = Router::new()
         .route("/auth/me", get(me))
@@ -49,7 +59,16 @@ async fn create_test_router() -> Router {
         .route("/collections/{name}/records/{id}", delete(delete_record))
         .route("/permissions/roles", post(create_role))
         .route("/permissions/roles", get(list_roles))
-        .route("/permissions/roles/{role_name}", get(get_role))
+        .route(
+            "/permissions/roles/{role_name}",
+            get(get_role).delete(delete_role),
+        )
+        .route(
+            "/permissions/defaults",
+            get(get_default_permissions).put(update_default_permissions),
+        )
+        .route("/permissions/export", get(export_permissions))
+        .route("/permissions/import", post(import_permissions))
         .route(
             "/permissions/collections/{name}",
             post(set_collection_permission),
@@ -58,6 +77,10 @@ async fn create_test_router() -> Router {
             "/permissions/collections/{name}",
             get(get_collection_permissions),
         )
+        .route(
+            "/permissions/collections/{name}/users",
+            get(list_collection_users),
+        )
         .route(
             "/permissions/users/{user_id}/collections/{name}",
             post(set_user_collection_permission),
@@ -70,6 +93,10 @@ async fn create_test_router() -> Router {
             "/permissions/users/{user_id}/collections",
             get(get_user_accessible_collections),
         )
+        .route(
+            "/permissions/users/{user_id}/records",
+            get(list_user_record_permissions).delete(revoke_user_record_permissions),
+        )
         .route(
             "/permissions/collections/{name}/records/{record_id}",
             post(set_record_permission),
@@ -106,6 +133,7 @@ async fn create_test_router() -> Router {
             "/ownership/collections/{name}/stats",
             get(get_ownership_stats),
         )
+        .route("/ownership/stats/global", get(get_global_ownership_stats))
         .layer(middleware::from_fn_with_state(
             app_state.auth_state.clone(),
             auth_middleware,
@@ -113,9 +141,11 @@ async fn create_test_router() -> Router {
 
     let api_routes = Router::new().merge(public_routes).merge(protected_routes);
 
-    let router = Router::new().nest("/api", api_routes).with_state(app_state);
+    let router = Router::new()
+        .nest("/api", api_routes)
+        .with_state(app_state.clone());
 
-    router
+    (router, app_state)
 }
 
 fn create_test_schema() -> CollectionSchema {
@@ -133,7 +163,15 @@ fn create_test_schema() -> CollectionSchema {
                     max_value: None,
                     pattern: None,
                     enum_values: None,
+                    precision: None,
+                    scale: None,
+                    source_field: None,
+                    number_format: None,
                 }),
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
             },
             FieldDefinition {
                 name: "content".to_string(),
@@ -147,7 +185,15 @@ fn create_test_schema() -> CollectionSchema {
                     max_value: None,
                     pattern: None,
                     enum_values: None,
+                    precision: None,
+                    scale: None,
+                    source_field: None,
+                    number_format: None,
                 }),
+                relation_target: None,
+                description: None,
+                example: None,
+                indexed_paths: None,
             },
         ],
     }
@@ -179,6 +225,9 @@ fn create_token_for_user(user_id: i32, role: &str) -> String {
         exp,
         iat: now,
         jti: uuid::Uuid::new_v4().to_string(),
+        guest: None,
+        workspace_id: None,
+        superadmin: false,
     };
 
     let jwt_secret = "test_permission_secret";
@@ -924,6 +973,72 @@ async fn test_ownership_full_scenario() {
 
     assert!(json_response["data"]["total_records"].as_i64().unwrap() >= 1);
     assert!(json_response["data"]["owned_records"].as_i64().unwrap() >= 1);
+
+    let global_stats_request = Request::builder()
+        .uri("/api/ownership/stats/global")
+        .method("GET")
+        .header("authorization", format!("Bearer {}", user1_token))
+        .body(Body::empty())
+        .unwrap();
+
+    let global_stats_response = app.clone().oneshot(global_stats_request).await.unwrap();
+    assert_eq!(global_stats_response.status(), StatusCode::FORBIDDEN);
+
+    {
+        use diesel::prelude::*;
+        use lunarbase::schema::users;
+
+        let config = common::create_test_config().expect("Failed to load config");
+        let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+        let mut conn = db_pool.get().expect("Failed to get database connection");
+        diesel::update(users::table.filter(users::id.eq(user2_id)))
+            .set(users::is_active.eq(false))
+            .execute(&mut conn)
+            .expect("Failed to deactivate user");
+    }
+
+    let global_stats_request = Request::builder()
+        .uri("/api/ownership/stats/global?orphan_limit=5")
+        .method("GET")
+        .header("authorization", format!("Bearer {}", admin_token))
+        .body(Body::empty())
+        .unwrap();
+
+    let global_stats_response = app.clone().oneshot(global_stats_request).await.unwrap();
+    assert_eq!(global_stats_response.status(), StatusCode::OK);
+
+    let body = global_stats_response
+        .into_body()
+        .collect()
+        .await
+        .unwrap()
+        .to_bytes();
+    let json_response: Value = serde_json::from_slice(&body).unwrap();
+    let data = &json_response["data"];
+
+    assert!(data["orphaned_records"].as_array().unwrap().len() <= 5);
+
+    let collection_stats = data["collections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["collection_name"] == unique_name.as_str())
+        .expect("collection missing from global stats");
+    assert_eq!(collection_stats["ownership_field"], "owner_id");
+    assert_eq!(collection_stats["total_records"], 1);
+    assert_eq!(collection_stats["orphaned_records"], 1);
+
+    let user2_stats = data["users"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|u| u["user_id"] == user2_id)
+        .expect("new owner missing from global stats");
+    assert_eq!(user2_stats["is_active"], false);
+    assert_eq!(
+        user2_stats["records_per_collection"][unique_name.as_str()],
+        1
+    );
 }
 
 #[tokio::test]
@@ -1949,3 +2064,1217 @@ async fn test_user_permissions_unauthorized_access() {
     assert_eq!(json_response["data"]["permissions"]["can_delete"], false);
     assert_eq!(json_response["data"]["permissions"]["can_list"], false);
 }
+
+#[tokio::test]
+async fn test_default_permissions_are_configurable() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let (_user_id, user_token) = create_test_user(&app, "user").await;
+
+    let send = |method: &'static str, uri: String, token: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+    let role_permissions = |collection: String, role: &'static str| {
+        send(
+            "GET",
+            format!(
+                "/api/permissions/collections/{}?role_name={}",
+                collection, role
+            ),
+            admin_token.clone(),
+            None,
+        )
+    };
+
+    let response = send(
+        "GET",
+        "/api/permissions/defaults".to_string(),
+        user_token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send(
+        "GET",
+        "/api/permissions/defaults".to_string(),
+        admin_token.clone(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let original = read_json(response).await["data"].clone();
+    assert_eq!(original["admin"]["can_delete"], true);
+
+    let mut weakened_admin = original.clone();
+    weakened_admin["admin"]["can_delete"] = json!(false);
+    let response = send(
+        "PUT",
+        "/api/permissions/defaults".to_string(),
+        admin_token.clone(),
+        Some(weakened_admin),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let no_access = json!({
+        "can_create": false,
+        "can_read": false,
+        "can_update": false,
+        "can_delete": false,
+        "can_list": false
+    });
+    let mut private_for_guests = original.clone();
+    private_for_guests["guest"] = no_access.clone();
+    let response = send(
+        "PUT",
+        "/api/permissions/defaults".to_string(),
+        admin_token.clone(),
+        Some(private_for_guests),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Collections created from now on use the new defaults
+    let collection_name = unique_collection_name("private_defaults");
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        admin_token.clone(),
+        Some(json!({ "name": collection_name, "schema": create_test_schema() })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = role_permissions(collection_name.clone(), "guest")
+        .await
+        .unwrap();
+    assert_eq!(read_json(response).await["data"]["permissions"], no_access);
+    let response = role_permissions(collection_name, "user").await.unwrap();
+    assert_eq!(
+        read_json(response).await["data"]["permissions"],
+        original["user"]
+    );
+
+    let response = send(
+        "PUT",
+        "/api/permissions/defaults".to_string(),
+        admin_token.clone(),
+        Some(original),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // An explicit permission set replaces the defaults; unlisted roles get nothing
+    let explicit_name = unique_collection_name("explicit_permissions");
+    let editor = json!({
+        "role_name": "user",
+        "can_create": true,
+        "can_read": true,
+        "can_update": true,
+        "can_delete": true,
+        "can_list": true
+    });
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        admin_token.clone(),
+        Some(json!({
+            "name": explicit_name,
+            "schema": create_test_schema(),
+            "permissions": [editor]
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = role_permissions(explicit_name.clone(), "user")
+        .await
+        .unwrap();
+    assert_eq!(
+        read_json(response).await["data"]["permissions"]["can_delete"],
+        true
+    );
+    let response = role_permissions(explicit_name, "guest").await.unwrap();
+    assert_eq!(read_json(response).await["data"]["permissions"], no_access);
+
+    for permissions in [
+        json!([{ "role_name": "no_such_role", "can_create": false, "can_read": true, "can_update": false, "can_delete": false, "can_list": true }]),
+        json!([{ "role_name": "admin", "can_create": false, "can_read": true, "can_update": false, "can_delete": false, "can_list": true }]),
+    ] {
+        let response = send(
+            "POST",
+            "/api/collections".to_string(),
+            admin_token.clone(),
+            Some(json!({
+                "name": unique_collection_name("invalid_permissions"),
+                "schema": create_test_schema(),
+                "permissions": permissions
+            })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_list_users_with_collection_access() {
+    let app = create_test_router().await;
+    let (admin_id, admin_token) = create_admin_token(&app).await;
+    let viewer_role = format!(
+        "viewer_{}",
+        &uuid::Uuid::new_v4().simple().to_string()[0..8]
+    );
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", admin_token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let response = send(
+        "POST",
+        "/api/permissions/roles".to_string(),
+        Some(json!({ "name": viewer_role, "priority": 10 })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (viewer_id, _) = create_test_user(&app, &viewer_role).await;
+    let (editor_id, _) = create_test_user(&app, "user").await;
+    let (record_reader_id, _) = create_test_user(&app, "user").await;
+    let (outsider_id, _) = create_test_user(&app, "user").await;
+
+    // Only the viewer role gets collection access; the user role gets none
+    let collection_name = unique_collection_name("access_listing");
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({
+            "name": collection_name,
+            "schema": create_test_schema(),
+            "permissions": [{
+                "role_name": viewer_role,
+                "can_create": false,
+                "can_read": true,
+                "can_update": false,
+                "can_delete": false,
+                "can_list": true
+            }]
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = send(
+        "POST",
+        format!(
+            "/api/permissions/users/{}/collections/{}",
+            editor_id, collection_name
+        ),
+        Some(json!({ "can_create": true })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let boundary = "boundary";
+    let body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+        boundary,
+        json!({ "title": "Shared" }),
+        boundary
+    );
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let record_id = read_json(response).await["data"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let response = send(
+        "POST",
+        format!(
+            "/api/permissions/collections/{}/records/{}",
+            collection_name, record_id
+        ),
+        Some(json!({
+            "user_id": record_reader_id,
+            "record_id": record_id,
+            "can_read": true,
+            "can_update": false,
+            "can_delete": false
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Users are ordered by id, so the ones created above are on the last page
+    let list = |access: &'static str| {
+        let collection_name = collection_name.clone();
+        let send = &send;
+        let read_json = &read_json;
+        async move {
+            let uri = |offset: i64| {
+                format!(
+                    "/api/permissions/collections/{}/users?access={}&limit=200&offset={}",
+                    collection_name, access, offset
+                )
+            };
+            let response = send("GET", uri(0), None).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let total = read_json(response).await["data"]["total_count"]
+                .as_i64()
+                .unwrap();
+            let response = send("GET", uri((total - 200).max(0)), None).await.unwrap();
+            read_json(response).await["data"]["users"]
+                .as_array()
+                .unwrap()
+                .clone()
+        }
+    };
+    let find = |users: &[Value], user_id: i32| {
+        users
+            .iter()
+            .find(|user| user["user_id"] == user_id)
+            .cloned()
+    };
+
+    let any = list("any").await;
+    let viewer = find(&any, viewer_id).expect("viewer has access through the role");
+    assert_eq!(
+        viewer["can_read"],
+        json!({ "granted": true, "source": "role" })
+    );
+    assert_eq!(
+        viewer["can_create"],
+        json!({ "granted": false, "source": "role" })
+    );
+    let editor = find(&any, editor_id).expect("editor has access through an override");
+    assert_eq!(
+        editor["can_create"],
+        json!({ "granted": true, "source": "user_override" })
+    );
+    assert_eq!(editor["can_read"]["granted"], false);
+    let reader = find(&any, record_reader_id).expect("record reader has access");
+    assert_eq!(reader["record_permissions"]["records"], 1);
+    assert_eq!(reader["record_permissions"]["can_read"], true);
+    assert!(find(&any, outsider_id).is_none());
+    let admin = find(&any, admin_id).expect("admins always have access");
+    assert_eq!(admin["can_delete"]["source"], "admin");
+
+    let read = list("read").await;
+    assert!(find(&read, viewer_id).is_some());
+    assert!(find(&read, record_reader_id).is_some());
+    assert!(find(&read, editor_id).is_none());
+
+    let write = list("write").await;
+    assert!(find(&write, editor_id).is_some());
+    assert!(find(&write, viewer_id).is_none());
+    assert!(find(&write, record_reader_id).is_none());
+}
+
+#[tokio::test]
+async fn test_role_permissions_inherit_from_lower_priority_roles() {
+    let (app, state) = create_test_router_with_state().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let suffix = &uuid::Uuid::new_v4().simple().to_string()[0..8];
+    let staff_role = format!("staff_{}", suffix);
+    let editor_role = format!("editor_{}", suffix);
+    let manager_role = format!("manager_{}", suffix);
+
+    // Only this router's configuration cache sees inheritance enabled
+    state
+        .configuration_manager
+        .update_cache("system", "permission_inheritance_enabled", "true")
+        .await;
+
+    let send = |method: &'static str, uri: String, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", admin_token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let collection_name = unique_collection_name("inheritance");
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        Some(json!({
+            "name": collection_name,
+            "schema": create_test_schema(),
+            "permissions": []
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Roles created after the collection have no permission rows for it
+    // Priorities no other test uses, so no other role sits between them
+    for (role, priority) in [(&staff_role, 70), (&editor_role, 71), (&manager_role, 71)] {
+        let response = send(
+            "POST",
+            "/api/permissions/roles".to_string(),
+            Some(json!({ "name": role, "priority": priority })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    for (role, can_create) in [(&staff_role, false), (&manager_role, true)] {
+        let response = send(
+            "POST",
+            format!("/api/permissions/collections/{}", collection_name),
+            Some(json!({
+                "role_name": role,
+                "can_create": can_create,
+                "can_read": true,
+                "can_update": false,
+                "can_delete": false,
+                "can_list": true
+            })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let (editor_id, _) = create_test_user(&app, &editor_role).await;
+
+    // The editor inherits from staff, not from the manager role of equal priority
+    let role_permissions = |role: String| {
+        send(
+            "GET",
+            format!(
+                "/api/permissions/collections/{}?role_name={}",
+                collection_name, role
+            ),
+            None,
+        )
+    };
+    let response = role_permissions(editor_role.clone()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let editor = read_json(response).await;
+    assert_eq!(editor["data"]["inherited_from"], staff_role.as_str());
+    assert_eq!(editor["data"]["permissions"]["can_read"], true);
+    assert_eq!(editor["data"]["permissions"]["can_create"], false);
+
+    let response = role_permissions(manager_role.clone()).await.unwrap();
+    let manager = read_json(response).await;
+    assert!(manager["data"]["inherited_from"].is_null());
+    assert_eq!(manager["data"]["permissions"]["can_create"], true);
+
+    let user_permissions = || {
+        send(
+            "GET",
+            format!(
+                "/api/permissions/users/{}/collections/{}",
+                editor_id, collection_name
+            ),
+            None,
+        )
+    };
+    let response = user_permissions().await.unwrap();
+    let permissions = read_json(response).await;
+    assert_eq!(permissions["data"]["permissions"]["can_read"], true);
+    assert_eq!(permissions["data"]["permissions"]["can_create"], false);
+
+    let response = send(
+        "GET",
+        format!(
+            "/api/permissions/collections/{}/users?access=read&limit=200",
+            collection_name
+        ),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let total = read_json(response).await["data"]["total_count"]
+        .as_i64()
+        .unwrap();
+    let response = send(
+        "GET",
+        format!(
+            "/api/permissions/collections/{}/users?access=read&limit=200&offset={}",
+            collection_name,
+            (total - 200).max(0)
+        ),
+        None,
+    )
+    .await
+    .unwrap();
+    let users = read_json(response).await["data"]["users"].clone();
+    let editor = users
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|user| user["user_id"] == editor_id)
+        .cloned()
+        .expect("editor reads through the inherited staff permissions");
+    assert_eq!(editor["inherited_from"], staff_role.as_str());
+    assert_eq!(
+        editor["can_read"],
+        json!({ "granted": true, "source": "inherited_role" })
+    );
+
+    // Without inheritance the editor role has no permissions of its own
+    state
+        .configuration_manager
+        .update_cache("system", "permission_inheritance_enabled", "false")
+        .await;
+
+    let response = role_permissions(editor_role.clone()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = user_permissions().await.unwrap();
+    let permissions = read_json(response).await;
+    assert_eq!(permissions["data"]["permissions"]["can_read"], false);
+
+    // The editor role still has a user assigned
+    for role in [&staff_role, &manager_role] {
+        let response = send("DELETE", format!("/api/permissions/roles/{}", role), None)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn test_denied_record_read_does_not_load_record() {
+    let (app, state) = create_test_router_with_state().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let (reader_id, reader_token) = create_test_user(&app, "user").await;
+
+    let send = |method: &'static str, uri: String, token: &str, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+
+    // An explicit empty permission list leaves the user role without access
+    let collection_name = unique_collection_name("read_probe");
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        &admin_token,
+        Some(json!({
+            "name": collection_name,
+            "schema": create_test_schema(),
+            "permissions": []
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let boundary = "boundary";
+    let body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+        boundary,
+        json!({ "title": "Large record", "content": "x".repeat(900) }),
+        boundary
+    );
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let record_id = serde_json::from_slice::<Value>(&body).unwrap()["data"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let record_uri = format!("/api/collections/{}/records/{}", collection_name, record_id);
+
+    // Every single-record read goes through the record cache, hit or miss
+    let record_reads = || {
+        let stats = state.collection_service.record_cache.stats();
+        stats.hits + stats.misses
+    };
+    let reads_before = record_reads();
+    let response = send("GET", record_uri.clone(), &reader_token, None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(record_reads(), reads_before);
+
+    let response = send(
+        "GET",
+        format!("/api/collections/{}/records/999999", collection_name),
+        &reader_token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(record_reads(), reads_before);
+
+    // A record-level grant is honoured by the probe as well
+    let response = send(
+        "POST",
+        format!(
+            "/api/permissions/collections/{}/records/{}",
+            collection_name, record_id
+        ),
+        &admin_token,
+        Some(json!({
+            "user_id": reader_id,
+            "record_id": record_id,
+            "can_read": true,
+            "can_update": false,
+            "can_delete": false
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send("GET", record_uri, &reader_token, None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(record_reads() > reads_before);
+}
+
+#[tokio::test]
+async fn test_permission_changes_apply_to_the_next_request_despite_caching() {
+    let app = create_test_router().await;
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let (user_id, user_token) = create_test_user(&app, "user").await;
+
+    let send = |method: &'static str, uri: String, token: &str, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+
+    let collection_name = unique_collection_name("cached_permissions");
+    let response = send(
+        "POST",
+        "/api/collections".to_string(),
+        &admin_token,
+        Some(json!({
+            "name": collection_name,
+            "schema": create_test_schema(),
+            "permissions": []
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let boundary = "boundary";
+    let body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+        boundary,
+        json!({ "title": "Cached", "content": "Permission cache probe" }),
+        boundary
+    );
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/collections/{}/records", collection_name))
+                .method("POST")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let record_id = serde_json::from_slice::<Value>(&body).unwrap()["data"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let record_uri = format!("/api/collections/{}/records/{}", collection_name, record_id);
+
+    let set_role_read = |can_read: bool| {
+        send(
+            "POST",
+            format!("/api/permissions/collections/{}", collection_name),
+            &admin_token,
+            Some(json!({
+                "role_name": "user",
+                "collection_name": collection_name,
+                "can_create": false,
+                "can_read": can_read,
+                "can_update": false,
+                "can_delete": false,
+                "can_list": false
+            })),
+        )
+    };
+
+    assert_eq!(set_role_read(true).await.unwrap().status(), StatusCode::OK);
+    // Repeated reads are served from the cache
+    for _ in 0..2 {
+        let response = send("GET", record_uri.clone(), &user_token, None)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    assert_eq!(set_role_read(false).await.unwrap().status(), StatusCode::OK);
+    let response = send("GET", record_uri.clone(), &user_token, None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send(
+        "POST",
+        format!(
+            "/api/permissions/users/{}/collections/{}",
+            user_id, collection_name
+        ),
+        &admin_token,
+        Some(json!({
+            "owner_id": user_id,
+            "collection_name": collection_name,
+            "can_read": true
+        })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = send("GET", record_uri, &user_token, None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_list_and_revoke_user_record_permissions() {
+    use diesel::prelude::*;
+    use lunarbase::schema::permission_audit_entries;
+
+    let (app, app_state) = create_test_router_with_state().await;
+    let (admin_id, admin_token) = create_admin_token(&app).await;
+    let (reader_id, reader_token) = create_test_user(&app, "user").await;
+
+    let send = |method: &'static str, uri: String, token: &str, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    // One granted record in each of two collections
+    let mut collection_names = Vec::new();
+    for prefix in ["grants_a", "grants_b"] {
+        let collection_name = unique_collection_name(prefix);
+        let response = send(
+            "POST",
+            "/api/collections".to_string(),
+            &admin_token,
+            Some(json!({ "name": collection_name, "schema": create_test_schema() })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let boundary = "boundary";
+        let body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"data\"\r\nContent-Type: application/json\r\n\r\n{}\r\n--{}--\r\n",
+            boundary,
+            json!({ "title": "Granted" }),
+            boundary
+        );
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/collections/{}/records", collection_name))
+                    .method("POST")
+                    .header(
+                        "content-type",
+                        format!("multipart/form-data; boundary={}", boundary),
+                    )
+                    .header("authorization", format!("Bearer {}", admin_token))
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let record_id = read_json(response).await["data"]["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = send(
+            "POST",
+            format!(
+                "/api/permissions/collections/{}/records/{}",
+                collection_name, record_id
+            ),
+            &admin_token,
+            Some(json!({
+                "user_id": reader_id,
+                "record_id": record_id,
+                "can_read": true,
+                "can_update": false,
+                "can_delete": false
+            })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        collection_names.push(collection_name);
+    }
+
+    let grants_uri = format!("/api/permissions/users/{}/records", reader_id);
+    let response = send("GET", grants_uri.clone(), &reader_token, None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send("GET", grants_uri.clone(), &admin_token, None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let page = read_json(response).await["data"].clone();
+    assert_eq!(page["total_count"], 2);
+    let grants = page["grants"].as_array().unwrap();
+    assert_eq!(grants[0]["collection_name"], collection_names[0].as_str());
+    assert_eq!(grants[1]["collection_name"], collection_names[1].as_str());
+    assert_eq!(grants[0]["can_read"], true);
+    assert_eq!(grants[0]["granted_by"], admin_id);
+    assert!(grants[0]["granted_by_email"].is_string());
+    assert!(grants[0]["granted_at"].is_string());
+
+    let response = send(
+        "DELETE",
+        format!("{}?collection={}", grants_uri, collection_names[0]),
+        &admin_token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["data"]["revoked"], 1);
+
+    let response = send("GET", grants_uri.clone(), &admin_token, None)
+        .await
+        .unwrap();
+    let page = read_json(response).await["data"].clone();
+    assert_eq!(page["total_count"], 1);
+    assert_eq!(
+        page["grants"][0]["collection_name"],
+        collection_names[1].as_str()
+    );
+
+    let response = send("DELETE", grants_uri.clone(), &admin_token, None)
+        .await
+        .unwrap();
+    assert_eq!(read_json(response).await["data"]["revoked"], 1);
+
+    let response = send(
+        "DELETE",
+        format!("{}?collection=missing_collection", grants_uri),
+        &admin_token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let mut conn = app_state.db_pool.get().unwrap();
+    let actions: Vec<(Option<i32>, String, Option<String>, i64)> = permission_audit_entries::table
+        .filter(permission_audit_entries::target_user_id.eq(reader_id))
+        .order(permission_audit_entries::id.asc())
+        .select((
+            permission_audit_entries::actor_id,
+            permission_audit_entries::action,
+            permission_audit_entries::collection_name,
+            permission_audit_entries::affected_count,
+        ))
+        .load(&mut conn)
+        .unwrap();
+    assert_eq!(
+        actions,
+        vec![
+            (
+                Some(admin_id),
+                "list_record_permissions".to_string(),
+                None,
+                2
+            ),
+            (
+                Some(admin_id),
+                "revoke_record_permissions".to_string(),
+                Some(collection_names[0].clone()),
+                1
+            ),
+            (
+                Some(admin_id),
+                "list_record_permissions".to_string(),
+                None,
+                1
+            ),
+            (
+                Some(admin_id),
+                "revoke_record_permissions".to_string(),
+                None,
+                1
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_permission_policy_export_and_import() {
+    use diesel::prelude::*;
+    use lunarbase::schema::{permission_audit_entries, users};
+
+    let (app, app_state) = create_test_router_with_state().await;
+    let (admin_id, admin_token) = create_admin_token(&app).await;
+    let (user_id, user_token) = create_test_user(&app, "user").await;
+    let user_email: String = users::table
+        .find(user_id)
+        .select(users::email)
+        .first(&mut app_state.db_pool.get().unwrap())
+        .unwrap();
+
+    let send = |method: &'static str, uri: &str, token: &str, body: Option<Value>| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("authorization", format!("Bearer {}", token));
+        if body.is_some() {
+            request = request.header("content-type", "application/json");
+        }
+        app.clone().oneshot(
+            request
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap(),
+        )
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let collection_name = unique_collection_name("policy");
+    let response = send(
+        "POST",
+        "/api/collections",
+        &admin_token,
+        Some(json!({ "name": collection_name, "schema": create_test_schema() })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = send("GET", "/api/permissions/export", &user_token, None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send("GET", "/api/permissions/export", &admin_token, None)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let exported = read_json(response).await["data"].clone();
+    assert_eq!(exported["version"], 1);
+    let exported_collection = exported["collections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|collection| collection["name"] == collection_name.as_str())
+        .unwrap();
+    assert_eq!(exported_collection["roles"]["admin"]["can_delete"], true);
+
+    let role_name = format!("policy_{}", &uuid::Uuid::new_v4().simple().to_string()[..8]);
+    let editor = json!({
+        "can_create": true, "can_read": true, "can_update": true,
+        "can_delete": false, "can_list": true
+    });
+    let policy = json!({
+        "version": 1,
+        "roles": [{ "name": role_name, "description": "Edits articles", "priority": 40 }],
+        "collections": [{
+            "name": collection_name,
+            "roles": { "admin": exported_collection["roles"]["admin"], role_name.clone(): editor },
+            "users": { user_email.clone(): { "can_read": false } }
+        }]
+    });
+
+    // Unknown collections, roles and users are reported where they occur
+    let mut broken = policy.clone();
+    broken["collections"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({ "name": "missing_collection", "roles": {} }));
+    broken["collections"][0]["roles"]["ghost_role"] = editor.clone();
+    broken["collections"][0]["users"]["nobody@example.com"] = json!({ "can_read": true });
+
+    let response = send(
+        "POST",
+        "/api/permissions/import?dry_run=true",
+        &admin_token,
+        Some(broken.clone()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report = read_json(response).await["data"].clone();
+    assert_eq!(report["applied"], false);
+    let locations: Vec<&str> = report["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| error["location"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        locations,
+        vec![
+            "collections[0].roles.ghost_role",
+            "collections[0].users.nobody@example.com",
+            "collections[1].name",
+        ]
+    );
+
+    let response = send(
+        "POST",
+        "/api/permissions/import",
+        &admin_token,
+        Some(broken),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = send(
+        "GET",
+        &format!("/api/permissions/roles/{}", role_name),
+        &admin_token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // A dry run shows the plan without applying it
+    let response = send(
+        "POST",
+        "/api/permissions/import?dry_run=true",
+        &admin_token,
+        Some(policy.clone()),
+    )
+    .await
+    .unwrap();
+    let report = read_json(response).await["data"].clone();
+    assert_eq!(report["applied"], false);
+    assert!(report["errors"].as_array().unwrap().is_empty());
+    let actions: Vec<&str> = report["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|change| change["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(actions[0], "create_role");
+    assert!(actions.contains(&"set_role_permissions"));
+    assert!(actions.contains(&"remove_role_permissions"));
+    assert!(actions.contains(&"set_user_override"));
+    let response = send(
+        "GET",
+        &format!("/api/permissions/roles/{}", role_name),
+        &admin_token,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = send(
+        "POST",
+        "/api/permissions/import",
+        &admin_token,
+        Some(policy.clone()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report = read_json(response).await["data"].clone();
+    assert_eq!(report["applied"], true);
+    let change_count = report["changes"].as_array().unwrap().len();
+
+    let response = send("GET", "/api/permissions/export", &admin_token, None)
+        .await
+        .unwrap();
+    let exported = read_json(response).await["data"].clone();
+    let exported_collection = exported["collections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|collection| collection["name"] == collection_name.as_str())
+        .unwrap()
+        .clone();
+    assert_eq!(
+        exported_collection["roles"],
+        policy["collections"][0]["roles"]
+    );
+    assert_eq!(
+        exported_collection["users"],
+        policy["collections"][0]["users"]
+    );
+    assert!(
+        exported["roles"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|role| role["name"] == role_name.as_str() && role["priority"] == 40)
+    );
+
+    // Importing the same document again changes nothing
+    let response = send(
+        "POST",
+        "/api/permissions/import",
+        &admin_token,
+        Some(policy),
+    )
+    .await
+    .unwrap();
+    let report = read_json(response).await["data"].clone();
+    assert!(report["changes"].as_array().unwrap().is_empty());
+
+    let mut conn = app_state.db_pool.get().unwrap();
+    let entries: Vec<(Option<i32>, Option<i32>, String, Option<String>)> =
+        permission_audit_entries::table
+            .filter(permission_audit_entries::actor_id.eq(admin_id))
+            .order(permission_audit_entries::id.asc())
+            .select((
+                permission_audit_entries::actor_id,
+                permission_audit_entries::target_user_id,
+                permission_audit_entries::action,
+                permission_audit_entries::role_name,
+            ))
+            .load(&mut conn)
+            .unwrap();
+    assert_eq!(entries.len(), change_count);
+    assert_eq!(
+        entries[0],
+        (
+            Some(admin_id),
+            None,
+            "policy_create_role".to_string(),
+            Some(role_name.clone())
+        )
+    );
+    assert!(entries.contains(&(
+        Some(admin_id),
+        Some(user_id),
+        "policy_set_user_override".to_string(),
+        None
+    )));
+}
@@ -0,0 +1,149 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, Version},
+};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use std::net::SocketAddr;
+use tokio::net::TcpStream;
+
+use lunarbase::AppState;
+use lunarbase::database::create_pool;
+use lunarbase::listeners::{CleartextAcceptor, ServerTuning, bind_tcp_listeners, tcp_server};
+use lunarbase::server::build_routes;
+
+mod common;
+
+async fn create_app_state() -> AppState {
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+
+    AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState")
+}
+
+async fn start_server(tuning: ServerTuning) -> (SocketAddr, axum_server::Handle) {
+    let app = build_routes(create_app_state().await, true);
+    let listener = bind_tcp_listeners(&["127.0.0.1:0".parse().unwrap()])
+        .unwrap()
+        .remove(0);
+    let address = listener.local_addr().unwrap();
+    let handle = axum_server::Handle::new();
+
+    let server =
+        tcp_server(listener, &tuning, handle.clone()).acceptor(CleartextAcceptor::new(&tuning));
+    tokio::spawn(server.serve(app.into_make_service_with_connect_info::<SocketAddr>()));
+    (address, handle)
+}
+
+async fn http1_get(
+    address: SocketAddr,
+    request: Request<Body>,
+) -> hyper::Result<hyper::Response<hyper::body::Incoming>> {
+    let stream = TcpStream::connect(address).await.unwrap();
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .unwrap();
+    tokio::spawn(connection);
+    sender.send_request(request).await
+}
+
+async fn h2c_get(
+    address: SocketAddr,
+    path: &str,
+) -> hyper::Result<hyper::Response<hyper::body::Incoming>> {
+    let stream = TcpStream::connect(address).await.unwrap();
+    let (mut sender, connection) =
+        hyper::client::conn::http2::handshake(TokioExecutor::new(), TokioIo::new(stream)).await?;
+    tokio::spawn(connection);
+    sender
+        .send_request(
+            Request::builder()
+                .uri(format!("http://{}{}", address, path))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+}
+
+fn health_request(address: SocketAddr) -> Request<Body> {
+    Request::builder()
+        .uri("/api/health")
+        .header("host", address.to_string())
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_plain_listener_serves_http1_and_websockets_without_h2c() {
+    let (address, handle) = start_server(ServerTuning::default()).await;
+
+    let response = http1_get(address, health_request(address)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.version(), Version::HTTP_11);
+
+    // Prior-knowledge HTTP/2 is refused unless h2c is enabled
+    assert!(h2c_get(address, "/api/health").await.is_err());
+
+    let (mut socket, response) =
+        tokio_tungstenite::connect_async(format!("ws://{}/api/ws", address))
+            .await
+            .expect("WebSocket upgrade should succeed over HTTP/1.1");
+    assert_eq!(response.status(), StatusCode::SWITCHING_PROTOCOLS);
+    socket.close(None).await.unwrap();
+
+    handle.shutdown();
+}
+
+#[tokio::test]
+async fn test_h2c_listener_serves_both_protocol_versions() {
+    let (address, handle) = start_server(ServerTuning {
+        http2_cleartext: true,
+        ..ServerTuning::default()
+    })
+    .await;
+
+    let response = h2c_get(address, "/api/health").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.version(), Version::HTTP_2);
+
+    let response = http1_get(address, health_request(address)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.version(), Version::HTTP_11);
+
+    // WebSockets keep upgrading HTTP/1.1 connections when h2c is enabled
+    let (mut socket, response) =
+        tokio_tungstenite::connect_async(format!("ws://{}/api/ws", address))
+            .await
+            .expect("WebSocket upgrade should succeed over HTTP/1.1");
+    assert_eq!(response.status(), StatusCode::SWITCHING_PROTOCOLS);
+    socket.close(None).await.unwrap();
+
+    handle.shutdown();
+}
+
+#[tokio::test]
+async fn test_oversized_headers_are_rejected() {
+    let (address, handle) = start_server(ServerTuning {
+        max_header_size: 8 * 1024,
+        ..ServerTuning::default()
+    })
+    .await;
+
+    let request = Request::builder()
+        .uri("/api/health")
+        .header("host", address.to_string())
+        .header("x-padding", "a".repeat(16 * 1024))
+        .body(Body::empty())
+        .unwrap();
+    let response = http1_get(address, request).await.unwrap();
+    assert_eq!(
+        response.status(),
+        StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+    );
+
+    let response = http1_get(address, health_request(address)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    handle.shutdown();
+}
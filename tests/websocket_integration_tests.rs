@@ -123,6 +123,9 @@ fn create_token_for_user(user_id: i32, email: &str, role: &str) -> String {
         exp,
         iat: now,
         jti: uuid::Uuid::new_v4().to_string(),
+        guest: None,
+        workspace_id: None,
+        superadmin: false,
     };
 
     let jwt_secret = "test_secret".to_string();
@@ -737,3 +740,887 @@ async fn test_record_specific_subscription() {
     assert!(subscription.matches_event(&matching_event));
     assert!(!subscription.matches_event(&non_matching_event));
 }
+
+#[tokio::test]
+async fn test_ownership_transferred_event_serialization_and_matching() {
+    use lunarbase::models::{PendingEvent, RecordEvent, SubscriptionData, SubscriptionType};
+
+    let event = RecordEvent::OwnershipTransferred {
+        record_id: "7".to_string(),
+        old_owner_id: Some(1),
+        new_owner_id: 2,
+    };
+
+    let serialized = serde_json::to_value(&event).unwrap();
+    assert_eq!(
+        serialized,
+        json!({
+            "action": "OwnershipTransferred",
+            "record_id": "7",
+            "old_owner_id": 1,
+            "new_owner_id": 2
+        })
+    );
+
+    let pending_event = PendingEvent {
+        collection_name: "articles".to_string(),
+        event,
+        user_id: Some(1),
+    };
+
+    let record_subscription = SubscriptionData::new(
+        "articles".to_string(),
+        SubscriptionType::Record {
+            record_id: "7".to_string(),
+        },
+        None,
+        Some(1),
+    );
+    let other_record_subscription = SubscriptionData::new(
+        "articles".to_string(),
+        SubscriptionType::Record {
+            record_id: "8".to_string(),
+        },
+        None,
+        Some(1),
+    );
+
+    assert!(record_subscription.matches_event(&pending_event));
+    assert!(!other_record_subscription.matches_event(&pending_event));
+}
+
+#[tokio::test]
+async fn test_bulk_change_serialization_and_matching() {
+    use lunarbase::models::{
+        BulkChange, BulkChangeMessage, PendingBulkChange, SubscriptionData, SubscriptionType,
+        WebSocketMessage,
+    };
+    use std::collections::{HashMap, HashSet};
+    use std::sync::Arc;
+
+    let change = BulkChange {
+        collection: "articles".to_string(),
+        created: 250,
+        updated: 0,
+        deleted: 1,
+        sample_ids: vec!["7".to_string()],
+    };
+    let message = WebSocketMessage::BulkChange(BulkChangeMessage {
+        subscription_id: "sub".to_string(),
+        change: change.clone(),
+    });
+    assert_eq!(
+        serde_json::to_value(&message).unwrap(),
+        json!({
+            "type": "BulkChange",
+            "data": {
+                "subscription_id": "sub",
+                "change": {
+                    "collection": "articles",
+                    "created": 250,
+                    "updated": 0,
+                    "deleted": 1,
+                    "sample_ids": ["7"]
+                }
+            }
+        })
+    );
+
+    let bulk = PendingBulkChange {
+        change,
+        record_ids: Arc::new(HashSet::from(["7".to_string(), "300".to_string()])),
+    };
+    let subscription = |collection: &str, subscription_type| {
+        SubscriptionData::new(collection.to_string(), subscription_type, None, None)
+    };
+
+    assert!(subscription("articles", SubscriptionType::Collection).matches_bulk_change(&bulk));
+    assert!(
+        subscription(
+            "articles",
+            SubscriptionType::Query {
+                filters: HashMap::new()
+            }
+        )
+        .matches_bulk_change(&bulk)
+    );
+    assert!(
+        subscription(
+            "articles",
+            SubscriptionType::Record {
+                record_id: "300".to_string()
+            }
+        )
+        .matches_bulk_change(&bulk)
+    );
+    assert!(
+        !subscription(
+            "articles",
+            SubscriptionType::Record {
+                record_id: "8".to_string()
+            }
+        )
+        .matches_bulk_change(&bulk)
+    );
+    assert!(!subscription("posts", SubscriptionType::Collection).matches_bulk_change(&bulk));
+}
+
+#[tokio::test]
+async fn test_system_notice_serialization() {
+    use lunarbase::models::{
+        QuotaKind, QuotaWarning, SystemNotice, SystemNoticeMessage, WebSocketMessage,
+    };
+
+    let message = WebSocketMessage::SystemNotice(SystemNoticeMessage {
+        subscription_id: "sub".to_string(),
+        notice: SystemNotice::QuotaWarning(QuotaWarning {
+            quota: QuotaKind::ConcurrentQueries,
+            subject: "7".to_string(),
+            used: 4,
+            limit: 5,
+            threshold_percent: 80,
+            triggered_at: "2025-10-02T09:00:00+00:00".to_string(),
+        }),
+    });
+    assert_eq!(
+        serde_json::to_value(&message).unwrap(),
+        json!({
+            "type": "SystemNotice",
+            "data": {
+                "subscription_id": "sub",
+                "notice": {
+                    "notice": "QuotaWarning",
+                    "quota": "concurrent_queries",
+                    "subject": "7",
+                    "used": 4,
+                    "limit": 5,
+                    "threshold_percent": 80,
+                    "triggered_at": "2025-10-02T09:00:00+00:00"
+                }
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_ownership_transfer_is_recorded_in_activity_log() {
+    use diesel::prelude::*;
+    use lunarbase::models::{
+        CollectionSchema, CollectionWorkflow, CreateCollectionRequest, CreateRecordRequest,
+        FieldDefinition, FieldType, User,
+    };
+    use lunarbase::schema::users;
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let app_state = AppState::new(
+        db_pool.clone(),
+        "test_secret",
+        "test_pepper".to_string(),
+        &config,
+    )
+    .await
+    .expect("Failed to create AppState");
+
+    let app = create_test_router().await;
+    let (admin_id, _admin_token) = create_admin_token(&app).await;
+    let (new_owner_id, _user_token) = create_test_user(&app, "user").await;
+
+    let collection_name = format!("owned_{}", uuid::Uuid::new_v4().simple());
+    app_state
+        .collection_service
+        .create_collection(
+            CreateCollectionRequest {
+                name: collection_name.clone(),
+                display_name: None,
+                description: None,
+                schema: CollectionSchema {
+                    fields: vec![FieldDefinition {
+                        name: "owner_id".to_string(),
+                        field_type: FieldType::Number,
+                        required: false,
+                        default_value: None,
+                        validation: None,
+                        relation_target: None,
+                        description: None,
+                        example: None,
+                        indexed_paths: None,
+                    }],
+                },
+                orderable: false,
+                id_type: Default::default(),
+                allow_explicit_ids: false,
+                workflow: CollectionWorkflow::None,
+                schedule: None,
+                retention: None,
+                permissions: None,
+            },
+            Some(admin_id),
+        )
+        .await
+        .expect("Failed to create collection");
+
+    let record = app_state
+        .collection_service
+        .create_record(
+            &collection_name,
+            CreateRecordRequest {
+                data: json!({"owner_id": admin_id}),
+                files: None,
+            },
+        )
+        .await
+        .expect("Failed to create record");
+
+    let admin: User = users::table
+        .find(admin_id)
+        .select(User::as_select())
+        .first(&mut db_pool.get().unwrap())
+        .unwrap();
+
+    app_state
+        .ownership_service
+        .transfer_ownership(&admin, &record, new_owner_id, &collection_name, &record.id)
+        .await
+        .expect("Failed to transfer ownership");
+
+    let activity = app_state.websocket_service.get_activity_log(100, 0).await;
+    let entry = activity
+        .activities
+        .iter()
+        .find(|entry| entry.action == "ownership_transferred")
+        .expect("Ownership transfer missing from activity log");
+    assert_eq!(entry.user_id, Some(admin_id));
+    let details = entry.details.as_deref().unwrap();
+    assert!(details.contains(&format!("{}/{}", collection_name, record.id)));
+    assert!(details.contains(&format!("owner {} -> {}", admin_id, new_owner_id)));
+}
+
+#[tokio::test]
+async fn test_disabling_activity_tracking_stops_the_activity_log() {
+    use lunarbase::models::{PendingEvent, RecordEvent};
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let app_state = AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState");
+
+    let transfer = |record_id: &str| PendingEvent {
+        collection_name: "posts".to_string(),
+        event: RecordEvent::OwnershipTransferred {
+            record_id: record_id.to_string(),
+            old_owner_id: None,
+            new_owner_id: 2,
+        },
+        user_id: Some(1),
+    };
+
+    // Only this AppState's cached settings change, so other tests keep tracking
+    app_state
+        .configuration_manager
+        .update_cache("system", "activity_tracking_enabled", "false")
+        .await;
+    app_state
+        .websocket_service
+        .broadcast_event(transfer("untracked"))
+        .await
+        .expect("Failed to broadcast event");
+    assert_eq!(
+        app_state
+            .websocket_service
+            .get_activity_log(100, 0)
+            .await
+            .total_count,
+        0
+    );
+
+    app_state
+        .configuration_manager
+        .update_cache("system", "activity_tracking_enabled", "true")
+        .await;
+    app_state
+        .websocket_service
+        .broadcast_event(transfer("tracked"))
+        .await
+        .expect("Failed to broadcast event");
+    let activity = app_state.websocket_service.get_activity_log(100, 0).await;
+    assert_eq!(activity.total_count, 1);
+    assert!(
+        activity.activities[0]
+            .details
+            .as_deref()
+            .unwrap()
+            .contains("posts/tracked")
+    );
+}
+
+#[tokio::test]
+async fn test_deactivating_user_closes_live_socket_and_revokes_tokens() {
+    use axum::body::Body;
+    use axum::http::Request;
+    use futures_util::StreamExt;
+    use lunarbase::handlers::update_user;
+    use lunarbase::services::ACCOUNT_DEACTIVATED_CLOSE_CODE;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let app_state = AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState");
+
+    let protected_routes = Router::new()
+        .route("/auth/me", get(me))
+        .route("/users/{user_id}", axum::routing::put(update_user))
+        .layer(middleware::from_fn_with_state(
+            app_state.auth_state.clone(),
+            auth_middleware,
+        ));
+    let app = Router::new()
+        .nest(
+            "/api",
+            Router::new()
+                .route("/ws", get(websocket_handler))
+                .merge(protected_routes),
+        )
+        .with_state(app_state.clone());
+
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let (user_id, user_token) = create_test_user(&app, "user").await;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server_app = app.clone();
+    tokio::spawn(async move { axum::serve(listener, server_app).await.unwrap() });
+
+    let (mut socket, _) =
+        tokio_tungstenite::connect_async(format!("ws://{}/api/ws?token={}", addr, user_token))
+            .await
+            .expect("Failed to open WebSocket");
+
+    for _ in 0..50 {
+        let details = app_state.websocket_service.get_connection_details().await;
+        if details.iter().any(|conn| conn.user_id == Some(user_id)) {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+
+    let set_active = |active: bool| {
+        Request::builder()
+            .uri(format!("/api/users/{}", user_id))
+            .method("PUT")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {}", admin_token))
+            .body(Body::from(json!({ "is_active": active }).to_string()))
+            .unwrap()
+    };
+    let me_request = || {
+        Request::builder()
+            .uri("/api/auth/me")
+            .header("authorization", format!("Bearer {}", user_token))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(set_active(false)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let frame = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+        .await
+        .expect("Socket was not closed")
+        .expect("Socket ended without a close frame")
+        .expect("WebSocket error");
+    match frame {
+        Message::Close(Some(close)) => {
+            assert_eq!(u16::from(close.code), ACCOUNT_DEACTIVATED_CLOSE_CODE);
+            assert_eq!(close.reason.as_str(), "account_deactivated");
+        }
+        other => panic!("Expected close frame, got {:?}", other),
+    }
+
+    let response = app.clone().oneshot(me_request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app.clone().oneshot(set_active(true)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app.clone().oneshot(me_request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_collection_changes_are_sent_on_collections_channel() {
+    use futures_util::{SinkExt, StreamExt};
+    use lunarbase::models::{
+        CollectionSchema, CollectionWorkflow, CreateCollectionRequest, FieldDefinition, FieldType,
+        SetCollectionPermissionRequest, UpdateCollectionRequest,
+    };
+    use serde_json::Value;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let app_state = AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState");
+    let app = Router::new()
+        .nest("/api", Router::new().route("/ws", get(websocket_handler)))
+        .with_state(app_state.clone());
+
+    let (admin_id, admin_token) = create_admin_token(&app).await;
+    let (_user_id, user_token) = create_test_user(&app, "user").await;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let mut sockets = Vec::new();
+    for token in [&admin_token, &user_token] {
+        let (mut socket, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/api/ws?token={}", addr, token))
+                .await
+                .expect("Failed to open WebSocket");
+        let subscribe = json!({
+            "type": "Subscribe",
+            "data": {
+                "subscription_id": "collections",
+                "collection_name": "_collections",
+                "subscription_type": "Collection",
+                "filters": null
+            }
+        });
+        socket
+            .send(Message::Text(subscribe.to_string().into()))
+            .await
+            .unwrap();
+        sockets.push(socket);
+    }
+    let (mut admin_socket, mut user_socket) = {
+        let mut sockets = sockets.into_iter();
+        (sockets.next().unwrap(), sockets.next().unwrap())
+    };
+
+    async fn next_json<S>(socket: &mut S) -> Value
+    where
+        S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>>
+            + Unpin,
+    {
+        loop {
+            let message = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+                .await
+                .expect("Timed out waiting for a message")
+                .expect("Socket closed")
+                .expect("WebSocket error");
+            if let Message::Text(text) = message {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    for socket in [&mut admin_socket, &mut user_socket] {
+        assert_eq!(next_json(socket).await["type"], "SubscriptionConfirmed");
+    }
+
+    let suffix = uuid::Uuid::new_v4().simple().to_string();
+    let visible = format!("visible_{}", &suffix[0..8]);
+    let hidden = format!("hidden_{}", &suffix[0..8]);
+    let field = |name: &str| FieldDefinition {
+        name: name.to_string(),
+        field_type: FieldType::Text,
+        required: false,
+        default_value: None,
+        validation: None,
+        relation_target: None,
+        description: None,
+        example: None,
+        indexed_paths: None,
+    };
+    let create = |name: &String, user_can_list: bool| CreateCollectionRequest {
+        name: name.clone(),
+        display_name: None,
+        description: None,
+        schema: CollectionSchema {
+            fields: vec![field("title")],
+        },
+        orderable: false,
+        id_type: Default::default(),
+        allow_explicit_ids: false,
+        workflow: CollectionWorkflow::None,
+        schedule: None,
+        retention: None,
+        permissions: Some(if user_can_list {
+            vec![SetCollectionPermissionRequest {
+                role_name: "user".to_string(),
+                can_create: false,
+                can_read: true,
+                can_update: false,
+                can_delete: false,
+                can_list: true,
+            }]
+        } else {
+            Vec::new()
+        }),
+    };
+
+    let service = &app_state.collection_service;
+    service
+        .create_collection(create(&visible, true), Some(admin_id))
+        .await
+        .unwrap();
+    service
+        .create_collection(create(&hidden, false), Some(admin_id))
+        .await
+        .unwrap();
+    service
+        .update_collection(
+            &visible,
+            UpdateCollectionRequest {
+                name: None,
+                display_name: None,
+                description: None,
+                schema: Some(CollectionSchema {
+                    fields: vec![field("title"), field("summary")],
+                }),
+                query_cache_ttl_seconds: None,
+                orderable: None,
+                id_type: None,
+                allow_explicit_ids: None,
+                workflow: None,
+                schedule: None,
+                retention: None,
+            },
+            Some(admin_id),
+        )
+        .await
+        .unwrap();
+    service.delete_collection(&hidden).await.unwrap();
+    service.delete_collection(&visible).await.unwrap();
+
+    // Other tests create collections too, so only this test's ones are kept
+    async fn events_until_deleted<S>(socket: &mut S, names: [&str; 2]) -> Vec<Value>
+    where
+        S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>>
+            + Unpin,
+    {
+        let mut events = Vec::new();
+        loop {
+            let message = next_json(socket).await;
+            assert_eq!(message["type"], "CollectionEvent");
+            assert_eq!(message["data"]["subscription_id"], "collections");
+            let event = message["data"]["event"].clone();
+            if names.iter().any(|name| event["collection"] == *name) {
+                events.push(event.clone());
+            }
+            if event == json!({ "action": "Deleted", "collection": names[0] }) {
+                return events;
+            }
+        }
+    }
+
+    let admin_events = events_until_deleted(&mut admin_socket, [&visible, &hidden]).await;
+    assert!(
+        admin_events.contains(&json!({ "action": "Created", "collection": hidden, "version": 1 }))
+    );
+    assert!(admin_events.contains(&json!({ "action": "Deleted", "collection": hidden })));
+
+    let user_events = events_until_deleted(&mut user_socket, [&visible, &hidden]).await;
+    assert_eq!(
+        user_events,
+        vec![
+            json!({ "action": "Created", "collection": visible, "version": 1 }),
+            json!({
+                "action": "SchemaUpdated",
+                "collection": visible,
+                "version": 2,
+                "renamed_from": null
+            }),
+            json!({ "action": "Deleted", "collection": visible }),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_websocket_limits_reject_messages_and_ban_repeat_offenders() {
+    use futures_util::{SinkExt, StreamExt};
+    use lunarbase::services::LIMIT_VIOLATIONS_CLOSE_CODE;
+    use tokio_tungstenite::tungstenite::{Error, Message};
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let app_state = AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState");
+    let app = Router::new()
+        .nest("/api", Router::new().route("/ws", get(websocket_handler)))
+        .with_state(app_state);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    let url = format!("ws://{}/api/ws", addr);
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(&url)
+        .await
+        .expect("Failed to open WebSocket");
+
+    let subscribe = |id: String, filter: &str| {
+        Message::Text(
+            json!({
+                "type": "Subscribe",
+                "data": {
+                    "subscription_id": id,
+                    "collection_name": "posts",
+                    "subscription_type": "Collection",
+                    "filters": { "title": filter }
+                }
+            })
+            .to_string()
+            .into(),
+        )
+    };
+    macro_rules! next_message {
+        () => {{
+            let message = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+                .await
+                .expect("No message received")
+                .expect("Socket ended")
+                .expect("WebSocket error");
+            match message {
+                Message::Text(text) => serde_json::from_str::<serde_json::Value>(&text).unwrap(),
+                other => panic!("Expected a text message, got {:?}", other),
+            }
+        }};
+    }
+
+    // Default limits: 64 KB messages, 100 subscriptions, 1024 filter characters
+    socket
+        .send(Message::Text("x".repeat(64 * 1024 + 1).into()))
+        .await
+        .unwrap();
+    let error = next_message!();
+    assert_eq!(error["type"], "Error");
+    assert_eq!(error["data"]["code"], "message_too_large");
+    assert_eq!(error["data"]["limit"], 64 * 1024);
+
+    socket
+        .send(subscribe("long".to_string(), &"x".repeat(1020)))
+        .await
+        .unwrap();
+    let error = next_message!();
+    assert_eq!(error["data"]["code"], "filter_too_long");
+    assert_eq!(error["data"]["subscription_id"], "long");
+
+    for index in 0..100 {
+        socket
+            .send(subscribe(format!("sub-{}", index), &"x".repeat(1019)))
+            .await
+            .unwrap();
+        assert_eq!(next_message!()["type"], "SubscriptionConfirmed");
+    }
+    socket
+        .send(subscribe("sub-100".to_string(), "x"))
+        .await
+        .unwrap();
+    let error = next_message!();
+    assert_eq!(error["data"]["code"], "too_many_subscriptions");
+    assert_eq!(error["data"]["limit"], 100);
+
+    // The tenth violation within a minute closes the connection and bans the client
+    for _ in 0..7 {
+        socket
+            .send(subscribe("sub-100".to_string(), "x"))
+            .await
+            .unwrap();
+    }
+    let close = loop {
+        let message = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+            .await
+            .expect("Socket was not closed")
+            .expect("Socket ended without a close frame")
+            .expect("WebSocket error");
+        if let Message::Close(close) = message {
+            break close.expect("Close frame without a code");
+        }
+    };
+    assert_eq!(u16::from(close.code), LIMIT_VIOLATIONS_CLOSE_CODE);
+    assert_eq!(close.reason.as_str(), "limit_violations");
+
+    match tokio_tungstenite::connect_async(&url).await {
+        Err(Error::Http(response)) => {
+            assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS)
+        }
+        other => panic!(
+            "Expected the banned client to be refused, got {:?}",
+            other.map(|_| ())
+        ),
+    }
+}
+
+#[tokio::test]
+async fn test_role_change_notification_is_pushed_and_kept_until_read() {
+    use axum::body::Body;
+    use axum::http::Request;
+    use futures_util::StreamExt;
+    use http_body_util::BodyExt;
+    use lunarbase::handlers::{list_my_notifications, mark_my_notifications_read, update_user};
+    use serde_json::Value;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+    let app_state = AppState::new(db_pool, "test_secret", "test_pepper".to_string(), &config)
+        .await
+        .expect("Failed to create AppState");
+
+    let protected_routes = Router::new()
+        .route("/users/{user_id}", axum::routing::put(update_user))
+        .route("/users/me/notifications", get(list_my_notifications))
+        .route(
+            "/users/me/notifications/read",
+            post(mark_my_notifications_read),
+        )
+        .layer(middleware::from_fn_with_state(
+            app_state.auth_state.clone(),
+            auth_middleware,
+        ));
+    let app = Router::new()
+        .nest(
+            "/api",
+            Router::new()
+                .route("/ws", get(websocket_handler))
+                .merge(protected_routes),
+        )
+        .with_state(app_state.clone());
+
+    let (_admin_id, admin_token) = create_admin_token(&app).await;
+    let (user_id, user_token) = create_test_user(&app, "user").await;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server_app = app.clone();
+    tokio::spawn(async move { axum::serve(listener, server_app).await.unwrap() });
+
+    let (mut socket, _) =
+        tokio_tungstenite::connect_async(format!("ws://{}/api/ws?token={}", addr, user_token))
+            .await
+            .expect("Failed to open WebSocket");
+    for _ in 0..50 {
+        let details = app_state.websocket_service.get_connection_details().await;
+        if details.iter().any(|conn| conn.user_id == Some(user_id)) {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+
+    let mut next_notification = async || loop {
+        let message = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+            .await
+            .expect("No notification was pushed")
+            .expect("Socket ended")
+            .expect("WebSocket error");
+        if let Message::Text(text) = message {
+            let message: Value = serde_json::from_str(&text).unwrap();
+            if message["type"] == "Notification" {
+                break message["data"].clone();
+            }
+        }
+    };
+    let list_notifications = |query: &str| {
+        Request::builder()
+            .uri(format!("/api/users/me/notifications{}", query))
+            .header("authorization", format!("Bearer {}", user_token))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/users/{}", user_id))
+                .method("PUT")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", admin_token))
+                .body(Body::from(json!({ "role": "guest" }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let pushed = next_notification().await;
+    assert_eq!(pushed["event"], "Created");
+    assert_eq!(pushed["unread_count"], 1);
+    assert_eq!(pushed["notification"]["kind"], "role_changed");
+    assert_eq!(
+        pushed["notification"]["data"],
+        json!({ "from": "user", "to": "guest" })
+    );
+
+    let response = app
+        .clone()
+        .oneshot(list_notifications("?unread_only=true"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["data"]["total"], 1);
+    assert_eq!(body["data"]["unread_count"], 1);
+    let notification_id = body["data"]["notifications"][0]["id"].as_i64().unwrap();
+    assert_eq!(
+        notification_id,
+        pushed["notification"]["id"].as_i64().unwrap()
+    );
+    assert_eq!(body["data"]["notifications"][0]["read"], false);
+
+    // Someone else's notification ids are ignored
+    let (_other_id, other_token) = create_test_user(&app, "user").await;
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/users/me/notifications/read")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", other_token))
+                .body(Body::from(json!({ "ids": [notification_id] }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["data"]["marked"], 0);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/users/me/notifications/read")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", user_token))
+                .body(Body::from(json!({ "ids": [notification_id] }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["data"]["marked"], 1);
+    assert_eq!(body["data"]["unread_count"], 0);
+
+    let pushed = next_notification().await;
+    assert_eq!(pushed, json!({ "event": "UnreadCount", "unread_count": 0 }));
+
+    let response = app.clone().oneshot(list_notifications("")).await.unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["data"]["total"], 1);
+    assert_eq!(body["data"]["unread_count"], 0);
+    assert_eq!(body["data"]["notifications"][0]["read"], true);
+}
@@ -0,0 +1,471 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use lunarbase::AppState;
+use lunarbase::database::create_pool;
+use lunarbase::models::{
+    DEFAULT_WORKSPACE_ID, NewRecordPermission, NewUser, User, WorkspaceSession,
+};
+use lunarbase::schema::{collections, record_permissions, system_settings, users};
+use lunarbase::server::build_routes;
+
+mod common;
+
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations/");
+
+type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+
+fn set_workspaces_enabled(pool: &DbPool, enabled: bool) {
+    let mut conn = pool.get().expect("Failed to get database connection");
+    diesel::update(
+        system_settings::table
+            .filter(system_settings::category.eq("system"))
+            .filter(system_settings::setting_key.eq("enable_workspaces")),
+    )
+    .set(system_settings::setting_value.eq(enabled.to_string()))
+    .execute(&mut conn)
+    .expect("Failed to update enable_workspaces");
+}
+
+/// Turns workspaces back off even when an assertion fails, so the shared
+/// database stays usable for the other test binaries.
+struct WorkspacesEnabled(DbPool);
+
+impl WorkspacesEnabled {
+    fn new(pool: DbPool) -> Self {
+        set_workspaces_enabled(&pool, true);
+        Self(pool)
+    }
+}
+
+impl Drop for WorkspacesEnabled {
+    fn drop(&mut self) {
+        set_workspaces_enabled(&self.0, false);
+    }
+}
+
+async fn create_app_state() -> (AppState, DbPool) {
+    let config = common::create_test_config().expect("Failed to load config");
+    let db_pool = create_pool(&config.database_url).expect("Failed to create database pool");
+
+    {
+        let mut conn = db_pool.get().expect("Failed to get database connection");
+        conn.run_pending_migrations(MIGRATIONS)
+            .expect("Failed to run migrations");
+    }
+
+    let app_state = AppState::new(
+        db_pool.clone(),
+        "test_secret",
+        "test_pepper".to_string(),
+        &config,
+    )
+    .await
+    .expect("Failed to create AppState");
+    (app_state, db_pool)
+}
+
+fn create_user(pool: &DbPool, role: &str) -> User {
+    let suffix = uuid::Uuid::new_v4().to_string()[0..8].to_string();
+    let new_user = NewUser::new_verified(
+        format!("ws_{}@example.com", suffix),
+        "Test123!@#",
+        format!("ws_{}", suffix),
+        role.to_string(),
+        true,
+        "test_pepper",
+    )
+    .expect("Failed to create new user");
+
+    let mut conn = pool.get().expect("Failed to get database connection");
+    diesel::insert_into(users::table)
+        .values(&new_user)
+        .execute(&mut conn)
+        .expect("Failed to insert user");
+
+    users::table
+        .filter(users::email.eq(&new_user.email))
+        .select(User::as_select())
+        .first(&mut conn)
+        .expect("Failed to fetch inserted user")
+}
+
+async fn token_for(app_state: &AppState, user: &User, workspace_id: Option<i32>) -> String {
+    let session = app_state
+        .workspace_service
+        .session_for(user, workspace_id)
+        .await
+        .expect("Failed to open workspace session");
+    app_state
+        .auth_state
+        .jwt_service
+        .generate_access_token(user.id, &user.email, &user.role, session.as_ref())
+        .await
+        .expect("Failed to generate token")
+}
+
+fn table_exists(pool: &DbPool, table: &str) -> bool {
+    #[derive(QueryableByName)]
+    struct Count {
+        #[diesel(sql_type = diesel::sql_types::BigInt)]
+        count: i64,
+    }
+
+    let mut conn = pool.get().expect("Failed to get database connection");
+    diesel::sql_query(
+        "SELECT COUNT(*) AS count FROM sqlite_master WHERE type = 'table' AND name = ?",
+    )
+    .bind::<diesel::sql_types::Text, _>(table)
+    .get_result::<Count>(&mut conn)
+    .expect("Failed to query sqlite_master")
+    .count
+        > 0
+}
+
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    token: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("authorization", format!("Bearer {}", token));
+    let body = match body {
+        Some(body) => {
+            builder = builder.header("content-type", "application/json");
+            Body::from(body.to_string())
+        }
+        None => Body::empty(),
+    };
+
+    let response = app
+        .clone()
+        .oneshot(builder.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+fn collection_payload(name: &str) -> Value {
+    json!({
+        "name": name,
+        "display_name": name,
+        "schema": {
+            "fields": [
+                { "name": "title", "field_type": "text", "required": true }
+            ]
+        }
+    })
+}
+
+// One test drives the whole flow because it flips an instance-wide setting.
+#[tokio::test]
+async fn test_workspaces_isolate_collections_and_instance_endpoints() {
+    let (setup_state, pool) = create_app_state().await;
+    let superadmin = create_user(&pool, "admin");
+    let workspace_admin = create_user(&pool, "user");
+    let suffix = uuid::Uuid::new_v4().to_string()[0..8].to_string();
+    let default_collection = format!("ws_default_{}", suffix);
+    let scoped_collection = format!("ws_scoped_{}", suffix);
+
+    // Tokens minted while workspaces are off carry no workspace.
+    let unscoped_token = token_for(&setup_state, &superadmin, None).await;
+
+    let enabled = WorkspacesEnabled::new(pool.clone());
+    let (app_state, _) = create_app_state().await;
+    let app = build_routes(app_state.clone(), true);
+
+    let (status, _) = send(&app, "GET", "/api/auth/me", &unscoped_token, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let super_token = token_for(&app_state, &superadmin, None).await;
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/admin/workspaces",
+        &super_token,
+        Some(json!({ "name": format!("acme_{}", suffix) })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    let workspace_id = body["data"]["id"].as_i64().unwrap() as i32;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/collections",
+        &super_token,
+        Some(collection_payload(&default_collection)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    assert_eq!(body["data"]["workspace_id"], DEFAULT_WORKSPACE_ID);
+
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &format!(
+            "/api/admin/workspaces/{}/members/{}",
+            workspace_id, workspace_admin.id
+        ),
+        &super_token,
+        Some(json!({ "role": "admin" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    // Members of a workspace no longer fall back to the default one.
+    let denied = app_state
+        .workspace_service
+        .session_for(&workspace_admin, Some(DEFAULT_WORKSPACE_ID))
+        .await;
+    assert!(denied.is_err());
+
+    let member_token = token_for(&app_state, &workspace_admin, None).await;
+    let session: WorkspaceSession = app_state
+        .auth_state
+        .jwt_service
+        .validate_access_token(&member_token)
+        .unwrap()
+        .workspace_session()
+        .unwrap();
+    assert_eq!(session.workspace_id, workspace_id);
+    assert_eq!(session.role, "admin");
+    assert!(!session.superadmin);
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/collections",
+        &member_token,
+        Some(collection_payload(&scoped_collection)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    assert_eq!(body["data"]["workspace_id"], workspace_id);
+    assert!(table_exists(
+        &pool,
+        &format!("ws{}_records_{}", workspace_id, scoped_collection)
+    ));
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        "/api/collections?limit=1000",
+        &member_token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let listed = body.to_string();
+    assert!(listed.contains(&scoped_collection));
+    assert!(!listed.contains(&default_collection));
+
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/api/collections/{}/records", default_collection),
+        &member_token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Record permissions of a user are listed and revoked per workspace.
+    {
+        let mut conn = pool.get().expect("Failed to get database connection");
+        let collection_ids: Vec<i32> = collections::table
+            .filter(collections::name.eq_any([&default_collection, &scoped_collection]))
+            .select(collections::id)
+            .load(&mut conn)
+            .expect("Failed to load collection ids");
+        for collection_id in collection_ids {
+            diesel::insert_into(record_permissions::table)
+                .values(&NewRecordPermission {
+                    record_id: "1".to_string(),
+                    collection_id,
+                    user_id: superadmin.id,
+                    can_read: true,
+                    can_update: false,
+                    can_delete: false,
+                    granted_by: None,
+                })
+                .execute(&mut conn)
+                .expect("Failed to grant record permission");
+        }
+    }
+    let grants_uri = format!("/api/permissions/users/{}/records", superadmin.id);
+    let (status, body) = send(&app, "GET", &grants_uri, &member_token, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["total_count"], 1);
+    assert_eq!(
+        body["data"]["grants"][0]["collection_name"],
+        scoped_collection
+    );
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("{}?collection={}", grants_uri, default_collection),
+        &member_token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = send(&app, "DELETE", &grants_uri, &member_token, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["revoked"], 1);
+
+    let (status, body) = send(&app, "GET", &grants_uri, &super_token, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["total_count"], 1);
+    assert_eq!(
+        body["data"]["grants"][0]["collection_name"],
+        default_collection
+    );
+
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!(
+            "/api/admin/analytics/records?collection={}",
+            default_collection
+        ),
+        &member_token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!(
+            "/api/admin/analytics/records?collection={}",
+            scoped_collection
+        ),
+        &member_token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/api/permissions/users/{}/collections", workspace_admin.id),
+        &member_token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let accessible = body.to_string();
+    assert!(accessible.contains(&scoped_collection));
+    assert!(!accessible.contains(&default_collection));
+
+    for uri in [
+        "/api/admin/workspaces",
+        "/api/users",
+        "/api/admin/analytics/users",
+    ] {
+        let (status, _) = send(&app, "GET", uri, &member_token, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{}", uri);
+    }
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/admin/collections/orphans/sweep?dry_run=true",
+        &member_token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &format!("/api/admin/workspaces/{}/collections", workspace_id),
+        &super_token,
+        Some(json!({ "collection": default_collection })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["workspace_id"], workspace_id);
+    assert!(!table_exists(
+        &pool,
+        &format!("records_{}", default_collection)
+    ));
+
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/api/collections/{}/records", default_collection),
+        &member_token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/api/admin/workspaces/{}", workspace_id),
+        &super_token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let super_in_workspace = token_for(&app_state, &superadmin, Some(workspace_id)).await;
+    for name in [&default_collection, &scoped_collection] {
+        let (status, body) = send(
+            &app,
+            "DELETE",
+            &format!("/api/collections/{}?force=true", name),
+            &super_in_workspace,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT, "{}", body);
+    }
+
+    let (status, body) = send(
+        &app,
+        "DELETE",
+        &format!("/api/admin/workspaces/{}", workspace_id),
+        &super_token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    drop(enabled);
+
+    // Scoped tokens stop working once workspaces are turned off again.
+    let (app_state, _) = create_app_state().await;
+    let app = build_routes(app_state, true);
+    let (status, _) = send(&app, "GET", "/api/auth/me", &super_token, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let mut conn = pool.get().expect("Failed to get database connection");
+    diesel::delete(users::table.filter(users::id.eq_any([superadmin.id, workspace_admin.id])))
+        .execute(&mut conn)
+        .expect("Failed to delete test users");
+}